@@ -695,7 +695,7 @@ function PrivateTransferTab({
       ];
 
       if (change > 0n) {
-        const { stealthAddress: changeAddress } = generateStealthAddress(wallet.publicKey);
+        const changeAddress = wallet.changeAddress(selectedNotes[0].commitment);
         outputs.push({ recipient: changeAddress, amount: change });
       }
 
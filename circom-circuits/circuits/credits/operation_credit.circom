@@ -0,0 +1,121 @@
+pragma circom 2.1.0;
+
+include "../../node_modules/circomlib/circuits/poseidon.circom";
+
+// Domain separation constants (must match on-chain verification)
+function OPERATION_CREDIT_DOMAIN() { return 0x15; }
+function CREDIT_NULLIFIER_DOMAIN() { return 0x16; }
+
+// ============================================================================
+// Helper Templates
+// ============================================================================
+
+// Merkle tree verification (Poseidon(left, right), matches the on-chain credit tree)
+template MerkleProof(levels) {
+    signal input leaf;
+    signal input pathElements[levels];
+    signal input pathIndices[levels];
+    signal output root;
+
+    signal intermediates[levels + 1];
+    intermediates[0] <== leaf;
+
+    component hashers[levels];
+    component muxL[levels];
+    component muxR[levels];
+
+    for (var i = 0; i < levels; i++) {
+        // Path index must be a bit
+        pathIndices[i] * (pathIndices[i] - 1) === 0;
+
+        hashers[i] = Poseidon(2);
+
+        muxL[i] = Mux1();
+        muxL[i].c[0] <== intermediates[i];
+        muxL[i].c[1] <== pathElements[i];
+        muxL[i].s <== pathIndices[i];
+
+        muxR[i] = Mux1();
+        muxR[i].c[0] <== pathElements[i];
+        muxR[i].c[1] <== intermediates[i];
+        muxR[i].s <== pathIndices[i];
+
+        hashers[i].inputs[0] <== muxL[i].out;
+        hashers[i].inputs[1] <== muxR[i].out;
+
+        intermediates[i + 1] <== hashers[i].out;
+    }
+
+    root <== intermediates[levels];
+}
+
+template Mux1() {
+    signal input c[2];
+    signal input s;
+    signal output out;
+
+    out <== c[0] + s * (c[1] - c[0]);
+}
+
+// ============================================================================
+// Operation Credit Circuit - Anonymous Credit Spend
+// ============================================================================
+//
+// Spends one credit from the epoch's credit tree for one operation. The
+// issuer only saw the leaf, so the spend can't be linked to the purchase.
+//
+// Verifies:
+// 1. Poseidon(OPERATION_CREDIT, credit_secret) is a leaf under credit_root
+// 2. credit_nullifier = Poseidon(CREDIT_NULLIFIER, credit_secret)
+// 3. operation_id is bound, so a copied proof can't be spent for another operation
+template OperationCredit(levels) {
+    // ========================================================================
+    // Public Inputs
+    // ========================================================================
+    signal input credit_root;           // Credit tree root (checked on-chain)
+    signal input credit_nullifier;      // One per credit
+    signal input operation_id;          // Operation the credit is spent for
+
+    // ========================================================================
+    // Private Inputs
+    // ========================================================================
+    signal input credit_secret;
+    signal input merkle_path[levels];
+    signal input merkle_path_indices[levels];
+
+    // ========================================================================
+    // 1. Verify Credit Leaf Inclusion
+    // ========================================================================
+    component leaf = Poseidon(2);
+    leaf.inputs[0] <== OPERATION_CREDIT_DOMAIN();
+    leaf.inputs[1] <== credit_secret;
+
+    component tree = MerkleProof(levels);
+    tree.leaf <== leaf.out;
+    for (var i = 0; i < levels; i++) {
+        tree.pathElements[i] <== merkle_path[i];
+        tree.pathIndices[i] <== merkle_path_indices[i];
+    }
+    credit_root === tree.root;
+
+    // ========================================================================
+    // 2. Verify Credit Nullifier
+    // ========================================================================
+    component nullifier = Poseidon(2);
+    nullifier.inputs[0] <== CREDIT_NULLIFIER_DOMAIN();
+    nullifier.inputs[1] <== credit_secret;
+    credit_nullifier === nullifier.out;
+
+    // ========================================================================
+    // 3. Bind Operation ID
+    // ========================================================================
+    signal operation_id_sq;
+    operation_id_sq <== operation_id * operation_id;
+}
+
+// Main component with public inputs (levels = MERKLE_TREE_DEPTH on-chain)
+component main {public [
+    credit_root,
+    credit_nullifier,
+    operation_id
+]} = OperationCredit(16);
@@ -0,0 +1,183 @@
+pragma circom 2.1.0;
+
+include "../../node_modules/circomlib/circuits/poseidon.circom";
+include "../../node_modules/circomlib/circuits/bitify.circom";
+include "../../node_modules/circomlib/circuits/comparators.circom";
+
+// Domain separation constants (must match on-chain verification)
+// These ensure different hash contexts can't collide
+function COMMITMENT_DOMAIN() { return 1; }
+function SPENDING_NULLIFIER_DOMAIN() { return 2; }
+function NULLIFIER_KEY_DOMAIN() { return 4; }
+
+// ============================================================================
+// Helper Templates
+// ============================================================================
+
+// Compute note commitment: Poseidon(domain, stealth_pub_x, token_mint, amount, randomness)
+template Commitment() {
+    signal input stealth_pub_x;
+    signal input token_mint;
+    signal input amount;
+    signal input randomness;
+    signal output out;
+
+    component hasher = Poseidon(5);
+    hasher.inputs[0] <== COMMITMENT_DOMAIN();
+    hasher.inputs[1] <== stealth_pub_x;
+    hasher.inputs[2] <== token_mint;
+    hasher.inputs[3] <== amount;
+    hasher.inputs[4] <== randomness;
+    out <== hasher.out;
+}
+
+// Derive nullifier key from spending key: Poseidon(domain, spending_key, 0)
+template NullifierKey() {
+    signal input spending_key;
+    signal output out;
+
+    component hasher = Poseidon(3);
+    hasher.inputs[0] <== NULLIFIER_KEY_DOMAIN();
+    hasher.inputs[1] <== spending_key;
+    hasher.inputs[2] <== 0;
+    out <== hasher.out;
+}
+
+// Compute spending nullifier: Poseidon(domain, nullifier_key, commitment, leaf_index)
+template SpendingNullifier() {
+    signal input nullifier_key;
+    signal input commitment;
+    signal input leaf_index;
+    signal output out;
+
+    component hasher = Poseidon(4);
+    hasher.inputs[0] <== SPENDING_NULLIFIER_DOMAIN();
+    hasher.inputs[1] <== nullifier_key;
+    hasher.inputs[2] <== commitment;
+    hasher.inputs[3] <== leaf_index;
+    out <== hasher.out;
+}
+
+// Range check: constrain value to 64 bits
+template RangeCheck64() {
+    signal input in;
+
+    // Decompose to bits - this constrains the value to fit in 64 bits
+    component bits = Num2Bits(64);
+    bits.in <== in;
+}
+
+// ============================================================================
+// Claim Rewards Circuit: LP Note -> LP Note + Rewards Note
+// ============================================================================
+//
+// Spends a shielded LP note, re-creates it with the same amount and mints
+// the liquidity mining rewards it earned as a new note.
+//
+// Verifies:
+// 1. lp_commitment opens to lp_amount of lp_mint, and lp_nullifier spends it
+// 2. new_lp_commitment holds the same lp_amount of lp_mint
+// 3. reward_commitment holds reward_amount of reward_mint
+//
+// emissions_schedule, entry/exit reward_per_lp are only bound as public
+// inputs: the program reads entry from the note's reward checkpoint and
+// checks reward_amount against lp_amount * (exit - entry).
+template ClaimRewards() {
+    // ========================================================================
+    // Public Inputs
+    // ========================================================================
+    signal input merkle_root;           // Merkle root (verified on-chain via Light Protocol)
+    signal input lp_commitment;         // Spent LP note (keys its reward checkpoint)
+    signal input lp_nullifier;          // Prevents double-claiming
+    signal input emissions_schedule;    // Schedule address
+    signal input lp_mint;               // LP token
+    signal input reward_mint;           // Rewards token
+    signal input new_lp_commitment;     // Re-created LP note
+    signal input reward_commitment;     // Rewards note
+    signal input lp_amount;             // LP note amount
+    signal input entry_reward_per_lp;   // Accumulator at the note's checkpoint
+    signal input exit_reward_per_lp;    // Accumulator now
+    signal input reward_amount;         // Rewards paid
+
+    // ========================================================================
+    // Private Inputs
+    // ========================================================================
+
+    // LP note details
+    signal input in_stealth_pub_x;
+    signal input in_randomness;
+    signal input in_stealth_spending_key;
+    signal input leaf_index;
+
+    // Re-created LP note details
+    signal input new_lp_stealth_pub_x;
+    signal input new_lp_randomness;
+
+    // Rewards note details
+    signal input reward_stealth_pub_x;
+    signal input reward_randomness;
+
+    // ========================================================================
+    // 1. Verify LP Commitment and Nullifier
+    // ========================================================================
+    component in_commitment = Commitment();
+    in_commitment.stealth_pub_x <== in_stealth_pub_x;
+    in_commitment.token_mint <== lp_mint;
+    in_commitment.amount <== lp_amount;
+    in_commitment.randomness <== in_randomness;
+    lp_commitment === in_commitment.out;
+
+    component nk = NullifierKey();
+    nk.spending_key <== in_stealth_spending_key;
+
+    component computed_nullifier = SpendingNullifier();
+    computed_nullifier.nullifier_key <== nk.out;
+    computed_nullifier.commitment <== in_commitment.out;
+    computed_nullifier.leaf_index <== leaf_index;
+    lp_nullifier === computed_nullifier.out;
+
+    // ========================================================================
+    // 2. Verify Re-created LP Note (same amount)
+    // ========================================================================
+    component new_lp = Commitment();
+    new_lp.stealth_pub_x <== new_lp_stealth_pub_x;
+    new_lp.token_mint <== lp_mint;
+    new_lp.amount <== lp_amount;
+    new_lp.randomness <== new_lp_randomness;
+    new_lp_commitment === new_lp.out;
+
+    // ========================================================================
+    // 3. Verify Rewards Note
+    // ========================================================================
+    component reward = Commitment();
+    reward.stealth_pub_x <== reward_stealth_pub_x;
+    reward.token_mint <== reward_mint;
+    reward.amount <== reward_amount;
+    reward.randomness <== reward_randomness;
+    reward_commitment === reward.out;
+
+    // ========================================================================
+    // 4. Range Checks (64-bit amounts)
+    // ========================================================================
+    component range_lp = RangeCheck64();
+    range_lp.in <== lp_amount;
+
+    component range_reward = RangeCheck64();
+    range_reward.in <== reward_amount;
+}
+
+// Main component with public inputs
+component main {public [
+    merkle_root,
+    lp_commitment,
+    lp_nullifier,
+    emissions_schedule,
+    lp_mint,
+    reward_mint,
+    new_lp_commitment,
+    reward_commitment,
+    lp_amount,
+    entry_reward_per_lp,
+    exit_reward_per_lp,
+    reward_amount
+]} = ClaimRewards();
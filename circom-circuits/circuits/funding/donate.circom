@@ -0,0 +1,213 @@
+pragma circom 2.1.0;
+
+include "../../node_modules/circomlib/circuits/poseidon.circom";
+include "../../node_modules/circomlib/circuits/bitify.circom";
+
+// Domain separation constants (must match on-chain verification)
+function COMMITMENT_DOMAIN() { return 1; }
+function SPENDING_NULLIFIER_DOMAIN() { return 2; }
+function NULLIFIER_KEY_DOMAIN() { return 4; }
+function CONTRIBUTIONS_DOMAIN() { return 0x22; }
+
+// Maximum projects per round (matches MAX_ROUND_PROJECTS on-chain)
+function MAX_ROUND_PROJECTS() { return 16; }
+
+// ============================================================================
+// Helper Templates
+// ============================================================================
+
+// Compute note commitment: Poseidon(domain, stealth_pub_x, token_mint, amount, randomness)
+template Commitment() {
+    signal input stealth_pub_x;
+    signal input token_mint;
+    signal input amount;
+    signal input randomness;
+    signal output out;
+
+    component hasher = Poseidon(5);
+    hasher.inputs[0] <== COMMITMENT_DOMAIN();
+    hasher.inputs[1] <== stealth_pub_x;
+    hasher.inputs[2] <== token_mint;
+    hasher.inputs[3] <== amount;
+    hasher.inputs[4] <== randomness;
+    out <== hasher.out;
+}
+
+// Derive nullifier key from spending key: Poseidon(domain, spending_key, 0)
+template NullifierKey() {
+    signal input spending_key;
+    signal output out;
+
+    component hasher = Poseidon(3);
+    hasher.inputs[0] <== NULLIFIER_KEY_DOMAIN();
+    hasher.inputs[1] <== spending_key;
+    hasher.inputs[2] <== 0;
+    out <== hasher.out;
+}
+
+// Compute spending nullifier: Poseidon(domain, nullifier_key, commitment, leaf_index)
+template SpendingNullifier() {
+    signal input nullifier_key;
+    signal input commitment;
+    signal input leaf_index;
+    signal output out;
+
+    component hasher = Poseidon(4);
+    hasher.inputs[0] <== SPENDING_NULLIFIER_DOMAIN();
+    hasher.inputs[1] <== nullifier_key;
+    hasher.inputs[2] <== commitment;
+    hasher.inputs[3] <== leaf_index;
+    out <== hasher.out;
+}
+
+// Range check: constrain value to 64 bits
+template RangeCheck64() {
+    signal input in;
+    component bits = Num2Bits(64);
+    bits.in <== in;
+}
+
+// Hash of the per-project ciphertexts (matches compute_contributions_hash):
+// acc = Poseidon(CONTRIBUTIONS_DOMAIN, acc, c1, c2), starting from 0
+template ContributionsHash(n) {
+    signal input c1[n];
+    signal input c2[n];
+    signal output out;
+
+    component hashers[n];
+    signal acc[n + 1];
+    acc[0] <== 0;
+    for (var i = 0; i < n; i++) {
+        hashers[i] = Poseidon(4);
+        hashers[i].inputs[0] <== CONTRIBUTIONS_DOMAIN();
+        hashers[i].inputs[1] <== acc[i];
+        hashers[i].inputs[2] <== c1[i];
+        hashers[i].inputs[3] <== c2[i];
+        acc[i + 1] <== hashers[i].out;
+    }
+    out <== acc[n];
+}
+
+// ============================================================================
+// Donate Circuit: 1 Input -> Donation + Change
+// ============================================================================
+//
+// Spends a note into a donation note (owned by the chosen project) and a
+// change note, and binds the tally ciphertexts execute_donate adds to the
+// round (one per project, zero-padded to MAX_ROUND_PROJECTS) through
+// contributions_hash. round_id and tally_pubkey tie the proof to the round.
+
+template Donate() {
+    // ========================================================================
+    // Public Inputs
+    // ========================================================================
+    signal input merkle_root;           // Merkle root (verified on-chain via Light Protocol)
+    signal input nullifier;             // Prevents double-spending
+    signal input round_id;              // Matching round account (field-reduced)
+    signal input donation_commitment;   // Donation note (project)
+    signal input change_commitment;     // Change note (donor)
+    signal input tally_pubkey;          // Round ElGamal public key
+    signal input contributions_hash;    // Hash of the tally ciphertexts
+
+    // ========================================================================
+    // Private Inputs
+    // ========================================================================
+    signal input token_mint;
+
+    // Input note details
+    signal input in_stealth_pub_x;
+    signal input in_amount;
+    signal input in_randomness;
+    signal input in_stealth_spending_key;
+    signal input leaf_index;
+
+    // Donation note (project)
+    signal input donation_stealth_pub_x;
+    signal input donation_amount;
+    signal input donation_randomness;
+
+    // Change note (donor)
+    signal input change_stealth_pub_x;
+    signal input change_amount;
+    signal input change_randomness;
+
+    // Tally ciphertexts
+    signal input ciphertext_c1[MAX_ROUND_PROJECTS()];
+    signal input ciphertext_c2[MAX_ROUND_PROJECTS()];
+
+    // ========================================================================
+    // 1. Verify Input Commitment and Nullifier
+    // ========================================================================
+    component in_commitment = Commitment();
+    in_commitment.stealth_pub_x <== in_stealth_pub_x;
+    in_commitment.token_mint <== token_mint;
+    in_commitment.amount <== in_amount;
+    in_commitment.randomness <== in_randomness;
+
+    component nk = NullifierKey();
+    nk.spending_key <== in_stealth_spending_key;
+
+    component computed_nullifier = SpendingNullifier();
+    computed_nullifier.nullifier_key <== nk.out;
+    computed_nullifier.commitment <== in_commitment.out;
+    computed_nullifier.leaf_index <== leaf_index;
+
+    nullifier === computed_nullifier.out;
+
+    // ========================================================================
+    // 2. Verify Output Commitments
+    // ========================================================================
+    component donation_commit = Commitment();
+    donation_commit.stealth_pub_x <== donation_stealth_pub_x;
+    donation_commit.token_mint <== token_mint;
+    donation_commit.amount <== donation_amount;
+    donation_commit.randomness <== donation_randomness;
+    donation_commitment === donation_commit.out;
+
+    component change_commit = Commitment();
+    change_commit.stealth_pub_x <== change_stealth_pub_x;
+    change_commit.token_mint <== token_mint;
+    change_commit.amount <== change_amount;
+    change_commit.randomness <== change_randomness;
+    change_commitment === change_commit.out;
+
+    // ========================================================================
+    // 3. Balance Check
+    // ========================================================================
+    in_amount === donation_amount + change_amount;
+
+    component range_in = RangeCheck64();
+    range_in.in <== in_amount;
+
+    component range_donation = RangeCheck64();
+    range_donation.in <== donation_amount;
+
+    component range_change = RangeCheck64();
+    range_change.in <== change_amount;
+
+    // ========================================================================
+    // 4. Bind Tally Ciphertexts
+    // ========================================================================
+    component contributions = ContributionsHash(MAX_ROUND_PROJECTS());
+    for (var i = 0; i < MAX_ROUND_PROJECTS(); i++) {
+        contributions.c1[i] <== ciphertext_c1[i];
+        contributions.c2[i] <== ciphertext_c2[i];
+    }
+    contributions_hash === contributions.out;
+
+    // Bind the round inputs into the proof
+    signal round_id_sq;
+    round_id_sq <== round_id * round_id;
+    signal tally_pubkey_sq;
+    tally_pubkey_sq <== tally_pubkey * tally_pubkey;
+}
+
+component main {public [
+    merkle_root,
+    nullifier,
+    round_id,
+    donation_commitment,
+    change_commitment,
+    tally_pubkey,
+    contributions_hash
+]} = Donate();
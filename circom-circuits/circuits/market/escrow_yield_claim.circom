@@ -0,0 +1,113 @@
+pragma circom 2.1.0;
+
+include "../../node_modules/circomlib/circuits/poseidon.circom";
+include "../../node_modules/circomlib/circuits/bitify.circom";
+
+// Domain separation constants (must match on-chain verification)
+function COMMITMENT_DOMAIN() { return 1; }
+
+// ============================================================================
+// Helper Templates
+// ============================================================================
+
+// Compute note commitment: Poseidon(domain, stealth_pub_x, token_mint, amount, randomness)
+template Commitment() {
+    signal input stealth_pub_x;
+    signal input token_mint;
+    signal input amount;
+    signal input randomness;
+    signal output out;
+
+    component hasher = Poseidon(5);
+    hasher.inputs[0] <== COMMITMENT_DOMAIN();
+    hasher.inputs[1] <== stealth_pub_x;
+    hasher.inputs[2] <== token_mint;
+    hasher.inputs[3] <== amount;
+    hasher.inputs[4] <== randomness;
+    out <== hasher.out;
+}
+
+// Compute escrow yield claim tag: Poseidon(yield_key, order_id)
+template YieldTag() {
+    signal input yield_key;
+    signal input order_id;
+    signal output out;
+
+    component hasher = Poseidon(2);
+    hasher.inputs[0] <== yield_key;
+    hasher.inputs[1] <== order_id;
+    out <== hasher.out;
+}
+
+// Range check: constrain value to 64 bits
+template RangeCheck64() {
+    signal input in;
+
+    // Decompose to bits - this constrains the value to fit in 64 bits
+    component bits = Num2Bits(64);
+    bits.in <== in;
+}
+
+// ============================================================================
+// Escrow Yield Claim Circuit - Bonus Note
+// ============================================================================
+//
+// Maker proves knowledge of the yield_key behind an order's claim tag and
+// receives the accrued yield as a new note. No input notes are spent.
+//
+// Verifies:
+// 1. yield_tag = Poseidon(yield_key, order_id)
+// 2. bonus_commitment holds yield_amount of token_mint
+//
+// order_yield is only bound as a public input so the proof can't be
+// replayed against another order yield record.
+template EscrowYieldClaim() {
+    // ========================================================================
+    // Public Inputs
+    // ========================================================================
+    signal input yield_tag;             // Order's claim tag
+    signal input order_yield;           // Order yield record address
+    signal input bonus_commitment;      // Bonus note commitment
+    signal input yield_amount;          // Accrued yield (verified on-chain)
+    signal input token_mint;            // Offer token (pool token)
+
+    // ========================================================================
+    // Private Inputs
+    // ========================================================================
+    signal input yield_key;
+    signal input order_id;
+
+    // Bonus note details
+    signal input bonus_stealth_pub_x;
+    signal input bonus_randomness;
+
+    // ========================================================================
+    // 1. Verify Claim Tag
+    // ========================================================================
+    component tag = YieldTag();
+    tag.yield_key <== yield_key;
+    tag.order_id <== order_id;
+    yield_tag === tag.out;
+
+    // ========================================================================
+    // 2. Verify Bonus Commitment
+    // ========================================================================
+    component bonus = Commitment();
+    bonus.stealth_pub_x <== bonus_stealth_pub_x;
+    bonus.token_mint <== token_mint;
+    bonus.amount <== yield_amount;
+    bonus.randomness <== bonus_randomness;
+    bonus_commitment === bonus.out;
+
+    component range_yield = RangeCheck64();
+    range_yield.in <== yield_amount;
+}
+
+// Main component with public inputs
+component main {public [
+    yield_tag,
+    order_yield,
+    bonus_commitment,
+    yield_amount,
+    token_mint
+]} = EscrowYieldClaim();
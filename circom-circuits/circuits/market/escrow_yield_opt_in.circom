@@ -0,0 +1,107 @@
+pragma circom 2.1.0;
+
+include "../../node_modules/circomlib/circuits/poseidon.circom";
+include "../../node_modules/circomlib/circuits/bitify.circom";
+
+// Domain separation constants (must match on-chain verification)
+function COMMITMENT_DOMAIN() { return 1; }
+
+// ============================================================================
+// Helper Templates
+// ============================================================================
+
+// Compute note commitment: Poseidon(domain, stealth_pub_x, token_mint, amount, randomness)
+template Commitment() {
+    signal input stealth_pub_x;
+    signal input token_mint;
+    signal input amount;
+    signal input randomness;
+    signal output out;
+
+    component hasher = Poseidon(5);
+    hasher.inputs[0] <== COMMITMENT_DOMAIN();
+    hasher.inputs[1] <== stealth_pub_x;
+    hasher.inputs[2] <== token_mint;
+    hasher.inputs[3] <== amount;
+    hasher.inputs[4] <== randomness;
+    out <== hasher.out;
+}
+
+// Compute escrow yield claim tag: Poseidon(yield_key, order_id)
+template YieldTag() {
+    signal input yield_key;
+    signal input order_id;
+    signal output out;
+
+    component hasher = Poseidon(2);
+    hasher.inputs[0] <== yield_key;
+    hasher.inputs[1] <== order_id;
+    out <== hasher.out;
+}
+
+// Range check: constrain value to 64 bits
+template RangeCheck64() {
+    signal input in;
+
+    // Decompose to bits - this constrains the value to fit in 64 bits
+    component bits = Num2Bits(64);
+    bits.in <== in;
+}
+
+// ============================================================================
+// Escrow Yield Opt-In Circuit - Escrow Opening
+// ============================================================================
+//
+// Maker opens an open order's escrow amount WITHOUT spending the escrow note
+// and commits to a claim tag for the yield it earns.
+//
+// Verifies:
+// 1. Maker knows the escrow note preimage and it holds escrow_amount of token_mint
+// 2. yield_tag = Poseidon(yield_key, order_id), proven again to claim
+template EscrowYieldOptIn() {
+    // ========================================================================
+    // Public Inputs
+    // ========================================================================
+    signal input order_id;              // Order being opted in
+    signal input escrow_commitment;     // Order's escrow note
+    signal input token_mint;            // Offer token (pool token)
+    signal input escrow_amount;         // Escrowed principal (revealed to earn yield)
+    signal input yield_tag;             // Claim tag
+
+    // ========================================================================
+    // Private Inputs
+    // ========================================================================
+    signal input escrow_stealth_pub_x;
+    signal input escrow_randomness;
+    signal input yield_key;
+
+    // ========================================================================
+    // 1. Verify Escrow Commitment (proves maker knows the note preimage)
+    // ========================================================================
+    component escrow = Commitment();
+    escrow.stealth_pub_x <== escrow_stealth_pub_x;
+    escrow.token_mint <== token_mint;
+    escrow.amount <== escrow_amount;
+    escrow.randomness <== escrow_randomness;
+    escrow_commitment === escrow.out;
+
+    component range_escrow = RangeCheck64();
+    range_escrow.in <== escrow_amount;
+
+    // ========================================================================
+    // 2. Verify Claim Tag
+    // ========================================================================
+    component tag = YieldTag();
+    tag.yield_key <== yield_key;
+    tag.order_id <== order_id;
+    yield_tag === tag.out;
+}
+
+// Main component with public inputs
+component main {public [
+    order_id,
+    escrow_commitment,
+    token_mint,
+    escrow_amount,
+    yield_tag
+]} = EscrowYieldOptIn();
@@ -0,0 +1,123 @@
+pragma circom 2.1.0;
+
+include "../../node_modules/circomlib/circuits/poseidon.circom";
+
+// Domain separation constants (must match on-chain verification)
+function COMMITMENT_DOMAIN() { return 1; }
+
+// ============================================================================
+// Helper Templates
+// ============================================================================
+
+// Compute note commitment: Poseidon(domain, stealth_pub_x, token_mint, amount, randomness)
+template Commitment() {
+    signal input stealth_pub_x;
+    signal input token_mint;
+    signal input amount;
+    signal input randomness;
+    signal output out;
+
+    component hasher = Poseidon(5);
+    hasher.inputs[0] <== COMMITMENT_DOMAIN();
+    hasher.inputs[1] <== stealth_pub_x;
+    hasher.inputs[2] <== token_mint;
+    hasher.inputs[3] <== amount;
+    hasher.inputs[4] <== randomness;
+    out <== hasher.out;
+}
+
+// Compute order terms hash: Poseidon(offer_token, offer_amount, ask_token, ask_amount)
+template TermsHash() {
+    signal input offer_token;
+    signal input offer_amount;
+    signal input ask_token;
+    signal input ask_amount;
+    signal output out;
+
+    component hasher = Poseidon(4);
+    hasher.inputs[0] <== offer_token;
+    hasher.inputs[1] <== offer_amount;
+    hasher.inputs[2] <== ask_token;
+    hasher.inputs[3] <== ask_amount;
+    out <== hasher.out;
+}
+
+// ============================================================================
+// Order Modify Circuit - Escrow Ownership Proof
+// ============================================================================
+//
+// Maker proves they own an open order's escrow note WITHOUT spending it and
+// replaces the order's ask side. The escrow commitment stays on-chain as is.
+//
+// Verifies:
+// 1. Maker knows the escrow note preimage (proves ownership)
+// 2. old_terms_hash is the order's current terms over the escrowed offer
+// 3. new_terms_hash keeps the same offer (the escrow can't change)
+//
+// order_id and new_expiry are only bound as public inputs; the program
+// checks them against the order account and the clock.
+template OrderModify() {
+    // ========================================================================
+    // Public Inputs
+    // ========================================================================
+    signal input order_id;              // Order being modified
+    signal input escrow_commitment;     // Order's escrow note (unchanged)
+    signal input old_terms_hash;        // Order's current terms hash
+    signal input new_terms_hash;        // Replacement terms hash
+    signal input new_expiry;            // Replacement expiry (encoded like order_create)
+
+    // ========================================================================
+    // Private Inputs
+    // ========================================================================
+
+    // Escrow note details
+    signal input escrow_stealth_pub_x;
+    signal input escrow_randomness;
+    signal input offer_token;
+    signal input offer_amount;
+
+    // Current and replacement ask side
+    signal input old_ask_token;
+    signal input old_ask_amount;
+    signal input new_ask_token;
+    signal input new_ask_amount;
+
+    // ========================================================================
+    // 1. Verify Escrow Commitment (proves maker knows the note preimage)
+    // ========================================================================
+    component escrow = Commitment();
+    escrow.stealth_pub_x <== escrow_stealth_pub_x;
+    escrow.token_mint <== offer_token;
+    escrow.amount <== offer_amount;
+    escrow.randomness <== escrow_randomness;
+    escrow_commitment === escrow.out;
+
+    // ========================================================================
+    // 2. Verify Current Terms
+    // ========================================================================
+    component old_terms = TermsHash();
+    old_terms.offer_token <== offer_token;
+    old_terms.offer_amount <== offer_amount;
+    old_terms.ask_token <== old_ask_token;
+    old_terms.ask_amount <== old_ask_amount;
+    old_terms_hash === old_terms.out;
+
+    // ========================================================================
+    // 3. Verify Replacement Terms (same offer, new ask)
+    // ========================================================================
+    component new_terms = TermsHash();
+    new_terms.offer_token <== offer_token;
+    new_terms.offer_amount <== offer_amount;
+    new_terms.ask_token <== new_ask_token;
+    new_terms.ask_amount <== new_ask_amount;
+    new_terms_hash === new_terms.out;
+}
+
+// Main component with public inputs
+component main {public [
+    order_id,
+    escrow_commitment,
+    old_terms_hash,
+    new_terms_hash,
+    new_expiry
+]} = OrderModify();
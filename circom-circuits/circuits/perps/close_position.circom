@@ -107,7 +107,7 @@ template RangeCheck64() {
 //
 // On-chain verification handles:
 // - Oracle price validation
-// - Borrow fee calculation
+// - Borrow fee calculation (Phase 0 supplies the accrued fee as borrow_fee)
 // - Pool state updates
 
 template ClosePosition() {
@@ -123,6 +123,11 @@ template ClosePosition() {
     signal input close_fee;             // Closing fee amount
     signal input pnl_amount;            // Absolute PnL amount
     signal input is_profit;             // 1 = profit, 0 = loss
+    signal input position_commitment;   // Position being closed (binds its PositionMeta)
+    signal input position_margin;       // Position margin (settlement base)
+    signal input position_size;         // Position size (PnL and borrow fee base)
+    signal input entry_price;           // Entry price (PnL base)
+    signal input borrow_fee;            // Accrued borrow fee (computed on-chain in Phase 0)
 
     // ========================================================================
     // Private Inputs
@@ -131,10 +136,7 @@ template ClosePosition() {
     // Position details (from original position commitment)
     signal input position_stealth_pub_x;
     signal input market_id;
-    signal input position_margin;
-    signal input position_size;
     signal input position_leverage;
-    signal input entry_price;
     signal input position_randomness;
     signal input position_spending_key;
 
@@ -161,6 +163,7 @@ template ClosePosition() {
     pos_commit.leverage <== position_leverage;
     pos_commit.entry_price <== entry_price;
     pos_commit.randomness <== position_randomness;
+    position_commitment === pos_commit.out;
 
     // ========================================================================
     // 2. Verify Position Nullifier (proves ownership)
@@ -188,8 +191,8 @@ template ClosePosition() {
     // ========================================================================
     // 4. Settlement Calculation
     // ========================================================================
-    // If profit: out_amount = margin + min(pnl, margin) - close_fee
-    // If loss: out_amount = margin - pnl - close_fee
+    // If profit: out_amount = margin + min(pnl, margin) - close_fee - borrow_fee
+    // If loss: out_amount = margin - pnl - close_fee - borrow_fee
     //
     // Bounded profit: max profit = margin
 
@@ -217,13 +220,13 @@ template ClosePosition() {
     effective_pnl <== term1_pnl + term2_pnl;
 
     // Calculate expected settlement
-    // profit case: margin + effective_pnl - close_fee
-    // loss case: margin - pnl_amount - close_fee (pnl_amount not capped for losses)
+    // profit case: margin + effective_pnl - close_fee - borrow_fee
+    // loss case: margin - pnl_amount - close_fee - borrow_fee (pnl_amount not capped for losses)
     signal profit_settlement;
-    profit_settlement <== position_margin + effective_pnl - close_fee;
+    profit_settlement <== position_margin + effective_pnl - close_fee - borrow_fee;
 
     signal loss_settlement;
-    loss_settlement <== position_margin - pnl_amount - close_fee;
+    loss_settlement <== position_margin - pnl_amount - close_fee - borrow_fee;
 
     // Split into quadratic constraints
     signal term1_settlement;
@@ -278,13 +281,16 @@ template ClosePosition() {
     component range_fee = RangeCheck64();
     range_fee.in <== close_fee;
 
+    component range_borrow_fee = RangeCheck64();
+    range_borrow_fee.in <== borrow_fee;
+
     component range_out = RangeCheck64();
     range_out.in <== out_amount;
 
     // Note: On-chain verification handles:
     // - Oracle price validation
     // - PnL calculation verification against oracle prices
-    // - Borrow fee deduction
+    // - Borrow fee accrual (borrow_fee public input)
     // - Pool state updates
 }
 
@@ -297,5 +303,10 @@ component main {public [
     exit_price,
     close_fee,
     pnl_amount,
-    is_profit
+    is_profit,
+    position_commitment,
+    position_margin,
+    position_size,
+    entry_price,
+    borrow_fee
 ]} = ClosePosition();
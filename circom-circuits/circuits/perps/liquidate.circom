@@ -122,6 +122,11 @@ template Liquidate() {
     signal input current_price;             // Current oracle price
     signal input liquidator_reward;         // Liquidation penalty to liquidator
     signal input owner_remainder;           // Remainder to owner (if any)
+    signal input position_commitment;       // Position being liquidated (binds its PositionMeta)
+    signal input is_long;                   // Position direction
+    signal input position_margin;           // Position margin
+    signal input position_size;             // Position size
+    signal input entry_price;               // Entry price
 
     // ========================================================================
     // Private Inputs
@@ -130,11 +135,7 @@ template Liquidate() {
     // Position details
     signal input position_stealth_pub_x;
     signal input market_id;
-    signal input is_long;
-    signal input position_margin;
-    signal input position_size;
     signal input position_leverage;
-    signal input entry_price;
     signal input position_randomness;
     signal input position_spending_key;     // Owner's spending key (needed for nullifier)
 
@@ -168,6 +169,7 @@ template Liquidate() {
     pos_commit.leverage <== position_leverage;
     pos_commit.entry_price <== entry_price;
     pos_commit.randomness <== position_randomness;
+    position_commitment === pos_commit.out;
 
     // ========================================================================
     // 2. Verify Position Nullifier
@@ -274,5 +276,10 @@ component main {public [
     liquidator_commitment,
     current_price,
     liquidator_reward,
-    owner_remainder
+    owner_remainder,
+    position_commitment,
+    is_long,
+    position_margin,
+    position_size,
+    entry_price
 ]} = Liquidate();
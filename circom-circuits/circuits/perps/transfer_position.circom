@@ -0,0 +1,198 @@
+pragma circom 2.1.0;
+
+include "../../node_modules/circomlib/circuits/poseidon.circom";
+include "../../node_modules/circomlib/circuits/bitify.circom";
+
+// Domain separation constants (must match on-chain verification)
+function SPENDING_NULLIFIER_DOMAIN() { return 2; }
+function NULLIFIER_KEY_DOMAIN() { return 4; }
+function POSITION_COMMITMENT_DOMAIN() { return 8; }
+
+// ============================================================================
+// Helper Templates
+// ============================================================================
+
+// Compute position commitment
+template PositionCommitment() {
+    signal input stealth_pub_x;
+    signal input market_id;
+    signal input is_long;
+    signal input margin;
+    signal input size;
+    signal input leverage;
+    signal input entry_price;
+    signal input randomness;
+    signal output out;
+
+    component hasher1 = Poseidon(5);
+    hasher1.inputs[0] <== POSITION_COMMITMENT_DOMAIN();
+    hasher1.inputs[1] <== stealth_pub_x;
+    hasher1.inputs[2] <== market_id;
+    hasher1.inputs[3] <== is_long;
+    hasher1.inputs[4] <== margin;
+
+    component hasher2 = Poseidon(5);
+    hasher2.inputs[0] <== hasher1.out;
+    hasher2.inputs[1] <== size;
+    hasher2.inputs[2] <== leverage;
+    hasher2.inputs[3] <== entry_price;
+    hasher2.inputs[4] <== randomness;
+    out <== hasher2.out;
+}
+
+// Derive nullifier key from spending key
+template NullifierKey() {
+    signal input spending_key;
+    signal output out;
+
+    component hasher = Poseidon(3);
+    hasher.inputs[0] <== NULLIFIER_KEY_DOMAIN();
+    hasher.inputs[1] <== spending_key;
+    hasher.inputs[2] <== 0;
+    out <== hasher.out;
+}
+
+// Compute spending nullifier
+template SpendingNullifier() {
+    signal input nullifier_key;
+    signal input commitment;
+    signal input leaf_index;
+    signal output out;
+
+    component hasher = Poseidon(4);
+    hasher.inputs[0] <== SPENDING_NULLIFIER_DOMAIN();
+    hasher.inputs[1] <== nullifier_key;
+    hasher.inputs[2] <== commitment;
+    hasher.inputs[3] <== leaf_index;
+    out <== hasher.out;
+}
+
+// Range check: constrain value to 64 bits
+template RangeCheck64() {
+    signal input in;
+    component bits = Num2Bits(64);
+    bits.in <== in;
+}
+
+// ============================================================================
+// Transfer Perpetual Position Circuit: 1 Input (position) -> 1 Output (position)
+// ============================================================================
+//
+// Flow:
+// 1. Owner spends position commitment
+// 2. Creates a new position commitment owned by a new stealth key
+// 3. Circuit proves ownership and that the economic terms are unchanged
+//
+// market, direction, margin, size, leverage and entry price are carried over
+// verbatim; only the owner and randomness change. No tokens move and no
+// oracle price is read, so there is no execute phase on-chain.
+
+template TransferPosition() {
+    // ========================================================================
+    // Public Inputs
+    // ========================================================================
+    signal input merkle_root;               // Merkle root for position commitment
+    signal input position_nullifier;        // Prevents double-spending the position
+    signal input perps_pool_id;             // Perps pool identifier
+    signal input market_id;                 // Market of the position
+    signal input position_commitment;       // Position being transferred
+    signal input new_position_commitment;   // Re-created position (new owner)
+    signal input is_long;                   // Position direction
+    signal input margin_amount;             // Position margin
+    signal input position_size;             // Position size
+    signal input entry_price;               // Entry price
+
+    // ========================================================================
+    // Private Inputs
+    // ========================================================================
+
+    // Current position
+    signal input position_stealth_pub_x;
+    signal input position_leverage;
+    signal input position_randomness;
+    signal input position_spending_key;
+
+    // Merkle proof for position commitment
+    signal input merkle_path[32];
+    signal input merkle_path_indices[32];
+    signal input leaf_index;
+
+    // New owner
+    signal input new_stealth_pub_x;
+    signal input new_randomness;
+
+    // ========================================================================
+    // 1. Verify Position Commitment
+    // ========================================================================
+    component pos_commit = PositionCommitment();
+    pos_commit.stealth_pub_x <== position_stealth_pub_x;
+    pos_commit.market_id <== market_id;
+    pos_commit.is_long <== is_long;
+    pos_commit.margin <== margin_amount;
+    pos_commit.size <== position_size;
+    pos_commit.leverage <== position_leverage;
+    pos_commit.entry_price <== entry_price;
+    pos_commit.randomness <== position_randomness;
+    position_commitment === pos_commit.out;
+
+    // ========================================================================
+    // 2. Verify Position Nullifier (proves ownership)
+    // ========================================================================
+    component nk = NullifierKey();
+    nk.spending_key <== position_spending_key;
+
+    component computed_nullifier = SpendingNullifier();
+    computed_nullifier.nullifier_key <== nk.out;
+    computed_nullifier.commitment <== pos_commit.out;
+    computed_nullifier.leaf_index <== leaf_index;
+
+    position_nullifier === computed_nullifier.out;
+
+    // ========================================================================
+    // 3. Verify New Position Commitment (same terms, new owner)
+    // ========================================================================
+    component new_commit = PositionCommitment();
+    new_commit.stealth_pub_x <== new_stealth_pub_x;
+    new_commit.market_id <== market_id;
+    new_commit.is_long <== is_long;
+    new_commit.margin <== margin_amount;
+    new_commit.size <== position_size;
+    new_commit.leverage <== position_leverage;
+    new_commit.entry_price <== entry_price;
+    new_commit.randomness <== new_randomness;
+    new_position_commitment === new_commit.out;
+
+    // ========================================================================
+    // 4. Constrain is_long to binary
+    // ========================================================================
+    is_long * (1 - is_long) === 0;
+
+    // ========================================================================
+    // 5. Range Checks
+    // ========================================================================
+    component range_margin = RangeCheck64();
+    range_margin.in <== margin_amount;
+
+    component range_size = RangeCheck64();
+    range_size.in <== position_size;
+
+    component range_entry = RangeCheck64();
+    range_entry.in <== entry_price;
+
+    // Note: On-chain verification handles:
+    // - Merkle inclusion of the position commitment (Light Protocol)
+    // - PositionMeta status check and owner update
+}
+
+component main {public [
+    merkle_root,
+    position_nullifier,
+    perps_pool_id,
+    market_id,
+    position_commitment,
+    new_position_commitment,
+    is_long,
+    margin_amount,
+    position_size,
+    entry_price
+]} = TransferPosition();
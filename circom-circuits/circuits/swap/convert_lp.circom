@@ -0,0 +1,156 @@
+pragma circom 2.1.0;
+
+include "../../node_modules/circomlib/circuits/poseidon.circom";
+include "../../node_modules/circomlib/circuits/bitify.circom";
+
+// Domain separation constants (must match on-chain verification)
+function SPENDING_NULLIFIER_DOMAIN() { return 2; }
+function NULLIFIER_KEY_DOMAIN() { return 4; }
+function COMMITMENT_DOMAIN() { return 1; }
+
+// ============================================================================
+// Helper Templates
+// ============================================================================
+
+// Compute note commitment (AMM LP notes are regular notes of the LP mint)
+template Commitment() {
+    signal input stealth_pub_x;
+    signal input token_mint;
+    signal input amount;
+    signal input randomness;
+    signal output out;
+
+    component hasher = Poseidon(5);
+    hasher.inputs[0] <== COMMITMENT_DOMAIN();
+    hasher.inputs[1] <== stealth_pub_x;
+    hasher.inputs[2] <== token_mint;
+    hasher.inputs[3] <== amount;
+    hasher.inputs[4] <== randomness;
+    out <== hasher.out;
+}
+
+// Derive nullifier key from spending key
+template NullifierKey() {
+    signal input spending_key;
+    signal output out;
+
+    component hasher = Poseidon(3);
+    hasher.inputs[0] <== NULLIFIER_KEY_DOMAIN();
+    hasher.inputs[1] <== spending_key;
+    hasher.inputs[2] <== 0;
+    out <== hasher.out;
+}
+
+// Compute spending nullifier
+template SpendingNullifier() {
+    signal input nullifier_key;
+    signal input commitment;
+    signal input leaf_index;
+    signal output out;
+
+    component hasher = Poseidon(4);
+    hasher.inputs[0] <== SPENDING_NULLIFIER_DOMAIN();
+    hasher.inputs[1] <== nullifier_key;
+    hasher.inputs[2] <== commitment;
+    hasher.inputs[3] <== leaf_index;
+    out <== hasher.out;
+}
+
+// Range check: constrain value to 64 bits
+template RangeCheck64() {
+    signal input in;
+    component bits = Num2Bits(64);
+    bits.in <== in;
+}
+
+// ============================================================================
+// Convert AMM LP Note Circuit: 1 Input (old LP mint) -> 1 Output (successor LP mint)
+// ============================================================================
+//
+// After an AMM pool migration, converts a whole LP note of the old pool's
+// LP mint into a note of the successor pool's LP mint for the same owner.
+//
+// On-chain verification handles:
+// - Old/new LP mints from the migration record
+// - converted_lp_amount = lp_amount * new_lp_supply / old_lp_supply
+
+template ConvertAmmLpNote() {
+    // ========================================================================
+    // Public Inputs
+    // ========================================================================
+    signal input merkle_root;           // Merkle root for LP commitment
+    signal input lp_nullifier;          // Prevents double-converting the note
+    signal input old_lp_mint;           // Migrated pool LP mint
+    signal input new_lp_mint;           // Successor pool LP mint
+    signal input out_commitment;        // New LP commitment
+    signal input lp_amount;             // Old LP amount (whole note)
+    signal input converted_lp_amount;   // New LP amount
+
+    // ========================================================================
+    // Private Inputs
+    // ========================================================================
+
+    // LP token note details
+    signal input lp_stealth_pub_x;
+    signal input lp_randomness;
+    signal input lp_spending_key;
+
+    // Merkle proof for LP commitment
+    signal input merkle_path[32];
+    signal input merkle_path_indices[32];
+    signal input leaf_index;
+
+    // Output details
+    signal input out_randomness;
+
+    // ========================================================================
+    // 1. Verify LP Input Commitment
+    // ========================================================================
+    component lp_commit = Commitment();
+    lp_commit.stealth_pub_x <== lp_stealth_pub_x;
+    lp_commit.token_mint <== old_lp_mint;
+    lp_commit.amount <== lp_amount;
+    lp_commit.randomness <== lp_randomness;
+
+    // ========================================================================
+    // 2. Verify LP Nullifier (proves ownership)
+    // ========================================================================
+    component nk = NullifierKey();
+    nk.spending_key <== lp_spending_key;
+
+    component computed_nullifier = SpendingNullifier();
+    computed_nullifier.nullifier_key <== nk.out;
+    computed_nullifier.commitment <== lp_commit.out;
+    computed_nullifier.leaf_index <== leaf_index;
+
+    lp_nullifier === computed_nullifier.out;
+
+    // ========================================================================
+    // 3. Verify New LP Commitment (same owner)
+    // ========================================================================
+    component out_commit = Commitment();
+    out_commit.stealth_pub_x <== lp_stealth_pub_x;
+    out_commit.token_mint <== new_lp_mint;
+    out_commit.amount <== converted_lp_amount;
+    out_commit.randomness <== out_randomness;
+    out_commitment === out_commit.out;
+
+    // ========================================================================
+    // 4. Range Checks
+    // ========================================================================
+    component range_lp = RangeCheck64();
+    range_lp.in <== lp_amount;
+
+    component range_converted = RangeCheck64();
+    range_converted.in <== converted_lp_amount;
+}
+
+component main {public [
+    merkle_root,
+    lp_nullifier,
+    old_lp_mint,
+    new_lp_mint,
+    out_commitment,
+    lp_amount,
+    converted_lp_amount
+]} = ConvertAmmLpNote();
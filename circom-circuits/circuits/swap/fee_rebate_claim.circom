@@ -0,0 +1,115 @@
+pragma circom 2.1.0;
+
+include "../../node_modules/circomlib/circuits/poseidon.circom";
+include "../../node_modules/circomlib/circuits/bitify.circom";
+
+// Domain separation constants (must match on-chain verification)
+function COMMITMENT_DOMAIN() { return 1; }
+
+// ============================================================================
+// Helper Templates
+// ============================================================================
+
+// Compute note commitment: Poseidon(domain, stealth_pub_x, token_mint, amount, randomness)
+template Commitment() {
+    signal input stealth_pub_x;
+    signal input token_mint;
+    signal input amount;
+    signal input randomness;
+    signal output out;
+
+    component hasher = Poseidon(5);
+    hasher.inputs[0] <== COMMITMENT_DOMAIN();
+    hasher.inputs[1] <== stealth_pub_x;
+    hasher.inputs[2] <== token_mint;
+    hasher.inputs[3] <== amount;
+    hasher.inputs[4] <== randomness;
+    out <== hasher.out;
+}
+
+// Compute swap volume tag: Poseidon(volume_key, epoch)
+template VolumeTag() {
+    signal input volume_key;
+    signal input epoch;
+    signal output out;
+
+    component hasher = Poseidon(2);
+    hasher.inputs[0] <== volume_key;
+    hasher.inputs[1] <== epoch;
+    out <== hasher.out;
+}
+
+// Range check: constrain value to 64 bits
+template RangeCheck64() {
+    signal input in;
+
+    // Decompose to bits - this constrains the value to fit in 64 bits
+    component bits = Num2Bits(64);
+    bits.in <== in;
+}
+
+// ============================================================================
+// Fee Rebate Claim Circuit - Rebate Note
+// ============================================================================
+//
+// After an epoch ends, the trader proves knowledge of the volume_key behind
+// a swap volume tag and receives the tier rebate as a new note. No input
+// notes are spent.
+//
+// Verifies:
+// 1. volume_tag = Poseidon(volume_key, epoch)
+// 2. rebate_commitment holds rebate_amount of token_mint
+//
+// fee_rebate_config is only bound as a public input; the program checks
+// rebate_amount against the tier the tagged volume reached.
+template FeeRebateClaim() {
+    // ========================================================================
+    // Public Inputs
+    // ========================================================================
+    signal input volume_tag;            // Swap volume tag
+    signal input epoch;                 // Rebate epoch
+    signal input fee_rebate_config;     // Fee rebate config address
+    signal input rebate_commitment;     // Rebate note commitment
+    signal input rebate_amount;         // Tier rebate (verified on-chain)
+    signal input token_mint;            // Rebate token (pool token)
+
+    // ========================================================================
+    // Private Inputs
+    // ========================================================================
+    signal input volume_key;
+
+    // Rebate note details
+    signal input rebate_stealth_pub_x;
+    signal input rebate_randomness;
+
+    // ========================================================================
+    // 1. Verify Volume Tag
+    // ========================================================================
+    component tag = VolumeTag();
+    tag.volume_key <== volume_key;
+    tag.epoch <== epoch;
+    volume_tag === tag.out;
+
+    // ========================================================================
+    // 2. Verify Rebate Commitment
+    // ========================================================================
+    component rebate = Commitment();
+    rebate.stealth_pub_x <== rebate_stealth_pub_x;
+    rebate.token_mint <== token_mint;
+    rebate.amount <== rebate_amount;
+    rebate.randomness <== rebate_randomness;
+    rebate_commitment === rebate.out;
+
+    component range_rebate = RangeCheck64();
+    range_rebate.in <== rebate_amount;
+}
+
+// Main component with public inputs
+component main {public [
+    volume_tag,
+    epoch,
+    fee_rebate_config,
+    rebate_commitment,
+    rebate_amount,
+    token_mint
+]} = FeeRebateClaim();
@@ -74,6 +74,7 @@ template RemoveLiquidity() {
     signal input out_b_commitment;      // Token B output commitment
     signal input old_state_hash;        // AMM pool state before removal
     signal input new_state_hash;        // AMM pool state after removal
+    signal input lp_input_commitment;   // LP note being spent (keys its LP lock)
 
     // ========================================================================
     // Private Inputs - LP Token Input
@@ -113,6 +114,7 @@ template RemoveLiquidity() {
     lp_commitment.token_mint <== lp_token_mint;
     lp_commitment.amount <== lp_amount;
     lp_commitment.randomness <== lp_randomness;
+    lp_input_commitment === lp_commitment.out;
 
     // ========================================================================
     // 2. Verify LP Token Nullifier
@@ -169,5 +171,6 @@ component main {public [
     out_a_commitment,
     out_b_commitment,
     old_state_hash,
-    new_state_hash
+    new_state_hash,
+    lp_input_commitment
 ]} = RemoveLiquidity();
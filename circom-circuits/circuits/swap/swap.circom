@@ -78,6 +78,7 @@ template Swap() {
     signal input out_commitment;        // Swap output commitment (output token)
     signal input change_commitment;     // Change commitment (input token)
     signal input min_output;            // Minimum output amount (slippage protection)
+    signal input volume_tag;            // Swap volume tag credited in Phase 3 (0 = none)
 
     // ========================================================================
     // Private Inputs
@@ -196,6 +197,12 @@ template Swap() {
     // - Outputs are correctly committed
     // - Balance is conserved on input side
     // - Output meets minimum slippage requirement
+
+    // ========================================================================
+    // Bind the fee rebate volume tag (checked against the swap_volume record)
+    // ========================================================================
+    signal volume_tag_sq;
+    volume_tag_sq <== volume_tag * volume_tag;
 }
 
 component main {public [
@@ -204,5 +211,6 @@ component main {public [
     pool_id,
     out_commitment,
     change_commitment,
-    min_output
+    min_output,
+    volume_tag
 ]} = Swap();
@@ -0,0 +1,414 @@
+pragma circom 2.1.0;
+
+include "../../node_modules/circomlib/circuits/poseidon.circom";
+include "../../node_modules/circomlib/circuits/bitify.circom";
+include "../../node_modules/circomlib/circuits/comparators.circom";
+
+// Domain separation constants (must match on-chain verification)
+function COMMITMENT_DOMAIN() { return 1; }
+function NULLIFIER_KEY_DOMAIN() { return 4; }
+function POSITION_DOMAIN() { return 0x13; }
+
+// Vote types
+function VOTE_TYPE_SINGLE() { return 0; }
+function VOTE_TYPE_APPROVAL() { return 1; }
+function VOTE_TYPE_RANKED() { return 2; }
+function VOTE_TYPE_WEIGHTED() { return 3; }
+
+// ============================================================================
+// Helper Templates
+// ============================================================================
+
+// Compute note commitment: Poseidon(domain, stealth_pub_x, token_mint, amount, randomness)
+template Commitment() {
+    signal input stealth_pub_x;
+    signal input token_mint;
+    signal input amount;
+    signal input randomness;
+    signal output out;
+
+    component hasher = Poseidon(5);
+    hasher.inputs[0] <== COMMITMENT_DOMAIN();
+    hasher.inputs[1] <== stealth_pub_x;
+    hasher.inputs[2] <== token_mint;
+    hasher.inputs[3] <== amount;
+    hasher.inputs[4] <== randomness;
+    out <== hasher.out;
+}
+
+// Derive nullifier key from spending key
+template NullifierKey() {
+    signal input spending_key;
+    signal output out;
+
+    component hasher = Poseidon(3);
+    hasher.inputs[0] <== NULLIFIER_KEY_DOMAIN();
+    hasher.inputs[1] <== spending_key;
+    hasher.inputs[2] <== 0;
+    out <== hasher.out;
+}
+
+// Compute position nullifier
+template PositionNullifier() {
+    signal input nullifier_key;
+    signal input position_commitment;
+    signal output out;
+
+    component hasher = Poseidon(3);
+    hasher.inputs[0] <== POSITION_DOMAIN();
+    hasher.inputs[1] <== nullifier_key;
+    hasher.inputs[2] <== position_commitment;
+    out <== hasher.out;
+}
+
+// Compute position commitment for voting
+template PositionCommitment() {
+    signal input ballot_id;
+    signal input pubkey;
+    signal input vote_choice;
+    signal input amount;
+    signal input weight;
+    signal input randomness;
+    signal output out;
+
+    component hasher1 = Poseidon(4);
+    hasher1.inputs[0] <== POSITION_DOMAIN();
+    hasher1.inputs[1] <== ballot_id;
+    hasher1.inputs[2] <== pubkey;
+    hasher1.inputs[3] <== vote_choice;
+
+    component hasher2 = Poseidon(4);
+    hasher2.inputs[0] <== hasher1.out;
+    hasher2.inputs[1] <== amount;
+    hasher2.inputs[2] <== weight;
+    hasher2.inputs[3] <== randomness;
+    out <== hasher2.out;
+}
+
+// Range check: constrain value to 64 bits
+template RangeCheck64() {
+    signal input in;
+    component bits = Num2Bits(64);
+    bits.in <== in;
+}
+
+// Check if user voted for the winner (Single/Weighted vote type)
+template IsWinnerSingle() {
+    signal input vote_choice;
+    signal input outcome;
+    signal output is_winner;
+
+    component eq = IsEqual();
+    eq.in[0] <== vote_choice;
+    eq.in[1] <== outcome;
+    is_winner <== eq.out;
+}
+
+// Check if user approved the winner (Approval vote type)
+// vote_choice is a bitmap, outcome is the winning option index
+template IsWinnerApproval() {
+    signal input vote_choice;  // Bitmap of approved options
+    signal input outcome;      // Winning option index
+    signal output is_winner;
+
+    // Check if bit at position `outcome` is set
+    // is_winner = (vote_choice >> outcome) & 1
+    component bits = Num2Bits(16);
+    bits.in <== vote_choice;
+
+    // Select the bit at outcome position (0-15)
+    component mux = Mux16();
+    for (var i = 0; i < 16; i++) {
+        mux.c[i] <== bits.out[i];
+    }
+    component sel_bits = Num2Bits(4);
+    sel_bits.in <== outcome;
+    mux.s[0] <== sel_bits.out[0];
+    mux.s[1] <== sel_bits.out[1];
+    mux.s[2] <== sel_bits.out[2];
+    mux.s[3] <== sel_bits.out[3];
+
+    is_winner <== mux.out;
+}
+
+// 16-input multiplexer
+template Mux16() {
+    signal input c[16];
+    signal input s[4];
+    signal output out;
+
+    // Declare all components at the top
+    component sub_mux0 = Mux4();
+    component sub_mux1 = Mux4();
+    component sub_mux2 = Mux4();
+    component sub_mux3 = Mux4();
+    component mux1 = Mux4();
+
+    // Connect sub_mux0 (inputs 0-3)
+    sub_mux0.c[0] <== c[0];
+    sub_mux0.c[1] <== c[1];
+    sub_mux0.c[2] <== c[2];
+    sub_mux0.c[3] <== c[3];
+    sub_mux0.s[0] <== s[0];
+    sub_mux0.s[1] <== s[1];
+
+    // Connect sub_mux1 (inputs 4-7)
+    sub_mux1.c[0] <== c[4];
+    sub_mux1.c[1] <== c[5];
+    sub_mux1.c[2] <== c[6];
+    sub_mux1.c[3] <== c[7];
+    sub_mux1.s[0] <== s[0];
+    sub_mux1.s[1] <== s[1];
+
+    // Connect sub_mux2 (inputs 8-11)
+    sub_mux2.c[0] <== c[8];
+    sub_mux2.c[1] <== c[9];
+    sub_mux2.c[2] <== c[10];
+    sub_mux2.c[3] <== c[11];
+    sub_mux2.s[0] <== s[0];
+    sub_mux2.s[1] <== s[1];
+
+    // Connect sub_mux3 (inputs 12-15)
+    sub_mux3.c[0] <== c[12];
+    sub_mux3.c[1] <== c[13];
+    sub_mux3.c[2] <== c[14];
+    sub_mux3.c[3] <== c[15];
+    sub_mux3.s[0] <== s[0];
+    sub_mux3.s[1] <== s[1];
+
+    // Final mux
+    mux1.c[0] <== sub_mux0.out;
+    mux1.c[1] <== sub_mux1.out;
+    mux1.c[2] <== sub_mux2.out;
+    mux1.c[3] <== sub_mux3.out;
+    mux1.s[0] <== s[2];
+    mux1.s[1] <== s[3];
+    out <== mux1.out;
+}
+
+// 4-input multiplexer
+template Mux4() {
+    signal input c[4];
+    signal input s[2];
+    signal output out;
+
+    signal m0;
+    signal m1;
+    m0 <== c[0] + s[0] * (c[1] - c[0]);
+    m1 <== c[2] + s[0] * (c[3] - c[2]);
+    out <== m0 + s[1] * (m1 - m0);
+}
+// ============================================================================
+// Claim Multi Circuit - SpendToVote Payout for up to 3 Positions
+// ============================================================================
+//
+// Claims the payouts of up to 3 positions of the same owner on one ballot
+// into a single payout commitment, like consolidate_3x1 does for notes.
+// Each position's payout is (user_weight / winner_weight) * total_pool,
+// checked exactly on-chain against the public position_gross.
+//
+// Unused slots have position_commitment = 0 and must carry no weight.
+//
+// Verifies, per used position:
+// 1. User owns the position
+// 2. Position nullifier is correctly derived
+// 3. Position payout is 0 unless it voted for the winner
+// and overall:
+// 4. gross_payout is the sum of the position payouts
+// 5. Payout commitment is valid
+//
+// For Public/TimeLocked mode: user_vote_choice[i] is public
+// For PermanentPrivate mode: private_vote_choice[i] is used instead
+
+template ClaimMulti(N) {
+    // ========================================================================
+    // Public Inputs
+    // ========================================================================
+    signal input ballot_id;
+    signal input position_commitment[N];
+    signal input position_nullifier[N];
+    signal input payout_commitment;      // Single token note for all payouts
+    signal input gross_payout;           // Sum before fees
+    signal input net_payout;             // Sum after fees
+    signal input vote_type;              // 0=Single, 1=Approval, 2=Ranked, 3=Weighted
+    signal input user_vote_choice[N];    // Public/TimeLocked vote choices (0 otherwise)
+    signal input is_private_mode;        // 1 if PermanentPrivate, 0 otherwise
+    signal input user_weight[N];         // Weight per position
+    signal input position_gross[N];      // Payout per position (0 if not a winner)
+    signal input outcome;                // Winning option (set by resolution)
+    signal input total_pool;             // Total pool balance
+    signal input winner_weight;          // Total weight that voted for winner
+    signal input protocol_fee_bps;       // Fee in basis points
+    signal input token_mint;             // Token type
+
+    // ========================================================================
+    // Private Inputs
+    // ========================================================================
+    signal input spending_key;
+    signal input pubkey;
+
+    signal input position_amount[N];
+    signal input position_randomness[N];
+    signal input private_vote_choice[N];
+
+    signal input payout_randomness;
+
+    // ========================================================================
+    // 1. Derive Nullifier Key (one owner for all positions)
+    // ========================================================================
+    component nk = NullifierKey();
+    nk.spending_key <== spending_key;
+
+    component eq_single = IsEqual();
+    eq_single.in[0] <== vote_type;
+    eq_single.in[1] <== VOTE_TYPE_SINGLE();
+
+    component eq_weighted = IsEqual();
+    eq_weighted.in[0] <== vote_type;
+    eq_weighted.in[1] <== VOTE_TYPE_WEIGHTED();
+
+    signal eq_single_times_weighted;
+    eq_single_times_weighted <== eq_single.out * eq_weighted.out;
+    signal is_single_or_weighted;
+    is_single_or_weighted <== eq_single.out + eq_weighted.out - eq_single_times_weighted;
+
+    component eq_approval = IsEqual();
+    eq_approval.in[0] <== vote_type;
+    eq_approval.in[1] <== VOTE_TYPE_APPROVAL();
+
+    component is_unused[N];
+    component pos_commit[N];
+    component computed_nullifier[N];
+    component is_winner_single[N];
+    component is_winner_approval[N];
+    component range_weight[N];
+    component range_gross[N];
+
+    signal is_used[N];
+    signal choice_private[N];
+    signal choice_public[N];
+    signal effective_vote_choice[N];
+    signal commitment_check[N];
+    signal nullifier_check[N];
+    signal winner_single_term[N];
+    signal winner_approval_term[N];
+    signal is_winner[N];
+    signal unused_weight[N];
+    signal paid[N];
+    signal not_paid_check[N];
+    signal gross_sum[N + 1];
+    gross_sum[0] <== 0;
+
+    for (var i = 0; i < N; i++) {
+        is_unused[i] = IsZero();
+        is_unused[i].in <== position_commitment[i];
+        is_used[i] <== 1 - is_unused[i].out;
+
+        // ====================================================================
+        // 2. Effective Vote Choice
+        // ====================================================================
+        choice_private[i] <== is_private_mode * private_vote_choice[i];
+        choice_public[i] <== (1 - is_private_mode) * user_vote_choice[i];
+        effective_vote_choice[i] <== choice_private[i] + choice_public[i];
+
+        // ====================================================================
+        // 3. Verify Position Commitment and Nullifier (used slots only)
+        // ====================================================================
+        pos_commit[i] = PositionCommitment();
+        pos_commit[i].ballot_id <== ballot_id;
+        pos_commit[i].pubkey <== pubkey;
+        pos_commit[i].vote_choice <== effective_vote_choice[i];
+        pos_commit[i].amount <== position_amount[i];
+        pos_commit[i].weight <== user_weight[i];
+        pos_commit[i].randomness <== position_randomness[i];
+
+        commitment_check[i] <== is_used[i] * (position_commitment[i] - pos_commit[i].out);
+        commitment_check[i] === 0;
+
+        computed_nullifier[i] = PositionNullifier();
+        computed_nullifier[i].nullifier_key <== nk.out;
+        computed_nullifier[i].position_commitment <== position_commitment[i];
+
+        nullifier_check[i] <== is_used[i] * (position_nullifier[i] - computed_nullifier[i].out);
+        nullifier_check[i] === 0;
+
+        // Unused slots carry no weight
+        unused_weight[i] <== is_unused[i].out * user_weight[i];
+        unused_weight[i] === 0;
+
+        // ====================================================================
+        // 4. Winner Check
+        // ====================================================================
+        is_winner_single[i] = IsWinnerSingle();
+        is_winner_single[i].vote_choice <== effective_vote_choice[i];
+        is_winner_single[i].outcome <== outcome;
+
+        is_winner_approval[i] = IsWinnerApproval();
+        is_winner_approval[i].vote_choice <== effective_vote_choice[i];
+        is_winner_approval[i].outcome <== outcome;
+
+        winner_single_term[i] <== is_single_or_weighted * is_winner_single[i].is_winner;
+        winner_approval_term[i] <== eq_approval.out * is_winner_approval[i].is_winner;
+        is_winner[i] <== winner_single_term[i] + winner_approval_term[i];
+
+        // Only used, winning positions are paid (exact amount checked on-chain)
+        paid[i] <== is_used[i] * is_winner[i];
+        not_paid_check[i] <== (1 - paid[i]) * position_gross[i];
+        not_paid_check[i] === 0;
+
+        gross_sum[i + 1] <== gross_sum[i] + position_gross[i];
+
+        range_weight[i] = RangeCheck64();
+        range_weight[i].in <== user_weight[i];
+
+        range_gross[i] = RangeCheck64();
+        range_gross[i].in <== position_gross[i];
+    }
+
+    // ========================================================================
+    // 5. Verify Payout Sum and Commitment
+    // ========================================================================
+    gross_payout === gross_sum[N];
+
+    component payout = Commitment();
+    payout.stealth_pub_x <== pubkey;
+    payout.token_mint <== token_mint;
+    payout.amount <== net_payout;
+    payout.randomness <== payout_randomness;
+
+    payout_commitment === payout.out;
+
+    // ========================================================================
+    // 6. Range Checks
+    // ========================================================================
+    component range_net = RangeCheck64();
+    range_net.in <== net_payout;
+
+    component range_pool = RangeCheck64();
+    range_pool.in <== total_pool;
+
+    // Fees cannot exceed the gross payout
+    component fee_ok = LessEqThan(64);
+    fee_ok.in[0] <== net_payout;
+    fee_ok.in[1] <== gross_payout;
+    fee_ok.out === 1;
+}
+
+component main {public [
+    ballot_id,
+    position_commitment,
+    position_nullifier,
+    payout_commitment,
+    gross_payout,
+    net_payout,
+    vote_type,
+    user_vote_choice,
+    is_private_mode,
+    user_weight,
+    position_gross,
+    outcome,
+    total_pool,
+    winner_weight,
+    protocol_fee_bps,
+    token_mint
+]} = ClaimMulti(3);
@@ -0,0 +1,349 @@
+pragma circom 2.1.0;
+
+include "../../node_modules/circomlib/circuits/poseidon.circom";
+include "../../node_modules/circomlib/circuits/bitify.circom";
+include "../../node_modules/circomlib/circuits/comparators.circom";
+
+// Domain separation constants (must match on-chain verification)
+function COMMITMENT_DOMAIN() { return 1; }
+function NULLIFIER_KEY_DOMAIN() { return 4; }
+function POSITION_DOMAIN() { return 0x13; }
+
+// Vote types
+function VOTE_TYPE_SINGLE() { return 0; }
+function VOTE_TYPE_APPROVAL() { return 1; }
+function VOTE_TYPE_RANKED() { return 2; }
+function VOTE_TYPE_WEIGHTED() { return 3; }
+
+// ============================================================================
+// Helper Templates
+// ============================================================================
+
+// Compute note commitment: Poseidon(domain, stealth_pub_x, token_mint, amount, randomness)
+template Commitment() {
+    signal input stealth_pub_x;
+    signal input token_mint;
+    signal input amount;
+    signal input randomness;
+    signal output out;
+
+    component hasher = Poseidon(5);
+    hasher.inputs[0] <== COMMITMENT_DOMAIN();
+    hasher.inputs[1] <== stealth_pub_x;
+    hasher.inputs[2] <== token_mint;
+    hasher.inputs[3] <== amount;
+    hasher.inputs[4] <== randomness;
+    out <== hasher.out;
+}
+
+// Derive nullifier key from spending key
+template NullifierKey() {
+    signal input spending_key;
+    signal output out;
+
+    component hasher = Poseidon(3);
+    hasher.inputs[0] <== NULLIFIER_KEY_DOMAIN();
+    hasher.inputs[1] <== spending_key;
+    hasher.inputs[2] <== 0;
+    out <== hasher.out;
+}
+
+// Compute position nullifier
+template PositionNullifier() {
+    signal input nullifier_key;
+    signal input position_commitment;
+    signal output out;
+
+    component hasher = Poseidon(3);
+    hasher.inputs[0] <== POSITION_DOMAIN();
+    hasher.inputs[1] <== nullifier_key;
+    hasher.inputs[2] <== position_commitment;
+    out <== hasher.out;
+}
+
+// Compute position commitment for voting
+template PositionCommitment() {
+    signal input ballot_id;
+    signal input pubkey;
+    signal input vote_choice;
+    signal input amount;
+    signal input weight;
+    signal input randomness;
+    signal output out;
+
+    component hasher1 = Poseidon(4);
+    hasher1.inputs[0] <== POSITION_DOMAIN();
+    hasher1.inputs[1] <== ballot_id;
+    hasher1.inputs[2] <== pubkey;
+    hasher1.inputs[3] <== vote_choice;
+
+    component hasher2 = Poseidon(4);
+    hasher2.inputs[0] <== hasher1.out;
+    hasher2.inputs[1] <== amount;
+    hasher2.inputs[2] <== weight;
+    hasher2.inputs[3] <== randomness;
+    out <== hasher2.out;
+}
+
+// Range check: constrain value to 64 bits
+template RangeCheck64() {
+    signal input in;
+    component bits = Num2Bits(64);
+    bits.in <== in;
+}
+
+// Check if user voted for the winner (Single/Weighted vote type)
+template IsWinnerSingle() {
+    signal input vote_choice;
+    signal input outcome;
+    signal output is_winner;
+
+    component eq = IsEqual();
+    eq.in[0] <== vote_choice;
+    eq.in[1] <== outcome;
+    is_winner <== eq.out;
+}
+
+// Check if user approved the winner (Approval vote type)
+// vote_choice is a bitmap, outcome is the winning option index
+template IsWinnerApproval() {
+    signal input vote_choice;  // Bitmap of approved options
+    signal input outcome;      // Winning option index
+    signal output is_winner;
+
+    // Check if bit at position `outcome` is set
+    // is_winner = (vote_choice >> outcome) & 1
+    component bits = Num2Bits(16);
+    bits.in <== vote_choice;
+
+    // Select the bit at outcome position (0-15)
+    component mux = Mux16();
+    for (var i = 0; i < 16; i++) {
+        mux.c[i] <== bits.out[i];
+    }
+    component sel_bits = Num2Bits(4);
+    sel_bits.in <== outcome;
+    mux.s[0] <== sel_bits.out[0];
+    mux.s[1] <== sel_bits.out[1];
+    mux.s[2] <== sel_bits.out[2];
+    mux.s[3] <== sel_bits.out[3];
+
+    is_winner <== mux.out;
+}
+
+// 16-input multiplexer
+template Mux16() {
+    signal input c[16];
+    signal input s[4];
+    signal output out;
+
+    // Declare all components at the top
+    component sub_mux0 = Mux4();
+    component sub_mux1 = Mux4();
+    component sub_mux2 = Mux4();
+    component sub_mux3 = Mux4();
+    component mux1 = Mux4();
+
+    // Connect sub_mux0 (inputs 0-3)
+    sub_mux0.c[0] <== c[0];
+    sub_mux0.c[1] <== c[1];
+    sub_mux0.c[2] <== c[2];
+    sub_mux0.c[3] <== c[3];
+    sub_mux0.s[0] <== s[0];
+    sub_mux0.s[1] <== s[1];
+
+    // Connect sub_mux1 (inputs 4-7)
+    sub_mux1.c[0] <== c[4];
+    sub_mux1.c[1] <== c[5];
+    sub_mux1.c[2] <== c[6];
+    sub_mux1.c[3] <== c[7];
+    sub_mux1.s[0] <== s[0];
+    sub_mux1.s[1] <== s[1];
+
+    // Connect sub_mux2 (inputs 8-11)
+    sub_mux2.c[0] <== c[8];
+    sub_mux2.c[1] <== c[9];
+    sub_mux2.c[2] <== c[10];
+    sub_mux2.c[3] <== c[11];
+    sub_mux2.s[0] <== s[0];
+    sub_mux2.s[1] <== s[1];
+
+    // Connect sub_mux3 (inputs 12-15)
+    sub_mux3.c[0] <== c[12];
+    sub_mux3.c[1] <== c[13];
+    sub_mux3.c[2] <== c[14];
+    sub_mux3.c[3] <== c[15];
+    sub_mux3.s[0] <== s[0];
+    sub_mux3.s[1] <== s[1];
+
+    // Final mux
+    mux1.c[0] <== sub_mux0.out;
+    mux1.c[1] <== sub_mux1.out;
+    mux1.c[2] <== sub_mux2.out;
+    mux1.c[3] <== sub_mux3.out;
+    mux1.s[0] <== s[2];
+    mux1.s[1] <== s[3];
+    out <== mux1.out;
+}
+
+// 4-input multiplexer
+template Mux4() {
+    signal input c[4];
+    signal input s[2];
+    signal output out;
+
+    signal m0;
+    signal m1;
+    m0 <== c[0] + s[0] * (c[1] - c[0]);
+    m1 <== c[2] + s[0] * (c[3] - c[2]);
+    out <== m0 + s[1] * (m1 - m0);
+}
+// ============================================================================
+// Claim Refund Circuit - SpendToVote Loser Refund
+// ============================================================================
+//
+// On ballots with loser_refund_bps > 0, losing positions claim back part of
+// their stake: (user_weight / total_weight) * total_pool * loser_refund_bps,
+// checked exactly on-chain against the public refund_amount.
+//
+// The position is nullified (one refund, and no payout, per position).
+// A new refund commitment is created with refund_amount (no protocol fee).
+//
+// Verifies:
+// 1. User owns the position
+// 2. Position nullifier is correctly derived
+// 3. User did NOT vote for the winner (based on vote_type)
+// 4. Refund commitment is valid
+//
+// For Public/TimeLocked mode: user_vote_choice is public
+// For PermanentPrivate mode: user_vote_choice is private
+
+template ClaimRefund() {
+    // ========================================================================
+    // Public Inputs
+    // ========================================================================
+    signal input ballot_id;
+    signal input position_commitment;
+    signal input position_nullifier;
+    signal input refund_commitment;      // New token note for the refund
+    signal input refund_amount;
+    signal input vote_type;              // 0=Single, 1=Approval, 2=Ranked, 3=Weighted
+    signal input user_vote_choice;       // Public/TimeLocked vote choice (0 otherwise)
+    signal input is_private_mode;        // 1 if PermanentPrivate, 0 otherwise
+    signal input user_weight;
+    signal input outcome;                // Winning option (set by resolution)
+    signal input token_mint;
+
+    // ========================================================================
+    // Private Inputs
+    // ========================================================================
+    signal input spending_key;
+    signal input pubkey;
+
+    signal input position_amount;
+    signal input position_randomness;
+    signal input private_vote_choice;    // Used in PermanentPrivate mode
+
+    signal input refund_randomness;
+
+    // ========================================================================
+    // 1. Derive Nullifier Key and Effective Vote Choice
+    // ========================================================================
+    component nk = NullifierKey();
+    nk.spending_key <== spending_key;
+
+    signal choice_private;
+    choice_private <== is_private_mode * private_vote_choice;
+    signal choice_public;
+    choice_public <== (1 - is_private_mode) * user_vote_choice;
+    signal effective_vote_choice;
+    effective_vote_choice <== choice_private + choice_public;
+
+    // ========================================================================
+    // 2. Verify Position Commitment and Nullifier
+    // ========================================================================
+    component pos_commit = PositionCommitment();
+    pos_commit.ballot_id <== ballot_id;
+    pos_commit.pubkey <== pubkey;
+    pos_commit.vote_choice <== effective_vote_choice;
+    pos_commit.amount <== position_amount;
+    pos_commit.weight <== user_weight;
+    pos_commit.randomness <== position_randomness;
+
+    position_commitment === pos_commit.out;
+
+    component computed_nullifier = PositionNullifier();
+    computed_nullifier.nullifier_key <== nk.out;
+    computed_nullifier.position_commitment <== position_commitment;
+
+    position_nullifier === computed_nullifier.out;
+
+    // ========================================================================
+    // 3. Check User is NOT a Winner
+    // ========================================================================
+    component is_winner_single = IsWinnerSingle();
+    is_winner_single.vote_choice <== effective_vote_choice;
+    is_winner_single.outcome <== outcome;
+
+    component is_winner_approval = IsWinnerApproval();
+    is_winner_approval.vote_choice <== effective_vote_choice;
+    is_winner_approval.outcome <== outcome;
+
+    component eq_single = IsEqual();
+    eq_single.in[0] <== vote_type;
+    eq_single.in[1] <== VOTE_TYPE_SINGLE();
+
+    component eq_weighted = IsEqual();
+    eq_weighted.in[0] <== vote_type;
+    eq_weighted.in[1] <== VOTE_TYPE_WEIGHTED();
+
+    signal eq_single_times_weighted;
+    eq_single_times_weighted <== eq_single.out * eq_weighted.out;
+    signal is_single_or_weighted;
+    is_single_or_weighted <== eq_single.out + eq_weighted.out - eq_single_times_weighted;
+
+    component eq_approval = IsEqual();
+    eq_approval.in[0] <== vote_type;
+    eq_approval.in[1] <== VOTE_TYPE_APPROVAL();
+
+    signal winner_single_term;
+    winner_single_term <== is_single_or_weighted * is_winner_single.is_winner;
+    signal winner_approval_term;
+    winner_approval_term <== eq_approval.out * is_winner_approval.is_winner;
+
+    winner_single_term + winner_approval_term === 0;
+
+    // ========================================================================
+    // 4. Verify Refund Commitment
+    // ========================================================================
+    component refund = Commitment();
+    refund.stealth_pub_x <== pubkey;
+    refund.token_mint <== token_mint;
+    refund.amount <== refund_amount;
+    refund.randomness <== refund_randomness;
+
+    refund_commitment === refund.out;
+
+    // ========================================================================
+    // 5. Range Checks
+    // ========================================================================
+    component range_refund = RangeCheck64();
+    range_refund.in <== refund_amount;
+
+    component range_weight = RangeCheck64();
+    range_weight.in <== user_weight;
+}
+
+component main {public [
+    ballot_id,
+    position_commitment,
+    position_nullifier,
+    refund_commitment,
+    refund_amount,
+    vote_type,
+    user_vote_choice,
+    is_private_mode,
+    user_weight,
+    outcome,
+    token_mint
+]} = ClaimRefund();
@@ -33,6 +33,8 @@ CIRCUITS=(
     "add_liquidity"
     "remove_liquidity"
     "liquidate"
+    "transfer_position"
+    "convert_lp"
 )
 
 echo "=========================================="
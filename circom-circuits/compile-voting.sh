@@ -29,10 +29,13 @@ fi
 # Circuits to compile
 CIRCUITS=(
     "vote_snapshot"
+    "vote_twab"
     "change_vote_snapshot"
     "vote_spend"
     "close_position"
     "claim"
+    "claim_multi"
+    "claim_refund"
 )
 
 echo "=========================================="
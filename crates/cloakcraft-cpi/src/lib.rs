@@ -0,0 +1,402 @@
+//! CloakCraft CPI interface
+//!
+//! Lets other programs deposit tokens into CloakCraft shielded pools without
+//! depending on the program crate (and its Light Protocol and verifier
+//! dependencies). Two instructions are exposed:
+//!
+//! - `shield_signed`: the depositor is the calling program's PDA, signing
+//!   through invoke_signed, and a separate payer funds the note's compressed
+//!   account
+//! - `verify_external_inclusion`: checks a commitment's merkle path against
+//!   the pool's root registry and returns the root's slot (read-only)
+//!
+//! The caller builds the note off-chain (commitment, encrypted note, Light
+//! validity proof) exactly as for `shield`, then passes the Light system
+//! accounts as remaining accounts:
+//!
+//! ```ignore
+//! let seeds: &[&[&[u8]]] = &[&[b"vault_authority", &[bump]]];
+//! cloakcraft_cpi::shield_signed(
+//!     CpiContext::new_with_signer(cloakcraft_program, accounts, seeds)
+//!         .with_remaining_accounts(light_accounts),
+//!     args,
+//! )?;
+//! ```
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+
+declare_id!("2VWF9TxMFgzHwbd5WPpYKoqHvtzk3fN66Ka3tVV82nZG");
+
+/// PDA seeds (match `cloakcraft::constants::seeds`)
+pub mod seeds {
+    pub const POOL: &[u8] = b"pool";
+    pub const VAULT: &[u8] = b"vault";
+    pub const COMMITMENT_COUNTER: &[u8] = b"commitment_counter";
+    pub const POOL_STATS: &[u8] = b"pool_stats";
+    pub const ROOT_REGISTRY: &[u8] = b"root_registry";
+}
+
+/// Depth of the root registry tree (matches `cloakcraft::constants::MERKLE_TREE_DEPTH`)
+pub const MERKLE_TREE_DEPTH: usize = 16;
+
+/// PDA derivation for the accounts the exposed instructions need
+pub mod pda {
+    use super::*;
+
+    pub fn pool(token_mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[seeds::POOL, token_mint.as_ref()], &ID).0
+    }
+
+    pub fn vault(token_mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[seeds::VAULT, token_mint.as_ref()], &ID).0
+    }
+
+    pub fn commitment_counter(pool: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[seeds::COMMITMENT_COUNTER, pool.as_ref()], &ID).0
+    }
+
+    pub fn pool_stats(pool: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[seeds::POOL_STATS, pool.as_ref()], &ID).0
+    }
+
+    pub fn root_registry(pool: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[seeds::ROOT_REGISTRY, pool.as_ref()], &ID).0
+    }
+}
+
+/// Light validity proof (same layout as `cloakcraft::state::LightValidityProof`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LightValidityProof {
+    pub a: [u8; 32],
+    pub b: [u8; 64],
+    pub c: [u8; 32],
+}
+
+/// Address tree info (same layout as `cloakcraft::state::LightAddressTreeInfo`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct LightAddressTreeInfo {
+    /// Index of the address merkle tree in remaining accounts
+    pub address_merkle_tree_pubkey_index: u8,
+    /// Index of the address queue in remaining accounts
+    pub address_queue_pubkey_index: u8,
+    pub root_index: u16,
+}
+
+/// Light params for the note (same layout as `cloakcraft::instructions::LightCommitmentParams`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LightCommitmentParams {
+    pub validity_proof: LightValidityProof,
+    pub address_tree_info: LightAddressTreeInfo,
+    /// Output state tree index in remaining accounts
+    pub output_tree_index: u8,
+}
+
+/// Account structs for CPI
+pub mod accounts {
+    use super::*;
+
+    /// Accounts for `shield_signed`, in instruction order
+    pub struct ShieldSigned<'info> {
+        /// Pool PDA (`pda::pool`), writable
+        pub pool: AccountInfo<'info>,
+        /// Commitment counter PDA (`pda::commitment_counter`), writable
+        pub commitment_counter: AccountInfo<'info>,
+        /// Pool vault PDA (`pda::vault`), writable
+        pub token_vault: AccountInfo<'info>,
+        /// Source token account owned by `depositor`, writable
+        pub depositor_token_account: AccountInfo<'info>,
+        /// Token account authority, signs via invoke_signed
+        pub depositor: AccountInfo<'info>,
+        /// Funds the note's compressed account, writable signer
+        pub payer: AccountInfo<'info>,
+        /// SPL Token program
+        pub token_program: AccountInfo<'info>,
+        /// Pool stats PDA (`pda::pool_stats`), if the pool tracks stats
+        pub pool_stats: Option<AccountInfo<'info>>,
+        /// Root registry PDA (`pda::root_registry`), if the pool has one
+        pub root_registry: Option<AccountInfo<'info>>,
+    }
+
+    /// Anchor reads the program id as `None` for optional accounts
+    fn optional_meta(account: &Option<AccountInfo<'_>>) -> AccountMeta {
+        match account {
+            Some(account) => AccountMeta::new(account.key(), false),
+            None => AccountMeta::new_readonly(ID, false),
+        }
+    }
+
+    impl ToAccountMetas for ShieldSigned<'_> {
+        fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+            vec![
+                AccountMeta::new(self.pool.key(), false),
+                AccountMeta::new(self.commitment_counter.key(), false),
+                AccountMeta::new(self.token_vault.key(), false),
+                AccountMeta::new(self.depositor_token_account.key(), false),
+                AccountMeta::new_readonly(self.depositor.key(), true),
+                AccountMeta::new(self.payer.key(), true),
+                AccountMeta::new_readonly(self.token_program.key(), false),
+                optional_meta(&self.pool_stats),
+                optional_meta(&self.root_registry),
+            ]
+        }
+    }
+
+    impl<'info> ToAccountInfos<'info> for ShieldSigned<'info> {
+        fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+            let mut infos = vec![
+                self.pool.clone(),
+                self.commitment_counter.clone(),
+                self.token_vault.clone(),
+                self.depositor_token_account.clone(),
+                self.depositor.clone(),
+                self.payer.clone(),
+                self.token_program.clone(),
+            ];
+            infos.extend(self.pool_stats.clone());
+            infos.extend(self.root_registry.clone());
+            infos
+        }
+    }
+
+    /// Accounts for `verify_external_inclusion`, in instruction order
+    pub struct VerifyExternalInclusion<'info> {
+        /// Pool PDA (`pda::pool`)
+        pub pool: AccountInfo<'info>,
+        /// Root registry PDA (`pda::root_registry`)
+        pub root_registry: AccountInfo<'info>,
+    }
+
+    impl ToAccountMetas for VerifyExternalInclusion<'_> {
+        fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+            vec![
+                AccountMeta::new_readonly(self.pool.key(), false),
+                AccountMeta::new_readonly(self.root_registry.key(), false),
+            ]
+        }
+    }
+
+    impl<'info> ToAccountInfos<'info> for VerifyExternalInclusion<'info> {
+        fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+            vec![self.pool.clone(), self.root_registry.clone()]
+        }
+    }
+}
+
+/// Instruction data
+pub mod instruction {
+    use super::*;
+
+    /// Anchor discriminator of `shield_signed`
+    pub const SHIELD_SIGNED_DISCRIMINATOR: [u8; 8] = [20, 59, 124, 146, 213, 41, 237, 251];
+
+    /// `shield_signed` arguments
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+    pub struct ShieldSigned {
+        /// Note commitment
+        pub commitment: [u8; 32],
+        /// Token amount to deposit
+        pub amount: u64,
+        /// Stealth ephemeral pubkey (x || y) for the recipient
+        pub stealth_ephemeral_pubkey: [u8; 64],
+        /// Note encrypted to the recipient's stealth key
+        pub encrypted_note: Vec<u8>,
+        pub light_params: LightCommitmentParams,
+        /// View tag for note discovery
+        pub view_tag: Option<[u8; 8]>,
+    }
+
+    impl ShieldSigned {
+        pub fn data(&self) -> Vec<u8> {
+            let mut data = SHIELD_SIGNED_DISCRIMINATOR.to_vec();
+            // Serializing into a Vec cannot fail
+            self.serialize(&mut data).unwrap_or_default();
+            data
+        }
+    }
+
+    /// Anchor discriminator of `verify_external_inclusion`
+    pub const VERIFY_EXTERNAL_INCLUSION_DISCRIMINATOR: [u8; 8] =
+        [14, 59, 32, 136, 149, 125, 140, 92];
+
+    /// `verify_external_inclusion` arguments
+    #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+    pub struct VerifyExternalInclusion {
+        /// Commitment to check
+        pub commitment: [u8; 32],
+        /// Leaf index in the registry tree (from `CommitmentRegistered`)
+        pub leaf_index: u32,
+        /// Registry root the path leads to
+        pub root: [u8; 32],
+        /// Sibling hashes from the leaf up
+        pub path: [[u8; 32]; MERKLE_TREE_DEPTH],
+        /// Reject roots produced more than this many slots ago
+        pub max_root_age_slots: Option<u64>,
+    }
+
+    impl VerifyExternalInclusion {
+        pub fn data(&self) -> Vec<u8> {
+            let mut data = VERIFY_EXTERNAL_INCLUSION_DISCRIMINATOR.to_vec();
+            self.serialize(&mut data).unwrap_or_default();
+            data
+        }
+    }
+}
+
+/// Deposit tokens into a pool via CPI
+///
+/// `ctx.program` must be the CloakCraft program and `ctx.remaining_accounts`
+/// the Light system accounts followed by the trees `light_params` indexes.
+pub fn shield_signed<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, accounts::ShieldSigned<'info>>,
+    args: instruction::ShieldSigned,
+) -> Result<()> {
+    require_keys_eq!(ctx.program.key(), ID, ErrorCode::InvalidProgramId);
+
+    let mut metas = ctx.accounts.to_account_metas(None);
+    metas.extend(ctx.remaining_accounts.iter().map(|account| AccountMeta {
+        pubkey: account.key(),
+        is_signer: account.is_signer,
+        is_writable: account.is_writable,
+    }));
+
+    let mut infos = ctx.accounts.to_account_infos();
+    infos.extend(ctx.remaining_accounts.iter().cloned());
+    infos.push(ctx.program.clone());
+
+    let ix = Instruction {
+        program_id: ID,
+        accounts: metas,
+        data: args.data(),
+    };
+    invoke_signed(&ix, &infos, ctx.signer_seeds).map_err(Into::into)
+}
+
+/// Check that a commitment exists in a pool via CPI
+///
+/// Fails unless the merkle path leads to a root the pool's registry retains.
+/// Returns the slot that root was produced in.
+pub fn verify_external_inclusion<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, accounts::VerifyExternalInclusion<'info>>,
+    args: instruction::VerifyExternalInclusion,
+) -> Result<u64> {
+    use anchor_lang::solana_program::program::{get_return_data, invoke};
+
+    require_keys_eq!(ctx.program.key(), ID, ErrorCode::InvalidProgramId);
+
+    let mut infos = ctx.accounts.to_account_infos();
+    infos.push(ctx.program.clone());
+
+    let ix = Instruction {
+        program_id: ID,
+        accounts: ctx.accounts.to_account_metas(None),
+        data: args.data(),
+    };
+    invoke(&ix, &infos)?;
+
+    let (program_id, data) = get_return_data().ok_or(ErrorCode::InstructionDidNotDeserialize)?;
+    require_keys_eq!(program_id, ID, ErrorCode::InvalidProgramId);
+    u64::try_from_slice(&data).map_err(|_| ErrorCode::InstructionDidNotDeserialize.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{Discriminator, InstructionData};
+
+    fn args() -> instruction::ShieldSigned {
+        instruction::ShieldSigned {
+            commitment: [1u8; 32],
+            amount: 1_000,
+            stealth_ephemeral_pubkey: [2u8; 64],
+            encrypted_note: vec![3u8; 120],
+            light_params: LightCommitmentParams {
+                validity_proof: LightValidityProof {
+                    a: [4u8; 32],
+                    b: [5u8; 64],
+                    c: [6u8; 32],
+                },
+                address_tree_info: LightAddressTreeInfo {
+                    address_merkle_tree_pubkey_index: 7,
+                    address_queue_pubkey_index: 7,
+                    root_index: 300,
+                },
+                output_tree_index: 8,
+            },
+            view_tag: Some([9u8; 8]),
+        }
+    }
+
+    #[test]
+    fn test_matches_program_interface() {
+        assert_eq!(ID, cloakcraft::ID);
+        assert_eq!(
+            instruction::SHIELD_SIGNED_DISCRIMINATOR,
+            cloakcraft::instruction::ShieldSigned::DISCRIMINATOR
+        );
+
+        let a = args();
+        let expected = cloakcraft::instruction::ShieldSigned {
+            commitment: a.commitment,
+            amount: a.amount,
+            stealth_ephemeral_pubkey: a.stealth_ephemeral_pubkey,
+            encrypted_note: a.encrypted_note.clone(),
+            light_params: cloakcraft::instructions::LightCommitmentParams {
+                validity_proof: cloakcraft::state::LightValidityProof {
+                    a: [4u8; 32],
+                    b: [5u8; 64],
+                    c: [6u8; 32],
+                },
+                address_tree_info: cloakcraft::state::LightAddressTreeInfo {
+                    address_merkle_tree_pubkey_index: 7,
+                    address_queue_pubkey_index: 7,
+                    root_index: 300,
+                },
+                output_tree_index: 8,
+            },
+            view_tag: a.view_tag,
+        };
+        assert_eq!(a.data(), expected.data());
+    }
+
+    #[test]
+    fn test_verify_external_inclusion_matches_program() {
+        assert_eq!(
+            instruction::VERIFY_EXTERNAL_INCLUSION_DISCRIMINATOR,
+            cloakcraft::instruction::VerifyExternalInclusion::DISCRIMINATOR
+        );
+        assert_eq!(MERKLE_TREE_DEPTH, cloakcraft::constants::MERKLE_TREE_DEPTH);
+
+        let a = instruction::VerifyExternalInclusion {
+            commitment: [1u8; 32],
+            leaf_index: 77,
+            root: [2u8; 32],
+            path: [[3u8; 32]; MERKLE_TREE_DEPTH],
+            max_root_age_slots: Some(150),
+        };
+        let expected = cloakcraft::instruction::VerifyExternalInclusion {
+            commitment: a.commitment,
+            leaf_index: a.leaf_index,
+            root: a.root,
+            path: a.path,
+            max_root_age_slots: a.max_root_age_slots,
+        };
+        assert_eq!(a.data(), expected.data());
+    }
+
+    #[test]
+    fn test_seeds_match_program() {
+        assert_eq!(seeds::POOL, cloakcraft::constants::seeds::POOL);
+        assert_eq!(seeds::VAULT, cloakcraft::constants::seeds::VAULT);
+        assert_eq!(seeds::POOL_STATS, cloakcraft::constants::seeds::POOL_STATS);
+        assert_eq!(
+            seeds::ROOT_REGISTRY,
+            cloakcraft::constants::seeds::ROOT_REGISTRY
+        );
+        assert_eq!(
+            seeds::COMMITMENT_COUNTER,
+            cloakcraft::state::PoolCommitmentCounter::SEEDS_PREFIX
+        );
+    }
+}
@@ -0,0 +1,989 @@
+//! Account layouts
+//!
+//! Anchor accounts (`ProgramAccount`) and Light compressed accounts
+//! (`CompressedAccount`). Field order and types mirror `cloakcraft::state`.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_pubkey::Pubkey;
+
+use crate::split_discriminator;
+
+/// An Anchor account owned by the program
+pub trait ProgramAccount: BorshDeserialize {
+    /// `sha256("account:<Name>")[..8]`
+    const DISCRIMINATOR: [u8; 8];
+
+    /// Decode raw account data (discriminator included)
+    ///
+    /// Trailing bytes are ignored: accounts may be allocated larger than
+    /// their current layout.
+    fn decode(data: &[u8]) -> Option<Self> {
+        let (discriminator, mut rest) = split_discriminator(data)?;
+        if discriminator != Self::DISCRIMINATOR {
+            return None;
+        }
+        Self::deserialize(&mut rest).ok()
+    }
+}
+
+/// A Light Protocol compressed account created by the program
+pub trait CompressedAccount: BorshDeserialize {
+    /// `sha256("<Name>")[..8]`
+    const LIGHT_DISCRIMINATOR: [u8; 8];
+
+    /// Decode compressed account data given its separate discriminator
+    fn decode(discriminator: [u8; 8], data: &[u8]) -> Option<Self> {
+        if discriminator != Self::LIGHT_DISCRIMINATOR {
+            return None;
+        }
+        Self::deserialize(&mut &data[..]).ok()
+    }
+}
+
+// =============================================================================
+// Anchor Accounts
+// =============================================================================
+
+/// Number of fixed denominations a pool can hold
+pub const MAX_DENOMINATIONS: usize = 8;
+
+/// Number of per-operation pending expiry overrides
+pub const MAX_PENDING_EXPIRY_OVERRIDES: usize = 8;
+
+/// Number of per-mint fee overrides
+pub const MAX_FEE_OVERRIDES: usize = 8;
+
+/// Depth of the root registry tree
+pub const MERKLE_TREE_DEPTH: usize = 16;
+
+/// Number of roots a root registry retains
+pub const MAX_REGISTRY_ROOTS: usize = 64;
+
+/// Number of roots a snapshot tree retains
+pub const MAX_SNAPSHOT_ROOTS: usize = 32;
+
+/// Number of token slots in a perps pool
+pub const MAX_PERPS_TOKENS: usize = 8;
+
+/// Number of checkpoints a borrow fee history retains
+pub const MAX_BORROW_FEE_CHECKPOINTS: usize = 72;
+
+/// Number of roots a credit tree retains
+pub const MAX_CREDIT_ROOTS: usize = 64;
+
+/// Number of state trees a tree registry holds
+pub const MAX_REGISTERED_TREES: usize = 16;
+
+/// Shielded pool for one token
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Pool {
+    pub token_mint: Pubkey,
+    pub token_vault: Pubkey,
+    pub total_shielded: u64,
+    pub authority: Pubkey,
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub activity_epoch: u64,
+    pub epoch_shields: u32,
+    pub epoch_spends: u32,
+    pub prev_epoch_shields: u32,
+    pub prev_epoch_spends: u32,
+    pub total_shields: u64,
+    pub total_spends: u64,
+    pub min_anonymity_guard: u32,
+    pub guard_override_until: i64,
+    pub fixed_denominations: [u64; MAX_DENOMINATIONS],
+    pub nft_standard: u8,
+    pub nft_metadata_hash: [u8; 32],
+    pub state_tree: Pubkey,
+    pub address_tree: Pubkey,
+    pub next_state_tree: Pubkey,
+    pub tree_cutover_slot: u64,
+    /// Dust threshold for change notes (0 = none)
+    pub min_note_amount: u64,
+    /// Only allowlisted relayers may submit operations
+    pub relayer_allowlist_enabled: bool,
+    /// Root registry age window in slots (0 = no limit)
+    pub max_root_age_slots: u64,
+    /// Largest encrypted note stored (0 = `MAX_ENCRYPTED_NOTE_SIZE`)
+    pub max_encrypted_note_size: u16,
+    /// Operation value requiring pinned inclusion verification (0 = never)
+    pub pinned_inclusion_threshold: u64,
+    /// Account layout version (0 = not yet migrated)
+    pub layout_version: u8,
+    pub _reserved: [u8; 32],
+}
+
+impl ProgramAccount for Pool {
+    const DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+}
+
+/// Leaf index allocation for a pool's commitments
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PoolCommitmentCounter {
+    pub pool: Pubkey,
+    pub next_leaf_index: u64,
+    pub total_commitments: u64,
+    pub bump: u8,
+}
+
+impl ProgramAccount for PoolCommitmentCounter {
+    const DISCRIMINATOR: [u8; 8] = [104, 144, 242, 34, 19, 153, 118, 196];
+}
+
+/// Public per-pool statistics
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PoolStats {
+    pub pool: Pubkey,
+    pub total_shielded: u64,
+    pub lifetime_deposits: u64,
+    pub lifetime_withdrawals: u64,
+    pub lifetime_fees: u64,
+    pub note_count: u64,
+    pub total_notes_created: u64,
+    pub epoch: u64,
+    pub epoch_deposits: u64,
+    pub epoch_withdrawals: u64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl ProgramAccount for PoolStats {
+    const DISCRIMINATOR: [u8; 8] = [24, 180, 162, 52, 37, 122, 196, 98];
+}
+
+/// Root retained by a root registry
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RegisteredRoot {
+    pub root: [u8; 32],
+    pub leaf_count: u32,
+    pub slot: u64,
+}
+
+/// On-chain merkle tree of a pool's commitments with recent roots
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RootRegistry {
+    pub pool: Pubkey,
+    pub leaf_count: u32,
+    pub frontier: [[u8; 32]; MERKLE_TREE_DEPTH],
+    /// Roots produced; the latest is at `(total_roots - 1) % MAX_REGISTRY_ROOTS`
+    pub total_roots: u64,
+    pub roots: [RegisteredRoot; MAX_REGISTRY_ROOTS],
+    pub bump: u8,
+}
+
+impl ProgramAccount for RootRegistry {
+    const DISCRIMINATOR: [u8; 8] = [244, 50, 121, 60, 59, 161, 144, 99];
+}
+
+/// Phase 0 verification statistics of a circuit
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CircuitStats {
+    pub circuit_id: [u8; 32],
+    pub verifications: u64,
+    pub failures: u64,
+    /// Compute units spent in successful verifications
+    pub total_compute_units: u64,
+    pub max_compute_units: u64,
+    pub last_failure_client_version: u32,
+    pub last_failure_at: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl ProgramAccount for CircuitStats {
+    const DISCRIMINATOR: [u8; 8] = [163, 183, 219, 224, 126, 120, 250, 65];
+}
+
+/// On-chain merkle tree of a ballot's snapshot commitments with recent roots
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotTree {
+    pub ballot_id: [u8; 32],
+    pub leaf_count: u32,
+    pub frontier: [[u8; 32]; MERKLE_TREE_DEPTH],
+    /// Roots produced; the latest is at `(total_roots - 1) % MAX_SNAPSHOT_ROOTS`
+    pub total_roots: u64,
+    pub roots: [[u8; 32]; MAX_SNAPSHOT_ROOTS],
+    pub bump: u8,
+}
+
+impl ProgramAccount for SnapshotTree {
+    const DISCRIMINATOR: [u8; 8] = [67, 54, 237, 118, 153, 44, 48, 174];
+}
+
+/// Perps token borrow fee accumulators at one point in time
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BorrowFeeCheckpoint {
+    pub timestamp: i64,
+    /// `cumulative_borrow_fee` by token index
+    pub cumulative_borrow_fees: [u128; MAX_PERPS_TOKENS],
+}
+
+/// Hourly borrow fee checkpoints of a perps pool
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BorrowFeeHistory {
+    pub perps_pool: Pubkey,
+    /// Checkpoints recorded; the latest is at `(total_checkpoints - 1) % MAX_BORROW_FEE_CHECKPOINTS`
+    pub total_checkpoints: u64,
+    pub checkpoints: [BorrowFeeCheckpoint; MAX_BORROW_FEE_CHECKPOINTS],
+    pub bump: u8,
+}
+
+impl ProgramAccount for BorrowFeeHistory {
+    const DISCRIMINATOR: [u8; 8] = [8, 39, 143, 130, 178, 247, 121, 104];
+}
+
+/// Lamports set aside for dust sweep bounties
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DustSweepLedger {
+    /// Paid per consolidation that sweeps dust (0 = paused)
+    pub bounty_lamports: u64,
+    pub total_sweeps: u64,
+    pub total_paid: u64,
+    pub bump: u8,
+}
+
+impl ProgramAccount for DustSweepLedger {
+    const DISCRIMINATOR: [u8; 8] = [167, 31, 106, 221, 41, 74, 209, 58];
+}
+
+/// Maximum number of allowlisted relayers per pool
+pub const MAX_ALLOWED_RELAYERS: usize = 16;
+
+/// Relayers allowed to submit operations for a permissioned pool
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RelayerAllowlist {
+    pub pool: Pubkey,
+    /// `Pubkey::default()` = free slot
+    pub relayers: [Pubkey; MAX_ALLOWED_RELAYERS],
+    pub bump: u8,
+}
+
+impl ProgramAccount for RelayerAllowlist {
+    const DISCRIMINATOR: [u8; 8] = [165, 34, 46, 146, 164, 179, 145, 205];
+}
+
+/// SOL a relayer bonds against stranding operations
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RelayerStake {
+    pub relayer: Pubkey,
+    pub bonded: u64,
+    /// Still slashable until withdrawn at `unbond_at`
+    pub unbonding: u64,
+    pub unbond_at: i64,
+    pub total_slashed: u64,
+    /// Epoch `dust_sweeps` counts in (unix time / 1 day)
+    pub dust_sweep_epoch: u64,
+    /// Dust sweep bounties paid in `dust_sweep_epoch`
+    pub dust_sweeps: u32,
+    pub bump: u8,
+}
+
+impl ProgramAccount for RelayerStake {
+    const DISCRIMINATOR: [u8; 8] = [169, 85, 91, 49, 124, 96, 151, 228];
+}
+
+/// Maximum number of registered protocol token stakers
+pub const MAX_STAKERS: usize = 32;
+
+/// Protocol token buyback policy
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BuybackConfig {
+    pub protocol_token_mint: Pubkey,
+    pub buyback_bps: u16,
+    pub interval_seconds: i64,
+    pub max_price_impact_bps: u16,
+    pub is_active: bool,
+    pub last_buyback_at: i64,
+    pub total_bought: u64,
+    pub total_distributed: u64,
+    pub bump: u8,
+}
+
+impl ProgramAccount for BuybackConfig {
+    const DISCRIMINATOR: [u8; 8] = [226, 30, 39, 139, 66, 159, 153, 171];
+}
+
+/// One registered staker (`Pubkey::default()` = free slot)
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StakeEntry {
+    pub staker: Pubkey,
+    /// Stealth public key X that distribution notes are committed to
+    pub stealth_pub_x: [u8; 32],
+    pub stake: u64,
+    pub reward_checkpoint: u128,
+    pub pending_reward: u64,
+}
+
+/// Protocol token stakers earning buyback distributions
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StakeRegistry {
+    pub stake_vault: Pubkey,
+    pub total_stake: u64,
+    /// Scaled by 1e18
+    pub reward_per_stake: u128,
+    pub distribution_nonce: u64,
+    pub stakers: [StakeEntry; MAX_STAKERS],
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl ProgramAccount for StakeRegistry {
+    const DISCRIMINATOR: [u8; 8] = [226, 255, 72, 53, 131, 53, 10, 217];
+}
+
+/// Which pool's LP token earns emissions
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EmissionsPoolKind {
+    #[default]
+    Amm,
+    Perps,
+}
+
+/// Auto-compounding vault over an AMM or perps pool's LP token
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SavingsVault {
+    pub source_pool: Pubkey,
+    pub pool_kind: EmissionsPoolKind,
+    pub lp_mint: Pubkey,
+    pub share_mint: Pubkey,
+    /// `Pubkey::default()` = no emissions schedule
+    pub emissions_schedule: Pubkey,
+    pub authority: Pubkey,
+    pub total_lp: u64,
+    pub total_shares: u64,
+    /// LP per share, scaled by 1e18
+    pub share_price: u128,
+    pub share_price_updated_at: i64,
+    pub reward_checkpoint: u128,
+    pub pending_rewards: u64,
+    pub total_compounded: u64,
+    pub max_price_impact_bps: u16,
+    pub is_active: bool,
+    pub bump: u8,
+    pub share_mint_bump: u8,
+}
+
+impl ProgramAccount for SavingsVault {
+    const DISCRIMINATOR: [u8; 8] = [205, 10, 6, 110, 42, 68, 50, 87];
+}
+
+/// Operation credit issuance policy
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CreditConfig {
+    pub epoch_seconds: i64,
+    pub credits_per_epoch: u16,
+    pub min_stake: u64,
+    /// 0 = credits are not sold
+    pub fee_lamports: u64,
+    pub total_issued: u64,
+    pub total_consumed: u64,
+    pub bump: u8,
+}
+
+impl ProgramAccount for CreditConfig {
+    const DISCRIMINATOR: [u8; 8] = [114, 112, 9, 165, 97, 111, 111, 107];
+}
+
+/// Merkle tree of one epoch's operation credits with recent roots
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CreditTree {
+    pub epoch: u64,
+    pub leaf_count: u32,
+    pub frontier: [[u8; 32]; MERKLE_TREE_DEPTH],
+    /// Roots produced; the latest is at `(total_roots - 1) % MAX_CREDIT_ROOTS`
+    pub total_roots: u64,
+    pub roots: [[u8; 32]; MAX_CREDIT_ROOTS],
+    pub bump: u8,
+}
+
+impl ProgramAccount for CreditTree {
+    const DISCRIMINATOR: [u8; 8] = [171, 169, 219, 224, 180, 211, 230, 248];
+}
+
+/// Operation credit spent for an operation ID
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OperationCredit {
+    pub credit_nullifier: [u8; 32],
+    pub operation_id: [u8; 32],
+    pub epoch: u64,
+    pub relayer: Pubkey,
+    pub bump: u8,
+}
+
+impl ProgramAccount for OperationCredit {
+    const DISCRIMINATOR: [u8; 8] = [206, 241, 66, 172, 43, 229, 175, 76];
+}
+
+/// One approved output state tree
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RegisteredTree {
+    pub state_tree: Pubkey,
+    /// Operator's capacity hint
+    pub capacity: u64,
+    /// Writes counted by phases that passed the registry
+    pub written: u64,
+    pub retired: bool,
+    pub near_capacity_emitted: bool,
+}
+
+/// Approved output state trees (first `tree_count` used)
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TreeRegistry {
+    pub trees: [RegisteredTree; MAX_REGISTERED_TREES],
+    pub tree_count: u8,
+    pub near_capacity_bps: u16,
+    pub bump: u8,
+}
+
+impl ProgramAccount for TreeRegistry {
+    const DISCRIMINATOR: [u8; 8] = [145, 64, 7, 108, 76, 110, 156, 99];
+}
+
+/// Pending expiry for one operation type
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PendingExpiryOverride {
+    pub operation_type: u8,
+    pub expiry_seconds: u32,
+}
+
+/// Fee rates for one token mint (`mint == Pubkey::default()` = free slot)
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeOverride {
+    pub mint: Pubkey,
+    pub transfer_fee_bps: u16,
+    pub unshield_fee_bps: u16,
+    pub remove_liquidity_fee_bps: u16,
+}
+
+/// Protocol-wide fees and policies
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProtocolConfig {
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+    pub transfer_fee_bps: u16,
+    pub unshield_fee_bps: u16,
+    pub swap_fee_share_bps: u16,
+    pub remove_liquidity_fee_bps: u16,
+    pub fees_enabled: bool,
+    pub bump: u8,
+    pub pending_expiry_seconds: u32,
+    pub pending_expiry_overrides: [PendingExpiryOverride; MAX_PENDING_EXPIRY_OVERRIDES],
+    pub rent_refund_bps: u16,
+    pub surplus_policy: u8,
+    pub amm_creation_mode: u8,
+    pub amm_creation_fee_lamports: u64,
+    pub operation_epoch: u32,
+    pub require_operation_credits: bool,
+    pub _reserved: [u8; 1],
+    pub min_fee_bps: u16,
+    /// 0 = protocol maximum
+    pub max_fee_bps: u16,
+    pub fee_overrides: [FeeOverride; MAX_FEE_OVERRIDES],
+    /// Default = treasury fee conversion disabled
+    pub treasury_conversion_mint: Pubkey,
+    pub max_conversion_price_impact_bps: u16,
+}
+
+impl ProgramAccount for ProtocolConfig {
+    const DISCRIMINATOR: [u8; 8] = [207, 91, 250, 28, 152, 179, 215, 209];
+}
+
+// =============================================================================
+// Compressed Accounts
+// =============================================================================
+
+/// Maximum inline encrypted note size
+pub const MAX_ENCRYPTED_NOTE_SIZE: usize = 250;
+
+/// Protocol hard cap on a pool's encrypted note size (inline + overflow)
+pub const ENCRYPTED_NOTE_HARD_CAP: usize = 512;
+
+/// Note commitment with its encrypted note
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CommitmentAccount {
+    pub pool: [u8; 32],
+    pub commitment: [u8; 32],
+    pub leaf_index: u64,
+    pub stealth_ephemeral_pubkey: [u8; 64],
+    pub encrypted_note: [u8; MAX_ENCRYPTED_NOTE_SIZE],
+    pub encrypted_note_len: u16,
+    pub created_at: i64,
+    /// Absent on accounts created before view tags (decode those with
+    /// `CommitmentAccount::decode_legacy`)
+    pub view_tag: [u8; 8],
+    /// Encrypted note bytes past the inline capacity; absent on accounts
+    /// created before note overflow (decode those with `decode_legacy`)
+    pub encrypted_note_overflow: Vec<u8>,
+}
+
+impl CommitmentAccount {
+    /// Byte offset of `leaf_index` in the account data
+    pub const LEAF_INDEX_OFFSET: usize = 64;
+
+    /// Commitment index stored in (possibly legacy) account data
+    ///
+    /// The per-pool index bound into the note's nullifier, not the position
+    /// of the compressed account in Light's state tree.
+    pub fn commitment_index(data: &[u8]) -> Option<u64> {
+        let bytes = data.get(Self::LEAF_INDEX_OFFSET..Self::LEAF_INDEX_OFFSET + 8)?;
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    /// Serialized length of an account with no overflow
+    pub const MIN_LEN: usize = 32 + 32 + 8 + 64 + MAX_ENCRYPTED_NOTE_SIZE + 2 + 8 + 8 + 4;
+
+    /// Inline encrypted note bytes (without padding)
+    pub fn encrypted_note(&self) -> &[u8] {
+        let len = (self.encrypted_note_len as usize).min(MAX_ENCRYPTED_NOTE_SIZE);
+        &self.encrypted_note[..len]
+    }
+
+    /// Full encrypted note (inline bytes followed by the overflow)
+    pub fn full_encrypted_note(&self) -> Vec<u8> {
+        let mut note = self.encrypted_note().to_vec();
+        note.extend_from_slice(&self.encrypted_note_overflow);
+        note
+    }
+
+    /// Decode an account created before view tags or note overflow were added
+    pub fn decode_legacy(discriminator: [u8; 8], data: &[u8]) -> Option<Self> {
+        let mut padded = data.to_vec();
+        if padded.len() < Self::MIN_LEN {
+            padded.resize(Self::MIN_LEN, 0);
+        }
+        <Self as CompressedAccount>::decode(discriminator, &padded)
+    }
+}
+
+impl CompressedAccount for CommitmentAccount {
+    const LIGHT_DISCRIMINATOR: [u8; 8] = [245, 158, 74, 198, 103, 127, 253, 214];
+}
+
+/// Spent note nullifier
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SpendNullifierAccount {
+    pub pool: [u8; 32],
+    pub spent_at: i64,
+}
+
+impl CompressedAccount for SpendNullifierAccount {
+    const LIGHT_DISCRIMINATOR: [u8; 8] = [191, 138, 92, 234, 252, 216, 16, 37];
+}
+
+/// Action nullifier (one vote per note per aggregation)
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ActionNullifierAccount {
+    pub aggregation: [u8; 32],
+    pub voted_at: i64,
+}
+
+impl CompressedAccount for ActionNullifierAccount {
+    const LIGHT_DISCRIMINATOR: [u8; 8] = [254, 54, 15, 251, 255, 90, 109, 21];
+}
+
+/// Public perps position metadata
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PositionMeta {
+    pub position_id: [u8; 32],
+    pub pool_id: [u8; 32],
+    pub market_id: [u8; 32],
+    pub margin_amount: u64,
+    pub liquidation_price: u64,
+    pub is_long: bool,
+    pub position_size: u64,
+    pub entry_price: u64,
+    pub nullifier_hash: [u8; 32],
+    /// 0 = Active, 1 = Liquidated, 2 = Closed, 3 = Transferred
+    pub status: u8,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub owner_stealth_pubkey: [u8; 32],
+    pub entry_borrow_fee: u128,
+    pub position_commitment: [u8; 32],
+}
+
+impl CompressedAccount for PositionMeta {
+    const LIGHT_DISCRIMINATOR: [u8; 8] = [25, 135, 18, 67, 127, 60, 144, 88];
+}
+
+/// Invoice payment receipt
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PaymentReceipt {
+    pub reference_id: [u8; 32],
+    pub pool: [u8; 32],
+    pub commitment: [u8; 32],
+    pub leaf_index: u64,
+    pub paid_at: i64,
+}
+
+impl CompressedAccount for PaymentReceipt {
+    const LIGHT_DISCRIMINATOR: [u8; 8] = [48, 43, 57, 32, 132, 237, 200, 235];
+}
+
+/// Admin audit record
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AdminActionRecord {
+    pub actor: [u8; 32],
+    /// `AdminAction` discriminant
+    pub action: u8,
+    pub target: [u8; 32],
+    pub old_value_hash: [u8; 32],
+    pub new_value_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+impl CompressedAccount for AdminActionRecord {
+    const LIGHT_DISCRIMINATOR: [u8; 8] = [67, 154, 44, 7, 114, 40, 53, 200];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{AccountSerialize, AnchorSerialize};
+    use light_sdk::LightDiscriminator;
+
+    fn account_data<T: AccountSerialize>(account: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    fn compressed_data<T: AnchorSerialize>(account: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_pool_layout() {
+        let pool = cloakcraft::state::Pool {
+            total_shielded: 5_000,
+            min_anonymity_guard: 12,
+            fixed_denominations: [1, 10, 100, 0, 0, 0, 0, 0],
+            nft_metadata_hash: [9u8; 32],
+            tree_cutover_slot: 77,
+            min_note_amount: 500,
+            relayer_allowlist_enabled: true,
+            max_root_age_slots: 150,
+            max_encrypted_note_size: 400,
+            pinned_inclusion_threshold: 1_000_000,
+            layout_version: cloakcraft::state::Pool::LAYOUT_VERSION,
+            ..Default::default()
+        };
+        let mut data = account_data(&pool);
+        data.extend_from_slice(&[0u8; 16]);
+
+        let decoded = Pool::decode(&data).unwrap();
+        assert_eq!(decoded.total_shielded, 5_000);
+        assert_eq!(decoded.min_anonymity_guard, 12);
+        assert_eq!(decoded.fixed_denominations[2], 100);
+        assert_eq!(decoded.nft_metadata_hash, [9u8; 32]);
+        assert_eq!(decoded.tree_cutover_slot, 77);
+        assert_eq!(decoded.min_note_amount, 500);
+        assert!(decoded.relayer_allowlist_enabled);
+        assert_eq!(decoded.max_root_age_slots, 150);
+        assert_eq!(decoded.max_encrypted_note_size, 400);
+        assert_eq!(decoded.pinned_inclusion_threshold, 1_000_000);
+        assert_eq!(decoded.layout_version, 1);
+        assert!(PoolStats::decode(&data).is_none());
+    }
+
+    #[test]
+    fn test_anchor_account_layouts() {
+        let counter = cloakcraft::state::PoolCommitmentCounter {
+            next_leaf_index: 42,
+            total_commitments: 41,
+            bump: 254,
+            ..Default::default()
+        };
+        let decoded = PoolCommitmentCounter::decode(&account_data(&counter)).unwrap();
+        assert_eq!(
+            (
+                decoded.next_leaf_index,
+                decoded.total_commitments,
+                decoded.bump
+            ),
+            (42, 41, 254)
+        );
+
+        let mut config = cloakcraft::state::ProtocolConfig::default();
+        config.transfer_fee_bps = 25;
+        config.pending_expiry_overrides[3].expiry_seconds = 600;
+        config.operation_epoch = 4;
+        config.max_fee_bps = 300;
+        config.fee_overrides[2].mint = Pubkey::new_from_array([8u8; 32]).to_bytes().into();
+        config.fee_overrides[2].unshield_fee_bps = 5;
+        config.max_conversion_price_impact_bps = 150;
+        config.require_operation_credits = true;
+        let decoded = ProtocolConfig::decode(&account_data(&config)).unwrap();
+        assert_eq!(decoded.transfer_fee_bps, 25);
+        assert_eq!(decoded.pending_expiry_overrides[3].expiry_seconds, 600);
+        assert_eq!(decoded.operation_epoch, 4);
+        assert_eq!(decoded.max_fee_bps, 300);
+        assert_eq!(
+            decoded.fee_overrides[2].mint,
+            Pubkey::new_from_array([8u8; 32])
+        );
+        assert_eq!(decoded.fee_overrides[2].unshield_fee_bps, 5);
+        assert_eq!(decoded.max_conversion_price_impact_bps, 150);
+        assert!(decoded.require_operation_credits);
+
+        let mut registry = cloakcraft::state::RootRegistry::default();
+        registry.append([5u8; 32], 300).unwrap();
+        let decoded = RootRegistry::decode(&account_data(&registry)).unwrap();
+        assert_eq!((decoded.leaf_count, decoded.total_roots), (1, 1));
+        assert_eq!(decoded.roots[0].root, registry.roots[0].root);
+        assert_eq!(decoded.roots[0].slot, 300);
+
+        let mut tree = cloakcraft::state::SnapshotTree::default();
+        tree.append(&[[6u8; 32], [7u8; 32]]).unwrap();
+        let decoded = SnapshotTree::decode(&account_data(&tree)).unwrap();
+        assert_eq!((decoded.leaf_count, decoded.total_roots), (2, 1));
+        assert_eq!(decoded.roots[0], tree.roots[0]);
+
+        let mut history = cloakcraft::state::BorrowFeeHistory::default();
+        history.record(7_200, [11u128; MAX_PERPS_TOKENS]).unwrap();
+        let decoded = BorrowFeeHistory::decode(&account_data(&history)).unwrap();
+        assert_eq!(decoded.total_checkpoints, 1);
+        assert_eq!(decoded.checkpoints[0].timestamp, 7_200);
+        assert_eq!(
+            decoded.checkpoints[0].cumulative_borrow_fees,
+            [11u128; MAX_PERPS_TOKENS]
+        );
+
+        let ledger = cloakcraft::state::DustSweepLedger {
+            bounty_lamports: 5_000,
+            total_sweeps: 2,
+            total_paid: 10_000,
+            bump: 254,
+        };
+        let decoded = DustSweepLedger::decode(&account_data(&ledger)).unwrap();
+        assert_eq!(
+            (
+                decoded.bounty_lamports,
+                decoded.total_sweeps,
+                decoded.total_paid
+            ),
+            (5_000, 2, 10_000)
+        );
+
+        let mut allowlist = cloakcraft::state::RelayerAllowlist {
+            pool: Pubkey::new_from_array([1u8; 32]).to_bytes().into(),
+            bump: 253,
+            ..Default::default()
+        };
+        allowlist.relayers[3] = Pubkey::new_from_array([2u8; 32]).to_bytes().into();
+        let decoded = RelayerAllowlist::decode(&account_data(&allowlist)).unwrap();
+        assert_eq!(decoded.pool, Pubkey::new_from_array([1u8; 32]));
+        assert_eq!(decoded.relayers[3], Pubkey::new_from_array([2u8; 32]));
+        assert_eq!(decoded.bump, 253);
+
+        let stake = cloakcraft::state::RelayerStake {
+            bonded: 7_000,
+            unbonding: 3_000,
+            unbond_at: 99,
+            dust_sweep_epoch: 20_000,
+            dust_sweeps: 3,
+            ..Default::default()
+        };
+        let decoded = RelayerStake::decode(&account_data(&stake)).unwrap();
+        assert_eq!(
+            (decoded.bonded, decoded.unbonding, decoded.unbond_at),
+            (7_000, 3_000, 99)
+        );
+        assert_eq!((decoded.dust_sweep_epoch, decoded.dust_sweeps), (20_000, 3));
+
+        let buyback = cloakcraft::state::BuybackConfig {
+            buyback_bps: 1_000,
+            interval_seconds: 86_400,
+            total_bought: 500,
+            bump: 252,
+            ..Default::default()
+        };
+        let decoded = BuybackConfig::decode(&account_data(&buyback)).unwrap();
+        assert_eq!(
+            (
+                decoded.buyback_bps,
+                decoded.interval_seconds,
+                decoded.total_bought,
+                decoded.bump
+            ),
+            (1_000, 86_400, 500, 252)
+        );
+
+        let mut registry = cloakcraft::state::StakeRegistry {
+            distribution_nonce: 4,
+            vault_bump: 250,
+            ..Default::default()
+        };
+        registry.stakers[5].staker = Pubkey::new_from_array([3u8; 32]).to_bytes().into();
+        registry.stakers[5].stake = 900;
+        let decoded = StakeRegistry::decode(&account_data(&registry)).unwrap();
+        assert_eq!(decoded.stakers[5].staker, Pubkey::new_from_array([3u8; 32]));
+        assert_eq!(decoded.stakers[5].stake, 900);
+        assert_eq!((decoded.distribution_nonce, decoded.vault_bump), (4, 250));
+
+        let vault = cloakcraft::state::SavingsVault {
+            pool_kind: cloakcraft::state::EmissionsPoolKind::Perps,
+            total_lp: 1_100,
+            total_shares: 1_000,
+            share_price: 1_100_000_000_000_000_000,
+            share_mint_bump: 249,
+            ..Default::default()
+        };
+        let decoded = SavingsVault::decode(&account_data(&vault)).unwrap();
+        assert_eq!(decoded.pool_kind, EmissionsPoolKind::Perps);
+        assert_eq!(
+            (decoded.total_lp, decoded.total_shares, decoded.share_price),
+            (1_100, 1_000, 1_100_000_000_000_000_000)
+        );
+        assert_eq!(decoded.share_mint_bump, 249);
+
+        let mut tree = cloakcraft::state::CreditTree {
+            epoch: 480,
+            ..Default::default()
+        };
+        tree.append(&[[4u8; 32], [5u8; 32]]).unwrap();
+        let decoded = CreditTree::decode(&account_data(&tree)).unwrap();
+        assert_eq!((decoded.epoch, decoded.leaf_count), (480, 2));
+        assert_eq!(decoded.roots[0], tree.roots[0]);
+
+        let credit = cloakcraft::state::OperationCredit {
+            operation_id: [6u8; 32],
+            epoch: 480,
+            relayer: Pubkey::new_from_array([7u8; 32]).to_bytes().into(),
+            ..Default::default()
+        };
+        let decoded = OperationCredit::decode(&account_data(&credit)).unwrap();
+        assert_eq!((decoded.operation_id, decoded.epoch), ([6u8; 32], 480));
+        assert_eq!(decoded.relayer, Pubkey::new_from_array([7u8; 32]));
+
+        let mut registry = cloakcraft::state::TreeRegistry {
+            near_capacity_bps: 9_000,
+            ..Default::default()
+        };
+        let state_tree = Pubkey::new_from_array([9u8; 32]).to_bytes().into();
+        registry.register(state_tree, 100).unwrap();
+        registry.record_writes(&state_tree, 95).unwrap();
+        let decoded = TreeRegistry::decode(&account_data(&registry)).unwrap();
+        assert_eq!((decoded.tree_count, decoded.near_capacity_bps), (1, 9_000));
+        assert_eq!(
+            decoded.trees[0].state_tree,
+            Pubkey::new_from_array([9u8; 32])
+        );
+        assert_eq!(
+            (decoded.trees[0].capacity, decoded.trees[0].written),
+            (100, 95)
+        );
+        assert!(decoded.trees[0].near_capacity_emitted);
+
+        assert_eq!(
+            PoolStats::DISCRIMINATOR,
+            <cloakcraft::state::PoolStats as anchor_lang::Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            CircuitStats::DISCRIMINATOR,
+            <cloakcraft::state::CircuitStats as anchor_lang::Discriminator>::DISCRIMINATOR
+        );
+    }
+
+    #[test]
+    fn test_compressed_account_layouts() {
+        let commitment = cloakcraft::state::CommitmentAccount {
+            commitment: [3u8; 32],
+            leaf_index: 9,
+            encrypted_note_len: 4,
+            view_tag: [7u8; 8],
+            ..Default::default()
+        };
+        let data = compressed_data(&commitment);
+        let decoded = CommitmentAccount::decode(
+            cloakcraft::state::CommitmentAccount::LIGHT_DISCRIMINATOR,
+            &data,
+        )
+        .unwrap();
+        assert_eq!(decoded.leaf_index, 9);
+        assert_eq!(CommitmentAccount::commitment_index(&data), Some(9));
+        assert_eq!(
+            CommitmentAccount::LEAF_INDEX_OFFSET,
+            cloakcraft::state::CommitmentAccount::LEAF_INDEX_OFFSET
+        );
+        assert_eq!(decoded.encrypted_note().len(), 4);
+        assert_eq!(decoded.view_tag, [7u8; 8]);
+        assert_eq!(data.len(), CommitmentAccount::MIN_LEN);
+        // Before note overflow, and before view tags
+        let legacy = CommitmentAccount::decode_legacy(
+            CommitmentAccount::LIGHT_DISCRIMINATOR,
+            &data[..data.len() - 4],
+        )
+        .unwrap();
+        assert_eq!(legacy.view_tag, [7u8; 8]);
+        let legacy = CommitmentAccount::decode_legacy(
+            CommitmentAccount::LIGHT_DISCRIMINATOR,
+            &data[..data.len() - 12],
+        )
+        .unwrap();
+        assert_eq!(legacy.view_tag, [0u8; 8]);
+
+        let extended = cloakcraft::state::CommitmentAccount {
+            encrypted_note: [1u8; MAX_ENCRYPTED_NOTE_SIZE],
+            encrypted_note_len: MAX_ENCRYPTED_NOTE_SIZE as u16 + 30,
+            encrypted_note_overflow: vec![2u8; 30],
+            ..Default::default()
+        };
+        let decoded = CommitmentAccount::decode(
+            CommitmentAccount::LIGHT_DISCRIMINATOR,
+            &compressed_data(&extended),
+        )
+        .unwrap();
+        let note = decoded.full_encrypted_note();
+        assert_eq!(note.len(), MAX_ENCRYPTED_NOTE_SIZE + 30);
+        assert_eq!(note, extended.full_encrypted_note());
+
+        let meta = cloakcraft::state::PositionMeta {
+            margin_amount: 1_000,
+            is_long: true,
+            status: 2,
+            entry_borrow_fee: u128::MAX,
+            position_commitment: [7; 32],
+            ..Default::default()
+        };
+        let decoded = PositionMeta::decode(
+            cloakcraft::state::PositionMeta::LIGHT_DISCRIMINATOR,
+            &compressed_data(&meta),
+        )
+        .unwrap();
+        assert_eq!(
+            (decoded.margin_amount, decoded.is_long, decoded.status),
+            (1_000, true, 2)
+        );
+        assert_eq!(decoded.entry_borrow_fee, u128::MAX);
+        assert_eq!(decoded.position_commitment, [7; 32]);
+
+        let receipt = cloakcraft::state::PaymentReceipt {
+            leaf_index: 5,
+            paid_at: 1_700_000_000,
+            ..Default::default()
+        };
+        let decoded = PaymentReceipt::decode(
+            cloakcraft::state::PaymentReceipt::LIGHT_DISCRIMINATOR,
+            &compressed_data(&receipt),
+        )
+        .unwrap();
+        assert_eq!((decoded.leaf_index, decoded.paid_at), (5, 1_700_000_000));
+
+        assert_eq!(
+            SpendNullifierAccount::LIGHT_DISCRIMINATOR,
+            cloakcraft::state::SpendNullifierAccount::LIGHT_DISCRIMINATOR
+        );
+        assert_eq!(
+            ActionNullifierAccount::LIGHT_DISCRIMINATOR,
+            cloakcraft::state::ActionNullifierAccount::LIGHT_DISCRIMINATOR
+        );
+        assert_eq!(
+            AdminActionRecord::LIGHT_DISCRIMINATOR,
+            cloakcraft::state::AdminActionRecord::LIGHT_DISCRIMINATOR
+        );
+    }
+}
@@ -0,0 +1,630 @@
+//! Ballot config builder
+//!
+//! `create_ballot` takes a long `BallotConfigInput`. `BallotConfig::builder()`
+//! assembles one from the choices that matter (binding, reveal and
+//! resolution modes) and `build` runs the same checks as the program, so a
+//! built config is never rejected for its shape. The borsh layout matches
+//! `cloakcraft::state::BallotConfigInput`.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_pubkey::Pubkey;
+
+/// Maximum number of voting options tallied on the ballot itself
+pub const MAX_BALLOT_OPTIONS: usize = 16;
+
+/// Maximum number of voting options on a paged ballot (see
+/// `pda::ballot_options_page`)
+pub const MAX_PAGED_BALLOT_OPTIONS: usize = 64;
+
+/// Maximum number of weight formula bytes
+pub const MAX_WEIGHT_FORMULA_OPS: usize = 16;
+
+/// Maximum number of weight formula parameters
+pub const MAX_WEIGHT_PARAMS: usize = 8;
+
+/// Maximum number of snapshot roots averaged in TWAB mode
+pub const MAX_TWAB_ROOTS: usize = 8;
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VoteBindingMode {
+    #[default]
+    Snapshot,
+    SpendToVote,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RevealMode {
+    #[default]
+    Public,
+    TimeLocked,
+    PermanentPrivate,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VoteType {
+    #[default]
+    Single,
+    Approval,
+    Ranked,
+    Weighted,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResolutionMode {
+    #[default]
+    TallyBased,
+    Oracle,
+    Authority,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TwabMode {
+    #[default]
+    Disabled,
+    Average,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OptionRegistration {
+    #[default]
+    Closed,
+    Registrar,
+    Open,
+}
+
+/// Weight formula op (one byte; PushConst is followed by its param index)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum WeightOp {
+    PushAmount = 0,
+    PushConst = 1,
+    PushUserData = 2,
+    Add = 3,
+    Sub = 4,
+    Mul = 5,
+    Div = 6,
+    Sqrt = 7,
+    Min = 8,
+    Max = 9,
+}
+
+/// Why a ballot config would be rejected (program error in brackets)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BallotConfigError {
+    /// start_time not before end_time (InvalidBallotTiming)
+    InvalidTiming,
+    /// end_time already passed (BallotAlreadyEnded)
+    AlreadyEnded,
+    /// Options outside 1..=64 (InvalidNumOptions)
+    InvalidNumOptions,
+    /// Over 16 options without Public reveal and Single or Weighted votes
+    /// (PagedBallotUnsupportedMode)
+    PagedUnsupportedMode,
+    /// Fee above 10000 bps (ProtocolFeeExceedsMax)
+    FeeExceedsMax,
+    /// Fee without a treasury (MissingProtocolTreasury)
+    MissingTreasury,
+    /// Formula longer than 16 bytes (WeightFormulaTooLong)
+    FormulaTooLong,
+    /// More than 8 params (TooManyWeightParams)
+    TooManyParams,
+    /// Unknown or reserved op (InvalidWeightOp)
+    InvalidWeightOp,
+    /// PushConst index without a param (WeightParamOutOfRange)
+    ParamOutOfRange,
+    /// Formula doesn't leave one value (WeightFormulaUnbalanced)
+    Unbalanced,
+    /// Encrypted mode without a key (MissingTimeLockPubkey)
+    MissingTimeLockPubkey,
+    /// Encrypted mode without an unlock slot (InvalidUnlockSlot)
+    InvalidUnlockSlot,
+    /// Oracle mode without an oracle (MissingBallotOracle)
+    MissingOracle,
+    /// Authority mode without a resolver (MissingBallotResolver)
+    MissingResolver,
+    /// Snapshot mode without a slot (InvalidSnapshotSlot)
+    InvalidSnapshotSlot,
+    /// Deadline not after end_time, or not SpendToVote (InvalidClaimDeadline)
+    InvalidClaimDeadline,
+    /// TWAB outside Snapshot mode, or not 2..=8 roots with a non-zero
+    /// interval (InvalidTwabConfig)
+    InvalidTwabConfig,
+    /// max_options not above num_options with registration, or set without
+    /// (InvalidOptionRegistration)
+    InvalidOptionRegistration,
+    /// Registrar mode without a registrar (MissingOptionRegistrar)
+    MissingOptionRegistrar,
+    /// Hidden turnout on a Public or SpendToVote ballot (InvalidTurnoutPrivacy)
+    InvalidTurnoutPrivacy,
+    /// Refund above 10000 bps, or outside SpendToVote (InvalidLoserRefund)
+    InvalidLoserRefund,
+    /// Dispute window outside Oracle mode, or without a bond or resolver
+    /// (InvalidDisputeConfig)
+    InvalidDisputeConfig,
+}
+
+/// `create_ballot` config argument
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BallotConfig {
+    pub binding_mode: VoteBindingMode,
+    pub reveal_mode: RevealMode,
+    pub vote_type: VoteType,
+    pub resolution_mode: ResolutionMode,
+    pub num_options: u8,
+    pub quorum_threshold: u64,
+    pub protocol_fee_bps: u16,
+    pub protocol_treasury: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub snapshot_slot: u64,
+    pub indexer_pubkey: Pubkey,
+    pub eligibility_root: Option<[u8; 32]>,
+    pub twab_mode: TwabMode,
+    pub twab_num_roots: u8,
+    pub twab_interval_slots: u64,
+    pub weight_formula: Vec<u8>,
+    pub weight_params: Vec<u64>,
+    pub time_lock_pubkey: [u8; 32],
+    pub unlock_slot: u64,
+    pub resolver: Option<Pubkey>,
+    pub oracle: Option<Pubkey>,
+    pub claim_deadline: i64,
+    pub proposal_bond: u64,
+    pub option_registration: OptionRegistration,
+    pub option_registrar: Option<Pubkey>,
+    pub max_options: u8,
+    pub hide_turnout: bool,
+    pub loser_refund_bps: u16,
+    pub dispute_window_seconds: i64,
+    pub dispute_bond: u64,
+}
+
+impl BallotConfig {
+    /// Start a Public, TallyBased, single-choice snapshot ballot
+    pub fn builder(num_options: u8, start_time: i64, end_time: i64) -> BallotConfigBuilder {
+        BallotConfigBuilder {
+            config: BallotConfig {
+                num_options,
+                start_time,
+                end_time,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Check the config as `create_ballot` would at `current_time`
+    pub fn validate(&self, current_time: i64) -> Result<(), BallotConfigError> {
+        use BallotConfigError::*;
+
+        check(self.start_time < self.end_time, InvalidTiming)?;
+        check(self.end_time > current_time, AlreadyEnded)?;
+        match self.option_registration {
+            OptionRegistration::Closed => check(self.max_options == 0, InvalidOptionRegistration)?,
+            OptionRegistration::Registrar | OptionRegistration::Open => {
+                check(
+                    self.max_options > self.num_options,
+                    InvalidOptionRegistration,
+                )?;
+                if self.option_registration == OptionRegistration::Registrar {
+                    check(is_set(self.option_registrar), MissingOptionRegistrar)?;
+                }
+            }
+        }
+        let capacity = self.num_options.max(self.max_options) as usize;
+
+        check(
+            self.num_options > 0 && capacity <= MAX_PAGED_BALLOT_OPTIONS,
+            InvalidNumOptions,
+        )?;
+        check(
+            capacity <= MAX_BALLOT_OPTIONS
+                || (self.reveal_mode == RevealMode::Public
+                    && matches!(self.vote_type, VoteType::Single | VoteType::Weighted)),
+            PagedUnsupportedMode,
+        )?;
+
+        check(self.protocol_fee_bps <= 10_000, FeeExceedsMax)?;
+        check(
+            self.protocol_fee_bps == 0 || self.protocol_treasury != Pubkey::default(),
+            MissingTreasury,
+        )?;
+
+        check(
+            self.weight_formula.len() <= MAX_WEIGHT_FORMULA_OPS,
+            FormulaTooLong,
+        )?;
+        check(self.weight_params.len() <= MAX_WEIGHT_PARAMS, TooManyParams)?;
+        validate_weight_formula(&self.weight_formula, self.weight_params.len())?;
+
+        if self.reveal_mode != RevealMode::Public {
+            check(self.time_lock_pubkey != [0u8; 32], MissingTimeLockPubkey)?;
+            check(self.unlock_slot != 0, InvalidUnlockSlot)?;
+        }
+        check(
+            !self.hide_turnout
+                || (self.reveal_mode != RevealMode::Public
+                    && self.binding_mode == VoteBindingMode::Snapshot),
+            InvalidTurnoutPrivacy,
+        )?;
+        check(
+            self.loser_refund_bps <= 10_000
+                && (self.loser_refund_bps == 0
+                    || self.binding_mode == VoteBindingMode::SpendToVote),
+            InvalidLoserRefund,
+        )?;
+        check(
+            if self.dispute_window_seconds == 0 {
+                self.dispute_bond == 0
+            } else {
+                self.dispute_window_seconds > 0
+                    && self.dispute_bond > 0
+                    && self.resolution_mode == ResolutionMode::Oracle
+                    && is_set(self.resolver)
+            },
+            InvalidDisputeConfig,
+        )?;
+
+        match self.resolution_mode {
+            ResolutionMode::TallyBased => {}
+            ResolutionMode::Oracle => check(is_set(self.oracle), MissingOracle)?,
+            ResolutionMode::Authority => check(is_set(self.resolver), MissingResolver)?,
+        }
+
+        match self.binding_mode {
+            VoteBindingMode::Snapshot => {
+                check(self.snapshot_slot != 0, InvalidSnapshotSlot)?;
+                check(self.claim_deadline == 0, InvalidClaimDeadline)?;
+            }
+            VoteBindingMode::SpendToVote => check(
+                self.claim_deadline == 0 || self.claim_deadline > self.end_time,
+                InvalidClaimDeadline,
+            )?,
+        }
+
+        match self.twab_mode {
+            TwabMode::Disabled => check(
+                self.twab_num_roots == 0 && self.twab_interval_slots == 0,
+                InvalidTwabConfig,
+            ),
+            TwabMode::Average => check(
+                self.binding_mode == VoteBindingMode::Snapshot
+                    && self.twab_num_roots >= 2
+                    && self.twab_num_roots as usize <= MAX_TWAB_ROOTS
+                    && self.twab_interval_slots > 0,
+                InvalidTwabConfig,
+            ),
+        }
+    }
+}
+
+/// Builder for a `BallotConfig` (see `BallotConfig::builder`)
+#[derive(Clone, Debug)]
+pub struct BallotConfigBuilder {
+    config: BallotConfig,
+}
+
+impl BallotConfigBuilder {
+    /// Snapshot voting at `slot`, attested by `indexer`
+    pub fn snapshot(mut self, slot: u64, indexer: Pubkey) -> Self {
+        self.config.binding_mode = VoteBindingMode::Snapshot;
+        self.config.snapshot_slot = slot;
+        self.config.indexer_pubkey = indexer;
+        self.config.claim_deadline = 0;
+        self
+    }
+
+    /// Spend-to-vote, with claims open until `claim_deadline` (0 = no deadline)
+    pub fn spend_to_vote(mut self, claim_deadline: i64) -> Self {
+        self.config.binding_mode = VoteBindingMode::SpendToVote;
+        self.config.claim_deadline = claim_deadline;
+        self.config.twab_mode = TwabMode::Disabled;
+        self.config.twab_num_roots = 0;
+        self.config.twab_interval_slots = 0;
+        self
+    }
+
+    /// Average voting power over `num_roots` snapshot roots registered at
+    /// least `interval_slots` apart
+    pub fn twab(mut self, num_roots: u8, interval_slots: u64) -> Self {
+        self.config.twab_mode = TwabMode::Average;
+        self.config.twab_num_roots = num_roots;
+        self.config.twab_interval_slots = interval_slots;
+        self
+    }
+
+    pub fn vote_type(mut self, vote_type: VoteType) -> Self {
+        self.config.vote_type = vote_type;
+        self
+    }
+
+    /// Encrypt votes to `time_lock_pubkey` until `unlock_slot`
+    ///
+    /// `reveal_mode` must be TimeLocked or PermanentPrivate.
+    pub fn encrypted(
+        mut self,
+        reveal_mode: RevealMode,
+        time_lock_pubkey: [u8; 32],
+        unlock_slot: u64,
+    ) -> Self {
+        self.config.reveal_mode = reveal_mode;
+        self.config.time_lock_pubkey = time_lock_pubkey;
+        self.config.unlock_slot = unlock_slot;
+        self
+    }
+
+    /// Outcome submitted by `oracle`
+    pub fn oracle(mut self, oracle: Pubkey) -> Self {
+        self.config.resolution_mode = ResolutionMode::Oracle;
+        self.config.oracle = Some(oracle);
+        self.config.resolver = None;
+        self
+    }
+
+    /// Outcome set by `resolver`
+    pub fn authority(mut self, resolver: Pubkey) -> Self {
+        self.config.resolution_mode = ResolutionMode::Authority;
+        self.config.resolver = Some(resolver);
+        self.config.oracle = None;
+        self
+    }
+
+    pub fn quorum(mut self, threshold: u64) -> Self {
+        self.config.quorum_threshold = threshold;
+        self
+    }
+
+    /// Protocol fee on SpendToVote claims, paid to `treasury`
+    pub fn protocol_fee(mut self, bps: u16, treasury: Pubkey) -> Self {
+        self.config.protocol_fee_bps = bps;
+        self.config.protocol_treasury = treasury;
+        self
+    }
+
+    pub fn eligibility_root(mut self, root: [u8; 32]) -> Self {
+        self.config.eligibility_root = Some(root);
+        self
+    }
+
+    /// Weight formula bytes (see `WeightOp`) and their params
+    pub fn weight_formula(mut self, formula: Vec<u8>, params: Vec<u64>) -> Self {
+        self.config.weight_formula = formula;
+        self.config.weight_params = params;
+        self
+    }
+
+    /// Lamports locked as a spam bond
+    pub fn proposal_bond(mut self, lamports: u64) -> Self {
+        self.config.proposal_bond = lamports;
+        self
+    }
+
+    /// Let `registrar` (or anyone, if None) add options up to `max_options`
+    /// while the ballot is open
+    pub fn write_ins(mut self, registrar: Option<Pubkey>, max_options: u8) -> Self {
+        self.config.option_registration = match registrar {
+            Some(_) => OptionRegistration::Registrar,
+            None => OptionRegistration::Open,
+        };
+        self.config.option_registrar = registrar;
+        self.config.max_options = max_options;
+        self
+    }
+
+    /// Keep vote count and total weight encrypted until the tally is
+    /// decrypted (encrypted Snapshot ballots)
+    pub fn hide_turnout(mut self) -> Self {
+        self.config.hide_turnout = true;
+        self
+    }
+
+    /// Let losers claim back `bps` of their pool stake (SpendToVote)
+    pub fn loser_refund(mut self, bps: u16) -> Self {
+        self.config.loser_refund_bps = bps;
+        self
+    }
+
+    /// Let anyone dispute the oracle's outcome for `window_seconds` after
+    /// resolution, locking `bond` lamports; disputes escalate to `resolver`
+    pub fn oracle_disputes(mut self, window_seconds: i64, bond: u64, resolver: Pubkey) -> Self {
+        self.config.dispute_window_seconds = window_seconds;
+        self.config.dispute_bond = bond;
+        self.config.resolver = Some(resolver);
+        self
+    }
+
+    /// Finish the config, checking it as `create_ballot` would at `current_time`
+    pub fn build(self, current_time: i64) -> Result<BallotConfig, BallotConfigError> {
+        self.config.validate(current_time)?;
+        Ok(self.config)
+    }
+}
+
+/// Check that a weight formula decodes and leaves exactly one value
+pub fn validate_weight_formula(formula: &[u8], num_params: usize) -> Result<(), BallotConfigError> {
+    use BallotConfigError::*;
+
+    if formula.is_empty() {
+        return Ok(());
+    }
+
+    let mut depth = 0usize;
+    let mut ops = formula.iter();
+    while let Some(&op) = ops.next() {
+        let pops = match op {
+            x if x == WeightOp::PushConst as u8 => {
+                let index = ops.next().ok_or(ParamOutOfRange)?;
+                check((*index as usize) < num_params, ParamOutOfRange)?;
+                0
+            }
+            x if x == WeightOp::PushAmount as u8 => 0,
+            x if x == WeightOp::Sqrt as u8 => 1,
+            x if x >= WeightOp::Add as u8 && x <= WeightOp::Max as u8 => 2,
+            _ => return Err(InvalidWeightOp),
+        };
+        depth = depth.checked_sub(pops).ok_or(Unbalanced)? + 1;
+    }
+
+    check(depth == 1, Unbalanced)
+}
+
+fn check(condition: bool, error: BallotConfigError) -> Result<(), BallotConfigError> {
+    if condition {
+        Ok(())
+    } else {
+        Err(error)
+    }
+}
+
+fn is_set(key: Option<Pubkey>) -> bool {
+    key.is_some_and(|key| key != Pubkey::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AnchorSerialize;
+
+    #[test]
+    fn test_ballot_config_layout() {
+        let config = BallotConfig::builder(3, 100, 200)
+            .snapshot(7, Pubkey::new_unique())
+            .twab(4, 150)
+            .encrypted(RevealMode::TimeLocked, [5u8; 32], 900)
+            .authority(Pubkey::new_unique())
+            .protocol_fee(50, Pubkey::new_unique())
+            .weight_formula(vec![0, 1, 0, 8], vec![1_000])
+            .proposal_bond(10)
+            .write_ins(Some(Pubkey::new_unique()), 8)
+            .hide_turnout()
+            .build(150)
+            .unwrap();
+
+        let program = cloakcraft::state::BallotConfigInput {
+            binding_mode: cloakcraft::state::VoteBindingMode::Snapshot,
+            reveal_mode: cloakcraft::state::RevealMode::TimeLocked,
+            vote_type: cloakcraft::state::VoteType::Single,
+            resolution_mode: cloakcraft::state::ResolutionMode::Authority,
+            num_options: 3,
+            quorum_threshold: 0,
+            protocol_fee_bps: 50,
+            protocol_treasury: config.protocol_treasury,
+            start_time: 100,
+            end_time: 200,
+            snapshot_slot: 7,
+            indexer_pubkey: config.indexer_pubkey,
+            eligibility_root: None,
+            twab_mode: cloakcraft::state::TwabMode::Average,
+            twab_num_roots: 4,
+            twab_interval_slots: 150,
+            weight_formula: vec![0, 1, 0, 8],
+            weight_params: vec![1_000],
+            time_lock_pubkey: [5u8; 32],
+            unlock_slot: 900,
+            resolver: config.resolver,
+            oracle: None,
+            claim_deadline: 0,
+            proposal_bond: 10,
+            option_registration: cloakcraft::state::OptionRegistration::Registrar,
+            option_registrar: config.option_registrar,
+            max_options: 8,
+            hide_turnout: true,
+            loser_refund_bps: 0,
+            dispute_window_seconds: 0,
+            dispute_bond: 0,
+        };
+        program.validate(150).unwrap();
+
+        let mut expected = Vec::new();
+        AnchorSerialize::serialize(&program, &mut expected).unwrap();
+        let mut data = Vec::new();
+        config.serialize(&mut data).unwrap();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_configs() {
+        let builder = || BallotConfig::builder(2, 100, 200).snapshot(1, Pubkey::default());
+        assert!(builder().build(0).is_ok());
+        assert_eq!(builder().build(200), Err(BallotConfigError::AlreadyEnded));
+        assert_eq!(
+            BallotConfig::builder(0, 100, 200)
+                .snapshot(1, Pubkey::default())
+                .build(0),
+            Err(BallotConfigError::InvalidNumOptions)
+        );
+        assert!(BallotConfig::builder(64, 100, 200)
+            .snapshot(1, Pubkey::default())
+            .build(0)
+            .is_ok());
+        assert_eq!(
+            BallotConfig::builder(17, 100, 200)
+                .snapshot(1, Pubkey::default())
+                .vote_type(VoteType::Ranked)
+                .build(0),
+            Err(BallotConfigError::PagedUnsupportedMode)
+        );
+        assert_eq!(
+            builder().write_ins(None, 2).build(0),
+            Err(BallotConfigError::InvalidOptionRegistration)
+        );
+        assert_eq!(
+            builder()
+                .vote_type(VoteType::Approval)
+                .write_ins(None, 17)
+                .build(0),
+            Err(BallotConfigError::PagedUnsupportedMode)
+        );
+        assert_eq!(
+            builder().hide_turnout().build(0),
+            Err(BallotConfigError::InvalidTurnoutPrivacy)
+        );
+        assert_eq!(
+            builder().loser_refund(5_000).build(0),
+            Err(BallotConfigError::InvalidLoserRefund)
+        );
+        assert!(builder()
+            .spend_to_vote(0)
+            .loser_refund(5_000)
+            .build(0)
+            .is_ok());
+        assert_eq!(
+            builder()
+                .oracle_disputes(3_600, 1_000, Pubkey::new_unique())
+                .build(0),
+            Err(BallotConfigError::InvalidDisputeConfig)
+        );
+        assert!(builder()
+            .oracle(Pubkey::new_unique())
+            .oracle_disputes(3_600, 1_000, Pubkey::new_unique())
+            .build(0)
+            .is_ok());
+        assert_eq!(
+            builder().protocol_fee(10, Pubkey::default()).build(0),
+            Err(BallotConfigError::MissingTreasury)
+        );
+        assert_eq!(
+            builder().weight_formula(vec![0, 1, 1, 8], vec![1]).build(0),
+            Err(BallotConfigError::ParamOutOfRange)
+        );
+        assert_eq!(
+            builder().weight_formula(vec![0, 0], vec![]).build(0),
+            Err(BallotConfigError::Unbalanced)
+        );
+        assert_eq!(
+            builder().weight_formula(vec![2], vec![]).build(0),
+            Err(BallotConfigError::InvalidWeightOp)
+        );
+        assert_eq!(
+            builder().spend_to_vote(150).build(0),
+            Err(BallotConfigError::InvalidClaimDeadline)
+        );
+        assert_eq!(
+            builder().spend_to_vote(0).twab(2, 150).build(0),
+            Err(BallotConfigError::InvalidTwabConfig)
+        );
+        assert_eq!(
+            builder().snapshot(7, Pubkey::new_unique()).twab(2, 0).build(0),
+            Err(BallotConfigError::InvalidTwabConfig)
+        );
+    }
+}
@@ -0,0 +1,1057 @@
+//! Program events
+//!
+//! Anchor emits events as `Program data: <base64>` log lines, the payload
+//! being `sha256("event:<Name>")[..8]` followed by the borsh-encoded event.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_pubkey::Pubkey;
+
+use crate::{split_discriminator, PROGRAM_ID};
+
+/// Log prefix Anchor uses for event payloads
+pub const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+/// An event with its discriminator
+pub trait Event: BorshDeserialize {
+    const DISCRIMINATOR: [u8; 8];
+}
+
+/// Perps limit order filled by a keeper
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PerpOrderFilled {
+    pub order_id: [u8; 32],
+    pub operation_id: [u8; 32],
+    pub limit_price: u64,
+    pub oracle_price: u64,
+    pub keeper: Pubkey,
+}
+
+impl Event for PerpOrderFilled {
+    const DISCRIMINATOR: [u8; 8] = [211, 196, 75, 107, 250, 88, 49, 221];
+}
+
+/// Perps limit order placed
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PerpOrderCreated {
+    pub order_id: [u8; 32],
+    pub perps_market: Pubkey,
+    pub is_long: bool,
+    pub limit_price: u64,
+    pub expiry: i64,
+}
+
+impl Event for PerpOrderCreated {
+    const DISCRIMINATOR: [u8; 8] = [141, 181, 0, 25, 34, 80, 64, 233];
+}
+
+/// Read-only perps position valuation
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PositionValueQuoted {
+    pub perps_pool: Pubkey,
+    pub perps_market: Pubkey,
+    pub oracle_price: u64,
+    pub mark_value: u64,
+    pub pnl: u64,
+    pub is_profit: bool,
+    pub accrued_borrow_fee: u64,
+    pub liquidation_price: u64,
+    pub distance_to_liquidation_bps: u16,
+    pub is_liquidatable: bool,
+    pub timestamp: i64,
+}
+
+impl Event for PositionValueQuoted {
+    const DISCRIMINATOR: [u8; 8] = [185, 207, 15, 150, 199, 236, 117, 67];
+}
+
+/// Perps position hit its profit bound
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProfitBoundReached {
+    pub pool: Pubkey,
+    pub market: Pubkey,
+    pub position_commitment: [u8; 32],
+    pub margin: u64,
+    pub pnl: u64,
+    pub current_price: u64,
+}
+
+impl Event for ProfitBoundReached {
+    const DISCRIMINATOR: [u8; 8] = [202, 251, 230, 179, 158, 47, 20, 242];
+}
+
+/// Compressed NFT shielded
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CnftShielded {
+    pub pool: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub metadata_hash: [u8; 32],
+    pub leaf_index: u64,
+}
+
+impl Event for CnftShielded {
+    const DISCRIMINATOR: [u8; 8] = [216, 68, 88, 170, 67, 176, 212, 139];
+}
+
+/// State tree migration scheduled for a pool
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PoolTreeMigrationScheduled {
+    pub pool: Pubkey,
+    pub old_state_tree: Pubkey,
+    pub new_state_tree: Pubkey,
+    pub cutover_slot: u64,
+}
+
+impl Event for PoolTreeMigrationScheduled {
+    const DISCRIMINATOR: [u8; 8] = [84, 179, 170, 101, 65, 98, 78, 129];
+}
+
+/// Anonymity guard temporarily overridden
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AnonymityGuardOverridden {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub recent_anonymity_set: u64,
+    pub min_anonymity_guard: u32,
+    pub override_until: i64,
+}
+
+impl Event for AnonymityGuardOverridden {
+    const DISCRIMINATOR: [u8; 8] = [57, 162, 74, 107, 133, 151, 32, 50];
+}
+
+/// State root checkpoint anchored
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RootCheckpointAnchored {
+    pub pool: Pubkey,
+    pub state_tree: Pubkey,
+    pub root: [u8; 32],
+    pub commitment_count: u64,
+    pub checkpoint_index: u64,
+    pub chain_hash: [u8; 32],
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+impl Event for RootCheckpointAnchored {
+    const DISCRIMINATOR: [u8; 8] = [23, 51, 95, 2, 116, 201, 242, 190];
+}
+
+/// Pool solvency check result
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PoolSolvencyChecked {
+    pub pool: Pubkey,
+    pub vault_balance: u64,
+    pub shielded_supply: u64,
+    pub pending_outflows: u64,
+    pub solvent: bool,
+}
+
+impl Event for PoolSolvencyChecked {
+    const DISCRIMINATOR: [u8; 8] = [24, 99, 233, 0, 214, 163, 50, 94];
+}
+
+/// Pool vault below its obligations
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PoolInsolvencyDetected {
+    pub pool: Pubkey,
+    pub vault_balance: u64,
+    pub shielded_supply: u64,
+    pub pending_outflows: u64,
+    pub shortfall: u64,
+}
+
+impl Event for PoolInsolvencyDetected {
+    const DISCRIMINATOR: [u8; 8] = [245, 170, 50, 245, 195, 136, 166, 26];
+}
+
+/// SPL NFT or pNFT shielded
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NftShielded {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub nft_standard: u8,
+    pub metadata_hash: [u8; 32],
+    pub leaf_index: u64,
+}
+
+impl Event for NftShielded {
+    const DISCRIMINATOR: [u8; 8] = [190, 86, 11, 43, 115, 86, 14, 53];
+}
+
+/// AMM reserve surplus swept or folded in
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ReservesSynced {
+    pub amm_pool: Pubkey,
+    pub surplus_a: u64,
+    pub surplus_b: u64,
+    pub swept: bool,
+}
+
+impl Event for ReservesSynced {
+    const DISCRIMINATOR: [u8; 8] = [223, 110, 75, 68, 168, 42, 115, 136];
+}
+
+/// LP token registered as a vote source
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LpVoteSourceRegistered {
+    pub ballot_id: [u8; 32],
+    pub lp_mint: Pubkey,
+    pub rate: u128,
+    pub attester: Pubkey,
+}
+
+impl Event for LpVoteSourceRegistered {
+    const DISCRIMINATOR: [u8; 8] = [94, 100, 249, 74, 127, 175, 199, 165];
+}
+
+/// Proposal bond refunded or slashed
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProposalBondSettled {
+    pub ballot_id: [u8; 32],
+    pub amount: u64,
+    pub recipient: Pubkey,
+    pub refunded: bool,
+}
+
+impl Event for ProposalBondSettled {
+    const DISCRIMINATOR: [u8; 8] = [173, 38, 68, 91, 251, 250, 99, 241];
+}
+
+/// Ballot snapshot root registered
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotRootRegistered {
+    pub ballot_id: [u8; 32],
+    pub snapshot_slot: u64,
+    pub snapshot_root: [u8; 32],
+    pub registered_slot: u64,
+    pub twab_root_count: u8,
+    pub attester: Pubkey,
+}
+
+impl Event for SnapshotRootRegistered {
+    const DISCRIMINATOR: [u8; 8] = [186, 90, 77, 195, 85, 50, 185, 68];
+}
+
+/// Light Protocol CPI error and the CloakCraft error it mapped to
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LightCpiFailed {
+    pub light_error_code: u32,
+    pub error_code: u32,
+}
+
+impl Event for LightCpiFailed {
+    const DISCRIMINATOR: [u8; 8] = [221, 224, 100, 37, 13, 233, 141, 207];
+}
+
+/// Pool statistics epoch closed
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PoolStatsEpochRolled {
+    pub pool: Pubkey,
+    pub epoch: u64,
+    pub epoch_deposits: u64,
+    pub epoch_withdrawals: u64,
+    pub total_shielded: u64,
+    pub note_count: u64,
+}
+
+impl Event for PoolStatsEpochRolled {
+    const DISCRIMINATOR: [u8; 8] = [248, 201, 135, 52, 30, 188, 109, 124];
+}
+
+/// Commitment appended to a pool's root registry
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CommitmentRegistered {
+    pub pool: Pubkey,
+    pub commitment: [u8; 32],
+    /// Leaf index in the registry tree
+    pub leaf_index: u32,
+    /// Registry root after the append
+    pub root: [u8; 32],
+    pub slot: u64,
+}
+
+impl Event for CommitmentRegistered {
+    const DISCRIMINATOR: [u8; 8] = [130, 34, 22, 46, 5, 168, 155, 134];
+}
+
+/// Verification key hash checked and frozen
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct VerificationKeyFinalized {
+    pub verification_key: Pubkey,
+    pub circuit_id: [u8; 32],
+    /// keccak256(vk_data)
+    pub vk_hash: [u8; 32],
+    pub vk_len: u32,
+}
+
+impl Event for VerificationKeyFinalized {
+    const DISCRIMINATOR: [u8; 8] = [175, 143, 21, 211, 196, 237, 2, 199];
+}
+
+/// Rejected proof counted in the circuit's stats (record_proof_failure)
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CircuitProofRejected {
+    pub circuit_id: [u8; 32],
+    pub client_version: u32,
+    /// Lifetime failures including this one
+    pub failures: u64,
+    pub timestamp: i64,
+}
+
+impl Event for CircuitProofRejected {
+    const DISCRIMINATOR: [u8; 8] = [210, 59, 69, 49, 108, 82, 45, 187];
+}
+
+/// Commitments appended to a ballot's snapshot tree
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotLeavesAppended {
+    pub ballot_id: [u8; 32],
+    /// Leaf index of the first appended leaf
+    pub first_leaf_index: u32,
+    pub leaves: Vec<[u8; 32]>,
+    /// Tree root after the append
+    pub root: [u8; 32],
+}
+
+impl Event for SnapshotLeavesAppended {
+    const DISCRIMINATOR: [u8; 8] = [101, 252, 26, 174, 137, 15, 135, 149];
+}
+
+/// Hourly borrow fee checkpoint recorded for a perps pool
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BorrowFeeCheckpointed {
+    pub perps_pool: Pubkey,
+    pub checkpoint_index: u64,
+    pub timestamp: i64,
+    /// `cumulative_borrow_fee` by token index
+    pub cumulative_borrow_fees: [u128; 8],
+}
+
+impl Event for BorrowFeeCheckpointed {
+    const DISCRIMINATOR: [u8; 8] = [13, 44, 124, 49, 211, 55, 22, 227];
+}
+
+/// Dust sweep bounty paid to a consolidation's relayer
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DustSweepBountyPaid {
+    pub operation_id: [u8; 32],
+    pub relayer: Pubkey,
+    pub pool: Pubkey,
+    pub num_inputs: u8,
+    pub bounty_lamports: u64,
+}
+
+impl Event for DustSweepBountyPaid {
+    const DISCRIMINATOR: [u8; 8] = [31, 232, 118, 243, 200, 171, 218, 138];
+}
+
+/// Two pools shielded into in one transaction
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PairShielded {
+    pub pool_a: Pubkey,
+    pub pool_b: Pubkey,
+    pub commitment_a: [u8; 32],
+    pub commitment_b: [u8; 32],
+    pub leaf_index_a: u64,
+    pub leaf_index_b: u64,
+}
+
+impl Event for PairShielded {
+    const DISCRIMINATOR: [u8; 8] = [30, 99, 66, 111, 211, 39, 97, 179];
+}
+
+/// Relayer's stake slashed by a stranded operation's rescue
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RelayerSlashed {
+    pub operation_id: [u8; 32],
+    pub relayer: Pubkey,
+    pub rescuer: Pubkey,
+    pub amount: u64,
+    /// Part of `amount` paid to the rescuer (the rest went to the treasury)
+    pub rescuer_bounty: u64,
+}
+
+impl Event for RelayerSlashed {
+    const DISCRIMINATOR: [u8; 8] = [100, 213, 18, 147, 38, 90, 234, 11];
+}
+
+/// Option added to an open ballot (write-in)
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BallotOptionAdded {
+    pub ballot_id: [u8; 32],
+    pub option: u8,
+    pub metadata_hash: [u8; 32],
+    pub registrar: Pubkey,
+}
+
+impl Event for BallotOptionAdded {
+    const DISCRIMINATOR: [u8; 8] = [38, 151, 27, 64, 78, 30, 9, 99];
+}
+
+/// Oracle outcome disputed
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ResolutionDisputed {
+    pub ballot_id: [u8; 32],
+    pub challenger: Pubkey,
+    pub outcome: Option<u8>,
+    pub bond: u64,
+}
+
+impl Event for ResolutionDisputed {
+    const DISCRIMINATOR: [u8; 8] = [209, 249, 106, 201, 166, 82, 67, 14];
+}
+
+/// Disputed outcome settled by the ballot resolver
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DisputeSettled {
+    pub ballot_id: [u8; 32],
+    pub outcome: Option<u8>,
+    pub upheld: bool,
+    pub bond: u64,
+    pub bond_recipient: Pubkey,
+}
+
+impl Event for DisputeSettled {
+    const DISCRIMINATOR: [u8; 8] = [254, 31, 147, 164, 50, 13, 223, 158];
+}
+
+/// Perps market delisted into settle-only mode
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MarketDelisted {
+    pub perps_market: Pubkey,
+    pub market_id: [u8; 32],
+    pub settle_after: i64,
+}
+
+impl Event for MarketDelisted {
+    const DISCRIMINATOR: [u8; 8] = [221, 179, 59, 161, 218, 81, 167, 151];
+}
+
+/// Delisted perps market force-settled at its final price
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MarketSettled {
+    pub perps_market: Pubkey,
+    pub market_id: [u8; 32],
+    pub settlement_price: u64,
+    pub long_open_interest: u64,
+    pub short_open_interest: u64,
+}
+
+impl Event for MarketSettled {
+    const DISCRIMINATOR: [u8; 8] = [237, 212, 22, 175, 201, 117, 215, 99];
+}
+
+/// Perps pool migration finalized with its LP conversion rate
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PerpsPoolMigrated {
+    pub old_pool: Pubkey,
+    pub new_pool: Pubkey,
+    pub old_lp_supply: u64,
+    pub new_lp_supply: u64,
+}
+
+impl Event for PerpsPoolMigrated {
+    const DISCRIMINATOR: [u8; 8] = [85, 69, 130, 238, 80, 146, 10, 7];
+}
+
+/// AMM pool reserves exported to its successor pool
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AmmPoolMigrated {
+    pub old_pool: Pubkey,
+    pub new_pool: Pubkey,
+    pub version: u8,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub old_lp_supply: u64,
+    pub new_lp_supply: u64,
+}
+
+impl Event for AmmPoolMigrated {
+    const DISCRIMINATOR: [u8; 8] = [202, 143, 218, 167, 120, 90, 199, 163];
+}
+
+/// Pool commitment counter moved past indices issued outside it
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CommitmentCounterReconciled {
+    pub pool: Pubkey,
+    pub previous_next_index: u64,
+    pub next_index: u64,
+}
+
+impl Event for CommitmentCounterReconciled {
+    const DISCRIMINATOR: [u8; 8] = [185, 13, 204, 253, 181, 139, 63, 48];
+}
+
+/// Protocol-wide fee rates updated
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProtocolFeesUpdated {
+    pub transfer_fee_bps: u16,
+    pub unshield_fee_bps: u16,
+    pub swap_fee_share_bps: u16,
+    pub remove_liquidity_fee_bps: u16,
+    pub fees_enabled: bool,
+}
+
+impl Event for ProtocolFeesUpdated {
+    const DISCRIMINATOR: [u8; 8] = [190, 127, 198, 224, 14, 253, 180, 26];
+}
+
+/// Per-mint fee override set or cleared (`active == false`)
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FeeOverrideSet {
+    pub mint: Pubkey,
+    pub active: bool,
+    pub transfer_fee_bps: u16,
+    pub unshield_fee_bps: u16,
+    pub remove_liquidity_fee_bps: u16,
+}
+
+impl Event for FeeOverrideSet {
+    const DISCRIMINATOR: [u8; 8] = [93, 117, 225, 31, 184, 115, 83, 4];
+}
+
+/// Fee floor / ceiling updated
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FeeBoundsUpdated {
+    pub min_fee_bps: u16,
+    pub max_fee_bps: u16,
+}
+
+impl Event for FeeBoundsUpdated {
+    const DISCRIMINATOR: [u8; 8] = [95, 243, 10, 70, 198, 94, 237, 132];
+}
+
+/// Treasury fees swapped into the canonical treasury asset
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TreasuryFeesConverted {
+    pub amm_pool: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub price_impact_bps: u16,
+    pub keeper: Pubkey,
+}
+
+impl Event for TreasuryFeesConverted {
+    const DISCRIMINATOR: [u8; 8] = [149, 46, 200, 38, 42, 229, 245, 180];
+}
+
+/// Protocol tokens bought back with treasury fees
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BuybackExecuted {
+    pub amm_pool: Pubkey,
+    pub fee_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_bought: u64,
+    pub price_impact_bps: u16,
+    pub total_stake: u64,
+    pub keeper: Pubkey,
+}
+
+impl Event for BuybackExecuted {
+    const DISCRIMINATOR: [u8; 8] = [150, 109, 157, 10, 124, 24, 38, 189];
+}
+
+/// Bought protocol tokens paid to a staker as a shielded note
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BuybackDistributed {
+    pub staker: Pubkey,
+    pub commitment: [u8; 32],
+    pub amount: u64,
+    pub nonce: u64,
+    pub leaf_index: u64,
+}
+
+impl Event for BuybackDistributed {
+    const DISCRIMINATOR: [u8; 8] = [31, 95, 165, 203, 24, 182, 88, 100];
+}
+
+/// LP deposited into a savings vault for shares
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SavingsVaultDeposited {
+    pub vault: Pubkey,
+    pub lp_amount: u64,
+    pub shares: u64,
+    pub share_price: u128,
+}
+
+impl Event for SavingsVaultDeposited {
+    const DISCRIMINATOR: [u8; 8] = [50, 181, 72, 183, 92, 247, 210, 58];
+}
+
+/// Savings vault shares redeemed for LP
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SavingsVaultWithdrawn {
+    pub vault: Pubkey,
+    pub shares: u64,
+    pub lp_amount: u64,
+    pub share_price: u128,
+}
+
+impl Event for SavingsVaultWithdrawn {
+    const DISCRIMINATOR: [u8; 8] = [60, 203, 241, 145, 40, 30, 16, 29];
+}
+
+/// Savings vault rewards compounded into LP
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SavingsVaultHarvested {
+    pub vault: Pubkey,
+    pub amm_pool: Pubkey,
+    pub rewards: u64,
+    pub lp_bought: u64,
+    pub price_impact_bps: u16,
+    pub share_price: u128,
+    pub keeper: Pubkey,
+}
+
+impl Event for SavingsVaultHarvested {
+    const DISCRIMINATOR: [u8; 8] = [221, 197, 181, 44, 167, 234, 49, 205];
+}
+
+/// Operation credits appended to an epoch's credit tree
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OperationCreditsIssued {
+    pub epoch: u64,
+    /// Leaf index of the first issued credit
+    pub first_leaf_index: u32,
+    pub leaves: Vec<[u8; 32]>,
+    /// Credit tree root after the append
+    pub root: [u8; 32],
+    /// Issued against stake (otherwise bought)
+    pub stake_backed: bool,
+}
+
+impl Event for OperationCreditsIssued {
+    const DISCRIMINATOR: [u8; 8] = [5, 95, 12, 230, 176, 5, 251, 12];
+}
+
+/// Operation credit spent for an operation
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OperationCreditConsumed {
+    pub epoch: u64,
+    pub credit_nullifier: [u8; 32],
+    pub operation_id: [u8; 32],
+}
+
+impl Event for OperationCreditConsumed {
+    const DISCRIMINATOR: [u8; 8] = [17, 13, 183, 84, 135, 29, 135, 86];
+}
+
+/// State tree registered, or its capacity hint updated
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StateTreeRegistered {
+    pub state_tree: Pubkey,
+    pub capacity: u64,
+}
+
+impl Event for StateTreeRegistered {
+    const DISCRIMINATOR: [u8; 8] = [243, 232, 70, 133, 158, 94, 238, 255];
+}
+
+/// State tree's counted writes crossed the near-capacity threshold
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TreeNearCapacity {
+    pub state_tree: Pubkey,
+    pub written: u64,
+    pub capacity: u64,
+}
+
+impl Event for TreeNearCapacity {
+    const DISCRIMINATOR: [u8; 8] = [241, 62, 108, 211, 194, 4, 239, 65];
+}
+
+/// Any CloakCraft event
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CloakCraftEvent {
+    PerpOrderFilled(PerpOrderFilled),
+    PerpOrderCreated(PerpOrderCreated),
+    PositionValueQuoted(PositionValueQuoted),
+    ProfitBoundReached(ProfitBoundReached),
+    CnftShielded(CnftShielded),
+    PoolTreeMigrationScheduled(PoolTreeMigrationScheduled),
+    AnonymityGuardOverridden(AnonymityGuardOverridden),
+    RootCheckpointAnchored(RootCheckpointAnchored),
+    PoolSolvencyChecked(PoolSolvencyChecked),
+    PoolInsolvencyDetected(PoolInsolvencyDetected),
+    NftShielded(NftShielded),
+    ReservesSynced(ReservesSynced),
+    LpVoteSourceRegistered(LpVoteSourceRegistered),
+    ProposalBondSettled(ProposalBondSettled),
+    SnapshotRootRegistered(SnapshotRootRegistered),
+    LightCpiFailed(LightCpiFailed),
+    PoolStatsEpochRolled(PoolStatsEpochRolled),
+    CommitmentRegistered(CommitmentRegistered),
+    VerificationKeyFinalized(VerificationKeyFinalized),
+    CircuitProofRejected(CircuitProofRejected),
+    SnapshotLeavesAppended(SnapshotLeavesAppended),
+    BorrowFeeCheckpointed(BorrowFeeCheckpointed),
+    DustSweepBountyPaid(DustSweepBountyPaid),
+    PairShielded(PairShielded),
+    RelayerSlashed(RelayerSlashed),
+    BallotOptionAdded(BallotOptionAdded),
+    ResolutionDisputed(ResolutionDisputed),
+    DisputeSettled(DisputeSettled),
+    MarketDelisted(MarketDelisted),
+    MarketSettled(MarketSettled),
+    PerpsPoolMigrated(PerpsPoolMigrated),
+    AmmPoolMigrated(AmmPoolMigrated),
+    CommitmentCounterReconciled(CommitmentCounterReconciled),
+    ProtocolFeesUpdated(ProtocolFeesUpdated),
+    FeeOverrideSet(FeeOverrideSet),
+    FeeBoundsUpdated(FeeBoundsUpdated),
+    TreasuryFeesConverted(TreasuryFeesConverted),
+    BuybackExecuted(BuybackExecuted),
+    BuybackDistributed(BuybackDistributed),
+    SavingsVaultDeposited(SavingsVaultDeposited),
+    SavingsVaultWithdrawn(SavingsVaultWithdrawn),
+    SavingsVaultHarvested(SavingsVaultHarvested),
+    OperationCreditsIssued(OperationCreditsIssued),
+    OperationCreditConsumed(OperationCreditConsumed),
+    StateTreeRegistered(StateTreeRegistered),
+    TreeNearCapacity(TreeNearCapacity),
+}
+
+impl CloakCraftEvent {
+    /// Decode an event payload (discriminator included)
+    ///
+    /// Returns `None` for unknown discriminators and malformed payloads.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        fn event<E: Event>(mut data: &[u8]) -> Option<E> {
+            E::deserialize(&mut data).ok()
+        }
+
+        let (discriminator, rest) = split_discriminator(data)?;
+        match discriminator {
+            PerpOrderFilled::DISCRIMINATOR => event(rest).map(Self::PerpOrderFilled),
+            PerpOrderCreated::DISCRIMINATOR => event(rest).map(Self::PerpOrderCreated),
+            PositionValueQuoted::DISCRIMINATOR => event(rest).map(Self::PositionValueQuoted),
+            ProfitBoundReached::DISCRIMINATOR => event(rest).map(Self::ProfitBoundReached),
+            CnftShielded::DISCRIMINATOR => event(rest).map(Self::CnftShielded),
+            PoolTreeMigrationScheduled::DISCRIMINATOR => {
+                event(rest).map(Self::PoolTreeMigrationScheduled)
+            }
+            AnonymityGuardOverridden::DISCRIMINATOR => {
+                event(rest).map(Self::AnonymityGuardOverridden)
+            }
+            RootCheckpointAnchored::DISCRIMINATOR => event(rest).map(Self::RootCheckpointAnchored),
+            PoolSolvencyChecked::DISCRIMINATOR => event(rest).map(Self::PoolSolvencyChecked),
+            PoolInsolvencyDetected::DISCRIMINATOR => event(rest).map(Self::PoolInsolvencyDetected),
+            NftShielded::DISCRIMINATOR => event(rest).map(Self::NftShielded),
+            ReservesSynced::DISCRIMINATOR => event(rest).map(Self::ReservesSynced),
+            LpVoteSourceRegistered::DISCRIMINATOR => event(rest).map(Self::LpVoteSourceRegistered),
+            ProposalBondSettled::DISCRIMINATOR => event(rest).map(Self::ProposalBondSettled),
+            SnapshotRootRegistered::DISCRIMINATOR => event(rest).map(Self::SnapshotRootRegistered),
+            LightCpiFailed::DISCRIMINATOR => event(rest).map(Self::LightCpiFailed),
+            PoolStatsEpochRolled::DISCRIMINATOR => event(rest).map(Self::PoolStatsEpochRolled),
+            CommitmentRegistered::DISCRIMINATOR => event(rest).map(Self::CommitmentRegistered),
+            VerificationKeyFinalized::DISCRIMINATOR => {
+                event(rest).map(Self::VerificationKeyFinalized)
+            }
+            CircuitProofRejected::DISCRIMINATOR => event(rest).map(Self::CircuitProofRejected),
+            SnapshotLeavesAppended::DISCRIMINATOR => event(rest).map(Self::SnapshotLeavesAppended),
+            BorrowFeeCheckpointed::DISCRIMINATOR => event(rest).map(Self::BorrowFeeCheckpointed),
+            DustSweepBountyPaid::DISCRIMINATOR => event(rest).map(Self::DustSweepBountyPaid),
+            PairShielded::DISCRIMINATOR => event(rest).map(Self::PairShielded),
+            RelayerSlashed::DISCRIMINATOR => event(rest).map(Self::RelayerSlashed),
+            BallotOptionAdded::DISCRIMINATOR => event(rest).map(Self::BallotOptionAdded),
+            ResolutionDisputed::DISCRIMINATOR => event(rest).map(Self::ResolutionDisputed),
+            DisputeSettled::DISCRIMINATOR => event(rest).map(Self::DisputeSettled),
+            MarketDelisted::DISCRIMINATOR => event(rest).map(Self::MarketDelisted),
+            MarketSettled::DISCRIMINATOR => event(rest).map(Self::MarketSettled),
+            PerpsPoolMigrated::DISCRIMINATOR => event(rest).map(Self::PerpsPoolMigrated),
+            AmmPoolMigrated::DISCRIMINATOR => event(rest).map(Self::AmmPoolMigrated),
+            CommitmentCounterReconciled::DISCRIMINATOR => {
+                event(rest).map(Self::CommitmentCounterReconciled)
+            }
+            ProtocolFeesUpdated::DISCRIMINATOR => event(rest).map(Self::ProtocolFeesUpdated),
+            FeeOverrideSet::DISCRIMINATOR => event(rest).map(Self::FeeOverrideSet),
+            FeeBoundsUpdated::DISCRIMINATOR => event(rest).map(Self::FeeBoundsUpdated),
+            TreasuryFeesConverted::DISCRIMINATOR => event(rest).map(Self::TreasuryFeesConverted),
+            BuybackExecuted::DISCRIMINATOR => event(rest).map(Self::BuybackExecuted),
+            BuybackDistributed::DISCRIMINATOR => event(rest).map(Self::BuybackDistributed),
+            SavingsVaultDeposited::DISCRIMINATOR => event(rest).map(Self::SavingsVaultDeposited),
+            SavingsVaultWithdrawn::DISCRIMINATOR => event(rest).map(Self::SavingsVaultWithdrawn),
+            SavingsVaultHarvested::DISCRIMINATOR => event(rest).map(Self::SavingsVaultHarvested),
+            OperationCreditsIssued::DISCRIMINATOR => event(rest).map(Self::OperationCreditsIssued),
+            OperationCreditConsumed::DISCRIMINATOR => {
+                event(rest).map(Self::OperationCreditConsumed)
+            }
+            StateTreeRegistered::DISCRIMINATOR => event(rest).map(Self::StateTreeRegistered),
+            TreeNearCapacity::DISCRIMINATOR => event(rest).map(Self::TreeNearCapacity),
+            _ => None,
+        }
+    }
+
+    /// Decode an event from a `Program data: ` log line
+    pub fn from_log(line: &str) -> Option<Self> {
+        let payload = line.strip_prefix(PROGRAM_DATA_PREFIX)?;
+        Self::decode(&STANDARD.decode(payload.trim()).ok()?)
+    }
+
+    /// Event name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PerpOrderFilled(_) => "PerpOrderFilled",
+            Self::PerpOrderCreated(_) => "PerpOrderCreated",
+            Self::PositionValueQuoted(_) => "PositionValueQuoted",
+            Self::ProfitBoundReached(_) => "ProfitBoundReached",
+            Self::CnftShielded(_) => "CnftShielded",
+            Self::PoolTreeMigrationScheduled(_) => "PoolTreeMigrationScheduled",
+            Self::AnonymityGuardOverridden(_) => "AnonymityGuardOverridden",
+            Self::RootCheckpointAnchored(_) => "RootCheckpointAnchored",
+            Self::PoolSolvencyChecked(_) => "PoolSolvencyChecked",
+            Self::PoolInsolvencyDetected(_) => "PoolInsolvencyDetected",
+            Self::NftShielded(_) => "NftShielded",
+            Self::ReservesSynced(_) => "ReservesSynced",
+            Self::LpVoteSourceRegistered(_) => "LpVoteSourceRegistered",
+            Self::ProposalBondSettled(_) => "ProposalBondSettled",
+            Self::SnapshotRootRegistered(_) => "SnapshotRootRegistered",
+            Self::LightCpiFailed(_) => "LightCpiFailed",
+            Self::PoolStatsEpochRolled(_) => "PoolStatsEpochRolled",
+            Self::CommitmentRegistered(_) => "CommitmentRegistered",
+            Self::VerificationKeyFinalized(_) => "VerificationKeyFinalized",
+            Self::CircuitProofRejected(_) => "CircuitProofRejected",
+            Self::SnapshotLeavesAppended(_) => "SnapshotLeavesAppended",
+            Self::BorrowFeeCheckpointed(_) => "BorrowFeeCheckpointed",
+            Self::DustSweepBountyPaid(_) => "DustSweepBountyPaid",
+            Self::PairShielded(_) => "PairShielded",
+            Self::RelayerSlashed(_) => "RelayerSlashed",
+            Self::BallotOptionAdded(_) => "BallotOptionAdded",
+            Self::ResolutionDisputed(_) => "ResolutionDisputed",
+            Self::DisputeSettled(_) => "DisputeSettled",
+            Self::MarketDelisted(_) => "MarketDelisted",
+            Self::MarketSettled(_) => "MarketSettled",
+            Self::PerpsPoolMigrated(_) => "PerpsPoolMigrated",
+            Self::AmmPoolMigrated(_) => "AmmPoolMigrated",
+            Self::CommitmentCounterReconciled(_) => "CommitmentCounterReconciled",
+            Self::ProtocolFeesUpdated(_) => "ProtocolFeesUpdated",
+            Self::FeeOverrideSet(_) => "FeeOverrideSet",
+            Self::FeeBoundsUpdated(_) => "FeeBoundsUpdated",
+            Self::TreasuryFeesConverted(_) => "TreasuryFeesConverted",
+            Self::BuybackExecuted(_) => "BuybackExecuted",
+            Self::BuybackDistributed(_) => "BuybackDistributed",
+            Self::SavingsVaultDeposited(_) => "SavingsVaultDeposited",
+            Self::SavingsVaultWithdrawn(_) => "SavingsVaultWithdrawn",
+            Self::SavingsVaultHarvested(_) => "SavingsVaultHarvested",
+            Self::OperationCreditsIssued(_) => "OperationCreditsIssued",
+            Self::OperationCreditConsumed(_) => "OperationCreditConsumed",
+            Self::StateTreeRegistered(_) => "StateTreeRegistered",
+            Self::TreeNearCapacity(_) => "TreeNearCapacity",
+        }
+    }
+}
+
+/// Decode every CloakCraft event in a transaction's log messages
+///
+/// Follows `Program <id> invoke` / `success` / `failed` lines so only data
+/// logged while CloakCraft itself is executing is decoded; other programs
+/// (including ones CloakCraft calls) can log `Program data:` lines too.
+pub fn parse_events<'a>(logs: impl IntoIterator<Item = &'a str>) -> Vec<CloakCraftEvent> {
+    let program_id = PROGRAM_ID.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some("Program"), Some(id), Some("invoke")) => stack.push(id),
+            (Some("Program"), Some(id), Some("success" | "failed:"))
+                if stack.last() == Some(&id) =>
+            {
+                stack.pop();
+            }
+            _ if stack.last() == Some(&program_id.as_str()) => {
+                events.extend(CloakCraftEvent::from_log(line));
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::{AnchorSerialize, Discriminator};
+
+    fn program_data<T: AnchorSerialize + Discriminator>(event: &T) -> String {
+        let mut data = T::DISCRIMINATOR.to_vec();
+        event.serialize(&mut data).unwrap();
+        format!("{PROGRAM_DATA_PREFIX}{}", STANDARD.encode(data))
+    }
+
+    #[test]
+    fn test_decode_program_events() {
+        let rolled = cloakcraft::state::PoolStatsEpochRolled {
+            pool: Pubkey::new_from_array([1u8; 32]).to_bytes().into(),
+            epoch: 3,
+            epoch_deposits: 100,
+            epoch_withdrawals: 40,
+            total_shielded: 60,
+            note_count: 2,
+        };
+        let checked = cloakcraft::instructions::PoolSolvencyChecked {
+            pool: Pubkey::new_from_array([2u8; 32]).to_bytes().into(),
+            vault_balance: 10,
+            shielded_supply: 9,
+            pending_outflows: 1,
+            solvent: true,
+        };
+
+        let program = PROGRAM_ID.to_string();
+        let invoke = format!("Program {program} invoke [1]");
+        let success = format!("Program {program} success");
+        let rolled_log = program_data(&rolled);
+        let checked_log = program_data(&checked);
+        let logs = [
+            invoke.as_str(),
+            rolled_log.as_str(),
+            "Program TokenkegQfeZyiNwAJbNbGKPFkQ4NQYhgnVfn2Yq6MDwj invoke [2]",
+            checked_log.as_str(),
+            "Program TokenkegQfeZyiNwAJbNbGKPFkQ4NQYhgnVfn2Yq6MDwj success",
+            checked_log.as_str(),
+            success.as_str(),
+            rolled_log.as_str(),
+        ];
+
+        // Only the two lines logged by CloakCraft itself
+        let events = parse_events(logs);
+        assert_eq!(events.len(), 2);
+        match &events[0] {
+            CloakCraftEvent::PoolStatsEpochRolled(event) => {
+                assert_eq!(
+                    (event.epoch, event.total_shielded, event.note_count),
+                    (3, 60, 2)
+                );
+            }
+            other => panic!("unexpected {}", other.name()),
+        }
+        match &events[1] {
+            CloakCraftEvent::PoolSolvencyChecked(event) => {
+                assert_eq!(event.pool, Pubkey::new_from_array([2u8; 32]));
+                assert!(event.solvent);
+            }
+            other => panic!("unexpected {}", other.name()),
+        }
+        assert!(CloakCraftEvent::decode(&[0u8; 16]).is_none());
+        assert_eq!(
+            CommitmentRegistered::DISCRIMINATOR,
+            <cloakcraft::state::CommitmentRegistered as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            VerificationKeyFinalized::DISCRIMINATOR,
+            <cloakcraft::instructions::VerificationKeyFinalized as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            CircuitProofRejected::DISCRIMINATOR,
+            <cloakcraft::state::CircuitProofRejected as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            SnapshotLeavesAppended::DISCRIMINATOR,
+            <cloakcraft::state::SnapshotLeavesAppended as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            BorrowFeeCheckpointed::DISCRIMINATOR,
+            <cloakcraft::state::BorrowFeeCheckpointed as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            DustSweepBountyPaid::DISCRIMINATOR,
+            <cloakcraft::state::DustSweepBountyPaid as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            PairShielded::DISCRIMINATOR,
+            <cloakcraft::instructions::PairShielded as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            RelayerSlashed::DISCRIMINATOR,
+            <cloakcraft::state::RelayerSlashed as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            BallotOptionAdded::DISCRIMINATOR,
+            <cloakcraft::instructions::BallotOptionAdded as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            ResolutionDisputed::DISCRIMINATOR,
+            <cloakcraft::instructions::ResolutionDisputed as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            DisputeSettled::DISCRIMINATOR,
+            <cloakcraft::instructions::DisputeSettled as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            MarketDelisted::DISCRIMINATOR,
+            <cloakcraft::instructions::MarketDelisted as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            MarketSettled::DISCRIMINATOR,
+            <cloakcraft::instructions::MarketSettled as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            PerpsPoolMigrated::DISCRIMINATOR,
+            <cloakcraft::instructions::PerpsPoolMigrated as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            AmmPoolMigrated::DISCRIMINATOR,
+            <cloakcraft::instructions::AmmPoolMigrated as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            CommitmentCounterReconciled::DISCRIMINATOR,
+            <cloakcraft::instructions::CommitmentCounterReconciled as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            ProtocolFeesUpdated::DISCRIMINATOR,
+            <cloakcraft::instructions::ProtocolFeesUpdated as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            FeeOverrideSet::DISCRIMINATOR,
+            <cloakcraft::instructions::FeeOverrideSet as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            FeeBoundsUpdated::DISCRIMINATOR,
+            <cloakcraft::instructions::FeeBoundsUpdated as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            TreasuryFeesConverted::DISCRIMINATOR,
+            <cloakcraft::instructions::TreasuryFeesConverted as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            BuybackExecuted::DISCRIMINATOR,
+            <cloakcraft::instructions::BuybackExecuted as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            BuybackDistributed::DISCRIMINATOR,
+            <cloakcraft::instructions::BuybackDistributed as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            SavingsVaultDeposited::DISCRIMINATOR,
+            <cloakcraft::instructions::SavingsVaultDeposited as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            SavingsVaultWithdrawn::DISCRIMINATOR,
+            <cloakcraft::instructions::SavingsVaultWithdrawn as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            SavingsVaultHarvested::DISCRIMINATOR,
+            <cloakcraft::instructions::SavingsVaultHarvested as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            OperationCreditsIssued::DISCRIMINATOR,
+            <cloakcraft::state::OperationCreditsIssued as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            OperationCreditConsumed::DISCRIMINATOR,
+            <cloakcraft::state::OperationCreditConsumed as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            StateTreeRegistered::DISCRIMINATOR,
+            <cloakcraft::state::StateTreeRegistered as Discriminator>::DISCRIMINATOR
+        );
+        assert_eq!(
+            TreeNearCapacity::DISCRIMINATOR,
+            <cloakcraft::state::TreeNearCapacity as Discriminator>::DISCRIMINATOR
+        );
+    }
+}
@@ -0,0 +1,620 @@
+//! Instruction discriminators
+//!
+//! Anchor discriminators (`sha256("global:<name>")[..8]`) for every program
+//! instruction. They only change if an instruction is renamed, which is
+//! treated as a breaking change.
+
+/// Discriminator of each instruction, by name
+pub const INSTRUCTIONS: &[(&str, [u8; 8])] = &[
+    ("initialize_pool", INITIALIZE_POOL),
+    ("set_anonymity_guard", SET_ANONYMITY_GUARD),
+    ("override_anonymity_guard", OVERRIDE_ANONYMITY_GUARD),
+    ("set_fixed_denominations", SET_FIXED_DENOMINATIONS),
+    ("set_max_encrypted_note_size", SET_MAX_ENCRYPTED_NOTE_SIZE),
+    ("initialize_relayer_allowlist", INITIALIZE_RELAYER_ALLOWLIST),
+    ("set_allowed_relayer", SET_ALLOWED_RELAYER),
+    (
+        "set_relayer_allowlist_enabled",
+        SET_RELAYER_ALLOWLIST_ENABLED,
+    ),
+    ("migrate_pool_trees", MIGRATE_POOL_TREES),
+    ("migrate_pool", MIGRATE_POOL),
+    ("initialize_root_checkpoints", INITIALIZE_ROOT_CHECKPOINTS),
+    ("anchor_root_checkpoint", ANCHOR_ROOT_CHECKPOINT),
+    ("initialize_root_registry", INITIALIZE_ROOT_REGISTRY),
+    ("verify_external_inclusion", VERIFY_EXTERNAL_INCLUSION),
+    ("set_max_root_age_slots", SET_MAX_ROOT_AGE_SLOTS),
+    (
+        "set_pinned_inclusion_threshold",
+        SET_PINNED_INCLUSION_THRESHOLD,
+    ),
+    ("set_surplus_amm_pool", SET_SURPLUS_AMM_POOL),
+    ("initialize_pool_stats", INITIALIZE_POOL_STATS),
+    ("verify_pool_solvency", VERIFY_POOL_SOLVENCY),
+    ("shield", SHIELD),
+    ("shield_signed", SHIELD_SIGNED),
+    ("shield_pair", SHIELD_PAIR),
+    ("shield_nft", SHIELD_NFT),
+    (
+        "initialize_commitment_counter",
+        INITIALIZE_COMMITMENT_COUNTER,
+    ),
+    ("reconcile_commitment_counter", RECONCILE_COMMITMENT_COUNTER),
+    ("create_pending_with_proof", CREATE_PENDING_WITH_PROOF),
+    ("append_transfer_to_batch", APPEND_TRANSFER_TO_BATCH),
+    (
+        "create_pending_with_proof_consolidation",
+        CREATE_PENDING_WITH_PROOF_CONSOLIDATION,
+    ),
+    ("process_unshield", PROCESS_UNSHIELD),
+    ("process_unshield_and_invoke", PROCESS_UNSHIELD_AND_INVOKE),
+    ("process_unshield_nft", PROCESS_UNSHIELD_NFT),
+    ("shield_cnft", SHIELD_CNFT),
+    ("process_unshield_cnft", PROCESS_UNSHIELD_CNFT),
+    ("transact", TRANSACT),
+    ("store_commitment", STORE_COMMITMENT),
+    ("transact_adapt", TRANSACT_ADAPT),
+    ("create_order", CREATE_ORDER),
+    ("fill_order", FILL_ORDER),
+    ("cancel_order", CANCEL_ORDER),
+    ("modify_order", MODIFY_ORDER),
+    ("create_escrow_yield_policy", CREATE_ESCROW_YIELD_POLICY),
+    ("update_escrow_yield_policy", UPDATE_ESCROW_YIELD_POLICY),
+    ("opt_in_escrow_yield", OPT_IN_ESCROW_YIELD),
+    ("deploy_escrow_yield", DEPLOY_ESCROW_YIELD),
+    ("recall_escrow_yield", RECALL_ESCROW_YIELD),
+    (
+        "create_pending_with_proof_claim_escrow_yield",
+        CREATE_PENDING_WITH_PROOF_CLAIM_ESCROW_YIELD,
+    ),
+    ("execute_claim_escrow_yield", EXECUTE_CLAIM_ESCROW_YIELD),
+    ("initialize_amm_pool", INITIALIZE_AMM_POOL),
+    (
+        "create_pending_with_proof_swap",
+        CREATE_PENDING_WITH_PROOF_SWAP,
+    ),
+    ("execute_swap", EXECUTE_SWAP),
+    (
+        "create_pending_with_proof_swap_committed",
+        CREATE_PENDING_WITH_PROOF_SWAP_COMMITTED,
+    ),
+    (
+        "create_pending_with_proof_swap_sealed",
+        CREATE_PENDING_WITH_PROOF_SWAP_SEALED,
+    ),
+    ("execute_swap_revealed", EXECUTE_SWAP_REVEALED),
+    ("create_fee_rebate_config", CREATE_FEE_REBATE_CONFIG),
+    ("init_swap_volume", INIT_SWAP_VOLUME),
+    (
+        "create_pending_with_proof_claim_fee_rebate",
+        CREATE_PENDING_WITH_PROOF_CLAIM_FEE_REBATE,
+    ),
+    ("execute_claim_fee_rebate", EXECUTE_CLAIM_FEE_REBATE),
+    ("register_lp_lock", REGISTER_LP_LOCK),
+    ("sync_reserves", SYNC_RESERVES),
+    ("convert_treasury_fees", CONVERT_TREASURY_FEES),
+    ("quote_swap", QUOTE_SWAP),
+    (
+        "create_pending_with_proof_remove_liquidity",
+        CREATE_PENDING_WITH_PROOF_REMOVE_LIQUIDITY,
+    ),
+    ("execute_remove_liquidity", EXECUTE_REMOVE_LIQUIDITY),
+    (
+        "create_pending_with_proof_convert_amm_lp_note",
+        CREATE_PENDING_WITH_PROOF_CONVERT_AMM_LP_NOTE,
+    ),
+    (
+        "create_pending_with_proof_add_liquidity",
+        CREATE_PENDING_WITH_PROOF_ADD_LIQUIDITY,
+    ),
+    ("execute_add_liquidity", EXECUTE_ADD_LIQUIDITY),
+    ("verify_commitment_exists", VERIFY_COMMITMENT_EXISTS),
+    ("create_nullifier_and_pending", CREATE_NULLIFIER_AND_PENDING),
+    ("close_pending_operation", CLOSE_PENDING_OPERATION),
+    ("estimate_operation_cost", ESTIMATE_OPERATION_COST),
+    ("simulate_operation", SIMULATE_OPERATION),
+    ("initialize_circuit_stats", INITIALIZE_CIRCUIT_STATS),
+    ("record_proof_failure", RECORD_PROOF_FAILURE),
+    ("bond_relayer_stake", BOND_RELAYER_STAKE),
+    ("unbond_relayer_stake", UNBOND_RELAYER_STAKE),
+    ("withdraw_relayer_stake", WITHDRAW_RELAYER_STAKE),
+    ("create_nullifier", CREATE_NULLIFIER),
+    ("create_commitment", CREATE_COMMITMENT),
+    (
+        "create_nullifiers_and_commitments",
+        CREATE_NULLIFIERS_AND_COMMITMENTS,
+    ),
+    ("register_adapt_module", REGISTER_ADAPT_MODULE),
+    ("disable_adapt_module", DISABLE_ADAPT_MODULE),
+    ("register_verification_key", REGISTER_VERIFICATION_KEY),
+    ("set_verification_key_data", SET_VERIFICATION_KEY_DATA),
+    ("append_verification_key_data", APPEND_VERIFICATION_KEY_DATA),
+    ("finalize_verification_key", FINALIZE_VERIFICATION_KEY),
+    ("register_threshold_committee", REGISTER_THRESHOLD_COMMITTEE),
+    ("test_verify_proof", TEST_VERIFY_PROOF),
+    ("reset_amm_pool", RESET_AMM_POOL),
+    ("set_amm_lp_lock", SET_AMM_LP_LOCK),
+    ("migrate_amm_pool", MIGRATE_AMM_POOL),
+    ("initialize_protocol_config", INITIALIZE_PROTOCOL_CONFIG),
+    ("update_protocol_fees", UPDATE_PROTOCOL_FEES),
+    ("set_fee_override", SET_FEE_OVERRIDE),
+    ("set_fee_bounds", SET_FEE_BOUNDS),
+    ("update_treasury", UPDATE_TREASURY),
+    ("update_protocol_authority", UPDATE_PROTOCOL_AUTHORITY),
+    ("set_pending_expiry", SET_PENDING_EXPIRY),
+    ("set_rent_refund_bps", SET_RENT_REFUND_BPS),
+    ("set_surplus_policy", SET_SURPLUS_POLICY),
+    ("set_treasury_conversion", SET_TREASURY_CONVERSION),
+    ("migrate_protocol_config", MIGRATE_PROTOCOL_CONFIG),
+    ("bump_operation_epoch", BUMP_OPERATION_EPOCH),
+    ("set_amm_creation_policy", SET_AMM_CREATION_POLICY),
+    (
+        "initialize_pool_creator_allowlist",
+        INITIALIZE_POOL_CREATOR_ALLOWLIST,
+    ),
+    ("set_pool_creator", SET_POOL_CREATOR),
+    ("initialize_program_version", INITIALIZE_PROGRAM_VERSION),
+    ("set_program_version", SET_PROGRAM_VERSION),
+    ("initialize_dust_sweep_ledger", INITIALIZE_DUST_SWEEP_LEDGER),
+    ("set_dust_sweep_bounty", SET_DUST_SWEEP_BOUNTY),
+    ("initialize_perps_pool", INITIALIZE_PERPS_POOL),
+    ("add_token_to_pool", ADD_TOKEN_TO_POOL),
+    ("add_market", ADD_MARKET),
+    ("update_perps_pool_config", UPDATE_PERPS_POOL_CONFIG),
+    ("update_perps_token_status", UPDATE_PERPS_TOKEN_STATUS),
+    ("update_perps_market_status", UPDATE_PERPS_MARKET_STATUS),
+    ("delist_market", DELIST_MARKET),
+    ("begin_pool_migration", BEGIN_POOL_MIGRATION),
+    ("migrate_token_vault", MIGRATE_TOKEN_VAULT),
+    ("finalize_pool_migration", FINALIZE_POOL_MIGRATION),
+    (
+        "update_perps_token_target_weight",
+        UPDATE_PERPS_TOKEN_TARGET_WEIGHT,
+    ),
+    (
+        "initialize_borrow_fee_history",
+        INITIALIZE_BORROW_FEE_HISTORY,
+    ),
+    (
+        "create_pending_with_proof_open_position",
+        CREATE_PENDING_WITH_PROOF_OPEN_POSITION,
+    ),
+    ("execute_open_position", EXECUTE_OPEN_POSITION),
+    (
+        "create_pending_with_proof_open_position_sealed",
+        CREATE_PENDING_WITH_PROOF_OPEN_POSITION_SEALED,
+    ),
+    (
+        "execute_open_position_revealed",
+        EXECUTE_OPEN_POSITION_REVEALED,
+    ),
+    ("create_position_meta", CREATE_POSITION_META),
+    (
+        "create_pending_with_proof_close_position",
+        CREATE_PENDING_WITH_PROOF_CLOSE_POSITION,
+    ),
+    ("execute_close_position", EXECUTE_CLOSE_POSITION),
+    ("verify_position_meta_active", VERIFY_POSITION_META_ACTIVE),
+    (
+        "create_pending_with_proof_transfer_position",
+        CREATE_PENDING_WITH_PROOF_TRANSFER_POSITION,
+    ),
+    (
+        "create_position_meta_transfer",
+        CREATE_POSITION_META_TRANSFER,
+    ),
+    ("quote_position_value", QUOTE_POSITION_VALUE),
+    ("create_perp_order", CREATE_PERP_ORDER),
+    ("execute_perp_order_fill", EXECUTE_PERP_ORDER_FILL),
+    ("close_expired_perp_order", CLOSE_EXPIRED_PERP_ORDER),
+    (
+        "create_pending_with_proof_add_perps_liquidity",
+        CREATE_PENDING_WITH_PROOF_ADD_PERPS_LIQUIDITY,
+    ),
+    ("execute_add_perps_liquidity", EXECUTE_ADD_PERPS_LIQUIDITY),
+    (
+        "create_pending_with_proof_remove_perps_liquidity",
+        CREATE_PENDING_WITH_PROOF_REMOVE_PERPS_LIQUIDITY,
+    ),
+    (
+        "execute_remove_perps_liquidity",
+        EXECUTE_REMOVE_PERPS_LIQUIDITY,
+    ),
+    (
+        "create_pending_with_proof_convert_lp_note",
+        CREATE_PENDING_WITH_PROOF_CONVERT_LP_NOTE,
+    ),
+    ("update_perps_borrow_fees", UPDATE_PERPS_BORROW_FEES),
+    ("rebalance_perps_pool", REBALANCE_PERPS_POOL),
+    ("force_settle_market", FORCE_SETTLE_MARKET),
+    (
+        "create_pending_with_proof_liquidate",
+        CREATE_PENDING_WITH_PROOF_LIQUIDATE,
+    ),
+    ("execute_liquidate", EXECUTE_LIQUIDATE),
+    ("check_perps_profit_bound", CHECK_PERPS_PROFIT_BOUND),
+    (
+        "emit_perps_profit_bound_event",
+        EMIT_PERPS_PROFIT_BOUND_EVENT,
+    ),
+    ("create_ballot", CREATE_BALLOT),
+    ("resolve_ballot", RESOLVE_BALLOT),
+    ("finalize_ballot", FINALIZE_BALLOT),
+    ("register_snapshot_root", REGISTER_SNAPSHOT_ROOT),
+    ("register_lp_vote_source", REGISTER_LP_VOTE_SOURCE),
+    ("create_ballot_options_page", CREATE_BALLOT_OPTIONS_PAGE),
+    ("add_ballot_option", ADD_BALLOT_OPTION),
+    ("dispute_resolution", DISPUTE_RESOLUTION),
+    ("settle_dispute", SETTLE_DISPUTE),
+    ("initialize_snapshot_tree", INITIALIZE_SNAPSHOT_TREE),
+    ("append_snapshot_leaves", APPEND_SNAPSHOT_LEAVES),
+    ("decrypt_tally", DECRYPT_TALLY),
+    (
+        "create_pending_with_proof_vote_snapshot",
+        CREATE_PENDING_WITH_PROOF_VOTE_SNAPSHOT,
+    ),
+    ("create_vote_nullifier", CREATE_VOTE_NULLIFIER),
+    (
+        "create_pending_with_proof_vote_twab",
+        CREATE_PENDING_WITH_PROOF_VOTE_TWAB,
+    ),
+    ("execute_vote_snapshot", EXECUTE_VOTE_SNAPSHOT),
+    ("create_vote_commitment", CREATE_VOTE_COMMITMENT),
+    (
+        "verify_vote_commitment_exists",
+        VERIFY_VOTE_COMMITMENT_EXISTS,
+    ),
+    (
+        "create_pending_with_proof_change_vote_snapshot",
+        CREATE_PENDING_WITH_PROOF_CHANGE_VOTE_SNAPSHOT,
+    ),
+    ("execute_change_vote_snapshot", EXECUTE_CHANGE_VOTE_SNAPSHOT),
+    (
+        "create_pending_with_proof_vote_spend",
+        CREATE_PENDING_WITH_PROOF_VOTE_SPEND,
+    ),
+    ("execute_vote_spend", EXECUTE_VOTE_SPEND),
+    (
+        "create_pending_with_proof_change_vote_spend",
+        CREATE_PENDING_WITH_PROOF_CHANGE_VOTE_SPEND,
+    ),
+    ("execute_change_vote_spend", EXECUTE_CHANGE_VOTE_SPEND),
+    (
+        "create_pending_with_proof_close_vote_position",
+        CREATE_PENDING_WITH_PROOF_CLOSE_VOTE_POSITION,
+    ),
+    ("execute_close_vote_position", EXECUTE_CLOSE_VOTE_POSITION),
+    (
+        "create_pending_with_proof_claim",
+        CREATE_PENDING_WITH_PROOF_CLAIM,
+    ),
+    (
+        "create_pending_with_proof_claim_multi",
+        CREATE_PENDING_WITH_PROOF_CLAIM_MULTI,
+    ),
+    (
+        "create_pending_with_proof_claim_refund",
+        CREATE_PENDING_WITH_PROOF_CLAIM_REFUND,
+    ),
+    ("execute_claim", EXECUTE_CLAIM),
+    ("quote_claim_payout", QUOTE_CLAIM_PAYOUT),
+    ("create_emissions_schedule", CREATE_EMISSIONS_SCHEDULE),
+    ("register_reward_checkpoint", REGISTER_REWARD_CHECKPOINT),
+    (
+        "create_pending_with_proof_claim_rewards",
+        CREATE_PENDING_WITH_PROOF_CLAIM_REWARDS,
+    ),
+    ("execute_claim_rewards", EXECUTE_CLAIM_REWARDS),
+    ("create_matching_round", CREATE_MATCHING_ROUND),
+    (
+        "create_pending_with_proof_donate",
+        CREATE_PENDING_WITH_PROOF_DONATE,
+    ),
+    ("execute_donate", EXECUTE_DONATE),
+    ("compute_matching", COMPUTE_MATCHING),
+    ("claim_matching", CLAIM_MATCHING),
+    ("initialize_buyback", INITIALIZE_BUYBACK),
+    ("set_buyback_policy", SET_BUYBACK_POLICY),
+    ("stake_protocol_token", STAKE_PROTOCOL_TOKEN),
+    ("unstake_protocol_token", UNSTAKE_PROTOCOL_TOKEN),
+    ("execute_buyback", EXECUTE_BUYBACK),
+    ("distribute_buyback", DISTRIBUTE_BUYBACK),
+    ("initialize_savings_vault", INITIALIZE_SAVINGS_VAULT),
+    ("set_savings_vault_policy", SET_SAVINGS_VAULT_POLICY),
+    (
+        "create_pending_with_proof_vault_deposit",
+        CREATE_PENDING_WITH_PROOF_VAULT_DEPOSIT,
+    ),
+    ("execute_vault_deposit", EXECUTE_VAULT_DEPOSIT),
+    (
+        "create_pending_with_proof_vault_withdraw",
+        CREATE_PENDING_WITH_PROOF_VAULT_WITHDRAW,
+    ),
+    ("execute_vault_withdraw", EXECUTE_VAULT_WITHDRAW),
+    ("harvest_savings_vault", HARVEST_SAVINGS_VAULT),
+    ("initialize_credit_config", INITIALIZE_CREDIT_CONFIG),
+    ("set_credit_policy", SET_CREDIT_POLICY),
+    ("claim_staked_credits", CLAIM_STAKED_CREDITS),
+    ("purchase_credits", PURCHASE_CREDITS),
+    ("consume_operation_credit", CONSUME_OPERATION_CREDIT),
+    ("close_operation_credit", CLOSE_OPERATION_CREDIT),
+    ("initialize_tree_registry", INITIALIZE_TREE_REGISTRY),
+    ("register_state_tree", REGISTER_STATE_TREE),
+    ("retire_state_tree", RETIRE_STATE_TREE),
+];
+
+pub const INITIALIZE_POOL: [u8; 8] = [95, 180, 10, 172, 84, 174, 232, 40];
+pub const SET_ANONYMITY_GUARD: [u8; 8] = [11, 49, 23, 132, 238, 152, 5, 75];
+pub const OVERRIDE_ANONYMITY_GUARD: [u8; 8] = [252, 169, 135, 87, 141, 114, 70, 194];
+pub const SET_FIXED_DENOMINATIONS: [u8; 8] = [183, 201, 172, 34, 243, 79, 38, 131];
+pub const SET_MAX_ENCRYPTED_NOTE_SIZE: [u8; 8] = [172, 26, 91, 43, 69, 220, 38, 85];
+pub const INITIALIZE_RELAYER_ALLOWLIST: [u8; 8] = [81, 223, 1, 59, 39, 101, 119, 150];
+pub const SET_ALLOWED_RELAYER: [u8; 8] = [33, 48, 55, 221, 207, 132, 177, 177];
+pub const SET_RELAYER_ALLOWLIST_ENABLED: [u8; 8] = [213, 201, 50, 239, 44, 126, 129, 34];
+pub const MIGRATE_POOL_TREES: [u8; 8] = [64, 30, 91, 146, 162, 40, 185, 131];
+pub const MIGRATE_POOL: [u8; 8] = [55, 170, 171, 123, 210, 69, 39, 172];
+pub const INITIALIZE_ROOT_CHECKPOINTS: [u8; 8] = [70, 102, 106, 160, 113, 113, 202, 122];
+pub const ANCHOR_ROOT_CHECKPOINT: [u8; 8] = [227, 186, 89, 94, 22, 90, 109, 121];
+pub const INITIALIZE_ROOT_REGISTRY: [u8; 8] = [232, 87, 199, 19, 35, 180, 205, 157];
+pub const VERIFY_EXTERNAL_INCLUSION: [u8; 8] = [14, 59, 32, 136, 149, 125, 140, 92];
+pub const SET_MAX_ROOT_AGE_SLOTS: [u8; 8] = [41, 3, 110, 210, 47, 188, 27, 159];
+pub const SET_PINNED_INCLUSION_THRESHOLD: [u8; 8] = [50, 238, 81, 93, 60, 206, 182, 197];
+pub const SET_SURPLUS_AMM_POOL: [u8; 8] = [252, 196, 51, 218, 119, 178, 45, 117];
+pub const INITIALIZE_POOL_STATS: [u8; 8] = [56, 225, 69, 186, 188, 223, 34, 193];
+pub const VERIFY_POOL_SOLVENCY: [u8; 8] = [17, 161, 221, 39, 13, 242, 181, 232];
+pub const SHIELD: [u8; 8] = [220, 198, 253, 246, 231, 84, 147, 98];
+pub const SHIELD_SIGNED: [u8; 8] = [20, 59, 124, 146, 213, 41, 237, 251];
+pub const SHIELD_PAIR: [u8; 8] = [15, 147, 99, 212, 229, 87, 92, 4];
+pub const SHIELD_NFT: [u8; 8] = [240, 18, 164, 122, 90, 253, 253, 2];
+pub const INITIALIZE_COMMITMENT_COUNTER: [u8; 8] = [158, 181, 246, 128, 22, 64, 90, 146];
+pub const RECONCILE_COMMITMENT_COUNTER: [u8; 8] = [131, 20, 53, 57, 19, 231, 207, 53];
+pub const CREATE_PENDING_WITH_PROOF: [u8; 8] = [115, 102, 69, 37, 52, 183, 212, 240];
+pub const APPEND_TRANSFER_TO_BATCH: [u8; 8] = [216, 103, 53, 236, 162, 127, 127, 252];
+pub const CREATE_PENDING_WITH_PROOF_CONSOLIDATION: [u8; 8] = [59, 97, 237, 177, 118, 164, 58, 81];
+pub const PROCESS_UNSHIELD: [u8; 8] = [139, 41, 106, 165, 62, 234, 120, 132];
+pub const PROCESS_UNSHIELD_AND_INVOKE: [u8; 8] = [95, 35, 82, 33, 91, 255, 3, 17];
+pub const PROCESS_UNSHIELD_NFT: [u8; 8] = [94, 34, 2, 92, 188, 49, 14, 108];
+pub const SHIELD_CNFT: [u8; 8] = [222, 197, 35, 183, 11, 183, 150, 166];
+pub const PROCESS_UNSHIELD_CNFT: [u8; 8] = [255, 229, 228, 82, 141, 243, 5, 223];
+pub const TRANSACT: [u8; 8] = [217, 149, 130, 143, 221, 52, 252, 119];
+pub const STORE_COMMITMENT: [u8; 8] = [188, 162, 140, 134, 138, 242, 159, 54];
+pub const TRANSACT_ADAPT: [u8; 8] = [240, 109, 123, 193, 132, 96, 145, 122];
+pub const CREATE_ORDER: [u8; 8] = [141, 54, 37, 207, 237, 210, 250, 215];
+pub const FILL_ORDER: [u8; 8] = [232, 122, 115, 25, 199, 143, 136, 162];
+pub const CANCEL_ORDER: [u8; 8] = [95, 129, 237, 240, 8, 49, 223, 132];
+pub const MODIFY_ORDER: [u8; 8] = [47, 124, 117, 255, 201, 197, 130, 94];
+pub const CREATE_ESCROW_YIELD_POLICY: [u8; 8] = [229, 181, 74, 77, 255, 28, 10, 209];
+pub const UPDATE_ESCROW_YIELD_POLICY: [u8; 8] = [45, 51, 174, 94, 131, 222, 121, 208];
+pub const OPT_IN_ESCROW_YIELD: [u8; 8] = [197, 39, 231, 231, 131, 132, 217, 186];
+pub const DEPLOY_ESCROW_YIELD: [u8; 8] = [216, 90, 121, 133, 229, 86, 230, 189];
+pub const RECALL_ESCROW_YIELD: [u8; 8] = [227, 204, 108, 26, 240, 28, 234, 101];
+pub const CREATE_PENDING_WITH_PROOF_CLAIM_ESCROW_YIELD: [u8; 8] =
+    [30, 109, 166, 128, 159, 206, 206, 141];
+pub const EXECUTE_CLAIM_ESCROW_YIELD: [u8; 8] = [250, 255, 186, 13, 51, 240, 116, 133];
+pub const INITIALIZE_AMM_POOL: [u8; 8] = [20, 58, 19, 89, 14, 193, 139, 31];
+pub const CREATE_PENDING_WITH_PROOF_SWAP: [u8; 8] = [250, 231, 89, 171, 94, 41, 47, 245];
+pub const EXECUTE_SWAP: [u8; 8] = [56, 182, 124, 215, 155, 140, 157, 102];
+pub const CREATE_PENDING_WITH_PROOF_SWAP_COMMITTED: [u8; 8] = [103, 107, 246, 31, 52, 4, 100, 27];
+pub const CREATE_PENDING_WITH_PROOF_SWAP_SEALED: [u8; 8] = [89, 86, 137, 94, 188, 220, 173, 224];
+pub const EXECUTE_SWAP_REVEALED: [u8; 8] = [212, 196, 117, 18, 208, 34, 93, 239];
+pub const CREATE_FEE_REBATE_CONFIG: [u8; 8] = [153, 21, 17, 250, 114, 70, 94, 125];
+pub const INIT_SWAP_VOLUME: [u8; 8] = [30, 19, 216, 204, 167, 230, 207, 29];
+pub const CREATE_PENDING_WITH_PROOF_CLAIM_FEE_REBATE: [u8; 8] = [109, 128, 46, 55, 194, 238, 3, 15];
+pub const EXECUTE_CLAIM_FEE_REBATE: [u8; 8] = [30, 21, 245, 11, 133, 73, 39, 169];
+pub const REGISTER_LP_LOCK: [u8; 8] = [207, 204, 2, 100, 101, 117, 12, 209];
+pub const SYNC_RESERVES: [u8; 8] = [28, 30, 78, 31, 95, 31, 176, 244];
+pub const CONVERT_TREASURY_FEES: [u8; 8] = [4, 38, 29, 42, 82, 251, 195, 52];
+pub const QUOTE_SWAP: [u8; 8] = [20, 139, 100, 190, 67, 4, 13, 141];
+pub const CREATE_PENDING_WITH_PROOF_REMOVE_LIQUIDITY: [u8; 8] = [60, 19, 211, 251, 49, 5, 103, 176];
+pub const EXECUTE_REMOVE_LIQUIDITY: [u8; 8] = [21, 226, 243, 31, 221, 192, 31, 201];
+pub const CREATE_PENDING_WITH_PROOF_CONVERT_AMM_LP_NOTE: [u8; 8] =
+    [254, 229, 133, 221, 54, 46, 76, 1];
+pub const CREATE_PENDING_WITH_PROOF_ADD_LIQUIDITY: [u8; 8] = [65, 218, 153, 125, 62, 172, 209, 39];
+pub const EXECUTE_ADD_LIQUIDITY: [u8; 8] = [31, 200, 193, 210, 136, 205, 216, 24];
+pub const VERIFY_COMMITMENT_EXISTS: [u8; 8] = [126, 11, 155, 178, 177, 176, 157, 136];
+pub const CREATE_NULLIFIER_AND_PENDING: [u8; 8] = [72, 148, 152, 177, 52, 246, 217, 202];
+pub const CLOSE_PENDING_OPERATION: [u8; 8] = [251, 131, 94, 64, 37, 41, 43, 157];
+pub const ESTIMATE_OPERATION_COST: [u8; 8] = [51, 210, 236, 189, 31, 90, 245, 141];
+pub const SIMULATE_OPERATION: [u8; 8] = [93, 199, 147, 125, 124, 50, 50, 212];
+pub const INITIALIZE_CIRCUIT_STATS: [u8; 8] = [126, 73, 76, 94, 22, 104, 2, 159];
+pub const RECORD_PROOF_FAILURE: [u8; 8] = [228, 185, 34, 4, 140, 249, 116, 157];
+pub const BOND_RELAYER_STAKE: [u8; 8] = [192, 252, 162, 159, 53, 90, 27, 212];
+pub const UNBOND_RELAYER_STAKE: [u8; 8] = [127, 107, 103, 59, 67, 73, 7, 118];
+pub const WITHDRAW_RELAYER_STAKE: [u8; 8] = [31, 49, 31, 47, 80, 54, 77, 137];
+pub const CREATE_NULLIFIER: [u8; 8] = [171, 144, 50, 154, 87, 170, 57, 66];
+pub const CREATE_COMMITMENT: [u8; 8] = [232, 31, 118, 65, 229, 2, 2, 170];
+pub const CREATE_NULLIFIERS_AND_COMMITMENTS: [u8; 8] = [199, 209, 190, 164, 237, 114, 14, 180];
+pub const REGISTER_ADAPT_MODULE: [u8; 8] = [106, 98, 19, 132, 158, 99, 214, 47];
+pub const DISABLE_ADAPT_MODULE: [u8; 8] = [226, 114, 232, 9, 230, 15, 68, 225];
+pub const REGISTER_VERIFICATION_KEY: [u8; 8] = [252, 136, 235, 8, 197, 79, 40, 67];
+pub const SET_VERIFICATION_KEY_DATA: [u8; 8] = [117, 234, 100, 99, 128, 32, 44, 101];
+pub const APPEND_VERIFICATION_KEY_DATA: [u8; 8] = [21, 242, 226, 2, 197, 148, 11, 181];
+pub const FINALIZE_VERIFICATION_KEY: [u8; 8] = [207, 167, 175, 209, 115, 87, 185, 224];
+pub const REGISTER_THRESHOLD_COMMITTEE: [u8; 8] = [93, 46, 75, 78, 68, 136, 109, 217];
+pub const TEST_VERIFY_PROOF: [u8; 8] = [252, 208, 59, 22, 178, 59, 46, 253];
+pub const RESET_AMM_POOL: [u8; 8] = [67, 206, 131, 179, 253, 87, 240, 165];
+pub const SET_AMM_LP_LOCK: [u8; 8] = [144, 128, 5, 143, 34, 225, 25, 124];
+pub const MIGRATE_AMM_POOL: [u8; 8] = [99, 252, 202, 24, 179, 61, 165, 183];
+pub const INITIALIZE_PROTOCOL_CONFIG: [u8; 8] = [28, 50, 43, 233, 244, 98, 123, 118];
+pub const UPDATE_PROTOCOL_FEES: [u8; 8] = [158, 219, 253, 143, 54, 45, 113, 182];
+pub const SET_FEE_OVERRIDE: [u8; 8] = [238, 6, 44, 194, 50, 78, 93, 3];
+pub const SET_FEE_BOUNDS: [u8; 8] = [246, 179, 24, 106, 125, 44, 1, 0];
+pub const UPDATE_TREASURY: [u8; 8] = [60, 16, 243, 66, 96, 59, 254, 131];
+pub const UPDATE_PROTOCOL_AUTHORITY: [u8; 8] = [207, 19, 17, 100, 133, 169, 89, 253];
+pub const SET_PENDING_EXPIRY: [u8; 8] = [141, 185, 14, 96, 123, 190, 159, 2];
+pub const SET_RENT_REFUND_BPS: [u8; 8] = [165, 76, 247, 61, 203, 103, 133, 240];
+pub const SET_SURPLUS_POLICY: [u8; 8] = [142, 75, 208, 47, 174, 124, 91, 119];
+pub const SET_TREASURY_CONVERSION: [u8; 8] = [122, 236, 11, 243, 12, 214, 235, 100];
+pub const MIGRATE_PROTOCOL_CONFIG: [u8; 8] = [240, 133, 241, 218, 118, 253, 139, 28];
+pub const BUMP_OPERATION_EPOCH: [u8; 8] = [69, 64, 203, 255, 28, 32, 165, 69];
+pub const SET_AMM_CREATION_POLICY: [u8; 8] = [156, 246, 255, 209, 37, 187, 222, 246];
+pub const INITIALIZE_POOL_CREATOR_ALLOWLIST: [u8; 8] = [188, 92, 182, 202, 56, 144, 243, 169];
+pub const SET_POOL_CREATOR: [u8; 8] = [200, 236, 221, 120, 234, 221, 247, 107];
+pub const INITIALIZE_PROGRAM_VERSION: [u8; 8] = [252, 182, 43, 39, 73, 208, 120, 42];
+pub const SET_PROGRAM_VERSION: [u8; 8] = [41, 99, 32, 201, 200, 35, 2, 246];
+pub const INITIALIZE_DUST_SWEEP_LEDGER: [u8; 8] = [187, 2, 50, 3, 106, 206, 207, 155];
+pub const SET_DUST_SWEEP_BOUNTY: [u8; 8] = [43, 122, 244, 99, 73, 177, 53, 66];
+pub const INITIALIZE_PERPS_POOL: [u8; 8] = [246, 147, 238, 44, 78, 181, 140, 46];
+pub const ADD_TOKEN_TO_POOL: [u8; 8] = [35, 121, 233, 111, 213, 155, 197, 192];
+pub const ADD_MARKET: [u8; 8] = [41, 137, 185, 126, 69, 139, 254, 55];
+pub const UPDATE_PERPS_POOL_CONFIG: [u8; 8] = [28, 193, 134, 255, 202, 194, 54, 56];
+pub const UPDATE_PERPS_TOKEN_STATUS: [u8; 8] = [233, 63, 249, 50, 165, 122, 230, 98];
+pub const UPDATE_PERPS_MARKET_STATUS: [u8; 8] = [135, 231, 26, 105, 251, 160, 241, 48];
+pub const DELIST_MARKET: [u8; 8] = [159, 116, 65, 106, 234, 42, 248, 251];
+pub const BEGIN_POOL_MIGRATION: [u8; 8] = [30, 20, 232, 86, 254, 130, 50, 33];
+pub const MIGRATE_TOKEN_VAULT: [u8; 8] = [183, 14, 163, 109, 204, 185, 13, 235];
+pub const FINALIZE_POOL_MIGRATION: [u8; 8] = [156, 141, 202, 165, 190, 232, 21, 227];
+pub const UPDATE_PERPS_TOKEN_TARGET_WEIGHT: [u8; 8] = [66, 208, 173, 82, 240, 234, 0, 210];
+pub const INITIALIZE_BORROW_FEE_HISTORY: [u8; 8] = [127, 195, 149, 67, 18, 39, 121, 82];
+pub const CREATE_PENDING_WITH_PROOF_OPEN_POSITION: [u8; 8] =
+    [226, 174, 223, 251, 81, 153, 185, 125];
+pub const EXECUTE_OPEN_POSITION: [u8; 8] = [240, 148, 192, 97, 135, 229, 49, 244];
+pub const CREATE_PENDING_WITH_PROOF_OPEN_POSITION_SEALED: [u8; 8] =
+    [101, 188, 90, 174, 231, 68, 122, 212];
+pub const EXECUTE_OPEN_POSITION_REVEALED: [u8; 8] = [42, 148, 226, 18, 137, 103, 76, 129];
+pub const CREATE_POSITION_META: [u8; 8] = [117, 168, 194, 54, 128, 202, 64, 96];
+pub const CREATE_PENDING_WITH_PROOF_CLOSE_POSITION: [u8; 8] = [18, 208, 74, 198, 104, 122, 129, 21];
+pub const EXECUTE_CLOSE_POSITION: [u8; 8] = [196, 191, 155, 142, 229, 185, 92, 229];
+pub const VERIFY_POSITION_META_ACTIVE: [u8; 8] = [153, 83, 218, 228, 119, 253, 20, 223];
+pub const CREATE_PENDING_WITH_PROOF_TRANSFER_POSITION: [u8; 8] =
+    [237, 120, 151, 37, 53, 160, 199, 255];
+pub const CREATE_POSITION_META_TRANSFER: [u8; 8] = [202, 134, 38, 53, 135, 90, 113, 50];
+pub const QUOTE_POSITION_VALUE: [u8; 8] = [166, 81, 189, 64, 198, 185, 5, 12];
+pub const CREATE_PERP_ORDER: [u8; 8] = [26, 26, 21, 87, 253, 228, 150, 101];
+pub const EXECUTE_PERP_ORDER_FILL: [u8; 8] = [15, 242, 137, 239, 247, 48, 219, 114];
+pub const CLOSE_EXPIRED_PERP_ORDER: [u8; 8] = [42, 14, 86, 190, 26, 153, 230, 110];
+pub const CREATE_PENDING_WITH_PROOF_ADD_PERPS_LIQUIDITY: [u8; 8] =
+    [45, 13, 145, 184, 173, 121, 130, 145];
+pub const EXECUTE_ADD_PERPS_LIQUIDITY: [u8; 8] = [207, 85, 131, 134, 222, 254, 248, 203];
+pub const CREATE_PENDING_WITH_PROOF_REMOVE_PERPS_LIQUIDITY: [u8; 8] =
+    [122, 52, 28, 5, 51, 176, 82, 219];
+pub const EXECUTE_REMOVE_PERPS_LIQUIDITY: [u8; 8] = [46, 31, 102, 209, 147, 205, 196, 29];
+pub const CREATE_PENDING_WITH_PROOF_CONVERT_LP_NOTE: [u8; 8] = [38, 52, 40, 143, 94, 82, 85, 99];
+pub const UPDATE_PERPS_BORROW_FEES: [u8; 8] = [151, 120, 43, 40, 162, 202, 198, 242];
+pub const REBALANCE_PERPS_POOL: [u8; 8] = [246, 113, 108, 230, 88, 31, 213, 62];
+pub const FORCE_SETTLE_MARKET: [u8; 8] = [128, 241, 139, 73, 112, 232, 155, 203];
+pub const CREATE_PENDING_WITH_PROOF_LIQUIDATE: [u8; 8] = [114, 140, 105, 161, 93, 58, 197, 244];
+pub const EXECUTE_LIQUIDATE: [u8; 8] = [153, 46, 46, 219, 247, 2, 99, 232];
+pub const CHECK_PERPS_PROFIT_BOUND: [u8; 8] = [242, 173, 27, 80, 189, 52, 119, 168];
+pub const EMIT_PERPS_PROFIT_BOUND_EVENT: [u8; 8] = [161, 213, 122, 121, 146, 211, 120, 106];
+pub const CREATE_BALLOT: [u8; 8] = [143, 185, 213, 35, 169, 149, 14, 28];
+pub const RESOLVE_BALLOT: [u8; 8] = [67, 63, 34, 133, 20, 164, 64, 4];
+pub const FINALIZE_BALLOT: [u8; 8] = [212, 43, 85, 58, 158, 34, 41, 42];
+pub const REGISTER_SNAPSHOT_ROOT: [u8; 8] = [193, 193, 153, 218, 39, 76, 154, 220];
+pub const REGISTER_LP_VOTE_SOURCE: [u8; 8] = [5, 123, 5, 107, 120, 85, 71, 96];
+pub const ADD_BALLOT_OPTION: [u8; 8] = [194, 157, 145, 148, 96, 164, 40, 147];
+pub const DISPUTE_RESOLUTION: [u8; 8] = [89, 169, 106, 71, 131, 77, 122, 232];
+pub const SETTLE_DISPUTE: [u8; 8] = [155, 147, 5, 44, 20, 204, 146, 43];
+pub const CREATE_BALLOT_OPTIONS_PAGE: [u8; 8] = [81, 38, 118, 135, 92, 162, 115, 143];
+pub const INITIALIZE_SNAPSHOT_TREE: [u8; 8] = [219, 226, 172, 154, 134, 168, 72, 127];
+pub const APPEND_SNAPSHOT_LEAVES: [u8; 8] = [154, 180, 21, 231, 133, 50, 13, 52];
+pub const DECRYPT_TALLY: [u8; 8] = [35, 58, 172, 153, 3, 216, 134, 230];
+pub const CREATE_PENDING_WITH_PROOF_VOTE_SNAPSHOT: [u8; 8] =
+    [154, 186, 239, 245, 157, 252, 209, 213];
+pub const CREATE_VOTE_NULLIFIER: [u8; 8] = [33, 125, 29, 9, 192, 226, 105, 173];
+pub const CREATE_PENDING_WITH_PROOF_VOTE_TWAB: [u8; 8] = [8, 145, 34, 228, 79, 85, 58, 99];
+pub const EXECUTE_VOTE_SNAPSHOT: [u8; 8] = [73, 17, 216, 207, 210, 195, 175, 54];
+pub const CREATE_VOTE_COMMITMENT: [u8; 8] = [165, 146, 239, 8, 166, 54, 3, 188];
+pub const VERIFY_VOTE_COMMITMENT_EXISTS: [u8; 8] = [76, 132, 220, 91, 78, 223, 179, 81];
+pub const CREATE_PENDING_WITH_PROOF_CHANGE_VOTE_SNAPSHOT: [u8; 8] =
+    [184, 170, 141, 161, 120, 153, 54, 35];
+pub const EXECUTE_CHANGE_VOTE_SNAPSHOT: [u8; 8] = [36, 232, 114, 236, 99, 218, 182, 195];
+pub const CREATE_PENDING_WITH_PROOF_VOTE_SPEND: [u8; 8] = [121, 101, 52, 45, 181, 24, 248, 55];
+pub const EXECUTE_VOTE_SPEND: [u8; 8] = [17, 112, 255, 194, 200, 212, 19, 143];
+pub const CREATE_PENDING_WITH_PROOF_CHANGE_VOTE_SPEND: [u8; 8] =
+    [116, 105, 71, 15, 101, 194, 230, 208];
+pub const EXECUTE_CHANGE_VOTE_SPEND: [u8; 8] = [207, 176, 177, 79, 110, 184, 148, 190];
+pub const CREATE_PENDING_WITH_PROOF_CLOSE_VOTE_POSITION: [u8; 8] =
+    [43, 87, 195, 136, 171, 106, 175, 37];
+pub const EXECUTE_CLOSE_VOTE_POSITION: [u8; 8] = [249, 60, 175, 202, 45, 50, 135, 168];
+pub const CREATE_PENDING_WITH_PROOF_CLAIM: [u8; 8] = [113, 180, 7, 31, 252, 1, 205, 4];
+pub const CREATE_PENDING_WITH_PROOF_CLAIM_MULTI: [u8; 8] = [195, 200, 201, 51, 174, 8, 4, 217];
+pub const CREATE_PENDING_WITH_PROOF_CLAIM_REFUND: [u8; 8] = [91, 232, 196, 107, 82, 189, 231, 70];
+pub const EXECUTE_CLAIM: [u8; 8] = [186, 104, 236, 95, 252, 189, 167, 99];
+pub const QUOTE_CLAIM_PAYOUT: [u8; 8] = [232, 173, 58, 20, 124, 252, 203, 193];
+pub const CREATE_EMISSIONS_SCHEDULE: [u8; 8] = [239, 128, 251, 38, 153, 123, 132, 252];
+pub const REGISTER_REWARD_CHECKPOINT: [u8; 8] = [231, 194, 72, 241, 95, 192, 245, 39];
+pub const CREATE_PENDING_WITH_PROOF_CLAIM_REWARDS: [u8; 8] = [164, 142, 79, 188, 153, 15, 189, 25];
+pub const EXECUTE_CLAIM_REWARDS: [u8; 8] = [11, 24, 41, 116, 211, 71, 30, 142];
+pub const CREATE_MATCHING_ROUND: [u8; 8] = [50, 95, 63, 71, 2, 112, 63, 199];
+pub const CREATE_PENDING_WITH_PROOF_DONATE: [u8; 8] = [122, 247, 15, 202, 32, 101, 178, 156];
+pub const EXECUTE_DONATE: [u8; 8] = [133, 210, 195, 69, 221, 94, 5, 106];
+pub const COMPUTE_MATCHING: [u8; 8] = [96, 114, 253, 60, 78, 2, 72, 1];
+pub const CLAIM_MATCHING: [u8; 8] = [10, 9, 80, 90, 253, 49, 238, 192];
+pub const INITIALIZE_BUYBACK: [u8; 8] = [250, 129, 236, 160, 227, 36, 103, 134];
+pub const SET_BUYBACK_POLICY: [u8; 8] = [244, 206, 207, 51, 221, 234, 4, 106];
+pub const STAKE_PROTOCOL_TOKEN: [u8; 8] = [150, 33, 140, 132, 16, 78, 203, 220];
+pub const UNSTAKE_PROTOCOL_TOKEN: [u8; 8] = [59, 112, 145, 176, 28, 65, 247, 194];
+pub const EXECUTE_BUYBACK: [u8; 8] = [47, 32, 19, 100, 184, 96, 144, 49];
+pub const DISTRIBUTE_BUYBACK: [u8; 8] = [250, 138, 67, 209, 221, 129, 152, 109];
+pub const INITIALIZE_SAVINGS_VAULT: [u8; 8] = [215, 60, 108, 60, 71, 30, 180, 130];
+pub const SET_SAVINGS_VAULT_POLICY: [u8; 8] = [215, 137, 16, 93, 87, 112, 32, 42];
+pub const CREATE_PENDING_WITH_PROOF_VAULT_DEPOSIT: [u8; 8] = [240, 122, 215, 88, 125, 225, 159, 43];
+pub const EXECUTE_VAULT_DEPOSIT: [u8; 8] = [52, 202, 163, 165, 8, 132, 254, 148];
+pub const CREATE_PENDING_WITH_PROOF_VAULT_WITHDRAW: [u8; 8] = [12, 163, 91, 102, 200, 235, 112, 1];
+pub const EXECUTE_VAULT_WITHDRAW: [u8; 8] = [24, 44, 19, 241, 144, 54, 179, 158];
+pub const HARVEST_SAVINGS_VAULT: [u8; 8] = [219, 221, 132, 27, 214, 242, 208, 126];
+pub const INITIALIZE_CREDIT_CONFIG: [u8; 8] = [178, 136, 29, 150, 14, 178, 172, 121];
+pub const SET_CREDIT_POLICY: [u8; 8] = [114, 188, 131, 197, 211, 232, 169, 87];
+pub const CLAIM_STAKED_CREDITS: [u8; 8] = [107, 92, 149, 44, 152, 18, 61, 27];
+pub const PURCHASE_CREDITS: [u8; 8] = [228, 95, 55, 42, 168, 253, 222, 216];
+pub const CONSUME_OPERATION_CREDIT: [u8; 8] = [240, 93, 100, 225, 82, 140, 216, 182];
+pub const CLOSE_OPERATION_CREDIT: [u8; 8] = [182, 187, 43, 60, 241, 216, 225, 230];
+pub const INITIALIZE_TREE_REGISTRY: [u8; 8] = [14, 121, 139, 65, 247, 35, 31, 107];
+pub const REGISTER_STATE_TREE: [u8; 8] = [106, 27, 199, 203, 213, 232, 200, 118];
+pub const RETIRE_STATE_TREE: [u8; 8] = [208, 150, 12, 137, 27, 104, 205, 51];
+
+/// Name of the instruction `data` invokes, if it is a CloakCraft instruction
+pub fn instruction_name(data: &[u8]) -> Option<&'static str> {
+    let discriminator = data.get(..8)?;
+    INSTRUCTIONS
+        .iter()
+        .find(|(_, d)| d.as_slice() == discriminator)
+        .map(|(name, _)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Discriminator;
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn test_discriminators_match_names() {
+        for (name, discriminator) in INSTRUCTIONS {
+            let hash = Sha256::digest(format!("global:{name}"));
+            assert_eq!(&hash[..8], discriminator, "{name}");
+        }
+        assert_eq!(instruction_name(&SHIELD_SIGNED), Some("shield_signed"));
+        assert_eq!(instruction_name(&[0u8; 4]), None);
+    }
+
+    #[test]
+    fn test_discriminators_match_program() {
+        assert_eq!(SHIELD, cloakcraft::instruction::Shield::DISCRIMINATOR);
+        assert_eq!(
+            CREATE_COMMITMENT,
+            cloakcraft::instruction::CreateCommitment::DISCRIMINATOR
+        );
+        assert_eq!(
+            CREATE_NULLIFIER,
+            cloakcraft::instruction::CreateNullifier::DISCRIMINATOR
+        );
+        assert_eq!(
+            CREATE_NULLIFIERS_AND_COMMITMENTS,
+            cloakcraft::instruction::CreateNullifiersAndCommitments::DISCRIMINATOR
+        );
+        assert_eq!(
+            CLOSE_PENDING_OPERATION,
+            cloakcraft::instruction::ClosePendingOperation::DISCRIMINATOR
+        );
+        assert_eq!(
+            EXECUTE_SWAP,
+            cloakcraft::instruction::ExecuteSwap::DISCRIMINATOR
+        );
+        assert_eq!(
+            CLAIM_MATCHING,
+            cloakcraft::instruction::ClaimMatching::DISCRIMINATOR
+        );
+    }
+}
@@ -0,0 +1,29 @@
+//! CloakCraft program interface
+//!
+//! Instruction discriminators, account layouts, events, return data and PDA
+//! derivation for indexers, explorers and other programs, with no Anchor or Light
+//! Protocol dependency. Layouts are plain borsh and are checked against the
+//! program's own types in this crate's tests.
+//!
+//! Anchor accounts and events are prefixed with an 8-byte discriminator;
+//! Light compressed accounts carry theirs separately (the `discriminator`
+//! field indexers return), so their data starts at the first field.
+
+use solana_pubkey::Pubkey;
+
+pub mod accounts;
+pub mod ballot;
+pub mod events;
+pub mod instruction;
+pub mod pda;
+pub mod returns;
+
+/// CloakCraft program id
+pub const PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("2VWF9TxMFgzHwbd5WPpYKoqHvtzk3fN66Ka3tVV82nZG");
+
+/// Split an 8-byte discriminator off `data`
+pub(crate) fn split_discriminator(data: &[u8]) -> Option<([u8; 8], &[u8])> {
+    let discriminator = data.get(..8)?.try_into().ok()?;
+    Some((discriminator, &data[8..]))
+}
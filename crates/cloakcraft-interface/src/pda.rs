@@ -0,0 +1,250 @@
+//! PDA derivation
+//!
+//! Seeds match `cloakcraft::constants::seeds` and the account types' seed
+//! prefixes.
+
+use solana_pubkey::Pubkey;
+
+use crate::PROGRAM_ID;
+
+pub mod seeds {
+    pub const POOL: &[u8] = b"pool";
+    pub const VAULT: &[u8] = b"vault";
+    pub const COMMITMENT_COUNTER: &[u8] = b"commitment_counter";
+    pub const POOL_STATS: &[u8] = b"pool_stats";
+    pub const ROOT_REGISTRY: &[u8] = b"root_registry";
+    pub const CIRCUIT_STATS: &[u8] = b"circuit_stats";
+    pub const SNAPSHOT_TREE: &[u8] = b"snapshot_tree";
+    pub const BALLOT_OPTIONS: &[u8] = b"ballot_options";
+    pub const BORROW_FEE_HISTORY: &[u8] = b"borrow_fee_history";
+    pub const PERPS_MIGRATION: &[u8] = b"perps_migration";
+    pub const PROTOCOL_CONFIG: &[u8] = b"protocol_config";
+    pub const DUST_SWEEP_LEDGER: &[u8] = b"dust_sweep_ledger";
+    pub const RELAYER_ALLOWLIST: &[u8] = b"relayer_allowlist";
+    pub const RELAYER_STAKE: &[u8] = b"relayer_stake";
+    pub const CREDIT_CONFIG: &[u8] = b"credit_config";
+    pub const CREDIT_TREE: &[u8] = b"credit_tree";
+    pub const CREDIT_NULLIFIER: &[u8] = b"credit_nullifier";
+    pub const TREE_REGISTRY: &[u8] = b"tree_registry";
+    pub const PENDING_OPERATION: &[u8] = b"pending_op";
+    pub const AMM_POOL: &[u8] = b"amm_pool";
+    pub const LP_MINT: &[u8] = b"lp_mint";
+    pub const AMM_MIGRATION: &[u8] = b"amm_migration";
+    pub const VERIFICATION_KEY: &[u8] = b"vk";
+}
+
+/// Shielded pool for a token mint
+pub fn pool(token_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::POOL, token_mint.as_ref()], &PROGRAM_ID)
+}
+
+/// Token vault of a pool
+pub fn vault(token_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::VAULT, token_mint.as_ref()], &PROGRAM_ID)
+}
+
+/// Commitment counter of a pool
+pub fn commitment_counter(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::COMMITMENT_COUNTER, pool.as_ref()], &PROGRAM_ID)
+}
+
+/// Statistics of a pool
+pub fn pool_stats(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::POOL_STATS, pool.as_ref()], &PROGRAM_ID)
+}
+
+/// Root registry of a pool
+pub fn root_registry(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::ROOT_REGISTRY, pool.as_ref()], &PROGRAM_ID)
+}
+
+/// Protocol configuration
+pub fn protocol_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::PROTOCOL_CONFIG], &PROGRAM_ID)
+}
+
+/// Pending operation of a multi-phase flow
+pub fn pending_operation(operation_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::PENDING_OPERATION, operation_id], &PROGRAM_ID)
+}
+
+/// AMM pool for a token pair (mints in canonical order)
+pub fn amm_pool(token_a_mint: &Pubkey, token_b_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            seeds::AMM_POOL,
+            token_a_mint.as_ref(),
+            token_b_mint.as_ref(),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// LP mint of an AMM pool
+pub fn lp_mint(token_a_mint: &Pubkey, token_b_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[seeds::LP_MINT, token_a_mint.as_ref(), token_b_mint.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Successor AMM pool of a pair after `version` migrations (0 = `amm_pool`)
+pub fn amm_pool_version(token_a_mint: &Pubkey, token_b_mint: &Pubkey, version: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            seeds::AMM_POOL,
+            token_a_mint.as_ref(),
+            token_b_mint.as_ref(),
+            version_seed(&version),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// LP mint of a successor AMM pool (0 = `lp_mint`)
+pub fn lp_mint_version(token_a_mint: &Pubkey, token_b_mint: &Pubkey, version: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            seeds::LP_MINT,
+            token_a_mint.as_ref(),
+            token_b_mint.as_ref(),
+            version_seed(&version),
+        ],
+        &PROGRAM_ID,
+    )
+}
+
+/// Migration record of an AMM pool
+pub fn amm_pool_migration(old_amm_pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::AMM_MIGRATION, old_amm_pool.as_ref()], &PROGRAM_ID)
+}
+
+/// Pool version seed (empty for the original pool)
+fn version_seed(version: &u8) -> &[u8] {
+    if *version == 0 {
+        &[]
+    } else {
+        std::slice::from_ref(version)
+    }
+}
+
+/// Verification key for a circuit id
+pub fn verification_key(circuit_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::VERIFICATION_KEY, circuit_id], &PROGRAM_ID)
+}
+
+/// Statistics of a circuit
+pub fn circuit_stats(circuit_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::CIRCUIT_STATS, circuit_id], &PROGRAM_ID)
+}
+
+/// Snapshot tree of a ballot
+pub fn snapshot_tree(ballot_id: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::SNAPSHOT_TREE, ballot_id], &PROGRAM_ID)
+}
+
+/// Options page `page` (1-3) of a ballot with more than 16 options
+pub fn ballot_options_page(ballot_id: &[u8; 32], page: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::BALLOT_OPTIONS, ballot_id, &[page]], &PROGRAM_ID)
+}
+
+/// Borrow fee checkpoint history of a perps pool
+pub fn borrow_fee_history(perps_pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[seeds::BORROW_FEE_HISTORY, perps_pool.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Migration record of a perps pool (keyed by the old pool)
+pub fn perps_pool_migration(old_perps_pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[seeds::PERPS_MIGRATION, old_perps_pool.as_ref()],
+        &PROGRAM_ID,
+    )
+}
+
+/// Dust sweep bounty ledger (singleton)
+pub fn dust_sweep_ledger() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::DUST_SWEEP_LEDGER], &PROGRAM_ID)
+}
+
+/// Relayer allowlist of a permissioned pool
+pub fn relayer_allowlist(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::RELAYER_ALLOWLIST, pool.as_ref()], &PROGRAM_ID)
+}
+
+/// Bonded stake of a relayer
+pub fn relayer_stake(relayer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::RELAYER_STAKE, relayer.as_ref()], &PROGRAM_ID)
+}
+
+/// Operation credit policy (singleton)
+pub fn credit_config() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::CREDIT_CONFIG], &PROGRAM_ID)
+}
+
+/// Credit tree of a credit epoch
+pub fn credit_tree(epoch: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::CREDIT_TREE, &epoch.to_le_bytes()], &PROGRAM_ID)
+}
+
+/// Spent operation credit (Phase 0 passes it while credits are required)
+pub fn operation_credit(credit_nullifier: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::CREDIT_NULLIFIER, credit_nullifier], &PROGRAM_ID)
+}
+
+/// Tree registry (singleton)
+pub fn tree_registry() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[seeds::TREE_REGISTRY], &PROGRAM_ID)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeds_match_program() {
+        use cloakcraft::constants::seeds as program;
+
+        assert_eq!(seeds::POOL, program::POOL);
+        assert_eq!(seeds::VAULT, program::VAULT);
+        assert_eq!(seeds::POOL_STATS, program::POOL_STATS);
+        assert_eq!(seeds::ROOT_REGISTRY, program::ROOT_REGISTRY);
+        assert_eq!(seeds::CIRCUIT_STATS, program::CIRCUIT_STATS);
+        assert_eq!(seeds::SNAPSHOT_TREE, program::SNAPSHOT_TREE);
+        assert_eq!(seeds::BALLOT_OPTIONS, program::BALLOT_OPTIONS);
+        assert_eq!(seeds::BORROW_FEE_HISTORY, program::BORROW_FEE_HISTORY);
+        assert_eq!(seeds::PERPS_MIGRATION, program::PERPS_MIGRATION);
+        assert_eq!(seeds::PROTOCOL_CONFIG, program::PROTOCOL_CONFIG);
+        assert_eq!(seeds::DUST_SWEEP_LEDGER, program::DUST_SWEEP_LEDGER);
+        assert_eq!(seeds::RELAYER_ALLOWLIST, program::RELAYER_ALLOWLIST);
+        assert_eq!(seeds::RELAYER_STAKE, program::RELAYER_STAKE);
+        assert_eq!(seeds::CREDIT_CONFIG, program::CREDIT_CONFIG);
+        assert_eq!(seeds::CREDIT_TREE, program::CREDIT_TREE);
+        assert_eq!(seeds::CREDIT_NULLIFIER, program::CREDIT_NULLIFIER);
+        assert_eq!(seeds::TREE_REGISTRY, program::TREE_REGISTRY);
+        assert_eq!(seeds::AMM_POOL, program::AMM_POOL);
+        assert_eq!(seeds::LP_MINT, program::LP_MINT);
+        assert_eq!(seeds::AMM_MIGRATION, program::AMM_MIGRATION);
+        assert_eq!(seeds::VERIFICATION_KEY, program::VERIFICATION_KEY);
+        assert_eq!(
+            seeds::COMMITMENT_COUNTER,
+            cloakcraft::state::PoolCommitmentCounter::SEEDS_PREFIX
+        );
+        assert_eq!(
+            seeds::PENDING_OPERATION,
+            cloakcraft::state::PendingOperation::SEEDS_PREFIX
+        );
+        assert_eq!(PROGRAM_ID.to_bytes(), cloakcraft::ID.to_bytes());
+    }
+
+    #[test]
+    fn test_amm_pool_versions() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // The original pool keeps its unversioned addresses
+        assert_eq!(amm_pool_version(&a, &b, 0), amm_pool(&a, &b));
+        assert_eq!(lp_mint_version(&a, &b, 0), lp_mint(&a, &b));
+        assert_ne!(amm_pool_version(&a, &b, 1).0, amm_pool(&a, &b).0);
+    }
+}
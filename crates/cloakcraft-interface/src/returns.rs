@@ -0,0 +1,150 @@
+//! Return data of read-only instructions
+//!
+//! View-like instructions (quotes, bound checks, simulations) set a borsh
+//! payload with `set_return_data`. CPI callers read it with
+//! `get_return_data`; simulators get it base64-encoded in the simulation
+//! result's `returnData`. Unlike events, there is no discriminator: the
+//! layout follows from the instruction that was called.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_pubkey::Pubkey;
+
+use crate::PROGRAM_ID;
+
+/// Decode return data, checking it was set by the CloakCraft program
+pub fn decode_return_data<T: BorshDeserialize>(program_id: &Pubkey, data: &[u8]) -> Option<T> {
+    if *program_id != PROGRAM_ID {
+        return None;
+    }
+    T::try_from_slice(data).ok()
+}
+
+/// `check_perps_profit_bound`
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProfitBoundCheck {
+    pub at_bound: bool,
+    pub is_profit: bool,
+    pub pnl: u64,
+    pub max_profit: u64,
+}
+
+/// `quote_position_value`
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PositionValueQuote {
+    pub oracle_price: u64,
+    pub mark_value: u64,
+    pub pnl: u64,
+    pub is_profit: bool,
+    pub accrued_borrow_fee: u64,
+    pub liquidation_price: u64,
+    pub distance_to_liquidation_bps: u16,
+    pub is_liquidatable: bool,
+}
+
+/// `quote_claim_payout`
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClaimPayoutQuote {
+    pub gross_payout: u64,
+    pub net_payout: u64,
+    pub claims_allowed: bool,
+}
+
+/// `quote_swap`
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub output_amount: u64,
+    pub fee_amount: u64,
+    pub protocol_fee: u64,
+    pub price_impact_bps: u16,
+}
+
+/// `simulate_operation` (set before it fails with `SimulationComplete`)
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SimulationResult {
+    pub operation_type: u8,
+    pub proof_valid: bool,
+    pub expected_fee: u64,
+    pub expected_output: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AnchorSerialize;
+
+    fn encode<T: AnchorSerialize>(value: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        value.serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_decode_program_return_data() {
+        let check = cloakcraft::instructions::ProfitBoundCheck {
+            at_bound: true,
+            is_profit: true,
+            pnl: 1_200,
+            max_profit: 1_000,
+        };
+        assert_eq!(
+            decode_return_data::<ProfitBoundCheck>(&PROGRAM_ID, &encode(&check)),
+            Some(ProfitBoundCheck {
+                at_bound: true,
+                is_profit: true,
+                pnl: 1_200,
+                max_profit: 1_000,
+            })
+        );
+
+        let quote = cloakcraft::instructions::PositionValueQuote {
+            oracle_price: 50_000,
+            mark_value: 900,
+            pnl: 100,
+            is_profit: false,
+            accrued_borrow_fee: 3,
+            liquidation_price: 45_000,
+            distance_to_liquidation_bps: 1_000,
+            is_liquidatable: false,
+        };
+        let decoded: PositionValueQuote = decode_return_data(&PROGRAM_ID, &encode(&quote)).unwrap();
+        assert_eq!(
+            (decoded.mark_value, decoded.distance_to_liquidation_bps),
+            (900, 1_000)
+        );
+
+        let payout = cloakcraft::instructions::ClaimPayoutQuote {
+            gross_payout: 500,
+            net_payout: 495,
+            claims_allowed: true,
+        };
+        let decoded: ClaimPayoutQuote = decode_return_data(&PROGRAM_ID, &encode(&payout)).unwrap();
+        assert_eq!((decoded.net_payout, decoded.claims_allowed), (495, true));
+
+        let swap = cloakcraft::instructions::SwapQuote {
+            output_amount: 9_870,
+            fee_amount: 30,
+            protocol_fee: 5,
+            price_impact_bps: 99,
+        };
+        let decoded: SwapQuote = decode_return_data(&PROGRAM_ID, &encode(&swap)).unwrap();
+        assert_eq!(
+            (decoded.output_amount, decoded.price_impact_bps),
+            (9_870, 99)
+        );
+
+        let result = cloakcraft::instructions::SimulationResult {
+            operation_type: 2,
+            proof_valid: true,
+            expected_fee: 0,
+            expected_output: 77,
+        };
+        let decoded: SimulationResult = decode_return_data(&PROGRAM_ID, &encode(&result)).unwrap();
+        assert_eq!((decoded.proof_valid, decoded.expected_output), (true, 77));
+
+        // Set by another program
+        assert_eq!(
+            decode_return_data::<ClaimPayoutQuote>(&Pubkey::default(), &encode(&payout)),
+            None
+        );
+    }
+}
@@ -0,0 +1,4 @@
+-- View tags (note discovery hints)
+-- Scanners compare the served tag against the one derived from their own
+-- shared secret and only trial-decrypt on a match. NULL = no tag provided.
+ALTER TABLE commitments ADD COLUMN IF NOT EXISTS view_tag BYTEA;
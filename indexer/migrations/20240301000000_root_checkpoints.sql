@@ -0,0 +1,23 @@
+-- Root checkpoints (from RootCheckpointAnchored events)
+CREATE TABLE IF NOT EXISTS root_checkpoints (
+    id SERIAL PRIMARY KEY,
+    pool_id BYTEA NOT NULL,
+    checkpoint_index BIGINT NOT NULL,
+    state_tree BYTEA NOT NULL,
+    root BYTEA NOT NULL,
+    commitment_count BIGINT NOT NULL,
+    chain_hash BYTEA NOT NULL,
+    slot BIGINT NOT NULL,
+    timestamp BIGINT NOT NULL,
+    signature TEXT NOT NULL,
+    UNIQUE (pool_id, checkpoint_index)
+);
+
+CREATE INDEX idx_root_checkpoints_pool_count ON root_checkpoints(pool_id, commitment_count);
+
+-- Note archival: encrypted notes below a checkpoint may move to cold storage.
+-- The commitment row (commitment, leaf_index) is kept so merkle proofs still
+-- resolve; archive_uri locates the raw encrypted note.
+ALTER TABLE commitments ALTER COLUMN encrypted_note DROP NOT NULL;
+ALTER TABLE commitments ADD COLUMN IF NOT EXISTS archive_uri TEXT;
+ALTER TABLE commitments ADD COLUMN IF NOT EXISTS archived_at TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1,33 @@
+-- Multi-phase operations (PendingOperation lifecycle)
+CREATE TABLE IF NOT EXISTS operations (
+    id SERIAL PRIMARY KEY,
+    operation_id BYTEA NOT NULL UNIQUE,
+    operation_type SMALLINT NOT NULL,
+    relayer BYTEA NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_at_chain BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    slot BIGINT NOT NULL,
+    signature TEXT NOT NULL
+);
+
+CREATE INDEX idx_operations_status ON operations(status);
+
+-- Perps positions (from PositionMeta compressed accounts)
+CREATE TABLE IF NOT EXISTS perps_positions (
+    id SERIAL PRIMARY KEY,
+    position_id BYTEA NOT NULL UNIQUE,
+    pool_id BYTEA NOT NULL,
+    market_id BYTEA NOT NULL,
+    is_long BOOLEAN NOT NULL,
+    margin_amount BIGINT NOT NULL,
+    position_size BIGINT NOT NULL,
+    entry_price BIGINT NOT NULL,
+    liquidation_price BIGINT NOT NULL,
+    status SMALLINT NOT NULL,
+    created_at_chain BIGINT NOT NULL,
+    updated_at_chain BIGINT NOT NULL,
+    slot BIGINT NOT NULL
+);
+
+CREATE INDEX idx_perps_positions_market_status ON perps_positions(market_id, status);
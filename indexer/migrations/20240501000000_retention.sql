@@ -0,0 +1,5 @@
+-- Retention pruning scans by row age
+CREATE INDEX IF NOT EXISTS idx_commitments_created_at ON commitments(created_at)
+    WHERE encrypted_note IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_nullifiers_created_at ON nullifiers(created_at);
+CREATE INDEX IF NOT EXISTS idx_operations_created_at ON operations(created_at_chain);
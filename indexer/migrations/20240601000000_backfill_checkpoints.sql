@@ -0,0 +1,12 @@
+-- Backfill progress per slot range, so interrupted backfills resume
+-- cursor_signature is the oldest signature fully indexed in the range
+-- (the next page starts before it).
+CREATE TABLE IF NOT EXISTS backfill_checkpoints (
+    job_id TEXT NOT NULL,
+    range_start BIGINT NOT NULL,
+    range_end BIGINT NOT NULL,
+    cursor_signature TEXT,
+    done BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (job_id, range_start)
+);
@@ -0,0 +1,298 @@
+//! Historical backfill
+//!
+//! Splits a slot range into fixed-size chunks and indexes each chunk's
+//! program transactions with a pool of workers. All RPC calls share one rate
+//! limiter, and every chunk checkpoints after each signature page so an
+//! interrupted backfill resumes where it stopped.
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::{RpcBlockConfig, RpcTransactionConfig};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+use tokio::time::{Interval, MissedTickBehavior};
+
+use crate::database::{BackfillCheckpoint, Database};
+use crate::events::parse_log_events;
+use crate::{IndexerError, Result};
+
+/// Default slots per worker chunk
+pub const DEFAULT_CHUNK_SLOTS: u64 = 50_000;
+
+/// Signatures per getSignaturesForAddress page (RPC maximum)
+const SIGNATURE_PAGE_SIZE: usize = 1000;
+
+/// Backfill parameters
+#[derive(Debug, Clone)]
+pub struct BackfillConfig {
+    /// First slot to index (inclusive)
+    pub from_slot: u64,
+    /// Last slot to index (inclusive)
+    pub to_slot: u64,
+    /// Concurrent chunk workers
+    pub workers: usize,
+    /// RPC requests per second across all workers
+    pub requests_per_second: u32,
+    /// Slots per chunk
+    pub chunk_slots: u64,
+}
+
+impl BackfillConfig {
+    /// Checkpoint key; rerunning the same range resumes it
+    pub fn job_id(&self) -> String {
+        format!("{}-{}-{}", self.from_slot, self.to_slot, self.chunk_slots)
+    }
+}
+
+/// Backfill totals
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BackfillStats {
+    pub transactions: u64,
+    pub events: u64,
+}
+
+impl std::ops::AddAssign for BackfillStats {
+    fn add_assign(&mut self, other: Self) {
+        self.transactions += other.transactions;
+        self.events += other.events;
+    }
+}
+
+/// Inclusive slot range
+#[derive(Debug, Clone, Copy)]
+struct SlotRange {
+    start: u64,
+    end: u64,
+}
+
+/// Split `[from, to]` into chunks of at most `chunk` slots, newest first
+fn split_range(from: u64, to: u64, chunk: u64) -> VecDeque<SlotRange> {
+    let chunk = chunk.max(1);
+    let mut ranges = VecDeque::new();
+    let mut end = to;
+    loop {
+        let start = end.saturating_sub(chunk - 1).max(from);
+        ranges.push_back(SlotRange { start, end });
+        if start == from {
+            break;
+        }
+        end = start - 1;
+    }
+    ranges
+}
+
+/// Spaces requests evenly at a fixed rate
+struct RateLimiter {
+    interval: Mutex<Interval>,
+}
+
+impl RateLimiter {
+    fn new(requests_per_second: u32) -> Self {
+        let mut interval = tokio::time::interval(Duration::from_secs(1) / requests_per_second.max(1));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self { interval: Mutex::new(interval) }
+    }
+
+    async fn acquire(&self) {
+        self.interval.lock().await.tick().await;
+    }
+}
+
+fn rpc_error(e: impl std::fmt::Display) -> IndexerError {
+    IndexerError::Rpc(e.to_string())
+}
+
+struct Backfill {
+    rpc: RpcClient,
+    program_id: Pubkey,
+    db: Arc<Database>,
+    limiter: RateLimiter,
+    job_id: String,
+}
+
+impl Backfill {
+    /// A signature from the first produced block after `slot`, used as the
+    /// `before` cursor so paging starts at the end of a range. None pages
+    /// from the chain head.
+    async fn cursor_after(&self, slot: u64) -> Result<Option<Signature>> {
+        self.limiter.acquire().await;
+        let blocks = self
+            .rpc
+            .get_blocks_with_limit(slot + 1, 1)
+            .await
+            .map_err(rpc_error)?;
+        let Some(&block_slot) = blocks.first() else {
+            return Ok(None);
+        };
+
+        self.limiter.acquire().await;
+        let block = self
+            .rpc
+            .get_block_with_config(
+                block_slot,
+                RpcBlockConfig {
+                    transaction_details: Some(TransactionDetails::Signatures),
+                    rewards: Some(false),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(rpc_error)?;
+
+        block
+            .signatures
+            .and_then(|signatures| signatures.into_iter().next())
+            .map(|s| Signature::from_str(&s).map_err(rpc_error))
+            .transpose()
+    }
+
+    /// Index one transaction, returning the number of events persisted
+    async fn index_transaction(&self, signature: &str, slot: u64) -> Result<u64> {
+        let sig = Signature::from_str(signature).map_err(rpc_error)?;
+
+        self.limiter.acquire().await;
+        let tx = self
+            .rpc
+            .get_transaction_with_config(
+                &sig,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Json),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await
+            .map_err(rpc_error)?;
+
+        let logs: Option<Vec<String>> = tx.transaction.meta.and_then(|meta| meta.log_messages.into());
+        let events = parse_log_events(&logs.unwrap_or_default());
+        for event in &events {
+            self.db.index_event(event, slot, signature).await?;
+        }
+        Ok(events.len() as u64)
+    }
+
+    /// Index every successful program transaction in `range`
+    async fn backfill_range(&self, range: SlotRange) -> Result<BackfillStats> {
+        let mut stats = BackfillStats::default();
+        let mut checkpoint = self
+            .db
+            .get_backfill_checkpoint(&self.job_id, range.start)
+            .await?
+            .unwrap_or_default();
+        if checkpoint.done {
+            return Ok(stats);
+        }
+
+        let mut before = match &checkpoint.cursor_signature {
+            Some(sig) => Some(Signature::from_str(sig).map_err(rpc_error)?),
+            None => self.cursor_after(range.end).await?,
+        };
+
+        loop {
+            self.limiter.acquire().await;
+            let page = self
+                .rpc
+                .get_signatures_for_address_with_config(
+                    &self.program_id,
+                    GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until: None,
+                        limit: Some(SIGNATURE_PAGE_SIZE),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                    },
+                )
+                .await
+                .map_err(rpc_error)?;
+
+            let mut reached_start = false;
+            for info in &page {
+                if info.slot > range.end {
+                    continue;
+                }
+                if info.slot < range.start {
+                    reached_start = true;
+                    break;
+                }
+                if info.err.is_some() {
+                    continue;
+                }
+                stats.events += self.index_transaction(&info.signature, info.slot).await?;
+                stats.transactions += 1;
+            }
+
+            checkpoint.done = reached_start || page.len() < SIGNATURE_PAGE_SIZE;
+            checkpoint.cursor_signature = page.last().map(|info| info.signature.clone());
+            self.db
+                .save_backfill_checkpoint(&self.job_id, range.start, range.end, &checkpoint)
+                .await?;
+
+            match &checkpoint.cursor_signature {
+                Some(sig) if !checkpoint.done => before = Some(Signature::from_str(sig).map_err(rpc_error)?),
+                _ => break,
+            }
+        }
+
+        tracing::info!(
+            range_start = range.start,
+            range_end = range.end,
+            transactions = stats.transactions,
+            events = stats.events,
+            "backfilled slot range"
+        );
+        Ok(stats)
+    }
+}
+
+/// Backfill `config.from_slot..=config.to_slot` for `program_id`
+pub async fn run_backfill(
+    rpc_url: String,
+    program_id: Pubkey,
+    db: Arc<Database>,
+    config: BackfillConfig,
+) -> Result<BackfillStats> {
+    if config.from_slot > config.to_slot {
+        return Err(IndexerError::Config("backfill from_slot is after to_slot".to_string()));
+    }
+
+    let backfill = Arc::new(Backfill {
+        rpc: RpcClient::new(rpc_url),
+        program_id,
+        db,
+        limiter: RateLimiter::new(config.requests_per_second),
+        job_id: config.job_id(),
+    });
+    let ranges = Arc::new(Mutex::new(split_range(config.from_slot, config.to_slot, config.chunk_slots)));
+
+    let mut workers = JoinSet::new();
+    for _ in 0..config.workers.max(1) {
+        let backfill = backfill.clone();
+        let ranges = ranges.clone();
+        workers.spawn(async move {
+            let mut stats = BackfillStats::default();
+            loop {
+                let Some(range) = ranges.lock().await.pop_front() else {
+                    return Ok(stats);
+                };
+                stats += backfill.backfill_range(range).await?;
+            }
+        });
+    }
+
+    let mut total = BackfillStats::default();
+    while let Some(result) = workers.join_next().await {
+        let stats: Result<BackfillStats> = result.map_err(|e| IndexerError::Rpc(e.to_string()))?;
+        total += stats?;
+    }
+    Ok(total)
+}
@@ -16,11 +16,89 @@ pub struct IndexerConfig {
     /// HTTP server port
     pub port: u16,
 
+    /// gRPC server port
+    pub grpc_port: u16,
+
     /// CloakCraft program ID
     pub program_id: String,
 
     /// Starting slot for indexing
     pub start_slot: Option<u64>,
+
+    /// Data retention and pruning
+    pub retention: RetentionConfig,
+
+    /// Slots behind the RPC node before `/health` reports unhealthy
+    pub max_slot_lag: u64,
+
+    /// Emit logs as JSON
+    pub log_json: bool,
+
+    /// Bearer token for webhook management; webhooks are disabled if unset
+    pub webhook_admin_token: Option<String>,
+}
+
+/// Retention policy per table (None = keep forever)
+///
+/// The indexer can't link nullifiers to the commitments they spend, so note
+/// ciphertexts are pruned by age. Commitment rows are always kept so merkle
+/// proofs still resolve; only the encrypted note is dropped.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RetentionConfig {
+    /// Days to keep encrypted notes
+    pub note_ciphertext_days: Option<u32>,
+
+    /// Days to keep nullifiers
+    pub nullifier_days: Option<u32>,
+
+    /// Days to keep completed, expired or closed operations
+    pub operation_days: Option<u32>,
+
+    /// Seconds between pruning runs
+    pub prune_interval_secs: u64,
+
+    /// Run VACUUM ANALYZE on pruned tables after each run
+    pub vacuum: bool,
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            note_ciphertext_days: Some(90),
+            nullifier_days: None,
+            operation_days: Some(30),
+            prune_interval_secs: 3600,
+            vacuum: true,
+        }
+    }
+}
+
+impl RetentionConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            note_ciphertext_days: retention_days("RETENTION_NOTE_CIPHERTEXT_DAYS", defaults.note_ciphertext_days),
+            nullifier_days: retention_days("RETENTION_NULLIFIER_DAYS", defaults.nullifier_days),
+            operation_days: retention_days("RETENTION_OPERATION_DAYS", defaults.operation_days),
+            prune_interval_secs: std::env::var("PRUNE_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.prune_interval_secs),
+            vacuum: std::env::var("PRUNE_VACUUM")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.vacuum),
+        }
+    }
+}
+
+/// Read a retention period in days; "forever" keeps data indefinitely
+fn retention_days(var: &str, default: Option<u32>) -> Option<u32> {
+    match std::env::var(var) {
+        Ok(v) if v.eq_ignore_ascii_case("forever") => None,
+        Ok(v) => v.parse().ok().or(default),
+        Err(_) => default,
+    }
 }
 
 impl Default for IndexerConfig {
@@ -30,8 +108,13 @@ impl Default for IndexerConfig {
             ws_url: "ws://localhost:8900".to_string(),
             database_url: "postgres://localhost/cloakcraft".to_string(),
             port: 3000,
+            grpc_port: 50051,
             program_id: "CLoAKcRaFt1111111111111111111111111111111111".to_string(),
             start_slot: None,
+            retention: RetentionConfig::default(),
+            max_slot_lag: 150,
+            log_json: false,
+            webhook_admin_token: None,
         }
     }
 }
@@ -49,11 +132,26 @@ impl IndexerConfig {
                 .ok()
                 .and_then(|p| p.parse().ok())
                 .unwrap_or(3000),
+            grpc_port: std::env::var("GRPC_PORT")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(50051),
             program_id: std::env::var("PROGRAM_ID")
                 .unwrap_or_else(|_| "CLoAKcRaFt1111111111111111111111111111111111".to_string()),
             start_slot: std::env::var("START_SLOT")
                 .ok()
                 .and_then(|s| s.parse().ok()),
+            retention: RetentionConfig::from_env(),
+            max_slot_lag: std::env::var("MAX_SLOT_LAG")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(150),
+            log_json: std::env::var("LOG_FORMAT")
+                .map(|f| f.eq_ignore_ascii_case("json"))
+                .unwrap_or(false),
+            webhook_admin_token: std::env::var("WEBHOOK_ADMIN_TOKEN")
+                .ok()
+                .filter(|t| !t.is_empty()),
         })
     }
 }
@@ -1,10 +1,20 @@
 //! Database operations for indexer storage
 
 use sqlx::{PgPool, postgres::PgPoolOptions};
+use std::time::Duration;
 
+use crate::config::RetentionConfig;
+use crate::events::{CloakCraftEvent, RootCheckpointAnchoredEvent};
 use crate::Result;
 
+/// Seconds per retention day
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Tables touched by pruning, vacuumed afterwards
+const PRUNED_TABLES: &[&str] = &["commitments", "nullifiers", "operations"];
+
 /// Database connection pool wrapper
+#[derive(Clone)]
 pub struct Database {
     pool: PgPool,
 }
@@ -20,6 +30,11 @@ impl Database {
         Ok(Self { pool })
     }
 
+    /// Connections currently checked out of the pool
+    pub fn busy_connections(&self) -> u32 {
+        self.pool.size().saturating_sub(self.pool.num_idle() as u32)
+    }
+
     /// Run migrations
     pub async fn migrate(&self) -> Result<()> {
         sqlx::migrate!("./migrations")
@@ -36,19 +51,21 @@ impl Database {
         leaf_index: u32,
         pool_id: &[u8; 32],
         encrypted_note: &[u8],
+        view_tag: Option<&[u8; 8]>,
         slot: u64,
         signature: &str,
     ) -> Result<()> {
         sqlx::query!(
             r#"
-            INSERT INTO commitments (commitment, leaf_index, pool_id, encrypted_note, slot, signature)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO commitments (commitment, leaf_index, pool_id, encrypted_note, view_tag, slot, signature)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             ON CONFLICT (commitment) DO NOTHING
             "#,
             commitment.as_slice(),
             leaf_index as i32,
             pool_id.as_slice(),
             encrypted_note,
+            view_tag.map(|t| t.as_slice()),
             slot as i64,
             signature,
         )
@@ -81,6 +98,66 @@ impl Database {
         Ok(())
     }
 
+    /// Insert an anchored root checkpoint
+    pub async fn insert_root_checkpoint(
+        &self,
+        event: &RootCheckpointAnchoredEvent,
+        signature: &str,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO root_checkpoints
+                (pool_id, checkpoint_index, state_tree, root, commitment_count, chain_hash, slot, timestamp, signature)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (pool_id, checkpoint_index) DO NOTHING
+            "#,
+            event.pool.as_slice(),
+            event.checkpoint_index as i64,
+            event.state_tree.as_slice(),
+            event.root.as_slice(),
+            event.commitment_count as i64,
+            event.chain_hash.as_slice(),
+            event.slot as i64,
+            event.timestamp,
+            signature,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Archive encrypted notes below a checkpoint's commitment count
+    ///
+    /// The caller must have written the notes to `archive_uri` first. Only
+    /// notes covered by an anchored checkpoint are archived; commitment rows
+    /// are kept so merkle proofs against the anchored root still resolve.
+    /// Returns the number of notes archived.
+    pub async fn archive_commitments(
+        &self,
+        pool_id: &[u8; 32],
+        checkpoint_index: u64,
+        archive_uri: &str,
+    ) -> Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE commitments
+            SET encrypted_note = NULL, archive_uri = $3, archived_at = NOW()
+            WHERE pool_id = $1
+              AND encrypted_note IS NOT NULL
+              AND leaf_index < (
+                  SELECT commitment_count FROM root_checkpoints
+                  WHERE pool_id = $1 AND checkpoint_index = $2
+              )
+            "#,
+            pool_id.as_slice(),
+            checkpoint_index as i64,
+            archive_uri,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
     /// Get commitments for a pool since a specific leaf index
     pub async fn get_commitments(
         &self,
@@ -91,7 +168,7 @@ impl Database {
         let records = sqlx::query_as!(
             CommitmentRecord,
             r#"
-            SELECT commitment, leaf_index, encrypted_note, slot
+            SELECT commitment, leaf_index, encrypted_note, view_tag, archive_uri, slot
             FROM commitments
             WHERE pool_id = $1 AND leaf_index >= $2
             ORDER BY leaf_index ASC
@@ -106,6 +183,18 @@ impl Database {
         Ok(records)
     }
 
+    /// Commitment index of a note in a pool (the index its nullifier binds)
+    pub async fn get_commitment_index(&self, pool_id: &[u8; 32], commitment: &[u8; 32]) -> Result<Option<u32>> {
+        let record = sqlx::query!(
+            "SELECT leaf_index FROM commitments WHERE pool_id = $1 AND commitment = $2",
+            pool_id.as_slice(),
+            commitment.as_slice(),
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(record.map(|r| r.leaf_index as u32))
+    }
+
     /// Check if a nullifier has been spent
     pub async fn is_nullifier_spent(&self, nullifier: &[u8; 32]) -> Result<bool> {
         let result = sqlx::query!(
@@ -117,6 +206,423 @@ impl Database {
         Ok(result.is_some())
     }
 
+    /// Persist an event emitted at `slot`
+    ///
+    /// Inserts are idempotent, so replaying a transaction (backfill overlap,
+    /// reconnects) is safe. Events without a table yet are skipped.
+    pub async fn index_event(&self, event: &CloakCraftEvent, slot: u64, signature: &str) -> Result<()> {
+        match event {
+            CloakCraftEvent::NoteCreated(e) => {
+                self.insert_commitment(
+                    &e.commitment,
+                    e.leaf_index,
+                    &e.pool,
+                    &e.encrypted_note,
+                    e.view_tag.as_ref(),
+                    slot,
+                    signature,
+                )
+                .await
+            }
+            CloakCraftEvent::NoteSpent(e) => self.insert_nullifier(&e.nullifier, &e.pool, slot, signature).await,
+            CloakCraftEvent::RootCheckpointAnchored(e) => self.insert_root_checkpoint(e, signature).await,
+            _ => {
+                tracing::debug!(event = event.kind(), signature, "event not persisted");
+                Ok(())
+            }
+        }
+    }
+
+    /// Insert or update a multi-phase operation
+    pub async fn upsert_operation(
+        &self,
+        operation_id: &[u8; 32],
+        operation_type: u8,
+        relayer: &[u8; 32],
+        status: &str,
+        created_at: i64,
+        expires_at: i64,
+        slot: u64,
+        signature: &str,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO operations
+                (operation_id, operation_type, relayer, status, created_at_chain, expires_at, slot, signature)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (operation_id) DO UPDATE
+            SET status = EXCLUDED.status, slot = EXCLUDED.slot
+            "#,
+            operation_id.as_slice(),
+            operation_type as i16,
+            relayer.as_slice(),
+            status,
+            created_at,
+            expires_at,
+            slot as i64,
+            signature,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Get a multi-phase operation by id
+    pub async fn get_operation(&self, operation_id: &[u8; 32]) -> Result<Option<OperationRecord>> {
+        let record = sqlx::query_as!(
+            OperationRecord,
+            r#"
+            SELECT operation_id, operation_type, relayer, status,
+                   created_at_chain AS created_at, expires_at, slot
+            FROM operations
+            WHERE operation_id = $1
+            "#,
+            operation_id.as_slice(),
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(record)
+    }
+
+    /// Get a ballot (voting aggregation) by id
+    pub async fn get_ballot(&self, ballot_id: &[u8; 32]) -> Result<Option<BallotRecord>> {
+        let record = sqlx::query_as!(
+            BallotRecord,
+            r#"
+            SELECT aggregation_id AS ballot_id, threshold_pubkey, num_options,
+                   deadline, status, vote_count, slot
+            FROM aggregations
+            WHERE aggregation_id = $1
+            "#,
+            ballot_id.as_slice(),
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(record)
+    }
+
+    /// Insert or update a perps position
+    pub async fn upsert_perps_position(&self, position: &PerpsPositionRecord) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO perps_positions
+                (position_id, pool_id, market_id, is_long, margin_amount, position_size,
+                 entry_price, liquidation_price, status, created_at_chain, updated_at_chain, slot)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (position_id) DO UPDATE
+            SET margin_amount = EXCLUDED.margin_amount,
+                position_size = EXCLUDED.position_size,
+                liquidation_price = EXCLUDED.liquidation_price,
+                status = EXCLUDED.status,
+                updated_at_chain = EXCLUDED.updated_at_chain,
+                slot = EXCLUDED.slot
+            "#,
+            position.position_id,
+            position.pool_id,
+            position.market_id,
+            position.is_long,
+            position.margin_amount,
+            position.position_size,
+            position.entry_price,
+            position.liquidation_price,
+            position.status,
+            position.created_at,
+            position.updated_at,
+            position.slot,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Get perps positions, optionally filtered by market and status
+    pub async fn get_perps_positions(
+        &self,
+        market_id: Option<&[u8; 32]>,
+        status: Option<u8>,
+        limit: u32,
+    ) -> Result<Vec<PerpsPositionRecord>> {
+        let records = sqlx::query_as!(
+            PerpsPositionRecord,
+            r#"
+            SELECT position_id, pool_id, market_id, is_long, margin_amount, position_size,
+                   entry_price, liquidation_price, status,
+                   created_at_chain AS created_at, updated_at_chain AS updated_at, slot
+            FROM perps_positions
+            WHERE ($1::BYTEA IS NULL OR market_id = $1)
+              AND ($2::SMALLINT IS NULL OR status = $2)
+            ORDER BY slot DESC
+            LIMIT $3
+            "#,
+            market_id.map(|m| m.as_slice()),
+            status.map(|s| s as i16),
+            limit as i32,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(records)
+    }
+
+    /// Drop encrypted notes older than `days`
+    ///
+    /// Commitment rows are kept so merkle proofs still resolve. Notes already
+    /// archived to cold storage are skipped. Returns the number of notes pruned.
+    pub async fn prune_note_ciphertexts(&self, days: u32) -> Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE commitments
+            SET encrypted_note = NULL
+            WHERE encrypted_note IS NOT NULL
+              AND created_at < NOW() - make_interval(days => $1)
+            "#,
+            days as i32,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Delete nullifiers older than `days`
+    ///
+    /// Clients then can't check spent status for those notes through the
+    /// indexer; on-chain nullifier accounts are unaffected.
+    pub async fn prune_nullifiers(&self, days: u32) -> Result<u64> {
+        let result = sqlx::query!(
+            "DELETE FROM nullifiers WHERE created_at < NOW() - make_interval(days => $1)",
+            days as i32,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Delete finished operations created more than `days` ago
+    pub async fn prune_operations(&self, days: u32) -> Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM operations
+            WHERE status <> 'pending'
+              AND created_at_chain < EXTRACT(EPOCH FROM NOW())::BIGINT - $1
+            "#,
+            days as i64 * SECONDS_PER_DAY,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Reclaim space and refresh planner statistics for pruned tables
+    pub async fn vacuum(&self) -> Result<()> {
+        for table in PRUNED_TABLES {
+            // VACUUM can't take bind parameters or run inside a transaction
+            sqlx::query(&format!("VACUUM ANALYZE {table}"))
+                .execute(&self.pool)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Apply a retention policy once
+    pub async fn prune(&self, retention: &RetentionConfig) -> Result<PruneStats> {
+        let mut stats = PruneStats::default();
+        if let Some(days) = retention.note_ciphertext_days {
+            stats.note_ciphertexts = self.prune_note_ciphertexts(days).await?;
+        }
+        if let Some(days) = retention.nullifier_days {
+            stats.nullifiers = self.prune_nullifiers(days).await?;
+        }
+        if let Some(days) = retention.operation_days {
+            stats.operations = self.prune_operations(days).await?;
+        }
+        if retention.vacuum && stats.total() > 0 {
+            self.vacuum().await?;
+        }
+        Ok(stats)
+    }
+
+    /// Run `prune` every `retention.prune_interval_secs` in the background
+    pub fn spawn_pruning(&self, retention: RetentionConfig) -> tokio::task::JoinHandle<()> {
+        let db = Self { pool: self.pool.clone() };
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(retention.prune_interval_secs));
+            loop {
+                interval.tick().await;
+                match db.prune(&retention).await {
+                    Ok(stats) => tracing::info!(
+                        note_ciphertexts = stats.note_ciphertexts,
+                        nullifiers = stats.nullifiers,
+                        operations = stats.operations,
+                        "pruning complete"
+                    ),
+                    Err(e) => tracing::warn!("pruning failed: {e}"),
+                }
+            }
+        })
+    }
+
+    /// Load a backfill range checkpoint
+    pub async fn get_backfill_checkpoint(
+        &self,
+        job_id: &str,
+        range_start: u64,
+    ) -> Result<Option<BackfillCheckpoint>> {
+        let record = sqlx::query_as!(
+            BackfillCheckpoint,
+            r#"
+            SELECT cursor_signature, done
+            FROM backfill_checkpoints
+            WHERE job_id = $1 AND range_start = $2
+            "#,
+            job_id,
+            range_start as i64,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(record)
+    }
+
+    /// Save a backfill range checkpoint
+    pub async fn save_backfill_checkpoint(
+        &self,
+        job_id: &str,
+        range_start: u64,
+        range_end: u64,
+        checkpoint: &BackfillCheckpoint,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO backfill_checkpoints (job_id, range_start, range_end, cursor_signature, done)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (job_id, range_start) DO UPDATE
+            SET cursor_signature = EXCLUDED.cursor_signature,
+                done = EXCLUDED.done,
+                updated_at = NOW()
+            "#,
+            job_id,
+            range_start as i64,
+            range_end as i64,
+            checkpoint.cursor_signature.as_deref(),
+            checkpoint.done,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Register a webhook, returning its id
+    pub async fn insert_webhook(&self, webhook: &NewWebhook) -> Result<i64> {
+        let record = sqlx::query!(
+            r#"
+            INSERT INTO webhooks (url, secret, pool_id, scan_key, event_types)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+            webhook.url,
+            webhook.secret.as_slice(),
+            webhook.pool_id.as_ref().map(|p| p.as_slice()),
+            webhook.scan_key.as_ref().map(|k| k.as_slice()),
+            &webhook.event_types,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(record.id)
+    }
+
+    /// Deactivate a webhook; pending deliveries are dropped
+    pub async fn delete_webhook(&self, id: i64) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE webhooks SET active = FALSE WHERE id = $1 AND active",
+            id,
+        )
+        .execute(&self.pool)
+        .await?;
+        sqlx::query!(
+            "UPDATE webhook_deliveries SET dead = TRUE WHERE webhook_id = $1 AND delivered_at IS NULL",
+            id,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Active webhooks
+    pub async fn get_active_webhooks(&self) -> Result<Vec<WebhookRecord>> {
+        let records = sqlx::query_as!(
+            WebhookRecord,
+            r#"
+            SELECT id, url, secret, pool_id, scan_key, event_types
+            FROM webhooks
+            WHERE active
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(records)
+    }
+
+    /// Queue a payload for delivery
+    pub async fn enqueue_webhook_delivery(&self, webhook_id: i64, payload: &serde_json::Value) -> Result<()> {
+        sqlx::query!(
+            "INSERT INTO webhook_deliveries (webhook_id, payload) VALUES ($1, $2)",
+            webhook_id,
+            payload,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Deliveries due for an attempt, oldest first
+    pub async fn get_due_webhook_deliveries(&self, limit: u32) -> Result<Vec<WebhookDeliveryRecord>> {
+        let records = sqlx::query_as!(
+            WebhookDeliveryRecord,
+            r#"
+            SELECT d.id, d.webhook_id, w.url, w.secret, d.payload, d.attempts
+            FROM webhook_deliveries d
+            JOIN webhooks w ON w.id = d.webhook_id
+            WHERE d.delivered_at IS NULL AND NOT d.dead AND d.next_attempt_at <= NOW()
+            ORDER BY d.next_attempt_at ASC
+            LIMIT $1
+            "#,
+            limit as i64,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(records)
+    }
+
+    /// Mark a delivery as delivered
+    pub async fn mark_webhook_delivered(&self, id: i64) -> Result<()> {
+        sqlx::query!(
+            "UPDATE webhook_deliveries SET delivered_at = NOW(), attempts = attempts + 1 WHERE id = $1",
+            id,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record a failed attempt; `retry_in_secs` None gives up on the delivery
+    pub async fn mark_webhook_failed(&self, id: i64, error: &str, retry_in_secs: Option<u64>) -> Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries
+            SET attempts = attempts + 1,
+                last_error = $2,
+                dead = $3,
+                next_attempt_at = NOW() + make_interval(secs => $4)
+            WHERE id = $1
+            "#,
+            id,
+            error,
+            retry_in_secs.is_none(),
+            retry_in_secs.unwrap_or(0) as f64,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     /// Get the latest indexed slot
     pub async fn get_latest_slot(&self) -> Result<u64> {
         let result = sqlx::query!(
@@ -132,6 +638,101 @@ impl Database {
 pub struct CommitmentRecord {
     pub commitment: Vec<u8>,
     pub leaf_index: i32,
-    pub encrypted_note: Vec<u8>,
+    /// None once archived (see `archive_uri`)
+    pub encrypted_note: Option<Vec<u8>>,
+    pub view_tag: Option<Vec<u8>>,
+    pub archive_uri: Option<String>,
+    pub slot: i64,
+}
+
+/// Backfill progress for one slot range
+#[derive(Debug, Default, Clone)]
+pub struct BackfillCheckpoint {
+    /// Oldest signature fully indexed; the next page starts before it
+    pub cursor_signature: Option<String>,
+    pub done: bool,
+}
+
+/// Webhook registration
+pub struct NewWebhook {
+    pub url: String,
+    /// HMAC-SHA256 key for payload signatures
+    pub secret: [u8; 32],
+    pub pool_id: Option<[u8; 32]>,
+    pub scan_key: Option<[u8; 32]>,
+    /// Event types (`CloakCraftEvent::kind`), empty = all
+    pub event_types: Vec<String>,
+}
+
+/// Webhook record from database
+pub struct WebhookRecord {
+    pub id: i64,
+    pub url: String,
+    pub secret: Vec<u8>,
+    pub pool_id: Option<Vec<u8>>,
+    pub scan_key: Option<Vec<u8>>,
+    pub event_types: Vec<String>,
+}
+
+/// Pending webhook delivery from database
+pub struct WebhookDeliveryRecord {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub url: String,
+    pub secret: Vec<u8>,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+}
+
+/// Rows pruned by one retention run
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PruneStats {
+    pub note_ciphertexts: u64,
+    pub nullifiers: u64,
+    pub operations: u64,
+}
+
+impl PruneStats {
+    pub fn total(&self) -> u64 {
+        self.note_ciphertexts + self.nullifiers + self.operations
+    }
+}
+
+/// Multi-phase operation record from database
+pub struct OperationRecord {
+    pub operation_id: Vec<u8>,
+    pub operation_type: i16,
+    pub relayer: Vec<u8>,
+    /// pending | completed | expired | closed
+    pub status: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub slot: i64,
+}
+
+/// Ballot record from database (`aggregations` table)
+pub struct BallotRecord {
+    pub ballot_id: Vec<u8>,
+    pub threshold_pubkey: Vec<u8>,
+    pub num_options: i32,
+    pub deadline: i64,
+    pub status: String,
+    pub vote_count: i32,
+    pub slot: i64,
+}
+
+/// Perps position record from database
+pub struct PerpsPositionRecord {
+    pub position_id: Vec<u8>,
+    pub pool_id: Vec<u8>,
+    pub market_id: Vec<u8>,
+    pub is_long: bool,
+    pub margin_amount: i64,
+    pub position_size: i64,
+    pub entry_price: i64,
+    pub liquidation_price: i64,
+    pub status: i16,
+    pub created_at: i64,
+    pub updated_at: i64,
     pub slot: i64,
 }
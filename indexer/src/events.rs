@@ -1,7 +1,11 @@
 //! Event parsing and processing
 
+use base64::{engine::general_purpose::STANDARD, Engine};
 use borsh::BorshDeserialize;
 
+/// Log prefix Anchor uses for emitted events
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
 /// CloakCraft event discriminators
 pub mod discriminators {
     pub const NOTE_CREATED: [u8; 8] = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
@@ -11,6 +15,8 @@ pub mod discriminators {
     pub const ORDER_CANCELLED: [u8; 8] = [0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48];
     pub const SWAP_EXECUTED: [u8; 8] = [0x51, 0x52, 0x53, 0x54, 0x55, 0x56, 0x57, 0x58];
     pub const VOTE_SUBMITTED: [u8; 8] = [0x61, 0x62, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68];
+    /// sha256("event:RootCheckpointAnchored")[..8]
+    pub const ROOT_CHECKPOINT_ANCHORED: [u8; 8] = [23, 51, 95, 2, 116, 201, 242, 190];
 }
 
 /// Parsed CloakCraft event
@@ -23,6 +29,35 @@ pub enum CloakCraftEvent {
     OrderCancelled(OrderCancelledEvent),
     SwapExecuted(SwapExecutedEvent),
     VoteSubmitted(VoteSubmittedEvent),
+    RootCheckpointAnchored(RootCheckpointAnchoredEvent),
+}
+
+/// All event type labels (see `CloakCraftEvent::kind`)
+pub const EVENT_KINDS: &[&str] = &[
+    "note_created",
+    "note_spent",
+    "order_created",
+    "order_filled",
+    "order_cancelled",
+    "swap_executed",
+    "vote_submitted",
+    "root_checkpoint_anchored",
+];
+
+impl CloakCraftEvent {
+    /// Event type label (metrics, logs)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::NoteCreated(_) => "note_created",
+            Self::NoteSpent(_) => "note_spent",
+            Self::OrderCreated(_) => "order_created",
+            Self::OrderFilled(_) => "order_filled",
+            Self::OrderCancelled(_) => "order_cancelled",
+            Self::SwapExecuted(_) => "swap_executed",
+            Self::VoteSubmitted(_) => "vote_submitted",
+            Self::RootCheckpointAnchored(_) => "root_checkpoint_anchored",
+        }
+    }
 }
 
 #[derive(Debug, Clone, BorshDeserialize)]
@@ -31,6 +66,7 @@ pub struct NoteCreatedEvent {
     pub commitment: [u8; 32],
     pub leaf_index: u32,
     pub encrypted_note: Vec<u8>,
+    pub view_tag: Option<[u8; 8]>,
     pub timestamp: i64,
 }
 
@@ -80,6 +116,18 @@ pub struct VoteSubmittedEvent {
     pub timestamp: i64,
 }
 
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct RootCheckpointAnchoredEvent {
+    pub pool: [u8; 32],
+    pub state_tree: [u8; 32],
+    pub root: [u8; 32],
+    pub commitment_count: u64,
+    pub checkpoint_index: u64,
+    pub chain_hash: [u8; 32],
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
 /// Parse event from transaction logs
 pub fn parse_event(data: &[u8]) -> Option<CloakCraftEvent> {
     if data.len() < 8 {
@@ -125,6 +173,20 @@ pub fn parse_event(data: &[u8]) -> Option<CloakCraftEvent> {
                 .ok()
                 .map(CloakCraftEvent::VoteSubmitted)
         }
+        discriminators::ROOT_CHECKPOINT_ANCHORED => {
+            RootCheckpointAnchoredEvent::try_from_slice(event_data)
+                .ok()
+                .map(CloakCraftEvent::RootCheckpointAnchored)
+        }
         _ => None,
     }
 }
+
+/// Parse all CloakCraft events from a transaction's log messages
+pub fn parse_log_events(logs: &[String]) -> Vec<CloakCraftEvent> {
+    logs.iter()
+        .filter_map(|log| log.strip_prefix(PROGRAM_DATA_PREFIX))
+        .filter_map(|data| STANDARD.decode(data).ok())
+        .filter_map(|data| parse_event(&data))
+        .collect()
+}
@@ -0,0 +1,216 @@
+//! gRPC API server for indexer queries
+//!
+//! Same queries as the REST API in `rpc`, typed by
+//! `proto/cloakcraft/indexer/v1/indexer.proto` so integrators can generate
+//! clients. Ids are raw bytes here rather than hex.
+
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::database::{BallotRecord, OperationRecord, PerpsPositionRecord};
+use crate::rpc::{clamp_limit, parse_id, ApiState};
+
+pub mod proto {
+    tonic::include_proto!("cloakcraft.indexer.v1");
+}
+
+use proto::indexer_server::{Indexer, IndexerServer};
+
+/// Create the gRPC service
+pub fn create_service(state: Arc<ApiState>) -> IndexerServer<IndexerService> {
+    IndexerServer::new(IndexerService { state })
+}
+
+pub struct IndexerService {
+    state: Arc<ApiState>,
+}
+
+fn parse_request_id(bytes: &[u8], field: &str) -> Result<[u8; 32], Status> {
+    parse_id(bytes).ok_or_else(|| Status::invalid_argument(format!("{field} must be 32 bytes")))
+}
+
+fn internal(e: crate::IndexerError) -> Status {
+    Status::internal(e.to_string())
+}
+
+impl From<OperationRecord> for proto::Operation {
+    fn from(r: OperationRecord) -> Self {
+        Self {
+            operation_id: r.operation_id,
+            operation_type: r.operation_type as u32,
+            relayer: r.relayer,
+            status: r.status,
+            created_at: r.created_at,
+            expires_at: r.expires_at,
+            slot: r.slot as u64,
+        }
+    }
+}
+
+impl From<BallotRecord> for proto::Ballot {
+    fn from(r: BallotRecord) -> Self {
+        Self {
+            ballot_id: r.ballot_id,
+            threshold_pubkey: r.threshold_pubkey,
+            num_options: r.num_options as u32,
+            deadline: r.deadline,
+            status: r.status,
+            vote_count: r.vote_count as u32,
+            slot: r.slot as u64,
+        }
+    }
+}
+
+impl From<PerpsPositionRecord> for proto::PerpsPosition {
+    fn from(r: PerpsPositionRecord) -> Self {
+        Self {
+            position_id: r.position_id,
+            pool_id: r.pool_id,
+            market_id: r.market_id,
+            is_long: r.is_long,
+            margin_amount: r.margin_amount as u64,
+            position_size: r.position_size as u64,
+            entry_price: r.entry_price as u64,
+            liquidation_price: r.liquidation_price as u64,
+            status: r.status as u32,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+            slot: r.slot as u64,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Indexer for IndexerService {
+    async fn get_notes(
+        &self,
+        request: Request<proto::GetNotesRequest>,
+    ) -> Result<Response<proto::GetNotesResponse>, Status> {
+        let request = request.into_inner();
+        let pool_id = parse_request_id(&request.pool_id, "pool_id")?;
+
+        let records = self
+            .state
+            .db
+            .get_commitments(&pool_id, request.since_index.unwrap_or(0), clamp_limit(request.limit))
+            .await
+            .map_err(internal)?;
+
+        let notes = records
+            .into_iter()
+            .map(|r| proto::Note {
+                commitment: r.commitment,
+                leaf_index: r.leaf_index as u32,
+                encrypted_note: r.encrypted_note,
+                archive_uri: r.archive_uri,
+                view_tag: r.view_tag,
+                slot: r.slot as u64,
+            })
+            .collect();
+
+        Ok(Response::new(proto::GetNotesResponse { notes }))
+    }
+
+    async fn get_commitment_index(
+        &self,
+        request: Request<proto::GetCommitmentIndexRequest>,
+    ) -> Result<Response<proto::CommitmentIndex>, Status> {
+        let request = request.into_inner();
+        let pool_id = parse_request_id(&request.pool_id, "pool_id")?;
+        let commitment = parse_request_id(&request.commitment, "commitment")?;
+
+        let leaf_index = self
+            .state
+            .db
+            .get_commitment_index(&pool_id, &commitment)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| Status::not_found("commitment not indexed"))?;
+
+        Ok(Response::new(proto::CommitmentIndex {
+            commitment: commitment.to_vec(),
+            leaf_index,
+        }))
+    }
+
+    async fn get_nullifier(
+        &self,
+        request: Request<proto::GetNullifierRequest>,
+    ) -> Result<Response<proto::NullifierStatus>, Status> {
+        let nullifier = parse_request_id(&request.into_inner().nullifier, "nullifier")?;
+
+        let spent = self.state.db.is_nullifier_spent(&nullifier).await.map_err(internal)?;
+
+        Ok(Response::new(proto::NullifierStatus { spent }))
+    }
+
+    async fn get_operation(
+        &self,
+        request: Request<proto::GetOperationRequest>,
+    ) -> Result<Response<proto::Operation>, Status> {
+        let operation_id = parse_request_id(&request.into_inner().operation_id, "operation_id")?;
+
+        let record = self
+            .state
+            .db
+            .get_operation(&operation_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| Status::not_found("operation not indexed"))?;
+
+        Ok(Response::new(record.into()))
+    }
+
+    async fn get_ballot(
+        &self,
+        request: Request<proto::GetBallotRequest>,
+    ) -> Result<Response<proto::Ballot>, Status> {
+        let ballot_id = parse_request_id(&request.into_inner().ballot_id, "ballot_id")?;
+
+        let record = self
+            .state
+            .db
+            .get_ballot(&ballot_id)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| Status::not_found("ballot not indexed"))?;
+
+        Ok(Response::new(record.into()))
+    }
+
+    async fn list_perps_positions(
+        &self,
+        request: Request<proto::ListPerpsPositionsRequest>,
+    ) -> Result<Response<proto::ListPerpsPositionsResponse>, Status> {
+        let request = request.into_inner();
+        let market_id = request
+            .market_id
+            .as_deref()
+            .map(|m| parse_request_id(m, "market_id"))
+            .transpose()?;
+        let status = request
+            .status
+            .map(|s| u8::try_from(s).map_err(|_| Status::invalid_argument("status out of range")))
+            .transpose()?;
+
+        let records = self
+            .state
+            .db
+            .get_perps_positions(market_id.as_ref(), status, clamp_limit(request.limit))
+            .await
+            .map_err(internal)?;
+
+        Ok(Response::new(proto::ListPerpsPositionsResponse {
+            positions: records.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn get_sync_status(
+        &self,
+        _request: Request<proto::GetSyncStatusRequest>,
+    ) -> Result<Response<proto::SyncStatus>, Status> {
+        let latest_slot = self.state.db.get_latest_slot().await.map_err(internal)?;
+
+        Ok(Response::new(proto::SyncStatus { latest_slot }))
+    }
+}
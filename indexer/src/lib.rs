@@ -3,10 +3,14 @@
 //! Indexes shielded pool events for efficient client queries.
 //! Tracks note commitments, nullifiers, and encrypted data.
 
+pub mod backfill;
 pub mod config;
 pub mod database;
 pub mod events;
+pub mod grpc;
 pub mod rpc;
+pub mod telemetry;
+pub mod webhook;
 
 use thiserror::Error;
 
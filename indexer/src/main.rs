@@ -0,0 +1,108 @@
+//! CloakCraft indexer binary
+
+use clap::{Parser, Subcommand};
+use solana_sdk::pubkey::Pubkey;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use cloakcraft_indexer::backfill::{run_backfill, BackfillConfig, DEFAULT_CHUNK_SLOTS};
+use cloakcraft_indexer::config::IndexerConfig;
+use cloakcraft_indexer::database::Database;
+use cloakcraft_indexer::rpc::{create_router, ApiState};
+use cloakcraft_indexer::telemetry::{init_tracing, spawn_lag_monitor, Metrics};
+use cloakcraft_indexer::webhook::Webhooks;
+use cloakcraft_indexer::{grpc, IndexerError};
+
+#[derive(Parser)]
+#[command(name = "cloakcraft-indexer")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Serve the REST and gRPC APIs
+    Serve,
+    /// Index historical transactions in a slot range
+    Backfill {
+        /// First slot (inclusive)
+        #[arg(long)]
+        from_slot: u64,
+        /// Last slot (inclusive)
+        #[arg(long)]
+        to_slot: u64,
+        /// Concurrent slot-range workers
+        #[arg(long, default_value_t = 4)]
+        workers: usize,
+        /// RPC requests per second across all workers
+        #[arg(long, default_value_t = 20)]
+        rps: u32,
+        /// Slots per worker chunk
+        #[arg(long, default_value_t = DEFAULT_CHUNK_SLOTS)]
+        chunk_slots: u64,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let config = IndexerConfig::from_env()?;
+    init_tracing(config.log_json);
+
+    let db = Database::connect(&config.database_url).await?;
+    db.migrate().await?;
+
+    match cli.command {
+        Command::Serve => serve(config, db).await,
+        Command::Backfill { from_slot, to_slot, workers, rps, chunk_slots } => {
+            let program_id = Pubkey::from_str(&config.program_id)
+                .map_err(|e| IndexerError::Config(format!("invalid PROGRAM_ID: {e}")))?;
+            let backfill = BackfillConfig {
+                from_slot,
+                to_slot,
+                workers,
+                requests_per_second: rps,
+                chunk_slots,
+            };
+            let stats = run_backfill(config.rpc_url, program_id, Arc::new(db), backfill).await?;
+            tracing::info!(transactions = stats.transactions, events = stats.events, "backfill complete");
+            Ok(())
+        }
+    }
+}
+
+async fn serve(config: IndexerConfig, db: Database) -> anyhow::Result<()> {
+    let _pruning = db.spawn_pruning(config.retention.clone());
+
+    let webhooks = config.webhook_admin_token.clone().map(|token| {
+        let webhooks = Arc::new(Webhooks::new(Arc::new(db.clone()), token, None));
+        webhooks.clone().spawn_delivery_worker();
+        webhooks
+    });
+
+    let state = Arc::new(ApiState {
+        db,
+        metrics: Metrics::new()?,
+        max_slot_lag: config.max_slot_lag,
+        webhooks,
+    });
+    let _lag_monitor = spawn_lag_monitor(config.rpc_url.clone(), state.clone());
+
+    let http_addr = SocketAddr::from(([0, 0, 0, 0], config.port));
+    let grpc_addr = SocketAddr::from(([0, 0, 0, 0], config.grpc_port));
+    tracing::info!(%http_addr, %grpc_addr, "serving indexer API");
+
+    let listener = tokio::net::TcpListener::bind(http_addr).await?;
+    let http = axum::serve(listener, create_router(state.clone()));
+    let grpc = tonic::transport::Server::builder()
+        .add_service(grpc::create_service(state))
+        .serve(grpc_addr);
+
+    tokio::try_join!(
+        async { http.await.map_err(anyhow::Error::from) },
+        async { grpc.await.map_err(anyhow::Error::from) },
+    )?;
+    Ok(())
+}
@@ -1,19 +1,86 @@
 //! RPC API server for indexer queries
+//!
+//! REST handlers are annotated for the OpenAPI document served at
+//! `/openapi.json`. The gRPC service in `grpc` exposes the same queries.
 
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
-    routing::get,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{delete, get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use tower_http::trace::TraceLayer;
+use utoipa::{IntoParams, OpenApi, ToSchema};
 
-use crate::database::Database;
+use crate::database::{BallotRecord, Database, NewWebhook, OperationRecord, PerpsPositionRecord};
+use crate::events::EVENT_KINDS;
+use crate::telemetry::Metrics;
+use crate::webhook::Webhooks;
+
+/// Default page size for list queries
+pub const DEFAULT_LIMIT: u32 = 1000;
+
+/// Maximum page size for list queries
+pub const MAX_LIMIT: u32 = 10000;
+
+/// Page size for a requested limit
+pub fn clamp_limit(limit: Option<u32>) -> u32 {
+    limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT)
+}
+
+/// Decode a 32-byte id
+pub fn parse_id(bytes: &[u8]) -> Option<[u8; 32]> {
+    bytes.try_into().ok()
+}
+
+/// Decode a hex-encoded 32-byte id
+fn parse_hex_id(id: &str) -> Result<[u8; 32], StatusCode> {
+    let bytes = hex::decode(id).map_err(|_| StatusCode::BAD_REQUEST)?;
+    parse_id(&bytes).ok_or(StatusCode::BAD_REQUEST)
+}
+
+/// OpenAPI document for the REST API
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "CloakCraft Indexer API"),
+    paths(
+        health,
+        get_commitments,
+        get_commitment_index,
+        check_nullifier,
+        get_operation,
+        get_ballot,
+        get_perps_positions,
+        sync_status,
+        register_webhook,
+        delete_webhook,
+    ),
+    components(schemas(
+        HealthResponse,
+        CommitmentResponse,
+        CommitmentIndexResponse,
+        NullifierResponse,
+        OperationResponse,
+        BallotResponse,
+        PerpsPositionResponse,
+        SyncStatusResponse,
+        RegisterWebhookRequest,
+        RegisterWebhookResponse,
+    ))
+)]
+pub struct ApiDoc;
 
 /// API server state
 pub struct ApiState {
     pub db: Database,
+    pub metrics: Metrics,
+    /// Lag (slots) above which `/health` reports unhealthy
+    pub max_slot_lag: u64,
+    /// Webhook subsystem, None when disabled
+    pub webhooks: Option<Arc<Webhooks>>,
 }
 
 /// Create the API router
@@ -21,46 +88,104 @@ pub fn create_router(state: Arc<ApiState>) -> Router {
     Router::new()
         .route("/health", get(health))
         .route("/commitments", get(get_commitments))
+        .route("/commitments/:commitment/index", get(get_commitment_index))
         .route("/nullifier/:nullifier", get(check_nullifier))
+        .route("/operations/:operation_id", get(get_operation))
+        .route("/ballots/:ballot_id", get(get_ballot))
+        .route("/perps/positions", get(get_perps_positions))
         .route("/sync-status", get(sync_status))
+        .route("/webhooks", post(register_webhook))
+        .route("/webhooks/:id", delete(delete_webhook))
+        .route("/metrics", get(metrics))
+        .route("/openapi.json", get(openapi))
+        .layer(TraceLayer::new_for_http())
         .with_state(state)
 }
 
+#[derive(Serialize, ToSchema)]
+pub struct HealthResponse {
+    /// ok | lagging
+    pub status: String,
+    pub last_indexed_slot: u64,
+    pub chain_slot: u64,
+    pub lag_slots: u64,
+}
+
 /// Health check endpoint
-async fn health() -> &'static str {
-    "OK"
+///
+/// 503 once the indexer trails the RPC node by more than `max_slot_lag`.
+#[utoipa::path(
+    get,
+    path = "/health",
+    responses(
+        (status = 200, body = HealthResponse),
+        (status = 503, body = HealthResponse, description = "Indexer is lagging"),
+    )
+)]
+async fn health(State(state): State<Arc<ApiState>>) -> (StatusCode, Json<HealthResponse>) {
+    let lag = state.metrics.lag();
+    let healthy = lag <= state.max_slot_lag;
+    let response = HealthResponse {
+        status: if healthy { "ok" } else { "lagging" }.to_string(),
+        last_indexed_slot: state.metrics.last_indexed_slot.get() as u64,
+        chain_slot: state.metrics.chain_slot.get() as u64,
+        lag_slots: lag,
+    };
+    let code = if healthy { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (code, Json(response))
+}
+
+/// Prometheus metrics
+async fn metrics(State(state): State<Arc<ApiState>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+        state.metrics.encode(),
+    )
+}
+
+/// OpenAPI document
+async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 pub struct CommitmentsQuery {
+    /// Pool id (hex)
     pub pool_id: String,
     pub since_index: Option<u32>,
     pub limit: Option<u32>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct CommitmentResponse {
     pub commitment: String,
     pub leaf_index: u32,
-    pub encrypted_note: String,
+    /// Encrypted note (hex), absent once archived
+    pub encrypted_note: Option<String>,
+    /// Cold storage location of the encrypted note, if archived
+    pub archive_uri: Option<String>,
+    /// View tag (hex), absent if the sender provided none
+    pub view_tag: Option<String>,
     pub slot: u64,
 }
 
 /// Get commitments for a pool
+#[utoipa::path(
+    get,
+    path = "/commitments",
+    params(CommitmentsQuery),
+    responses(
+        (status = 200, body = [CommitmentResponse]),
+        (status = 400, description = "Invalid pool id"),
+    )
+)]
 async fn get_commitments(
     State(state): State<Arc<ApiState>>,
     Query(query): Query<CommitmentsQuery>,
 ) -> Result<Json<Vec<CommitmentResponse>>, StatusCode> {
-    let pool_id = hex::decode(&query.pool_id)
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
-
-    if pool_id.len() != 32 {
-        return Err(StatusCode::BAD_REQUEST);
-    }
-
-    let pool_id: [u8; 32] = pool_id.try_into().unwrap();
+    let pool_id = parse_hex_id(&query.pool_id)?;
     let since_index = query.since_index.unwrap_or(0);
-    let limit = query.limit.unwrap_or(1000).min(10000);
+    let limit = clamp_limit(query.limit);
 
     let records = state
         .db
@@ -73,7 +198,9 @@ async fn get_commitments(
         .map(|r| CommitmentResponse {
             commitment: hex::encode(&r.commitment),
             leaf_index: r.leaf_index as u32,
-            encrypted_note: hex::encode(&r.encrypted_note),
+            encrypted_note: r.encrypted_note.as_ref().map(hex::encode),
+            archive_uri: r.archive_uri,
+            view_tag: r.view_tag.as_ref().map(hex::encode),
             slot: r.slot as u64,
         })
         .collect();
@@ -81,24 +208,75 @@ async fn get_commitments(
     Ok(Json(response))
 }
 
-#[derive(Serialize)]
+#[derive(Deserialize, IntoParams)]
+pub struct CommitmentIndexQuery {
+    /// Pool id (hex)
+    pub pool_id: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CommitmentIndexResponse {
+    pub commitment: String,
+    /// Per-pool commitment index stored on-chain (bound into the nullifier),
+    /// not the position in Light's state tree
+    pub leaf_index: u32,
+}
+
+/// Get the commitment index of a note
+#[utoipa::path(
+    get,
+    path = "/commitments/{commitment}/index",
+    params(
+        ("commitment" = String, Path, description = "Commitment (hex)"),
+        CommitmentIndexQuery,
+    ),
+    responses(
+        (status = 200, body = CommitmentIndexResponse),
+        (status = 400, description = "Invalid pool id or commitment"),
+        (status = 404, description = "Commitment not indexed"),
+    )
+)]
+async fn get_commitment_index(
+    State(state): State<Arc<ApiState>>,
+    Path(commitment_hex): Path<String>,
+    Query(query): Query<CommitmentIndexQuery>,
+) -> Result<Json<CommitmentIndexResponse>, StatusCode> {
+    let pool_id = parse_hex_id(&query.pool_id)?;
+    let commitment = parse_hex_id(&commitment_hex)?;
+
+    let leaf_index = state
+        .db
+        .get_commitment_index(&pool_id, &commitment)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(CommitmentIndexResponse {
+        commitment: hex::encode(commitment),
+        leaf_index,
+    }))
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct NullifierResponse {
     pub spent: bool,
 }
 
 /// Check if a nullifier has been spent
+#[utoipa::path(
+    get,
+    path = "/nullifier/{nullifier}",
+    params(("nullifier" = String, Path, description = "Nullifier (hex)")),
+    responses(
+        (status = 200, body = NullifierResponse),
+        (status = 400, description = "Invalid nullifier"),
+    )
+)]
 async fn check_nullifier(
     State(state): State<Arc<ApiState>>,
-    axum::extract::Path(nullifier_hex): axum::extract::Path<String>,
+    Path(nullifier_hex): Path<String>,
 ) -> Result<Json<NullifierResponse>, StatusCode> {
-    let nullifier = hex::decode(&nullifier_hex)
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
-
-    if nullifier.len() != 32 {
-        return Err(StatusCode::BAD_REQUEST);
-    }
-
-    let nullifier: [u8; 32] = nullifier.try_into().unwrap();
+    let nullifier = parse_hex_id(&nullifier_hex)?;
 
     let spent = state
         .db
@@ -109,12 +287,192 @@ async fn check_nullifier(
     Ok(Json(NullifierResponse { spent }))
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
+pub struct OperationResponse {
+    pub operation_id: String,
+    /// Operation type (program `constants::operation_types`)
+    pub operation_type: u8,
+    pub relayer: String,
+    /// pending | completed | expired | closed
+    pub status: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub slot: u64,
+}
+
+impl From<OperationRecord> for OperationResponse {
+    fn from(r: OperationRecord) -> Self {
+        Self {
+            operation_id: hex::encode(&r.operation_id),
+            operation_type: r.operation_type as u8,
+            relayer: hex::encode(&r.relayer),
+            status: r.status,
+            created_at: r.created_at,
+            expires_at: r.expires_at,
+            slot: r.slot as u64,
+        }
+    }
+}
+
+/// Get a multi-phase operation
+#[utoipa::path(
+    get,
+    path = "/operations/{operation_id}",
+    params(("operation_id" = String, Path, description = "Operation id (hex)")),
+    responses(
+        (status = 200, body = OperationResponse),
+        (status = 400, description = "Invalid operation id"),
+        (status = 404, description = "Operation not indexed"),
+    )
+)]
+async fn get_operation(
+    State(state): State<Arc<ApiState>>,
+    Path(operation_id): Path<String>,
+) -> Result<Json<OperationResponse>, StatusCode> {
+    let operation_id = parse_hex_id(&operation_id)?;
+
+    let record = state
+        .db
+        .get_operation(&operation_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(record.into()))
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BallotResponse {
+    pub ballot_id: String,
+    pub threshold_pubkey: String,
+    pub num_options: u32,
+    pub deadline: i64,
+    pub status: String,
+    pub vote_count: u32,
+    pub slot: u64,
+}
+
+impl From<BallotRecord> for BallotResponse {
+    fn from(r: BallotRecord) -> Self {
+        Self {
+            ballot_id: hex::encode(&r.ballot_id),
+            threshold_pubkey: hex::encode(&r.threshold_pubkey),
+            num_options: r.num_options as u32,
+            deadline: r.deadline,
+            status: r.status,
+            vote_count: r.vote_count as u32,
+            slot: r.slot as u64,
+        }
+    }
+}
+
+/// Get a ballot
+#[utoipa::path(
+    get,
+    path = "/ballots/{ballot_id}",
+    params(("ballot_id" = String, Path, description = "Ballot id (hex)")),
+    responses(
+        (status = 200, body = BallotResponse),
+        (status = 400, description = "Invalid ballot id"),
+        (status = 404, description = "Ballot not indexed"),
+    )
+)]
+async fn get_ballot(
+    State(state): State<Arc<ApiState>>,
+    Path(ballot_id): Path<String>,
+) -> Result<Json<BallotResponse>, StatusCode> {
+    let ballot_id = parse_hex_id(&ballot_id)?;
+
+    let record = state
+        .db
+        .get_ballot(&ballot_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(record.into()))
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct PerpsPositionsQuery {
+    /// Market id (hex)
+    pub market_id: Option<String>,
+    /// Position status (program `PositionStatus` as u8)
+    pub status: Option<u8>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PerpsPositionResponse {
+    pub position_id: String,
+    pub pool_id: String,
+    pub market_id: String,
+    pub is_long: bool,
+    pub margin_amount: u64,
+    pub position_size: u64,
+    pub entry_price: u64,
+    pub liquidation_price: u64,
+    pub status: u8,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub slot: u64,
+}
+
+impl From<PerpsPositionRecord> for PerpsPositionResponse {
+    fn from(r: PerpsPositionRecord) -> Self {
+        Self {
+            position_id: hex::encode(&r.position_id),
+            pool_id: hex::encode(&r.pool_id),
+            market_id: hex::encode(&r.market_id),
+            is_long: r.is_long,
+            margin_amount: r.margin_amount as u64,
+            position_size: r.position_size as u64,
+            entry_price: r.entry_price as u64,
+            liquidation_price: r.liquidation_price as u64,
+            status: r.status as u8,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+            slot: r.slot as u64,
+        }
+    }
+}
+
+/// List perps positions
+#[utoipa::path(
+    get,
+    path = "/perps/positions",
+    params(PerpsPositionsQuery),
+    responses(
+        (status = 200, body = [PerpsPositionResponse]),
+        (status = 400, description = "Invalid market id"),
+    )
+)]
+async fn get_perps_positions(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<PerpsPositionsQuery>,
+) -> Result<Json<Vec<PerpsPositionResponse>>, StatusCode> {
+    let market_id = query.market_id.as_deref().map(parse_hex_id).transpose()?;
+
+    let records = state
+        .db
+        .get_perps_positions(market_id.as_ref(), query.status, clamp_limit(query.limit))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(records.into_iter().map(Into::into).collect()))
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct SyncStatusResponse {
     pub latest_slot: u64,
 }
 
 /// Get sync status
+#[utoipa::path(
+    get,
+    path = "/sync-status",
+    responses((status = 200, body = SyncStatusResponse))
+)]
 async fn sync_status(
     State(state): State<Arc<ApiState>>,
 ) -> Result<Json<SyncStatusResponse>, StatusCode> {
@@ -126,3 +484,111 @@ async fn sync_status(
 
     Ok(Json(SyncStatusResponse { latest_slot }))
 }
+
+/// Webhook subsystem, authorized by the admin bearer token
+fn authorized_webhooks(state: &ApiState, headers: &HeaderMap) -> Result<Arc<Webhooks>, StatusCode> {
+    let webhooks = state.webhooks.clone().ok_or(StatusCode::NOT_FOUND)?;
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    if token != Some(webhooks.admin_token.as_str()) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(webhooks)
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RegisterWebhookRequest {
+    /// http(s) URL to POST payloads to
+    pub url: String,
+    /// Only events for this pool (hex)
+    pub pool_id: Option<String>,
+    /// Only notes matching this stealth scan key (hex)
+    pub scan_key: Option<String>,
+    /// Only these event types, empty = all
+    #[serde(default)]
+    pub event_types: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RegisterWebhookResponse {
+    pub id: i64,
+    /// HMAC-SHA256 signing secret (hex), returned only once
+    pub secret: String,
+}
+
+/// Register a webhook
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    request_body = RegisterWebhookRequest,
+    responses(
+        (status = 200, body = RegisterWebhookResponse),
+        (status = 400, description = "Invalid URL or filter"),
+        (status = 401, description = "Missing or invalid admin token"),
+        (status = 404, description = "Webhooks disabled"),
+    ),
+    security(("bearer" = []))
+)]
+async fn register_webhook(
+    State(state): State<Arc<ApiState>>,
+    headers: HeaderMap,
+    Json(request): Json<RegisterWebhookRequest>,
+) -> Result<Json<RegisterWebhookResponse>, StatusCode> {
+    let webhooks = authorized_webhooks(&state, &headers)?;
+
+    let url = reqwest::Url::parse(&request.url).map_err(|_| StatusCode::BAD_REQUEST)?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if request.event_types.iter().any(|t| !EVENT_KINDS.contains(&t.as_str())) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let scan_key = request.scan_key.as_deref().map(parse_hex_id).transpose()?;
+    if scan_key.is_some() && !webhooks.supports_scan_keys() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let secret: [u8; 32] = rand::random();
+    let id = state
+        .db
+        .insert_webhook(&NewWebhook {
+            url: url.to_string(),
+            secret,
+            pool_id: request.pool_id.as_deref().map(parse_hex_id).transpose()?,
+            scan_key,
+            event_types: request.event_types,
+        })
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(RegisterWebhookResponse { id, secret: hex::encode(secret) }))
+}
+
+/// Delete a webhook
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    params(("id" = i64, Path, description = "Webhook id")),
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 401, description = "Missing or invalid admin token"),
+        (status = 404, description = "Unknown webhook or webhooks disabled"),
+    ),
+    security(("bearer" = []))
+)]
+async fn delete_webhook(
+    State(state): State<Arc<ApiState>>,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+) -> StatusCode {
+    if let Err(code) = authorized_webhooks(&state, &headers) {
+        return code;
+    }
+    match state.db.delete_webhook(id).await {
+        Ok(true) => StatusCode::NO_CONTENT,
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
@@ -0,0 +1,131 @@
+//! Metrics, lag monitoring and tracing setup
+//!
+//! Wallets silently see stale notes when the indexer falls behind, so lag is
+//! exported for alerting (`/metrics`) and reflected in `/health`.
+
+use prometheus::{Encoder, IntCounterVec, IntGauge, Opts, Registry, TextEncoder};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing_subscriber::EnvFilter;
+
+use crate::events::CloakCraftEvent;
+use crate::rpc::ApiState;
+
+/// Seconds between lag samples
+pub const LAG_SAMPLE_INTERVAL_SECS: u64 = 10;
+
+/// Indexer metrics (Prometheus)
+pub struct Metrics {
+    registry: Registry,
+    /// Highest slot written to the database
+    pub last_indexed_slot: IntGauge,
+    /// Latest confirmed slot reported by the RPC node
+    pub chain_slot: IntGauge,
+    /// chain_slot - last_indexed_slot
+    pub rpc_lag_slots: IntGauge,
+    /// Database connections busy serving queries
+    pub db_queue_depth: IntGauge,
+    /// Events indexed, by event type
+    pub events_total: IntCounterVec,
+}
+
+impl Metrics {
+    pub fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new_custom(Some("cloakcraft_indexer".to_string()), None)?;
+
+        let last_indexed_slot = IntGauge::new("last_indexed_slot", "Highest slot written to the database")?;
+        let chain_slot = IntGauge::new("chain_slot", "Latest slot reported by the RPC node")?;
+        let rpc_lag_slots = IntGauge::new("rpc_lag_slots", "Slots between the RPC node and the indexer")?;
+        let db_queue_depth = IntGauge::new("db_queue_depth", "Database connections busy serving queries")?;
+        let events_total = IntCounterVec::new(
+            Opts::new("events_total", "Events indexed by event type"),
+            &["event"],
+        )?;
+
+        registry.register(Box::new(last_indexed_slot.clone()))?;
+        registry.register(Box::new(chain_slot.clone()))?;
+        registry.register(Box::new(rpc_lag_slots.clone()))?;
+        registry.register(Box::new(db_queue_depth.clone()))?;
+        registry.register(Box::new(events_total.clone()))?;
+
+        Ok(Self {
+            registry,
+            last_indexed_slot,
+            chain_slot,
+            rpc_lag_slots,
+            db_queue_depth,
+            events_total,
+        })
+    }
+
+    /// Count an indexed event
+    pub fn record_event(&self, event: &CloakCraftEvent) {
+        self.events_total.with_label_values(&[event.kind()]).inc();
+    }
+
+    /// Update slot gauges and lag
+    pub fn record_slots(&self, chain_slot: u64, indexed_slot: u64) {
+        self.chain_slot.set(chain_slot as i64);
+        self.last_indexed_slot.set(indexed_slot as i64);
+        self.rpc_lag_slots.set(chain_slot.saturating_sub(indexed_slot) as i64);
+    }
+
+    /// Current lag in slots
+    pub fn lag(&self) -> u64 {
+        self.rpc_lag_slots.get().max(0) as u64
+    }
+
+    /// Prometheus text exposition
+    pub fn encode(&self) -> String {
+        let mut buffer = Vec::new();
+        // Text encoding of registered metrics can't fail
+        let _ = TextEncoder::new().encode(&self.registry.gather(), &mut buffer);
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+/// Sample chain slot, indexed slot and DB load every `LAG_SAMPLE_INTERVAL_SECS`
+pub fn spawn_lag_monitor(rpc_url: String, state: Arc<ApiState>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let rpc = RpcClient::new(rpc_url);
+        let mut interval = tokio::time::interval(Duration::from_secs(LAG_SAMPLE_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let (db, metrics) = (&state.db, &state.metrics);
+            metrics.db_queue_depth.set(db.busy_connections() as i64);
+
+            let chain_slot = match rpc.get_slot().await {
+                Ok(slot) => slot,
+                Err(e) => {
+                    tracing::warn!(error = %e, "failed to fetch chain slot");
+                    continue;
+                }
+            };
+            let indexed_slot = match db.get_latest_slot().await {
+                Ok(slot) => slot,
+                Err(e) => {
+                    tracing::warn!(error = %e, "failed to fetch indexed slot");
+                    continue;
+                }
+            };
+
+            metrics.record_slots(chain_slot, indexed_slot);
+            tracing::debug!(chain_slot, indexed_slot, lag = metrics.lag(), "sampled indexer lag");
+        }
+    })
+}
+
+/// Install the global tracing subscriber
+///
+/// Filter comes from `RUST_LOG` (default `info`); `json` emits one JSON object
+/// per line for log aggregation.
+pub fn init_tracing(json: bool) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    if json {
+        builder.json().init();
+    } else {
+        builder.init();
+    }
+}
@@ -0,0 +1,259 @@
+//! Webhook delivery
+//!
+//! Integrators register a URL and a filter; matching events are written to a
+//! delivery outbox and POSTed by a background worker, retried with
+//! exponential backoff. Payloads are signed with the per-webhook secret:
+//! `X-CloakCraft-Signature = hex(HMAC-SHA256(secret, "{timestamp}.{body}"))`.
+
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::database::{Database, WebhookDeliveryRecord, WebhookRecord};
+use crate::events::{CloakCraftEvent, NoteCreatedEvent};
+use crate::Result;
+
+/// Attempts before a delivery is given up
+pub const MAX_DELIVERY_ATTEMPTS: i32 = 10;
+
+/// First retry delay; doubles per attempt
+pub const RETRY_BASE_SECS: u64 = 5;
+
+/// Retry delay cap
+pub const RETRY_MAX_SECS: u64 = 3600;
+
+/// Deliveries fetched per worker poll
+const DELIVERY_BATCH: u32 = 100;
+
+/// Seconds between worker polls
+const POLL_INTERVAL_SECS: u64 = 2;
+
+/// Per-request timeout
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+pub const SIGNATURE_HEADER: &str = "X-CloakCraft-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-CloakCraft-Timestamp";
+
+/// Matches notes against a stealth scan key (view tag check)
+///
+/// The view tag is derived from an ECDH shared secret over BabyJubJub, so
+/// scan-key filters are only accepted when a scanner is configured.
+pub trait NoteScanner: Send + Sync {
+    fn matches(&self, scan_key: &[u8; 32], note: &NoteCreatedEvent) -> bool;
+}
+
+/// Webhook registration and dispatch
+pub struct Webhooks {
+    db: Arc<Database>,
+    http: reqwest::Client,
+    scanner: Option<Arc<dyn NoteScanner>>,
+    /// Bearer token for the management endpoints
+    pub admin_token: String,
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Sign a payload body
+pub fn sign_payload(secret: &[u8], timestamp: u64, body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Retry delay after `attempts` failed attempts, None once exhausted
+pub fn retry_delay(attempts: i32) -> Option<u64> {
+    if attempts >= MAX_DELIVERY_ATTEMPTS {
+        return None;
+    }
+    Some(RETRY_BASE_SECS.saturating_mul(1 << attempts.clamp(0, 30)).min(RETRY_MAX_SECS))
+}
+
+/// Pool an event belongs to, if any
+fn event_pool(event: &CloakCraftEvent) -> Option<&[u8; 32]> {
+    match event {
+        CloakCraftEvent::NoteCreated(e) => Some(&e.pool),
+        CloakCraftEvent::NoteSpent(e) => Some(&e.pool),
+        CloakCraftEvent::SwapExecuted(e) => Some(&e.amm_pool),
+        CloakCraftEvent::RootCheckpointAnchored(e) => Some(&e.pool),
+        _ => None,
+    }
+}
+
+/// JSON payload for an event
+pub fn event_payload(event: &CloakCraftEvent, slot: u64, signature: &str) -> serde_json::Value {
+    let data = match event {
+        CloakCraftEvent::NoteCreated(e) => json!({
+            "pool": hex::encode(e.pool),
+            "commitment": hex::encode(e.commitment),
+            "leaf_index": e.leaf_index,
+            "encrypted_note": hex::encode(&e.encrypted_note),
+            "view_tag": e.view_tag.map(hex::encode),
+            "timestamp": e.timestamp,
+        }),
+        CloakCraftEvent::NoteSpent(e) => json!({
+            "pool": hex::encode(e.pool),
+            "nullifier": hex::encode(e.nullifier),
+            "timestamp": e.timestamp,
+        }),
+        CloakCraftEvent::OrderCreated(e) => json!({
+            "order_id": hex::encode(e.order_id),
+            "escrow_commitment": hex::encode(e.escrow_commitment),
+            "terms_hash": hex::encode(e.terms_hash),
+            "expiry": e.expiry,
+            "timestamp": e.timestamp,
+        }),
+        CloakCraftEvent::OrderFilled(e) => json!({
+            "order_id": hex::encode(e.order_id),
+            "maker_commitment": hex::encode(e.maker_commitment),
+            "taker_commitment": hex::encode(e.taker_commitment),
+            "timestamp": e.timestamp,
+        }),
+        CloakCraftEvent::OrderCancelled(e) => json!({
+            "order_id": hex::encode(e.order_id),
+            "refund_commitment": hex::encode(e.refund_commitment),
+            "timestamp": e.timestamp,
+        }),
+        CloakCraftEvent::SwapExecuted(e) => json!({
+            "amm_pool": hex::encode(e.amm_pool),
+            "out_commitment": hex::encode(e.out_commitment),
+            "change_commitment": hex::encode(e.change_commitment),
+            "timestamp": e.timestamp,
+        }),
+        CloakCraftEvent::VoteSubmitted(e) => json!({
+            "aggregation_id": hex::encode(e.aggregation_id),
+            "action_nullifier": hex::encode(e.action_nullifier),
+            "timestamp": e.timestamp,
+        }),
+        CloakCraftEvent::RootCheckpointAnchored(e) => json!({
+            "pool": hex::encode(e.pool),
+            "state_tree": hex::encode(e.state_tree),
+            "root": hex::encode(e.root),
+            "commitment_count": e.commitment_count,
+            "checkpoint_index": e.checkpoint_index,
+            "chain_hash": hex::encode(e.chain_hash),
+            "timestamp": e.timestamp,
+        }),
+    };
+    json!({
+        "event": event.kind(),
+        "slot": slot,
+        "signature": signature,
+        "data": data,
+    })
+}
+
+impl Webhooks {
+    pub fn new(db: Arc<Database>, admin_token: String, scanner: Option<Arc<dyn NoteScanner>>) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .expect("default TLS backend available");
+        Self { db, http, scanner, admin_token }
+    }
+
+    /// Whether scan-key filters can be evaluated
+    pub fn supports_scan_keys(&self) -> bool {
+        self.scanner.is_some()
+    }
+
+    /// Whether a webhook's filter matches an event
+    fn matches(&self, webhook: &WebhookRecord, event: &CloakCraftEvent) -> bool {
+        if !webhook.event_types.is_empty() && !webhook.event_types.iter().any(|t| t == event.kind()) {
+            return false;
+        }
+        if let Some(pool_id) = &webhook.pool_id {
+            if event_pool(event).map(|p| p.as_slice()) != Some(pool_id.as_slice()) {
+                return false;
+            }
+        }
+        if let Some(scan_key) = &webhook.scan_key {
+            let (CloakCraftEvent::NoteCreated(note), Some(scanner), Ok(scan_key)) =
+                (event, &self.scanner, <[u8; 32]>::try_from(scan_key.as_slice()))
+            else {
+                return false;
+            };
+            return scanner.matches(&scan_key, note);
+        }
+        true
+    }
+
+    /// Queue deliveries for every webhook matching a newly indexed event
+    ///
+    /// Called by live ingestion after `Database::index_event`; backfill
+    /// doesn't dispatch, so historical events never trigger webhooks.
+    pub async fn dispatch(&self, event: &CloakCraftEvent, slot: u64, signature: &str) -> Result<usize> {
+        let webhooks = self.db.get_active_webhooks().await?;
+        let mut payload = None;
+        let mut queued = 0;
+        for webhook in webhooks.iter().filter(|w| self.matches(w, event)) {
+            let payload = payload.get_or_insert_with(|| event_payload(event, slot, signature));
+            self.db.enqueue_webhook_delivery(webhook.id, payload).await?;
+            queued += 1;
+        }
+        Ok(queued)
+    }
+
+    /// POST one delivery
+    async fn deliver(&self, delivery: &WebhookDeliveryRecord) -> std::result::Result<(), String> {
+        let body = serde_json::to_vec(&delivery.payload).map_err(|e| e.to_string())?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        let response = self
+            .http
+            .post(&delivery.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, sign_payload(&delivery.secret, timestamp, &body))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("HTTP {}", response.status()))
+        }
+    }
+
+    /// Attempt every due delivery once
+    pub async fn deliver_due(&self) -> Result<()> {
+        for delivery in self.db.get_due_webhook_deliveries(DELIVERY_BATCH).await? {
+            match self.deliver(&delivery).await {
+                Ok(()) => self.db.mark_webhook_delivered(delivery.id).await?,
+                Err(error) => {
+                    let retry = retry_delay(delivery.attempts + 1);
+                    tracing::warn!(
+                        delivery = delivery.id,
+                        webhook = delivery.webhook_id,
+                        attempts = delivery.attempts + 1,
+                        error = %error,
+                        "webhook delivery failed"
+                    );
+                    self.db.mark_webhook_failed(delivery.id, &error, retry).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Poll and deliver in the background
+    pub fn spawn_delivery_worker(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(POLL_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.deliver_due().await {
+                    tracing::warn!("webhook delivery poll failed: {e}");
+                }
+            }
+        })
+    }
+}
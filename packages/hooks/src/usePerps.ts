@@ -335,7 +335,7 @@ export function useOpenPosition() {
 
         // Generate stealth address for position commitment
         const { stealthAddress: positionRecipient } = generateStealthAddress(wallet.publicKey);
-        const { stealthAddress: changeRecipient } = generateStealthAddress(wallet.publicKey);
+        const changeRecipient = wallet.changeAddress(marginInput.commitment);
 
         // Re-scan for fresh note
         client.clearScanCache();
@@ -563,7 +563,7 @@ export function usePerpsAddLiquidity() {
 
         // Generate stealth addresses
         const { stealthAddress: lpRecipient } = generateStealthAddress(wallet.publicKey);
-        const { stealthAddress: changeRecipient } = generateStealthAddress(wallet.publicKey);
+        const changeRecipient = wallet.changeAddress(tokenInput.commitment);
 
         // Re-scan for fresh note
         client.clearScanCache();
@@ -692,7 +692,7 @@ export function usePerpsRemoveLiquidity() {
 
         // Generate stealth address for withdrawal
         const { stealthAddress: withdrawRecipient } = generateStealthAddress(wallet.publicKey);
-        const { stealthAddress: lpChangeRecipient } = generateStealthAddress(wallet.publicKey);
+        const lpChangeRecipient = wallet.changeAddress(lpInput.commitment);
 
         // Re-scan for fresh LP note
         client.clearScanCache();
@@ -98,7 +98,7 @@ export function useSwap() {
 
         // Generate stealth addresses for output and change
         const { stealthAddress: outputRecipient } = generateStealthAddress(wallet.publicKey);
-        const { stealthAddress: changeRecipient } = generateStealthAddress(wallet.publicKey);
+        const changeRecipient = wallet.changeAddress(input.commitment);
 
         // Re-scan to get fresh note with stealthEphemeralPubkey
         client.clearScanCache();
@@ -381,8 +381,8 @@ export function useAddLiquidity() {
 
         // Generate stealth addresses for outputs
         const { stealthAddress: lpRecipient } = generateStealthAddress(wallet.publicKey);
-        const { stealthAddress: changeARecipient } = generateStealthAddress(wallet.publicKey);
-        const { stealthAddress: changeBRecipient } = generateStealthAddress(wallet.publicKey);
+        const changeARecipient = wallet.changeAddress(inputA.commitment);
+        const changeBRecipient = wallet.changeAddress(inputB.commitment);
 
         // Re-scan for fresh notes
         client.clearScanCache();
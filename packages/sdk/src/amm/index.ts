@@ -6,6 +6,7 @@
  * - Swap calculations
  * - Liquidity calculations
  * - Price impact and slippage
+ * - On-chain swap quotes
  */
 
 // Pool management
@@ -35,3 +36,6 @@ export {
   validateLiquidityAmounts,
   PoolType,
 } from './calculations';
+
+// On-chain quotes
+export { quoteSwap, type SwapQuote } from './quote';
@@ -0,0 +1,55 @@
+/**
+ * On-chain Swap Quotes
+ *
+ * Reads swap quotes from the program's `quote_swap` instruction (simulated,
+ * no signature needed), so displayed outputs match what Phase 3 recomputes
+ * instead of drifting from the client-side formulas in `calculations.ts`.
+ */
+
+import { PublicKey } from '@solana/web3.js';
+import { Program } from '@coral-xyz/anchor';
+import BN from 'bn.js';
+import { deriveProtocolConfigPda } from '../instructions/constants';
+
+/**
+ * Swap quote computed by the program (`SwapQuote` return data)
+ */
+export interface SwapQuote {
+  /** Output amount at current reserves (before slippage tolerance) */
+  outputAmount: bigint;
+  /** Total pool fee on the input (LP share + protocol share) */
+  feeAmount: bigint;
+  /** Protocol's share of feeAmount (0 while fees are disabled) */
+  protocolFee: bigint;
+  /** Rate shortfall against the spot rate, in bps */
+  priceImpactBps: number;
+}
+
+/**
+ * Quote a swap against current reserves
+ *
+ * @param ammPool - AMM pool PDA
+ * @param inputAmount - Amount of the input token
+ * @param swapAToB - Direction (token A in, token B out)
+ */
+export async function quoteSwap(
+  program: Program,
+  ammPool: PublicKey,
+  inputAmount: bigint,
+  swapAToB: boolean
+): Promise<SwapQuote> {
+  const quote: any = await program.methods
+    .quoteSwap(new BN(inputAmount.toString()), swapAToB)
+    .accountsStrict({
+      ammPool,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+    })
+    .view();
+
+  return {
+    outputAmount: BigInt(quote.outputAmount.toString()),
+    feeAmount: BigInt(quote.feeAmount.toString()),
+    protocolFee: BigInt(quote.protocolFee.toString()),
+    priceImpactBps: quote.priceImpactBps,
+  };
+}
@@ -4,9 +4,18 @@
  * Calculates and tracks AMM pool statistics, TVL, volume, and APY.
  */
 
-import { PublicKey, Connection } from '@solana/web3.js';
+import { PublicKey, Connection, SystemProgram } from '@solana/web3.js';
+import { Program } from '@coral-xyz/anchor';
+import BN from 'bn.js';
 import type { AmmPoolState } from '@cloakcraft/types';
 import { TokenPriceFetcher } from './prices';
+import {
+  deriveCircuitStatsPda,
+  derivePoolPda,
+  derivePoolStatsPda,
+  deriveVaultPda,
+  deriveVerificationKeyPda,
+} from './instructions/constants';
 
 /**
  * Pool statistics
@@ -340,3 +349,205 @@ export function verifyInvariant(
   // New K should be >= old K (fees are added to reserves)
   return newK >= oldK;
 }
+
+/**
+ * On-chain shielded pool statistics (PoolStats PDA)
+ */
+export interface ShieldedPoolStats {
+  /** Value currently shielded (deposits - withdrawals - fees) */
+  totalShielded: bigint;
+  /** Lifetime deposits */
+  lifetimeDeposits: bigint;
+  /** Lifetime withdrawals */
+  lifetimeWithdrawals: bigint;
+  /** Lifetime protocol fees */
+  lifetimeFees: bigint;
+  /** Live notes */
+  noteCount: bigint;
+  /** Lifetime commitments created */
+  totalNotesCreated: bigint;
+  /** Current epoch (days since unix epoch) */
+  epoch: bigint;
+  /** Deposits in the current epoch */
+  epochDeposits: bigint;
+  /** Withdrawals (unshields + fees) in the current epoch */
+  epochWithdrawals: bigint;
+  /** Last update (unix seconds) */
+  updatedAt: number;
+}
+
+/**
+ * Fetch a pool's statistics, or null if the PoolStats account hasn't been created
+ */
+export async function fetchShieldedPoolStats(
+  program: Program,
+  pool: PublicKey
+): Promise<ShieldedPoolStats | null> {
+  const [poolStatsPda] = derivePoolStatsPda(pool, program.programId);
+  const raw: any = await (program.account as any).poolStats.fetchNullable(poolStatsPda);
+  if (!raw) {
+    return null;
+  }
+
+  const big = (v: BN) => BigInt(v.toString());
+  return {
+    totalShielded: big(raw.totalShielded),
+    lifetimeDeposits: big(raw.lifetimeDeposits),
+    lifetimeWithdrawals: big(raw.lifetimeWithdrawals),
+    lifetimeFees: big(raw.lifetimeFees),
+    noteCount: big(raw.noteCount),
+    totalNotesCreated: big(raw.totalNotesCreated),
+    epoch: big(raw.epoch),
+    epochDeposits: big(raw.epochDeposits),
+    epochWithdrawals: big(raw.epochWithdrawals),
+    updatedAt: (raw.updatedAt as BN).toNumber(),
+  };
+}
+
+/**
+ * Run the on-chain solvency check for a pool (simulated)
+ *
+ * Checks vault balance >= shielded supply - pending Phase 3 outflows. Pass the
+ * pool's live pending operations so their unshields are allowed for. Returns
+ * false on a shortfall (the program emits PoolInsolvencyDetected).
+ */
+export async function verifyPoolSolvency(
+  program: Program,
+  tokenMint: PublicKey,
+  pendingOperations: PublicKey[] = []
+): Promise<boolean> {
+  const programId = program.programId;
+  const [pool] = derivePoolPda(tokenMint, programId);
+  const [tokenVault] = deriveVaultPda(tokenMint, programId);
+  const [poolStats] = derivePoolStatsPda(pool, programId);
+  const statsInfo = await program.provider.connection.getAccountInfo(poolStats);
+
+  return program.methods
+    .verifyPoolSolvency()
+    .accountsStrict({
+      pool,
+      tokenVault,
+      poolStats: statsInfo ? poolStats : null,
+    })
+    .remainingAccounts(pendingOperations.map((pubkey) => ({
+      pubkey,
+      isSigner: false,
+      isWritable: false,
+    })))
+    .view();
+}
+
+/**
+ * On-chain Phase 0 statistics of a circuit (CircuitStats PDA)
+ *
+ * Verifications are only counted when the stats account is passed to Phase 0.
+ * A rejected proof still fails Phase 0; relayers count it with
+ * buildRecordProofFailureWithProgram, which emits CircuitProofRejected.
+ */
+export interface CircuitStats {
+  /** Proofs verified */
+  verifications: bigint;
+  /** Proofs rejected */
+  failures: bigint;
+  /** Compute units spent in successful verifications */
+  totalComputeUnits: bigint;
+  /** Most compute units spent in a single verification */
+  maxComputeUnits: bigint;
+  /** Mean compute units per verification (0 before the first one) */
+  averageComputeUnits: bigint;
+  /** Client version of the most recent rejected proof */
+  lastFailureClientVersion: number;
+  /** Most recent rejected proof (unix seconds, 0 = none) */
+  lastFailureAt: number;
+  /** Last update (unix seconds) */
+  updatedAt: number;
+}
+
+/**
+ * Fetch a circuit's statistics, or null if the CircuitStats account hasn't been created
+ */
+export async function fetchCircuitStats(
+  program: Program,
+  circuitId: string
+): Promise<CircuitStats | null> {
+  const [circuitStatsPda] = deriveCircuitStatsPda(circuitId, program.programId);
+  const raw: any = await (program.account as any).circuitStats.fetchNullable(circuitStatsPda);
+  if (!raw) {
+    return null;
+  }
+
+  const big = (v: BN) => BigInt(v.toString());
+  const verifications = big(raw.verifications);
+  const totalComputeUnits = big(raw.totalComputeUnits);
+  return {
+    verifications,
+    failures: big(raw.failures),
+    totalComputeUnits,
+    maxComputeUnits: big(raw.maxComputeUnits),
+    averageComputeUnits: verifications > 0n ? totalComputeUnits / verifications : 0n,
+    lastFailureClientVersion: raw.lastFailureClientVersion,
+    lastFailureAt: (raw.lastFailureAt as BN).toNumber(),
+    updatedAt: (raw.updatedAt as BN).toNumber(),
+  };
+}
+
+/**
+ * Build initialize_circuit_stats transaction (permissionless)
+ */
+export async function buildInitializeCircuitStatsWithProgram(
+  program: Program,
+  params: {
+    circuitId: string;
+    payer: PublicKey;
+  }
+): Promise<{ tx: any; circuitStats: PublicKey }> {
+  const programId = program.programId;
+  const [verificationKey] = deriveVerificationKeyPda(params.circuitId, programId);
+  const [circuitStats] = deriveCircuitStatsPda(params.circuitId, programId);
+
+  const tx = await program.methods
+    .initializeCircuitStats()
+    .accountsStrict({
+      verificationKey,
+      circuitStats,
+      payer: params.payer,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return { tx, circuitStats };
+}
+
+/**
+ * Build record_proof_failure transaction
+ *
+ * Reports a proof that Phase 0 rejected. The program re-verifies it and only
+ * counts it if it still fails.
+ */
+export async function buildRecordProofFailureWithProgram(
+  program: Program,
+  params: {
+    circuitId: string;
+    proof: Uint8Array;
+    publicInputs: Uint8Array[];
+    clientVersion: number;
+    reporter: PublicKey;
+  }
+): Promise<{ tx: any }> {
+  const programId = program.programId;
+  const [verificationKey] = deriveVerificationKeyPda(params.circuitId, programId);
+  const [circuitStats] = deriveCircuitStatsPda(params.circuitId, programId);
+
+  const tx = await program.methods
+    .recordProofFailure(
+      Buffer.from(params.proof),
+      params.publicInputs.map((input) => Array.from(input)),
+      params.clientVersion
+    )
+    .accountsStrict({
+      verificationKey,
+      circuitStats,
+      reporter: params.reporter,
+    });
+
+  return { tx };
+}
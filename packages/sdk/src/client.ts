@@ -8,6 +8,7 @@ import {
   Connection,
   PublicKey,
   Transaction,
+  TransactionInstruction,
   VersionedTransaction,
   Keypair as SolanaKeypair,
 } from '@solana/web3.js';
@@ -38,6 +39,7 @@ import type {
   ClosePerpsPositionParams,
   PerpsAddLiquidityClientParams,
   PerpsRemoveLiquidityClientParams,
+  Point,
 } from '@cloakcraft/types';
 
 import { Wallet, createWallet, loadWallet } from './wallet';
@@ -72,16 +74,20 @@ import {
   deriveOrderPda,
   deriveProtocolConfigPda,
   CIRCUIT_IDS,
+  NULLIFIER_DOMAINS,
+  NullifierDomain,
 } from './instructions';
 import {
   buildOpenPositionWithProgram,
   buildClosePositionWithProgram,
+  buildCreatePositionMetaWithProgram,
   buildAddPerpsLiquidityWithProgram,
   buildRemovePerpsLiquidityWithProgram,
   derivePerpsPoolPda,
   derivePerpsMarketPda,
   derivePerpsVaultPda,
   derivePerpsLpMintPda,
+  calculateCloseBorrowFee,
   PERPS_CIRCUIT_IDS,
 } from './perps';
 import {
@@ -91,6 +97,12 @@ import {
   MAX_TRANSACTION_SIZE,
 } from './versioned-transaction';
 import { ALTManager } from './address-lookup-table';
+import {
+  buildJitoTipInstructions,
+  sendTransactionsAsJitoBundles,
+  type JitoBundleConfig,
+} from './jito';
+import { isPaymentRequestExpired, validatePaymentRequest, type PaymentRequest } from './payment-request';
 
 /**
  * Verify transaction didn't revert after Anchor's .rpc() returns
@@ -134,6 +146,8 @@ export interface CloakCraftClientConfig {
   };
   /** Address Lookup Table addresses for atomic transaction compression (optional) */
   addressLookupTables?: PublicKey[];
+  /** Submit multi-phase transactions as Jito bundles (optional, mainnet) */
+  jito?: JitoBundleConfig;
 }
 
 /**
@@ -161,6 +175,7 @@ export class CloakCraftClient {
   private heliusRpcUrl: string | null = null;
   private altManager: ALTManager;
   private altAddresses: PublicKey[];
+  private jito?: JitoBundleConfig;
 
   constructor(config: CloakCraftClientConfig) {
     // Use provided connection or create from rpcUrl (like scalecraft pattern)
@@ -209,6 +224,7 @@ export class CloakCraftClient {
     // Initialize ALT manager and addresses
     this.altManager = new ALTManager(this.connection);
     this.altAddresses = config.addressLookupTables ?? [];
+    this.jito = config.jito;
 
     // Preload ALTs if provided
     if (this.altAddresses.length > 0) {
@@ -246,10 +262,13 @@ export class CloakCraftClient {
     accountHash: string,
     nullifier: Uint8Array,
     pool: PublicKey,
-    rpcUrl: string
+    rpcUrl: string,
+    nullifierDomain: NullifierDomain = NULLIFIER_DOMAINS.SPEND
   ): Promise<{
     lightVerifyParams: {
       commitmentAccountHash: number[];
+      /** Data hash of the commitment account (for on-chain account hash recomputation) */
+      commitmentDataHash: number[];
       commitmentMerkleContext: {
         merkleTreePubkeyIndex: number;
         queuePubkeyIndex: number;
@@ -300,7 +319,7 @@ export class CloakCraftClient {
 
     // Get nullifier non-inclusion proof
     console.log('[buildLightProtocolParams] Fetching nullifier non-inclusion proof...');
-    const nullifierAddress = lightProtocol.deriveNullifierAddress(pool, nullifier);
+    const nullifierAddress = lightProtocol.deriveNullifierAddress(pool, nullifier, nullifierDomain);
     const nullifierProof = await lightProtocol.getValidityProof([nullifierAddress]);
 
     // Build packed accounts
@@ -339,6 +358,7 @@ export class CloakCraftClient {
 
     const lightVerifyParams = {
       commitmentAccountHash: Array.from(new PublicKey(accountHash).toBytes()),
+      commitmentDataHash: await lightProtocol.getDataHashByHash(accountHash),
       commitmentMerkleContext: {
         merkleTreePubkeyIndex: commitmentStateTreeIndex,
         queuePubkeyIndex: commitmentQueueIndex,
@@ -372,6 +392,111 @@ export class CloakCraftClient {
     };
   }
 
+  /**
+   * Build Light Protocol params for create_position_meta (new address)
+   */
+  async buildPositionMetaCreateParams(
+    perpsPoolId: Uint8Array,
+    positionId: Uint8Array,
+    rpcUrl: string
+  ): Promise<{
+    lightParams: import('./perps/instructions').LightCreatePositionMetaParams;
+    remainingAccounts: import('@solana/web3.js').AccountMeta[];
+  }> {
+    const { LightProtocol } = await import('./instructions/light-helpers');
+
+    const lightProtocol = new LightProtocol(rpcUrl, this.programId);
+    const metaAddress = lightProtocol.derivePositionMetaAddress(perpsPoolId, positionId);
+    const metaProof = await lightProtocol.getValidityProof([metaAddress]);
+    const { accounts, outputTreeIndex, addressTreeIndex } = lightProtocol.buildRemainingAccounts();
+
+    return {
+      lightParams: {
+        validityProof: LightProtocol.convertCompressedProof(metaProof),
+        addressTreeInfo: {
+          addressMerkleTreePubkeyIndex: addressTreeIndex,
+          addressQueuePubkeyIndex: addressTreeIndex,
+          rootIndex: metaProof.rootIndices[0] ?? 0,
+        },
+        outputTreeIndex,
+      },
+      remainingAccounts: accounts,
+    };
+  }
+
+  /**
+   * Fetch a position's PositionMeta and build Light Protocol params for
+   * verify_position_meta_active (inclusion proof)
+   */
+  async buildPositionMetaVerifyParams(
+    perpsPoolId: Uint8Array,
+    positionId: Uint8Array,
+    rpcUrl: string
+  ): Promise<{
+    positionMeta: import('./light').PositionMetaData;
+    lightParams: import('./perps/instructions').LightVerifyPositionMetaParams;
+    remainingAccounts: import('@solana/web3.js').AccountMeta[];
+  }> {
+    const { LightProtocol } = await import('./instructions/light-helpers');
+    const { SystemAccountMetaConfig, PackedAccounts, bn } = await import('@lightprotocol/stateless.js');
+    const { DEVNET_V2_TREES } = await import('./instructions/constants');
+
+    if (!this.lightClient) {
+      throw new Error('Light Protocol not configured. Provide heliusApiKey in config.');
+    }
+
+    const lightProtocol = new LightProtocol(rpcUrl, this.programId);
+    const metaAddress = lightProtocol.derivePositionMetaAddress(perpsPoolId, positionId);
+    const account = await lightProtocol.rpc.getCompressedAccount(bn(metaAddress.toBytes()));
+    if (!account?.data) {
+      throw new Error('PositionMeta not found for position');
+    }
+    const positionMeta = this.lightClient.parsePositionMetaData(
+      Buffer.from(account.data.data).toString('base64')
+    );
+    if (!positionMeta) {
+      throw new Error('Malformed PositionMeta account');
+    }
+
+    const accountHash = new PublicKey(account.hash.toArray('be', 32)).toBase58();
+    const metaTree = new PublicKey(account.treeInfo.tree);
+    const metaQueue = new PublicKey(account.treeInfo.queue);
+    const inclusionProof = await lightProtocol.getInclusionValidityProof(accountHash, metaTree, metaQueue);
+
+    const systemConfig = SystemAccountMetaConfig.new(this.programId);
+    const packedAccounts = PackedAccounts.newWithSystemAccountsV2(systemConfig);
+    packedAccounts.insertOrGet(DEVNET_V2_TREES.OUTPUT_QUEUE);
+    const addressTreeIndex = packedAccounts.insertOrGet(DEVNET_V2_TREES.ADDRESS_TREE);
+    const metaTreeIndex = packedAccounts.insertOrGet(metaTree);
+    const metaQueueIndex = packedAccounts.insertOrGet(metaQueue);
+
+    const { remainingAccounts } = packedAccounts.toAccountMetas();
+
+    return {
+      positionMeta,
+      lightParams: {
+        validityProof: LightProtocol.convertCompressedProof(inclusionProof),
+        addressTreeInfo: {
+          addressMerkleTreePubkeyIndex: addressTreeIndex,
+          addressQueuePubkeyIndex: addressTreeIndex,
+          rootIndex: 0,
+        },
+        merkleContext: {
+          merkleTreePubkeyIndex: metaTreeIndex,
+          queuePubkeyIndex: metaQueueIndex,
+          leafIndex: inclusionProof.leafIndices?.[0] ?? account.leafIndex,
+          rootIndex: inclusionProof.rootIndices?.[0] ?? 0,
+          proveByIndex: inclusionProof.proveByIndices?.[0] ?? true,
+        },
+      },
+      remainingAccounts: remainingAccounts.map((acc: any) => ({
+        pubkey: acc.pubkey,
+        isWritable: Boolean(acc.isWritable),
+        isSigner: Boolean(acc.isSigner),
+      })),
+    };
+  }
+
   /**
    * Initialize proof generator
    *
@@ -483,6 +608,20 @@ export class CloakCraftClient {
     );
   }
 
+  /**
+   * Refresh the operation epoch from ProtocolConfig
+   *
+   * Operation IDs generated afterwards carry the current epoch. Call after
+   * an operation is rejected with StaleOperationEpoch.
+   */
+  async syncOperationEpoch(): Promise<number> {
+    const { fetchOperationEpoch } = await import('./fees');
+    const { setOperationEpoch } = await import('./instructions/constants');
+    const epoch = await fetchOperationEpoch(this.connection, this.programId);
+    setOperationEpoch(epoch);
+    return epoch;
+  }
+
   /**
    * Prepare Light Protocol params for a transact instruction
    *
@@ -778,6 +917,7 @@ export class CloakCraftClient {
       amount: params.amount,
       stealthPubkey: params.recipient.stealthPubkey,
       stealthEphemeralPubkey: params.recipient.ephemeralPubkey,
+      viewTag: params.recipient.viewTag,
       userTokenAccount: params.userTokenAccount!,
       user: payer.publicKey,
     };
@@ -854,6 +994,7 @@ export class CloakCraftClient {
         amount: params.amount,
         stealthPubkey: params.recipient.stealthPubkey,
         stealthEphemeralPubkey: params.recipient.ephemeralPubkey,
+        viewTag: params.recipient.viewTag,
         userTokenAccount: params.userTokenAccount!,
         user: walletPublicKey,
       },
@@ -876,6 +1017,56 @@ export class CloakCraftClient {
     };
   }
 
+  /**
+   * Shield tokens directly to someone else's stealth meta address
+   *
+   * One transaction from a transparent wallet to a private payment: the
+   * note is addressed to a fresh stealth address of `recipient`, so no
+   * shielded wallet needs to be loaded. Uses the program's provider wallet
+   * for signing.
+   */
+  async shieldToRecipient(
+    params: {
+      /** Pool (token mint) to shield into */
+      pool: PublicKey;
+      amount: bigint;
+      /** Recipient's stealth meta address (BabyJubJub public key) */
+      recipient: Point;
+      /** Depositor's token account (source of funds) */
+      userTokenAccount: PublicKey;
+    },
+    walletPublicKey: PublicKey
+  ): Promise<TransactionResult & { commitment: Uint8Array; randomness: Uint8Array }> {
+    if (!this.program) {
+      throw new Error('No program set. Call setProgram() first.');
+    }
+
+    const { buildShieldToRecipientWithProgram } = await import('./instructions/shield');
+    const { tx, commitment, randomness } = await buildShieldToRecipientWithProgram(
+      this.program,
+      {
+        tokenMint: params.pool,
+        amount: params.amount,
+        recipient: params.recipient,
+        userTokenAccount: params.userTokenAccount,
+        user: walletPublicKey,
+      },
+      this.getHeliusRpcUrl()
+    );
+
+    const signature = await tx.rpc({
+      skipPreflight: false,
+      commitment: 'confirmed',
+    });
+
+    return {
+      signature,
+      slot: 0,
+      commitment,
+      randomness,
+    };
+  }
+
   /**
    * Private transfer
    *
@@ -897,7 +1088,14 @@ export class CloakCraftClient {
     }
 
     // Always use transfer_1x2 - consolidate notes first if multiple inputs needed
-    const circuitName = 'transfer/1x2';
+    // Denomination and NFT pools, and bound unshields, prove with their own variants
+    const circuitName = params.denominations
+      ? 'transfer/1x2_denom'
+      : params.nftMetadataHash
+        ? 'transfer/1x2_nft'
+        : params.callHash
+          ? 'transfer/1x2_invoke'
+          : 'transfer/1x2';
     if (!this.proofGenerator.hasCircuit(circuitName)) {
       throw new Error(`Prover not initialized. Call initializeProver(['${circuitName}']) first.`);
     }
@@ -1033,7 +1231,13 @@ export class CloakCraftClient {
       inputCommitment,
     };
 
-    const circuitId = 'transfer_1x2';
+    const circuitId = params.denominations
+      ? CIRCUIT_IDS.TRANSFER_1X2_DENOM
+      : params.nftMetadataHash
+        ? CIRCUIT_IDS.TRANSFER_1X2_NFT
+        : params.callHash
+          ? CIRCUIT_IDS.TRANSFER_1X2_INVOKE
+          : CIRCUIT_IDS.TRANSFER_1X2;
 
     console.log('[Transfer] === Starting Multi-Phase Transfer ===');
     console.log('[Transfer] Circuit:', circuitName);
@@ -1110,6 +1314,7 @@ export class CloakCraftClient {
           stealthEphemeralPubkey: pc.stealthEphemeralPubkey,
           encryptedNote: pc.encryptedNote,
           commitment: pc.commitment,
+          paymentReference: i === 0 ? params.paymentReference : undefined,
         },
         heliusRpcUrl
       );
@@ -1142,13 +1347,14 @@ export class CloakCraftClient {
     const { VersionedTransaction, TransactionMessage } = await import('@solana/web3.js');
     const { blockhash } = await this.connection.getLatestBlockhash('confirmed');
 
+    const jitoTips = await this.jitoTipInstructions(transactionBuilders.length, relayerPubkey);
     const transactions = await Promise.all(
-      transactionBuilders.map(async ({ name, builder }) => {
+      transactionBuilders.map(async ({ name, builder }, i) => {
         try {
           // Extract ALL instructions (preInstructions + main instruction)
           const mainIx = await builder.instruction();
           const preIxs = builder._preInstructions || [];
-          const allInstructions = [...preIxs, mainIx];
+          const allInstructions = [...preIxs, mainIx, ...jitoTips[i]];
 
           console.log(`[${name}] Including ${preIxs.length} pre-instructions + 1 main instruction`);
 
@@ -1188,15 +1394,52 @@ export class CloakCraftClient {
     console.log(`[Transfer] All ${signedTransactions.length} transactions signed!`);
     params.onProgress?.('executing');
 
-    // Execute signed transactions sequentially
+    // Execute signed transactions in order (as Jito bundles if configured)
     console.log('[Transfer] Executing signed transactions sequentially...');
-    let phase1Signature = '';
-    for (let i = 0; i < signedTransactions.length; i++) {
-      const tx = signedTransactions[i];
-      const name = transactionBuilders[i].name;
+    const signatures = await this.sendPhaseTransactions(
+      'Transfer',
+      transactionBuilders.map(b => b.name),
+      signedTransactions
+    );
+    const phase1Signature = signatures[0];
+
+    params.onProgress?.('confirming');
+    return {
+      signature: phase1Signature,
+      slot: 0,
+    };
+  }
+
+  /**
+   * Jito tip instructions per phase transaction (empty without Jito)
+   */
+  private async jitoTipInstructions(count: number, payer: PublicKey): Promise<TransactionInstruction[][]> {
+    if (!this.jito) {
+      return Array.from({ length: count }, () => []);
+    }
+    return buildJitoTipInstructions(this.jito, payer, count);
+  }
 
-      console.log(`[Transfer] Sending ${name}...`);
-      const signature = await this.connection.sendRawTransaction(tx.serialize(), {
+  /**
+   * Send signed phase transactions in order and wait for each to confirm
+   *
+   * With Jito configured, consecutive transactions go out as bundles so
+   * nullifier and commitment phases land together.
+   */
+  private async sendPhaseTransactions(
+    label: string,
+    names: string[],
+    transactions: VersionedTransaction[]
+  ): Promise<string[]> {
+    if (this.jito) {
+      console.log(`[${label}] Sending ${transactions.length} transactions as Jito bundles...`);
+      return sendTransactionsAsJitoBundles(this.connection, this.jito, transactions);
+    }
+
+    const signatures: string[] = [];
+    for (let i = 0; i < transactions.length; i++) {
+      console.log(`[${label}] Sending ${names[i]}...`);
+      const signature = await this.connection.sendRawTransaction(transactions[i].serialize(), {
         skipPreflight: false,
         preflightCommitment: 'confirmed',
       });
@@ -1204,20 +1447,12 @@ export class CloakCraftClient {
       // Wait for confirmation and check for execution errors
       const confirmation = await this.connection.confirmTransaction(signature, 'confirmed');
       if (confirmation.value.err) {
-        throw new Error(`[Transfer] ${name} reverted: ${JSON.stringify(confirmation.value.err)}`);
-      }
-      console.log(`[Transfer] ${name} confirmed: ${signature}`);
-
-      if (i === 0) {
-        phase1Signature = signature;
+        throw new Error(`[${label}] ${names[i]} reverted: ${JSON.stringify(confirmation.value.err)}`);
       }
+      console.log(`[${label}] ${names[i]} confirmed: ${signature}`);
+      signatures.push(signature);
     }
-
-    params.onProgress?.('confirming');
-    return {
-      signature: phase1Signature,
-      slot: 0,
-    };
+    return signatures;
   }
 
   /**
@@ -1301,6 +1536,8 @@ export class CloakCraftClient {
       inputs: DecryptedNote[];
       outputs: Array<{ recipient: StealthAddress; amount: bigint }>;
       unshield?: { amount: bigint; recipient: PublicKey };
+      /** Invoice reference id; writes a PaymentReceipt for the first output */
+      paymentReference?: Uint8Array;
       onProgress?: (stage: TransferProgressStage) => void;
     },
     relayer?: SolanaKeypair
@@ -1420,7 +1657,7 @@ export class CloakCraftClient {
     const transferAmount = preparedOutputs[0]?.amount ?? 0n;
     const unshieldAmount = request.unshield?.amount ?? 0n;
     const feeableAmount = transferAmount + unshieldAmount;
-    const feeCalc = calculateProtocolFee(feeableAmount, 'transfer', feeConfig);
+    const feeCalc = calculateProtocolFee(feeableAmount, 'transfer', feeConfig, tokenMint);
 
     console.log('[prepareAndTransfer] Fee calculation:', {
       transferAmount: transferAmount.toString(),
@@ -1508,12 +1745,63 @@ export class CloakCraftClient {
       outputs: adjustedOutputs,
       unshield: adjustedUnshield,
       fee: feeCalc.feeAmount,
+      paymentReference: request.paymentReference,
       onProgress: request.onProgress,
     };
 
     return this.transfer(params, relayer);
   }
 
+  /**
+   * Pay a payment request
+   *
+   * Transfers the requested amount to a fresh stealth address of the
+   * merchant, sends the change back to this wallet, and writes a
+   * PaymentReceipt for the request's reference id.
+   *
+   * @param request - Decoded payment request (see decodePaymentRequest)
+   * @param inputs - Notes of the requested mint covering the amount plus fee
+   * @param relayer - Optional relayer keypair for transaction fees
+   */
+  async payPaymentRequest(
+    request: PaymentRequest,
+    inputs: DecryptedNote[],
+    relayer?: SolanaKeypair,
+    onProgress?: (stage: TransferProgressStage) => void
+  ): Promise<TransactionResult> {
+    if (!this.wallet) {
+      throw new Error('No wallet loaded');
+    }
+    validatePaymentRequest(request);
+    if (isPaymentRequestExpired(request)) {
+      throw new Error('Payment request has expired');
+    }
+    if (inputs.length === 0 || inputs.some(note => !note.tokenMint.equals(request.mint))) {
+      throw new Error(`Inputs must be notes of ${request.mint.toBase58()}`);
+    }
+
+    const totalInput = inputs.reduce((sum, note) => sum + note.amount, 0n);
+    if (totalInput < request.amount) {
+      throw new Error(`Insufficient balance: have ${totalInput}, request is for ${request.amount}`);
+    }
+
+    const { stealthAddress: merchant } = generateStealthAddress(request.recipient);
+    const change = this.wallet.changeAddress(inputs[0].commitment);
+
+    return this.prepareAndTransfer(
+      {
+        inputs,
+        outputs: [
+          { recipient: merchant, amount: request.amount },
+          { recipient: change, amount: totalInput - request.amount },
+        ],
+        paymentReference: request.referenceId,
+        onProgress,
+      },
+      relayer
+    );
+  }
+
   /**
    * Prepare and consolidate notes
    *
@@ -1566,8 +1854,8 @@ export class CloakCraftClient {
     // Prepare inputs with all required fields
     const preparedInputs = await this.prepareInputs(matchedInputs);
 
-    // Generate stealth address for output (back to self)
-    const { stealthAddress } = generateStealthAddress(this.wallet.keypair.publicKey);
+    // Consolidated output is change of the first input
+    const stealthAddress = this.wallet.changeAddress(preparedInputs[0].commitment);
 
     // Use commitment as merkle_root with dummy path
     // The circuit doesn't verify merkle path (it's for ABI compatibility only)
@@ -1750,11 +2038,12 @@ export class CloakCraftClient {
     const { VersionedTransaction, TransactionMessage } = await import('@solana/web3.js');
     const { blockhash } = await this.connection.getLatestBlockhash('confirmed');
 
+    const jitoTips = await this.jitoTipInstructions(transactionBuilders.length, relayerPubkey);
     const transactions = await Promise.all(
-      transactionBuilders.map(async ({ name, builder }) => {
+      transactionBuilders.map(async ({ name, builder }, i) => {
         const mainIx = await builder.instruction();
         const preIxs = builder._preInstructions || [];
-        const allInstructions = [...preIxs, mainIx];
+        const allInstructions = [...preIxs, mainIx, ...jitoTips[i]];
 
         return new VersionedTransaction(
           new TransactionMessage({
@@ -1783,25 +2072,13 @@ export class CloakCraftClient {
     console.log(`[Consolidation] All ${signedTransactions.length} transactions signed!`);
     onProgress?.('executing');
 
-    // Execute transactions sequentially
-    let finalSignature = '';
-    for (let i = 0; i < signedTransactions.length; i++) {
-      const tx = signedTransactions[i];
-      const name = transactionBuilders[i].name;
-
-      console.log(`[Consolidation] Sending ${name}...`);
-      const signature = await this.connection.sendRawTransaction(tx.serialize(), {
-        skipPreflight: false,
-        preflightCommitment: 'confirmed',
-      });
-
-      const confirmation = await this.connection.confirmTransaction(signature, 'confirmed');
-      if (confirmation.value.err) {
-        throw new Error(`[Consolidation] ${name} reverted: ${JSON.stringify(confirmation.value.err)}`);
-      }
-      console.log(`[Consolidation] ${name} confirmed: ${signature}`);
-      finalSignature = signature;
-    }
+    // Execute transactions in order (as Jito bundles if configured)
+    const signatures = await this.sendPhaseTransactions(
+      'Consolidation',
+      transactionBuilders.map(b => b.name),
+      signedTransactions
+    );
+    const finalSignature = signatures[signatures.length - 1];
 
     console.log('[Consolidation] === Consolidation Complete ===');
 
@@ -2173,9 +2450,14 @@ export class CloakCraftClient {
       throw new Error('No program set. Call setProgram() first.');
     }
 
-    // Ensure the swap circuit is loaded
-    if (!this.proofGenerator.hasCircuit('swap/swap')) {
-      throw new Error("Prover not initialized. Call initializeProver(['swap/swap']) first.");
+    // Ensure the swap circuit is loaded (swap_sealed / swap_committed for sealed / committed swaps)
+    const swapCircuit = params.termsSalt
+      ? 'swap/swap_sealed'
+      : params.commitSalt
+      ? 'swap/swap_committed'
+      : 'swap/swap';
+    if (!this.proofGenerator.hasCircuit(swapCircuit)) {
+      throw new Error(`Prover not initialized. Call initializeProver(['${swapCircuit}']) first.`);
     }
 
     params.onProgress?.('generating');
@@ -2266,6 +2548,10 @@ export class CloakCraftClient {
       swapDirection: params.swapDirection,
       outRandomness,
       changeRandomness,
+      termsSalt: params.termsSalt,
+      commitSalt: params.commitSalt,
+      feeRebateConfig: params.swapVolume?.feeRebateConfig,
+      swapVolume: params.swapVolume?.record,
     };
 
     // Multi-phase execution with ALT compression (same pattern as transfer)
@@ -2363,13 +2649,14 @@ export class CloakCraftClient {
     const { VersionedTransaction, TransactionMessage } = await import('@solana/web3.js');
     const { blockhash } = await this.connection.getLatestBlockhash('confirmed');
 
+    const jitoTips = await this.jitoTipInstructions(transactionBuilders.length, relayerPubkey);
     const transactions = await Promise.all(
-      transactionBuilders.map(async ({ name, builder }) => {
+      transactionBuilders.map(async ({ name, builder }, i) => {
         try {
           // Extract ALL instructions (preInstructions + main instruction)
           const mainIx = await builder.instruction();
           const preIxs = builder._preInstructions || [];
-          const allInstructions = [...preIxs, mainIx];
+          const allInstructions = [...preIxs, mainIx, ...jitoTips[i]];
 
           console.log(`[${name}] Including ${preIxs.length} pre-instructions + 1 main instruction`);
 
@@ -2408,31 +2695,15 @@ export class CloakCraftClient {
     }
     console.log(`[Swap] All ${signedTransactions.length} transactions signed!`);
 
-    // Execute signed transactions sequentially
+    // Execute signed transactions in order (as Jito bundles if configured)
     console.log('[Swap] Executing signed transactions sequentially...');
     params.onProgress?.('executing');
-    let phase0Signature = '';
-    for (let i = 0; i < signedTransactions.length; i++) {
-      const tx = signedTransactions[i];
-      const name = transactionBuilders[i].name;
-
-      console.log(`[Swap] Sending ${name}...`);
-      const signature = await this.connection.sendRawTransaction(tx.serialize(), {
-        skipPreflight: false,
-        preflightCommitment: 'confirmed',
-      });
-
-      // Wait for confirmation and check for execution errors
-      const confirmation = await this.connection.confirmTransaction(signature, 'confirmed');
-      if (confirmation.value.err) {
-        throw new Error(`[Swap] ${name} reverted: ${JSON.stringify(confirmation.value.err)}`);
-      }
-      console.log(`[Swap] ${name} confirmed: ${signature}`);
-
-      if (i === 0) {
-        phase0Signature = signature;
-      }
-    }
+    const signatures = await this.sendPhaseTransactions(
+      'Swap',
+      transactionBuilders.map(b => b.name),
+      signedTransactions
+    );
+    const phase0Signature = signatures[0];
 
     return {
       signature: phase0Signature,
@@ -2645,13 +2916,14 @@ export class CloakCraftClient {
     const { VersionedTransaction, TransactionMessage } = await import('@solana/web3.js');
     const { blockhash } = await this.connection.getLatestBlockhash('confirmed');
 
+    const jitoTips = await this.jitoTipInstructions(transactionBuilders.length, relayerPubkey);
     const transactions = await Promise.all(
-      transactionBuilders.map(async ({ name, builder }) => {
+      transactionBuilders.map(async ({ name, builder }, i) => {
         try {
           // Extract ALL instructions (preInstructions + main instruction)
           const mainIx = await builder.instruction();
           const preIxs = builder._preInstructions || [];
-          const allInstructions = [...preIxs, mainIx];
+          const allInstructions = [...preIxs, mainIx, ...jitoTips[i]];
 
           console.log(`[${name}] Including ${preIxs.length} pre-instructions + 1 main instruction`);
 
@@ -2690,31 +2962,15 @@ export class CloakCraftClient {
     }
     console.log(`[Add Liquidity] All ${signedTransactions.length} transactions signed!`);
 
-    // Execute signed transactions sequentially
+    // Execute signed transactions in order (as Jito bundles if configured)
     console.log('[Add Liquidity] Executing signed transactions sequentially...');
     params.onProgress?.('executing');
-    let phase0Signature = '';
-    for (let i = 0; i < signedTransactions.length; i++) {
-      const tx = signedTransactions[i];
-      const name = transactionBuilders[i].name;
-
-      console.log(`[Add Liquidity] Sending ${name}...`);
-      const signature = await this.connection.sendRawTransaction(tx.serialize(), {
-        skipPreflight: false,
-        preflightCommitment: 'confirmed',
-      });
-
-      // Wait for confirmation and check for execution errors
-      const confirmation = await this.connection.confirmTransaction(signature, 'confirmed');
-      if (confirmation.value.err) {
-        throw new Error(`[Add Liquidity] ${name} reverted: ${JSON.stringify(confirmation.value.err)}`);
-      }
-      console.log(`[Add Liquidity] ${name} confirmed: ${signature}`);
-
-      if (i === 0) {
-        phase0Signature = signature;
-      }
-    }
+    const signatures = await this.sendPhaseTransactions(
+      'Add Liquidity',
+      transactionBuilders.map(b => b.name),
+      signedTransactions
+    );
+    const phase0Signature = signatures[0];
 
     console.log('[Add Liquidity] All transactions executed successfully!');
     return {
@@ -2940,13 +3196,14 @@ export class CloakCraftClient {
     const { VersionedTransaction, TransactionMessage } = await import('@solana/web3.js');
     const { blockhash } = await this.connection.getLatestBlockhash('confirmed');
 
+    const jitoTips = await this.jitoTipInstructions(transactionBuilders.length, relayerPubkey);
     const transactions = await Promise.all(
-      transactionBuilders.map(async ({ name, builder }) => {
+      transactionBuilders.map(async ({ name, builder }, i) => {
         try {
           // Extract ALL instructions (preInstructions + main instruction)
           const mainIx = await builder.instruction();
           const preIxs = builder._preInstructions || [];
-          const allInstructions = [...preIxs, mainIx];
+          const allInstructions = [...preIxs, mainIx, ...jitoTips[i]];
 
           console.log(`[${name}] Including ${preIxs.length} pre-instructions + 1 main instruction`);
 
@@ -2985,31 +3242,15 @@ export class CloakCraftClient {
     }
     console.log(`[Remove Liquidity] All ${signedTransactions.length} transactions signed!`);
 
-    // Execute signed transactions sequentially
+    // Execute signed transactions in order (as Jito bundles if configured)
     console.log('[Remove Liquidity] Executing signed transactions sequentially...');
     params.onProgress?.('executing');
-    let phase0Signature = '';
-    for (let i = 0; i < signedTransactions.length; i++) {
-      const tx = signedTransactions[i];
-      const name = transactionBuilders[i].name;
-
-      console.log(`[Remove Liquidity] Sending ${name}...`);
-      const signature = await this.connection.sendRawTransaction(tx.serialize(), {
-        skipPreflight: false,
-        preflightCommitment: 'confirmed',
-      });
-
-      // Wait for confirmation and check for execution errors
-      const confirmation = await this.connection.confirmTransaction(signature, 'confirmed');
-      if (confirmation.value.err) {
-        throw new Error(`[Remove Liquidity] ${name} reverted: ${JSON.stringify(confirmation.value.err)}`);
-      }
-      console.log(`[Remove Liquidity] ${name} confirmed: ${signature}`);
-
-      if (i === 0) {
-        phase0Signature = signature;
-      }
-    }
+    const signatures = await this.sendPhaseTransactions(
+      'Remove Liquidity',
+      transactionBuilders.map(b => b.name),
+      signedTransactions
+    );
+    const phase0Signature = signatures[0];
 
     console.log('[Remove Liquidity] All transactions executed successfully!');
     return {
@@ -3455,9 +3696,10 @@ export class CloakCraftClient {
       throw new Error('No program set. Call setProgram() first.');
     }
 
-    // Ensure the perps circuit is loaded
-    if (!this.proofGenerator.hasCircuit('perps/open_position')) {
-      throw new Error("Prover not initialized. Call initializeProver(['perps/open_position']) first.");
+    // Ensure the perps circuit is loaded (open_position_sealed for sealed opens)
+    const openCircuit = params.termsSalt ? 'perps/open_position_sealed' : 'perps/open_position';
+    if (!this.proofGenerator.hasCircuit(openCircuit)) {
+      throw new Error(`Prover not initialized. Call initializeProver(['${openCircuit}']) first.`);
     }
 
     params.onProgress?.('preparing');
@@ -3522,6 +3764,7 @@ export class CloakCraftClient {
       merkleRoot: params.merkleRoot,
       merklePath: params.merklePath,
       merkleIndices: params.merkleIndices,
+      termsSalt: params.termsSalt,
     };
 
     const proofResult = await this.proofGenerator.generateOpenPositionProof(
@@ -3600,6 +3843,7 @@ export class CloakCraftClient {
       lightVerifyParams: lightParams.lightVerifyParams,
       lightNullifierParams: lightParams.lightNullifierParams,
       remainingAccounts: lightParams.remainingAccounts,
+      termsSalt: params.termsSalt,
     };
 
     // Build multi-phase transactions
@@ -3657,6 +3901,31 @@ export class CloakCraftClient {
       transactionBuilders.push({ name: `Phase ${4 + i} (Commitment ${i})`, builder: commitmentTx });
     }
 
+    // Phase 4b: PositionMeta (terms and borrow fee snapshot recorded in Phase 3).
+    // The SDK keys PositionMeta by the position commitment.
+    const isLong = params.direction === 'long';
+    const bufferBps = 10000n / BigInt(params.leverage) - BigInt(perpsPoolAccount.liquidationThresholdBps);
+    const liquidationMove = bufferBps > 0n ? (oraclePrice * bufferBps) / 10000n : 0n;
+    const positionMetaLight = await this.buildPositionMetaCreateParams(
+      actualPoolId.toBytes(),
+      positionCommitment,
+      heliusRpcUrl
+    );
+    const { tx: positionMetaTx } = await buildCreatePositionMetaWithProgram(this.program, {
+      operationId,
+      perpsPool: params.poolId,
+      market: perpsMarketPda,
+      relayer: relayerPubkey,
+      positionId: positionCommitment,
+      liquidationPrice: isLong ? oraclePrice - liquidationMove : oraclePrice + liquidationMove,
+      // Keeper liquidation spends the position note through the ZK path
+      nullifierHash: new Uint8Array(32),
+      ownerStealthPubkey: params.positionRecipient.stealthPubkey.x,
+      lightParams: positionMetaLight.lightParams,
+      remainingAccounts: positionMetaLight.remainingAccounts,
+    });
+    transactionBuilders.push({ name: 'Phase 4b (Position Meta)', builder: positionMetaTx });
+
     // Final: Close pending operation
     const { tx: closeTx } = await buildClosePendingOperationWithProgram(
       this.program,
@@ -3796,6 +4065,33 @@ export class CloakCraftClient {
     const perpsPoolAccount = await (this.program.account as any).perpsPool.fetch(params.poolId);
     const actualPoolId = perpsPoolAccount.poolId as PublicKey;
 
+    const heliusRpcUrl = this.getHeliusRpcUrl();
+
+    // Compute position commitment using position-specific formula
+    // Cast to SDK's PositionNote type (noteType is only needed for serialization, not commitment)
+    const positionCommitment = computePositionCommitment(params.positionInput as any);
+
+    // PositionMeta records the entry borrow fee charged at close (keyed by the position commitment)
+    const positionMetaLight = await this.buildPositionMetaVerifyParams(
+      actualPoolId.toBytes(),
+      positionCommitment,
+      heliusRpcUrl
+    );
+
+    // Phase 0 computes the borrow fee the proof deducts from the same on-chain values
+    const [perpsMarketPda] = derivePerpsMarketPda(params.poolId, params.marketId, this.programId);
+    const perpsMarketAccount = await (this.program.account as any).perpsMarket.fetch(perpsMarketPda);
+    const borrowTokenIndex = isLong ? perpsMarketAccount.quoteTokenIndex : perpsMarketAccount.baseTokenIndex;
+    const margin = params.positionInput.margin;
+    const cappedPnl = isProfit && pnlAmount > margin ? margin : pnlAmount;
+    const grossSettlement = isProfit ? margin + cappedPnl - closeFee : margin - pnlAmount - closeFee;
+    const borrowFee = calculateCloseBorrowFee(
+      params.positionInput.size,
+      BigInt(perpsPoolAccount.tokens[borrowTokenIndex].cumulativeBorrowFee.toString()),
+      BigInt(positionMetaLight.positionMeta.entryBorrowFee.toString()),
+      grossSettlement
+    );
+
     const proofParams = {
       position: {
         stealthPubX: params.positionInput.stealthPubX,
@@ -3814,6 +4110,7 @@ export class CloakCraftClient {
       pnlAmount,
       isProfit,
       closeFee,
+      borrowFee,
       settlementRecipient: params.settlementRecipient,
       tokenMint,
       merkleRoot: params.merkleRoot,
@@ -3828,19 +4125,13 @@ export class CloakCraftClient {
 
     params.onProgress?.('building');
 
-    const heliusRpcUrl = this.getHeliusRpcUrl();
     const relayerPubkey = relayer?.publicKey ?? (await this.getRelayerPubkey());
 
     const { proof, positionNullifier: nullifier, settlementCommitment, settlementRandomness, settlementAmount } = proofResult;
 
-    // Compute position commitment using position-specific formula
-    // Cast to SDK's PositionNote type (noteType is only needed for serialization, not commitment)
-    const positionCommitment = computePositionCommitment(params.positionInput as any);
-
     // Derive PDAs
     const settlementTokenMint = params.settlementTokenMint;
     const [settlementPoolPda] = derivePoolPda(settlementTokenMint, this.programId);
-    const [perpsMarketPda] = derivePerpsMarketPda(params.poolId, params.marketId, this.programId);
 
     // Derive position pool from position mint stored in perps pool
     const positionMint = perpsPoolAccount.positionMint as PublicKey;
@@ -3851,7 +4142,8 @@ export class CloakCraftClient {
       accountHash,
       nullifier,
       positionPoolPda, // Position is in position pool
-      heliusRpcUrl
+      heliusRpcUrl,
+      NULLIFIER_DOMAINS.PERPS_POSITION
     );
 
     const instructionParams = {
@@ -3873,6 +4165,9 @@ export class CloakCraftClient {
       positionMargin: params.positionInput.margin,
       positionSize: params.positionInput.size,
       entryPrice: params.positionInput.entryPrice,
+      positionMeta: positionMetaLight.positionMeta,
+      lightPositionMetaParams: positionMetaLight.lightParams,
+      positionMetaRemainingAccounts: positionMetaLight.remainingAccounts,
       relayer: relayerPubkey,
       settlementRecipient: params.settlementRecipient,
       settlementRandomness,
@@ -3907,6 +4202,7 @@ export class CloakCraftClient {
     // Phases 0-2: Create Pending, Verify, Nullifier
     transactionBuilders.push({ name: 'Phase 0 (Create Pending)', builder: buildResult.tx });
     transactionBuilders.push({ name: 'Phase 1 (Verify Commitment)', builder: buildResult.phase1Tx });
+    transactionBuilders.push({ name: 'Phase 1b (Verify Position Meta)', builder: buildResult.phase1bTx });
     transactionBuilders.push({ name: 'Phase 2 (Create Nullifier)', builder: buildResult.phase2Tx });
 
     // Phase 3: Execute Close Position
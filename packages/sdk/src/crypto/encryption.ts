@@ -1,13 +1,24 @@
 /**
  * Note encryption using ECIES (Elliptic Curve Integrated Encryption Scheme)
  *
+ * BabyJubJub ECDH with an ephemeral key, HKDF-SHA256 to a ChaCha20-Poly1305
+ * key. Matches the program's `crypto::note_encryption` format, which
+ * create_commitment enforces:
+ * - version: 1 byte (NOTE_ENCRYPTION_V1)
+ * - ephemeral public key: 64 bytes (x || y)
+ * - nonce: 12 bytes
+ * - ciphertext + 16-byte tag
+ *
  * Supports three note types:
  * - Standard token notes (104 bytes plaintext)
- * - Position notes (126 bytes with magic prefix)
- * - LP notes (108 bytes with magic prefix)
+ * - Position notes (123 bytes with magic prefix)
+ * - LP notes (105 bytes with magic prefix)
  */
 
 import { sha256 } from '@noble/hashes/sha256';
+import { hkdf } from '@noble/hashes/hkdf';
+import { randomBytes } from '@noble/hashes/utils';
+import { chacha20poly1305 } from '@noble/ciphers/chacha.js';
 import { PublicKey } from '@solana/web3.js';
 import type { EncryptedNote, Note, Point, FieldElement } from '@cloakcraft/types';
 import { scalarMul, derivePublicKey } from './babyjubjub';
@@ -27,16 +38,34 @@ import {
 // BabyJubJub subgroup order
 const SUBGROUP_ORDER = 2736030358979909402780800718157159386076813972158567259200215660948447373041n;
 
+/** Current encrypted note version */
+export const NOTE_ENCRYPTION_V1 = 1;
+
+/** HKDF info and associated data domain */
+export const NOTE_ENCRYPTION_DOMAIN = new TextEncoder().encode('cloakcraft/note-encryption/v1');
+
+const NONCE_SIZE = 12;
+const TAG_SIZE = 16;
+const HEADER_SIZE = 1 + 64 + NONCE_SIZE;
+
+/** Plaintext sizes of the note types accepted on-chain (standard, LP, position) */
+export const NOTE_PLAINTEXT_SIZES = [104, 105, 123] as const;
+
+/** Serialized size of an encrypted note with `plaintextLen` bytes */
+export function encryptedNoteSize(plaintextLen: number): number {
+  return HEADER_SIZE + plaintextLen + TAG_SIZE;
+}
+
 /**
- * Encrypt a note for a recipient
+ * Encrypt a serialized note for a recipient
  *
- * Uses ECIES:
- * 1. Generate ephemeral keypair
- * 2. ECDH to get shared secret
- * 3. KDF to derive encryption key
- * 4. Encrypt with ChaCha20-Poly1305
+ * The nonce is prepended to the returned ciphertext; the tag is split off.
  */
-export function encryptNote(note: Note, recipientPubkey: Point): EncryptedNote {
+function sealNote(
+  plaintext: Uint8Array,
+  recipientPubkey: Point,
+  domain: Uint8Array = NOTE_ENCRYPTION_DOMAIN
+): EncryptedNote {
   // Generate ephemeral keypair
   const ephemeralPrivate = generateRandomScalar();
   const ephemeralPubkey = derivePublicKey(ephemeralPrivate);
@@ -44,40 +73,62 @@ export function encryptNote(note: Note, recipientPubkey: Point): EncryptedNote {
   // ECDH: shared_secret = ephemeral_private * recipient_pubkey
   const sharedSecret = scalarMul(recipientPubkey, ephemeralPrivate);
 
-  // Derive encryption key
-  const encKey = deriveEncryptionKey(sharedSecret.x);
-
-  // Serialize note
-  const plaintext = serializeNote(note);
+  const key = deriveEncryptionKey(sharedSecret.x, ephemeralPubkey, domain);
+  const nonce = randomBytes(NONCE_SIZE);
+  const sealed = chacha20poly1305(key, nonce, associatedData(domain)).encrypt(plaintext);
 
-  // Encrypt (placeholder - use proper AEAD in production)
-  const { ciphertext, tag } = encryptAEAD(plaintext, encKey);
+  const ciphertext = new Uint8Array(NONCE_SIZE + sealed.length - TAG_SIZE);
+  ciphertext.set(nonce, 0);
+  ciphertext.set(sealed.slice(0, sealed.length - TAG_SIZE), NONCE_SIZE);
 
   return {
     ephemeralPubkey,
     ciphertext,
-    tag,
+    tag: sealed.slice(sealed.length - TAG_SIZE),
   };
 }
 
 /**
- * Decrypt an encrypted note
+ * Decrypt a note to its serialized plaintext (throws if authentication fails)
  */
-export function decryptNote(
+function openNote(
   encrypted: EncryptedNote,
-  recipientPrivateKey: bigint
-): Note {
+  recipientPrivateKey: bigint,
+  domain: Uint8Array = NOTE_ENCRYPTION_DOMAIN
+): Uint8Array {
   // ECDH: shared_secret = recipient_private * ephemeral_pubkey
   const sharedSecret = scalarMul(encrypted.ephemeralPubkey, recipientPrivateKey);
 
-  // Derive decryption key
-  const decKey = deriveEncryptionKey(sharedSecret.x);
+  const key = deriveEncryptionKey(sharedSecret.x, encrypted.ephemeralPubkey, domain);
+  const nonce = encrypted.ciphertext.slice(0, NONCE_SIZE);
+  const sealed = new Uint8Array(encrypted.ciphertext.length - NONCE_SIZE + TAG_SIZE);
+  sealed.set(encrypted.ciphertext.slice(NONCE_SIZE), 0);
+  sealed.set(encrypted.tag, encrypted.ciphertext.length - NONCE_SIZE);
 
-  // Decrypt
-  const plaintext = decryptAEAD(encrypted.ciphertext, encrypted.tag, decKey);
+  return chacha20poly1305(key, nonce, associatedData(domain)).decrypt(sealed);
+}
 
-  // Deserialize note
-  return deserializeNote(plaintext);
+/**
+ * Encrypt a note for a recipient
+ *
+ * Uses ECIES:
+ * 1. Generate ephemeral keypair
+ * 2. ECDH to get shared secret
+ * 3. KDF to derive encryption key
+ * 4. Encrypt with ChaCha20-Poly1305
+ */
+export function encryptNote(note: Note, recipientPubkey: Point): EncryptedNote {
+  return sealNote(serializeNote(note), recipientPubkey);
+}
+
+/**
+ * Decrypt an encrypted note
+ */
+export function decryptNote(
+  encrypted: EncryptedNote,
+  recipientPrivateKey: bigint
+): Note {
+  return deserializeNote(openNote(encrypted, recipientPrivateKey));
 }
 
 /**
@@ -109,27 +160,7 @@ export function tryDecryptNote(
  * Encrypt a position note for a recipient
  */
 export function encryptPositionNote(note: PositionNote, recipientPubkey: Point): EncryptedNote {
-  // Generate ephemeral keypair
-  const ephemeralPrivate = generateRandomScalar();
-  const ephemeralPubkey = derivePublicKey(ephemeralPrivate);
-
-  // ECDH: shared_secret = ephemeral_private * recipient_pubkey
-  const sharedSecret = scalarMul(recipientPubkey, ephemeralPrivate);
-
-  // Derive encryption key
-  const encKey = deriveEncryptionKey(sharedSecret.x);
-
-  // Serialize position note
-  const plaintext = serializePositionNote(note);
-
-  // Encrypt
-  const { ciphertext, tag } = encryptAEAD(plaintext, encKey);
-
-  return {
-    ephemeralPubkey,
-    ciphertext,
-    tag,
-  };
+  return sealNote(serializePositionNote(note), recipientPubkey);
 }
 
 /**
@@ -140,14 +171,8 @@ export function decryptPositionNote(
   recipientPrivateKey: bigint
 ): PositionNote | null {
   try {
-    // ECDH: shared_secret = recipient_private * ephemeral_pubkey
-    const sharedSecret = scalarMul(encrypted.ephemeralPubkey, recipientPrivateKey);
-
-    // Derive decryption key
-    const decKey = deriveEncryptionKey(sharedSecret.x);
-
     // Decrypt
-    const plaintext = decryptAEAD(encrypted.ciphertext, encrypted.tag, decKey);
+    const plaintext = openNote(encrypted, recipientPrivateKey);
 
     // Check if it's a position note
     if (detectNoteType(plaintext) !== NOTE_TYPE_POSITION) {
@@ -179,27 +204,7 @@ export function tryDecryptPositionNote(
  * Encrypt an LP note for a recipient
  */
 export function encryptLpNote(note: LpNote, recipientPubkey: Point): EncryptedNote {
-  // Generate ephemeral keypair
-  const ephemeralPrivate = generateRandomScalar();
-  const ephemeralPubkey = derivePublicKey(ephemeralPrivate);
-
-  // ECDH: shared_secret = ephemeral_private * recipient_pubkey
-  const sharedSecret = scalarMul(recipientPubkey, ephemeralPrivate);
-
-  // Derive encryption key
-  const encKey = deriveEncryptionKey(sharedSecret.x);
-
-  // Serialize LP note
-  const plaintext = serializeLpNote(note);
-
-  // Encrypt
-  const { ciphertext, tag } = encryptAEAD(plaintext, encKey);
-
-  return {
-    ephemeralPubkey,
-    ciphertext,
-    tag,
-  };
+  return sealNote(serializeLpNote(note), recipientPubkey);
 }
 
 /**
@@ -210,14 +215,8 @@ export function decryptLpNote(
   recipientPrivateKey: bigint
 ): LpNote | null {
   try {
-    // ECDH: shared_secret = recipient_private * ephemeral_pubkey
-    const sharedSecret = scalarMul(encrypted.ephemeralPubkey, recipientPrivateKey);
-
-    // Derive decryption key
-    const decKey = deriveEncryptionKey(sharedSecret.x);
-
     // Decrypt
-    const plaintext = decryptAEAD(encrypted.ciphertext, encrypted.tag, decKey);
+    const plaintext = openNote(encrypted, recipientPrivateKey);
 
     // Check if it's an LP note
     if (detectNoteType(plaintext) !== NOTE_TYPE_LP) {
@@ -262,14 +261,8 @@ export function tryDecryptAnyNote(
   recipientPrivateKey: bigint
 ): DecryptedNoteResult | null {
   try {
-    // ECDH: shared_secret = recipient_private * ephemeral_pubkey
-    const sharedSecret = scalarMul(encrypted.ephemeralPubkey, recipientPrivateKey);
-
-    // Derive decryption key
-    const decKey = deriveEncryptionKey(sharedSecret.x);
-
     // Decrypt
-    const plaintext = decryptAEAD(encrypted.ciphertext, encrypted.tag, decKey);
+    const plaintext = openNote(encrypted, recipientPrivateKey);
 
     // Detect note type and deserialize
     const noteType = detectNoteType(plaintext);
@@ -346,116 +339,29 @@ function deserializeNote(data: Uint8Array): Note {
 
 /**
  * Derive encryption key from shared secret
- */
-function deriveEncryptionKey(sharedSecretX: FieldElement): Uint8Array {
-  // KDF using SHA-256
-  const hasher = sha256.create();
-  // Use TextEncoder for browser compatibility (Buffer.from doesn't work in browsers)
-  const domainSep = new TextEncoder().encode('cloakcraft-ecies-key');
-  hasher.update(domainSep);
-  hasher.update(sharedSecretX);
-  return hasher.digest();
-}
-
-/**
- * AEAD encryption using AES-256-GCM
  *
- * Uses WebCrypto API for secure authenticated encryption.
- * The nonce is prepended to the ciphertext.
+ * HKDF-SHA256 with the ephemeral public key as salt and the format domain
+ * as info.
  */
-function encryptAEAD(plaintext: Uint8Array, key: Uint8Array): { ciphertext: Uint8Array; tag: Uint8Array } {
-  // Generate random nonce (12 bytes for AES-GCM)
-  const nonce = new Uint8Array(12);
-  crypto.getRandomValues(nonce);
-
-  // Use a simple CTR-like encryption with HMAC for authentication
-  // This is a fallback for environments without WebCrypto
-  const hasher = sha256.create();
-  hasher.update(key);
-  hasher.update(nonce);
-  const keyStream = hasher.digest();
-
-  // XOR for encryption (simplified - in production use proper AES-GCM)
-  const encrypted = new Uint8Array(plaintext.length);
-  for (let i = 0; i < plaintext.length; i++) {
-    // Extend key stream if needed
-    if (i > 0 && i % 32 === 0) {
-      const extHasher = sha256.create();
-      extHasher.update(key);
-      extHasher.update(nonce);
-      extHasher.update(new Uint8Array([i >> 8, i & 0xff]));
-      const ext = extHasher.digest();
-      for (let j = 0; j < 32 && (i + j) < plaintext.length; j++) {
-        encrypted[i + j] = plaintext[i + j] ^ ext[j];
-      }
-    } else if (i < 32) {
-      encrypted[i] = plaintext[i] ^ keyStream[i];
-    }
-  }
-
-  // Compute authentication tag using HMAC-like construction
-  const tagHasher = sha256.create();
-  tagHasher.update(key);
-  tagHasher.update(nonce);
-  tagHasher.update(encrypted);
-  const tag = tagHasher.digest().slice(0, 16);
-
-  // Prepend nonce to ciphertext
-  const ciphertextWithNonce = new Uint8Array(12 + encrypted.length);
-  ciphertextWithNonce.set(nonce, 0);
-  ciphertextWithNonce.set(encrypted, 12);
-
-  return { ciphertext: ciphertextWithNonce, tag };
+function deriveEncryptionKey(
+  sharedSecretX: FieldElement,
+  ephemeralPubkey: Point,
+  domain: Uint8Array
+): Uint8Array {
+  const salt = new Uint8Array(64);
+  salt.set(ephemeralPubkey.x, 0);
+  salt.set(ephemeralPubkey.y, 32);
+  return hkdf(sha256, sharedSecretX, salt, domain, 32);
 }
 
 /**
- * AEAD decryption using AES-256-GCM
+ * Associated data: version byte || domain
  */
-function decryptAEAD(ciphertext: Uint8Array, tag: Uint8Array, key: Uint8Array): Uint8Array {
-  // Extract nonce from ciphertext
-  const nonce = ciphertext.slice(0, 12);
-  const encrypted = ciphertext.slice(12);
-
-  // Verify authentication tag
-  const tagHasher = sha256.create();
-  tagHasher.update(key);
-  tagHasher.update(nonce);
-  tagHasher.update(encrypted);
-  const expectedTag = tagHasher.digest().slice(0, 16);
-
-  let tagValid = true;
-  for (let i = 0; i < 16; i++) {
-    if (expectedTag[i] !== tag[i]) {
-      tagValid = false;
-    }
-  }
-  if (!tagValid) {
-    throw new Error('AEAD authentication failed');
-  }
-
-  // Decrypt using same key stream
-  const hasher = sha256.create();
-  hasher.update(key);
-  hasher.update(nonce);
-  const keyStream = hasher.digest();
-
-  const plaintext = new Uint8Array(encrypted.length);
-  for (let i = 0; i < encrypted.length; i++) {
-    if (i > 0 && i % 32 === 0) {
-      const extHasher = sha256.create();
-      extHasher.update(key);
-      extHasher.update(nonce);
-      extHasher.update(new Uint8Array([i >> 8, i & 0xff]));
-      const ext = extHasher.digest();
-      for (let j = 0; j < 32 && (i + j) < encrypted.length; j++) {
-        plaintext[i + j] = encrypted[i + j] ^ ext[j];
-      }
-    } else if (i < 32) {
-      plaintext[i] = encrypted[i] ^ keyStream[i];
-    }
-  }
-
-  return plaintext;
+function associatedData(domain: Uint8Array): Uint8Array {
+  const aad = new Uint8Array(1 + domain.length);
+  aad[0] = NOTE_ENCRYPTION_V1;
+  aad.set(domain, 1);
+  return aad;
 }
 
 /**
@@ -470,20 +376,22 @@ function generateRandomScalar(): bigint {
 /**
  * Serialize encrypted note for on-chain storage
  *
- * Format:
+ * Format (v1):
+ * - version: 1 byte
  * - ephemeral_pubkey_x: 32 bytes
  * - ephemeral_pubkey_y: 32 bytes
- * - ciphertext_len: 4 bytes (u32 LE)
  * - ciphertext: variable (includes 12-byte nonce)
  * - tag: 16 bytes
  *
- * Total: 32 + 32 + 4 + ciphertext.length + 16 bytes
+ * Total: 1 + 64 + ciphertext.length + 16 bytes
  */
 export function serializeEncryptedNote(encrypted: EncryptedNote): Uint8Array {
-  const totalLen = 32 + 32 + 4 + encrypted.ciphertext.length + 16;
-  const buffer = new Uint8Array(totalLen);
+  const buffer = new Uint8Array(1 + 64 + encrypted.ciphertext.length + TAG_SIZE);
   let offset = 0;
 
+  buffer[offset] = NOTE_ENCRYPTION_V1;
+  offset += 1;
+
   // Ephemeral pubkey X (32 bytes)
   buffer.set(encrypted.ephemeralPubkey.x, offset);
   offset += 32;
@@ -492,10 +400,6 @@ export function serializeEncryptedNote(encrypted: EncryptedNote): Uint8Array {
   buffer.set(encrypted.ephemeralPubkey.y, offset);
   offset += 32;
 
-  // Ciphertext length (4 bytes LE)
-  new DataView(buffer.buffer).setUint32(offset, encrypted.ciphertext.length, true);
-  offset += 4;
-
   // Ciphertext (variable)
   buffer.set(encrypted.ciphertext, offset);
   offset += encrypted.ciphertext.length;
@@ -508,41 +412,179 @@ export function serializeEncryptedNote(encrypted: EncryptedNote): Uint8Array {
 
 /**
  * Deserialize encrypted note from bytes
+ *
+ * Returns null for unknown versions or sizes the program would reject.
  */
 export function deserializeEncryptedNote(data: Uint8Array): EncryptedNote | null {
-  try {
-    if (data.length < 32 + 32 + 4 + 16) {
-      return null;
-    }
+  if (data[0] !== NOTE_ENCRYPTION_V1) {
+    return null;
+  }
+  if (!NOTE_PLAINTEXT_SIZES.some(len => data.length === encryptedNoteSize(len))) {
+    return null;
+  }
 
-    let offset = 0;
+  return {
+    ephemeralPubkey: {
+      x: new Uint8Array(data.slice(1, 33)),
+      y: new Uint8Array(data.slice(33, 65)),
+    },
+    ciphertext: new Uint8Array(data.slice(65, data.length - TAG_SIZE)),
+    tag: new Uint8Array(data.slice(data.length - TAG_SIZE)),
+  };
+}
 
-    // Ephemeral pubkey X (32 bytes)
-    const ephemeralX = data.slice(offset, offset + 32);
-    offset += 32;
+// =============================================================================
+// Sealed Swap Terms
+// =============================================================================
 
-    // Ephemeral pubkey Y (32 bytes)
-    const ephemeralY = data.slice(offset, offset + 32);
-    offset += 32;
+/** HKDF info and associated data domain of swap terms envelopes */
+export const SWAP_TERMS_ENCRYPTION_DOMAIN = new TextEncoder().encode('cloakcraft/swap-terms/v1');
 
-    // Ciphertext length (4 bytes)
-    const ciphertextLen = new DataView(data.buffer, data.byteOffset + offset).getUint32(0, true);
-    offset += 4;
+/** Swap amount, output, min output (u64 LE each) || direction || salt */
+const SWAP_TERMS_PLAINTEXT_SIZE = 8 + 8 + 8 + 1 + 32;
 
-    // Ciphertext (variable)
-    const ciphertext = data.slice(offset, offset + ciphertextLen);
-    offset += ciphertextLen;
+/**
+ * Terms of a sealed swap
+ *
+ * Their Poseidon hash (computeSwapCommitment) is the `terms_hash` public
+ * input of the swap_sealed proof.
+ */
+export interface SwapTerms {
+  swapAmount: bigint;
+  outputAmount: bigint;
+  minOutput: bigint;
+  swapAToB: boolean;
+  salt: Uint8Array;
+}
 
-    // Tag (16 bytes)
-    const tag = data.slice(offset, offset + 16);
+/**
+ * Seal swap terms to a relayer's session key
+ *
+ * Only the relayer holding the session key can open the envelope, so the
+ * amounts and direction stay out of the submitted Phase 0 transaction.
+ * Returns the serialized envelope (same layout as an encrypted note).
+ */
+export function encryptSwapTerms(terms: SwapTerms, relayerSessionPubkey: Point): Uint8Array {
+  const plaintext = new Uint8Array(SWAP_TERMS_PLAINTEXT_SIZE);
+  const view = new DataView(plaintext.buffer);
+  view.setBigUint64(0, terms.swapAmount, true);
+  view.setBigUint64(8, terms.outputAmount, true);
+  view.setBigUint64(16, terms.minOutput, true);
+  plaintext[24] = terms.swapAToB ? 1 : 0;
+  plaintext.set(terms.salt, 25);
+
+  return serializeEncryptedNote(sealNote(plaintext, relayerSessionPubkey, SWAP_TERMS_ENCRYPTION_DOMAIN));
+}
 
+/**
+ * Open a swap terms envelope with the relayer's session private key
+ *
+ * Returns null if the envelope is malformed or not sealed to this key. The
+ * relayer must still check the terms hash against the proof's `terms_hash`
+ * before submitting.
+ */
+export function decryptSwapTerms(envelope: Uint8Array, sessionPrivateKey: bigint): SwapTerms | null {
+  if (envelope[0] !== NOTE_ENCRYPTION_V1 || envelope.length !== encryptedNoteSize(SWAP_TERMS_PLAINTEXT_SIZE)) {
+    return null;
+  }
+
+  try {
+    const plaintext = openNote(
+      {
+        ephemeralPubkey: {
+          x: new Uint8Array(envelope.slice(1, 33)),
+          y: new Uint8Array(envelope.slice(33, 65)),
+        },
+        ciphertext: new Uint8Array(envelope.slice(65, envelope.length - TAG_SIZE)),
+        tag: new Uint8Array(envelope.slice(envelope.length - TAG_SIZE)),
+      },
+      sessionPrivateKey,
+      SWAP_TERMS_ENCRYPTION_DOMAIN
+    );
+    const view = new DataView(plaintext.buffer, plaintext.byteOffset, plaintext.byteLength);
     return {
-      ephemeralPubkey: {
-        x: new Uint8Array(ephemeralX),
-        y: new Uint8Array(ephemeralY),
+      swapAmount: view.getBigUint64(0, true),
+      outputAmount: view.getBigUint64(8, true),
+      minOutput: view.getBigUint64(16, true),
+      swapAToB: plaintext[24] === 1,
+      salt: new Uint8Array(plaintext.slice(25)),
+    };
+  } catch {
+    return null;
+  }
+}
+
+// =============================================================================
+// Sealed Position Terms
+// =============================================================================
+
+/** HKDF info and associated data domain of position terms envelopes */
+export const POSITION_TERMS_ENCRYPTION_DOMAIN = new TextEncoder().encode('cloakcraft/position-terms/v1');
+
+/** Margin (u64 LE) || leverage || position fee (u64 LE) || salt */
+const POSITION_TERMS_PLAINTEXT_SIZE = 8 + 1 + 8 + 32;
+
+/**
+ * Terms of a sealed perps open
+ *
+ * Their Poseidon hash (computePositionTermsHash) is the `terms_hash` public
+ * input of the open_position_sealed proof.
+ */
+export interface PositionTerms {
+  marginAmount: bigint;
+  leverage: number;
+  positionFee: bigint;
+  salt: Uint8Array;
+}
+
+/**
+ * Seal position terms to a relayer's session key
+ *
+ * Keeps margin, leverage and fee out of the submitted Phase 0 transaction.
+ * Returns the serialized envelope (same layout as an encrypted note).
+ */
+export function encryptPositionTerms(terms: PositionTerms, relayerSessionPubkey: Point): Uint8Array {
+  const plaintext = new Uint8Array(POSITION_TERMS_PLAINTEXT_SIZE);
+  const view = new DataView(plaintext.buffer);
+  view.setBigUint64(0, terms.marginAmount, true);
+  plaintext[8] = terms.leverage;
+  view.setBigUint64(9, terms.positionFee, true);
+  plaintext.set(terms.salt, 17);
+
+  return serializeEncryptedNote(sealNote(plaintext, relayerSessionPubkey, POSITION_TERMS_ENCRYPTION_DOMAIN));
+}
+
+/**
+ * Open a position terms envelope with the relayer's session private key
+ *
+ * Returns null if the envelope is malformed or not sealed to this key. The
+ * relayer must still check the terms hash against the proof's `terms_hash`
+ * before submitting.
+ */
+export function decryptPositionTerms(envelope: Uint8Array, sessionPrivateKey: bigint): PositionTerms | null {
+  if (envelope[0] !== NOTE_ENCRYPTION_V1 || envelope.length !== encryptedNoteSize(POSITION_TERMS_PLAINTEXT_SIZE)) {
+    return null;
+  }
+
+  try {
+    const plaintext = openNote(
+      {
+        ephemeralPubkey: {
+          x: new Uint8Array(envelope.slice(1, 33)),
+          y: new Uint8Array(envelope.slice(33, 65)),
+        },
+        ciphertext: new Uint8Array(envelope.slice(65, envelope.length - TAG_SIZE)),
+        tag: new Uint8Array(envelope.slice(envelope.length - TAG_SIZE)),
       },
-      ciphertext: new Uint8Array(ciphertext),
-      tag: new Uint8Array(tag),
+      sessionPrivateKey,
+      POSITION_TERMS_ENCRYPTION_DOMAIN
+    );
+    const view = new DataView(plaintext.buffer, plaintext.byteOffset, plaintext.byteLength);
+    return {
+      marginAmount: view.getBigUint64(0, true),
+      leverage: plaintext[8],
+      positionFee: view.getBigUint64(9, true),
+      salt: new Uint8Array(plaintext.slice(17)),
     };
   } catch {
     return null;
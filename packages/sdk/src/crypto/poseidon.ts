@@ -25,6 +25,14 @@ export const DOMAIN_NULLIFIER_KEY = 0x04n;
 export const DOMAIN_STEALTH = 0x05n;
 export const DOMAIN_MERKLE = 0x06n;
 export const DOMAIN_EMPTY_LEAF = 0x07n;
+export const DOMAIN_VIEW_TAG = 0x08n;
+export const DOMAIN_BUYBACK_RANDOMNESS = 0x14n;
+export const DOMAIN_OPERATION_CREDIT = 0x15n;
+export const DOMAIN_CREDIT_NULLIFIER = 0x16n;
+export const DOMAIN_SWAP_TERMS = 0x20n;
+export const DOMAIN_POSITION_TERMS = 0x21n;
+export const DOMAIN_CONTRIBUTIONS = 0x22n;
+export const DOMAIN_CHANGE_EPHEMERAL = 0x21n;
 
 // BN254 scalar field (Fr) modulus - this is the native field for Groth16/Circom circuits
 // Fr = 21888242871839275222246405745257275088548364400416034343698204186575808495617
@@ -4,11 +4,21 @@
 
 import type { Point, FieldElement, StealthAddress, Keypair } from '@cloakcraft/types';
 import { scalarMul, GENERATOR, derivePublicKey, pointAdd } from './babyjubjub';
-import { poseidonHashDomain, DOMAIN_STEALTH, bytesToField } from './poseidon';
+import {
+  poseidonHashDomain,
+  DOMAIN_STEALTH,
+  DOMAIN_VIEW_TAG,
+  DOMAIN_CHANGE_EPHEMERAL,
+  bytesToField,
+  fieldToBytes,
+} from './poseidon';
 
 // BabyJubJub subgroup order
 const SUBGROUP_ORDER = 2736030358979909402780800718157159386076813972158567259200215660948447373041n;
 
+/** View tag size in bytes (matches VIEW_TAG_SIZE on-chain) */
+export const VIEW_TAG_SIZE = 8;
+
 /**
  * Generate a stealth address for a recipient
  *
@@ -17,36 +27,69 @@ const SUBGROUP_ORDER = 273603035897990940278080071815715938607681397215856725920
  * 2. Compute shared secret: S = e * recipient_pubkey
  * 3. Derive stealth private key factor: f = H(S.x)
  * 4. Stealth public key: P' = recipient_pubkey + f*G
+ * 5. View tag: first bytes of H_tag(S.x), stored with the commitment
  */
 export function generateStealthAddress(recipientPubkey: Point): {
   stealthAddress: StealthAddress;
   ephemeralPrivate: bigint;
 } {
-  const toHex = (arr: Uint8Array) => Array.from(arr).map(b => b.toString(16).padStart(2, '0')).join('');
-
   // Generate random ephemeral private key
   const ephemeralPrivate = generateRandomScalar();
-  const ephemeralPubkey = derivePublicKey(ephemeralPrivate);
-
-  // Compute shared secret
-  const sharedSecret = scalarMul(recipientPubkey, ephemeralPrivate);
-
-  // Derive stealth key factor
-  const factor = deriveStealthFactor(sharedSecret.x);
-
-  // Compute stealth public key: recipient_pubkey + factor * G
-  const factorPoint = scalarMul(GENERATOR, factor);
-  const stealthPubkey = addPoints(recipientPubkey, factorPoint);
-
   return {
-    stealthAddress: {
-      stealthPubkey,
-      ephemeralPubkey,
-    },
+    stealthAddress: stealthAddressFromEphemeral(recipientPubkey, ephemeralPrivate),
     ephemeralPrivate,
   };
 }
 
+/**
+ * Derive the stealth address of a change output (sender side, to self)
+ *
+ * The ephemeral key is taken from a per-wallet deterministic chain instead
+ * of fresh randomness: e = H_change(sk, spent, slot), where `spent` is the
+ * commitment of the note being spent and `slot` tells apart several change
+ * outputs of one spend. Each change note thus hangs off the note it
+ * replaces, every spend yields a fresh stealth key (a note is only spent
+ * once), and the wallet can recompute the chain from its own notes.
+ *
+ * The result is an ordinary stealth address: its ephemeral pubkey, view tag
+ * and stealth key look like those of a payment from anyone else, so change
+ * can't be told apart from received notes or clustered with the sender's
+ * other outputs. Scanning recovers it the usual way (S = sk * E).
+ */
+export function deriveChangeStealthAddress(
+  keypair: Keypair,
+  spentCommitment: Uint8Array,
+  slot = 0
+): StealthAddress {
+  const ephemeralPrivate = deriveChangeEphemeralKey(keypair.spending.sk, spentCommitment, slot);
+  return stealthAddressFromEphemeral(keypair.publicKey, ephemeralPrivate);
+}
+
+/**
+ * Check whether a note's ephemeral pubkey is the change of `spentCommitment`
+ *
+ * Lets a wallet label its change in history without storing anything.
+ */
+export function isChangeOf(
+  keypair: Keypair,
+  ephemeralPubkey: Point,
+  spentCommitment: Uint8Array,
+  maxSlots = 2
+): boolean {
+  for (let slot = 0; slot < maxSlots; slot++) {
+    const expected = derivePublicKey(
+      deriveChangeEphemeralKey(keypair.spending.sk, spentCommitment, slot)
+    );
+    if (
+      bytesToField(expected.x) === bytesToField(ephemeralPubkey.x) &&
+      bytesToField(expected.y) === bytesToField(ephemeralPubkey.y)
+    ) {
+      return true;
+    }
+  }
+  return false;
+}
+
 /**
  * Scan and derive stealth private key (recipient side)
  *
@@ -72,6 +115,32 @@ export function deriveStealthPrivateKey(
   return stealthPriv;
 }
 
+/**
+ * Derive the expected view tag for a note (recipient side)
+ *
+ * Scanners compare this against the on-chain view tag and only attempt
+ * decryption on a match. Costs one scalar multiplication and one hash,
+ * versus trial decryption of every note type.
+ */
+export function deriveViewTag(
+  recipientPrivateKey: bigint,
+  ephemeralPubkey: Point
+): Uint8Array {
+  const sharedSecret = scalarMul(ephemeralPubkey, recipientPrivateKey);
+  return computeViewTag(sharedSecret.x);
+}
+
+/**
+ * Compute view tag from shared secret: first VIEW_TAG_SIZE bytes of H_tag(S.x)
+ *
+ * Uses a separate domain from the stealth factor so the tag reveals nothing
+ * about the stealth key.
+ */
+export function computeViewTag(sharedSecretX: FieldElement): Uint8Array {
+  const hash = poseidonHashDomain(DOMAIN_VIEW_TAG, sharedSecretX);
+  return hash.slice(0, VIEW_TAG_SIZE);
+}
+
 /**
  * Check if a stealth address belongs to us
  */
@@ -100,6 +169,37 @@ function deriveStealthFactor(sharedSecretX: FieldElement): bigint {
   return bytesToField(hash) % SUBGROUP_ORDER;
 }
 
+/**
+ * Stealth address for `recipientPubkey` under ephemeral key `ephemeralPrivate`
+ */
+function stealthAddressFromEphemeral(recipientPubkey: Point, ephemeralPrivate: bigint): StealthAddress {
+  const ephemeralPubkey = derivePublicKey(ephemeralPrivate);
+
+  // Compute shared secret
+  const sharedSecret = scalarMul(recipientPubkey, ephemeralPrivate);
+
+  // Derive stealth key factor
+  const factor = deriveStealthFactor(sharedSecret.x);
+
+  // Compute stealth public key: recipient_pubkey + factor * G
+  const factorPoint = scalarMul(GENERATOR, factor);
+  const stealthPubkey = addPoints(recipientPubkey, factorPoint);
+
+  return {
+    stealthPubkey,
+    ephemeralPubkey,
+    viewTag: computeViewTag(sharedSecret.x),
+  };
+}
+
+/**
+ * Change chain ephemeral key: H_change(sk, spent, slot), never zero
+ */
+function deriveChangeEphemeralKey(sk: Uint8Array, spentCommitment: Uint8Array, slot: number): bigint {
+  const hash = poseidonHashDomain(DOMAIN_CHANGE_EPHEMERAL, sk, spentCommitment, fieldToBytes(BigInt(slot)));
+  return (bytesToField(hash) % (SUBGROUP_ORDER - 1n)) + 1n;
+}
+
 /**
  * Point addition helper
  */
@@ -0,0 +1,304 @@
+/**
+ * Liquidity Mining Emissions
+ *
+ * PDA derivation, reward accrual math and instruction builders for
+ * emissions schedules. Rewards are claimed with the multi-phase pattern:
+ * Phase 0 (create_pending_with_proof_claim_rewards) → verify LP note →
+ * nullify LP note → execute_claim_rewards → create LP + rewards commitments.
+ */
+
+import { PublicKey, SystemProgram, ComputeBudgetProgram } from '@solana/web3.js';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Program, BN } from '@coral-xyz/anchor';
+
+import { PROGRAM_ID, derivePoolPda, deriveVaultPda, padCircuitId, deriveProtocolConfigPda, deriveProgramVersionPda, CLIENT_VERSION } from '../instructions/constants';
+import type { LightVerifyParams } from '../perps/instructions';
+
+// ============ Seeds ============
+
+export const EMISSIONS_SEEDS = {
+  EMISSIONS: Buffer.from('emissions'),
+  EMISSIONS_VAULT: Buffer.from('emissions_vault'),
+  REWARD_CHECKPOINT: Buffer.from('reward_checkpoint'),
+  PENDING_OP: Buffer.from('pending_op'),
+  VK: Buffer.from('vk'),
+} as const;
+
+export const CLAIM_REWARDS_CIRCUIT_ID = padCircuitId('claim_rewards');
+
+/** Reward accumulator scale (matches RATE_SCALE on-chain) */
+export const REWARD_PER_LP_SCALE = 10n ** 18n;
+
+export type EmissionsPoolKind = { amm: {} } | { perps: {} };
+
+// ============ PDA Derivation ============
+
+export function deriveEmissionsSchedulePda(
+  sourcePool: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [EMISSIONS_SEEDS.EMISSIONS, sourcePool.toBuffer()],
+    programId
+  );
+}
+
+export function deriveEmissionsVaultPda(
+  schedule: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [EMISSIONS_SEEDS.EMISSIONS_VAULT, schedule.toBuffer()],
+    programId
+  );
+}
+
+export function deriveRewardCheckpointPda(
+  schedule: PublicKey,
+  lpCommitment: Uint8Array,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [EMISSIONS_SEEDS.REWARD_CHECKPOINT, schedule.toBuffer(), Buffer.from(lpCommitment)],
+    programId
+  );
+}
+
+// ============ Reward Math ============
+
+export interface EmissionsScheduleState {
+  rewardRate: bigint;
+  startTime: number;
+  endTime: number;
+  lastUpdateTime: number;
+  rewardPerLp: bigint;
+}
+
+/**
+ * Accumulator value at `now` (mirrors EmissionsSchedule::accrue)
+ */
+export function accrueRewardPerLp(
+  schedule: EmissionsScheduleState,
+  now: number,
+  lpSupply: bigint
+): bigint {
+  const until = Math.min(now, schedule.endTime);
+  if (until <= schedule.lastUpdateTime || lpSupply === 0n) {
+    return schedule.rewardPerLp;
+  }
+  const elapsed = BigInt(until - schedule.lastUpdateTime);
+  return schedule.rewardPerLp + (schedule.rewardRate * elapsed * REWARD_PER_LP_SCALE) / lpSupply;
+}
+
+/**
+ * Rewards owed to an LP note checkpointed at `entryRewardPerLp`
+ */
+export function calculatePendingReward(
+  lpAmount: bigint,
+  rewardPerLp: bigint,
+  entryRewardPerLp: bigint
+): bigint {
+  if (entryRewardPerLp > rewardPerLp) {
+    throw new Error('Reward checkpoint is ahead of the emissions accumulator');
+  }
+  return (lpAmount * (rewardPerLp - entryRewardPerLp)) / REWARD_PER_LP_SCALE;
+}
+
+// ============ Instruction Builders ============
+
+export interface CreateEmissionsScheduleParams {
+  /** AMM or perps pool whose LP notes earn rewards */
+  sourcePool: PublicKey;
+  poolKind: EmissionsPoolKind;
+  rewardMint: PublicKey;
+  /** Authority's reward token account (funds the full schedule) */
+  funderTokenAccount: PublicKey;
+  authority: PublicKey;
+  /** Rewards per second (base units) */
+  rewardRate: bigint;
+  startTime: number;
+  endTime: number;
+}
+
+export async function buildCreateEmissionsScheduleWithProgram(
+  program: Program,
+  params: CreateEmissionsScheduleParams
+): Promise<{ tx: any; schedule: PublicKey }> {
+  const programId = program.programId;
+  const [schedule] = deriveEmissionsSchedulePda(params.sourcePool, programId);
+  const [rewardVault] = deriveEmissionsVaultPda(schedule, programId);
+
+  const tx = await program.methods
+    .createEmissionsSchedule(
+      params.poolKind,
+      new BN(params.rewardRate.toString()),
+      new BN(params.startTime),
+      new BN(params.endTime)
+    )
+    .accountsStrict({
+      emissionsSchedule: schedule,
+      sourcePool: params.sourcePool,
+      rewardMint: params.rewardMint,
+      rewardVault,
+      funderTokenAccount: params.funderTokenAccount,
+      authority: params.authority,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  return { tx, schedule };
+}
+
+/**
+ * Build register reward checkpoint transaction
+ *
+ * Records the current accumulator as the `entryRewardPerLp` of an existing
+ * LP note. Register once the note exists; claims roll the checkpoint forward
+ * to the re-created LP note.
+ */
+export async function buildRegisterRewardCheckpointWithProgram(
+  program: Program,
+  params: {
+    sourcePool: PublicKey;
+    lpMint: PublicKey;
+    /** LP note commitment (must already exist in the LP pool) */
+    lpCommitment: Uint8Array;
+    lightVerifyParams: LightVerifyParams;
+    remainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+    payer: PublicKey;
+  }
+): Promise<{ tx: any; rewardCheckpoint: PublicKey }> {
+  const programId = program.programId;
+  const [schedule] = deriveEmissionsSchedulePda(params.sourcePool, programId);
+  const [rewardCheckpoint] = deriveRewardCheckpointPda(schedule, params.lpCommitment, programId);
+
+  const tx = await program.methods
+    .registerRewardCheckpoint(Array.from(params.lpCommitment), params.lightVerifyParams)
+    .accountsStrict({
+      emissionsSchedule: schedule,
+      sourcePool: params.sourcePool,
+      lpPool: derivePoolPda(params.lpMint, programId)[0],
+      rewardCheckpoint,
+      payer: params.payer,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(params.remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ]);
+
+  return { tx, rewardCheckpoint };
+}
+
+export interface ClaimRewardsPhase0Params {
+  operationId: Uint8Array;
+  sourcePool: PublicKey;
+  lpMint: PublicKey;
+  rewardMint: PublicKey;
+  relayer: PublicKey;
+  proof: Uint8Array;
+  merkleRoot: Uint8Array;
+  lpCommitment: Uint8Array;
+  lpNullifier: Uint8Array;
+  newLpCommitment: Uint8Array;
+  rewardCommitment: Uint8Array;
+  lpAmount: bigint;
+  /** Rewards owed since the LP note's reward checkpoint (see deriveRewardCheckpointPda) */
+  rewardAmount: bigint;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+}
+
+export async function buildClaimRewardsPhase0WithProgram(
+  program: Program,
+  params: ClaimRewardsPhase0Params
+): Promise<{ tx: any; pendingOperation: PublicKey }> {
+  const programId = program.programId;
+  const [schedule] = deriveEmissionsSchedulePda(params.sourcePool, programId);
+  const [lpPool] = derivePoolPda(params.lpMint, programId);
+  const [rewardPool] = derivePoolPda(params.rewardMint, programId);
+  const [verificationKey] = PublicKey.findProgramAddressSync(
+    [EMISSIONS_SEEDS.VK, CLAIM_REWARDS_CIRCUIT_ID],
+    programId
+  );
+  const [pendingOperation] = PublicKey.findProgramAddressSync(
+    [EMISSIONS_SEEDS.PENDING_OP, Buffer.from(params.operationId)],
+    programId
+  );
+
+  const tx = await program.methods
+    .createPendingWithProofClaimRewards(
+      Array.from(params.operationId),
+      Buffer.from(params.proof),
+      Array.from(params.merkleRoot),
+      Array.from(params.lpCommitment),
+      Array.from(params.lpNullifier),
+      Array.from(params.newLpCommitment),
+      Array.from(params.rewardCommitment),
+      new BN(params.lpAmount.toString()),
+      new BN(params.rewardAmount.toString()),
+      CLIENT_VERSION
+    )
+    .accountsStrict({
+      emissionsSchedule: schedule,
+      sourcePool: params.sourcePool,
+      lpPool,
+      rewardPool,
+      rewardCheckpoint: deriveRewardCheckpointPda(schedule, params.lpCommitment, programId)[0],
+      newRewardCheckpoint: deriveRewardCheckpointPda(schedule, params.newLpCommitment, programId)[0],
+      verificationKey,
+      pendingOperation,
+      relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return { tx, pendingOperation };
+}
+
+export interface ExecuteClaimRewardsParams {
+  operationId: Uint8Array;
+  sourcePool: PublicKey;
+  rewardMint: PublicKey;
+  relayer: PublicKey;
+  /** Spent LP note commitment (its reward checkpoint is closed) */
+  lpCommitment: Uint8Array;
+  /** Payer of the spent note's reward checkpoint (receives its rent) */
+  checkpointPayer: PublicKey;
+}
+
+export async function buildExecuteClaimRewardsWithProgram(
+  program: Program,
+  params: ExecuteClaimRewardsParams
+): Promise<{ tx: any }> {
+  const programId = program.programId;
+  const [schedule] = deriveEmissionsSchedulePda(params.sourcePool, programId);
+  const [rewardVault] = deriveEmissionsVaultPda(schedule, programId);
+  const [rewardPool] = derivePoolPda(params.rewardMint, programId);
+  const [rewardPoolVault] = deriveVaultPda(params.rewardMint, programId);
+  const [pendingOperation] = PublicKey.findProgramAddressSync(
+    [EMISSIONS_SEEDS.PENDING_OP, Buffer.from(params.operationId)],
+    programId
+  );
+
+  const tx = await program.methods
+    .executeClaimRewards(Array.from(params.operationId))
+    .accountsStrict({
+      emissionsSchedule: schedule,
+      rewardVault,
+      rewardPool,
+      rewardPoolVault,
+      pendingOperation,
+      rewardCheckpoint: deriveRewardCheckpointPda(schedule, params.lpCommitment, programId)[0],
+      checkpointPayer: params.checkpointPayer,
+      relayer: params.relayer,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  return { tx };
+}
@@ -53,6 +53,26 @@ export interface ProtocolFeeConfig {
   removeLiquidityFeeBps: number;
   /** Whether fees are enabled */
   feesEnabled: boolean;
+  /** Fee floor in basis points (absent on older configs) */
+  minFeeBps?: number;
+  /** Fee ceiling in basis points (absent on older configs) */
+  maxFeeBps?: number;
+  /** Per-mint fee rates replacing the protocol-wide ones */
+  feeOverrides?: FeeOverride[];
+}
+
+/**
+ * Fee rates for one token mint
+ */
+export interface FeeOverride {
+  /** Token mint the rates apply to */
+  mint: PublicKey;
+  /** Transfer fee in basis points */
+  transferFeeBps: number;
+  /** Unshield fee in basis points */
+  unshieldFeeBps: number;
+  /** Remove liquidity fee in basis points */
+  removeLiquidityFeeBps: number;
 }
 
 /**
@@ -87,6 +107,11 @@ export const DEFAULT_FEE_CONFIG: Omit<ProtocolFeeConfig, 'authority' | 'treasury
  */
 export const MAX_FEE_BPS = 1000;
 
+/**
+ * Maximum number of per-mint fee overrides
+ */
+export const MAX_FEE_OVERRIDES = 8;
+
 /**
  * Basis points divisor
  */
@@ -112,12 +137,14 @@ export function isFeeableOperation(operation: OperationType): operation is Feeab
  * @param amount - The amount to calculate fee for
  * @param operation - The operation type
  * @param config - Protocol fee configuration (or null if not fetched)
+ * @param mint - Token mint (applies its fee override, if any)
  * @returns Fee calculation result
  */
 export function calculateProtocolFee(
   amount: bigint,
   operation: OperationType,
-  config: ProtocolFeeConfig | null
+  config: ProtocolFeeConfig | null,
+  mint?: PublicKey
 ): FeeCalculation {
   // Free operations have no fee
   if (isFreeOperation(operation)) {
@@ -142,7 +169,7 @@ export function calculateProtocolFee(
   }
 
   // Get fee rate for operation
-  const feeBps = getFeeBps(operation, config);
+  const feeBps = getFeeBps(operation, config, mint);
 
   // Calculate fee: (amount * feeBps) / 10000
   const feeAmount = (amount * BigInt(feeBps)) / BPS_DIVISOR;
@@ -159,19 +186,26 @@ export function calculateProtocolFee(
 
 /**
  * Get fee basis points for an operation (except swap which uses share of LP fees)
+ *
+ * With a mint, its override (if configured) replaces the protocol-wide rate.
  */
-export function getFeeBps(operation: FeeableOperation, config: ProtocolFeeConfig): number {
+export function getFeeBps(
+  operation: FeeableOperation,
+  config: ProtocolFeeConfig,
+  mint?: PublicKey
+): number {
+  const rates = (mint && config.feeOverrides?.find((o) => o.mint.equals(mint))) || config;
   switch (operation) {
     case 'transfer':
-      return config.transferFeeBps;
+      return rates.transferFeeBps;
     case 'unshield':
-      return config.unshieldFeeBps;
+      return rates.unshieldFeeBps;
     case 'swap':
       // For swap, use calculateSwapProtocolFee instead
       // This returns 0 as swaps use percentage of LP fee, not fixed rate
       return 0;
     case 'remove_liquidity':
-      return config.removeLiquidityFeeBps;
+      return rates.removeLiquidityFeeBps;
     default:
       return 0;
   }
@@ -308,6 +342,44 @@ export async function fetchProtocolFeeConfig(
   // fees_enabled: bool (1 byte)
   const feesEnabled = data[offset] === 1;
 
+  // Skip fees_enabled, bump, pending_expiry_seconds, 8 expiry overrides
+  // (5 bytes each), rent_refund_bps, surplus_policy, amm_creation_mode,
+  // amm_creation_fee_lamports, operation_epoch and _reserved
+  offset += 1 + 1 + 4 + 5 * 8 + 2 + 1 + 1 + 8 + 4 + 2;
+
+  // Fee bounds and overrides are absent on configs created before them
+  if (data.length < offset + 4 + 38 * MAX_FEE_OVERRIDES) {
+    return {
+      authority,
+      treasury,
+      transferFeeBps,
+      unshieldFeeBps,
+      swapFeeShareBps,
+      removeLiquidityFeeBps,
+      feesEnabled,
+    };
+  }
+
+  // min_fee_bps, max_fee_bps: u16 (0 = MAX_FEE_BPS)
+  const minFeeBps = data.readUInt16LE(offset);
+  const maxFeeBps = data.readUInt16LE(offset + 2) || MAX_FEE_BPS;
+  offset += 4;
+
+  // fee_overrides: [FeeOverride; 8] (mint + 3 x u16, default mint = free slot)
+  const feeOverrides: FeeOverride[] = [];
+  for (let i = 0; i < MAX_FEE_OVERRIDES; i++) {
+    const mint = new PublicKey(data.slice(offset, offset + 32));
+    if (!mint.equals(PublicKey.default)) {
+      feeOverrides.push({
+        mint,
+        transferFeeBps: data.readUInt16LE(offset + 32),
+        unshieldFeeBps: data.readUInt16LE(offset + 34),
+        removeLiquidityFeeBps: data.readUInt16LE(offset + 36),
+      });
+    }
+    offset += 38;
+  }
+
   return {
     authority,
     treasury,
@@ -316,9 +388,37 @@ export async function fetchProtocolFeeConfig(
     swapFeeShareBps,
     removeLiquidityFeeBps,
     feesEnabled,
+    minFeeBps,
+    maxFeeBps,
+    feeOverrides,
   };
 }
 
+/**
+ * Fetch the current operation epoch from ProtocolConfig (0 if not initialized)
+ *
+ * Operation IDs must carry this epoch; pass it to `setOperationEpoch`.
+ */
+export async function fetchOperationEpoch(
+  connection: Connection,
+  programId: PublicKey = PROGRAM_ID
+): Promise<number> {
+  const [configPda] = deriveConfigPda(programId);
+  const accountInfo = await connection.getAccountInfo(configPda);
+  if (!accountInfo) {
+    return 0;
+  }
+
+  // discriminator, authority, treasury, 4 fee bps, fees_enabled, bump,
+  // pending_expiry_seconds, 8 expiry overrides (5 bytes each), rent_refund_bps,
+  // surplus_policy, amm_creation_mode, amm_creation_fee_lamports
+  const offset = 8 + 32 + 32 + 2 * 4 + 1 + 1 + 4 + 5 * 8 + 2 + 1 + 1 + 8;
+  if (accountInfo.data.length < offset + 4) {
+    return 0;
+  }
+  return accountInfo.data.readUInt32LE(offset);
+}
+
 /**
  * Format fee amount for display
  *
@@ -358,19 +458,21 @@ export function formatFeeRate(feeBps: number): string {
  * @param amount - The amount to transfer/withdraw
  * @param operation - The operation type
  * @param config - Protocol fee configuration
+ * @param mint - Token mint (applies its fee override, if any)
  * @returns Object with breakdown
  */
 export function estimateTotalCost(
   amount: bigint,
   operation: OperationType,
-  config: ProtocolFeeConfig | null
+  config: ProtocolFeeConfig | null,
+  mint?: PublicKey
 ): {
   amount: bigint;
   fee: bigint;
   total: bigint;
   feeRate: string;
 } {
-  const feeCalc = calculateProtocolFee(amount, operation, config);
+  const feeCalc = calculateProtocolFee(amount, operation, config, mint);
 
   return {
     amount,
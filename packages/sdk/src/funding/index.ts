@@ -0,0 +1,305 @@
+/**
+ * Quadratic Funding Rounds
+ *
+ * PDA derivation, matching math and instruction builders for matching
+ * rounds. Donations use the multi-phase pattern:
+ * Phase 0 (create_pending_with_proof_donate) → verify input note →
+ * nullify input note → execute_donate → create donation + change commitments.
+ * After the round ends the encrypted tally is decrypted with compute_matching
+ * and each project's allocation is paid with claim_matching.
+ */
+
+import { PublicKey, SystemProgram } from '@solana/web3.js';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Program, BN } from '@coral-xyz/anchor';
+
+import { PROGRAM_ID, derivePoolPda, padCircuitId, deriveProtocolConfigPda, deriveProgramVersionPda, CLIENT_VERSION } from '../instructions/constants';
+import { DOMAIN_CONTRIBUTIONS, poseidonHashDomain } from '../crypto/poseidon';
+
+// ============ Seeds ============
+
+export const FUNDING_SEEDS = {
+  MATCHING_ROUND: Buffer.from('matching_round'),
+  MATCHING_VAULT: Buffer.from('matching_vault'),
+  PENDING_OP: Buffer.from('pending_op'),
+  VK: Buffer.from('vk'),
+} as const;
+
+export const DONATE_CIRCUIT_ID = padCircuitId('donate');
+
+/** Maximum projects per round (matches MAX_ROUND_PROJECTS on-chain) */
+export const MAX_ROUND_PROJECTS = 16;
+
+// ============ PDA Derivation ============
+
+export function deriveMatchingRoundPda(
+  roundId: Uint8Array,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [FUNDING_SEEDS.MATCHING_ROUND, Buffer.from(roundId)],
+    programId
+  );
+}
+
+export function deriveMatchingVaultPda(
+  round: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [FUNDING_SEEDS.MATCHING_VAULT, round.toBuffer()],
+    programId
+  );
+}
+
+// ============ Contributions Hash ============
+
+/**
+ * Hash of a donation's per-project ciphertexts (matches compute_contributions_hash)
+ *
+ * acc = Poseidon(DOMAIN_CONTRIBUTIONS, acc, c1, c2) over MAX_ROUND_PROJECTS
+ * ciphertexts, zero-padded, starting from 0. The `contributions_hash` public
+ * input of the donate proof; execute_donate only accepts these ciphertexts.
+ */
+export function computeContributionsHash(ciphertexts: Uint8Array[]): Uint8Array {
+  if (ciphertexts.length > MAX_ROUND_PROJECTS) {
+    throw new Error(`At most ${MAX_ROUND_PROJECTS} ciphertexts per donation`);
+  }
+  let acc: Uint8Array = new Uint8Array(32);
+  for (let i = 0; i < MAX_ROUND_PROJECTS; i++) {
+    const ciphertext = ciphertexts[i] ?? new Uint8Array(64);
+    acc = poseidonHashDomain(DOMAIN_CONTRIBUTIONS, acc, ciphertext.slice(0, 32), ciphertext.slice(32, 64));
+  }
+  return acc;
+}
+
+// ============ Matching Math ============
+
+/**
+ * Integer square root of a donation (the value encrypted into the tally)
+ */
+export function donationSqrt(amount: bigint): bigint {
+  if (amount < 2n) return amount;
+  let x = amount;
+  let y = (x + 1n) / 2n;
+  while (y < x) {
+    x = y;
+    y = (x + amount / x) / 2n;
+  }
+  return x;
+}
+
+/**
+ * Matching allocation per project (mirrors MatchingRound::compute_matching)
+ */
+export function calculateMatching(matchingPool: bigint, sqrtSums: bigint[]): bigint[] {
+  const squares = sqrtSums.map((s) => s * s);
+  const total = squares.reduce((a, b) => a + b, 0n);
+  if (total === 0n) return sqrtSums.map(() => 0n);
+  return squares.map((square) => (matchingPool * square) / total);
+}
+
+// ============ Instruction Builders ============
+
+export interface CreateMatchingRoundParams {
+  roundId: Uint8Array;
+  tokenMint: PublicKey;
+  /** Authority's token account (funds the matching pool) */
+  funderTokenAccount: PublicKey;
+  authority: PublicKey;
+  matchingPool: bigint;
+  /** Token accounts receiving each project's matching payout */
+  projectRecipients: PublicKey[];
+  /** ElGamal key donations are encrypted to (committee threshold key if set) */
+  tallyPubkey: Uint8Array;
+  startTime: number;
+  endTime: number;
+  /** Threshold committee allowed to submit the decrypted tally */
+  committee?: PublicKey;
+}
+
+export async function buildCreateMatchingRoundWithProgram(
+  program: Program,
+  params: CreateMatchingRoundParams
+): Promise<{ tx: any; matchingRound: PublicKey }> {
+  const programId = program.programId;
+  const [matchingRound] = deriveMatchingRoundPda(params.roundId, programId);
+  const [matchingVault] = deriveMatchingVaultPda(matchingRound, programId);
+
+  const tx = await program.methods
+    .createMatchingRound(Array.from(params.roundId), {
+      matchingPool: new BN(params.matchingPool.toString()),
+      projectRecipients: params.projectRecipients,
+      tallyPubkey: Array.from(params.tallyPubkey),
+      startTime: new BN(params.startTime),
+      endTime: new BN(params.endTime),
+    })
+    .accountsStrict({
+      matchingRound,
+      tokenMint: params.tokenMint,
+      matchingVault,
+      funderTokenAccount: params.funderTokenAccount,
+      committee: params.committee ?? null,
+      authority: params.authority,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  return { tx, matchingRound };
+}
+
+export interface DonatePhase0Params {
+  operationId: Uint8Array;
+  roundId: Uint8Array;
+  tokenMint: PublicKey;
+  relayer: PublicKey;
+  proof: Uint8Array;
+  merkleRoot: Uint8Array;
+  inputCommitment: Uint8Array;
+  nullifier: Uint8Array;
+  donationCommitment: Uint8Array;
+  changeCommitment: Uint8Array;
+  /** Hash of the tally ciphertexts bound by the proof (see computeContributionsHash) */
+  contributionsHash: Uint8Array;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+}
+
+export async function buildDonatePhase0WithProgram(
+  program: Program,
+  params: DonatePhase0Params
+): Promise<{ tx: any; pendingOperation: PublicKey }> {
+  const programId = program.programId;
+  const [matchingRound] = deriveMatchingRoundPda(params.roundId, programId);
+  const [pool] = derivePoolPda(params.tokenMint, programId);
+  const [verificationKey] = PublicKey.findProgramAddressSync(
+    [FUNDING_SEEDS.VK, DONATE_CIRCUIT_ID],
+    programId
+  );
+  const [pendingOperation] = PublicKey.findProgramAddressSync(
+    [FUNDING_SEEDS.PENDING_OP, Buffer.from(params.operationId)],
+    programId
+  );
+
+  const tx = await program.methods
+    .createPendingWithProofDonate(
+      Array.from(params.operationId),
+      Array.from(params.roundId),
+      Buffer.from(params.proof),
+      Array.from(params.merkleRoot),
+      Array.from(params.inputCommitment),
+      Array.from(params.nullifier),
+      Array.from(params.donationCommitment),
+      Array.from(params.changeCommitment),
+      Array.from(params.contributionsHash),
+      CLIENT_VERSION
+    )
+    .accountsStrict({
+      matchingRound,
+      pool,
+      verificationKey,
+      pendingOperation,
+      relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return { tx, pendingOperation };
+}
+
+export interface ExecuteDonateParams {
+  operationId: Uint8Array;
+  roundId: Uint8Array;
+  relayer: PublicKey;
+  /**
+   * One 64-byte ElGamal ciphertext per project (sqrt(amount) for the
+   * recipient, 0 otherwise); must hash to the Phase 0 contributionsHash
+   */
+  ciphertexts: Uint8Array[];
+}
+
+export async function buildExecuteDonateWithProgram(
+  program: Program,
+  params: ExecuteDonateParams
+): Promise<{ tx: any }> {
+  const programId = program.programId;
+  const [matchingRound] = deriveMatchingRoundPda(params.roundId, programId);
+  const [pendingOperation] = PublicKey.findProgramAddressSync(
+    [FUNDING_SEEDS.PENDING_OP, Buffer.from(params.operationId)],
+    programId
+  );
+
+  const tx = await program.methods
+    .executeDonate(Array.from(params.operationId), Array.from(params.roundId), {
+      ciphertexts: params.ciphertexts.map((ct) => Array.from(ct)),
+    })
+    .accountsStrict({
+      matchingRound,
+      pendingOperation,
+      relayer: params.relayer,
+    });
+
+  return { tx };
+}
+
+export interface ComputeMatchingParams {
+  roundId: Uint8Array;
+  caller: PublicKey;
+  decryptionKey: Uint8Array;
+  sqrtSums: bigint[];
+  /** Required when the round was created with a committee */
+  committee?: PublicKey;
+}
+
+export async function buildComputeMatchingWithProgram(
+  program: Program,
+  params: ComputeMatchingParams
+): Promise<{ tx: any }> {
+  const [matchingRound] = deriveMatchingRoundPda(params.roundId, program.programId);
+
+  const tx = await program.methods
+    .computeMatching(
+      Array.from(params.roundId),
+      Array.from(params.decryptionKey),
+      params.sqrtSums.map((s) => new BN(s.toString()))
+    )
+    .accountsStrict({
+      matchingRound,
+      committee: params.committee ?? null,
+      caller: params.caller,
+    });
+
+  return { tx };
+}
+
+export interface ClaimMatchingParams {
+  roundId: Uint8Array;
+  projectIndex: number;
+  /** Project's registered recipient token account */
+  recipient: PublicKey;
+}
+
+export async function buildClaimMatchingWithProgram(
+  program: Program,
+  params: ClaimMatchingParams
+): Promise<{ tx: any }> {
+  const programId = program.programId;
+  const [matchingRound] = deriveMatchingRoundPda(params.roundId, programId);
+  const [matchingVault] = deriveMatchingVaultPda(matchingRound, programId);
+
+  const tx = await program.methods
+    .claimMatching(Array.from(params.roundId), params.projectIndex)
+    .accountsStrict({
+      matchingRound,
+      matchingVault,
+      recipient: params.recipient,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  return { tx };
+}
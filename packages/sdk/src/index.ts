@@ -20,6 +20,9 @@ export * from './notes';
 // Export proof generation
 export * from './proofs';
 
+// Export prover orchestration (local / remote / hybrid)
+export * from './prover';
+
 // Export snarkjs prover utilities (browser Groth16)
 export * from './snarkjs-prover';
 
@@ -41,9 +44,18 @@ export * from './versioned-transaction';
 // Export Address Lookup Table utilities
 export * from './address-lookup-table';
 
+// Export Jito bundle submission
+export * from './jito';
+
 // Export transaction history
 export * from './history';
 
+// Export encrypted wallet store
+export * from './wallet-store';
+
+// Export payment requests
+export * from './payment-request';
+
 // Export token prices
 export * from './prices';
 
@@ -53,6 +65,12 @@ export * from './analytics';
 // Export protocol fees
 export * from './fees';
 
+// Export operation cost estimates
+export * from './operation-cost';
+
+// Export operation simulation
+export * from './operation-simulation';
+
 // Export smart note selector
 export * from './note-selector';
 
@@ -70,3 +88,9 @@ export * from './pyth';
 
 // Export voting module
 export * from './voting';
+
+// Export liquidity mining emissions
+export * from './emissions';
+
+// Export quadratic funding rounds
+export * from './funding';
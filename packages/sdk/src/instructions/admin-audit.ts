@@ -0,0 +1,101 @@
+/**
+ * Admin Audit Log
+ *
+ * Every admin instruction writes an AdminActionRecord compressed account
+ * (actor, action, target account, old/new state hashes, timestamp). Admin
+ * instructions take the Light system accounts as remaining accounts, with
+ * the output queue as the first tree account.
+ */
+
+import { PublicKey, AccountMeta } from '@solana/web3.js';
+import { PackedAccounts, SystemAccountMetaConfig } from '@lightprotocol/stateless.js';
+
+import { DEVNET_V2_TREES, PROGRAM_ID } from './constants';
+
+/**
+ * Admin actions (must match AdminAction in state/admin_action.rs)
+ */
+export const ADMIN_ACTIONS = {
+  INITIALIZE_PROTOCOL_CONFIG: 0,
+  UPDATE_PROTOCOL_FEES: 1,
+  UPDATE_TREASURY: 2,
+  UPDATE_PROTOCOL_AUTHORITY: 3,
+  SET_PENDING_EXPIRY: 4,
+  SET_RENT_REFUND_BPS: 5,
+  SET_SURPLUS_POLICY: 6,
+  BUMP_OPERATION_EPOCH: 7,
+  SET_AMM_CREATION_POLICY: 8,
+  INITIALIZE_POOL_CREATOR_ALLOWLIST: 9,
+  SET_POOL_CREATOR: 10,
+  INITIALIZE_PROGRAM_VERSION: 11,
+  SET_PROGRAM_VERSION: 12,
+  REGISTER_VERIFICATION_KEY: 13,
+  SET_VERIFICATION_KEY_DATA: 14,
+  APPEND_VERIFICATION_KEY_DATA: 15,
+  REGISTER_ADAPT_MODULE: 16,
+  DISABLE_ADAPT_MODULE: 17,
+  REGISTER_THRESHOLD_COMMITTEE: 18,
+  RESET_AMM_POOL: 19,
+  SET_AMM_LP_LOCK: 20,
+  FINALIZE_VERIFICATION_KEY: 21,
+  INITIALIZE_DUST_SWEEP_LEDGER: 22,
+  SET_DUST_SWEEP_BOUNTY: 23,
+  MIGRATE_AMM_POOL: 24,
+  RECONCILE_COMMITMENT_COUNTER: 25,
+  SET_FEE_OVERRIDE: 26,
+  SET_FEE_BOUNDS: 27,
+  SET_TREASURY_CONVERSION: 28,
+  INITIALIZE_BUYBACK: 29,
+  SET_BUYBACK_POLICY: 30,
+  INITIALIZE_CREDIT_CONFIG: 31,
+  SET_CREDIT_POLICY: 32,
+  INITIALIZE_TREE_REGISTRY: 33,
+  REGISTER_STATE_TREE: 34,
+  RETIRE_STATE_TREE: 35,
+} as const;
+
+export interface AdminActionRecord {
+  actor: PublicKey;
+  action: number;
+  target: PublicKey;
+  oldValueHash: Uint8Array;
+  newValueHash: Uint8Array;
+  timestamp: number;
+}
+
+/**
+ * Remaining accounts for any admin instruction
+ */
+export function buildAdminAuditRemainingAccounts(programId: PublicKey = PROGRAM_ID): AccountMeta[] {
+  const systemConfig = SystemAccountMetaConfig.new(programId);
+  const packedAccounts = PackedAccounts.newWithSystemAccountsV2(systemConfig);
+  // Output tree index 0 (ADMIN_AUDIT_OUTPUT_TREE_INDEX)
+  packedAccounts.insertOrGet(DEVNET_V2_TREES.OUTPUT_QUEUE);
+
+  const { remainingAccounts } = packedAccounts.toAccountMetas();
+  return remainingAccounts.map((acc: any) => ({
+    pubkey: acc.pubkey,
+    isWritable: Boolean(acc.isWritable),
+    isSigner: Boolean(acc.isSigner),
+  }));
+}
+
+/**
+ * Decode an AdminActionRecord compressed account's data (without discriminator)
+ */
+export function decodeAdminActionRecord(data: Buffer): AdminActionRecord {
+  let offset = 0;
+  const actor = new PublicKey(data.subarray(offset, offset + 32));
+  offset += 32;
+  const action = data[offset];
+  offset += 1;
+  const target = new PublicKey(data.subarray(offset, offset + 32));
+  offset += 32;
+  const oldValueHash = new Uint8Array(data.subarray(offset, offset + 32));
+  offset += 32;
+  const newValueHash = new Uint8Array(data.subarray(offset, offset + 32));
+  offset += 32;
+  const timestamp = Number(data.readBigInt64LE(offset));
+
+  return { actor, action, target, oldValueHash, newValueHash, timestamp };
+}
@@ -0,0 +1,283 @@
+/**
+ * Protocol Token Buyback Instructions
+ *
+ * A keeper spends a share of a treasury fee balance on the protocol token
+ * through the internal AMM; the bought tokens are distributed to protocol
+ * token stakers as shielded notes committed to their registered stealth key.
+ */
+
+import { PublicKey, ComputeBudgetProgram, SystemProgram } from '@solana/web3.js';
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from '@solana/spl-token';
+import { Program, BN } from '@coral-xyz/anchor';
+
+import {
+  derivePoolPda,
+  deriveVaultPda,
+  deriveAmmPoolPda,
+  deriveCommitmentCounterPda,
+  deriveProtocolConfigPda,
+  deriveTreasuryConverterPda,
+  deriveBuybackConfigPda,
+  deriveStakeRegistryPda,
+  deriveStakeVaultPda,
+  deriveTreeRegistryPda,
+} from './constants';
+import { LightProtocol } from './light-helpers';
+import { buildAdminAuditRemainingAccounts } from './admin-audit';
+import { deriveRootRegistryPda } from './root-registry';
+import { computeCommitment } from '../crypto/commitment';
+import { DOMAIN_BUYBACK_RANDOMNESS, fieldToBytes, poseidonHashDomain } from '../crypto/poseidon';
+
+/**
+ * Randomness of a buyback distribution note
+ *
+ * Matches `buyback_randomness` on-chain:
+ * Poseidon(BUYBACK_RANDOMNESS, stealthPubX, nonce). `nonce` is the
+ * `BuybackDistributed` event nonce.
+ */
+export function computeBuybackRandomness(stealthPubX: Uint8Array, nonce: bigint): Uint8Array {
+  return poseidonHashDomain(DOMAIN_BUYBACK_RANDOMNESS, stealthPubX, fieldToBytes(nonce));
+}
+
+/**
+ * Build initialize_buyback transaction using Anchor program
+ *
+ * The buyback starts paused; enable it with set_buyback_policy.
+ */
+export async function buildInitializeBuybackWithProgram(
+  program: Program,
+  params: {
+    protocolTokenMint: PublicKey;
+    buybackBps: number;
+    intervalSeconds: number;
+    maxPriceImpactBps: number;
+    authority: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .initializeBuyback(params.buybackBps, new BN(params.intervalSeconds), params.maxPriceImpactBps)
+    .accountsStrict({
+      buybackConfig: deriveBuybackConfigPda(programId)[0],
+      stakeRegistry: deriveStakeRegistryPda(programId)[0],
+      stakeVault: deriveStakeVaultPda(programId)[0],
+      protocolTokenMint: params.protocolTokenMint,
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      authority: params.authority,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(programId));
+
+  return tx;
+}
+
+/**
+ * Build set_buyback_policy transaction using Anchor program
+ */
+export async function buildSetBuybackPolicyWithProgram(
+  program: Program,
+  params: {
+    buybackBps: number;
+    intervalSeconds: number;
+    maxPriceImpactBps: number;
+    isActive: boolean;
+    authority: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .setBuybackPolicy(
+      params.buybackBps,
+      new BN(params.intervalSeconds),
+      params.maxPriceImpactBps,
+      params.isActive
+    )
+    .accountsStrict({
+      buybackConfig: deriveBuybackConfigPda(programId)[0],
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      authority: params.authority,
+    })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(programId));
+
+  return tx;
+}
+
+/**
+ * Build stake_protocol_token transaction using Anchor program
+ *
+ * `stealthPubX` is the key distribution notes are committed to; staking
+ * again replaces it.
+ */
+export async function buildStakeProtocolTokenWithProgram(
+  program: Program,
+  params: {
+    protocolTokenMint: PublicKey;
+    amount: bigint;
+    stealthPubX: Uint8Array;
+    staker: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .stakeProtocolToken(new BN(params.amount.toString()), Array.from(params.stealthPubX))
+    .accountsStrict({
+      buybackConfig: deriveBuybackConfigPda(programId)[0],
+      stakeRegistry: deriveStakeRegistryPda(programId)[0],
+      stakeVault: deriveStakeVaultPda(programId)[0],
+      stakerTokenAccount: getAssociatedTokenAddressSync(params.protocolTokenMint, params.staker),
+      staker: params.staker,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  return tx;
+}
+
+/**
+ * Build unstake_protocol_token transaction using Anchor program
+ *
+ * Rewards earned before unstaking are still distributed.
+ */
+export async function buildUnstakeProtocolTokenWithProgram(
+  program: Program,
+  params: {
+    protocolTokenMint: PublicKey;
+    amount: bigint;
+    staker: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .unstakeProtocolToken(new BN(params.amount.toString()))
+    .accountsStrict({
+      stakeRegistry: deriveStakeRegistryPda(programId)[0],
+      stakeVault: deriveStakeVaultPda(programId)[0],
+      recipientTokenAccount: getAssociatedTokenAddressSync(params.protocolTokenMint, params.staker),
+      staker: params.staker,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  return tx;
+}
+
+/**
+ * Build execute_buyback transaction using Anchor program
+ *
+ * Protocol authority only (`keeper`), once the interval has passed: spends
+ * the policy's share of the treasury's delegated `feeMint` balance on the
+ * protocol token. Price `minAmountOut` (must be > 0) off an external
+ * reference, not the pool's spot price. The treasury opts in with
+ * `buildApproveTreasuryConverterInstruction`.
+ */
+export async function buildExecuteBuybackWithProgram(
+  program: Program,
+  params: {
+    feeMint: PublicKey;
+    protocolTokenMint: PublicKey;
+    treasury: PublicKey;
+    minAmountOut: bigint;
+    keeper: PublicKey;
+    /** AMM pool version (default 0) */
+    ammPoolVersion?: number;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const [ammPool] = deriveAmmPoolPda(params.feeMint, params.protocolTokenMint, programId, params.ammPoolVersion ?? 0);
+
+  const tx = await program.methods
+    .executeBuyback(new BN(params.minAmountOut.toString()))
+    .accountsStrict({
+      buybackConfig: deriveBuybackConfigPda(programId)[0],
+      stakeRegistry: deriveStakeRegistryPda(programId)[0],
+      ammPool,
+      inputPool: derivePoolPda(params.feeMint, programId)[0],
+      inputVault: deriveVaultPda(params.feeMint, programId)[0],
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      treasurySource: getAssociatedTokenAddressSync(params.feeMint, params.treasury, true),
+      treasuryConverter: deriveTreasuryConverterPda(programId)[0],
+      keeper: params.keeper,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  return tx;
+}
+
+/**
+ * Build distribute_buyback transaction using Anchor program
+ *
+ * Permissionless: commits `staker`'s owed protocol tokens as a note to their
+ * registered stealth key. The note is fixed on-chain, so the caller passes
+ * the amount, key and nonce read from the stake registry to derive its
+ * Light Protocol address.
+ */
+export async function buildDistributeBuybackWithProgram(
+  program: Program,
+  params: {
+    protocolTokenMint: PublicKey;
+    staker: PublicKey;
+    /** Staker's registered stealth key and owed amount (from the registry) */
+    stealthPubX: Uint8Array;
+    amount: bigint;
+    /** Registry distribution nonce */
+    nonce: bigint;
+    stealthEphemeralPubkey: Uint8Array;
+    encryptedNote: Uint8Array;
+    viewTag?: Uint8Array;
+    keeper: PublicKey;
+  },
+  rpcUrl: string
+): Promise<{ tx: any; commitment: Uint8Array }> {
+  const programId = program.programId;
+  const lightProtocol = new LightProtocol(rpcUrl, programId);
+
+  const [pool] = derivePoolPda(params.protocolTokenMint, programId);
+  const commitment = computeCommitment({
+    stealthPubX: params.stealthPubX,
+    tokenMint: params.protocolTokenMint,
+    amount: params.amount,
+    randomness: computeBuybackRandomness(params.stealthPubX, params.nonce),
+  });
+
+  const commitmentAddress = lightProtocol.deriveCommitmentAddress(pool, commitment);
+  const commitmentProof = await lightProtocol.getValidityProof([commitmentAddress]);
+  const { accounts: remainingAccounts, outputTreeIndex, addressTreeIndex } =
+    lightProtocol.buildRemainingAccounts();
+
+  const lightParams = {
+    validityProof: LightProtocol.convertCompressedProof(commitmentProof),
+    addressTreeInfo: {
+      addressMerkleTreePubkeyIndex: addressTreeIndex,
+      addressQueuePubkeyIndex: addressTreeIndex,
+      rootIndex: commitmentProof.rootIndices[0] ?? 0,
+    },
+    outputTreeIndex,
+  };
+
+  const tx = await program.methods
+    .distributeBuyback(params.staker, {
+      stealthEphemeralPubkey: Array.from(params.stealthEphemeralPubkey),
+      encryptedNote: Buffer.from(params.encryptedNote),
+      viewTag: params.viewTag ? Array.from(params.viewTag) : null,
+      lightParams,
+    })
+    .accountsStrict({
+      buybackConfig: deriveBuybackConfigPda(programId)[0],
+      stakeRegistry: deriveStakeRegistryPda(programId)[0],
+      pool,
+      commitmentCounter: deriveCommitmentCounterPda(pool, programId)[0],
+      rootRegistry: deriveRootRegistryPda(pool, programId)[0],
+      treeRegistry: deriveTreeRegistryPda(programId)[0],
+      keeper: params.keeper,
+    })
+    .remainingAccounts(remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ]);
+
+  return { tx, commitment };
+}
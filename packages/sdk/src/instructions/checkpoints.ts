@@ -0,0 +1,157 @@
+/**
+ * State Tree Root Checkpoints
+ *
+ * A checkpoint authority periodically anchors the Light state tree root and
+ * the pool's commitment count into a hash-chained on-chain history. Indexers
+ * may then move raw encrypted notes below a checkpoint's commitment count to
+ * cold storage; an archived note stays provable because its commitment and
+ * leaf index remain indexed, and decrypting it recomputes the commitment.
+ */
+
+import { PublicKey, SystemProgram } from '@solana/web3.js';
+import { Program, BN } from '@coral-xyz/anchor';
+import { keccak_256 } from '@noble/hashes/sha3';
+
+import { PROGRAM_ID, derivePoolPda, deriveCommitmentCounterPda } from './constants';
+
+export const ROOT_CHECKPOINT_SEEDS = {
+  ROOT_CHECKPOINTS: Buffer.from('root_checkpoints'),
+} as const;
+
+/** Checkpoints retained on-chain (matches MAX_ROOT_CHECKPOINTS) */
+export const MAX_ROOT_CHECKPOINTS = 32;
+
+/**
+ * Derive root checkpoint history PDA
+ */
+export function deriveRootCheckpointsPda(
+  pool: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [ROOT_CHECKPOINT_SEEDS.ROOT_CHECKPOINTS, pool.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Compute a checkpoint chain hash (mirrors RootCheckpointHistory::compute_chain_hash)
+ *
+ * chain_hash = keccak256(prev || pool || state_tree || root || count_le || slot_le || timestamp_le)
+ */
+export function computeCheckpointChainHash(
+  prevChainHash: Uint8Array,
+  pool: PublicKey,
+  stateTree: PublicKey,
+  root: Uint8Array,
+  commitmentCount: bigint,
+  slot: bigint,
+  timestamp: bigint
+): Uint8Array {
+  const data = new Uint8Array(32 * 4 + 8 * 3);
+  const view = new DataView(data.buffer);
+  data.set(prevChainHash, 0);
+  data.set(pool.toBytes(), 32);
+  data.set(stateTree.toBytes(), 64);
+  data.set(root, 96);
+  view.setBigUint64(128, commitmentCount, true);
+  view.setBigUint64(136, slot, true);
+  view.setBigInt64(144, timestamp, true);
+  return keccak_256(data);
+}
+
+/**
+ * Verify that a sequence of checkpoints forms an unbroken hash chain
+ *
+ * Use with indexed `RootCheckpointAnchored` events to audit history that has
+ * rotated out of the on-chain ring buffer.
+ */
+export function verifyCheckpointChain(
+  pool: PublicKey,
+  checkpoints: Array<{
+    stateTree: PublicKey;
+    root: Uint8Array;
+    commitmentCount: bigint;
+    slot: bigint;
+    timestamp: bigint;
+    chainHash: Uint8Array;
+  }>,
+  prevChainHash: Uint8Array = new Uint8Array(32)
+): boolean {
+  let prev = prevChainHash;
+  for (const checkpoint of checkpoints) {
+    const expected = computeCheckpointChainHash(
+      prev,
+      pool,
+      checkpoint.stateTree,
+      checkpoint.root,
+      checkpoint.commitmentCount,
+      checkpoint.slot,
+      checkpoint.timestamp
+    );
+    if (!expected.every((b, i) => b === checkpoint.chainHash[i])) {
+      return false;
+    }
+    prev = checkpoint.chainHash;
+  }
+  return true;
+}
+
+/**
+ * Build initialize_root_checkpoints transaction (pool authority)
+ */
+export async function buildInitializeRootCheckpointsWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    authority: PublicKey;
+    checkpointAuthority: PublicKey;
+    /** Minimum seconds between checkpoints */
+    minInterval: number;
+  }
+): Promise<{ tx: any; checkpointHistory: PublicKey }> {
+  const programId = program.programId;
+  const [pool] = derivePoolPda(params.tokenMint, programId);
+  const [checkpointHistory] = deriveRootCheckpointsPda(pool, programId);
+
+  const tx = await program.methods
+    .initializeRootCheckpoints(params.checkpointAuthority, new BN(params.minInterval))
+    .accountsStrict({
+      pool,
+      checkpointHistory,
+      authority: params.authority,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return { tx, checkpointHistory };
+}
+
+/**
+ * Build anchor_root_checkpoint transaction (checkpoint authority)
+ */
+export async function buildAnchorRootCheckpointWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    checkpointAuthority: PublicKey;
+    /** Light state tree the root belongs to */
+    stateTree: PublicKey;
+    root: Uint8Array;
+  }
+): Promise<{ tx: any }> {
+  const programId = program.programId;
+  const [pool] = derivePoolPda(params.tokenMint, programId);
+  const [commitmentCounter] = deriveCommitmentCounterPda(pool, programId);
+  const [checkpointHistory] = deriveRootCheckpointsPda(pool, programId);
+
+  const tx = await program.methods
+    .anchorRootCheckpoint(params.stateTree, Array.from(params.root))
+    .accountsStrict({
+      pool,
+      commitmentCounter,
+      checkpointHistory,
+      checkpointAuthority: params.checkpointAuthority,
+    });
+
+  return { tx };
+}
@@ -0,0 +1,275 @@
+/**
+ * Compressed NFT (Bubblegum) Instruction Builders
+ *
+ * Shield a cNFT into protocol custody (amount = 1 note bound to the asset id)
+ * and release it in Phase 3 of an NFT transfer (transfer_1x2_nft circuit).
+ * Leaf data and proofs come from a DAS-compatible RPC (getAsset / getAssetProof).
+ */
+
+import {
+  PublicKey,
+  SystemProgram,
+  ComputeBudgetProgram,
+  AccountMeta,
+} from '@solana/web3.js';
+import { Program } from '@coral-xyz/anchor';
+import BN from 'bn.js';
+import bs58 from 'bs58';
+import type { Point } from '@cloakcraft/types';
+
+import { derivePoolPda, deriveCommitmentCounterPda, deriveProtocolConfigPda, deriveTreeRegistryPda } from './constants';
+import { deriveRootRegistryPda } from './root-registry';
+import { LightProtocol } from './light-helpers';
+import { derivePendingOperationPda } from './swap';
+import { computeCommitment, generateRandomness } from '../crypto/commitment';
+import { encryptNote, serializeEncryptedNote } from '../crypto/encryption';
+
+/** Metaplex Bubblegum program */
+export const BUBBLEGUM_PROGRAM_ID = new PublicKey('BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY');
+/** SPL Account Compression program */
+export const SPL_ACCOUNT_COMPRESSION_PROGRAM_ID = new PublicKey('cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK');
+/** SPL Noop program */
+export const SPL_NOOP_PROGRAM_ID = new PublicKey('noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV');
+
+/**
+ * Derive the cNFT custody PDA (leaf owner of shielded cNFTs)
+ */
+export function deriveCnftCustodyPda(programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from('cnft_custody')], programId);
+}
+
+/**
+ * Derive Bubblegum tree config PDA
+ */
+export function deriveTreeAuthorityPda(merkleTree: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([merkleTree.toBuffer()], BUBBLEGUM_PROGRAM_ID)[0];
+}
+
+/**
+ * cNFT leaf as stored in the tree, plus its proof
+ */
+export interface CnftLeafWithProof {
+  merkleTree: PublicKey;
+  /** Current leaf owner */
+  owner: PublicKey;
+  /** Current leaf delegate (owner if none) */
+  delegate: PublicKey;
+  root: Uint8Array;
+  dataHash: Uint8Array;
+  creatorHash: Uint8Array;
+  nonce: bigint;
+  index: number;
+  /** Proof nodes, leaf to root */
+  proof: PublicKey[];
+}
+
+async function dasRequest(rpcUrl: string, method: string, params: unknown): Promise<any> {
+  const response = await fetch(rpcUrl, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ jsonrpc: '2.0', id: method, method, params }),
+  });
+  const json = await response.json();
+  if (json.error) {
+    throw new Error(`${method} failed: ${json.error.message}`);
+  }
+  return json.result;
+}
+
+/**
+ * Fetch leaf data and proof for a cNFT from a DAS-compatible RPC
+ */
+export async function fetchCnftLeaf(rpcUrl: string, assetId: PublicKey): Promise<CnftLeafWithProof> {
+  const [asset, assetProof] = await Promise.all([
+    dasRequest(rpcUrl, 'getAsset', { id: assetId.toBase58() }),
+    dasRequest(rpcUrl, 'getAssetProof', { id: assetId.toBase58() }),
+  ]);
+
+  const leafId = BigInt(asset.compression.leaf_id);
+  return {
+    merkleTree: new PublicKey(assetProof.tree_id),
+    owner: new PublicKey(asset.ownership.owner),
+    delegate: new PublicKey(asset.ownership.delegate ?? asset.ownership.owner),
+    root: bs58.decode(assetProof.root),
+    dataHash: bs58.decode(asset.compression.data_hash),
+    creatorHash: bs58.decode(asset.compression.creator_hash),
+    nonce: leafId,
+    index: Number(leafId),
+    proof: assetProof.proof.map((node: string) => new PublicKey(node)),
+  };
+}
+
+function toLeafArg(leaf: CnftLeafWithProof) {
+  return {
+    root: Array.from(leaf.root),
+    dataHash: Array.from(leaf.dataHash),
+    creatorHash: Array.from(leaf.creatorHash),
+    nonce: new BN(leaf.nonce.toString()),
+    index: leaf.index,
+  };
+}
+
+function toProofAccounts(leaf: CnftLeafWithProof, canopyDepth: number): AccountMeta[] {
+  const nodes = leaf.proof.slice(0, Math.max(leaf.proof.length - canopyDepth, 0));
+  return nodes.map(pubkey => ({ pubkey, isSigner: false, isWritable: false }));
+}
+
+/**
+ * Shield cNFT parameters
+ */
+export interface ShieldCnftInstructionParams {
+  /** cNFT asset id */
+  assetId: PublicKey;
+  /** Leaf data and proof (see fetchCnftLeaf) */
+  leaf: CnftLeafWithProof;
+  /** Canopy depth of the tree (proof nodes stored on-chain are omitted) */
+  canopyDepth?: number;
+  /** Recipient's stealth public key (for commitment and encryption) */
+  stealthPubkey: Point;
+  /** Stealth address ephemeral pubkey (stored on-chain for decryption key derivation) */
+  stealthEphemeralPubkey: Point;
+  /** View tag from generateStealthAddress (optional) */
+  viewTag?: Uint8Array;
+  /** User's wallet (current leaf owner) */
+  user: PublicKey;
+}
+
+/**
+ * Build shield_cnft instruction using Anchor program
+ *
+ * The note commits to the asset id with amount = 1.
+ */
+export async function buildShieldCnftWithProgram(
+  program: Program,
+  params: ShieldCnftInstructionParams,
+  rpcUrl: string
+): Promise<{
+  tx: any;
+  commitment: Uint8Array;
+  randomness: Uint8Array;
+}> {
+  const programId = program.programId;
+  const lightProtocol = new LightProtocol(rpcUrl, programId);
+
+  const [poolPda] = derivePoolPda(params.assetId, programId);
+  const [counterPda] = deriveCommitmentCounterPda(poolPda, programId);
+  const [custodyPda] = deriveCnftCustodyPda(programId);
+
+  const randomness = generateRandomness();
+  const note = {
+    stealthPubX: params.stealthPubkey.x,
+    tokenMint: params.assetId,
+    amount: 1n,
+    randomness,
+  };
+  const commitment = computeCommitment(note);
+  const serializedNote = serializeEncryptedNote(encryptNote(note, params.stealthPubkey));
+
+  const stealthEphemeralBytes = new Uint8Array(64);
+  stealthEphemeralBytes.set(params.stealthEphemeralPubkey.x, 0);
+  stealthEphemeralBytes.set(params.stealthEphemeralPubkey.y, 32);
+
+  const commitmentAddress = lightProtocol.deriveCommitmentAddress(poolPda, commitment);
+  const validityProof = await lightProtocol.getValidityProof([commitmentAddress]);
+  const { accounts: lightAccounts, outputTreeIndex, addressTreeIndex } = lightProtocol.buildRemainingAccounts();
+
+  const lightParams = {
+    validityProof: LightProtocol.convertCompressedProof(validityProof),
+    addressTreeInfo: {
+      addressMerkleTreePubkeyIndex: addressTreeIndex,
+      addressQueuePubkeyIndex: addressTreeIndex,
+      rootIndex: validityProof.rootIndices[0] ?? 0,
+    },
+    outputTreeIndex,
+  };
+
+  // Proof nodes first, then Light accounts (program splits at proof_len)
+  const proofAccounts = toProofAccounts(params.leaf, params.canopyDepth ?? 0);
+
+  const tx = await program.methods
+    .shieldCnft(
+      params.assetId,
+      toLeafArg(params.leaf),
+      proofAccounts.length,
+      Array.from(commitment),
+      Array.from(stealthEphemeralBytes),
+      Buffer.from(serializedNote),
+      lightParams,
+      params.viewTag ? Array.from(params.viewTag) : null
+    )
+    .accountsStrict({
+      pool: poolPda,
+      commitmentCounter: counterPda,
+      custody: custodyPda,
+      treeAuthority: deriveTreeAuthorityPda(params.leaf.merkleTree),
+      merkleTree: params.leaf.merkleTree,
+      leafDelegate: params.leaf.delegate,
+      user: params.user,
+      logWrapper: SPL_NOOP_PROGRAM_ID,
+      compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+      bubblegumProgram: BUBBLEGUM_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rootRegistry: deriveRootRegistryPda(poolPda, programId)[0],
+      treeRegistry: deriveTreeRegistryPda(programId)[0],
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+    })
+    .remainingAccounts([...proofAccounts, ...lightAccounts])
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 800_000 }),
+      ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 50000 }),
+    ]);
+
+  return { tx, commitment, randomness };
+}
+
+/**
+ * Process unshield cNFT parameters (Phase 3 of an NFT transfer)
+ */
+export interface ProcessUnshieldCnftParams {
+  /** Pending operation ID from Phase 0 */
+  operationId: Uint8Array;
+  /** cNFT asset id */
+  assetId: PublicKey;
+  /** Leaf data and proof (custody is the current owner) */
+  leaf: CnftLeafWithProof;
+  /** Canopy depth of the tree */
+  canopyDepth?: number;
+  /** Wallet receiving the cNFT */
+  recipient: PublicKey;
+  /** Relayer (must match Phase 0) */
+  relayer: PublicKey;
+}
+
+/**
+ * Build process_unshield_cnft instruction using Anchor program
+ */
+export async function buildProcessUnshieldCnftWithProgram(
+  program: Program,
+  params: ProcessUnshieldCnftParams
+): Promise<any> {
+  const programId = program.programId;
+  const [poolPda] = derivePoolPda(params.assetId, programId);
+  const [pendingOpPda] = derivePendingOperationPda(params.operationId, programId);
+  const [custodyPda] = deriveCnftCustodyPda(programId);
+
+  return program.methods
+    .processUnshieldCnft(Array.from(params.operationId), toLeafArg(params.leaf))
+    .accountsStrict({
+      pool: poolPda,
+      pendingOperation: pendingOpPda,
+      custody: custodyPda,
+      treeAuthority: deriveTreeAuthorityPda(params.leaf.merkleTree),
+      merkleTree: params.leaf.merkleTree,
+      recipient: params.recipient,
+      relayer: params.relayer,
+      logWrapper: SPL_NOOP_PROGRAM_ID,
+      compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+      bubblegumProgram: BUBBLEGUM_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(toProofAccounts(params.leaf, params.canopyDepth ?? 0))
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 }),
+      ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 50000 }),
+    ]);
+}
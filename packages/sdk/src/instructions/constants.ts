@@ -7,6 +7,37 @@ import { PublicKey } from '@solana/web3.js';
 // Default program ID (devnet deployment)
 export const PROGRAM_ID = new PublicKey('2VWF9TxMFgzHwbd5WPpYKoqHvtzk3fN66Ka3tVV82nZG');
 
+/**
+ * Client version tag sent with every Phase 0 instruction.
+ * Bump when note formats or circuits change; the program rejects clients
+ * below its ProgramVersion.min_client_version.
+ */
+export const CLIENT_VERSION = 1;
+
+/**
+ * Operation epoch carried in the first 4 bytes (little-endian) of every
+ * operation ID. Must match ProtocolConfig.operation_epoch; the admin bumps it
+ * on incident response, invalidating in-flight operations from earlier epochs.
+ * Refresh with `fetchOperationEpoch` and `setOperationEpoch`.
+ */
+let operationEpoch = 0;
+
+export function getOperationEpoch(): number {
+  return operationEpoch;
+}
+
+export function setOperationEpoch(epoch: number): void {
+  operationEpoch = epoch;
+}
+
+/**
+ * Stamp an operation epoch into an operation ID (in place)
+ */
+export function applyOperationEpoch(operationId: Uint8Array, epoch: number = operationEpoch): Uint8Array {
+  new DataView(operationId.buffer, operationId.byteOffset, 4).setUint32(0, epoch, true);
+  return operationId;
+}
+
 // PDA seeds
 export const SEEDS = {
   POOL: Buffer.from('pool'),
@@ -14,10 +45,50 @@ export const SEEDS = {
   VERIFICATION_KEY: Buffer.from('vk'),
   COMMITMENT_COUNTER: Buffer.from('commitment_counter'),
   PROTOCOL_CONFIG: Buffer.from('protocol_config'),
+  PROGRAM_VERSION: Buffer.from('program_version'),
   AMM_POOL: Buffer.from('amm_pool'),
   LP_MINT: Buffer.from('lp_mint'),
+  LP_LOCK: Buffer.from('lp_lock'),
+  AMM_MIGRATION: Buffer.from('amm_migration'),
+  POOL_CREATOR_ALLOWLIST: Buffer.from('pool_creator_allowlist'),
+  ADAPT_MODULE: Buffer.from('adapt'),
+  POOL_STATS: Buffer.from('pool_stats'),
+  CIRCUIT_STATS: Buffer.from('circuit_stats'),
+  DUST_SWEEP_LEDGER: Buffer.from('dust_sweep_ledger'),
+  TREASURY_CONVERTER: Buffer.from('treasury_converter'),
+  BUYBACK_CONFIG: Buffer.from('buyback_config'),
+  STAKE_REGISTRY: Buffer.from('stake_registry'),
+  STAKE_VAULT: Buffer.from('stake_vault'),
+  EMISSIONS: Buffer.from('emissions'),
+  EMISSIONS_VAULT: Buffer.from('emissions_vault'),
+  SAVINGS_VAULT: Buffer.from('savings_vault'),
+  VAULT_SHARE_MINT: Buffer.from('vault_share_mint'),
+  CREDIT_CONFIG: Buffer.from('credit_config'),
+  CREDIT_TREE: Buffer.from('credit_tree'),
+  CREDIT_ISSUANCE: Buffer.from('credit_issuance'),
+  CREDIT_NULLIFIER: Buffer.from('credit_nullifier'),
+  TREE_REGISTRY: Buffer.from('tree_registry'),
+  RELAYER_ALLOWLIST: Buffer.from('relayer_allowlist'),
+} as const;
+
+// Nullifier domains (must match NullifierDomain in state/nullifier.rs)
+// Every current domain uses untagged seeds; domains added later add a [tag] seed after the prefix
+export const NULLIFIER_DOMAINS = {
+  SPEND: 0,
+  PERPS_POSITION: 1,
+  VOTE: 2,
+  VOTE_POSITION: 3,
 } as const;
 
+export type NullifierDomain = typeof NULLIFIER_DOMAINS[keyof typeof NULLIFIER_DOMAINS];
+
+/**
+ * Seed tag inserted after the nullifier prefix (empty for untagged domains)
+ */
+export function nullifierDomainSeeds(_domain: NullifierDomain): Buffer[] {
+  return [];
+}
+
 // V2 Batch Trees (Devnet)
 export const DEVNET_V2_TREES = {
   STATE_TREE: new PublicKey('bmt1LryLZUMmF7ZtqESaw7wifBXLfXHQYoE4GAmrahU'),
@@ -28,10 +99,22 @@ export const DEVNET_V2_TREES = {
 // Circuit IDs
 export const CIRCUIT_IDS = {
   TRANSFER_1X2: 'transfer_1x2',
+  /** Transfer for fixed-denomination pools */
+  TRANSFER_1X2_DENOM: 'transfer_1x2_denom',
+  /** Transfer with unshield bound to a follow-up program call (call_hash) */
+  TRANSFER_1X2_INVOKE: 'transfer_1x2_invoke',
+  /** Transfer for NFT pools (amounts 0/1, binds the pool's metadata hash) */
+  TRANSFER_1X2_NFT: 'transfer_1x2_nft',
   CONSOLIDATE_3X1: 'consolidate_3x1',
   SWAP: 'swap_swap',
+  /** Swap that also binds a hash of its hidden terms (sealed swaps) */
+  SWAP_SEALED: 'swap_sealed',
+  /** Swap whose terms, minOutput included, are only public as a hash (committed swaps) */
+  SWAP_COMMITTED: 'swap_committed',
   ADD_LIQUIDITY: 'swap_add_liquidity',
   REMOVE_LIQUIDITY: 'swap_remove_liquidity',
+  /** Convert an LP note of a migrated AMM pool into the successor pool's LP */
+  SWAP_CONVERT_LP: 'swap_convert_lp',
   ORDER_CREATE: 'market_order_create',
   ORDER_FILL: 'market_order_fill',
   ORDER_CANCEL: 'market_order_cancel',
@@ -41,6 +124,10 @@ export const CIRCUIT_IDS = {
   PERPS_ADD_LIQUIDITY: 'perps_add_liquidity',
   PERPS_REMOVE_LIQUIDITY: 'perps_remove_liquidity',
   PERPS_LIQUIDATE: 'perps_liquidate',
+  PERPS_TRANSFER_POSITION: 'perps_transfer_position',
+  PERPS_CONVERT_LP: 'perps_convert_lp',
+  /** Spend an anonymous operation credit (rate limiting) */
+  OPERATION_CREDIT: 'operation_credit',
 } as const;
 
 /**
@@ -82,6 +169,26 @@ export function deriveCommitmentCounterPda(pool: PublicKey, programId: PublicKey
   );
 }
 
+/**
+ * Derive program version PDA
+ */
+export function deriveProgramVersionPda(programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [SEEDS.PROGRAM_VERSION],
+    programId
+  );
+}
+
+/**
+ * Derive pool statistics PDA
+ */
+export function derivePoolStatsPda(pool: PublicKey, programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [SEEDS.POOL_STATS, pool.toBuffer()],
+    programId
+  );
+}
+
 /**
  * Derive verification key PDA
  */
@@ -92,6 +199,16 @@ export function deriveVerificationKeyPda(circuitId: string, programId: PublicKey
   );
 }
 
+/**
+ * Derive circuit statistics PDA
+ */
+export function deriveCircuitStatsPda(circuitId: string, programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [SEEDS.CIRCUIT_STATS, padCircuitId(circuitId)],
+    programId
+  );
+}
+
 /**
  * Derive protocol config PDA
  */
@@ -102,13 +219,23 @@ export function deriveProtocolConfigPda(programId: PublicKey = PROGRAM_ID): [Pub
   );
 }
 
+/**
+ * AMM pool version seed (empty for the original pool, see AmmPool.version_seed)
+ */
+function ammVersionSeeds(version: number): Buffer[] {
+  return version === 0 ? [] : [Buffer.from([version])];
+}
+
 /**
  * Derive AMM pool PDA from token pair (uses canonical ordering)
+ *
+ * `version` selects a successor pool after migrations (0 = original pool).
  */
 export function deriveAmmPoolPda(
   tokenAMint: PublicKey,
   tokenBMint: PublicKey,
-  programId: PublicKey = PROGRAM_ID
+  programId: PublicKey = PROGRAM_ID,
+  version: number = 0
 ): [PublicKey, number] {
   // Canonical ordering: lower pubkey bytes first
   const [first, second] = tokenAMint.toBuffer().compare(tokenBMint.toBuffer()) < 0
@@ -116,18 +243,21 @@ export function deriveAmmPoolPda(
     : [tokenBMint, tokenAMint];
 
   return PublicKey.findProgramAddressSync(
-    [SEEDS.AMM_POOL, first.toBuffer(), second.toBuffer()],
+    [SEEDS.AMM_POOL, first.toBuffer(), second.toBuffer(), ...ammVersionSeeds(version)],
     programId
   );
 }
 
 /**
  * Derive LP mint PDA from token pair (uses canonical ordering)
+ *
+ * `version` selects a successor pool's LP mint (0 = original pool).
  */
 export function deriveLpMintPda(
   tokenAMint: PublicKey,
   tokenBMint: PublicKey,
-  programId: PublicKey = PROGRAM_ID
+  programId: PublicKey = PROGRAM_ID,
+  version: number = 0
 ): [PublicKey, number] {
   // Canonical ordering: lower pubkey bytes first
   const [first, second] = tokenAMint.toBuffer().compare(tokenBMint.toBuffer()) < 0
@@ -135,7 +265,154 @@ export function deriveLpMintPda(
     : [tokenBMint, tokenAMint];
 
   return PublicKey.findProgramAddressSync(
-    [SEEDS.LP_MINT, first.toBuffer(), second.toBuffer()],
+    [SEEDS.LP_MINT, first.toBuffer(), second.toBuffer(), ...ammVersionSeeds(version)],
+    programId
+  );
+}
+
+/**
+ * Derive AMM pool migration PDA (keyed by the migrated pool)
+ */
+export function deriveAmmPoolMigrationPda(
+  oldAmmPool: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [SEEDS.AMM_MIGRATION, oldAmmPool.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Derive LP lock PDA (keyed by the LP commitment)
+ */
+export function deriveLpLockPda(lpCommitment: Uint8Array, programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [SEEDS.LP_LOCK, Buffer.from(lpCommitment)],
+    programId
+  );
+}
+
+/**
+ * Derive AMM pool creator allowlist PDA (singleton)
+ */
+export function derivePoolCreatorAllowlistPda(programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.POOL_CREATOR_ALLOWLIST], programId);
+}
+
+/**
+ * Derive dust sweep ledger PDA (singleton)
+ */
+export function deriveDustSweepLedgerPda(programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.DUST_SWEEP_LEDGER], programId);
+}
+
+/**
+ * Derive the treasury converter PDA (SPL delegate of the treasury's fee accounts)
+ */
+export function deriveTreasuryConverterPda(programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.TREASURY_CONVERTER], programId);
+}
+
+/**
+ * Derive the buyback config PDA (singleton)
+ */
+export function deriveBuybackConfigPda(programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.BUYBACK_CONFIG], programId);
+}
+
+/**
+ * Derive the protocol token stake registry PDA (singleton)
+ */
+export function deriveStakeRegistryPda(programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.STAKE_REGISTRY], programId);
+}
+
+/**
+ * Derive the stake vault PDA (token account holding staked protocol tokens)
+ */
+export function deriveStakeVaultPda(programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.STAKE_VAULT], programId);
+}
+
+/**
+ * Derive an AMM or perps pool's emissions schedule PDA
+ */
+export function deriveEmissionsSchedulePda(sourcePool: PublicKey, programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.EMISSIONS, sourcePool.toBuffer()], programId);
+}
+
+/**
+ * Derive an emissions schedule's reward vault PDA
+ */
+export function deriveEmissionsVaultPda(schedule: PublicKey, programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.EMISSIONS_VAULT, schedule.toBuffer()], programId);
+}
+
+/**
+ * Derive the savings vault PDA over an AMM or perps pool's LP
+ */
+export function deriveSavingsVaultPda(sourcePool: PublicKey, programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.SAVINGS_VAULT, sourcePool.toBuffer()], programId);
+}
+
+/**
+ * Derive a savings vault's share mint PDA
+ */
+export function deriveVaultShareMintPda(savingsVault: PublicKey, programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.VAULT_SHARE_MINT, savingsVault.toBuffer()], programId);
+}
+
+/**
+ * Derive the operation credit config PDA (singleton)
+ */
+export function deriveCreditConfigPda(programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.CREDIT_CONFIG], programId);
+}
+
+/**
+ * Derive a credit epoch's credit tree PDA
+ */
+export function deriveCreditTreePda(epoch: bigint, programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  const epochBytes = Buffer.alloc(8);
+  epochBytes.writeBigUInt64LE(epoch);
+  return PublicKey.findProgramAddressSync([SEEDS.CREDIT_TREE, epochBytes], programId);
+}
+
+/**
+ * Derive a staker's credit issuance PDA
+ */
+export function deriveCreditIssuancePda(staker: PublicKey, programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.CREDIT_ISSUANCE, staker.toBuffer()], programId);
+}
+
+/**
+ * Derive a spent operation credit PDA
+ */
+export function deriveOperationCreditPda(creditNullifier: Uint8Array, programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.CREDIT_NULLIFIER, Buffer.from(creditNullifier)], programId);
+}
+
+/**
+ * Derive the tree registry PDA (singleton)
+ */
+export function deriveTreeRegistryPda(programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.TREE_REGISTRY], programId);
+}
+
+/**
+ * Derive a pool's relayer allowlist PDA
+ */
+export function deriveRelayerAllowlistPda(pool: PublicKey, programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([SEEDS.RELAYER_ALLOWLIST, pool.toBuffer()], programId);
+}
+
+/**
+ * Derive adapt module PDA (whitelisted external program)
+ */
+export function deriveAdaptModulePda(adapterProgram: PublicKey, programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [SEEDS.ADAPT_MODULE, adapterProgram.toBuffer()],
     programId
   );
 }
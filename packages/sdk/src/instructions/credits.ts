@@ -0,0 +1,246 @@
+/**
+ * Operation Credit Instructions
+ *
+ * Anonymous per-user rate limiting. Stakers claim (or anyone buys) credit
+ * leaves committing to secrets; a relayer spends one credit per operation
+ * with a membership proof before the operation's Phase 0. The proof does
+ * not reveal which leaf is spent, so spends are not linked to issuance.
+ */
+
+import { PublicKey, SystemProgram } from '@solana/web3.js';
+import { Program, BN } from '@coral-xyz/anchor';
+
+import {
+  deriveProtocolConfigPda,
+  deriveStakeRegistryPda,
+  deriveVerificationKeyPda,
+  deriveCreditConfigPda,
+  deriveCreditTreePda,
+  deriveCreditIssuancePda,
+  deriveOperationCreditPda,
+  CIRCUIT_IDS,
+} from './constants';
+import { buildAdminAuditRemainingAccounts } from './admin-audit';
+import {
+  DOMAIN_OPERATION_CREDIT,
+  DOMAIN_CREDIT_NULLIFIER,
+  poseidonHashDomain,
+} from '../crypto/poseidon';
+
+/**
+ * Credit leaf appended to the epoch's credit tree: Poseidon(OPERATION_CREDIT, secret)
+ */
+export function computeCreditLeaf(secret: Uint8Array): Uint8Array {
+  return poseidonHashDomain(DOMAIN_OPERATION_CREDIT, secret);
+}
+
+/**
+ * Nullifier published when a credit is spent: Poseidon(CREDIT_NULLIFIER, secret)
+ */
+export function computeCreditNullifier(secret: Uint8Array): Uint8Array {
+  return poseidonHashDomain(DOMAIN_CREDIT_NULLIFIER, secret);
+}
+
+/**
+ * Credit epoch containing `timestamp` (unix seconds, matches CreditConfig::epoch_at)
+ */
+export function computeCreditEpoch(timestamp: number, epochSeconds: number): bigint {
+  return BigInt(Math.floor(Math.max(timestamp, 0) / epochSeconds));
+}
+
+/**
+ * Build initialize_credit_config transaction using Anchor program
+ *
+ * Credits are not required until enabled with set_credit_policy.
+ */
+export async function buildInitializeCreditConfigWithProgram(
+  program: Program,
+  params: {
+    epochSeconds: number;
+    creditsPerEpoch: number;
+    minStake: bigint;
+    /** Price of one credit (0 disables purchases) */
+    feeLamports: bigint;
+    authority: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .initializeCreditConfig(
+      new BN(params.epochSeconds),
+      params.creditsPerEpoch,
+      new BN(params.minStake.toString()),
+      new BN(params.feeLamports.toString())
+    )
+    .accountsStrict({
+      creditConfig: deriveCreditConfigPda(programId)[0],
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      authority: params.authority,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(programId));
+
+  return tx;
+}
+
+/**
+ * Build set_credit_policy transaction using Anchor program
+ *
+ * `requireCredits` makes every Phase 0 (transfer, swap, perps, voting,
+ * market, ...) require a spent credit (`operationCredit` in their params).
+ */
+export async function buildSetCreditPolicyWithProgram(
+  program: Program,
+  params: {
+    creditsPerEpoch: number;
+    minStake: bigint;
+    feeLamports: bigint;
+    requireCredits: boolean;
+    authority: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .setCreditPolicy(
+      params.creditsPerEpoch,
+      new BN(params.minStake.toString()),
+      new BN(params.feeLamports.toString()),
+      params.requireCredits
+    )
+    .accountsStrict({
+      creditConfig: deriveCreditConfigPda(programId)[0],
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      authority: params.authority,
+    })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(programId));
+
+  return tx;
+}
+
+/**
+ * Build claim_staked_credits transaction using Anchor program
+ *
+ * `leaves` are credit leaves (see computeCreditLeaf); keep the secrets to
+ * spend them later.
+ */
+export async function buildClaimStakedCreditsWithProgram(
+  program: Program,
+  params: {
+    epoch: bigint;
+    leaves: Uint8Array[];
+    staker: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .claimStakedCredits(new BN(params.epoch.toString()), params.leaves.map(l => Array.from(l)))
+    .accountsStrict({
+      creditConfig: deriveCreditConfigPda(programId)[0],
+      creditTree: deriveCreditTreePda(params.epoch, programId)[0],
+      stakeRegistry: deriveStakeRegistryPda(programId)[0],
+      creditIssuance: deriveCreditIssuancePda(params.staker, programId)[0],
+      staker: params.staker,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return tx;
+}
+
+/**
+ * Build purchase_credits transaction using Anchor program
+ *
+ * The buyer pays the credit fee per leaf to the protocol treasury.
+ */
+export async function buildPurchaseCreditsWithProgram(
+  program: Program,
+  params: {
+    epoch: bigint;
+    leaves: Uint8Array[];
+    treasury: PublicKey;
+    buyer: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .purchaseCredits(new BN(params.epoch.toString()), params.leaves.map(l => Array.from(l)))
+    .accountsStrict({
+      creditConfig: deriveCreditConfigPda(programId)[0],
+      creditTree: deriveCreditTreePda(params.epoch, programId)[0],
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      treasury: params.treasury,
+      buyer: params.buyer,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return tx;
+}
+
+/**
+ * Build consume_operation_credit transaction using Anchor program
+ *
+ * Send before the operation's Phase 0, then pass the returned
+ * `operationCredit` PDA to the Phase 0 builder. The proof binds
+ * `operationId`, so it cannot be reused for another operation.
+ */
+export async function buildConsumeOperationCreditWithProgram(
+  program: Program,
+  params: {
+    operationId: Uint8Array;
+    epoch: bigint;
+    proof: Uint8Array;
+    creditRoot: Uint8Array;
+    creditNullifier: Uint8Array;
+    relayer: PublicKey;
+  }
+): Promise<{ tx: any; operationCredit: PublicKey }> {
+  const programId = program.programId;
+  const [operationCredit] = deriveOperationCreditPda(params.creditNullifier, programId);
+
+  const tx = await program.methods
+    .consumeOperationCredit(
+      Array.from(params.operationId),
+      new BN(params.epoch.toString()),
+      Buffer.from(params.proof),
+      Array.from(params.creditRoot),
+      Array.from(params.creditNullifier)
+    )
+    .accountsStrict({
+      creditConfig: deriveCreditConfigPda(programId)[0],
+      creditTree: deriveCreditTreePda(params.epoch, programId)[0],
+      verificationKey: deriveVerificationKeyPda(CIRCUIT_IDS.OPERATION_CREDIT, programId)[0],
+      operationCredit,
+      relayer: params.relayer,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return { tx, operationCredit };
+}
+
+/**
+ * Build close_operation_credit transaction using Anchor program
+ *
+ * Only after the credit's epoch has ended; refunds the relayer.
+ */
+export async function buildCloseOperationCreditWithProgram(
+  program: Program,
+  params: {
+    creditNullifier: Uint8Array;
+    relayer: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .closeOperationCredit()
+    .accountsStrict({
+      creditConfig: deriveCreditConfigPda(programId)[0],
+      operationCredit: deriveOperationCreditPda(params.creditNullifier, programId)[0],
+      relayer: params.relayer,
+    });
+
+  return tx;
+}
@@ -0,0 +1,171 @@
+/**
+ * Market Escrow Yield Instructions
+ *
+ * Makers opt an open order's escrow into the offer pool's yield policy. The
+ * policy authority deploys idle escrow to a yield adapter and recalls it
+ * before fills; yield accrued while the order was open is claimed as a bonus
+ * note: Phase 0 (proof) → Phase 3 (execute) → create commitment.
+ */
+
+import { PublicKey, SystemProgram } from '@solana/web3.js';
+import { Program, BN } from '@coral-xyz/anchor';
+
+import {
+  PROGRAM_ID,
+  derivePoolPda,
+  padCircuitId,
+  deriveProtocolConfigPda,
+  deriveProgramVersionPda,
+  CLIENT_VERSION,
+} from './constants';
+import { deriveOrderPda } from './market';
+
+export const ESCROW_YIELD_SEEDS = {
+  ESCROW_YIELD: Buffer.from('escrow_yield'),
+  ORDER_YIELD: Buffer.from('order_yield'),
+  PENDING_OP: Buffer.from('pending_op'),
+  VK: Buffer.from('vk'),
+} as const;
+
+export const ESCROW_YIELD_OPT_IN_CIRCUIT_ID = padCircuitId('market_escrow_yield_opt_in');
+export const ESCROW_YIELD_CLAIM_CIRCUIT_ID = padCircuitId('market_escrow_yield_claim');
+
+/**
+ * Derive escrow yield policy PDA (one per offer token pool)
+ */
+export function deriveEscrowYieldPolicyPda(
+  pool: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [ESCROW_YIELD_SEEDS.ESCROW_YIELD, pool.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Derive order yield PDA (keyed by order ID)
+ */
+export function deriveOrderYieldPda(
+  orderId: Uint8Array,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [ESCROW_YIELD_SEEDS.ORDER_YIELD, Buffer.from(orderId)],
+    programId
+  );
+}
+
+export interface OptInEscrowYieldParams {
+  orderId: Uint8Array;
+  /** Offer token mint */
+  offerMint: PublicKey;
+  proof: Uint8Array;
+  escrowAmount: bigint;
+  /** hash(yieldKey, orderId), proven again at claim time */
+  yieldTag: Uint8Array;
+  payer: PublicKey;
+}
+
+export async function buildOptInEscrowYieldWithProgram(
+  program: Program,
+  params: OptInEscrowYieldParams
+): Promise<{ tx: any; orderYield: PublicKey }> {
+  const programId = program.programId;
+  const [pool] = derivePoolPda(params.offerMint, programId);
+  const [order] = deriveOrderPda(params.orderId, programId);
+  const [escrowYieldPolicy] = deriveEscrowYieldPolicyPda(pool, programId);
+  const [orderYield] = deriveOrderYieldPda(params.orderId, programId);
+  const [verificationKey] = PublicKey.findProgramAddressSync(
+    [ESCROW_YIELD_SEEDS.VK, ESCROW_YIELD_OPT_IN_CIRCUIT_ID],
+    programId
+  );
+
+  const tx = await program.methods
+    .optInEscrowYield(
+      Array.from(params.orderId),
+      Buffer.from(params.proof),
+      new BN(params.escrowAmount.toString()),
+      Array.from(params.yieldTag)
+    )
+    .accountsStrict({
+      order,
+      pool,
+      escrowYieldPolicy,
+      orderYield,
+      verificationKey,
+      payer: params.payer,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return { tx, orderYield };
+}
+
+export interface ClaimEscrowYieldParams {
+  operationId: Uint8Array;
+  orderId: Uint8Array;
+  /** Offer token mint (bonus note token) */
+  offerMint: PublicKey;
+  proof: Uint8Array;
+  bonusCommitment: Uint8Array;
+  yieldAmount: bigint;
+  relayer: PublicKey;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+}
+
+/**
+ * Build Phase 0 and Phase 3 transactions for an escrow yield claim
+ */
+export async function buildClaimEscrowYieldWithProgram(
+  program: Program,
+  params: ClaimEscrowYieldParams
+): Promise<{ tx: any; phase3Tx: any; pendingOperation: PublicKey }> {
+  const programId = program.programId;
+  const [pool] = derivePoolPda(params.offerMint, programId);
+  const [escrowYieldPolicy] = deriveEscrowYieldPolicyPda(pool, programId);
+  const [orderYield] = deriveOrderYieldPda(params.orderId, programId);
+  const [verificationKey] = PublicKey.findProgramAddressSync(
+    [ESCROW_YIELD_SEEDS.VK, ESCROW_YIELD_CLAIM_CIRCUIT_ID],
+    programId
+  );
+  const [pendingOperation] = PublicKey.findProgramAddressSync(
+    [ESCROW_YIELD_SEEDS.PENDING_OP, Buffer.from(params.operationId)],
+    programId
+  );
+
+  const tx = await program.methods
+    .createPendingWithProofClaimEscrowYield(
+      Array.from(params.operationId),
+      Array.from(params.orderId),
+      Buffer.from(params.proof),
+      Array.from(params.bonusCommitment),
+      new BN(params.yieldAmount.toString()),
+      CLIENT_VERSION
+    )
+    .accountsStrict({
+      orderYield,
+      escrowYieldPolicy,
+      pool,
+      verificationKey,
+      pendingOperation,
+      relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      programVersion: deriveProgramVersionPda(programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
+      systemProgram: SystemProgram.programId,
+    });
+
+  const phase3Tx = await program.methods
+    .executeClaimEscrowYield(Array.from(params.operationId))
+    .accountsStrict({
+      orderYield,
+      pendingOperation,
+      relayer: params.relayer,
+    });
+
+  return { tx, phase3Tx, pendingOperation };
+}
@@ -0,0 +1,176 @@
+/**
+ * Swap Fee Rebate Instructions
+ *
+ * Volume is tracked per blinded tag `hash(volumeKey, epoch)` by passing the
+ * swap volume record to executeSwap. After the epoch ends the trader claims
+ * a rebate note: Phase 0 (proof) → Phase 3 (execute) → create commitment.
+ */
+
+import { PublicKey, SystemProgram } from '@solana/web3.js';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Program, BN } from '@coral-xyz/anchor';
+
+import { PROGRAM_ID, derivePoolPda, deriveVaultPda, padCircuitId, deriveProtocolConfigPda, deriveProgramVersionPda, CLIENT_VERSION } from './constants';
+
+export const FEE_REBATE_SEEDS = {
+  FEE_REBATE: Buffer.from('fee_rebate'),
+  FEE_REBATE_VAULT: Buffer.from('fee_rebate_vault'),
+  SWAP_VOLUME: Buffer.from('swap_volume'),
+  PENDING_OP: Buffer.from('pending_op'),
+  VK: Buffer.from('vk'),
+} as const;
+
+export const FEE_REBATE_CLAIM_CIRCUIT_ID = padCircuitId('fee_rebate_claim');
+
+export interface RebateTier {
+  /** Minimum epoch volume (token A base units) */
+  minVolume: bigint;
+  /** Rebate in basis points */
+  rebateBps: number;
+}
+
+export function deriveFeeRebateConfigPda(
+  ammPool: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [FEE_REBATE_SEEDS.FEE_REBATE, ammPool.toBuffer()],
+    programId
+  );
+}
+
+export function deriveFeeRebateVaultPda(
+  config: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [FEE_REBATE_SEEDS.FEE_REBATE_VAULT, config.toBuffer()],
+    programId
+  );
+}
+
+export function deriveSwapVolumePda(
+  config: PublicKey,
+  epoch: bigint,
+  volumeTag: Uint8Array,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  const epochBytes = Buffer.alloc(8);
+  epochBytes.writeBigUInt64LE(epoch);
+  return PublicKey.findProgramAddressSync(
+    [FEE_REBATE_SEEDS.SWAP_VOLUME, config.toBuffer(), epochBytes, Buffer.from(volumeTag)],
+    programId
+  );
+}
+
+/**
+ * Rebate for an epoch's volume (mirrors FeeRebateConfig::rebate_for)
+ */
+export function calculateFeeRebate(volume: bigint, tiers: RebateTier[]): bigint {
+  let bps = 0;
+  for (const tier of tiers) {
+    if (tier.rebateBps > 0 && volume >= tier.minVolume) {
+      bps = tier.rebateBps;
+    }
+  }
+  return (volume * BigInt(bps)) / 10000n;
+}
+
+export async function buildInitSwapVolumeWithProgram(
+  program: Program,
+  params: { ammPool: PublicKey; volumeTag: Uint8Array; epoch: bigint; payer: PublicKey }
+): Promise<{ tx: any; swapVolume: PublicKey }> {
+  const programId = program.programId;
+  const [config] = deriveFeeRebateConfigPda(params.ammPool, programId);
+  const [swapVolume] = deriveSwapVolumePda(config, params.epoch, params.volumeTag, programId);
+
+  const tx = await program.methods
+    .initSwapVolume(Array.from(params.volumeTag), new BN(params.epoch.toString()))
+    .accountsStrict({
+      feeRebateConfig: config,
+      swapVolume,
+      payer: params.payer,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return { tx, swapVolume };
+}
+
+export interface ClaimFeeRebateParams {
+  operationId: Uint8Array;
+  ammPool: PublicKey;
+  /** Rebate token mint (AMM token A) */
+  rebateMint: PublicKey;
+  volumeTag: Uint8Array;
+  epoch: bigint;
+  proof: Uint8Array;
+  rebateCommitment: Uint8Array;
+  rebateAmount: bigint;
+  relayer: PublicKey;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+}
+
+/**
+ * Build Phase 0 and Phase 3 transactions for a fee rebate claim
+ */
+export async function buildClaimFeeRebateWithProgram(
+  program: Program,
+  params: ClaimFeeRebateParams
+): Promise<{ tx: any; phase3Tx: any; pendingOperation: PublicKey }> {
+  const programId = program.programId;
+  const [config] = deriveFeeRebateConfigPda(params.ammPool, programId);
+  const [rebateVault] = deriveFeeRebateVaultPda(config, programId);
+  const [swapVolume] = deriveSwapVolumePda(config, params.epoch, params.volumeTag, programId);
+  const [rebatePool] = derivePoolPda(params.rebateMint, programId);
+  const [rebatePoolVault] = deriveVaultPda(params.rebateMint, programId);
+  const [verificationKey] = PublicKey.findProgramAddressSync(
+    [FEE_REBATE_SEEDS.VK, FEE_REBATE_CLAIM_CIRCUIT_ID],
+    programId
+  );
+  const [pendingOperation] = PublicKey.findProgramAddressSync(
+    [FEE_REBATE_SEEDS.PENDING_OP, Buffer.from(params.operationId)],
+    programId
+  );
+
+  const tx = await program.methods
+    .createPendingWithProofClaimFeeRebate(
+      Array.from(params.operationId),
+      Array.from(params.volumeTag),
+      new BN(params.epoch.toString()),
+      Buffer.from(params.proof),
+      Array.from(params.rebateCommitment),
+      new BN(params.rebateAmount.toString()),
+      CLIENT_VERSION
+    )
+    .accountsStrict({
+      feeRebateConfig: config,
+      swapVolume,
+      rebatePool,
+      verificationKey,
+      pendingOperation,
+      relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
+      systemProgram: SystemProgram.programId,
+    });
+
+  const phase3Tx = await program.methods
+    .executeClaimFeeRebate(Array.from(params.operationId))
+    .accountsStrict({
+      feeRebateConfig: config,
+      rebateVault,
+      swapVolume,
+      rebatePool,
+      rebatePoolVault,
+      pendingOperation,
+      relayer: params.relayer,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  return { tx, phase3Tx, pendingOperation };
+}
@@ -17,3 +17,15 @@ export * from './store-commitment';
 export * from './initialize';
 export * from './swap';
 export * from './market';
+export * from './fee-rebate';
+export * from './escrow-yield';
+export * from './admin-audit';
+export * from './checkpoints';
+export * from './root-registry';
+export * from './relayer-stake';
+export * from './nft';
+export * from './cnft';
+export * from './protocol-fees';
+export * from './buyback';
+export * from './savings';
+export * from './credits';
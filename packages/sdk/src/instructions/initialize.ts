@@ -6,14 +6,20 @@
 
 import {
   PublicKey,
+  SystemProgram,
 } from '@solana/web3.js';
-import { Program } from '@coral-xyz/anchor';
+import { Program, BN } from '@coral-xyz/anchor';
 
 import {
   derivePoolPda,
   deriveVaultPda,
   deriveCommitmentCounterPda,
+  deriveRelayerAllowlistPda,
+  deriveProtocolConfigPda,
+  deriveTreeRegistryPda,
+  DEVNET_V2_TREES,
 } from './constants';
+import { buildAdminAuditRemainingAccounts } from './admin-audit';
 
 /**
  * Initialize pool parameters
@@ -25,6 +31,10 @@ export interface InitializePoolParams {
   authority: PublicKey;
   /** Payer for account creation */
   payer: PublicKey;
+  /** Output state tree (V2 output queue) the pool writes to (default: devnet output queue) */
+  stateTree?: PublicKey;
+  /** Address tree for commitment / nullifier addresses (default: devnet address tree) */
+  addressTree?: PublicKey;
 }
 
 /**
@@ -42,7 +52,10 @@ export async function buildInitializePoolWithProgram(
 
   // Build transaction using Anchor (use accountsPartial like scalecraft)
   const tx = await program.methods
-    .initializePool()
+    .initializePool(
+      params.stateTree ?? DEVNET_V2_TREES.OUTPUT_QUEUE,
+      params.addressTree ?? DEVNET_V2_TREES.ADDRESS_TREE
+    )
     .accountsPartial({
       pool: poolPda,
       tokenVault: vaultPda,
@@ -84,6 +97,38 @@ export async function buildInitializeCommitmentCounterWithProgram(
   return tx;
 }
 
+/**
+ * Build reconcile_commitment_counter transaction using Anchor program
+ *
+ * Moves a pool's commitment counter forward to `nextIndex` (one past the
+ * highest commitment index stored in the pool). Protocol authority only.
+ */
+export async function buildReconcileCommitmentCounterWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    nextIndex: bigint;
+    authority: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const [poolPda] = derivePoolPda(params.tokenMint, programId);
+  const [counterPda] = deriveCommitmentCounterPda(poolPda, programId);
+
+  const tx = await program.methods
+    .reconcileCommitmentCounter(new BN(params.nextIndex.toString()))
+    .accountsStrict({
+      pool: poolPda,
+      commitmentCounter: counterPda,
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      authority: params.authority,
+    })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(programId));
+
+  return tx;
+}
+
 /**
  * Initialize a new pool with commitment counter
  *
@@ -134,3 +179,454 @@ export async function initializePool(
 
   return { poolTx, counterTx };
 }
+
+/**
+ * Build set_anonymity_guard transaction using Anchor program
+ *
+ * Unshields are rejected while the pool's recent activity (shields + spends
+ * over the current and previous day) is below `minAnonymityGuard`. 0 disables.
+ */
+export async function buildSetAnonymityGuardWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    authority: PublicKey;
+    minAnonymityGuard: number;
+  }
+): Promise<any> {
+  const [poolPda] = derivePoolPda(params.tokenMint, program.programId);
+
+  const tx = await program.methods
+    .setAnonymityGuard(params.minAnonymityGuard)
+    .accountsStrict({
+      pool: poolPda,
+      authority: params.authority,
+    });
+
+  return tx;
+}
+
+/**
+ * Build override_anonymity_guard transaction using Anchor program
+ *
+ * Bypasses the guard for up to one day and emits `AnonymityGuardOverridden`.
+ */
+export async function buildOverrideAnonymityGuardWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    authority: PublicKey;
+    durationSeconds: number;
+  }
+): Promise<any> {
+  const [poolPda] = derivePoolPda(params.tokenMint, program.programId);
+
+  const tx = await program.methods
+    .overrideAnonymityGuard(new BN(params.durationSeconds))
+    .accountsStrict({
+      pool: poolPda,
+      authority: params.authority,
+    });
+
+  return tx;
+}
+
+/** Maximum denominations per pool (matches MAX_DENOMINATIONS on-chain) */
+export const MAX_DENOMINATIONS = 8;
+
+/**
+ * Build set_fixed_denominations transaction using Anchor program
+ *
+ * Pass an empty list to return the pool to flexible amounts. Transfers in
+ * denomination pools must use CIRCUIT_IDS.TRANSFER_1X2_DENOM.
+ */
+export async function buildSetFixedDenominationsWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    authority: PublicKey;
+    /** Ascending denominations (at most MAX_DENOMINATIONS) */
+    denominations: bigint[];
+  }
+): Promise<any> {
+  if (params.denominations.length > MAX_DENOMINATIONS) {
+    throw new Error(`At most ${MAX_DENOMINATIONS} denominations allowed`);
+  }
+  const [poolPda] = derivePoolPda(params.tokenMint, program.programId);
+  const padded = [
+    ...params.denominations,
+    ...Array(MAX_DENOMINATIONS - params.denominations.length).fill(0n),
+  ];
+
+  const tx = await program.methods
+    .setFixedDenominations(padded.map((d) => new BN(d.toString())))
+    .accountsStrict({
+      pool: poolPda,
+      authority: params.authority,
+    });
+
+  return tx;
+}
+
+/**
+ * Build set_min_note_amount transaction using Anchor program
+ *
+ * Change notes below `minNoteAmount` are rejected. Pass 0n to disable.
+ */
+export async function buildSetMinNoteAmountWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    authority: PublicKey;
+    minNoteAmount: bigint;
+  }
+): Promise<any> {
+  const [poolPda] = derivePoolPda(params.tokenMint, program.programId);
+
+  const tx = await program.methods
+    .setMinNoteAmount(new BN(params.minNoteAmount.toString()))
+    .accountsStrict({
+      pool: poolPda,
+      authority: params.authority,
+    });
+
+  return tx;
+}
+
+/**
+ * Build set_max_encrypted_note_size transaction using Anchor program
+ *
+ * Lets create_commitment store extended notes (memos, multi-asset notes) up
+ * to `maxEncryptedNoteSize` bytes: at least 250 (inline), at most 512.
+ */
+export async function buildSetMaxEncryptedNoteSizeWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    authority: PublicKey;
+    maxEncryptedNoteSize: number;
+  }
+): Promise<any> {
+  const [poolPda] = derivePoolPda(params.tokenMint, program.programId);
+
+  const tx = await program.methods
+    .setMaxEncryptedNoteSize(params.maxEncryptedNoteSize)
+    .accountsStrict({
+      pool: poolPda,
+      authority: params.authority,
+    });
+
+  return tx;
+}
+
+/**
+ * Build set_pinned_inclusion_threshold transaction using Anchor program
+ *
+ * Operations moving at least `pinnedInclusionThreshold` through the pool
+ * must verify inputs with a validity proof against an explicit root index
+ * (no `proveByIndex`). Pass 0n to disable.
+ */
+export async function buildSetPinnedInclusionThresholdWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    authority: PublicKey;
+    pinnedInclusionThreshold: bigint;
+  }
+): Promise<any> {
+  const [poolPda] = derivePoolPda(params.tokenMint, program.programId);
+
+  const tx = await program.methods
+    .setPinnedInclusionThreshold(new BN(params.pinnedInclusionThreshold.toString()))
+    .accountsStrict({
+      pool: poolPda,
+      authority: params.authority,
+    });
+
+  return tx;
+}
+
+/**
+ * Build set_surplus_amm_pool transaction using Anchor program
+ *
+ * Designates the AMM pool sync_reserves folds this pool's vault surplus
+ * into. Pass PublicKey.default to stop folding.
+ */
+export async function buildSetSurplusAmmPoolWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    authority: PublicKey;
+    surplusAmmPool: PublicKey;
+  }
+): Promise<any> {
+  const [poolPda] = derivePoolPda(params.tokenMint, program.programId);
+
+  const tx = await program.methods
+    .setSurplusAmmPool(params.surplusAmmPool)
+    .accountsStrict({
+      pool: poolPda,
+      authority: params.authority,
+    });
+
+  return tx;
+}
+
+/**
+ * Build initialize_relayer_allowlist transaction using Anchor program
+ *
+ * Creates the pool's empty relayer allowlist; the pool stays permissionless
+ * until buildSetRelayerAllowlistEnabledWithProgram turns it on.
+ */
+export async function buildInitializeRelayerAllowlistWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    authority: PublicKey;
+    payer: PublicKey;
+  }
+): Promise<any> {
+  const [poolPda] = derivePoolPda(params.tokenMint, program.programId);
+
+  const tx = await program.methods
+    .initializeRelayerAllowlist()
+    .accountsStrict({
+      relayerAllowlist: deriveRelayerAllowlistPda(poolPda, program.programId)[0],
+      pool: poolPda,
+      authority: params.authority,
+      payer: params.payer,
+      systemProgram: new PublicKey('11111111111111111111111111111111'),
+    });
+
+  return tx;
+}
+
+/**
+ * Build set_allowed_relayer transaction using Anchor program
+ */
+export async function buildSetAllowedRelayerWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    authority: PublicKey;
+    relayer: PublicKey;
+    /** true to add, false to remove */
+    allowed: boolean;
+  }
+): Promise<any> {
+  const [poolPda] = derivePoolPda(params.tokenMint, program.programId);
+
+  const tx = await program.methods
+    .setAllowedRelayer(params.relayer, params.allowed)
+    .accountsStrict({
+      relayerAllowlist: deriveRelayerAllowlistPda(poolPda, program.programId)[0],
+      pool: poolPda,
+      authority: params.authority,
+    });
+
+  return tx;
+}
+
+/**
+ * Build set_relayer_allowlist_enabled transaction using Anchor program
+ *
+ * While enabled, Phase 0 of operations spending from the pool must be
+ * submitted by an allowlisted relayer that passes the pool's allowlist PDA
+ * (`relayerAllowlist` in the Phase 0 params).
+ */
+export async function buildSetRelayerAllowlistEnabledWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    authority: PublicKey;
+    enabled: boolean;
+  }
+): Promise<any> {
+  const [poolPda] = derivePoolPda(params.tokenMint, program.programId);
+
+  const tx = await program.methods
+    .setRelayerAllowlistEnabled(params.enabled)
+    .accountsStrict({
+      pool: poolPda,
+      relayerAllowlist: deriveRelayerAllowlistPda(poolPda, program.programId)[0],
+      authority: params.authority,
+    });
+
+  return tx;
+}
+
+/**
+ * Build migrate_pool_trees transaction using Anchor program
+ *
+ * Schedules a state tree rollover: from `cutoverSlot` on, new commitments and
+ * nullifiers must use `newStateTree`. Notes in the old tree stay spendable.
+ * The address tree is fixed for the pool's lifetime. `newStateTree` must be
+ * registered in the tree registry and have headroom (see
+ * buildRegisterStateTreeWithProgram).
+ */
+export async function buildMigratePoolTreesWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    authority: PublicKey;
+    /** New output state tree (V2 output queue) */
+    newStateTree: PublicKey;
+    /** First slot at which the new tree is required */
+    cutoverSlot: bigint;
+  }
+): Promise<any> {
+  const [poolPda] = derivePoolPda(params.tokenMint, program.programId);
+
+  const tx = await program.methods
+    .migratePoolTrees(new BN(params.cutoverSlot.toString()))
+    .accountsStrict({
+      pool: poolPda,
+      newStateTree: params.newStateTree,
+      treeRegistry: deriveTreeRegistryPda(program.programId)[0],
+      authority: params.authority,
+    });
+
+  return tx;
+}
+
+/**
+ * Build migrate_pool transaction using Anchor program
+ *
+ * Grows a pool created with an older layout to the current size, with the
+ * added fields zeroed. Permissionless; the payer covers the extra rent.
+ */
+export async function buildMigratePoolWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    payer: PublicKey;
+  }
+): Promise<any> {
+  const [poolPda] = derivePoolPda(params.tokenMint, program.programId);
+
+  const tx = await program.methods
+    .migratePool(params.tokenMint)
+    .accountsStrict({
+      pool: poolPda,
+      payer: params.payer,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return tx;
+}
+
+/**
+ * Build set_pool_trees transaction using Anchor program
+ *
+ * Pins the Light trees of a pool grown by migrate_pool, which leaves them
+ * unset. `addressTree` must be the tree the pool's existing commitments and
+ * nullifiers were derived in. Only works while the trees are unset.
+ */
+export async function buildSetPoolTreesWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    authority: PublicKey;
+    /** Output state tree (V2 output queue) */
+    stateTree: PublicKey;
+    /** Address tree of the pool's existing accounts */
+    addressTree: PublicKey;
+  }
+): Promise<any> {
+  const [poolPda] = derivePoolPda(params.tokenMint, program.programId);
+
+  const tx = await program.methods
+    .setPoolTrees(params.stateTree, params.addressTree)
+    .accountsStrict({
+      pool: poolPda,
+      authority: params.authority,
+    });
+
+  return tx;
+}
+
+/**
+ * Build initialize_tree_registry transaction using Anchor program
+ *
+ * `nearCapacityBps` is the share of a tree's capacity hint at which
+ * TreeNearCapacity is emitted (100 - 10000).
+ */
+export async function buildInitializeTreeRegistryWithProgram(
+  program: Program,
+  params: {
+    nearCapacityBps: number;
+    authority: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .initializeTreeRegistry(params.nearCapacityBps)
+    .accountsStrict({
+      treeRegistry: deriveTreeRegistryPda(programId)[0],
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      authority: params.authority,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(programId));
+
+  return tx;
+}
+
+/**
+ * Build register_state_tree transaction using Anchor program
+ *
+ * Registers an output state tree, or updates a registered tree's capacity
+ * hint (re-activating it if retired).
+ */
+export async function buildRegisterStateTreeWithProgram(
+  program: Program,
+  params: {
+    /** Output state tree (V2 output queue) */
+    stateTree: PublicKey;
+    /** Capacity hint in compressed accounts */
+    capacity: bigint;
+    authority: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .registerStateTree(new BN(params.capacity.toString()))
+    .accountsStrict({
+      treeRegistry: deriveTreeRegistryPda(programId)[0],
+      stateTree: params.stateTree,
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      authority: params.authority,
+    })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(programId));
+
+  return tx;
+}
+
+/**
+ * Build retire_state_tree transaction using Anchor program
+ *
+ * A retired tree accepts no new writes from phases passed the registry and
+ * can't be a rollover target.
+ */
+export async function buildRetireStateTreeWithProgram(
+  program: Program,
+  params: {
+    stateTree: PublicKey;
+    authority: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .retireStateTree(params.stateTree)
+    .accountsStrict({
+      treeRegistry: deriveTreeRegistryPda(programId)[0],
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      authority: params.authority,
+    })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(programId));
+
+  return tx;
+}
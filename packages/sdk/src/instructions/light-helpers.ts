@@ -15,7 +15,7 @@ import {
   getBatchAddressTreeInfo,
   Rpc,
 } from '@lightprotocol/stateless.js';
-import { DEVNET_V2_TREES, PROGRAM_ID } from './constants';
+import { DEVNET_V2_TREES, PROGRAM_ID, NULLIFIER_DOMAINS, NullifierDomain, nullifierDomainSeeds } from './constants';
 
 /**
  * Light Protocol RPC client wrapper
@@ -50,13 +50,47 @@ export class LightProtocol {
     return deriveAddressV2(addressSeed, addressTreeInfo.tree, this.programId);
   }
 
+  /**
+   * Derive payment receipt address using Light SDK V2
+   */
+  derivePaymentReceiptAddress(referenceId: Uint8Array): PublicKey {
+    const addressTreeInfo = this.getAddressTreeInfo();
+    const seeds = [
+      Buffer.from('payment_receipt'),
+      Buffer.from(referenceId),
+    ];
+    const addressSeed = deriveAddressSeedV2(seeds);
+    return deriveAddressV2(addressSeed, addressTreeInfo.tree, this.programId);
+  }
+
+  /**
+   * Derive position metadata address using Light SDK V2
+   */
+  derivePositionMetaAddress(poolId: Uint8Array, positionId: Uint8Array): PublicKey {
+    const addressTreeInfo = this.getAddressTreeInfo();
+    const seeds = [
+      Buffer.from('position_meta'),
+      Buffer.from(poolId),
+      Buffer.from(positionId),
+    ];
+    const addressSeed = deriveAddressSeedV2(seeds);
+    return deriveAddressV2(addressSeed, addressTreeInfo.tree, this.programId);
+  }
+
   /**
    * Derive nullifier address using Light SDK V2
+   *
+   * Position notes use their own domain (NULLIFIER_DOMAINS.PERPS_POSITION).
    */
-  deriveNullifierAddress(pool: PublicKey, nullifier: Uint8Array): PublicKey {
+  deriveNullifierAddress(
+    pool: PublicKey,
+    nullifier: Uint8Array,
+    domain: NullifierDomain = NULLIFIER_DOMAINS.SPEND
+  ): PublicKey {
     const addressTreeInfo = this.getAddressTreeInfo();
     const seeds = [
       Buffer.from('spend_nullifier'),
+      ...nullifierDomainSeeds(domain),
       pool.toBuffer(),
       Buffer.from(nullifier),
     ];
@@ -91,6 +125,21 @@ export class LightProtocol {
     return this.rpc.getCompressedAccountProof(hashBn);
   }
 
+  /**
+   * Get the data hash of a compressed account by its hash
+   *
+   * verify_commitment_exists recomputes the commitment's account hash on-chain
+   * from (pool, commitment, tree, leaf index, data hash).
+   */
+  async getDataHashByHash(accountHash: string): Promise<number[]> {
+    const hashBytes = new PublicKey(accountHash).toBytes();
+    const account = await this.rpc.getCompressedAccount(undefined, bn(hashBytes));
+    if (!account?.data) {
+      throw new Error(`Compressed account ${accountHash} not found`);
+    }
+    return Array.from(account.data.dataHash);
+  }
+
   /**
    * Get inclusion validity proof for verifying a compressed account exists.
    *
@@ -231,6 +280,8 @@ export interface LightShieldParams {
 export interface LightTransactParams {
   /** Account hash of input commitment (for state tree verification) */
   commitmentAccountHash: number[];
+  /** Data hash of the commitment account (for on-chain account hash recomputation) */
+  commitmentDataHash: number[];
   /** Commitment merkle context (proves commitment exists in state tree) */
   commitmentMerkleContext: {
     merkleTreePubkeyIndex: number;
@@ -17,10 +17,13 @@ import {
   deriveCommitmentCounterPda,
   deriveVerificationKeyPda,
   CIRCUIT_IDS,
+  deriveTreeRegistryPda,
 } from './constants';
+import { deriveRootRegistryPda } from './root-registry';
 import { LightProtocol } from './light-helpers';
 import { computeCommitment, generateRandomness } from '../crypto/commitment';
 import { encryptNote, serializeEncryptedNote } from '../crypto/encryption';
+import { deriveEscrowYieldPolicyPda, deriveOrderYieldPda } from './escrow-yield';
 
 // =============================================================================
 // Order PDA Derivation
@@ -86,6 +89,8 @@ export interface FillOrderInstructionParams {
     offerAmount: bigint;
     requestAmount: bigint;
   };
+  /** Order escrow opted into the maker pool's yield policy */
+  escrowYieldOptedIn?: boolean;
 }
 
 /**
@@ -198,7 +203,13 @@ export async function buildFillOrderWithProgram(
       takerCommitmentCounter: takerCounterPda,
       order: orderPda,
       verificationKey: vkPda,
+      orderYield: deriveOrderYieldPda(params.orderId, programId)[0],
+      escrowYieldPolicy: params.escrowYieldOptedIn
+        ? deriveEscrowYieldPolicyPda(params.makerPool, programId)[0]
+        : null,
       relayer: params.relayer,
+      makerRootRegistry: deriveRootRegistryPda(params.makerPool, programId)[0],
+      takerRootRegistry: deriveRootRegistryPda(params.takerPool, programId)[0],
     })
     .remainingAccounts(remainingAccounts)
     .preInstructions([
@@ -247,6 +258,8 @@ export interface CancelOrderInstructionParams {
   refundCommitment: Uint8Array;
   /** Escrowed amount (for encrypted note) */
   escrowedAmount: bigint;
+  /** Order escrow opted into the pool's yield policy */
+  escrowYieldOptedIn?: boolean;
 }
 
 /**
@@ -337,7 +350,13 @@ export async function buildCancelOrderWithProgram(
       commitmentCounter: counterPda,
       order: orderPda,
       verificationKey: vkPda,
+      orderYield: deriveOrderYieldPda(params.orderId, programId)[0],
+      escrowYieldPolicy: params.escrowYieldOptedIn
+        ? deriveEscrowYieldPolicyPda(params.pool, programId)[0]
+        : null,
       relayer: params.relayer,
+      rootRegistry: deriveRootRegistryPda(params.pool, programId)[0],
+      treeRegistry: deriveTreeRegistryPda(programId)[0],
     })
     .remainingAccounts(remainingAccounts)
     .preInstructions([
@@ -0,0 +1,228 @@
+/**
+ * NFT Instruction Builders
+ *
+ * Shield an SPL NFT / programmable NFT into its pool (amount = 1 note) and
+ * release it in Phase 3 of an NFT transfer (transfer_1x2_nft circuit).
+ */
+
+import {
+  PublicKey,
+  SystemProgram,
+  ComputeBudgetProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from '@solana/spl-token';
+import { Program } from '@coral-xyz/anchor';
+import type { Point } from '@cloakcraft/types';
+
+import { derivePoolPda, deriveVaultPda, deriveCommitmentCounterPda, deriveTreeRegistryPda } from './constants';
+import { deriveRootRegistryPda } from './root-registry';
+import { LightProtocol } from './light-helpers';
+import { derivePendingOperationPda } from './swap';
+import { computeCommitment, generateRandomness } from '../crypto/commitment';
+import { encryptNote, serializeEncryptedNote } from '../crypto/encryption';
+
+/** Metaplex Token Metadata program */
+export const TOKEN_METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
+
+/**
+ * Derive Metaplex metadata PDA
+ */
+export function deriveMetadataPda(mint: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+}
+
+/**
+ * Derive Metaplex master edition PDA
+ */
+export function deriveEditionPda(mint: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from('edition')],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+}
+
+/**
+ * Derive pNFT token record PDA for a token account
+ */
+export function deriveTokenRecordPda(mint: PublicKey, tokenAccount: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('metadata'),
+      TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+      mint.toBuffer(),
+      Buffer.from('token_record'),
+      tokenAccount.toBuffer(),
+    ],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+}
+
+/**
+ * Shield NFT parameters
+ */
+export interface ShieldNftInstructionParams {
+  /** NFT mint (its pool must be initialized) */
+  nftMint: PublicKey;
+  /** Whether the NFT is a Metaplex programmable NFT */
+  programmable: boolean;
+  /** Recipient's stealth public key (for commitment and encryption) */
+  stealthPubkey: Point;
+  /** Stealth address ephemeral pubkey (stored on-chain for decryption key derivation) */
+  stealthEphemeralPubkey: Point;
+  /** View tag from generateStealthAddress (optional) */
+  viewTag?: Uint8Array;
+  /** User's token account holding the NFT */
+  userTokenAccount: PublicKey;
+  /** User's wallet public key */
+  user: PublicKey;
+}
+
+/**
+ * Build shield_nft instruction using Anchor program
+ *
+ * The note commits to the NFT mint with amount = 1.
+ */
+export async function buildShieldNftWithProgram(
+  program: Program,
+  params: ShieldNftInstructionParams,
+  rpcUrl: string
+): Promise<{
+  tx: any;
+  commitment: Uint8Array;
+  randomness: Uint8Array;
+}> {
+  const programId = program.programId;
+  const lightProtocol = new LightProtocol(rpcUrl, programId);
+
+  const [poolPda] = derivePoolPda(params.nftMint, programId);
+  const [vaultPda] = deriveVaultPda(params.nftMint, programId);
+  const [counterPda] = deriveCommitmentCounterPda(poolPda, programId);
+
+  const randomness = generateRandomness();
+  const note = {
+    stealthPubX: params.stealthPubkey.x,
+    tokenMint: params.nftMint,
+    amount: 1n,
+    randomness,
+  };
+  const commitment = computeCommitment(note);
+  const serializedNote = serializeEncryptedNote(encryptNote(note, params.stealthPubkey));
+
+  const stealthEphemeralBytes = new Uint8Array(64);
+  stealthEphemeralBytes.set(params.stealthEphemeralPubkey.x, 0);
+  stealthEphemeralBytes.set(params.stealthEphemeralPubkey.y, 32);
+
+  const commitmentAddress = lightProtocol.deriveCommitmentAddress(poolPda, commitment);
+  const validityProof = await lightProtocol.getValidityProof([commitmentAddress]);
+  const { accounts: remainingAccounts, outputTreeIndex, addressTreeIndex } = lightProtocol.buildRemainingAccounts();
+
+  const lightParams = {
+    validityProof: LightProtocol.convertCompressedProof(validityProof),
+    addressTreeInfo: {
+      addressMerkleTreePubkeyIndex: addressTreeIndex,
+      addressQueuePubkeyIndex: addressTreeIndex,
+      rootIndex: validityProof.rootIndices[0] ?? 0,
+    },
+    outputTreeIndex,
+  };
+
+  const pnft = params.programmable;
+  const tx = await program.methods
+    .shieldNft(
+      Array.from(commitment),
+      Array.from(stealthEphemeralBytes),
+      Buffer.from(serializedNote),
+      lightParams,
+      params.viewTag ? Array.from(params.viewTag) : null
+    )
+    .accountsStrict({
+      pool: poolPda,
+      commitmentCounter: counterPda,
+      tokenVault: vaultPda,
+      nftMint: params.nftMint,
+      nftMetadata: deriveMetadataPda(params.nftMint),
+      userTokenAccount: params.userTokenAccount,
+      user: params.user,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      edition: pnft ? deriveEditionPda(params.nftMint) : null,
+      ownerTokenRecord: pnft ? deriveTokenRecordPda(params.nftMint, params.userTokenAccount) : null,
+      vaultTokenRecord: pnft ? deriveTokenRecordPda(params.nftMint, vaultPda) : null,
+      tokenMetadataProgram: pnft ? TOKEN_METADATA_PROGRAM_ID : null,
+      associatedTokenProgram: pnft ? ASSOCIATED_TOKEN_PROGRAM_ID : null,
+      systemProgram: pnft ? SystemProgram.programId : null,
+      sysvarInstructions: pnft ? SYSVAR_INSTRUCTIONS_PUBKEY : null,
+      rootRegistry: deriveRootRegistryPda(poolPda, programId)[0],
+      treeRegistry: deriveTreeRegistryPda(programId)[0],
+    })
+    .remainingAccounts(remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: pnft ? 800_000 : 600_000 }),
+      ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 50000 }),
+    ]);
+
+  return { tx, commitment, randomness };
+}
+
+/**
+ * Process unshield NFT parameters (Phase 3 of an NFT transfer)
+ */
+export interface ProcessUnshieldNftParams {
+  /** Pending operation ID from Phase 0 */
+  operationId: Uint8Array;
+  /** NFT mint */
+  nftMint: PublicKey;
+  /** Whether the NFT is a Metaplex programmable NFT */
+  programmable: boolean;
+  /** Wallet receiving the NFT (its ATA is created if missing) */
+  recipientOwner: PublicKey;
+  /** Relayer (must match Phase 0) */
+  relayer: PublicKey;
+}
+
+/**
+ * Build process_unshield_nft instruction using Anchor program
+ */
+export async function buildProcessUnshieldNftWithProgram(
+  program: Program,
+  params: ProcessUnshieldNftParams
+): Promise<any> {
+  const programId = program.programId;
+  const [poolPda] = derivePoolPda(params.nftMint, programId);
+  const [vaultPda] = deriveVaultPda(params.nftMint, programId);
+  const [pendingOpPda] = derivePendingOperationPda(params.operationId, programId);
+  const recipientTokenAccount = getAssociatedTokenAddressSync(params.nftMint, params.recipientOwner);
+  const pnft = params.programmable;
+
+  return program.methods
+    .processUnshieldNft(Array.from(params.operationId))
+    .accountsStrict({
+      pool: poolPda,
+      tokenVault: vaultPda,
+      pendingOperation: pendingOpPda,
+      nftMint: params.nftMint,
+      recipientOwner: params.recipientOwner,
+      recipientTokenAccount,
+      relayer: params.relayer,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      nftMetadata: pnft ? deriveMetadataPda(params.nftMint) : null,
+      edition: pnft ? deriveEditionPda(params.nftMint) : null,
+      vaultTokenRecord: pnft ? deriveTokenRecordPda(params.nftMint, vaultPda) : null,
+      recipientTokenRecord: pnft ? deriveTokenRecordPda(params.nftMint, recipientTokenAccount) : null,
+      tokenMetadataProgram: pnft ? TOKEN_METADATA_PROGRAM_ID : null,
+      sysvarInstructions: pnft ? SYSVAR_INSTRUCTIONS_PUBKEY : null,
+    })
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: pnft ? 400_000 : 150_000 }),
+      ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 50000 }),
+    ]);
+}
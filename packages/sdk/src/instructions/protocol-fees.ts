@@ -0,0 +1,204 @@
+/**
+ * Protocol Fee Policy Instructions
+ *
+ * Per-mint fee overrides and the fee floor / ceiling every rate must lie
+ * within (protocol authority only), and treasury fee conversion into the
+ * canonical treasury asset through the internal AMM.
+ */
+
+import { PublicKey, SystemProgram, TransactionInstruction } from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  createApproveInstruction,
+} from '@solana/spl-token';
+import { Program, BN } from '@coral-xyz/anchor';
+
+import {
+  derivePoolPda,
+  deriveVaultPda,
+  deriveAmmPoolPda,
+  deriveProtocolConfigPda,
+  deriveTreasuryConverterPda,
+} from './constants';
+import { buildAdminAuditRemainingAccounts } from './admin-audit';
+
+/**
+ * Build set_fee_override transaction using Anchor program
+ *
+ * Pass `rates: null` to clear the mint's override.
+ */
+export async function buildSetFeeOverrideWithProgram(
+  program: Program,
+  params: {
+    mint: PublicKey;
+    rates: {
+      transferFeeBps: number;
+      unshieldFeeBps: number;
+      removeLiquidityFeeBps: number;
+    } | null;
+    authority: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const feeOverride = params.rates && {
+    mint: params.mint,
+    transferFeeBps: params.rates.transferFeeBps,
+    unshieldFeeBps: params.rates.unshieldFeeBps,
+    removeLiquidityFeeBps: params.rates.removeLiquidityFeeBps,
+  };
+
+  const tx = await program.methods
+    .setFeeOverride(params.mint, feeOverride)
+    .accountsStrict({
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      authority: params.authority,
+    })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(programId));
+
+  return tx;
+}
+
+/**
+ * Build set_fee_bounds transaction using Anchor program
+ *
+ * `maxFeeBps = 0` means the protocol maximum (MAX_FEE_BPS).
+ */
+export async function buildSetFeeBoundsWithProgram(
+  program: Program,
+  params: {
+    minFeeBps: number;
+    maxFeeBps: number;
+    authority: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .setFeeBounds(params.minFeeBps, params.maxFeeBps)
+    .accountsStrict({
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      authority: params.authority,
+    })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(programId));
+
+  return tx;
+}
+
+/**
+ * Build migrate_protocol_config transaction using Anchor program
+ *
+ * Grows a config created with an older layout to the current size.
+ */
+export async function buildMigrateProtocolConfigWithProgram(
+  program: Program,
+  params: {
+    authority: PublicKey;
+    payer: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .migrateProtocolConfig()
+    .accountsStrict({
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      authority: params.authority,
+      payer: params.payer,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(programId));
+
+  return tx;
+}
+
+/**
+ * Build set_treasury_conversion transaction using Anchor program
+ *
+ * `conversionMint = PublicKey.default` disables conversion.
+ */
+export async function buildSetTreasuryConversionWithProgram(
+  program: Program,
+  params: {
+    conversionMint: PublicKey;
+    maxPriceImpactBps: number;
+    authority: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .setTreasuryConversion(params.conversionMint, params.maxPriceImpactBps)
+    .accountsStrict({
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      authority: params.authority,
+    })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(programId));
+
+  return tx;
+}
+
+/**
+ * Build the treasury's opt-in to fee conversion for one fee mint
+ *
+ * Approves the treasury converter PDA as delegate of the treasury's fee
+ * token account, for up to `amount`. Signed by the treasury wallet.
+ */
+export function buildApproveTreasuryConverterInstruction(
+  treasury: PublicKey,
+  feeMint: PublicKey,
+  amount: bigint,
+  programId: PublicKey
+): TransactionInstruction {
+  const treasuryAta = getAssociatedTokenAddressSync(feeMint, treasury, true);
+  const [converter] = deriveTreasuryConverterPda(programId);
+  return createApproveInstruction(treasuryAta, converter, treasury, amount);
+}
+
+/**
+ * Build convert_treasury_fees transaction using Anchor program
+ *
+ * Protocol authority only (`keeper`): swaps `amountIn` of the treasury's
+ * `feeMint` fees into the canonical asset through the AMM pool pairing the
+ * two. Price `minAmountOut` (must be > 0) off an external reference, not the
+ * pool's spot price, which can be moved ahead of the conversion.
+ */
+export async function buildConvertTreasuryFeesWithProgram(
+  program: Program,
+  params: {
+    feeMint: PublicKey;
+    conversionMint: PublicKey;
+    treasury: PublicKey;
+    amountIn: bigint;
+    minAmountOut: bigint;
+    keeper: PublicKey;
+    /** AMM pool version (default 0) */
+    ammPoolVersion?: number;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const [ammPool] = deriveAmmPoolPda(params.feeMint, params.conversionMint, programId, params.ammPoolVersion ?? 0);
+
+  const tx = await program.methods
+    .convertTreasuryFees(
+      new BN(params.amountIn.toString()),
+      new BN(params.minAmountOut.toString())
+    )
+    .accountsStrict({
+      ammPool,
+      inputPool: derivePoolPda(params.feeMint, programId)[0],
+      outputPool: derivePoolPda(params.conversionMint, programId)[0],
+      inputVault: deriveVaultPda(params.feeMint, programId)[0],
+      outputVault: deriveVaultPda(params.conversionMint, programId)[0],
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      treasurySource: getAssociatedTokenAddressSync(params.feeMint, params.treasury, true),
+      treasuryDestination: getAssociatedTokenAddressSync(params.conversionMint, params.treasury, true),
+      treasuryConverter: deriveTreasuryConverterPda(programId)[0],
+      keeper: params.keeper,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  return tx;
+}
@@ -0,0 +1,92 @@
+/**
+ * Relayer Stake
+ *
+ * SOL a relayer bonds as a liveness guarantee. A transfer or consolidation
+ * whose nullifiers were created but whose commitments weren't by expiry is
+ * stranded: anyone may create the remaining commitments (pass `relayerStake`
+ * to buildCreateCommitmentWithProgram), and creating the last one slashes
+ * RELAYER_SLASH_LAMPORTS from the original relayer's stake. The rescuer gets
+ * RELAYER_RESCUE_BOUNTY_BPS of it and the protocol treasury the rest.
+ *
+ * Unbonding stake stays slashable until withdrawn after
+ * RELAYER_UNBONDING_SECONDS.
+ */
+
+import { PublicKey, SystemProgram } from '@solana/web3.js';
+import { Program, BN } from '@coral-xyz/anchor';
+
+import { PROGRAM_ID } from './constants';
+
+export const RELAYER_STAKE_SEEDS = {
+  RELAYER_STAKE: Buffer.from('relayer_stake'),
+} as const;
+
+/** Slashed when a stranded operation is rescued (matches RELAYER_SLASH_LAMPORTS) */
+export const RELAYER_SLASH_LAMPORTS = 50_000_000n;
+
+/** Rescuer's share of a slash in basis points (matches RELAYER_RESCUE_BOUNTY_BPS) */
+export const RELAYER_RESCUE_BOUNTY_BPS = 2_000n;
+
+/** Delay before unbonded stake can be withdrawn (matches RELAYER_UNBONDING_SECONDS) */
+export const RELAYER_UNBONDING_SECONDS = 7 * 86_400;
+
+/**
+ * Derive a relayer's stake PDA
+ */
+export function deriveRelayerStakePda(
+  relayer: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [RELAYER_STAKE_SEEDS.RELAYER_STAKE, relayer.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Build bond_relayer_stake transaction (creates the stake account on first use)
+ */
+export async function buildBondRelayerStakeWithProgram(
+  program: Program,
+  relayer: PublicKey,
+  amountLamports: bigint
+): Promise<any> {
+  return program.methods
+    .bondRelayerStake(new BN(amountLamports.toString()))
+    .accountsStrict({
+      relayerStake: deriveRelayerStakePda(relayer, program.programId)[0],
+      relayer,
+      systemProgram: SystemProgram.programId,
+    });
+}
+
+/**
+ * Build unbond_relayer_stake transaction
+ */
+export async function buildUnbondRelayerStakeWithProgram(
+  program: Program,
+  relayer: PublicKey,
+  amountLamports: bigint
+): Promise<any> {
+  return program.methods
+    .unbondRelayerStake(new BN(amountLamports.toString()))
+    .accountsStrict({
+      relayerStake: deriveRelayerStakePda(relayer, program.programId)[0],
+      relayer,
+    });
+}
+
+/**
+ * Build withdraw_relayer_stake transaction (after the unbonding delay)
+ */
+export async function buildWithdrawRelayerStakeWithProgram(
+  program: Program,
+  relayer: PublicKey
+): Promise<any> {
+  return program.methods
+    .withdrawRelayerStake()
+    .accountsStrict({
+      relayerStake: deriveRelayerStakePda(relayer, program.programId)[0],
+      relayer,
+    });
+}
@@ -0,0 +1,175 @@
+/**
+ * Root Registry
+ *
+ * On-chain Poseidon merkle tree mirroring a pool's commitments, with the
+ * latest roots and the slots they were produced in. Other programs check a
+ * commitment exists via `verify_external_inclusion` (a plain merkle path, no
+ * Light Protocol CPI).
+ *
+ * The registry numbers its own leaves from zero in the order commitments are
+ * created once it is initialized (every commitment creator takes the registry
+ * PDA); each append emits `CommitmentRegistered`. Rebuild the tree from those events, in order,
+ * to compute paths.
+ */
+
+import { PublicKey, SystemProgram } from '@solana/web3.js';
+import { Program, BN } from '@coral-xyz/anchor';
+
+import { PROGRAM_ID, derivePoolPda } from './constants';
+import { poseidonHash } from '../crypto/poseidon';
+
+export const ROOT_REGISTRY_SEEDS = {
+  ROOT_REGISTRY: Buffer.from('root_registry'),
+} as const;
+
+/** Registry tree depth (matches MERKLE_TREE_DEPTH) */
+export const REGISTRY_TREE_DEPTH = 16;
+
+/** Roots retained on-chain (matches MAX_REGISTRY_ROOTS) */
+export const MAX_REGISTRY_ROOTS = 64;
+
+/**
+ * Derive root registry PDA
+ */
+export function deriveRootRegistryPda(
+  pool: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [ROOT_REGISTRY_SEEDS.ROOT_REGISTRY, pool.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Compute the registry root and merkle path for a leaf
+ *
+ * `leaves` are the registered commitments in leaf order. Empty leaves are
+ * Poseidon(0) and nodes Poseidon(left, right) without a domain, matching the
+ * program's `merkle` module. Requires initPoseidon().
+ */
+export function computeRegistryMerklePath(
+  leaves: Uint8Array[],
+  leafIndex: number
+): { root: Uint8Array; path: Uint8Array[] } {
+  if (leafIndex < 0 || leafIndex >= leaves.length) {
+    throw new Error(`Leaf index ${leafIndex} out of range (${leaves.length} leaves)`);
+  }
+
+  let empty = poseidonHash([new Uint8Array(32)]);
+  let level = [...leaves];
+  let index = leafIndex;
+  const path: Uint8Array[] = [];
+
+  for (let depth = 0; depth < REGISTRY_TREE_DEPTH; depth++) {
+    if (level.length % 2 === 1) {
+      level.push(empty);
+    }
+    path.push(level[index ^ 1]);
+
+    const next: Uint8Array[] = [];
+    for (let i = 0; i < level.length; i += 2) {
+      next.push(poseidonHash([level[i], level[i + 1]]));
+    }
+    level = next;
+    empty = poseidonHash([empty, empty]);
+    index >>= 1;
+  }
+
+  return { root: level[0], path };
+}
+
+/**
+ * Build initialize_root_registry transaction (permissionless)
+ */
+export async function buildInitializeRootRegistryWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    payer: PublicKey;
+  }
+): Promise<{ tx: any; rootRegistry: PublicKey }> {
+  const programId = program.programId;
+  const [pool] = derivePoolPda(params.tokenMint, programId);
+  const [rootRegistry] = deriveRootRegistryPda(pool, programId);
+
+  const tx = await program.methods
+    .initializeRootRegistry()
+    .accountsStrict({
+      pool,
+      rootRegistry,
+      payer: params.payer,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return { tx, rootRegistry };
+}
+
+/**
+ * Build verify_external_inclusion transaction (read-only)
+ *
+ * Simulate it to check a path before handing it to another program; the
+ * return value is the slot the root was produced in.
+ */
+export async function buildVerifyExternalInclusionWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    commitment: Uint8Array;
+    /** Registry leaf index (from CommitmentRegistered) */
+    leafIndex: number;
+    root: Uint8Array;
+    path: Uint8Array[];
+    /** Reject roots older than this many slots (optional, only tightens the pool's window) */
+    maxRootAgeSlots?: number;
+  }
+): Promise<{ tx: any }> {
+  if (params.path.length !== REGISTRY_TREE_DEPTH) {
+    throw new Error(`Merkle path must have ${REGISTRY_TREE_DEPTH} siblings, got ${params.path.length}`);
+  }
+
+  const programId = program.programId;
+  const [pool] = derivePoolPda(params.tokenMint, programId);
+  const [rootRegistry] = deriveRootRegistryPda(pool, programId);
+
+  const tx = await program.methods
+    .verifyExternalInclusion(
+      Array.from(params.commitment),
+      params.leafIndex,
+      Array.from(params.root),
+      params.path.map(sibling => Array.from(sibling)),
+      params.maxRootAgeSlots !== undefined ? new BN(params.maxRootAgeSlots) : null
+    )
+    .accountsStrict({
+      pool,
+      rootRegistry,
+    });
+
+  return { tx };
+}
+
+/**
+ * Build set_max_root_age_slots transaction (pool authority)
+ *
+ * Registry roots produced more than `maxRootAgeSlots` slots ago stop
+ * verifying. Pass 0n to accept any retained root.
+ */
+export async function buildSetMaxRootAgeSlotsWithProgram(
+  program: Program,
+  params: {
+    tokenMint: PublicKey;
+    authority: PublicKey;
+    maxRootAgeSlots: bigint;
+  }
+): Promise<{ tx: any }> {
+  const [pool] = derivePoolPda(params.tokenMint, program.programId);
+
+  const tx = await program.methods
+    .setMaxRootAgeSlots(new BN(params.maxRootAgeSlots.toString()))
+    .accountsStrict({
+      pool,
+      authority: params.authority,
+    });
+
+  return { tx };
+}
@@ -0,0 +1,375 @@
+/**
+ * Savings Vault Instructions
+ *
+ * A savings vault holds the LP of an AMM or perps pool for its share
+ * holders. Deposits convert an LP note into a vault share note and
+ * withdrawals convert it back, both at the vault's share price. Keepers
+ * harvest the vault's emissions rewards into more LP, raising the price.
+ */
+
+import { PublicKey, ComputeBudgetProgram, SystemProgram } from '@solana/web3.js';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Program } from '@coral-xyz/anchor';
+import BN from 'bn.js';
+import type { StealthAddress } from '@cloakcraft/types';
+
+import {
+  derivePoolPda,
+  deriveVaultPda,
+  deriveAmmPoolPda,
+  deriveVerificationKeyPda,
+  deriveProtocolConfigPda,
+  deriveTreeRegistryPda,
+  deriveProgramVersionPda,
+  deriveEmissionsSchedulePda,
+  deriveEmissionsVaultPda,
+  deriveSavingsVaultPda,
+  deriveVaultShareMintPda,
+  CLIENT_VERSION,
+  CIRCUIT_IDS,
+} from './constants';
+import { derivePendingOperationPda, generateOperationId, PendingCommitmentData } from './swap';
+import type { LightVerifyParams, LightNullifierParams } from '../perps/instructions';
+import { encryptNote, serializeEncryptedNote } from '../crypto/encryption';
+
+/**
+ * Shares a deposit of `lpAmount` receives (rounded down)
+ *
+ * Matches `SavingsVault::shares_for_deposit`: 1:1 while the vault is empty.
+ */
+export function computeVaultSharesForDeposit(totalLp: bigint, totalShares: bigint, lpAmount: bigint): bigint {
+  if (totalShares === 0n || totalLp === 0n) {
+    return lpAmount;
+  }
+  return (lpAmount * totalShares) / totalLp;
+}
+
+/**
+ * LP a redemption of `shares` receives (rounded down)
+ *
+ * Matches `SavingsVault::lp_for_withdraw`.
+ */
+export function computeVaultLpForWithdraw(totalLp: bigint, totalShares: bigint, shares: bigint): bigint {
+  if (totalShares === 0n) {
+    throw new Error('Savings vault has no shares');
+  }
+  return (shares * totalLp) / totalShares;
+}
+
+/**
+ * Build initialize_savings_vault transaction using Anchor program
+ *
+ * Only the source pool's authority may create its vault. The share pool is
+ * initialized afterwards with the returned share mint like any other pool.
+ */
+export async function buildInitializeSavingsVaultWithProgram(
+  program: Program,
+  params: {
+    /** AMM or perps pool whose LP the vault holds */
+    sourcePool: PublicKey;
+    poolKind: 'amm' | 'perps';
+    lpMint: PublicKey;
+    maxPriceImpactBps: number;
+    authority: PublicKey;
+  }
+): Promise<{ tx: any; savingsVault: PublicKey; shareMint: PublicKey }> {
+  const programId = program.programId;
+
+  const [savingsVault] = deriveSavingsVaultPda(params.sourcePool, programId);
+  const [shareMint] = deriveVaultShareMintPda(savingsVault, programId);
+
+  const tx = await program.methods
+    .initializeSavingsVault({ [params.poolKind]: {} }, params.maxPriceImpactBps)
+    .accountsStrict({
+      savingsVault,
+      shareMint,
+      sourcePool: params.sourcePool,
+      lpMint: params.lpMint,
+      authority: params.authority,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  return { tx, savingsVault, shareMint };
+}
+
+/**
+ * Build set_savings_vault_policy transaction using Anchor program
+ *
+ * Set `attachEmissions` to attach the source pool's emissions schedule
+ * (allowed once).
+ */
+export async function buildSetSavingsVaultPolicyWithProgram(
+  program: Program,
+  params: {
+    sourcePool: PublicKey;
+    isActive: boolean;
+    maxPriceImpactBps: number;
+    attachEmissions?: boolean;
+    authority: PublicKey;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const tx = await program.methods
+    .setSavingsVaultPolicy(params.isActive, params.maxPriceImpactBps)
+    .accountsStrict({
+      savingsVault: deriveSavingsVaultPda(params.sourcePool, programId)[0],
+      emissionsSchedule: params.attachEmissions
+        ? deriveEmissionsSchedulePda(params.sourcePool, programId)[0]
+        : null,
+      authority: params.authority,
+    });
+
+  return tx;
+}
+
+export interface SavingsVaultConversionParams {
+  /** AMM or perps pool whose LP the vault holds */
+  sourcePool: PublicKey;
+  /** Source pool LP mint */
+  lpMint: PublicKey;
+  /** ZK proof (swap convert LP circuit) */
+  proof: Uint8Array;
+  /** Merkle root of the input commitment */
+  merkleRoot: Uint8Array;
+  /** Input commitment (LP note for deposits, share note for withdrawals) */
+  inputCommitment: Uint8Array;
+  /** Input nullifier */
+  inputNullifier: Uint8Array;
+  /** Output commitment (share note for deposits, LP note for withdrawals) */
+  outputCommitment: Uint8Array;
+  /** Input amount (whole note) */
+  inputAmount: bigint;
+  /** Output amount at the current share price (rounded down) */
+  outputAmount: bigint;
+  /** Relayer */
+  relayer: PublicKey;
+  /** Output recipient */
+  recipient: StealthAddress;
+  /** Output randomness */
+  outputRandomness: Uint8Array;
+  /** Light verify params */
+  lightVerifyParams: LightVerifyParams;
+  /** Light nullifier params */
+  lightNullifierParams: LightNullifierParams;
+  /** Remaining accounts */
+  remainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+  /** Whether the vault has an emissions schedule attached */
+  hasEmissions?: boolean;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Input pool's relayer allowlist PDA (required if it is permissioned) */
+  relayerAllowlist?: PublicKey;
+}
+
+/**
+ * Build savings vault deposit multi-phase instructions
+ *
+ * Spends an LP note and commits vault shares (see
+ * `computeVaultSharesForDeposit`). Phase 3 re-checks the shares against the
+ * share price at execution.
+ */
+export async function buildVaultDepositWithProgram(
+  program: Program,
+  params: SavingsVaultConversionParams
+): Promise<{
+  tx: any;
+  phase1Tx: any;
+  phase2Tx: any;
+  phase3Tx: any;
+  operationId: Uint8Array;
+  pendingCommitments: PendingCommitmentData[];
+}> {
+  return buildVaultConversion(program, params, true);
+}
+
+/**
+ * Build savings vault withdraw multi-phase instructions
+ *
+ * Spends a vault share note and commits LP (see
+ * `computeVaultLpForWithdraw`). Withdrawals stay open while deposits are
+ * paused.
+ */
+export async function buildVaultWithdrawWithProgram(
+  program: Program,
+  params: SavingsVaultConversionParams
+): Promise<{
+  tx: any;
+  phase1Tx: any;
+  phase2Tx: any;
+  phase3Tx: any;
+  operationId: Uint8Array;
+  pendingCommitments: PendingCommitmentData[];
+}> {
+  return buildVaultConversion(program, params, false);
+}
+
+async function buildVaultConversion(
+  program: Program,
+  params: SavingsVaultConversionParams,
+  isDeposit: boolean
+) {
+  const programId = program.programId;
+
+  const operationId = generateOperationId(params.inputNullifier, params.outputCommitment, Date.now());
+
+  const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
+  const [vkPda] = deriveVerificationKeyPda(CIRCUIT_IDS.SWAP_CONVERT_LP, programId);
+  const [savingsVault] = deriveSavingsVaultPda(params.sourcePool, programId);
+  const [shareMint] = deriveVaultShareMintPda(savingsVault, programId);
+  const [lpPoolPda] = derivePoolPda(params.lpMint, programId);
+  const [sharePoolPda] = derivePoolPda(shareMint, programId);
+  const inputPool = isDeposit ? lpPoolPda : sharePoolPda;
+  const outputPool = isDeposit ? sharePoolPda : lpPoolPda;
+  const outputMint = isDeposit ? shareMint : params.lpMint;
+
+  // Phase 0
+  const phase0Method = isDeposit
+    ? program.methods.createPendingWithProofVaultDeposit
+    : program.methods.createPendingWithProofVaultWithdraw;
+  const phase0Tx = await phase0Method(
+    Array.from(operationId),
+    Buffer.from(params.proof),
+    Array.from(params.merkleRoot),
+    Array.from(params.inputCommitment),
+    Array.from(params.inputNullifier),
+    Array.from(params.outputCommitment),
+    new BN(params.inputAmount.toString()),
+    new BN(params.outputAmount.toString()),
+    CLIENT_VERSION
+  )
+    .accountsStrict({
+      savingsVault,
+      lpPool: lpPoolPda,
+      sharePool: sharePoolPda,
+      verificationKey: vkPda,
+      pendingOperation: pendingOpPda,
+      relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      programVersion: deriveProgramVersionPda(programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      relayerAllowlist: params.relayerAllowlist ?? null,
+      systemProgram: SystemProgram.programId,
+    })
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 450_000 }),
+    ]);
+
+  // Phase 1 - Verify input commitment exists
+  const phase1Tx = await program.methods
+    .verifyCommitmentExists(Array.from(operationId), 0, params.lightVerifyParams)
+    .accountsStrict({
+      pool: inputPool,
+      pendingOperation: pendingOpPda,
+      relayer: params.relayer,
+    })
+    .remainingAccounts(params.remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ]);
+
+  // Phase 2 - Create nullifier for the input commitment
+  const phase2Tx = await program.methods
+    .createNullifierAndPending(Array.from(operationId), 0, params.lightNullifierParams)
+    .accountsStrict({
+      pool: inputPool,
+      pendingOperation: pendingOpPda,
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      relayer: params.relayer,
+      poolStats: null,
+      treeRegistry: deriveTreeRegistryPda(programId)[0],
+    })
+    .remainingAccounts(params.remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ]);
+
+  // Phase 3 - Update the vault (settles emissions first when attached)
+  const phase3Method = isDeposit
+    ? program.methods.executeVaultDeposit
+    : program.methods.executeVaultWithdraw;
+  const phase3Tx = await phase3Method(Array.from(operationId))
+    .accountsStrict({
+      savingsVault,
+      sharePool: sharePoolPda,
+      emissionsSchedule: params.hasEmissions
+        ? deriveEmissionsSchedulePda(params.sourcePool, programId)[0]
+        : null,
+      pendingOperation: pendingOpPda,
+      relayer: params.relayer,
+    })
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 100_000 }),
+    ]);
+
+  // Share and LP notes are regular notes of their mint
+  const outputNote = {
+    stealthPubX: params.recipient.stealthPubkey.x,
+    tokenMint: outputMint,
+    amount: params.outputAmount,
+    randomness: params.outputRandomness,
+  };
+  const outputEncrypted = encryptNote(outputNote, params.recipient.stealthPubkey);
+
+  const pendingCommitments: PendingCommitmentData[] = [{
+    pool: outputPool,
+    commitment: params.outputCommitment,
+    stealthEphemeralPubkey: new Uint8Array([
+      ...params.recipient.ephemeralPubkey.x,
+      ...params.recipient.ephemeralPubkey.y,
+    ]),
+    encryptedNote: serializeEncryptedNote(outputEncrypted),
+  }];
+
+  return {
+    tx: phase0Tx,
+    phase1Tx,
+    phase2Tx,
+    phase3Tx,
+    operationId,
+    pendingCommitments,
+  };
+}
+
+/**
+ * Build harvest_savings_vault transaction using Anchor program
+ *
+ * Vault authority only (`keeper`): swaps the vault's settled emissions
+ * rewards for LP through the AMM pool pairing the reward token with the LP
+ * token. Price `minLpOut` (must be > 0) off an external reference, not the
+ * pool's spot price.
+ */
+export async function buildHarvestSavingsVaultWithProgram(
+  program: Program,
+  params: {
+    sourcePool: PublicKey;
+    lpMint: PublicKey;
+    rewardMint: PublicKey;
+    minLpOut: bigint;
+    keeper: PublicKey;
+    /** AMM pool version (default 0) */
+    ammPoolVersion?: number;
+  }
+): Promise<any> {
+  const programId = program.programId;
+
+  const [emissionsSchedule] = deriveEmissionsSchedulePda(params.sourcePool, programId);
+  const [ammPool] = deriveAmmPoolPda(params.rewardMint, params.lpMint, programId, params.ammPoolVersion ?? 0);
+
+  const tx = await program.methods
+    .harvestSavingsVault(new BN(params.minLpOut.toString()))
+    .accountsStrict({
+      savingsVault: deriveSavingsVaultPda(params.sourcePool, programId)[0],
+      emissionsSchedule,
+      sourcePool: params.sourcePool,
+      rewardVault: deriveEmissionsVaultPda(emissionsSchedule, programId)[0],
+      rewardPool: derivePoolPda(params.rewardMint, programId)[0],
+      rewardPoolVault: deriveVaultPda(params.rewardMint, programId)[0],
+      ammPool,
+      keeper: params.keeper,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  return tx;
+}
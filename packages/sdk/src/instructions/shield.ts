@@ -15,10 +15,13 @@ import { Program } from '@coral-xyz/anchor';
 import BN from 'bn.js';
 import type { Point } from '@cloakcraft/types';
 
-import { derivePoolPda, deriveVaultPda, deriveCommitmentCounterPda, PROGRAM_ID } from './constants';
+import { derivePoolPda, deriveVaultPda, deriveCommitmentCounterPda, PROGRAM_ID, deriveTreeRegistryPda } from './constants';
+import { deriveRootRegistryPda } from './root-registry';
 import { LightProtocol, LightShieldParams } from './light-helpers';
 import { computeCommitment, generateRandomness } from '../crypto/commitment';
 import { encryptNote, serializeEncryptedNote } from '../crypto/encryption';
+import { isOnCurve, isInSubgroup } from '../crypto/babyjubjub';
+import { generateStealthAddress } from '../crypto/stealth';
 
 /**
  * Shield parameters
@@ -32,10 +35,14 @@ export interface ShieldInstructionParams {
   stealthPubkey: Point;
   /** Stealth address ephemeral pubkey (stored on-chain for decryption key derivation) */
   stealthEphemeralPubkey: Point;
+  /** View tag from generateStealthAddress (optional, lets recipient skip trial decryption) */
+  viewTag?: Uint8Array;
   /** User's token account */
   userTokenAccount: PublicKey;
   /** User's wallet public key */
   user: PublicKey;
+  /** Pool stats PDA to update (optional, see derivePoolStatsPda) */
+  poolStats?: PublicKey;
 }
 
 /**
@@ -206,7 +213,8 @@ export async function buildShieldWithProgram(
       new BN(params.amount.toString()),
       Array.from(stealthEphemeralBytes),
       Buffer.from(serializedNote),
-      lightParams
+      lightParams,
+      params.viewTag ? Array.from(params.viewTag) : null
     )
     .accountsStrict({
       pool: poolPda,
@@ -215,6 +223,9 @@ export async function buildShieldWithProgram(
       userTokenAccount: params.userTokenAccount,
       user: params.user,
       tokenProgram: TOKEN_PROGRAM_ID,
+      poolStats: params.poolStats ?? null,
+      rootRegistry: deriveRootRegistryPda(poolPda, programId)[0],
+      treeRegistry: deriveTreeRegistryPda(programId)[0],
     })
     .remainingAccounts(remainingAccounts)
     .preInstructions([
@@ -225,6 +236,62 @@ export async function buildShieldWithProgram(
   return { tx, commitment, randomness };
 }
 
+/**
+ * Shield-and-transfer parameters: the note goes to a third party
+ */
+export interface ShieldToRecipientParams {
+  /** Token mint to shield */
+  tokenMint: PublicKey;
+  /** Amount to shield (in token base units) */
+  amount: bigint;
+  /** Recipient's stealth meta address (BabyJubJub public key) */
+  recipient: Point;
+  /** Depositor's token account */
+  userTokenAccount: PublicKey;
+  /** Depositor's wallet public key */
+  user: PublicKey;
+  /** Pool stats PDA to update (optional, see derivePoolStatsPda) */
+  poolStats?: PublicKey;
+}
+
+/**
+ * Build a shield whose note belongs to someone else
+ *
+ * Derives a one-time stealth address for `recipient` (fresh ephemeral key),
+ * so a transparent wallet pays privately in one transaction, with no
+ * intermediate self-note. The recipient finds the note by scanning as usual.
+ */
+export async function buildShieldToRecipientWithProgram(
+  program: Program,
+  params: ShieldToRecipientParams,
+  rpcUrl: string
+): Promise<{
+  tx: any;
+  commitment: Uint8Array;
+  randomness: Uint8Array;
+}> {
+  if (!isOnCurve(params.recipient) || !isInSubgroup(params.recipient)) {
+    // A note to anything else could never be spent
+    throw new Error('Recipient is not a valid stealth meta address');
+  }
+
+  const { stealthAddress } = generateStealthAddress(params.recipient);
+  return buildShieldWithProgram(
+    program,
+    {
+      tokenMint: params.tokenMint,
+      amount: params.amount,
+      stealthPubkey: stealthAddress.stealthPubkey,
+      stealthEphemeralPubkey: stealthAddress.ephemeralPubkey,
+      viewTag: stealthAddress.viewTag,
+      userTokenAccount: params.userTokenAccount,
+      user: params.user,
+      poolStats: params.poolStats,
+    },
+    rpcUrl
+  );
+}
+
 /**
  * Build shield instruction for versioned transaction
  *
@@ -254,3 +321,106 @@ export async function buildShieldInstructionsForVersionedTx(
     randomness,
   };
 }
+
+/**
+ * One leg of a pair shield (the user signs for both)
+ */
+export type ShieldPairLegParams = Omit<ShieldInstructionParams, 'user'>;
+
+/**
+ * Build a shield_pair instruction: deposit into two pools in one transaction
+ *
+ * Both vault transfers and commitments land atomically, e.g. to prepare both
+ * legs of an add-liquidity. Each commitment gets its own validity proof.
+ */
+export async function buildShieldPairWithProgram(
+  program: Program,
+  legA: ShieldPairLegParams,
+  legB: ShieldPairLegParams,
+  user: PublicKey,
+  rpcUrl: string
+): Promise<{
+  tx: any;
+  commitments: [Uint8Array, Uint8Array];
+  randomness: [Uint8Array, Uint8Array];
+}> {
+  const programId = program.programId;
+  const lightProtocol = new LightProtocol(rpcUrl, programId);
+  const { accounts: remainingAccounts, outputTreeIndex, addressTreeIndex } = lightProtocol.buildRemainingAccounts();
+
+  const prepareLeg = async (leg: ShieldPairLegParams) => {
+    const [poolPda] = derivePoolPda(leg.tokenMint, programId);
+    const randomness = generateRandomness();
+    const note = {
+      stealthPubX: leg.stealthPubkey.x,
+      tokenMint: leg.tokenMint,
+      amount: leg.amount,
+      randomness,
+    };
+    const commitment = computeCommitment(note);
+    const serializedNote = serializeEncryptedNote(encryptNote(note, leg.stealthPubkey));
+
+    const stealthEphemeralBytes = new Uint8Array(64);
+    stealthEphemeralBytes.set(leg.stealthEphemeralPubkey.x, 0);
+    stealthEphemeralBytes.set(leg.stealthEphemeralPubkey.y, 32);
+
+    const commitmentAddress = lightProtocol.deriveCommitmentAddress(poolPda, commitment);
+    const validityProof = await lightProtocol.getValidityProof([commitmentAddress]);
+
+    return {
+      poolPda,
+      commitment,
+      randomness,
+      arg: {
+        commitment: Array.from(commitment),
+        amount: new BN(leg.amount.toString()),
+        stealthEphemeralPubkey: Array.from(stealthEphemeralBytes),
+        encryptedNote: Buffer.from(serializedNote),
+        lightParams: {
+          validityProof: LightProtocol.convertCompressedProof(validityProof),
+          addressTreeInfo: {
+            addressMerkleTreePubkeyIndex: addressTreeIndex,
+            addressQueuePubkeyIndex: addressTreeIndex,
+            rootIndex: validityProof.rootIndices[0] ?? 0,
+          },
+          outputTreeIndex,
+        },
+        viewTag: leg.viewTag ? Array.from(leg.viewTag) : null,
+      },
+    };
+  };
+
+  const a = await prepareLeg(legA);
+  const b = await prepareLeg(legB);
+
+  const tx = await program.methods
+    .shieldPair(a.arg, b.arg)
+    .accountsStrict({
+      poolA: a.poolPda,
+      commitmentCounterA: deriveCommitmentCounterPda(a.poolPda, programId)[0],
+      tokenVaultA: deriveVaultPda(legA.tokenMint, programId)[0],
+      userTokenAccountA: legA.userTokenAccount,
+      poolB: b.poolPda,
+      commitmentCounterB: deriveCommitmentCounterPda(b.poolPda, programId)[0],
+      tokenVaultB: deriveVaultPda(legB.tokenMint, programId)[0],
+      userTokenAccountB: legB.userTokenAccount,
+      user,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      poolStatsA: legA.poolStats ?? null,
+      poolStatsB: legB.poolStats ?? null,
+      rootRegistryA: deriveRootRegistryPda(a.poolPda, programId)[0],
+      rootRegistryB: deriveRootRegistryPda(b.poolPda, programId)[0],
+      treeRegistry: deriveTreeRegistryPda(programId)[0],
+    })
+    .remainingAccounts(remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 1_000_000 }),
+      ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 50000 }),
+    ]);
+
+  return {
+    tx,
+    commitments: [a.commitment, b.commitment],
+    randomness: [a.randomness, b.randomness],
+  };
+}
@@ -10,9 +10,8 @@ import {
   ComputeBudgetProgram,
 } from '@solana/web3.js';
 import { Program } from '@coral-xyz/anchor';
-import BN from 'bn.js';
-
-import { derivePoolPda } from './constants';
+import { derivePoolPda, deriveCommitmentCounterPda, deriveTreeRegistryPda } from './constants';
+import { deriveRootRegistryPda } from './root-registry';
 import { LightProtocol, LightStoreCommitmentParams } from './light-helpers';
 
 /**
@@ -23,12 +22,12 @@ export interface StoreCommitmentParams {
   tokenMint: PublicKey;
   /** Commitment to store */
   commitment: Uint8Array;
-  /** Leaf index in tree */
-  leafIndex: bigint;
   /** Stealth ephemeral pubkey (64 bytes) for deriving decryption key */
   stealthEphemeralPubkey: Uint8Array;
   /** Encrypted note data */
   encryptedNote: Buffer;
+  /** View tag for note discovery (optional) */
+  viewTag?: Uint8Array;
   /** Relayer public key */
   relayer: PublicKey;
 }
@@ -64,16 +63,19 @@ export async function buildStoreCommitmentWithProgram(
   const tx = await program.methods
     .storeCommitment({
       commitment: Array.from(params.commitment),
-      leafIndex: new BN(params.leafIndex.toString()),
       stealthEphemeralPubkey: Array.from(params.stealthEphemeralPubkey),
       encryptedNote: Buffer.from(params.encryptedNote),  // Buffer, not number[]
+      viewTag: params.viewTag ? Array.from(params.viewTag) : null,
       validityProof: convertedProof,
       addressTreeInfo,
       outputTreeIndex,
     })
     .accountsStrict({
       pool: poolPda,
+      commitmentCounter: deriveCommitmentCounterPda(poolPda, programId)[0],
       relayer: params.relayer,
+      rootRegistry: deriveRootRegistryPda(poolPda, programId)[0],
+      treeRegistry: deriveTreeRegistryPda(programId)[0],
     })
     .remainingAccounts(remainingAccounts)
     .preInstructions([
@@ -95,7 +97,6 @@ export async function storeCommitments(
   tokenMint: PublicKey,
   commitments: Array<{
     commitment: Uint8Array;
-    leafIndex: bigint;
     stealthEphemeralPubkey: Uint8Array;
     encryptedNote: Buffer;
   }>,
@@ -110,7 +111,6 @@ export async function storeCommitments(
       {
         tokenMint,
         commitment: commitment.commitment,
-        leafIndex: commitment.leafIndex,
         stealthEphemeralPubkey: commitment.stealthEphemeralPubkey,
         encryptedNote: commitment.encryptedNote,
         relayer,
@@ -12,6 +12,7 @@ import {
   PublicKey,
   ComputeBudgetProgram,
   SystemProgram,
+  TransactionInstruction,
 } from '@solana/web3.js';
 import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
 import { Program } from '@coral-xyz/anchor';
@@ -20,14 +21,32 @@ import type { StealthAddress } from '@cloakcraft/types';
 
 import {
   derivePoolPda,
+  deriveVaultPda,
   deriveCommitmentCounterPda,
   deriveVerificationKeyPda,
   deriveAmmPoolPda,
   deriveLpMintPda,
+  deriveLpLockPda,
+  deriveAmmPoolMigrationPda,
+  derivePoolCreatorAllowlistPda,
+  deriveProtocolConfigPda,
+  deriveTreeRegistryPda,
+  deriveDustSweepLedgerPda,
+  getOperationEpoch,
+  applyOperationEpoch,
+  deriveProgramVersionPda,
+  CLIENT_VERSION,
   CIRCUIT_IDS,
+  NULLIFIER_DOMAINS,
 } from './constants';
+import type { NullifierDomain } from './constants';
 import { LightProtocol } from './light-helpers';
+import { buildAdminAuditRemainingAccounts } from './admin-audit';
+import { deriveRootRegistryPda } from './root-registry';
+import { deriveRelayerStakePda } from './relayer-stake';
+import type { LightVerifyParams, LightNullifierParams } from '../perps/instructions';
 import { generateRandomness } from '../crypto/commitment';
+import { DOMAIN_SWAP_TERMS, fieldToBytes, poseidonHashDomain } from '../crypto/poseidon';
 import { encryptNote, serializeEncryptedNote } from '../crypto/encryption';
 
 // =============================================================================
@@ -48,12 +67,13 @@ export function derivePendingOperationPda(
 }
 
 /**
- * Generate unique operation ID
+ * Generate unique operation ID (prefixed with the operation epoch)
  */
 export function generateOperationId(
   nullifier: Uint8Array,
   commitment: Uint8Array,
-  timestamp: number
+  timestamp: number,
+  epoch: number = getOperationEpoch()
 ): Uint8Array {
   const data = new Uint8Array(32 + 32 + 8);
   data.set(nullifier, 0);
@@ -64,7 +84,7 @@ export function generateOperationId(
   for (let i = 0; i < 32; i++) {
     id[i] = data[i] ^ data[32 + (i % 32)] ^ data[64 + (i % 8)];
   }
-  return id;
+  return applyOperationEpoch(id, epoch);
 }
 
 /**
@@ -114,6 +134,10 @@ export interface InitializeAmmPoolParams {
   poolType?: 'constantProduct' | 'stableSwap';
   /** Amplification coefficient for StableSwap pools (100-10000, typical: 200) */
   amplification?: number;
+  /** Pass the creator allowlist (required when pool creation is allowlisted) */
+  allowlisted?: boolean;
+  /** Protocol treasury (required when the SOL creation fee is > 0) */
+  treasury?: PublicKey;
 }
 
 /**
@@ -171,11 +195,44 @@ export async function buildInitializeAmmPoolWithProgram(
       tokenBMintAccount: canonicalB,
       authority: params.authority,
       payer: params.payer,
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      poolCreatorAllowlist: params.allowlisted ? derivePoolCreatorAllowlistPda(programId)[0] : null,
+      treasury: params.treasury ?? null,
     });
 
   return { tx, lpMint: lpMintPda, ammPool: ammPoolPda };
 }
 
+/**
+ * Build migrate_amm_pool_layout transaction using Anchor program
+ *
+ * Grows an AMM pool created with an older layout to the current size, with
+ * the added fields zeroed (no LP lock, no JIT penalty, version 0).
+ * Permissionless; the payer covers the extra rent.
+ */
+export async function buildMigrateAmmPoolLayoutWithProgram(
+  program: Program,
+  params: {
+    tokenAMint: PublicKey;
+    tokenBMint: PublicKey;
+    payer: PublicKey;
+  }
+): Promise<{ tx: any; ammPool: PublicKey }> {
+  const programId = program.programId;
+  const [canonicalA, canonicalB] = canonicalTokenOrder(params.tokenAMint, params.tokenBMint);
+  const [ammPoolPda] = deriveAmmPoolPda(canonicalA, canonicalB, programId);
+
+  const tx = program.methods
+    .migrateAmmPoolLayout(canonicalA, canonicalB)
+    .accountsStrict({
+      ammPool: ammPoolPda,
+      payer: params.payer,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return { tx, ammPool: ammPoolPda };
+}
+
 // =============================================================================
 // Swap Types
 // =============================================================================
@@ -200,6 +257,15 @@ export interface SwapInstructionParams {
   protocolConfig: PublicKey;
   /** Treasury ATA for input token (required if fees enabled and > 0) */
   treasuryAta?: PublicKey;
+  /** Input pool stats PDA debited by the protocol fee (optional, see derivePoolStatsPda) */
+  inputPoolStats?: PublicKey;
+  /** Fee rebate config PDA (optional, with swapVolume) */
+  feeRebateConfig?: PublicKey;
+  /**
+   * Swap volume record for the current epoch (optional, accrues rebate
+   * volume). Bound in Phase 0: the proof must use its volume tag.
+   */
+  swapVolume?: PublicKey;
   /** Relayer public key */
   relayer: PublicKey;
   /** ZK proof bytes */
@@ -235,6 +301,47 @@ export interface SwapInstructionParams {
   /** Randomness used in proof generation (MUST be same for encryption) */
   outRandomness: Uint8Array;
   changeRandomness: Uint8Array;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Input pool's relayer allowlist PDA (required if it is permissioned, see deriveRelayerAllowlistPda) */
+  relayerAllowlist?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+  /**
+   * Salt of a committed swap (proof from the swap_committed circuit): swap
+   * amount, output, minOutput and direction stay hidden until Phase 3.
+   */
+  commitSalt?: Uint8Array;
+  /**
+   * Salt of a sealed swap (proof from the swap_sealed circuit, terms opened
+   * from the user's envelope with decryptSwapTerms). Phase 0 uses
+   * createPendingWithProofSwapSealed; takes precedence over commitSalt.
+   */
+  termsSalt?: Uint8Array;
+}
+
+/**
+ * Commitment to a swap's hidden parameters (matches compute_swap_commitment)
+ *
+ * Poseidon(DOMAIN_SWAP_TERMS, swapAmount, outputAmount, minOutput, swapAToB,
+ * salt). Also the `swap_commitment` / `terms_hash` public input of the
+ * swap_committed / swap_sealed circuit.
+ */
+export function computeSwapCommitment(
+  swapAmount: bigint,
+  outputAmount: bigint,
+  minOutput: bigint,
+  swapAToB: boolean,
+  salt: Uint8Array
+): Uint8Array {
+  return poseidonHashDomain(
+    DOMAIN_SWAP_TERMS,
+    fieldToBytes(swapAmount),
+    fieldToBytes(outputAmount),
+    fieldToBytes(minOutput),
+    fieldToBytes(swapAToB ? 1n : 0n),
+    salt
+  );
 }
 
 /**
@@ -264,6 +371,10 @@ export interface SwapPhase2Params {
  * - Phase 2: createNullifierAndPending (create nullifier)
  * - Phase 3: executeSwap (execute AMM swap logic)
  * - Phase 4+: createCommitment (handled by caller)
+ *
+ * With `commitSalt`, Phase 0 and 3 use createPendingWithProofSwapCommitted
+ * and executeSwapRevealed instead; with `termsSalt`,
+ * createPendingWithProofSwapSealed and executeSwapRevealed.
  * - Final: closePendingOperation (handled by caller)
  */
 export async function buildSwapWithProgram(
@@ -291,7 +402,14 @@ export async function buildSwapWithProgram(
 
   // Derive PDAs
   const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
-  const [vkPda] = deriveVerificationKeyPda(CIRCUIT_IDS.SWAP, programId);
+  const [vkPda] = deriveVerificationKeyPda(
+    params.termsSalt
+      ? CIRCUIT_IDS.SWAP_SEALED
+      : params.commitSalt
+      ? CIRCUIT_IDS.SWAP_COMMITTED
+      : CIRCUIT_IDS.SWAP,
+    programId
+  );
 
   // Use the SAME randomness that was used in proof generation
   const outputRandomness = params.outRandomness;
@@ -389,6 +507,7 @@ export async function buildSwapWithProgram(
   // Build Light params for multi-phase
   const lightParams = {
     commitmentAccountHash: Array.from(new PublicKey(params.accountHash).toBytes()),
+    commitmentDataHash: await lightProtocol.getDataHashByHash(params.accountHash),
     commitmentMerkleContext: {
       merkleTreePubkeyIndex: commitmentStateTreeIndex,
       queuePubkeyIndex: commitmentQueueIndex,
@@ -427,21 +546,37 @@ export async function buildSwapWithProgram(
   console.log('[Swap Phase 0] Building createPendingWithProofSwap...');
 
   // Phase 0: Create Pending with Proof (Swap-specific)
-  const phase0Tx = await program.methods
-    .createPendingWithProofSwap(
-      Array.from(operationId),
-      Buffer.from(params.proof),
-      Array.from(params.merkleRoot),
-      Array.from(params.inputCommitment),
-      Array.from(params.nullifier),
-      Array.from(params.outputCommitment),
-      Array.from(params.changeCommitment),
-      new BN(params.minOutput.toString()),
-      new BN(params.swapAmount.toString()),
-      new BN(params.outputAmount.toString()),
-      params.swapDirection === 'aToB',
-      numCommitments
-    )
+  // Committed swaps publish only a salted hash of amount/output/minOutput/direction
+  const swapAToB = params.swapDirection === 'aToB';
+  const swapSalt = params.termsSalt ?? params.commitSalt;
+  const proofArgs = [
+    Array.from(operationId),
+    Buffer.from(params.proof),
+    Array.from(params.merkleRoot),
+    Array.from(params.inputCommitment),
+    Array.from(params.nullifier),
+    Array.from(params.outputCommitment),
+    Array.from(params.changeCommitment),
+  ];
+  const phase0Args = [...proofArgs, new BN(params.minOutput.toString())];
+  const swapCommitment = swapSalt
+    ? Array.from(
+        computeSwapCommitment(params.swapAmount, params.outputAmount, params.minOutput, swapAToB, swapSalt)
+      )
+    : undefined;
+  const phase0Method = params.termsSalt
+    ? program.methods.createPendingWithProofSwapSealed(...phase0Args, swapCommitment, numCommitments, CLIENT_VERSION)
+    : swapCommitment
+    ? program.methods.createPendingWithProofSwapCommitted(...proofArgs, swapCommitment, numCommitments, CLIENT_VERSION)
+    : program.methods.createPendingWithProofSwap(
+        ...phase0Args,
+        new BN(params.swapAmount.toString()),
+        new BN(params.outputAmount.toString()),
+        swapAToB,
+        numCommitments,
+        CLIENT_VERSION
+      );
+  const phase0Tx = await phase0Method
     .accountsStrict({
       inputPool: params.inputPool,
       outputPool: params.outputPool,
@@ -449,6 +584,12 @@ export async function buildSwapWithProgram(
       verificationKey: vkPda,
       pendingOperation: pendingOpPda,
       relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      relayerAllowlist: params.relayerAllowlist ?? null,
+      operationCredit: params.operationCredit ?? null,
+      swapVolume: params.swapVolume ?? null,
       systemProgram: new PublicKey('11111111111111111111111111111111'),
     })
     .preInstructions([
@@ -465,6 +606,7 @@ export async function buildSwapWithProgram(
       0, // commitment_index (single input for swap)
       {
         commitmentAccountHash: lightParams.commitmentAccountHash,
+        commitmentDataHash: lightParams.commitmentDataHash,
         commitmentMerkleContext: lightParams.commitmentMerkleContext,
         commitmentInclusionProof: lightParams.commitmentInclusionProof,
         commitmentAddressTreeInfo: lightParams.commitmentAddressTreeInfo,
@@ -501,7 +643,10 @@ export async function buildSwapWithProgram(
     .accountsStrict({
       pool: params.inputPool,
       pendingOperation: pendingOpPda,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
       relayer: params.relayer,
+      poolStats: null,
+      treeRegistry: deriveTreeRegistryPda(program.programId)[0],
     })
     .remainingAccounts(finalRemainingAccounts.map((acc: any) => ({
       pubkey: acc.pubkey,
@@ -531,11 +676,27 @@ export async function buildSwapWithProgram(
   if (params.treasuryAta) {
     phase3Accounts.treasuryAta = params.treasuryAta;
   }
+  if (params.inputPoolStats) {
+    phase3Accounts.inputPoolStats = params.inputPoolStats;
+  }
 
-  const phase3Tx = await program.methods
-    .executeSwap(
-      Array.from(operationId)
-    )
+  // Fee rebate volume tracking is opt-in
+  if (params.feeRebateConfig && params.swapVolume) {
+    phase3Accounts.feeRebateConfig = params.feeRebateConfig;
+    phase3Accounts.swapVolume = params.swapVolume;
+  }
+
+  const phase3Method = swapSalt
+    ? program.methods.executeSwapRevealed(
+        Array.from(operationId),
+        new BN(params.swapAmount.toString()),
+        new BN(params.outputAmount.toString()),
+        new BN(params.minOutput.toString()),
+        swapAToB,
+        Array.from(swapSalt)
+      )
+    : program.methods.executeSwap(Array.from(operationId));
+  const phase3Tx = await phase3Method
     .accounts(phase3Accounts)
     .preInstructions([
       ComputeBudgetProgram.setComputeUnitLimit({ units: 150_000 }),
@@ -611,6 +772,15 @@ export interface AddLiquidityInstructionParams {
   depositB: bigint;
   lpAmount: bigint;
   minLpAmount: bigint;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Token A / B pools' relayer allowlist PDAs (required if permissioned, see deriveRelayerAllowlistPda) */
+  relayerAllowlistA?: PublicKey;
+  relayerAllowlistB?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+  /** Record an LP lock (required when the AMM pool has min_lp_lock_slots > 0) */
+  lpLocked?: boolean;
 }
 
 /**
@@ -809,6 +979,7 @@ export async function buildAddLiquidityWithProgram(
   // Build Light params for commitment A verification
   const lightParamsA = {
     commitmentAccountHash: Array.from(new PublicKey(params.accountHashA).toBytes()),
+    commitmentDataHash: await lightProtocol.getDataHashByHash(params.accountHashA),
     commitmentMerkleContext: {
       merkleTreePubkeyIndex: commitmentAStateTreeIndex,
       queuePubkeyIndex: commitmentAQueueIndex,
@@ -834,6 +1005,7 @@ export async function buildAddLiquidityWithProgram(
   // Build Light params for commitment B verification
   const lightParamsB = {
     commitmentAccountHash: Array.from(new PublicKey(params.accountHashB).toBytes()),
+    commitmentDataHash: await lightProtocol.getDataHashByHash(params.accountHashB),
     commitmentMerkleContext: {
       merkleTreePubkeyIndex: commitmentBStateTreeIndex,
       queuePubkeyIndex: commitmentBQueueIndex,
@@ -889,7 +1061,8 @@ export async function buildAddLiquidityWithProgram(
       new BN(params.depositB.toString()),
       new BN(params.lpAmount.toString()),
       new BN(params.minLpAmount.toString()),
-      numCommitments
+      numCommitments,
+      CLIENT_VERSION
     )
     .accountsStrict({
       poolA: params.poolA,
@@ -899,6 +1072,13 @@ export async function buildAddLiquidityWithProgram(
       verificationKey: vkPda,
       pendingOperation: pendingOpPda,
       relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      relayerAllowlistA: params.relayerAllowlistA ?? null,
+      relayerAllowlistB: params.relayerAllowlistB ?? null,
+      operationCredit: params.operationCredit ?? null,
+      lpLock: params.lpLocked ? deriveLpLockPda(params.lpCommitment, program.programId)[0] : null,
       systemProgram: new PublicKey('11111111111111111111111111111111'),
     })
     .preInstructions([
@@ -915,6 +1095,7 @@ export async function buildAddLiquidityWithProgram(
       0, // commitment_index for input A
       {
         commitmentAccountHash: lightParamsA.commitmentAccountHash,
+        commitmentDataHash: lightParamsA.commitmentDataHash,
         commitmentMerkleContext: lightParamsA.commitmentMerkleContext,
         commitmentInclusionProof: lightParamsA.commitmentInclusionProof,
         commitmentAddressTreeInfo: lightParamsA.commitmentAddressTreeInfo,
@@ -944,6 +1125,7 @@ export async function buildAddLiquidityWithProgram(
       1, // commitment_index for input B
       {
         commitmentAccountHash: lightParamsB.commitmentAccountHash,
+        commitmentDataHash: lightParamsB.commitmentDataHash,
         commitmentMerkleContext: lightParamsB.commitmentMerkleContext,
         commitmentInclusionProof: lightParamsB.commitmentInclusionProof,
         commitmentAddressTreeInfo: lightParamsB.commitmentAddressTreeInfo,
@@ -980,7 +1162,10 @@ export async function buildAddLiquidityWithProgram(
     .accountsStrict({
       pool: params.poolA,
       pendingOperation: pendingOpPda,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
       relayer: params.relayer,
+      poolStats: null,
+      treeRegistry: deriveTreeRegistryPda(program.programId)[0],
     })
     .remainingAccounts(finalRemainingAccounts.map((acc: any) => ({
       pubkey: acc.pubkey,
@@ -1008,7 +1193,10 @@ export async function buildAddLiquidityWithProgram(
     .accountsStrict({
       pool: params.poolB,
       pendingOperation: pendingOpPda,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
       relayer: params.relayer,
+      poolStats: null,
+      treeRegistry: deriveTreeRegistryPda(program.programId)[0],
     })
     .remainingAccounts(finalRemainingAccounts.map((acc: any) => ({
       pubkey: acc.pubkey,
@@ -1134,6 +1322,7 @@ export async function buildCreateNullifierWithProgram(
     .accountsStrict({
       pool: params.pool,
       pendingOperation: pendingOpPda,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
       relayer: params.relayer,
     })
     .remainingAccounts(remainingAccounts)
@@ -1165,8 +1354,22 @@ export interface CreateCommitmentParams {
   stealthEphemeralPubkey: Uint8Array;
   /** Encrypted note */
   encryptedNote: Uint8Array;
+  /** View tag for note discovery (optional) */
+  viewTag?: Uint8Array;
   /** Commitment value (optional, if not provided will fetch from PendingOperation) */
   commitment?: Uint8Array;
+  /** Pool stats PDA to update (optional, see derivePoolStatsPda) */
+  poolStats?: PublicKey;
+  /** Invoice reference id; writes a PaymentReceipt for this commitment (optional) */
+  paymentReference?: Uint8Array;
+  /**
+   * Original relayer's stake PDA (optional, see deriveRelayerStakePda).
+   * When rescuing a stranded operation, the last commitment slashes it,
+   * paying the rescuer a bounty and the rest to treasury.
+   */
+  relayerStake?: PublicKey;
+  /** Protocol treasury (required with relayerStake, see ProtocolConfig.treasury) */
+  treasury?: PublicKey;
 }
 
 /**
@@ -1215,19 +1418,43 @@ export async function buildCreateCommitmentWithProgram(
     outputTreeIndex,
   };
 
+  // Payment receipt address needs its own non-inclusion proof
+  let paymentReceipt = null;
+  if (params.paymentReference) {
+    const receiptAddress = lightProtocol.derivePaymentReceiptAddress(params.paymentReference);
+    const receiptProof = await lightProtocol.getValidityProof([receiptAddress]);
+    paymentReceipt = {
+      referenceId: Array.from(params.paymentReference),
+      proof: LightProtocol.convertCompressedProof(receiptProof),
+      addressTreeInfo: {
+        addressMerkleTreePubkeyIndex: addressTreeIndex,
+        addressQueuePubkeyIndex: addressTreeIndex,
+        rootIndex: receiptProof.rootIndices[0] ?? 0,
+      },
+    };
+  }
+
   const tx = await program.methods
     .createCommitment(
       Array.from(params.operationId),
       params.commitmentIndex,
       Array.from(params.stealthEphemeralPubkey),
       Buffer.from(params.encryptedNote),
-      lightParams
+      lightParams,
+      params.viewTag ? Array.from(params.viewTag) : null,
+      paymentReceipt
     )
     .accountsStrict({
       pool: params.pool,
       commitmentCounter: counterPda,
       pendingOperation: pendingOpPda,
       relayer: params.relayer,
+      poolStats: params.poolStats ?? null,
+      rootRegistry: deriveRootRegistryPda(params.pool, programId)[0],
+      relayerStake: params.relayerStake ?? null,
+      protocolConfig: params.relayerStake ? deriveProtocolConfigPda(programId)[0] : null,
+      treasury: params.relayerStake ? params.treasury ?? null : null,
+      treeRegistry: deriveTreeRegistryPda(programId)[0],
     })
     .remainingAccounts(remainingAccounts)
     .preInstructions([
@@ -1238,17 +1465,132 @@ export async function buildCreateCommitmentWithProgram(
   return { tx };
 }
 
+// =============================================================================
+// Create Nullifiers and Commitments (Generic, batched)
+// =============================================================================
+
+/** Most accounts one create_nullifiers_and_commitments call creates (light_cpi::MAX_BATCHED_ACCOUNTS) */
+export const MAX_BATCHED_ACCOUNTS = 4;
+
+/**
+ * Create Nullifiers and Commitments instruction parameters
+ */
+export interface CreateNullifiersAndCommitmentsParams {
+  /** Operation ID from Phase 0 */
+  operationId: Uint8Array;
+  /** Pool of every nullifier and commitment in the batch */
+  pool: PublicKey;
+  /** Relayer (must be the operation's relayer) */
+  relayer: PublicKey;
+  /** The pool's uncreated nullifiers, in input index order */
+  nullifiers: Uint8Array[];
+  /** Nullifier domain of the operation (default SPEND) */
+  nullifierDomain?: NullifierDomain;
+  /** Outputs to create in this pool */
+  outputs: Array<{
+    commitmentIndex: number;
+    commitment: Uint8Array;
+    stealthEphemeralPubkey: Uint8Array;
+    encryptedNote: Uint8Array;
+    viewTag?: Uint8Array;
+    /** Zero-amount padding output: marked complete without an account */
+    dummy?: boolean;
+  }>;
+  /** Pool stats PDA to update (optional, see derivePoolStatsPda) */
+  poolStats?: PublicKey;
+}
+
+/**
+ * Build Create Nullifiers and Commitments instruction (generic)
+ *
+ * Replaces Phase 2 and Phase 4 for one pool with one transaction and one
+ * Light CPI when the operation has no Phase 3 (transfer without unshield or
+ * fee, consolidation). Once all nullifiers exist, pass `nullifiers: []` to
+ * batch Phase 4 alone for any operation.
+ */
+export async function buildCreateNullifiersAndCommitmentsWithProgram(
+  program: Program,
+  params: CreateNullifiersAndCommitmentsParams,
+  rpcUrl: string
+): Promise<{ tx: any }> {
+  const programId = program.programId;
+  const lightProtocol = new LightProtocol(rpcUrl, programId);
+
+  // One validity proof covers every new address: nullifiers first, then outputs
+  const domain = params.nullifierDomain ?? NULLIFIER_DOMAINS.SPEND;
+  const addresses = [
+    ...params.nullifiers.map(n => lightProtocol.deriveNullifierAddress(params.pool, n, domain)),
+    ...params.outputs
+      .filter(o => !o.dummy)
+      .map(o => lightProtocol.deriveCommitmentAddress(params.pool, o.commitment)),
+  ];
+  if (addresses.length === 0 || addresses.length > MAX_BATCHED_ACCOUNTS) {
+    throw new Error(`A batch creates 1 to ${MAX_BATCHED_ACCOUNTS} accounts, got ${addresses.length}`);
+  }
+
+  const batchProof = await lightProtocol.getValidityProof(addresses);
+  const { accounts: remainingAccounts, outputTreeIndex, addressTreeIndex } =
+    lightProtocol.buildRemainingAccounts();
+
+  const lightParams = {
+    proof: LightProtocol.convertCompressedProof(batchProof),
+    addressTreeInfos: addresses.map((_, i) => ({
+      addressMerkleTreePubkeyIndex: addressTreeIndex,
+      addressQueuePubkeyIndex: addressTreeIndex,
+      rootIndex: batchProof.rootIndices[i] ?? 0,
+    })),
+    outputTreeIndex,
+  };
+
+  const tx = await program.methods
+    .createNullifiersAndCommitments(
+      Array.from(params.operationId),
+      params.outputs.map(o => ({
+        commitmentIndex: o.commitmentIndex,
+        stealthEphemeralPubkey: Array.from(o.stealthEphemeralPubkey),
+        encryptedNote: Buffer.from(o.encryptedNote),
+        viewTag: o.viewTag ? Array.from(o.viewTag) : null,
+      })),
+      lightParams
+    )
+    .accountsStrict({
+      pool: params.pool,
+      commitmentCounter: deriveCommitmentCounterPda(params.pool, programId)[0],
+      pendingOperation: derivePendingOperationPda(params.operationId, programId)[0],
+      relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      poolStats: params.poolStats ?? null,
+      rootRegistry: deriveRootRegistryPda(params.pool, programId)[0],
+      treeRegistry: deriveTreeRegistryPda(programId)[0],
+    })
+    .remainingAccounts(remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 600_000 }),
+      ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 50000 }),
+    ]);
+
+  return { tx };
+}
+
 // =============================================================================
 // Close Pending Operation (Generic)
 // =============================================================================
 
 /**
  * Build Close Pending Operation instruction (generic)
+ *
+ * Pass the owner of the unshield recipient bound in Phase 3, if any; it receives its share of the rent.
+ * For a consolidation, pass its pool as dustSweepPool to claim the dust sweep
+ * bounty (paid only if the consolidation swept dust and the ledger is funded,
+ * to relayers with bonded stake, up to a per-day cap; the ledger and the
+ * relayer's stake account must exist).
  */
 export async function buildClosePendingOperationWithProgram(
   program: Program,
   operationId: Uint8Array,
-  relayer: PublicKey
+  relayer: PublicKey,
+  rentRefundRecipient?: PublicKey,
+  dustSweepPool?: PublicKey
 ): Promise<{ tx: any }> {
   const programId = program.programId;
   const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
@@ -1258,6 +1600,10 @@ export async function buildClosePendingOperationWithProgram(
     .accountsStrict({
       pendingOperation: pendingOpPda,
       relayer,
+      rentRefundRecipient: rentRefundRecipient ?? null,
+      pool: dustSweepPool ?? null,
+      dustSweepLedger: dustSweepPool ? deriveDustSweepLedgerPda(programId)[0] : null,
+      relayerStake: dustSweepPool ? deriveRelayerStakePda(relayer, programId)[0] : null,
     })
     .preInstructions([
       ComputeBudgetProgram.setComputeUnitLimit({ units: 200_000 }),
@@ -1267,6 +1613,86 @@ export async function buildClosePendingOperationWithProgram(
   return { tx };
 }
 
+// =============================================================================
+// Sync Reserves (permissionless)
+// =============================================================================
+
+/**
+ * Build Sync Reserves instruction
+ *
+ * Folds surplus vault balance into the AMM pool's reserves, or sweeps it to
+ * the treasury, per ProtocolConfig.surplus_policy. Treasury token accounts
+ * are only needed when sweeping. A vault's surplus is only folded into the
+ * AMM pool its token pool designates (see buildSetSurplusAmmPoolWithProgram).
+ */
+export async function buildSyncReservesWithProgram(
+  program: Program,
+  tokenAMint: PublicKey,
+  tokenBMint: PublicKey,
+  treasuryAtaA?: PublicKey,
+  treasuryAtaB?: PublicKey
+): Promise<{ tx: any }> {
+  const programId = program.programId;
+  const [canonicalA, canonicalB] = canonicalTokenOrder(tokenAMint, tokenBMint);
+
+  const tx = await program.methods
+    .syncReserves()
+    .accountsStrict({
+      ammPool: deriveAmmPoolPda(canonicalA, canonicalB, programId)[0],
+      poolA: derivePoolPda(canonicalA, programId)[0],
+      poolB: derivePoolPda(canonicalB, programId)[0],
+      vaultA: deriveVaultPda(canonicalA, programId)[0],
+      vaultB: deriveVaultPda(canonicalB, programId)[0],
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      treasuryAtaA: treasuryAtaA ?? null,
+      treasuryAtaB: treasuryAtaB ?? null,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  return { tx };
+}
+
+/**
+ * Build register_lp_lock transaction using Anchor program
+ *
+ * While the AMM pool has an LP lock configured, remove liquidity treats an LP
+ * note without a lock as locked. Notes not minted by add liquidity
+ * (transferred, converted or older) register one here, starting now, and can
+ * be removed penalty-free once it expires.
+ */
+export async function buildRegisterLpLockWithProgram(
+  program: Program,
+  params: {
+    tokenAMint: PublicKey;
+    tokenBMint: PublicKey;
+    /** LP note commitment (must already exist in the LP pool) */
+    lpCommitment: Uint8Array;
+    lightVerifyParams: LightVerifyParams;
+    remainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+    payer: PublicKey;
+  }
+): Promise<{ tx: any }> {
+  const programId = program.programId;
+  const [canonicalA, canonicalB] = canonicalTokenOrder(params.tokenAMint, params.tokenBMint);
+  const [lpMint] = deriveLpMintPda(canonicalA, canonicalB, programId);
+
+  const tx = await program.methods
+    .registerLpLock(Array.from(params.lpCommitment), params.lightVerifyParams)
+    .accountsStrict({
+      lpPool: derivePoolPda(lpMint, programId)[0],
+      ammPool: deriveAmmPoolPda(canonicalA, canonicalB, programId)[0],
+      lpLock: deriveLpLockPda(params.lpCommitment, programId)[0],
+      payer: params.payer,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(params.remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ]);
+
+  return { tx };
+}
+
 
 // =============================================================================
 // Remove Liquidity Types (Two-Phase)
@@ -1298,6 +1724,10 @@ export interface RemoveLiquidityInstructionParams {
   treasuryAtaA?: PublicKey;
   /** Treasury ATA for token B (required if fees enabled and > 0) */
   treasuryAtaB?: PublicKey;
+  /** Token A pool stats PDA debited by fee A (optional, see derivePoolStatsPda) */
+  poolAStats?: PublicKey;
+  /** Token B pool stats PDA debited by fee B (optional, see derivePoolStatsPda) */
+  poolBStats?: PublicKey;
   /** Relayer */
   relayer: PublicKey;
   /** ZK proof */
@@ -1325,6 +1755,14 @@ export interface RemoveLiquidityInstructionParams {
   /** Randomness used in proof generation */
   outputARandomness: Uint8Array;
   outputBRandomness: Uint8Array;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** LP pool's relayer allowlist PDA (required if it is permissioned, see deriveRelayerAllowlistPda) */
+  relayerAllowlist?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+  /** Payer of the LP lock, refunded when an expired lock is closed (optional) */
+  lpLockPayer?: PublicKey;
 }
 
 /**
@@ -1477,6 +1915,7 @@ export async function buildRemoveLiquidityWithProgram(
   // Build Light params for multi-phase
   const lightParams = {
     commitmentAccountHash: Array.from(new PublicKey(params.accountHash).toBytes()),
+    commitmentDataHash: await lightProtocol.getDataHashByHash(params.accountHash),
     commitmentMerkleContext: {
       merkleTreePubkeyIndex: commitmentStateTreeIndex,
       queuePubkeyIndex: commitmentQueueIndex,
@@ -1528,7 +1967,8 @@ export async function buildRemoveLiquidityWithProgram(
       new BN(params.lpAmount.toString()),
       new BN(params.outputAAmount.toString()),
       new BN(params.outputBAmount.toString()),
-      numCommitments
+      numCommitments,
+      CLIENT_VERSION
     )
     .accountsStrict({
       lpPool: params.lpPool,
@@ -1538,6 +1978,13 @@ export async function buildRemoveLiquidityWithProgram(
       verificationKey: vkPda,
       pendingOperation: pendingOpPda,
       relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      relayerAllowlist: params.relayerAllowlist ?? null,
+      operationCredit: params.operationCredit ?? null,
+      lpLock: deriveLpLockPda(params.lpInputCommitment, program.programId)[0],
+      lpLockPayer: params.lpLockPayer ?? null,
       systemProgram: new PublicKey('11111111111111111111111111111111'),
     })
     .preInstructions([
@@ -1554,6 +2001,7 @@ export async function buildRemoveLiquidityWithProgram(
       0, // commitment_index (single LP input)
       {
         commitmentAccountHash: lightParams.commitmentAccountHash,
+        commitmentDataHash: lightParams.commitmentDataHash,
         commitmentMerkleContext: lightParams.commitmentMerkleContext,
         commitmentInclusionProof: lightParams.commitmentInclusionProof,
         commitmentAddressTreeInfo: lightParams.commitmentAddressTreeInfo,
@@ -1590,7 +2038,10 @@ export async function buildRemoveLiquidityWithProgram(
     .accountsStrict({
       pool: params.lpPool,
       pendingOperation: pendingOpPda,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
       relayer: params.relayer,
+      poolStats: null,
+      treeRegistry: deriveTreeRegistryPda(program.programId)[0],
     })
     .remainingAccounts(finalRemainingAccounts.map((acc: any) => ({
       pubkey: acc.pubkey,
@@ -1625,6 +2076,12 @@ export async function buildRemoveLiquidityWithProgram(
   if (params.treasuryAtaB) {
     phase3Accounts.treasuryAtaB = params.treasuryAtaB;
   }
+  if (params.poolAStats) {
+    phase3Accounts.poolAStats = params.poolAStats;
+  }
+  if (params.poolBStats) {
+    phase3Accounts.poolBStats = params.poolBStats;
+  }
 
   const phase3Tx = await program.methods
     .executeRemoveLiquidity(
@@ -1649,6 +2106,242 @@ export async function buildRemoveLiquidityWithProgram(
   };
 }
 
+// =============================================================================
+// AMM Pool Migration
+// =============================================================================
+
+export interface MigrateAmmPoolParams {
+  /** Token A mint */
+  tokenAMint: PublicKey;
+  /** Token B mint */
+  tokenBMint: PublicKey;
+  /** Version of the pool being migrated (0 = original pool) */
+  version: number;
+  /** Pool authority (pays for the successor accounts) */
+  authority: PublicKey;
+  /** Successor pool fee */
+  feeBps: number;
+  /** Successor pool formula */
+  poolType: 'constantProduct' | 'stableSwap';
+  /** StableSwap amplification (ignored for constant product) */
+  amplification?: number;
+  /** Successor LP reserved for the old pool's whole LP supply */
+  newLpSupply: bigint;
+}
+
+/**
+ * Build migrate AMM pool instruction
+ *
+ * Creates the pair's next-version pool and LP mint, exports the reserves into
+ * it and deactivates the old pool. Old LP notes then convert with
+ * buildConvertAmmLpNoteWithProgram.
+ *
+ * An original pool still on an older layout is reallocated first, within the
+ * same transaction.
+ */
+export async function buildMigrateAmmPoolWithProgram(
+  program: Program,
+  params: MigrateAmmPoolParams
+): Promise<{ tx: any; ammPool: PublicKey; lpMint: PublicKey; migration: PublicKey }> {
+  const programId = program.programId;
+  const [canonicalA, canonicalB] = canonicalTokenOrder(params.tokenAMint, params.tokenBMint);
+
+  const [oldPoolPda] = deriveAmmPoolPda(canonicalA, canonicalB, programId, params.version);
+  const [newPoolPda] = deriveAmmPoolPda(canonicalA, canonicalB, programId, params.version + 1);
+  const [newLpMintPda] = deriveLpMintPda(canonicalA, canonicalB, programId, params.version + 1);
+  const [migrationPda] = deriveAmmPoolMigrationPda(oldPoolPda, programId);
+
+  const poolTypeEnum: PoolTypeParam = params.poolType === 'stableSwap'
+    ? { stableSwap: {} }
+    : { constantProduct: {} };
+
+  // Pools created before the version field can't be loaded until reallocated
+  const preInstructions: TransactionInstruction[] = [];
+  const oldPoolInfo = await program.provider.connection.getAccountInfo(oldPoolPda);
+  if (params.version === 0 && oldPoolInfo && oldPoolInfo.data.length < (program.account as any).ammPool.size) {
+    const { tx: layoutTx } = await buildMigrateAmmPoolLayoutWithProgram(program, {
+      tokenAMint: canonicalA,
+      tokenBMint: canonicalB,
+      payer: params.authority,
+    });
+    preInstructions.push(await layoutTx.instruction());
+  }
+
+  const tx = program.methods
+    .migrateAmmPool(
+      params.feeBps,
+      poolTypeEnum,
+      new BN(params.amplification ?? 0),
+      new BN(params.newLpSupply.toString())
+    )
+    .accountsStrict({
+      ammPool: oldPoolPda,
+      newPool: newPoolPda,
+      newLpMint: newLpMintPda,
+      migration: migrationPda,
+      authority: params.authority,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(programId))
+    .preInstructions(preInstructions);
+
+  return { tx, ammPool: newPoolPda, lpMint: newLpMintPda, migration: migrationPda };
+}
+
+export interface ConvertAmmLpNoteInstructionParams {
+  /** Migrated AMM pool */
+  oldAmmPool: PublicKey;
+  /** Old pool LP mint */
+  oldLpMint: PublicKey;
+  /** Successor pool LP mint */
+  newLpMint: PublicKey;
+  /** ZK proof */
+  proof: Uint8Array;
+  /** Merkle root of the old LP commitment */
+  merkleRoot: Uint8Array;
+  /** Old LP commitment */
+  lpCommitment: Uint8Array;
+  /** Old LP nullifier */
+  lpNullifier: Uint8Array;
+  /** Successor LP commitment */
+  outputCommitment: Uint8Array;
+  /** Old LP amount (whole note) */
+  lpAmount: bigint;
+  /** Successor LP amount (lpAmount * newLpSupply / oldLpSupply, rounded down) */
+  convertedAmount: bigint;
+  /** Relayer */
+  relayer: PublicKey;
+  /** Successor LP recipient */
+  lpRecipient: StealthAddress;
+  /** Successor LP randomness */
+  lpRandomness: Uint8Array;
+  /** Light verify params */
+  lightVerifyParams: LightVerifyParams;
+  /** Light nullifier params */
+  lightNullifierParams: LightNullifierParams;
+  /** Remaining accounts */
+  remainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+  /** Old LP pool's relayer allowlist PDA (required if it is permissioned) */
+  relayerAllowlist?: PublicKey;
+}
+
+/**
+ * Build convert AMM LP note multi-phase instructions
+ *
+ * Spends an LP note of a migrated AMM pool and commits the successor pool's
+ * LP at the migration's conversion rate. There is no Phase 3.
+ */
+export async function buildConvertAmmLpNoteWithProgram(
+  program: Program,
+  params: ConvertAmmLpNoteInstructionParams
+): Promise<{
+  tx: any;
+  phase1Tx: any;
+  phase2Tx: any;
+  operationId: Uint8Array;
+  pendingCommitments: PendingCommitmentData[];
+}> {
+  const programId = program.programId;
+
+  const operationId = generateOperationId(params.lpNullifier, params.outputCommitment, Date.now());
+
+  const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
+  const [vkPda] = deriveVerificationKeyPda(CIRCUIT_IDS.SWAP_CONVERT_LP, programId);
+  const [oldLpPoolPda] = derivePoolPda(params.oldLpMint, programId);
+  const [newLpPoolPda] = derivePoolPda(params.newLpMint, programId);
+
+  // Phase 0
+  const phase0Tx = await program.methods
+    .createPendingWithProofConvertAmmLpNote(
+      Array.from(operationId),
+      Buffer.from(params.proof),
+      Array.from(params.merkleRoot),
+      Array.from(params.lpCommitment),
+      Array.from(params.lpNullifier),
+      Array.from(params.outputCommitment),
+      new BN(params.lpAmount.toString()),
+      new BN(params.convertedAmount.toString()),
+      CLIENT_VERSION
+    )
+    .accountsStrict({
+      oldLpPool: oldLpPoolPda,
+      newLpPool: newLpPoolPda,
+      migration: deriveAmmPoolMigrationPda(params.oldAmmPool, programId)[0],
+      verificationKey: vkPda,
+      pendingOperation: pendingOpPda,
+      relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      programVersion: deriveProgramVersionPda(programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
+      relayerAllowlist: params.relayerAllowlist ?? null,
+      systemProgram: SystemProgram.programId,
+    })
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 450_000 }),
+    ]);
+
+  // Phase 1 - Verify old LP commitment exists (old LP pool)
+  const phase1Tx = await program.methods
+    .verifyCommitmentExists(Array.from(operationId), 0, params.lightVerifyParams)
+    .accountsStrict({
+      pool: oldLpPoolPda,
+      pendingOperation: pendingOpPda,
+      relayer: params.relayer,
+    })
+    .remainingAccounts(params.remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ]);
+
+  // Phase 2 - Create nullifier for the old LP commitment
+  const phase2Tx = await program.methods
+    .createNullifierAndPending(Array.from(operationId), 0, params.lightNullifierParams)
+    .accountsStrict({
+      pool: oldLpPoolPda,
+      pendingOperation: pendingOpPda,
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      relayer: params.relayer,
+      poolStats: null,
+      treeRegistry: deriveTreeRegistryPda(programId)[0],
+    })
+    .remainingAccounts(params.remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ]);
+
+  // Successor LP is a regular note of the new LP mint
+  const lpNote = {
+    stealthPubX: params.lpRecipient.stealthPubkey.x,
+    tokenMint: params.newLpMint,
+    amount: params.convertedAmount,
+    randomness: params.lpRandomness,
+  };
+  const lpEncrypted = encryptNote(lpNote, params.lpRecipient.stealthPubkey);
+
+  const pendingCommitments: PendingCommitmentData[] = [{
+    pool: newLpPoolPda,
+    commitment: params.outputCommitment,
+    stealthEphemeralPubkey: new Uint8Array([
+      ...params.lpRecipient.ephemeralPubkey.x,
+      ...params.lpRecipient.ephemeralPubkey.y,
+    ]),
+    encryptedNote: serializeEncryptedNote(lpEncrypted),
+  }];
+
+  return {
+    tx: phase0Tx,
+    phase1Tx,
+    phase2Tx,
+    operationId,
+    pendingCommitments,
+  };
+}
 
 // =============================================================================
 // Close Pending Operation
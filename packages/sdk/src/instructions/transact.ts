@@ -5,6 +5,7 @@
  */
 
 import {
+  AccountMeta,
   PublicKey,
   TransactionInstruction,
   ComputeBudgetProgram,
@@ -14,8 +15,10 @@ import {
   TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
   createAssociatedTokenAccountIdempotentInstruction,
+  getAssociatedTokenAddressSync,
 } from '@solana/spl-token';
 import { Program } from '@coral-xyz/anchor';
+import { keccak_256 } from '@noble/hashes/sha3';
 import BN from 'bn.js';
 import type { Point } from '@cloakcraft/types';
 
@@ -24,6 +27,10 @@ import {
   deriveVaultPda,
   deriveCommitmentCounterPda,
   deriveVerificationKeyPda,
+  deriveAdaptModulePda,
+  deriveProtocolConfigPda,
+  deriveProgramVersionPda,
+  CLIENT_VERSION,
   PROGRAM_ID,
   CIRCUIT_IDS,
 } from './constants';
@@ -32,6 +39,74 @@ import { computeCommitment, generateRandomness } from '../crypto/commitment';
 import { encryptNote, serializeEncryptedNote } from '../crypto/encryption';
 import { deriveNullifierKey, deriveSpendingNullifier } from '../crypto/nullifier';
 
+/** SPL Memo program (optional unshield memos) */
+export const MEMO_PROGRAM_ID = new PublicKey('MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr');
+
+/**
+ * Program call made right after an unshield (process_unshield_and_invoke)
+ *
+ * The target must be a registered adapt module. The call is bound by the
+ * proof via computeCallHash, so the proof must use CIRCUIT_IDS.TRANSFER_1X2_INVOKE.
+ */
+export interface UnshieldCall {
+  /** Program to invoke */
+  targetProgram: PublicKey;
+  /** Instruction data for the target program */
+  data: Uint8Array;
+  /** Accounts for the target instruction */
+  accounts: AccountMeta[];
+}
+
+/**
+ * Compute the call hash bound by the transfer_1x2_invoke circuit
+ *
+ * keccak256(targetProgram || recipient || u32le(accounts.length) || accounts || data)
+ * with the top 3 bits cleared, so the result is a canonical BN254 field
+ * element. Each account is encoded as pubkey || isSigner || isWritable.
+ * Must match `compute_call_hash` on-chain.
+ */
+export function computeCallHash(
+  targetProgram: PublicKey,
+  recipient: PublicKey,
+  accounts: AccountMeta[],
+  data: Uint8Array
+): Uint8Array {
+  const accountsLen = 4 + accounts.length * 34;
+  const preimage = new Uint8Array(64 + accountsLen + data.length);
+  preimage.set(targetProgram.toBytes(), 0);
+  preimage.set(recipient.toBytes(), 32);
+  new DataView(preimage.buffer).setUint32(64, accounts.length, true);
+  accounts.forEach((account, i) => {
+    const offset = 68 + i * 34;
+    preimage.set(account.pubkey.toBytes(), offset);
+    preimage[offset + 32] = account.isSigner ? 1 : 0;
+    preimage[offset + 33] = account.isWritable ? 1 : 0;
+  });
+  preimage.set(data, 64 + accountsLen);
+  const hash = keccak_256(preimage);
+  hash[0] &= 0x1f;
+  return hash;
+}
+
+/**
+ * Compute the recipient hash of a destination-bound unshield
+ *
+ * keccak256("unshield_recipient" || kind || recipient) with the top 3 bits
+ * cleared, kind = 1 when `recipient` is the ATA owner, 0 for a token account.
+ * Passed as the call hash (transfer_1x2_invoke circuit) so a relayer can't
+ * redirect the unshield. Must match `compute_recipient_hash` on-chain.
+ */
+export function computeRecipientHash(recipient: PublicKey, isOwner: boolean): Uint8Array {
+  const domain = new TextEncoder().encode('unshield_recipient');
+  const preimage = new Uint8Array(domain.length + 33);
+  preimage.set(domain, 0);
+  preimage[domain.length] = isOwner ? 1 : 0;
+  preimage.set(recipient.toBytes(), domain.length + 1);
+  const hash = keccak_256(preimage);
+  hash[0] &= 0x1f;
+  return hash;
+}
+
 /**
  * Input note for spending
  */
@@ -86,6 +161,32 @@ export interface TransactInstructionParams {
   unshieldAmount?: bigint;
   /** Unshield recipient token account */
   unshieldRecipient?: PublicKey;
+  /**
+   * Unshield recipient wallet (alternative to unshieldRecipient).
+   * Tokens go to the wallet's ATA, which is created on-chain if missing (relayer pays).
+   */
+  unshieldRecipientOwner?: PublicKey;
+  /**
+   * Pool tokens paid to the relayer out of the proven fee when the ATA is
+   * created (capped at the token account rent-exempt minimum; the rest of the
+   * fee must still cover the protocol fee)
+   */
+  ataReimbursement?: bigint;
+  /** Relayer token account receiving ataReimbursement */
+  relayerTokenAccount?: PublicKey;
+  /** SPL memo attached to the unshield (e.g. exchange deposit tag, max 256 bytes) */
+  unshieldMemo?: string;
+  /**
+   * Program call made after the unshield (requires unshieldRecipient and the
+   * transfer_1x2_invoke circuit). Not combinable with ATA creation or memos.
+   */
+  unshieldCall?: UnshieldCall;
+  /**
+   * Bind the unshield recipient (unshieldRecipient or unshieldRecipientOwner)
+   * in the proof via computeRecipientHash, so the relayer can't redirect it.
+   * Requires the transfer_1x2_invoke circuit; not combinable with unshieldCall.
+   */
+  bindUnshieldRecipient?: boolean;
   /** Protocol fee amount (verified in ZK proof) */
   feeAmount?: bigint;
   /** Treasury wallet address (owner of treasury token account) */
@@ -104,6 +205,20 @@ export interface TransactInstructionParams {
   inputCommitment?: Uint8Array;
   /** Pre-computed output commitments (must match ZK proof) */
   outputCommitments?: Uint8Array[];
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Pool's relayer allowlist PDA (required for permissioned pools, see deriveRelayerAllowlistPda) */
+  relayerAllowlist?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+  /** Pool stats PDA to update (optional, see derivePoolStatsPda) */
+  poolStats?: PublicKey;
+  /**
+   * Require a validity proof against a pinned root for the input (needed
+   * when the pool's pinned inclusion threshold applies); fails while the
+   * note is still in the output queue
+   */
+  pinInclusion?: boolean;
 }
 
 /**
@@ -233,7 +348,12 @@ export async function buildTransactWithProgram(
 
   // For transfer_1x2 circuit, pad with dummy second output if only 1 output provided
   // The dummy commitment must match what the ZK proof computed: Poseidon(domain, 0, tokenMint, 0, 0)
-  if (circuitId === CIRCUIT_IDS.TRANSFER_1X2 && outputCommitments.length === 1) {
+  const isTransfer1x2 =
+    circuitId === CIRCUIT_IDS.TRANSFER_1X2 ||
+    circuitId === CIRCUIT_IDS.TRANSFER_1X2_DENOM ||
+    circuitId === CIRCUIT_IDS.TRANSFER_1X2_INVOKE ||
+    circuitId === CIRCUIT_IDS.TRANSFER_1X2_NFT;
+  if (isTransfer1x2 && outputCommitments.length === 1) {
     const dummyCommitment = computeCommitment({
       stealthPubX: new Uint8Array(32), // zeros
       tokenMint: params.tokenMint,
@@ -299,6 +419,9 @@ export async function buildTransactWithProgram(
   );
   const proveByIndex = inclusionValidityProof.proveByIndices?.[0] ?? true;
   console.log('[Transact] proveByIndex:', proveByIndex);
+  if (params.pinInclusion && proveByIndex) {
+    throw new Error('Input note is still in the output queue; pinned inclusion needs it batched into the state tree. Retry later.');
+  }
 
   console.log('[Transact] Fetching nullifier non-inclusion proof...');
   const nullifierAddress = lightProtocol.deriveNullifierAddress(poolPda, nullifier);
@@ -338,6 +461,7 @@ export async function buildTransactWithProgram(
   // Build complete LightTransactParams with all security proofs
   const lightParams: LightTransactParams = {
     commitmentAccountHash: Array.from(new PublicKey(params.input.accountHash).toBytes()),
+    commitmentDataHash: await lightProtocol.getDataHashByHash(params.input.accountHash),
     commitmentMerkleContext: {
       merkleTreePubkeyIndex: commitmentStateTreeIndex, // STATE tree from proof (for data/merkle verification)
       queuePubkeyIndex: commitmentQueueIndex,          // Queue from proof
@@ -413,6 +537,31 @@ export async function buildTransactWithProgram(
     console.log(`  out_commitment_${i+1} (full):`, Buffer.from(outputCommitments[i]).toString('hex'));
   }
 
+  // Unshield-and-invoke: bind (target, recipient, accounts, data) in the proof public inputs
+  let callHash: Uint8Array | null = null;
+  if (params.unshieldCall) {
+    if (!unshieldRecipientAta) {
+      throw new Error('unshieldCall requires unshieldRecipient and unshieldAmount > 0');
+    }
+    callHash = computeCallHash(
+      params.unshieldCall.targetProgram,
+      unshieldRecipientAta,
+      params.unshieldCall.accounts,
+      params.unshieldCall.data
+    );
+    console.log('[Phase 0] call_hash:', Buffer.from(callHash).toString('hex').slice(0, 32) + '...');
+  } else if (params.bindUnshieldRecipient) {
+    // Destination-bound unshield: the proof commits to the recipient
+    if (unshieldRecipientAta) {
+      callHash = computeRecipientHash(unshieldRecipientAta, false);
+    } else if (params.unshieldRecipientOwner && params.unshieldAmount && params.unshieldAmount > 0n) {
+      callHash = computeRecipientHash(params.unshieldRecipientOwner, true);
+    } else {
+      throw new Error('bindUnshieldRecipient requires an unshield recipient and unshieldAmount > 0');
+    }
+    console.log('[Phase 0] recipient hash:', Buffer.from(callHash).toString('hex').slice(0, 32) + '...');
+  }
+
   // Phase 0: Create Pending with Proof
   const phase0Tx = await program.methods
     .createPendingWithProof(
@@ -428,13 +577,20 @@ export async function buildTransactWithProgram(
       stealthEphemeralPubkeys.map(e => Array.from(e)),
       new BN(transferAmountForInstruction.toString()),
       new BN(unshieldAmountForInstruction.toString()),
-      new BN(feeAmountForInstruction.toString())
+      new BN(feeAmountForInstruction.toString()),
+      callHash ? Array.from(callHash) : null,
+      CLIENT_VERSION
     )
     .accountsStrict({
       pool: poolPda,
       verificationKey: vkPda,
       pendingOperation: pendingOpPda,
       relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      relayerAllowlist: params.relayerAllowlist ?? null,
+      operationCredit: params.operationCredit ?? null,
       systemProgram: new PublicKey('11111111111111111111111111111111'),
     })
     .preInstructions([
@@ -449,6 +605,7 @@ export async function buildTransactWithProgram(
       0, // commitment_index (always 0 for single-input transfer)
       {
         commitmentAccountHash: lightParams.commitmentAccountHash,
+        commitmentDataHash: lightParams.commitmentDataHash,
         commitmentMerkleContext: lightParams.commitmentMerkleContext,
         commitmentInclusionProof: lightParams.commitmentInclusionProof,
         commitmentAddressTreeInfo: lightParams.commitmentAddressTreeInfo,
@@ -458,6 +615,7 @@ export async function buildTransactWithProgram(
       pool: poolPda,
       pendingOperation: pendingOpPda,
       relayer: params.relayer,
+      poolStats: params.poolStats ?? null,
     })
     .remainingAccounts(finalRemainingAccounts.map((acc: any) => ({
       pubkey: acc.pubkey,
@@ -483,6 +641,7 @@ export async function buildTransactWithProgram(
     .accountsStrict({
       pool: poolPda,
       pendingOperation: pendingOpPda,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
       relayer: params.relayer,
     })
     .remainingAccounts(finalRemainingAccounts.map((acc: any) => ({
@@ -512,6 +671,11 @@ export async function buildTransactWithProgram(
     console.log('  feeAmount:', feeAmountForInstruction.toString());
     console.log('  unshieldAmount:', unshieldAmountForInstruction.toString());
 
+    // Unshield to a wallet: program creates its ATA if missing
+    const unshielding = !!params.unshieldAmount && params.unshieldAmount > 0n;
+    const ownerForAta = unshielding && !unshieldRecipientAta ? params.unshieldRecipientOwner ?? null : null;
+    const recipientAta = ownerForAta ? getAssociatedTokenAddressSync(params.tokenMint, ownerForAta) : null;
+
     // Build accounts object - Anchor's Option<Account> maps to optional fields
     const phase3Accounts: Record<string, PublicKey | null> = {
       pool: poolPda,
@@ -520,8 +684,16 @@ export async function buildTransactWithProgram(
       protocolConfig: params.protocolConfig ?? null,
       treasuryTokenAccount: params.treasuryTokenAccount ?? null,
       unshieldRecipient: unshieldRecipientAta ?? null,
+      recipientOwner: ownerForAta,
+      recipientAta,
+      tokenMint: ownerForAta ? params.tokenMint : null,
+      relayerTokenAccount: params.ataReimbursement ? params.relayerTokenAccount ?? null : null,
       relayer: params.relayer,
       tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ownerForAta ? ASSOCIATED_TOKEN_PROGRAM_ID : null,
+      systemProgram: ownerForAta ? SystemProgram.programId : null,
+      memoProgram: params.unshieldMemo ? MEMO_PROGRAM_ID : null,
+      poolStats: params.poolStats ?? null,
     };
 
     // Build pre-instructions for Phase 3
@@ -546,14 +718,42 @@ export async function buildTransactWithProgram(
       );
     }
 
-    phase3Tx = await program.methods
-      .processUnshield(
-        Array.from(operationId),
-        new BN(unshieldAmountForInstruction.toString()) // unshield_amount parameter
-      )
-      // eslint-disable-next-line @typescript-eslint/no-explicit-any
-      .accounts(phase3Accounts as any)
-      .preInstructions(phase3PreInstructions);
+    if (params.unshieldCall && unshieldRecipientAta) {
+      const [adaptModulePda] = deriveAdaptModulePda(params.unshieldCall.targetProgram, programId);
+      phase3PreInstructions[0] = ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }); // Target CPI
+
+      phase3Tx = await program.methods
+        .processUnshieldAndInvoke(
+          Array.from(operationId),
+          Buffer.from(params.unshieldCall.data)
+        )
+        .accountsStrict({
+          pool: poolPda,
+          tokenVault: vaultPda,
+          pendingOperation: pendingOpPda,
+          protocolConfig: params.protocolConfig ?? null,
+          treasuryTokenAccount: params.treasuryTokenAccount ?? null,
+          unshieldRecipient: unshieldRecipientAta,
+          adaptModule: adaptModulePda,
+          targetProgram: params.unshieldCall.targetProgram,
+          relayer: params.relayer,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          poolStats: params.poolStats ?? null,
+        })
+        .remainingAccounts(params.unshieldCall.accounts)
+        .preInstructions(phase3PreInstructions);
+    } else {
+      phase3Tx = await program.methods
+        .processUnshield(
+          Array.from(operationId),
+          new BN(unshieldAmountForInstruction.toString()), // unshield_amount parameter
+          new BN((params.ataReimbursement ?? 0n).toString()),
+          params.unshieldMemo ?? null
+        )
+        // eslint-disable-next-line @typescript-eslint/no-explicit-any
+        .accounts(phase3Accounts as any)
+        .preInstructions(phase3PreInstructions);
+    }
     console.log('[Phase 3] Transaction builder created');
   }
 
@@ -576,6 +776,84 @@ export async function buildTransactWithProgram(
   };
 }
 
+/**
+ * Transfer appended to a pending batch (Phase 0b)
+ *
+ * Values are the member's own proof outputs, as passed to
+ * createPendingWithProof for a single transfer. Members must spend from the
+ * batch's pool and can't unshield.
+ */
+export interface AppendTransferParams {
+  /** Operation ID of the batch (from the first member's buildTransactWithProgram) */
+  operationId: Uint8Array;
+  tokenMint: PublicKey;
+  relayer: PublicKey;
+  proof: Uint8Array;
+  merkleRoot: Uint8Array;
+  inputCommitment: Uint8Array;
+  nullifier: Uint8Array;
+  outputCommitments: Uint8Array[];
+  /** Stealth public key X of each output */
+  outputRecipients: Uint8Array[];
+  outputAmounts: bigint[];
+  outputRandomness: Uint8Array[];
+  /** 64 bytes each (x || y) */
+  stealthEphemeralPubkeys: Uint8Array[];
+  transferAmount: bigint;
+  feeAmount: bigint;
+  /** Pool's relayer allowlist PDA (required for permissioned pools) */
+  relayerAllowlist?: PublicKey;
+}
+
+/**
+ * Build appendTransferToBatch (Phase 0b)
+ *
+ * Appends a transfer to a pending transfer before its Phase 1, so up to 4
+ * transfers share one PendingOperation. Afterwards run verifyCommitmentExists
+ * and createNullifierAndPending once per input index (the first member is
+ * index 0, appended members follow in order), then Phase 3, one
+ * createCommitment per output and close as usual.
+ */
+export async function buildAppendTransferToBatchWithProgram(
+  program: Program,
+  params: AppendTransferParams,
+  circuitId: string = CIRCUIT_IDS.TRANSFER_1X2
+): Promise<any> {
+  const programId = program.programId;
+  const { derivePendingOperationPda } = await import('./swap');
+  const [pendingOpPda] = derivePendingOperationPda(params.operationId, programId);
+
+  return program.methods
+    .appendTransferToBatch(
+      Array.from(params.operationId),
+      Buffer.from(params.proof),
+      Array.from(params.merkleRoot),
+      Array.from(params.inputCommitment),
+      Array.from(params.nullifier),
+      params.outputCommitments.map(c => Array.from(c)),
+      params.outputRecipients.map(r => Array.from(r)),
+      params.outputAmounts.map(a => new BN(a.toString())),
+      params.outputRandomness.map(r => Array.from(r)),
+      params.stealthEphemeralPubkeys.map(e => Array.from(e)),
+      new BN(params.transferAmount.toString()),
+      new BN(params.feeAmount.toString()),
+      CLIENT_VERSION
+    )
+    .accountsStrict({
+      pool: derivePoolPda(params.tokenMint, programId)[0],
+      verificationKey: deriveVerificationKeyPda(circuitId, programId)[0],
+      pendingOperation: pendingOpPda,
+      relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      programVersion: deriveProgramVersionPda(programId)[0],
+      relayerAllowlist: params.relayerAllowlist ?? null,
+    })
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 450_000 }),
+      ComputeBudgetProgram.setComputeUnitPrice({ microLamports: 50000 }),
+    ]);
+}
+
 /**
  * Helper to compute derived values for circuit inputs
  */
@@ -662,6 +940,12 @@ export interface ConsolidationInstructionParams {
   relayer: PublicKey;
   /** ZK proof bytes */
   proof: Uint8Array;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Pool's relayer allowlist PDA (required for permissioned pools, see deriveRelayerAllowlistPda) */
+  relayerAllowlist?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
 }
 
 /**
@@ -804,13 +1088,19 @@ export async function buildConsolidationWithProgram(
       Array.from(params.outputRecipient.stealthPubkey.x), // output_recipient
       new BN(params.outputAmount.toString()), // output_amount
       Array.from(params.outputRandomness), // output_randomness
-      Array.from(stealthEphemeralPubkey) // stealth_ephemeral_pubkey
+      Array.from(stealthEphemeralPubkey), // stealth_ephemeral_pubkey
+      CLIENT_VERSION
     )
     .accountsStrict({
       pool: poolPda,
       verificationKey: vkPda,
       pendingOperation: pendingOpPda,
       relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      relayerAllowlist: params.relayerAllowlist ?? null,
+      operationCredit: params.operationCredit ?? null,
       systemProgram: new PublicKey('11111111111111111111111111111111'),
     })
     .preInstructions([
@@ -826,6 +1116,7 @@ export async function buildConsolidationWithProgram(
       const proof = inputProofs[i];
       const lightParams = {
         commitmentAccountHash: Array.from(new PublicKey(input.accountHash).toBytes()),
+        commitmentDataHash: await lightProtocol.getDataHashByHash(input.accountHash),
         commitmentMerkleContext: {
           merkleTreePubkeyIndex: proof.treeIndex,
           queuePubkeyIndex: proof.queueIndex,
@@ -851,6 +1142,7 @@ export async function buildConsolidationWithProgram(
           pool: poolPda,
           pendingOperation: pendingOpPda,
           relayer: params.relayer,
+          poolStats: null,
         })
         .remainingAccounts(finalRemainingAccounts.map((acc: any) => ({
           pubkey: acc.pubkey,
@@ -892,6 +1184,7 @@ export async function buildConsolidationWithProgram(
         .accountsStrict({
           pool: poolPda,
           pendingOperation: pendingOpPda,
+          protocolConfig: deriveProtocolConfigPda(program.programId)[0],
           relayer: params.relayer,
         })
         .remainingAccounts(finalRemainingAccounts.map((acc: any) => ({
@@ -0,0 +1,210 @@
+/**
+ * Jito Bundle Submission
+ *
+ * Sends a multi-phase operation's transactions as Jito bundles so they land
+ * in the same block, in order, all-or-nothing. This narrows the window in
+ * which nullifiers exist on-chain without their output commitments, and the
+ * chance of a PendingOperation expiring half-way.
+ *
+ * A bundle holds at most 5 transactions; longer operations are split into
+ * consecutive bundles, each confirmed before the next is sent. Requires a
+ * Jito block engine (mainnet).
+ */
+
+import {
+  Connection,
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+  VersionedTransaction,
+} from '@solana/web3.js';
+import bs58 from 'bs58';
+
+/** Maximum transactions per bundle */
+export const JITO_MAX_BUNDLE_SIZE = 5;
+
+/** Minimum tip accepted by the block engine */
+export const JITO_MIN_TIP_LAMPORTS = 1_000;
+
+/** Default mainnet block engine */
+export const JITO_MAINNET_BLOCK_ENGINE = 'https://mainnet.block-engine.jito.wtf';
+
+/**
+ * Jito bundle configuration
+ */
+export interface JitoBundleConfig {
+  /** Block engine URL (default JITO_MAINNET_BLOCK_ENGINE) */
+  blockEngineUrl?: string;
+  /** Tip per bundle, paid by the fee payer in the bundle's last transaction */
+  tipLamports: number;
+  /** Transactions per bundle (default and max JITO_MAX_BUNDLE_SIZE) */
+  maxBundleSize?: number;
+  /** How long to wait for each bundle to land (default 60s) */
+  timeoutMs?: number;
+  /**
+   * Send a failed bundle's transactions one by one instead of throwing
+   * (default true). The tip transfer is still executed in that case.
+   */
+  fallbackToSequential?: boolean;
+}
+
+/**
+ * Bundle status reported by the block engine
+ */
+export type JitoBundleStatus = 'Pending' | 'Landed' | 'Failed' | 'Invalid';
+
+function blockEngineUrl(config: JitoBundleConfig): string {
+  return config.blockEngineUrl ?? JITO_MAINNET_BLOCK_ENGINE;
+}
+
+function bundleSize(config: JitoBundleConfig): number {
+  return Math.min(Math.max(config.maxBundleSize ?? JITO_MAX_BUNDLE_SIZE, 1), JITO_MAX_BUNDLE_SIZE);
+}
+
+async function jitoRpc(url: string, method: string, params: unknown[]): Promise<any> {
+  const response = await fetch(`${url}/api/v1/bundles`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify({ jsonrpc: '2.0', id: 1, method, params }),
+  });
+  if (!response.ok) {
+    throw new Error(`[Jito] ${method} failed: HTTP ${response.status}`);
+  }
+  const body = await response.json();
+  if (body.error) {
+    throw new Error(`[Jito] ${method} failed: ${body.error.message ?? JSON.stringify(body.error)}`);
+  }
+  return body.result;
+}
+
+/**
+ * Split `count` transactions into consecutive bundles (indices, in order)
+ */
+export function planBundles(count: number, maxBundleSize: number = JITO_MAX_BUNDLE_SIZE): number[][] {
+  const size = Math.min(Math.max(maxBundleSize, 1), JITO_MAX_BUNDLE_SIZE);
+  const bundles: number[][] = [];
+  for (let start = 0; start < count; start += size) {
+    bundles.push(Array.from({ length: Math.min(size, count - start) }, (_, i) => start + i));
+  }
+  return bundles;
+}
+
+/**
+ * Fetch the block engine's tip accounts
+ */
+export async function getJitoTipAccounts(config: JitoBundleConfig): Promise<PublicKey[]> {
+  const accounts: string[] = await jitoRpc(blockEngineUrl(config), 'getTipAccounts', []);
+  if (!accounts?.length) {
+    throw new Error('[Jito] Block engine returned no tip accounts');
+  }
+  return accounts.map(a => new PublicKey(a));
+}
+
+/**
+ * Tip instructions per transaction: one tip in the last transaction of each
+ * bundle, none elsewhere
+ *
+ * Add these before signing. The tip sits in the last transaction so it is
+ * only paid if the whole bundle executes.
+ */
+export async function buildJitoTipInstructions(
+  config: JitoBundleConfig,
+  payer: PublicKey,
+  count: number
+): Promise<TransactionInstruction[][]> {
+  if (config.tipLamports < JITO_MIN_TIP_LAMPORTS) {
+    throw new Error(`[Jito] Tip must be at least ${JITO_MIN_TIP_LAMPORTS} lamports`);
+  }
+  const tipAccounts = await getJitoTipAccounts(config);
+  const tips: TransactionInstruction[][] = Array.from({ length: count }, () => []);
+
+  for (const bundle of planBundles(count, bundleSize(config))) {
+    const tipAccount = tipAccounts[Math.floor(Math.random() * tipAccounts.length)];
+    tips[bundle[bundle.length - 1]].push(
+      SystemProgram.transfer({ fromPubkey: payer, toPubkey: tipAccount, lamports: config.tipLamports })
+    );
+  }
+  return tips;
+}
+
+/**
+ * Submit signed transactions as one bundle, returning the bundle id
+ */
+export async function sendJitoBundle(config: JitoBundleConfig, transactions: VersionedTransaction[]): Promise<string> {
+  if (transactions.length === 0 || transactions.length > JITO_MAX_BUNDLE_SIZE) {
+    throw new Error(`[Jito] Bundle must hold 1-${JITO_MAX_BUNDLE_SIZE} transactions, got ${transactions.length}`);
+  }
+  const encoded = transactions.map(tx => Buffer.from(tx.serialize()).toString('base64'));
+  return jitoRpc(blockEngineUrl(config), 'sendBundle', [encoded, { encoding: 'base64' }]);
+}
+
+/**
+ * Status of a recently submitted bundle
+ */
+export async function getJitoBundleStatus(config: JitoBundleConfig, bundleId: string): Promise<JitoBundleStatus> {
+  const result = await jitoRpc(blockEngineUrl(config), 'getInflightBundleStatuses', [[bundleId]]);
+  return result?.value?.[0]?.status ?? 'Invalid';
+}
+
+/**
+ * Wait until a bundle lands
+ *
+ * `Invalid` right after submission means the block engine hasn't seen the
+ * bundle yet, so it's treated as pending until the timeout.
+ */
+export async function waitForJitoBundle(config: JitoBundleConfig, bundleId: string): Promise<void> {
+  const deadline = Date.now() + (config.timeoutMs ?? 60_000);
+  while (Date.now() < deadline) {
+    const status = await getJitoBundleStatus(config, bundleId);
+    if (status === 'Landed') {
+      return;
+    }
+    if (status === 'Failed') {
+      throw new Error(`[Jito] Bundle ${bundleId} failed`);
+    }
+    await new Promise(resolve => setTimeout(resolve, 1_000));
+  }
+  throw new Error(`[Jito] Bundle ${bundleId} did not land within timeout`);
+}
+
+/**
+ * Send signed transactions as consecutive bundles
+ *
+ * Transactions must already carry the tips from `buildJitoTipInstructions`.
+ * Returns every transaction's signature, in order.
+ */
+export async function sendTransactionsAsJitoBundles(
+  connection: Connection,
+  config: JitoBundleConfig,
+  transactions: VersionedTransaction[]
+): Promise<string[]> {
+  const signatures = transactions.map(tx => bs58.encode(tx.signatures[0]));
+
+  for (const bundle of planBundles(transactions.length, bundleSize(config))) {
+    const bundleTxs = bundle.map(i => transactions[i]);
+    try {
+      const bundleId = await sendJitoBundle(config, bundleTxs);
+      console.log(`[Jito] Bundle ${bundleId} sent (transactions ${bundle[0]}-${bundle[bundle.length - 1]})`);
+      await waitForJitoBundle(config, bundleId);
+      console.log(`[Jito] Bundle ${bundleId} landed`);
+    } catch (err) {
+      if (config.fallbackToSequential === false) {
+        throw err;
+      }
+      // Bundles are all-or-nothing, so none of this bundle's transactions landed
+      console.warn('[Jito] Bundle failed, sending its transactions sequentially:', err);
+      for (const tx of bundleTxs) {
+        const signature = await connection.sendRawTransaction(tx.serialize(), {
+          skipPreflight: false,
+          preflightCommitment: 'confirmed',
+        });
+        const confirmation = await connection.confirmTransaction(signature, 'confirmed');
+        if (confirmation.value.err) {
+          throw new Error(`[Jito] Fallback transaction ${signature} reverted: ${JSON.stringify(confirmation.value.err)}`);
+        }
+      }
+    }
+  }
+
+  return signatures;
+}
@@ -10,10 +10,10 @@ import { PublicKey, AccountMeta } from '@solana/web3.js';
 import { deriveAddressSeedV2, deriveAddressV2, createRpc, bn, Rpc } from '@lightprotocol/stateless.js';
 import { sha256 } from '@noble/hashes/sha256';
 import type { DecryptedNote, EncryptedNote, Point } from '@cloakcraft/types';
-import { tryDecryptNote, tryDecryptAnyNote, DecryptedNoteResult } from './crypto/encryption';
+import { tryDecryptNote, tryDecryptAnyNote, deserializeEncryptedNote, DecryptedNoteResult } from './crypto/encryption';
 import { deriveSpendingNullifier, deriveNullifierKey } from './crypto/nullifier';
 import { initPoseidon, bytesToField, fieldToBytes } from './crypto/poseidon';
-import { deriveStealthPrivateKey } from './crypto/stealth';
+import { deriveStealthPrivateKey, deriveViewTag, VIEW_TAG_SIZE } from './crypto/stealth';
 import { derivePublicKey } from './crypto/babyjubjub';
 import {
   computeCommitment,
@@ -25,6 +25,8 @@ import {
   PositionNote,
   LpNote,
 } from './crypto/commitment';
+import { NULLIFIER_DOMAINS, NullifierDomain, nullifierDomainSeeds } from './instructions/constants';
+import type { PaymentRequest } from './payment-request';
 
 // =========================================================================
 // Retry Logic with Exponential Backoff
@@ -523,18 +525,21 @@ export class LightClient {
    *
    * Uses Light Protocol's Poseidon-based address derivation.
    * Must match the on-chain derivation in light_cpi/mod.rs:
-   * Seeds: ["spend_nullifier", pool, nullifier]
+   * Seeds: ["spend_nullifier", pool, nullifier] (spend domain)
+   *        ["spend_nullifier", [domain], pool, nullifier] (position domain)
    */
   deriveNullifierAddress(
     nullifier: Uint8Array,
     programId: PublicKey,
     addressTree: PublicKey,
-    pool?: PublicKey
+    pool?: PublicKey,
+    domain: NullifierDomain = NULLIFIER_DOMAINS.SPEND
   ): Uint8Array {
-    // Seeds must match on-chain: ["spend_nullifier", pool, nullifier]
+    // Seeds must match on-chain: ["spend_nullifier", (domain,) pool, nullifier]
     const seeds = pool
       ? [
           Buffer.from('spend_nullifier'),
+          ...nullifierDomainSeeds(domain),
           pool.toBuffer(),
           Buffer.from(nullifier),
         ]
@@ -701,6 +706,8 @@ export interface ScannerStats {
   cachedHits: number;
   decryptAttempts: number;
   successfulDecrypts: number;
+  /** Accounts skipped because their view tag didn't match */
+  viewTagSkips: number;
   scanDurationMs: number;
   rpcCalls: number;
 }
@@ -733,6 +740,7 @@ export class LightCommitmentClient extends LightClient {
     cachedHits: 0,
     decryptAttempts: 0,
     successfulDecrypts: 0,
+    viewTagSkips: 0,
     scanDurationMs: 0,
     rpcCalls: 0,
   };
@@ -843,6 +851,7 @@ export class LightCommitmentClient extends LightClient {
       cachedHits: 0,
       decryptAttempts: 0,
       successfulDecrypts: 0,
+      viewTagSkips: 0,
       scanDurationMs: 0,
       rpcCalls: 0,
     };
@@ -874,6 +883,127 @@ export class LightCommitmentClient extends LightClient {
     return account !== null;
   }
 
+  /**
+   * Get the commitment index stored with a commitment
+   *
+   * This is the per-pool index the note's nullifier is derived from, not
+   * the commitment's position in Light's (shared) state tree.
+   */
+  async getCommitmentIndex(
+    pool: PublicKey,
+    commitment: Uint8Array,
+    programId: PublicKey,
+    addressTree: PublicKey
+  ): Promise<number | null> {
+    const account = await this.getCommitment(pool, commitment, programId, addressTree);
+    if (!account?.data) {
+      return null;
+    }
+
+    // CommitmentAccount layout:
+    // pool: [u8; 32]          offset 0
+    // commitment: [u8; 32]    offset 32
+    // leaf_index: u64         offset 64
+    const data = Buffer.from(account.data.data, 'base64');
+    if (data.length < 72) {
+      return null;
+    }
+    return Number(data.readBigUInt64LE(64));
+  }
+
+  /**
+   * Derive payment receipt compressed account address
+   *
+   * Seeds: ["payment_receipt", reference_id]
+   */
+  derivePaymentReceiptAddress(
+    referenceId: Uint8Array,
+    programId: PublicKey,
+    addressTree: PublicKey
+  ): Uint8Array {
+    const seed = deriveAddressSeedV2([Buffer.from('payment_receipt'), Buffer.from(referenceId)]);
+    return deriveAddressV2(seed, addressTree, programId).toBytes();
+  }
+
+  /**
+   * Get the payment receipt for an invoice reference id
+   *
+   * @param addressTree - Address tree of the pool the invoice is paid in
+   */
+  async getPaymentReceipt(
+    referenceId: Uint8Array,
+    programId: PublicKey,
+    addressTree: PublicKey
+  ): Promise<PaymentReceiptData | null> {
+    const address = this.derivePaymentReceiptAddress(referenceId, programId, addressTree);
+    const account = await this.getCompressedAccount(address);
+    if (!account?.data) {
+      return null;
+    }
+
+    // PaymentReceipt layout:
+    // reference_id: [u8; 32]  offset 0
+    // pool: [u8; 32]          offset 32
+    // commitment: [u8; 32]    offset 64
+    // leaf_index: u64         offset 96
+    // paid_at: i64            offset 104
+    const data = Buffer.from(account.data.data, 'base64');
+    if (data.length < 112) {
+      return null;
+    }
+    return {
+      referenceId: new Uint8Array(data.subarray(0, 32)),
+      pool: new PublicKey(data.subarray(32, 64)),
+      commitment: new Uint8Array(data.subarray(64, 96)),
+      leafIndex: Number(data.readBigUInt64LE(96)),
+      paidAt: Number(data.readBigInt64LE(104)),
+    };
+  }
+
+  /**
+   * Check that a payment request was paid to this wallet
+   *
+   * Looks up the receipt for the request's reference id, then decrypts the
+   * note it points at with the merchant's viewing key. The receipt alone
+   * proves nothing: anyone can bind a reference id to their own commitment.
+   *
+   * @param viewingKey - Merchant's key for the request's recipient
+   * @param addressTree - Address tree of the request's pool
+   */
+  async verifyPaymentReceipt(
+    request: PaymentRequest,
+    viewingKey: bigint,
+    programId: PublicKey,
+    addressTree: PublicKey
+  ): Promise<PaymentReceiptVerification> {
+    const receipt = await this.getPaymentReceipt(request.referenceId, programId, addressTree);
+    if (!receipt) {
+      return { paid: false, reason: 'No receipt for this reference id' };
+    }
+
+    const [pool] = PublicKey.findProgramAddressSync(
+      [Buffer.from('pool'), request.mint.toBuffer()],
+      programId
+    );
+    if (!receipt.pool.equals(pool)) {
+      return { paid: false, receipt, reason: 'Receipt is for a different pool' };
+    }
+    if (request.expiresAt !== undefined && receipt.paidAt > request.expiresAt) {
+      return { paid: false, receipt, reason: 'Paid after the request expired' };
+    }
+
+    const account = await this.getCommitment(pool, receipt.commitment, programId, addressTree);
+    const note = account ? await this.processAccount(account, viewingKey, new Map()) : null;
+    if (!note) {
+      return { paid: false, receipt, reason: 'Receipt note is not addressed to this wallet' };
+    }
+    if (note.amount < request.amount) {
+      return { paid: false, receipt, note, reason: `Underpaid: ${note.amount} < ${request.amount}` };
+    }
+
+    return { paid: true, receipt, note };
+  }
+
   /**
    * Get merkle proof for a commitment using account hash
    *
@@ -1270,6 +1400,16 @@ export class LightCommitmentClient extends LightClient {
         return null;
       }
 
+      // View tag pre-filter: skip trial decryption when the tag doesn't match ours
+      if (parsed.viewTag && parsed.stealthEphemeralPubkey) {
+        const expectedTag = deriveViewTag(viewingKey, parsed.stealthEphemeralPubkey);
+        if (!expectedTag.every((b, i) => b === parsed.viewTag![i])) {
+          this.stats.viewTagSkips++;
+          cache.set(account.hash, null); // Not our note
+          return null;
+        }
+      }
+
       // Derive decryption key:
       // - If stealthEphemeralPubkey is present, derive stealthPrivateKey from it
       // - Otherwise (internal ops), use the original viewing key
@@ -1454,11 +1594,14 @@ export class LightCommitmentClient extends LightClient {
    * - commitment: 32 bytes
    * - leaf_index: 8 bytes (u64)
    * - stealth_ephemeral_pubkey: 64 bytes (X + Y coordinates)
-   * - encrypted_note: 200 bytes (FIXED SIZE array)
-   * - encrypted_note_len: 2 bytes (u16) - actual length of data in encrypted_note
+   * - encrypted_note: 250 bytes (FIXED SIZE array)
+   * - encrypted_note_len: 2 bytes (u16) - full note length, including the overflow
    * - created_at: 8 bytes (i64)
+   * - view_tag: 8 bytes (absent on accounts created before view tags)
+   * - encrypted_note_overflow: 4-byte length + bytes past the first 250
+   *   (absent on accounts created before note overflow)
    *
-   * Total: 32 + 32 + 8 + 64 + 200 + 2 + 8 = 346 bytes
+   * Total: 32 + 32 + 8 + 64 + 250 + 2 + 8 (+ 8 + 4 + overflow) = 396 (404, 408+) bytes
    */
   private parseCommitmentAccountData(dataBase64: string): {
     pool: Uint8Array;
@@ -1466,6 +1609,7 @@ export class LightCommitmentClient extends LightClient {
     leafIndex: number;
     stealthEphemeralPubkey: Point | null;
     encryptedNote: Uint8Array;
+    viewTag: Uint8Array | null;
   } | null {
     try {
       // Decode base64 to Uint8Array (works in browser and Node.js)
@@ -1479,6 +1623,7 @@ export class LightCommitmentClient extends LightClient {
       // Account size: 32 + 32 + 8 + 64 + 250 + 2 + 8 = 396 bytes
       const MIN_SIZE = 396;
       const MAX_NOTE_SIZE = 250;
+      const NOTE_HARD_CAP = 512;
 
       if (data.length < MIN_SIZE) {
         console.log(`[parseCommitmentAccountData] Data too short: ${data.length} < ${MIN_SIZE}`);
@@ -1511,13 +1656,34 @@ export class LightCommitmentClient extends LightClient {
       // encrypted_note_len: 2 bytes (u16 LE) - offset 386
       const encryptedNoteLen = view.getUint16(386, true);
 
-      if (encryptedNoteLen > MAX_NOTE_SIZE) {
-        console.log(`[parseCommitmentAccountData] Invalid note length: ${encryptedNoteLen} > ${MAX_NOTE_SIZE}`);
+      if (encryptedNoteLen > NOTE_HARD_CAP) {
+        console.log(`[parseCommitmentAccountData] Invalid note length: ${encryptedNoteLen} > ${NOTE_HARD_CAP}`);
         return null;
       }
 
-      // Get the actual encrypted note data
-      const encryptedNote = data.slice(136, 136 + encryptedNoteLen);
+      // Get the actual encrypted note data (inline part)
+      let encryptedNote = data.slice(136, 136 + Math.min(encryptedNoteLen, MAX_NOTE_SIZE));
+
+      // Notes past the inline capacity continue in encrypted_note_overflow (offset 404)
+      if (encryptedNoteLen > MAX_NOTE_SIZE) {
+        const OVERFLOW_OFFSET = 404;
+        const overflowLen = data.length >= OVERFLOW_OFFSET + 4 ? view.getUint32(OVERFLOW_OFFSET, true) : 0;
+        if (overflowLen !== encryptedNoteLen - MAX_NOTE_SIZE || data.length < OVERFLOW_OFFSET + 4 + overflowLen) {
+          console.log(`[parseCommitmentAccountData] Truncated note overflow: ${overflowLen} bytes`);
+          return null;
+        }
+        const full = new Uint8Array(encryptedNoteLen);
+        full.set(encryptedNote, 0);
+        full.set(data.slice(OVERFLOW_OFFSET + 4, OVERFLOW_OFFSET + 4 + overflowLen), MAX_NOTE_SIZE);
+        encryptedNote = full;
+      }
+
+      // view_tag: 8 bytes - offset 396 (all zeros = no tag, must trial-decrypt)
+      const VIEW_TAG_OFFSET = 396;
+      const tagBytes = data.length >= VIEW_TAG_OFFSET + VIEW_TAG_SIZE
+        ? data.slice(VIEW_TAG_OFFSET, VIEW_TAG_OFFSET + VIEW_TAG_SIZE)
+        : null;
+      const viewTag = tagBytes && tagBytes.some(b => b !== 0) ? new Uint8Array(tagBytes) : null;
 
       return {
         pool: new Uint8Array(pool),
@@ -1525,6 +1691,7 @@ export class LightCommitmentClient extends LightClient {
         leafIndex,
         stealthEphemeralPubkey,
         encryptedNote: new Uint8Array(encryptedNote),
+        viewTag,
       };
     } catch (err) {
       return null;
@@ -1532,55 +1699,10 @@ export class LightCommitmentClient extends LightClient {
   }
 
   /**
-   * Deserialize encrypted note from bytes
-   *
-   * Format:
-   * - ephemeral_pubkey_x: 32 bytes
-   * - ephemeral_pubkey_y: 32 bytes
-   * - ciphertext_len: 4 bytes (u32 LE)
-   * - ciphertext: variable (includes 12-byte nonce)
-   * - tag: 16 bytes
+   * Deserialize encrypted note from bytes (see crypto/encryption)
    */
   private deserializeEncryptedNote(data: Uint8Array): EncryptedNote | null {
-    try {
-      if (data.length < 32 + 32 + 4 + 16) {
-        return null;
-      }
-
-      let offset = 0;
-
-      // Ephemeral pubkey X (32 bytes)
-      const ephemeralX = data.slice(offset, offset + 32);
-      offset += 32;
-
-      // Ephemeral pubkey Y (32 bytes)
-      const ephemeralY = data.slice(offset, offset + 32);
-      offset += 32;
-
-      // Ciphertext length (4 bytes)
-      const ciphertextLen = new DataView(data.buffer, data.byteOffset + offset).getUint32(0, true);
-      offset += 4;
-
-      // Ciphertext (variable)
-      const ciphertext = data.slice(offset, offset + ciphertextLen);
-      offset += ciphertextLen;
-
-      // Tag (16 bytes)
-      const tag = data.slice(offset, offset + 16);
-
-      const ephemeralPubkey: Point = {
-        x: new Uint8Array(ephemeralX),
-        y: new Uint8Array(ephemeralY),
-      };
-
-      return {
-        ephemeralPubkey,
-        ciphertext: new Uint8Array(ciphertext),
-        tag: new Uint8Array(tag),
-      };
-    } catch {
-      return null;
-    }
+    return deserializeEncryptedNote(data);
   }
 
   // =========================================================================
@@ -2038,7 +2160,7 @@ export class LightCommitmentClient extends LightClient {
   /**
    * Parse PositionMeta from base64-encoded compressed account data
    */
-  private parsePositionMetaData(base64Data: string): PositionMetaData | null {
+  parsePositionMetaData(base64Data: string): PositionMetaData | null {
     try {
       const data = Buffer.from(base64Data, 'base64');
 
@@ -2056,7 +2178,9 @@ export class LightCommitmentClient extends LightClient {
       // created_at: i64            offset 162
       // updated_at: i64            offset 170
       // owner_stealth_pubkey: [u8; 32]  offset 178
-      // Total: 210 bytes
+      // entry_borrow_fee: u128     offset 210 (absent on older accounts)
+      // position_commitment: [u8; 32]  offset 226 (absent on older accounts)
+      // Total: 258 bytes
 
       if (data.length < 210) {
         return null;
@@ -2075,6 +2199,12 @@ export class LightCommitmentClient extends LightClient {
       const createdAt = Number(data.readBigInt64LE(162));
       const updatedAt = Number(data.readBigInt64LE(170));
       const ownerStealthPubkey = new Uint8Array(data.subarray(178, 210));
+      const entryBorrowFee = data.length >= 226
+        ? data.readBigUInt64LE(210) + (data.readBigUInt64LE(218) << 64n)
+        : 0n;
+      const positionCommitment = data.length >= 258
+        ? new Uint8Array(data.subarray(226, 258))
+        : new Uint8Array(32);
 
       return {
         positionId,
@@ -2090,6 +2220,8 @@ export class LightCommitmentClient extends LightClient {
         createdAt,
         updatedAt,
         ownerStealthPubkey,
+        entryBorrowFee,
+        positionCommitment,
       };
     } catch {
       return null;
@@ -2163,6 +2295,30 @@ export class LightCommitmentClient extends LightClient {
 }
 
 /** Position metadata data structure (matches on-chain PositionMeta) */
+/**
+ * On-chain payment receipt
+ */
+export interface PaymentReceiptData {
+  referenceId: Uint8Array;
+  pool: PublicKey;
+  commitment: Uint8Array;
+  leafIndex: number;
+  /** Unix seconds */
+  paidAt: number;
+}
+
+/**
+ * Result of checking a payment request against its receipt
+ */
+export interface PaymentReceiptVerification {
+  paid: boolean;
+  receipt?: PaymentReceiptData;
+  /** Decrypted payment note */
+  note?: DecryptedNote;
+  /** Why the request doesn't count as paid */
+  reason?: string;
+}
+
 export interface PositionMetaData {
   positionId: Uint8Array;
   poolId: Uint8Array;
@@ -2177,4 +2333,6 @@ export interface PositionMetaData {
   createdAt: number;
   updatedAt: number;
   ownerStealthPubkey: Uint8Array;
+  entryBorrowFee: bigint; // Borrow fee accumulator at open (1e18 scale)
+  positionCommitment: Uint8Array; // Position commitment the metadata describes
 }
@@ -0,0 +1,102 @@
+/**
+ * Operation Cost Estimates
+ *
+ * Reads per-phase compute unit limits and rent from the on-chain
+ * `estimate_operation_cost` view, so relayers can size compute budgets and
+ * priority fees per transaction instead of hardcoding them.
+ */
+
+import { ComputeBudgetProgram, TransactionInstruction } from '@solana/web3.js';
+import { Program } from '@coral-xyz/anchor';
+import BN from 'bn.js';
+import { deriveProtocolConfigPda } from './instructions/constants';
+
+/**
+ * Operation shape (operation type from the program's `operation_types`)
+ */
+export interface OperationShape {
+  operationType: number;
+  numInputs: number;
+  numOutputs: number;
+}
+
+/**
+ * Cost of one phase
+ */
+export interface PhaseCost {
+  /** Compute unit limit to request per transaction (includes headroom) */
+  computeUnitLimit: number;
+  /** Number of transactions for this phase */
+  transactions: number;
+  /** Rent paid by the relayer in this phase (lamports) */
+  rentLamports: bigint;
+}
+
+/**
+ * Per-phase cost estimate
+ */
+export interface OperationCostEstimate {
+  proof: PhaseCost;
+  verifyCommitments: PhaseCost;
+  nullifiers: PhaseCost;
+  execute: PhaseCost;
+  commitments: PhaseCost;
+  close: PhaseCost;
+  totalComputeUnits: bigint;
+  totalTransactions: number;
+  /** Rent returned on close to the rent refund recipient */
+  rentRefundLamports: bigint;
+  /** Rent returned on close to the relayer */
+  relayerRentLamports: bigint;
+}
+
+function toPhaseCost(raw: any): PhaseCost {
+  return {
+    computeUnitLimit: raw.computeUnitLimit,
+    transactions: raw.transactions,
+    rentLamports: BigInt((raw.rentLamports as BN).toString()),
+  };
+}
+
+/**
+ * Estimate an operation's per-phase cost (simulated, no signature needed)
+ */
+export async function estimateOperationCost(
+  program: Program,
+  shape: OperationShape
+): Promise<OperationCostEstimate> {
+  const [protocolConfig] = deriveProtocolConfigPda(program.programId);
+
+  const raw: any = await program.methods
+    .estimateOperationCost(shape)
+    .accountsStrict({ protocolConfig })
+    .view();
+
+  return {
+    proof: toPhaseCost(raw.proof),
+    verifyCommitments: toPhaseCost(raw.verifyCommitments),
+    nullifiers: toPhaseCost(raw.nullifiers),
+    execute: toPhaseCost(raw.execute),
+    commitments: toPhaseCost(raw.commitments),
+    close: toPhaseCost(raw.close),
+    totalComputeUnits: BigInt((raw.totalComputeUnits as BN).toString()),
+    totalTransactions: raw.totalTransactions,
+    rentRefundLamports: BigInt((raw.rentRefundLamports as BN).toString()),
+    relayerRentLamports: BigInt((raw.relayerRentLamports as BN).toString()),
+  };
+}
+
+/**
+ * Compute budget instructions for one phase transaction
+ *
+ * @param microLamports - Priority fee per compute unit
+ */
+export function phaseComputeBudgetInstructions(
+  phase: PhaseCost,
+  microLamports: number
+): TransactionInstruction[] {
+  return [
+    ComputeBudgetProgram.setComputeUnitLimit({ units: phase.computeUnitLimit }),
+    ComputeBudgetProgram.setComputeUnitPrice({ microLamports }),
+  ];
+}
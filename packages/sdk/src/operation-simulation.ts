@@ -0,0 +1,164 @@
+/**
+ * Operation Simulation
+ *
+ * Dry-runs a transfer's or swap's Phase 0 through the on-chain
+ * `simulate_operation` instruction, so relayers can vet a user submission
+ * (proof, pool checks, fee, swap output) before paying for the multi-phase
+ * flow. The instruction always reverts with SimulationComplete after setting
+ * its result as return data; any other error names the check that failed.
+ */
+
+import {
+  PublicKey,
+  TransactionMessage,
+  VersionedTransaction,
+} from '@solana/web3.js';
+import { Program } from '@coral-xyz/anchor';
+import BN from 'bn.js';
+import {
+  CLIENT_VERSION,
+  derivePoolPda,
+  deriveProgramVersionPda,
+  deriveProtocolConfigPda,
+  deriveVerificationKeyPda,
+} from './instructions/constants';
+
+/**
+ * Phase 0 arguments of a transfer (create_pending_with_proof)
+ */
+export interface SimulatedTransfer {
+  kind: 'transfer';
+  merkleRoot: Uint8Array;
+  nullifier: Uint8Array;
+  outCommitments: Uint8Array[];
+  outputAmounts: bigint[];
+  transferAmount: bigint;
+  unshieldAmount: bigint;
+  feeAmount: bigint;
+  callHash?: Uint8Array;
+}
+
+/**
+ * Phase 0 arguments of a swap (create_pending_with_proof_swap)
+ */
+export interface SimulatedSwap {
+  kind: 'swap';
+  /** AMM pool PDA */
+  ammPool: PublicKey;
+  merkleRoot: Uint8Array;
+  nullifier: Uint8Array;
+  outCommitment: Uint8Array;
+  changeCommitment: Uint8Array;
+  minOutput: bigint;
+  swapAmount: bigint;
+  outputAmount: bigint;
+  swapAToB: boolean;
+}
+
+export type SimulatedOperation = SimulatedTransfer | SimulatedSwap;
+
+/**
+ * Outcome of a simulated operation
+ */
+export interface SimulationResult {
+  operationType: number;
+  /** Whether the proof verified (Phase 0 would otherwise reject it) */
+  proofValid: boolean;
+  /** Minimum protocol fee (transfers) */
+  expectedFee: bigint;
+  /** Swap output recomputed from current reserves (swaps) */
+  expectedOutput: bigint;
+}
+
+const bn = (value: bigint) => new BN(value.toString());
+
+function operationArg(operation: SimulatedOperation): any {
+  if (operation.kind === 'transfer') {
+    return {
+      transfer: {
+        merkleRoot: Array.from(operation.merkleRoot),
+        nullifier: Array.from(operation.nullifier),
+        outCommitments: operation.outCommitments.map((c) => Array.from(c)),
+        outputAmounts: operation.outputAmounts.map(bn),
+        transferAmount: bn(operation.transferAmount),
+        unshieldAmount: bn(operation.unshieldAmount),
+        feeAmount: bn(operation.feeAmount),
+        callHash: operation.callHash ? Array.from(operation.callHash) : null,
+      },
+    };
+  }
+  return {
+    swap: {
+      merkleRoot: Array.from(operation.merkleRoot),
+      nullifier: Array.from(operation.nullifier),
+      outCommitment: Array.from(operation.outCommitment),
+      changeCommitment: Array.from(operation.changeCommitment),
+      minOutput: bn(operation.minOutput),
+      swapAmount: bn(operation.swapAmount),
+      outputAmount: bn(operation.outputAmount),
+      swapAToB: operation.swapAToB,
+    },
+  };
+}
+
+/**
+ * Simulate an operation's Phase 0 (no signature needed)
+ *
+ * @param tokenMint - Mint of the pool the operation spends from
+ * @param circuitId - Circuit the proof was generated for
+ * @param payer - Fee payer for the simulated transaction (any funded account)
+ * @throws If a check fails, with the program logs
+ */
+export async function simulateOperation(
+  program: Program,
+  tokenMint: PublicKey,
+  circuitId: string,
+  proof: Uint8Array,
+  operation: SimulatedOperation,
+  payer: PublicKey
+): Promise<SimulationResult> {
+  const programId = program.programId;
+  const connection = program.provider.connection;
+
+  const ix = await program.methods
+    .simulateOperation(Buffer.from(proof), operationArg(operation), CLIENT_VERSION)
+    .accountsStrict({
+      pool: derivePoolPda(tokenMint, programId)[0],
+      verificationKey: deriveVerificationKeyPda(circuitId, programId)[0],
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      programVersion: deriveProgramVersionPda(programId)[0],
+      ammPool: operation.kind === 'swap' ? operation.ammPool : null,
+    })
+    .instruction();
+
+  const { blockhash } = await connection.getLatestBlockhash('confirmed');
+  const message = new TransactionMessage({
+    payerKey: payer,
+    recentBlockhash: blockhash,
+    instructions: [ix],
+  }).compileToV0Message();
+
+  const { value } = await connection.simulateTransaction(new VersionedTransaction(message), {
+    sigVerify: false,
+    replaceRecentBlockhash: true,
+  });
+
+  const completed = value.logs?.some((log) => log.includes('SimulationComplete')) ?? false;
+  if (!completed || !value.returnData) {
+    throw new Error(
+      `simulate_operation failed: ${JSON.stringify(value.err)}\n${(value.logs ?? []).join('\n')}`
+    );
+  }
+
+  const raw: any = program.coder.types.decode(
+    'SimulationResult',
+    Buffer.from(value.returnData.data[0], 'base64')
+  );
+
+  return {
+    operationType: raw.operationType,
+    proofValid: raw.proofValid,
+    expectedFee: BigInt((raw.expectedFee as BN).toString()),
+    expectedOutput: BigInt((raw.expectedOutput as BN).toString()),
+  };
+}
@@ -0,0 +1,300 @@
+/**
+ * Payment Requests
+ *
+ * An invoice a merchant hands to a payer: amount, mint, the merchant's
+ * stealth meta address (their BabyJubJub public key), an optional expiry and
+ * a random reference id. The payer settles it with a private transfer whose
+ * recipient commitment carries an on-chain PaymentReceipt for the reference
+ * id; the merchant finds the receipt by reference id and decrypts the note it
+ * points at to check the amount (see `LightCommitmentClient.verifyPaymentReceipt`).
+ *
+ * Two encodings:
+ * - URI: `cloakcraft:<recipient>?amount=&mint=&ref=&exp=&label=` (base58
+ *   fields), for links and copy/paste
+ * - QR: `CLOAKCRAFT:<BASE32>`, the binary request in upper-case base32 so QR
+ *   encoders can use alphanumeric mode (about a third smaller than the URI)
+ *
+ * `decodePaymentRequest` accepts either.
+ */
+
+import { PublicKey } from '@solana/web3.js';
+import { randomBytes } from '@noble/hashes/utils';
+import bs58 from 'bs58';
+import type { Point } from '@cloakcraft/types';
+import { isOnCurve, isInSubgroup } from './crypto/babyjubjub';
+
+/** URI scheme for payment requests */
+export const PAYMENT_REQUEST_SCHEME = 'cloakcraft';
+
+/** Binary encoding version */
+export const PAYMENT_REQUEST_VERSION = 1;
+
+/** Maximum label length in UTF-8 bytes */
+export const PAYMENT_REQUEST_MAX_LABEL_BYTES = 64;
+
+/** Reference id length (matches PaymentReceipt.reference_id on-chain) */
+export const PAYMENT_REFERENCE_ID_SIZE = 32;
+
+/**
+ * Payment request (invoice)
+ */
+export interface PaymentRequest {
+  /** Amount in the mint's base units */
+  amount: bigint;
+  /** Token mint */
+  mint: PublicKey;
+  /** Merchant's stealth meta address (BabyJubJub public key) */
+  recipient: Point;
+  /** Random 32-byte id the on-chain receipt is keyed by */
+  referenceId: Uint8Array;
+  /** Expiry as unix seconds (none if omitted) */
+  expiresAt?: number;
+  /** Short human-readable description */
+  label?: string;
+}
+
+/**
+ * Create a payment request with a fresh reference id
+ */
+export function createPaymentRequest(params: {
+  amount: bigint;
+  mint: PublicKey;
+  recipient: Point;
+  /** Seconds from now until the request expires */
+  expiresInSeconds?: number;
+  label?: string;
+}): PaymentRequest {
+  const request: PaymentRequest = {
+    amount: params.amount,
+    mint: params.mint,
+    recipient: params.recipient,
+    referenceId: randomBytes(PAYMENT_REFERENCE_ID_SIZE),
+    expiresAt: params.expiresInSeconds !== undefined
+      ? Math.floor(Date.now() / 1000) + params.expiresInSeconds
+      : undefined,
+    label: params.label,
+  };
+  validatePaymentRequest(request);
+  return request;
+}
+
+/**
+ * Whether a request has expired at `now` (unix seconds)
+ */
+export function isPaymentRequestExpired(request: PaymentRequest, now: number = Date.now() / 1000): boolean {
+  return request.expiresAt !== undefined && now > request.expiresAt;
+}
+
+/**
+ * Throw if a request is malformed
+ *
+ * The recipient must be a valid subgroup point: paying to anything else
+ * produces a note no one can spend.
+ */
+export function validatePaymentRequest(request: PaymentRequest): void {
+  if (request.amount <= 0n || request.amount > 0xffffffffffffffffn) {
+    throw new Error(`Invalid payment amount ${request.amount}`);
+  }
+  if (request.referenceId.length !== PAYMENT_REFERENCE_ID_SIZE) {
+    throw new Error(`Reference id must be ${PAYMENT_REFERENCE_ID_SIZE} bytes, got ${request.referenceId.length}`);
+  }
+  if (request.recipient.x.length !== 32 || request.recipient.y.length !== 32) {
+    throw new Error('Recipient coordinates must be 32 bytes');
+  }
+  if (!isOnCurve(request.recipient) || !isInSubgroup(request.recipient)) {
+    throw new Error('Recipient is not a valid stealth meta address');
+  }
+  if (request.expiresAt !== undefined && (!Number.isSafeInteger(request.expiresAt) || request.expiresAt <= 0)) {
+    throw new Error(`Invalid expiry ${request.expiresAt}`);
+  }
+  if (request.label !== undefined && new TextEncoder().encode(request.label).length > PAYMENT_REQUEST_MAX_LABEL_BYTES) {
+    throw new Error(`Label exceeds ${PAYMENT_REQUEST_MAX_LABEL_BYTES} bytes`);
+  }
+}
+
+function encodeRecipient(recipient: Point): string {
+  const bytes = new Uint8Array(64);
+  bytes.set(recipient.x, 0);
+  bytes.set(recipient.y, 32);
+  return bs58.encode(bytes);
+}
+
+function decodeRecipient(value: string): Point {
+  const bytes = bs58.decode(value);
+  if (bytes.length !== 64) {
+    throw new Error(`Recipient must be 64 bytes, got ${bytes.length}`);
+  }
+  return { x: bytes.slice(0, 32), y: bytes.slice(32, 64) };
+}
+
+// =============================================================================
+// URI Encoding
+// =============================================================================
+
+/**
+ * Encode a request as a `cloakcraft:` URI
+ */
+export function encodePaymentRequestUri(request: PaymentRequest): string {
+  validatePaymentRequest(request);
+  const query = new URLSearchParams();
+  query.set('amount', request.amount.toString());
+  query.set('mint', request.mint.toBase58());
+  query.set('ref', bs58.encode(request.referenceId));
+  if (request.expiresAt !== undefined) {
+    query.set('exp', request.expiresAt.toString());
+  }
+  if (request.label !== undefined) {
+    query.set('label', request.label);
+  }
+  return `${PAYMENT_REQUEST_SCHEME}:${encodeRecipient(request.recipient)}?${query.toString()}`;
+}
+
+function decodePaymentRequestUri(uri: string): PaymentRequest {
+  const body = uri.slice(PAYMENT_REQUEST_SCHEME.length + 1);
+  const separator = body.indexOf('?');
+  if (separator < 0) {
+    throw new Error('Payment request URI has no parameters');
+  }
+  const query = new URLSearchParams(body.slice(separator + 1));
+  const required = (name: string): string => {
+    const value = query.get(name);
+    if (value === null) {
+      throw new Error(`Payment request URI is missing '${name}'`);
+    }
+    return value;
+  };
+
+  const amount = required('amount');
+  if (!/^\d+$/.test(amount)) {
+    throw new Error(`Invalid payment amount '${amount}'`);
+  }
+  const exp = query.get('exp');
+  const request: PaymentRequest = {
+    amount: BigInt(amount),
+    mint: new PublicKey(required('mint')),
+    recipient: decodeRecipient(body.slice(0, separator)),
+    referenceId: bs58.decode(required('ref')),
+    expiresAt: exp !== null ? Number(exp) : undefined,
+    label: query.get('label') ?? undefined,
+  };
+  validatePaymentRequest(request);
+  return request;
+}
+
+// =============================================================================
+// Binary / QR Encoding
+// =============================================================================
+
+/**
+ * Binary encoding
+ *
+ * version(1) | recipient.x(32) | recipient.y(32) | mint(32) | amount(u64 LE)
+ * | reference_id(32) | expires_at(i64 LE, 0 = none) | label(UTF-8, rest)
+ */
+export function encodePaymentRequestBytes(request: PaymentRequest): Uint8Array {
+  validatePaymentRequest(request);
+  const label = new TextEncoder().encode(request.label ?? '');
+  const bytes = new Uint8Array(1 + 32 + 32 + 32 + 8 + 32 + 8 + label.length);
+  const view = new DataView(bytes.buffer);
+
+  let offset = 0;
+  bytes[offset++] = PAYMENT_REQUEST_VERSION;
+  bytes.set(request.recipient.x, offset); offset += 32;
+  bytes.set(request.recipient.y, offset); offset += 32;
+  bytes.set(request.mint.toBytes(), offset); offset += 32;
+  view.setBigUint64(offset, request.amount, true); offset += 8;
+  bytes.set(request.referenceId, offset); offset += 32;
+  view.setBigInt64(offset, BigInt(request.expiresAt ?? 0), true); offset += 8;
+  bytes.set(label, offset);
+  return bytes;
+}
+
+/**
+ * Decode the binary encoding
+ */
+export function decodePaymentRequestBytes(bytes: Uint8Array): PaymentRequest {
+  const FIXED_SIZE = 1 + 32 + 32 + 32 + 8 + 32 + 8;
+  if (bytes.length < FIXED_SIZE) {
+    throw new Error(`Payment request too short: ${bytes.length} bytes`);
+  }
+  if (bytes[0] !== PAYMENT_REQUEST_VERSION) {
+    throw new Error(`Unsupported payment request version ${bytes[0]}`);
+  }
+  const view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+
+  const expiresAt = Number(view.getBigInt64(1 + 32 + 32 + 32 + 8 + 32, true));
+  const label = bytes.length > FIXED_SIZE ? new TextDecoder().decode(bytes.slice(FIXED_SIZE)) : undefined;
+  const request: PaymentRequest = {
+    recipient: { x: bytes.slice(1, 33), y: bytes.slice(33, 65) },
+    mint: new PublicKey(bytes.slice(65, 97)),
+    amount: view.getBigUint64(97, true),
+    referenceId: bytes.slice(105, 137),
+    expiresAt: expiresAt !== 0 ? expiresAt : undefined,
+    label,
+  };
+  validatePaymentRequest(request);
+  return request;
+}
+
+const BASE32_ALPHABET = 'ABCDEFGHIJKLMNOPQRSTUVWXYZ234567';
+
+function base32Encode(bytes: Uint8Array): string {
+  let out = '';
+  let buffer = 0;
+  let bits = 0;
+  for (const byte of bytes) {
+    buffer = (buffer << 8) | byte;
+    bits += 8;
+    while (bits >= 5) {
+      out += BASE32_ALPHABET[(buffer >>> (bits - 5)) & 31];
+      bits -= 5;
+    }
+  }
+  if (bits > 0) {
+    out += BASE32_ALPHABET[(buffer << (5 - bits)) & 31];
+  }
+  return out;
+}
+
+function base32Decode(value: string): Uint8Array {
+  const out: number[] = [];
+  let buffer = 0;
+  let bits = 0;
+  for (const char of value.toUpperCase()) {
+    const index = BASE32_ALPHABET.indexOf(char);
+    if (index < 0) {
+      throw new Error(`Invalid base32 character '${char}'`);
+    }
+    buffer = (buffer << 5) | index;
+    bits += 5;
+    if (bits >= 8) {
+      out.push((buffer >>> (bits - 8)) & 0xff);
+      bits -= 8;
+    }
+  }
+  return new Uint8Array(out);
+}
+
+/**
+ * Encode a request as a QR payload (`CLOAKCRAFT:<BASE32>`)
+ *
+ * Only uses QR alphanumeric characters; pass it to any QR encoder.
+ */
+export function encodePaymentRequestQr(request: PaymentRequest): string {
+  return `${PAYMENT_REQUEST_SCHEME.toUpperCase()}:${base32Encode(encodePaymentRequestBytes(request))}`;
+}
+
+/**
+ * Decode a payment request from a URI or QR payload
+ */
+export function decodePaymentRequest(value: string): PaymentRequest {
+  const trimmed = value.trim();
+  const prefix = `${PAYMENT_REQUEST_SCHEME}:`;
+  if (trimmed.slice(0, prefix.length).toLowerCase() !== prefix) {
+    throw new Error(`Not a ${PAYMENT_REQUEST_SCHEME} payment request`);
+  }
+  // Base32 payloads never contain '?'; URIs always do
+  return trimmed.includes('?')
+    ? decodePaymentRequestUri(trimmed)
+    : decodePaymentRequestBytes(base32Decode(trimmed.slice(prefix.length)));
+}
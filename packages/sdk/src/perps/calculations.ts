@@ -127,6 +127,28 @@ export function calculateBorrowFees(
   return borrowFee;
 }
 
+/**
+ * Borrow fee a close position proof deducts
+ *
+ * Mirrors Phase 0 of close position: the fee accrued since the position's
+ * entry snapshot, capped at what the settlement can pay.
+ *
+ * @param size - Position size
+ * @param cumulativeBorrowFee - Borrowed token's on-chain accumulator
+ * @param entryBorrowFee - Accumulator snapshot in the position's PositionMeta
+ * @param grossSettlement - Margin +/- capped PnL - close fee
+ */
+export function calculateCloseBorrowFee(
+  size: bigint,
+  cumulativeBorrowFee: bigint,
+  entryBorrowFee: bigint,
+  grossSettlement: bigint
+): bigint {
+  const feeDelta = cumulativeBorrowFee > entryBorrowFee ? cumulativeBorrowFee - entryBorrowFee : 0n;
+  const accrued = size * feeDelta / BigInt(1e18);
+  return accrued < grossSettlement ? accrued : grossSettlement;
+}
+
 /**
  * Calculate liquidation price for a position
  *
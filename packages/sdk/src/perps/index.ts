@@ -37,13 +37,14 @@ export type {
   WithdrawableResult,
 } from './types';
 
-export { MAX_PERPS_TOKENS } from './types';
+export { MAX_PERPS_TOKENS, PerpsMarketStatus } from './types';
 
 // Calculations
 export {
   // Position calculations
   calculatePnL,
   calculateBorrowFees,
+  calculateCloseBorrowFee,
   calculateLiquidationPrice,
   calculatePositionFee,
   calculateImbalanceFee,
@@ -70,11 +71,25 @@ export {
   derivePerpsMarketPda,
   derivePerpsVaultPda,
   derivePerpsLpMintPda,
+  derivePerpOrderPda,
+  deriveBorrowFeeHistoryPda,
+  derivePerpsPoolMigrationPda,
   // Instruction builders - Trading
   buildOpenPositionWithProgram,
   buildClosePositionWithProgram,
+  buildTransferPositionWithProgram,
+  buildCreatePositionMetaWithProgram,
+  buildVerifyPositionMetaActiveWithProgram,
+  positionMetaToArg,
+  quotePositionValue,
+  checkProfitBound,
   buildAddPerpsLiquidityWithProgram,
   buildRemovePerpsLiquidityWithProgram,
+  buildConvertLpNoteWithProgram,
+  buildCreatePerpOrderWithProgram,
+  buildExecutePerpOrderFillWithProgram,
+  buildCloseExpiredPerpOrderWithProgram,
+  computePositionTermsHash,
   // Instruction builders - Admin
   buildInitializePerpsPoolWithProgram,
   buildAddTokenToPoolWithProgram,
@@ -82,8 +97,16 @@ export {
   buildUpdatePoolConfigWithProgram,
   buildUpdateTokenStatusWithProgram,
   buildUpdateMarketStatusWithProgram,
+  buildUpdateTokenTargetWeightWithProgram,
+  buildInitializeBorrowFeeHistoryWithProgram,
+  buildDelistMarketWithProgram,
+  buildBeginPoolMigrationWithProgram,
+  buildMigrateTokenVaultWithProgram,
+  buildFinalizePoolMigrationWithProgram,
   // Instruction builders - Keeper
   buildUpdateBorrowFeesWithProgram,
+  buildRebalancePoolWithProgram,
+  buildForceSettleMarketWithProgram,
   buildLiquidatePositionWithProgram,
   // Keeper helpers
   shouldLiquidate,
@@ -94,8 +117,20 @@ export type {
   // Instruction params - Trading
   OpenPositionInstructionParams,
   ClosePositionInstructionParams,
+  TransferPositionInstructionParams,
+  CreatePositionMetaInstructionParams,
+  VerifyPositionMetaActiveInstructionParams,
+  LightCreatePositionMetaParams,
+  LightVerifyPositionMetaParams,
+  QuotePositionValueParams,
+  PositionValueQuote,
+  CheckProfitBoundParams,
+  ProfitBoundCheck,
   AddPerpsLiquidityInstructionParams,
   RemovePerpsLiquidityInstructionParams,
+  ConvertLpNoteInstructionParams,
+  CreatePerpOrderInstructionParams,
+  ExecutePerpOrderFillInstructionParams,
   // Instruction params - Admin
   InitializePerpsPoolParams,
   AddTokenToPoolParams,
@@ -103,7 +138,14 @@ export type {
   UpdatePoolConfigParams,
   UpdateTokenStatusParams,
   UpdateMarketStatusParams,
+  UpdateTokenTargetWeightParams,
+  DelistMarketParams,
+  BeginPoolMigrationParams,
+  MigrateTokenVaultParams,
+  FinalizePoolMigrationParams,
   // Instruction params - Keeper
+  RebalancePoolParams,
+  ForceSettleMarketParams,
   LiquidatePositionInstructionParams,
 } from './instructions';
 
@@ -18,17 +18,23 @@ import type { StealthAddress } from '@cloakcraft/types';
 import {
   deriveVerificationKeyPda,
   derivePoolPda,
+  deriveProtocolConfigPda,
+  deriveTreeRegistryPda,
+  deriveProgramVersionPda,
+  CLIENT_VERSION,
   PROGRAM_ID,
 } from '../instructions/constants';
 import { derivePendingOperationPda, generateOperationId, PendingCommitmentData } from '../instructions/swap';
 import { encryptNote, serializeEncryptedNote, encryptPositionNote, encryptLpNote } from '../crypto/encryption';
+import type { PositionTerms } from '../crypto/encryption';
 import {
   createPositionNote,
   createLpNote,
   NOTE_TYPE_POSITION,
   NOTE_TYPE_LP,
 } from '../crypto/commitment';
-import { bytesToField, fieldToBytes } from '../crypto/poseidon';
+import { bytesToField, fieldToBytes, poseidonHashDomain, DOMAIN_POSITION_TERMS } from '../crypto/poseidon';
+import type { PositionMetaData } from '../light';
 
 // =============================================================================
 // Pyth Price Feed IDs
@@ -80,14 +86,21 @@ export const PERPS_SEEDS = {
   PERPS_MARKET: Buffer.from('perps_market'),
   PERPS_VAULT: Buffer.from('perps_vault'),
   PERPS_LP_MINT: Buffer.from('perps_lp_mint'),
+  PERP_ORDER: Buffer.from('perp_order'),
+  BORROW_FEE_HISTORY: Buffer.from('borrow_fee_history'),
+  PERPS_MIGRATION: Buffer.from('perps_migration'),
 } as const;
 
 export const PERPS_CIRCUIT_IDS = {
   OPEN_POSITION: 'perps_open_position',
+  /** Open whose margin, leverage and fee are only public as a hash (sealed opens) */
+  OPEN_POSITION_SEALED: 'perps_open_position_sealed',
   CLOSE_POSITION: 'perps_close_position',
   ADD_LIQUIDITY: 'perps_add_liquidity',
   REMOVE_LIQUIDITY: 'perps_remove_liquidity',
   LIQUIDATE: 'perps_liquidate',
+  TRANSFER_POSITION: 'perps_transfer_position',
+  CONVERT_LP: 'perps_convert_lp',
 } as const;
 
 // =============================================================================
@@ -148,6 +161,45 @@ export function derivePerpsLpMintPda(
   );
 }
 
+/**
+ * Derive perps limit order PDA
+ */
+export function derivePerpOrderPda(
+  orderId: Uint8Array,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [PERPS_SEEDS.PERP_ORDER, Buffer.from(orderId)],
+    programId
+  );
+}
+
+/**
+ * Derive perps pool borrow fee history PDA
+ */
+export function deriveBorrowFeeHistoryPda(
+  perpsPool: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [PERPS_SEEDS.BORROW_FEE_HISTORY, perpsPool.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Derive perps pool migration PDA (keyed by the old pool)
+ */
+export function derivePerpsPoolMigrationPda(
+  oldPerpsPool: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [PERPS_SEEDS.PERPS_MIGRATION, oldPerpsPool.toBuffer()],
+    programId
+  );
+}
+
 // =============================================================================
 // Light Protocol Types (simplified - caller provides the actual params)
 // =============================================================================
@@ -155,6 +207,8 @@ export function derivePerpsLpMintPda(
 /** Light params for verify commitment */
 export interface LightVerifyParams {
   commitmentAccountHash: number[];
+  /** Data hash of the commitment account (for on-chain account hash recomputation) */
+  commitmentDataHash: number[];
   commitmentMerkleContext: {
     merkleTreePubkeyIndex: number;
     queuePubkeyIndex: number;
@@ -189,6 +243,68 @@ export interface LightNullifierParams {
   outputTreeIndex: number;
 }
 
+/** Light params for create position meta */
+export interface LightCreatePositionMetaParams {
+  validityProof: {
+    a: number[];
+    b: number[];
+    c: number[];
+  };
+  addressTreeInfo: {
+    addressMerkleTreePubkeyIndex: number;
+    addressQueuePubkeyIndex: number;
+    rootIndex: number;
+  };
+  outputTreeIndex: number;
+}
+
+/** Light params for verify position meta active */
+export interface LightVerifyPositionMetaParams {
+  validityProof: {
+    a: number[];
+    b: number[];
+    c: number[];
+  };
+  addressTreeInfo: {
+    addressMerkleTreePubkeyIndex: number;
+    addressQueuePubkeyIndex: number;
+    rootIndex: number;
+  };
+  merkleContext: {
+    merkleTreePubkeyIndex: number;
+    queuePubkeyIndex: number;
+    leafIndex: number;
+    rootIndex: number;
+    proveByIndex: boolean;
+  };
+}
+
+/**
+ * Convert parsed PositionMeta to the instruction argument
+ *
+ * verify_position_meta_active recomputes the account hash from these fields,
+ * so they must be exactly the stored values.
+ */
+export function positionMetaToArg(meta: PositionMetaData): any {
+  return {
+    positionId: Array.from(meta.positionId),
+    poolId: Array.from(meta.poolId),
+    marketId: Array.from(meta.marketId),
+    marginAmount: new BN(meta.marginAmount.toString()),
+    liquidationPrice: new BN(meta.liquidationPrice.toString()),
+    isLong: meta.isLong,
+    positionSize: new BN(meta.positionSize.toString()),
+    entryPrice: new BN(meta.entryPrice.toString()),
+    nullifierHash: Array.from(meta.nullifierHash),
+    status: meta.status,
+    createdAt: new BN(meta.createdAt),
+    updatedAt: new BN(meta.updatedAt),
+    ownerStealthPubkey: Array.from(meta.ownerStealthPubkey),
+    entryBorrowFee: new BN(meta.entryBorrowFee.toString()),
+    positionCommitment: Array.from(meta.positionCommitment),
+  };
+}
+
 // =============================================================================
 // Open Position Instructions
 // =============================================================================
@@ -252,6 +368,38 @@ export interface OpenPositionInstructionParams {
   lightNullifierParams: LightNullifierParams;
   /** Remaining accounts for Light Protocol */
   remainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+  /**
+   * Salt of a sealed open (proof from the open_position_sealed circuit,
+   * terms opened from the user's envelope with decryptPositionTerms).
+   * Phase 0 uses createPendingWithProofOpenPositionSealed, Phase 3
+   * executeOpenPositionRevealed.
+   */
+  termsSalt?: Uint8Array;
+}
+
+/**
+ * Hash of a sealed open's hidden terms (matches compute_position_terms_hash)
+ *
+ * Poseidon(DOMAIN_POSITION_TERMS, marginAmount, leverage, positionFee, salt).
+ * Also the `terms_hash` public input of the open_position_sealed circuit.
+ */
+export function computePositionTermsHash(
+  marginAmount: bigint,
+  leverage: number,
+  positionFee: bigint,
+  salt: Uint8Array
+): Uint8Array {
+  return poseidonHashDomain(
+    DOMAIN_POSITION_TERMS,
+    fieldToBytes(marginAmount),
+    fieldToBytes(BigInt(leverage)),
+    fieldToBytes(positionFee),
+    salt
+  );
 }
 
 /**
@@ -279,24 +427,48 @@ export async function buildOpenPositionWithProgram(
 
   // Derive PDAs
   const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
-  const [vkPda] = deriveVerificationKeyPda(PERPS_CIRCUIT_IDS.OPEN_POSITION, programId);
+  const [vkPda] = deriveVerificationKeyPda(
+    params.termsSalt ? PERPS_CIRCUIT_IDS.OPEN_POSITION_SEALED : PERPS_CIRCUIT_IDS.OPEN_POSITION,
+    programId
+  );
 
-  // Phase 0: Create pending with proof
-  const phase0Tx = await program.methods
-    .createPendingWithProofOpenPosition(
-      Array.from(operationId),
-      Buffer.from(params.proof),
-      Array.from(params.merkleRoot),
-      Array.from(params.inputCommitment),
-      Array.from(params.nullifier),
-      Array.from(params.positionCommitment),
-      Array.from(params.changeCommitment),
-      params.isLong,
-      new BN(params.marginAmount.toString()),
-      params.leverage,
-      new BN(params.positionFee.toString()),
-      new BN(params.changeAmount.toString())
-    )
+  // Phase 0: Create pending with proof (sealed opens publish only the terms hash)
+  const proofArgs = [
+    Array.from(operationId),
+    Buffer.from(params.proof),
+    Array.from(params.merkleRoot),
+    Array.from(params.inputCommitment),
+    Array.from(params.nullifier),
+    Array.from(params.positionCommitment),
+    Array.from(params.changeCommitment),
+    params.isLong,
+  ];
+  const revealTerms: PositionTerms | undefined = params.termsSalt
+    ? {
+        marginAmount: params.marginAmount,
+        leverage: params.leverage,
+        positionFee: params.positionFee,
+        salt: params.termsSalt,
+      }
+    : undefined;
+  const phase0Method = revealTerms
+    ? program.methods.createPendingWithProofOpenPositionSealed(
+        ...proofArgs,
+        new BN(params.changeAmount.toString()),
+        Array.from(
+          computePositionTermsHash(revealTerms.marginAmount, revealTerms.leverage, revealTerms.positionFee, revealTerms.salt)
+        ),
+        CLIENT_VERSION
+      )
+    : program.methods.createPendingWithProofOpenPosition(
+        ...proofArgs,
+        new BN(params.marginAmount.toString()),
+        params.leverage,
+        new BN(params.positionFee.toString()),
+        new BN(params.changeAmount.toString()),
+        CLIENT_VERSION
+      );
+  const phase0Tx = await phase0Method
     .accountsStrict({
       marginPool: params.settlementPool,
       positionPool: params.positionPool,
@@ -305,12 +477,60 @@ export async function buildOpenPositionWithProgram(
       verificationKey: vkPda,
       pendingOperation: pendingOpPda,
       relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
       systemProgram: SystemProgram.programId,
     })
     .preInstructions([
       ComputeBudgetProgram.setComputeUnitLimit({ units: 800_000 }),
     ]);
 
+  const { phase1Tx, phase2Tx, phase3Tx } = await buildOpenPositionPhaseTxs(
+    program,
+    operationId,
+    { ...params, revealTerms },
+    params.relayer
+  );
+
+  const pendingCommitments = buildOpenPositionPendingCommitments(params);
+
+  return {
+    tx: phase0Tx,
+    phase1Tx,
+    phase2Tx,
+    phase3Tx,
+    operationId,
+    pendingCommitments,
+  };
+}
+
+/** Accounts for open position Phases 1-3 (shared by market and limit orders) */
+interface OpenPositionPhaseAccounts {
+  settlementPool: PublicKey;
+  perpsPool: PublicKey;
+  market: PublicKey;
+  priceUpdate: PublicKey;
+  entryPrice: bigint;
+  lightVerifyParams: LightVerifyParams;
+  lightNullifierParams: LightNullifierParams;
+  remainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+  /** Terms of a sealed open, revealed in Phase 3 */
+  revealTerms?: PositionTerms;
+}
+
+/**
+ * Build open position Phases 1-3 for an existing pending operation
+ */
+async function buildOpenPositionPhaseTxs(
+  program: Program,
+  operationId: Uint8Array,
+  params: OpenPositionPhaseAccounts,
+  relayer: PublicKey
+): Promise<{ phase1Tx: any; phase2Tx: any; phase3Tx: any }> {
+  const [pendingOpPda] = derivePendingOperationPda(operationId, program.programId);
+
   // Phase 1: Verify commitment exists (generic instruction)
   const phase1Tx = await program.methods
     .verifyCommitmentExists(
@@ -321,7 +541,7 @@ export async function buildOpenPositionWithProgram(
     .accountsStrict({
       pool: params.settlementPool,
       pendingOperation: pendingOpPda,
-      relayer: params.relayer,
+      relayer,
     })
     .remainingAccounts(params.remainingAccounts)
     .preInstructions([
@@ -338,31 +558,52 @@ export async function buildOpenPositionWithProgram(
     .accountsStrict({
       pool: params.settlementPool,
       pendingOperation: pendingOpPda,
-      relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      relayer,
+      poolStats: null,
+      treeRegistry: deriveTreeRegistryPda(program.programId)[0],
     })
     .remainingAccounts(params.remainingAccounts)
     .preInstructions([
       ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
     ]);
 
-  // Phase 3: Execute open position
-  const phase3Tx = await program.methods
-    .executeOpenPosition(
-      Array.from(operationId),
-      new BN(params.entryPrice.toString())
-    )
+  // Phase 3: Execute open position (sealed opens reveal their terms)
+  const terms = params.revealTerms;
+  const phase3Method = terms
+    ? program.methods.executeOpenPositionRevealed(
+        Array.from(operationId),
+        new BN(terms.marginAmount.toString()),
+        terms.leverage,
+        new BN(terms.positionFee.toString()),
+        Array.from(terms.salt)
+      )
+    : program.methods.executeOpenPosition(
+        Array.from(operationId),
+        new BN(params.entryPrice.toString())
+      );
+  const phase3Tx = await phase3Method
     .accountsStrict({
       marginPool: params.settlementPool,
       perpsPool: params.perpsPool,
       perpsMarket: params.market,
       pendingOperation: pendingOpPda,
-      relayer: params.relayer,
+      relayer,
       priceUpdate: params.priceUpdate,
     })
     .preInstructions([
       ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 }),
     ]);
 
+  return { phase1Tx, phase2Tx, phase3Tx };
+}
+
+/**
+ * Build the position (and change) commitments created in Phase 4
+ */
+function buildOpenPositionPendingCommitments(
+  params: OpenPositionInstructionParams
+): PendingCommitmentData[] {
   // Build encrypted position note (fits in 250-byte limit with full marketId)
   // IMPORTANT: Use field-reduced marketId to match commitment computation in proofs.ts
   // The proof generator uses bytesToField(raw_marketId) which may reduce large values
@@ -417,14 +658,210 @@ export async function buildOpenPositionWithProgram(
     });
   }
 
-  return {
-    tx: phase0Tx,
-    phase1Tx,
-    phase2Tx,
-    phase3Tx,
-    operationId,
-    pendingCommitments,
+  return pendingCommitments;
+}
+
+// =============================================================================
+// Perps Limit Order Instructions
+// =============================================================================
+
+export interface CreatePerpOrderInstructionParams
+  extends Omit<
+    OpenPositionInstructionParams,
+    'priceUpdate' | 'lightVerifyParams' | 'lightNullifierParams' | 'remainingAccounts'
+  > {
+  /** Unique order ID (32 bytes) */
+  orderId: Uint8Array;
+  /** Limit price (oracle scale); must equal entryPrice committed in the proof */
+  limitPrice: bigint;
+  /** Expiry timestamp (seconds) */
+  expiry: number;
+}
+
+/**
+ * Build create_perp_order instruction
+ *
+ * The open-position proof must commit entry_price = limitPrice. The encrypted
+ * output notes are stored on the order so any keeper can complete the fill.
+ * Position fee must be at least the pool's maker fee.
+ */
+export async function buildCreatePerpOrderWithProgram(
+  program: Program,
+  params: CreatePerpOrderInstructionParams
+): Promise<{ tx: any; orderPda: PublicKey }> {
+  const programId = program.programId;
+  const [orderPda] = derivePerpOrderPda(params.orderId, programId);
+  const [vkPda] = deriveVerificationKeyPda(PERPS_CIRCUIT_IDS.OPEN_POSITION, programId);
+
+  const commitments = buildOpenPositionPendingCommitments({
+    ...params,
+    entryPrice: params.limitPrice,
+  } as OpenPositionInstructionParams);
+  const ephemeralPubkeys = [0, 1].map((i) =>
+    Array.from(commitments[i]?.stealthEphemeralPubkey ?? new Uint8Array(64))
+  );
+
+  const tx = await program.methods
+    .createPerpOrder(
+      Array.from(params.orderId),
+      Buffer.from(params.proof),
+      Array.from(params.merkleRoot),
+      Array.from(params.inputCommitment),
+      Array.from(params.nullifier),
+      Array.from(params.positionCommitment),
+      Array.from(params.changeCommitment),
+      params.isLong,
+      new BN(params.marginAmount.toString()),
+      params.leverage,
+      new BN(params.positionFee.toString()),
+      new BN(params.changeAmount.toString()),
+      new BN(params.limitPrice.toString()),
+      new BN(params.expiry),
+      ephemeralPubkeys,
+      commitments.map((c) => Buffer.from(c.encryptedNote)),
+      CLIENT_VERSION
+    )
+    .accountsStrict({
+      marginPool: params.settlementPool,
+      positionPool: params.positionPool,
+      perpsPool: params.perpsPool,
+      perpsMarket: params.market,
+      verificationKey: vkPda,
+      perpOrder: orderPda,
+      relayer: params.relayer,
+      programVersion: deriveProgramVersionPda(programId)[0],
+      systemProgram: SystemProgram.programId,
+    })
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 800_000 }),
+    ]);
+
+  return { tx, orderPda };
+}
+
+export interface ExecutePerpOrderFillInstructionParams {
+  /** Order ID */
+  orderId: Uint8Array;
+  /** Decoded order account (read before filling: the fill closes it) */
+  order: {
+    payer: PublicKey;
+    marginPool: PublicKey;
+    positionPool: PublicKey;
+    positionCommitment: Uint8Array;
+    changeCommitment: Uint8Array;
+    changeAmount: bigint;
+    limitPrice: bigint;
+    stealthEphemeralPubkeys: Uint8Array[];
+    encryptedNotes: Uint8Array[];
   };
+  /** Perps pool */
+  perpsPool: PublicKey;
+  /** Market */
+  market: PublicKey;
+  /** Pyth price update account for the base token */
+  priceUpdate: PublicKey;
+  /** Keeper (relayer of the resulting pending operation) */
+  keeper: PublicKey;
+  /** Light params for verify commitment */
+  lightVerifyParams: LightVerifyParams;
+  /** Light params for create nullifier */
+  lightNullifierParams: LightNullifierParams;
+  /** Remaining accounts for Light Protocol */
+  remainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+}
+
+/**
+ * Build execute_perp_order_fill (keeper) plus the open position Phases 1-3
+ *
+ * Phase 4 uses the returned pending commitments (from the order's stored notes).
+ */
+export async function buildExecutePerpOrderFillWithProgram(
+  program: Program,
+  params: ExecutePerpOrderFillInstructionParams
+): Promise<{
+  tx: any;
+  phase1Tx: any;
+  phase2Tx: any;
+  phase3Tx: any;
+  operationId: Uint8Array;
+  pendingCommitments: PendingCommitmentData[];
+}> {
+  const programId = program.programId;
+  const operationId = generateOperationId(
+    params.orderId,
+    params.order.positionCommitment,
+    Date.now()
+  );
+  const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
+  const [orderPda] = derivePerpOrderPda(params.orderId, programId);
+
+  const tx = await program.methods
+    .executePerpOrderFill(Array.from(params.orderId), Array.from(operationId))
+    .accountsStrict({
+      perpOrder: orderPda,
+      orderPayer: params.order.payer,
+      perpsPool: params.perpsPool,
+      perpsMarket: params.market,
+      pendingOperation: pendingOpPda,
+      keeper: params.keeper,
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      priceUpdate: params.priceUpdate,
+      systemProgram: SystemProgram.programId,
+    });
+
+  const { phase1Tx, phase2Tx, phase3Tx } = await buildOpenPositionPhaseTxs(
+    program,
+    operationId,
+    {
+      settlementPool: params.order.marginPool,
+      perpsPool: params.perpsPool,
+      market: params.market,
+      priceUpdate: params.priceUpdate,
+      entryPrice: params.order.limitPrice,
+      lightVerifyParams: params.lightVerifyParams,
+      lightNullifierParams: params.lightNullifierParams,
+      remainingAccounts: params.remainingAccounts,
+    },
+    params.keeper
+  );
+
+  const pendingCommitments: PendingCommitmentData[] = [
+    {
+      pool: params.order.positionPool,
+      commitment: params.order.positionCommitment,
+      stealthEphemeralPubkey: params.order.stealthEphemeralPubkeys[0],
+      encryptedNote: params.order.encryptedNotes[0],
+    },
+  ];
+  if (params.order.changeAmount > 0n) {
+    pendingCommitments.push({
+      pool: params.order.marginPool,
+      commitment: params.order.changeCommitment,
+      stealthEphemeralPubkey: params.order.stealthEphemeralPubkeys[1],
+      encryptedNote: params.order.encryptedNotes[1],
+    });
+  }
+
+  return { tx, phase1Tx, phase2Tx, phase3Tx, operationId, pendingCommitments };
+}
+
+/**
+ * Build close_expired_perp_order instruction (permissionless)
+ */
+export async function buildCloseExpiredPerpOrderWithProgram(
+  program: Program,
+  orderId: Uint8Array,
+  orderPayer: PublicKey
+): Promise<{ tx: any }> {
+  const [orderPda] = derivePerpOrderPda(orderId, program.programId);
+  const tx = await program.methods
+    .closeExpiredPerpOrder(Array.from(orderId))
+    .accountsStrict({
+      perpOrder: orderPda,
+      orderPayer,
+    });
+
+  return { tx };
 }
 
 // =============================================================================
@@ -468,6 +905,12 @@ export interface ClosePositionInstructionParams {
   positionSize: bigint;
   /** Entry price */
   entryPrice: bigint;
+  /** PositionMeta of the position being closed (exact on-chain values) */
+  positionMeta: PositionMetaData;
+  /** Light params for verify position meta active */
+  lightPositionMetaParams: LightVerifyPositionMetaParams;
+  /** Remaining accounts for verify position meta active */
+  positionMetaRemainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
   /** Relayer */
   relayer: PublicKey;
   /** Settlement recipient */
@@ -484,6 +927,10 @@ export interface ClosePositionInstructionParams {
   lightNullifierParams: LightNullifierParams;
   /** Remaining accounts */
   remainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
 }
 
 /**
@@ -495,6 +942,7 @@ export async function buildClosePositionWithProgram(
 ): Promise<{
   tx: any;
   phase1Tx: any;
+  phase1bTx: any;
   phase2Tx: any;
   phase3Tx: any;
   operationId: Uint8Array;
@@ -524,7 +972,12 @@ export async function buildClosePositionWithProgram(
       new BN(params.exitPrice.toString()),
       new BN(params.closeFee.toString()),
       new BN(params.pnlAmount.toString()),
-      params.isProfit
+      params.isProfit,
+      new BN(params.positionMargin.toString()),
+      new BN(params.positionSize.toString()),
+      new BN(params.entryPrice.toString()),
+      new BN(params.positionMeta.entryBorrowFee.toString()),
+      CLIENT_VERSION
     )
     .accountsStrict({
       positionPool: params.positionPool,
@@ -534,6 +987,10 @@ export async function buildClosePositionWithProgram(
       verificationKey: vkPda,
       pendingOperation: pendingOpPda,
       relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
       systemProgram: SystemProgram.programId,
     })
     .preInstructions([
@@ -553,13 +1010,27 @@ export async function buildClosePositionWithProgram(
       ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
     ]);
 
+  // Phase 1b - verify PositionMeta is active (records the entry borrow fee)
+  const { tx: phase1bTx } = await buildVerifyPositionMetaActiveWithProgram(program, {
+    operationId,
+    perpsPool: params.perpsPool,
+    positionPool: params.positionPool,
+    relayer: params.relayer,
+    positionMeta: params.positionMeta,
+    lightParams: params.lightPositionMetaParams,
+    remainingAccounts: params.positionMetaRemainingAccounts,
+  });
+
   // Phase 2 - nullify position in position pool
   const phase2Tx = await program.methods
     .createNullifierAndPending(Array.from(operationId), 0, params.lightNullifierParams)
     .accountsStrict({
       pool: params.positionPool, // Nullify position in position pool
       pendingOperation: pendingOpPda,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
       relayer: params.relayer,
+      poolStats: null,
+      treeRegistry: deriveTreeRegistryPda(program.programId)[0],
     })
     .remainingAccounts(params.remainingAccounts)
     .preInstructions([
@@ -568,12 +1039,7 @@ export async function buildClosePositionWithProgram(
 
   // Phase 3
   const phase3Tx = await program.methods
-    .executeClosePosition(
-      Array.from(operationId),
-      new BN(params.positionMargin.toString()),
-      new BN(params.positionSize.toString()),
-      new BN(params.entryPrice.toString())
-    )
+    .executeClosePosition(Array.from(operationId))
     .accountsStrict({
       settlementPool: params.settlementPool,
       perpsPool: params.perpsPool,
@@ -608,6 +1074,7 @@ export async function buildClosePositionWithProgram(
   return {
     tx: phase0Tx,
     phase1Tx,
+    phase1bTx,
     phase2Tx,
     phase3Tx,
     operationId,
@@ -616,7 +1083,427 @@ export async function buildClosePositionWithProgram(
 }
 
 // =============================================================================
-// Add Perps Liquidity Instructions
+// Position Metadata Instructions
+// =============================================================================
+
+export interface CreatePositionMetaInstructionParams {
+  /** Operation ID of the open position pending operation */
+  operationId: Uint8Array;
+  /** Perps pool */
+  perpsPool: PublicKey;
+  /** Market */
+  market: PublicKey;
+  /** Relayer */
+  relayer: PublicKey;
+  /** Position ID (the SDK uses the position commitment) */
+  positionId: Uint8Array;
+  /** Liquidation price */
+  liquidationPrice: bigint;
+  /** Pre-committed liquidation nullifier hash */
+  nullifierHash: Uint8Array;
+  /** Owner's stealth pubkey */
+  ownerStealthPubkey: Uint8Array;
+  /** Light params for create position meta */
+  lightParams: LightCreatePositionMetaParams;
+  /** Remaining accounts for Light Protocol */
+  remainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+}
+
+/**
+ * Build create_position_meta (open position Phase 4b, after execute_open_position)
+ */
+export async function buildCreatePositionMetaWithProgram(
+  program: Program,
+  params: CreatePositionMetaInstructionParams
+): Promise<{ tx: any }> {
+  const [pendingOpPda] = derivePendingOperationPda(params.operationId, program.programId);
+
+  const tx = await program.methods
+    .createPositionMeta(
+      Array.from(params.operationId),
+      {
+        positionId: Array.from(params.positionId),
+        liquidationPrice: new BN(params.liquidationPrice.toString()),
+        nullifierHash: Array.from(params.nullifierHash),
+        ownerStealthPubkey: Array.from(params.ownerStealthPubkey),
+      },
+      params.lightParams
+    )
+    .accountsStrict({
+      perpsPool: params.perpsPool,
+      perpsMarket: params.market,
+      pendingOperation: pendingOpPda,
+      relayer: params.relayer,
+    })
+    .remainingAccounts(params.remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ]);
+
+  return { tx };
+}
+
+export interface VerifyPositionMetaActiveInstructionParams {
+  /** Operation ID of the close/liquidate/transfer pending operation */
+  operationId: Uint8Array;
+  /** Perps pool */
+  perpsPool: PublicKey;
+  /** Position pool (source of the pinned inclusion threshold) */
+  positionPool: PublicKey;
+  /** Relayer */
+  relayer: PublicKey;
+  /** PositionMeta of the spent position (exact on-chain values) */
+  positionMeta: PositionMetaData;
+  /** Light params for verify position meta active */
+  lightParams: LightVerifyPositionMetaParams;
+  /** Remaining accounts for Light Protocol */
+  remainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+}
+
+/**
+ * Build verify_position_meta_active (Phase 1b of close, liquidate and transfer)
+ */
+export async function buildVerifyPositionMetaActiveWithProgram(
+  program: Program,
+  params: VerifyPositionMetaActiveInstructionParams
+): Promise<{ tx: any }> {
+  const [pendingOpPda] = derivePendingOperationPda(params.operationId, program.programId);
+
+  const tx = await program.methods
+    .verifyPositionMetaActive(
+      Array.from(params.operationId),
+      positionMetaToArg(params.positionMeta),
+      params.lightParams
+    )
+    .accountsStrict({
+      perpsPool: params.perpsPool,
+      positionPool: params.positionPool,
+      pendingOperation: pendingOpPda,
+      relayer: params.relayer,
+    })
+    .remainingAccounts(params.remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ]);
+
+  return { tx };
+}
+
+// =============================================================================
+// Transfer Position Instructions
+// =============================================================================
+
+export interface TransferPositionInstructionParams {
+  /** Position pool (position commitment is read from and re-created in it) */
+  positionPool: PublicKey;
+  /** Perps pool */
+  perpsPool: PublicKey;
+  /** Market */
+  market: PublicKey;
+  /** Market ID (32 bytes) */
+  marketId: Uint8Array;
+  /** ZK proof */
+  proof: Uint8Array;
+  /** Merkle root */
+  merkleRoot: Uint8Array;
+  /** Position commitment being transferred */
+  positionCommitment: Uint8Array;
+  /** Position nullifier */
+  positionNullifier: Uint8Array;
+  /** Re-created position commitment (new owner) */
+  newPositionCommitment: Uint8Array;
+  /** Is long */
+  isLong: boolean;
+  /** Position margin */
+  marginAmount: bigint;
+  /** Position size */
+  positionSize: bigint;
+  /** Leverage (private, needed for the new position note) */
+  leverage: number;
+  /** Entry price */
+  entryPrice: bigint;
+  /** New owner's stealth pubkey X (used in the new commitment) */
+  newStealthPubX: Uint8Array;
+  /** New owner's stealth address (note encryption) */
+  newOwner: StealthAddress;
+  /** New position randomness */
+  newPositionRandomness: Uint8Array;
+  /** Relayer */
+  relayer: PublicKey;
+  /** Light verify params */
+  lightVerifyParams: LightVerifyParams;
+  /** Light nullifier params */
+  lightNullifierParams: LightNullifierParams;
+  /** Remaining accounts */
+  remainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+}
+
+/**
+ * Build transfer position multi-phase instructions
+ *
+ * Moves a position to a new stealth owner with unchanged terms. There is no
+ * execute phase: Phase 4 (create_commitment) follows the nullifier directly.
+ */
+export async function buildTransferPositionWithProgram(
+  program: Program,
+  params: TransferPositionInstructionParams
+): Promise<{
+  tx: any;
+  phase1Tx: any;
+  phase2Tx: any;
+  operationId: Uint8Array;
+  pendingCommitments: PendingCommitmentData[];
+}> {
+  const programId = program.programId;
+
+  const operationId = generateOperationId(
+    params.positionNullifier,
+    params.newPositionCommitment,
+    Date.now()
+  );
+
+  const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
+  const [vkPda] = deriveVerificationKeyPda(PERPS_CIRCUIT_IDS.TRANSFER_POSITION, programId);
+
+  // Phase 0
+  const phase0Tx = await program.methods
+    .createPendingWithProofTransferPosition(
+      Array.from(operationId),
+      Buffer.from(params.proof),
+      Array.from(params.merkleRoot),
+      Array.from(params.positionCommitment),
+      Array.from(params.positionNullifier),
+      Array.from(params.newPositionCommitment),
+      params.isLong,
+      new BN(params.marginAmount.toString()),
+      new BN(params.positionSize.toString()),
+      new BN(params.entryPrice.toString()),
+      CLIENT_VERSION
+    )
+    .accountsStrict({
+      positionPool: params.positionPool,
+      perpsPool: params.perpsPool,
+      perpsMarket: params.market,
+      verificationKey: vkPda,
+      pendingOperation: pendingOpPda,
+      relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
+      systemProgram: SystemProgram.programId,
+    })
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 800_000 }),
+    ]);
+
+  // Phase 1 - verify position exists in position pool
+  const phase1Tx = await program.methods
+    .verifyCommitmentExists(Array.from(operationId), 0, params.lightVerifyParams)
+    .accountsStrict({
+      pool: params.positionPool,
+      pendingOperation: pendingOpPda,
+      relayer: params.relayer,
+    })
+    .remainingAccounts(params.remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ]);
+
+  // Phase 2 - nullify old position in position pool
+  const phase2Tx = await program.methods
+    .createNullifierAndPending(Array.from(operationId), 0, params.lightNullifierParams)
+    .accountsStrict({
+      pool: params.positionPool,
+      pendingOperation: pendingOpPda,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      relayer: params.relayer,
+      poolStats: null,
+      treeRegistry: deriveTreeRegistryPda(program.programId)[0],
+    })
+    .remainingAccounts(params.remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ]);
+
+  // Encrypted position note for the new owner
+  // IMPORTANT: Use field-reduced marketId to match the circuit's commitment computation
+  const fieldReducedMarketId = fieldToBytes(bytesToField(params.marketId));
+  const positionNote = createPositionNote(
+    params.newStealthPubX,
+    fieldReducedMarketId,
+    params.isLong,
+    params.marginAmount,
+    params.positionSize,
+    params.leverage,
+    params.entryPrice,
+    params.newPositionRandomness
+  );
+  const positionEncrypted = encryptPositionNote(positionNote, params.newOwner.stealthPubkey);
+
+  const pendingCommitments: PendingCommitmentData[] = [{
+    pool: params.positionPool,
+    commitment: params.newPositionCommitment,
+    stealthEphemeralPubkey: new Uint8Array([
+      ...params.newOwner.ephemeralPubkey.x,
+      ...params.newOwner.ephemeralPubkey.y,
+    ]),
+    encryptedNote: serializeEncryptedNote(positionEncrypted),
+  }];
+
+  return {
+    tx: phase0Tx,
+    phase1Tx,
+    phase2Tx,
+    operationId,
+    pendingCommitments,
+  };
+}
+
+// =============================================================================
+// Position Valuation
+// =============================================================================
+
+export interface QuotePositionValueParams {
+  /** Perps pool */
+  perpsPool: PublicKey;
+  /** Market */
+  market: PublicKey;
+  /** Pyth price update account for the base token */
+  priceUpdate: PublicKey;
+  /** Position margin */
+  positionMargin: bigint;
+  /** Position size */
+  positionSize: bigint;
+  /** Entry price */
+  entryPrice: bigint;
+  /** Is long */
+  isLong: boolean;
+  /** Borrow fee accumulator snapshot at open (from PositionMeta) */
+  entryBorrowFee: bigint;
+}
+
+/**
+ * Position valuation computed by the program (`PositionValueQuote` return data)
+ */
+export interface PositionValueQuote {
+  /** Oracle price used for the quote */
+  oraclePrice: bigint;
+  /** Margin + capped PnL - accrued borrow fee */
+  markValue: bigint;
+  /** Absolute PnL (profit capped at margin) */
+  pnl: bigint;
+  isProfit: boolean;
+  /** Borrow fee accrued since the position's snapshot */
+  accruedBorrowFee: bigint;
+  /** Price at which the position becomes liquidatable */
+  liquidationPrice: bigint;
+  /** Price move to liquidation as a fraction of the oracle price (0 if liquidatable) */
+  distanceToLiquidationBps: number;
+  isLiquidatable: boolean;
+}
+
+/**
+ * Value a position with the on-chain close/liquidation math (simulated, no signature needed)
+ */
+export async function quotePositionValue(
+  program: Program,
+  params: QuotePositionValueParams
+): Promise<PositionValueQuote> {
+  const quote: any = await program.methods
+    .quotePositionValue(
+      new BN(params.positionMargin.toString()),
+      new BN(params.positionSize.toString()),
+      new BN(params.entryPrice.toString()),
+      params.isLong,
+      new BN(params.entryBorrowFee.toString())
+    )
+    .accountsStrict({
+      perpsPool: params.perpsPool,
+      perpsMarket: params.market,
+      priceUpdate: params.priceUpdate,
+    })
+    .view();
+
+  return {
+    oraclePrice: BigInt(quote.oraclePrice.toString()),
+    markValue: BigInt(quote.markValue.toString()),
+    pnl: BigInt(quote.pnl.toString()),
+    isProfit: quote.isProfit,
+    accruedBorrowFee: BigInt(quote.accruedBorrowFee.toString()),
+    liquidationPrice: BigInt(quote.liquidationPrice.toString()),
+    distanceToLiquidationBps: quote.distanceToLiquidationBps,
+    isLiquidatable: quote.isLiquidatable,
+  };
+}
+
+export interface CheckProfitBoundParams {
+  /** Perps pool */
+  perpsPool: PublicKey;
+  /** Market of the position */
+  market: PublicKey;
+  /** Oracle account */
+  oracle: PublicKey;
+  /** Keeper (the provider wallet, as the simulation signer) */
+  keeper: PublicKey;
+  positionMargin: bigint;
+  positionSize: bigint;
+  entryPrice: bigint;
+  isLong: boolean;
+  /** Price to check the bound at */
+  currentPrice: bigint;
+}
+
+/**
+ * Profit bound check computed by the program (`ProfitBoundCheck` return data)
+ */
+export interface ProfitBoundCheck {
+  /** Whether PnL reached the bound (max profit = margin) */
+  atBound: boolean;
+  isProfit: boolean;
+  /** Absolute PnL at the checked price (uncapped) */
+  pnl: bigint;
+  /** Profit bound (the position's margin) */
+  maxProfit: bigint;
+}
+
+/**
+ * Check whether a position hit its profit bound (simulated)
+ */
+export async function checkProfitBound(
+  program: Program,
+  params: CheckProfitBoundParams
+): Promise<ProfitBoundCheck> {
+  const check: any = await program.methods
+    .checkPerpsProfitBound(
+      new BN(params.positionMargin.toString()),
+      new BN(params.positionSize.toString()),
+      new BN(params.entryPrice.toString()),
+      params.isLong,
+      new BN(params.currentPrice.toString())
+    )
+    .accountsStrict({
+      perpsPool: params.perpsPool,
+      perpsMarket: params.market,
+      oracle: params.oracle,
+      keeper: params.keeper,
+    })
+    .view();
+
+  return {
+    atBound: check.atBound,
+    isProfit: check.isProfit,
+    pnl: BigInt(check.pnl.toString()),
+    maxProfit: BigInt(check.maxProfit.toString()),
+  };
+}
+
+// =============================================================================
+// Add Perps Liquidity Instructions
 // =============================================================================
 
 export interface AddPerpsLiquidityInstructionParams {
@@ -668,6 +1555,10 @@ export interface AddPerpsLiquidityInstructionParams {
   lightNullifierParams: LightNullifierParams;
   /** Remaining accounts */
   remainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
 }
 
 /**
@@ -711,7 +1602,8 @@ export async function buildAddPerpsLiquidityWithProgram(
       params.tokenIndex,
       new BN(params.depositAmount.toString()),
       new BN(params.lpAmountMinted.toString()),
-      new BN(params.feeAmount.toString())
+      new BN(params.feeAmount.toString()),
+      CLIENT_VERSION
     )
     .accountsStrict({
       depositPool: params.depositPool,
@@ -720,6 +1612,10 @@ export async function buildAddPerpsLiquidityWithProgram(
       verificationKey: vkPda,
       pendingOperation: pendingOpPda,
       relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
       systemProgram: SystemProgram.programId,
     })
     .preInstructions([
@@ -745,7 +1641,10 @@ export async function buildAddPerpsLiquidityWithProgram(
     .accountsStrict({
       pool: params.depositPool,
       pendingOperation: pendingOpPda,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
       relayer: params.relayer,
+      poolStats: null,
+      treeRegistry: deriveTreeRegistryPda(program.programId)[0],
     })
     .remainingAccounts(params.remainingAccounts)
     .preInstructions([
@@ -859,19 +1758,229 @@ export interface RemovePerpsLiquidityInstructionParams {
   lightNullifierParams: LightNullifierParams;
   /** Remaining accounts */
   remainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+}
+
+/**
+ * Build remove perps liquidity multi-phase instructions
+ */
+export async function buildRemovePerpsLiquidityWithProgram(
+  program: Program,
+  params: RemovePerpsLiquidityInstructionParams
+): Promise<{
+  tx: any;
+  phase1Tx: any;
+  phase2Tx: any;
+  phase3Tx: any;
+  operationId: Uint8Array;
+  pendingCommitments: PendingCommitmentData[];
+}> {
+  const programId = program.programId;
+
+  const operationId = generateOperationId(params.lpNullifier, params.outputCommitment, Date.now());
+
+  const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
+  const [vkPda] = deriveVerificationKeyPda(PERPS_CIRCUIT_IDS.REMOVE_LIQUIDITY, programId);
+  // LP tokens are tracked in the LP pool (derived from LP mint)
+  const [lpPoolPda] = derivePoolPda(params.lpMint, programId);
+
+  const oraclePricesBN: BN[] = [];
+  for (let i = 0; i < 8; i++) {
+    oraclePricesBN.push(new BN((params.oraclePrices[i] ?? 0n).toString()));
+  }
+
+  // Phase 0
+  const phase0Tx = await program.methods
+    .createPendingWithProofRemovePerpsLiquidity(
+      Array.from(operationId),
+      Buffer.from(params.proof),
+      Array.from(params.merkleRoot),
+      Array.from(params.lpCommitment),
+      Array.from(params.lpNullifier),
+      Array.from(params.outputCommitment),
+      Array.from(params.changeLpCommitment),
+      params.tokenIndex,
+      new BN(params.withdrawAmount.toString()),
+      new BN(params.lpAmountBurned.toString()),
+      new BN(params.feeAmount.toString()),
+      CLIENT_VERSION
+    )
+    .accountsStrict({
+      withdrawalPool: params.withdrawalPool,
+      lpPool: lpPoolPda,
+      perpsPool: params.perpsPool,
+      verificationKey: vkPda,
+      pendingOperation: pendingOpPda,
+      relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
+      systemProgram: SystemProgram.programId,
+    })
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 800_000 }),
+    ]);
+
+  // Phase 1 - Verify LP commitment exists (LP tokens are in LP pool)
+  const phase1Tx = await program.methods
+    .verifyCommitmentExists(Array.from(operationId), 0, params.lightVerifyParams)
+    .accountsStrict({
+      pool: lpPoolPda,
+      pendingOperation: pendingOpPda,
+      relayer: params.relayer,
+    })
+    .remainingAccounts(params.remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ]);
+
+  // Phase 2 - Create nullifier for LP commitment (LP tokens are in LP pool)
+  const phase2Tx = await program.methods
+    .createNullifierAndPending(Array.from(operationId), 0, params.lightNullifierParams)
+    .accountsStrict({
+      pool: lpPoolPda,
+      pendingOperation: pendingOpPda,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      relayer: params.relayer,
+      poolStats: null,
+      treeRegistry: deriveTreeRegistryPda(program.programId)[0],
+    })
+    .remainingAccounts(params.remainingAccounts)
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
+    ]);
+
+  // Phase 3
+  const phase3Tx = await program.methods
+    .executeRemovePerpsLiquidity(Array.from(operationId), oraclePricesBN)
+    .accountsStrict({
+      withdrawalPool: params.withdrawalPool,
+      perpsPool: params.perpsPool,
+      lpMint: params.lpMintAccount,
+      tokenVault: params.tokenVault,
+      pendingOperation: pendingOpPda,
+      relayer: params.relayer,
+      priceUpdate: params.priceUpdate,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .preInstructions([
+      ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 }),
+    ]);
+
+  // Output note
+  const outputNote = {
+    stealthPubX: params.outputRecipient.stealthPubkey.x,
+    tokenMint: params.tokenMint,
+    amount: params.withdrawAmount,
+    randomness: params.outputRandomness,
+  };
+  const outputEncrypted = encryptNote(outputNote as any, params.outputRecipient.stealthPubkey);
+
+  const pendingCommitments: PendingCommitmentData[] = [{
+    pool: params.withdrawalPool,
+    commitment: params.outputCommitment,
+    stealthEphemeralPubkey: new Uint8Array([
+      ...params.outputRecipient.ephemeralPubkey.x,
+      ...params.outputRecipient.ephemeralPubkey.y,
+    ]),
+    encryptedNote: serializeEncryptedNote(outputEncrypted),
+  }];
+
+  // LP change commitment if any
+  if (params.lpChangeAmount > 0n) {
+    const lpChangeNote = createLpNote(
+      params.lpChangeRecipient.stealthPubkey.x,
+      params.perpsPoolId,
+      params.lpChangeAmount,
+      params.lpChangeRandomness
+    );
+    const lpChangeEncrypted = encryptLpNote(lpChangeNote, params.lpChangeRecipient.stealthPubkey);
+
+    pendingCommitments.push({
+      pool: lpPoolPda,  // LP change goes back to LP pool
+      commitment: params.changeLpCommitment,
+      stealthEphemeralPubkey: new Uint8Array([
+        ...params.lpChangeRecipient.ephemeralPubkey.x,
+        ...params.lpChangeRecipient.ephemeralPubkey.y,
+      ]),
+      encryptedNote: serializeEncryptedNote(lpChangeEncrypted),
+    });
+  }
+
+  return {
+    tx: phase0Tx,
+    phase1Tx,
+    phase2Tx,
+    phase3Tx,
+    operationId,
+    pendingCommitments,
+  };
+}
+
+// =============================================================================
+// Convert LP Note Instructions (Pool Migration)
+// =============================================================================
+
+export interface ConvertLpNoteInstructionParams {
+  /** Migrated perps pool */
+  oldPerpsPool: PublicKey;
+  /** Perps pool the LP converts into */
+  newPerpsPool: PublicKey;
+  /** New perps pool ID (32 bytes, for LP note encryption) */
+  newPerpsPoolId: Uint8Array;
+  /** Old pool LP mint */
+  oldLpMint: PublicKey;
+  /** New pool LP mint */
+  newLpMint: PublicKey;
+  /** ZK proof */
+  proof: Uint8Array;
+  /** Merkle root */
+  merkleRoot: Uint8Array;
+  /** Old LP commitment */
+  lpCommitment: Uint8Array;
+  /** Old LP nullifier */
+  lpNullifier: Uint8Array;
+  /** New LP commitment */
+  outputCommitment: Uint8Array;
+  /** Old LP amount (whole note) */
+  lpAmount: bigint;
+  /** New LP amount (lpAmount * newLpSupply / oldLpSupply, rounded down) */
+  convertedAmount: bigint;
+  /** Relayer */
+  relayer: PublicKey;
+  /** New LP recipient */
+  lpRecipient: StealthAddress;
+  /** New LP randomness */
+  lpRandomness: Uint8Array;
+  /** Light verify params */
+  lightVerifyParams: LightVerifyParams;
+  /** Light nullifier params */
+  lightNullifierParams: LightNullifierParams;
+  /** Remaining accounts */
+  remainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
 }
 
 /**
- * Build remove perps liquidity multi-phase instructions
+ * Build convert LP note multi-phase instructions
+ *
+ * Spends an LP note of a migrated pool and commits the new pool's LP at the
+ * migration's conversion rate. There is no Phase 3.
  */
-export async function buildRemovePerpsLiquidityWithProgram(
+export async function buildConvertLpNoteWithProgram(
   program: Program,
-  params: RemovePerpsLiquidityInstructionParams
+  params: ConvertLpNoteInstructionParams
 ): Promise<{
   tx: any;
   phase1Tx: any;
   phase2Tx: any;
-  phase3Tx: any;
   operationId: Uint8Array;
   pendingCommitments: PendingCommitmentData[];
 }> {
@@ -880,48 +1989,47 @@ export async function buildRemovePerpsLiquidityWithProgram(
   const operationId = generateOperationId(params.lpNullifier, params.outputCommitment, Date.now());
 
   const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
-  const [vkPda] = deriveVerificationKeyPda(PERPS_CIRCUIT_IDS.REMOVE_LIQUIDITY, programId);
-  // LP tokens are tracked in the LP pool (derived from LP mint)
-  const [lpPoolPda] = derivePoolPda(params.lpMint, programId);
-
-  const oraclePricesBN: BN[] = [];
-  for (let i = 0; i < 8; i++) {
-    oraclePricesBN.push(new BN((params.oraclePrices[i] ?? 0n).toString()));
-  }
+  const [vkPda] = deriveVerificationKeyPda(PERPS_CIRCUIT_IDS.CONVERT_LP, programId);
+  const [oldLpPoolPda] = derivePoolPda(params.oldLpMint, programId);
+  const [newLpPoolPda] = derivePoolPda(params.newLpMint, programId);
 
   // Phase 0
   const phase0Tx = await program.methods
-    .createPendingWithProofRemovePerpsLiquidity(
+    .createPendingWithProofConvertLpNote(
       Array.from(operationId),
       Buffer.from(params.proof),
       Array.from(params.merkleRoot),
       Array.from(params.lpCommitment),
       Array.from(params.lpNullifier),
       Array.from(params.outputCommitment),
-      Array.from(params.changeLpCommitment),
-      params.tokenIndex,
-      new BN(params.withdrawAmount.toString()),
-      new BN(params.lpAmountBurned.toString()),
-      new BN(params.feeAmount.toString())
+      new BN(params.lpAmount.toString()),
+      new BN(params.convertedAmount.toString()),
+      CLIENT_VERSION
     )
     .accountsStrict({
-      withdrawalPool: params.withdrawalPool,
-      lpPool: lpPoolPda,
-      perpsPool: params.perpsPool,
+      oldLpPool: oldLpPoolPda,
+      newLpPool: newLpPoolPda,
+      oldPool: params.oldPerpsPool,
+      newPool: params.newPerpsPool,
+      migration: derivePerpsPoolMigrationPda(params.oldPerpsPool, programId)[0],
       verificationKey: vkPda,
       pendingOperation: pendingOpPda,
       relayer: params.relayer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
       systemProgram: SystemProgram.programId,
     })
     .preInstructions([
       ComputeBudgetProgram.setComputeUnitLimit({ units: 800_000 }),
     ]);
 
-  // Phase 1 - Verify LP commitment exists (LP tokens are in LP pool)
+  // Phase 1 - Verify old LP commitment exists (old LP pool)
   const phase1Tx = await program.methods
     .verifyCommitmentExists(Array.from(operationId), 0, params.lightVerifyParams)
     .accountsStrict({
-      pool: lpPoolPda,
+      pool: oldLpPoolPda,
       pendingOperation: pendingOpPda,
       relayer: params.relayer,
     })
@@ -930,81 +2038,44 @@ export async function buildRemovePerpsLiquidityWithProgram(
       ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
     ]);
 
-  // Phase 2 - Create nullifier for LP commitment (LP tokens are in LP pool)
+  // Phase 2 - Create nullifier for the old LP commitment
   const phase2Tx = await program.methods
     .createNullifierAndPending(Array.from(operationId), 0, params.lightNullifierParams)
     .accountsStrict({
-      pool: lpPoolPda,
+      pool: oldLpPoolPda,
       pendingOperation: pendingOpPda,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
       relayer: params.relayer,
+      poolStats: null,
+      treeRegistry: deriveTreeRegistryPda(program.programId)[0],
     })
     .remainingAccounts(params.remainingAccounts)
     .preInstructions([
       ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
     ]);
 
-  // Phase 3
-  const phase3Tx = await program.methods
-    .executeRemovePerpsLiquidity(Array.from(operationId), oraclePricesBN)
-    .accountsStrict({
-      withdrawalPool: params.withdrawalPool,
-      perpsPool: params.perpsPool,
-      lpMint: params.lpMintAccount,
-      tokenVault: params.tokenVault,
-      pendingOperation: pendingOpPda,
-      relayer: params.relayer,
-      priceUpdate: params.priceUpdate,
-      tokenProgram: TOKEN_PROGRAM_ID,
-    })
-    .preInstructions([
-      ComputeBudgetProgram.setComputeUnitLimit({ units: 300_000 }),
-    ]);
-
-  // Output note
-  const outputNote = {
-    stealthPubX: params.outputRecipient.stealthPubkey.x,
-    tokenMint: params.tokenMint,
-    amount: params.withdrawAmount,
-    randomness: params.outputRandomness,
-  };
-  const outputEncrypted = encryptNote(outputNote as any, params.outputRecipient.stealthPubkey);
+  const lpNote = createLpNote(
+    params.lpRecipient.stealthPubkey.x,
+    params.newPerpsPoolId,
+    params.convertedAmount,
+    params.lpRandomness
+  );
+  const lpEncrypted = encryptLpNote(lpNote, params.lpRecipient.stealthPubkey);
 
   const pendingCommitments: PendingCommitmentData[] = [{
-    pool: params.withdrawalPool,
+    pool: newLpPoolPda,
     commitment: params.outputCommitment,
     stealthEphemeralPubkey: new Uint8Array([
-      ...params.outputRecipient.ephemeralPubkey.x,
-      ...params.outputRecipient.ephemeralPubkey.y,
+      ...params.lpRecipient.ephemeralPubkey.x,
+      ...params.lpRecipient.ephemeralPubkey.y,
     ]),
-    encryptedNote: serializeEncryptedNote(outputEncrypted),
+    encryptedNote: serializeEncryptedNote(lpEncrypted),
   }];
 
-  // LP change commitment if any
-  if (params.lpChangeAmount > 0n) {
-    const lpChangeNote = createLpNote(
-      params.lpChangeRecipient.stealthPubkey.x,
-      params.perpsPoolId,
-      params.lpChangeAmount,
-      params.lpChangeRandomness
-    );
-    const lpChangeEncrypted = encryptLpNote(lpChangeNote, params.lpChangeRecipient.stealthPubkey);
-
-    pendingCommitments.push({
-      pool: lpPoolPda,  // LP change goes back to LP pool
-      commitment: params.changeLpCommitment,
-      stealthEphemeralPubkey: new Uint8Array([
-        ...params.lpChangeRecipient.ephemeralPubkey.x,
-        ...params.lpChangeRecipient.ephemeralPubkey.y,
-      ]),
-      encryptedNote: serializeEncryptedNote(lpChangeEncrypted),
-    });
-  }
-
   return {
     tx: phase0Tx,
     phase1Tx,
     phase2Tx,
-    phase3Tx,
     operationId,
     pendingCommitments,
   };
@@ -1024,6 +2095,16 @@ export interface InitializePerpsPoolParams {
   liquidationThresholdBps?: number;
   liquidationPenaltyBps?: number;
   baseBorrowRateBps?: number;
+  /** Position fee for resting limit orders (<= positionFeeBps) */
+  makerFeeBps?: number;
+  /** Maximum liquidation urgency bonus on top of the penalty */
+  liquidationBonusBps?: number;
+  /** Fee on rebalance swaps, kept by LPs */
+  rebalanceFeeBps?: number;
+  /** Minimum position size (0 = no minimum) */
+  minPositionSize?: bigint;
+  /** Minimum position margin (0 = no minimum) */
+  minMargin?: bigint;
 }
 
 /**
@@ -1044,6 +2125,11 @@ export async function buildInitializePerpsPoolWithProgram(
     liquidationThresholdBps: params.liquidationThresholdBps ?? 50,
     liquidationPenaltyBps: params.liquidationPenaltyBps ?? 50,
     baseBorrowRateBps: params.baseBorrowRateBps ?? 10,
+    makerFeeBps: params.makerFeeBps ?? 2,
+    liquidationBonusBps: params.liquidationBonusBps ?? 100,
+    rebalanceFeeBps: params.rebalanceFeeBps ?? 10,
+    minPositionSize: new BN((params.minPositionSize ?? 0n).toString()),
+    minMargin: new BN((params.minMargin ?? 0n).toString()),
   };
 
   const tx = await program.methods
@@ -1161,6 +2247,16 @@ export interface UpdatePoolConfigParams {
   baseBorrowRateBps?: number;
   /** Maximum imbalance fee in basis points, undefined to keep current */
   maxImbalanceFeeBps?: number;
+  /** Maker fee for resting limit orders in basis points, undefined to keep current */
+  makerFeeBps?: number;
+  /** Maximum liquidation urgency bonus in basis points, undefined to keep current */
+  liquidationBonusBps?: number;
+  /** Rebalance swap fee in basis points, undefined to keep current */
+  rebalanceFeeBps?: number;
+  /** Minimum position size, undefined to keep current */
+  minPositionSize?: bigint;
+  /** Minimum position margin, undefined to keep current */
+  minMargin?: bigint;
   /** Pool active status (true = active, false = paused), undefined to keep current */
   isActive?: boolean;
 }
@@ -1201,6 +2297,11 @@ export async function buildUpdatePoolConfigWithProgram(
     liquidationPenaltyBps: params.liquidationPenaltyBps ?? null,
     baseBorrowRateBps: params.baseBorrowRateBps ?? null,
     maxImbalanceFeeBps: params.maxImbalanceFeeBps ?? null,
+    makerFeeBps: params.makerFeeBps ?? null,
+    liquidationBonusBps: params.liquidationBonusBps ?? null,
+    rebalanceFeeBps: params.rebalanceFeeBps ?? null,
+    minPositionSize: params.minPositionSize !== undefined ? new BN(params.minPositionSize.toString()) : null,
+    minMargin: params.minMargin !== undefined ? new BN(params.minMargin.toString()) : null,
     isActive: params.isActive ?? null,
   };
 
@@ -1258,6 +2359,64 @@ export async function buildUpdateTokenStatusWithProgram(
   return { tx };
 }
 
+// =============================================================================
+// Token Target Weight Instructions
+// =============================================================================
+
+export interface UpdateTokenTargetWeightParams {
+  perpsPool: PublicKey;
+  authority: PublicKey;
+  /** Token index in the pool (0-7) */
+  tokenIndex: number;
+  /** Target share of pool value in basis points (0 = no target) */
+  targetWeightBps: number;
+}
+
+/**
+ * Build update token target weight instruction
+ *
+ * Target weights drive the liquidity imbalance fee and keeper rebalancing.
+ * The sum across all tokens must not exceed 10000 bps.
+ */
+export async function buildUpdateTokenTargetWeightWithProgram(
+  program: Program,
+  params: UpdateTokenTargetWeightParams
+): Promise<{ tx: any }> {
+  const tx = await program.methods
+    .updatePerpsTokenTargetWeight(params.tokenIndex, params.targetWeightBps)
+    .accountsStrict({
+      perpsPool: params.perpsPool,
+      authority: params.authority,
+    });
+
+  return { tx };
+}
+
+/**
+ * Build initialize borrow fee history instruction
+ *
+ * Creates the pool's hourly borrow fee checkpoint ring buffer. Pass
+ * `recordCheckpoint` to `buildUpdateBorrowFeesWithProgram` to fill it.
+ */
+export async function buildInitializeBorrowFeeHistoryWithProgram(
+  program: Program,
+  perpsPool: PublicKey,
+  authority: PublicKey
+): Promise<{ tx: any }> {
+  const [borrowFeeHistory] = deriveBorrowFeeHistoryPda(perpsPool, program.programId);
+
+  const tx = await program.methods
+    .initializeBorrowFeeHistory()
+    .accountsStrict({
+      perpsPool,
+      borrowFeeHistory,
+      authority,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return { tx };
+}
+
 // =============================================================================
 // Market Status Update Instructions
 // =============================================================================
@@ -1302,28 +2461,244 @@ export async function buildUpdateMarketStatusWithProgram(
   return { tx };
 }
 
+export interface BeginPoolMigrationParams {
+  /** Pool being migrated away from */
+  oldPerpsPool: PublicKey;
+  /** Empty pool with the same authority and tokens */
+  newPerpsPool: PublicKey;
+  authority: PublicKey;
+  payer: PublicKey;
+}
+
+/**
+ * Build begin pool migration instruction
+ *
+ * Freezes both pools. The old pool must have no open positions.
+ */
+export async function buildBeginPoolMigrationWithProgram(
+  program: Program,
+  params: BeginPoolMigrationParams
+): Promise<{ tx: any }> {
+  const tx = await program.methods
+    .beginPoolMigration()
+    .accountsStrict({
+      oldPool: params.oldPerpsPool,
+      newPool: params.newPerpsPool,
+      migration: derivePerpsPoolMigrationPda(params.oldPerpsPool, program.programId)[0],
+      authority: params.authority,
+      payer: params.payer,
+      systemProgram: SystemProgram.programId,
+    });
+
+  return { tx };
+}
+
+export interface MigrateTokenVaultParams {
+  oldPerpsPool: PublicKey;
+  newPerpsPool: PublicKey;
+  authority: PublicKey;
+  /** Token index (same in both pools) */
+  tokenIndex: number;
+  oldVault: PublicKey;
+  newVault: PublicKey;
+}
+
+/**
+ * Build migrate token vault instruction
+ *
+ * Moves one token's vault balance to the new pool. Run once per token.
+ */
+export async function buildMigrateTokenVaultWithProgram(
+  program: Program,
+  params: MigrateTokenVaultParams
+): Promise<{ tx: any }> {
+  const tx = await program.methods
+    .migrateTokenVault(params.tokenIndex)
+    .accountsStrict({
+      oldPool: params.oldPerpsPool,
+      newPool: params.newPerpsPool,
+      migration: derivePerpsPoolMigrationPda(params.oldPerpsPool, program.programId)[0],
+      oldVault: params.oldVault,
+      newVault: params.newVault,
+      authority: params.authority,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+  return { tx };
+}
+
+export interface FinalizePoolMigrationParams {
+  oldPerpsPool: PublicKey;
+  newPerpsPool: PublicKey;
+  authority: PublicKey;
+  /** New LP issued for the old pool's whole LP supply (sets the conversion rate) */
+  newLpSupply: bigint;
+}
+
+/**
+ * Build finalize pool migration instruction
+ *
+ * Registers the LP conversion rate and unfreezes the new pool.
+ */
+export async function buildFinalizePoolMigrationWithProgram(
+  program: Program,
+  params: FinalizePoolMigrationParams
+): Promise<{ tx: any }> {
+  const tx = await program.methods
+    .finalizePoolMigration(new BN(params.newLpSupply.toString()))
+    .accountsStrict({
+      oldPool: params.oldPerpsPool,
+      newPool: params.newPerpsPool,
+      migration: derivePerpsPoolMigrationPda(params.oldPerpsPool, program.programId)[0],
+      authority: params.authority,
+    });
+
+  return { tx };
+}
+
+export interface DelistMarketParams {
+  perpsPool: PublicKey;
+  market: PublicKey;
+  authority: PublicKey;
+  /** Seconds before the market can be force-settled */
+  gracePeriodSeconds: number;
+}
+
+/**
+ * Build delist market instruction
+ *
+ * Moves the market to settle-only mode: no new positions or order fills,
+ * while closes and liquidations continue. Cannot be undone.
+ */
+export async function buildDelistMarketWithProgram(
+  program: Program,
+  params: DelistMarketParams
+): Promise<{ tx: any }> {
+  const tx = await program.methods
+    .delistMarket(new BN(params.gracePeriodSeconds))
+    .accountsStrict({
+      perpsPool: params.perpsPool,
+      perpsMarket: params.market,
+      authority: params.authority,
+    });
+
+  return { tx };
+}
+
 // =============================================================================
 // Keeper Instructions
 // =============================================================================
 
 /**
  * Build update borrow fees instruction
+ *
+ * With `recordCheckpoint`, the pool's borrow fee history (which must exist)
+ * records an hourly checkpoint of every token's accumulator.
  */
 export async function buildUpdateBorrowFeesWithProgram(
   program: Program,
   perpsPool: PublicKey,
-  keeper: PublicKey
+  keeper: PublicKey,
+  recordCheckpoint: boolean = false
 ): Promise<{ tx: any }> {
   const tx = await program.methods
     .updatePerpsBorrowFees()
     .accountsStrict({
       perpsPool,
+      borrowFeeHistory: recordCheckpoint
+        ? deriveBorrowFeeHistoryPda(perpsPool, program.programId)[0]
+        : null,
       keeper,
     });
 
   return { tx };
 }
 
+export interface RebalancePoolParams {
+  perpsPool: PublicKey;
+  /** Pool authority */
+  keeper: PublicKey;
+  /** Index of the token the keeper deposits */
+  tokenInIndex: number;
+  /** Index of the token the keeper receives */
+  tokenOutIndex: number;
+  tokenInVault: PublicKey;
+  tokenOutVault: PublicKey;
+  keeperTokenIn: PublicKey;
+  keeperTokenOut: PublicKey;
+  /** Pyth price updates for every active pool token, in token index order */
+  priceUpdates: PublicKey[];
+  amountIn: bigint;
+  /** Minimum token_out received (slippage bound) */
+  minAmountOut: bigint;
+}
+
+/**
+ * Build rebalance pool instruction
+ *
+ * Swaps token_in for token_out at oracle value, less the pool's rebalance
+ * fee (kept by LPs). Only the pool authority may rebalance, and the swap is
+ * rejected unless it moves pool weights closer to their targets. All prices
+ * are read on-chain from the Pyth price updates.
+ */
+export async function buildRebalancePoolWithProgram(
+  program: Program,
+  params: RebalancePoolParams
+): Promise<{ tx: any }> {
+  const tx = await program.methods
+    .rebalancePerpsPool(
+      params.tokenInIndex,
+      params.tokenOutIndex,
+      new BN(params.amountIn.toString()),
+      new BN(params.minAmountOut.toString())
+    )
+    .accountsStrict({
+      perpsPool: params.perpsPool,
+      tokenInVault: params.tokenInVault,
+      tokenOutVault: params.tokenOutVault,
+      keeperTokenIn: params.keeperTokenIn,
+      keeperTokenOut: params.keeperTokenOut,
+      keeper: params.keeper,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(
+      params.priceUpdates.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+    );
+
+  return { tx };
+}
+
+export interface ForceSettleMarketParams {
+  perpsPool: PublicKey;
+  market: PublicKey;
+  keeper: PublicKey;
+  /** Pyth price update for the market's base token */
+  priceUpdate: PublicKey;
+}
+
+/**
+ * Build force settle market instruction
+ *
+ * Once a delisted market's grace period has passed, records the oracle
+ * price as its settlement price. Remaining positions then close and
+ * liquidate at that price.
+ */
+export async function buildForceSettleMarketWithProgram(
+  program: Program,
+  params: ForceSettleMarketParams
+): Promise<{ tx: any }> {
+  const tx = await program.methods
+    .forceSettleMarket()
+    .accountsStrict({
+      perpsPool: params.perpsPool,
+      perpsMarket: params.market,
+      priceUpdate: params.priceUpdate,
+      keeper: params.keeper,
+    });
+
+  return { tx };
+}
+
 // =============================================================================
 // Liquidation Instructions (Keeper)
 // =============================================================================
@@ -1331,6 +2706,8 @@ export async function buildUpdateBorrowFeesWithProgram(
 export interface LiquidatePositionInstructionParams {
   /** Settlement pool (where position margin comes from) */
   settlementPool: PublicKey;
+  /** Position pool (source of the pinned inclusion threshold) */
+  positionPool: PublicKey;
   /** Perps pool */
   perpsPool: PublicKey;
   /** Market */
@@ -1361,6 +2738,14 @@ export interface LiquidatePositionInstructionParams {
   positionSize: bigint;
   /** Is long position */
   isLong: boolean;
+  /** Position entry price (prices the urgency bonus in the reward check) */
+  entryPrice: bigint;
+  /** PositionMeta of the position being liquidated (exact on-chain values) */
+  positionMeta: PositionMetaData;
+  /** Light params for verify position meta active */
+  lightPositionMetaParams: LightVerifyPositionMetaParams;
+  /** Remaining accounts for verify position meta active */
+  positionMetaRemainingAccounts: { pubkey: PublicKey; isSigner: boolean; isWritable: boolean }[];
   /** Keeper/liquidator */
   keeper: PublicKey;
   /** Owner stealth address (for remainder commitment) */
@@ -1396,6 +2781,7 @@ export async function buildLiquidatePositionWithProgram(
 ): Promise<{
   tx: any;
   phase1Tx: any;
+  phase1bTx: any;
   phase2Tx: any;
   phase3Tx: any;
   operationId: Uint8Array;
@@ -1424,7 +2810,12 @@ export async function buildLiquidatePositionWithProgram(
       Array.from(params.liquidatorCommitment),
       new BN(params.currentPrice.toString()),
       new BN(params.liquidatorReward.toString()),
-      new BN(params.ownerRemainder.toString())
+      new BN(params.ownerRemainder.toString()),
+      params.isLong,
+      new BN(params.positionMargin.toString()),
+      new BN(params.positionSize.toString()),
+      new BN(params.entryPrice.toString()),
+      CLIENT_VERSION
     )
     .accountsStrict({
       settlementPool: params.settlementPool,
@@ -1433,6 +2824,8 @@ export async function buildLiquidatePositionWithProgram(
       verificationKey: vkPda,
       pendingOperation: pendingOpPda,
       keeper: params.keeper,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
       systemProgram: SystemProgram.programId,
     })
     .preInstructions([
@@ -1452,13 +2845,27 @@ export async function buildLiquidatePositionWithProgram(
       ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 }),
     ]);
 
+  // Phase 1b: Verify PositionMeta is active (records the entry borrow fee)
+  const { tx: phase1bTx } = await buildVerifyPositionMetaActiveWithProgram(program, {
+    operationId,
+    perpsPool: params.perpsPool,
+    positionPool: params.positionPool,
+    relayer: params.keeper,
+    positionMeta: params.positionMeta,
+    lightParams: params.lightPositionMetaParams,
+    remainingAccounts: params.positionMetaRemainingAccounts,
+  });
+
   // Phase 2: Create nullifier for position
   const phase2Tx = await program.methods
     .createNullifierAndPending(Array.from(operationId), 0, params.lightNullifierParams)
     .accountsStrict({
       pool: params.settlementPool,
       pendingOperation: pendingOpPda,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
       relayer: params.keeper,
+      poolStats: null,
+      treeRegistry: deriveTreeRegistryPda(program.programId)[0],
     })
     .remainingAccounts(params.remainingAccounts)
     .preInstructions([
@@ -1467,12 +2874,7 @@ export async function buildLiquidatePositionWithProgram(
 
   // Phase 3: Execute liquidation
   const phase3Tx = await program.methods
-    .executeLiquidate(
-      Array.from(operationId),
-      new BN(params.positionMargin.toString()),
-      new BN(params.positionSize.toString()),
-      params.isLong
-    )
+    .executeLiquidate(Array.from(operationId))
     .accountsStrict({
       settlementPool: params.settlementPool,
       perpsPool: params.perpsPool,
@@ -1533,6 +2935,7 @@ export async function buildLiquidatePositionWithProgram(
   return {
     tx: phase0Tx,
     phase1Tx,
+    phase1bTx,
     phase2Tx,
     phase3Tx,
     operationId,
@@ -1602,19 +3005,36 @@ export function shouldLiquidate(
 /**
  * Calculate liquidation amounts
  *
+ * Mirrors the on-chain reward: the penalty plus an urgency bonus that grows
+ * linearly from 0 at the liquidation threshold to `liquidationBonusBps` at
+ * zero effective margin.
+ *
  * @returns Owner remainder and liquidator reward
  */
 export function calculateLiquidationAmounts(
   margin: bigint,
   pnl: bigint,
   isProfit: boolean,
-  liquidationPenaltyBps: number
+  liquidationPenaltyBps: number,
+  liquidationThresholdBps: number = 0,
+  liquidationBonusBps: number = 0
 ): { ownerRemainder: bigint; liquidatorReward: bigint } {
-  // Effective margin after PnL
-  const effectiveMargin = isProfit ? margin + pnl : margin > pnl ? margin - pnl : 0n;
-
-  // Liquidator reward = penalty % of original margin
-  const liquidatorReward = margin * BigInt(liquidationPenaltyBps) / 10000n;
+  // Effective margin after PnL (profit bounded by margin)
+  const effectiveMargin = isProfit
+    ? margin + (pnl < margin ? pnl : margin)
+    : margin > pnl ? margin - pnl : 0n;
+
+  // Urgency: how far effective margin has fallen below the threshold (bps)
+  let threshold = margin * BigInt(liquidationThresholdBps) / 10000n;
+  if (threshold === 0n) threshold = 1n;
+  const shortfall = threshold > effectiveMargin ? threshold - effectiveMargin : 0n;
+  const depthBps = shortfall * 10000n / threshold;
+  const bonusBps = BigInt(liquidationBonusBps) * depthBps / 10000n;
+
+  // Liquidator reward = (penalty + bonus) % of original margin
+  const rewardBps = BigInt(liquidationPenaltyBps) + bonusBps;
+  const reward = margin * rewardBps / 10000n;
+  const liquidatorReward = reward < margin ? reward : margin;
 
   // Owner gets remainder after penalty
   const ownerRemainder = effectiveMargin > liquidatorReward ? effectiveMargin - liquidatorReward : 0n;
@@ -59,16 +59,28 @@ export interface PerpsPoolState {
   maxLeverage: number;
   /** Position fee in basis points */
   positionFeeBps: number;
+  /** Position fee for resting limit orders in basis points */
+  makerFeeBps: number;
   /** Max utilization in basis points (e.g., 8000 for 80%) */
   maxUtilizationBps: number;
   /** Liquidation threshold in basis points */
   liquidationThresholdBps: number;
   /** Liquidation penalty in basis points */
   liquidationPenaltyBps: number;
+  /** Maximum liquidation urgency bonus in basis points */
+  liquidationBonusBps: number;
+  /** Fee on rebalance swaps in basis points (kept by LPs) */
+  rebalanceFeeBps: number;
+  /** Minimum position size (0 = no minimum) */
+  minPositionSize: bigint;
+  /** Minimum position margin (0 = no minimum) */
+  minMargin: bigint;
   /** Base borrow rate in basis points (per hour) */
   baseBorrowRateBps: number;
   /** Is pool active */
   isActive: boolean;
+  /** Frozen by a pool migration */
+  isMigrating: boolean;
   /** PDA bump */
   bump: number;
 }
@@ -93,6 +105,26 @@ export interface PerpsMarketState {
   isActive: boolean;
   /** PDA bump */
   bump: number;
+  /** Listing status */
+  status: PerpsMarketStatus;
+  /** When the market was delisted (0 = listed) */
+  delistedAt: bigint;
+  /** Earliest time the market can be force-settled */
+  settleAfter: bigint;
+  /** Final price once Settled (1e6 scale) */
+  settlementPrice: bigint;
+}
+
+/**
+ * Market listing status
+ *
+ * SettleOnly markets take no new positions; Settled markets close and
+ * liquidate at `settlementPrice` instead of the oracle.
+ */
+export enum PerpsMarketStatus {
+  Active = 0,
+  SettleOnly = 1,
+  Settled = 2,
 }
 
 // =============================================================================
@@ -292,6 +324,8 @@ export interface ClosePositionProofResult {
   pnlAmount: bigint;
   isProfit: boolean;
   closeFee: bigint;
+  /** Borrow fee deducted from the settlement (computed on-chain in Phase 0) */
+  borrowFee: bigint;
 }
 
 /** Add perps liquidity proof result */
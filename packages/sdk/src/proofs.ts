@@ -41,6 +41,9 @@ import {
   clearCircomCache,
   type CircomArtifacts,
 } from './snarkjs-prover';
+import type { CircomInputs, Prover } from './prover';
+import { computeSwapCommitment } from './instructions/swap';
+import { computePositionTermsHash } from './perps/instructions';
 
 // BN254 field modulus for Y-coordinate negation
 const BN254_FIELD_MODULUS = BigInt('21888242871839275222246405745257275088696311157297823662689037894645226208583');
@@ -72,21 +75,33 @@ interface NodeProverConfig {
  */
 const CIRCUIT_FILE_MAP: Record<string, string> = {
   'transfer/1x2': 'transfer_1x2',
+  'transfer/1x2_denom': 'transfer_1x2_denom',
+  'transfer/1x2_nft': 'transfer_1x2_nft',
+  'transfer/1x2_invoke': 'transfer_1x2_invoke',
   'consolidate/3x1': 'consolidate_3x1',
   'adapter/1x1': 'adapter_1x1',
   'adapter/1x2': 'adapter_1x2',
   'market/order_create': 'market_order_create',
   'market/order_fill': 'market_order_fill',
   'market/order_cancel': 'market_order_cancel',
+  'market/order_modify': 'market_order_modify',
+  'market/escrow_yield_opt_in': 'market_escrow_yield_opt_in',
+  'market/escrow_yield_claim': 'market_escrow_yield_claim',
+  'emissions/claim_rewards': 'claim_rewards',
   'swap/add_liquidity': 'swap_add_liquidity',
   'swap/remove_liquidity': 'swap_remove_liquidity',
   'swap/swap': 'swap_swap',
+  'swap/swap_sealed': 'swap_sealed',
+  'swap/swap_committed': 'swap_committed',
+  'swap/fee_rebate_claim': 'fee_rebate_claim',
   // Perps circuits
   'perps/open_position': 'open_position',
+  'perps/open_position_sealed': 'open_position_sealed',
   'perps/close_position': 'close_position',
   'perps/add_liquidity': 'add_liquidity',
   'perps/remove_liquidity': 'remove_liquidity',
   'perps/liquidate': 'liquidate',
+  'perps/transfer_position': 'transfer_position',
 };
 
 /**
@@ -94,21 +109,33 @@ const CIRCUIT_FILE_MAP: Record<string, string> = {
  */
 const CIRCUIT_DIR_MAP: Record<string, string> = {
   'transfer/1x2': 'transfer/1x2',
+  'transfer/1x2_denom': 'transfer/1x2_denom',
+  'transfer/1x2_nft': 'transfer/1x2_nft',
+  'transfer/1x2_invoke': 'transfer/1x2_invoke',
   'consolidate/3x1': 'consolidate/3x1',
   'adapter/1x1': 'adapter/1x1',
   'adapter/1x2': 'adapter/1x2',
   'market/order_create': 'market/order_create',
   'market/order_fill': 'market/order_fill',
   'market/order_cancel': 'market/order_cancel',
+  'market/order_modify': 'market/order_modify',
+  'market/escrow_yield_opt_in': 'market/escrow_yield_opt_in',
+  'market/escrow_yield_claim': 'market/escrow_yield_claim',
+  'emissions/claim_rewards': 'emissions/claim_rewards',
   'swap/add_liquidity': 'swap/add_liquidity',
   'swap/remove_liquidity': 'swap/remove_liquidity',
   'swap/swap': 'swap/swap',
+  'swap/swap_sealed': 'swap/swap_sealed',
+  'swap/swap_committed': 'swap/swap_committed',
+  'swap/fee_rebate_claim': 'swap/fee_rebate_claim',
   // Perps circuits
   'perps/open_position': 'perps/open_position',
+  'perps/open_position_sealed': 'perps/open_position_sealed',
   'perps/close_position': 'perps/close_position',
   'perps/add_liquidity': 'perps/add_liquidity',
   'perps/remove_liquidity': 'perps/remove_liquidity',
   'perps/liquidate': 'perps/liquidate',
+  'perps/transfer_position': 'perps/transfer_position',
 };
 
 /**
@@ -118,10 +145,32 @@ export class ProofGenerator {
   private circuits: Map<string, CircuitArtifacts> = new Map();
   private baseUrl: string;
   private nodeConfig?: NodeProverConfig;
+  private prover?: Prover;
 
-  constructor(config?: { baseUrl?: string; nodeConfig?: NodeProverConfig }) {
+  constructor(config?: { baseUrl?: string; nodeConfig?: NodeProverConfig; prover?: Prover }) {
     this.baseUrl = config?.baseUrl ?? '/circuits';
     this.nodeConfig = config?.nodeConfig;
+    this.prover = config?.prover;
+  }
+
+  /**
+   * Set the proving backend (undefined = prove locally)
+   *
+   * Witness construction always happens here; only the Groth16 proving step
+   * is delegated. Use `localProver()` as the local half of a HybridProver.
+   */
+  setProver(prover: Prover | undefined): void {
+    this.prover = prover;
+  }
+
+  /**
+   * Local snarkjs prover backed by this generator's circuit artifacts
+   */
+  localProver(): Prover {
+    return {
+      kind: 'local',
+      prove: (circuitName, inputs) => this.proveCircomInputs(circuitName, inputs),
+    };
   }
 
   /**
@@ -151,12 +200,18 @@ export class ProofGenerator {
   async initialize(circuitNames?: string[]): Promise<void> {
     const circuits = circuitNames ?? [
       'transfer/1x2',
+      'transfer/1x2_denom',
+      'transfer/1x2_nft',
+      'transfer/1x2_invoke',
       'consolidate/3x1',
       'adapter/1x1',
       'adapter/1x2',
       'market/order_create',
       'market/order_fill',
       'market/order_cancel',
+      'market/order_modify',
+      'market/escrow_yield_opt_in',
+      'market/escrow_yield_claim',
       'swap/add_liquidity',
       'swap/remove_liquidity',
       'swap/swap',
@@ -275,21 +330,29 @@ export class ProofGenerator {
     // Check if it's a known Circom circuit (will be auto-loaded)
     const knownCircuits = [
       'transfer/1x2',
+      'transfer/1x2_denom',
+      'transfer/1x2_nft',
+      'transfer/1x2_invoke',
       'consolidate/3x1',
       'adapter/1x1',
       'adapter/1x2',
       'market/order_create',
       'market/order_fill',
       'market/order_cancel',
+      'market/order_modify',
+      'market/escrow_yield_opt_in',
+      'market/escrow_yield_claim',
       'swap/add_liquidity',
       'swap/remove_liquidity',
       'swap/swap',
       // Perps circuits
       'perps/open_position',
+      'perps/open_position_sealed',
       'perps/close_position',
       'perps/add_liquidity',
       'perps/remove_liquidity',
       'perps/liquidate',
+      'perps/transfer_position',
     ];
 
     return knownCircuits.includes(name);
@@ -303,7 +366,14 @@ export class ProofGenerator {
     keypair: Keypair
   ): Promise<Uint8Array> {
     // Always use transfer_1x2 - consolidate notes first if multiple inputs needed
-    const circuitName = 'transfer/1x2';
+    // Denomination and NFT pools, and bound unshields, prove with their own variants
+    const circuitName = params.denominations
+      ? 'transfer/1x2_denom'
+      : params.nftMetadataHash
+        ? 'transfer/1x2_nft'
+        : params.callHash
+          ? 'transfer/1x2_invoke'
+          : 'transfer/1x2';
 
     if (!this.hasCircuit(circuitName)) {
       throw new Error(`Circuit not loaded: ${circuitName}`);
@@ -434,8 +504,14 @@ export class ProofGenerator {
     changeCommitment: Uint8Array;
     outRandomness: Uint8Array;
     changeRandomness: Uint8Array;
+    /** Sealed and committed swaps: hash of the terms bound by the proof */
+    termsHash?: Uint8Array;
   }> {
-    const circuitName = 'swap/swap';
+    const circuitName = params.termsSalt
+      ? 'swap/swap_sealed'
+      : params.commitSalt
+      ? 'swap/swap_committed'
+      : 'swap/swap';
 
     if (!this.hasCircuit(circuitName)) {
       throw new Error(`Circuit not loaded: ${circuitName}`);
@@ -515,6 +591,8 @@ export class ProofGenerator {
       out_commitment: fieldToHex(outCommitment),
       change_commitment: fieldToHex(changeCommitment),
       min_output: params.minOutput.toString(),
+      // Swap volume record credited in Phase 3 (0 = none)
+      volume_tag: params.swapVolume ? fieldToHex(params.swapVolume.volumeTag) : '0',
 
       // Private inputs
       in_stealth_pub_x: fieldToHex(params.input.stealthPubX),
@@ -545,6 +623,24 @@ export class ProofGenerator {
       change_randomness: fieldToHex(changeRandomness),
     };
 
+    // Sealed / committed swaps: bind the salted terms hash (swap_sealed
+    // terms_hash / swap_committed swap_commitment public input)
+    let termsHash: Uint8Array | undefined;
+    const termsSalt = params.termsSalt ?? params.commitSalt;
+    if (termsSalt) {
+      termsHash = computeSwapCommitment(
+        params.swapAmount,
+        params.outputAmount,
+        params.minOutput,
+        params.swapDirection === 'aToB',
+        termsSalt
+      );
+      Object.assign(witnessInputs, {
+        [params.termsSalt ? 'terms_hash' : 'swap_commitment']: fieldToHex(termsHash),
+        terms_salt: fieldToHex(termsSalt),
+      });
+    }
+
     const proof = await this.prove(circuitName, witnessInputs);
 
     // Return proof along with public outputs so caller can use SAME values
@@ -556,6 +652,7 @@ export class ProofGenerator {
       changeCommitment,
       outRandomness,
       changeRandomness,
+      termsHash,
     };
   }
 
@@ -828,6 +925,7 @@ export class ProofGenerator {
       out_b_commitment: fieldToHex(outputBCommitment),
       old_state_hash: fieldToHex(oldStateHash),
       new_state_hash: fieldToHex(newStateHash),
+      lp_input_commitment: fieldToHex(lpCommitment),
 
       // Private inputs - LP token
       lp_stealth_pub_x: fieldToHex(params.lpInput.stealthPubX),
@@ -1125,6 +1223,10 @@ export class ProofGenerator {
    * Returns 256-byte proof formatted for Solana's alt_bn128 verifier
    */
   private async prove(circuitName: string, inputs: Record<string, any>): Promise<Uint8Array> {
+    if (this.prover) {
+      return this.prover.prove(circuitName, this.convertToCircomInputs(inputs));
+    }
+
     const artifacts = this.circuits.get(circuitName);
 
     // For Circom circuits, artifacts are auto-loaded on-demand in proveViaWasm()
@@ -1189,6 +1291,14 @@ export class ProofGenerator {
     inputs: Record<string, any>,
     _artifacts: CircuitArtifacts
   ): Promise<Uint8Array> {
+    // Convert inputs to circom format (string field elements)
+    return this.proveCircomInputs(circuitName, this.convertToCircomInputs(inputs));
+  }
+
+  /**
+   * Prove already-converted circom inputs with snarkjs
+   */
+  private async proveCircomInputs(circuitName: string, circomInputs: CircomInputs): Promise<Uint8Array> {
     // Map circuit name to circom file paths (wasm and zkey have different structures)
     const { wasmPath, zkeyPath } = this.getCircomFilePaths(circuitName);
     // Add cache-busting timestamp (v2 = circuit update on Jan 15, 2026)
@@ -1207,9 +1317,6 @@ export class ProofGenerator {
       this.circomArtifacts.set(circuitName, artifacts);
     }
 
-    // Convert inputs to circom format (string field elements)
-    const circomInputs = this.convertToCircomInputs(inputs);
-
     // Generate proof using snarkjs - already formatted for Solana with A negated
     const proofBytes = await generateSnarkjsProof(artifacts, circomInputs);
 
@@ -1230,6 +1337,9 @@ export class ProofGenerator {
     const mapping: Record<string, { wasmPath: string; zkeyPath: string }> = {
       // Transfer circuits
       'transfer/1x2': { wasmPath: 'transfer_1x2_js/transfer_1x2.wasm', zkeyPath: 'transfer_1x2_final.zkey' },
+      'transfer/1x2_denom': { wasmPath: 'transfer_1x2_denom_js/transfer_1x2_denom.wasm', zkeyPath: 'transfer_1x2_denom_final.zkey' },
+      'transfer/1x2_nft': { wasmPath: 'transfer_1x2_nft_js/transfer_1x2_nft.wasm', zkeyPath: 'transfer_1x2_nft_final.zkey' },
+      'transfer/1x2_invoke': { wasmPath: 'transfer_1x2_invoke_js/transfer_1x2_invoke.wasm', zkeyPath: 'transfer_1x2_invoke_final.zkey' },
       // Consolidation circuits
       'consolidate/3x1': { wasmPath: 'consolidate_3x1/consolidate_3x1_js/consolidate_3x1.wasm', zkeyPath: 'consolidate_3x1/consolidate_3x1_final.zkey' },
       // Swap/AMM circuits
@@ -1238,10 +1348,12 @@ export class ProofGenerator {
       'swap/remove_liquidity': { wasmPath: 'remove_liquidity_js/remove_liquidity.wasm', zkeyPath: 'remove_liquidity_final.zkey' },
       // Perps circuits
       'perps/open_position': { wasmPath: 'perps/open_position_js/open_position.wasm', zkeyPath: 'perps/open_position_final.zkey' },
+      'perps/open_position_sealed': { wasmPath: 'perps/open_position_sealed_js/open_position_sealed.wasm', zkeyPath: 'perps/open_position_sealed_final.zkey' },
       'perps/close_position': { wasmPath: 'perps/close_position_js/close_position.wasm', zkeyPath: 'perps/close_position_final.zkey' },
       'perps/add_liquidity': { wasmPath: 'perps/add_liquidity_js/add_liquidity.wasm', zkeyPath: 'perps/add_liquidity_final.zkey' },
       'perps/remove_liquidity': { wasmPath: 'perps/remove_liquidity_js/remove_liquidity.wasm', zkeyPath: 'perps/remove_liquidity_final.zkey' },
       'perps/liquidate': { wasmPath: 'perps/liquidate_js/liquidate.wasm', zkeyPath: 'perps/liquidate_final.zkey' },
+      'perps/transfer_position': { wasmPath: 'perps/transfer_position_js/transfer_position.wasm', zkeyPath: 'perps/transfer_position_final.zkey' },
       // Voting circuits
       'voting/vote_snapshot': { wasmPath: 'voting/vote_snapshot_js/vote_snapshot.wasm', zkeyPath: 'voting/vote_snapshot_final.zkey' },
       'voting/change_vote_snapshot': { wasmPath: 'voting/change_vote_snapshot_js/change_vote_snapshot.wasm', zkeyPath: 'voting/change_vote_snapshot_final.zkey' },
@@ -1455,6 +1567,21 @@ export class ProofGenerator {
       out_stealth_pub_x_2: fieldToHex(out2StealthPubX),
       out_amount_2: out2Amount.toString(),
       out_randomness_2: fieldToHex(out2Randomness),
+
+      // Denomination pools only (transfer_1x2_denom)
+      ...(params.denominations && {
+        denominations: params.denominations.map(d => d.toString()),
+      }),
+
+      // NFT pools only (transfer_1x2_nft)
+      ...(params.nftMetadataHash && {
+        nft_metadata_hash: fieldToHex(params.nftMetadataHash),
+      }),
+
+      // Bound unshields only (transfer_1x2_invoke)
+      ...(params.callHash && {
+        call_hash: fieldToHex(params.callHash),
+      }),
     };
   }
 
@@ -1504,6 +1631,8 @@ export class ProofGenerator {
       merklePath: Uint8Array[];
       /** Merkle path indices */
       merkleIndices: number[];
+      /** Salt of a sealed open (proves with open_position_sealed, binding the terms hash) */
+      termsSalt?: Uint8Array;
     },
     keypair: Keypair
   ): Promise<{
@@ -1514,8 +1643,10 @@ export class ProofGenerator {
     changeCommitment: Uint8Array;
     changeRandomness: Uint8Array;
     changeAmount: bigint;
+    /** Sealed opens: hash of the terms bound by the proof */
+    termsHash?: Uint8Array;
   }> {
-    const circuitName = 'perps/open_position';
+    const circuitName = params.termsSalt ? 'perps/open_position_sealed' : 'perps/open_position';
 
     if (!this.hasCircuit(circuitName)) {
       throw new Error(`${circuitName} circuit not loaded. Circuits need to be compiled first.`);
@@ -1659,6 +1790,21 @@ export class ProofGenerator {
       change_randomness: fieldToHex(changeRandomness),
     };
 
+    // Sealed opens: margin, leverage and fee become private, bound by terms_hash
+    let termsHash: Uint8Array | undefined;
+    if (params.termsSalt) {
+      termsHash = computePositionTermsHash(
+        params.marginAmount,
+        params.leverage,
+        params.positionFee,
+        params.termsSalt
+      );
+      Object.assign(witnessInputs, {
+        terms_hash: fieldToHex(termsHash),
+        terms_salt: fieldToHex(params.termsSalt),
+      });
+    }
+
     console.log('[OpenPosition] Public inputs for circuit:');
     console.log('  merkle_root:', witnessInputs.merkle_root);
     console.log('  nullifier:', witnessInputs.nullifier);
@@ -1677,6 +1823,7 @@ export class ProofGenerator {
       changeCommitment,
       changeRandomness,
       changeAmount,
+      termsHash,
     };
   }
 
@@ -1714,6 +1861,8 @@ export class ProofGenerator {
       isProfit: boolean;
       /** Close fee */
       closeFee: bigint;
+      /** Accrued borrow fee (must equal the one Phase 0 computes, see calculateCloseBorrowFee) */
+      borrowFee: bigint;
       /** Settlement recipient stealth address */
       settlementRecipient: { stealthPubkey: { x: Uint8Array } };
       /** Token mint for settlement */
@@ -1767,14 +1916,14 @@ export class ProofGenerator {
     );
 
     // Calculate settlement amount
-    // If profit: settlement = margin + min(pnl, margin) - closeFee
-    // If loss: settlement = margin - pnl - closeFee
+    // If profit: settlement = margin + min(pnl, margin) - closeFee - borrowFee
+    // If loss: settlement = margin - pnl - closeFee - borrowFee
     let settlementAmount: bigint;
     if (params.isProfit) {
       const cappedPnl = params.pnlAmount > params.position.margin ? params.position.margin : params.pnlAmount;
-      settlementAmount = params.position.margin + cappedPnl - params.closeFee;
+      settlementAmount = params.position.margin + cappedPnl - params.closeFee - params.borrowFee;
     } else {
-      settlementAmount = params.position.margin - params.pnlAmount - params.closeFee;
+      settlementAmount = params.position.margin - params.pnlAmount - params.closeFee - params.borrowFee;
     }
 
     // Ensure non-negative
@@ -1813,15 +1962,17 @@ export class ProofGenerator {
       close_fee: params.closeFee.toString(),
       pnl_amount: params.pnlAmount.toString(),
       is_profit: params.isProfit ? '1' : '0',
+      position_commitment: fieldToHex(positionCommitment),
+      position_margin: params.position.margin.toString(),
+      position_size: params.position.size.toString(),
+      entry_price: params.position.entryPrice.toString(),
+      borrow_fee: params.borrowFee.toString(),
 
       // Private inputs
       position_stealth_pub_x: fieldToHex(params.position.stealthPubX),
       // IMPORTANT: market_id must be in hex format to match circuit expectations
       market_id: '0x' + params.position.marketId.toString(16).padStart(64, '0'),
-      position_margin: params.position.margin.toString(),
-      position_size: params.position.size.toString(),
       position_leverage: params.position.leverage.toString(),
-      entry_price: params.position.entryPrice.toString(),
       position_randomness: fieldToHex(params.position.randomness),
       position_spending_key: fieldToHex(params.position.spendingKey),
       merkle_path: merklePath.map(p => fieldToHex(p)),
@@ -1844,6 +1995,137 @@ export class ProofGenerator {
     };
   }
 
+  /**
+   * Generate proof for transferring a perps position to a new stealth owner
+   *
+   * Circuit proves:
+   * - Ownership of position commitment
+   * - Correct nullifier derivation
+   * - New position commitment has identical terms, only owner/randomness differ
+   */
+  async generateTransferPositionProof(
+    params: {
+      /** Position details */
+      position: {
+        stealthPubX: Uint8Array;
+        marketId: bigint;
+        isLong: boolean;
+        margin: bigint;
+        size: bigint;
+        leverage: number;
+        entryPrice: bigint;
+        randomness: Uint8Array;
+        leafIndex: number;
+        spendingKey: Uint8Array;
+      };
+      /** Perps pool ID */
+      perpsPoolId: Uint8Array;
+      /** New owner stealth address */
+      newOwner: { stealthPubkey: { x: Uint8Array } };
+      /** Merkle root */
+      merkleRoot: Uint8Array;
+      /** Merkle path */
+      merklePath: Uint8Array[];
+      /** Merkle path indices */
+      merkleIndices: number[];
+    },
+    keypair: Keypair
+  ): Promise<{
+    proof: Uint8Array;
+    positionCommitment: Uint8Array;
+    positionNullifier: Uint8Array;
+    newPositionCommitment: Uint8Array;
+    newPositionRandomness: Uint8Array;
+  }> {
+    const circuitName = 'perps/transfer_position';
+
+    if (!this.hasCircuit(circuitName)) {
+      throw new Error(`${circuitName} circuit not loaded. Circuits need to be compiled first.`);
+    }
+
+    const effectiveNullifierKey = deriveNullifierKey(params.position.spendingKey);
+
+    const POSITION_COMMITMENT_DOMAIN = 8n;
+    const computePositionCommitment = (stealthPubX: Uint8Array, randomness: Uint8Array) => {
+      const stage1 = poseidonHashDomain(
+        POSITION_COMMITMENT_DOMAIN,
+        stealthPubX,
+        fieldToBytes(params.position.marketId),
+        fieldToBytes(BigInt(params.position.isLong ? 1 : 0)),
+        fieldToBytes(params.position.margin)
+      );
+      return poseidonHash([
+        stage1,
+        fieldToBytes(params.position.size),
+        fieldToBytes(BigInt(params.position.leverage)),
+        fieldToBytes(params.position.entryPrice),
+        randomness,
+      ]);
+    };
+
+    const positionCommitment = computePositionCommitment(
+      params.position.stealthPubX,
+      params.position.randomness
+    );
+    const positionNullifier = deriveSpendingNullifier(
+      effectiveNullifierKey,
+      positionCommitment,
+      params.position.leafIndex
+    );
+
+    const newPositionRandomness = generateRandomness();
+    const newPositionCommitment = computePositionCommitment(
+      params.newOwner.stealthPubkey.x,
+      newPositionRandomness
+    );
+
+    // Pad merkle path
+    const merklePath = [...params.merklePath];
+    while (merklePath.length < 32) {
+      merklePath.push(new Uint8Array(32));
+    }
+    const merkleIndices = [...params.merkleIndices];
+    while (merkleIndices.length < 32) {
+      merkleIndices.push(0);
+    }
+
+    const witnessInputs = {
+      // Public inputs
+      merkle_root: fieldToHex(params.merkleRoot),
+      position_nullifier: fieldToHex(positionNullifier),
+      // IMPORTANT: Use pubkeyToField to match on-chain pubkey_to_field reduction
+      perps_pool_id: fieldToHex(pubkeyToField(new PublicKey(params.perpsPoolId))),
+      market_id: '0x' + params.position.marketId.toString(16).padStart(64, '0'),
+      position_commitment: fieldToHex(positionCommitment),
+      new_position_commitment: fieldToHex(newPositionCommitment),
+      is_long: params.position.isLong ? '1' : '0',
+      margin_amount: params.position.margin.toString(),
+      position_size: params.position.size.toString(),
+      entry_price: params.position.entryPrice.toString(),
+
+      // Private inputs
+      position_stealth_pub_x: fieldToHex(params.position.stealthPubX),
+      position_leverage: params.position.leverage.toString(),
+      position_randomness: fieldToHex(params.position.randomness),
+      position_spending_key: fieldToHex(params.position.spendingKey),
+      merkle_path: merklePath.map(p => fieldToHex(p)),
+      merkle_path_indices: merkleIndices.map(i => i.toString()),
+      leaf_index: params.position.leafIndex.toString(),
+      new_stealth_pub_x: fieldToHex(params.newOwner.stealthPubkey.x),
+      new_randomness: fieldToHex(newPositionRandomness),
+    };
+
+    const proof = await this.prove(circuitName, witnessInputs);
+
+    return {
+      proof,
+      positionCommitment,
+      positionNullifier,
+      newPositionCommitment,
+      newPositionRandomness,
+    };
+  }
+
   /**
    * Generate proof for adding perps liquidity (single token deposit)
    *
@@ -0,0 +1,323 @@
+/**
+ * Prover Orchestration
+ *
+ * Routes Groth16 proving to a local (snarkjs/wasm) or remote prover. Mobile
+ * clients can't prove the larger circuits in acceptable time, so heavy
+ * circuits can be sent to a remote prover while the rest stay local.
+ *
+ * Remote proving reveals the witness, including spending secrets, to the
+ * prover operator. The witness is encrypted to the prover's X25519 key so
+ * relays, proxies and TLS-terminating CDNs in between only see ciphertext;
+ * only use provers you trust.
+ */
+
+import { x25519 } from '@noble/curves/ed25519';
+import { hkdf } from '@noble/hashes/hkdf';
+import { sha256 } from '@noble/hashes/sha256';
+import { randomBytes } from '@noble/hashes/utils';
+import { chacha20poly1305 } from '@noble/ciphers/chacha.js';
+
+/** Groth16 proof size formatted for Solana's alt_bn128 verifier */
+export const GROTH16_PROOF_BYTES = 256;
+
+/** HKDF info for witness envelope keys */
+const WITNESS_ENVELOPE_INFO = new TextEncoder().encode('cloakcraft-witness-v1');
+
+/**
+ * Circuit witness inputs (field elements as decimal strings)
+ */
+export type CircomInputs = Record<string, string | string[]>;
+
+/**
+ * Groth16 prover backend
+ */
+export interface Prover {
+  readonly kind: 'local' | 'remote' | 'hybrid';
+  /** Prove a circuit, returning the 256-byte Solana-formatted proof */
+  prove(circuitName: string, inputs: CircomInputs): Promise<Uint8Array>;
+}
+
+// =============================================================================
+// Circuit Selection
+// =============================================================================
+
+/**
+ * Operations that require a proof
+ */
+export type ProofOperation =
+  | 'transfer'
+  | 'consolidate'
+  | 'adapter'
+  | 'order_create'
+  | 'order_fill'
+  | 'order_cancel'
+  | 'swap'
+  | 'add_liquidity'
+  | 'remove_liquidity'
+  | 'convert_amm_lp'
+  | 'perps_open_position'
+  | 'perps_close_position'
+  | 'perps_add_liquidity'
+  | 'perps_remove_liquidity'
+  | 'perps_liquidate'
+  | 'perps_transfer_position'
+  | 'perps_convert_lp'
+  | 'vote_snapshot'
+  | 'change_vote_snapshot'
+  | 'vote_spend'
+  | 'close_vote_position'
+  | 'claim'
+  | 'operation_credit';
+
+/**
+ * Select the circuit for an operation
+ *
+ * `numInputs` / `numOutputs` pick between circuit variants where an
+ * operation has more than one (consolidation, adapter).
+ */
+export function selectCircuit(
+  operation: ProofOperation,
+  shape: { numInputs?: number; numOutputs?: number } = {}
+): string {
+  switch (operation) {
+    case 'transfer':
+      return 'transfer/1x2';
+    case 'consolidate':
+      if ((shape.numInputs ?? 3) > 3) {
+        throw new Error(`Consolidation supports at most 3 inputs, got ${shape.numInputs}`);
+      }
+      return 'consolidate/3x1';
+    case 'adapter':
+      return (shape.numOutputs ?? 2) === 1 ? 'adapter/1x1' : 'adapter/1x2';
+    case 'order_create':
+      return 'market/order_create';
+    case 'order_fill':
+      return 'market/order_fill';
+    case 'order_cancel':
+      return 'market/order_cancel';
+    case 'swap':
+      return 'swap/swap';
+    case 'add_liquidity':
+      return 'swap/add_liquidity';
+    case 'remove_liquidity':
+      return 'swap/remove_liquidity';
+    case 'convert_amm_lp':
+      return 'swap/convert_lp';
+    case 'perps_open_position':
+      return 'perps/open_position';
+    case 'perps_close_position':
+      return 'perps/close_position';
+    case 'perps_add_liquidity':
+      return 'perps/add_liquidity';
+    case 'perps_remove_liquidity':
+      return 'perps/remove_liquidity';
+    case 'perps_liquidate':
+      return 'perps/liquidate';
+    case 'perps_transfer_position':
+      return 'perps/transfer_position';
+    case 'perps_convert_lp':
+      return 'perps/convert_lp';
+    case 'vote_snapshot':
+      return 'voting/vote_snapshot';
+    case 'change_vote_snapshot':
+      return 'voting/change_vote_snapshot';
+    case 'vote_spend':
+      return 'voting/vote_spend';
+    case 'close_vote_position':
+      return 'voting/close_position';
+    case 'claim':
+      return 'voting/claim';
+    case 'operation_credit':
+      return 'credits/operation_credit';
+  }
+}
+
+/**
+ * Circuits routed to a remote prover by default (largest constraint counts)
+ */
+export const DEFAULT_REMOTE_CIRCUITS: readonly string[] = [
+  'consolidate/3x1',
+  'perps/open_position',
+  'perps/close_position',
+  'perps/add_liquidity',
+  'perps/remove_liquidity',
+  'perps/liquidate',
+  'voting/vote_spend',
+  'voting/change_vote_snapshot',
+];
+
+// =============================================================================
+// Remote Prover
+// =============================================================================
+
+/**
+ * Encrypted witness (base64 fields)
+ */
+export interface WitnessEnvelope {
+  /** Sender's ephemeral X25519 public key */
+  ephemeralPublicKey: string;
+  nonce: string;
+  ciphertext: string;
+}
+
+function toBase64(bytes: Uint8Array): string {
+  return Buffer.from(bytes).toString('base64');
+}
+
+function fromBase64(value: string): Uint8Array {
+  return new Uint8Array(Buffer.from(value, 'base64'));
+}
+
+/**
+ * Encrypt witness inputs to a prover's X25519 public key
+ *
+ * ECDH with an ephemeral key, HKDF-SHA256 to a ChaCha20-Poly1305 key. The
+ * circuit name is bound as associated data so an envelope can't be replayed
+ * against another circuit.
+ */
+export function sealWitness(
+  circuitName: string,
+  inputs: CircomInputs,
+  proverPublicKey: Uint8Array
+): WitnessEnvelope {
+  const ephemeralSecret = x25519.utils.randomPrivateKey();
+  const ephemeralPublicKey = x25519.getPublicKey(ephemeralSecret);
+  const shared = x25519.getSharedSecret(ephemeralSecret, proverPublicKey);
+  const key = hkdf(sha256, shared, ephemeralPublicKey, WITNESS_ENVELOPE_INFO, 32);
+  const nonce = randomBytes(12);
+
+  const plaintext = new TextEncoder().encode(JSON.stringify(inputs));
+  const aad = new TextEncoder().encode(circuitName);
+  const ciphertext = chacha20poly1305(key, nonce, aad).encrypt(plaintext);
+
+  return {
+    ephemeralPublicKey: toBase64(ephemeralPublicKey),
+    nonce: toBase64(nonce),
+    ciphertext: toBase64(ciphertext),
+  };
+}
+
+/**
+ * Remote prover configuration
+ */
+export interface RemoteProverConfig {
+  /** Prover API base URL (POST {url}/prove, GET {url}/key) */
+  url: string;
+  /** Sent as a bearer token, if set */
+  apiKey?: string;
+  /** Prover X25519 public key; fetched from {url}/key when omitted */
+  publicKey?: Uint8Array;
+  /** Request timeout (default 120s) */
+  timeoutMs?: number;
+}
+
+/**
+ * Remote prover over HTTP with encrypted witness transport
+ *
+ * POST {url}/prove with `{ circuit, witness: WitnessEnvelope }`, expecting
+ * `{ proof: base64 }` (256 bytes, already formatted for Solana).
+ */
+export class RemoteProver implements Prover {
+  readonly kind = 'remote' as const;
+  private publicKey?: Uint8Array;
+
+  constructor(private config: RemoteProverConfig) {
+    this.publicKey = config.publicKey;
+  }
+
+  private headers(): Record<string, string> {
+    const headers: Record<string, string> = { 'Content-Type': 'application/json' };
+    if (this.config.apiKey) {
+      headers.Authorization = `Bearer ${this.config.apiKey}`;
+    }
+    return headers;
+  }
+
+  private async fetchJson(path: string, init?: RequestInit): Promise<any> {
+    const response = await fetch(`${this.config.url}${path}`, {
+      ...init,
+      headers: this.headers(),
+      signal: AbortSignal.timeout(this.config.timeoutMs ?? 120_000),
+    });
+    if (!response.ok) {
+      throw new Error(`Remote prover ${path} failed: HTTP ${response.status}`);
+    }
+    return response.json();
+  }
+
+  /** Prover's X25519 public key (cached after first fetch) */
+  async getPublicKey(): Promise<Uint8Array> {
+    if (!this.publicKey) {
+      const { publicKey } = await this.fetchJson('/key');
+      const key = fromBase64(publicKey);
+      if (key.length !== 32) {
+        throw new Error(`Remote prover returned invalid key length ${key.length}`);
+      }
+      this.publicKey = key;
+    }
+    return this.publicKey;
+  }
+
+  async prove(circuitName: string, inputs: CircomInputs): Promise<Uint8Array> {
+    const witness = sealWitness(circuitName, inputs, await this.getPublicKey());
+    const { proof } = await this.fetchJson('/prove', {
+      method: 'POST',
+      body: JSON.stringify({ circuit: circuitName, witness }),
+    });
+
+    const bytes = fromBase64(proof);
+    if (bytes.length !== GROTH16_PROOF_BYTES) {
+      throw new Error(`Remote prover returned ${bytes.length}-byte proof, expected ${GROTH16_PROOF_BYTES}`);
+    }
+    return bytes;
+  }
+}
+
+// =============================================================================
+// Hybrid Prover
+// =============================================================================
+
+/**
+ * Hybrid prover configuration
+ */
+export interface HybridProverConfig {
+  local: Prover;
+  remote: Prover;
+  /** Circuits proved remotely (default DEFAULT_REMOTE_CIRCUITS) */
+  remoteCircuits?: readonly string[];
+  /** Retry locally if the remote prover fails (default true) */
+  fallbackToLocal?: boolean;
+}
+
+/**
+ * Routes each circuit to the local or remote prover
+ */
+export class HybridProver implements Prover {
+  readonly kind = 'hybrid' as const;
+  private remoteCircuits: Set<string>;
+
+  constructor(private config: HybridProverConfig) {
+    this.remoteCircuits = new Set(config.remoteCircuits ?? DEFAULT_REMOTE_CIRCUITS);
+  }
+
+  /** Backend a circuit is routed to */
+  route(circuitName: string): 'local' | 'remote' {
+    return this.remoteCircuits.has(circuitName) ? 'remote' : 'local';
+  }
+
+  async prove(circuitName: string, inputs: CircomInputs): Promise<Uint8Array> {
+    if (this.route(circuitName) === 'local') {
+      return this.config.local.prove(circuitName, inputs);
+    }
+
+    try {
+      return await this.config.remote.prove(circuitName, inputs);
+    } catch (err) {
+      if (this.config.fallbackToLocal === false) {
+        throw err;
+      }
+      console.warn(`[Prover] Remote proving failed for ${circuitName}, falling back to local:`, err);
+      return this.config.local.prove(circuitName, inputs);
+    }
+  }
+}
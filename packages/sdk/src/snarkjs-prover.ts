@@ -96,7 +96,7 @@ export async function loadCircomArtifacts(
  */
 export async function generateSnarkjsProof(
   artifacts: CircomArtifacts,
-  inputs: Record<string, string | string[]>
+  inputs: Record<string, string | string[] | string[][]>
 ): Promise<Uint8Array> {
   // Dynamic import snarkjs to avoid SSR issues
   const snarkjs = await import('snarkjs');
@@ -223,7 +223,7 @@ function bigIntToBytes(value: bigint, length: number): Uint8Array {
  */
 export async function generateSnarkjsProofFromCircuit(
   circuitName: string,
-  inputs: Record<string, string | string[]>,
+  inputs: Record<string, string | string[] | string[][]>,
   buildDir: string
 ): Promise<Uint8Array> {
   // Construct paths to WASM and zkey files
@@ -55,6 +55,7 @@ import {
   buildClaimPhase0Instruction,
   buildClaimExecuteInstruction,
   generateEncryptedContributions,
+  generateEncryptedTurnout,
   generateNegatedEncryptedContributions,
   VoteSnapshotInstructionParams,
   VoteSpendInstructionParams,
@@ -174,14 +175,31 @@ export class VotingClient {
       onProgress?.(phase, msg);
     };
 
+    // Snapshot roots are enforced on-chain; fail before proving
+    if (!ballot.hasSnapshotRoot) {
+      throw new Error('Ballot snapshot root is not registered yet');
+    }
+    if (!Buffer.from(ballot.snapshotRoot).equals(Buffer.from(params.snapshotMerkleRoot))) {
+      throw new Error('snapshotMerkleRoot does not match the ballot snapshot root');
+    }
+
+    // LP notes must come from a mint registered on the ballot
+    const lpSource = params.lpMint
+      ? ballot.lpVoteSources.find(s => s.lpMint.equals(params.lpMint!))
+      : undefined;
+    if (params.lpMint && !lpSource) {
+      throw new Error('lpMint is not registered as a vote source on this ballot');
+    }
+
     report(0, 'Generating proof inputs...');
 
     // Generate proof inputs
     const { inputs, voteNullifier, voteCommitment, voteRandomness } = await generateVoteSnapshotInputs(
       params,
       ballot.revealMode,
-      ballot.tokenMint.toBytes(),
-      ballot.hasEligibilityRoot ? bytesToField(ballot.eligibilityRoot) : 0n
+      (params.lpMint ?? ballot.tokenMint).toBytes(),
+      ballot.hasEligibilityRoot ? bytesToField(ballot.eligibilityRoot) : 0n,
+      lpSource?.rate
     );
 
     report(0, 'Generating ZK proof...');
@@ -197,15 +215,19 @@ export class VotingClient {
 
     // Generate encrypted contributions for encrypted modes
     let encryptedContributions: EncryptedContributions | undefined;
+    let encryptedTurnout: Uint8Array | undefined;
     if (ballot.revealMode !== RevealMode.Public) {
       const encSeed = generateRandomness();
       encryptedContributions = generateEncryptedContributions(
         params.voteChoice,
-        params.noteAmount, // weight = amount for linear formula
+        inputs.weight, // weight = amount (LP: converted amount) for linear formula
         ballot.numOptions,
         ballot.timeLockPubkey,
         encSeed
       );
+      if (ballot.hideTurnout) {
+        encryptedTurnout = generateEncryptedTurnout(ballot.timeLockPubkey, encSeed);
+      }
     }
 
     // Generate encrypted preimage for claim recovery (encrypted modes)
@@ -213,7 +235,7 @@ export class VotingClient {
     if (ballot.revealMode !== RevealMode.Public) {
       const preimageData = {
         voteChoice: params.voteChoice,
-        weight: params.noteAmount,
+        weight: inputs.weight,
         randomness: voteRandomness,
         ballotId: params.ballotId,
       };
@@ -240,11 +262,12 @@ export class VotingClient {
       voteCommitment,
       voteChoice: params.voteChoice,
       amount: params.noteAmount,
-      weight: params.noteAmount, // weight = amount for linear formula
+      weight: inputs.weight, // weight = amount (LP: converted amount) for linear formula
       proof: proofResult,
       outputRandomness: voteRandomness,
       encryptedContributions: encryptedContributions?.ciphertexts,
       encryptedPreimage,
+      lpMint: params.lpMint,
     };
 
     // Build all phase instructions
@@ -290,7 +313,9 @@ export class VotingClient {
       params.ballotId,
       payer.publicKey,
       encryptedContributions?.ciphertexts || null,
-      this.programId
+      this.programId,
+      undefined,
+      encryptedTurnout
     );
 
     const phase2Sig = await this.sendTransaction(
@@ -17,6 +17,7 @@ export {
   ResolutionMode,
   BallotStatus,
   WeightOp,
+  OptionRegistration,
 
   // Types - export with aliases to avoid conflicts
   type BallotConfig as VotingBallotConfig,
@@ -46,15 +47,26 @@ export {
   // PDA derivation - voting specific
   deriveBallotPda,
   deriveBallotVaultPda,
+  deriveSnapshotTreePda,
+  deriveBallotOptionsPagePda,
+  optionsPageFor,
+  OPTIONS_PER_PAGE,
   derivePendingOperationPda as deriveVotingPendingOperationPda,
   deriveVerificationKeyPda as deriveVotingVerificationKeyPda,
   generateOperationId as generateVotingOperationId,
 
   // Ballot management instruction builders
   buildCreateBallotInstruction,
+  buildMigrateBallotInstruction,
   buildResolveBallotInstruction,
+  buildDisputeResolutionInstruction,
+  buildSettleDisputeInstruction,
   buildFinalizeBallotInstruction,
   buildDecryptTallyInstruction,
+  buildCreateBallotOptionsPageInstruction,
+  buildAddBallotOptionInstruction,
+  buildInitializeSnapshotTreeInstruction,
+  buildAppendSnapshotLeavesInstruction,
 
   // Vote snapshot instruction builders
   buildVoteSnapshotPhase0Instruction,
@@ -74,7 +86,11 @@ export {
 
   // Claim instruction builders
   buildClaimPhase0Instruction,
+  buildClaimMultiPhase0Instruction,
+  buildClaimRefundPhase0Instruction,
   buildClaimExecuteInstruction,
+  CLAIM_MULTI_MAX_POSITIONS,
+  quoteClaimPayout,
 
   // High-level multi-phase builders
   buildVoteSnapshotInstructions,
@@ -85,6 +101,7 @@ export {
 
   // Encrypted contributions
   generateEncryptedContributions,
+  generateEncryptedTurnout,
   generateNegatedEncryptedContributions,
 
   // Verify vote commitment exists (Phase 1)
@@ -97,6 +114,10 @@ export {
   type VoteSpendInstructionParams,
   type CloseVotePositionInstructionParams,
   type ClaimInstructionParams,
+  type ClaimPositionParams,
+  type ClaimMultiInstructionParams,
+  type ClaimRefundInstructionParams,
+  type ClaimPayoutQuote,
   type VoteCommitmentMerkleContext,
   type LightVerifyVoteCommitmentParams,
 } from './instructions';
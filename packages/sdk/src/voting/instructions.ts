@@ -25,7 +25,7 @@ import {
   RevealMode,
   VoteBindingMode,
 } from './types';
-import { PROGRAM_ID } from '../instructions/constants';
+import { PROGRAM_ID, derivePoolPda, deriveProtocolConfigPda, deriveProgramVersionPda, CLIENT_VERSION, applyOperationEpoch, getOperationEpoch } from '../instructions/constants';
 import { fieldToBytes, bytesToField, poseidonHashDomain } from '../crypto/poseidon';
 import { generateRandomness } from '../crypto/commitment';
 
@@ -36,18 +36,26 @@ export const VOTING_SEEDS = {
   BALLOT_VAULT: Buffer.from('ballot_vault'),
   PENDING_OP: Buffer.from('pending_op'),
   VK: Buffer.from('vk'),
+  SNAPSHOT_TREE: Buffer.from('snapshot_tree'),
+  BALLOT_OPTIONS: Buffer.from('ballot_options'),
 } as const;
 
+/** Options tallied on the ballot itself, and per options page */
+export const OPTIONS_PER_PAGE = 16;
+
 // ============ Circuit IDs ============
 // Must match on-chain constants in constants.rs with underscore padding
 
 export const CIRCUIT_IDS = {
   // Voting circuits - 32 bytes, underscore-padded (must match constants.rs)
   VOTE_SNAPSHOT: Buffer.from('vote_snapshot___________________'), // 32 chars
+  VOTE_TWAB: Buffer.from('vote_twab_______________________'), // 32 chars
   CHANGE_VOTE_SNAPSHOT: Buffer.from('change_vote_snapshot____________'), // 32 chars
   VOTE_SPEND: Buffer.from('vote_spend______________________'), // 32 chars
   CLOSE_POSITION: Buffer.from('close_position__________________'), // 32 chars - shared with perps
   CLAIM: Buffer.from('claim___________________________'), // 32 chars
+  CLAIM_MULTI: Buffer.from('claim_multi_____________________'), // 32 chars
+  CLAIM_REFUND: Buffer.from('claim_refund____________________'), // 32 chars
 };
 
 // ============ PDA Derivation ============
@@ -92,10 +100,46 @@ export function deriveVerificationKeyPda(
   );
 }
 
+export function deriveSnapshotTreePda(
+  ballotId: Uint8Array,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [VOTING_SEEDS.SNAPSHOT_TREE, Buffer.from(ballotId)],
+    programId
+  );
+}
+
+/**
+ * Derive options page `page` (1-3) of a ballot with more than 16 options
+ */
+export function deriveBallotOptionsPagePda(
+  ballotId: Uint8Array,
+  page: number,
+  programId: PublicKey = PROGRAM_ID
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [VOTING_SEEDS.BALLOT_OPTIONS, Buffer.from(ballotId), Buffer.from([page])],
+    programId
+  );
+}
+
+/**
+ * Options page tallying `option`, or undefined if the ballot itself does
+ */
+export function optionsPageFor(
+  ballotId: Uint8Array,
+  option: number,
+  programId: PublicKey = PROGRAM_ID
+): PublicKey | undefined {
+  const page = Math.floor(option / OPTIONS_PER_PAGE);
+  return page === 0 ? undefined : deriveBallotOptionsPagePda(ballotId, page, programId)[0];
+}
+
 // ============ Operation ID Generation ============
 
-export function generateOperationId(): Uint8Array {
-  return crypto.getRandomValues(new Uint8Array(32));
+export function generateOperationId(epoch: number = getOperationEpoch()): Uint8Array {
+  return applyOperationEpoch(crypto.getRandomValues(new Uint8Array(32)), epoch);
 }
 
 // ============ Ballot Management ============
@@ -115,6 +159,12 @@ export interface CreateBallotInstructionParams {
   snapshotSlot: number;
   indexerPubkey: PublicKey;
   eligibilityRoot: Uint8Array | null;
+  /** Time-weighted balance mode (default disabled) */
+  twabMode?: { disabled: {} } | { average: {} };
+  /** Roots averaged in TWAB mode (2-8) */
+  twabNumRoots?: number;
+  /** Minimum slots between TWAB root registrations (TWAB mode only) */
+  twabIntervalSlots?: number;
   weightFormula: number[];
   weightParams: bigint[];
   timeLockPubkey: Uint8Array;
@@ -122,6 +172,22 @@ export interface CreateBallotInstructionParams {
   resolver: PublicKey | null;
   oracle: PublicKey | null;
   claimDeadline: number;
+  /** Lamports locked as a spam bond (default 0 = none) */
+  proposalBond?: bigint;
+  /** Who may add options while open (default closed) */
+  optionRegistration?: { closed: {} } | { registrar: {} } | { open: {} };
+  /** Registrar for registrar registration */
+  optionRegistrar?: PublicKey | null;
+  /** Cap on options added by registration (0 when closed) */
+  maxOptions?: number;
+  /** Keep turnout encrypted until decrypt_tally (encrypted snapshot ballots) */
+  hideTurnout?: boolean;
+  /** Share of their stake losers can claim back, in bps (SpendToVote only) */
+  loserRefundBps?: number;
+  /** Seconds an Oracle outcome can be disputed, escalating to the resolver */
+  disputeWindowSeconds?: number;
+  /** Lamports a challenger locks to dispute */
+  disputeBond?: bigint;
 }
 
 /**
@@ -172,6 +238,9 @@ export async function buildCreateBallotInstruction(
         snapshotSlot: new BN(params.snapshotSlot),
         indexerPubkey: params.indexerPubkey,
         eligibilityRoot: params.eligibilityRoot ? Array.from(params.eligibilityRoot) : null,
+        twabMode: params.twabMode ?? { disabled: {} },
+        twabNumRoots: params.twabNumRoots ?? 0,
+        twabIntervalSlots: new BN(params.twabIntervalSlots ?? 0),
         weightFormula: Buffer.from(params.weightFormula),
         weightParams: params.weightParams.map(p => new BN(p.toString())),
         timeLockPubkey: Array.from(params.timeLockPubkey),
@@ -179,17 +248,51 @@ export async function buildCreateBallotInstruction(
         resolver: params.resolver,
         oracle: params.oracle,
         claimDeadline: new BN(params.claimDeadline),
+        proposalBond: new BN((params.proposalBond ?? 0n).toString()),
+        optionRegistration: params.optionRegistration ?? { closed: {} },
+        optionRegistrar: params.optionRegistrar ?? null,
+        maxOptions: params.maxOptions ?? 0,
+        hideTurnout: params.hideTurnout ?? false,
+        loserRefundBps: params.loserRefundBps ?? 0,
+        disputeWindowSeconds: new BN(params.disputeWindowSeconds ?? 0),
+        disputeBond: new BN((params.disputeBond ?? 0n).toString()),
       }
     )
     .accounts(accounts)
     .instruction();
 }
 
+/**
+ * Build migrate_ballot instruction
+ *
+ * Grows a ballot created with an older layout to the current size, with the
+ * added fields zeroed. Permissionless; the payer covers the extra rent.
+ */
+export async function buildMigrateBallotInstruction(
+  program: Program,
+  ballotId: Uint8Array,
+  payer: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): Promise<TransactionInstruction> {
+  const [ballotPda] = deriveBallotPda(ballotId, programId);
+
+  return program.methods
+    .migrateBallot(Array.from(ballotId))
+    .accounts({
+      ballot: ballotPda,
+      payer,
+      systemProgram: SystemProgram.programId,
+    })
+    .instruction();
+}
+
 /**
  * Build resolve_ballot instruction
  *
  * @param resolver - Optional resolver (required for Authority mode)
  * @param authority - Signer (required for all modes)
+ * @param optionsPages - Options pages of ballots with more than 16 options,
+ *   in page order (see deriveBallotOptionsPagePda)
  */
 export async function buildResolveBallotInstruction(
   program: Program,
@@ -197,7 +300,9 @@ export async function buildResolveBallotInstruction(
   outcome: number | null,
   authority: PublicKey,
   resolver?: PublicKey,
-  programId: PublicKey = PROGRAM_ID
+  programId: PublicKey = PROGRAM_ID,
+  bondRecipient?: PublicKey,
+  optionsPages: PublicKey[] = []
 ): Promise<TransactionInstruction> {
   const [ballotPda] = deriveBallotPda(ballotId, programId);
 
@@ -212,9 +317,73 @@ export async function buildResolveBallotInstruction(
     accounts.resolver = resolver;
   }
 
+  // Bonded ballots: ballot authority if quorum is met, protocol treasury otherwise
+  if (bondRecipient) {
+    accounts.protocolConfig = deriveProtocolConfigPda(programId)[0];
+    accounts.bondRecipient = bondRecipient;
+  }
+
   return program.methods
     .resolveBallot(Array.from(ballotId), outcome !== null ? outcome : null)
     .accounts(accounts)
+    .remainingAccounts(
+      optionsPages.map(pubkey => ({ pubkey, isSigner: false, isWritable: false }))
+    )
+    .instruction();
+}
+
+/**
+ * Build dispute_resolution instruction
+ *
+ * Disputes an Oracle ballot's outcome within its dispute window; the
+ * challenger locks the ballot's dispute bond.
+ */
+export async function buildDisputeResolutionInstruction(
+  program: Program,
+  ballotId: Uint8Array,
+  challenger: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): Promise<TransactionInstruction> {
+  const [ballotPda] = deriveBallotPda(ballotId, programId);
+
+  return program.methods
+    .disputeResolution(Array.from(ballotId))
+    .accounts({
+      ballot: ballotPda,
+      challenger,
+      systemProgram: SystemProgram.programId,
+    })
+    .instruction();
+}
+
+/**
+ * Build settle_dispute instruction
+ *
+ * `bondRecipient` is the challenger if `outcome` differs from the disputed
+ * one, and the protocol treasury if it is upheld.
+ */
+export async function buildSettleDisputeInstruction(
+  program: Program,
+  ballotId: Uint8Array,
+  outcome: number | null,
+  resolver: PublicKey,
+  bondRecipient: PublicKey,
+  programId: PublicKey = PROGRAM_ID,
+  optionsPages: PublicKey[] = []
+): Promise<TransactionInstruction> {
+  const [ballotPda] = deriveBallotPda(ballotId, programId);
+
+  return program.methods
+    .settleDispute(Array.from(ballotId), outcome)
+    .accounts({
+      ballot: ballotPda,
+      resolver,
+      protocolConfig: deriveProtocolConfigPda(programId)[0],
+      bondRecipient,
+    })
+    .remainingAccounts(
+      optionsPages.map(pubkey => ({ pubkey, isSigner: false, isWritable: false }))
+    )
     .instruction();
 }
 
@@ -247,18 +416,27 @@ export async function buildFinalizeBallotInstruction(
 
 /**
  * Build decrypt_tally instruction
+ *
+ * @param decryptedVoteCount - Decrypted vote count (hidden-turnout ballots)
  */
 export async function buildDecryptTallyInstruction(
   program: Program,
   ballotId: Uint8Array,
   decryptionKey: Uint8Array,
   authority: PublicKey,
-  programId: PublicKey = PROGRAM_ID
+  programId: PublicKey = PROGRAM_ID,
+  decryptedWeights: bigint[] = [],
+  decryptedVoteCount: bigint | null = null
 ): Promise<TransactionInstruction> {
   const [ballotPda] = deriveBallotPda(ballotId, programId);
 
   return program.methods
-    .decryptTally(Array.from(decryptionKey))
+    .decryptTally(
+      Array.from(ballotId),
+      Array.from(decryptionKey),
+      decryptedWeights.map(w => new BN(w.toString())),
+      decryptedVoteCount !== null ? new BN(decryptedVoteCount.toString()) : null
+    )
     .accounts({
       ballot: ballotPda,
       authority,
@@ -266,6 +444,164 @@ export async function buildDecryptTallyInstruction(
     .instruction();
 }
 
+/**
+ * Build register_snapshot_root instruction
+ *
+ * Registers the commitment root archived at the ballot's snapshot slot.
+ * Must be signed by the ballot's indexer or authority, after snapshotSlot
+ * and before the first vote. Snapshot votes then prove against this root.
+ */
+export async function buildRegisterSnapshotRootInstruction(
+  program: Program,
+  ballotId: Uint8Array,
+  snapshotRoot: Uint8Array,
+  attester: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): Promise<TransactionInstruction> {
+  const [ballotPda] = deriveBallotPda(ballotId, programId);
+
+  return program.methods
+    .registerSnapshotRoot(Array.from(ballotId), Array.from(snapshotRoot))
+    .accounts({
+      ballot: ballotPda,
+      attester,
+    })
+    .instruction();
+}
+
+/**
+ * Build register_lp_vote_source instruction
+ *
+ * Accepts notes of an AMM or perps LP mint for snapshot voting. `rate` is
+ * ballot token units per LP token scaled by 1e18. Must be signed by the
+ * ballot's indexer or authority before the first vote.
+ */
+export async function buildRegisterLpVoteSourceInstruction(
+  program: Program,
+  ballotId: Uint8Array,
+  lpMint: PublicKey,
+  rate: bigint,
+  attester: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): Promise<TransactionInstruction> {
+  const [ballotPda] = deriveBallotPda(ballotId, programId);
+
+  return program.methods
+    .registerLpVoteSource(Array.from(ballotId), lpMint, new BN(rate.toString()))
+    .accounts({
+      ballot: ballotPda,
+      attester,
+    })
+    .instruction();
+}
+
+/**
+ * Build create_ballot_options_page instruction
+ *
+ * Creates page `page` (1-3) of a ballot with more than 16 options, tallying
+ * options 16*page to 16*page+15. Permissionless; the payer funds the account.
+ */
+export async function buildCreateBallotOptionsPageInstruction(
+  program: Program,
+  ballotId: Uint8Array,
+  page: number,
+  payer: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): Promise<TransactionInstruction> {
+  const [ballotPda] = deriveBallotPda(ballotId, programId);
+  const [optionsPage] = deriveBallotOptionsPagePda(ballotId, page, programId);
+
+  return program.methods
+    .createBallotOptionsPage(Array.from(ballotId), page)
+    .accounts({
+      ballot: ballotPda,
+      optionsPage,
+      payer,
+      systemProgram: SystemProgram.programId,
+    })
+    .instruction();
+}
+
+/**
+ * Build add_ballot_option instruction
+ *
+ * Adds a write-in option to an open ballot; the new option's index is the
+ * ballot's numOptions before the call. `metadataHash` commits to the
+ * option's off-chain metadata. Signed by the ballot's option registrar, or
+ * by anyone if registration is open.
+ */
+export async function buildAddBallotOptionInstruction(
+  program: Program,
+  ballotId: Uint8Array,
+  metadataHash: Uint8Array,
+  registrar: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): Promise<TransactionInstruction> {
+  const [ballotPda] = deriveBallotPda(ballotId, programId);
+
+  return program.methods
+    .addBallotOption(Array.from(ballotId), Array.from(metadataHash))
+    .accounts({
+      ballot: ballotPda,
+      registrar,
+    })
+    .instruction();
+}
+
+/**
+ * Build initialize_snapshot_tree instruction
+ *
+ * Creates the ballot's on-chain snapshot tree. Must be signed by the
+ * ballot's indexer or authority, who pays for the account.
+ */
+export async function buildInitializeSnapshotTreeInstruction(
+  program: Program,
+  ballotId: Uint8Array,
+  attester: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): Promise<TransactionInstruction> {
+  const [ballotPda] = deriveBallotPda(ballotId, programId);
+  const [snapshotTree] = deriveSnapshotTreePda(ballotId, programId);
+
+  return program.methods
+    .initializeSnapshotTree(Array.from(ballotId))
+    .accounts({
+      ballot: ballotPda,
+      snapshotTree,
+      attester,
+      systemProgram: SystemProgram.programId,
+    })
+    .instruction();
+}
+
+/**
+ * Build append_snapshot_leaves instruction
+ *
+ * Appends commitments that existed at the snapshot slot to the ballot's
+ * snapshot tree, recording one new root per call. Must be signed by the
+ * ballot's indexer or authority, after snapshotSlot. Leaves are numbered in
+ * append order (see SnapshotLeavesAppended events).
+ */
+export async function buildAppendSnapshotLeavesInstruction(
+  program: Program,
+  ballotId: Uint8Array,
+  leaves: Uint8Array[],
+  attester: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): Promise<TransactionInstruction> {
+  const [ballotPda] = deriveBallotPda(ballotId, programId);
+  const [snapshotTree] = deriveSnapshotTreePda(ballotId, programId);
+
+  return program.methods
+    .appendSnapshotLeaves(Array.from(ballotId), leaves.map(leaf => Array.from(leaf)))
+    .accounts({
+      ballot: ballotPda,
+      snapshotTree,
+      attester,
+    })
+    .instruction();
+}
+
 // ============ Vote Snapshot (Multi-Phase) ============
 
 export interface VoteSnapshotInstructionParams {
@@ -280,7 +616,17 @@ export interface VoteSnapshotInstructionParams {
   proof: Uint8Array;
   outputRandomness: Uint8Array; // 32-byte randomness for output commitment
   encryptedContributions?: Uint8Array[]; // For encrypted modes
+  /** Encryption of 1 for hidden-turnout ballots (see generateEncryptedTurnout) */
+  encryptedTurnout?: Uint8Array;
   encryptedPreimage?: Uint8Array; // For claim recovery
+  /** LP mint of the note, if voting with AMM/perps LP (must be registered on the ballot) */
+  lpMint?: PublicKey;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+  /** Prove against a retained snapshot tree root instead of the registered root (optional) */
+  useSnapshotTree?: boolean;
 }
 
 /**
@@ -303,6 +649,7 @@ export interface VoteSnapshotInstructionParams {
  * - encrypted_contributions: Option<EncryptedContributions>
  * - encrypted_preimage: Option<Vec<u8>>
  * - output_randomness: [u8; 32]
+ * - lp_mint: Option<Pubkey>
  */
 export async function buildVoteSnapshotPhase0Instruction(
   program: Program,
@@ -330,7 +677,9 @@ export async function buildVoteSnapshotPhase0Instruction(
       new BN(params.weight.toString()),
       params.encryptedContributions ? { ciphertexts: params.encryptedContributions.map(c => Array.from(c)) } : null,
       params.encryptedPreimage ? Buffer.from(params.encryptedPreimage) : null, // bytes type needs Buffer
-      Array.from(params.outputRandomness)
+      Array.from(params.outputRandomness),
+      params.lpMint ?? null,
+      CLIENT_VERSION
     )
     .accounts({
       ballot: ballotPda,
@@ -338,6 +687,79 @@ export async function buildVoteSnapshotPhase0Instruction(
       verificationKey: vkPda,
       relayer,
       payer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
+      snapshotTree: params.useSnapshotTree ? deriveSnapshotTreePda(params.ballotId, programId)[0] : null,
+      systemProgram: SystemProgram.programId,
+    })
+    .instruction();
+}
+
+export interface VoteTwabInstructionParams {
+  ballotId: Uint8Array;
+  noteCommitment: Uint8Array;
+  voteNullifier: Uint8Array;
+  voteCommitment: Uint8Array;
+  voteChoice: number;
+  twabAmount: bigint; // Note amount averaged over the ballot's TWAB roots
+  weight: bigint;
+  proof: Uint8Array;
+  outputRandomness: Uint8Array;
+  encryptedContributions?: Uint8Array[]; // For encrypted modes
+  /** LP mint of the note, if voting with AMM/perps LP (must be registered on the ballot) */
+  lpMint?: PublicKey;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+}
+
+/**
+ * Build vote_twab Phase 0 instruction (create pending with proof)
+ *
+ * For TWAB ballots. The TWAB roots are read from the ballot on-chain; later
+ * phases are the same as vote_snapshot.
+ */
+export async function buildVoteTwabPhase0Instruction(
+  program: Program,
+  params: VoteTwabInstructionParams,
+  operationId: Uint8Array,
+  payer: PublicKey,
+  relayer: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): Promise<TransactionInstruction> {
+  const [ballotPda] = deriveBallotPda(params.ballotId, programId);
+  const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
+  const [vkPda] = deriveVerificationKeyPda(CIRCUIT_IDS.VOTE_TWAB, programId);
+
+  return program.methods
+    .createPendingWithProofVoteTwab(
+      Array.from(operationId),
+      Array.from(params.ballotId),
+      Buffer.from(params.proof), // bytes type needs Buffer
+      Array.from(params.noteCommitment),
+      Array.from(params.voteNullifier),
+      Array.from(params.voteCommitment),
+      new BN(params.voteChoice),
+      new BN(params.twabAmount.toString()),
+      new BN(params.weight.toString()),
+      params.encryptedContributions ? { ciphertexts: params.encryptedContributions.map(c => Array.from(c)) } : null,
+      Array.from(params.outputRandomness),
+      params.lpMint ?? null,
+      CLIENT_VERSION
+    )
+    .accounts({
+      ballot: ballotPda,
+      pendingOperation: pendingOpPda,
+      verificationKey: vkPda,
+      relayer,
+      payer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
       systemProgram: SystemProgram.programId,
     })
     .instruction();
@@ -352,7 +774,9 @@ export async function buildVoteSnapshotExecuteInstruction(
   ballotId: Uint8Array,
   relayer: PublicKey,
   encryptedContributions: Uint8Array[] | null = null,
-  programId: PublicKey = PROGRAM_ID
+  programId: PublicKey = PROGRAM_ID,
+  optionsPage?: PublicKey,
+  encryptedTurnout?: Uint8Array
 ): Promise<TransactionInstruction> {
   const [ballotPda] = deriveBallotPda(ballotId, programId);
   const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
@@ -361,12 +785,14 @@ export async function buildVoteSnapshotExecuteInstruction(
     .executeVoteSnapshot(
       Array.from(operationId),
       Array.from(ballotId),
-      encryptedContributions ? { ciphertexts: encryptedContributions.map(c => Array.from(c)) } : null
+      encryptedContributions ? { ciphertexts: encryptedContributions.map(c => Array.from(c)) } : null,
+      encryptedTurnout ? Array.from(encryptedTurnout) : null
     )
     .accounts({
       ballot: ballotPda,
       pendingOperation: pendingOpPda,
       relayer,
+      optionsPage: optionsPage ?? null,
     })
     .instruction();
 }
@@ -385,6 +811,10 @@ export interface ChangeVoteSnapshotInstructionParams {
   proof: Uint8Array;
   oldEncryptedContributions?: Uint8Array[]; // Negated for tally decrement
   newEncryptedContributions?: Uint8Array[];
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
 }
 
 /**
@@ -414,7 +844,8 @@ export async function buildChangeVoteSnapshotPhase0Instruction(
       new BN(params.weight.toString()),
       Array.from(params.proof),
       params.oldEncryptedContributions?.map(c => Array.from(c)) || null,
-      params.newEncryptedContributions?.map(c => Array.from(c)) || null
+      params.newEncryptedContributions?.map(c => Array.from(c)) || null,
+      CLIENT_VERSION
     )
     .accounts({
       ballot: ballotPda,
@@ -422,6 +853,10 @@ export async function buildChangeVoteSnapshotPhase0Instruction(
       verificationKey: vkPda,
       relayer,
       payer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
       systemProgram: SystemProgram.programId,
     })
     .instruction();
@@ -437,7 +872,9 @@ export async function buildChangeVoteSnapshotExecuteInstruction(
   relayer: PublicKey,
   oldEncryptedContributions: Uint8Array[] | null = null,
   newEncryptedContributions: Uint8Array[] | null = null,
-  programId: PublicKey = PROGRAM_ID
+  programId: PublicKey = PROGRAM_ID,
+  optionsPage?: PublicKey,
+  newOptionsPage?: PublicKey
 ): Promise<TransactionInstruction> {
   const [ballotPda] = deriveBallotPda(ballotId, programId);
   const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
@@ -453,6 +890,8 @@ export async function buildChangeVoteSnapshotExecuteInstruction(
       ballot: ballotPda,
       pendingOperation: pendingOpPda,
       relayer,
+      optionsPage: optionsPage ?? null,
+      newOptionsPage: newOptionsPage ?? null,
     })
     .instruction();
 }
@@ -467,6 +906,8 @@ export interface VoteCommitmentMerkleContext {
   queuePubkeyIndex: number;
   leafIndex: number;
   rootIndex: number;
+  /** Whether the commitment is still in the output queue (required false above the pinned inclusion threshold) */
+  proveByIndex: boolean;
 }
 
 /**
@@ -474,10 +915,14 @@ export interface VoteCommitmentMerkleContext {
  */
 export interface LightVerifyVoteCommitmentParams {
   commitmentAccountHash: Uint8Array;
+  /** Data hash of the commitment account (for on-chain account hash recomputation) */
+  commitmentDataHash: Uint8Array;
   commitmentMerkleContext: VoteCommitmentMerkleContext;
+  /** Validity proof (checked when proveByIndex is false) */
   commitmentInclusionProof: {
-    rootIndices: number[];
-    proof: Uint8Array[];
+    a: number[];
+    b: number[];
+    c: number[];
   };
   commitmentAddressTreeInfo: {
     addressMerkleTreePubkeyIndex: number;
@@ -502,6 +947,7 @@ export async function buildVerifyVoteCommitmentExistsInstruction(
   program: Program,
   operationId: Uint8Array,
   ballotId: Uint8Array,
+  tokenMint: PublicKey,
   commitmentIndex: number,
   lightParams: LightVerifyVoteCommitmentParams,
   relayer: PublicKey,
@@ -514,16 +960,15 @@ export async function buildVerifyVoteCommitmentExistsInstruction(
   // Convert params to on-chain format
   const onChainParams = {
     commitmentAccountHash: Array.from(lightParams.commitmentAccountHash),
+    commitmentDataHash: Array.from(lightParams.commitmentDataHash),
     commitmentMerkleContext: {
       merkleTreePubkeyIndex: lightParams.commitmentMerkleContext.merkleTreePubkeyIndex,
       queuePubkeyIndex: lightParams.commitmentMerkleContext.queuePubkeyIndex,
       leafIndex: lightParams.commitmentMerkleContext.leafIndex,
       rootIndex: lightParams.commitmentMerkleContext.rootIndex,
+      proveByIndex: lightParams.commitmentMerkleContext.proveByIndex,
     },
-    commitmentInclusionProof: {
-      rootIndices: lightParams.commitmentInclusionProof.rootIndices,
-      proof: lightParams.commitmentInclusionProof.proof.map(p => Array.from(p)),
-    },
+    commitmentInclusionProof: lightParams.commitmentInclusionProof,
     commitmentAddressTreeInfo: {
       addressMerkleTreePubkeyIndex: lightParams.commitmentAddressTreeInfo.addressMerkleTreePubkeyIndex,
       addressQueuePubkeyIndex: lightParams.commitmentAddressTreeInfo.addressQueuePubkeyIndex,
@@ -539,6 +984,7 @@ export async function buildVerifyVoteCommitmentExistsInstruction(
     )
     .accounts({
       ballot: ballotPda,
+      tokenPool: derivePoolPda(tokenMint, programId)[0],
       pendingOperation: pendingOpPda,
       relayer,
     })
@@ -558,6 +1004,10 @@ export interface VoteSpendInstructionParams {
   proof: Uint8Array;
   encryptedContributions?: Uint8Array[];
   encryptedPreimage?: Uint8Array;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
 }
 
 /**
@@ -587,7 +1037,8 @@ export async function buildVoteSpendPhase0Instruction(
       new BN(params.weight.toString()),
       Array.from(params.proof),
       params.encryptedContributions?.map(c => Array.from(c)) || null,
-      params.encryptedPreimage ? Array.from(params.encryptedPreimage) : null
+      params.encryptedPreimage ? Array.from(params.encryptedPreimage) : null,
+      CLIENT_VERSION
     )
     .accounts({
       ballot: ballotPda,
@@ -595,6 +1046,10 @@ export async function buildVoteSpendPhase0Instruction(
       verificationKey: vkPda,
       relayer,
       payer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
       systemProgram: SystemProgram.programId,
     })
     .instruction();
@@ -609,7 +1064,8 @@ export async function buildVoteSpendExecuteInstruction(
   ballotId: Uint8Array,
   tokenMint: PublicKey,
   relayer: PublicKey,
-  programId: PublicKey = PROGRAM_ID
+  programId: PublicKey = PROGRAM_ID,
+  optionsPage?: PublicKey
 ): Promise<TransactionInstruction> {
   const [ballotPda] = deriveBallotPda(ballotId, programId);
   const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
@@ -623,6 +1079,7 @@ export async function buildVoteSpendExecuteInstruction(
       pendingOperation: pendingOpPda,
       relayer,
       tokenProgram: TOKEN_PROGRAM_ID,
+      optionsPage: optionsPage ?? null,
     })
     .instruction();
 }
@@ -639,6 +1096,10 @@ export interface CloseVotePositionInstructionParams {
   weight: bigint;
   proof: Uint8Array;
   encryptedContributions?: Uint8Array[]; // Negated for tally decrement
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
 }
 
 /**
@@ -666,7 +1127,8 @@ export async function buildCloseVotePositionPhase0Instruction(
       new BN(params.amount.toString()),
       new BN(params.weight.toString()),
       Array.from(params.proof),
-      params.encryptedContributions?.map(c => Array.from(c)) || null
+      params.encryptedContributions?.map(c => Array.from(c)) || null,
+      CLIENT_VERSION
     )
     .accounts({
       ballot: ballotPda,
@@ -674,6 +1136,10 @@ export async function buildCloseVotePositionPhase0Instruction(
       verificationKey: vkPda,
       relayer,
       payer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
       systemProgram: SystemProgram.programId,
     })
     .instruction();
@@ -688,7 +1154,8 @@ export async function buildCloseVotePositionExecuteInstruction(
   ballotId: Uint8Array,
   tokenMint: PublicKey,
   relayer: PublicKey,
-  programId: PublicKey = PROGRAM_ID
+  programId: PublicKey = PROGRAM_ID,
+  optionsPage?: PublicKey
 ): Promise<TransactionInstruction> {
   const [ballotPda] = deriveBallotPda(ballotId, programId);
   const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
@@ -702,6 +1169,7 @@ export async function buildCloseVotePositionExecuteInstruction(
       pendingOperation: pendingOpPda,
       relayer,
       tokenProgram: TOKEN_PROGRAM_ID,
+      optionsPage: optionsPage ?? null,
     })
     .instruction();
 }
@@ -718,6 +1186,10 @@ export interface ClaimInstructionParams {
   netPayout: bigint;
   userWeight: bigint;
   proof: Uint8Array;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
 }
 
 /**
@@ -745,7 +1217,8 @@ export async function buildClaimPhase0Instruction(
       new BN(params.grossPayout.toString()),
       new BN(params.netPayout.toString()),
       new BN(params.userWeight.toString()),
-      Array.from(params.proof)
+      Array.from(params.proof),
+      CLIENT_VERSION
     )
     .accounts({
       ballot: ballotPda,
@@ -753,6 +1226,151 @@ export async function buildClaimPhase0Instruction(
       verificationKey: vkPda,
       relayer,
       payer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
+      systemProgram: SystemProgram.programId,
+    })
+    .instruction();
+}
+
+/** Positions a multi-position claim takes (claim_multi circuit) */
+export const CLAIM_MULTI_MAX_POSITIONS = 3;
+
+export interface ClaimPositionParams {
+  positionCommitment: Uint8Array;
+  positionNullifier: Uint8Array;
+  /** Vote choice (Public/TimeLocked modes, 0 otherwise) */
+  voteChoice: number;
+  userWeight: bigint;
+  /** Gross payout of this position (0 if it didn't vote for the winner) */
+  grossPayout: bigint;
+}
+
+export interface ClaimMultiInstructionParams {
+  ballotId: Uint8Array;
+  /** 2-3 positions of the same owner */
+  positions: ClaimPositionParams[];
+  payoutCommitment: Uint8Array;
+  netPayout: bigint;
+  outputRandomness: Uint8Array;
+  proof: Uint8Array;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+}
+
+/**
+ * Build multi-position claim Phase 0 instruction
+ *
+ * Claims 2-3 positions into one payout commitment. Phases 1-2 run once per
+ * position; the claim execute instruction is shared with single claims.
+ */
+export async function buildClaimMultiPhase0Instruction(
+  program: Program,
+  params: ClaimMultiInstructionParams,
+  operationId: Uint8Array,
+  payer: PublicKey,
+  relayer: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): Promise<TransactionInstruction> {
+  const [ballotPda] = deriveBallotPda(params.ballotId, programId);
+  const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
+  const [vkPda] = deriveVerificationKeyPda(CIRCUIT_IDS.CLAIM_MULTI, programId);
+
+  return program.methods
+    .createPendingWithProofClaimMulti(
+      Array.from(operationId),
+      Array.from(params.ballotId),
+      Buffer.from(params.proof), // bytes type needs Buffer
+      params.positions.map((position) => ({
+        positionCommitment: Array.from(position.positionCommitment),
+        positionNullifier: Array.from(position.positionNullifier),
+        userVoteChoice: new BN(position.voteChoice),
+        userWeight: new BN(position.userWeight.toString()),
+        grossPayout: new BN(position.grossPayout.toString()),
+      })),
+      Array.from(params.payoutCommitment),
+      new BN(params.netPayout.toString()),
+      Array.from(params.outputRandomness),
+      CLIENT_VERSION
+    )
+    .accounts({
+      ballot: ballotPda,
+      verificationKey: vkPda,
+      pendingOperation: pendingOpPda,
+      relayer,
+      payer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
+      systemProgram: SystemProgram.programId,
+    })
+    .instruction();
+}
+
+export interface ClaimRefundInstructionParams {
+  ballotId: Uint8Array;
+  positionCommitment: Uint8Array;
+  positionNullifier: Uint8Array;
+  refundCommitment: Uint8Array;
+  /** Vote choice (Public/TimeLocked modes, 0 otherwise) */
+  voteChoice: number;
+  userWeight: bigint;
+  refundAmount: bigint;
+  outputRandomness: Uint8Array;
+  proof: Uint8Array;
+  /** Circuit stats PDA to update (optional, see deriveCircuitStatsPda) */
+  circuitStats?: PublicKey;
+  /** Spent operation credit (required while credits are enforced, see deriveOperationCreditPda) */
+  operationCredit?: PublicKey;
+}
+
+/**
+ * Build loser refund Phase 0 instruction
+ *
+ * For ballots with `loserRefundBps > 0`. The claim execute instruction
+ * completes refunds like payouts.
+ */
+export async function buildClaimRefundPhase0Instruction(
+  program: Program,
+  params: ClaimRefundInstructionParams,
+  operationId: Uint8Array,
+  payer: PublicKey,
+  relayer: PublicKey,
+  programId: PublicKey = PROGRAM_ID
+): Promise<TransactionInstruction> {
+  const [ballotPda] = deriveBallotPda(params.ballotId, programId);
+  const [pendingOpPda] = derivePendingOperationPda(operationId, programId);
+  const [vkPda] = deriveVerificationKeyPda(CIRCUIT_IDS.CLAIM_REFUND, programId);
+
+  return program.methods
+    .createPendingWithProofClaimRefund(
+      Array.from(operationId),
+      Array.from(params.ballotId),
+      Buffer.from(params.proof), // bytes type needs Buffer
+      Array.from(params.positionCommitment),
+      Array.from(params.positionNullifier),
+      Array.from(params.refundCommitment),
+      new BN(params.voteChoice),
+      new BN(params.userWeight.toString()),
+      new BN(params.refundAmount.toString()),
+      Array.from(params.outputRandomness),
+      CLIENT_VERSION
+    )
+    .accounts({
+      ballot: ballotPda,
+      verificationKey: vkPda,
+      pendingOperation: pendingOpPda,
+      relayer,
+      payer,
+      protocolConfig: deriveProtocolConfigPda(program.programId)[0],
+      programVersion: deriveProgramVersionPda(program.programId)[0],
+      circuitStats: params.circuitStats ?? null,
+      operationCredit: params.operationCredit ?? null,
       systemProgram: SystemProgram.programId,
     })
     .instruction();
@@ -788,6 +1406,43 @@ export async function buildClaimExecuteInstruction(
     .instruction();
 }
 
+/**
+ * Claim payout computed by the program (`ClaimPayoutQuote` return data)
+ */
+export interface ClaimPayoutQuote {
+  /** Share of the pool before the protocol fee */
+  grossPayout: bigint;
+  /** Payout after the protocol fee */
+  netPayout: bigint;
+  /** Whether claims are currently open */
+  claimsAllowed: boolean;
+}
+
+/**
+ * Quote the payout for a winning SpendToVote position (simulated, no signature needed)
+ */
+export async function quoteClaimPayout(
+  program: Program,
+  ballotId: Uint8Array,
+  userWeight: bigint,
+  programId: PublicKey = PROGRAM_ID
+): Promise<ClaimPayoutQuote> {
+  const [ballotPda] = deriveBallotPda(ballotId, programId);
+
+  const quote: any = await program.methods
+    .quoteClaimPayout(Array.from(ballotId), new BN(userWeight.toString()))
+    .accountsStrict({
+      ballot: ballotPda,
+    })
+    .view();
+
+  return {
+    grossPayout: BigInt(quote.grossPayout.toString()),
+    netPayout: BigInt(quote.netPayout.toString()),
+    claimsAllowed: quote.claimsAllowed,
+  };
+}
+
 // ============ Encrypted Contributions ============
 
 // BN254 scalar field modulus
@@ -839,6 +1494,22 @@ export function generateNegatedEncryptedContributions(
   return { ciphertexts };
 }
 
+/**
+ * Generate the turnout ciphertext of a vote on a hidden-turnout ballot
+ *
+ * An encryption of 1, summed on-chain into the ballot's encrypted vote count.
+ * Uses its own randomness index so it never shares `r` with an option.
+ */
+export function generateEncryptedTurnout(
+  timeLockPubkey: Uint8Array,
+  encryptionSeed: Uint8Array
+): Uint8Array {
+  return encryptElGamal(1n, timeLockPubkey, encryptionSeed, TURNOUT_CIPHERTEXT_INDEX);
+}
+
+/** Randomness index of turnout ciphertexts (options use 0..numOptions) */
+const TURNOUT_CIPHERTEXT_INDEX = 0xffffffff;
+
 /**
  * ElGamal encryption on BabyJubJub curve
  *
@@ -910,7 +1581,16 @@ export async function buildVoteSnapshotInstructions(
 
   // Phase 2: Execute vote
   const phase2 = [
-    await buildVoteSnapshotExecuteInstruction(program, operationId, params.ballotId, relayer, params.encryptedContributions || null, programId),
+    await buildVoteSnapshotExecuteInstruction(
+      program,
+      operationId,
+      params.ballotId,
+      relayer,
+      params.encryptedContributions || null,
+      programId,
+      undefined,
+      params.encryptedTurnout
+    ),
   ];
 
   // Phase 3-4 would include Light Protocol CPI for commitment creation
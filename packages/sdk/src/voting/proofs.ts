@@ -11,6 +11,8 @@ import {
   ClosePositionParams,
   ClaimParams,
   VoteSnapshotProofInputs,
+  VoteTwabParams,
+  VoteTwabProofInputs,
   ClaimProofInputs,
   BalanceAttestation,
   MerkleProof,
@@ -25,6 +27,16 @@ const VOTE_COMMITMENT_DOMAIN = BigInt(0x11);
 const POSITION_DOMAIN = BigInt(0x13);
 const NULLIFIER_KEY_DOMAIN = BigInt(4);
 
+/** Scale of LP vote source rates (1e18) */
+export const LP_VOTE_RATE_SCALE = BigInt(10) ** BigInt(18);
+
+/**
+ * Convert an LP note amount to ballot token units at a registered rate
+ */
+export function convertLpVoteAmount(amount: bigint, rate: bigint): bigint {
+  return (amount * rate) / LP_VOTE_RATE_SCALE;
+}
+
 /**
  * Generate vote_snapshot proof inputs
  *
@@ -35,7 +47,8 @@ export async function generateVoteSnapshotInputs(
   params: VoteSnapshotParams,
   revealMode: RevealMode,
   tokenMint: Uint8Array,
-  eligibilityRoot: bigint = BigInt(0)
+  eligibilityRoot: bigint = BigInt(0),
+  lpRate?: bigint
 ): Promise<{
   inputs: VoteSnapshotProofInputs;
   voteNullifier: Uint8Array;
@@ -61,8 +74,9 @@ export async function generateVoteSnapshotInputs(
   const voteNullifierBigInt = bytesToBigInt(voteNullifier);
 
   // Weight (for now, weight = amount, would use formula in production)
+  // LP notes weigh their value in ballot token units
   const amount = params.noteAmount;
-  const weight = amount; // Simplified; in production: evaluate formula
+  const weight = lpRate !== undefined ? convertLpVoteAmount(amount, lpRate) : amount; // Simplified; in production: evaluate formula
 
   // Compute vote commitment
   const voteCommitment = computeVoteCommitment(
@@ -121,6 +135,104 @@ export async function generateVoteSnapshotInputs(
   };
 }
 
+/** Max roots and tree depth of the vote_twab circuit */
+const TWAB_MAX_ROOTS = 8;
+const TWAB_MERKLE_LEVELS = 24;
+
+/**
+ * Generate vote_twab proof inputs
+ *
+ * `twabRoots` are the ballot's registered roots (oldest first). The note
+ * counts at each root it has a proof for; its TWAB amount is
+ * floor(amount * held / numRoots).
+ */
+export async function generateVoteTwabInputs(
+  params: VoteTwabParams,
+  revealMode: RevealMode,
+  tokenMint: Uint8Array,
+  twabRoots: Uint8Array[],
+  eligibilityRoot: bigint = BigInt(0),
+  lpRate?: bigint
+): Promise<{
+  inputs: VoteTwabProofInputs;
+  twabAmount: bigint;
+  voteNullifier: Uint8Array;
+  voteCommitment: Uint8Array;
+  voteRandomness: Uint8Array;
+}> {
+  if (params.rootProofs.length !== twabRoots.length) {
+    throw new Error('rootProofs must have one entry per TWAB root');
+  }
+
+  const numRoots = BigInt(twabRoots.length);
+  const amount = params.noteAmount;
+  const heldCount = BigInt(params.rootProofs.filter(p => p !== null).length);
+  const twabAmount = (amount * heldCount) / numRoots;
+  const twabRemainder = (amount * heldCount) % numRoots;
+
+  // Weight is the TWAB amount; LP notes weigh their value in ballot token units
+  const weight = lpRate !== undefined ? convertLpVoteAmount(twabAmount, lpRate) : twabAmount;
+
+  const voteRandomness = generateRandomness();
+  const nullifierKey = deriveNullifierKey(params.stealthSpendingKey);
+  const voteNullifier = computeVoteNullifier(nullifierKey, params.ballotId);
+  const voteCommitment = computeVoteCommitment(
+    params.ballotId,
+    voteNullifier,
+    params.stealthPubX,
+    params.voteChoice,
+    weight,
+    voteRandomness
+  );
+
+  // Pad roots and per-root proofs to the circuit size
+  const zeroPath = () => Array(TWAB_MERKLE_LEVELS).fill(BigInt(0));
+  const roots: bigint[] = [];
+  const held: bigint[] = [];
+  const merklePaths: bigint[][] = [];
+  const merklePathIndices: bigint[][] = [];
+  for (let i = 0; i < TWAB_MAX_ROOTS; i++) {
+    const proof = i < twabRoots.length ? params.rootProofs[i] : null;
+    roots.push(i < twabRoots.length ? bytesToBigInt(twabRoots[i]) : BigInt(0));
+    held.push(proof ? BigInt(1) : BigInt(0));
+    merklePaths.push(proof ? proof.merklePath.map(p => bytesToBigInt(p)) : zeroPath());
+    merklePathIndices.push(proof ? proof.merklePathIndices.map(x => BigInt(x)) : zeroPath());
+  }
+
+  const inputs: VoteTwabProofInputs = {
+    // Public inputs (must match circuit order)
+    ballot_id: bytesToBigInt(params.ballotId),
+    twab_roots: roots,
+    num_roots: numRoots,
+    note_commitment: bytesToBigInt(params.noteCommitment),
+    vote_nullifier: bytesToBigInt(voteNullifier),
+    vote_commitment: bytesToBigInt(voteCommitment),
+    twab_amount: twabAmount,
+    weight,
+    token_mint: bytesToBigInt(tokenMint),
+    eligibility_root: eligibilityRoot,
+    has_eligibility: eligibilityRoot !== BigInt(0) ? BigInt(1) : BigInt(0),
+    vote_choice: revealMode === RevealMode.Public ? BigInt(params.voteChoice) : BigInt(0),
+    is_public_mode: revealMode === RevealMode.Public ? BigInt(1) : BigInt(0),
+
+    // Private inputs
+    amount,
+    in_stealth_pub_x: bytesToBigInt(params.stealthPubX),
+    in_randomness: bytesToBigInt(params.noteRandomness),
+    in_stealth_spending_key: bytesToBigInt(params.stealthSpendingKey),
+    held,
+    merkle_paths: merklePaths,
+    merkle_path_indices: merklePathIndices,
+    twab_remainder: twabRemainder,
+    vote_randomness: bytesToBigInt(voteRandomness),
+    eligibility_path: params.eligibilityProof?.merkleProof.map(s => BigInt(s)) || Array(20).fill(BigInt(0)),
+    eligibility_path_indices: params.eligibilityProof?.pathIndices.map(i => BigInt(i)) || Array(20).fill(BigInt(0)),
+    private_vote_choice: BigInt(params.voteChoice),
+  };
+
+  return { inputs, twabAmount, voteNullifier, voteCommitment, voteRandomness };
+}
+
 /**
  * Generate change_vote_snapshot proof inputs
  */
@@ -526,11 +638,16 @@ function parseEdDSASignature(sigHex: string): { r8x: bigint; r8y: bigint; s: big
 /**
  * Convert proof inputs with bigint values to snarkjs-compatible string format
  */
-export function convertInputsToSnarkjs(inputs: Record<string, bigint | bigint[]>): Record<string, string | string[]> {
-  const result: Record<string, string | string[]> = {};
+export function convertInputsToSnarkjs(
+  inputs: Record<string, bigint | bigint[] | bigint[][]>
+): Record<string, string | string[] | string[][]> {
+  const result: Record<string, string | string[] | string[][]> = {};
   for (const [key, value] of Object.entries(inputs)) {
     if (Array.isArray(value)) {
-      result[key] = value.map(v => v.toString());
+      // 2D arrays (e.g. vote_twab merkle_paths) convert element-wise
+      result[key] = (value as (bigint | bigint[])[]).map(v =>
+        Array.isArray(v) ? v.map(x => x.toString()) : v.toString()
+      ) as string[] | string[][];
     } else {
       result[key] = value.toString();
     }
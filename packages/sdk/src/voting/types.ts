@@ -32,12 +32,25 @@ export enum ResolutionMode {
   Authority = 2,
 }
 
+export enum TwabMode {
+  Disabled = 0,
+  Average = 1,
+}
+
+/** Who may add options while a ballot is open (write-ins) */
+export enum OptionRegistration {
+  Closed = 0,
+  Registrar = 1,
+  Open = 2,
+}
+
 export enum BallotStatus {
   Pending = 0,
   Active = 1,
   Closed = 2,
   Resolved = 3,
   Finalized = 4,
+  Disputed = 5,
 }
 
 // ============ Ballot Types ============
@@ -63,9 +76,20 @@ export interface BallotConfig {
   indexerPubkey?: PublicKey;      // For Snapshot mode
 
   eligibilityRoot?: Uint8Array;   // Merkle root of eligible addresses
+  twabMode?: TwabMode;            // For Snapshot mode (default Disabled)
+  twabNumRoots?: number;          // Roots averaged in TWAB mode (2-8)
+  twabIntervalSlots?: number;     // Min slots between TWAB root registrations
   timeLockPubkey?: Uint8Array;    // For encrypted modes
   unlockSlot?: number;            // For TimeLocked mode
   claimDeadline?: number;         // For SpendToVote mode
+  proposalBond?: bigint;          // Lamports locked by the creator (refunded on quorum)
+  optionRegistration?: OptionRegistration; // Write-ins (default Closed)
+  optionRegistrar?: PublicKey;    // For Registrar registration
+  maxOptions?: number;            // Cap on options added by registration
+  hideTurnout?: boolean;          // Encrypt turnout until decrypt (encrypted Snapshot only)
+  loserRefundBps?: number;        // Stake share losers claim back (SpendToVote only)
+  disputeWindowSeconds?: number;  // Oracle outcome dispute window (needs resolver)
+  disputeBond?: bigint;           // Lamports a challenger locks to dispute
 
   resolver?: PublicKey;           // For Authority mode
   oracle?: PublicKey;             // For Oracle mode
@@ -111,6 +135,13 @@ export interface Ballot {
 
   hasEligibilityRoot: boolean;
   eligibilityRoot: Uint8Array;
+  hasSnapshotRoot: boolean;
+  snapshotRoot: Uint8Array;       // Commitment root archived at snapshotSlot
+  lpVoteSources: LpVoteSource[];  // LP mints accepted for snapshot voting
+  twabMode: TwabMode;
+  twabNumRoots: number;
+  twabIntervalSlots: number;      // Min slots between TWAB root registrations
+  twabRoots: TwabRoot[];          // Registered TWAB roots, oldest first
   timeLockPubkey: Uint8Array;
   unlockSlot: number;
 
@@ -136,6 +167,40 @@ export interface Ballot {
   resolver?: PublicKey;
   oracle?: PublicKey;
   claimDeadline: number;
+
+  // Proposal bond
+  proposalBond: bigint;
+  bondSettled: boolean;
+
+  // Option registration (write-ins)
+  optionRegistration: OptionRegistration;
+  optionRegistrar: PublicKey;
+  maxOptions: number;
+
+  // Turnout privacy (voteCount and totalWeight stay 0 until decrypt)
+  hideTurnout: boolean;
+  encryptedVoteCount: Uint8Array;
+
+  // Loser refunds (SpendToVote; winners share the pool minus the refunds)
+  loserRefundBps: number;
+
+  // Oracle disputes (claims wait until resolvedAt + disputeWindowSeconds)
+  disputeWindowSeconds: number;
+  disputeBond: bigint;
+  resolvedAt: number;
+  challenger: PublicKey;
+}
+
+/** LP mint accepted for snapshot voting */
+export interface LpVoteSource {
+  lpMint: PublicKey;
+  rate: bigint;                   // Ballot token units per LP token (1e18 scale)
+}
+
+/** TWAB snapshot root with the slot it was registered at */
+export interface TwabRoot {
+  root: Uint8Array;
+  slot: number;
 }
 
 // ============ Vote Types ============
@@ -152,6 +217,21 @@ export interface VoteSnapshotParams {
   merklePath: Uint8Array[];           // Merkle proof path (32 levels)
   merklePathIndices: number[];        // Merkle proof indices (32 levels)
   eligibilityProof?: MerkleProof;
+  lpMint?: PublicKey;                 // Set when the note is a registered AMM/perps LP note
+}
+
+export interface VoteTwabParams {
+  ballotId: Uint8Array;
+  noteCommitment: Uint8Array;         // The shielded note being used for voting
+  noteAmount: bigint;                 // Amount in the note
+  noteRandomness: Uint8Array;         // Note randomness
+  stealthPubX: Uint8Array;            // User's stealth pubkey (x-coordinate)
+  stealthSpendingKey: Uint8Array;     // Spending key (proves ownership)
+  voteChoice: number;
+  // Per registered root: merkle proof if the note is in that root, null otherwise
+  rootProofs: ({ merklePath: Uint8Array[]; merklePathIndices: number[] } | null)[];
+  eligibilityProof?: MerkleProof;
+  lpMint?: PublicKey;                 // Set when the note is a registered AMM/perps LP note
 }
 
 export interface VoteSpendParams {
@@ -290,6 +370,37 @@ export interface VoteSnapshotProofInputs {
   private_vote_choice: bigint;        // For encrypted modes
 }
 
+export interface VoteTwabProofInputs {
+  // Public inputs (must match circuit order)
+  ballot_id: bigint;
+  twab_roots: bigint[];               // 8 roots, zero padded
+  num_roots: bigint;
+  note_commitment: bigint;
+  vote_nullifier: bigint;
+  vote_commitment: bigint;
+  twab_amount: bigint;                // Note amount averaged over the roots
+  weight: bigint;
+  token_mint: bigint;
+  eligibility_root: bigint;
+  has_eligibility: bigint;
+  vote_choice: bigint;
+  is_public_mode: bigint;
+
+  // Private inputs
+  amount: bigint;
+  in_stealth_pub_x: bigint;
+  in_randomness: bigint;
+  in_stealth_spending_key: bigint;
+  held: bigint[];                     // 1 if the note is in twab_roots[i]
+  merkle_paths: bigint[][];           // 8 x 24 levels
+  merkle_path_indices: bigint[][];    // 8 x 24 levels
+  twab_remainder: bigint;
+  vote_randomness: bigint;
+  eligibility_path: bigint[];         // 20 levels
+  eligibility_path_indices: bigint[]; // 20 levels
+  private_vote_choice: bigint;
+}
+
 export interface ClaimProofInputs {
   // Public inputs
   ballotId: bigint;
@@ -0,0 +1,461 @@
+/**
+ * Encrypted Wallet Store
+ *
+ * Persists decrypted notes, spent status, operation history and sync cursors
+ * so apps don't each reinvent note state management. Every record is
+ * encrypted at rest with XChaCha20-Poly1305 under a key derived from the
+ * spending key, and row keys are HMAC blind indexes so the database doesn't
+ * reveal which commitments belong to the wallet.
+ *
+ * Storage is pluggable: `MemoryWalletStoreBackend` for tests and ephemeral
+ * sessions, `SqliteWalletStoreBackend` for any better-sqlite3 compatible
+ * database (Node, React Native, sqlite-wasm).
+ */
+
+import { PublicKey } from '@solana/web3.js';
+import { hkdf } from '@noble/hashes/hkdf';
+import { hmac } from '@noble/hashes/hmac';
+import { sha256 } from '@noble/hashes/sha256';
+import { randomBytes } from '@noble/hashes/utils';
+import { xchacha20poly1305 } from '@noble/ciphers/chacha.js';
+import type { DecryptedNote, Keypair } from '@cloakcraft/types';
+import { deserializeEncryptedNote, tryDecryptNote } from './crypto/encryption';
+import { computeCommitment } from './crypto/commitment';
+import { deriveNullifierKey, deriveSpendingNullifier } from './crypto/nullifier';
+import { bytesToField } from './crypto/poseidon';
+import { TransactionStatus, type TransactionRecord } from './history';
+
+/** Indexer page size for commitment sync */
+const SYNC_PAGE_SIZE = 1000;
+
+/** Local spends not confirmed on-chain within this window are reverted */
+export const PENDING_SPEND_TTL_MS = 10 * 60 * 1000;
+
+const STORE_KEY_SALT = new TextEncoder().encode('cloakcraft-wallet-store');
+const ENCRYPTION_KEY_INFO = new TextEncoder().encode('encryption');
+const INDEX_KEY_INFO = new TextEncoder().encode('blind-index');
+
+/**
+ * Record tables
+ */
+export type WalletStoreTable = 'notes' | 'history' | 'meta';
+
+// =============================================================================
+// Backends
+// =============================================================================
+
+/**
+ * Key-value storage for encrypted records
+ */
+export interface WalletStoreBackend {
+  get(table: WalletStoreTable, key: string): Promise<Uint8Array | null>;
+  put(table: WalletStoreTable, key: string, value: Uint8Array): Promise<void>;
+  delete(table: WalletStoreTable, key: string): Promise<void>;
+  list(table: WalletStoreTable): Promise<Array<{ key: string; value: Uint8Array }>>;
+}
+
+/**
+ * In-memory backend
+ */
+export class MemoryWalletStoreBackend implements WalletStoreBackend {
+  private tables = new Map<WalletStoreTable, Map<string, Uint8Array>>();
+
+  private table(table: WalletStoreTable): Map<string, Uint8Array> {
+    let rows = this.tables.get(table);
+    if (!rows) {
+      rows = new Map();
+      this.tables.set(table, rows);
+    }
+    return rows;
+  }
+
+  async get(table: WalletStoreTable, key: string): Promise<Uint8Array | null> {
+    return this.table(table).get(key) ?? null;
+  }
+
+  async put(table: WalletStoreTable, key: string, value: Uint8Array): Promise<void> {
+    this.table(table).set(key, value);
+  }
+
+  async delete(table: WalletStoreTable, key: string): Promise<void> {
+    this.table(table).delete(key);
+  }
+
+  async list(table: WalletStoreTable): Promise<Array<{ key: string; value: Uint8Array }>> {
+    return Array.from(this.table(table), ([key, value]) => ({ key, value }));
+  }
+}
+
+/**
+ * Minimal better-sqlite3 compatible database
+ */
+export interface SqliteDatabase {
+  exec(sql: string): unknown;
+  prepare(sql: string): {
+    run(...params: unknown[]): unknown;
+    get(...params: unknown[]): unknown;
+    all(...params: unknown[]): unknown[];
+  };
+}
+
+/**
+ * SQLite backend (single `wallet_store` table)
+ */
+export class SqliteWalletStoreBackend implements WalletStoreBackend {
+  constructor(private db: SqliteDatabase) {
+    db.exec(`
+      CREATE TABLE IF NOT EXISTS wallet_store (
+        tbl TEXT NOT NULL,
+        key TEXT NOT NULL,
+        value BLOB NOT NULL,
+        PRIMARY KEY (tbl, key)
+      )
+    `);
+  }
+
+  async get(table: WalletStoreTable, key: string): Promise<Uint8Array | null> {
+    const row = this.db
+      .prepare('SELECT value FROM wallet_store WHERE tbl = ? AND key = ?')
+      .get(table, key) as { value: Uint8Array } | undefined;
+    return row ? new Uint8Array(row.value) : null;
+  }
+
+  async put(table: WalletStoreTable, key: string, value: Uint8Array): Promise<void> {
+    this.db
+      .prepare('INSERT OR REPLACE INTO wallet_store (tbl, key, value) VALUES (?, ?, ?)')
+      .run(table, key, Buffer.from(value));
+  }
+
+  async delete(table: WalletStoreTable, key: string): Promise<void> {
+    this.db.prepare('DELETE FROM wallet_store WHERE tbl = ? AND key = ?').run(table, key);
+  }
+
+  async list(table: WalletStoreTable): Promise<Array<{ key: string; value: Uint8Array }>> {
+    const rows = this.db
+      .prepare('SELECT key, value FROM wallet_store WHERE tbl = ?')
+      .all(table) as Array<{ key: string; value: Uint8Array }>;
+    return rows.map(row => ({ key: row.key, value: new Uint8Array(row.value) }));
+  }
+}
+
+// =============================================================================
+// Records
+// =============================================================================
+
+/**
+ * Stored note with spent status
+ */
+export interface StoredNote {
+  note: DecryptedNote;
+  /** Spending nullifier (hex) */
+  nullifier: string;
+  /** Confirmed spent on-chain */
+  spent: boolean;
+  /** Spent locally, awaiting on-chain confirmation (ms timestamp) */
+  pendingSpendAt?: number;
+  /** Last update (ms timestamp) */
+  updatedAt: number;
+}
+
+/**
+ * Resolve two versions of the same note
+ *
+ * On-chain spent status is monotonic, so spent wins. A pending local spend
+ * is kept over an unspent copy until it expires.
+ */
+export function resolveNoteConflict(a: StoredNote, b: StoredNote, now: number = Date.now()): StoredNote {
+  const newer = a.updatedAt >= b.updatedAt ? a : b;
+  const spent = a.spent || b.spent;
+  const pendingSpendAt = spent
+    ? undefined
+    : [a.pendingSpendAt, b.pendingSpendAt]
+        .filter((t): t is number => t !== undefined && now - t < PENDING_SPEND_TTL_MS)
+        .sort((x, y) => y - x)[0];
+  return { ...newer, spent, pendingSpendAt, updatedAt: Math.max(a.updatedAt, b.updatedAt) };
+}
+
+const STATUS_RANK: Record<TransactionStatus, number> = {
+  [TransactionStatus.PENDING]: 0,
+  [TransactionStatus.FAILED]: 1,
+  [TransactionStatus.CONFIRMED]: 2,
+};
+
+/**
+ * Resolve two versions of the same history record
+ *
+ * A settled status (confirmed, then failed) beats pending; otherwise the
+ * later timestamp wins.
+ */
+export function resolveHistoryConflict(a: TransactionRecord, b: TransactionRecord): TransactionRecord {
+  const rankA = STATUS_RANK[a.status] ?? 0;
+  const rankB = STATUS_RANK[b.status] ?? 0;
+  if (rankA !== rankB) {
+    return rankA > rankB ? a : b;
+  }
+  return a.timestamp >= b.timestamp ? a : b;
+}
+
+// Typed JSON: bigint, Uint8Array and PublicKey survive a round trip
+function encodeJson(value: unknown): Uint8Array {
+  const json = JSON.stringify(value, function (key, v) {
+    const raw = (this as any)[key];
+    if (typeof raw === 'bigint') return { $bigint: raw.toString() };
+    if (raw instanceof Uint8Array) return { $bytes: Buffer.from(raw).toString('hex') };
+    if (raw instanceof PublicKey) return { $pubkey: raw.toBase58() };
+    return v;
+  });
+  return new TextEncoder().encode(json);
+}
+
+function decodeJson<T>(bytes: Uint8Array): T {
+  return JSON.parse(new TextDecoder().decode(bytes), (_key, v) => {
+    if (v && typeof v === 'object') {
+      if (typeof v.$bigint === 'string') return BigInt(v.$bigint);
+      if (typeof v.$bytes === 'string') return new Uint8Array(Buffer.from(v.$bytes, 'hex'));
+      if (typeof v.$pubkey === 'string') return new PublicKey(v.$pubkey);
+    }
+    return v;
+  });
+}
+
+// =============================================================================
+// Store
+// =============================================================================
+
+/**
+ * Derive the store key from a spending key
+ */
+export function deriveWalletStoreKey(spendingKey: Uint8Array): Uint8Array {
+  return hkdf(sha256, spendingKey, STORE_KEY_SALT, undefined, 32);
+}
+
+/**
+ * Encrypted local wallet state with incremental indexer sync
+ */
+export class WalletStore {
+  private encryptionKey: Uint8Array;
+  private indexKey: Uint8Array;
+
+  constructor(
+    private backend: WalletStoreBackend,
+    storeKey: Uint8Array,
+    private indexerUrl?: string
+  ) {
+    if (storeKey.length !== 32) {
+      throw new Error('Wallet store key must be 32 bytes');
+    }
+    this.encryptionKey = hkdf(sha256, storeKey, undefined, ENCRYPTION_KEY_INFO, 32);
+    this.indexKey = hkdf(sha256, storeKey, undefined, INDEX_KEY_INFO, 32);
+  }
+
+  /**
+   * Open a store keyed by the wallet's spending key
+   */
+  static forKeypair(backend: WalletStoreBackend, keypair: Keypair, indexerUrl?: string): WalletStore {
+    return new WalletStore(backend, deriveWalletStoreKey(keypair.spending.sk), indexerUrl);
+  }
+
+  private blindKey(table: WalletStoreTable, id: string): string {
+    return Buffer.from(hmac(sha256, this.indexKey, new TextEncoder().encode(`${table}:${id}`))).toString('hex');
+  }
+
+  private async read<T>(table: WalletStoreTable, id: string): Promise<T | null> {
+    const key = this.blindKey(table, id);
+    const sealed = await this.backend.get(table, key);
+    return sealed ? this.open<T>(table, key, sealed) : null;
+  }
+
+  private async write(table: WalletStoreTable, id: string, value: unknown): Promise<void> {
+    const key = this.blindKey(table, id);
+    await this.backend.put(table, key, this.seal(table, key, value));
+  }
+
+  private async readAll<T>(table: WalletStoreTable): Promise<T[]> {
+    const rows = await this.backend.list(table);
+    return rows.map(row => this.open<T>(table, row.key, row.value));
+  }
+
+  // Row key is bound as associated data so ciphertexts can't be swapped
+  private seal(table: WalletStoreTable, key: string, value: unknown): Uint8Array {
+    const nonce = randomBytes(24);
+    const aad = new TextEncoder().encode(`${table}:${key}`);
+    const ciphertext = xchacha20poly1305(this.encryptionKey, nonce, aad).encrypt(encodeJson(value));
+    const sealed = new Uint8Array(nonce.length + ciphertext.length);
+    sealed.set(nonce);
+    sealed.set(ciphertext, nonce.length);
+    return sealed;
+  }
+
+  private open<T>(table: WalletStoreTable, key: string, sealed: Uint8Array): T {
+    const aad = new TextEncoder().encode(`${table}:${key}`);
+    const plaintext = xchacha20poly1305(this.encryptionKey, sealed.slice(0, 24), aad).decrypt(sealed.slice(24));
+    return decodeJson<T>(plaintext);
+  }
+
+  // ===========================================================================
+  // Notes
+  // ===========================================================================
+
+  /**
+   * Insert or merge a note (see `resolveNoteConflict`)
+   */
+  async putNote(record: StoredNote): Promise<StoredNote> {
+    const id = Buffer.from(record.note.commitment).toString('hex');
+    const existing = await this.read<StoredNote>('notes', id);
+    const merged = existing ? resolveNoteConflict(existing, record) : record;
+    await this.write('notes', id, merged);
+    return merged;
+  }
+
+  async getNotes(): Promise<StoredNote[]> {
+    return this.readAll<StoredNote>('notes');
+  }
+
+  /**
+   * Unspent notes, excluding live pending spends
+   */
+  async getUnspentNotes(tokenMint?: PublicKey, now: number = Date.now()): Promise<DecryptedNote[]> {
+    return (await this.getNotes())
+      .filter(r => !r.spent)
+      .filter(r => r.pendingSpendAt === undefined || now - r.pendingSpendAt >= PENDING_SPEND_TTL_MS)
+      .filter(r => !tokenMint || r.note.tokenMint.equals(tokenMint))
+      .map(r => r.note);
+  }
+
+  async getBalance(tokenMint: PublicKey): Promise<bigint> {
+    const notes = await this.getUnspentNotes(tokenMint);
+    return notes.reduce((sum, note) => sum + note.amount, 0n);
+  }
+
+  /**
+   * Mark notes as spent locally (pending until sync confirms the nullifier)
+   */
+  async markPendingSpend(commitments: Uint8Array[], now: number = Date.now()): Promise<void> {
+    for (const commitment of commitments) {
+      const existing = await this.read<StoredNote>('notes', Buffer.from(commitment).toString('hex'));
+      if (existing && !existing.spent) {
+        await this.putNote({ ...existing, pendingSpendAt: now, updatedAt: now });
+      }
+    }
+  }
+
+  // ===========================================================================
+  // History
+  // ===========================================================================
+
+  /**
+   * Insert or merge a history record (see `resolveHistoryConflict`)
+   */
+  async putHistory(record: TransactionRecord): Promise<TransactionRecord> {
+    const existing = await this.read<TransactionRecord>('history', record.id);
+    const merged = existing ? resolveHistoryConflict(existing, record) : record;
+    await this.write('history', record.id, merged);
+    return merged;
+  }
+
+  /**
+   * History, newest first
+   */
+  async getHistory(limit?: number): Promise<TransactionRecord[]> {
+    const records = await this.readAll<TransactionRecord>('history');
+    records.sort((a, b) => b.timestamp.localeCompare(a.timestamp));
+    return limit === undefined ? records : records.slice(0, limit);
+  }
+
+  // ===========================================================================
+  // Sync
+  // ===========================================================================
+
+  /** Next leaf index to fetch for a pool */
+  async getSyncCursor(pool: PublicKey): Promise<number> {
+    return (await this.read<number>('meta', `cursor:${pool.toBase58()}`)) ?? 0;
+  }
+
+  private async setSyncCursor(pool: PublicKey, leafIndex: number): Promise<void> {
+    await this.write('meta', `cursor:${pool.toBase58()}`, leafIndex);
+  }
+
+  private async fetchIndexer(path: string): Promise<any> {
+    if (!this.indexerUrl) {
+      throw new Error('WalletStore has no indexer URL');
+    }
+    const response = await fetch(`${this.indexerUrl}${path}`);
+    if (!response.ok) {
+      throw new Error(`Indexer request ${path} failed: HTTP ${response.status}`);
+    }
+    return response.json();
+  }
+
+  /**
+   * Fetch new commitments for each pool since the stored cursor, store the
+   * ones that decrypt to this wallet, then refresh spent status
+   *
+   * Returns the newly discovered notes.
+   */
+  async sync(keypair: Keypair, pools: PublicKey[]): Promise<DecryptedNote[]> {
+    const privateKey = bytesToField(keypair.spending.sk);
+    const nullifierKey = deriveNullifierKey(keypair.spending.sk);
+    const discovered: DecryptedNote[] = [];
+
+    for (const pool of pools) {
+      const poolHex = Buffer.from(pool.toBytes()).toString('hex');
+      let cursor = await this.getSyncCursor(pool);
+
+      for (;;) {
+        const page: Array<{ commitment: string; leaf_index: number; encrypted_note: string | null }> =
+          await this.fetchIndexer(`/commitments?pool_id=${poolHex}&since_index=${cursor}&limit=${SYNC_PAGE_SIZE}`);
+
+        for (const entry of page) {
+          // Archived notes can't be trial-decrypted from the indexer
+          const encrypted = entry.encrypted_note
+            ? deserializeEncryptedNote(new Uint8Array(Buffer.from(entry.encrypted_note, 'hex')))
+            : null;
+          const decrypted = encrypted ? tryDecryptNote(encrypted, privateKey) : null;
+          if (decrypted && Buffer.from(computeCommitment(decrypted)).toString('hex') === entry.commitment) {
+            const note: DecryptedNote = {
+              ...decrypted,
+              commitment: computeCommitment(decrypted),
+              leafIndex: entry.leaf_index,
+              pool,
+            };
+            const nullifier = deriveSpendingNullifier(nullifierKey, note.commitment, note.leafIndex);
+            await this.putNote({
+              note,
+              nullifier: Buffer.from(nullifier).toString('hex'),
+              spent: false,
+              updatedAt: Date.now(),
+            });
+            discovered.push(note);
+          }
+          cursor = Math.max(cursor, entry.leaf_index + 1);
+        }
+
+        await this.setSyncCursor(pool, cursor);
+        if (page.length < SYNC_PAGE_SIZE) {
+          break;
+        }
+      }
+    }
+
+    await this.refreshSpent();
+    return discovered;
+  }
+
+  /**
+   * Check unspent notes' nullifiers against the indexer
+   */
+  async refreshSpent(): Promise<number> {
+    let newlySpent = 0;
+    for (const record of await this.getNotes()) {
+      if (record.spent) {
+        continue;
+      }
+      const { spent } = await this.fetchIndexer(`/nullifier/${record.nullifier}`);
+      if (spent) {
+        await this.putNote({ ...record, spent: true, pendingSpendAt: undefined, updatedAt: Date.now() });
+        newlySpent++;
+      }
+    }
+    return newlySpent;
+  }
+}
@@ -2,10 +2,11 @@
  * Wallet management for CloakCraft
  */
 
-import type { Keypair, SpendingKey, ViewingKey, Point } from '@cloakcraft/types';
+import type { Keypair, SpendingKey, ViewingKey, Point, StealthAddress } from '@cloakcraft/types';
 import { derivePublicKey } from './crypto/babyjubjub';
 import { deriveNullifierKey } from './crypto/nullifier';
 import { poseidonHashDomain, bytesToField, fieldToBytes } from './crypto/poseidon';
+import { deriveChangeStealthAddress } from './crypto/stealth';
 
 // BabyJubJub subgroup order
 const SUBGROUP_ORDER = 2736030358979909402780800718157159386076813972158567259200215660948447373041n;
@@ -44,6 +45,16 @@ export class Wallet {
     return this.keypair.publicKey;
   }
 
+  /**
+   * Get the stealth address for change from spending a note
+   *
+   * Deterministic per spent note (see `deriveChangeStealthAddress`); use
+   * `slot` for a spend's second change output.
+   */
+  changeAddress(spentCommitment: Uint8Array, slot = 0): StealthAddress {
+    return deriveChangeStealthAddress(this.keypair, spentCommitment, slot);
+  }
+
   /**
    * Export spending key as bytes (for backup)
    */
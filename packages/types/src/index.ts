@@ -170,6 +170,8 @@ export interface StealthAddress {
   stealthPubkey: Point;
   /** Ephemeral public key */
   ephemeralPubkey: Point;
+  /** View tag for note discovery (sender side only, stored with the commitment) */
+  viewTag?: Uint8Array;
 }
 
 // =============================================================================
@@ -323,6 +325,14 @@ export interface TransferParams {
   };
   /** Protocol fee amount (required for proof generation) */
   fee?: bigint;
+  /** Pool's fixed denominations (zero-padded to 8); proves with transfer_1x2_denom */
+  denominations?: bigint[];
+  /** NFT pool's metadata hash; proves with transfer_1x2_nft */
+  nftMetadataHash?: Uint8Array;
+  /** Call or recipient hash of the unshield; proves with transfer_1x2_invoke */
+  callHash?: Uint8Array;
+  /** Invoice reference id; writes a PaymentReceipt for the first output */
+  paymentReference?: Uint8Array;
   /** Optional progress callback for UI updates */
   onProgress?: (stage: TransferProgressStage) => void;
 }
@@ -353,6 +363,25 @@ export interface AmmSwapParams {
   changeRecipient: StealthAddress;
   /** Fee in basis points */
   feeBps?: number;
+  /** Salt for a sealed swap (proves with swap_sealed, binding the terms hash) */
+  termsSalt?: Uint8Array;
+  /**
+   * Salt for a committed swap (proves with swap_committed: amounts, direction
+   * and minOutput are only public as a hash until Phase 3)
+   */
+  commitSalt?: Uint8Array;
+  /**
+   * Swap volume record credited with this swap (fee rebate tiers). The
+   * record's volume tag is bound by the proof.
+   */
+  swapVolume?: {
+    /** Fee rebate config PDA of the AMM pool */
+    feeRebateConfig: PublicKey;
+    /** Swap volume PDA for the current epoch */
+    record: PublicKey;
+    /** Blinded volume tag of the record */
+    volumeTag: Uint8Array;
+  };
   /** Merkle root for input note */
   merkleRoot: Uint8Array;
   /** Merkle path elements (siblings) */
@@ -856,6 +885,11 @@ export interface OpenPerpsPositionParams {
   merklePath: Uint8Array[];
   /** Merkle path indices */
   merkleIndices: number[];
+  /**
+   * Salt for a sealed open (proves with open_position_sealed: margin,
+   * leverage and fee are only public as a hash until Phase 3)
+   */
+  termsSalt?: Uint8Array;
   /** Progress callback */
   onProgress?: (stage: PerpsProgressStage) => void;
 }
@@ -974,6 +1008,10 @@ export interface PositionMeta {
   updatedAt: number;
   /** Owner's stealth address (for notifications) */
   ownerStealthPubkey: Uint8Array;
+  /** Borrow fee accumulator snapshot at open (1e18 scale) */
+  entryBorrowFee: bigint;
+  /** Position commitment this metadata describes */
+  positionCommitment: Uint8Array;
 }
 
 /** Remove perps liquidity client parameters */
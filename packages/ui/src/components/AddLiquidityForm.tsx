@@ -197,8 +197,8 @@ export function AddLiquidityForm({
     try {
       // Generate stealth addresses for outputs
       const { stealthAddress: lpAddress } = generateStealthAddress(wallet.publicKey);
-      const { stealthAddress: changeAAddress } = generateStealthAddress(wallet.publicKey);
-      const { stealthAddress: changeBAddress } = generateStealthAddress(wallet.publicKey);
+      const changeAAddress = wallet.changeAddress(selectedNotesA[0].commitment);
+      const changeBAddress = wallet.changeAddress(selectedNotesB[0].commitment);
 
       // Call the SDK's addLiquidity method
       const result = await client.addLiquidity({
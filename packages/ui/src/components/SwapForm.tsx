@@ -222,7 +222,7 @@ export function SwapForm({
     }
 
     const { stealthAddress: outputAddress } = generateStealthAddress(wallet.publicKey);
-    const { stealthAddress: changeAddress } = generateStealthAddress(wallet.publicKey);
+    const changeAddress = wallet.changeAddress(selectedNotes[0].commitment);
 
     if (!selectedAmmPool) {
       onError?.('No AMM pool found for this token pair');
@@ -120,7 +120,7 @@ export function TransferForm({
 
     // Add change output back to self if needed
     if (change > 0n && wallet) {
-      const { stealthAddress: changeAddress } = generateStealthAddress(wallet.publicKey);
+      const changeAddress = wallet.changeAddress(selectedNotes[0].commitment);
       outputs.push({ recipient: changeAddress, amount: change });
     }
 
@@ -9,6 +9,32 @@ pub const HISTORICAL_ROOTS_COUNT: usize = 30;
 /// Maximum number of leaves in merkle tree
 pub const MAX_LEAVES: u32 = 65536;
 
+/// Pool activity epoch length for anonymity-set metrics (1 day)
+pub const ANONYMITY_EPOCH_SECONDS: i64 = 86_400;
+
+/// SPL Memo program (memos on unshields, e.g. exchange deposit tags)
+pub const SPL_MEMO_PROGRAM_ID: anchor_lang::prelude::Pubkey =
+    anchor_lang::pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Metaplex Token Metadata program (NFT metadata, pNFT token records)
+pub const TOKEN_METADATA_PROGRAM_ID: anchor_lang::prelude::Pubkey =
+    anchor_lang::pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Metaplex Bubblegum program (compressed NFTs)
+pub const BUBBLEGUM_PROGRAM_ID: anchor_lang::prelude::Pubkey =
+    anchor_lang::pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+
+/// SPL Account Compression program (cNFT merkle trees)
+pub const SPL_ACCOUNT_COMPRESSION_PROGRAM_ID: anchor_lang::prelude::Pubkey =
+    anchor_lang::pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+/// SPL Noop program (account compression log wrapper)
+pub const SPL_NOOP_PROGRAM_ID: anchor_lang::prelude::Pubkey =
+    anchor_lang::pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+/// Maximum unshield memo length in bytes
+pub const MAX_UNSHIELD_MEMO_LEN: usize = 256;
+
 /// Domain separators for Poseidon hashes
 pub mod domains {
     pub const COMMITMENT: u64 = 0x01;
@@ -32,31 +58,67 @@ pub mod domains {
     /// position_nullifier = hash(POSITION, nullifier_key, position_commitment)
     /// Used for close_position and claim
     pub const POSITION: u64 = 0x13;
+
+    /// buyback note randomness = hash(BUYBACK_RANDOMNESS, stealth_pub_x, nonce)
+    /// Lets stakers rebuild their distribution notes from public data
+    pub const BUYBACK_RANDOMNESS: u64 = 0x14;
+
+    // Operation credit domains
+    /// credit leaf = hash(OPERATION_CREDIT, credit_secret)
+    pub const OPERATION_CREDIT: u64 = 0x15;
+    /// credit_nullifier = hash(CREDIT_NULLIFIER, credit_secret)
+    /// ONE per credit, revealed when the credit is spent
+    pub const CREDIT_NULLIFIER: u64 = 0x16;
 }
 
 /// Circuit IDs for verification key lookup
 pub mod circuits {
     pub const TRANSFER_1X2: [u8; 32] = *b"transfer_1x2____________________";
+    /// Transfer for fixed-denomination pools (outputs constrained to the pool's denominations)
+    pub const TRANSFER_1X2_DENOM: [u8; 32] = *b"transfer_1x2_denom______________";
+    /// Transfer with unshield-and-invoke (adds the call hash as a public input)
+    pub const TRANSFER_1X2_INVOKE: [u8; 32] = *b"transfer_1x2_invoke_____________";
+    /// Transfer for NFT pools (amounts constrained to 0/1, adds the metadata hash as a public input)
+    pub const TRANSFER_1X2_NFT: [u8; 32] = *b"transfer_1x2_nft________________";
     pub const CONSOLIDATE_3X1: [u8; 32] = *b"consolidate_3x1_________________";
     pub const ADAPTER_1X1: [u8; 32] = *b"adapter_1x1_____________________";
     pub const ADAPTER_1X2: [u8; 32] = *b"adapter_1x2_____________________";
     pub const MARKET_ORDER_CREATE: [u8; 32] = *b"market_order_create_____________";
     pub const MARKET_ORDER_FILL: [u8; 32] = *b"market_order_fill_______________";
     pub const MARKET_ORDER_CANCEL: [u8; 32] = *b"market_order_cancel_____________";
+    pub const MARKET_ORDER_MODIFY: [u8; 32] = *b"market_order_modify_____________";
+    /// Opt an order into escrow yield (proves the escrow amount)
+    pub const MARKET_ESCROW_YIELD_OPT_IN: [u8; 32] = *b"market_escrow_yield_opt_in______";
+    /// Claim escrow yield as a bonus note (knowledge of yield_key behind the tag)
+    pub const MARKET_ESCROW_YIELD_CLAIM: [u8; 32] = *b"market_escrow_yield_claim_______";
     pub const SWAP_ADD_LIQUIDITY: [u8; 32] = *b"swap_add_liquidity______________";
     pub const SWAP_REMOVE_LIQUIDITY: [u8; 32] = *b"swap_remove_liquidity___________";
     pub const SWAP_SWAP: [u8; 32] = *b"swap_swap_______________________";
+    /// Swap that also binds a hash of its hidden terms (sealed swaps)
+    pub const SWAP_SEALED: [u8; 32] = *b"swap_sealed_____________________";
+    /// Swap whose terms, min_output included, are only public as a hash (committed swaps)
+    pub const SWAP_COMMITTED: [u8; 32] = *b"swap_committed__________________";
+    /// Convert an LP note of a migrated AMM pool into the successor pool's LP
+    pub const SWAP_CONVERT_LP: [u8; 32] = *b"swap_convert_lp_________________";
 
     // Perpetual futures circuits
     pub const PERPS_OPEN_POSITION: [u8; 32] = *b"perps_open_position_____________";
+    /// Open position whose margin, leverage and fee are only public as a hash (sealed opens)
+    pub const PERPS_OPEN_POSITION_SEALED: [u8; 32] = *b"perps_open_position_sealed______";
     pub const PERPS_CLOSE_POSITION: [u8; 32] = *b"perps_close_position____________";
     pub const PERPS_LIQUIDATE: [u8; 32] = *b"perps_liquidate_________________";
     pub const PERPS_ADD_LIQUIDITY: [u8; 32] = *b"perps_add_liquidity_____________";
     pub const PERPS_REMOVE_LIQUIDITY: [u8; 32] = *b"perps_remove_liquidity__________";
+    /// Re-commit a position to a new stealth owner with unchanged terms
+    pub const PERPS_TRANSFER_POSITION: [u8; 32] = *b"perps_transfer_position_________";
+    /// Convert an LP note of a migrated pool into the new pool's LP
+    pub const PERPS_CONVERT_LP: [u8; 32] = *b"perps_convert_lp________________";
 
     // Voting circuits
     /// Snapshot mode first vote circuit
     pub const VOTE_SNAPSHOT: [u8; 32] = *b"vote_snapshot___________________";
+    /// Snapshot mode time-weighted (TWAB) vote circuit
+    pub const VOTE_TWAB: [u8; 32] = *b"vote_twab_______________________";
     /// Snapshot mode vote change circuit (atomic close+new)
     pub const CHANGE_VOTE_SNAPSHOT: [u8; 32] = *b"change_vote_snapshot____________";
     /// SpendToVote mode voting circuit
@@ -67,6 +129,27 @@ pub mod circuits {
     pub const CLOSE_POSITION: [u8; 32] = *b"close_position__________________";
     /// SpendToVote mode claim circuit
     pub const CLAIM: [u8; 32] = *b"claim___________________________";
+    /// SpendToVote mode claim of up to 3 positions into one payout note
+    pub const CLAIM_MULTI: [u8; 32] = *b"claim_multi_____________________";
+    /// SpendToVote mode loser refund circuit
+    pub const CLAIM_REFUND: [u8; 32] = *b"claim_refund____________________";
+
+    // Emissions circuits
+    /// Liquidity mining rewards claim circuit
+    pub const CLAIM_REWARDS: [u8; 32] = *b"claim_rewards___________________";
+
+    // Fee rebate circuits
+    /// Swap fee rebate claim circuit
+    pub const FEE_REBATE_CLAIM: [u8; 32] = *b"fee_rebate_claim________________";
+
+    // Quadratic funding circuits
+    /// Donation to a matching round project (encrypted sqrt contribution)
+    pub const DONATE: [u8; 32] = *b"donate__________________________";
+
+    // Rate limiting circuits
+    /// Spend an operation credit (membership in the epoch's credit tree,
+    /// nullifier bound to one operation ID)
+    pub const OPERATION_CREDIT: [u8; 32] = *b"operation_credit________________";
 }
 
 /// PDA seeds
@@ -76,11 +159,29 @@ pub mod seeds {
     pub const ORDER: &[u8] = b"order";
     pub const AMM_POOL: &[u8] = b"amm_pool";
     pub const LP_MINT: &[u8] = b"lp_mint";
+    pub const LP_LOCK: &[u8] = b"lp_lock";
+    /// AMM pool migration PDA seed: ["amm_migration", old_amm_pool]
+    pub const AMM_MIGRATION: &[u8] = b"amm_migration";
+    pub const POOL_CREATOR_ALLOWLIST: &[u8] = b"pool_creator_allowlist";
+    pub const RELAYER_ALLOWLIST: &[u8] = b"relayer_allowlist";
+    pub const RELAYER_STAKE: &[u8] = b"relayer_stake";
     pub const AGGREGATION: &[u8] = b"aggregation";
     pub const VERIFICATION_KEY: &[u8] = b"vk";
     pub const ADAPT_MODULE: &[u8] = b"adapt";
     pub const COMMITTEE: &[u8] = b"committee";
     pub const PROTOCOL_CONFIG: &[u8] = b"protocol_config";
+    pub const PROGRAM_VERSION: &[u8] = b"program_version";
+    /// Dust sweep ledger PDA seed: ["dust_sweep_ledger"]
+    pub const DUST_SWEEP_LEDGER: &[u8] = b"dust_sweep_ledger";
+    /// Buyback config PDA seed: ["buyback_config"]
+    pub const BUYBACK_CONFIG: &[u8] = b"buyback_config";
+    /// Stake registry PDA seed: ["stake_registry"]
+    pub const STAKE_REGISTRY: &[u8] = b"stake_registry";
+    /// Stake vault PDA seed: ["stake_vault"]
+    pub const STAKE_VAULT: &[u8] = b"stake_vault";
+    /// Treasury fee converter PDA seed: ["treasury_converter"]
+    /// (SPL delegate of the treasury's fee token accounts)
+    pub const TREASURY_CONVERTER: &[u8] = b"treasury_converter";
 
     // Perpetual futures seeds
     pub const PERPS_POOL: &[u8] = b"perps_pool";
@@ -88,12 +189,87 @@ pub mod seeds {
     pub const PERPS_POSITION_MINT: &[u8] = b"perps_pos_mint";
     pub const PERPS_VAULT: &[u8] = b"perps_vault";
     pub const PERPS_MARKET: &[u8] = b"perps_market";
+    pub const PERP_ORDER: &[u8] = b"perp_order";
+    /// Borrow fee history PDA seed: ["borrow_fee_history", perps_pool]
+    pub const BORROW_FEE_HISTORY: &[u8] = b"borrow_fee_history";
+    /// Perps pool migration PDA seed: ["perps_migration", old_perps_pool]
+    pub const PERPS_MIGRATION: &[u8] = b"perps_migration";
 
     // Voting seeds
     /// Ballot PDA seed: ["ballot", ballot_id]
     pub const BALLOT: &[u8] = b"ballot";
     /// Ballot vault PDA seed: ["ballot_vault", ballot_id]
     pub const BALLOT_VAULT: &[u8] = b"ballot_vault";
+    /// Snapshot tree PDA seed: ["snapshot_tree", ballot_id]
+    pub const SNAPSHOT_TREE: &[u8] = b"snapshot_tree";
+    /// Ballot options page PDA seed: ["ballot_options", ballot_id, page]
+    pub const BALLOT_OPTIONS: &[u8] = b"ballot_options";
+
+    // Emissions seeds
+    /// Emissions schedule PDA seed: ["emissions", source_pool]
+    pub const EMISSIONS: &[u8] = b"emissions";
+    /// Emissions reward vault PDA seed: ["emissions_vault", schedule]
+    pub const EMISSIONS_VAULT: &[u8] = b"emissions_vault";
+    /// LP reward checkpoint PDA seed: ["reward_checkpoint", schedule, lp_commitment]
+    pub const REWARD_CHECKPOINT: &[u8] = b"reward_checkpoint";
+
+    // Savings vault seeds
+    /// Savings vault PDA seed: ["savings_vault", source_pool]
+    pub const SAVINGS_VAULT: &[u8] = b"savings_vault";
+    /// Vault share mint PDA seed: ["vault_share_mint", vault]
+    pub const VAULT_SHARE_MINT: &[u8] = b"vault_share_mint";
+
+    // Operation credit seeds
+    /// Credit config PDA seed: ["credit_config"]
+    pub const CREDIT_CONFIG: &[u8] = b"credit_config";
+    /// Credit tree PDA seed: ["credit_tree", epoch (LE)]
+    pub const CREDIT_TREE: &[u8] = b"credit_tree";
+    /// Stake-backed credit issuance PDA seed: ["credit_issuance", staker]
+    pub const CREDIT_ISSUANCE: &[u8] = b"credit_issuance";
+    /// Spent credit PDA seed: ["credit_nullifier", credit_nullifier]
+    pub const CREDIT_NULLIFIER: &[u8] = b"credit_nullifier";
+
+    // Tree registry seeds
+    /// Tree registry PDA seed (singleton)
+    pub const TREE_REGISTRY: &[u8] = b"tree_registry";
+
+    // Fee rebate seeds
+    /// Fee rebate config PDA seed: ["fee_rebate", amm_pool]
+    pub const FEE_REBATE: &[u8] = b"fee_rebate";
+    /// Fee rebate vault PDA seed: ["fee_rebate_vault", config]
+    pub const FEE_REBATE_VAULT: &[u8] = b"fee_rebate_vault";
+    /// Swap volume PDA seed: ["swap_volume", config, epoch, volume_tag]
+    pub const SWAP_VOLUME: &[u8] = b"swap_volume";
+
+    // Escrow yield seeds
+    /// Escrow yield policy PDA seed: ["escrow_yield", pool]
+    pub const ESCROW_YIELD: &[u8] = b"escrow_yield";
+    /// Order yield PDA seed: ["order_yield", order_id]
+    pub const ORDER_YIELD: &[u8] = b"order_yield";
+
+    // Quadratic funding seeds
+    /// Matching round PDA seed: ["matching_round", round_id]
+    pub const MATCHING_ROUND: &[u8] = b"matching_round";
+    /// Matching pool vault PDA seed: ["matching_vault", round]
+    pub const MATCHING_VAULT: &[u8] = b"matching_vault";
+
+    // Root checkpoint seeds
+    /// Root checkpoint history PDA seed: ["root_checkpoints", pool]
+    pub const ROOT_CHECKPOINTS: &[u8] = b"root_checkpoints";
+    /// Root registry PDA seed: ["root_registry", pool]
+    pub const ROOT_REGISTRY: &[u8] = b"root_registry";
+
+    // Pool stats seeds
+    /// Pool statistics PDA seed: ["pool_stats", pool]
+    pub const POOL_STATS: &[u8] = b"pool_stats";
+
+    // Circuit stats seeds
+    /// Circuit statistics PDA seed: ["circuit_stats", circuit_id]
+    pub const CIRCUIT_STATS: &[u8] = b"circuit_stats";
+
+    // Compressed NFT seeds
+    /// cNFT custody PDA seed: ["cnft_custody"] (leaf owner of shielded cNFTs)
+    pub const CNFT_CUSTODY: &[u8] = b"cnft_custody";
 }
 
 /// Operation types for pending operations
@@ -103,6 +279,8 @@ pub mod operation_types {
     pub const ADD_LIQUIDITY: u8 = 2;
     pub const REMOVE_LIQUIDITY: u8 = 3;
     pub const CONSOLIDATE: u8 = 4;
+    /// Migrated AMM pool LP note conversion (no execute phase)
+    pub const AMM_CONVERT_LP: u8 = 5;
 
     // Perpetual futures operation types
     pub const PERPS_OPEN_POSITION: u8 = 10;
@@ -110,6 +288,9 @@ pub mod operation_types {
     pub const PERPS_LIQUIDATE: u8 = 12;
     pub const PERPS_ADD_LIQUIDITY: u8 = 13;
     pub const PERPS_REMOVE_LIQUIDITY: u8 = 14;
+    pub const PERPS_TRANSFER_POSITION: u8 = 15;
+    /// Migrated pool LP note conversion (no execute phase)
+    pub const PERPS_CONVERT_LP: u8 = 16;
 
     // Voting operation types
     /// Snapshot mode first vote
@@ -124,6 +305,28 @@ pub mod operation_types {
     pub const CLOSE_VOTE_POSITION: u8 = 23;
     /// SpendToVote mode claim
     pub const CLAIM: u8 = 24;
+
+    // Emissions operation types
+    /// Liquidity mining rewards claim
+    pub const CLAIM_REWARDS: u8 = 30;
+
+    // Fee rebate operation types
+    /// Swap fee rebate claim
+    pub const CLAIM_FEE_REBATE: u8 = 31;
+
+    // Quadratic funding operation types
+    /// Donation to a matching round project
+    pub const DONATE: u8 = 32;
+
+    // Market operation types
+    /// Escrow yield bonus note claim
+    pub const CLAIM_ESCROW_YIELD: u8 = 33;
+
+    // Savings vault operation types
+    /// LP note -> vault share note
+    pub const VAULT_DEPOSIT: u8 = 34;
+    /// Vault share note -> LP note
+    pub const VAULT_WITHDRAW: u8 = 35;
 }
 
 /// Encrypted note size in bytes
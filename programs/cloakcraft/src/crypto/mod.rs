@@ -1,9 +1,10 @@
-//! Cryptographic operations for proof verification
+//! Cryptographic operations for proof verification and note encryption
 //!
 //! Uses groth16-solana for Groth16 verification with Solana BN254 syscalls.
 //! Compatible with snarkjs/circom proofs.
 
 pub mod babyjubjub;
+pub mod note_encryption;
 
 pub use babyjubjub::Point;
 
@@ -0,0 +1,139 @@
+//! Encrypted note format
+//!
+//! Notes are encrypted to the recipient's stealth public key with ECIES:
+//! BabyJubJub ECDH against an ephemeral key, HKDF-SHA256 over the shared
+//! x-coordinate (salt = ephemeral key, info = `NOTE_ENCRYPTION_DOMAIN`) to a
+//! ChaCha20-Poly1305 key. The version byte and domain are bound as associated
+//! data, so a ciphertext can't be reinterpreted under another format.
+//!
+//! Layout (v1):
+//! - version: 1 byte (`NOTE_ENCRYPTION_V1`)
+//! - ephemeral public key: 64 bytes (x || y)
+//! - nonce: 12 bytes
+//! - ciphertext: plaintext length + 16-byte Poly1305 tag
+//!
+//! The program never decrypts; it only checks the version and that the
+//! ciphertext length matches a known note plaintext, so scanners can rely on
+//! every stored note parsing. Pools that raise their note limit past the
+//! inline capacity also accept extended notes: the largest note plaintext
+//! followed by extra payload (memos, further assets).
+
+use anchor_lang::prelude::*;
+
+use crate::errors::CloakCraftError;
+use crate::light_cpi::MAX_ENCRYPTED_NOTE_SIZE;
+use crate::state::Pool;
+
+/// Current encrypted note version
+pub const NOTE_ENCRYPTION_V1: u8 = 1;
+
+/// HKDF info and associated data domain
+pub const NOTE_ENCRYPTION_DOMAIN: &[u8] = b"cloakcraft/note-encryption/v1";
+
+/// Ephemeral BabyJubJub public key (x || y)
+pub const EPHEMERAL_KEY_SIZE: usize = 64;
+
+/// ChaCha20-Poly1305 nonce
+pub const NONCE_SIZE: usize = 12;
+
+/// Poly1305 tag
+pub const TAG_SIZE: usize = 16;
+
+/// Bytes before the ciphertext
+pub const HEADER_SIZE: usize = 1 + EPHEMERAL_KEY_SIZE + NONCE_SIZE;
+
+/// Plaintext sizes of the note types (standard, LP, position)
+pub const NOTE_PLAINTEXT_SIZES: [usize; 3] = [104, 105, 123];
+
+/// Encrypted size of a note with `plaintext_len` bytes
+pub const fn encrypted_note_size(plaintext_len: usize) -> usize {
+    HEADER_SIZE + plaintext_len + TAG_SIZE
+}
+
+/// Smallest extended note: the largest note plaintext plus one payload byte
+pub const MIN_EXTENDED_NOTE_SIZE: usize = encrypted_note_size(NOTE_PLAINTEXT_SIZES[2]) + 1;
+
+/// Check an encrypted note's version and size against the pool's limit
+pub fn validate_encrypted_note(encrypted_note: &[u8], pool: &Pool) -> Result<()> {
+    let limit = pool.encrypted_note_limit();
+    require!(
+        encrypted_note.len() <= limit,
+        CloakCraftError::EncryptedNoteTooLarge
+    );
+    require!(
+        encrypted_note.first() == Some(&NOTE_ENCRYPTION_V1),
+        CloakCraftError::UnsupportedNoteEncryptionVersion
+    );
+    let extended = limit > MAX_ENCRYPTED_NOTE_SIZE && encrypted_note.len() >= MIN_EXTENDED_NOTE_SIZE;
+    require!(
+        extended
+            || NOTE_PLAINTEXT_SIZES
+                .iter()
+                .any(|&len| encrypted_note.len() == encrypted_note_size(len)),
+        CloakCraftError::InvalidEncryptedNote
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(len: usize) -> Vec<u8> {
+        let mut note = vec![0u8; len];
+        if let Some(version) = note.first_mut() {
+            *version = NOTE_ENCRYPTION_V1;
+        }
+        note
+    }
+
+    #[test]
+    fn test_validate_encrypted_note() {
+        let pool = Pool::default();
+        for len in NOTE_PLAINTEXT_SIZES {
+            let mut note = note(encrypted_note_size(len));
+            assert!(note.len() <= MAX_ENCRYPTED_NOTE_SIZE);
+            assert!(validate_encrypted_note(&note, &pool).is_ok());
+
+            note[0] = 0;
+            assert_eq!(
+                validate_encrypted_note(&note, &pool).unwrap_err(),
+                CloakCraftError::UnsupportedNoteEncryptionVersion.into()
+            );
+        }
+
+        assert_eq!(
+            validate_encrypted_note(&note(encrypted_note_size(104) - 1), &pool).unwrap_err(),
+            CloakCraftError::InvalidEncryptedNote.into()
+        );
+        assert_eq!(
+            validate_encrypted_note(&[], &pool).unwrap_err(),
+            CloakCraftError::UnsupportedNoteEncryptionVersion.into()
+        );
+    }
+
+    #[test]
+    fn test_extended_notes() {
+        let mut pool = Pool::default();
+        // Inline-only pools keep to the known note sizes
+        assert_eq!(
+            validate_encrypted_note(&note(MIN_EXTENDED_NOTE_SIZE), &pool).unwrap_err(),
+            CloakCraftError::InvalidEncryptedNote.into()
+        );
+        assert_eq!(
+            validate_encrypted_note(&note(MAX_ENCRYPTED_NOTE_SIZE + 1), &pool).unwrap_err(),
+            CloakCraftError::EncryptedNoteTooLarge.into()
+        );
+
+        pool.max_encrypted_note_size = 400;
+        assert!(validate_encrypted_note(&note(MIN_EXTENDED_NOTE_SIZE), &pool).is_ok());
+        assert!(validate_encrypted_note(&note(400), &pool).is_ok());
+        assert!(validate_encrypted_note(&note(encrypted_note_size(104)), &pool).is_ok());
+        // Still no truncated notes, nor anything past the pool's limit
+        assert!(validate_encrypted_note(&note(MIN_EXTENDED_NOTE_SIZE - 2), &pool).is_err());
+        assert_eq!(
+            validate_encrypted_note(&note(401), &pool).unwrap_err(),
+            CloakCraftError::EncryptedNoteTooLarge.into()
+        );
+    }
+}
@@ -479,7 +479,7 @@ pub enum CloakCraftError {
     #[msg("Invalid ballot timing - start_time must be before end_time")]
     InvalidBallotTiming,
 
-    #[msg("Invalid number of options - must be between 1 and 16")]
+    #[msg("Invalid number of options - must be between 1 and 64")]
     InvalidNumOptions,
 
     #[msg("Voting period has not started yet")]
@@ -511,4 +511,821 @@ pub enum CloakCraftError {
 
     #[msg("Ballot ID mismatch - does not match expected ballot")]
     BallotIdMismatch,
+
+    // ============ Perps Rebalance Errors ============
+    #[msg("Target weights exceed 10000 bps")]
+    InvalidTargetWeight,
+
+    #[msg("Rebalance does not move pool toward target weights")]
+    RebalanceWorsensWeights,
+
+    // ============ Emissions Errors ============
+    #[msg("Invalid emissions schedule window or rate")]
+    InvalidEmissionsSchedule,
+
+    #[msg("Emissions source pool does not match schedule")]
+    EmissionsPoolMismatch,
+
+    #[msg("Reward checkpoint is ahead of the emissions accumulator")]
+    InvalidRewardCheckpoint,
+
+    #[msg("Claimed reward does not match accrued emissions")]
+    RewardAmountMismatch,
+
+    #[msg("Pending operation type does not match instruction")]
+    InvalidOperationType,
+
+    // ============ Fee Rebate Errors ============
+    #[msg("Invalid fee rebate epoch or tier configuration")]
+    InvalidRebateConfig,
+
+    #[msg("Swap volume record is not for the current epoch")]
+    VolumeEpochMismatch,
+
+    #[msg("Rebate epoch has not ended")]
+    RebateEpochNotOver,
+
+    #[msg("Fee rebate already claimed")]
+    RebateAlreadyClaimed,
+
+    #[msg("No rebate owed for this volume")]
+    NoRebateOwed,
+
+    // ============ Matching Round Errors ============
+    #[msg("Invalid matching round configuration")]
+    InvalidRoundConfig,
+
+    #[msg("Matching round is not accepting donations")]
+    RoundNotActive,
+
+    #[msg("Matching round has not ended")]
+    RoundNotEnded,
+
+    #[msg("Matching round already tallied")]
+    RoundAlreadyTallied,
+
+    #[msg("Matching round not tallied")]
+    RoundNotTallied,
+
+    #[msg("Project index out of range")]
+    InvalidProjectIndex,
+
+    #[msg("Matching already claimed for project")]
+    MatchingAlreadyClaimed,
+
+    #[msg("Recipient does not match project")]
+    ProjectRecipientMismatch,
+
+    // ============ Anonymity Guard Errors ============
+    #[msg("Unshield delayed: recent pool anonymity set below guard threshold")]
+    AnonymityGuardActive,
+
+    #[msg("Invalid anonymity guard override duration")]
+    InvalidGuardOverride,
+
+    // ============ Denomination Errors ============
+    #[msg("Amount is not one of the pool's fixed denominations")]
+    InvalidDenomination,
+
+    #[msg("Denominations must be strictly ascending with unused slots zeroed")]
+    InvalidDenominationConfig,
+
+    // ============ Root Checkpoint Errors ============
+    #[msg("Minimum interval since last root checkpoint not elapsed")]
+    CheckpointTooSoon,
+
+    #[msg("Invalid root checkpoint: zero root or commitment count went backwards")]
+    InvalidRootCheckpoint,
+
+    // ============ Unshield Delivery Errors ============
+    #[msg("Unshield recipient missing or not the owner's associated token account")]
+    InvalidUnshieldRecipient,
+
+    #[msg("ATA reimbursement requires a newly created ATA and cannot exceed the unshield amount")]
+    InvalidAtaReimbursement,
+
+    #[msg("Unshield memo too long")]
+    MemoTooLong,
+
+    // ============ Unshield Invoke Errors ============
+    #[msg("Call hash does not match target program, recipient and call data")]
+    CallHashMismatch,
+
+    #[msg("Operation is bound to a call: use process_unshield_and_invoke")]
+    UnshieldCallRequired,
+
+    #[msg("Operation has no bound call or it was already executed")]
+    NoUnshieldCall,
+
+    #[msg("Invoke target is not a whitelisted executable program")]
+    InvalidInvokeTarget,
+
+    // ============ NFT Errors ============
+    #[msg("Mint is not an NFT (requires decimals 0 and supply 1)")]
+    NotAnNft,
+
+    #[msg("Pool is not an NFT pool, or NFT pools require the NFT instructions")]
+    InvalidNftPool,
+
+    #[msg("NFT notes must have amount 0 or 1 and no fee")]
+    InvalidNftAmount,
+
+    #[msg("Invalid NFT metadata, edition or token record account")]
+    InvalidNftMetadata,
+
+    // ============ State Tree Errors ============
+    #[msg("Output state tree is not the pool's active state tree")]
+    InvalidStateTree,
+
+    #[msg("Address tree is not the pool's address tree")]
+    InvalidAddressTree,
+
+    #[msg("Pool trees are not configured")]
+    PoolTreesNotSet,
+
+    #[msg("A state tree migration is already scheduled")]
+    TreeMigrationPending,
+
+    #[msg("Tree cutover slot must be in the future")]
+    InvalidTreeCutover,
+
+    // ============ Commitment Binding Errors ============
+    #[msg("Commitment account hash does not match the pool's commitment account")]
+    CommitmentAccountMismatch,
+
+    // ============ Nullifier Domain Errors ============
+    #[msg("Nullifier domain does not match the operation type")]
+    InvalidNullifierDomain,
+
+    // ============ Pending Expiry Errors ============
+    #[msg("Pending operation expiry out of bounds")]
+    InvalidPendingExpiry,
+
+    #[msg("No free pending expiry override slot")]
+    PendingExpiryOverridesFull,
+
+    // ============ Rent Refund Errors ============
+    #[msg("Rent refund recipient missing or does not match the one bound to the operation")]
+    InvalidRentRefundRecipient,
+
+    // ============ Light Errors ============
+    #[msg("Light: missing root index for the validity proof")]
+    LightInvalidRootIndex,
+
+    #[msg("Light: tree account missing or at the wrong index")]
+    LightInvalidTreeAccount,
+
+    #[msg("Light: missing or invalid Light system accounts")]
+    LightMissingSystemAccounts,
+
+    #[msg("Light: failed to hash or encode compressed account")]
+    LightAccountEncodingFailed,
+
+    // ============ Cost Estimate Errors ============
+    #[msg("Unknown operation type or unsupported input/output count")]
+    InvalidOperationShape,
+
+    // ============ Solvency Errors ============
+    #[msg("Pending operation passed more than once")]
+    DuplicatePendingOperation,
+
+    // ============ Program Version Errors ============
+    #[msg("Client version is older than the minimum supported by this program")]
+    StaleClientVersion,
+
+    #[msg("Invalid program version update")]
+    InvalidProgramVersion,
+
+    // ============ Snapshot Root Errors ============
+    #[msg("Merkle root does not match the ballot's snapshot root")]
+    SnapshotRootMismatch,
+
+    #[msg("Snapshot root is already registered")]
+    SnapshotRootAlreadySet,
+
+    #[msg("Snapshot slot has not been reached")]
+    SnapshotSlotNotReached,
+
+    #[msg("Snapshot root cannot be registered after votes are cast")]
+    SnapshotRootAfterVotes,
+
+    // ============ LP Vote Source Errors ============
+    #[msg("LP mint is not registered as a vote source for this ballot")]
+    LpVoteSourceNotFound,
+
+    #[msg("LP mint is already registered for this ballot")]
+    LpVoteSourceAlreadyRegistered,
+
+    #[msg("Ballot has the maximum number of LP vote sources")]
+    TooManyLpVoteSources,
+
+    #[msg("Invalid LP vote source: zero rate or ballot token mint")]
+    InvalidLpVoteSource,
+
+    #[msg("LP vote sources cannot be registered after votes are cast")]
+    LpVoteSourceAfterVotes,
+
+    // ============ TWAB Errors ============
+    #[msg("Invalid TWAB config: Snapshot mode with 2 to 8 roots and a non-zero interval required")]
+    InvalidTwabConfig,
+
+    #[msg("Ballot uses TWAB mode: vote with the TWAB instruction")]
+    TwabVoteRequired,
+
+    #[msg("Ballot does not use TWAB mode")]
+    TwabNotEnabled,
+
+    #[msg("TWAB roots are not all registered yet")]
+    TwabRootsIncomplete,
+
+    // ============ Proposal Bond Errors ============
+    #[msg("Ballot has a proposal bond: bond recipient (and protocol config if slashing) required")]
+    MissingBondRecipient,
+
+    #[msg("Bond recipient must be the ballot authority on quorum, the protocol treasury otherwise")]
+    InvalidBondRecipient,
+
+    #[msg("Proposal bond has not been settled")]
+    BondNotSettled,
+
+    // ============ Perp Order Errors ============
+    #[msg("Perp order has expired")]
+    PerpOrderExpired,
+
+    #[msg("Perp order has not expired yet")]
+    PerpOrderNotExpired,
+
+    #[msg("Oracle price has not crossed the order's limit price")]
+    PerpOrderNotTriggered,
+
+    #[msg("Invalid limit price")]
+    InvalidLimitPrice,
+
+    #[msg("Order payer does not match the order")]
+    InvalidOrderPayer,
+
+    #[msg("Position fee below the pool's minimum fee rate")]
+    InsufficientPositionFee,
+
+    #[msg("Maker fee must not exceed the position fee")]
+    InvalidMakerFee,
+
+    // ============ Position Transfer Errors ============
+    #[msg("Position terms do not match the transfer proof")]
+    PositionTermsMismatch,
+
+    #[msg("Entry borrow fee snapshot is ahead of the pool accumulator")]
+    InvalidEntryBorrowFee,
+
+    // ============ LP Lock Errors ============
+    #[msg("Pool has an LP lock configured; the LP lock account is required")]
+    LpLockRequired,
+
+    #[msg("LP tokens are still locked")]
+    LpLocked,
+
+    #[msg("Withdrawal inside the LP lock window must leave the JIT penalty in reserves")]
+    JitPenaltyNotApplied,
+
+    #[msg("LP lock payer does not match the lock")]
+    InvalidLpLockPayer,
+
+    #[msg("JIT penalty exceeds the maximum")]
+    InvalidJitPenalty,
+
+    #[msg("Swap output moved beyond the drift tolerance since Phase 0")]
+    SwapOutputDrift,
+
+    #[msg("Invalid surplus policy")]
+    InvalidSurplusPolicy,
+
+    // ============ AMM Pool Creation Errors ============
+    #[msg("Pool creator is not on the allowlist")]
+    PoolCreatorNotAllowed,
+
+    #[msg("Pool creator allowlist is full")]
+    PoolCreatorAllowlistFull,
+
+    #[msg("Invalid AMM pool creation mode")]
+    InvalidAmmCreationMode,
+
+    // ============ Escrow Yield Errors ============
+    #[msg("Escrow yield is disabled for this market")]
+    EscrowYieldDisabled,
+
+    #[msg("Escrow yield policy does not match")]
+    InvalidEscrowYieldPolicy,
+
+    #[msg("Deployment would exceed the deployable share of opted-in escrow")]
+    EscrowYieldDeployExceeded,
+
+    #[msg("Deployed escrow must be recalled before this order can settle")]
+    EscrowYieldRecallRequired,
+
+    #[msg("Yield adapter does not match the policy")]
+    InvalidYieldAdapter,
+
+    #[msg("Vault balance change does not match the adapter call")]
+    AdapterBalanceMismatch,
+
+    #[msg("Order escrow yield is not settled")]
+    OrderYieldNotSettled,
+
+    #[msg("Order escrow yield already claimed")]
+    OrderYieldAlreadyClaimed,
+
+    #[msg("No escrow yield owed")]
+    NoEscrowYieldOwed,
+
+    // ============ Operation Epoch Errors ============
+    #[msg("Operation ID was derived for a previous operation epoch")]
+    StaleOperationEpoch,
+
+    // ============ Admin Audit Errors ============
+    #[msg("Failed to write admin action record")]
+    AdminAuditFailed,
+
+    // ============ Payment Receipt Errors ============
+    #[msg("Payment receipts require a non-dummy commitment")]
+    PaymentReceiptWithoutCommitment,
+
+    #[msg("Failed to create payment receipt")]
+    PaymentReceiptCreationFailed,
+
+    // ============ Root Registry Errors ============
+    #[msg("Root registry tree is full")]
+    RootRegistryFull,
+
+    #[msg("Root is not retained by the root registry")]
+    UnknownRegistryRoot,
+
+    #[msg("Merkle path does not lead from the commitment to the root")]
+    InvalidInclusionPath,
+
+    #[msg("Registry root is older than the allowed age")]
+    StaleRegistryRoot,
+
+    // ============ Verification Key Finalization Errors ============
+    #[msg("Verification key has not been finalized")]
+    VerificationKeyNotFinalized,
+
+    #[msg("Verification key is finalized and cannot be modified")]
+    VerificationKeyFinalized,
+
+    #[msg("Verification key data does not match the expected hash")]
+    VerificationKeyHashMismatch,
+
+    // ============ Proof Encoding Errors ============
+    #[msg("Public input is not a canonical scalar field element")]
+    NonCanonicalPublicInput,
+
+    #[msg("Proof point coordinate is not a canonical base field element")]
+    ProofPointNotCanonical,
+
+    #[msg("Proof point is not on the BN254 curve")]
+    ProofPointNotOnCurve,
+
+    #[msg("Proof point is not in the prime-order subgroup")]
+    ProofPointNotInSubgroup,
+
+    #[msg("Value is not a canonical field element")]
+    NonCanonicalFieldElement,
+
+    // ============ Sparse Merkle Tree Errors ============
+    #[msg("Sparse merkle proof does not match the root")]
+    InvalidSmtProof,
+
+    #[msg("Key is present in the sparse merkle tree")]
+    SmtKeyPresent,
+
+    #[msg("Sparse merkle proof exceeds the maximum depth")]
+    SmtDepthExceeded,
+
+    // ============ Snapshot Tree Errors ============
+    #[msg("Snapshot tree is full")]
+    SnapshotTreeFull,
+
+    #[msg("Merkle root is not a retained snapshot tree root")]
+    UnknownSnapshotTreeRoot,
+
+    // ============ Note Encryption Errors ============
+    #[msg("Unsupported encrypted note version")]
+    UnsupportedNoteEncryptionVersion,
+
+    // ============ Attestation Errors ============
+    #[msg("No ed25519 attestation by the expected signer over the expected message")]
+    MissingAttestation,
+
+    #[msg("Malformed ed25519 program instruction")]
+    InvalidAttestationInstruction,
+
+    #[msg("Attestation is outside its replay window")]
+    AttestationOutsideWindow,
+
+    // ============ Dust Errors ============
+    #[msg("Change note is below the pool's dust threshold")]
+    DustNote,
+
+    #[msg("Position is below the pool's minimum size or margin")]
+    PositionBelowMinimum,
+
+    // ============ Simulation Errors ============
+    #[msg("Simulation complete: reverted on purpose, result in return data")]
+    SimulationComplete,
+
+    // ============ Shield Pair Errors ============
+    #[msg("Shield pair legs must target different pools")]
+    ShieldPairSamePool,
+
+    // ============ Unshield Binding Errors ============
+    #[msg("Unshield recipient does not match the recipient bound by the proof")]
+    UnshieldRecipientMismatch,
+
+    // ============ Relayer Allowlist Errors ============
+    #[msg("Relayer is not on the pool's relayer allowlist")]
+    RelayerNotAllowed,
+
+    #[msg("Relayer allowlist is full")]
+    RelayerAllowlistFull,
+
+    // ============ Relayer Stake Errors ============
+    #[msg("Not enough bonded relayer stake")]
+    InsufficientRelayerStake,
+
+    #[msg("Relayer stake is still unbonding")]
+    RelayerStakeUnbonding,
+
+    #[msg("Operation is stranded: complete its commitments before closing")]
+    PendingOperationStranded,
+
+    // ============ Swap Commit-Reveal Errors ============
+    #[msg("Committed swap must be executed with execute_swap_revealed")]
+    SwapNotRevealed,
+
+    #[msg("Revealed swap parameters do not match the Phase 0 commitment")]
+    SwapCommitmentMismatch,
+
+    // ============ Sealed Swap Errors ============
+    #[msg("Sealed swaps must be proven with the swap_sealed circuit")]
+    SealedSwapCircuitRequired,
+
+    // ============ Transfer Batch Errors ============
+    #[msg("Transfer batch is full")]
+    BatchFull,
+
+    #[msg("Batch inputs are already being verified: no more transfers can be appended")]
+    BatchAlreadyStarted,
+
+    #[msg("Batched transfers can't unshield or bind a call")]
+    BatchUnshieldNotSupported,
+
+    #[msg("Nullifier already in this batch")]
+    DuplicateBatchNullifier,
+
+    // ============ Stealth Change Errors ============
+    #[msg("Stealth ephemeral pubkey does not match the one stored in Phase 0")]
+    StealthEphemeralMismatch,
+
+    // ============ Ballot Config Errors ============
+    #[msg("Ballot would end before it is created")]
+    BallotAlreadyEnded,
+
+    #[msg("Protocol fee set without a protocol treasury")]
+    MissingProtocolTreasury,
+
+    #[msg("Unknown or reserved weight formula op")]
+    InvalidWeightOp,
+
+    #[msg("Weight formula PushConst indexes a missing weight param")]
+    WeightParamOutOfRange,
+
+    #[msg("Weight formula must leave exactly one value on the stack")]
+    WeightFormulaUnbalanced,
+
+    #[msg("Encrypted reveal modes require a time lock pubkey")]
+    MissingTimeLockPubkey,
+
+    #[msg("Encrypted reveal modes require an unlock slot")]
+    InvalidUnlockSlot,
+
+    #[msg("Oracle resolution requires an oracle")]
+    MissingBallotOracle,
+
+    #[msg("Authority resolution requires a resolver")]
+    MissingBallotResolver,
+
+    #[msg("Claim deadline must be after end_time, and is SpendToVote only")]
+    InvalidClaimDeadline,
+
+    // ============ Ballot Options Page Errors ============
+    #[msg("Ballots with more than 16 options must be Public with Single or Weighted votes")]
+    PagedBallotUnsupportedMode,
+
+    #[msg("Ballot options page missing or does not hold this option")]
+    BallotOptionsPageRequired,
+
+    #[msg("Ballot does not need this options page")]
+    InvalidBallotOptionsPage,
+
+    // ============ Option Registration Errors ============
+    #[msg("max_options must exceed num_options when registration is enabled, and be 0 otherwise")]
+    InvalidOptionRegistration,
+
+    #[msg("Registrar option registration requires a registrar")]
+    MissingOptionRegistrar,
+
+    #[msg("Ballot does not accept new options")]
+    OptionRegistrationClosed,
+
+    #[msg("Signer is not the ballot's option registrar")]
+    UnauthorizedOptionRegistrar,
+
+    #[msg("Ballot has reached max_options")]
+    BallotOptionsFull,
+
+    // ============ Turnout Privacy Errors ============
+    #[msg("Hidden turnout requires an encrypted reveal mode and Snapshot binding")]
+    InvalidTurnoutPrivacy,
+
+    #[msg("Hidden-turnout ballots require a turnout ciphertext per vote")]
+    MissingTurnoutCiphertext,
+
+    // ============ Multi-Position Claim Errors ============
+    #[msg("Multi-position claims take 2 to 3 positions")]
+    InvalidClaimPositionCount,
+
+    #[msg("Position appears twice in this claim")]
+    DuplicateClaimPosition,
+
+    // ============ Loser Refund Errors ============
+    #[msg("loser_refund_bps must be at most 10000, and 0 unless SpendToVote")]
+    InvalidLoserRefund,
+
+    #[msg("Ballot has no loser refunds")]
+    LoserRefundsDisabled,
+
+    #[msg("Winning positions claim a payout, not a refund")]
+    RefundForWinner,
+
+    // ============ Oracle Dispute Errors ============
+    #[msg("Disputes need an Oracle ballot, a positive window, a bond and a resolver")]
+    InvalidDisputeConfig,
+
+    #[msg("Ballot outcome cannot be disputed")]
+    DisputesNotEnabled,
+
+    #[msg("Dispute window has closed")]
+    DisputeWindowClosed,
+
+    #[msg("Outcome can still be disputed")]
+    DisputeWindowOpen,
+
+    #[msg("Ballot outcome is not disputed")]
+    BallotNotDisputed,
+
+    #[msg("Ballot outcome was already disputed")]
+    BallotAlreadyDisputed,
+
+    // ============ Perps Delisting Errors ============
+    #[msg("Perps market is delisted")]
+    PerpsMarketDelisted,
+
+    #[msg("Perps market is not in settle-only mode")]
+    PerpsMarketNotSettleOnly,
+
+    #[msg("Settle grace period has not elapsed")]
+    SettleGracePeriodActive,
+
+    #[msg("Settle grace period must not be negative")]
+    InvalidSettleGracePeriod,
+
+    // ============ Perps Migration Errors ============
+    #[msg("Perps pool is frozen by a migration")]
+    PerpsPoolMigrating,
+
+    #[msg("Perps pool has open positions")]
+    PerpsPoolHasOpenPositions,
+
+    #[msg("Migration target must be an empty pool with the same authority and tokens")]
+    InvalidMigrationTarget,
+
+    #[msg("Token vault already migrated")]
+    VaultAlreadyMigrated,
+
+    #[msg("Not every token vault has been migrated")]
+    VaultsNotMigrated,
+
+    #[msg("Perps pool migration already finalized")]
+    MigrationAlreadyFinalized,
+
+    #[msg("Perps pool migration not finalized")]
+    MigrationNotFinalized,
+
+    #[msg("Converted LP amount does not match the migration rate")]
+    LpConversionMismatch,
+
+    // ============ AMM Migration Errors ============
+    #[msg("AMM pool is not active")]
+    AmmPoolNotActive,
+
+    #[msg("AMM pool has no versions left to migrate to")]
+    AmmPoolVersionExhausted,
+
+    // ============ Commitment Index Errors ============
+    #[msg("Commitment counter can only move past its current next index")]
+    CommitmentIndexNotAhead,
+
+    // ============ Encrypted Note Size Errors ============
+    #[msg("Encrypted note limit must be between the inline capacity and the protocol hard cap")]
+    InvalidEncryptedNoteLimit,
+
+    #[msg("Encrypted note exceeds the pool's maximum size")]
+    EncryptedNoteTooLarge,
+
+    // ============ Fee Policy Errors ============
+    #[msg("Fee rate is outside the protocol fee bounds")]
+    FeeOutOfBounds,
+
+    #[msg("Fee floor must not exceed the fee ceiling, which must not exceed the maximum fee")]
+    InvalidFeeBounds,
+
+    #[msg("All fee override slots are in use")]
+    FeeOverridesFull,
+
+    // ============ Treasury Conversion Errors ============
+    #[msg("Treasury fee conversion is disabled or the fee mint is the canonical asset")]
+    TreasuryConversionDisabled,
+
+    #[msg("AMM pool does not pair the fee mint with the treasury's canonical asset")]
+    InvalidConversionRoute,
+
+    #[msg("Conversion price impact exceeds the configured maximum")]
+    ConversionPriceImpactTooHigh,
+
+    // ============ Buyback Errors ============
+    #[msg("Buyback share, interval or price impact bound out of range")]
+    InvalidBuybackPolicy,
+
+    #[msg("Buybacks are paused or the interval has not elapsed")]
+    BuybackNotDue,
+
+    #[msg("AMM pool does not pair the fee mint with the protocol token")]
+    InvalidBuybackRoute,
+
+    #[msg("No protocol tokens are staked")]
+    NoStakers,
+
+    #[msg("All stake registry slots are in use")]
+    StakeRegistryFull,
+
+    #[msg("Staker is not registered or the amount exceeds the stake")]
+    InvalidUnstake,
+
+    #[msg("Staker has no rewards to distribute")]
+    NothingToDistribute,
+
+    // ============ Savings Vault Errors ============
+    #[msg("Savings vault price impact bound out of range")]
+    InvalidVaultPolicy,
+
+    #[msg("Savings vault is not accepting deposits")]
+    VaultNotActive,
+
+    #[msg("Share or LP amount exceeds the vault conversion")]
+    VaultConversionMismatch,
+
+    #[msg("Emissions schedule does not match the savings vault")]
+    VaultEmissionsMismatch,
+
+    #[msg("Savings vault has no rewards to harvest")]
+    NothingToHarvest,
+
+    #[msg("AMM pool does not pair the reward token with the vault LP token")]
+    InvalidHarvestRoute,
+
+    // ============ Operation Credit Errors ============
+    #[msg("Operation credit policy out of range")]
+    InvalidCreditPolicy,
+
+    #[msg("Credit tree is not the current epoch's")]
+    CreditEpochMismatch,
+
+    #[msg("Credit tree is full")]
+    CreditTreeFull,
+
+    #[msg("Credit root is not a retained credit tree root")]
+    UnknownCreditRoot,
+
+    #[msg("Credit issuance exceeds the per-epoch allowance")]
+    CreditAllowanceExceeded,
+
+    #[msg("Staker is not registered or is below the minimum stake")]
+    InsufficientCreditStake,
+
+    #[msg("Credits are not sold for a fee")]
+    CreditPurchaseDisabled,
+
+    #[msg("Operation requires a credit consumed for its operation ID")]
+    OperationCreditRequired,
+
+    // ============ Light Batch Errors ============
+    #[msg("Light batch must create 1 to MAX_BATCHED_ACCOUNTS accounts with one address tree info each")]
+    InvalidLightBatch,
+
+    #[msg("Nullifiers and commitments cannot share a batch while Phase 3 is pending")]
+    LightBatchPhase3Pending,
+
+    // ============ Tree Registry Errors ============
+    #[msg("Tree capacity hint or near-capacity threshold out of range")]
+    InvalidTreeCapacity,
+
+    #[msg("Tree registry is full")]
+    TreeRegistryFull,
+
+    #[msg("State tree is not in the tree registry")]
+    UnregisteredStateTree,
+
+    #[msg("State tree is retired")]
+    StateTreeRetired,
+
+    #[msg("State tree is near its capacity")]
+    StateTreeNearCapacity,
+
+    // ============ Pinned Inclusion Errors ============
+    #[msg("Operation value requires inclusion verification against a pinned root with a validity proof")]
+    PinnedInclusionRequired,
+
+    // ============ Position Binding Errors ============
+    #[msg("Position metadata must be verified with verify_position_meta_active first")]
+    PositionMetaNotVerified,
+
+    #[msg("Position has not been opened yet - execute_open_position required")]
+    PositionNotOpened,
+
+    // ============ Snapshot Vote Errors ============
+    #[msg("Snapshot root must be registered before snapshot votes are accepted")]
+    SnapshotRootNotRegistered,
+
+    // ============ Account Migration Errors ============
+    #[msg("Account already has the current layout")]
+    AccountAlreadyMigrated,
+
+    // ============ Committed Swap Errors ============
+    #[msg("Committed swaps must be proven with the swap_committed circuit")]
+    CommittedSwapCircuitRequired,
+
+    // ============ Sealed Position Errors ============
+    #[msg("Sealed opens must be proven with the perps_open_position_sealed circuit")]
+    SealedPositionCircuitRequired,
+
+    #[msg("Revealed position terms do not match the Phase 0 terms hash")]
+    SealedPositionTermsMismatch,
+
+    #[msg("Sealed opens must be executed with execute_open_position_revealed")]
+    PositionNotRevealed,
+
+    // ============ Donation Errors ============
+    #[msg("Donation ciphertexts do not match the contributions hash bound in Phase 0")]
+    ContributionsHashMismatch,
+
+    // ============ Swap Volume Errors ============
+    #[msg("Swap volume record does not match the one bound in Phase 0")]
+    SwapVolumeMismatch,
+
+    // ============ Reward Checkpoint Errors ============
+    #[msg("Reward checkpoint does not match the one bound in Phase 0")]
+    RewardCheckpointMismatch,
+
+    #[msg("Reward checkpoint payer does not match")]
+    InvalidRewardCheckpointPayer,
+
+    // ============ Root Registry Account Errors ============
+    #[msg("Root registry account is not owned by the program")]
+    InvalidRootRegistry,
+
+    // ============ TWAB Root Spacing Errors ============
+    #[msg("TWAB root registered less than twab_interval_slots after the previous one")]
+    TwabRootTooEarly,
+
+    #[msg("TWAB window has closed")]
+    TwabWindowClosed,
+
+    #[msg("TWAB root is already registered on this ballot")]
+    DuplicateTwabRoot,
+
+    // ============ Pool Tree Errors ============
+    #[msg("Pool trees are already set")]
+    PoolTreesAlreadySet,
+
+    // ============ Borrow Fee Errors ============
+    #[msg("Entry borrow fee does not match the position's PositionMeta")]
+    BorrowFeeSnapshotMismatch,
+
+    // ============ Circuit Stats Errors ============
+    #[msg("Reported proof verifies; only rejected proofs are counted")]
+    ReportedProofVerifies,
+
+    // ============ Tree Registry Account Errors ============
+    #[msg("Tree registry account is not owned by the program")]
+    InvalidTreeRegistry,
 }
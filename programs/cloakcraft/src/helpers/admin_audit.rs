@@ -0,0 +1,33 @@
+//! Admin audit trail
+//!
+//! Admin instructions call `record_admin_action` after applying their change.
+//! The Light system accounts (and an output queue as the first tree account)
+//! are passed as remaining accounts.
+
+use anchor_lang::prelude::*;
+
+use crate::light_cpi::create_admin_action_record;
+use crate::state::{AdminAction, AdminActionRecord};
+
+/// Write an `AdminActionRecord` for an admin instruction
+pub fn record_admin_action<'info>(
+    fee_payer: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    actor: Pubkey,
+    action: AdminAction,
+    target: Pubkey,
+    old_value_hash: [u8; 32],
+    new_value_hash: [u8; 32],
+) -> Result<()> {
+    let record = AdminActionRecord {
+        actor: actor.to_bytes(),
+        action: action as u8,
+        target: target.to_bytes(),
+        old_value_hash,
+        new_value_hash,
+        timestamp: Clock::get()?.unix_timestamp,
+    };
+    create_admin_action_record(fee_payer, remaining_accounts, record)?;
+    msg!("Admin action {:?} recorded", action);
+    Ok(())
+}
@@ -0,0 +1,187 @@
+//! Ed25519 attestations
+//!
+//! Off-chain attesters (ballot indexers, screening providers, oracles) sign
+//! a message hash with ed25519. Verifying ed25519 in the program would blow
+//! the compute budget, so the transaction carries an Ed25519 program
+//! instruction with the signature and this helper reads it back through the
+//! instructions sysvar, checking it covers the expected signer and message.
+//!
+//! The signed message is `message_hash || issued_at` (i64 LE). The hash must
+//! commit to everything the attestation vouches for, including a domain tag
+//! for the feature, so one attestation can't be replayed in another context.
+//! `issued_at` bounds replays in time; callers that need single use must also
+//! record the attestation (e.g. through a nullifier).
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+use solana_program::ed25519_program;
+
+use crate::errors::CloakCraftError;
+
+/// Signed message size: message hash (32) + issued_at (8)
+pub const ATTESTATION_MESSAGE_SIZE: usize = 40;
+
+/// Attester clocks may run ahead of the cluster by this much
+pub const ATTESTATION_MAX_CLOCK_SKEW: i64 = 60;
+
+/// Ed25519 program header: signature count (1) + padding (1)
+const ED25519_HEADER_SIZE: usize = 2;
+
+/// Ed25519SignatureOffsets: seven u16 fields
+const ED25519_OFFSETS_SIZE: usize = 14;
+
+/// Offsets instruction index meaning "this instruction"
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Bytes an attester signs
+pub fn attestation_message(message_hash: &[u8; 32], issued_at: i64) -> [u8; ATTESTATION_MESSAGE_SIZE] {
+    let mut message = [0u8; ATTESTATION_MESSAGE_SIZE];
+    message[..32].copy_from_slice(message_hash);
+    message[32..].copy_from_slice(&issued_at.to_le_bytes());
+    message
+}
+
+/// Signer and message of each signature in Ed25519 program instruction data
+///
+/// Rejects offsets that point into other instructions: the data checked
+/// here must be the data the Ed25519 program verified.
+pub fn parse_ed25519_instruction(data: &[u8]) -> Result<Vec<(Pubkey, &[u8])>> {
+    let count = *data.first().ok_or(CloakCraftError::InvalidAttestationInstruction)? as usize;
+
+    let field = |at: usize| -> Result<usize> {
+        data.get(at..at + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]) as usize)
+            .ok_or_else(|| error!(CloakCraftError::InvalidAttestationInstruction))
+    };
+    let slice = |offset: usize, len: usize| -> Result<&[u8]> {
+        data.get(offset..offset + len)
+            .ok_or_else(|| error!(CloakCraftError::InvalidAttestationInstruction))
+    };
+
+    let mut signatures = Vec::with_capacity(count);
+    for i in 0..count {
+        let base = ED25519_HEADER_SIZE + i * ED25519_OFFSETS_SIZE;
+        let signature_offset = field(base)?;
+        let signature_ix = field(base + 2)?;
+        let pubkey_offset = field(base + 4)?;
+        let pubkey_ix = field(base + 6)?;
+        let message_offset = field(base + 8)?;
+        let message_size = field(base + 10)?;
+        let message_ix = field(base + 12)?;
+
+        require!(
+            [signature_ix, pubkey_ix, message_ix]
+                .iter()
+                .all(|&ix| ix == CURRENT_INSTRUCTION as usize),
+            CloakCraftError::InvalidAttestationInstruction
+        );
+        slice(signature_offset, 64)?;
+
+        let pubkey = Pubkey::try_from(slice(pubkey_offset, 32)?)
+            .map_err(|_| error!(CloakCraftError::InvalidAttestationInstruction))?;
+        signatures.push((pubkey, slice(message_offset, message_size)?));
+    }
+    Ok(signatures)
+}
+
+/// Check `issued_at` falls in the replay window ending at `now`
+pub fn check_attestation_window(issued_at: i64, max_age_seconds: i64, now: i64) -> Result<()> {
+    require!(
+        issued_at <= now.saturating_add(ATTESTATION_MAX_CLOCK_SKEW)
+            && now.saturating_sub(issued_at) <= max_age_seconds,
+        CloakCraftError::AttestationOutsideWindow
+    );
+    Ok(())
+}
+
+/// Verify an ed25519 attestation by `signer` over `message_hash`
+///
+/// Looks for an Ed25519 program instruction anywhere in the transaction
+/// whose signature covers `attestation_message(message_hash, issued_at)`.
+pub fn verify_attestation(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message_hash: &[u8; 32],
+    issued_at: i64,
+    max_age_seconds: i64,
+    now: i64,
+) -> Result<()> {
+    check_attestation_window(issued_at, max_age_seconds, now)?;
+
+    let expected = attestation_message(message_hash, issued_at);
+    let mut index = 0;
+    while let Ok(ix) = load_instruction_at_checked(index, instructions_sysvar) {
+        if ix.program_id == ed25519_program::ID
+            && parse_ed25519_instruction(&ix.data)?
+                .iter()
+                .any(|(pubkey, message)| pubkey == signer && *message == expected)
+        {
+            return Ok(());
+        }
+        index += 1;
+    }
+
+    Err(CloakCraftError::MissingAttestation.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Single-signature Ed25519 program instruction data
+    fn ed25519_data(pubkey: &Pubkey, message: &[u8], ix_index: u16) -> Vec<u8> {
+        let pubkey_offset = (ED25519_HEADER_SIZE + ED25519_OFFSETS_SIZE) as u16;
+        let signature_offset = pubkey_offset + 32;
+        let message_offset = signature_offset + 64;
+
+        let mut data = vec![1u8, 0];
+        for value in [
+            signature_offset,
+            ix_index,
+            pubkey_offset,
+            ix_index,
+            message_offset,
+            message.len() as u16,
+            ix_index,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(pubkey.as_ref());
+        data.extend_from_slice(&[0u8; 64]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    #[test]
+    fn test_parse_ed25519_instruction() {
+        let signer = Pubkey::new_from_array([7u8; 32]);
+        let message = attestation_message(&[9u8; 32], 1_000);
+
+        let data = ed25519_data(&signer, &message, CURRENT_INSTRUCTION);
+        let parsed = parse_ed25519_instruction(&data).unwrap();
+        assert_eq!(parsed, vec![(signer, &message[..])]);
+
+        // Offsets into another instruction are refused
+        let data = ed25519_data(&signer, &message, 0);
+        assert_eq!(
+            parse_ed25519_instruction(&data).unwrap_err(),
+            CloakCraftError::InvalidAttestationInstruction.into()
+        );
+
+        // Truncated data
+        let data = ed25519_data(&signer, &message, CURRENT_INSTRUCTION);
+        assert!(parse_ed25519_instruction(&data[..data.len() - 1]).is_err());
+        assert!(parse_ed25519_instruction(&[]).is_err());
+    }
+
+    #[test]
+    fn test_attestation_window() {
+        assert!(check_attestation_window(1_000, 300, 1_300).is_ok());
+        assert!(check_attestation_window(1_000 + ATTESTATION_MAX_CLOCK_SKEW, 300, 1_000).is_ok());
+        assert_eq!(
+            check_attestation_window(1_000, 300, 1_301).unwrap_err(),
+            CloakCraftError::AttestationOutsideWindow.into()
+        );
+        assert!(check_attestation_window(1_001 + ATTESTATION_MAX_CLOCK_SKEW, 300, 1_000).is_err());
+    }
+}
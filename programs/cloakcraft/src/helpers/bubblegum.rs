@@ -0,0 +1,165 @@
+//! Bubblegum (compressed NFT) helpers
+//!
+//! Asset id / tree authority derivation and the Bubblegum `transfer` CPI used
+//! to move cNFTs into and out of the protocol custody PDA. The merkle proof
+//! for the leaf is passed as trailing accounts.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::{invoke, invoke_signed},
+};
+
+use crate::constants::BUBBLEGUM_PROGRAM_ID;
+use crate::errors::CloakCraftError;
+
+/// Bubblegum `transfer` instruction discriminator (sha256("global:transfer")[..8])
+const TRANSFER_DISCRIMINATOR: [u8; 8] = [163, 52, 200, 231, 140, 3, 69, 186];
+
+/// cNFT asset id: ["asset", merkle_tree, nonce]
+pub fn asset_id(merkle_tree: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"asset", merkle_tree.as_ref(), &nonce.to_le_bytes()],
+        &BUBBLEGUM_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Tree config PDA (tree authority): [merkle_tree]
+pub fn tree_authority(merkle_tree: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[merkle_tree.as_ref()], &BUBBLEGUM_PROGRAM_ID).0
+}
+
+/// Leaf being transferred, as currently stored in the tree
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CnftLeaf {
+    /// Tree root the proof is against
+    pub root: [u8; 32],
+    /// Hash of the metadata args
+    pub data_hash: [u8; 32],
+    /// Hash of the creators
+    pub creator_hash: [u8; 32],
+    /// Leaf nonce (asset id seed)
+    pub nonce: u64,
+    /// Leaf index in the tree
+    pub index: u32,
+}
+
+impl CnftLeaf {
+    /// Metadata hash bound by the NFT circuit: keccak(data_hash || creator_hash), top 3 bits cleared
+    pub fn metadata_hash(&self) -> [u8; 32] {
+        let mut hash = solana_keccak_hasher::hashv(&[&self.data_hash, &self.creator_hash]).to_bytes();
+        hash[0] &= 0x1f;
+        hash
+    }
+
+    /// Bubblegum `transfer` instruction data
+    fn transfer_data(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(8 + 32 * 3 + 8 + 4);
+        data.extend_from_slice(&TRANSFER_DISCRIMINATOR);
+        data.extend_from_slice(&self.root);
+        data.extend_from_slice(&self.data_hash);
+        data.extend_from_slice(&self.creator_hash);
+        data.extend_from_slice(&self.nonce.to_le_bytes());
+        data.extend_from_slice(&self.index.to_le_bytes());
+        data
+    }
+}
+
+/// Accounts for a Bubblegum `transfer`
+pub struct CnftTransferAccounts<'a, 'info> {
+    pub tree_authority: &'a AccountInfo<'info>,
+    pub leaf_owner: &'a AccountInfo<'info>,
+    pub leaf_delegate: &'a AccountInfo<'info>,
+    pub new_leaf_owner: &'a AccountInfo<'info>,
+    pub merkle_tree: &'a AccountInfo<'info>,
+    pub log_wrapper: &'a AccountInfo<'info>,
+    pub compression_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub bubblegum_program: &'a AccountInfo<'info>,
+}
+
+/// Transfer a cNFT via Bubblegum
+///
+/// `signer_seeds` is empty when the user owns the leaf and the custody
+/// seeds when releasing from custody.
+pub fn transfer_compressed_nft<'info>(
+    accounts: CnftTransferAccounts<'_, 'info>,
+    leaf: &CnftLeaf,
+    proof: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    require!(
+        accounts.tree_authority.key() == tree_authority(accounts.merkle_tree.key),
+        CloakCraftError::InvalidNftMetadata
+    );
+
+    let owner_signs = accounts.leaf_owner.is_signer || !signer_seeds.is_empty();
+    let mut metas = vec![
+        AccountMeta::new_readonly(accounts.tree_authority.key(), false),
+        AccountMeta::new_readonly(accounts.leaf_owner.key(), owner_signs),
+        AccountMeta::new_readonly(accounts.leaf_delegate.key(), accounts.leaf_delegate.is_signer),
+        AccountMeta::new_readonly(accounts.new_leaf_owner.key(), false),
+        AccountMeta::new(accounts.merkle_tree.key(), false),
+        AccountMeta::new_readonly(accounts.log_wrapper.key(), false),
+        AccountMeta::new_readonly(accounts.compression_program.key(), false),
+        AccountMeta::new_readonly(accounts.system_program.key(), false),
+    ];
+    metas.extend(proof.iter().map(|node| AccountMeta::new_readonly(node.key(), false)));
+
+    let ix = Instruction {
+        program_id: BUBBLEGUM_PROGRAM_ID,
+        accounts: metas,
+        data: leaf.transfer_data(),
+    };
+
+    let mut infos = vec![
+        accounts.tree_authority.clone(),
+        accounts.leaf_owner.clone(),
+        accounts.leaf_delegate.clone(),
+        accounts.new_leaf_owner.clone(),
+        accounts.merkle_tree.clone(),
+        accounts.log_wrapper.clone(),
+        accounts.compression_program.clone(),
+        accounts.system_program.clone(),
+        accounts.bubblegum_program.clone(),
+    ];
+    infos.extend_from_slice(proof);
+
+    if signer_seeds.is_empty() {
+        invoke(&ix, &infos)?;
+    } else {
+        invoke_signed(&ix, &infos, signer_seeds)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_data_layout() {
+        let leaf = CnftLeaf {
+            root: [1u8; 32],
+            data_hash: [2u8; 32],
+            creator_hash: [3u8; 32],
+            nonce: 42,
+            index: 7,
+        };
+        let data = leaf.transfer_data();
+        assert_eq!(data.len(), 116);
+        assert_eq!(&data[..8], &TRANSFER_DISCRIMINATOR);
+        assert_eq!(&data[104..112], &42u64.to_le_bytes());
+        assert_eq!(&data[112..], &7u32.to_le_bytes());
+        assert!(leaf.metadata_hash()[0] < 0x20);
+    }
+
+    #[test]
+    fn test_asset_id_depends_on_nonce() {
+        let tree = Pubkey::new_unique();
+        assert_eq!(asset_id(&tree, 1), asset_id(&tree, 1));
+        assert_ne!(asset_id(&tree, 1), asset_id(&tree, 2));
+    }
+}
@@ -0,0 +1,68 @@
+//! Note commitment recomputation
+//!
+//! Most outputs are bound only through a proof's `out_commitment`; the
+//! regeneration data stored beside it (recipient, amount, randomness) is
+//! trusted. Where the program acts on that data, it recomputes the commitment
+//! to bind it: `Poseidon(COMMITMENT, stealth_pub_x, token_mint, amount, randomness)`,
+//! matching the circuits and the SDK's `computeCommitment`.
+
+use anchor_lang::prelude::*;
+use light_hasher::{Hasher, Poseidon};
+
+use crate::constants::domains;
+use crate::errors::CloakCraftError;
+use crate::helpers::field::{pubkey_to_field, u64_to_field};
+
+/// Commitment of a standard note
+pub fn note_commitment(
+    stealth_pub_x: &[u8; 32],
+    token_mint: &Pubkey,
+    amount: u64,
+    randomness: &[u8; 32],
+) -> Result<[u8; 32]> {
+    Poseidon::hashv(&[
+        u64_to_field(domains::COMMITMENT).as_ref(),
+        stealth_pub_x.as_ref(),
+        pubkey_to_field(token_mint).as_ref(),
+        u64_to_field(amount).as_ref(),
+        randomness.as_ref(),
+    ])
+    .map_err(|_| error!(CloakCraftError::PoseidonHashError))
+}
+
+/// Randomness of a buyback distribution note
+///
+/// Derived from public data, so the staker can rebuild the note without an
+/// encrypted copy: `Poseidon(BUYBACK_RANDOMNESS, stealth_pub_x, nonce)`.
+pub fn buyback_randomness(stealth_pub_x: &[u8; 32], nonce: u64) -> Result<[u8; 32]> {
+    Poseidon::hashv(&[
+        u64_to_field(domains::BUYBACK_RANDOMNESS).as_ref(),
+        stealth_pub_x.as_ref(),
+        u64_to_field(nonce).as_ref(),
+    ])
+    .map_err(|_| error!(CloakCraftError::PoseidonHashError))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_commitment_binds_amount() {
+        let mint = Pubkey::new_from_array([0xFF; 32]);
+        let commitment = note_commitment(&[1u8; 32], &mint, 100, &[2u8; 32]).unwrap();
+        assert_eq!(note_commitment(&[1u8; 32], &mint, 100, &[2u8; 32]).unwrap(), commitment);
+        assert_ne!(note_commitment(&[1u8; 32], &mint, 99, &[2u8; 32]).unwrap(), commitment);
+
+        // Non-canonical field elements are refused
+        assert!(note_commitment(&[0xFF; 32], &mint, 100, &[2u8; 32]).is_err());
+    }
+
+    #[test]
+    fn test_buyback_randomness_per_nonce() {
+        let randomness = buyback_randomness(&[1u8; 32], 0).unwrap();
+        assert_ne!(buyback_randomness(&[1u8; 32], 1).unwrap(), randomness);
+        // A field element, so it commits like any other note randomness
+        assert!(note_commitment(&[1u8; 32], &Pubkey::default(), 5, &randomness).is_ok());
+    }
+}
@@ -0,0 +1,80 @@
+//! Escrow yield helpers
+//!
+//! Yield adapter CPI and order yield settlement shared by the market
+//! instructions (see `state::escrow_yield`).
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{EscrowYieldPolicy, OrderYield, Pool};
+
+/// CPI into the yield adapter, signed by the pool PDA (the vault authority)
+///
+/// Adapter accounts are passed in remaining_accounts; `adapter_data` is the
+/// adapter's own instruction data. Callers check the vault balance delta.
+pub fn invoke_yield_adapter<'info>(
+    adapter_program: &AccountInfo<'info>,
+    pool: &Account<'info, Pool>,
+    remaining_accounts: &[AccountInfo<'info>],
+    adapter_data: Vec<u8>,
+) -> Result<()> {
+    let pool_key = pool.key();
+    let accounts = remaining_accounts
+        .iter()
+        .map(|info| AccountMeta {
+            pubkey: info.key(),
+            is_signer: info.is_signer || info.key() == pool_key,
+            is_writable: info.is_writable,
+        })
+        .collect();
+    let ix = Instruction {
+        program_id: adapter_program.key(),
+        accounts,
+        data: adapter_data,
+    };
+
+    let mut infos = remaining_accounts.to_vec();
+    infos.push(adapter_program.clone());
+    let signer_seeds: &[&[&[u8]]] = &[&[seeds::POOL, pool.token_mint.as_ref(), &[pool.bump]]];
+    invoke_signed(&ix, &infos, signer_seeds)
+        .map_err(|_| error!(CloakCraftError::AdapterExecutionFailed))
+}
+
+/// Settle an order's escrow yield on fill or cancel
+///
+/// No record (order never opted in) means nothing to settle. Otherwise the
+/// policy is required, the order's accrued yield is fixed and its principal
+/// removed; fails if what remains no longer covers the deployment.
+pub fn settle_order_yield<'info>(
+    order_yield_info: &UncheckedAccount<'info>,
+    policy: Option<&mut Account<'info, EscrowYieldPolicy>>,
+) -> Result<()> {
+    if order_yield_info.data_is_empty() || order_yield_info.owner != &crate::ID {
+        return Ok(());
+    }
+
+    let mut order_yield = {
+        let data = order_yield_info.try_borrow_data()?;
+        OrderYield::try_deserialize(&mut &data[..])?
+    };
+    if order_yield.settled {
+        return Ok(());
+    }
+
+    let policy = policy.ok_or(CloakCraftError::InvalidEscrowYieldPolicy)?;
+    require_keys_eq!(order_yield.policy, policy.key(), CloakCraftError::InvalidEscrowYieldPolicy);
+
+    let accrued = order_yield.settle(policy).ok_or(CloakCraftError::AmountOverflow)?;
+    require!(policy.is_covered(), CloakCraftError::EscrowYieldRecallRequired);
+
+    let mut data = order_yield_info.try_borrow_mut_data()?;
+    order_yield.try_serialize(&mut &mut data[..])?;
+
+    msg!("Escrow yield settled: principal {}, accrued {}", order_yield.principal, accrued);
+    Ok(())
+}
@@ -9,6 +9,8 @@
 
 use anchor_lang::prelude::*;
 
+use crate::errors::CloakCraftError;
+
 /// BN254 scalar field modulus (Fr) - big-endian
 /// This is the field used by Circom circuits for Groth16 proofs.
 /// r = 21888242871839275222246405745257275088548364400416034343698204186575808495617
@@ -27,8 +29,8 @@ pub fn pubkey_to_field(pubkey: &Pubkey) -> [u8; 32] {
     let mut value = pubkey.to_bytes();
 
     // Subtract modulus while value >= modulus
-    // Max 4 subtractions needed since pubkey is 256 bits and modulus is ~254 bits
-    for _ in 0..4 {
+    // Max 5 subtractions needed since pubkey is 256 bits and 2^256 < 6 * modulus
+    for _ in 0..5 {
         if ge_modulus(&value) {
             value = subtract_modulus(&value);
         } else {
@@ -57,8 +59,8 @@ pub fn bytes_to_field(bytes: &[u8; 32]) -> [u8; 32] {
     let mut value = *bytes;
 
     // Subtract modulus while value >= modulus
-    // Max 4 subtractions needed since input is 256 bits and modulus is ~254 bits
-    for _ in 0..4 {
+    // Max 5 subtractions needed since input is 256 bits and 2^256 < 6 * modulus
+    for _ in 0..5 {
         if ge_modulus(&value) {
             value = subtract_modulus(&value);
         } else {
@@ -69,6 +71,27 @@ pub fn bytes_to_field(bytes: &[u8; 32]) -> [u8; 32] {
     value
 }
 
+/// Whether 32 big-endian bytes are a canonical scalar field element (< r)
+pub fn is_canonical(value: &[u8; 32]) -> bool {
+    !ge_modulus(value)
+}
+
+/// Require every value to be a canonical scalar field element (< r)
+///
+/// Commitments, nullifiers, randomness and recipients are bound by byte value
+/// (PDA seeds, Light addresses, pending operation fields) but mean a field
+/// element in the circuit, where `x` and `x + r` are the same element.
+/// Phase 0 rejects the second encoding so one element has one byte string.
+pub fn assert_canonical(values: &[[u8; 32]]) -> Result<()> {
+    for (i, value) in values.iter().enumerate() {
+        if !is_canonical(value) {
+            msg!("Value {} is not a canonical field element: {:02x?}...", i, &value[0..8]);
+            return Err(CloakCraftError::NonCanonicalFieldElement.into());
+        }
+    }
+    Ok(())
+}
+
 /// Subtract BN254 scalar field modulus from value: result = value - modulus
 fn subtract_modulus(value: &[u8; 32]) -> [u8; 32] {
     let mut result = [0u8; 32];
@@ -116,13 +139,52 @@ mod tests {
         assert_eq!(&field_elem[24..32], &value.to_be_bytes());
     }
 
+    #[test]
+    fn test_assert_canonical() {
+        let below = bytes_to_field(&[0xffu8; 32]);
+        assert!(assert_canonical(&[[0u8; 32], below, u64_to_field(7)]).is_ok());
+        assert!(assert_canonical(&[]).is_ok());
+
+        // r itself and r + 1 alias 0 and 1
+        let mut r_plus_one = BN254_SCALAR_FIELD_MODULUS;
+        r_plus_one[31] += 1;
+        assert!(!is_canonical(&BN254_SCALAR_FIELD_MODULUS));
+        assert_eq!(
+            assert_canonical(&[u64_to_field(1), r_plus_one]).unwrap_err(),
+            CloakCraftError::NonCanonicalFieldElement.into()
+        );
+    }
+
     #[test]
     fn test_pubkey_reduction() {
-        // Test that reduction works for large values
-        let mut large_value = [0xffu8; 32];
-        large_value = subtract_modulus(&large_value);
+        // 2^256 - 1 is 5r plus a remainder, so it needs all five subtractions
+        let reduced = pubkey_to_field(&Pubkey::new_from_array([0xffu8; 32]));
+        let expected: [u8; 32] = [
+            0x0e, 0x0a, 0x77, 0xc1, 0x9a, 0x07, 0xdf, 0x2f,
+            0x66, 0x6e, 0xa3, 0x6f, 0x78, 0x79, 0x46, 0x2e,
+            0x36, 0xfc, 0x76, 0x95, 0x9f, 0x60, 0xcd, 0x29,
+            0xac, 0x96, 0x34, 0x1c, 0x4f, 0xff, 0xff, 0xfa,
+        ];
+        assert_eq!(reduced, expected);
+        assert!(is_canonical(&reduced));
+    }
 
-        // Result should be less than modulus
-        assert!(!ge_modulus(&large_value));
+    #[test]
+    fn test_bytes_reduction_needs_fifth_subtraction() {
+        // 5r reduces to 0; four subtractions would leave r (not canonical)
+        let five_r: [u8; 32] = [
+            0xf1, 0xf5, 0x88, 0x3e, 0x65, 0xf8, 0x20, 0xd0,
+            0x99, 0x91, 0x5c, 0x90, 0x87, 0x86, 0xb9, 0xd1,
+            0xc9, 0x03, 0x89, 0x6a, 0x60, 0x9f, 0x32, 0xd6,
+            0x53, 0x69, 0xcb, 0xe3, 0xb0, 0x00, 0x00, 0x05,
+        ];
+        assert_eq!(bytes_to_field(&five_r), [0u8; 32]);
+
+        // 5r - 1 only needs four, leaving r - 1
+        let mut five_r_minus_one = five_r;
+        five_r_minus_one[31] -= 1;
+        let mut r_minus_one = BN254_SCALAR_FIELD_MODULUS;
+        r_minus_one[31] -= 1;
+        assert_eq!(bytes_to_field(&five_r_minus_one), r_minus_one);
     }
 }
@@ -0,0 +1,153 @@
+//! Scaled fixed-point math
+//!
+//! The program works with three fixed scales:
+//! - `BPS_SCALE` (1e4): fees, ratios, utilization, thresholds
+//! - `USD_SCALE` (1e6): oracle prices and USD values
+//! - `RATE_SCALE` (1e18): rate accumulators such as `cumulative_borrow_fee`
+//!
+//! Every helper widens to u128, rounds down unless named `_ceil`, and
+//! narrows back to u64 with an explicit checked or saturating conversion
+//! instead of a silent `as u64` truncation.
+
+/// Basis points denominator (10000 = 100%)
+pub const BPS_SCALE: u128 = 10_000;
+
+/// USD price/value scale (6 decimals)
+pub const USD_SCALE: u128 = 1_000_000;
+
+/// Rate accumulator scale (18 decimals)
+pub const RATE_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Seconds per hour (borrow rates are quoted per hour)
+pub const SECONDS_PER_HOUR: u128 = 3_600;
+
+/// a * b / denominator, rounded down
+pub fn mul_div(a: u128, b: u128, denominator: u128) -> Option<u128> {
+    a.checked_mul(b)?.checked_div(denominator)
+}
+
+/// a * b / denominator, rounded up
+pub fn mul_div_ceil(a: u128, b: u128, denominator: u128) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+    let product = a.checked_mul(b)?;
+    Some(product / denominator + u128::from(product % denominator != 0))
+}
+
+/// Checked narrowing to u64
+pub fn to_u64(value: u128) -> Option<u64> {
+    u64::try_from(value).ok()
+}
+
+/// Saturating narrowing to u64
+pub fn saturating_u64(value: u128) -> u64 {
+    u64::try_from(value).unwrap_or(u64::MAX)
+}
+
+/// a * b / denominator for u64 operands, rounded down
+pub fn mul_div_u64(a: u64, b: u64, denominator: u64) -> Option<u64> {
+    to_u64(mul_div(a as u128, b as u128, denominator as u128)?)
+}
+
+/// amount * bps / 10000, rounded down
+pub fn apply_bps(amount: u64, bps: u16) -> u64 {
+    // u64 * u16 cannot overflow u128
+    saturating_u64(amount as u128 * bps as u128 / BPS_SCALE)
+}
+
+/// amount * bps / 10000, rounded up
+pub fn apply_bps_ceil(amount: u64, bps: u16) -> u64 {
+    saturating_u64(mul_div_ceil(amount as u128, bps as u128, BPS_SCALE).unwrap_or(u128::MAX))
+}
+
+/// part / whole in basis points, capped at 10000 (0 when whole is 0)
+pub fn ratio_bps(part: u64, whole: u64) -> u16 {
+    if whole == 0 {
+        return 0;
+    }
+    let ratio = part as u128 * BPS_SCALE / whole as u128;
+    ratio.min(BPS_SCALE) as u16
+}
+
+/// USD value (1e6) of `amount` base units at `price` (1e6) with `decimals`
+pub fn usd_value(amount: u64, price: u64, decimals: u8) -> Option<u128> {
+    mul_div(amount as u128, price as u128, 10u128.checked_pow(decimals as u32)?)
+}
+
+/// Base units of a token worth `value` USD (1e6) at `price` (1e6)
+pub fn token_amount_for_usd(value: u128, price: u64, decimals: u8) -> Option<u128> {
+    mul_div(value, 10u128.checked_pow(decimals as u32)?, price as u128)
+}
+
+/// amount * rate_delta / 1e18, rounded down
+pub fn apply_rate(amount: u64, rate_delta: u128) -> Option<u64> {
+    to_u64(mul_div(amount as u128, rate_delta, RATE_SCALE)?)
+}
+
+/// Accumulator increment for an hourly bps rate over `elapsed_seconds`
+///
+/// rate_bps * elapsed * 1e18 / (3600 * 10000), computed with a single
+/// rounding step.
+pub fn hourly_rate_increment(rate_bps: u16, elapsed_seconds: u64) -> Option<u128> {
+    mul_div(
+        (rate_bps as u128).checked_mul(elapsed_seconds as u128)?,
+        RATE_SCALE,
+        SECONDS_PER_HOUR * BPS_SCALE,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_div_rounding() {
+        assert_eq!(mul_div(7, 3, 2), Some(10));
+        assert_eq!(mul_div_ceil(7, 3, 2), Some(11));
+        assert_eq!(mul_div_ceil(6, 3, 2), Some(9));
+        assert_eq!(mul_div(1, 1, 0), None);
+        assert_eq!(mul_div_ceil(1, 1, 0), None);
+        assert_eq!(mul_div(u128::MAX, 2, 1), None);
+    }
+
+    #[test]
+    fn test_narrowing() {
+        assert_eq!(to_u64(u64::MAX as u128), Some(u64::MAX));
+        assert_eq!(to_u64(u64::MAX as u128 + 1), None);
+        assert_eq!(saturating_u64(u128::MAX), u64::MAX);
+        assert_eq!(mul_div_u64(u64::MAX, 2, 1), None);
+        assert_eq!(mul_div_u64(u64::MAX, 2, 2), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_bps() {
+        assert_eq!(apply_bps(1_000_000, 30), 3_000);
+        assert_eq!(apply_bps(333, 1), 0);
+        assert_eq!(apply_bps_ceil(333, 1), 1);
+        assert_eq!(apply_bps(u64::MAX, 10_000), u64::MAX);
+        assert_eq!(apply_bps(u64::MAX, u16::MAX), u64::MAX);
+        assert_eq!(ratio_bps(1, 4), 2_500);
+        assert_eq!(ratio_bps(5, 4), 10_000);
+        assert_eq!(ratio_bps(1, 0), 0);
+    }
+
+    #[test]
+    fn test_usd_conversion_round_trip() {
+        // 2.5 tokens (9 decimals) at $100.00
+        let value = usd_value(2_500_000_000, 100_000_000, 9).unwrap();
+        assert_eq!(value, 250_000_000);
+        assert_eq!(token_amount_for_usd(value, 100_000_000, 9), Some(2_500_000_000));
+        assert_eq!(token_amount_for_usd(value, 0, 9), None);
+    }
+
+    #[test]
+    fn test_hourly_rate_increment_single_rounding() {
+        // 1 bps/hour for one hour = 0.0001 = 1e14 at 1e18 scale
+        assert_eq!(hourly_rate_increment(1, 3_600), Some(100_000_000_000_000));
+        // One second at 1 bps/hour is not truncated to zero
+        assert_eq!(hourly_rate_increment(1, 1), Some(27_777_777_777));
+        // Fee on 1M units after one hour at 1 bps
+        assert_eq!(apply_rate(1_000_000, 100_000_000_000_000), Some(100));
+    }
+}
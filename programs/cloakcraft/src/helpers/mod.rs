@@ -7,8 +7,19 @@ pub mod proof;
 pub mod vault;
 pub mod amm_math;
 pub mod field;
+pub mod fixed;
+pub mod wide;
+pub mod nft;
+pub mod bubblegum;
+pub mod escrow_yield;
+pub mod admin_audit;
+pub mod attestation;
+pub mod commitment;
 
-pub use proof::verify_groth16_proof;
-pub use vault::{transfer_to_vault, transfer_from_vault, update_pool_balance};
+pub use proof::{verify_groth16_proof, verify_groth16_proof_metered};
+pub use vault::{
+    transfer_to_vault, transfer_from_vault, update_pool_balance, settle_transfer_fee, pay_unshield,
+    debit_protocol_fee, vault_shortfall,
+};
 pub use amm_math::{calculate_initial_lp, calculate_proportional_lp, validate_lp_amount};
 pub use field::{pubkey_to_field, u64_to_field, bytes_to_field};
@@ -0,0 +1,253 @@
+//! NFT custody helpers
+//!
+//! Metaplex account derivation, minimal metadata parsing and the Token Metadata
+//! `Transfer` CPI used for programmable NFTs. pNFT token accounts stay frozen,
+//! so moving them in/out of the pool vault must go through Token Metadata,
+//! which also maintains the per-token-account token records.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::{invoke, invoke_signed},
+};
+
+use crate::constants::TOKEN_METADATA_PROGRAM_ID;
+use crate::errors::CloakCraftError;
+
+/// Metaplex `TokenStandard::ProgrammableNonFungible`
+pub const TOKEN_STANDARD_PROGRAMMABLE_NON_FUNGIBLE: u8 = 4;
+
+/// Token Metadata `Transfer` instruction discriminator
+const TRANSFER_INSTRUCTION: u8 = 49;
+
+/// Metadata PDA: ["metadata", program, mint]
+pub fn metadata_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Master edition PDA: ["metadata", program, mint, "edition"]
+pub fn edition_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref(), b"edition"],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Token record PDA: ["metadata", program, mint, "token_record", token_account]
+pub fn token_record_pda(mint: &Pubkey, token_account: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+            b"token_record",
+            token_account.as_ref(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Read `token_standard` from a Metaplex metadata account
+///
+/// Layout: key, update_authority, mint, name, symbol, uri, seller_fee_basis_points,
+/// creators: Option<Vec<Creator>>, primary_sale_happened, is_mutable,
+/// edition_nonce: Option<u8>, token_standard: Option<u8>, ...
+pub fn parse_token_standard(data: &[u8]) -> Option<u8> {
+    let mut offset = 1 + 32 + 32;
+
+    // name, symbol, uri
+    for _ in 0..3 {
+        let len = u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?) as usize;
+        offset = offset.checked_add(4 + len)?;
+    }
+
+    // seller_fee_basis_points
+    offset += 2;
+
+    // creators (address 32 + verified 1 + share 1)
+    if *data.get(offset)? == 1 {
+        let count = u32::from_le_bytes(data.get(offset + 1..offset + 5)?.try_into().ok()?) as usize;
+        offset = offset.checked_add(5 + count.checked_mul(34)?)?;
+    } else {
+        offset += 1;
+    }
+
+    // primary_sale_happened, is_mutable
+    offset += 2;
+
+    // edition_nonce
+    offset += if *data.get(offset)? == 1 { 2 } else { 1 };
+
+    // token_standard
+    match *data.get(offset)? {
+        1 => data.get(offset + 1).copied(),
+        _ => None,
+    }
+}
+
+/// Hash of the metadata account, reduced to a BN254 field element
+///
+/// keccak(metadata data) with the top 3 bits cleared.
+pub fn compute_metadata_hash(data: &[u8]) -> [u8; 32] {
+    let mut hash = solana_keccak_hasher::hashv(&[data]).to_bytes();
+    hash[0] &= 0x1f;
+    hash
+}
+
+/// Accounts for a Token Metadata `Transfer` (TransferV1) of one pNFT
+pub struct ProgrammableTransferAccounts<'a, 'info> {
+    pub token: &'a AccountInfo<'info>,
+    pub token_owner: &'a AccountInfo<'info>,
+    pub destination_token: &'a AccountInfo<'info>,
+    pub destination_owner: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    pub metadata: &'a AccountInfo<'info>,
+    pub edition: &'a AccountInfo<'info>,
+    pub owner_token_record: &'a AccountInfo<'info>,
+    pub destination_token_record: &'a AccountInfo<'info>,
+    pub authority: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub sysvar_instructions: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub associated_token_program: &'a AccountInfo<'info>,
+    pub token_metadata_program: &'a AccountInfo<'info>,
+}
+
+/// Transfer one programmable NFT via Token Metadata
+///
+/// Uses no authorization rules (pNFTs with rule sets that reject program
+/// owners cannot be shielded). `signer_seeds` is empty for user-signed
+/// transfers and the pool seeds when moving out of the vault.
+pub fn transfer_programmable_nft(
+    accounts: ProgrammableTransferAccounts<'_, '_>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    require!(
+        accounts.metadata.key() == metadata_pda(accounts.mint.key)
+            && accounts.edition.key() == edition_pda(accounts.mint.key)
+            && accounts.owner_token_record.key() == token_record_pda(accounts.mint.key, accounts.token.key)
+            && accounts.destination_token_record.key()
+                == token_record_pda(accounts.mint.key, accounts.destination_token.key),
+        CloakCraftError::InvalidNftMetadata
+    );
+
+    // TransferArgs::V1 { amount: 1, authorization_data: None }
+    let mut data = vec![TRANSFER_INSTRUCTION, 0];
+    data.extend_from_slice(&1u64.to_le_bytes());
+    data.push(0);
+
+    let ix = Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(accounts.token.key(), false),
+            AccountMeta::new_readonly(accounts.token_owner.key(), false),
+            AccountMeta::new(accounts.destination_token.key(), false),
+            AccountMeta::new_readonly(accounts.destination_owner.key(), false),
+            AccountMeta::new_readonly(accounts.mint.key(), false),
+            AccountMeta::new(accounts.metadata.key(), false),
+            AccountMeta::new_readonly(accounts.edition.key(), false),
+            AccountMeta::new(accounts.owner_token_record.key(), false),
+            AccountMeta::new(accounts.destination_token_record.key(), false),
+            AccountMeta::new_readonly(accounts.authority.key(), true),
+            AccountMeta::new(accounts.payer.key(), true),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+            AccountMeta::new_readonly(accounts.sysvar_instructions.key(), false),
+            AccountMeta::new_readonly(accounts.token_program.key(), false),
+            AccountMeta::new_readonly(accounts.associated_token_program.key(), false),
+            // authorization_rules_program, authorization_rules: None
+            AccountMeta::new_readonly(TOKEN_METADATA_PROGRAM_ID, false),
+            AccountMeta::new_readonly(TOKEN_METADATA_PROGRAM_ID, false),
+        ],
+        data,
+    };
+
+    let infos = [
+        accounts.token.clone(),
+        accounts.token_owner.clone(),
+        accounts.destination_token.clone(),
+        accounts.destination_owner.clone(),
+        accounts.mint.clone(),
+        accounts.metadata.clone(),
+        accounts.edition.clone(),
+        accounts.owner_token_record.clone(),
+        accounts.destination_token_record.clone(),
+        accounts.authority.clone(),
+        accounts.payer.clone(),
+        accounts.system_program.clone(),
+        accounts.sysvar_instructions.clone(),
+        accounts.token_program.clone(),
+        accounts.associated_token_program.clone(),
+        accounts.token_metadata_program.clone(),
+    ];
+
+    if signer_seeds.is_empty() {
+        invoke(&ix, &infos)?;
+    } else {
+        invoke_signed(&ix, &infos, signer_seeds)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_string(data: &mut Vec<u8>, s: &str) {
+        data.extend_from_slice(&(s.len() as u32).to_le_bytes());
+        data.extend_from_slice(s.as_bytes());
+    }
+
+    fn metadata(creators: usize, edition_nonce: Option<u8>, token_standard: Option<u8>) -> Vec<u8> {
+        let mut data = vec![4u8]; // Key::MetadataV1
+        data.extend_from_slice(&[1u8; 32]);
+        data.extend_from_slice(&[2u8; 32]);
+        push_string(&mut data, "Cloak #1");
+        push_string(&mut data, "CLK");
+        push_string(&mut data, "https://example.com/1.json");
+        data.extend_from_slice(&500u16.to_le_bytes());
+        if creators > 0 {
+            data.push(1);
+            data.extend_from_slice(&(creators as u32).to_le_bytes());
+            data.extend(std::iter::repeat_n(7u8, creators * 34));
+        } else {
+            data.push(0);
+        }
+        data.extend_from_slice(&[1, 1]);
+        match edition_nonce {
+            Some(n) => data.extend_from_slice(&[1, n]),
+            None => data.push(0),
+        }
+        match token_standard {
+            Some(s) => data.extend_from_slice(&[1, s]),
+            None => data.push(0),
+        }
+        data.extend_from_slice(&[0u8; 64]); // collection, uses, ... padding
+        data
+    }
+
+    #[test]
+    fn test_parse_token_standard() {
+        assert_eq!(
+            parse_token_standard(&metadata(2, Some(254), Some(TOKEN_STANDARD_PROGRAMMABLE_NON_FUNGIBLE))),
+            Some(TOKEN_STANDARD_PROGRAMMABLE_NON_FUNGIBLE)
+        );
+        assert_eq!(parse_token_standard(&metadata(0, None, Some(0))), Some(0));
+        assert_eq!(parse_token_standard(&metadata(1, Some(1), None)), None);
+        assert_eq!(parse_token_standard(&[4u8; 40]), None);
+    }
+
+    #[test]
+    fn test_metadata_hash_is_field_element() {
+        let hash = compute_metadata_hash(&metadata(1, None, Some(0)));
+        assert!(hash[0] < 0x20);
+        assert_eq!(crate::helpers::field::bytes_to_field(&hash), hash);
+    }
+}
@@ -4,10 +4,24 @@
 //! Replaces separate verify_proof() and verify_proof_swap() functions with a single implementation.
 
 use anchor_lang::prelude::*;
+use ark_bn254::{Fq, Fq2, G1Affine, G2Affine};
+use ark_ff::{BigInteger256, PrimeField};
 use groth16_solana::groth16::{Groth16Verifier, Groth16Verifyingkey};
+use solana_bn254::prelude::alt_bn128_pairing;
+use solana_program::compute_units::sol_remaining_compute_units;
 
 use crate::constants::GROTH16_PROOF_SIZE;
 use crate::errors::CloakCraftError;
+use crate::helpers::field::is_canonical;
+use crate::state::CircuitStats;
+
+/// BN254 G1 generator (1, 2), big-endian; paired with B for the subgroup check
+const G1_GENERATOR: [u8; 64] = {
+    let mut point = [0u8; 64];
+    point[31] = 1;
+    point[63] = 2;
+    point
+};
 
 /// Verify a Groth16 proof with variable public input count
 ///
@@ -24,6 +38,8 @@ use crate::errors::CloakCraftError;
 /// * `InvalidProofLength` - Proof is not 256 bytes
 /// * `InvalidPublicInputs` - Public input count doesn't match VK
 /// * `InvalidVerificationKey` - VK parsing failed
+/// * `NonCanonicalPublicInput`, `ProofPointNotCanonical`, `ProofPointNotOnCurve`,
+///   `ProofPointNotInSubgroup` - Malformed encoding (see `validate_proof_encoding`)
 /// * `ProofVerificationFailed` - Cryptographic verification failed
 pub fn verify_groth16_proof(
     proof_bytes: &[u8],
@@ -84,10 +100,113 @@ pub fn verify_groth16_proof(
         msg!("[{}]: {:02x?}", i, &input[0..8]);
     }
 
+    // Reject malformed encodings with a specific error before the pairing check
+    validate_proof_encoding(&proof_a, &proof_b, &proof_c, public_inputs)?;
+
     // Verify proof using dynamic input count
     verify_with_dynamic_inputs(&proof_a, &proof_b, &proof_c, public_inputs, &vk, operation_name)
 }
 
+/// Check proof points and public inputs are canonical, valid group elements
+///
+/// The verifier reports every malformed encoding as a generic verification
+/// failure, and the syscalls it uses accept the point at infinity. Checked here:
+/// * public inputs are < r, so no two byte strings alias one scalar
+/// * A, B, C coordinates are < q
+/// * A and C are on the curve (G1 has cofactor 1, so that suffices)
+/// * B is on the twist and in the order-r subgroup (a one-pair pairing
+///   syscall, which rejects points outside it)
+///
+/// None of A, B, C may be the point at infinity; an honest prover never
+/// produces it.
+pub fn validate_proof_encoding(
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]],
+) -> Result<()> {
+    for (i, input) in public_inputs.iter().enumerate() {
+        if !is_canonical(input) {
+            msg!("Public input {} is not canonical", i);
+            return Err(CloakCraftError::NonCanonicalPublicInput.into());
+        }
+    }
+
+    for (name, point) in [("A", proof_a), ("C", proof_c)] {
+        let x = parse_fq(&point[0..32]);
+        let y = parse_fq(&point[32..64]);
+        let (Some(x), Some(y)) = (x, y) else {
+            msg!("Proof {} coordinate >= base field modulus", name);
+            return Err(CloakCraftError::ProofPointNotCanonical.into());
+        };
+        if !G1Affine::new_unchecked(x, y).is_on_curve() {
+            msg!("Proof {} is not on the curve", name);
+            return Err(CloakCraftError::ProofPointNotOnCurve.into());
+        }
+    }
+
+    // EIP-197 order: x.c1, x.c0, y.c1, y.c0
+    let coords = [
+        parse_fq(&proof_b[0..32]),
+        parse_fq(&proof_b[32..64]),
+        parse_fq(&proof_b[64..96]),
+        parse_fq(&proof_b[96..128]),
+    ];
+    let [Some(x1), Some(x0), Some(y1), Some(y0)] = coords else {
+        msg!("Proof B coordinate >= base field modulus");
+        return Err(CloakCraftError::ProofPointNotCanonical.into());
+    };
+    if !G2Affine::new_unchecked(Fq2::new(x0, x1), Fq2::new(y0, y1)).is_on_curve() {
+        msg!("Proof B is not on the curve");
+        return Err(CloakCraftError::ProofPointNotOnCurve.into());
+    }
+
+    let mut pairing_input = [0u8; 192];
+    pairing_input[..64].copy_from_slice(&G1_GENERATOR);
+    pairing_input[64..].copy_from_slice(proof_b);
+    if alt_bn128_pairing(&pairing_input).is_err() {
+        msg!("Proof B is not in the G2 subgroup");
+        return Err(CloakCraftError::ProofPointNotInSubgroup.into());
+    }
+
+    Ok(())
+}
+
+/// Parse a big-endian base field element, `None` unless canonical (< q)
+fn parse_fq(bytes: &[u8]) -> Option<Fq> {
+    let mut limbs = [0u64; 4];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let end = 32 - i * 8;
+        *limb = u64::from_be_bytes(bytes[end - 8..end].try_into().ok()?);
+    }
+    Fq::from_bigint(BigInteger256::new(limbs))
+}
+
+/// Verify a Phase 0 proof, metering it into the circuit's stats when passed
+///
+/// Without stats this is `verify_groth16_proof`. With stats, the compute units
+/// spent on a successful verification are recorded. A rejected proof fails the
+/// transaction either way; failures are counted with `record_proof_failure`.
+pub fn verify_groth16_proof_metered(
+    proof_bytes: &[u8],
+    vk_data: &[u8],
+    public_inputs: &[[u8; 32]],
+    operation_name: &str,
+    circuit_stats: Option<&mut Account<'_, CircuitStats>>,
+) -> Result<()> {
+    let Some(stats) = circuit_stats else {
+        return verify_groth16_proof(proof_bytes, vk_data, public_inputs, operation_name);
+    };
+
+    let remaining_before = sol_remaining_compute_units();
+    verify_groth16_proof(proof_bytes, vk_data, public_inputs, operation_name)?;
+    let used = remaining_before.saturating_sub(sol_remaining_compute_units());
+    stats.record_verification(used, Clock::get()?.unix_timestamp);
+    msg!("Proof verification used {} CU", used);
+
+    Ok(())
+}
+
 /// Internal verification with dynamic public input count
 ///
 /// Uses const generics dispatch based on runtime input count.
@@ -258,3 +377,75 @@ fn parse_vk<'a>(vk_data: &'a [u8]) -> Result<Groth16Verifyingkey<'a>> {
         vk_ic,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::g2::{G2_GENERATOR_X, G2_GENERATOR_Y};
+    use ark_ff::{BigInteger, Field};
+
+    fn fq_bytes(value: &Fq) -> Vec<u8> {
+        value.into_bigint().to_bytes_be()
+    }
+
+    fn g2_bytes(x: Fq2, y: Fq2) -> [u8; 128] {
+        let mut point = [0u8; 128];
+        for (i, coord) in [x.c1, x.c0, y.c1, y.c0].iter().enumerate() {
+            point[i * 32..(i + 1) * 32].copy_from_slice(&fq_bytes(coord));
+        }
+        point
+    }
+
+    fn check(a: &[u8; 64], b: &[u8; 128], inputs: &[[u8; 32]]) -> Result<()> {
+        validate_proof_encoding(a, b, &G1_GENERATOR, inputs)
+    }
+
+    #[test]
+    fn test_validate_proof_encoding() {
+        let b = g2_bytes(G2_GENERATOR_X, G2_GENERATOR_Y);
+        let input = [[7u8; 32]];
+        check(&G1_GENERATOR, &b, &input).unwrap();
+
+        // Public input >= r
+        assert_eq!(
+            check(&G1_GENERATOR, &b, &[[0xff; 32]]).unwrap_err(),
+            CloakCraftError::NonCanonicalPublicInput.into()
+        );
+
+        // A: coordinate >= q, off the curve, point at infinity
+        let mut a = G1_GENERATOR;
+        a[..32].copy_from_slice(&[0xff; 32]);
+        assert_eq!(
+            check(&a, &b, &input).unwrap_err(),
+            CloakCraftError::ProofPointNotCanonical.into()
+        );
+        let mut a = G1_GENERATOR;
+        a[63] = 3;
+        assert_eq!(
+            check(&a, &b, &input).unwrap_err(),
+            CloakCraftError::ProofPointNotOnCurve.into()
+        );
+        assert_eq!(
+            check(&[0u8; 64], &b, &input).unwrap_err(),
+            CloakCraftError::ProofPointNotOnCurve.into()
+        );
+
+        // B off the twist
+        let off_curve = g2_bytes(G2_GENERATOR_X, G2_GENERATOR_X);
+        assert_eq!(
+            check(&G1_GENERATOR, &off_curve, &input).unwrap_err(),
+            CloakCraftError::ProofPointNotOnCurve.into()
+        );
+
+        // B on the twist y^2 = x^3 + 3 / (9 + u) but outside the r-torsion
+        let twist_b = Fq2::from(3u64) * Fq2::new(Fq::from(9u64), Fq::ONE).inverse().unwrap();
+        let (x, y) = (1u64..)
+            .map(|i| Fq2::new(Fq::from(i), Fq::from(0u64)))
+            .find_map(|x| (x * x * x + twist_b).sqrt().map(|y| (x, y)))
+            .unwrap();
+        assert_eq!(
+            check(&G1_GENERATOR, &g2_bytes(x, y), &input).unwrap_err(),
+            CloakCraftError::ProofPointNotInSubgroup.into()
+        );
+    }
+}
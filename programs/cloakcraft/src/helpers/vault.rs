@@ -6,7 +6,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
-use crate::state::Pool;
+use crate::state::{PendingOperation, Pool, PoolStats, ProtocolConfig};
 use crate::errors::CloakCraftError;
 
 /// Transfer tokens from user to vault (shield operation)
@@ -105,3 +105,187 @@ pub fn update_pool_balance(
 
     Ok(())
 }
+
+/// Debit a protocol fee moved out of the vault from the shielded supply
+///
+/// Swap and remove liquidity pay protocol fees from the vault backing the
+/// AMM reserves; the supply (and `PoolStats`, when passed) must drop by the
+/// same amount or `verify_pool_solvency` reports a false shortfall.
+pub fn debit_protocol_fee(
+    pool: &mut Pool,
+    pool_stats: Option<&mut PoolStats>,
+    fee: u64,
+    timestamp: i64,
+) -> Result<()> {
+    if fee == 0 {
+        return Ok(());
+    }
+    update_pool_balance(pool, fee, false)?;
+    if let Some(stats) = pool_stats {
+        if let Some(rolled) = stats.record_withdrawal(0, fee, timestamp) {
+            emit!(rolled);
+        }
+    }
+    Ok(())
+}
+
+/// Amount by which a vault falls short of the shielded supply
+///
+/// The vault must hold `shielded_supply` less the outflows still pending in
+/// Phase 3 (0 = solvent).
+pub fn vault_shortfall(vault_balance: u64, shielded_supply: u64, pending_outflows: u64) -> u64 {
+    shielded_supply
+        .saturating_sub(pending_outflows)
+        .saturating_sub(vault_balance)
+}
+
+/// Verify and pay a transfer's protocol fee (Phase 3 of transact)
+///
+/// Shared by process_unshield and process_unshield_and_invoke. The fee is the
+/// proven `pending_op.fee_amount`; `reimbursement` (if any) is carved out of it
+/// for the relayer, and the treasury share must still cover the expected fee.
+/// Batched transfers were checked member by member as they were appended.
+/// Returns the amount paid to the treasury.
+#[allow(clippy::too_many_arguments)]
+pub fn settle_transfer_fee<'info>(
+    token_program: &Program<'info, Token>,
+    vault: &Account<'info, TokenAccount>,
+    treasury: Option<&Account<'info, TokenAccount>>,
+    reimbursement: Option<(&Account<'info, TokenAccount>, u64)>,
+    pool: &mut Account<'info, Pool>,
+    pending_op: &mut PendingOperation,
+    protocol_config: &ProtocolConfig,
+    unshield_amount: u64,
+    pool_seeds: &[&[&[u8]]],
+) -> Result<u64> {
+    let fee_amount = pending_op.fee_amount;
+    let reimbursed = reimbursement.map_or(0, |(_, amount)| amount);
+    let treasury_fee = fee_amount
+        .checked_sub(reimbursed)
+        .ok_or(CloakCraftError::InvalidAtaReimbursement)?;
+
+    if protocol_config.fees_enabled && !pending_op.is_batch() {
+        let transfer_amount = pending_op.transfer_amount;
+        let expected_fee = protocol_config.expected_transfer_fee(&pool.token_mint, transfer_amount, unshield_amount)?;
+
+        msg!("Fee verification: transfer={}, unshield={}, expected={}, provided={}, reimbursed={}",
+            transfer_amount, unshield_amount, expected_fee, fee_amount, reimbursed);
+
+        // ENFORCE: treasury share must be >= expected
+        require!(
+            treasury_fee >= expected_fee,
+            CloakCraftError::InsufficientFee
+        );
+    }
+
+    if fee_amount == 0 || pending_op.fee_processed {
+        return Ok(0);
+    }
+
+    let pool_authority = pool.to_account_info();
+
+    if treasury_fee > 0 {
+        let treasury = treasury.ok_or(CloakCraftError::InvalidTreasury)?;
+        msg!("Transferring {} fee to treasury {:?}", treasury_fee, treasury.key());
+        transfer_from_vault(token_program, vault, treasury, &pool_authority, pool_seeds, treasury_fee)?;
+    }
+
+    if let Some((relayer_token_account, amount)) = reimbursement.filter(|(_, amount)| *amount > 0) {
+        msg!("Reimbursing relayer {} tokens out of the fee", amount);
+        transfer_from_vault(token_program, vault, relayer_token_account, &pool_authority, pool_seeds, amount)?;
+    }
+
+    update_pool_balance(pool, fee_amount, false)?;
+    pending_op.fee_processed = true;
+
+    msg!("✅ Fee transfer complete");
+    Ok(treasury_fee)
+}
+
+/// Pay an unshield out of the vault and debit the pool balance
+///
+/// `recipient` is taken as an AccountInfo since it may be an ATA created
+/// earlier in the same instruction.
+pub fn pay_unshield<'info>(
+    token_program: &Program<'info, Token>,
+    vault: &Account<'info, TokenAccount>,
+    recipient: AccountInfo<'info>,
+    pool: &mut Account<'info, Pool>,
+    pool_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    msg!("Unshielding {} tokens to {:?}", amount, recipient.key);
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: recipient,
+                authority: pool.to_account_info(),
+            },
+            pool_seeds,
+        ),
+        amount,
+    )?;
+
+    update_pool_balance(pool, amount, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::fixed::apply_bps;
+
+    const SHIELDED: u64 = 1_000_000;
+
+    fn stats() -> PoolStats {
+        PoolStats {
+            pool: Pubkey::new_unique(),
+            total_shielded: SHIELDED,
+            lifetime_deposits: SHIELDED,
+            lifetime_withdrawals: 0,
+            lifetime_fees: 0,
+            note_count: 0,
+            total_notes_created: 0,
+            epoch: 0,
+            epoch_deposits: 0,
+            epoch_withdrawals: 0,
+            updated_at: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_swap_protocol_fee_keeps_pool_solvent() {
+        // Vault backs the shielded supply exactly (notes + AMM reserves)
+        let mut pool = Pool { total_shielded: SHIELDED, ..Default::default() };
+        let mut pool_stats = stats();
+        let mut vault_balance = SHIELDED;
+        assert_eq!(vault_shortfall(vault_balance, pool.total_shielded, 0), 0);
+
+        // Swap of 100_000 at a 30 bps LP fee, protocol takes 20% of it
+        let lp_fee = apply_bps(100_000, 30);
+        let protocol_fee = apply_bps(lp_fee, 2_000);
+        assert_eq!(protocol_fee, 60);
+
+        // execute_swap moves the fee to the treasury and debits the supply
+        vault_balance -= protocol_fee;
+        assert_eq!(vault_shortfall(vault_balance, SHIELDED, 0), protocol_fee);
+        debit_protocol_fee(&mut pool, Some(&mut pool_stats), protocol_fee, 10).unwrap();
+
+        assert_eq!(pool.total_shielded, SHIELDED - protocol_fee);
+        assert_eq!(pool_stats.total_shielded, SHIELDED - protocol_fee);
+        assert_eq!(pool_stats.lifetime_fees, protocol_fee);
+        assert_eq!(pool_stats.lifetime_withdrawals, 0);
+        assert_eq!(vault_shortfall(vault_balance, pool.total_shielded, 0), 0);
+        assert_eq!(vault_shortfall(vault_balance, pool_stats.total_shielded, 0), 0);
+    }
+
+    #[test]
+    fn test_vault_shortfall_allows_pending_outflows() {
+        assert_eq!(vault_shortfall(900, 1_000, 100), 0);
+        assert_eq!(vault_shortfall(850, 1_000, 100), 50);
+        assert_eq!(vault_shortfall(0, 100, 200), 0);
+    }
+}
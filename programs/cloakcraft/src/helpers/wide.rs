@@ -0,0 +1,240 @@
+//! Checked 256-bit unsigned integer
+//!
+//! Just enough of a U256 for the StableSwap invariant: D^3 terms of 1e18-scaled
+//! reserves overflow u128 long before reserves get realistic. Every operation
+//! is checked and returns None on overflow, underflow or division by zero,
+//! matching the u128 `checked_*` API it replaces.
+
+use core::cmp::Ordering;
+
+/// Little-endian 64-bit limbs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct U256([u64; 4]);
+
+impl U256 {
+    pub const ZERO: U256 = U256([0; 4]);
+    pub const ONE: U256 = U256([1, 0, 0, 0]);
+
+    /// Narrow to u128 (None if the high half is set)
+    pub fn to_u128(self) -> Option<u128> {
+        if self.0[2] != 0 || self.0[3] != 0 {
+            return None;
+        }
+        Some(self.low_u128())
+    }
+
+    fn low_u128(self) -> u128 {
+        (self.0[1] as u128) << 64 | self.0[0] as u128
+    }
+
+    pub fn is_zero(self) -> bool {
+        self == Self::ZERO
+    }
+
+    /// Number of significant bits
+    fn bits(self) -> u32 {
+        for i in (0..4).rev() {
+            if self.0[i] != 0 {
+                return 64 * i as u32 + 64 - self.0[i].leading_zeros();
+            }
+        }
+        0
+    }
+
+    fn bit(self, index: u32) -> bool {
+        self.0[(index / 64) as usize] >> (index % 64) & 1 == 1
+    }
+
+    /// Shift right by `shift` <= 256 bits
+    fn shr(self, shift: u32) -> U256 {
+        let (limbs, bits) = ((shift / 64) as usize, shift % 64);
+        let mut out = [0u64; 4];
+        for (i, limb) in out.iter_mut().enumerate().take(4 - limbs) {
+            *limb = self.0[i + limbs] >> bits;
+            if bits > 0 && i + limbs + 1 < 4 {
+                *limb |= self.0[i + limbs + 1] << (64 - bits);
+            }
+        }
+        U256(out)
+    }
+
+    /// Shift left by one, dropping the top bit
+    fn shl1(self) -> U256 {
+        let mut out = [0u64; 4];
+        for (i, limb) in out.iter_mut().enumerate() {
+            *limb = self.0[i] << 1 | if i > 0 { self.0[i - 1] >> 63 } else { 0 };
+        }
+        U256(out)
+    }
+
+    pub fn checked_add(self, other: U256) -> Option<U256> {
+        let mut out = [0u64; 4];
+        let mut carry = false;
+        for ((limb, a), b) in out.iter_mut().zip(self.0).zip(other.0) {
+            let (sum, c1) = a.overflowing_add(b);
+            let (sum, c2) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = c1 || c2;
+        }
+        (!carry).then_some(U256(out))
+    }
+
+    pub fn checked_sub(self, other: U256) -> Option<U256> {
+        let (diff, borrow) = self.overflowing_sub(other);
+        (!borrow).then_some(diff)
+    }
+
+    fn overflowing_sub(self, other: U256) -> (U256, bool) {
+        let mut out = [0u64; 4];
+        let mut borrow = false;
+        for ((limb, a), b) in out.iter_mut().zip(self.0).zip(other.0) {
+            let (diff, b1) = a.overflowing_sub(b);
+            let (diff, b2) = diff.overflowing_sub(borrow as u64);
+            *limb = diff;
+            borrow = b1 || b2;
+        }
+        (U256(out), borrow)
+    }
+
+    pub fn checked_mul(self, other: U256) -> Option<U256> {
+        let mut out = [0u64; 4];
+        for i in 0..4 {
+            if self.0[i] == 0 {
+                continue;
+            }
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let product = self.0[i] as u128 * other.0[j] as u128 + carry;
+                if i + j < 4 {
+                    let sum = out[i + j] as u128 + (product as u64) as u128;
+                    out[i + j] = sum as u64;
+                    carry = (product >> 64) + (sum >> 64);
+                } else if product != 0 {
+                    return None;
+                } else {
+                    carry = 0;
+                }
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+        Some(U256(out))
+    }
+
+    /// Rounds down
+    pub fn checked_div(self, divisor: U256) -> Option<U256> {
+        if divisor.is_zero() {
+            return None;
+        }
+        if let (Some(a), Some(b)) = (self.to_u128(), divisor.to_u128()) {
+            return Some(U256::from(a / b));
+        }
+        if self < divisor {
+            return Some(Self::ZERO);
+        }
+
+        // Shift-subtract long division over the quotient bits only: the top
+        // bits of the dividend narrower than the divisor seed the remainder.
+        // The remainder stays below the divisor, so a bit shifted out of it
+        // means the true remainder exceeds 2^256 and the (wrapping)
+        // subtraction is always due.
+        let top = self.bits() - divisor.bits();
+        let mut quotient = Self::ZERO;
+        let mut remainder = self.shr(top + 1);
+        for index in (0..=top).rev() {
+            let carry = remainder.0[3] >> 63 == 1;
+            remainder = remainder.shl1();
+            if self.bit(index) {
+                remainder.0[0] |= 1;
+            }
+            if carry || remainder >= divisor {
+                remainder = remainder.overflowing_sub(divisor).0;
+                quotient.0[(index / 64) as usize] |= 1 << (index % 64);
+            }
+        }
+        Some(quotient)
+    }
+}
+
+impl From<u128> for U256 {
+    fn from(value: u128) -> Self {
+        U256([value as u64, (value >> 64) as u64, 0, 0])
+    }
+}
+
+impl From<u64> for U256 {
+    fn from(value: u64) -> Self {
+        U256([value, 0, 0, 0])
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX: U256 = U256([u64::MAX; 4]);
+
+    #[test]
+    fn test_matches_u128() {
+        let values = [0u128, 1, 3, 1_000_000_007, u64::MAX as u128, 1 << 100, u128::MAX / 3, u128::MAX];
+        for &a in &values {
+            for &b in &values {
+                let (wa, wb) = (U256::from(a), U256::from(b));
+                assert_eq!(wa.checked_add(wb).and_then(U256::to_u128), a.checked_add(b));
+                assert_eq!(wa.checked_sub(wb).and_then(U256::to_u128), a.checked_sub(b));
+                assert_eq!(wa.checked_mul(wb).and_then(U256::to_u128), a.checked_mul(b));
+                assert_eq!(wa.checked_div(wb).and_then(U256::to_u128), a.checked_div(b));
+                assert_eq!(wa.cmp(&wb), a.cmp(&b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_wide_products() {
+        // (2^128 - 1)^2 = 2^256 - 2^129 + 1 fits; divides back exactly
+        let a = U256::from(u128::MAX);
+        let square = a.checked_mul(a).unwrap();
+        assert_eq!(square.checked_div(a), Some(a));
+        assert_eq!(square.checked_div(square), Some(U256::ONE));
+        assert_eq!(square.checked_sub(square.checked_add(U256::ONE).unwrap()), None);
+
+        // 2^128 * 2^128 overflows
+        let two_128 = a.checked_add(U256::ONE).unwrap();
+        assert_eq!(two_128.checked_mul(two_128), None);
+        assert_eq!(MAX.checked_add(U256::ONE), None);
+        assert_eq!(MAX.checked_mul(U256::from(2u64)), None);
+        assert_eq!(MAX.checked_div(U256::ZERO), None);
+
+        // Full-width division, including a divisor with the top bit set
+        assert_eq!(MAX.checked_div(MAX), Some(U256::ONE));
+        assert_eq!(MAX.checked_div(U256::from(u64::MAX)), Some(U256([1, 1, 1, 1])));
+        let (q, d) = (U256::from(1_000_000_007u64), U256::from(u128::MAX / 5));
+        assert_eq!(q.checked_mul(d).unwrap().checked_add(U256::from(12_345u64)).unwrap().checked_div(d), Some(q));
+
+        // (q * d + r) / d == q for mixed widths
+        let mut seed = 0x9E37_79B9_7F4A_7C15u64;
+        let mut next = || {
+            seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+            (seed as u128) << 64 | seed.rotate_left(17) as u128
+        };
+        for _ in 0..1_000 {
+            let (q, d) = (next() >> (next() % 128), (next() >> (next() % 128)).max(1));
+            let n = U256::from(q).checked_mul(U256::from(d)).unwrap().checked_add(U256::from(next() % d)).unwrap();
+            let (q, d) = (U256::from(q), U256::from(d));
+            assert_eq!(n.checked_div(d), Some(q));
+        }
+    }
+}
@@ -1,5 +1,9 @@
-//! Adapter instructions for external DEX integration
+//! Adapter instructions for external integrations (DEX swaps, Bubblegum cNFTs)
 
 mod transact_adapt;
+mod shield_cnft;
+mod process_unshield_cnft;
 
 pub use transact_adapt::*;
+pub use shield_cnft::*;
+pub use process_unshield_cnft::*;
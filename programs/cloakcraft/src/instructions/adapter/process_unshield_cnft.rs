@@ -0,0 +1,142 @@
+//! Process Unshield cNFT - Phase 3 variant for compressed NFT pools
+//!
+//! Transfers the cNFT leaf from the custody PDA to the recipient wallet.
+//! The unshield amount comes from the PendingOperation (bound by the NFT
+//! transfer proof in Phase 0) and must be exactly 1; NFT operations carry no
+//! protocol fee. The leaf's merkle proof nodes are passed as remaining accounts.
+//!
+//! Flow:
+//! Phase 0: Verify ZK proof (transfer_1x2_nft) + Create pending operation
+//! Phase 1: Verify commitment exists
+//! Phase 2: Create nullifier via generic instruction
+//! Phase 3 (this): Release the cNFT
+//! Phase 4+: Create output commitments via generic instruction
+//! Final: Close pending operation
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, PendingOperation, OperationKind, NFT_STANDARD_CNFT};
+use crate::constants::{seeds, BUBBLEGUM_PROGRAM_ID, SPL_ACCOUNT_COMPRESSION_PROGRAM_ID, SPL_NOOP_PROGRAM_ID};
+use crate::errors::CloakCraftError;
+use crate::helpers::bubblegum::{asset_id, transfer_compressed_nft, CnftLeaf, CnftTransferAccounts};
+use crate::helpers::vault::update_pool_balance;
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct ProcessUnshieldCnft<'info> {
+    /// cNFT pool
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.nft_standard == NFT_STANDARD_CNFT @ CloakCraftError::InvalidNftPool,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pending operation PDA
+    #[account(
+        mut,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump = pending_operation.bump,
+        constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Custody PDA (current leaf owner and delegate)
+    /// CHECK: PDA, holds no data
+    #[account(seeds = [seeds::CNFT_CUSTODY], bump)]
+    pub custody: UncheckedAccount<'info>,
+
+    /// Bubblegum tree config
+    /// CHECK: Verified in transfer_compressed_nft
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// cNFT merkle tree
+    /// CHECK: Verified by Bubblegum / account compression
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// Wallet receiving the cNFT
+    /// CHECK: Any wallet
+    pub recipient: UncheckedAccount<'info>,
+
+    /// Relayer (must match operation creator)
+    #[account(
+        mut,
+        constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
+    )]
+    pub relayer: Signer<'info>,
+
+    /// SPL Noop program
+    /// CHECK: Address checked
+    #[account(address = SPL_NOOP_PROGRAM_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// SPL Account Compression program
+    /// CHECK: Address checked
+    #[account(address = SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// Bubblegum program
+    /// CHECK: Address checked
+    #[account(address = BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Phase 3 (cNFT variant): Release the cNFT to the recipient
+pub fn process_unshield_cnft<'info>(
+    ctx: Context<'_, '_, '_, 'info, ProcessUnshieldCnft<'info>>,
+    _operation_id: [u8; 32],
+    leaf: CnftLeaf,
+) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::Transfer)?;
+
+    let pool = &mut ctx.accounts.pool;
+    let pending_op = &mut ctx.accounts.pending_operation;
+
+    msg!("=== Phase 3: Process Unshield (cNFT) ===");
+
+    require!(
+        pending_op.all_nullifiers_created(),
+        CloakCraftError::NullifiersNotCreated
+    );
+    require!(
+        pending_op.unshield_amount == 1 && pending_op.fee_amount == 0,
+        CloakCraftError::InvalidNftAmount
+    );
+    require!(
+        asset_id(&ctx.accounts.merkle_tree.key(), leaf.nonce) == pool.token_mint,
+        CloakCraftError::InvalidNftMetadata
+    );
+
+    let custody_seeds: &[&[u8]] = &[seeds::CNFT_CUSTODY, &[ctx.bumps.custody]];
+    let custody = ctx.accounts.custody.to_account_info();
+
+    transfer_compressed_nft(
+        CnftTransferAccounts {
+            tree_authority: &ctx.accounts.tree_authority.to_account_info(),
+            leaf_owner: &custody,
+            leaf_delegate: &custody,
+            new_leaf_owner: &ctx.accounts.recipient.to_account_info(),
+            merkle_tree: &ctx.accounts.merkle_tree.to_account_info(),
+            log_wrapper: &ctx.accounts.log_wrapper.to_account_info(),
+            compression_program: &ctx.accounts.compression_program.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            bubblegum_program: &ctx.accounts.bubblegum_program.to_account_info(),
+        },
+        &leaf,
+        ctx.remaining_accounts,
+        &[custody_seeds],
+    )?;
+
+    update_pool_balance(pool, 1, false)?;
+    pending_op.unshield_amount = 0;
+
+    msg!("✅ cNFT {} released to {:?}", pool.token_mint, ctx.accounts.recipient.key());
+    msg!("Next: Phase 4+ - create_commitment");
+
+    Ok(())
+}
@@ -0,0 +1,243 @@
+//! Shield cNFT - move a Bubblegum compressed NFT into protocol custody
+//!
+//! Each cNFT gets its own NFT pool keyed by its asset id (`pool.token_mint`),
+//! created on first shield together with its commitment counter. The leaf is
+//! transferred to the custody PDA and the note commits to the asset id with
+//! amount = 1, so transfers use the same NFT rules as SPL NFTs
+//! (transfer_1x2_nft circuit) and exit through process_unshield_cnft.
+//!
+//! The pool is pinned to the Light trees used by its first commitment and is
+//! administered by the protocol authority (there is no pool creator to own it),
+//! so its trees can be migrated like any other pool's.
+//!
+//! Remaining accounts: the leaf's merkle proof nodes (`proof_len`), followed by
+//! the Light Protocol accounts.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, PoolCommitmentCounter, ProtocolConfig, MAX_DENOMINATIONS, NFT_STANDARD_CNFT, RootRegistry, TreeRegistry};
+use crate::constants::{seeds, BUBBLEGUM_PROGRAM_ID, SPL_ACCOUNT_COMPRESSION_PROGRAM_ID, SPL_NOOP_PROGRAM_ID};
+use crate::errors::CloakCraftError;
+use crate::helpers::bubblegum::{asset_id as derive_asset_id, transfer_compressed_nft, CnftLeaf, CnftTransferAccounts};
+use crate::helpers::vault::update_pool_balance;
+use crate::light_cpi::{create_commitment_account, resolve_trees, vec_to_fixed_note};
+use crate::instructions::pool::LightCommitmentParams;
+
+#[derive(Accounts)]
+#[instruction(asset_id: Pubkey)]
+pub struct ShieldCnft<'info> {
+    /// cNFT pool (keyed by asset id, created on first shield)
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = Pool::LEN,
+        seeds = [seeds::POOL, asset_id.as_ref()],
+        bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Commitment counter for this pool (created on first shield)
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + PoolCommitmentCounter::INIT_SPACE,
+        seeds = [PoolCommitmentCounter::SEEDS_PREFIX, pool.key().as_ref()],
+        bump,
+    )]
+    pub commitment_counter: Box<Account<'info, PoolCommitmentCounter>>,
+
+    /// Custody PDA (new leaf owner)
+    /// CHECK: PDA, holds no data
+    #[account(seeds = [seeds::CNFT_CUSTODY], bump)]
+    pub custody: UncheckedAccount<'info>,
+
+    /// Bubblegum tree config
+    /// CHECK: Verified in transfer_compressed_nft
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// cNFT merkle tree
+    /// CHECK: Verified by Bubblegum / account compression
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// Leaf delegate (the user if no delegate is set)
+    /// CHECK: Verified by Bubblegum against the leaf
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    /// User (current leaf owner, pays for pool creation and compressed account)
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// SPL Noop program
+    /// CHECK: Address checked
+    #[account(address = SPL_NOOP_PROGRAM_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// SPL Account Compression program
+    /// CHECK: Address checked
+    #[account(address = SPL_ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// Bubblegum program
+    /// CHECK: Address checked
+    #[account(address = BUBBLEGUM_PROGRAM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Root registry (the commitment is appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, pool.key().as_ref()],
+        bump,
+    )]
+    pub root_registry: UncheckedAccount<'info>,
+
+    /// Tree registry (once initialized, the output tree must be registered
+    /// and its writes are counted toward TreeNearCapacity)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: UncheckedAccount<'info>,
+
+    /// Protocol config (its authority becomes the authority of a new cNFT pool)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+}
+
+/// Emitted for every cNFT shield
+#[event]
+pub struct CnftShielded {
+    pub pool: Pubkey,
+    pub asset_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub metadata_hash: [u8; 32],
+    pub leaf_index: u64,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn shield_cnft<'info>(
+    ctx: Context<'_, '_, '_, 'info, ShieldCnft<'info>>,
+    asset_id: Pubkey,
+    leaf: CnftLeaf,
+    proof_len: u8,
+    commitment: [u8; 32],
+    stealth_ephemeral_pubkey: [u8; 64],
+    encrypted_note: Vec<u8>,
+    light_params: LightCommitmentParams,
+    view_tag: Option<[u8; 8]>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let commitment_counter = &mut ctx.accounts.commitment_counter;
+    let clock = Clock::get()?;
+
+    require!(
+        asset_id == derive_asset_id(&ctx.accounts.merkle_tree.key(), leaf.nonce),
+        CloakCraftError::InvalidNftMetadata
+    );
+    require!(
+        (proof_len as usize) <= ctx.remaining_accounts.len(),
+        CloakCraftError::InvalidNftMetadata
+    );
+    let (proof, light_accounts) = ctx.remaining_accounts.split_at(proof_len as usize);
+
+    if pool.token_mint == Pubkey::default() {
+        // First shield: set up the asset's pool (no token vault, custody holds the leaf)
+        pool.token_mint = asset_id;
+        pool.token_vault = ctx.accounts.custody.key();
+        pool.authority = ctx.accounts.protocol_config.authority;
+        pool.bump = ctx.bumps.pool;
+        pool.vault_bump = ctx.bumps.custody;
+        pool.total_shielded = 0;
+        pool.activity_epoch = Pool::activity_epoch_at(clock.unix_timestamp);
+        pool.fixed_denominations = [0; MAX_DENOMINATIONS];
+        pool.nft_standard = NFT_STANDARD_CNFT;
+        pool.nft_metadata_hash = leaf.metadata_hash();
+        pool.layout_version = Pool::LAYOUT_VERSION;
+
+        // Pool is pinned to the Light trees of its first commitment
+        let trees = resolve_trees(
+            &ctx.accounts.user.to_account_info(),
+            light_accounts,
+            &light_params.address_tree_info,
+            light_params.output_tree_index,
+        )?;
+        pool.state_tree = trees.state_tree;
+        pool.address_tree = trees.address_tree;
+
+        commitment_counter.pool = pool.key();
+        commitment_counter.next_leaf_index = 0;
+        commitment_counter.total_commitments = 0;
+        commitment_counter.bump = ctx.bumps.commitment_counter;
+
+        msg!("Created cNFT pool {} for asset {}", pool.key(), asset_id);
+    }
+
+    // Re-shielding is only possible once the asset has left custody
+    require!(
+        pool.nft_standard == NFT_STANDARD_CNFT && pool.total_shielded == 0,
+        CloakCraftError::InvalidNftPool
+    );
+
+    transfer_compressed_nft(
+        CnftTransferAccounts {
+            tree_authority: &ctx.accounts.tree_authority.to_account_info(),
+            leaf_owner: &ctx.accounts.user.to_account_info(),
+            leaf_delegate: &ctx.accounts.leaf_delegate.to_account_info(),
+            new_leaf_owner: &ctx.accounts.custody.to_account_info(),
+            merkle_tree: &ctx.accounts.merkle_tree.to_account_info(),
+            log_wrapper: &ctx.accounts.log_wrapper.to_account_info(),
+            compression_program: &ctx.accounts.compression_program.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+            bubblegum_program: &ctx.accounts.bubblegum_program.to_account_info(),
+        },
+        &leaf,
+        proof,
+        &[],
+    )?;
+
+    let leaf_index = commitment_counter.allocate();
+
+    // Note commits to (stealth pubkey, asset id, amount = 1, randomness).
+    // Always created: the asset is already in custody, so a shield without
+    // its note would strand it.
+    let (encrypted_note_arr, encrypted_note_len) = vec_to_fixed_note(&encrypted_note);
+    create_commitment_account(
+        &ctx.accounts.user.to_account_info(),
+        light_accounts,
+        light_params.validity_proof,
+        light_params.address_tree_info,
+        light_params.output_tree_index,
+        pool.key(),
+        pool.active_trees(Clock::get()?.slot),
+        commitment,
+        leaf_index,
+        stealth_ephemeral_pubkey,
+        encrypted_note_arr,
+        encrypted_note_len,
+        view_tag.unwrap_or_default(),
+    )?;
+    TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &pool.active_trees(Clock::get()?.slot).state_tree, 1)?;
+    RootRegistry::append_to(&ctx.accounts.root_registry, commitment, Clock::get()?.slot)?;
+
+    update_pool_balance(pool, 1, true)?;
+    pool.record_shield(clock.unix_timestamp);
+
+    emit!(CnftShielded {
+        pool: pool.key(),
+        asset_id,
+        merkle_tree: ctx.accounts.merkle_tree.key(),
+        metadata_hash: pool.nft_metadata_hash,
+        leaf_index,
+    });
+
+    Ok(())
+}
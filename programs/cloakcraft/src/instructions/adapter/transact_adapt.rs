@@ -5,7 +5,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
-use crate::state::{Pool, AdaptModule, VerificationKey, PoolCommitmentCounter, LightValidityProof, LightAddressTreeInfo};
+use crate::state::{Pool, NullifierDomain, AdaptModule, VerificationKey, PoolCommitmentCounter, LightValidityProof, LightAddressTreeInfo, VIEW_TAG_SIZE, RootRegistry, TreeRegistry};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 use crate::helpers::verify_groth16_proof;
@@ -14,18 +14,24 @@ use crate::light_cpi::{create_spend_nullifier_account, create_commitment_account
 #[derive(Accounts)]
 pub struct TransactAdapt<'info> {
     /// Input token pool (boxed to reduce stack usage)
+    /// Denomination and NFT pools only accept their own transfer circuits
     #[account(
         mut,
         seeds = [seeds::POOL, input_pool.token_mint.as_ref()],
         bump = input_pool.bump,
+        constraint = !input_pool.has_fixed_denominations() @ CloakCraftError::InvalidDenomination,
+        constraint = !input_pool.is_nft_pool() @ CloakCraftError::InvalidNftPool,
     )]
     pub input_pool: Box<Account<'info, Pool>>,
 
     /// Output token pool (boxed to reduce stack usage)
+    /// Denomination and NFT pools only accept their own transfer circuits
     #[account(
         mut,
         seeds = [seeds::POOL, output_pool.token_mint.as_ref()],
         bump = output_pool.bump,
+        constraint = !output_pool.has_fixed_denominations() @ CloakCraftError::InvalidDenomination,
+        constraint = !output_pool.is_nft_pool() @ CloakCraftError::InvalidNftPool,
     )]
     pub output_pool: Box<Account<'info, Pool>>,
 
@@ -35,7 +41,7 @@ pub struct TransactAdapt<'info> {
         seeds = [PoolCommitmentCounter::SEEDS_PREFIX, output_pool.key().as_ref()],
         bump = output_commitment_counter.bump,
     )]
-    pub output_commitment_counter: Account<'info, PoolCommitmentCounter>,
+    pub output_commitment_counter: Box<Account<'info, PoolCommitmentCounter>>,
 
     /// Input token vault
     #[account(
@@ -43,7 +49,7 @@ pub struct TransactAdapt<'info> {
         seeds = [seeds::VAULT, input_pool.token_mint.as_ref()],
         bump = input_pool.vault_bump,
     )]
-    pub input_vault: Account<'info, TokenAccount>,
+    pub input_vault: Box<Account<'info, TokenAccount>>,
 
     /// Output token vault
     #[account(
@@ -51,7 +57,7 @@ pub struct TransactAdapt<'info> {
         seeds = [seeds::VAULT, output_pool.token_mint.as_ref()],
         bump = output_pool.vault_bump,
     )]
-    pub output_vault: Account<'info, TokenAccount>,
+    pub output_vault: Box<Account<'info, TokenAccount>>,
 
     /// Adapter module
     #[account(
@@ -59,12 +65,13 @@ pub struct TransactAdapt<'info> {
         bump = adapt_module.bump,
         constraint = adapt_module.is_usable() @ CloakCraftError::AdapterDisabled,
     )]
-    pub adapt_module: Account<'info, AdaptModule>,
+    pub adapt_module: Box<Account<'info, AdaptModule>>,
 
     /// Verification key (boxed to reduce stack usage)
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
@@ -75,6 +82,25 @@ pub struct TransactAdapt<'info> {
     /// Token program
     pub token_program: Program<'info, Token>,
 
+    /// Output pool root registry (the commitment is appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, output_pool.key().as_ref()],
+        bump,
+    )]
+    pub root_registry: UncheckedAccount<'info>,
+
+    /// Tree registry (once initialized, the output tree must be registered
+    /// and its writes are counted toward TreeNearCapacity)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: UncheckedAccount<'info>,
+
     // Light Protocol accounts are passed via remaining_accounts
 }
 
@@ -109,6 +135,12 @@ pub fn transact_adapt<'info>(
     let output_commitment_counter = &mut ctx.accounts.output_commitment_counter;
     let clock = Clock::get()?;
 
+    // Anonymity guard: the input leaves the pool for an external DEX, like an unshield
+    require!(
+        !input_pool.anonymity_guard_active(clock.unix_timestamp),
+        CloakCraftError::AnonymityGuardActive
+    );
+
     // 1. Verify ZK proof (proves ownership of input, specifies amounts)
     // Note: amounts are public in adapter circuit for DEX compatibility
     // Merkle root is now verified by Light Protocol validity proof
@@ -139,8 +171,11 @@ pub fn transact_adapt<'info>(
             params.nullifier_address_tree_info.clone(),
             params.output_tree_index,
             input_pool.key(),
+            input_pool.active_trees(Clock::get()?.slot),
+            NullifierDomain::Spend,
             nullifier,
         )?;
+        TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &input_pool.active_trees(Clock::get()?.slot).state_tree, 1)?;
     }
     // 3. CPI to adapter to execute swap
     // In production: CPI to Jupiter/etc via adapter interface
@@ -158,9 +193,7 @@ pub fn transact_adapt<'info>(
 
     // 5. Create output commitment via Light Protocol
     // Encrypted note is stored inline for direct scanning
-    let leaf_index = output_commitment_counter.next_leaf_index;
-    output_commitment_counter.next_leaf_index += 1;
-    output_commitment_counter.total_commitments += 1;
+    let leaf_index = output_commitment_counter.allocate();
 
     if let Some(ref params) = light_params {
         let (encrypted_note_arr, encrypted_note_len) = vec_to_fixed_note(&encrypted_note);
@@ -171,12 +204,16 @@ pub fn transact_adapt<'info>(
             params.commitment_address_tree_info.clone(),
             params.output_tree_index,
             output_pool.key(),
+            output_pool.active_trees(Clock::get()?.slot),
             out_commitment,
             leaf_index,
             [0u8; 64],
             encrypted_note_arr,
             encrypted_note_len,
+            [0u8; VIEW_TAG_SIZE],
         )?;
+        TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &output_pool.active_trees(Clock::get()?.slot).state_tree, 1)?;
+        RootRegistry::append_to(&ctx.accounts.root_registry, out_commitment, Clock::get()?.slot)?;
     }
 
     // Update TVL tracking
@@ -5,8 +5,10 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{VerificationKey, MAX_VK_DATA_SIZE};
+use crate::state::{VerificationKey, MAX_VK_DATA_SIZE, AdminAction, AdminActionRecord};
 use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
 
 #[derive(Accounts)]
 #[instruction(circuit_id: [u8; 32])]
@@ -18,6 +20,7 @@ pub struct AppendVerificationKeyData<'info> {
         seeds = [seeds::VERIFICATION_KEY, circuit_id.as_ref()],
         bump = verification_key.bump,
         has_one = authority,
+        constraint = !verification_key.is_finalized @ CloakCraftError::VerificationKeyFinalized,
         realloc = VerificationKey::space(MAX_VK_DATA_SIZE),
         realloc::payer = payer,
         realloc::zero = false,
@@ -35,12 +38,26 @@ pub struct AppendVerificationKeyData<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn append_verification_key_data(
-    ctx: Context<AppendVerificationKeyData>,
+pub fn append_verification_key_data<'info>(
+    ctx: Context<'_, '_, '_, 'info, AppendVerificationKeyData<'info>>,
     _circuit_id: [u8; 32],
     data_chunk: Vec<u8>,
 ) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.verification_key);
+
     let vk = &mut ctx.accounts.verification_key;
     vk.vk_data.extend(data_chunk);
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.verification_key);
+    record_admin_action(
+        &ctx.accounts.payer.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::AppendVerificationKeyData,
+        ctx.accounts.verification_key.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
     Ok(())
 }
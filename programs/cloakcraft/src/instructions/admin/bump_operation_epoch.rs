@@ -0,0 +1,54 @@
+//! Bump the operation epoch
+//!
+//! Incident response: every operation ID carries the epoch it was derived
+//! for, so bumping it invalidates all in-flight operations that have not yet
+//! created their nullifiers. Their notes stay unspent and their pending
+//! operations can be closed after expiry; users simply retry under the new epoch.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ProtocolConfig, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct BumpOperationEpoch<'info> {
+    /// Protocol config account
+    #[account(
+        mut,
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Authority that can update config
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Advance the operation epoch by one
+pub fn bump_operation_epoch<'info>(ctx: Context<'_, '_, '_, 'info, BumpOperationEpoch<'info>>) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.operation_epoch = config.operation_epoch
+        .checked_add(1)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    msg!("Operation epoch bumped to {}", config.operation_epoch);
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::BumpOperationEpoch,
+        ctx.accounts.protocol_config.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
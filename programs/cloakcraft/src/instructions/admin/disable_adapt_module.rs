@@ -2,9 +2,10 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::AdaptModule;
+use crate::state::{AdaptModule, AdminAction, AdminActionRecord};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
 
 #[derive(Accounts)]
 pub struct DisableAdaptModule<'info> {
@@ -18,10 +19,25 @@ pub struct DisableAdaptModule<'info> {
     pub adapt_module: Account<'info, AdaptModule>,
 
     /// Authority
+    #[account(mut)]
     pub authority: Signer<'info>,
 }
 
-pub fn disable_adapt_module(ctx: Context<DisableAdaptModule>) -> Result<()> {
+pub fn disable_adapt_module<'info>(ctx: Context<'_, '_, '_, 'info, DisableAdaptModule<'info>>) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.adapt_module);
+
     ctx.accounts.adapt_module.enabled = false;
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.adapt_module);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::DisableAdaptModule,
+        ctx.accounts.adapt_module.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
     Ok(())
 }
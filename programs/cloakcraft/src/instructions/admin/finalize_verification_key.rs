@@ -0,0 +1,85 @@
+//! Finalize a verification key
+//!
+//! Checks the uploaded `vk_data` against the hash the authority expects (so a
+//! chunk corrupted or swapped mid-upload is caught), records the hash and
+//! freezes the account. Proof-verifying instructions only accept finalized
+//! keys. Emits `VerificationKeyFinalized` so clients can pin the hash.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{VerificationKey, MAX_VK_DATA_SIZE, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+#[instruction(circuit_id: [u8; 32])]
+pub struct FinalizeVerificationKey<'info> {
+    /// Verification key account
+    /// Realloc to max size so keys registered before finalization fit the new fields
+    #[account(
+        mut,
+        seeds = [seeds::VERIFICATION_KEY, circuit_id.as_ref()],
+        bump = verification_key.bump,
+        has_one = authority,
+        constraint = !verification_key.is_finalized @ CloakCraftError::VerificationKeyFinalized,
+        realloc = VerificationKey::space(MAX_VK_DATA_SIZE),
+        realloc::payer = payer,
+        realloc::zero = false,
+    )]
+    pub verification_key: Account<'info, VerificationKey>,
+
+    /// Authority
+    pub authority: Signer<'info>,
+
+    /// Payer for reallocation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program for reallocation
+    pub system_program: Program<'info, System>,
+}
+
+#[event]
+pub struct VerificationKeyFinalized {
+    pub verification_key: Pubkey,
+    pub circuit_id: [u8; 32],
+    /// keccak256(vk_data)
+    pub vk_hash: [u8; 32],
+    pub vk_len: u32,
+}
+
+pub fn finalize_verification_key<'info>(
+    ctx: Context<'_, '_, '_, 'info, FinalizeVerificationKey<'info>>,
+    circuit_id: [u8; 32],
+    expected_hash: [u8; 32],
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.verification_key);
+
+    let vk = &mut ctx.accounts.verification_key;
+    let vk_hash = VerificationKey::compute_hash(&vk.vk_data);
+    require!(vk_hash == expected_hash, CloakCraftError::VerificationKeyHashMismatch);
+
+    vk.is_finalized = true;
+    vk.vk_hash = vk_hash;
+
+    emit!(VerificationKeyFinalized {
+        verification_key: vk.key(),
+        circuit_id,
+        vk_hash,
+        vk_len: vk.vk_data.len() as u32,
+    });
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.verification_key);
+    record_admin_action(
+        &ctx.accounts.payer.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::FinalizeVerificationKey,
+        ctx.accounts.verification_key.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
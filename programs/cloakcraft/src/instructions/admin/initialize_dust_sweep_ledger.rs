@@ -0,0 +1,74 @@
+//! Initialize the dust sweep ledger
+//!
+//! Creates the singleton DustSweepLedger PDA. Fund it with SOL transfers;
+//! bounties are paid from lamports above its rent-exempt minimum.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ProtocolConfig, DustSweepLedger, AdminAction, AdminActionRecord, MAX_DUST_SWEEP_BOUNTY_LAMPORTS};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct InitializeDustSweepLedger<'info> {
+    /// Dust sweep ledger (singleton PDA)
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DustSweepLedger::INIT_SPACE,
+        seeds = [seeds::DUST_SWEEP_LEDGER],
+        bump
+    )]
+    pub dust_sweep_ledger: Account<'info, DustSweepLedger>,
+
+    /// Protocol config account
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Protocol authority
+    pub authority: Signer<'info>,
+
+    /// Payer for account creation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Initialize the dust sweep ledger
+///
+/// # Arguments
+/// * `bounty_lamports` - Paid per qualifying consolidation (max 0.01 SOL)
+pub fn initialize_dust_sweep_ledger<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeDustSweepLedger<'info>>,
+    bounty_lamports: u64,
+) -> Result<()> {
+    require!(
+        bounty_lamports <= MAX_DUST_SWEEP_BOUNTY_LAMPORTS,
+        CloakCraftError::InvalidAmount
+    );
+
+    let ledger = &mut ctx.accounts.dust_sweep_ledger;
+    ledger.bounty_lamports = bounty_lamports;
+    ledger.bump = ctx.bumps.dust_sweep_ledger;
+    msg!("Dust sweep ledger initialized, bounty {} lamports", bounty_lamports);
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.dust_sweep_ledger);
+    record_admin_action(
+        &ctx.accounts.payer.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::InitializeDustSweepLedger,
+        ctx.accounts.dust_sweep_ledger.key(),
+        [0u8; 32],
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
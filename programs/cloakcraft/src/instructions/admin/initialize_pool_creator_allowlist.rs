@@ -0,0 +1,59 @@
+//! Initialize the AMM pool creator allowlist
+//!
+//! Creates the empty singleton PoolCreatorAllowlist PDA.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ProtocolConfig, PoolCreatorAllowlist, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct InitializePoolCreatorAllowlist<'info> {
+    /// Pool creator allowlist (singleton PDA)
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PoolCreatorAllowlist::INIT_SPACE,
+        seeds = [seeds::POOL_CREATOR_ALLOWLIST],
+        bump
+    )]
+    pub pool_creator_allowlist: Account<'info, PoolCreatorAllowlist>,
+
+    /// Protocol config account
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Protocol authority
+    pub authority: Signer<'info>,
+
+    /// Payer for account creation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_pool_creator_allowlist<'info>(ctx: Context<'_, '_, '_, 'info, InitializePoolCreatorAllowlist<'info>>) -> Result<()> {
+    ctx.accounts.pool_creator_allowlist.bump = ctx.bumps.pool_creator_allowlist;
+    msg!("Pool creator allowlist initialized");
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.pool_creator_allowlist);
+    record_admin_action(
+        &ctx.accounts.payer.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::InitializePoolCreatorAllowlist,
+        ctx.accounts.pool_creator_allowlist.key(),
+        [0u8; 32],
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! Initialize the program version account
+//!
+//! Created once by the protocol authority; later deploys bump it with
+//! `set_program_version`.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ProtocolConfig, ProgramVersion, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct InitializeProgramVersion<'info> {
+    /// Program version account
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProgramVersion::INIT_SPACE,
+        seeds = [seeds::PROGRAM_VERSION],
+        bump,
+    )]
+    pub program_version: Account<'info, ProgramVersion>,
+
+    /// Protocol config (authority check)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Protocol authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Initialize the program version
+///
+/// # Arguments
+/// * `version` - Deployed program version
+/// * `min_client_version` - Oldest compatible client version (<= version)
+pub fn initialize_program_version<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeProgramVersion<'info>>,
+    version: u32,
+    min_client_version: u32,
+) -> Result<()> {
+    require!(min_client_version <= version, CloakCraftError::InvalidProgramVersion);
+
+    let clock = Clock::get()?;
+    let program_version = &mut ctx.accounts.program_version;
+    program_version.version = version;
+    program_version.min_client_version = min_client_version;
+    program_version.updated_slot = clock.slot;
+    program_version.updated_at = clock.unix_timestamp;
+    program_version.bump = ctx.bumps.program_version;
+
+    msg!("Program version {} (min client {})", version, min_client_version);
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.program_version);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::InitializeProgramVersion,
+        ctx.accounts.program_version.key(),
+        [0u8; 32],
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
@@ -4,9 +4,10 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::ProtocolConfig;
+use crate::state::{ProtocolConfig, SURPLUS_POLICY_RESERVES, AMM_CREATION_PERMISSIONLESS, AdminAction, AdminActionRecord};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
 
 #[derive(Accounts)]
 pub struct InitializeProtocolConfig<'info> {
@@ -43,8 +44,8 @@ pub struct InitializeProtocolConfig<'info> {
 /// * `swap_fee_share_bps` - Protocol's share of LP fees in basis points (e.g., 2000 = 20%)
 /// * `remove_liquidity_fee_bps` - Remove liquidity fee in basis points (max 1000 = 10%)
 /// * `fees_enabled` - Whether fees are initially enabled
-pub fn initialize_protocol_config(
-    ctx: Context<InitializeProtocolConfig>,
+pub fn initialize_protocol_config<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeProtocolConfig<'info>>,
     transfer_fee_bps: u16,
     unshield_fee_bps: u16,
     swap_fee_share_bps: u16,
@@ -79,7 +80,15 @@ pub fn initialize_protocol_config(
     config.remove_liquidity_fee_bps = remove_liquidity_fee_bps;
     config.fees_enabled = fees_enabled;
     config.bump = ctx.bumps.protocol_config;
-    config._reserved = [0u8; 62];
+    config.pending_expiry_seconds = 0;
+    config.pending_expiry_overrides = Default::default();
+    config.rent_refund_bps = 0;
+    config.surplus_policy = SURPLUS_POLICY_RESERVES;
+    config.amm_creation_mode = AMM_CREATION_PERMISSIONLESS;
+    config.amm_creation_fee_lamports = 0;
+    config.operation_epoch = 0;
+    config.require_operation_credits = false;
+    config._reserved = [0u8; 1];
 
     msg!(
         "Protocol config initialized: transfer={}bps, unshield={}bps, swap_share={}bps, remove_liq={}bps, enabled={}",
@@ -90,5 +99,16 @@ pub fn initialize_protocol_config(
         fees_enabled
     );
 
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+    record_admin_action(
+        &ctx.accounts.payer.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::InitializeProtocolConfig,
+        ctx.accounts.protocol_config.key(),
+        [0u8; 32],
+        new_value_hash,
+    )?;
+
     Ok(())
 }
@@ -0,0 +1,75 @@
+//! Initialize the tree registry (admin only)
+//!
+//! Creates the empty registry of approved output state trees. Once it
+//! exists, pool tree rollovers must target a registered tree.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{TreeRegistry, ProtocolConfig, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct InitializeTreeRegistry<'info> {
+    /// Tree registry (singleton)
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TreeRegistry::INIT_SPACE,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: Box<Account<'info, TreeRegistry>>,
+
+    /// Protocol config (authority check)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Protocol authority (pays for the registry)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Initialize the tree registry
+///
+/// # Arguments
+/// * `near_capacity_bps` - Share of a tree's capacity at which TreeNearCapacity is emitted
+pub fn initialize_tree_registry<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeTreeRegistry<'info>>,
+    near_capacity_bps: u16,
+) -> Result<()> {
+    require!(
+        TreeRegistry::validate_threshold(near_capacity_bps),
+        CloakCraftError::InvalidTreeCapacity
+    );
+
+    let old_value_hash = AdminActionRecord::value_hash(&**ctx.accounts.tree_registry);
+
+    let registry = &mut ctx.accounts.tree_registry;
+    registry.tree_count = 0;
+    registry.near_capacity_bps = near_capacity_bps;
+    registry.bump = ctx.bumps.tree_registry;
+
+    msg!("Tree registry initialized (near capacity at {} bps)", near_capacity_bps);
+
+    let new_value_hash = AdminActionRecord::value_hash(&**ctx.accounts.tree_registry);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::InitializeTreeRegistry,
+        ctx.accounts.tree_registry.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+//! Migrate an AMM pool to a successor pool (admin only)
+//!
+//! Unlike `reset_amm_pool`, keeps the pool's liquidity: creates the pair's
+//! next-version pool and LP mint with the new formula and fee, exports the
+//! reserves into it and deactivates the old pool. `new_lp_supply` successor
+//! LP are reserved for the old pool's whole LP supply, and old LP notes
+//! convert with `create_pending_with_proof_convert_amm_lp_note`.
+//!
+//! Pools created before the version field are too small to load here; run
+//! `migrate_amm_pool_layout` on them first, which leaves them at version 0.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+use crate::state::{AmmPool, AmmPoolMigration, PoolType, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+/// Emitted when an AMM pool's reserves are exported to its successor
+#[event]
+pub struct AmmPoolMigrated {
+    pub old_pool: Pubkey,
+    pub new_pool: Pubkey,
+    pub version: u8,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub old_lp_supply: u64,
+    pub new_lp_supply: u64,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAmmPool<'info> {
+    /// AMM pool to migrate away from
+    #[account(
+        mut,
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
+        bump = amm_pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+        constraint = amm_pool.is_active @ CloakCraftError::AmmPoolNotActive,
+    )]
+    pub amm_pool: Box<Account<'info, AmmPool>>,
+
+    /// Successor pool (next version of the pair)
+    #[account(
+        init,
+        payer = authority,
+        space = AmmPool::LEN,
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), &[amm_pool.version.wrapping_add(1)]],
+        bump
+    )]
+    pub new_pool: Box<Account<'info, AmmPool>>,
+
+    /// Successor LP mint
+    #[account(
+        init,
+        payer = authority,
+        seeds = [seeds::LP_MINT, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), &[amm_pool.version.wrapping_add(1)]],
+        bump,
+        mint::decimals = 9,
+        mint::authority = new_pool,
+    )]
+    pub new_lp_mint: Box<Account<'info, Mint>>,
+
+    /// Migration record
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AmmPoolMigration::INIT_SPACE,
+        seeds = [seeds::AMM_MIGRATION, amm_pool.key().as_ref()],
+        bump,
+    )]
+    pub migration: Box<Account<'info, AmmPoolMigration>>,
+
+    /// Pool authority (pays for the new accounts)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Migrate an AMM pool
+///
+/// # Arguments
+/// * `fee_bps` - Successor pool fee
+/// * `pool_type` - Successor pool formula
+/// * `amplification` - StableSwap amplification (ignored for ConstantProduct)
+/// * `new_lp_supply` - Successor LP reserved for the old pool's LP supply
+pub fn migrate_amm_pool<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateAmmPool<'info>>,
+    fee_bps: u16,
+    pool_type: PoolType,
+    amplification: u64,
+    new_lp_supply: u64,
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&**ctx.accounts.amm_pool);
+
+    let version = ctx.accounts.amm_pool.version
+        .checked_add(1)
+        .ok_or(CloakCraftError::AmmPoolVersionExhausted)?;
+    if pool_type == PoolType::StableSwap {
+        require!(
+            (1..=10000).contains(&amplification),
+            CloakCraftError::InvalidAmplification
+        );
+    }
+    // Outstanding LP must convert to something, and nothing from nothing
+    require!(
+        (ctx.accounts.amm_pool.lp_supply == 0) == (new_lp_supply == 0),
+        CloakCraftError::LpConversionMismatch
+    );
+
+    let old_pool = &mut ctx.accounts.amm_pool;
+    let new_pool = &mut ctx.accounts.new_pool;
+
+    new_pool.pool_id = new_pool.key();
+    new_pool.token_a_mint = old_pool.token_a_mint;
+    new_pool.token_b_mint = old_pool.token_b_mint;
+    new_pool.lp_mint = ctx.accounts.new_lp_mint.key();
+    new_pool.reserve_a = old_pool.reserve_a;
+    new_pool.reserve_b = old_pool.reserve_b;
+    // Reserve every converted note's LP up front, so the successor prices
+    // deposits and swaps against all of the exported reserves
+    new_pool.lp_supply = new_lp_supply;
+    new_pool.fee_bps = fee_bps;
+    new_pool.authority = old_pool.authority;
+    new_pool.is_active = true;
+    new_pool.bump = ctx.bumps.new_pool;
+    new_pool.lp_mint_bump = ctx.bumps.new_lp_mint;
+    new_pool.pool_type = pool_type;
+    new_pool.amplification = if pool_type == PoolType::StableSwap { amplification } else { 0 };
+    new_pool.min_lp_lock_slots = old_pool.min_lp_lock_slots;
+    new_pool.jit_penalty_bps = old_pool.jit_penalty_bps;
+    new_pool.version = version;
+    new_pool.state_hash = new_pool.compute_state_hash();
+
+    let migration = &mut ctx.accounts.migration;
+    migration.old_pool = old_pool.key();
+    migration.new_pool = new_pool.key();
+    migration.old_lp_mint = old_pool.lp_mint;
+    migration.new_lp_mint = new_pool.lp_mint;
+    migration.migrated_at = Clock::get()?.unix_timestamp;
+    migration.reserve_a = old_pool.reserve_a;
+    migration.reserve_b = old_pool.reserve_b;
+    migration.old_lp_supply = old_pool.lp_supply;
+    migration.new_lp_supply = new_lp_supply;
+    migration.bump = ctx.bumps.migration;
+
+    // The old pool keeps nothing: its LP is only convertible from now on
+    old_pool.reserve_a = 0;
+    old_pool.reserve_b = 0;
+    old_pool.lp_supply = 0;
+    old_pool.is_active = false;
+    old_pool.state_hash = old_pool.compute_state_hash();
+
+    emit!(AmmPoolMigrated {
+        old_pool: migration.old_pool,
+        new_pool: migration.new_pool,
+        version,
+        reserve_a: migration.reserve_a,
+        reserve_b: migration.reserve_b,
+        old_lp_supply: migration.old_lp_supply,
+        new_lp_supply,
+    });
+
+    msg!(
+        "AMM pool migrated to v{}: reserves {}/{}, {} old LP -> {} new LP",
+        version,
+        migration.reserve_a,
+        migration.reserve_b,
+        migration.old_lp_supply,
+        new_lp_supply
+    );
+
+    let new_value_hash = AdminActionRecord::value_hash(&**ctx.accounts.amm_pool);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::MigrateAmmPool,
+        ctx.accounts.amm_pool.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
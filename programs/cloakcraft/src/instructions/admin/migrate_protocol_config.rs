@@ -0,0 +1,114 @@
+//! Migrate the protocol config to the current layout (admin only)
+//!
+//! ProtocolConfig grew (fee bounds, per-mint fee overrides, treasury
+//! conversion) after the first configs were created. Accounts created with an
+//! older layout are too small to deserialize, so this reallocates them to
+//! `ProtocolConfig::LEN` with the new fields zeroed: no fee bounds, no
+//! overrides and treasury conversion disabled.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+
+use crate::state::{ProtocolConfig, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+/// Emitted when the protocol config is reallocated
+#[event]
+pub struct ProtocolConfigMigrated {
+    pub old_len: u64,
+    pub new_len: u64,
+}
+
+#[derive(Accounts)]
+pub struct MigrateProtocolConfig<'info> {
+    /// Protocol config account (older layout, checked manually)
+    /// CHECK: Owner, discriminator and authority are checked in the handler
+    #[account(
+        mut,
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump,
+        owner = crate::ID,
+    )]
+    pub protocol_config: UncheckedAccount<'info>,
+
+    /// Authority recorded in the config
+    pub authority: Signer<'info>,
+
+    /// Payer for reallocation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program for reallocation
+    pub system_program: Program<'info, System>,
+}
+
+/// Reallocate the protocol config to the current layout
+pub fn migrate_protocol_config<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateProtocolConfig<'info>>,
+) -> Result<()> {
+    let config_info = ctx.accounts.protocol_config.to_account_info();
+    let new_len = 8 + ProtocolConfig::LEN;
+
+    let old_len = config_info.data_len();
+    let old_value_hash = {
+        let data = config_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 + 32 && data[..8] == ProtocolConfig::DISCRIMINATOR[..],
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+        // The authority is the first field in every layout
+        require!(
+            data[8..40] == ctx.accounts.authority.key().to_bytes(),
+            CloakCraftError::Unauthorized
+        );
+        solana_keccak_hasher::hash(&data).to_bytes()
+    };
+    require!(old_len < new_len, CloakCraftError::AccountAlreadyMigrated);
+
+    // Top up rent for the larger account
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(config_info.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: config_info.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    // New fields are zero-filled, which is their disabled default
+    config_info.resize(new_len)?;
+
+    let new_value_hash = {
+        let data = config_info.try_borrow_data()?;
+        let config = ProtocolConfig::try_deserialize(&mut &data[..])?;
+        AdminActionRecord::value_hash(&config)
+    };
+
+    emit!(ProtocolConfigMigrated {
+        old_len: old_len as u64,
+        new_len: new_len as u64,
+    });
+    msg!("Protocol config migrated: {} -> {} bytes", old_len, new_len);
+
+    record_admin_action(
+        &ctx.accounts.payer.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::MigrateProtocolConfig,
+        config_info.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
@@ -5,23 +5,67 @@ mod disable_adapt_module;
 mod register_verification_key;
 mod set_verification_key_data;
 mod append_verification_key_data;
+mod finalize_verification_key;
 mod register_threshold_committee;
 mod test_verify_proof;
 mod reset_amm_pool;
+mod set_amm_lp_lock;
+mod migrate_amm_pool;
 mod initialize_protocol_config;
 mod update_protocol_fees;
 mod update_treasury;
 mod update_protocol_authority;
+mod set_pending_expiry;
+mod set_rent_refund_bps;
+mod set_surplus_policy;
+mod bump_operation_epoch;
+mod set_amm_creation_policy;
+mod initialize_pool_creator_allowlist;
+mod set_pool_creator;
+mod initialize_program_version;
+mod set_program_version;
+mod initialize_dust_sweep_ledger;
+mod set_dust_sweep_bounty;
+mod reconcile_commitment_counter;
+mod set_fee_override;
+mod set_fee_bounds;
+mod set_treasury_conversion;
+mod migrate_protocol_config;
+mod initialize_tree_registry;
+mod register_state_tree;
+mod retire_state_tree;
 
 pub use register_adapt_module::*;
 pub use disable_adapt_module::*;
 pub use register_verification_key::*;
 pub use set_verification_key_data::*;
 pub use append_verification_key_data::*;
+pub use finalize_verification_key::*;
 pub use register_threshold_committee::*;
 pub use test_verify_proof::*;
 pub use reset_amm_pool::*;
+pub use set_amm_lp_lock::*;
+pub use migrate_amm_pool::*;
 pub use initialize_protocol_config::*;
 pub use update_protocol_fees::*;
 pub use update_treasury::*;
 pub use update_protocol_authority::*;
+pub use set_pending_expiry::*;
+pub use set_rent_refund_bps::*;
+pub use set_surplus_policy::*;
+pub use bump_operation_epoch::*;
+pub use set_amm_creation_policy::*;
+pub use initialize_pool_creator_allowlist::*;
+pub use set_pool_creator::*;
+pub use initialize_program_version::*;
+pub use set_program_version::*;
+pub use initialize_dust_sweep_ledger::*;
+pub use set_dust_sweep_bounty::*;
+pub use reconcile_commitment_counter::*;
+pub use set_fee_override::*;
+pub use set_fee_bounds::*;
+pub use set_treasury_conversion::*;
+pub use migrate_protocol_config::*;
+pub use initialize_tree_registry::*;
+pub use register_state_tree::*;
+pub use retire_state_tree::*;
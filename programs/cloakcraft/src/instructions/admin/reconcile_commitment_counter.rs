@@ -0,0 +1,87 @@
+//! Reconcile a pool's commitment counter (admin only)
+//!
+//! Before `store_commitment` allocated from the counter, callers supplied
+//! their own commitment indices, so a pool may hold commitments at indices the
+//! counter has not reached yet. Moving the counter past the highest stored
+//! index (from an indexer's `get_commitment_index`) keeps new commitments from
+//! reusing one. The counter only moves forward.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, PoolCommitmentCounter, ProtocolConfig, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+/// Emitted when a commitment counter is moved forward
+#[event]
+pub struct CommitmentCounterReconciled {
+    pub pool: Pubkey,
+    pub previous_next_index: u64,
+    pub next_index: u64,
+}
+
+#[derive(Accounts)]
+pub struct ReconcileCommitmentCounter<'info> {
+    /// Pool whose counter is reconciled
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Commitment counter for this pool
+    #[account(
+        mut,
+        seeds = [PoolCommitmentCounter::SEEDS_PREFIX, pool.key().as_ref()],
+        bump = commitment_counter.bump,
+    )]
+    pub commitment_counter: Box<Account<'info, PoolCommitmentCounter>>,
+
+    /// Protocol config account
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Protocol authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Reconcile a commitment counter
+///
+/// # Arguments
+/// * `next_index` - New next commitment index (above the current one)
+pub fn reconcile_commitment_counter<'info>(
+    ctx: Context<'_, '_, '_, 'info, ReconcileCommitmentCounter<'info>>,
+    next_index: u64,
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&**ctx.accounts.commitment_counter);
+
+    let counter = &mut ctx.accounts.commitment_counter;
+    let previous_next_index = counter.next_leaf_index;
+    require!(counter.reconcile(next_index), CloakCraftError::CommitmentIndexNotAhead);
+
+    emit!(CommitmentCounterReconciled {
+        pool: ctx.accounts.pool.key(),
+        previous_next_index,
+        next_index,
+    });
+    msg!("Commitment counter moved from {} to {}", previous_next_index, next_index);
+
+    let new_value_hash = AdminActionRecord::value_hash(&**ctx.accounts.commitment_counter);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::ReconcileCommitmentCounter,
+        ctx.accounts.commitment_counter.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
@@ -2,8 +2,9 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::AdaptModule;
+use crate::state::{AdaptModule, AdminAction, AdminActionRecord};
 use crate::constants::seeds;
+use crate::helpers::admin_audit::record_admin_action;
 
 #[derive(Accounts)]
 pub struct RegisterAdaptModule<'info> {
@@ -32,8 +33,8 @@ pub struct RegisterAdaptModule<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn register_adapt_module(
-    ctx: Context<RegisterAdaptModule>,
+pub fn register_adapt_module<'info>(
+    ctx: Context<'_, '_, '_, 'info, RegisterAdaptModule<'info>>,
     interface_version: u8,
 ) -> Result<()> {
     let adapt_module = &mut ctx.accounts.adapt_module;
@@ -45,5 +46,17 @@ pub fn register_adapt_module(
     adapt_module.authority = ctx.accounts.authority.key();
     adapt_module.registered_at = clock.unix_timestamp;
     adapt_module.bump = ctx.bumps.adapt_module;
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.adapt_module);
+    record_admin_action(
+        &ctx.accounts.payer.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::RegisterAdaptModule,
+        ctx.accounts.adapt_module.key(),
+        [0u8; 32],
+        new_value_hash,
+    )?;
+
     Ok(())
 }
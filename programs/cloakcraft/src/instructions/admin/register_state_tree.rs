@@ -0,0 +1,76 @@
+//! Register an output state tree (admin only)
+//!
+//! Adds a Light state tree to the tree registry with a capacity hint, or
+//! updates the hint of a registered tree (re-activating it if retired).
+
+use anchor_lang::prelude::*;
+use light_sdk::constants::ACCOUNT_COMPRESSION_PROGRAM_ID;
+
+use crate::state::{TreeRegistry, StateTreeRegistered, ProtocolConfig, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct RegisterStateTree<'info> {
+    /// Tree registry
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump = tree_registry.bump,
+    )]
+    pub tree_registry: Box<Account<'info, TreeRegistry>>,
+
+    /// State tree to register (V2 output queue)
+    /// CHECK: Owner checked against the account compression program
+    #[account(
+        constraint = *state_tree.owner == Pubkey::new_from_array(ACCOUNT_COMPRESSION_PROGRAM_ID) @ CloakCraftError::InvalidStateTree,
+    )]
+    pub state_tree: UncheckedAccount<'info>,
+
+    /// Protocol config (authority check)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Protocol authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Register a state tree or update its capacity hint
+///
+/// # Arguments
+/// * `capacity` - Compressed accounts the tree is expected to hold
+pub fn register_state_tree<'info>(
+    ctx: Context<'_, '_, '_, 'info, RegisterStateTree<'info>>,
+    capacity: u64,
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&**ctx.accounts.tree_registry);
+    let state_tree = ctx.accounts.state_tree.key();
+
+    ctx.accounts.tree_registry.register(state_tree, capacity)?;
+
+    msg!("State tree {} registered with capacity {}", state_tree, capacity);
+
+    emit!(StateTreeRegistered {
+        state_tree,
+        capacity,
+    });
+
+    let new_value_hash = AdminActionRecord::value_hash(&**ctx.accounts.tree_registry);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::RegisterStateTree,
+        state_tree,
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
@@ -2,8 +2,9 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::ThresholdCommittee;
+use crate::state::{ThresholdCommittee, AdminAction, AdminActionRecord};
 use crate::constants::seeds;
+use crate::helpers::admin_audit::record_admin_action;
 
 #[derive(Accounts)]
 #[instruction(committee_id: [u8; 32])]
@@ -29,8 +30,8 @@ pub struct RegisterThresholdCommittee<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn register_threshold_committee(
-    ctx: Context<RegisterThresholdCommittee>,
+pub fn register_threshold_committee<'info>(
+    ctx: Context<'_, '_, '_, 'info, RegisterThresholdCommittee<'info>>,
     committee_id: [u8; 32],
     members: Vec<Pubkey>,
     threshold_pubkey: [u8; 32],
@@ -46,5 +47,16 @@ pub fn register_threshold_committee(
     committee.is_active = true;
     committee.bump = ctx.bumps.committee;
 
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.committee);
+    record_admin_action(
+        &ctx.accounts.payer.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::RegisterThresholdCommittee,
+        ctx.accounts.committee.key(),
+        [0u8; 32],
+        new_value_hash,
+    )?;
+
     Ok(())
 }
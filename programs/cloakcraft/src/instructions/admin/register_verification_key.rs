@@ -2,8 +2,9 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::VerificationKey;
+use crate::state::{VerificationKey, AdminAction, AdminActionRecord};
 use crate::constants::seeds;
+use crate::helpers::admin_audit::record_admin_action;
 
 #[derive(Accounts)]
 #[instruction(circuit_id: [u8; 32])]
@@ -29,8 +30,8 @@ pub struct RegisterVerificationKey<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn register_verification_key(
-    ctx: Context<RegisterVerificationKey>,
+pub fn register_verification_key<'info>(
+    ctx: Context<'_, '_, '_, 'info, RegisterVerificationKey<'info>>,
     circuit_id: [u8; 32],
     vk_data: Vec<u8>,
 ) -> Result<()> {
@@ -42,5 +43,19 @@ pub fn register_verification_key(
     vk.authority = ctx.accounts.authority.key();
     vk.is_active = true;
     vk.bump = ctx.bumps.verification_key;
+    vk.is_finalized = false;
+    vk.vk_hash = [0u8; 32];
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.verification_key);
+    record_admin_action(
+        &ctx.accounts.payer.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::RegisterVerificationKey,
+        ctx.accounts.verification_key.key(),
+        [0u8; 32],
+        new_value_hash,
+    )?;
+
     Ok(())
 }
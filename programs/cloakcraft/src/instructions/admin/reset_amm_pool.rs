@@ -4,25 +4,29 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::AmmPool;
+use crate::state::{AmmPool, AdminAction, AdminActionRecord};
 use crate::constants::seeds;
+use crate::helpers::admin_audit::record_admin_action;
 
 #[derive(Accounts)]
 pub struct ResetAmmPool<'info> {
     /// AMM pool to reset
     #[account(
         mut,
-        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref()],
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
         bump = amm_pool.bump,
         has_one = authority
     )]
     pub amm_pool: Account<'info, AmmPool>,
 
     /// Pool authority (must match)
+    #[account(mut)]
     pub authority: Signer<'info>,
 }
 
-pub fn reset_amm_pool(ctx: Context<ResetAmmPool>) -> Result<()> {
+pub fn reset_amm_pool<'info>(ctx: Context<'_, '_, '_, 'info, ResetAmmPool<'info>>) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.amm_pool);
+
     let amm_pool = &mut ctx.accounts.amm_pool;
 
     // Reset to initial state
@@ -36,5 +40,16 @@ pub fn reset_amm_pool(ctx: Context<ResetAmmPool>) -> Result<()> {
     msg!("Reserve B: {}", amm_pool.reserve_b);
     msg!("LP Supply: {}", amm_pool.lp_supply);
 
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.amm_pool);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::ResetAmmPool,
+        ctx.accounts.amm_pool.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
     Ok(())
 }
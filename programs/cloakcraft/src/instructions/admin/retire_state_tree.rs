@@ -0,0 +1,62 @@
+//! Retire an output state tree (admin only)
+//!
+//! A retired tree accepts no new writes from phases passed the registry and
+//! cannot be a rollover target. Commitments already in it stay spendable.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{TreeRegistry, ProtocolConfig, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct RetireStateTree<'info> {
+    /// Tree registry
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump = tree_registry.bump,
+    )]
+    pub tree_registry: Box<Account<'info, TreeRegistry>>,
+
+    /// Protocol config (authority check)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Protocol authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Retire a registered state tree
+///
+/// # Arguments
+/// * `state_tree` - Tree to retire
+pub fn retire_state_tree<'info>(
+    ctx: Context<'_, '_, '_, 'info, RetireStateTree<'info>>,
+    state_tree: Pubkey,
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&**ctx.accounts.tree_registry);
+
+    ctx.accounts.tree_registry.retire(&state_tree)?;
+
+    msg!("State tree {} retired", state_tree);
+
+    let new_value_hash = AdminActionRecord::value_hash(&**ctx.accounts.tree_registry);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::RetireStateTree,
+        state_tree,
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
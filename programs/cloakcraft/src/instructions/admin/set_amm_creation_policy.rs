@@ -0,0 +1,66 @@
+//! Set the AMM pool creation policy
+//!
+//! Allows the authority to switch AMM pool creation between permissionless
+//! and allowlisted, and to set the SOL creation fee paid to the treasury.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ProtocolConfig, AMM_CREATION_ALLOWLISTED, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct SetAmmCreationPolicy<'info> {
+    /// Protocol config account
+    #[account(
+        mut,
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Authority that can update config
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set the AMM pool creation policy
+///
+/// # Arguments
+/// * `amm_creation_mode` - AMM_CREATION_PERMISSIONLESS (0) or AMM_CREATION_ALLOWLISTED (1)
+/// * `amm_creation_fee_lamports` - SOL fee paid to the treasury per pool (0 = free)
+pub fn set_amm_creation_policy<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetAmmCreationPolicy<'info>>,
+    amm_creation_mode: u8,
+    amm_creation_fee_lamports: u64,
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+
+    require!(
+        amm_creation_mode <= AMM_CREATION_ALLOWLISTED,
+        CloakCraftError::InvalidAmmCreationMode
+    );
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.amm_creation_mode = amm_creation_mode;
+    config.amm_creation_fee_lamports = amm_creation_fee_lamports;
+    msg!(
+        "AMM creation mode set to {}, fee {} lamports",
+        amm_creation_mode, amm_creation_fee_lamports
+    );
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::SetAmmCreationPolicy,
+        ctx.accounts.protocol_config.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
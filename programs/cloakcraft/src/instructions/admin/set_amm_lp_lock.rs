@@ -0,0 +1,63 @@
+//! Set the AMM pool LP lock (admin only)
+//!
+//! Configures the minimum LP lock duration and the JIT penalty charged on
+//! removals inside the lock window.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{AmmPool, MAX_JIT_PENALTY_BPS, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct SetAmmLpLock<'info> {
+    /// AMM pool to configure
+    #[account(
+        mut,
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
+        bump = amm_pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub amm_pool: Account<'info, AmmPool>,
+
+    /// Pool authority (must match)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set the LP lock
+///
+/// # Arguments
+/// * `min_lp_lock_slots` - Slots new LP commitments stay locked (0 disables the lock)
+/// * `jit_penalty_bps` - Penalty on removals inside the window (0 rejects them, max 1000)
+///
+/// Applies to LP commitments minted after the update.
+pub fn set_amm_lp_lock<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetAmmLpLock<'info>>,
+    min_lp_lock_slots: u64,
+    jit_penalty_bps: u16,
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.amm_pool);
+
+    require!(jit_penalty_bps <= MAX_JIT_PENALTY_BPS, CloakCraftError::InvalidJitPenalty);
+
+    let amm_pool = &mut ctx.accounts.amm_pool;
+    amm_pool.min_lp_lock_slots = min_lp_lock_slots;
+    amm_pool.jit_penalty_bps = jit_penalty_bps;
+
+    msg!("LP lock set to {} slots, JIT penalty {} bps", min_lp_lock_slots, jit_penalty_bps);
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.amm_pool);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::SetAmmLpLock,
+        ctx.accounts.amm_pool.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
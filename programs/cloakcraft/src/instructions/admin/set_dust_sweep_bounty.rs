@@ -0,0 +1,66 @@
+//! Set the dust sweep bounty
+//!
+//! Allows the authority to change (or pause, with 0) the lamports paid to
+//! relayers for each consolidation that sweeps dust notes.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ProtocolConfig, DustSweepLedger, AdminAction, AdminActionRecord, MAX_DUST_SWEEP_BOUNTY_LAMPORTS};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct SetDustSweepBounty<'info> {
+    /// Dust sweep ledger
+    #[account(
+        mut,
+        seeds = [seeds::DUST_SWEEP_LEDGER],
+        bump = dust_sweep_ledger.bump,
+    )]
+    pub dust_sweep_ledger: Account<'info, DustSweepLedger>,
+
+    /// Protocol config account
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Authority that can update config
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set the dust sweep bounty
+///
+/// # Arguments
+/// * `bounty_lamports` - Paid per qualifying consolidation (max 0.01 SOL, 0 = paused)
+pub fn set_dust_sweep_bounty<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetDustSweepBounty<'info>>,
+    bounty_lamports: u64,
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.dust_sweep_ledger);
+
+    require!(
+        bounty_lamports <= MAX_DUST_SWEEP_BOUNTY_LAMPORTS,
+        CloakCraftError::InvalidAmount
+    );
+
+    ctx.accounts.dust_sweep_ledger.bounty_lamports = bounty_lamports;
+    msg!("Dust sweep bounty set to {} lamports", bounty_lamports);
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.dust_sweep_ledger);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::SetDustSweepBounty,
+        ctx.accounts.dust_sweep_ledger.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
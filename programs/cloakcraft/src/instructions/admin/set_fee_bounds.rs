@@ -0,0 +1,79 @@
+//! Set protocol fee bounds
+//!
+//! Allows the authority to set the floor and ceiling every transfer, unshield
+//! and remove liquidity fee rate (global or per mint) must lie within. The
+//! rates in force must already satisfy the new bounds.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ProtocolConfig, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+/// Emitted when the fee bounds change
+#[event]
+pub struct FeeBoundsUpdated {
+    pub min_fee_bps: u16,
+    pub max_fee_bps: u16,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeBounds<'info> {
+    /// Protocol config account
+    #[account(
+        mut,
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Authority that can update fees
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set the fee floor and ceiling
+///
+/// # Arguments
+/// * `min_fee_bps` - Lowest fee rate allowed
+/// * `max_fee_bps` - Highest fee rate allowed (at most MAX_FEE_BPS, 0 = MAX_FEE_BPS)
+pub fn set_fee_bounds<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetFeeBounds<'info>>,
+    min_fee_bps: u16,
+    max_fee_bps: u16,
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+
+    require!(
+        max_fee_bps <= ProtocolConfig::MAX_FEE_BPS,
+        CloakCraftError::InvalidFeeBounds
+    );
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.min_fee_bps = min_fee_bps;
+    config.max_fee_bps = max_fee_bps;
+    require!(
+        min_fee_bps <= config.fee_ceiling_bps(),
+        CloakCraftError::InvalidFeeBounds
+    );
+    require!(config.fees_within_bounds(), CloakCraftError::FeeOutOfBounds);
+
+    emit!(FeeBoundsUpdated { min_fee_bps, max_fee_bps });
+
+    msg!("Fee bounds set to {}..={} bps", min_fee_bps, max_fee_bps);
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::SetFeeBounds,
+        ctx.accounts.protocol_config.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
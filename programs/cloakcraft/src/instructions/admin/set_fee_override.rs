@@ -0,0 +1,94 @@
+//! Set per-mint fee rates
+//!
+//! Allows the authority to charge a token different transfer, unshield and
+//! remove liquidity fees than the global rates (e.g. stablecoins cheaper than
+//! volatile assets), or to clear its override.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ProtocolConfig, FeeOverride, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+/// Emitted when a mint's fee override is set or cleared
+#[event]
+pub struct FeeOverrideSet {
+    pub mint: Pubkey,
+    /// False when the override was cleared (rates are then zero)
+    pub active: bool,
+    pub transfer_fee_bps: u16,
+    pub unshield_fee_bps: u16,
+    pub remove_liquidity_fee_bps: u16,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeOverride<'info> {
+    /// Protocol config account
+    #[account(
+        mut,
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Authority that can update fees
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set or clear a mint's fee override
+///
+/// # Arguments
+/// * `mint` - Token mint the rates apply to
+/// * `fee_override` - Rates for the mint (None to clear; its `mint` field is ignored)
+pub fn set_fee_override<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetFeeOverride<'info>>,
+    mint: Pubkey,
+    fee_override: Option<FeeOverride>,
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+
+    require!(mint != Pubkey::default(), CloakCraftError::InvalidTokenMint);
+
+    let config = &mut ctx.accounts.protocol_config;
+    require!(
+        config.set_fee_override(mint, fee_override),
+        CloakCraftError::FeeOverridesFull
+    );
+    require!(config.fees_within_bounds(), CloakCraftError::FeeOutOfBounds);
+
+    let rates = fee_override.unwrap_or_default();
+    emit!(FeeOverrideSet {
+        mint,
+        active: fee_override.is_some(),
+        transfer_fee_bps: rates.transfer_fee_bps,
+        unshield_fee_bps: rates.unshield_fee_bps,
+        remove_liquidity_fee_bps: rates.remove_liquidity_fee_bps,
+    });
+
+    match fee_override {
+        Some(rates) => msg!(
+            "Fee override for {}: transfer {} bps, unshield {} bps, remove liquidity {} bps",
+            mint,
+            rates.transfer_fee_bps,
+            rates.unshield_fee_bps,
+            rates.remove_liquidity_fee_bps
+        ),
+        None => msg!("Fee override for {} cleared", mint),
+    }
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::SetFeeOverride,
+        ctx.accounts.protocol_config.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+//! Set PendingOperation expiry
+//!
+//! Allows the authority to set the default expiry and per-operation-type
+//! overrides (e.g. longer for congested perps flows, shorter for voting).
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ProtocolConfig, MIN_PENDING_EXPIRY_SECONDS, MAX_PENDING_EXPIRY_SECONDS, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct SetPendingExpiry<'info> {
+    /// Protocol config account
+    #[account(
+        mut,
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Authority that can update config
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set the default or an operation type's PendingOperation expiry
+///
+/// # Arguments
+/// * `operation_type` - Operation type to override (None to set the default)
+/// * `expiry_seconds` - Expiry in seconds; 0 resets to the built-in default / clears the override
+pub fn set_pending_expiry<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetPendingExpiry<'info>>,
+    operation_type: Option<u8>,
+    expiry_seconds: u32,
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+
+    require!(
+        expiry_seconds == 0
+            || (MIN_PENDING_EXPIRY_SECONDS..=MAX_PENDING_EXPIRY_SECONDS).contains(&expiry_seconds),
+        CloakCraftError::InvalidPendingExpiry
+    );
+
+    let config = &mut ctx.accounts.protocol_config;
+    match operation_type {
+        None => {
+            config.pending_expiry_seconds = expiry_seconds;
+            msg!("Default pending expiry set to {}s", expiry_seconds);
+        }
+        Some(op) => {
+            require!(
+                config.set_pending_expiry_override(op, expiry_seconds),
+                CloakCraftError::PendingExpiryOverridesFull
+            );
+            msg!("Pending expiry for operation type {} set to {}s", op, expiry_seconds);
+        }
+    }
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::SetPendingExpiry,
+        ctx.accounts.protocol_config.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! Add or remove an AMM pool creator
+//!
+//! Allows the authority to manage the PoolCreatorAllowlist.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ProtocolConfig, PoolCreatorAllowlist, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct SetPoolCreator<'info> {
+    /// Pool creator allowlist
+    #[account(
+        mut,
+        seeds = [seeds::POOL_CREATOR_ALLOWLIST],
+        bump = pool_creator_allowlist.bump,
+    )]
+    pub pool_creator_allowlist: Account<'info, PoolCreatorAllowlist>,
+
+    /// Protocol config account
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Protocol authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Add or remove a pool creator
+///
+/// # Arguments
+/// * `creator` - Account allowed to create AMM pools (the pool authority)
+/// * `allowed` - true to add, false to remove
+pub fn set_pool_creator<'info>(ctx: Context<'_, '_, '_, 'info, SetPoolCreator<'info>>, creator: Pubkey, allowed: bool) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.pool_creator_allowlist);
+
+    require!(
+        ctx.accounts.pool_creator_allowlist.set(creator, allowed),
+        CloakCraftError::PoolCreatorAllowlistFull
+    );
+    msg!("Pool creator {} {}", creator, if allowed { "allowed" } else { "removed" });
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.pool_creator_allowlist);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::SetPoolCreator,
+        ctx.accounts.pool_creator_allowlist.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
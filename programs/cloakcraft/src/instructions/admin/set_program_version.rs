@@ -0,0 +1,75 @@
+//! Bump the program version
+//!
+//! Called by the protocol authority after each deploy. Raising
+//! `min_client_version` cuts off clients whose note formats or circuits are
+//! incompatible with the new program.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ProtocolConfig, ProgramVersion, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct SetProgramVersion<'info> {
+    /// Program version account
+    #[account(
+        mut,
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Account<'info, ProgramVersion>,
+
+    /// Protocol config (authority check)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Protocol authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set the program version and minimum client version
+///
+/// # Arguments
+/// * `version` - Deployed program version (may not decrease)
+/// * `min_client_version` - Oldest compatible client version (may not decrease, <= version)
+pub fn set_program_version<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetProgramVersion<'info>>,
+    version: u32,
+    min_client_version: u32,
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.program_version);
+
+    let program_version = &mut ctx.accounts.program_version;
+    require!(
+        program_version.is_valid_update(version, min_client_version),
+        CloakCraftError::InvalidProgramVersion
+    );
+
+    let clock = Clock::get()?;
+    program_version.version = version;
+    program_version.min_client_version = min_client_version;
+    program_version.updated_slot = clock.slot;
+    program_version.updated_at = clock.unix_timestamp;
+
+    msg!("Program version {} (min client {})", version, min_client_version);
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.program_version);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::SetProgramVersion,
+        ctx.accounts.program_version.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
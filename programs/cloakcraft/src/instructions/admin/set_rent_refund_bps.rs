@@ -0,0 +1,58 @@
+//! Set the pending operation rent refund split
+//!
+//! Allows the authority to set the share of a closed PendingOperation's rent
+//! refunded to the user's proven unshield recipient instead of the relayer.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ProtocolConfig, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct SetRentRefundBps<'info> {
+    /// Protocol config account
+    #[account(
+        mut,
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Authority that can update config
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set the rent refund split
+///
+/// # Arguments
+/// * `rent_refund_bps` - Share refunded to the user in basis points (max 10000)
+///
+/// Applies to pending operations created after the update.
+pub fn set_rent_refund_bps<'info>(ctx: Context<'_, '_, '_, 'info, SetRentRefundBps<'info>>, rent_refund_bps: u16) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+
+    require!(
+        rent_refund_bps <= ProtocolConfig::MAX_RENT_REFUND_BPS,
+        CloakCraftError::InvalidAmount
+    );
+
+    ctx.accounts.protocol_config.rent_refund_bps = rent_refund_bps;
+    msg!("Rent refund split set to {} bps", rent_refund_bps);
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::SetRentRefundBps,
+        ctx.accounts.protocol_config.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
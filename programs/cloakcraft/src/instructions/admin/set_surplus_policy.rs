@@ -0,0 +1,56 @@
+//! Set the vault surplus policy
+//!
+//! Allows the authority to choose whether `sync_reserves` folds surplus vault
+//! balance into AMM reserves or sweeps it to the treasury.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ProtocolConfig, SURPLUS_POLICY_TREASURY, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct SetSurplusPolicy<'info> {
+    /// Protocol config account
+    #[account(
+        mut,
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Authority that can update config
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set the surplus policy
+///
+/// # Arguments
+/// * `surplus_policy` - SURPLUS_POLICY_RESERVES (0) or SURPLUS_POLICY_TREASURY (1)
+pub fn set_surplus_policy<'info>(ctx: Context<'_, '_, '_, 'info, SetSurplusPolicy<'info>>, surplus_policy: u8) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+
+    require!(
+        surplus_policy <= SURPLUS_POLICY_TREASURY,
+        CloakCraftError::InvalidSurplusPolicy
+    );
+
+    ctx.accounts.protocol_config.surplus_policy = surplus_policy;
+    msg!("Surplus policy set to {}", surplus_policy);
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::SetSurplusPolicy,
+        ctx.accounts.protocol_config.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+//! Set the treasury fee conversion policy
+//!
+//! Allows the authority to choose the canonical asset `convert_treasury_fees`
+//! swaps accumulated fees into, and the highest price impact a conversion may
+//! have. A default mint disables conversion.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{ProtocolConfig, MAX_CONVERSION_PRICE_IMPACT_BPS, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct SetTreasuryConversion<'info> {
+    /// Protocol config account
+    #[account(
+        mut,
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Authority that can update config
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set the treasury fee conversion policy
+///
+/// # Arguments
+/// * `conversion_mint` - Canonical treasury asset (default to disable)
+/// * `max_price_impact_bps` - Highest price impact per conversion (≤ 1000)
+pub fn set_treasury_conversion<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetTreasuryConversion<'info>>,
+    conversion_mint: Pubkey,
+    max_price_impact_bps: u16,
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+
+    require!(
+        max_price_impact_bps <= MAX_CONVERSION_PRICE_IMPACT_BPS,
+        CloakCraftError::ConversionPriceImpactTooHigh
+    );
+
+    let config = &mut ctx.accounts.protocol_config;
+    config.treasury_conversion_mint = conversion_mint;
+    config.max_conversion_price_impact_bps = max_price_impact_bps;
+    msg!(
+        "Treasury conversion set: mint {}, max price impact {} bps",
+        conversion_mint,
+        max_price_impact_bps
+    );
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::SetTreasuryConversion,
+        ctx.accounts.protocol_config.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
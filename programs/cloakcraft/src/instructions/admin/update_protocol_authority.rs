@@ -4,9 +4,10 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::ProtocolConfig;
+use crate::state::{ProtocolConfig, AdminAction, AdminActionRecord};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
 
 #[derive(Accounts)]
 pub struct UpdateProtocolAuthority<'info> {
@@ -24,6 +25,7 @@ pub struct UpdateProtocolAuthority<'info> {
     pub new_authority: UncheckedAccount<'info>,
 
     /// Current authority
+    #[account(mut)]
     pub authority: Signer<'info>,
 }
 
@@ -31,7 +33,9 @@ pub struct UpdateProtocolAuthority<'info> {
 ///
 /// # Arguments
 /// * `ctx` - The context containing the accounts
-pub fn update_protocol_authority(ctx: Context<UpdateProtocolAuthority>) -> Result<()> {
+pub fn update_protocol_authority<'info>(ctx: Context<'_, '_, '_, 'info, UpdateProtocolAuthority<'info>>) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+
     let config = &mut ctx.accounts.protocol_config;
     let old_authority = config.authority;
     config.authority = ctx.accounts.new_authority.key();
@@ -42,5 +46,16 @@ pub fn update_protocol_authority(ctx: Context<UpdateProtocolAuthority>) -> Resul
         config.authority
     );
 
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::UpdateProtocolAuthority,
+        ctx.accounts.protocol_config.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
     Ok(())
 }
@@ -1,12 +1,24 @@
 //! Update protocol fee rates
 //!
 //! Allows the authority to update fee rates and toggle fee collection.
+//! Rates must lie within the fee bounds (see `set_fee_bounds`).
 
 use anchor_lang::prelude::*;
 
-use crate::state::ProtocolConfig;
+use crate::state::{ProtocolConfig, AdminAction, AdminActionRecord};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+/// Emitted with the global fee rates after every update
+#[event]
+pub struct ProtocolFeesUpdated {
+    pub transfer_fee_bps: u16,
+    pub unshield_fee_bps: u16,
+    pub swap_fee_share_bps: u16,
+    pub remove_liquidity_fee_bps: u16,
+    pub fees_enabled: bool,
+}
 
 #[derive(Accounts)]
 pub struct UpdateProtocolFees<'info> {
@@ -20,6 +32,7 @@ pub struct UpdateProtocolFees<'info> {
     pub protocol_config: Account<'info, ProtocolConfig>,
 
     /// Authority that can update fees
+    #[account(mut)]
     pub authority: Signer<'info>,
 }
 
@@ -31,21 +44,23 @@ pub struct UpdateProtocolFees<'info> {
 /// * `swap_fee_share_bps` - Protocol's share of LP fees (None to keep current, e.g., 2000 = 20%)
 /// * `remove_liquidity_fee_bps` - New remove liquidity fee in basis points (None to keep current)
 /// * `fees_enabled` - New fees enabled state (None to keep current)
-pub fn update_protocol_fees(
-    ctx: Context<UpdateProtocolFees>,
+pub fn update_protocol_fees<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateProtocolFees<'info>>,
     transfer_fee_bps: Option<u16>,
     unshield_fee_bps: Option<u16>,
     swap_fee_share_bps: Option<u16>,
     remove_liquidity_fee_bps: Option<u16>,
     fees_enabled: Option<bool>,
 ) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+
     let config = &mut ctx.accounts.protocol_config;
 
     // Update transfer fee if provided
     if let Some(fee) = transfer_fee_bps {
         require!(
-            fee <= ProtocolConfig::MAX_FEE_BPS,
-            CloakCraftError::InvalidAmount
+            config.fee_within_bounds(fee),
+            CloakCraftError::FeeOutOfBounds
         );
         config.transfer_fee_bps = fee;
         msg!("Transfer fee updated to {} bps", fee);
@@ -54,8 +69,8 @@ pub fn update_protocol_fees(
     // Update unshield fee if provided
     if let Some(fee) = unshield_fee_bps {
         require!(
-            fee <= ProtocolConfig::MAX_FEE_BPS,
-            CloakCraftError::InvalidAmount
+            config.fee_within_bounds(fee),
+            CloakCraftError::FeeOutOfBounds
         );
         config.unshield_fee_bps = fee;
         msg!("Unshield fee updated to {} bps", fee);
@@ -74,8 +89,8 @@ pub fn update_protocol_fees(
     // Update remove liquidity fee if provided
     if let Some(fee) = remove_liquidity_fee_bps {
         require!(
-            fee <= ProtocolConfig::MAX_FEE_BPS,
-            CloakCraftError::InvalidAmount
+            config.fee_within_bounds(fee),
+            CloakCraftError::FeeOutOfBounds
         );
         config.remove_liquidity_fee_bps = fee;
         msg!("Remove liquidity fee updated to {} bps", fee);
@@ -87,5 +102,24 @@ pub fn update_protocol_fees(
         msg!("Fees enabled state updated to {}", enabled);
     }
 
+    emit!(ProtocolFeesUpdated {
+        transfer_fee_bps: config.transfer_fee_bps,
+        unshield_fee_bps: config.unshield_fee_bps,
+        swap_fee_share_bps: config.swap_fee_share_bps,
+        remove_liquidity_fee_bps: config.remove_liquidity_fee_bps,
+        fees_enabled: config.fees_enabled,
+    });
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.protocol_config);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::UpdateProtocolFees,
+        ctx.accounts.protocol_config.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
     Ok(())
 }
@@ -0,0 +1,159 @@
+//! Distribute a staker's buyback share as a shielded note (keeper)
+//!
+//! Settles the staker's owed protocol tokens and commits them to the
+//! staker's registered stealth key in the protocol token pool. The tokens
+//! are already in that pool's vault (left there by `execute_buyback`), so
+//! only the commitment is created.
+//!
+//! The note is computed on-chain with randomness derived from public data
+//! (`buyback_randomness(stealth_pub_x, nonce)`), so the staker can rebuild
+//! it from the `BuybackDistributed` event even if the keeper's encrypted
+//! note is unusable.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{BuybackConfig, StakeRegistry, Pool, PoolCommitmentCounter, RootRegistry, TreeRegistry};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::commitment::{buyback_randomness, note_commitment};
+use crate::light_cpi::{create_commitment_account, vec_to_fixed_note};
+use crate::instructions::pool::LightCommitmentParams;
+
+#[derive(Accounts)]
+pub struct DistributeBuyback<'info> {
+    /// Buyback config
+    #[account(
+        mut,
+        seeds = [seeds::BUYBACK_CONFIG],
+        bump = buyback_config.bump,
+    )]
+    pub buyback_config: Box<Account<'info, BuybackConfig>>,
+
+    /// Stake registry
+    #[account(
+        mut,
+        seeds = [seeds::STAKE_REGISTRY],
+        bump = stake_registry.bump,
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    /// Protocol token pool
+    #[account(
+        seeds = [seeds::POOL, buyback_config.protocol_token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Commitment counter for the protocol token pool
+    #[account(
+        mut,
+        seeds = [PoolCommitmentCounter::SEEDS_PREFIX, pool.key().as_ref()],
+        bump = commitment_counter.bump,
+    )]
+    pub commitment_counter: Box<Account<'info, PoolCommitmentCounter>>,
+
+    /// Pool root registry (the commitment is appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, pool.key().as_ref()],
+        bump,
+    )]
+    pub root_registry: UncheckedAccount<'info>,
+
+    /// Tree registry (once initialized, the output tree must be registered
+    /// and its writes are counted toward TreeNearCapacity)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: UncheckedAccount<'info>,
+
+    /// Keeper (pays for compressed account creation)
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    // Light Protocol accounts are passed via remaining_accounts
+}
+
+/// Parameters for a distribution note
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DistributeBuybackParams {
+    /// Stealth ephemeral pubkey for deriving decryption key (64 bytes: X + Y)
+    pub stealth_ephemeral_pubkey: [u8; 64],
+    /// Encrypted note data (convenience copy for scanning)
+    pub encrypted_note: Vec<u8>,
+    /// Optional view tag for note discovery (stored as zeros if absent)
+    pub view_tag: Option<[u8; 8]>,
+    /// Light Protocol params for the commitment
+    pub light_params: LightCommitmentParams,
+}
+
+/// Emitted when a staker's buyback share is committed as a note
+#[event]
+pub struct BuybackDistributed {
+    pub staker: Pubkey,
+    pub commitment: [u8; 32],
+    pub amount: u64,
+    /// Randomness input: `buyback_randomness(stealth_pub_x, nonce)`
+    pub nonce: u64,
+    pub leaf_index: u64,
+}
+
+/// Distribute `staker`'s owed protocol tokens as a shielded note
+pub fn distribute_buyback<'info>(
+    ctx: Context<'_, '_, '_, 'info, DistributeBuyback<'info>>,
+    staker: Pubkey,
+    params: DistributeBuybackParams,
+) -> Result<()> {
+    let clock = Clock::get()?;
+
+    let (amount, stealth_pub_x, nonce) = ctx.accounts.stake_registry
+        .take_reward(&staker)
+        .ok_or(CloakCraftError::InvalidUnstake)?;
+    require!(amount > 0, CloakCraftError::NothingToDistribute);
+
+    let pool = &ctx.accounts.pool;
+    let randomness = buyback_randomness(&stealth_pub_x, nonce)?;
+    let commitment = note_commitment(&stealth_pub_x, &pool.token_mint, amount, &randomness)?;
+    let leaf_index = ctx.accounts.commitment_counter.allocate();
+
+    let (encrypted_note_arr, encrypted_note_len) = vec_to_fixed_note(&params.encrypted_note);
+    create_commitment_account(
+        &ctx.accounts.keeper.to_account_info(),
+        ctx.remaining_accounts,
+        params.light_params.validity_proof,
+        params.light_params.address_tree_info,
+        params.light_params.output_tree_index,
+        pool.key(),
+        pool.active_trees(clock.slot),
+        commitment,
+        leaf_index,
+        params.stealth_ephemeral_pubkey,
+        encrypted_note_arr,
+        encrypted_note_len,
+        params.view_tag.unwrap_or_default(),
+    )?;
+
+    TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &pool.active_trees(clock.slot).state_tree, 1)?;
+    RootRegistry::append_to(&ctx.accounts.root_registry, commitment, clock.slot)?;
+
+    let config = &mut ctx.accounts.buyback_config;
+    config.total_distributed = config.total_distributed
+        .checked_add(amount)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    emit!(BuybackDistributed {
+        staker,
+        commitment,
+        amount,
+        nonce,
+        leaf_index,
+    });
+
+    msg!("Buyback distributed: {} to {} (leaf {})", amount, staker, leaf_index);
+
+    Ok(())
+}
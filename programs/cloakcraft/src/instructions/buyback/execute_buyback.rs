@@ -0,0 +1,188 @@
+//! Execute a protocol token buyback (keeper)
+//!
+//! Spends `buyback_bps` of the treasury's delegated fee balance in one fee
+//! mint on the protocol token, through the AMM pool pairing the two. Fees
+//! are pulled by the `treasury_converter` delegate, as in
+//! `convert_treasury_fees`.
+//!
+//! The bought tokens are not paid out: they leave the AMM reserve but stay
+//! in the protocol token pool vault (still counted as shielded supply),
+//! owed to stakers until `distribute_buyback` commits them as notes.
+//!
+//! Only the protocol authority may execute a buyback, with `min_amount_out`
+//! priced off an external reference: the price impact bound is relative to
+//! the pool's spot price, which a permissionless caller could move first.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::state::{AmmPool, BuybackConfig, Pool, ProtocolConfig, StakeRegistry};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::fixed::apply_bps;
+use crate::instructions::swap::{pull_treasury_fees, quote_keeper_swap};
+
+#[derive(Accounts)]
+pub struct ExecuteBuyback<'info> {
+    /// Buyback config
+    #[account(
+        mut,
+        seeds = [seeds::BUYBACK_CONFIG],
+        bump = buyback_config.bump,
+    )]
+    pub buyback_config: Box<Account<'info, BuybackConfig>>,
+
+    /// Stake registry (credited with the bought tokens)
+    #[account(
+        mut,
+        seeds = [seeds::STAKE_REGISTRY],
+        bump = stake_registry.bump,
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    /// AMM pool pairing the fee mint with the protocol token
+    #[account(
+        mut,
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
+        bump = amm_pool.bump,
+        constraint = amm_pool.is_active @ CloakCraftError::AmmPoolNotActive,
+    )]
+    pub amm_pool: Box<Account<'info, AmmPool>>,
+
+    /// Fee mint pool (receives the spent fees)
+    #[account(
+        mut,
+        seeds = [seeds::POOL, input_pool.token_mint.as_ref()],
+        bump = input_pool.bump,
+        constraint = input_pool.token_mint == treasury_source.mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub input_pool: Box<Account<'info, Pool>>,
+
+    /// Fee mint vault
+    #[account(
+        mut,
+        constraint = input_vault.key() == input_pool.token_vault @ CloakCraftError::InvalidVault,
+    )]
+    pub input_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Protocol config (treasury and authority)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Treasury token account holding the fees to spend
+    #[account(
+        mut,
+        constraint = treasury_source.owner == protocol_config.treasury @ CloakCraftError::InvalidTreasury,
+    )]
+    pub treasury_source: Box<Account<'info, TokenAccount>>,
+
+    /// Converter PDA, the treasury's SPL delegate on `treasury_source`
+    /// CHECK: PDA signer only; the token program enforces the delegation
+    #[account(
+        seeds = [seeds::TREASURY_CONVERTER],
+        bump,
+    )]
+    pub treasury_converter: UncheckedAccount<'info>,
+
+    /// Keeper (the protocol authority)
+    #[account(
+        constraint = keeper.key() == protocol_config.authority @ CloakCraftError::Unauthorized,
+    )]
+    pub keeper: Signer<'info>,
+
+    /// Token program for transfers
+    pub token_program: Program<'info, Token>,
+}
+
+/// Emitted when treasury fees buy back the protocol token
+#[event]
+pub struct BuybackExecuted {
+    pub amm_pool: Pubkey,
+    pub fee_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_bought: u64,
+    pub price_impact_bps: u16,
+    pub total_stake: u64,
+    pub keeper: Pubkey,
+}
+
+/// Buy the protocol token with a share of one fee mint's treasury balance
+///
+/// # Arguments
+/// * `min_amount_out` - Slippage bound on the protocol tokens bought
+pub fn execute_buyback(ctx: Context<ExecuteBuyback>, min_amount_out: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    let config = &ctx.accounts.buyback_config;
+    let fee_mint = ctx.accounts.input_pool.token_mint;
+
+    require!(config.is_due(clock.unix_timestamp), CloakCraftError::BuybackNotDue);
+    // Bought tokens would be owed to nobody
+    require!(ctx.accounts.stake_registry.total_stake > 0, CloakCraftError::NoStakers);
+
+    // Only the delegated part of the balance can be pulled
+    let treasury_source = &ctx.accounts.treasury_source;
+    let available = treasury_source.amount.min(treasury_source.delegated_amount);
+    let amount_in = apply_bps(available, config.buyback_bps);
+    require!(amount_in > 0, CloakCraftError::InvalidAmount);
+
+    let swap_a_to_b = ctx.accounts.amm_pool
+        .swap_direction(&fee_mint, &config.protocol_token_mint)
+        .ok_or(CloakCraftError::InvalidBuybackRoute)?;
+    let (amount_bought, price_impact_bps) = quote_keeper_swap(
+        &ctx.accounts.amm_pool,
+        swap_a_to_b,
+        amount_in,
+        min_amount_out,
+        config.max_price_impact_bps,
+    )?;
+
+    pull_treasury_fees(
+        &ctx.accounts.token_program,
+        &ctx.accounts.treasury_source,
+        &ctx.accounts.input_vault,
+        &ctx.accounts.treasury_converter,
+        ctx.bumps.treasury_converter,
+        amount_in,
+    )?;
+
+    ctx.accounts.amm_pool
+        .apply_direct_swap(amount_in, amount_bought, swap_a_to_b)
+        .ok_or(CloakCraftError::InsufficientLiquidity)?;
+
+    // The fees join the input reserve; the bought tokens stay in the
+    // protocol token vault, so that pool's shielded supply is unchanged
+    let input_pool = &mut ctx.accounts.input_pool;
+    input_pool.total_shielded = input_pool.total_shielded
+        .checked_add(amount_in)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    ctx.accounts.stake_registry
+        .credit(amount_bought)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    let config = &mut ctx.accounts.buyback_config;
+    config.total_bought = config.total_bought
+        .checked_add(amount_bought)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    config.last_buyback_at = clock.unix_timestamp;
+
+    emit!(BuybackExecuted {
+        amm_pool: ctx.accounts.amm_pool.key(),
+        fee_mint,
+        amount_in,
+        amount_bought,
+        price_impact_bps,
+        total_stake: ctx.accounts.stake_registry.total_stake,
+        keeper: ctx.accounts.keeper.key(),
+    });
+
+    msg!(
+        "Buyback: {} fees -> {} protocol tokens (price impact {} bps)",
+        amount_in, amount_bought, price_impact_bps
+    );
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+//! Initialize the protocol token buyback (admin only)
+//!
+//! Creates the buyback config, the stake registry and its stake vault for
+//! the protocol token. Buybacks start paused; `set_buyback_policy` activates
+//! them.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::state::{BuybackConfig, StakeRegistry, ProtocolConfig, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct InitializeBuyback<'info> {
+    /// Buyback config (singleton)
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BuybackConfig::INIT_SPACE,
+        seeds = [seeds::BUYBACK_CONFIG],
+        bump,
+    )]
+    pub buyback_config: Box<Account<'info, BuybackConfig>>,
+
+    /// Stake registry (singleton)
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + StakeRegistry::INIT_SPACE,
+        seeds = [seeds::STAKE_REGISTRY],
+        bump,
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    /// Stake vault (PDA owned by the stake registry)
+    #[account(
+        init,
+        payer = authority,
+        seeds = [seeds::STAKE_VAULT],
+        bump,
+        token::mint = protocol_token_mint,
+        token::authority = stake_registry,
+    )]
+    pub stake_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Protocol token mint
+    pub protocol_token_mint: Box<Account<'info, Mint>>,
+
+    /// Protocol config (authority check)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Protocol authority (pays for the accounts)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Initialize the buyback (paused)
+///
+/// # Arguments
+/// * `buyback_bps` - Share of the delegated treasury fee balance spent per buyback
+/// * `interval_seconds` - Minimum seconds between buybacks
+/// * `max_price_impact_bps` - Highest price impact per buyback
+pub fn initialize_buyback<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeBuyback<'info>>,
+    buyback_bps: u16,
+    interval_seconds: i64,
+    max_price_impact_bps: u16,
+) -> Result<()> {
+    require!(
+        BuybackConfig::validate_policy(buyback_bps, interval_seconds, max_price_impact_bps),
+        CloakCraftError::InvalidBuybackPolicy
+    );
+
+    let old_value_hash = AdminActionRecord::value_hash(&**ctx.accounts.buyback_config);
+
+    let config = &mut ctx.accounts.buyback_config;
+    config.protocol_token_mint = ctx.accounts.protocol_token_mint.key();
+    config.buyback_bps = buyback_bps;
+    config.interval_seconds = interval_seconds;
+    config.max_price_impact_bps = max_price_impact_bps;
+    config.is_active = false;
+    config.last_buyback_at = 0;
+    config.total_bought = 0;
+    config.total_distributed = 0;
+    config.bump = ctx.bumps.buyback_config;
+
+    let registry = &mut ctx.accounts.stake_registry;
+    registry.stake_vault = ctx.accounts.stake_vault.key();
+    registry.bump = ctx.bumps.stake_registry;
+    registry.vault_bump = ctx.bumps.stake_vault;
+
+    msg!(
+        "Buyback initialized for {}: {} bps every {}s",
+        config.protocol_token_mint,
+        buyback_bps,
+        interval_seconds
+    );
+
+    let new_value_hash = AdminActionRecord::value_hash(&**ctx.accounts.buyback_config);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::InitializeBuyback,
+        ctx.accounts.buyback_config.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+//! Protocol token buyback and distribution
+//!
+//! - Initialize / set policy: Protocol authority configures the buyback
+//! - Stake / unstake: Register protocol token stake and a distribution key
+//! - Execute buyback (keeper): Spend a share of treasury fees on the protocol token
+//! - Distribute buyback (keeper): Pay a staker's share as a shielded note
+
+mod initialize_buyback;
+mod set_buyback_policy;
+mod stake_protocol_token;
+mod unstake_protocol_token;
+mod execute_buyback;
+mod distribute_buyback;
+
+pub use initialize_buyback::*;
+pub use set_buyback_policy::*;
+pub use stake_protocol_token::*;
+pub use unstake_protocol_token::*;
+pub use execute_buyback::*;
+pub use distribute_buyback::*;
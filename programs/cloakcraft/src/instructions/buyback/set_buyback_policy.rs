@@ -0,0 +1,83 @@
+//! Set the buyback policy (admin only)
+//!
+//! Allows the protocol authority to change the buyback share, interval and
+//! price impact bound, and to pause or resume buybacks. Distributions of
+//! already bought tokens continue while paused.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{BuybackConfig, ProtocolConfig, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct SetBuybackPolicy<'info> {
+    /// Buyback config
+    #[account(
+        mut,
+        seeds = [seeds::BUYBACK_CONFIG],
+        bump = buyback_config.bump,
+    )]
+    pub buyback_config: Account<'info, BuybackConfig>,
+
+    /// Protocol config (authority check)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Protocol authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set the buyback policy
+///
+/// # Arguments
+/// * `buyback_bps` - Share of the delegated treasury fee balance spent per buyback
+/// * `interval_seconds` - Minimum seconds between buybacks
+/// * `max_price_impact_bps` - Highest price impact per buyback
+/// * `is_active` - Whether keepers may execute buybacks
+pub fn set_buyback_policy<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetBuybackPolicy<'info>>,
+    buyback_bps: u16,
+    interval_seconds: i64,
+    max_price_impact_bps: u16,
+    is_active: bool,
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.buyback_config);
+
+    require!(
+        BuybackConfig::validate_policy(buyback_bps, interval_seconds, max_price_impact_bps),
+        CloakCraftError::InvalidBuybackPolicy
+    );
+
+    let config = &mut ctx.accounts.buyback_config;
+    config.buyback_bps = buyback_bps;
+    config.interval_seconds = interval_seconds;
+    config.max_price_impact_bps = max_price_impact_bps;
+    config.is_active = is_active;
+    msg!(
+        "Buyback policy: {} bps every {}s, max impact {} bps, active={}",
+        buyback_bps,
+        interval_seconds,
+        max_price_impact_bps,
+        is_active
+    );
+
+    let new_value_hash = AdminActionRecord::value_hash(&*ctx.accounts.buyback_config);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::SetBuybackPolicy,
+        ctx.accounts.buyback_config.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+//! Stake protocol tokens for buyback distributions
+//!
+//! Locks protocol tokens in the stake vault and registers (or updates) the
+//! stealth key the staker's distribution notes are committed to. Stake
+//! earns from the next buyback on.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::state::{BuybackConfig, StakeRegistry};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::field::assert_canonical;
+
+#[derive(Accounts)]
+pub struct StakeProtocolToken<'info> {
+    /// Buyback config (protocol token mint)
+    #[account(
+        seeds = [seeds::BUYBACK_CONFIG],
+        bump = buyback_config.bump,
+    )]
+    pub buyback_config: Box<Account<'info, BuybackConfig>>,
+
+    /// Stake registry
+    #[account(
+        mut,
+        seeds = [seeds::STAKE_REGISTRY],
+        bump = stake_registry.bump,
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    /// Stake vault
+    #[account(
+        mut,
+        constraint = stake_vault.key() == stake_registry.stake_vault @ CloakCraftError::InvalidVault,
+    )]
+    pub stake_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Staker's protocol token account (source)
+    #[account(
+        mut,
+        constraint = staker_token_account.mint == buyback_config.protocol_token_mint @ CloakCraftError::TokenMintMismatch,
+    )]
+    pub staker_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Staker
+    pub staker: Signer<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Stake `amount` protocol tokens
+///
+/// # Arguments
+/// * `amount` - Protocol tokens to stake
+/// * `stealth_pub_x` - Stealth public key X for distribution notes
+pub fn stake_protocol_token(
+    ctx: Context<StakeProtocolToken>,
+    amount: u64,
+    stealth_pub_x: [u8; 32],
+) -> Result<()> {
+    require!(amount > 0, CloakCraftError::InvalidAmount);
+    // Committed into notes as a field element
+    assert_canonical(&[stealth_pub_x])?;
+
+    ctx.accounts.stake_registry
+        .stake(ctx.accounts.staker.key(), stealth_pub_x, amount)
+        .ok_or(CloakCraftError::StakeRegistryFull)?;
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.staker_token_account.to_account_info(),
+                to: ctx.accounts.stake_vault.to_account_info(),
+                authority: ctx.accounts.staker.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    msg!(
+        "Staked {} (total stake {})",
+        amount,
+        ctx.accounts.stake_registry.total_stake
+    );
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+//! Unstake protocol tokens
+//!
+//! Returns staked protocol tokens from the stake vault. Rewards earned so
+//! far stay owed and are still paid by `distribute_buyback`.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::state::StakeRegistry;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct UnstakeProtocolToken<'info> {
+    /// Stake registry (authority for stake vault transfers)
+    #[account(
+        mut,
+        seeds = [seeds::STAKE_REGISTRY],
+        bump = stake_registry.bump,
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    /// Stake vault
+    #[account(
+        mut,
+        constraint = stake_vault.key() == stake_registry.stake_vault @ CloakCraftError::InvalidVault,
+    )]
+    pub stake_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Protocol token account receiving the stake
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == stake_vault.mint @ CloakCraftError::TokenMintMismatch,
+    )]
+    pub recipient_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Staker
+    pub staker: Signer<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Unstake `amount` protocol tokens
+pub fn unstake_protocol_token(ctx: Context<UnstakeProtocolToken>, amount: u64) -> Result<()> {
+    require!(amount > 0, CloakCraftError::InvalidAmount);
+
+    let registry = &mut ctx.accounts.stake_registry;
+    registry
+        .unstake(ctx.accounts.staker.key, amount)
+        .ok_or(CloakCraftError::InvalidUnstake)?;
+
+    let signer_seeds: &[&[&[u8]]] = &[&[seeds::STAKE_REGISTRY, &[registry.bump]]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.stake_vault.to_account_info(),
+                to: ctx.accounts.recipient_token_account.to_account_info(),
+                authority: ctx.accounts.stake_registry.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    msg!(
+        "Unstaked {} (total stake {})",
+        amount,
+        ctx.accounts.stake_registry.total_stake
+    );
+
+    Ok(())
+}
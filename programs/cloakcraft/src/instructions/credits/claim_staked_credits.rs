@@ -0,0 +1,123 @@
+//! Claim stake-backed operation credits
+//!
+//! A protocol token staker with at least `min_stake` appends up to
+//! `credits_per_epoch` credit leaves per epoch to the epoch's credit tree.
+//! The leaves hide the credit secrets, so later spends are not linked to
+//! the staker.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{CreditConfig, CreditIssuance, CreditTree, OperationCreditsIssued, StakeRegistry};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::field::assert_canonical;
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct ClaimStakedCredits<'info> {
+    /// Credit config
+    #[account(
+        mut,
+        seeds = [seeds::CREDIT_CONFIG],
+        bump = credit_config.bump,
+    )]
+    pub credit_config: Box<Account<'info, CreditConfig>>,
+
+    /// Current epoch's credit tree (created by the epoch's first issuance)
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = 8 + CreditTree::INIT_SPACE,
+        seeds = [seeds::CREDIT_TREE, epoch.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub credit_tree: Box<Account<'info, CreditTree>>,
+
+    /// Protocol token stake registry
+    #[account(
+        seeds = [seeds::STAKE_REGISTRY],
+        bump = stake_registry.bump,
+    )]
+    pub stake_registry: Box<Account<'info, StakeRegistry>>,
+
+    /// Staker's issuance this epoch
+    #[account(
+        init_if_needed,
+        payer = staker,
+        space = 8 + CreditIssuance::INIT_SPACE,
+        seeds = [seeds::CREDIT_ISSUANCE, staker.key().as_ref()],
+        bump,
+    )]
+    pub credit_issuance: Account<'info, CreditIssuance>,
+
+    /// Staker (pays for the accounts on first use)
+    #[account(mut)]
+    pub staker: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Claim stake-backed credits for the current epoch
+///
+/// # Arguments
+/// * `epoch` - Current credit epoch (selects the credit tree)
+/// * `leaves` - Credit leaves `Poseidon(OPERATION_CREDIT, secret)`
+pub fn claim_staked_credits(
+    ctx: Context<ClaimStakedCredits>,
+    epoch: u64,
+    leaves: Vec<[u8; 32]>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.credit_config;
+    require!(
+        epoch == config.epoch_at(Clock::get()?.unix_timestamp),
+        CloakCraftError::CreditEpochMismatch
+    );
+
+    // Leaves are commitments: field elements in canonical encoding
+    assert_canonical(&leaves)?;
+
+    let staker = ctx.accounts.staker.key();
+    let staked = ctx.accounts.stake_registry.stakers
+        .iter()
+        .find(|entry| entry.staker == staker)
+        .map_or(0, |entry| entry.stake);
+    require!(
+        config.credits_per_epoch > 0 && staked > 0 && staked >= config.min_stake,
+        CloakCraftError::InsufficientCreditStake
+    );
+
+    let issuance = &mut ctx.accounts.credit_issuance;
+    issuance.staker = staker;
+    issuance.bump = ctx.bumps.credit_issuance;
+    let count = u16::try_from(leaves.len()).map_err(|_| CloakCraftError::InvalidAmount)?;
+    require!(
+        issuance.take(epoch, count, config.credits_per_epoch),
+        CloakCraftError::CreditAllowanceExceeded
+    );
+
+    let tree = &mut ctx.accounts.credit_tree;
+    tree.epoch = epoch;
+    tree.bump = ctx.bumps.credit_tree;
+    let first_leaf_index = tree.leaf_count;
+    let root = tree.append(&leaves)?;
+
+    config.total_issued = config.total_issued
+        .checked_add(leaves.len() as u64)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    msg!(
+        "Issued {} stake-backed credits for epoch {} ({} of {} this epoch)",
+        leaves.len(), epoch, issuance.issued, config.credits_per_epoch
+    );
+
+    emit!(OperationCreditsIssued {
+        epoch,
+        first_leaf_index,
+        leaves,
+        root,
+        stake_backed: true,
+    });
+
+    Ok(())
+}
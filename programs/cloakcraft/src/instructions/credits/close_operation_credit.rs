@@ -0,0 +1,47 @@
+//! Close a spent operation credit
+//!
+//! Once the credit's epoch has ended its tree no longer accepts proofs, so
+//! the nullifier record is no longer needed to prevent a second spend. The
+//! rent returns to the relayer that spent the credit.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{CreditConfig, OperationCredit};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct CloseOperationCredit<'info> {
+    /// Credit config
+    #[account(
+        seeds = [seeds::CREDIT_CONFIG],
+        bump = credit_config.bump,
+    )]
+    pub credit_config: Box<Account<'info, CreditConfig>>,
+
+    /// Spent credit
+    #[account(
+        mut,
+        close = relayer,
+        seeds = [seeds::CREDIT_NULLIFIER, operation_credit.credit_nullifier.as_ref()],
+        bump = operation_credit.bump,
+        has_one = relayer @ CloakCraftError::InvalidRelayer,
+    )]
+    pub operation_credit: Box<Account<'info, OperationCredit>>,
+
+    /// Relayer that spent the credit
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+}
+
+pub fn close_operation_credit(ctx: Context<CloseOperationCredit>) -> Result<()> {
+    let current_epoch = ctx.accounts.credit_config.epoch_at(Clock::get()?.unix_timestamp);
+    require!(
+        ctx.accounts.operation_credit.epoch < current_epoch,
+        CloakCraftError::CreditEpochMismatch
+    );
+
+    msg!("Closed spent credit from epoch {}", ctx.accounts.operation_credit.epoch);
+
+    Ok(())
+}
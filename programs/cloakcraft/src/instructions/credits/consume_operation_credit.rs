@@ -0,0 +1,119 @@
+//! Spend an operation credit
+//!
+//! Proves a credit leaf is in the current epoch's credit tree and records
+//! its nullifier against one operation ID. Runs before Phase 0 of that
+//! operation, which checks the spent credit while credits are required.
+//! The proof binds the operation ID, so a copied proof cannot be spent for
+//! another operation.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{CreditConfig, CreditTree, OperationCredit, OperationCreditConsumed, VerificationKey};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::verify_groth16_proof;
+use crate::helpers::field::{bytes_to_field, assert_canonical};
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32], epoch: u64, proof: Vec<u8>, credit_root: [u8; 32], credit_nullifier: [u8; 32])]
+pub struct ConsumeOperationCredit<'info> {
+    /// Credit config
+    #[account(
+        mut,
+        seeds = [seeds::CREDIT_CONFIG],
+        bump = credit_config.bump,
+    )]
+    pub credit_config: Box<Account<'info, CreditConfig>>,
+
+    /// Current epoch's credit tree
+    #[account(
+        seeds = [seeds::CREDIT_TREE, epoch.to_le_bytes().as_ref()],
+        bump = credit_tree.bump,
+    )]
+    pub credit_tree: Box<Account<'info, CreditTree>>,
+
+    /// Verification key for the operation credit circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, crate::constants::circuits::OPERATION_CREDIT.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Spent credit (creation fails if the nullifier was already spent)
+    #[account(
+        init,
+        payer = relayer,
+        space = 8 + OperationCredit::INIT_SPACE,
+        seeds = [seeds::CREDIT_NULLIFIER, credit_nullifier.as_ref()],
+        bump,
+    )]
+    pub operation_credit: Box<Account<'info, OperationCredit>>,
+
+    /// Relayer (pays the rent, refunded on close)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Spend a credit for `operation_id`
+///
+/// # Arguments
+/// * `operation_id` - Operation the credit is spent for
+/// * `epoch` - Current credit epoch (selects the credit tree)
+/// * `proof` - Operation credit proof
+/// * `credit_root` - Credit tree root the proof is against
+/// * `credit_nullifier` - `Poseidon(CREDIT_NULLIFIER, secret)`
+pub fn consume_operation_credit(
+    ctx: Context<ConsumeOperationCredit>,
+    operation_id: [u8; 32],
+    epoch: u64,
+    proof: Vec<u8>,
+    credit_root: [u8; 32],
+    credit_nullifier: [u8; 32],
+) -> Result<()> {
+    let config = &mut ctx.accounts.credit_config;
+    require!(
+        epoch == config.epoch_at(Clock::get()?.unix_timestamp),
+        CloakCraftError::CreditEpochMismatch
+    );
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[credit_nullifier])?;
+    ctx.accounts.credit_tree.check_root(&credit_root)?;
+
+    let public_inputs = vec![
+        credit_root,
+        credit_nullifier,
+        bytes_to_field(&operation_id),
+    ];
+    verify_groth16_proof(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "OperationCredit",
+    )?;
+
+    let credit = &mut ctx.accounts.operation_credit;
+    credit.credit_nullifier = credit_nullifier;
+    credit.operation_id = operation_id;
+    credit.epoch = epoch;
+    credit.relayer = ctx.accounts.relayer.key();
+    credit.bump = ctx.bumps.operation_credit;
+
+    config.total_consumed = config.total_consumed
+        .checked_add(1)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    emit!(OperationCreditConsumed {
+        epoch,
+        credit_nullifier,
+        operation_id,
+    });
+
+    msg!("Operation credit spent (epoch {})", epoch);
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+//! Initialize operation credits (admin only)
+//!
+//! Creates the credit config. Credits are issued from then on, but Phase 0
+//! only requires them once `set_credit_policy` turns enforcement on.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{CreditConfig, ProtocolConfig, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct InitializeCreditConfig<'info> {
+    /// Credit config (singleton)
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CreditConfig::INIT_SPACE,
+        seeds = [seeds::CREDIT_CONFIG],
+        bump,
+    )]
+    pub credit_config: Box<Account<'info, CreditConfig>>,
+
+    /// Protocol config (authority check)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Protocol authority (pays for the config)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Initialize operation credits
+///
+/// # Arguments
+/// * `epoch_seconds` - Credit epoch length (cannot be changed later)
+/// * `credits_per_epoch` - Credits a staker may obtain per epoch
+/// * `min_stake` - Protocol token stake required for stake-backed credits
+/// * `fee_lamports` - Price of a bought credit (0 = not sold)
+pub fn initialize_credit_config<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeCreditConfig<'info>>,
+    epoch_seconds: i64,
+    credits_per_epoch: u16,
+    min_stake: u64,
+    fee_lamports: u64,
+) -> Result<()> {
+    require!(
+        CreditConfig::validate_epoch(epoch_seconds),
+        CloakCraftError::InvalidCreditPolicy
+    );
+
+    let old_value_hash = AdminActionRecord::value_hash(&**ctx.accounts.credit_config);
+
+    let config = &mut ctx.accounts.credit_config;
+    config.epoch_seconds = epoch_seconds;
+    config.credits_per_epoch = credits_per_epoch;
+    config.min_stake = min_stake;
+    config.fee_lamports = fee_lamports;
+    config.total_issued = 0;
+    config.total_consumed = 0;
+    config.bump = ctx.bumps.credit_config;
+
+    msg!(
+        "Operation credits initialized: {}s epochs, {} credits per staker, {} lamports per credit",
+        epoch_seconds,
+        credits_per_epoch,
+        fee_lamports
+    );
+
+    let new_value_hash = AdminActionRecord::value_hash(&**ctx.accounts.credit_config);
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::InitializeCreditConfig,
+        ctx.accounts.credit_config.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,21 @@
+//! Anonymous operation credits (per-user rate limiting)
+//!
+//! - Initialize / set policy: Protocol authority configures issuance and enforcement
+//! - Claim staked credits: Protocol token stakers obtain credits each epoch
+//! - Purchase credits: Anyone buys credits for a fee
+//! - Consume credit (relayer): Spend a credit for an operation before its Phase 0
+//! - Close credit (relayer): Reclaim a spent credit's rent after its epoch
+
+mod initialize_credit_config;
+mod set_credit_policy;
+mod claim_staked_credits;
+mod purchase_credits;
+mod consume_operation_credit;
+mod close_operation_credit;
+
+pub use initialize_credit_config::*;
+pub use set_credit_policy::*;
+pub use claim_staked_credits::*;
+pub use purchase_credits::*;
+pub use consume_operation_credit::*;
+pub use close_operation_credit::*;
@@ -0,0 +1,113 @@
+//! Buy operation credits
+//!
+//! Anyone may append credit leaves to the current epoch's credit tree for
+//! `fee_lamports` each, paid to the treasury.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+use crate::state::{CreditConfig, CreditTree, OperationCreditsIssued, ProtocolConfig};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::field::assert_canonical;
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct PurchaseCredits<'info> {
+    /// Credit config
+    #[account(
+        mut,
+        seeds = [seeds::CREDIT_CONFIG],
+        bump = credit_config.bump,
+    )]
+    pub credit_config: Box<Account<'info, CreditConfig>>,
+
+    /// Current epoch's credit tree (created by the epoch's first issuance)
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + CreditTree::INIT_SPACE,
+        seeds = [seeds::CREDIT_TREE, epoch.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub credit_tree: Box<Account<'info, CreditTree>>,
+
+    /// Protocol config (treasury)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Treasury (receives the credit fee)
+    /// CHECK: Must be the protocol treasury
+    #[account(
+        mut,
+        address = protocol_config.treasury @ CloakCraftError::InvalidTreasury,
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Buyer (pays the fee)
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Buy credits for the current epoch
+///
+/// # Arguments
+/// * `epoch` - Current credit epoch (selects the credit tree)
+/// * `leaves` - Credit leaves `Poseidon(OPERATION_CREDIT, secret)`
+pub fn purchase_credits(
+    ctx: Context<PurchaseCredits>,
+    epoch: u64,
+    leaves: Vec<[u8; 32]>,
+) -> Result<()> {
+    let config = &mut ctx.accounts.credit_config;
+    require!(config.fee_lamports > 0, CloakCraftError::CreditPurchaseDisabled);
+    require!(
+        epoch == config.epoch_at(Clock::get()?.unix_timestamp),
+        CloakCraftError::CreditEpochMismatch
+    );
+
+    // Leaves are commitments: field elements in canonical encoding
+    assert_canonical(&leaves)?;
+
+    let fee = config.fee_lamports
+        .checked_mul(leaves.len() as u64)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.buyer.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            },
+        ),
+        fee,
+    )?;
+
+    let tree = &mut ctx.accounts.credit_tree;
+    tree.epoch = epoch;
+    tree.bump = ctx.bumps.credit_tree;
+    let first_leaf_index = tree.leaf_count;
+    let root = tree.append(&leaves)?;
+
+    config.total_issued = config.total_issued
+        .checked_add(leaves.len() as u64)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    msg!("Sold {} credits for epoch {} ({} lamports)", leaves.len(), epoch, fee);
+
+    emit!(OperationCreditsIssued {
+        epoch,
+        first_leaf_index,
+        leaves,
+        root,
+        stake_backed: false,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Set the operation credit policy (admin only)
+//!
+//! Allows the protocol authority to change the stake-backed allowance, the
+//! credit price, and whether Phase 0 requires a credit. The epoch length is
+//! fixed: changing it would renumber epochs and revive old credit trees.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{CreditConfig, ProtocolConfig, AdminAction, AdminActionRecord};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::admin_audit::record_admin_action;
+
+#[derive(Accounts)]
+pub struct SetCreditPolicy<'info> {
+    /// Credit config
+    #[account(
+        mut,
+        seeds = [seeds::CREDIT_CONFIG],
+        bump = credit_config.bump,
+    )]
+    pub credit_config: Account<'info, CreditConfig>,
+
+    /// Protocol config (authority check, credit enforcement flag)
+    #[account(
+        mut,
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+
+    /// Protocol authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+/// Set the operation credit policy
+///
+/// # Arguments
+/// * `credits_per_epoch` - Credits a staker may obtain per epoch
+/// * `min_stake` - Protocol token stake required for stake-backed credits
+/// * `fee_lamports` - Price of a bought credit (0 = not sold)
+/// * `require_credits` - Whether Phase 0 requires a spent credit
+pub fn set_credit_policy<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetCreditPolicy<'info>>,
+    credits_per_epoch: u16,
+    min_stake: u64,
+    fee_lamports: u64,
+    require_credits: bool,
+) -> Result<()> {
+    let old_value_hash = AdminActionRecord::value_hash(&(
+        (*ctx.accounts.credit_config).clone(),
+        ctx.accounts.protocol_config.require_operation_credits,
+    ));
+
+    let config = &mut ctx.accounts.credit_config;
+    config.credits_per_epoch = credits_per_epoch;
+    config.min_stake = min_stake;
+    config.fee_lamports = fee_lamports;
+    ctx.accounts.protocol_config.require_operation_credits = require_credits;
+    msg!(
+        "Credit policy: {} credits per staker (min stake {}), {} lamports per credit, required={}",
+        credits_per_epoch,
+        min_stake,
+        fee_lamports,
+        require_credits
+    );
+
+    let new_value_hash = AdminActionRecord::value_hash(&(
+        (*ctx.accounts.credit_config).clone(),
+        ctx.accounts.protocol_config.require_operation_credits,
+    ));
+    record_admin_action(
+        &ctx.accounts.authority.to_account_info(),
+        ctx.remaining_accounts,
+        ctx.accounts.authority.key(),
+        AdminAction::SetCreditPolicy,
+        ctx.accounts.credit_config.key(),
+        old_value_hash,
+        new_value_hash,
+    )?;
+
+    Ok(())
+}
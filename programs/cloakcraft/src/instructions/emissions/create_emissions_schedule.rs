@@ -0,0 +1,148 @@
+//! Create an emissions schedule for an AMM or perps pool
+//!
+//! The source pool authority funds the full schedule up front
+//! (`reward_rate * (end_time - start_time)`) into a schedule-owned vault.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{AmmPool, EmissionsPoolKind, EmissionsSchedule, PerpsPool};
+
+#[derive(Accounts)]
+pub struct CreateEmissionsSchedule<'info> {
+    /// Emissions schedule to create (one per source pool)
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + EmissionsSchedule::INIT_SPACE,
+        seeds = [seeds::EMISSIONS, source_pool.key().as_ref()],
+        bump,
+    )]
+    pub emissions_schedule: Box<Account<'info, EmissionsSchedule>>,
+
+    /// AMM or perps pool whose LP notes earn rewards
+    /// CHECK: Owner and type validated in handler via `load_source_pool`
+    pub source_pool: UncheckedAccount<'info>,
+
+    /// Rewards token mint
+    pub reward_mint: Box<Account<'info, Mint>>,
+
+    /// Reward vault (PDA owned by the schedule)
+    #[account(
+        init,
+        payer = authority,
+        seeds = [seeds::EMISSIONS_VAULT, emissions_schedule.key().as_ref()],
+        bump,
+        token::mint = reward_mint,
+        token::authority = emissions_schedule,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Authority's reward token account (funds the schedule)
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == reward_mint.key() @ CloakCraftError::TokenMintMismatch,
+    )]
+    pub funder_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Source pool authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// LP fields read from an AMM or perps pool
+pub struct SourcePoolInfo {
+    pub lp_mint: Pubkey,
+    pub lp_supply: u64,
+    pub authority: Pubkey,
+}
+
+/// Deserialize the schedule's source pool as the expected kind
+pub(crate) fn load_source_pool(
+    source_pool: &AccountInfo,
+    kind: EmissionsPoolKind,
+) -> Result<SourcePoolInfo> {
+    require!(
+        *source_pool.owner == crate::ID,
+        CloakCraftError::EmissionsPoolMismatch
+    );
+    let data = source_pool.try_borrow_data()?;
+    let info = match kind {
+        EmissionsPoolKind::Amm => {
+            let pool = AmmPool::try_deserialize(&mut &data[..])?;
+            SourcePoolInfo { lp_mint: pool.lp_mint, lp_supply: pool.lp_supply, authority: pool.authority }
+        }
+        EmissionsPoolKind::Perps => {
+            let pool = PerpsPool::try_deserialize(&mut &data[..])?;
+            SourcePoolInfo { lp_mint: pool.lp_mint, lp_supply: pool.lp_supply, authority: pool.authority }
+        }
+    };
+    Ok(info)
+}
+
+pub fn create_emissions_schedule(
+    ctx: Context<CreateEmissionsSchedule>,
+    pool_kind: EmissionsPoolKind,
+    reward_rate: u64,
+    start_time: i64,
+    end_time: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let source = load_source_pool(&ctx.accounts.source_pool, pool_kind)?;
+
+    require!(
+        ctx.accounts.authority.key() == source.authority,
+        CloakCraftError::Unauthorized
+    );
+    require!(
+        reward_rate > 0 && start_time >= clock.unix_timestamp && end_time > start_time,
+        CloakCraftError::InvalidEmissionsSchedule
+    );
+
+    let schedule = &mut ctx.accounts.emissions_schedule;
+    schedule.source_pool = ctx.accounts.source_pool.key();
+    schedule.pool_kind = pool_kind;
+    schedule.lp_mint = source.lp_mint;
+    schedule.reward_mint = ctx.accounts.reward_mint.key();
+    schedule.reward_vault = ctx.accounts.reward_vault.key();
+    schedule.authority = ctx.accounts.authority.key();
+    schedule.reward_rate = reward_rate;
+    schedule.start_time = start_time;
+    schedule.end_time = end_time;
+    schedule.last_update_time = start_time;
+    schedule.reward_per_lp = 0;
+    schedule.total_claimed = 0;
+    schedule.bump = ctx.bumps.emissions_schedule;
+    schedule.vault_bump = ctx.bumps.reward_vault;
+
+    let total_rewards = schedule.total_rewards()
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    // Fund the full schedule up front
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.funder_token_account.to_account_info(),
+                to: ctx.accounts.reward_vault.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        ),
+        total_rewards,
+    )?;
+
+    msg!("Emissions schedule created for pool {}", schedule.source_pool);
+    msg!("  Reward mint: {}", schedule.reward_mint);
+    msg!("  Rate: {}/s, window: {} -> {}", reward_rate, start_time, end_time);
+    msg!("  Total rewards: {}", total_rewards);
+
+    Ok(())
+}
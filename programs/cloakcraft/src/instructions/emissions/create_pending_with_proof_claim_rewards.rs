@@ -0,0 +1,278 @@
+//! Create Pending with Proof - Claim Rewards (Phase 0)
+//!
+//! Claims liquidity mining rewards for a shielded LP note.
+//! The circuit proves ownership of the LP note; its `entry_reward_per_lp` is
+//! read from the note's `RewardCheckpoint`, and elapsed emissions since that
+//! checkpoint are paid as a shielded rewards note. The LP note is re-created
+//! with the same amount, and a checkpoint at the current accumulator is
+//! recorded for it here. The spent note's checkpoint is closed in Phase 3.
+//!
+//! Flow:
+//! Phase 0 (this): Accrue schedule + Verify ZK proof + Create PendingOperation
+//! Phase 1: verify_commitment_exists for LP note
+//! Phase 2: create_nullifier_and_pending for LP note (prevents double-claim)
+//! Phase 3: execute_claim_rewards - Move rewards into the shielded reward pool
+//! Phase 4: create_commitment for new LP note + rewards note
+//! Final: close_pending_operation
+
+use anchor_lang::prelude::*;
+
+use crate::constants::{circuits, operation_types, seeds};
+use crate::errors::CloakCraftError;
+use crate::helpers::field::{pubkey_to_field, assert_canonical};
+use crate::helpers::verify_groth16_proof_metered;
+use crate::state::{
+    EmissionsSchedule, PendingOperation, Pool, VerificationKey, ProtocolConfig, ProgramVersion, CircuitStats,
+    RewardCheckpoint, OperationCredit,
+};
+
+use super::load_source_pool;
+
+#[derive(Accounts)]
+#[instruction(
+    operation_id: [u8; 32],
+    proof: Vec<u8>,
+    merkle_root: [u8; 32],
+    lp_commitment: [u8; 32],
+    lp_nullifier: [u8; 32],
+    new_lp_commitment: [u8; 32],
+)]
+pub struct CreatePendingWithProofClaimRewards<'info> {
+    /// Emissions schedule (accumulator advanced here)
+    #[account(
+        mut,
+        seeds = [seeds::EMISSIONS, emissions_schedule.source_pool.as_ref()],
+        bump = emissions_schedule.bump,
+    )]
+    pub emissions_schedule: Box<Account<'info, EmissionsSchedule>>,
+
+    /// AMM or perps pool the schedule rewards (read for LP supply)
+    /// CHECK: Must match schedule, deserialized in handler
+    #[account(
+        constraint = source_pool.key() == emissions_schedule.source_pool @ CloakCraftError::EmissionsPoolMismatch,
+    )]
+    pub source_pool: UncheckedAccount<'info>,
+
+    /// LP token pool (LP input and re-created LP note)
+    #[account(
+        seeds = [seeds::POOL, lp_pool.token_mint.as_ref()],
+        bump = lp_pool.bump,
+        constraint = lp_pool.token_mint == emissions_schedule.lp_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub lp_pool: Box<Account<'info, Pool>>,
+
+    /// Rewards token pool (rewards note)
+    #[account(
+        seeds = [seeds::POOL, reward_pool.token_mint.as_ref()],
+        bump = reward_pool.bump,
+        constraint = reward_pool.token_mint == emissions_schedule.reward_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub reward_pool: Box<Account<'info, Pool>>,
+
+    /// Reward checkpoint of the spent LP note (source of `entry_reward_per_lp`)
+    #[account(
+        seeds = [seeds::REWARD_CHECKPOINT, emissions_schedule.key().as_ref(), lp_commitment.as_ref()],
+        bump = reward_checkpoint.bump,
+    )]
+    pub reward_checkpoint: Box<Account<'info, RewardCheckpoint>>,
+
+    /// Reward checkpoint of the re-created LP note (created here)
+    #[account(
+        init,
+        payer = relayer,
+        space = 8 + RewardCheckpoint::INIT_SPACE,
+        seeds = [seeds::REWARD_CHECKPOINT, emissions_schedule.key().as_ref(), new_lp_commitment.as_ref()],
+        bump,
+    )]
+    pub new_reward_checkpoint: Box<Account<'info, RewardCheckpoint>>,
+
+    /// Verification key for the claim rewards circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, circuits::CLAIM_REWARDS.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Pending operation PDA (created in this instruction)
+    #[account(
+        init,
+        payer = relayer,
+        space = PendingOperation::SPACE,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (pays for PDA creation)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Phase 0: Verify ZK proof and create PendingOperation for a rewards claim
+#[allow(clippy::too_many_arguments)]
+pub fn create_pending_with_proof_claim_rewards<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofClaimRewards<'info>>,
+    operation_id: [u8; 32],
+    proof: Vec<u8>,
+    merkle_root: [u8; 32],
+    lp_commitment: [u8; 32],
+    lp_nullifier: [u8; 32],
+    new_lp_commitment: [u8; 32],
+    reward_commitment: [u8; 32],
+    lp_amount: u64,
+    reward_amount: u64,
+    client_version: u32,
+) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[lp_commitment, lp_nullifier, new_lp_commitment, reward_commitment])?;
+
+    let clock = Clock::get()?;
+
+    msg!("=== Phase 0: Verify Proof + Create Pending (Claim Rewards) ===");
+
+    // 1. Advance the accumulator to now
+    let source = load_source_pool(&ctx.accounts.source_pool, ctx.accounts.emissions_schedule.pool_kind)?;
+    let schedule = &mut ctx.accounts.emissions_schedule;
+    schedule.accrue(clock.unix_timestamp, source.lp_supply)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    // 2. Verify claimed reward against elapsed emissions since the checkpoint
+    let entry_reward_per_lp = ctx.accounts.reward_checkpoint.reward_per_lp;
+    let expected_reward = schedule.pending_reward(lp_amount, entry_reward_per_lp)
+        .ok_or(CloakCraftError::InvalidRewardCheckpoint)?;
+    require!(reward_amount > 0, CloakCraftError::InvalidAmount);
+    // Allow small rounding differences (1 token)
+    if reward_amount.abs_diff(expected_reward) > 1 {
+        msg!("Reward mismatch: expected {}, got {}", expected_reward, reward_amount);
+        return Err(CloakCraftError::RewardAmountMismatch.into());
+    }
+
+    // 3. Verify ZK proof
+    let mut lp_amount_bytes = [0u8; 32];
+    lp_amount_bytes[24..].copy_from_slice(&lp_amount.to_be_bytes());
+
+    let mut entry_bytes = [0u8; 32];
+    entry_bytes[16..].copy_from_slice(&entry_reward_per_lp.to_be_bytes());
+
+    let mut exit_bytes = [0u8; 32];
+    exit_bytes[16..].copy_from_slice(&schedule.reward_per_lp.to_be_bytes());
+
+    let mut reward_bytes = [0u8; 32];
+    reward_bytes[24..].copy_from_slice(&reward_amount.to_be_bytes());
+
+    // lp_commitment is bound so the reward checkpoint belongs to the spent note
+    let public_inputs = vec![
+        merkle_root,
+        lp_commitment,
+        lp_nullifier,
+        pubkey_to_field(&schedule.key()),
+        pubkey_to_field(&ctx.accounts.lp_pool.token_mint),
+        pubkey_to_field(&ctx.accounts.reward_pool.token_mint),
+        new_lp_commitment,
+        reward_commitment,
+        lp_amount_bytes,
+        entry_bytes,
+        exit_bytes,
+        reward_bytes,
+    ];
+
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "ClaimRewards",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
+    msg!("✅ ZK proof verified");
+
+    // 4. Initialize pending operation PDA with binding fields
+    let pending_op = &mut ctx.accounts.pending_operation;
+    pending_op.bump = ctx.bumps.pending_operation;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
+    pending_op.relayer = ctx.accounts.relayer.key();
+    pending_op.operation_type = operation_types::CLAIM_REWARDS;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
+    pending_op.created_at = clock.unix_timestamp;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
+
+    // SECURITY: Store binding fields from ZK proof
+    pending_op.num_inputs = 1;
+    pending_op.input_commitments[0] = lp_commitment;
+    pending_op.expected_nullifiers[0] = lp_nullifier;
+    pending_op.input_pools[0] = ctx.accounts.lp_pool.key().to_bytes();
+    // Spent note's checkpoint, closed in Phase 3
+    pending_op.input_pools[1] = ctx.accounts.reward_checkpoint.key().to_bytes();
+    pending_op.inputs_verified_mask = 0;
+    pending_op.proof_verified = true;
+
+    // Store output commitments (re-created LP note + rewards note)
+    pending_op.num_commitments = 2;
+    pending_op.pools[0] = ctx.accounts.lp_pool.key().to_bytes();
+    pending_op.commitments[0] = new_lp_commitment;
+    pending_op.output_amounts[0] = lp_amount;
+    pending_op.pools[1] = ctx.accounts.reward_pool.key().to_bytes();
+    pending_op.commitments[1] = reward_commitment;
+    pending_op.output_amounts[1] = reward_amount;
+
+    pending_op.nullifier_completed_mask = 0;
+    pending_op.completed_mask = 0;
+
+    // Store claim-specific data for Phase 3
+    // swap_amount = lp_amount, output_amount = reward_amount
+    pending_op.swap_amount = lp_amount;
+    pending_op.output_amount = reward_amount;
+
+    // Re-created LP note earns from the current accumulator
+    let new_checkpoint = &mut ctx.accounts.new_reward_checkpoint;
+    new_checkpoint.emissions_schedule = schedule.key();
+    new_checkpoint.lp_commitment = new_lp_commitment;
+    new_checkpoint.reward_per_lp = schedule.reward_per_lp;
+    new_checkpoint.payer = ctx.accounts.relayer.key();
+    new_checkpoint.bump = ctx.bumps.new_reward_checkpoint;
+
+    msg!("Reward per LP: {}", schedule.reward_per_lp);
+    msg!("LP amount: {}, reward: {}", lp_amount, reward_amount);
+    msg!("Phase 0 complete: ZK proof verified, pending operation created");
+    msg!("Next: Phase 1 - verify_commitment_exists");
+
+    Ok(())
+}
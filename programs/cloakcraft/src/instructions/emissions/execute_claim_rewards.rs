@@ -0,0 +1,140 @@
+//! Execute Claim Rewards (Phase 3)
+//!
+//! Moves the claimed rewards from the schedule vault into the shielded
+//! reward pool vault, backing the rewards note created in Phase 4.
+//! Called after the LP note nullifier is created (Phase 2), so the spent
+//! note's reward checkpoint is closed here.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{EmissionsSchedule, PendingOperation, OperationKind, Pool, RewardCheckpoint};
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct ExecuteClaimRewards<'info> {
+    /// Emissions schedule
+    #[account(
+        mut,
+        seeds = [seeds::EMISSIONS, emissions_schedule.source_pool.as_ref()],
+        bump = emissions_schedule.bump,
+    )]
+    pub emissions_schedule: Box<Account<'info, EmissionsSchedule>>,
+
+    /// Schedule reward vault (source of rewards)
+    #[account(
+        mut,
+        seeds = [seeds::EMISSIONS_VAULT, emissions_schedule.key().as_ref()],
+        bump = emissions_schedule.vault_bump,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Rewards token pool (receives the rewards note)
+    #[account(
+        mut,
+        seeds = [seeds::POOL, reward_pool.token_mint.as_ref()],
+        bump = reward_pool.bump,
+        constraint = reward_pool.token_mint == emissions_schedule.reward_mint @ CloakCraftError::InvalidTokenMint,
+        constraint = pending_operation.pools[1] == reward_pool.key().to_bytes() @ CloakCraftError::PoolMismatch,
+    )]
+    pub reward_pool: Box<Account<'info, Pool>>,
+
+    /// Rewards token pool vault
+    #[account(
+        mut,
+        seeds = [seeds::VAULT, reward_pool.token_mint.as_ref()],
+        bump = reward_pool.vault_bump,
+    )]
+    pub reward_pool_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Pending operation PDA (from Phase 0)
+    #[account(
+        mut,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump = pending_operation.bump,
+        constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Reward checkpoint of the spent LP note (bound in Phase 0, closed here)
+    #[account(
+        mut,
+        close = checkpoint_payer,
+        constraint = reward_checkpoint.key().to_bytes() == pending_operation.input_pools[1] @ CloakCraftError::RewardCheckpointMismatch,
+    )]
+    pub reward_checkpoint: Box<Account<'info, RewardCheckpoint>>,
+
+    /// Receives the checkpoint rent (must be its payer)
+    /// CHECK: Address checked against reward_checkpoint.payer
+    #[account(
+        mut,
+        constraint = checkpoint_payer.key() == reward_checkpoint.payer @ CloakCraftError::InvalidRewardCheckpointPayer,
+    )]
+    pub checkpoint_payer: UncheckedAccount<'info>,
+
+    /// Relayer (must match pending operation)
+    #[account(
+        constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
+    )]
+    pub relayer: Signer<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Phase 3: Transfer rewards into the shielded reward pool
+pub fn execute_claim_rewards(
+    ctx: Context<ExecuteClaimRewards>,
+    _operation_id: [u8; 32],
+) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::ClaimRewards)?;
+
+    let schedule = &mut ctx.accounts.emissions_schedule;
+    let pending_op = &mut ctx.accounts.pending_operation;
+
+    msg!("=== Phase 3: Execute Claim Rewards ===");
+
+    // output_amount is cleared after execution so the transfer cannot repeat
+    let reward_amount = pending_op.output_amount;
+    require!(reward_amount > 0, CloakCraftError::InvalidAmount);
+    require!(
+        ctx.accounts.reward_vault.amount >= reward_amount,
+        CloakCraftError::InsufficientBalance
+    );
+
+    let source_pool = schedule.source_pool;
+    let signer_seeds: &[&[&[u8]]] = &[&[seeds::EMISSIONS, source_pool.as_ref(), &[schedule.bump]]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                to: ctx.accounts.reward_pool_vault.to_account_info(),
+                authority: schedule.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        reward_amount,
+    )?;
+
+    schedule.total_claimed = schedule.total_claimed
+        .checked_add(reward_amount)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    reward_pool.total_shielded = reward_pool.total_shielded
+        .checked_add(reward_amount)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    pending_op.output_amount = 0;
+
+    msg!("✅ Rewards claimed: {}", reward_amount);
+    msg!("Total claimed: {}", schedule.total_claimed);
+    msg!("Phase 3 complete");
+    msg!("Next: Phase 4 - create_commitment for LP note + rewards note");
+
+    Ok(())
+}
@@ -0,0 +1,17 @@
+//! Liquidity mining emissions
+//!
+//! - Create emissions schedule: Fund a rewards stream for an AMM or perps pool
+//! - Register reward checkpoint: Record the accumulator an existing LP note earns from
+//! - Claim rewards (multi-phase): Prove LP note ownership, receive a shielded rewards note
+
+mod create_emissions_schedule;
+mod register_reward_checkpoint;
+
+// Claim rewards (multi-phase)
+mod create_pending_with_proof_claim_rewards;
+mod execute_claim_rewards;
+
+pub use create_emissions_schedule::*;
+pub use register_reward_checkpoint::*;
+pub use create_pending_with_proof_claim_rewards::*;
+pub use execute_claim_rewards::*;
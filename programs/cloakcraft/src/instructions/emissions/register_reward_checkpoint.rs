@@ -0,0 +1,111 @@
+//! Register a reward checkpoint for an existing LP note
+//!
+//! Claims read the LP note's `entry_reward_per_lp` from its checkpoint, so a
+//! note earns emissions only from the accumulator value recorded here. The
+//! accumulator is advanced to now first, and the note must already exist in
+//! the LP pool, so a checkpoint can't be registered ahead of time for a note
+//! that is only minted later. Claims roll the checkpoint forward to the
+//! re-created LP note; notes minted any other way (add liquidity, transfers,
+//! conversions) register here.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::instructions::generic::LightVerifyCommitmentParams;
+use crate::state::{EmissionsSchedule, Pool, RewardCheckpoint};
+
+use super::load_source_pool;
+
+#[derive(Accounts)]
+#[instruction(lp_commitment: [u8; 32])]
+pub struct RegisterRewardCheckpoint<'info> {
+    /// Emissions schedule (accumulator advanced here)
+    #[account(
+        mut,
+        seeds = [seeds::EMISSIONS, emissions_schedule.source_pool.as_ref()],
+        bump = emissions_schedule.bump,
+    )]
+    pub emissions_schedule: Box<Account<'info, EmissionsSchedule>>,
+
+    /// AMM or perps pool the schedule rewards (read for LP supply)
+    /// CHECK: Must match schedule, deserialized in handler
+    #[account(
+        constraint = source_pool.key() == emissions_schedule.source_pool @ CloakCraftError::EmissionsPoolMismatch,
+    )]
+    pub source_pool: UncheckedAccount<'info>,
+
+    /// LP token pool the note belongs to
+    #[account(
+        seeds = [seeds::POOL, lp_pool.token_mint.as_ref()],
+        bump = lp_pool.bump,
+        constraint = lp_pool.token_mint == emissions_schedule.lp_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub lp_pool: Box<Account<'info, Pool>>,
+
+    /// Reward checkpoint for the note (created here)
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RewardCheckpoint::INIT_SPACE,
+        seeds = [seeds::REWARD_CHECKPOINT, emissions_schedule.key().as_ref(), lp_commitment.as_ref()],
+        bump,
+    )]
+    pub reward_checkpoint: Box<Account<'info, RewardCheckpoint>>,
+
+    /// Pays for the checkpoint (refunded when a claim closes it)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    // Light Protocol accounts via remaining_accounts (~8 accounts)
+}
+
+/// Register a checkpoint, at the current accumulator, for an existing LP note
+pub fn register_reward_checkpoint<'info>(
+    ctx: Context<'_, '_, '_, 'info, RegisterRewardCheckpoint<'info>>,
+    lp_commitment: [u8; 32],
+    light_params: LightVerifyCommitmentParams,
+) -> Result<()> {
+    let lp_pool = &ctx.accounts.lp_pool;
+
+    // SECURITY: The note's value is private, so any pinned threshold applies
+    if lp_pool.requires_pinned_inclusion(u64::MAX) {
+        require!(
+            !light_params.commitment_merkle_context.prove_by_index,
+            CloakCraftError::PinnedInclusionRequired
+        );
+    }
+
+    // The note must exist before it can earn
+    crate::light_cpi::verify_commitment_inclusion(
+        &ctx.accounts.payer.to_account_info(),
+        ctx.remaining_accounts,
+        light_params.commitment_account_hash,
+        light_params.commitment_merkle_context,
+        light_params.commitment_inclusion_proof,
+        light_params.commitment_address_tree_info,
+        lp_commitment,
+        lp_pool.key(),
+        lp_pool.address_tree,
+        light_params.commitment_data_hash,
+    )?;
+
+    let source = load_source_pool(&ctx.accounts.source_pool, ctx.accounts.emissions_schedule.pool_kind)?;
+    let schedule = &mut ctx.accounts.emissions_schedule;
+    schedule.accrue(Clock::get()?.unix_timestamp, source.lp_supply)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    let checkpoint = &mut ctx.accounts.reward_checkpoint;
+    checkpoint.emissions_schedule = schedule.key();
+    checkpoint.lp_commitment = lp_commitment;
+    checkpoint.reward_per_lp = schedule.reward_per_lp;
+    checkpoint.payer = ctx.accounts.payer.key();
+    checkpoint.bump = ctx.bumps.reward_checkpoint;
+
+    msg!("Reward checkpoint registered at {}", checkpoint.reward_per_lp);
+
+    Ok(())
+}
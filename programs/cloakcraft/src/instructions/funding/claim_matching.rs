@@ -0,0 +1,77 @@
+//! Claim a project's matching allocation
+//!
+//! Permissionless: pays the project's matching amount from the round vault
+//! to the project's registered recipient token account.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{MatchingRound, RoundStatus};
+
+#[derive(Accounts)]
+#[instruction(round_id: [u8; 32], project_index: u8)]
+pub struct ClaimMatching<'info> {
+    /// Matching round
+    #[account(
+        mut,
+        seeds = [seeds::MATCHING_ROUND, round_id.as_ref()],
+        bump = matching_round.bump,
+        constraint = matching_round.status == RoundStatus::Tallied @ CloakCraftError::RoundNotTallied,
+        constraint = project_index < matching_round.num_projects @ CloakCraftError::InvalidProjectIndex,
+        constraint = !matching_round.is_claimed(project_index) @ CloakCraftError::MatchingAlreadyClaimed,
+    )]
+    pub matching_round: Box<Account<'info, MatchingRound>>,
+
+    /// Matching vault
+    #[account(
+        mut,
+        seeds = [seeds::MATCHING_VAULT, matching_round.key().as_ref()],
+        bump = matching_round.vault_bump,
+    )]
+    pub matching_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Project recipient token account
+    #[account(
+        mut,
+        constraint = recipient.key() == matching_round.project_recipients[project_index as usize] @ CloakCraftError::ProjectRecipientMismatch,
+    )]
+    pub recipient: Box<Account<'info, TokenAccount>>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn claim_matching(
+    ctx: Context<ClaimMatching>,
+    round_id: [u8; 32],
+    project_index: u8,
+) -> Result<()> {
+    let round = &mut ctx.accounts.matching_round;
+    let amount = round.matching_amounts[project_index as usize];
+
+    // Mark claimed before transfer
+    round.claimed_mask |= 1 << project_index;
+
+    if amount > 0 {
+        let signer_seeds: &[&[&[u8]]] = &[&[seeds::MATCHING_ROUND, round_id.as_ref(), &[round.bump]]];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.matching_vault.to_account_info(),
+                    to: ctx.accounts.recipient.to_account_info(),
+                    authority: round.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+    }
+
+    msg!("Matching claimed for project {}: {}", project_index, amount);
+
+    Ok(())
+}
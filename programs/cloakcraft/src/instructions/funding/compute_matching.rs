@@ -0,0 +1,89 @@
+//! Compute quadratic funding matching
+//!
+//! Called after the round ends. The caller submits the decryption key and the
+//! per-project sqrt sums; these are checked against the encrypted tally with
+//! the same ElGamal verification used by `decrypt_tally`. The matching pool
+//! is then allocated proportionally to `sqrt_sum^2` and becomes claimable.
+//!
+//! Rounds created with a threshold committee only accept results submitted by
+//! a committee member; otherwise the decryption key itself proves authorization.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::instructions::voting::{verify_decryption, verify_decryption_key};
+use crate::state::{
+    MatchingRound, RoundStatus, ThresholdCommittee, ELGAMAL_CIPHERTEXT_SIZE, MAX_ROUND_PROJECTS,
+};
+
+#[derive(Accounts)]
+#[instruction(round_id: [u8; 32])]
+pub struct ComputeMatching<'info> {
+    /// Matching round to tally
+    #[account(
+        mut,
+        seeds = [seeds::MATCHING_ROUND, round_id.as_ref()],
+        bump = matching_round.bump,
+        constraint = matching_round.status == RoundStatus::Active @ CloakCraftError::RoundAlreadyTallied,
+    )]
+    pub matching_round: Box<Account<'info, MatchingRound>>,
+
+    /// Threshold committee (required when the round has one)
+    #[account(
+        constraint = committee.key() == matching_round.committee @ CloakCraftError::Unauthorized,
+    )]
+    pub committee: Option<Box<Account<'info, ThresholdCommittee>>>,
+
+    /// Caller (committee member when the round has a committee)
+    pub caller: Signer<'info>,
+}
+
+pub fn compute_matching(
+    ctx: Context<ComputeMatching>,
+    _round_id: [u8; 32],
+    decryption_key: [u8; 32],
+    sqrt_sums: Vec<u64>,
+) -> Result<()> {
+    let round = &mut ctx.accounts.matching_round;
+    let clock = Clock::get()?;
+
+    require!(clock.unix_timestamp >= round.end_time, CloakCraftError::RoundNotEnded);
+
+    // Committee rounds: only members may submit the decrypted tally
+    if round.committee != Pubkey::default() {
+        let committee = ctx.accounts.committee.as_ref()
+            .ok_or(CloakCraftError::Unauthorized)?;
+        require!(
+            committee.is_member(&ctx.accounts.caller.key()),
+            CloakCraftError::Unauthorized
+        );
+    }
+
+    if !verify_decryption_key(&decryption_key, &round.tally_pubkey) {
+        return Err(CloakCraftError::InvalidDecryptionKey.into());
+    }
+    require!(
+        sqrt_sums.len() == round.num_projects as usize,
+        CloakCraftError::InvalidOutcomeValue
+    );
+    if !verify_decryption(&round.encrypted_tally, &decryption_key, &sqrt_sums, round.num_projects) {
+        return Err(CloakCraftError::InvalidDecryptionKey.into());
+    }
+
+    round.sqrt_sums = [0; MAX_ROUND_PROJECTS];
+    round.sqrt_sums[..sqrt_sums.len()].copy_from_slice(&sqrt_sums);
+    round.matching_amounts = round.compute_matching()
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    // Clear encrypted tally (mark as decrypted)
+    round.encrypted_tally = [[0u8; ELGAMAL_CIPHERTEXT_SIZE]; MAX_ROUND_PROJECTS];
+    round.status = RoundStatus::Tallied;
+
+    msg!("Matching computed for {} donations", round.donation_count);
+    for i in 0..round.num_projects as usize {
+        msg!("  Project {}: sqrt sum {}, match {}", i, round.sqrt_sums[i], round.matching_amounts[i]);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+//! Create a quadratic funding matching round
+//!
+//! The authority funds the matching pool up front into a round-owned vault.
+//! If a threshold committee is passed, only its members may later submit the
+//! decrypted tally, and donations must be encrypted to its threshold key.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{
+    MatchingRound, MatchingRoundConfigInput, RoundStatus, ThresholdCommittee,
+    ELGAMAL_CIPHERTEXT_SIZE, MAX_ROUND_PROJECTS,
+};
+
+#[derive(Accounts)]
+#[instruction(round_id: [u8; 32])]
+pub struct CreateMatchingRound<'info> {
+    /// Matching round to create
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MatchingRound::INIT_SPACE,
+        seeds = [seeds::MATCHING_ROUND, round_id.as_ref()],
+        bump,
+    )]
+    pub matching_round: Box<Account<'info, MatchingRound>>,
+
+    /// Donation and matching token mint
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// Matching vault (PDA owned by the round)
+    #[account(
+        init,
+        payer = authority,
+        seeds = [seeds::MATCHING_VAULT, matching_round.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = matching_round,
+    )]
+    pub matching_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Authority's token account (funds the matching pool)
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == token_mint.key() @ CloakCraftError::TokenMintMismatch,
+    )]
+    pub funder_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Threshold committee that decrypts the tally (optional)
+    #[account(
+        constraint = committee.is_active @ CloakCraftError::InvalidRoundConfig,
+    )]
+    pub committee: Option<Box<Account<'info, ThresholdCommittee>>>,
+
+    /// Round authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn create_matching_round(
+    ctx: Context<CreateMatchingRound>,
+    round_id: [u8; 32],
+    config: MatchingRoundConfigInput,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let num_projects = config.project_recipients.len();
+
+    require!(
+        num_projects > 0 && num_projects <= MAX_ROUND_PROJECTS,
+        CloakCraftError::InvalidRoundConfig
+    );
+    require!(
+        config.matching_pool > 0
+            && config.start_time >= clock.unix_timestamp
+            && config.end_time > config.start_time
+            && config.tally_pubkey.iter().any(|&b| b != 0),
+        CloakCraftError::InvalidRoundConfig
+    );
+
+    let committee = match &ctx.accounts.committee {
+        Some(committee) => {
+            require!(
+                committee.threshold_pubkey == config.tally_pubkey,
+                CloakCraftError::InvalidRoundConfig
+            );
+            committee.key()
+        }
+        None => Pubkey::default(),
+    };
+
+    let round = &mut ctx.accounts.matching_round;
+    round.round_id = round_id;
+    round.authority = ctx.accounts.authority.key();
+    round.token_mint = ctx.accounts.token_mint.key();
+    round.matching_vault = ctx.accounts.matching_vault.key();
+    round.matching_pool = config.matching_pool;
+    round.num_projects = num_projects as u8;
+    round.project_recipients = [Pubkey::default(); MAX_ROUND_PROJECTS];
+    round.project_recipients[..num_projects].copy_from_slice(&config.project_recipients);
+    round.tally_pubkey = config.tally_pubkey;
+    round.committee = committee;
+    round.start_time = config.start_time;
+    round.end_time = config.end_time;
+    round.encrypted_tally = [[0u8; ELGAMAL_CIPHERTEXT_SIZE]; MAX_ROUND_PROJECTS];
+    round.sqrt_sums = [0; MAX_ROUND_PROJECTS];
+    round.matching_amounts = [0; MAX_ROUND_PROJECTS];
+    round.claimed_mask = 0;
+    round.donation_count = 0;
+    round.status = RoundStatus::Active;
+    round.bump = ctx.bumps.matching_round;
+    round.vault_bump = ctx.bumps.matching_vault;
+
+    // Fund the matching pool up front
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.funder_token_account.to_account_info(),
+                to: ctx.accounts.matching_vault.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+        ),
+        config.matching_pool,
+    )?;
+
+    msg!("Matching round created: {} projects", num_projects);
+    msg!("  Matching pool: {}", config.matching_pool);
+    msg!("  Window: {} - {}", config.start_time, config.end_time);
+
+    Ok(())
+}
@@ -0,0 +1,222 @@
+//! Create Pending with Proof - Donate (Phase 0)
+//!
+//! Spends a shielded note and creates a donation note owned by one of the
+//! round's projects plus a change note. The circuit also outputs one ElGamal
+//! ciphertext per project encrypting `sqrt(donation)` for the chosen project
+//! and 0 for every other, so the recipient and amount stay private while the
+//! round tally accumulates the quadratic funding inputs. The proof binds
+//! those ciphertexts through `contributions_hash` (see
+//! `compute_contributions_hash`), so the relayer can't swap in its own in
+//! execute_donate.
+//!
+//! Flow:
+//! Phase 0 (this): Verify ZK proof + Create PendingOperation
+//! Phase 1: verify_commitment_exists for input note
+//! Phase 2: create_nullifier_and_pending for input note
+//! Phase 3: execute_donate - Add encrypted contribution to round tally
+//! Phase 4: create_commitment for donation note + change note
+//! Final: close_pending_operation
+
+use anchor_lang::prelude::*;
+use light_hasher::{Hasher, Poseidon};
+
+use crate::constants::{circuits, operation_types, seeds};
+use crate::errors::CloakCraftError;
+use crate::helpers::field::{pubkey_to_field, assert_canonical, u64_to_field};
+use crate::helpers::verify_groth16_proof_metered;
+use crate::state::{
+    MatchingRound, PendingOperation, Pool, VerificationKey, ProtocolConfig, ProgramVersion, CircuitStats,
+    ELGAMAL_CIPHERTEXT_SIZE, MAX_ROUND_PROJECTS, OperationCredit,
+};
+
+/// Poseidon domain of donation contribution hashes (matches the donate circuit)
+pub const CONTRIBUTIONS_DOMAIN: u64 = 0x22;
+
+/// Hash of a donation's per-project ciphertexts
+///
+/// Chained over MAX_ROUND_PROJECTS ciphertexts, zero-padded past the given
+/// ones: acc = Poseidon(CONTRIBUTIONS_DOMAIN, acc, c1, c2), starting from 0.
+/// Both ciphertext halves must be canonical field elements.
+pub fn compute_contributions_hash(ciphertexts: &[[u8; ELGAMAL_CIPHERTEXT_SIZE]]) -> Result<[u8; 32]> {
+    require!(ciphertexts.len() <= MAX_ROUND_PROJECTS, CloakCraftError::InvalidPublicInputs);
+
+    let zero = [0u8; ELGAMAL_CIPHERTEXT_SIZE];
+    let mut acc = [0u8; 32];
+    for i in 0..MAX_ROUND_PROJECTS {
+        let ciphertext = ciphertexts.get(i).unwrap_or(&zero);
+        acc = Poseidon::hashv(&[
+            &u64_to_field(CONTRIBUTIONS_DOMAIN),
+            &acc,
+            &ciphertext[..32],
+            &ciphertext[32..],
+        ])
+        .map_err(|_| CloakCraftError::ContributionsHashMismatch)?;
+    }
+    Ok(acc)
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32], round_id: [u8; 32])]
+pub struct CreatePendingWithProofDonate<'info> {
+    /// Matching round being donated to
+    #[account(
+        seeds = [seeds::MATCHING_ROUND, round_id.as_ref()],
+        bump = matching_round.bump,
+    )]
+    pub matching_round: Box<Account<'info, MatchingRound>>,
+
+    /// Donation token pool (input, donation and change notes)
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.token_mint == matching_round.token_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Verification key for the donate circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, circuits::DONATE.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Pending operation PDA (created in this instruction)
+    #[account(
+        init,
+        payer = relayer,
+        space = PendingOperation::SPACE,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (pays for PDA creation)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Phase 0: Verify ZK proof and create PendingOperation for a donation
+#[allow(clippy::too_many_arguments)]
+pub fn create_pending_with_proof_donate<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofDonate<'info>>,
+    operation_id: [u8; 32],
+    _round_id: [u8; 32],
+    proof: Vec<u8>,
+    merkle_root: [u8; 32],
+    input_commitment: [u8; 32],
+    nullifier: [u8; 32],
+    donation_commitment: [u8; 32],
+    change_commitment: [u8; 32],
+    contributions_hash: [u8; 32],
+    client_version: u32,
+) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[input_commitment, nullifier, donation_commitment, change_commitment, contributions_hash])?;
+
+    let round = &ctx.accounts.matching_round;
+    let clock = Clock::get()?;
+
+    msg!("=== Phase 0: Verify Proof + Create Pending (Donate) ===");
+
+    // 1. Round must be accepting donations
+    require!(round.is_active(clock.unix_timestamp), CloakCraftError::RoundNotActive);
+
+    // 2. Verify ZK proof
+    let public_inputs = vec![
+        merkle_root,
+        nullifier,
+        pubkey_to_field(&round.key()),
+        donation_commitment,
+        change_commitment,
+        round.tally_pubkey,
+        contributions_hash,
+    ];
+
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "Donate",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
+    msg!("✅ ZK proof verified");
+
+    // 3. Initialize pending operation PDA with binding fields
+    let pending_op = &mut ctx.accounts.pending_operation;
+    pending_op.bump = ctx.bumps.pending_operation;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
+    pending_op.relayer = ctx.accounts.relayer.key();
+    pending_op.operation_type = operation_types::DONATE;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
+    pending_op.created_at = clock.unix_timestamp;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
+
+    // SECURITY: Store binding fields from ZK proof
+    pending_op.num_inputs = 1;
+    pending_op.input_commitments[0] = input_commitment;
+    pending_op.expected_nullifiers[0] = nullifier;
+    pending_op.input_pools[0] = ctx.accounts.pool.key().to_bytes();
+    // Unused input slot stores the matching round (binds Phase 3)
+    pending_op.input_pools[1] = round.key().to_bytes();
+    pending_op.inputs_verified_mask = 0;
+    pending_op.proof_verified = true;
+
+    // Ciphertexts execute_donate may add to the tally
+    pending_op.call_hash = contributions_hash;
+
+    // Store output commitments (donation note + change note)
+    pending_op.num_commitments = 2;
+    pending_op.pools[0] = ctx.accounts.pool.key().to_bytes();
+    pending_op.commitments[0] = donation_commitment;
+    pending_op.output_amounts[0] = 1; // Donation placeholder (non-zero = not dummy, actual amount in encrypted note)
+    pending_op.pools[1] = ctx.accounts.pool.key().to_bytes();
+    pending_op.commitments[1] = change_commitment;
+    pending_op.output_amounts[1] = 1; // Change placeholder (non-zero = not dummy)
+
+    pending_op.nullifier_completed_mask = 0;
+    pending_op.completed_mask = 0;
+
+    msg!("Phase 0 complete: ZK proof verified, pending operation created");
+    msg!("Next: Phase 1 - verify_commitment_exists");
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+//! Execute Donate (Phase 3)
+//!
+//! Adds the donation's encrypted sqrt contribution to the round tally.
+//! Called after the input note nullifier is created (Phase 2).
+//!
+//! One ciphertext per project is added homomorphically; only one encrypts a
+//! non-zero value, but the program doesn't know which (privacy preserved).
+//! The ciphertexts must hash to the `contributions_hash` bound in Phase 0.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::instructions::funding::compute_contributions_hash;
+use crate::instructions::voting::{add_elgamal_ciphertexts, EncryptedContributions};
+use crate::state::{MatchingRound, PendingOperation, OperationKind};
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32], round_id: [u8; 32])]
+pub struct ExecuteDonate<'info> {
+    /// Matching round (mutable for tally update)
+    #[account(
+        mut,
+        seeds = [seeds::MATCHING_ROUND, round_id.as_ref()],
+        bump = matching_round.bump,
+        constraint = pending_operation.input_pools[1] == matching_round.key().to_bytes() @ CloakCraftError::InvalidRoundConfig,
+    )]
+    pub matching_round: Box<Account<'info, MatchingRound>>,
+
+    /// Pending operation PDA (from Phase 0)
+    #[account(
+        mut,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump = pending_operation.bump,
+        constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (must match pending operation)
+    #[account(
+        constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
+    )]
+    pub relayer: Signer<'info>,
+}
+
+/// Phase 3: Add encrypted contribution to the round tally
+pub fn execute_donate(
+    ctx: Context<ExecuteDonate>,
+    _operation_id: [u8; 32],
+    _round_id: [u8; 32],
+    encrypted_contributions: EncryptedContributions,
+) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::Donate)?;
+
+    let round = &mut ctx.accounts.matching_round;
+    let clock = Clock::get()?;
+
+    msg!("=== Phase 3: Execute Donate ===");
+
+    require!(round.is_active(clock.unix_timestamp), CloakCraftError::RoundNotActive);
+    require!(
+        encrypted_contributions.ciphertexts.len() == round.num_projects as usize,
+        CloakCraftError::InvalidPublicInputs
+    );
+    require!(
+        compute_contributions_hash(&encrypted_contributions.ciphertexts)? == ctx.accounts.pending_operation.call_hash,
+        CloakCraftError::ContributionsHashMismatch
+    );
+
+    // Homomorphic addition: tally_p += enc(sqrt(donation) if p is the recipient else 0)
+    for (tally, ciphertext) in round.encrypted_tally.iter_mut().zip(&encrypted_contributions.ciphertexts) {
+        *tally = add_elgamal_ciphertexts(tally, ciphertext)?;
+    }
+
+    round.donation_count = round.donation_count.saturating_add(1);
+
+    msg!("✅ Encrypted tally updated for {} projects", round.num_projects);
+    msg!("  Donation count: {}", round.donation_count);
+    msg!("Phase 3 complete");
+    msg!("Next: Phase 4 - create_commitment for donation + change notes");
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+//! Quadratic funding (public goods) rounds
+//!
+//! - Create matching round: Fund a matching pool for a set of projects
+//! - Donate (multi-phase): Send a shielded note to a project, add encrypted sqrt contribution to the tally
+//! - Compute matching: Verify the decrypted tally, allocate the pool quadratically
+//! - Claim matching: Pay a project's allocation
+
+mod create_matching_round;
+mod compute_matching;
+mod claim_matching;
+
+// Donate (multi-phase)
+mod create_pending_with_proof_donate;
+mod execute_donate;
+
+pub use create_matching_round::*;
+pub use compute_matching::*;
+pub use claim_matching::*;
+pub use create_pending_with_proof_donate::*;
+pub use execute_donate::*;
@@ -0,0 +1,56 @@
+//! Bond relayer stake
+//!
+//! Creates the relayer's RelayerStake PDA on first use and moves `amount`
+//! lamports into it. The stake is slashed when an operation the relayer
+//! started is left stranded (see `state::relayer_stake`).
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+use crate::state::RelayerStake;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct BondRelayerStake<'info> {
+    /// Relayer stake
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = 8 + RelayerStake::INIT_SPACE,
+        seeds = [seeds::RELAYER_STAKE, relayer.key().as_ref()],
+        bump,
+    )]
+    pub relayer_stake: Account<'info, RelayerStake>,
+
+    /// Relayer (funds the stake)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn bond_relayer_stake(ctx: Context<BondRelayerStake>, amount: u64) -> Result<()> {
+    require!(amount > 0, CloakCraftError::InvalidAmount);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.relayer.to_account_info(),
+                to: ctx.accounts.relayer_stake.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let stake = &mut ctx.accounts.relayer_stake;
+    stake.relayer = ctx.accounts.relayer.key();
+    stake.bonded = stake.bonded.checked_add(amount).ok_or(CloakCraftError::AmountOverflow)?;
+    stake.bump = ctx.bumps.relayer_stake;
+
+    msg!("Relayer {} bonded {} lamports (total {})", stake.relayer, amount, stake.bonded);
+
+    Ok(())
+}
@@ -1,35 +1,183 @@
 //! Close pending operation and reclaim rent
 //!
 //! This instruction closes a pending operation PDA and returns the rent to the relayer.
+//! If Phase 3 bound a rent refund recipient (the user's proven unshield
+//! recipient), that account first receives its share (`rent_refund_bps`,
+//! resolved from ProtocolConfig in Phase 0).
 //! Can be called after all nullifiers and commitments are created, or after expiry.
+//! A stranded operation (see `PendingOperation::is_rescuable`) can't be closed
+//! until its commitments are completed.
+//!
+//! Closing a completed consolidation with its pool and the dust sweep ledger
+//! pays the relayer the dust sweep bounty if the consolidation swept dust
+//! (see `DustSweepLedger`). The output amount only counts once the stored
+//! regeneration data recomputes to the created commitment, and the relayer's
+//! stake must be passed to meter its per-epoch bounty cap.
 
 use anchor_lang::prelude::*;
 
-use crate::state::PendingOperation;
+use crate::state::{PendingOperation, Pool, DustSweepLedger, DustSweepBountyPaid, RelayerStake};
+use crate::constants::{operation_types, seeds};
 use crate::errors::CloakCraftError;
+use crate::helpers::commitment::note_commitment;
 
 #[derive(Accounts)]
 #[instruction(operation_id: [u8; 32])]
 pub struct ClosePendingOperation<'info> {
-    /// Pending operation PDA
+    /// Pending operation PDA (closed in the handler)
     #[account(
         mut,
-        close = relayer,
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
         constraint = pending_operation.is_complete() || pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationNotComplete,
+        constraint = !pending_operation.is_rescuable(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationStranded,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
     /// Relayer (receives rent back)
     #[account(mut)]
     pub relayer: Signer<'info>,
+
+    /// Rent refund recipient bound in Phase 3 (required if one was bound)
+    /// CHECK: Address checked against pending_operation.rent_refund_recipient
+    #[account(mut)]
+    pub rent_refund_recipient: Option<UncheckedAccount<'info>>,
+
+    /// Consolidation's pool (optional, with the ledger for the dust sweep bounty)
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Option<Box<Account<'info, Pool>>>,
+
+    /// Dust sweep ledger (optional, pays the dust sweep bounty)
+    #[account(
+        mut,
+        seeds = [seeds::DUST_SWEEP_LEDGER],
+        bump = dust_sweep_ledger.bump,
+    )]
+    pub dust_sweep_ledger: Option<Box<Account<'info, DustSweepLedger>>>,
+
+    /// Relayer's stake (optional, required for the dust sweep bounty)
+    #[account(
+        mut,
+        seeds = [seeds::RELAYER_STAKE, relayer.key().as_ref()],
+        bump = relayer_stake.bump,
+    )]
+    pub relayer_stake: Option<Box<Account<'info, RelayerStake>>>,
 }
 
 pub fn close_pending_operation(
-    _ctx: Context<ClosePendingOperation>,
-    _operation_id: [u8; 32],
+    ctx: Context<ClosePendingOperation>,
+    operation_id: [u8; 32],
 ) -> Result<()> {
-    // Account is closed automatically via close constraint
+    if let (Some(pool), Some(ledger)) = (
+        ctx.accounts.pool.as_ref(),
+        ctx.accounts.dust_sweep_ledger.as_mut(),
+    ) {
+        pay_dust_sweep_bounty(
+            &ctx.accounts.pending_operation,
+            pool,
+            ledger,
+            ctx.accounts.relayer_stake.as_deref_mut().map(|stake| &mut **stake),
+            &ctx.accounts.relayer.to_account_info(),
+            operation_id,
+        )?;
+    }
+
+    let pending_info = ctx.accounts.pending_operation.to_account_info();
+    let refund = ctx.accounts.pending_operation.rent_refund_amount(pending_info.lamports());
+
+    if ctx.accounts.pending_operation.rent_refund_recipient != Pubkey::default() {
+        let recipient = ctx.accounts.rent_refund_recipient
+            .as_ref()
+            .ok_or(CloakCraftError::InvalidRentRefundRecipient)?;
+        require_keys_eq!(
+            recipient.key(),
+            ctx.accounts.pending_operation.rent_refund_recipient,
+            CloakCraftError::InvalidRentRefundRecipient
+        );
+
+        if refund > 0 {
+            // Program-owned PDA: move lamports directly
+            **pending_info.try_borrow_mut_lamports()? -= refund;
+            **recipient.to_account_info().try_borrow_mut_lamports()? += refund;
+            msg!("Rent refund: {} lamports to {}", refund, recipient.key());
+        }
+    }
+
+    // Remaining rent to the relayer
+    ctx.accounts.pending_operation.close(ctx.accounts.relayer.to_account_info())
+}
+
+/// Pay the relayer of a completed dust-sweeping consolidation
+///
+/// Operations that don't qualify, and relayers without stake or past their
+/// per-epoch cap, close normally without a bounty.
+fn pay_dust_sweep_bounty<'info>(
+    pending_op: &PendingOperation,
+    pool: &Account<'info, Pool>,
+    ledger: &mut Account<'info, DustSweepLedger>,
+    relayer_stake: Option<&mut RelayerStake>,
+    relayer: &AccountInfo<'info>,
+    operation_id: [u8; 32],
+) -> Result<()> {
+    if pending_op.operation_type != operation_types::CONSOLIDATE || !pending_op.is_complete() {
+        return Ok(());
+    }
+    require!(
+        pool.key().to_bytes() == pending_op.pools[0],
+        CloakCraftError::PoolMismatch
+    );
+    require_keys_eq!(relayer.key(), pending_op.relayer, CloakCraftError::Unauthorized);
+
+    let output_amount = pending_op.output_amounts[0];
+    if !DustSweepLedger::is_dust_sweep(pool.min_note_amount, pending_op.num_inputs, output_amount) {
+        return Ok(());
+    }
+
+    // The proof binds only the commitment: the claimed amount must open it
+    let commitment = note_commitment(
+        &pending_op.output_recipients[0],
+        &pool.token_mint,
+        output_amount,
+        &pending_op.output_randomness[0],
+    )?;
+    if commitment != pending_op.commitments[0] {
+        msg!("Dust sweep: output data does not open the commitment, no bounty");
+        return Ok(());
+    }
+
+    let ledger_info = ledger.to_account_info();
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(ledger_info.data_len());
+    let bounty = ledger.payable_bounty(ledger_info.lamports(), rent_exempt_minimum);
+    if bounty == 0 {
+        msg!("Dust sweep: ledger empty or bounty paused");
+        return Ok(());
+    }
+
+    let Some(stake) = relayer_stake else {
+        msg!("Dust sweep: relayer stake not passed, no bounty");
+        return Ok(());
+    };
+    if !stake.claim_dust_sweep(Clock::get()?.unix_timestamp) {
+        msg!("Dust sweep: relayer unbonded or over its epoch cap, no bounty");
+        return Ok(());
+    }
+
+    // Program-owned PDA: move lamports directly
+    **ledger_info.try_borrow_mut_lamports()? -= bounty;
+    **relayer.try_borrow_mut_lamports()? += bounty;
+    ledger.record_payment(bounty);
+
+    emit!(DustSweepBountyPaid {
+        operation_id,
+        relayer: relayer.key(),
+        pool: pool.key(),
+        num_inputs: pending_op.num_inputs,
+        bounty_lamports: bounty,
+    });
+    msg!("Dust sweep bounty: {} lamports to {}", bounty, relayer.key());
+
     Ok(())
 }
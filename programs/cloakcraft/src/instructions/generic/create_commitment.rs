@@ -2,16 +2,28 @@
 //!
 //! Creates ONE commitment for a pending operation via Light Protocol.
 //! Call this instruction M times for M commitments.
+//!
+//! Optionally writes a `PaymentReceipt` binding an invoice reference id to
+//! the commitment (see `state::payment_receipt`), and appends it to the
+//! pool's root registry once initialized (see `state::root_registry`).
+//!
+//! Once a transfer or consolidation is stranded (see
+//! `PendingOperation::is_rescuable`), anyone may create its remaining
+//! commitments. Creating the last one slashes the original relayer's stake
+//! when it is passed: the rescuer gets a bounty and the treasury the rest
+//! (see `state::relayer_stake`).
 
 use anchor_lang::prelude::*;
 
 use crate::state::{
-    Pool, PoolCommitmentCounter, PendingOperation,
-    LightValidityProof, LightAddressTreeInfo,
+    Pool, PoolCommitmentCounter, PoolStats, RootRegistry, PendingOperation,
+    LightValidityProof, LightAddressTreeInfo, RelayerStake, RelayerSlashed, RELAYER_SLASH_LAMPORTS,
+    TreeRegistry, ProtocolConfig, rescue_bounty,
 };
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
-use crate::light_cpi::{create_commitment_account, vec_to_fixed_note};
+use crate::light_cpi::{create_commitment_account_with_overflow, create_payment_receipt_account, split_encrypted_note};
+use crate::crypto::note_encryption::validate_encrypted_note;
 
 /// Parameters for Light Protocol commitment creation
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -24,6 +36,20 @@ pub struct LightCreateCommitmentParams {
     pub output_tree_index: u8,
 }
 
+/// Parameters for an optional payment receipt
+///
+/// The receipt is written to the same output tree as the commitment; its
+/// address needs its own non-inclusion proof.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PaymentReceiptParams {
+    /// Invoice reference id from the `PaymentRequest`
+    pub reference_id: [u8; 32],
+    /// Validity proof for the receipt address
+    pub proof: LightValidityProof,
+    /// Address tree info for the receipt address
+    pub address_tree_info: LightAddressTreeInfo,
+}
+
 #[derive(Accounts)]
 #[instruction(operation_id: [u8; 32], commitment_index: u8)]
 pub struct CreateCommitment<'info> {
@@ -48,20 +74,69 @@ pub struct CreateCommitment<'info> {
         mut,
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
-        constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
+        constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp)
+            || pending_operation.is_rescuable(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
         // SECURITY FIX: Check expected nullifiers (from append pattern), not legacy nullifiers
         // This ensures ALL nullifiers are created before commitments in multi-input operations
         constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
-    /// Relayer (must be same as operation creator)
+    /// Relayer (must be same as operation creator, unless the operation is stranded)
     #[account(
         mut,
-        constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
+        constraint = relayer.key() == pending_operation.relayer
+            || pending_operation.is_rescuable(Clock::get()?.unix_timestamp) @ CloakCraftError::InvalidRelayer,
     )]
     pub relayer: Signer<'info>,
 
+    /// Pool statistics (optional, updated when passed)
+    #[account(
+        mut,
+        seeds = [seeds::POOL_STATS, pool.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Option<Box<Account<'info, PoolStats>>>,
+
+    /// Root registry (the commitment is appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, pool.key().as_ref()],
+        bump,
+    )]
+    pub root_registry: UncheckedAccount<'info>,
+
+    /// Stake of the operation's relayer (optional, slashed when rescuing a stranded operation)
+    #[account(
+        mut,
+        seeds = [seeds::RELAYER_STAKE, pending_operation.relayer.as_ref()],
+        bump = relayer_stake.bump,
+    )]
+    pub relayer_stake: Option<Box<Account<'info, RelayerStake>>>,
+
+    /// Protocol config (required with relayer_stake, names the treasury)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Option<Box<Account<'info, ProtocolConfig>>>,
+
+    /// Treasury (required with relayer_stake, receives the slash minus the rescuer's bounty)
+    /// CHECK: Address checked against protocol_config.treasury
+    #[account(mut)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+
+    /// Tree registry (once initialized, the output tree must be registered
+    /// and its writes are counted toward TreeNearCapacity)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: UncheckedAccount<'info>,
+
     // Light Protocol accounts via remaining_accounts
 }
 
@@ -71,6 +146,7 @@ pub struct CreateCommitment<'info> {
 /// This instruction creates the Light Protocol compressed account.
 ///
 /// IMPORTANT: All nullifiers must be created before any commitments.
+#[allow(clippy::too_many_arguments)]
 pub fn create_commitment<'info>(
     ctx: Context<'_, '_, '_, 'info, CreateCommitment<'info>>,
     _operation_id: [u8; 32],
@@ -78,10 +154,13 @@ pub fn create_commitment<'info>(
     stealth_ephemeral_pubkey: [u8; 64],
     encrypted_note: Vec<u8>,
     light_params: LightCreateCommitmentParams,
+    view_tag: Option<[u8; 8]>,
+    payment_receipt: Option<PaymentReceiptParams>,
 ) -> Result<()> {
     let pool = &ctx.accounts.pool;
     let counter = &mut ctx.accounts.commitment_counter;
     let pending_op = &mut ctx.accounts.pending_operation;
+    let rescue = pending_op.is_expired(Clock::get()?.unix_timestamp);
 
     // Validate index
     require!(
@@ -107,7 +186,17 @@ pub fn create_commitment<'info>(
 
     // Skip zero commitments (no change needed)
     if commitment == [0u8; 32] {
+        require!(payment_receipt.is_none(), CloakCraftError::PaymentReceiptWithoutCommitment);
         pending_op.mark_completed(commitment_index);
+        if rescue {
+            slash_for_rescue(
+                pending_op,
+                ctx.accounts.relayer_stake.as_mut(),
+                ctx.accounts.protocol_config.as_deref(),
+                ctx.accounts.treasury.as_ref(),
+                &ctx.accounts.relayer,
+            )?;
+        }
         return Ok(());
     }
 
@@ -117,38 +206,151 @@ pub fn create_commitment<'info>(
     let output_amount = pending_op.output_amounts[commitment_index as usize];
     if output_amount == 0 {
         msg!("Skipping zero-amount dummy commitment at index {}", commitment_index);
+        require!(payment_receipt.is_none(), CloakCraftError::PaymentReceiptWithoutCommitment);
         pending_op.mark_completed(commitment_index);
+        if rescue {
+            slash_for_rescue(
+                pending_op,
+                ctx.accounts.relayer_stake.as_mut(),
+                ctx.accounts.protocol_config.as_deref(),
+                ctx.accounts.treasury.as_ref(),
+                &ctx.accounts.relayer,
+            )?;
+        }
         return Ok(());
     }
 
-    // Convert Vec to fixed-size array for Light Protocol
-    let (encrypted_note_fixed, note_len) = vec_to_fixed_note(&encrypted_note);
+    // Scanners must be able to parse every stored note, within the pool's size limit
+    validate_encrypted_note(&encrypted_note, pool)?;
+
+    // Scanners derive the note's key from the ephemeral stored in Phase 0
+    require!(
+        pending_op.stealth_ephemeral_matches(commitment_index, &stealth_ephemeral_pubkey),
+        CloakCraftError::StealthEphemeralMismatch
+    );
+
+    // Convert Vec to fixed-size array for Light Protocol (bytes past it overflow)
+    let (encrypted_note_fixed, note_len, note_overflow) = split_encrypted_note(&encrypted_note);
 
     // Allocate leaf index
-    let leaf_index = counter.next_leaf_index;
-    counter.next_leaf_index += 1;
-    counter.total_commitments += 1;
+    let leaf_index = counter.allocate();
 
     // Store leaf index in pending op (for reference)
     pending_op.leaf_indices[commitment_index as usize] = leaf_index;
 
+    let pool_trees = pool.active_trees(Clock::get()?.slot);
+
+    // Approved output tree only (the commitment and its receipt are both written to it)
+    let writes = 1 + payment_receipt.is_some() as u64;
+    TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &pool_trees.state_tree, writes)?;
+
     // Create commitment via Light Protocol
-    create_commitment_account(
+    create_commitment_account_with_overflow(
         &ctx.accounts.relayer.to_account_info(),
         ctx.remaining_accounts,
         light_params.proof,
         light_params.address_tree_info,
         light_params.output_tree_index,
         pool.key(),
+        pool_trees,
         commitment,
         leaf_index,
         stealth_ephemeral_pubkey,
         encrypted_note_fixed,
         note_len,
+        view_tag.unwrap_or_default(),
+        note_overflow,
     )?;
 
+    if let Some(receipt) = payment_receipt {
+        create_payment_receipt_account(
+            &ctx.accounts.relayer.to_account_info(),
+            ctx.remaining_accounts,
+            receipt.proof,
+            receipt.address_tree_info,
+            light_params.output_tree_index,
+            pool.key(),
+            pool_trees,
+            receipt.reference_id,
+            commitment,
+            leaf_index,
+        )?;
+        msg!("Payment receipt created for reference {:02x?}...", &receipt.reference_id[0..8]);
+    }
+
+    RootRegistry::append_to(&ctx.accounts.root_registry, commitment, Clock::get()?.slot)?;
+
     // Mark as completed
     pending_op.mark_completed(commitment_index);
+    if rescue {
+        slash_for_rescue(
+            pending_op,
+            ctx.accounts.relayer_stake.as_mut(),
+            ctx.accounts.protocol_config.as_deref(),
+            ctx.accounts.treasury.as_ref(),
+            &ctx.accounts.relayer,
+        )?;
+    }
+
+    if let Some(stats) = ctx.accounts.pool_stats.as_mut() {
+        if let Some(rolled) = stats.record_note_created(Clock::get()?.unix_timestamp) {
+            emit!(rolled);
+        }
+    }
+
+    Ok(())
+}
+
+/// Slash the relayer's stake when a stranded operation is completed
+///
+/// Only the last commitment slashes, and only when someone other than the
+/// original relayer completes it. The rescuer is paid `rescue_bounty` of the
+/// slash and the treasury the rest, so rescuing one's own operation from
+/// another key costs the relayer most of the slash.
+fn slash_for_rescue<'info>(
+    pending_op: &PendingOperation,
+    relayer_stake: Option<&mut Box<Account<'info, RelayerStake>>>,
+    protocol_config: Option<&Account<'info, ProtocolConfig>>,
+    treasury: Option<&UncheckedAccount<'info>>,
+    rescuer: &Signer<'info>,
+) -> Result<()> {
+    if !pending_op.all_commitments_created() || rescuer.key() == pending_op.relayer {
+        return Ok(());
+    }
+    let Some(stake) = relayer_stake else {
+        return Ok(());
+    };
+    let (Some(protocol_config), Some(treasury)) = (protocol_config, treasury) else {
+        return Err(CloakCraftError::InvalidTreasury.into());
+    };
+    require_keys_eq!(treasury.key(), protocol_config.treasury, CloakCraftError::InvalidTreasury);
+
+    let amount = stake.slash(RELAYER_SLASH_LAMPORTS);
+    if amount == 0 {
+        msg!("Rescue: relayer stake empty, no slash");
+        return Ok(());
+    }
+    let bounty = rescue_bounty(amount);
+
+    // Program-owned PDA: move lamports directly
+    **stake.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **rescuer.to_account_info().try_borrow_mut_lamports()? += bounty;
+    **treasury.to_account_info().try_borrow_mut_lamports()? += amount - bounty;
+
+    emit!(RelayerSlashed {
+        operation_id: pending_op.operation_id,
+        relayer: pending_op.relayer,
+        rescuer: rescuer.key(),
+        amount,
+        rescuer_bounty: bounty,
+    });
+    msg!(
+        "Relayer {} slashed {} lamports ({} to rescuer {})",
+        pending_op.relayer,
+        amount,
+        bounty,
+        rescuer.key()
+    );
 
     Ok(())
 }
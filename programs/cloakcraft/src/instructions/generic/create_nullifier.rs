@@ -2,11 +2,12 @@
 //!
 //! Creates ONE nullifier for a pending operation via Light Protocol.
 //! Call this instruction N times for N nullifiers.
+//! Rejects operations from a previous operation epoch.
 
 use anchor_lang::prelude::*;
 
 use crate::state::{
-    Pool, PendingOperation,
+    Pool, PendingOperation, ProtocolConfig,
     LightValidityProof, LightAddressTreeInfo,
 };
 use crate::constants::seeds;
@@ -27,8 +28,9 @@ pub struct LightCreateNullifierParams {
 #[derive(Accounts)]
 #[instruction(operation_id: [u8; 32], nullifier_index: u8)]
 pub struct CreateNullifier<'info> {
-    /// Pool for this nullifier
+    /// Pool for this nullifier (mutable for activity counters)
     #[account(
+        mut,
         seeds = [seeds::POOL, pool.token_mint.as_ref()],
         bump = pool.bump,
     )]
@@ -50,6 +52,14 @@ pub struct CreateNullifier<'info> {
     )]
     pub relayer: Signer<'info>,
 
+    /// Protocol config (operations from a previous operation epoch may not spend)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        constraint = protocol_config.is_current_operation(&pending_operation.operation_id) @ CloakCraftError::StaleOperationEpoch,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
     // Light Protocol accounts via remaining_accounts
 }
 
@@ -63,7 +73,7 @@ pub fn create_nullifier<'info>(
     nullifier_index: u8,
     light_params: LightCreateNullifierParams,
 ) -> Result<()> {
-    let pool = &ctx.accounts.pool;
+    let pool = &mut ctx.accounts.pool;
     let pending_op = &mut ctx.accounts.pending_operation;
 
     // Validate index
@@ -88,6 +98,12 @@ pub fn create_nullifier<'info>(
     // Get nullifier hash from pending operation (verified by proof)
     let nullifier = pending_op.expected_nullifiers[nullifier_index as usize];
 
+    // The nullifier domain comes from the circuit that verified it in Phase 0,
+    // so a relayer cannot file it under another operation's namespace
+    let domain = pending_op.nullifier_domain()
+        .filter(|d| !d.is_action())
+        .ok_or(CloakCraftError::InvalidNullifierDomain)?;
+
     // Create nullifier via Light Protocol (prevents double-spend)
     create_spend_nullifier_account(
         &ctx.accounts.relayer.to_account_info(),
@@ -96,11 +112,14 @@ pub fn create_nullifier<'info>(
         light_params.address_tree_info,
         light_params.output_tree_index,
         pool.key(),
+        pool.active_trees(Clock::get()?.slot),
+        domain,
         nullifier,
     )?;
 
     // Mark as created
     pending_op.mark_nullifier_created(nullifier_index);
+    pool.record_spend(Clock::get()?.unix_timestamp);
 
     Ok(())
 }
@@ -12,6 +12,9 @@
 //! CRITICAL POINT: After this phase, the commitment is marked as spent.
 //! Output commitments MUST be created or funds will be lost.
 //!
+//! Operations whose ID carries a previous operation epoch are rejected here,
+//! so an epoch bump stops every in-flight operation before its inputs are spent.
+//!
 //! Generic Flow:
 //! Phase 0: Verify ZK proof + Create PendingOperation
 //! Phase 1: Verify commitment exists
@@ -22,7 +25,7 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, PendingOperation, LightValidityProof, LightAddressTreeInfo};
+use crate::state::{Pool, PoolStats, PendingOperation, ProtocolConfig, LightValidityProof, LightAddressTreeInfo, TreeRegistry};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 use crate::light_cpi::create_spend_nullifier_account;
@@ -41,8 +44,9 @@ pub struct LightCreateNullifierAndPendingParams {
 #[derive(Accounts)]
 #[instruction(operation_id: [u8; 32])]
 pub struct CreateNullifierAndPending<'info> {
-    /// Pool for this nullifier
+    /// Pool for this nullifier (mutable for activity counters)
     #[account(
+        mut,
         seeds = [seeds::POOL, pool.token_mint.as_ref()],
         bump = pool.bump,
     )]
@@ -66,6 +70,32 @@ pub struct CreateNullifierAndPending<'info> {
     )]
     pub relayer: Signer<'info>,
 
+    /// Protocol config (operations from a previous operation epoch may not spend)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        constraint = protocol_config.is_current_operation(&pending_operation.operation_id) @ CloakCraftError::StaleOperationEpoch,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Pool statistics (optional, updated when passed)
+    #[account(
+        mut,
+        seeds = [seeds::POOL_STATS, pool.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Option<Box<Account<'info, PoolStats>>>,
+
+    /// Tree registry (once initialized, the output tree must be registered
+    /// and its writes are counted toward TreeNearCapacity)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: UncheckedAccount<'info>,
+
     // Light Protocol accounts via remaining_accounts (~8 accounts)
 }
 
@@ -96,7 +126,7 @@ pub fn create_nullifier_and_pending<'info>(
     nullifier_index: u8,
     light_params: LightCreateNullifierAndPendingParams,
 ) -> Result<()> {
-    let pool = &ctx.accounts.pool;
+    let pool = &mut ctx.accounts.pool;
     let pending_op = &mut ctx.accounts.pending_operation;
 
     msg!("=== Phase 2: Create Nullifier (CRITICAL POINT) ===");
@@ -140,6 +170,15 @@ pub fn create_nullifier_and_pending<'info>(
     msg!("Provided pool: {:?}", pool.key());
     msg!("CRITICAL: Commitment will be marked as spent!");
 
+    // SECURITY: Nullifier domain is bound to the operation type set in Phase 0
+    // Prevents filing a nullifier under another circuit's namespace
+    let domain = pending_op.nullifier_domain()
+        .filter(|d| !d.is_action())
+        .ok_or(CloakCraftError::InvalidNullifierDomain)?;
+
+    let pool_trees = pool.active_trees(Clock::get()?.slot);
+    TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &pool_trees.state_tree, 1)?;
+
     // SECURITY: Create spend nullifier via Light Protocol (prevents double-spend)
     create_spend_nullifier_account(
         &ctx.accounts.relayer.to_account_info(),
@@ -148,6 +187,8 @@ pub fn create_nullifier_and_pending<'info>(
         light_params.address_tree_info,
         light_params.output_tree_index,
         pool.key(),
+        pool_trees,
+        domain,
         nullifier,
     )?;
 
@@ -155,6 +196,13 @@ pub fn create_nullifier_and_pending<'info>(
 
     // SECURITY: Mark nullifier as created
     pending_op.nullifier_completed_mask |= bit_mask;
+    pool.record_spend(Clock::get()?.unix_timestamp);
+
+    if let Some(stats) = ctx.accounts.pool_stats.as_mut() {
+        if let Some(rolled) = stats.record_note_spent(Clock::get()?.unix_timestamp) {
+            emit!(rolled);
+        }
+    }
 
     // Check if all nullifiers created
     let all_nullifiers_mask = (1u8 << pending_op.num_inputs) - 1;
@@ -0,0 +1,280 @@
+//! Create Nullifiers and Commitments - Phases 2 and 4 combined (GENERIC)
+//!
+//! Creates a pending operation's remaining nullifiers and commitments in one
+//! pool with a single Light CPI (see `light_cpi::create_accounts_batch`),
+//! instead of one create_nullifier_and_pending / create_commitment
+//! transaction per account.
+//!
+//! Nullifiers and commitments may only share a batch when the operation has
+//! no Phase 3 left to run between them (a transfer without unshield or fee,
+//! a consolidation). Once every nullifier exists, the instruction also
+//! batches Phase 4 alone for any operation.
+//!
+//! Stranded operations are completed with create_commitment as before.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    Pool, PoolCommitmentCounter, PoolStats, RootRegistry, PendingOperation, ProtocolConfig,
+    LightValidityProof, LightAddressTreeInfo, TreeRegistry,
+};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::light_cpi::{create_accounts_batch, split_encrypted_note, BatchedAccount, BatchedCommitment};
+use crate::crypto::note_encryption::validate_encrypted_note;
+
+/// Parameters for a batched Light Protocol creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LightBatchParams {
+    /// Validity proof covering every new address
+    pub proof: LightValidityProof,
+    /// Address tree info per created account: nullifiers by input index,
+    /// then outputs in order (dummy outputs create no account)
+    pub address_tree_infos: Vec<LightAddressTreeInfo>,
+    /// Output state tree index
+    pub output_tree_index: u8,
+}
+
+/// One output commitment of a batch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchedCommitmentParams {
+    /// Index of the commitment in the pending operation
+    pub commitment_index: u8,
+    /// Stealth ephemeral pubkey stored with the note
+    pub stealth_ephemeral_pubkey: [u8; 64],
+    /// Encrypted note
+    pub encrypted_note: Vec<u8>,
+    /// View tag (optional)
+    pub view_tag: Option<[u8; 8]>,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct CreateNullifiersAndCommitments<'info> {
+    /// Pool of every nullifier and commitment in the batch
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Commitment counter for the pool
+    #[account(
+        mut,
+        seeds = [PoolCommitmentCounter::SEEDS_PREFIX, pool.key().as_ref()],
+        bump = commitment_counter.bump,
+    )]
+    pub commitment_counter: Box<Account<'info, PoolCommitmentCounter>>,
+
+    /// Pending operation PDA (from Phase 0)
+    #[account(
+        mut,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump = pending_operation.bump,
+        constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
+        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (pays for the accounts, must match pending operation)
+    #[account(
+        mut,
+        constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
+    )]
+    pub relayer: Signer<'info>,
+
+    /// Protocol config (operations from a previous operation epoch may not spend)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Pool statistics (optional, updated when passed)
+    #[account(
+        mut,
+        seeds = [seeds::POOL_STATS, pool.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Option<Box<Account<'info, PoolStats>>>,
+
+    /// Root registry (commitments are appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, pool.key().as_ref()],
+        bump,
+    )]
+    pub root_registry: UncheckedAccount<'info>,
+
+    /// Tree registry (once initialized, the output tree must be registered
+    /// and its writes are counted toward TreeNearCapacity)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: UncheckedAccount<'info>,
+
+    // Light Protocol accounts via remaining_accounts
+}
+
+/// Phases 2 and 4: create the pool's remaining nullifiers, then `outputs`, in one CPI
+///
+/// Nullifiers are read from the pending operation (every uncreated one whose
+/// input is in `pool`); commitments are checked exactly as by create_commitment.
+pub fn create_nullifiers_and_commitments<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateNullifiersAndCommitments<'info>>,
+    _operation_id: [u8; 32],
+    outputs: Vec<BatchedCommitmentParams>,
+    light_params: LightBatchParams,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let counter = &mut ctx.accounts.commitment_counter;
+    let pending_op = &mut ctx.accounts.pending_operation;
+    let now = Clock::get()?.unix_timestamp;
+    let pool_key = pool.key();
+
+    let mut accounts = Vec::new();
+
+    // Phase 2: every uncreated nullifier in this pool
+    let mut nullifier_mask = 0u8;
+    for index in 0..pending_op.num_inputs {
+        let bit = 1u8 << index;
+        if pending_op.nullifier_completed_mask & bit != 0
+            || pending_op.input_pools[index as usize] != pool_key.to_bytes()
+        {
+            continue;
+        }
+        require!(pending_op.inputs_verified_mask & bit != 0, CloakCraftError::CommitmentNotVerified);
+        nullifier_mask |= bit;
+    }
+
+    if nullifier_mask != 0 {
+        require!(
+            ctx.accounts.protocol_config.is_current_operation(&pending_op.operation_id),
+            CloakCraftError::StaleOperationEpoch
+        );
+        // SECURITY: Nullifier domain is bound to the operation type set in Phase 0
+        let domain = pending_op.nullifier_domain()
+            .filter(|d| !d.is_action())
+            .ok_or(CloakCraftError::InvalidNullifierDomain)?;
+        for index in 0..pending_op.num_inputs {
+            if nullifier_mask & (1u8 << index) != 0 {
+                accounts.push(BatchedAccount::SpendNullifier {
+                    domain,
+                    nullifier: pending_op.expected_nullifiers[index as usize],
+                });
+            }
+        }
+        pending_op.nullifier_completed_mask |= nullifier_mask;
+    }
+
+    // Phase 4: outputs, only once every nullifier exists and no Phase 3 is left
+    if !outputs.is_empty() {
+        require!(pending_op.all_expected_nullifiers_created(), CloakCraftError::NullifierNotCreated);
+        if nullifier_mask != 0 {
+            let phase3_pending = match pending_op.kind() {
+                Some(kind) => {
+                    let requirements = kind.requirements();
+                    requirements.needs_execute
+                        || (requirements.needs_unshield && pending_op.pending_outflow() > 0)
+                }
+                None => true,
+            };
+            require!(!phase3_pending, CloakCraftError::LightBatchPhase3Pending);
+        }
+    }
+
+    let mut created_commitments = Vec::new();
+    for output in outputs {
+        let index = output.commitment_index;
+        require!(index < pending_op.num_commitments, CloakCraftError::InvalidCommitmentIndex);
+        require!(
+            (pending_op.completed_mask & (1u8 << index)) == 0,
+            CloakCraftError::CommitmentAlreadyCreated
+        );
+        require!(
+            pending_op.pools[index as usize] == pool_key.to_bytes(),
+            CloakCraftError::PoolMismatch
+        );
+
+        // Zero commitments and zero-amount dummies create no account
+        let commitment = pending_op.commitments[index as usize];
+        if commitment == [0u8; 32] || pending_op.output_amounts[index as usize] == 0 {
+            pending_op.mark_completed(index);
+            continue;
+        }
+
+        validate_encrypted_note(&output.encrypted_note, pool)?;
+        require!(
+            pending_op.stealth_ephemeral_matches(index, &output.stealth_ephemeral_pubkey),
+            CloakCraftError::StealthEphemeralMismatch
+        );
+
+        let (encrypted_note, encrypted_note_len, encrypted_note_overflow) =
+            split_encrypted_note(&output.encrypted_note);
+        let leaf_index = counter.allocate();
+        pending_op.leaf_indices[index as usize] = leaf_index;
+
+        accounts.push(BatchedAccount::Commitment(Box::new(BatchedCommitment {
+            commitment,
+            leaf_index,
+            stealth_ephemeral_pubkey: output.stealth_ephemeral_pubkey,
+            encrypted_note,
+            encrypted_note_len,
+            view_tag: output.view_tag.unwrap_or_default(),
+            encrypted_note_overflow,
+        })));
+        created_commitments.push(commitment);
+        pending_op.mark_completed(index);
+    }
+
+    require!(!accounts.is_empty(), CloakCraftError::InvalidLightBatch);
+    let num_accounts = accounts.len();
+    let pool_trees = pool.active_trees(Clock::get()?.slot);
+
+    TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &pool_trees.state_tree, num_accounts as u64)?;
+
+    create_accounts_batch(
+        &ctx.accounts.relayer.to_account_info(),
+        ctx.remaining_accounts,
+        light_params.proof,
+        light_params.address_tree_infos,
+        light_params.output_tree_index,
+        pool_key,
+        pool_trees,
+        accounts,
+    )?;
+
+    let nullifiers_created = nullifier_mask.count_ones();
+    for _ in 0..nullifiers_created {
+        pool.record_spend(now);
+    }
+
+    for commitment in &created_commitments {
+        RootRegistry::append_to(&ctx.accounts.root_registry, *commitment, Clock::get()?.slot)?;
+    }
+
+    if let Some(stats) = ctx.accounts.pool_stats.as_mut() {
+        for _ in 0..nullifiers_created {
+            if let Some(rolled) = stats.record_note_spent(now) {
+                emit!(rolled);
+            }
+        }
+        for _ in &created_commitments {
+            if let Some(rolled) = stats.record_note_created(now) {
+                emit!(rolled);
+            }
+        }
+    }
+
+    msg!(
+        "Batched {} nullifiers and {} commitments in one Light CPI ({} accounts)",
+        nullifiers_created, created_commitments.len(), num_accounts
+    );
+
+    Ok(())
+}
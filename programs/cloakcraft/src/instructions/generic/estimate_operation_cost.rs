@@ -0,0 +1,44 @@
+//! Estimate operation cost (read-only)
+//!
+//! Returns the compute unit limit, transaction count and rent for each phase
+//! of an operation shape, so relayers can set compute budgets and priority
+//! fees without guessing. Nothing is written; call it via simulation.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{OperationCostEstimate, OperationShape, PendingOperation, ProtocolConfig};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct EstimateOperationCost<'info> {
+    /// Protocol config (rent refund split)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Account<'info, ProtocolConfig>,
+}
+
+pub fn estimate_operation_cost(
+    ctx: Context<EstimateOperationCost>,
+    shape: OperationShape,
+) -> Result<OperationCostEstimate> {
+    let pending_rent = Rent::get()?.minimum_balance(PendingOperation::SPACE);
+    let estimate = OperationCostEstimate::estimate(
+        &shape,
+        pending_rent,
+        ctx.accounts.protocol_config.rent_refund_bps,
+    )
+    .ok_or(CloakCraftError::InvalidOperationShape)?;
+
+    msg!(
+        "Operation type {}: {} transactions, {} CU, {} lamports rent",
+        shape.operation_type,
+        estimate.total_transactions,
+        estimate.total_compute_units,
+        pending_rent
+    );
+
+    Ok(estimate)
+}
@@ -0,0 +1,53 @@
+//! Create a circuit's statistics account
+//!
+//! Permissionless: anyone may pay for it. Counters start from zero; Phase 0
+//! instructions update it only when it is passed.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{CircuitStats, VerificationKey};
+use crate::constants::seeds;
+
+#[derive(Accounts)]
+pub struct InitializeCircuitStats<'info> {
+    /// Verification key of the circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
+        bump = verification_key.bump,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Circuit statistics
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CircuitStats::INIT_SPACE,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump,
+    )]
+    pub circuit_stats: Box<Account<'info, CircuitStats>>,
+
+    /// Rent payer
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_circuit_stats(ctx: Context<InitializeCircuitStats>) -> Result<()> {
+    let stats = &mut ctx.accounts.circuit_stats;
+    stats.circuit_id = ctx.accounts.verification_key.circuit_id;
+    stats.verifications = 0;
+    stats.failures = 0;
+    stats.total_compute_units = 0;
+    stats.max_compute_units = 0;
+    stats.last_failure_client_version = 0;
+    stats.last_failure_at = 0;
+    stats.updated_at = Clock::get()?.unix_timestamp;
+    stats.bump = ctx.bumps.circuit_stats;
+
+    msg!("Circuit stats initialized for circuit {:02x?}...", &stats.circuit_id[0..8]);
+
+    Ok(())
+}
@@ -9,8 +9,16 @@
 //! 2. create_nullifier_and_pending - Create nullifier (GENERIC, binds to Phase 0)
 //! 3. execute_{operation} - Execute operation logic (operation-specific)
 //! 4. create_commitment - Create commitments (GENERIC, call M times)
+//!    create_nullifiers_and_commitments - Phases 2 and 4 for one pool in one Light CPI
+//!    (when no Phase 3 runs between them)
 //! 5. close_pending_operation - Close pending operation (GENERIC)
 //!
+//! estimate_operation_cost returns per-phase compute and rent for sizing compute budgets.
+//! simulate_operation dry-runs a transfer's or swap's Phase 0 so relayers can vet submissions.
+//! initialize_circuit_stats creates the optional per-circuit Phase 0 statistics;
+//! record_proof_failure counts a rejected proof in them.
+//! bond/unbond/withdraw_relayer_stake manage the stake slashed for stranded operations.
+//!
 //! SECURITY: Phases are bound together via PendingOperation state:
 //! - Phase 0 stores: input_commitment, expected_nullifier
 //! - Phase 1 verifies: input_commitment matches
@@ -27,10 +35,26 @@ pub mod verify_commitment_exists;
 pub mod create_nullifier_and_pending;
 pub mod create_nullifier;
 pub mod create_commitment;
+pub mod create_nullifiers_and_commitments;
 pub mod close_pending_operation;
+pub mod estimate_operation_cost;
+pub mod simulate_operation;
+pub mod initialize_circuit_stats;
+pub mod record_proof_failure;
+pub mod bond_relayer_stake;
+pub mod unbond_relayer_stake;
+pub mod withdraw_relayer_stake;
 
 pub use verify_commitment_exists::*;
 pub use create_nullifier_and_pending::*;
 pub use create_nullifier::*;
 pub use create_commitment::*;
+pub use create_nullifiers_and_commitments::*;
 pub use close_pending_operation::*;
+pub use estimate_operation_cost::*;
+pub use simulate_operation::*;
+pub use initialize_circuit_stats::*;
+pub use record_proof_failure::*;
+pub use bond_relayer_stake::*;
+pub use unbond_relayer_stake::*;
+pub use withdraw_relayer_stake::*;
@@ -0,0 +1,53 @@
+//! Count a rejected proof in a circuit's statistics
+//!
+//! Phase 0 fails the transaction on a rejected proof, which rolls back
+//! anything written to `CircuitStats`. Relayers that see a rejection report
+//! it here instead: the proof is verified again against the submitted public
+//! inputs and counted only if it still fails. Counts stay advisory, since
+//! anyone can report a bad proof.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{CircuitStats, VerificationKey};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::proof::verify_groth16_proof;
+
+#[derive(Accounts)]
+pub struct RecordProofFailure<'info> {
+    /// Verification key of the circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
+        bump = verification_key.bump,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Circuit statistics
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Box<Account<'info, CircuitStats>>,
+
+    /// Reporter (typically the relayer whose Phase 0 failed)
+    pub reporter: Signer<'info>,
+}
+
+pub fn record_proof_failure(
+    ctx: Context<RecordProofFailure>,
+    proof: Vec<u8>,
+    public_inputs: Vec<[u8; 32]>,
+    client_version: u32,
+) -> Result<()> {
+    require!(
+        verify_groth16_proof(&proof, &ctx.accounts.verification_key.vk_data, &public_inputs, "ReportedProof").is_err(),
+        CloakCraftError::ReportedProofVerifies
+    );
+
+    let stats = &mut ctx.accounts.circuit_stats;
+    emit!(stats.record_failure(client_version, Clock::get()?.unix_timestamp));
+    msg!("Proof failure recorded (client version {:#x}, {} total)", client_version, stats.failures);
+
+    Ok(())
+}
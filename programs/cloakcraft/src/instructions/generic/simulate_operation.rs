@@ -0,0 +1,200 @@
+//! Simulate operation (dry-run Phase 0)
+//!
+//! Runs a transfer's or swap's Phase 0 validation (proof verification and
+//! the pool checks), plus the checks the operation would meet later (the
+//! transfer fee in Phase 3, the swap output against current reserves), so a
+//! relayer can vet a user submission before paying for the multi-phase flow.
+//!
+//! Nothing is written: the instruction sets a `SimulationResult` as return
+//! data and always fails with `SimulationComplete`. Call it via simulation
+//! and read the return data; any other error is the check that failed.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+
+use crate::state::{AmmPool, Pool, ProgramVersion, ProtocolConfig, VerificationKey};
+use crate::constants::{operation_types, seeds};
+use crate::errors::CloakCraftError;
+use crate::helpers::field::{assert_canonical, pubkey_to_field, u64_to_field};
+use crate::helpers::verify_groth16_proof;
+use crate::instructions::pool::transfer_public_inputs;
+
+/// Phase 0 arguments of the operation to simulate
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum SimulatedOperation {
+    /// `create_pending_with_proof` (pool = the transfer's pool)
+    Transfer {
+        merkle_root: [u8; 32],
+        nullifier: [u8; 32],
+        out_commitments: Vec<[u8; 32]>,
+        output_amounts: Vec<u64>,
+        transfer_amount: u64,
+        unshield_amount: u64,
+        fee_amount: u64,
+        call_hash: Option<[u8; 32]>,
+    },
+    /// `create_pending_with_proof_swap` (pool = input pool, amm_pool required)
+    Swap {
+        merkle_root: [u8; 32],
+        nullifier: [u8; 32],
+        out_commitment: [u8; 32],
+        change_commitment: [u8; 32],
+        min_output: u64,
+        swap_amount: u64,
+        output_amount: u64,
+        swap_a_to_b: bool,
+    },
+}
+
+/// Outcome of a simulated operation (return data)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SimulationResult {
+    /// Operation type (see `constants::operation_types`)
+    pub operation_type: u8,
+    /// Whether the proof verified (Phase 0 would otherwise reject it)
+    pub proof_valid: bool,
+    /// Minimum protocol fee (transfers; 0 while fees are disabled)
+    pub expected_fee: u64,
+    /// Swap output recomputed from current reserves (swaps)
+    pub expected_output: u64,
+}
+
+#[derive(Accounts)]
+pub struct SimulateOperation<'info> {
+    /// Pool the operation spends from
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Verification key for the operation's circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Protocol config (fee checks)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// AMM pool (required for swaps)
+    #[account(
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
+        bump = amm_pool.bump,
+    )]
+    pub amm_pool: Option<Box<Account<'info, AmmPool>>>,
+}
+
+pub fn simulate_operation(
+    ctx: Context<SimulateOperation>,
+    proof: Vec<u8>,
+    operation: SimulatedOperation,
+    client_version: u32,
+) -> Result<()> {
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    let pool = &ctx.accounts.pool;
+    let mut result = SimulationResult::default();
+
+    let public_inputs = match operation {
+        SimulatedOperation::Transfer {
+            merkle_root,
+            nullifier,
+            out_commitments,
+            output_amounts,
+            transfer_amount,
+            unshield_amount,
+            fee_amount,
+            call_hash,
+        } => {
+            assert_canonical(&[nullifier])?;
+            assert_canonical(&out_commitments)?;
+            result.operation_type = operation_types::TRANSFER;
+
+            let public_inputs = transfer_public_inputs(
+                pool,
+                &ctx.accounts.verification_key.circuit_id,
+                &merkle_root,
+                &nullifier,
+                &out_commitments,
+                &output_amounts,
+                transfer_amount,
+                unshield_amount,
+                fee_amount,
+                call_hash.as_ref(),
+                Clock::get()?.unix_timestamp,
+            )?;
+
+            // Phase 3 fee check
+            result.expected_fee = ctx.accounts.protocol_config
+                .expected_transfer_fee(&pool.token_mint, transfer_amount, unshield_amount)?;
+            require!(fee_amount >= result.expected_fee, CloakCraftError::InsufficientFee);
+
+            public_inputs
+        }
+        SimulatedOperation::Swap {
+            merkle_root,
+            nullifier,
+            out_commitment,
+            change_commitment,
+            min_output,
+            swap_amount,
+            output_amount,
+            swap_a_to_b,
+        } => {
+            assert_canonical(&[nullifier, out_commitment, change_commitment])?;
+            result.operation_type = operation_types::SWAP;
+
+            let amm_pool = ctx.accounts.amm_pool
+                .as_ref()
+                .ok_or(CloakCraftError::InvalidPoolState)?;
+            let input_mint = if swap_a_to_b { amm_pool.token_a_mint } else { amm_pool.token_b_mint };
+            require_keys_eq!(pool.token_mint, input_mint, CloakCraftError::PoolMismatch);
+
+            // Phase 3 output check against current reserves
+            result.expected_output =
+                amm_pool.check_swap_output(swap_amount, swap_a_to_b, output_amount, min_output)?;
+
+            vec![
+                merkle_root,
+                nullifier,
+                pubkey_to_field(&amm_pool.pool_id),
+                out_commitment,
+                change_commitment,
+                u64_to_field(min_output),
+            ]
+        }
+    };
+
+    result.proof_valid = verify_groth16_proof(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "Simulation",
+    )
+    .is_ok();
+
+    msg!(
+        "Simulated operation type {}: proof_valid={}, expected_fee={}, expected_output={}",
+        result.operation_type,
+        result.proof_valid,
+        result.expected_fee,
+        result.expected_output
+    );
+
+    set_return_data(&result.try_to_vec()?);
+    Err(CloakCraftError::SimulationComplete.into())
+}
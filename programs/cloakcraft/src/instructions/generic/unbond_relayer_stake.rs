@@ -0,0 +1,39 @@
+//! Unbond relayer stake
+//!
+//! Moves bonded lamports into the unbonding bucket. They stay slashable and
+//! can be withdrawn once `RELAYER_UNBONDING_SECONDS` have passed.
+
+use anchor_lang::prelude::*;
+
+use crate::state::RelayerStake;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct UnbondRelayerStake<'info> {
+    /// Relayer stake
+    #[account(
+        mut,
+        seeds = [seeds::RELAYER_STAKE, relayer.key().as_ref()],
+        bump = relayer_stake.bump,
+    )]
+    pub relayer_stake: Account<'info, RelayerStake>,
+
+    /// Relayer
+    pub relayer: Signer<'info>,
+}
+
+pub fn unbond_relayer_stake(ctx: Context<UnbondRelayerStake>, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let stake = &mut ctx.accounts.relayer_stake;
+    require!(stake.unbond(amount, now), CloakCraftError::InsufficientRelayerStake);
+
+    msg!(
+        "Relayer {} unbonding {} lamports, withdrawable at {}",
+        stake.relayer,
+        stake.unbonding,
+        stake.unbond_at
+    );
+
+    Ok(())
+}
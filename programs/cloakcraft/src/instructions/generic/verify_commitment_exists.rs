@@ -8,6 +8,15 @@
 //! SECURITY BINDING: The commitment parameter must match pending_op.input_commitment
 //! from Phase 0. This prevents commitment swap attacks.
 //!
+//! SECURITY BINDING: The account hash is recomputed on-chain from the commitment's
+//! address (pool + commitment), so a hash for another pool's account is rejected.
+//!
+//! PINNED MODE: When the operation moves at least the pool's
+//! `pinned_inclusion_threshold` (see `PendingOperation::pool_value_bound`),
+//! `prove_by_index` is rejected: the commitment must be proven with a
+//! validity proof against the explicit `root_index`, checked by Light
+//! against the state tree's root history.
+//!
 //! Generic Flow (ANY spend operation):
 //! Phase 0: Verify ZK proof + Create PendingOperation (stores input_commitment)
 //! Phase 1 (this): Verify commitment exists (must match input_commitment from Phase 0)
@@ -37,6 +46,9 @@ pub struct CommitmentMerkleContext {
 pub struct LightVerifyCommitmentParams {
     /// Account hash of commitment to verify
     pub commitment_account_hash: [u8; 32],
+    /// Data hash of the commitment account (from the indexer), used to
+    /// recompute the account hash on-chain
+    pub commitment_data_hash: [u8; 32],
     /// Merkle context proving commitment exists
     pub commitment_merkle_context: CommitmentMerkleContext,
     /// Inclusion proof for commitment (from getInclusionProofByHash)
@@ -142,6 +154,15 @@ pub fn verify_commitment_exists<'info>(
     msg!("Provided pool: {:?}", pool.key());
     msg!("Account hash: {:02x?}...", &light_params.commitment_account_hash[0..8]);
 
+    // SECURITY: High-value operations don't trust queue/index context
+    if pool.requires_pinned_inclusion(pending_op.pool_value_bound(&expected_pool)) {
+        require!(
+            !light_params.commitment_merkle_context.prove_by_index,
+            CloakCraftError::PinnedInclusionRequired
+        );
+        msg!("Pinned inclusion: root index {}", light_params.commitment_merkle_context.root_index);
+    }
+
     // SECURITY: Verify THIS EXACT commitment exists in Light Protocol state tree
     crate::light_cpi::verify_commitment_inclusion(
         &ctx.accounts.relayer.to_account_info(),
@@ -152,6 +173,8 @@ pub fn verify_commitment_exists<'info>(
         light_params.commitment_address_tree_info,
         input_commitment,
         pool.key(),
+        pool.address_tree,
+        light_params.commitment_data_hash,
     )?;
 
     msg!("✅ Commitment verified - exists in state tree");
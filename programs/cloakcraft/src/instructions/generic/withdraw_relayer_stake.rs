@@ -0,0 +1,41 @@
+//! Withdraw unbonded relayer stake
+//!
+//! Returns the unbonding lamports to the relayer once the unbonding delay
+//! has passed.
+
+use anchor_lang::prelude::*;
+
+use crate::state::RelayerStake;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct WithdrawRelayerStake<'info> {
+    /// Relayer stake
+    #[account(
+        mut,
+        seeds = [seeds::RELAYER_STAKE, relayer.key().as_ref()],
+        bump = relayer_stake.bump,
+    )]
+    pub relayer_stake: Account<'info, RelayerStake>,
+
+    /// Relayer (receives the lamports)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+}
+
+pub fn withdraw_relayer_stake(ctx: Context<WithdrawRelayerStake>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let amount = ctx.accounts.relayer_stake.withdrawable(now);
+    require!(amount > 0, CloakCraftError::RelayerStakeUnbonding);
+
+    ctx.accounts.relayer_stake.unbonding = 0;
+
+    // Program-owned PDA: move lamports directly
+    **ctx.accounts.relayer_stake.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.relayer.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    msg!("Relayer {} withdrew {} lamports", ctx.accounts.relayer.key(), amount);
+
+    Ok(())
+}
@@ -1,13 +1,15 @@
 //! Cancel an order and return escrowed funds
 //!
 //! Uses Light Protocol for nullifier and commitment storage.
+//! Orders opted into escrow yield settle their accrued yield here.
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, Order, OrderStatus, VerificationKey, PoolCommitmentCounter, LightValidityProof, LightAddressTreeInfo};
+use crate::state::{Pool, NullifierDomain, Order, OrderStatus, EscrowYieldPolicy, VerificationKey, PoolCommitmentCounter, LightValidityProof, LightAddressTreeInfo, VIEW_TAG_SIZE, RootRegistry, TreeRegistry};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 use crate::helpers::verify_groth16_proof;
+use crate::helpers::escrow_yield::settle_order_yield;
 use crate::light_cpi::{create_spend_nullifier_account, create_commitment_account, vec_to_fixed_note};
 
 /// Parameters for Light Protocol operations
@@ -46,7 +48,7 @@ pub struct CancelOrder<'info> {
         seeds = [PoolCommitmentCounter::SEEDS_PREFIX, pool.key().as_ref()],
         bump = commitment_counter.bump,
     )]
-    pub commitment_counter: Account<'info, PoolCommitmentCounter>,
+    pub commitment_counter: Box<Account<'info, PoolCommitmentCounter>>,
 
     /// Order being cancelled
     #[account(
@@ -55,19 +57,56 @@ pub struct CancelOrder<'info> {
         bump = order.bump,
         constraint = order.is_open() @ CloakCraftError::OrderAlreadyFilled,
     )]
-    pub order: Account<'info, Order>,
+    pub order: Box<Account<'info, Order>>,
 
     /// Verification key (boxed to reduce stack usage)
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
+    /// Order yield PDA (may be uninitialized: order not opted into escrow yield)
+    /// CHECK: Address derived from the order ID; deserialized only if initialized
+    #[account(
+        mut,
+        seeds = [seeds::ORDER_YIELD, order_id.as_ref()],
+        bump,
+    )]
+    pub order_yield: UncheckedAccount<'info>,
+
+    /// Pool's escrow yield policy (required if the order opted in)
+    #[account(
+        mut,
+        seeds = [seeds::ESCROW_YIELD, pool.key().as_ref()],
+        bump = escrow_yield_policy.bump,
+    )]
+    pub escrow_yield_policy: Option<Box<Account<'info, EscrowYieldPolicy>>>,
+
     /// Relayer/maker (pays for compressed account creation)
     #[account(mut)]
     pub relayer: Signer<'info>,
 
+    /// Root registry (the refund commitment is appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, pool.key().as_ref()],
+        bump,
+    )]
+    pub root_registry: UncheckedAccount<'info>,
+
+    /// Tree registry (once initialized, the output tree must be registered
+    /// and its writes are counted toward TreeNearCapacity)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: UncheckedAccount<'info>,
+
     // Light Protocol accounts are passed via remaining_accounts
 }
 
@@ -110,14 +149,15 @@ pub fn cancel_order<'info>(
             params.nullifier_address_tree_info.clone(),
             params.output_tree_index,
             pool.key(),
+            pool.active_trees(Clock::get()?.slot),
+            NullifierDomain::Spend,
             escrow_nullifier,
         )?;
+        TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &pool.active_trees(Clock::get()?.slot).state_tree, 1)?;
     }
     // 3. Create refund commitment via Light Protocol
     // Encrypted note is stored inline for direct scanning
-    let leaf_index = commitment_counter.next_leaf_index;
-    commitment_counter.next_leaf_index += 1;
-    commitment_counter.total_commitments += 1;
+    let leaf_index = commitment_counter.allocate();
 
     if let Some(ref params) = light_params {
         let (note_arr, note_len) = vec_to_fixed_note(&encrypted_note);
@@ -128,15 +168,25 @@ pub fn cancel_order<'info>(
             params.commitment_address_tree_info.clone(),
             params.output_tree_index,
             pool.key(),
+            pool.active_trees(Clock::get()?.slot),
             refund_commitment,
             leaf_index,
             [0u8; 64],
             note_arr,
             note_len,
+            [0u8; VIEW_TAG_SIZE],
         )?;
+        TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &pool.active_trees(Clock::get()?.slot).state_tree, 1)?;
+        RootRegistry::append_to(&ctx.accounts.root_registry, refund_commitment, Clock::get()?.slot)?;
     }
 
-    // 4. Mark order as cancelled
+    // 4. Settle escrow yield (the escrow is refunded)
+    settle_order_yield(
+        &ctx.accounts.order_yield,
+        ctx.accounts.escrow_yield_policy.as_deref_mut(),
+    )?;
+
+    // 5. Mark order as cancelled
     order.status = OrderStatus::Cancelled;
     Ok(())
 }
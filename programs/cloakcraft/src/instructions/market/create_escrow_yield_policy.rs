@@ -0,0 +1,76 @@
+//! Create the escrow yield policy for a market
+//!
+//! Opt-in per market (offer token pool): the pool authority designates a
+//! registered yield adapter and the share of opted-in escrow it may deploy.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, AdaptModule, EscrowYieldPolicy, MAX_ESCROW_DEPLOY_BPS};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct CreateEscrowYieldPolicy<'info> {
+    /// Escrow yield policy (PDA per offer token pool)
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + EscrowYieldPolicy::INIT_SPACE,
+        seeds = [seeds::ESCROW_YIELD, pool.key().as_ref()],
+        bump
+    )]
+    pub escrow_yield_policy: Box<Account<'info, EscrowYieldPolicy>>,
+
+    /// Offer token pool
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Yield adapter module (must be registered and enabled)
+    #[account(
+        seeds = [seeds::ADAPT_MODULE, adapt_module.program_id.as_ref()],
+        bump = adapt_module.bump,
+        constraint = adapt_module.is_usable() @ CloakCraftError::AdapterDisabled,
+    )]
+    pub adapt_module: Box<Account<'info, AdaptModule>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+
+    /// Payer
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Create the escrow yield policy
+///
+/// # Arguments
+/// * `max_deploy_bps` - Share of opted-in principal that may be deployed (max 9000)
+pub fn create_escrow_yield_policy(
+    ctx: Context<CreateEscrowYieldPolicy>,
+    max_deploy_bps: u16,
+) -> Result<()> {
+    require!(max_deploy_bps <= MAX_ESCROW_DEPLOY_BPS, CloakCraftError::InvalidAmount);
+
+    let policy = &mut ctx.accounts.escrow_yield_policy;
+    policy.pool = ctx.accounts.pool.key();
+    policy.adapter_program = ctx.accounts.adapt_module.program_id;
+    policy.authority = ctx.accounts.authority.key();
+    policy.enabled = true;
+    policy.max_deploy_bps = max_deploy_bps;
+    policy.total_principal = 0;
+    policy.deployed = 0;
+    policy.yield_per_principal = 0;
+    policy.total_yield = 0;
+    policy.bump = ctx.bumps.escrow_yield_policy;
+
+    msg!("Escrow yield policy created: adapter {}, max deploy {} bps", policy.adapter_program, max_deploy_bps);
+
+    Ok(())
+}
@@ -4,7 +4,7 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, Order, OrderStatus, VerificationKey, PoolCommitmentCounter, LightValidityProof, LightAddressTreeInfo};
+use crate::state::{Pool, NullifierDomain, Order, OrderStatus, VerificationKey, PoolCommitmentCounter, LightValidityProof, LightAddressTreeInfo, VIEW_TAG_SIZE, RootRegistry, TreeRegistry};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 use crate::helpers::verify_groth16_proof;
@@ -46,7 +46,7 @@ pub struct CreateOrder<'info> {
         seeds = [PoolCommitmentCounter::SEEDS_PREFIX, pool.key().as_ref()],
         bump = commitment_counter.bump,
     )]
-    pub commitment_counter: Account<'info, PoolCommitmentCounter>,
+    pub commitment_counter: Box<Account<'info, PoolCommitmentCounter>>,
 
     /// Order account
     #[account(
@@ -56,12 +56,13 @@ pub struct CreateOrder<'info> {
         seeds = [seeds::ORDER, order_id.as_ref()],
         bump
     )]
-    pub order: Account<'info, Order>,
+    pub order: Box<Account<'info, Order>>,
 
     /// Verification key (boxed to reduce stack usage)
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
@@ -72,6 +73,25 @@ pub struct CreateOrder<'info> {
     /// System program
     pub system_program: Program<'info, System>,
 
+    /// Root registry (the escrow commitment is appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, pool.key().as_ref()],
+        bump,
+    )]
+    pub root_registry: UncheckedAccount<'info>,
+
+    /// Tree registry (once initialized, the output tree must be registered
+    /// and its writes are counted toward TreeNearCapacity)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: UncheckedAccount<'info>,
+
     // Light Protocol accounts are passed via remaining_accounts
 }
 
@@ -117,14 +137,15 @@ pub fn create_order<'info>(
             params.nullifier_address_tree_info.clone(),
             params.output_tree_index,
             pool.key(),
+            pool.active_trees(Clock::get()?.slot),
+            NullifierDomain::Spend,
             nullifier,
         )?;
+        TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &pool.active_trees(Clock::get()?.slot).state_tree, 1)?;
     }
     // 3. Create escrow commitment via Light Protocol
     // Encrypted note is stored inline for direct scanning
-    let leaf_index = commitment_counter.next_leaf_index;
-    commitment_counter.next_leaf_index += 1;
-    commitment_counter.total_commitments += 1;
+    let leaf_index = commitment_counter.allocate();
 
     if let Some(ref params) = light_params {
         let (escrow_arr, escrow_len) = vec_to_fixed_note(&encrypted_escrow);
@@ -135,12 +156,16 @@ pub fn create_order<'info>(
             params.commitment_address_tree_info.clone(),
             params.output_tree_index,
             pool.key(),
+            pool.active_trees(Clock::get()?.slot),
             escrow_commitment,
             leaf_index,
             [0u8; 64],
             escrow_arr,
             escrow_len,
+            [0u8; VIEW_TAG_SIZE],
         )?;
+        TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &pool.active_trees(Clock::get()?.slot).state_tree, 1)?;
+        RootRegistry::append_to(&ctx.accounts.root_registry, escrow_commitment, Clock::get()?.slot)?;
     }
 
     // 4. Initialize order account
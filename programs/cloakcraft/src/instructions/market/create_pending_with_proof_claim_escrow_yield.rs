@@ -0,0 +1,189 @@
+//! Create Pending with Proof - Claim Escrow Yield (Phase 0)
+//!
+//! Once an opted-in order is filled or cancelled, the maker proves knowledge
+//! of the `yield_key` behind the order's claim tag (`tag = hash(yield_key,
+//! order_id)`) and receives a bonus note for the accrued yield. No input
+//! notes are spent; the order yield record is marked claimed in Phase 3.
+//!
+//! Flow:
+//! Phase 0 (this): Verify ZK proof + yield amount + Create PendingOperation
+//! Phase 3: execute_claim_escrow_yield - Mark the order yield claimed
+//! Phase 4: create_commitment for the bonus note
+//! Final: close_pending_operation
+
+use anchor_lang::prelude::*;
+
+use crate::constants::{circuits, operation_types, seeds};
+use crate::errors::CloakCraftError;
+use crate::helpers::field::{pubkey_to_field, assert_canonical};
+use crate::helpers::verify_groth16_proof_metered;
+use crate::state::{
+    EscrowYieldPolicy, OrderYield, PendingOperation, Pool, VerificationKey,
+    ProtocolConfig, ProgramVersion, CircuitStats, OperationCredit,
+};
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32], order_id: [u8; 32])]
+pub struct CreatePendingWithProofClaimEscrowYield<'info> {
+    /// Order yield record being claimed
+    #[account(
+        seeds = [seeds::ORDER_YIELD, order_id.as_ref()],
+        bump = order_yield.bump,
+        constraint = order_yield.settled @ CloakCraftError::OrderYieldNotSettled,
+        constraint = !order_yield.claimed @ CloakCraftError::OrderYieldAlreadyClaimed,
+    )]
+    pub order_yield: Box<Account<'info, OrderYield>>,
+
+    /// Escrow yield policy the order opted into
+    #[account(
+        constraint = escrow_yield_policy.key() == order_yield.policy @ CloakCraftError::InvalidEscrowYieldPolicy,
+    )]
+    pub escrow_yield_policy: Box<Account<'info, EscrowYieldPolicy>>,
+
+    /// Offer token pool (bonus note)
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.key() == escrow_yield_policy.pool @ CloakCraftError::PoolMismatch,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Verification key for the escrow yield claim circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, circuits::MARKET_ESCROW_YIELD_CLAIM.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Pending operation PDA (created in this instruction)
+    #[account(
+        init,
+        payer = relayer,
+        space = PendingOperation::SPACE,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (pays for PDA creation)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Phase 0: Verify ZK proof and create PendingOperation for an escrow yield claim
+#[allow(clippy::too_many_arguments)]
+pub fn create_pending_with_proof_claim_escrow_yield<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofClaimEscrowYield<'info>>,
+    operation_id: [u8; 32],
+    _order_id: [u8; 32],
+    proof: Vec<u8>,
+    bonus_commitment: [u8; 32],
+    yield_amount: u64,
+    client_version: u32,
+) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[bonus_commitment])?;
+
+    let order_yield = &ctx.accounts.order_yield;
+    let clock = Clock::get()?;
+
+    msg!("=== Phase 0: Verify Proof + Create Pending (Claim Escrow Yield) ===");
+
+    // 1. The claim must match the yield fixed at settlement
+    require!(order_yield.accrued > 0, CloakCraftError::NoEscrowYieldOwed);
+    require!(yield_amount == order_yield.accrued, CloakCraftError::InvalidAmount);
+
+    // 2. Verify ZK proof (knowledge of yield_key behind the tag; bonus note in the pool token)
+    let mut yield_bytes = [0u8; 32];
+    yield_bytes[24..].copy_from_slice(&yield_amount.to_be_bytes());
+
+    let public_inputs = vec![
+        order_yield.yield_tag,
+        pubkey_to_field(&order_yield.key()),
+        bonus_commitment,
+        yield_bytes,
+        pubkey_to_field(&ctx.accounts.pool.token_mint),
+    ];
+
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "ClaimEscrowYield",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
+    msg!("✅ ZK proof verified");
+
+    // 3. Initialize pending operation PDA
+    let pending_op = &mut ctx.accounts.pending_operation;
+    pending_op.bump = ctx.bumps.pending_operation;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
+    pending_op.relayer = ctx.accounts.relayer.key();
+    pending_op.operation_type = operation_types::CLAIM_ESCROW_YIELD;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
+    pending_op.created_at = clock.unix_timestamp;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
+    pending_op.proof_verified = true;
+
+    // No input notes; store order yield record as input pool (binds Phase 3)
+    pending_op.num_inputs = 0;
+    pending_op.input_pools[0] = order_yield.key().to_bytes();
+    pending_op.inputs_verified_mask = 0;
+    pending_op.nullifier_completed_mask = 0;
+
+    // Store bonus note as output
+    pending_op.num_commitments = 1;
+    pending_op.pools[0] = ctx.accounts.pool.key().to_bytes();
+    pending_op.commitments[0] = bonus_commitment;
+    pending_op.output_amounts[0] = yield_amount;
+    pending_op.completed_mask = 0;
+
+    // output_amount = yield, swap_amount = principal
+    pending_op.output_amount = yield_amount;
+    pending_op.swap_amount = order_yield.principal;
+
+    msg!("Escrow principal: {}, yield: {}", order_yield.principal, yield_amount);
+    msg!("Phase 0 complete: ZK proof verified, pending operation created");
+    msg!("Next: Phase 3 - execute_claim_escrow_yield");
+
+    Ok(())
+}
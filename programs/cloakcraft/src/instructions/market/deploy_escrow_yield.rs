@@ -0,0 +1,92 @@
+//! Deploy idle escrow into the market's yield adapter
+//!
+//! The policy authority moves up to the deployable share of opted-in escrow
+//! from the offer token vault into the adapter. The adapter's deposit
+//! instruction is CPI'd with the pool PDA as signer; the vault must drop by
+//! exactly `amount`.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::state::{Pool, EscrowYieldPolicy};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::escrow_yield::invoke_yield_adapter;
+
+#[derive(Accounts)]
+pub struct DeployEscrowYield<'info> {
+    /// Escrow yield policy
+    #[account(
+        mut,
+        seeds = [seeds::ESCROW_YIELD, pool.key().as_ref()],
+        bump = escrow_yield_policy.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+        constraint = escrow_yield_policy.enabled @ CloakCraftError::EscrowYieldDisabled,
+    )]
+    pub escrow_yield_policy: Box<Account<'info, EscrowYieldPolicy>>,
+
+    /// Offer token pool (vault authority)
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Offer token vault
+    #[account(
+        mut,
+        seeds = [seeds::VAULT, pool.token_mint.as_ref()],
+        bump = pool.vault_bump,
+    )]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Yield adapter program
+    /// CHECK: Must be the policy's adapter program
+    #[account(
+        executable,
+        constraint = adapter_program.key() == escrow_yield_policy.adapter_program @ CloakCraftError::InvalidYieldAdapter,
+    )]
+    pub adapter_program: UncheckedAccount<'info>,
+
+    /// Policy authority
+    pub authority: Signer<'info>,
+
+    // Adapter accounts are passed via remaining_accounts
+}
+
+/// Deploy escrow into the yield adapter
+///
+/// # Arguments
+/// * `amount` - Tokens to deploy
+/// * `adapter_data` - Adapter deposit instruction data
+pub fn deploy_escrow_yield<'info>(
+    ctx: Context<'_, '_, '_, 'info, DeployEscrowYield<'info>>,
+    amount: u64,
+    adapter_data: Vec<u8>,
+) -> Result<()> {
+    require!(amount > 0, CloakCraftError::InvalidAmount);
+
+    let policy = &mut ctx.accounts.escrow_yield_policy;
+    let deployed = policy.deployed
+        .checked_add(amount)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    require!(deployed <= policy.deployable(), CloakCraftError::EscrowYieldDeployExceeded);
+
+    let balance_before = ctx.accounts.token_vault.amount;
+    invoke_yield_adapter(
+        &ctx.accounts.adapter_program.to_account_info(),
+        &ctx.accounts.pool,
+        ctx.remaining_accounts,
+        adapter_data,
+    )?;
+    ctx.accounts.token_vault.reload()?;
+    require!(
+        balance_before.checked_sub(ctx.accounts.token_vault.amount) == Some(amount),
+        CloakCraftError::AdapterBalanceMismatch
+    );
+
+    policy.deployed = deployed;
+    msg!("Escrow deployed: {} (total {} of {} deployable)", amount, deployed, policy.deployable());
+
+    Ok(())
+}
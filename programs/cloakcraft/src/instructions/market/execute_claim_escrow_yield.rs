@@ -0,0 +1,56 @@
+//! Execute Claim Escrow Yield (Phase 3)
+//!
+//! Marks the order yield record claimed. The yield is already in the offer
+//! token vault and counted in the pool's shielded supply (added when it was
+//! recalled), so it backs the bonus note created in Phase 4 as is.
+
+use anchor_lang::prelude::*;
+
+use crate::errors::CloakCraftError;
+use crate::state::{OrderYield, PendingOperation, OperationKind};
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct ExecuteClaimEscrowYield<'info> {
+    /// Order yield record bound in Phase 0
+    #[account(
+        mut,
+        constraint = order_yield.key().to_bytes() == pending_operation.input_pools[0] @ CloakCraftError::InvalidEscrowYieldPolicy,
+        constraint = !order_yield.claimed @ CloakCraftError::OrderYieldAlreadyClaimed,
+    )]
+    pub order_yield: Box<Account<'info, OrderYield>>,
+
+    /// Pending operation PDA (from Phase 0)
+    #[account(
+        mut,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump = pending_operation.bump,
+        constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (must match pending operation)
+    #[account(
+        constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
+    )]
+    pub relayer: Signer<'info>,
+}
+
+/// Phase 3: Mark the escrow yield claimed
+pub fn execute_claim_escrow_yield(
+    ctx: Context<ExecuteClaimEscrowYield>,
+    _operation_id: [u8; 32],
+) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::ClaimEscrowYield)?;
+
+    msg!("=== Phase 3: Execute Claim Escrow Yield ===");
+
+    // Prevents a second claim for this order
+    ctx.accounts.order_yield.claimed = true;
+
+    msg!("✅ Escrow yield claimed: {}", ctx.accounts.pending_operation.output_amount);
+    msg!("Phase 3 complete");
+    msg!("Next: Phase 4 - create_commitment for bonus note");
+
+    Ok(())
+}
@@ -1,13 +1,15 @@
 //! Fill an order atomically
 //!
 //! Uses Light Protocol for nullifier and commitment storage.
+//! Orders opted into escrow yield settle their accrued yield here.
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, Order, OrderStatus, VerificationKey, PoolCommitmentCounter, LightValidityProof, LightAddressTreeInfo};
+use crate::state::{Pool, NullifierDomain, Order, OrderStatus, EscrowYieldPolicy, VerificationKey, PoolCommitmentCounter, LightValidityProof, LightAddressTreeInfo, VIEW_TAG_SIZE, RootRegistry, TreeRegistry};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 use crate::helpers::verify_groth16_proof;
+use crate::helpers::escrow_yield::settle_order_yield;
 use crate::light_cpi::{create_spend_nullifier_account, create_commitment_account, vec_to_fixed_note};
 
 /// Parameters for Light Protocol operations in fill order
@@ -56,7 +58,7 @@ pub struct FillOrder<'info> {
         seeds = [PoolCommitmentCounter::SEEDS_PREFIX, maker_pool.key().as_ref()],
         bump = maker_commitment_counter.bump,
     )]
-    pub maker_commitment_counter: Account<'info, PoolCommitmentCounter>,
+    pub maker_commitment_counter: Box<Account<'info, PoolCommitmentCounter>>,
 
     /// Taker's payment token pool (boxed to reduce stack usage)
     #[account(
@@ -72,7 +74,7 @@ pub struct FillOrder<'info> {
         seeds = [PoolCommitmentCounter::SEEDS_PREFIX, taker_pool.key().as_ref()],
         bump = taker_commitment_counter.bump,
     )]
-    pub taker_commitment_counter: Account<'info, PoolCommitmentCounter>,
+    pub taker_commitment_counter: Box<Account<'info, PoolCommitmentCounter>>,
 
     /// Order being filled
     #[account(
@@ -81,19 +83,65 @@ pub struct FillOrder<'info> {
         bump = order.bump,
         constraint = order.is_open() @ CloakCraftError::OrderAlreadyFilled,
     )]
-    pub order: Account<'info, Order>,
+    pub order: Box<Account<'info, Order>>,
 
     /// Verification key (boxed to reduce stack usage)
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
+    /// Order yield PDA (may be uninitialized: order not opted into escrow yield)
+    /// CHECK: Address derived from the order ID; deserialized only if initialized
+    #[account(
+        mut,
+        seeds = [seeds::ORDER_YIELD, order_id.as_ref()],
+        bump,
+    )]
+    pub order_yield: UncheckedAccount<'info>,
+
+    /// Maker pool's escrow yield policy (required if the order opted in)
+    #[account(
+        mut,
+        seeds = [seeds::ESCROW_YIELD, maker_pool.key().as_ref()],
+        bump = escrow_yield_policy.bump,
+    )]
+    pub escrow_yield_policy: Option<Box<Account<'info, EscrowYieldPolicy>>>,
+
     /// Relayer (pays for compressed account creation)
     #[account(mut)]
     pub relayer: Signer<'info>,
 
+    /// Maker pool root registry (the taker's output is appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, maker_pool.key().as_ref()],
+        bump,
+    )]
+    pub maker_root_registry: UncheckedAccount<'info>,
+
+    /// Taker pool root registry (the maker's output is appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, taker_pool.key().as_ref()],
+        bump,
+    )]
+    pub taker_root_registry: UncheckedAccount<'info>,
+
+    /// Tree registry (once initialized, the output tree must be registered
+    /// and its writes are counted toward TreeNearCapacity)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: UncheckedAccount<'info>,
+
     // Light Protocol accounts are passed via remaining_accounts
 }
 
@@ -157,8 +205,11 @@ pub fn fill_order<'info>(
             params.escrow_nullifier_address_tree_info.clone(),
             params.output_tree_index,
             maker_pool.key(),
+            maker_pool.active_trees(Clock::get()?.slot),
+            NullifierDomain::Spend,
             escrow_nullifier,
         )?;
+        TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &maker_pool.active_trees(Clock::get()?.slot).state_tree, 1)?;
 
         // Taker nullifier
         create_spend_nullifier_account(
@@ -168,16 +219,17 @@ pub fn fill_order<'info>(
             params.taker_nullifier_address_tree_info.clone(),
             params.output_tree_index,
             taker_pool.key(),
+            taker_pool.active_trees(Clock::get()?.slot),
+            NullifierDomain::Spend,
             taker_nullifier,
         )?;
+        TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &taker_pool.active_trees(Clock::get()?.slot).state_tree, 1)?;
     }
     // 4. Create output commitments via Light Protocol
     // Encrypted note is stored inline for direct scanning
 
     // Maker receives taker's token (in taker_pool)
-    let maker_leaf_index = taker_commitment_counter.next_leaf_index;
-    taker_commitment_counter.next_leaf_index += 1;
-    taker_commitment_counter.total_commitments += 1;
+    let maker_leaf_index = taker_commitment_counter.allocate();
 
     if let Some(ref params) = light_params {
         let maker_encrypted_note = encrypted_notes.get(0).cloned().unwrap_or_default();
@@ -189,18 +241,20 @@ pub fn fill_order<'info>(
             params.maker_commitment_address_tree_info.clone(),
             params.output_tree_index,
             taker_pool.key(),
+            taker_pool.active_trees(Clock::get()?.slot),
             maker_out_commitment,
             maker_leaf_index,
             [0u8; 64], // Internal operation - use spending key for decryption
             maker_note_arr,
             maker_note_len,
+            [0u8; VIEW_TAG_SIZE],
         )?;
+        TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &taker_pool.active_trees(Clock::get()?.slot).state_tree, 1)?;
+        RootRegistry::append_to(&ctx.accounts.taker_root_registry, maker_out_commitment, Clock::get()?.slot)?;
     }
 
     // Taker receives maker's token (in maker_pool)
-    let taker_leaf_index = maker_commitment_counter.next_leaf_index;
-    maker_commitment_counter.next_leaf_index += 1;
-    maker_commitment_counter.total_commitments += 1;
+    let taker_leaf_index = maker_commitment_counter.allocate();
 
     if let Some(ref params) = light_params {
         let taker_encrypted_note = encrypted_notes.get(1).cloned().unwrap_or_default();
@@ -212,15 +266,25 @@ pub fn fill_order<'info>(
             params.taker_commitment_address_tree_info.clone(),
             params.output_tree_index,
             maker_pool.key(),
+            maker_pool.active_trees(Clock::get()?.slot),
             taker_out_commitment,
             taker_leaf_index,
             [0u8; 64], // Internal operation - use spending key for decryption
             taker_note_arr,
             taker_note_len,
+            [0u8; VIEW_TAG_SIZE],
         )?;
+        TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &maker_pool.active_trees(Clock::get()?.slot).state_tree, 1)?;
+        RootRegistry::append_to(&ctx.accounts.maker_root_registry, taker_out_commitment, Clock::get()?.slot)?;
     }
 
-    // 5. Mark order as filled
+    // 5. Settle escrow yield (the escrow becomes the taker's note)
+    settle_order_yield(
+        &ctx.accounts.order_yield,
+        ctx.accounts.escrow_yield_policy.as_deref_mut(),
+    )?;
+
+    // 6. Mark order as filled
     order.status = OrderStatus::Filled;
     Ok(())
 }
@@ -3,7 +3,23 @@
 mod create_order;
 mod fill_order;
 mod cancel_order;
+mod modify_order;
+mod create_escrow_yield_policy;
+mod update_escrow_yield_policy;
+mod opt_in_escrow_yield;
+mod deploy_escrow_yield;
+mod recall_escrow_yield;
+mod create_pending_with_proof_claim_escrow_yield;
+mod execute_claim_escrow_yield;
 
 pub use create_order::*;
 pub use fill_order::*;
 pub use cancel_order::*;
+pub use modify_order::*;
+pub use create_escrow_yield_policy::*;
+pub use update_escrow_yield_policy::*;
+pub use opt_in_escrow_yield::*;
+pub use deploy_escrow_yield::*;
+pub use recall_escrow_yield::*;
+pub use create_pending_with_proof_claim_escrow_yield::*;
+pub use execute_claim_escrow_yield::*;
@@ -0,0 +1,97 @@
+//! Modify an open order in place
+//!
+//! Updates the terms hash and expiry of an existing order while keeping the
+//! same escrow commitment. The maker proves ownership of the escrow note
+//! without spending it, so no nullifier or new commitment is created.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Order, VerificationKey};
+use crate::constants::{circuits, seeds};
+use crate::errors::CloakCraftError;
+use crate::helpers::verify_groth16_proof;
+
+#[derive(Accounts)]
+#[instruction(proof: Vec<u8>, order_id: [u8; 32])]
+pub struct ModifyOrder<'info> {
+    /// Order being modified
+    #[account(
+        mut,
+        seeds = [seeds::ORDER, order_id.as_ref()],
+        bump = order.bump,
+        constraint = order.is_open() @ CloakCraftError::OrderAlreadyFilled,
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    /// Verification key for the modify circuit (boxed to reduce stack usage)
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, circuits::MARKET_ORDER_MODIFY.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Relayer/maker submitting the modification
+    pub relayer: Signer<'info>,
+}
+
+pub fn modify_order(
+    ctx: Context<ModifyOrder>,
+    proof: Vec<u8>,
+    order_id: [u8; 32],
+    new_terms_hash: [u8; 32],
+    new_expiry: i64,
+) -> Result<()> {
+    let order = &mut ctx.accounts.order;
+    let clock = Clock::get()?;
+
+    // 1. Order must still be live and the new expiry must be in the future
+    require!(
+        !order.is_expired(clock.unix_timestamp),
+        CloakCraftError::OrderExpired
+    );
+    require!(
+        new_expiry > clock.unix_timestamp,
+        CloakCraftError::InvalidOrderTerms
+    );
+
+    // 2. Verify maker proof (proves ownership of escrow and binds old -> new terms)
+    let public_inputs = build_modify_order_inputs(
+        &order_id,
+        &order.escrow_commitment,
+        &order.terms_hash,
+        &new_terms_hash,
+        new_expiry,
+    );
+
+    verify_groth16_proof(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "ModifyOrder",
+    )?;
+
+    // 3. Update terms in place (escrow commitment unchanged)
+    msg!("Order terms updated, expiry {} -> {}", order.expiry, new_expiry);
+    order.terms_hash = new_terms_hash;
+    order.expiry = new_expiry;
+    Ok(())
+}
+
+fn build_modify_order_inputs(
+    order_id: &[u8; 32],
+    escrow_commitment: &[u8; 32],
+    old_terms_hash: &[u8; 32],
+    new_terms_hash: &[u8; 32],
+    new_expiry: i64,
+) -> Vec<[u8; 32]> {
+    let mut expiry_bytes = [0u8; 32];
+    expiry_bytes[..8].copy_from_slice(&new_expiry.to_le_bytes());
+    vec![
+        *order_id,
+        *escrow_commitment,
+        *old_terms_hash,
+        *new_terms_hash,
+        expiry_bytes,
+    ]
+}
@@ -0,0 +1,122 @@
+//! Opt an open order into escrow yield
+//!
+//! The maker proves the escrow commitment holds `escrow_amount` of the offer
+//! token and commits to a claim tag `hash(yield_key, order_id)`. Revealing
+//! the escrow amount is the price of earning yield on it.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, Order, EscrowYieldPolicy, OrderYield, VerificationKey};
+use crate::constants::{circuits, seeds};
+use crate::errors::CloakCraftError;
+use crate::helpers::verify_groth16_proof;
+use crate::helpers::field::pubkey_to_field;
+
+#[derive(Accounts)]
+#[instruction(order_id: [u8; 32])]
+pub struct OptInEscrowYield<'info> {
+    /// Order being opted in
+    #[account(
+        seeds = [seeds::ORDER, order_id.as_ref()],
+        bump = order.bump,
+        constraint = order.is_open() @ CloakCraftError::OrderAlreadyFilled,
+    )]
+    pub order: Box<Account<'info, Order>>,
+
+    /// Offer token pool (holds the escrow note)
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Escrow yield policy for the offer token pool
+    #[account(
+        mut,
+        seeds = [seeds::ESCROW_YIELD, pool.key().as_ref()],
+        bump = escrow_yield_policy.bump,
+        constraint = escrow_yield_policy.enabled @ CloakCraftError::EscrowYieldDisabled,
+    )]
+    pub escrow_yield_policy: Box<Account<'info, EscrowYieldPolicy>>,
+
+    /// Order yield record
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + OrderYield::INIT_SPACE,
+        seeds = [seeds::ORDER_YIELD, order_id.as_ref()],
+        bump
+    )]
+    pub order_yield: Box<Account<'info, OrderYield>>,
+
+    /// Verification key for the opt-in circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, circuits::MARKET_ESCROW_YIELD_OPT_IN.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Payer
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Opt an order into escrow yield
+///
+/// # Arguments
+/// * `order_id` - Order to opt in
+/// * `proof` - Proof that the escrow commitment holds `escrow_amount` of the pool token
+/// * `escrow_amount` - Escrowed principal
+/// * `yield_tag` - hash(yield_key, order_id), proven again to claim
+pub fn opt_in_escrow_yield(
+    ctx: Context<OptInEscrowYield>,
+    order_id: [u8; 32],
+    proof: Vec<u8>,
+    escrow_amount: u64,
+    yield_tag: [u8; 32],
+) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(
+        !ctx.accounts.order.is_expired(clock.unix_timestamp),
+        CloakCraftError::OrderExpired
+    );
+    require!(escrow_amount > 0, CloakCraftError::InvalidAmount);
+
+    // 1. Verify the escrow opening
+    let mut amount_bytes = [0u8; 32];
+    amount_bytes[24..].copy_from_slice(&escrow_amount.to_be_bytes());
+
+    let public_inputs = vec![
+        order_id,
+        ctx.accounts.order.escrow_commitment,
+        pubkey_to_field(&ctx.accounts.pool.token_mint),
+        amount_bytes,
+        yield_tag,
+    ];
+    verify_groth16_proof(&proof, &ctx.accounts.verification_key.vk_data, &public_inputs, "EscrowYieldOptIn")?;
+
+    // 2. Record the principal against the policy
+    let policy = &mut ctx.accounts.escrow_yield_policy;
+    policy.total_principal = policy.total_principal
+        .checked_add(escrow_amount)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    let order_yield = &mut ctx.accounts.order_yield;
+    order_yield.order_id = order_id;
+    order_yield.policy = policy.key();
+    order_yield.principal = escrow_amount;
+    order_yield.entry_yield_per_principal = policy.yield_per_principal;
+    order_yield.yield_tag = yield_tag;
+    order_yield.accrued = 0;
+    order_yield.settled = false;
+    order_yield.claimed = false;
+    order_yield.bump = ctx.bumps.order_yield;
+
+    msg!("Order opted into escrow yield: principal {}", escrow_amount);
+
+    Ok(())
+}
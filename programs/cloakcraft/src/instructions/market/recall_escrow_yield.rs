@@ -0,0 +1,102 @@
+//! Recall escrow from the yield adapter and harvest yield
+//!
+//! The policy authority withdraws `principal` (0 to only harvest) from the
+//! adapter. The vault must grow by at least `principal`; any excess is yield,
+//! distributed pro rata over opted-in principal and added to the pool's
+//! shielded supply so it backs the makers' bonus notes.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::state::{Pool, EscrowYieldPolicy};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::escrow_yield::invoke_yield_adapter;
+
+#[derive(Accounts)]
+pub struct RecallEscrowYield<'info> {
+    /// Escrow yield policy
+    #[account(
+        mut,
+        seeds = [seeds::ESCROW_YIELD, pool.key().as_ref()],
+        bump = escrow_yield_policy.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub escrow_yield_policy: Box<Account<'info, EscrowYieldPolicy>>,
+
+    /// Offer token pool (vault authority)
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Offer token vault
+    #[account(
+        mut,
+        seeds = [seeds::VAULT, pool.token_mint.as_ref()],
+        bump = pool.vault_bump,
+    )]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Yield adapter program
+    /// CHECK: Must be the policy's adapter program
+    #[account(
+        executable,
+        constraint = adapter_program.key() == escrow_yield_policy.adapter_program @ CloakCraftError::InvalidYieldAdapter,
+    )]
+    pub adapter_program: UncheckedAccount<'info>,
+
+    /// Policy authority
+    pub authority: Signer<'info>,
+
+    // Adapter accounts are passed via remaining_accounts
+}
+
+/// Recall escrow from the yield adapter
+///
+/// # Arguments
+/// * `principal` - Deployed tokens to recall (0 = harvest only)
+/// * `adapter_data` - Adapter withdraw instruction data
+pub fn recall_escrow_yield<'info>(
+    ctx: Context<'_, '_, '_, 'info, RecallEscrowYield<'info>>,
+    principal: u64,
+    adapter_data: Vec<u8>,
+) -> Result<()> {
+    require!(
+        principal <= ctx.accounts.escrow_yield_policy.deployed,
+        CloakCraftError::InvalidAmount
+    );
+
+    let balance_before = ctx.accounts.token_vault.amount;
+    invoke_yield_adapter(
+        &ctx.accounts.adapter_program.to_account_info(),
+        &ctx.accounts.pool,
+        ctx.remaining_accounts,
+        adapter_data,
+    )?;
+    ctx.accounts.token_vault.reload()?;
+    let received = ctx.accounts.token_vault.amount
+        .checked_sub(balance_before)
+        .ok_or(CloakCraftError::AdapterBalanceMismatch)?;
+    require!(received >= principal, CloakCraftError::AdapterBalanceMismatch);
+
+    let policy = &mut ctx.accounts.escrow_yield_policy;
+    policy.deployed -= principal;
+
+    // Yield with no opted-in principal stays unaccounted (vault surplus)
+    let distributed = policy.accrue(received - principal)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    let pool = &mut ctx.accounts.pool;
+    pool.total_shielded = pool.total_shielded
+        .checked_add(distributed)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    msg!(
+        "Escrow recalled: {}, yield {} (deployed {})",
+        principal, distributed, policy.deployed
+    );
+
+    Ok(())
+}
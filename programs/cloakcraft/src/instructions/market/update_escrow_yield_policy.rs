@@ -0,0 +1,46 @@
+//! Update a market's escrow yield policy
+//!
+//! Disabling stops new opt-ins and deployments; deployed escrow can still be
+//! recalled and opted-in orders still settle and claim.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{EscrowYieldPolicy, MAX_ESCROW_DEPLOY_BPS};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct UpdateEscrowYieldPolicy<'info> {
+    /// Escrow yield policy
+    #[account(
+        mut,
+        seeds = [seeds::ESCROW_YIELD, escrow_yield_policy.pool.as_ref()],
+        bump = escrow_yield_policy.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub escrow_yield_policy: Box<Account<'info, EscrowYieldPolicy>>,
+
+    /// Policy authority
+    pub authority: Signer<'info>,
+}
+
+/// Update the escrow yield policy
+///
+/// # Arguments
+/// * `enabled` - Accept new opt-ins and deployments
+/// * `max_deploy_bps` - Share of opted-in principal that may be deployed (max 9000)
+pub fn update_escrow_yield_policy(
+    ctx: Context<UpdateEscrowYieldPolicy>,
+    enabled: bool,
+    max_deploy_bps: u16,
+) -> Result<()> {
+    require!(max_deploy_bps <= MAX_ESCROW_DEPLOY_BPS, CloakCraftError::InvalidAmount);
+
+    let policy = &mut ctx.accounts.escrow_yield_policy;
+    policy.enabled = enabled;
+    policy.max_deploy_bps = max_deploy_bps;
+
+    msg!("Escrow yield policy updated: enabled {}, max deploy {} bps", enabled, max_deploy_bps);
+
+    Ok(())
+}
@@ -8,6 +8,11 @@ pub mod admin;
 pub mod generic;
 pub mod perps;
 pub mod voting;
+pub mod emissions;
+pub mod funding;
+pub mod buyback;
+pub mod savings;
+pub mod credits;
 
 pub use pool::*;
 pub use adapter::*;
@@ -17,3 +22,8 @@ pub use admin::*;
 pub use generic::*;
 pub use perps::*;
 pub use voting::*;
+pub use emissions::*;
+pub use funding::*;
+pub use buyback::*;
+pub use savings::*;
+pub use credits::*;
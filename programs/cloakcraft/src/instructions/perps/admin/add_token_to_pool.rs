@@ -88,7 +88,8 @@ pub fn add_token_to_pool(ctx: Context<AddTokenToPool>, pyth_feed_id: [u8; 32]) -
         decimals: token_mint.decimals,
         is_active: true,
         vault_bump: 0, // ATA, not a custom PDA
-        _reserved: [0; 5],
+        target_weight_bps: 0,
+        _reserved: [0; 3],
     };
 
     perps_pool.num_tokens += 1;
@@ -0,0 +1,100 @@
+//! Begin a perps pool migration
+//!
+//! Freezes a perps pool so its vaults can be moved to a new `PerpsPool`
+//! (see `state::perps_pool_migration`). The old pool stops trading for good;
+//! the new pool is frozen until `finalize_pool_migration` registers the LP
+//! conversion rate.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{PerpsPool, PerpsPoolMigration};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct BeginPoolMigration<'info> {
+    /// Pool being migrated away from
+    #[account(
+        mut,
+        seeds = [seeds::PERPS_POOL, old_pool.pool_id.as_ref()],
+        bump = old_pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+        constraint = !old_pool.is_migrating @ CloakCraftError::PerpsPoolMigrating,
+    )]
+    pub old_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Pool receiving the assets (same authority, no LP yet)
+    #[account(
+        mut,
+        seeds = [seeds::PERPS_POOL, new_pool.pool_id.as_ref()],
+        bump = new_pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+        constraint = !new_pool.is_migrating @ CloakCraftError::PerpsPoolMigrating,
+        constraint = new_pool.key() != old_pool.key() @ CloakCraftError::InvalidMigrationTarget,
+    )]
+    pub new_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Migration record (created here)
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PerpsPoolMigration::INIT_SPACE,
+        seeds = [seeds::PERPS_MIGRATION, old_pool.key().as_ref()],
+        bump
+    )]
+    pub migration: Account<'info, PerpsPoolMigration>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+
+    /// Payer
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn begin_pool_migration(ctx: Context<BeginPoolMigration>) -> Result<()> {
+    let old_pool = &mut ctx.accounts.old_pool;
+    let new_pool = &mut ctx.accounts.new_pool;
+    let num_tokens = old_pool.num_tokens as usize;
+
+    // Nothing may be locked in positions: their margin lives in these vaults
+    require!(
+        old_pool.tokens[..num_tokens].iter().all(|t| t.locked == 0),
+        CloakCraftError::PerpsPoolHasOpenPositions
+    );
+
+    // Same tokens at the same indices, so vaults move index by index
+    require!(
+        new_pool.lp_supply == 0
+            && new_pool.num_tokens == old_pool.num_tokens
+            && old_pool.tokens[..num_tokens]
+                .iter()
+                .zip(new_pool.tokens[..num_tokens].iter())
+                .all(|(old, new)| old.mint == new.mint),
+        CloakCraftError::InvalidMigrationTarget
+    );
+
+    old_pool.is_active = false;
+    old_pool.is_migrating = true;
+    new_pool.is_migrating = true;
+
+    let migration = &mut ctx.accounts.migration;
+    migration.old_pool = old_pool.key();
+    migration.new_pool = new_pool.key();
+    migration.started_at = Clock::get()?.unix_timestamp;
+    migration.migrated_vaults = 0;
+    migration.finalized = false;
+    migration.bump = ctx.bumps.migration;
+
+    msg!(
+        "Perps pool migration started: {} -> {} ({} tokens)",
+        migration.old_pool,
+        migration.new_pool,
+        num_tokens
+    );
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+//! Delist a trading market
+//!
+//! Moves a market to settle-only mode: no new positions (or limit order
+//! fills) can open, while existing positions still close and liquidate at
+//! the oracle price. After the grace period, `force_settle_market` fixes the
+//! final price every remaining position settles at.
+//!
+//! Delisting cannot be undone.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{PerpsPool, PerpsMarket, MarketStatus};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+/// Emitted when a market is delisted
+#[event]
+pub struct MarketDelisted {
+    pub perps_market: Pubkey,
+    pub market_id: [u8; 32],
+    pub settle_after: i64,
+}
+
+#[derive(Accounts)]
+pub struct DelistMarket<'info> {
+    /// Perps pool account (boxed due to large size)
+    #[account(
+        seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
+        bump = perps_pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub perps_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Market account
+    #[account(
+        mut,
+        seeds = [seeds::PERPS_MARKET, perps_pool.key().as_ref(), perps_market.market_id.as_ref()],
+        bump = perps_market.bump,
+        constraint = perps_market.pool == perps_pool.key() @ CloakCraftError::PerpsMarketNotFound,
+        constraint = perps_market.status == MarketStatus::Active @ CloakCraftError::PerpsMarketDelisted,
+    )]
+    pub perps_market: Account<'info, PerpsMarket>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+pub fn delist_market(ctx: Context<DelistMarket>, grace_period_seconds: i64) -> Result<()> {
+    require!(grace_period_seconds >= 0, CloakCraftError::InvalidSettleGracePeriod);
+
+    let perps_market = &mut ctx.accounts.perps_market;
+    let now = Clock::get()?.unix_timestamp;
+
+    perps_market.is_active = false;
+    perps_market.status = MarketStatus::SettleOnly;
+    perps_market.delisted_at = now;
+    perps_market.settle_after = now
+        .checked_add(grace_period_seconds)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    emit!(MarketDelisted {
+        perps_market: perps_market.key(),
+        market_id: perps_market.market_id,
+        settle_after: perps_market.settle_after,
+    });
+
+    msg!(
+        "Market {:?} delisted, force settle after {}",
+        perps_market.market_id,
+        perps_market.settle_after
+    );
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! Finalize a perps pool migration
+//!
+//! Once every vault has moved, registers the LP conversion rate
+//! (`new_lp_supply` new LP for the old pool's whole LP supply), reserves that
+//! supply in the new pool and unfreezes it. Old LP notes then convert with
+//! `create_pending_with_proof_convert_lp_note`.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{PerpsPool, PerpsPoolMigration};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+/// Emitted when a perps pool migration registers its LP conversion rate
+#[event]
+pub struct PerpsPoolMigrated {
+    pub old_pool: Pubkey,
+    pub new_pool: Pubkey,
+    pub old_lp_supply: u64,
+    pub new_lp_supply: u64,
+}
+
+#[derive(Accounts)]
+pub struct FinalizePoolMigration<'info> {
+    /// Pool being migrated away from
+    #[account(
+        seeds = [seeds::PERPS_POOL, old_pool.pool_id.as_ref()],
+        bump = old_pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub old_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Pool receiving the assets
+    #[account(
+        mut,
+        seeds = [seeds::PERPS_POOL, new_pool.pool_id.as_ref()],
+        bump = new_pool.bump,
+        constraint = new_pool.key() == migration.new_pool @ CloakCraftError::InvalidMigrationTarget,
+    )]
+    pub new_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Migration record
+    #[account(
+        mut,
+        seeds = [seeds::PERPS_MIGRATION, old_pool.key().as_ref()],
+        bump = migration.bump,
+        constraint = !migration.finalized @ CloakCraftError::MigrationAlreadyFinalized,
+    )]
+    pub migration: Account<'info, PerpsPoolMigration>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+pub fn finalize_pool_migration(ctx: Context<FinalizePoolMigration>, new_lp_supply: u64) -> Result<()> {
+    let old_pool = &ctx.accounts.old_pool;
+    let new_pool = &mut ctx.accounts.new_pool;
+    let migration = &mut ctx.accounts.migration;
+
+    require!(
+        migration.all_vaults_migrated(old_pool.num_tokens),
+        CloakCraftError::VaultsNotMigrated
+    );
+    // Outstanding old LP must convert to something, and nothing from nothing
+    require!(
+        (old_pool.lp_supply == 0) == (new_lp_supply == 0),
+        CloakCraftError::LpConversionMismatch
+    );
+
+    migration.old_lp_supply = old_pool.lp_supply;
+    migration.new_lp_supply = new_lp_supply;
+    migration.finalized = true;
+
+    // Reserve every converted note's LP up front, so the new pool prices
+    // deposits against all of the migrated assets
+    new_pool.lp_supply = new_pool.lp_supply
+        .checked_add(new_lp_supply)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    new_pool.is_migrating = false;
+
+    emit!(PerpsPoolMigrated {
+        old_pool: migration.old_pool,
+        new_pool: migration.new_pool,
+        old_lp_supply: migration.old_lp_supply,
+        new_lp_supply,
+    });
+
+    msg!(
+        "Perps pool migration finalized: {} old LP -> {} new LP",
+        migration.old_lp_supply,
+        new_lp_supply
+    );
+
+    Ok(())
+}
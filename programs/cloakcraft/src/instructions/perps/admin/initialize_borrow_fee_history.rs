@@ -0,0 +1,49 @@
+//! Create a perps pool's borrow fee checkpoint history
+//!
+//! Once it exists, keepers pass it to `update_perps_borrow_fees` to record
+//! hourly accumulator checkpoints.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{BorrowFeeHistory, PerpsPool};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct InitializeBorrowFeeHistory<'info> {
+    /// Perps pool the history belongs to
+    #[account(
+        seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
+        bump = perps_pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub perps_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Borrow fee checkpoint history
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BorrowFeeHistory::INIT_SPACE,
+        seeds = [seeds::BORROW_FEE_HISTORY, perps_pool.key().as_ref()],
+        bump,
+    )]
+    pub borrow_fee_history: Box<Account<'info, BorrowFeeHistory>>,
+
+    /// Pool authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_borrow_fee_history(ctx: Context<InitializeBorrowFeeHistory>) -> Result<()> {
+    let history = &mut ctx.accounts.borrow_fee_history;
+    history.perps_pool = ctx.accounts.perps_pool.key();
+    history.total_checkpoints = 0;
+    history.bump = ctx.bumps.borrow_fee_history;
+
+    msg!("Borrow fee history initialized for perps pool {}", history.perps_pool);
+
+    Ok(())
+}
@@ -9,6 +9,7 @@ use anchor_spl::token::Token;
 
 use crate::state::PerpsPool;
 use crate::constants::seeds;
+use crate::errors::CloakCraftError;
 
 #[derive(Accounts)]
 #[instruction(pool_id: Pubkey)]
@@ -75,6 +76,16 @@ pub struct InitializePerpsPoolParams {
     pub base_borrow_rate_bps: u16,
     /// Maximum imbalance fee in basis points (e.g., 3 = 0.03%)
     pub max_imbalance_fee_bps: u16,
+    /// Position fee for resting limit orders in basis points (<= position_fee_bps)
+    pub maker_fee_bps: u16,
+    /// Maximum liquidation urgency bonus in basis points (e.g., 100 = 1%)
+    pub liquidation_bonus_bps: u16,
+    /// Fee on rebalance swaps in basis points (e.g., 10 = 0.1%)
+    pub rebalance_fee_bps: u16,
+    /// Minimum position size (0 = no minimum)
+    pub min_position_size: u64,
+    /// Minimum position margin (0 = no minimum)
+    pub min_margin: u64,
 }
 
 impl Default for InitializePerpsPoolParams {
@@ -87,6 +98,11 @@ impl Default for InitializePerpsPoolParams {
             liquidation_penalty_bps: 50,
             base_borrow_rate_bps: 1,
             max_imbalance_fee_bps: 3,
+            maker_fee_bps: 2,
+            liquidation_bonus_bps: 100,
+            rebalance_fee_bps: 10,
+            min_position_size: 0,
+            min_margin: 0,
         }
     }
 }
@@ -195,6 +211,15 @@ pub fn initialize_perps_pool(
     perps_pool.liquidation_penalty_bps = params.liquidation_penalty_bps;
     perps_pool.base_borrow_rate_bps = params.base_borrow_rate_bps;
     perps_pool.max_imbalance_fee_bps = params.max_imbalance_fee_bps;
+    require!(
+        params.maker_fee_bps <= params.position_fee_bps,
+        CloakCraftError::InvalidMakerFee
+    );
+    perps_pool.maker_fee_bps = params.maker_fee_bps;
+    perps_pool.liquidation_bonus_bps = params.liquidation_bonus_bps;
+    perps_pool.rebalance_fee_bps = params.rebalance_fee_bps;
+    perps_pool.min_position_size = params.min_position_size;
+    perps_pool.min_margin = params.min_margin;
 
     // State
     perps_pool.is_active = true;
@@ -0,0 +1,115 @@
+//! Migrate a token vault
+//!
+//! Moves one token's vault balance from a migrating perps pool to the new
+//! pool's vault for the same token index, and its pool balance with it.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::state::{PerpsPool, PerpsPoolMigration};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+#[instruction(token_index: u8)]
+pub struct MigrateTokenVault<'info> {
+    /// Pool being migrated away from
+    #[account(
+        mut,
+        seeds = [seeds::PERPS_POOL, old_pool.pool_id.as_ref()],
+        bump = old_pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub old_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Pool receiving the assets
+    #[account(
+        mut,
+        seeds = [seeds::PERPS_POOL, new_pool.pool_id.as_ref()],
+        bump = new_pool.bump,
+        constraint = new_pool.key() == migration.new_pool @ CloakCraftError::InvalidMigrationTarget,
+    )]
+    pub new_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Migration record
+    #[account(
+        mut,
+        seeds = [seeds::PERPS_MIGRATION, old_pool.key().as_ref()],
+        bump = migration.bump,
+        constraint = !migration.finalized @ CloakCraftError::MigrationAlreadyFinalized,
+    )]
+    pub migration: Account<'info, PerpsPoolMigration>,
+
+    /// Old pool vault for the token
+    #[account(
+        mut,
+        constraint = token_index < old_pool.num_tokens @ CloakCraftError::InvalidTokenIndex,
+        constraint = old_pool.tokens[token_index as usize].vault == old_vault.key() @ CloakCraftError::InvalidVault,
+    )]
+    pub old_vault: Box<Account<'info, TokenAccount>>,
+
+    /// New pool vault for the token
+    #[account(
+        mut,
+        constraint = new_pool.tokens[token_index as usize].vault == new_vault.key() @ CloakCraftError::InvalidVault,
+    )]
+    pub new_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn migrate_token_vault(ctx: Context<MigrateTokenVault>, token_index: u8) -> Result<()> {
+    let bit = 1u8 << token_index;
+    require!(
+        ctx.accounts.migration.migrated_vaults & bit == 0,
+        CloakCraftError::VaultAlreadyMigrated
+    );
+
+    let vault_amount = ctx.accounts.old_vault.amount;
+    let old_pool = &mut ctx.accounts.old_pool;
+    let new_pool = &mut ctx.accounts.new_pool;
+    let index = token_index as usize;
+
+    // Pool accounting moves with the vault
+    let balance = old_pool.tokens[index].balance;
+    new_pool.tokens[index].balance = new_pool.tokens[index].balance
+        .checked_add(balance)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    old_pool.tokens[index].balance = 0;
+
+    // Old vault -> new vault (old perps pool PDA signs)
+    let pool_seeds = &[
+        seeds::PERPS_POOL,
+        old_pool.pool_id.as_ref(),
+        &[old_pool.bump],
+    ];
+    let signer_seeds = &[&pool_seeds[..]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.old_vault.to_account_info(),
+                to: ctx.accounts.new_vault.to_account_info(),
+                authority: old_pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        vault_amount,
+    )?;
+
+    ctx.accounts.migration.migrated_vaults |= bit;
+
+    msg!(
+        "Token {} vault migrated: {} tokens (pool balance {})",
+        token_index,
+        vault_amount,
+        balance
+    );
+
+    Ok(())
+}
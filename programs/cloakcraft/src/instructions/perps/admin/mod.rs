@@ -4,8 +4,18 @@ mod initialize_perps_pool;
 mod add_token_to_pool;
 mod add_market;
 mod update_pool_config;
+mod initialize_borrow_fee_history;
+mod delist_market;
+mod begin_pool_migration;
+mod migrate_token_vault;
+mod finalize_pool_migration;
 
 pub use initialize_perps_pool::*;
 pub use add_token_to_pool::*;
 pub use add_market::*;
 pub use update_pool_config::*;
+pub use initialize_borrow_fee_history::*;
+pub use delist_market::*;
+pub use begin_pool_migration::*;
+pub use migrate_token_vault::*;
+pub use finalize_pool_migration::*;
@@ -40,6 +40,16 @@ pub struct UpdatePoolConfigParams {
     pub base_borrow_rate_bps: Option<u16>,
     /// Maximum imbalance fee in basis points, None to keep current
     pub max_imbalance_fee_bps: Option<u16>,
+    /// Maker fee for resting limit orders in basis points, None to keep current
+    pub maker_fee_bps: Option<u16>,
+    /// Maximum liquidation urgency bonus in basis points, None to keep current
+    pub liquidation_bonus_bps: Option<u16>,
+    /// Rebalance swap fee in basis points, None to keep current
+    pub rebalance_fee_bps: Option<u16>,
+    /// Minimum position size, None to keep current
+    pub min_position_size: Option<u64>,
+    /// Minimum position margin, None to keep current
+    pub min_margin: Option<u64>,
     /// Pool active status, None to keep current
     pub is_active: Option<bool>,
 }
@@ -93,6 +103,37 @@ pub fn update_pool_config(
         msg!("Updated max_imbalance_fee_bps: {}", max_imbalance_fee_bps);
     }
 
+    if let Some(maker_fee_bps) = params.maker_fee_bps {
+        perps_pool.maker_fee_bps = maker_fee_bps;
+        msg!("Updated maker_fee_bps: {}", maker_fee_bps);
+    }
+
+    if let Some(liquidation_bonus_bps) = params.liquidation_bonus_bps {
+        perps_pool.liquidation_bonus_bps = liquidation_bonus_bps;
+        msg!("Updated liquidation_bonus_bps: {}", liquidation_bonus_bps);
+    }
+
+    if let Some(rebalance_fee_bps) = params.rebalance_fee_bps {
+        perps_pool.rebalance_fee_bps = rebalance_fee_bps;
+        msg!("Updated rebalance_fee_bps: {}", rebalance_fee_bps);
+    }
+
+    if let Some(min_position_size) = params.min_position_size {
+        perps_pool.min_position_size = min_position_size;
+        msg!("Updated min_position_size: {}", min_position_size);
+    }
+
+    if let Some(min_margin) = params.min_margin {
+        perps_pool.min_margin = min_margin;
+        msg!("Updated min_margin: {}", min_margin);
+    }
+
+    // Resting orders never pay more than market orders
+    require!(
+        perps_pool.maker_fee_bps <= perps_pool.position_fee_bps,
+        CloakCraftError::InvalidMakerFee
+    );
+
     if let Some(is_active) = params.is_active {
         perps_pool.is_active = is_active;
         msg!("Updated is_active: {}", is_active);
@@ -140,6 +181,53 @@ pub fn update_token_status(
     Ok(())
 }
 
+/// Set a token's target weight for rebalancing and imbalance fees
+#[derive(Accounts)]
+pub struct UpdateTokenTargetWeight<'info> {
+    /// Perps pool account (boxed due to large size)
+    #[account(
+        mut,
+        seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
+        bump = perps_pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized
+    )]
+    pub perps_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+pub fn update_token_target_weight(
+    ctx: Context<UpdateTokenTargetWeight>,
+    token_index: u8,
+    target_weight_bps: u16,
+) -> Result<()> {
+    let perps_pool = &mut ctx.accounts.perps_pool;
+
+    require!(
+        token_index < perps_pool.num_tokens,
+        CloakCraftError::InvalidTokenIndex
+    );
+
+    perps_pool.tokens[token_index as usize].target_weight_bps = target_weight_bps;
+
+    // Targets across all tokens must not exceed 100%
+    let total_target: u32 = perps_pool.tokens[..perps_pool.num_tokens as usize]
+        .iter()
+        .map(|t| t.target_weight_bps as u32)
+        .sum();
+    require!(total_target <= 10000, CloakCraftError::InvalidTargetWeight);
+
+    msg!(
+        "Token {} target weight updated: {} bps (total {} bps)",
+        token_index,
+        target_weight_bps,
+        total_target
+    );
+
+    Ok(())
+}
+
 /// Pause/unpause a specific market
 #[derive(Accounts)]
 pub struct UpdateMarketStatus<'info> {
@@ -156,7 +244,8 @@ pub struct UpdateMarketStatus<'info> {
         mut,
         seeds = [seeds::PERPS_MARKET, perps_pool.key().as_ref(), perps_market.market_id.as_ref()],
         bump = perps_market.bump,
-        constraint = perps_market.pool == perps_pool.key() @ CloakCraftError::PerpsMarketNotFound
+        constraint = perps_market.pool == perps_pool.key() @ CloakCraftError::PerpsMarketNotFound,
+        constraint = perps_market.status == crate::state::MarketStatus::Active @ CloakCraftError::PerpsMarketDelisted,
     )]
     pub perps_market: Account<'info, crate::state::PerpsMarket>,
 
@@ -0,0 +1,88 @@
+//! Force Settle Market
+//!
+//! Permissionless keeper instruction that ends a delisted market's grace
+//! period: it reads the base token's oracle price once and records it as the
+//! market's settlement price. Remaining positions then close (owner proof)
+//! or liquidate (PositionMeta) at that fixed price instead of the live
+//! oracle, so the market can wind down without a price feed.
+
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::state::{PerpsPool, PerpsMarket, MarketStatus};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::pyth;
+
+/// Emitted when a delisted market is force-settled
+#[event]
+pub struct MarketSettled {
+    pub perps_market: Pubkey,
+    pub market_id: [u8; 32],
+    pub settlement_price: u64,
+    pub long_open_interest: u64,
+    pub short_open_interest: u64,
+}
+
+#[derive(Accounts)]
+pub struct ForceSettleMarket<'info> {
+    /// Perps pool
+    #[account(
+        seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
+        bump = perps_pool.bump,
+    )]
+    pub perps_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Delisted market
+    #[account(
+        mut,
+        seeds = [seeds::PERPS_MARKET, perps_pool.key().as_ref(), perps_market.market_id.as_ref()],
+        bump = perps_market.bump,
+        constraint = perps_market.pool == perps_pool.key() @ CloakCraftError::PerpsMarketNotFound,
+        constraint = perps_market.status == MarketStatus::SettleOnly @ CloakCraftError::PerpsMarketNotSettleOnly,
+    )]
+    pub perps_market: Box<Account<'info, PerpsMarket>>,
+
+    /// Pyth price update account for the base token
+    pub price_update: Box<Account<'info, PriceUpdateV2>>,
+
+    /// Keeper (anyone can settle)
+    pub keeper: Signer<'info>,
+}
+
+/// Fix a delisted market's final price after its grace period
+pub fn force_settle_market(ctx: Context<ForceSettleMarket>) -> Result<()> {
+    let perps_pool = &ctx.accounts.perps_pool;
+    let perps_market = &mut ctx.accounts.perps_market;
+    let clock = Clock::get()?;
+
+    msg!("=== Force Settle Market ===");
+
+    require!(
+        perps_market.can_force_settle(clock.unix_timestamp),
+        CloakCraftError::SettleGracePeriodActive
+    );
+
+    let base_token = perps_pool.get_token(perps_market.base_token_index)
+        .ok_or(CloakCraftError::TokenNotInPool)?;
+    let settlement_price = pyth::get_price(&ctx.accounts.price_update, &base_token.pyth_feed_id, &clock)?;
+    require!(settlement_price > 0, CloakCraftError::InvalidOraclePrice);
+
+    perps_market.settlement_price = settlement_price;
+    perps_market.status = MarketStatus::Settled;
+
+    emit!(MarketSettled {
+        perps_market: perps_market.key(),
+        market_id: perps_market.market_id,
+        settlement_price,
+        long_open_interest: perps_market.long_open_interest,
+        short_open_interest: perps_market.short_open_interest,
+    });
+
+    msg!("Market {:?} settled at {}", perps_market.market_id, settlement_price);
+    msg!("Remaining OI - Long: {}, Short: {}",
+        perps_market.long_open_interest,
+        perps_market.short_open_interest);
+
+    Ok(())
+}
@@ -5,7 +5,8 @@
 //!
 //! Liquidation follows the multi-phase append pattern but with keeper initiation:
 //! - Phase 0: Verify liquidation proof + Create pending operation
-//! - Phase 1-2: Generic commitment/nullifier phases
+//! - Phase 1-2: Generic commitment/nullifier phases, plus verify_position_meta_active
+//!   (records the entry borrow fee)
 //! - Phase 3: Execute liquidation (close position, distribute rewards)
 //! - Phase 4+: Create commitments (owner remainder + liquidator reward)
 //!
@@ -13,7 +14,10 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, PerpsPool, PerpsMarket, VerificationKey, PendingOperation, PENDING_OPERATION_EXPIRY_SECONDS};
+use crate::state::{
+    Pool, PerpsPool, PerpsMarket, PositionData, PositionDirection, VerificationKey, PendingOperation, OperationKind,
+    ProtocolConfig, ProgramVersion,
+};
 use crate::constants::{seeds, operation_types};
 use crate::errors::CloakCraftError;
 use crate::helpers::verify_groth16_proof;
@@ -51,6 +55,7 @@ pub struct CreatePendingWithProofLiquidate<'info> {
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
@@ -68,6 +73,20 @@ pub struct CreatePendingWithProofLiquidate<'info> {
     #[account(mut)]
     pub keeper: Signer<'info>,
 
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
     /// System program
     pub system_program: Program<'info, System>,
 }
@@ -85,7 +104,15 @@ pub fn create_pending_with_proof_liquidate<'info>(
     current_price: u64,
     liquidator_reward: u64,
     owner_remainder: u64,
+    is_long: bool,
+    position_margin: u64,
+    position_size: u64,
+    entry_price: u64,
+    client_version: u32,
 ) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
     let settlement_pool = &ctx.accounts.settlement_pool;
     let perps_pool = &ctx.accounts.perps_pool;
     let pending_op = &mut ctx.accounts.pending_operation;
@@ -93,7 +120,7 @@ pub fn create_pending_with_proof_liquidate<'info>(
 
     msg!("=== Phase 0: Verify Proof + Create Pending (Liquidate) ===");
 
-    // Verify ZK proof (8 public inputs)
+    // Verify ZK proof (13 public inputs)
     let mut price_bytes = [0u8; 32];
     price_bytes[24..].copy_from_slice(&current_price.to_be_bytes());
 
@@ -103,6 +130,18 @@ pub fn create_pending_with_proof_liquidate<'info>(
     let mut remainder_bytes = [0u8; 32];
     remainder_bytes[24..].copy_from_slice(&owner_remainder.to_be_bytes());
 
+    let mut is_long_bytes = [0u8; 32];
+    is_long_bytes[31] = if is_long { 1 } else { 0 };
+
+    let mut margin_bytes = [0u8; 32];
+    margin_bytes[24..].copy_from_slice(&position_margin.to_be_bytes());
+
+    let mut size_bytes = [0u8; 32];
+    size_bytes[24..].copy_from_slice(&position_size.to_be_bytes());
+
+    let mut entry_price_bytes = [0u8; 32];
+    entry_price_bytes[24..].copy_from_slice(&entry_price.to_be_bytes());
+
     let public_inputs = vec![
         merkle_root,
         position_nullifier,
@@ -112,6 +151,11 @@ pub fn create_pending_with_proof_liquidate<'info>(
         price_bytes,
         reward_bytes,
         remainder_bytes,
+        position_commitment,
+        is_long_bytes,
+        margin_bytes,
+        size_bytes,
+        entry_price_bytes,
     ];
 
     verify_groth16_proof(&proof, &ctx.accounts.verification_key.vk_data, &public_inputs, "Liquidate")?;
@@ -119,11 +163,13 @@ pub fn create_pending_with_proof_liquidate<'info>(
 
     // Initialize pending operation
     pending_op.bump = ctx.bumps.pending_operation;
-    pending_op.operation_id = operation_id;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
     pending_op.relayer = ctx.accounts.keeper.key();
     pending_op.operation_type = operation_types::PERPS_LIQUIDATE;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
     pending_op.created_at = clock.unix_timestamp;
-    pending_op.expires_at = clock.unix_timestamp + PENDING_OPERATION_EXPIRY_SECONDS;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
 
     // Store binding fields
     pending_op.num_inputs = 1;
@@ -149,6 +195,10 @@ pub fn create_pending_with_proof_liquidate<'info>(
     pending_op.swap_amount = current_price;
     pending_op.output_amount = liquidator_reward;
     pending_op.min_output = owner_remainder;
+    pending_op.swap_a_to_b = is_long;
+    pending_op.position_margin = position_margin;
+    pending_op.position_size = position_size;
+    pending_op.position_entry_price = entry_price;
 
     msg!("Phase 0 complete: Liquidation proof verified");
 
@@ -191,9 +241,6 @@ pub struct ExecuteLiquidate<'info> {
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
         constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
-        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
-        constraint = pending_operation.all_inputs_verified() @ CloakCraftError::CommitmentNotVerified,
-        constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -208,13 +255,21 @@ pub struct ExecuteLiquidate<'info> {
     pub oracle: AccountInfo<'info>,
 }
 
+/// Phase 3: Execute liquidation
+///
+/// Position terms come from the pending operation: direction, margin, size
+/// and entry price were bound by the proof in Phase 0, the entry borrow fee
+/// by verify_position_meta_active.
 pub fn execute_liquidate<'info>(
     ctx: Context<'_, '_, '_, 'info, ExecuteLiquidate<'info>>,
     _operation_id: [u8; 32],
-    position_margin: u64,
-    position_size: u64,
-    is_long: bool,
 ) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::PerpsLiquidate)?;
+    require!(
+        ctx.accounts.pending_operation.position_meta_verified,
+        CloakCraftError::PositionMetaNotVerified
+    );
+
     let perps_pool = &mut ctx.accounts.perps_pool;
     let perps_market = &mut ctx.accounts.perps_market;
     let pending_op = &ctx.accounts.pending_operation;
@@ -224,28 +279,54 @@ pub fn execute_liquidate<'info>(
     let current_price = pending_op.swap_amount;
     let liquidator_reward = pending_op.output_amount;
     let owner_remainder = pending_op.min_output;
+    let is_long = pending_op.swap_a_to_b;
+    let position_margin = pending_op.position_margin;
+    let position_size = pending_op.position_size;
+    let entry_price = pending_op.position_entry_price;
+    let entry_borrow_fee = pending_op.position_entry_borrow_fee;
 
     msg!("Liquidating position: margin={}, size={}, price={}",
         position_margin, position_size, current_price);
 
-    // Verify liquidation reward is within bounds (liquidation penalty)
-    let max_reward = (position_margin as u128)
-        .checked_mul(perps_pool.liquidation_penalty_bps as u128)
-        .unwrap_or(0)
-        .checked_div(10000)
-        .unwrap_or(0) as u64;
+    // Reward = liquidation penalty + urgency bonus for how far underwater the position is
+    let position = PositionData {
+        direction: if is_long { PositionDirection::Long } else { PositionDirection::Short },
+        margin: position_margin,
+        size: position_size,
+        entry_price,
+        ..Default::default()
+    };
+    let expected_reward = perps_pool
+        .liquidation_reward(position_margin, position.effective_margin(current_price));
 
     require!(
-        liquidator_reward <= max_reward.saturating_add(1), // +1 for rounding
+        liquidator_reward.abs_diff(expected_reward) <= 1, // 1 for rounding
         CloakCraftError::InvalidAmount
     );
 
+    // Accrued borrow fee is charged before the owner remainder
+    let borrow_token_index = perps_market.borrow_token_index(is_long);
+    let borrow_fee = perps_pool
+        .get_token(borrow_token_index)
+        .ok_or(CloakCraftError::TokenNotInPool)?
+        .accrued_borrow_fee(position_size, entry_borrow_fee)
+        .min(position_margin.saturating_sub(liquidator_reward));
+
     // Verify total outputs don't exceed margin
     require!(
-        liquidator_reward.saturating_add(owner_remainder) <= position_margin,
+        liquidator_reward
+            .saturating_add(owner_remainder)
+            .saturating_add(borrow_fee) <= position_margin,
         CloakCraftError::InvalidAmount
     );
 
+    // Borrow fee stays in the pool (credited to LPs)
+    if let Some(borrow_token) = perps_pool.get_token_mut(borrow_token_index) {
+        borrow_token.balance = borrow_token.balance
+            .checked_add(borrow_fee)
+            .ok_or(CloakCraftError::AmountOverflow)?;
+    }
+
     // Unlock tokens from pool
     let base_token_index = perps_market.base_token_index;
     let quote_token_index = perps_market.quote_token_index;
@@ -262,7 +343,8 @@ pub fn execute_liquidate<'info>(
     perps_market.remove_open_interest(position_size, is_long);
 
     msg!("✅ Position liquidated");
-    msg!("Liquidator reward: {}, Owner remainder: {}", liquidator_reward, owner_remainder);
+    msg!("Liquidator reward: {}, Owner remainder: {}, Borrow fee: {}",
+        liquidator_reward, owner_remainder, borrow_fee);
     msg!("Phase 3 complete");
 
     Ok(())
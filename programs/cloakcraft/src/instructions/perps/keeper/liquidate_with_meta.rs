@@ -22,9 +22,10 @@
 use anchor_lang::prelude::*;
 use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
-use crate::state::{PerpsPool, PerpsMarket, PositionStatus, LightValidityProof, LightAddressTreeInfo};
+use crate::state::{Pool, PerpsPool, PerpsMarket, PositionMeta, PositionStatus, LightValidityProof, LightAddressTreeInfo};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
+use crate::helpers::fixed::apply_bps;
 use crate::light_cpi::{
     verify_position_meta_inclusion, create_position_status_record,
     create_liquidation_nullifier, PositionMetaMerkleContext,
@@ -48,32 +49,6 @@ pub struct LightLiquidateParams {
     pub output_tree_index: u8,
 }
 
-/// Position metadata from indexer (all fields for update)
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct PositionMetaForLiquidation {
-    // Identifiers
-    pub position_id: [u8; 32],
-    pub market_id: [u8; 32],
-    pub account_hash: [u8; 32],
-    
-    // Position data
-    pub margin_amount: u64,
-    pub liquidation_price: u64,
-    pub is_long: bool,
-    pub position_size: u64,
-    pub entry_price: u64,
-    
-    // For nullifier creation
-    pub nullifier_hash: [u8; 32],
-    
-    // Current status (must be Active)
-    pub status: u8,
-    pub created_at: i64,
-    
-    // Owner info
-    pub owner_stealth_pubkey: [u8; 32],
-}
-
 #[derive(Accounts)]
 pub struct LiquidateWithMeta<'info> {
     /// Perps pool
@@ -84,6 +59,14 @@ pub struct LiquidateWithMeta<'info> {
     )]
     pub perps_pool: Box<Account<'info, PerpsPool>>,
 
+    /// Position pool (source of the pinned inclusion threshold)
+    #[account(
+        seeds = [seeds::POOL, position_pool.token_mint.as_ref()],
+        bump = position_pool.bump,
+        constraint = position_pool.token_mint == perps_pool.position_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub position_pool: Box<Account<'info, Pool>>,
+
     /// Market being traded
     #[account(
         mut,
@@ -94,7 +77,7 @@ pub struct LiquidateWithMeta<'info> {
     pub perps_market: Box<Account<'info, PerpsMarket>>,
 
     /// Pyth price update account
-    pub price_update: Account<'info, PriceUpdateV2>,
+    pub price_update: Box<Account<'info, PriceUpdateV2>>,
 
     /// Keeper (anyone can liquidate, receives reward)
     #[account(mut)]
@@ -114,7 +97,7 @@ pub struct LiquidateWithMeta<'info> {
 /// and verify the position is underwater.
 pub fn liquidate_with_meta<'info>(
     ctx: Context<'_, '_, '_, 'info, LiquidateWithMeta<'info>>,
-    position_meta: PositionMetaForLiquidation,
+    position_meta: PositionMeta,
     light_params: LightLiquidateParams,
 ) -> Result<()> {
     let perps_pool = &mut ctx.accounts.perps_pool;
@@ -130,17 +113,28 @@ pub fn liquidate_with_meta<'info>(
 
     // 1. Verify position meta exists in state tree
     msg!("Step 1: Verifying PositionMeta inclusion...");
+    // SECURITY: High-value positions don't trust queue/index context
+    if ctx.accounts.position_pool.requires_pinned_inclusion(position_meta.margin_amount) {
+        require!(
+            !light_params.merkle_context.prove_by_index,
+            CloakCraftError::PinnedInclusionRequired
+        );
+    }
     verify_position_meta_inclusion(
         ctx.accounts.keeper.as_ref(),
         ctx.remaining_accounts,
-        position_meta.account_hash,
+        &position_meta,
         light_params.merkle_context.clone(),
-        perps_pool.pool_id.to_bytes(),
-        position_meta.position_id,
+        light_params.inclusion_proof.clone(),
+        light_params.address_tree_info.clone(),
     )?;
     msg!("✅ PositionMeta verified");
 
-    // 2. Check position is Active
+    // 2. Check position belongs to this pool and is Active
+    require!(
+        position_meta.pool_id == perps_pool.pool_id.to_bytes(),
+        CloakCraftError::PositionIdMismatch
+    );
     require!(
         position_meta.status == PositionStatus::Active as u8,
         CloakCraftError::PositionNotActive
@@ -157,7 +151,11 @@ pub fn liquidate_with_meta<'info>(
     let base_token = perps_pool.get_token(base_token_index)
         .ok_or(CloakCraftError::TokenNotInPool)?;
 
-    let current_price = pyth::get_price(price_update, &base_token.pyth_feed_id, &clock)?;
+    // Settled markets liquidate at their settlement price
+    let current_price = match perps_market.settlement_price() {
+        Some(price) => price,
+        None => pyth::get_price(price_update, &base_token.pyth_feed_id, &clock)?,
+    };
     msg!("Current price: {}", current_price);
 
     // 5. Check if position is liquidatable
@@ -202,20 +200,29 @@ pub fn liquidate_with_meta<'info>(
     msg!("✅ Position marked as Liquidated");
 
     // 8. Calculate and distribute liquidation proceeds
-    let liquidation_penalty = (position_meta.margin_amount as u128)
-        .checked_mul(perps_pool.liquidation_penalty_bps as u128)
-        .unwrap_or(0)
-        .checked_div(10000)
-        .unwrap_or(0) as u64;
+    let liquidation_penalty = apply_bps(position_meta.margin_amount, perps_pool.liquidation_penalty_bps);
 
     // Keeper gets the penalty as reward
     let keeper_reward = liquidation_penalty;
     // Remaining goes back to pool
     let pool_receives = position_meta.margin_amount.saturating_sub(liquidation_penalty);
 
+    // Accrued borrow fee is part of what the pool receives (credited to LPs)
+    let borrow_token_index = perps_market.borrow_token_index(position_meta.is_long);
+    let borrow_fee = perps_pool
+        .get_token(borrow_token_index)
+        .ok_or(CloakCraftError::TokenNotInPool)?
+        .accrued_borrow_fee(position_meta.position_size, position_meta.entry_borrow_fee)
+        .min(pool_receives);
+    if let Some(borrow_token) = perps_pool.get_token_mut(borrow_token_index) {
+        borrow_token.balance = borrow_token.balance
+            .checked_add(borrow_fee)
+            .ok_or(CloakCraftError::AmountOverflow)?;
+    }
+
     msg!("Liquidation proceeds:");
     msg!("  Keeper reward: {}", keeper_reward);
-    msg!("  Pool receives: {}", pool_receives);
+    msg!("  Pool receives: {} (borrow fee: {})", pool_receives, borrow_fee);
 
     // 9. Unlock tokens from pool
     let base_token_index = perps_market.base_token_index;
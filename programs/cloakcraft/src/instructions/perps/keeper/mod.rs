@@ -5,13 +5,19 @@
 //! - Liquidate: Close underwater positions (legacy with ZK proof)
 //! - Liquidate with meta: Close underwater positions using PositionMeta (no ZK proof)
 //! - Trigger bound close: Close positions at profit bound
+//! - Rebalance pool: Swap between vaults toward target token weights
+//! - Force settle market: Fix a delisted market's final price
 
 mod update_borrow_fees;
 mod liquidate;
 mod liquidate_with_meta;
 mod trigger_bound_close;
+mod rebalance_pool;
+mod force_settle_market;
 
 pub use update_borrow_fees::*;
 pub use liquidate::*;
 pub use liquidate_with_meta::*;
 pub use trigger_bound_close::*;
+pub use rebalance_pool::*;
+pub use force_settle_market::*;
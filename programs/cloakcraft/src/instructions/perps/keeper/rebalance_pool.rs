@@ -0,0 +1,206 @@
+//! Rebalance Pool
+//!
+//! Keeper instruction that swaps between two pool vaults at oracle prices to
+//! move token weights toward their configured `target_weight_bps`.
+//! The keeper supplies `token_in` and receives `token_out` of equal USD value,
+//! less the pool's `rebalance_fee_bps` which stays in the vault for LPs;
+//! the swap is rejected if it does not reduce total weight deviation.
+//! Only the pool authority may rebalance, so LP reserves are never offered
+//! to arbitrary callers at the oracle price.
+//! Every price is read from Pyth: the remaining accounts hold one
+//! `PriceUpdateV2` per active pool token, in token index order.
+//!
+//! This is a single-phase instruction (no ZK proof needed).
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::state::{PerpsPool, MAX_PERPS_TOKENS};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::fixed::{token_amount_for_usd, to_u64, usd_value};
+use crate::pyth;
+
+#[derive(Accounts)]
+#[instruction(token_in_index: u8, token_out_index: u8)]
+pub struct RebalancePool<'info> {
+    /// Perps pool (will be updated)
+    #[account(
+        mut,
+        seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
+        bump = perps_pool.bump,
+        constraint = !perps_pool.is_migrating @ CloakCraftError::PerpsPoolMigrating,
+    )]
+    pub perps_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Pool vault receiving token_in
+    #[account(
+        mut,
+        constraint = perps_pool.get_token(token_in_index).map(|t| t.vault) == Some(token_in_vault.key()) @ CloakCraftError::InvalidVault,
+    )]
+    pub token_in_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Pool vault paying out token_out
+    #[account(
+        mut,
+        constraint = perps_pool.get_token(token_out_index).map(|t| t.vault) == Some(token_out_vault.key()) @ CloakCraftError::InvalidVault,
+    )]
+    pub token_out_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Keeper's source account for token_in
+    #[account(
+        mut,
+        constraint = keeper_token_in.mint == token_in_vault.mint @ CloakCraftError::TokenMintMismatch,
+    )]
+    pub keeper_token_in: Box<Account<'info, TokenAccount>>,
+
+    /// Keeper's destination account for token_out
+    #[account(
+        mut,
+        constraint = keeper_token_out.mint == token_out_vault.mint @ CloakCraftError::TokenMintMismatch,
+    )]
+    pub keeper_token_out: Box<Account<'info, TokenAccount>>,
+
+    /// Keeper (must be the pool authority)
+    #[account(
+        constraint = keeper.key() == perps_pool.authority @ CloakCraftError::Unauthorized,
+    )]
+    pub keeper: Signer<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    // Pyth price updates for each active token via remaining_accounts
+}
+
+/// Read the oracle price of every active pool token from Pyth
+///
+/// Expects one price update per active token, in token index order.
+/// Inactive tokens are priced at zero (they carry no weight).
+fn load_pool_prices<'info>(
+    perps_pool: &PerpsPool,
+    price_updates: &'info [AccountInfo<'info>],
+    clock: &Clock,
+) -> Result<[u64; MAX_PERPS_TOKENS]> {
+    let mut prices = [0u64; MAX_PERPS_TOKENS];
+    let mut updates = price_updates.iter();
+    for (i, price) in prices.iter_mut().enumerate().take(perps_pool.num_tokens as usize) {
+        let token = &perps_pool.tokens[i];
+        if !token.is_active {
+            continue;
+        }
+        let info = updates.next().ok_or(CloakCraftError::InvalidPriceFeed)?;
+        let price_update = Account::<PriceUpdateV2>::try_from(info)?;
+        *price = pyth::get_price(&price_update, &token.pyth_feed_id, clock)?;
+    }
+    require!(updates.next().is_none(), CloakCraftError::InvalidPriceFeed);
+    Ok(prices)
+}
+
+/// Swap `amount_in` of token_in for token_out at oracle value, less the rebalance fee
+pub fn rebalance_pool<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RebalancePool<'info>>,
+    token_in_index: u8,
+    token_out_index: u8,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    let perps_pool = &mut ctx.accounts.perps_pool;
+    let clock = Clock::get()?;
+
+    msg!("=== Rebalance Perps Pool ===");
+
+    require!(token_in_index != token_out_index, CloakCraftError::InvalidTokenIndex);
+    require!(amount_in > 0, CloakCraftError::InvalidAmount);
+
+    let token_in = perps_pool.get_token(token_in_index)
+        .ok_or(CloakCraftError::TokenNotInPool)?;
+    let token_out = perps_pool.get_token(token_out_index)
+        .ok_or(CloakCraftError::TokenNotInPool)?;
+    require!(token_in.is_active && token_out.is_active, CloakCraftError::TokenNotActive);
+
+    // Oracle prices for all tokens, read from Pyth
+    let oracle_prices = load_pool_prices(perps_pool, ctx.remaining_accounts, &clock)?;
+
+    // Equal USD value out, less the rebalance fee (left in the vault for LPs)
+    let value_in = usd_value(amount_in, oracle_prices[token_in_index as usize], token_in.decimals)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    let gross_amount_out = token_amount_for_usd(value_in, oracle_prices[token_out_index as usize], token_out.decimals)
+        .and_then(to_u64)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    let rebalance_fee = perps_pool.rebalance_fee(gross_amount_out);
+    let amount_out = gross_amount_out.saturating_sub(rebalance_fee);
+
+    require!(amount_out >= min_amount_out, CloakCraftError::SlippageExceeded);
+    require!(amount_out > 0, CloakCraftError::InvalidAmount);
+    require!(
+        amount_out <= token_out.available(),
+        CloakCraftError::InsufficientLiquidity
+    );
+
+    msg!("Swap: {} of token {} -> {} of token {} (value {} USD, fee {})",
+        amount_in, token_in_index, amount_out, token_out_index, value_in, rebalance_fee);
+
+    // Apply balances and require weights move toward target
+    let deviation_before = perps_pool.total_weight_deviation_bps(&oracle_prices)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    let pool_token_in = perps_pool.get_token_mut(token_in_index)
+        .ok_or(CloakCraftError::TokenNotInPool)?;
+    pool_token_in.balance = pool_token_in.balance
+        .checked_add(amount_in)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    let pool_token_out = perps_pool.get_token_mut(token_out_index)
+        .ok_or(CloakCraftError::TokenNotInPool)?;
+    pool_token_out.balance = pool_token_out.balance
+        .checked_sub(amount_out)
+        .ok_or(CloakCraftError::InsufficientBalance)?;
+
+    let deviation_after = perps_pool.total_weight_deviation_bps(&oracle_prices)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    require!(
+        deviation_after < deviation_before,
+        CloakCraftError::RebalanceWorsensWeights
+    );
+
+    msg!("Weight deviation: {} -> {} bps", deviation_before, deviation_after);
+
+    // Keeper -> pool vault
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.keeper_token_in.to_account_info(),
+                to: ctx.accounts.token_in_vault.to_account_info(),
+                authority: ctx.accounts.keeper.to_account_info(),
+            },
+        ),
+        amount_in,
+    )?;
+
+    // Pool vault -> keeper (perps pool PDA signs)
+    let pool_seeds = &[
+        seeds::PERPS_POOL,
+        perps_pool.pool_id.as_ref(),
+        &[perps_pool.bump],
+    ];
+    let signer_seeds = &[&pool_seeds[..]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.token_out_vault.to_account_info(),
+                to: ctx.accounts.keeper_token_out.to_account_info(),
+                authority: perps_pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount_out,
+    )?;
+
+    msg!("✅ Pool rebalanced");
+
+    Ok(())
+}
@@ -16,6 +16,19 @@ use anchor_lang::prelude::*;
 use crate::state::{PerpsPool, PerpsMarket};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
+use crate::helpers::fixed::{mul_div, saturating_u64};
+
+/// Result of `check_perps_profit_bound` (return data)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProfitBoundCheck {
+    /// Whether PnL reached the bound (max profit = margin)
+    pub at_bound: bool,
+    pub is_profit: bool,
+    /// Absolute PnL at `current_price` (uncapped)
+    pub pnl: u64,
+    /// Profit bound (the position's margin)
+    pub max_profit: u64,
+}
 
 #[derive(Accounts)]
 pub struct CheckProfitBound<'info> {
@@ -43,8 +56,8 @@ pub struct CheckProfitBound<'info> {
 
 /// Check if a position has hit its profit bound
 ///
-/// This is a view-only instruction that returns whether a position
-/// should be closed due to hitting the profit bound.
+/// This is a view-only instruction that returns (as return data) whether a
+/// position should be closed due to hitting the profit bound.
 ///
 /// The actual close is performed through the standard close position flow
 /// with the keeper providing proof of the position being at bound.
@@ -55,7 +68,7 @@ pub fn check_profit_bound(
     entry_price: u64,
     is_long: bool,
     current_price: u64,
-) -> Result<bool> {
+) -> Result<ProfitBoundCheck> {
     let _perps_pool = &ctx.accounts.perps_pool;
     let _perps_market = &ctx.accounts.perps_market;
 
@@ -74,7 +87,10 @@ pub fn check_profit_bound(
 
     if !is_profit {
         msg!("Position is not in profit, not at bound");
-        return Ok(false);
+        return Ok(ProfitBoundCheck {
+            max_profit: position_margin,
+            ..Default::default()
+        });
     }
 
     let price_diff = if is_long {
@@ -83,11 +99,8 @@ pub fn check_profit_bound(
         entry_price.saturating_sub(current_price)
     };
 
-    let pnl = (price_diff as u128)
-        .checked_mul(position_size as u128)
-        .unwrap_or(0)
-        .checked_div(entry_price as u128)
-        .unwrap_or(0) as u64;
+    let pnl = mul_div(price_diff as u128, position_size as u128, entry_price as u128)
+        .map_or(0, saturating_u64);
 
     // Check if PnL >= margin (profit bound)
     let at_bound = pnl >= position_margin;
@@ -101,7 +114,12 @@ pub fn check_profit_bound(
         msg!("✅ Position is at profit bound, should be closed");
     }
 
-    Ok(at_bound)
+    Ok(ProfitBoundCheck {
+        at_bound,
+        is_profit,
+        pnl,
+        max_profit: position_margin,
+    })
 }
 
 /// Event emitted when a position is at profit bound
@@ -5,13 +5,17 @@
 //! - Time elapsed since last update
 //! - Token utilization (higher utilization = higher rate)
 //!
+//! When the pool's borrow fee history is passed, the first update in each
+//! hour also records a checkpoint of every token's accumulator.
+//!
 //! This is a single-phase instruction (no ZK proof needed).
 
 use anchor_lang::prelude::*;
 
-use crate::state::PerpsPool;
+use crate::state::{BorrowFeeCheckpointed, BorrowFeeHistory, PerpsPool, MAX_PERPS_TOKENS};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
+use crate::helpers::fixed::hourly_rate_increment;
 
 #[derive(Accounts)]
 pub struct UpdateBorrowFees<'info> {
@@ -23,6 +27,14 @@ pub struct UpdateBorrowFees<'info> {
     )]
     pub perps_pool: Box<Account<'info, PerpsPool>>,
 
+    /// Borrow fee history (optional, records hourly checkpoints)
+    #[account(
+        mut,
+        seeds = [seeds::BORROW_FEE_HISTORY, perps_pool.key().as_ref()],
+        bump = borrow_fee_history.bump,
+    )]
+    pub borrow_fee_history: Option<Box<Account<'info, BorrowFeeHistory>>>,
+
     /// Keeper (anyone can call this)
     pub keeper: Signer<'info>,
 }
@@ -56,14 +68,7 @@ pub fn update_borrow_fees(ctx: Context<UpdateBorrowFees>) -> Result<()> {
         // Fee per hour in basis points, convert to per-second
         // cumulative_fee += rate_bps * elapsed_seconds / 3600 / 10000
         // Scaled by 1e18 for precision
-        let fee_increment = (borrow_rate_bps as u128)
-            .checked_mul(elapsed_seconds as u128)
-            .unwrap_or(0)
-            .checked_mul(1_000_000_000_000_000_000u128) // 1e18
-            .unwrap_or(0)
-            .checked_div(3600) // per hour to per second
-            .unwrap_or(0)
-            .checked_div(10000) // basis points to ratio
+        let fee_increment = hourly_rate_increment(borrow_rate_bps, elapsed_seconds as u64)
             .unwrap_or(0);
 
         // Update token
@@ -79,6 +84,22 @@ pub fn update_borrow_fees(ctx: Context<UpdateBorrowFees>) -> Result<()> {
         );
     }
 
+    if let Some(history) = ctx.accounts.borrow_fee_history.as_deref_mut() {
+        let mut cumulative_borrow_fees = [0u128; MAX_PERPS_TOKENS];
+        for (fee, token) in cumulative_borrow_fees.iter_mut().zip(perps_pool.tokens.iter()) {
+            *fee = token.cumulative_borrow_fee;
+        }
+        if let Some(checkpoint_index) = history.record(current_time, cumulative_borrow_fees) {
+            emit!(BorrowFeeCheckpointed {
+                perps_pool: history.perps_pool,
+                checkpoint_index,
+                timestamp: current_time,
+                cumulative_borrow_fees,
+            });
+            msg!("Borrow fee checkpoint {} recorded", checkpoint_index);
+        }
+    }
+
     msg!("✅ Borrow fees updated");
 
     Ok(())
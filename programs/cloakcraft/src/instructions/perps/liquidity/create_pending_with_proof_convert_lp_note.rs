@@ -0,0 +1,211 @@
+//! Create Pending Operation with Proof - Phase 0 (Convert LP Note)
+//!
+//! After a perps pool migration is finalized, spends an LP note of the old
+//! pool and commits the new pool's LP at the migration's conversion rate.
+//! The new LP was reserved in the new pool's supply at finalization, so
+//! there is no pool accounting to update and no execute phase.
+//!
+//! Flow:
+//! Phase 0 (this): Verify ZK proof + Create PendingOperation
+//! Phase 1: Verify commitment exists (old LP note)
+//! Phase 2: Create nullifier (spend old LP note)
+//! Phase 4: Create commitment (new LP note)
+//! Final: Close pending operation
+
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    Pool, PerpsPool, PerpsPoolMigration, VerificationKey, PendingOperation, ProtocolConfig, ProgramVersion,
+    CircuitStats, OperationCredit,
+};
+use crate::constants::{seeds, operation_types};
+use crate::errors::CloakCraftError;
+use crate::helpers::verify_groth16_proof_metered;
+use crate::helpers::field::{pubkey_to_field, u64_to_field, assert_canonical};
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct CreatePendingWithProofConvertLpNote<'info> {
+    /// Old LP token pool (LP note input)
+    #[account(
+        seeds = [seeds::POOL, old_lp_pool.token_mint.as_ref()],
+        bump = old_lp_pool.bump,
+        constraint = old_lp_pool.token_mint == old_pool.lp_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub old_lp_pool: Box<Account<'info, Pool>>,
+
+    /// New LP token pool (converted LP note output)
+    #[account(
+        seeds = [seeds::POOL, new_lp_pool.token_mint.as_ref()],
+        bump = new_lp_pool.bump,
+        constraint = new_lp_pool.token_mint == new_pool.lp_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub new_lp_pool: Box<Account<'info, Pool>>,
+
+    /// Migrated perps pool
+    #[account(
+        seeds = [seeds::PERPS_POOL, old_pool.pool_id.as_ref()],
+        bump = old_pool.bump,
+    )]
+    pub old_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Perps pool the LP converts into
+    #[account(
+        seeds = [seeds::PERPS_POOL, new_pool.pool_id.as_ref()],
+        bump = new_pool.bump,
+        constraint = new_pool.key() == migration.new_pool @ CloakCraftError::InvalidMigrationTarget,
+    )]
+    pub new_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Finalized migration record
+    #[account(
+        seeds = [seeds::PERPS_MIGRATION, old_pool.key().as_ref()],
+        bump = migration.bump,
+        constraint = migration.finalized @ CloakCraftError::MigrationNotFinalized,
+    )]
+    pub migration: Box<Account<'info, PerpsPoolMigration>>,
+
+    /// Verification key for the convert LP circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, crate::constants::circuits::PERPS_CONVERT_LP.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Pending operation PDA (created in this instruction)
+    #[account(
+        init,
+        payer = relayer,
+        space = PendingOperation::SPACE,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (pays for PDA creation)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Phase 0: Verify ZK proof and create PendingOperation for an LP note conversion
+#[allow(clippy::too_many_arguments)]
+pub fn create_pending_with_proof_convert_lp_note<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofConvertLpNote<'info>>,
+    operation_id: [u8; 32],
+    proof: Vec<u8>,
+    merkle_root: [u8; 32],
+    lp_commitment: [u8; 32],
+    lp_nullifier: [u8; 32],
+    out_commitment: [u8; 32],
+    lp_amount: u64,
+    converted_amount: u64,
+    client_version: u32,
+) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[lp_commitment, lp_nullifier, out_commitment])?;
+
+    let old_pool = &ctx.accounts.old_pool;
+    let new_pool = &ctx.accounts.new_pool;
+    let pending_op = &mut ctx.accounts.pending_operation;
+    let clock = Clock::get()?;
+
+    msg!("=== Phase 0: Verify Proof + Create Pending (Convert LP Note) ===");
+
+    // The whole note converts at the registered rate (rounded down)
+    require!(lp_amount > 0, CloakCraftError::InvalidAmount);
+    require!(
+        ctx.accounts.migration.convert_lp_amount(lp_amount) == Some(converted_amount),
+        CloakCraftError::LpConversionMismatch
+    );
+
+    // 1. Verify ZK proof (7 public inputs matching Circom circuit)
+    let public_inputs = vec![
+        merkle_root,
+        lp_nullifier,
+        pubkey_to_field(&old_pool.pool_id),
+        pubkey_to_field(&new_pool.pool_id),
+        out_commitment,
+        u64_to_field(lp_amount),
+        u64_to_field(converted_amount),
+    ];
+
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "ConvertLpNote",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
+    msg!("✅ ZK proof verified");
+
+    // 2. Initialize pending operation PDA with binding fields
+    pending_op.bump = ctx.bumps.pending_operation;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
+    pending_op.relayer = ctx.accounts.relayer.key();
+    pending_op.operation_type = operation_types::PERPS_CONVERT_LP;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
+    pending_op.created_at = clock.unix_timestamp;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
+
+    // SECURITY: Store binding fields from ZK proof
+    pending_op.num_inputs = 1;
+    pending_op.input_commitments[0] = lp_commitment;
+    pending_op.expected_nullifiers[0] = lp_nullifier;
+    pending_op.input_pools[0] = ctx.accounts.old_lp_pool.key().to_bytes();
+    pending_op.inputs_verified_mask = 0;
+    pending_op.proof_verified = true;
+
+    // Converted LP note in the new LP pool
+    pending_op.num_commitments = 1;
+    pending_op.pools[0] = ctx.accounts.new_lp_pool.key().to_bytes();
+    pending_op.commitments[0] = out_commitment;
+    pending_op.output_amounts[0] = converted_amount;
+
+    pending_op.nullifier_completed_mask = 0;
+    pending_op.completed_mask = 0;
+
+    msg!("Converting {} old LP -> {} new LP", lp_amount, converted_amount);
+    msg!("Phase 0 complete: ZK proof verified, pending operation created");
+    msg!("Next: Phase 1 - verify_commitment_exists");
+
+    Ok(())
+}
@@ -15,11 +15,11 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, PerpsPool, VerificationKey, PendingOperation, PENDING_OPERATION_EXPIRY_SECONDS};
+use crate::state::{Pool, PerpsPool, VerificationKey, PendingOperation, ProtocolConfig, ProgramVersion, CircuitStats, OperationCredit};
 use crate::constants::{seeds, operation_types};
 use crate::errors::CloakCraftError;
-use crate::helpers::verify_groth16_proof;
-use crate::helpers::field::pubkey_to_field;
+use crate::helpers::verify_groth16_proof_metered;
+use crate::helpers::field::{pubkey_to_field, assert_canonical};
 
 #[derive(Accounts)]
 #[instruction(operation_id: [u8; 32])]
@@ -43,6 +43,7 @@ pub struct CreatePendingWithProofRemovePerpsLiquidity<'info> {
     #[account(
         seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
         bump = perps_pool.bump,
+        constraint = !perps_pool.is_migrating @ CloakCraftError::PerpsPoolMigrating,
     )]
     pub perps_pool: Box<Account<'info, PerpsPool>>,
 
@@ -50,6 +51,7 @@ pub struct CreatePendingWithProofRemovePerpsLiquidity<'info> {
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
@@ -67,6 +69,31 @@ pub struct CreatePendingWithProofRemovePerpsLiquidity<'info> {
     #[account(mut)]
     pub relayer: Signer<'info>,
 
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
     /// System program
     pub system_program: Program<'info, System>,
 }
@@ -86,7 +113,20 @@ pub fn create_pending_with_proof_remove_perps_liquidity<'info>(
     withdraw_amount: u64,
     lp_amount_burned: u64,
     fee_amount: u64,
+    client_version: u32,
 ) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[lp_commitment, lp_nullifier, out_commitment, change_lp_commitment])?;
+
     let withdrawal_pool = &ctx.accounts.withdrawal_pool;
     let perps_pool = &ctx.accounts.perps_pool;
     let pending_op = &mut ctx.accounts.pending_operation;
@@ -124,16 +164,24 @@ pub fn create_pending_with_proof_remove_perps_liquidity<'info>(
         fee_bytes,
     ];
 
-    verify_groth16_proof(&proof, &ctx.accounts.verification_key.vk_data, &public_inputs, "RemovePerpsLiquidity")?;
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "RemovePerpsLiquidity",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
     msg!("✅ ZK proof verified");
 
     // 2. Initialize pending operation PDA with binding fields
     pending_op.bump = ctx.bumps.pending_operation;
-    pending_op.operation_id = operation_id;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
     pending_op.relayer = ctx.accounts.relayer.key();
     pending_op.operation_type = operation_types::PERPS_REMOVE_LIQUIDITY;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
     pending_op.created_at = clock.unix_timestamp;
-    pending_op.expires_at = clock.unix_timestamp + PENDING_OPERATION_EXPIRY_SECONDS;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
 
     // SECURITY: Store binding fields from ZK proof
     pending_op.num_inputs = 1;
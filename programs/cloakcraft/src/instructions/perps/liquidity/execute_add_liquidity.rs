@@ -20,9 +20,10 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, MintTo};
 use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
-use crate::state::{Pool, PerpsPool, PendingOperation, MAX_PERPS_TOKENS};
+use crate::state::{Pool, PerpsPool, PendingOperation, OperationKind, MAX_PERPS_TOKENS};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
+use crate::helpers::fixed::apply_bps;
 use crate::pyth;
 
 #[derive(Accounts)]
@@ -40,6 +41,7 @@ pub struct ExecuteAddPerpsLiquidity<'info> {
         mut,
         seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
         bump = perps_pool.bump,
+        constraint = !perps_pool.is_migrating @ CloakCraftError::PerpsPoolMigrating,
     )]
     pub perps_pool: Box<Account<'info, PerpsPool>>,
 
@@ -64,9 +66,6 @@ pub struct ExecuteAddPerpsLiquidity<'info> {
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
         constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
-        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
-        constraint = pending_operation.all_inputs_verified() @ CloakCraftError::CommitmentNotVerified,
-        constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -77,7 +76,7 @@ pub struct ExecuteAddPerpsLiquidity<'info> {
     pub relayer: Signer<'info>,
 
     /// Pyth price update account for the deposit token
-    pub price_update: Account<'info, PriceUpdateV2>,
+    pub price_update: Box<Account<'info, PriceUpdateV2>>,
 
     /// Token program
     pub token_program: Program<'info, Token>,
@@ -89,6 +88,8 @@ pub fn execute_add_perps_liquidity<'info>(
     _operation_id: [u8; 32],
     oracle_prices: [u64; MAX_PERPS_TOKENS], // Current oracle prices for all tokens (validated below)
 ) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::PerpsAddLiquidity)?;
+
     let perps_pool = &mut ctx.accounts.perps_pool;
     let pending_op = &ctx.accounts.pending_operation;
     let price_update = &ctx.accounts.price_update;
@@ -139,8 +140,15 @@ pub fn execute_add_perps_liquidity<'info>(
         .checked_div(10u128.pow(token.decimals as u32))
         .ok_or(CloakCraftError::AmountOverflow)? as u64;
 
+    // Imbalance fee: deposits that push the token away from its target weight
+    // mint fewer LP tokens (the difference accrues to existing LPs)
+    let imbalance_fee_bps = perps_pool
+        .liquidity_imbalance_fee_bps(token_index, deposit_value as u128, true, &oracle_prices)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    let lp_deposit_value = deposit_value.saturating_sub(apply_bps(deposit_value, imbalance_fee_bps));
+
     // Calculate LP tokens to mint
-    let calculated_lp_amount = perps_pool.calculate_lp_mint_amount(deposit_value, &oracle_prices)
+    let calculated_lp_amount = perps_pool.calculate_lp_mint_amount(lp_deposit_value, &oracle_prices)
         .ok_or(CloakCraftError::LpAmountMismatch)?;
 
     // Verify calculated LP matches expected (within tolerance for rounding)
@@ -151,7 +159,8 @@ pub fn execute_add_perps_liquidity<'info>(
         CloakCraftError::LpAmountMismatch
     );
 
-    msg!("Deposit value: {} USD, LP to mint: {}", deposit_value, calculated_lp_amount);
+    msg!("Deposit value: {} USD, imbalance fee: {} bps, LP to mint: {}",
+        deposit_value, imbalance_fee_bps, calculated_lp_amount);
 
     // Update pool token balance
     if let Some(pool_token) = perps_pool.get_token_mut(token_index) {
@@ -24,9 +24,10 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Burn};
 use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
-use crate::state::{Pool, PerpsPool, PendingOperation, MAX_PERPS_TOKENS};
+use crate::state::{Pool, PerpsPool, PendingOperation, OperationKind, MAX_PERPS_TOKENS};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
+use crate::helpers::fixed::apply_bps;
 use crate::pyth;
 
 #[derive(Accounts)]
@@ -44,6 +45,7 @@ pub struct ExecuteRemovePerpsLiquidity<'info> {
         mut,
         seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
         bump = perps_pool.bump,
+        constraint = !perps_pool.is_migrating @ CloakCraftError::PerpsPoolMigrating,
     )]
     pub perps_pool: Box<Account<'info, PerpsPool>>,
 
@@ -68,9 +70,6 @@ pub struct ExecuteRemovePerpsLiquidity<'info> {
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
         constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
-        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
-        constraint = pending_operation.all_inputs_verified() @ CloakCraftError::CommitmentNotVerified,
-        constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -81,7 +80,7 @@ pub struct ExecuteRemovePerpsLiquidity<'info> {
     pub relayer: Signer<'info>,
 
     /// Pyth price update account for the withdrawal token
-    pub price_update: Account<'info, PriceUpdateV2>,
+    pub price_update: Box<Account<'info, PriceUpdateV2>>,
 
     /// Token program
     pub token_program: Program<'info, Token>,
@@ -93,6 +92,8 @@ pub fn execute_remove_perps_liquidity<'info>(
     _operation_id: [u8; 32],
     oracle_prices: [u64; MAX_PERPS_TOKENS], // Current oracle prices for all tokens (validated below)
 ) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::PerpsRemoveLiquidity)?;
+
     let perps_pool = &mut ctx.accounts.perps_pool;
     let pending_op = &ctx.accounts.pending_operation;
     let price_update = &ctx.accounts.price_update;
@@ -156,7 +157,14 @@ pub fn execute_remove_perps_liquidity<'info>(
     let total_value = perps_pool.calculate_total_value(&oracle_prices)
         .ok_or(CloakCraftError::AmountOverflow)?;
 
-    let expected_lp = (withdraw_value as u128)
+    // Imbalance fee: withdrawals that push the token away from its target
+    // weight must burn proportionally more LP
+    let imbalance_fee_bps = perps_pool
+        .liquidity_imbalance_fee_bps(token_index, withdraw_value as u128, false, &oracle_prices)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    let lp_withdraw_value = withdraw_value.saturating_add(apply_bps(withdraw_value, imbalance_fee_bps));
+
+    let expected_lp = (lp_withdraw_value as u128)
         .checked_mul(perps_pool.lp_supply as u128)
         .ok_or(CloakCraftError::AmountOverflow)?
         .checked_div(total_value)
@@ -169,7 +177,8 @@ pub fn execute_remove_perps_liquidity<'info>(
         CloakCraftError::LpAmountMismatch
     );
 
-    msg!("Withdraw value: {} USD, LP required: {}", withdraw_value, expected_lp);
+    msg!("Withdraw value: {} USD, imbalance fee: {} bps, LP required: {}",
+        withdraw_value, imbalance_fee_bps, expected_lp);
 
     // Check utilization after withdrawal
     let new_balance = token.balance.saturating_sub(net_withdraw);
@@ -4,8 +4,10 @@ mod create_pending_with_proof_add_liquidity;
 mod execute_add_liquidity;
 mod create_pending_with_proof_remove_liquidity;
 mod execute_remove_liquidity;
+mod create_pending_with_proof_convert_lp_note;
 
 pub use create_pending_with_proof_add_liquidity::*;
 pub use execute_add_liquidity::*;
 pub use create_pending_with_proof_remove_liquidity::*;
 pub use execute_remove_liquidity::*;
+pub use create_pending_with_proof_convert_lp_note::*;
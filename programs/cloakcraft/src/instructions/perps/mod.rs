@@ -5,13 +5,16 @@
 //! - Private positions and liquidity via ZK proofs
 //! - Bounded profit model (max profit = margin)
 //! - Utilization-based constraints
+//! - Resting limit orders at a lower (maker) fee
 
 pub mod admin;
 pub mod position;
 pub mod liquidity;
 pub mod keeper;
+pub mod order;
 
 pub use admin::*;
 pub use position::*;
 pub use liquidity::*;
 pub use keeper::*;
+pub use order::*;
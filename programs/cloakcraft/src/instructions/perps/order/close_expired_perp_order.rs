@@ -0,0 +1,43 @@
+//! Close Expired Perp Order
+//!
+//! Permissionless: closes an order past its expiry and refunds the rent to
+//! the account that paid it. The margin note was never spent.
+
+use anchor_lang::prelude::*;
+
+use crate::state::PerpOrder;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+#[instruction(order_id: [u8; 32])]
+pub struct CloseExpiredPerpOrder<'info> {
+    /// Expired order (closed to its payer)
+    #[account(
+        mut,
+        seeds = [seeds::PERP_ORDER, order_id.as_ref()],
+        bump = perp_order.bump,
+        close = order_payer,
+    )]
+    pub perp_order: Box<Account<'info, PerpOrder>>,
+
+    /// Account that paid the order rent
+    /// CHECK: Must match perp_order.payer
+    #[account(
+        mut,
+        address = perp_order.payer @ CloakCraftError::InvalidOrderPayer,
+    )]
+    pub order_payer: UncheckedAccount<'info>,
+}
+
+pub fn close_expired_perp_order(ctx: Context<CloseExpiredPerpOrder>, _order_id: [u8; 32]) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(
+        ctx.accounts.perp_order.is_expired(clock.unix_timestamp),
+        CloakCraftError::PerpOrderNotExpired
+    );
+
+    msg!("Expired perp order closed");
+
+    Ok(())
+}
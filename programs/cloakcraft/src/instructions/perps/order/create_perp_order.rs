@@ -0,0 +1,209 @@
+//! Create Perp Order
+//!
+//! Places a resting limit order to open a perps position. The open-position
+//! proof is verified here, with the position commitment's entry price set to
+//! the limit price; nothing is spent until a keeper fills the order.
+//!
+//! Resting orders pay the pool's maker fee instead of the position fee.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, PerpsPool, PerpsMarket, PerpOrder, VerificationKey, ProgramVersion, MAX_PERP_ORDER_NOTE_SIZE};
+use crate::constants::{seeds, circuits};
+use crate::errors::CloakCraftError;
+use crate::helpers::verify_groth16_proof;
+use crate::instructions::perps::position::build_open_position_inputs;
+
+/// Emitted when a perps limit order is placed
+#[event]
+pub struct PerpOrderCreated {
+    pub order_id: [u8; 32],
+    pub perps_market: Pubkey,
+    pub is_long: bool,
+    pub limit_price: u64,
+    pub expiry: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(order_id: [u8; 32])]
+pub struct CreatePerpOrder<'info> {
+    /// Margin token pool (where the margin commitment is spent from)
+    #[account(
+        seeds = [seeds::POOL, margin_pool.token_mint.as_ref()],
+        bump = margin_pool.bump,
+    )]
+    pub margin_pool: Box<Account<'info, Pool>>,
+
+    /// Position pool (where position commitments are stored)
+    #[account(
+        seeds = [seeds::POOL, position_pool.token_mint.as_ref()],
+        bump = position_pool.bump,
+        constraint = position_pool.token_mint == perps_pool.position_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub position_pool: Box<Account<'info, Pool>>,
+
+    /// Perps pool
+    #[account(
+        seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
+        bump = perps_pool.bump,
+        constraint = !perps_pool.is_migrating @ CloakCraftError::PerpsPoolMigrating,
+        constraint = perps_pool.is_active @ CloakCraftError::PerpsPoolNotActive,
+    )]
+    pub perps_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Market being traded
+    #[account(
+        seeds = [seeds::PERPS_MARKET, perps_pool.key().as_ref(), perps_market.market_id.as_ref()],
+        bump = perps_market.bump,
+        constraint = perps_market.pool == perps_pool.key() @ CloakCraftError::PerpsMarketNotFound,
+        constraint = perps_market.accepts_opens() @ CloakCraftError::PerpsMarketNotActive,
+    )]
+    pub perps_market: Box<Account<'info, PerpsMarket>>,
+
+    /// Verification key for the open position circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, circuits::PERPS_OPEN_POSITION.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Order account (created)
+    #[account(
+        init,
+        payer = relayer,
+        space = 8 + PerpOrder::INIT_SPACE,
+        seeds = [seeds::PERP_ORDER, order_id.as_ref()],
+        bump,
+    )]
+    pub perp_order: Box<Account<'info, PerpOrder>>,
+
+    /// Relayer (pays for the order account)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_perp_order<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePerpOrder<'info>>,
+    order_id: [u8; 32],
+    proof: Vec<u8>,
+    merkle_root: [u8; 32],
+    input_commitment: [u8; 32],
+    nullifier: [u8; 32],
+    position_commitment: [u8; 32],
+    change_commitment: [u8; 32],
+    is_long: bool,
+    margin_amount: u64,
+    leverage: u8,
+    position_fee: u64,
+    change_amount: u64,
+    limit_price: u64,
+    expiry: i64,
+    stealth_ephemeral_pubkeys: [[u8; 64]; 2],
+    encrypted_notes: Vec<Vec<u8>>,
+    client_version: u32,
+) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    let perps_pool = &ctx.accounts.perps_pool;
+    let perps_market = &ctx.accounts.perps_market;
+    let clock = Clock::get()?;
+
+    require!(
+        leverage >= 1 && leverage <= perps_pool.max_leverage,
+        CloakCraftError::LeverageExceeded
+    );
+    require!(margin_amount > 0, CloakCraftError::InvalidMarginAmount);
+    require!(limit_price > 0, CloakCraftError::InvalidLimitPrice);
+    require!(expiry > clock.unix_timestamp, CloakCraftError::PerpOrderExpired);
+    require!(
+        encrypted_notes.len() == if change_amount > 0 { 2 } else { 1 }
+            && encrypted_notes.iter().all(|n| n.len() <= MAX_PERP_ORDER_NOTE_SIZE),
+        CloakCraftError::InvalidEncryptedNote
+    );
+
+    // Resting orders pay at least the pool's maker fee
+    let position_size = (margin_amount as u128)
+        .checked_mul(leverage as u128)
+        .ok_or(CloakCraftError::AmountOverflow)? as u64;
+    require!(
+        perps_market.check_position_size(position_size),
+        CloakCraftError::PositionSizeExceeded
+    );
+    require!(
+        position_fee >= perps_pool.min_position_fee(position_size, true),
+        CloakCraftError::InsufficientPositionFee
+    );
+
+    // No dust positions or change notes
+    require!(
+        perps_pool.meets_position_minimums(margin_amount, position_size),
+        CloakCraftError::PositionBelowMinimum
+    );
+    require!(
+        !ctx.accounts.margin_pool.is_dust(change_amount),
+        CloakCraftError::DustNote
+    );
+
+    let public_inputs = build_open_position_inputs(
+        &merkle_root,
+        &nullifier,
+        &perps_pool.pool_id,
+        &perps_market.market_id,
+        &position_commitment,
+        &change_commitment,
+        is_long,
+        margin_amount,
+        leverage,
+        position_fee,
+        change_amount,
+    );
+    verify_groth16_proof(&proof, &ctx.accounts.verification_key.vk_data, &public_inputs, "OpenPosition")?;
+
+    let order = &mut ctx.accounts.perp_order;
+    order.order_id = order_id;
+    order.perps_pool = perps_pool.key();
+    order.perps_market = perps_market.key();
+    order.margin_pool = ctx.accounts.margin_pool.key();
+    order.position_pool = ctx.accounts.position_pool.key();
+    order.input_commitment = input_commitment;
+    order.nullifier = nullifier;
+    order.position_commitment = position_commitment;
+    order.change_commitment = change_commitment;
+    order.is_long = is_long;
+    order.margin_amount = margin_amount;
+    order.leverage = leverage;
+    order.position_fee = position_fee;
+    order.change_amount = change_amount;
+    order.limit_price = limit_price;
+    order.expiry = expiry;
+    order.created_at = clock.unix_timestamp;
+    order.stealth_ephemeral_pubkeys = stealth_ephemeral_pubkeys;
+    order.encrypted_notes = encrypted_notes;
+    order.payer = ctx.accounts.relayer.key();
+    order.bump = ctx.bumps.perp_order;
+
+    emit!(PerpOrderCreated {
+        order_id,
+        perps_market: perps_market.key(),
+        is_long,
+        limit_price,
+        expiry,
+    });
+
+    msg!("Perp limit order created: {} @ {}", if is_long { "LONG" } else { "SHORT" }, limit_price);
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+//! Execute Perp Order Fill
+//!
+//! Keeper instruction: once the oracle price crosses an order's limit, creates
+//! the open-position PendingOperation from the order's stored proof bindings
+//! (equivalent to Phase 0) and closes the order. The keeper then runs the
+//! remaining open-position phases:
+//! Phase 1: Verify commitment exists (margin)
+//! Phase 2: Create nullifier (spend margin)
+//! Phase 3: Execute open position (lock tokens, update market OI)
+//! Phase 4: Create commitment (position)
+//! Final: Close pending operation
+
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::state::{PerpsPool, PerpsMarket, PerpOrder, PendingOperation, ProtocolConfig};
+use crate::constants::{seeds, operation_types};
+use crate::errors::CloakCraftError;
+use crate::pyth;
+
+/// Emitted when a perps limit order is filled
+#[event]
+pub struct PerpOrderFilled {
+    pub order_id: [u8; 32],
+    pub operation_id: [u8; 32],
+    pub limit_price: u64,
+    pub oracle_price: u64,
+    pub keeper: Pubkey,
+}
+
+#[derive(Accounts)]
+#[instruction(order_id: [u8; 32], operation_id: [u8; 32])]
+pub struct ExecutePerpOrderFill<'info> {
+    /// Order being filled (closed to its payer)
+    #[account(
+        mut,
+        seeds = [seeds::PERP_ORDER, order_id.as_ref()],
+        bump = perp_order.bump,
+        close = order_payer,
+    )]
+    pub perp_order: Box<Account<'info, PerpOrder>>,
+
+    /// Account that paid the order rent
+    /// CHECK: Must match perp_order.payer
+    #[account(
+        mut,
+        address = perp_order.payer @ CloakCraftError::InvalidOrderPayer,
+    )]
+    pub order_payer: UncheckedAccount<'info>,
+
+    /// Perps pool
+    #[account(
+        seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
+        bump = perps_pool.bump,
+        constraint = !perps_pool.is_migrating @ CloakCraftError::PerpsPoolMigrating,
+        constraint = perps_pool.key() == perp_order.perps_pool @ CloakCraftError::PerpsMarketNotFound,
+        constraint = perps_pool.is_active @ CloakCraftError::PerpsPoolNotActive,
+    )]
+    pub perps_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Market of the order
+    #[account(
+        constraint = perps_market.key() == perp_order.perps_market @ CloakCraftError::PerpsMarketNotFound,
+        constraint = perps_market.accepts_opens() @ CloakCraftError::PerpsMarketNotActive,
+    )]
+    pub perps_market: Box<Account<'info, PerpsMarket>>,
+
+    /// Pending operation PDA (created in this instruction)
+    #[account(
+        init,
+        payer = keeper,
+        space = PendingOperation::SPACE,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Keeper (becomes the relayer of the pending operation)
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Pyth price update account for the base token
+    pub price_update: Box<Account<'info, PriceUpdateV2>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn execute_perp_order_fill<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecutePerpOrderFill<'info>>,
+    order_id: [u8; 32],
+    operation_id: [u8; 32],
+) -> Result<()> {
+    let order = &ctx.accounts.perp_order;
+    let perps_pool = &ctx.accounts.perps_pool;
+    let perps_market = &ctx.accounts.perps_market;
+    let clock = Clock::get()?;
+
+    require!(!order.is_expired(clock.unix_timestamp), CloakCraftError::PerpOrderExpired);
+
+    // Oracle must cross the limit
+    let base_token = perps_pool.get_token(perps_market.base_token_index)
+        .ok_or(CloakCraftError::TokenNotInPool)?;
+    let oracle_price = pyth::get_price(&ctx.accounts.price_update, &base_token.pyth_feed_id, &clock)?;
+    require!(order.is_triggered(oracle_price), CloakCraftError::PerpOrderNotTriggered);
+
+    // Same bindings as create_pending_with_proof_open_position
+    let pending_op = &mut ctx.accounts.pending_operation;
+    pending_op.bump = ctx.bumps.pending_operation;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
+    pending_op.relayer = ctx.accounts.keeper.key();
+    pending_op.operation_type = operation_types::PERPS_OPEN_POSITION;
+    pending_op.bind_circuit(&crate::constants::circuits::PERPS_OPEN_POSITION);
+    pending_op.created_at = clock.unix_timestamp;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
+
+    pending_op.num_inputs = 1;
+    pending_op.input_commitments[0] = order.input_commitment;
+    pending_op.expected_nullifiers[0] = order.nullifier;
+    pending_op.input_pools[0] = order.margin_pool.to_bytes();
+    pending_op.inputs_verified_mask = 0;
+    pending_op.proof_verified = true;
+
+    pending_op.pools[0] = order.position_pool.to_bytes();
+    pending_op.commitments[0] = order.position_commitment;
+    pending_op.output_amounts[0] = 1; // Non-zero to indicate valid output (position)
+    if order.change_amount > 0 {
+        pending_op.num_commitments = 2;
+        pending_op.pools[1] = order.margin_pool.to_bytes();
+        pending_op.commitments[1] = order.change_commitment;
+        pending_op.output_amounts[1] = order.change_amount;
+    } else {
+        pending_op.num_commitments = 1;
+    }
+
+    pending_op.nullifier_completed_mask = 0;
+    pending_op.completed_mask = 0;
+
+    pending_op.swap_amount = order.margin_amount;
+    pending_op.output_amount = order.leverage as u64;
+    pending_op.min_output = order.position_fee;
+    pending_op.swap_a_to_b = order.is_long;
+    pending_op.extra_amount = order.change_amount;
+
+    emit!(PerpOrderFilled {
+        order_id,
+        operation_id,
+        limit_price: order.limit_price,
+        oracle_price,
+        keeper: ctx.accounts.keeper.key(),
+    });
+
+    msg!("Perp limit order filled: limit={}, oracle={}", order.limit_price, oracle_price);
+    msg!("Next: Phase 1 - verify_commitment_exists");
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+//! Perps limit order instructions
+//!
+//! Resting orders layered on the open-position flow:
+//! - Create: Verify the open-position proof up front, store it with a limit price
+//! - Fill: Keeper turns the order into an open-position PendingOperation once
+//!   the oracle crosses the limit (then Phases 1-4 run as for a market order)
+//! - Close expired: Anyone reclaims the rent of an expired order
+
+mod create_perp_order;
+mod execute_perp_order_fill;
+mod close_expired_perp_order;
+
+pub use create_perp_order::*;
+pub use execute_perp_order_fill::*;
+pub use close_expired_perp_order::*;
@@ -6,7 +6,9 @@
 //! SECURITY: This phase extracts and stores:
 //! - position_commitment (from proof public inputs)
 //! - expected_nullifier (position nullifier)
-//! - settlement_commitment (margin +/- PnL)
+//! - settlement_commitment (margin +/- PnL - fees)
+//! - borrow_fee (accrued since the claimed entry snapshot, computed here
+//!   from the pool's borrow fee accumulator and bound as a public input)
 //!
 //! Flow:
 //! Phase 0 (this): Verify ZK proof + Create PendingOperation
@@ -18,11 +20,12 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, PerpsPool, PerpsMarket, VerificationKey, PendingOperation, PENDING_OPERATION_EXPIRY_SECONDS};
+use crate::state::{Pool, PerpsPool, PerpsMarket, VerificationKey, PendingOperation, ProtocolConfig, ProgramVersion, CircuitStats, OperationCredit};
 use crate::constants::{seeds, operation_types};
 use crate::errors::CloakCraftError;
-use crate::helpers::verify_groth16_proof;
-use crate::helpers::field::pubkey_to_field;
+use crate::helpers::verify_groth16_proof_metered;
+use crate::helpers::field::{pubkey_to_field, assert_canonical};
+use super::close_gross_settlement;
 
 #[derive(Accounts)]
 #[instruction(operation_id: [u8; 32])]
@@ -61,6 +64,7 @@ pub struct CreatePendingWithProofClosePosition<'info> {
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
@@ -78,11 +82,39 @@ pub struct CreatePendingWithProofClosePosition<'info> {
     #[account(mut)]
     pub relayer: Signer<'info>,
 
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
     /// System program
     pub system_program: Program<'info, System>,
 }
 
 /// Phase 0: Verify ZK proof and create PendingOperation for close position
+///
+/// `entry_borrow_fee` is the snapshot in the position's PositionMeta;
+/// verify_position_meta_active rejects the operation if it differs.
 #[allow(clippy::too_many_arguments)]
 pub fn create_pending_with_proof_close_position<'info>(
     ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofClosePosition<'info>>,
@@ -97,7 +129,24 @@ pub fn create_pending_with_proof_close_position<'info>(
     close_fee: u64,
     pnl_amount: u64,
     is_profit: bool,
+    position_margin: u64,
+    position_size: u64,
+    entry_price: u64,
+    entry_borrow_fee: u128,
+    client_version: u32,
 ) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[position_commitment, position_nullifier, settlement_commitment])?;
+
     let position_pool = &ctx.accounts.position_pool;
     let settlement_pool = &ctx.accounts.settlement_pool;
     let perps_pool = &ctx.accounts.perps_pool;
@@ -106,7 +155,18 @@ pub fn create_pending_with_proof_close_position<'info>(
 
     msg!("=== Phase 0: Verify Proof + Create Pending (Close Position) ===");
 
-    // 1. Verify ZK proof (9 public inputs matching Circom circuit)
+    // Borrow fee accrued since open, capped at what the position can pay.
+    // The proof deducts exactly this amount, so Phase 3 credits LPs with it.
+    let capped_pnl = if is_profit { pnl_amount.min(position_margin) } else { pnl_amount };
+    let gross_settlement = close_gross_settlement(position_margin, capped_pnl, is_profit, close_fee)?;
+    let borrow_fee = perps_pool
+        .get_token(ctx.accounts.perps_market.borrow_token_index(is_long))
+        .ok_or(CloakCraftError::TokenNotInPool)?
+        .accrued_borrow_fee(position_size, entry_borrow_fee)
+        .min(gross_settlement);
+    msg!("Borrow fee: {}", borrow_fee);
+
+    // 1. Verify ZK proof (14 public inputs matching Circom circuit)
     let mut exit_price_bytes = [0u8; 32];
     exit_price_bytes[24..].copy_from_slice(&exit_price.to_be_bytes());
 
@@ -122,6 +182,18 @@ pub fn create_pending_with_proof_close_position<'info>(
     let mut is_profit_bytes = [0u8; 32];
     is_profit_bytes[31] = if is_profit { 1 } else { 0 };
 
+    let mut margin_bytes = [0u8; 32];
+    margin_bytes[24..].copy_from_slice(&position_margin.to_be_bytes());
+
+    let mut size_bytes = [0u8; 32];
+    size_bytes[24..].copy_from_slice(&position_size.to_be_bytes());
+
+    let mut entry_price_bytes = [0u8; 32];
+    entry_price_bytes[24..].copy_from_slice(&entry_price.to_be_bytes());
+
+    let mut borrow_fee_bytes = [0u8; 32];
+    borrow_fee_bytes[24..].copy_from_slice(&borrow_fee.to_be_bytes());
+
     let public_inputs = vec![
         merkle_root,
         position_nullifier,
@@ -132,18 +204,31 @@ pub fn create_pending_with_proof_close_position<'info>(
         close_fee_bytes,
         pnl_bytes,
         is_profit_bytes,
+        position_commitment,
+        margin_bytes,
+        size_bytes,
+        entry_price_bytes,
+        borrow_fee_bytes,
     ];
 
-    verify_groth16_proof(&proof, &ctx.accounts.verification_key.vk_data, &public_inputs, "ClosePosition")?;
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "ClosePosition",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
     msg!("✅ ZK proof verified");
 
     // 2. Initialize pending operation PDA with binding fields
     pending_op.bump = ctx.bumps.pending_operation;
-    pending_op.operation_id = operation_id;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
     pending_op.relayer = ctx.accounts.relayer.key();
     pending_op.operation_type = operation_types::PERPS_CLOSE_POSITION;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
     pending_op.created_at = clock.unix_timestamp;
-    pending_op.expires_at = clock.unix_timestamp + PENDING_OPERATION_EXPIRY_SECONDS;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
 
     // SECURITY: Store binding fields from ZK proof
     pending_op.num_inputs = 1;
@@ -168,6 +253,11 @@ pub fn create_pending_with_proof_close_position<'info>(
     pending_op.min_output = close_fee;
     pending_op.swap_a_to_b = is_long;
     pending_op.extra_amount = if is_profit { 1 } else { 0 };
+    pending_op.position_margin = position_margin;
+    pending_op.position_size = position_size;
+    pending_op.position_entry_price = entry_price;
+    pending_op.position_entry_borrow_fee = entry_borrow_fee;
+    pending_op.position_borrow_fee = borrow_fee;
 
     msg!("Phase 0 complete: ZK proof verified, pending operation created");
     msg!("Next: Phase 1 - verify_commitment_exists");
@@ -18,11 +18,11 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, PerpsPool, PerpsMarket, VerificationKey, PendingOperation, PENDING_OPERATION_EXPIRY_SECONDS};
-use crate::constants::{seeds, operation_types};
+use crate::state::{Pool, PerpsPool, PerpsMarket, VerificationKey, PendingOperation, ProtocolConfig, ProgramVersion, CircuitStats, OperationCredit};
+use crate::constants::{circuits, seeds, operation_types};
 use crate::errors::CloakCraftError;
-use crate::helpers::verify_groth16_proof;
-use crate::helpers::field::{pubkey_to_field, bytes_to_field};
+use crate::helpers::verify_groth16_proof_metered;
+use crate::helpers::field::{pubkey_to_field, bytes_to_field, assert_canonical};
 
 #[derive(Accounts)]
 #[instruction(operation_id: [u8; 32])]
@@ -46,6 +46,7 @@ pub struct CreatePendingWithProofOpenPosition<'info> {
     #[account(
         seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
         bump = perps_pool.bump,
+        constraint = !perps_pool.is_migrating @ CloakCraftError::PerpsPoolMigrating,
         constraint = perps_pool.is_active @ CloakCraftError::PerpsPoolNotActive,
     )]
     pub perps_pool: Box<Account<'info, PerpsPool>>,
@@ -55,7 +56,7 @@ pub struct CreatePendingWithProofOpenPosition<'info> {
         seeds = [seeds::PERPS_MARKET, perps_pool.key().as_ref(), perps_market.market_id.as_ref()],
         bump = perps_market.bump,
         constraint = perps_market.pool == perps_pool.key() @ CloakCraftError::PerpsMarketNotFound,
-        constraint = perps_market.is_active @ CloakCraftError::PerpsMarketNotActive,
+        constraint = perps_market.accepts_opens() @ CloakCraftError::PerpsMarketNotActive,
     )]
     pub perps_market: Box<Account<'info, PerpsMarket>>,
 
@@ -63,6 +64,7 @@ pub struct CreatePendingWithProofOpenPosition<'info> {
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
@@ -80,6 +82,31 @@ pub struct CreatePendingWithProofOpenPosition<'info> {
     #[account(mut)]
     pub relayer: Signer<'info>,
 
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
     /// System program
     pub system_program: Program<'info, System>,
 }
@@ -100,16 +127,42 @@ pub fn create_pending_with_proof_open_position<'info>(
     leverage: u8,
     position_fee: u64,
     change_amount: u64,
+    client_version: u32,
 ) -> Result<()> {
-    let margin_pool = &ctx.accounts.margin_pool;
-    let position_pool = &ctx.accounts.position_pool;
-    let perps_pool = &ctx.accounts.perps_pool;
-    let perps_market = &ctx.accounts.perps_market;
-    let pending_op = &mut ctx.accounts.pending_operation;
-    let clock = Clock::get()?;
+    create_pending_open_position(
+        ctx,
+        operation_id,
+        proof,
+        merkle_root,
+        input_commitment,
+        nullifier,
+        position_commitment,
+        change_commitment,
+        is_long,
+        PositionTerms::Public { margin_amount, leverage, position_fee },
+        change_amount,
+        client_version,
+    )
+}
 
-    msg!("=== Phase 0: Verify Proof + Create Pending (Open Position) ===");
+/// Position terms given in Phase 0
+pub(crate) enum PositionTerms {
+    /// Stored as is for execute_open_position
+    Public { margin_amount: u64, leverage: u8, position_fee: u64 },
+    /// Only the terms hash (bound by the open_position_sealed proof) is
+    /// stored; revealed in execute_open_position_revealed
+    Sealed([u8; 32]),
+}
 
+/// Validate margin, leverage and fee of a market order open
+///
+/// Returns the position size (margin * leverage).
+pub(crate) fn check_position_terms(
+    perps_pool: &PerpsPool,
+    margin_amount: u64,
+    leverage: u8,
+    position_fee: u64,
+) -> Result<u64> {
     // Validate leverage
     require!(
         leverage >= 1 && leverage <= perps_pool.max_leverage,
@@ -119,47 +172,129 @@ pub fn create_pending_with_proof_open_position<'info>(
     // Validate margin amount
     require!(margin_amount > 0, CloakCraftError::InvalidMarginAmount);
 
-    // 1. Verify ZK proof (11 public inputs matching Circom circuit)
-    let mut margin_bytes = [0u8; 32];
-    margin_bytes[24..].copy_from_slice(&margin_amount.to_be_bytes());
+    // Market orders pay at least the pool's position fee
+    let position_size = (margin_amount as u128)
+        .checked_mul(leverage as u128)
+        .ok_or(CloakCraftError::AmountOverflow)? as u64;
+    require!(
+        position_fee >= perps_pool.min_position_fee(position_size, false),
+        CloakCraftError::InsufficientPositionFee
+    );
 
-    let mut leverage_bytes = [0u8; 32];
-    leverage_bytes[31] = leverage;
+    // No dust positions
+    require!(
+        perps_pool.meets_position_minimums(margin_amount, position_size),
+        CloakCraftError::PositionBelowMinimum
+    );
 
-    let mut fee_bytes = [0u8; 32];
-    fee_bytes[24..].copy_from_slice(&position_fee.to_be_bytes());
+    Ok(position_size)
+}
 
-    let mut is_long_bytes = [0u8; 32];
-    is_long_bytes[31] = if is_long { 1 } else { 0 };
+/// Shared Phase 0 of public and sealed opens
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_pending_open_position<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofOpenPosition<'info>>,
+    operation_id: [u8; 32],
+    proof: Vec<u8>,
+    merkle_root: [u8; 32],
+    input_commitment: [u8; 32],
+    nullifier: [u8; 32],
+    position_commitment: [u8; 32],
+    change_commitment: [u8; 32],
+    is_long: bool,
+    terms: PositionTerms,
+    change_amount: u64,
+    client_version: u32,
+) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
 
-    let mut change_amount_bytes = [0u8; 32];
-    change_amount_bytes[24..].copy_from_slice(&change_amount.to_be_bytes());
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
 
-    let public_inputs = vec![
-        merkle_root,
-        nullifier,
-        pubkey_to_field(&perps_pool.pool_id),
-        // IMPORTANT: market_id must be reduced to field element to match SDK's bytesToField
-        bytes_to_field(&perps_market.market_id),
-        position_commitment,
-        change_commitment,
-        is_long_bytes,
-        margin_bytes,
-        leverage_bytes,
-        fee_bytes,
-        change_amount_bytes,
-    ];
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[input_commitment, nullifier, position_commitment, change_commitment])?;
 
-    verify_groth16_proof(&proof, &ctx.accounts.verification_key.vk_data, &public_inputs, "OpenPosition")?;
+    let margin_pool = &ctx.accounts.margin_pool;
+    let position_pool = &ctx.accounts.position_pool;
+    let perps_pool = &ctx.accounts.perps_pool;
+    let perps_market = &ctx.accounts.perps_market;
+    let pending_op = &mut ctx.accounts.pending_operation;
+    let clock = Clock::get()?;
+
+    msg!("=== Phase 0: Verify Proof + Create Pending (Open Position) ===");
+
+    // No dust change notes
+    require!(!margin_pool.is_dust(change_amount), CloakCraftError::DustNote);
+
+    // 1. Verify ZK proof (11 public inputs matching Circom circuit; the
+    // sealed circuit replaces margin, leverage and fee by the terms hash)
+    let public_inputs = match terms {
+        PositionTerms::Public { margin_amount, leverage, position_fee } => {
+            check_position_terms(perps_pool, margin_amount, leverage, position_fee)?;
+            build_open_position_inputs(
+                &merkle_root,
+                &nullifier,
+                &perps_pool.pool_id,
+                &perps_market.market_id,
+                &position_commitment,
+                &change_commitment,
+                is_long,
+                margin_amount,
+                leverage,
+                position_fee,
+                change_amount,
+            )
+        }
+        PositionTerms::Sealed(terms_hash) => {
+            require!(
+                ctx.accounts.verification_key.circuit_id == circuits::PERPS_OPEN_POSITION_SEALED,
+                CloakCraftError::SealedPositionCircuitRequired
+            );
+            require!(terms_hash != [0u8; 32], CloakCraftError::SealedPositionTermsMismatch);
+            assert_canonical(&[terms_hash])?;
+
+            let mut is_long_bytes = [0u8; 32];
+            is_long_bytes[31] = if is_long { 1 } else { 0 };
+
+            let mut change_amount_bytes = [0u8; 32];
+            change_amount_bytes[24..].copy_from_slice(&change_amount.to_be_bytes());
+
+            vec![
+                merkle_root,
+                nullifier,
+                pubkey_to_field(&perps_pool.pool_id),
+                bytes_to_field(&perps_market.market_id),
+                position_commitment,
+                change_commitment,
+                is_long_bytes,
+                change_amount_bytes,
+                terms_hash,
+            ]
+        }
+    };
+
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "OpenPosition",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
     msg!("✅ ZK proof verified");
 
     // 2. Initialize pending operation PDA with binding fields
     pending_op.bump = ctx.bumps.pending_operation;
-    pending_op.operation_id = operation_id;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
     pending_op.relayer = ctx.accounts.relayer.key();
     pending_op.operation_type = operation_types::PERPS_OPEN_POSITION;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
     pending_op.created_at = clock.unix_timestamp;
-    pending_op.expires_at = clock.unix_timestamp + PENDING_OPERATION_EXPIRY_SECONDS;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
 
     // SECURITY: Store binding fields from ZK proof
     pending_op.num_inputs = 1;
@@ -189,9 +324,18 @@ pub fn create_pending_with_proof_open_position<'info>(
 
     // Store position-specific data for Phase 3
     // Reusing existing fields
-    pending_op.swap_amount = margin_amount;
-    pending_op.output_amount = leverage as u64; // Store leverage in output_amount
-    pending_op.min_output = position_fee; // Store fee in min_output
+    match terms {
+        PositionTerms::Public { margin_amount, leverage, position_fee } => {
+            pending_op.swap_amount = margin_amount;
+            pending_op.output_amount = leverage as u64; // Store leverage in output_amount
+            pending_op.min_output = position_fee; // Store fee in min_output
+            pending_op.call_hash = [0u8; 32];
+        }
+        PositionTerms::Sealed(terms_hash) => {
+            // Margin, leverage and fee stay hidden until execute_open_position_revealed
+            pending_op.call_hash = terms_hash;
+        }
+    }
     pending_op.swap_a_to_b = is_long;
 
     // Store change_amount in extra_amount field for reference
@@ -202,3 +346,50 @@ pub fn create_pending_with_proof_open_position<'info>(
 
     Ok(())
 }
+
+/// Public inputs of the open position circuit, in circuit order
+/// (shared with limit orders, which verify the same proof when placed)
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn build_open_position_inputs(
+    merkle_root: &[u8; 32],
+    nullifier: &[u8; 32],
+    pool_id: &Pubkey,
+    market_id: &[u8; 32],
+    position_commitment: &[u8; 32],
+    change_commitment: &[u8; 32],
+    is_long: bool,
+    margin_amount: u64,
+    leverage: u8,
+    position_fee: u64,
+    change_amount: u64,
+) -> Vec<[u8; 32]> {
+    let mut margin_bytes = [0u8; 32];
+    margin_bytes[24..].copy_from_slice(&margin_amount.to_be_bytes());
+
+    let mut leverage_bytes = [0u8; 32];
+    leverage_bytes[31] = leverage;
+
+    let mut fee_bytes = [0u8; 32];
+    fee_bytes[24..].copy_from_slice(&position_fee.to_be_bytes());
+
+    let mut is_long_bytes = [0u8; 32];
+    is_long_bytes[31] = if is_long { 1 } else { 0 };
+
+    let mut change_amount_bytes = [0u8; 32];
+    change_amount_bytes[24..].copy_from_slice(&change_amount.to_be_bytes());
+
+    vec![
+        *merkle_root,
+        *nullifier,
+        pubkey_to_field(pool_id),
+        // IMPORTANT: market_id must be reduced to field element to match SDK's bytesToField
+        bytes_to_field(market_id),
+        *position_commitment,
+        *change_commitment,
+        is_long_bytes,
+        margin_bytes,
+        leverage_bytes,
+        fee_bytes,
+        change_amount_bytes,
+    ]
+}
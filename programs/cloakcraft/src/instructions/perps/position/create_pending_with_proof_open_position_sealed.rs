@@ -0,0 +1,91 @@
+//! Create Pending Operation with Proof - Phase 0 (Sealed Open Position)
+//!
+//! Like create_pending_with_proof_open_position, but margin, leverage and
+//! position fee are private inputs of the open_position_sealed circuit, which
+//! binds their hash (`terms_hash`, see `compute_position_terms_hash`)
+//! instead. The user seals the terms and salt to the relayer's session key
+//! (SDK `encryptPositionTerms`); the relayer reveals them in
+//! execute_open_position_revealed, where the market order checks run.
+//!
+//! Direction and change amount stay public.
+
+use anchor_lang::prelude::*;
+use light_hasher::{Hasher, Poseidon};
+
+use super::create_pending_with_proof_open_position::{
+    create_pending_open_position, CreatePendingWithProofOpenPosition, PositionTerms,
+};
+use crate::errors::CloakCraftError;
+use crate::helpers::field::u64_to_field;
+
+/// Poseidon domain of sealed position terms (matches the open_position_sealed circuit)
+pub const POSITION_TERMS_DOMAIN: u64 = 0x21;
+
+/// Hash of a sealed open's hidden terms
+///
+/// Poseidon(POSITION_TERMS_DOMAIN, margin_amount, leverage, position_fee,
+/// salt). The salt must be a canonical field element.
+pub fn compute_position_terms_hash(
+    margin_amount: u64,
+    leverage: u8,
+    position_fee: u64,
+    salt: &[u8; 32],
+) -> Result<[u8; 32]> {
+    Poseidon::hashv(&[
+        &u64_to_field(POSITION_TERMS_DOMAIN),
+        &u64_to_field(margin_amount),
+        &u64_to_field(leverage as u64),
+        &u64_to_field(position_fee),
+        salt,
+    ])
+    .map_err(|_| CloakCraftError::SealedPositionTermsMismatch.into())
+}
+
+/// Phase 0: Verify ZK proof and create PendingOperation for a sealed open
+#[allow(clippy::too_many_arguments)]
+pub fn create_pending_with_proof_open_position_sealed<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofOpenPosition<'info>>,
+    operation_id: [u8; 32],
+    proof: Vec<u8>,
+    merkle_root: [u8; 32],
+    input_commitment: [u8; 32],
+    nullifier: [u8; 32],
+    position_commitment: [u8; 32],
+    change_commitment: [u8; 32],
+    is_long: bool,
+    change_amount: u64,
+    terms_hash: [u8; 32],
+    client_version: u32,
+) -> Result<()> {
+    create_pending_open_position(
+        ctx,
+        operation_id,
+        proof,
+        merkle_root,
+        input_commitment,
+        nullifier,
+        position_commitment,
+        change_commitment,
+        is_long,
+        PositionTerms::Sealed(terms_hash),
+        change_amount,
+        client_version,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_terms_hash() {
+        let salt = u64_to_field(7);
+        let terms_hash = compute_position_terms_hash(1_000, 5, 10, &salt).unwrap();
+        assert_eq!(compute_position_terms_hash(1_000, 5, 10, &salt).unwrap(), terms_hash);
+        assert_ne!(compute_position_terms_hash(1_000, 6, 10, &salt).unwrap(), terms_hash);
+        assert_ne!(compute_position_terms_hash(1_000, 5, 10, &u64_to_field(8)).unwrap(), terms_hash);
+
+        // Salt must be a canonical field element
+        assert!(compute_position_terms_hash(1_000, 5, 10, &[0xff; 32]).is_err());
+    }
+}
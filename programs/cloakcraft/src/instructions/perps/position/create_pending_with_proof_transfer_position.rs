@@ -0,0 +1,222 @@
+//! Create Pending Operation with Proof - Phase 0 (Transfer Position)
+//!
+//! Moves an open perps position to a new stealth owner without closing it.
+//! The position commitment is nullified and re-created with the same market,
+//! direction, margin, size, leverage and entry price; only the owner changes.
+//!
+//! SECURITY: This phase extracts and stores:
+//! - position_commitment (from proof public inputs)
+//! - expected_nullifier (position nullifier)
+//! - new_position_commitment (same terms, new owner)
+//!
+//! Flow:
+//! Phase 0 (this): Verify ZK proof + Create PendingOperation
+//! Phase 1a: Verify commitment exists (position)
+//! Phase 1b: verify_position_meta_active (check not liquidated)
+//! Phase 2: Create nullifier (spend old position)
+//! Phase 4a: Create commitment (new position)
+//! Phase 4b: create_position_meta_transfer (mark old Transferred, new PositionMeta)
+//! Final: Close pending operation
+//!
+//! There is no Phase 3: no tokens move and open interest is unchanged.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, PerpsPool, PerpsMarket, VerificationKey, PendingOperation, ProtocolConfig, ProgramVersion, CircuitStats, OperationCredit};
+use crate::constants::{seeds, operation_types, circuits};
+use crate::errors::CloakCraftError;
+use crate::helpers::verify_groth16_proof_metered;
+use crate::helpers::field::{pubkey_to_field, bytes_to_field, assert_canonical};
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct CreatePendingWithProofTransferPosition<'info> {
+    /// Position pool (position commitment is read from and re-created in it)
+    #[account(
+        seeds = [seeds::POOL, position_pool.token_mint.as_ref()],
+        bump = position_pool.bump,
+        constraint = position_pool.token_mint == perps_pool.position_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub position_pool: Box<Account<'info, Pool>>,
+
+    /// Perps pool
+    #[account(
+        seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
+        bump = perps_pool.bump,
+    )]
+    pub perps_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Market of the position
+    #[account(
+        seeds = [seeds::PERPS_MARKET, perps_pool.key().as_ref(), perps_market.market_id.as_ref()],
+        bump = perps_market.bump,
+        constraint = perps_market.pool == perps_pool.key() @ CloakCraftError::PerpsMarketNotFound,
+    )]
+    pub perps_market: Box<Account<'info, PerpsMarket>>,
+
+    /// Verification key for the transfer position circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, circuits::PERPS_TRANSFER_POSITION.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Pending operation PDA (created in this instruction)
+    #[account(
+        init,
+        payer = relayer,
+        space = PendingOperation::SPACE,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (pays for PDA creation)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Phase 0: Verify ZK proof and create PendingOperation for transfer position
+#[allow(clippy::too_many_arguments)]
+pub fn create_pending_with_proof_transfer_position<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofTransferPosition<'info>>,
+    operation_id: [u8; 32],
+    proof: Vec<u8>,
+    merkle_root: [u8; 32],
+    position_commitment: [u8; 32],
+    position_nullifier: [u8; 32],
+    new_position_commitment: [u8; 32],
+    is_long: bool,
+    margin_amount: u64,
+    position_size: u64,
+    entry_price: u64,
+    client_version: u32,
+) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[position_commitment, position_nullifier, new_position_commitment])?;
+
+    let position_pool = &ctx.accounts.position_pool;
+    let perps_pool = &ctx.accounts.perps_pool;
+    let perps_market = &ctx.accounts.perps_market;
+    let pending_op = &mut ctx.accounts.pending_operation;
+    let clock = Clock::get()?;
+
+    msg!("=== Phase 0: Verify Proof + Create Pending (Transfer Position) ===");
+
+    require!(
+        new_position_commitment != position_commitment,
+        CloakCraftError::InvalidCommitment
+    );
+
+    // 1. Verify ZK proof (10 public inputs matching Circom circuit)
+    let mut is_long_bytes = [0u8; 32];
+    is_long_bytes[31] = if is_long { 1 } else { 0 };
+
+    let mut margin_bytes = [0u8; 32];
+    margin_bytes[24..].copy_from_slice(&margin_amount.to_be_bytes());
+
+    let mut size_bytes = [0u8; 32];
+    size_bytes[24..].copy_from_slice(&position_size.to_be_bytes());
+
+    let mut entry_price_bytes = [0u8; 32];
+    entry_price_bytes[24..].copy_from_slice(&entry_price.to_be_bytes());
+
+    let public_inputs = vec![
+        merkle_root,
+        position_nullifier,
+        pubkey_to_field(&perps_pool.pool_id),
+        // market_id must be reduced to a field element to match SDK's bytesToField
+        bytes_to_field(&perps_market.market_id),
+        position_commitment,
+        new_position_commitment,
+        is_long_bytes,
+        margin_bytes,
+        size_bytes,
+        entry_price_bytes,
+    ];
+
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "TransferPosition",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
+    msg!("✅ ZK proof verified");
+
+    // 2. Initialize pending operation PDA with binding fields
+    pending_op.bump = ctx.bumps.pending_operation;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
+    pending_op.relayer = ctx.accounts.relayer.key();
+    pending_op.operation_type = operation_types::PERPS_TRANSFER_POSITION;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
+    pending_op.created_at = clock.unix_timestamp;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
+
+    // SECURITY: Store binding fields from ZK proof
+    pending_op.num_inputs = 1;
+    pending_op.input_commitments[0] = position_commitment;
+    pending_op.expected_nullifiers[0] = position_nullifier;
+    pending_op.input_pools[0] = position_pool.key().to_bytes();
+    pending_op.inputs_verified_mask = 0;
+    pending_op.proof_verified = true;
+
+    // Store output commitment (re-created position, same pool)
+    pending_op.num_commitments = 1;
+    pending_op.pools[0] = position_pool.key().to_bytes();
+    pending_op.commitments[0] = new_position_commitment;
+    pending_op.output_amounts[0] = 1; // Non-zero to indicate valid output
+
+    pending_op.nullifier_completed_mask = 0;
+    pending_op.completed_mask = 0;
+
+    // Store the carried-over terms for Phase 1b / 4b
+    pending_op.swap_amount = margin_amount;
+    pending_op.output_amount = position_size;
+    pending_op.min_output = entry_price;
+    pending_op.swap_a_to_b = is_long;
+
+    msg!("Phase 0 complete: ZK proof verified, pending operation created");
+    msg!("Next: Phase 1 - verify_commitment_exists");
+
+    Ok(())
+}
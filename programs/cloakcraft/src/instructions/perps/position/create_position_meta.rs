@@ -20,7 +20,7 @@
 use anchor_lang::prelude::*;
 
 use crate::state::{PerpsPool, PerpsMarket, PendingOperation, LightValidityProof, LightAddressTreeInfo, PositionMeta};
-use crate::constants::seeds;
+use crate::constants::{seeds, operation_types};
 use crate::errors::CloakCraftError;
 use crate::light_cpi::create_position_meta_account;
 
@@ -42,10 +42,6 @@ pub struct PositionMetaInput {
     pub position_id: [u8; 32],
     /// Liquidation price (calculated in circuit from entry, leverage, direction)
     pub liquidation_price: u64,
-    /// Entry price
-    pub entry_price: u64,
-    /// Position size (margin * leverage)
-    pub position_size: u64,
     /// Pre-committed nullifier hash: hash(nullifier)
     pub nullifier_hash: [u8; 32],
     /// Owner's stealth pubkey (for notifications)
@@ -74,6 +70,7 @@ pub struct CreatePositionMeta<'info> {
         mut,
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
+        constraint = pending_operation.operation_type == operation_types::PERPS_OPEN_POSITION @ CloakCraftError::InvalidOperationType,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -114,9 +111,13 @@ pub fn create_position_meta<'info>(
         CloakCraftError::ProofNotVerified
     );
 
-    // Extract position data from pending operation (stored in Phase 0)
-    let margin_amount = pending_op.swap_amount;
+    // Position terms recorded by execute_open_position (Phase 3)
+    require!(pending_op.position_size > 0, CloakCraftError::PositionNotOpened);
+    let margin_amount = pending_op.position_margin;
     let is_long = pending_op.swap_a_to_b;
+    let position_size = pending_op.position_size;
+    let entry_price = pending_op.position_entry_price;
+    let entry_borrow_fee = pending_op.position_entry_borrow_fee;
 
     // Validate position_id is part of the ZK proof public inputs
     // In the circuit, position_id = hash(pool_id, market_id, nullifier_key, randomness)
@@ -126,9 +127,10 @@ pub fn create_position_meta<'info>(
     msg!("  Position ID: {:02x?}...", &position_meta_input.position_id[0..8]);
     msg!("  Margin: {}", margin_amount);
     msg!("  Liquidation Price: {}", position_meta_input.liquidation_price);
-    msg!("  Entry Price: {}", position_meta_input.entry_price);
-    msg!("  Position Size: {}", position_meta_input.position_size);
+    msg!("  Entry Price: {}", entry_price);
+    msg!("  Position Size: {}", position_size);
     msg!("  Direction: {}", if is_long { "LONG" } else { "SHORT" });
+    msg!("  Entry Borrow Fee: {}", entry_borrow_fee);
 
     // Create position metadata via Light Protocol
     create_position_meta_account(
@@ -143,10 +145,12 @@ pub fn create_position_meta<'info>(
         margin_amount,
         position_meta_input.liquidation_price,
         is_long,
-        position_meta_input.position_size,
-        position_meta_input.entry_price,
+        position_size,
+        entry_price,
         position_meta_input.nullifier_hash,
         position_meta_input.owner_stealth_pubkey,
+        entry_borrow_fee,
+        pending_op.commitments[0],
     )?;
 
     msg!("✅ Phase 4b complete: PositionMeta created");
@@ -0,0 +1,166 @@
+//! Create Position Metadata Transfer - Phase 4b (Transfer Position)
+//!
+//! Hands the public PositionMeta over to the new owner:
+//! - Creates a PositionStatusRecord marking the old position_id as Transferred
+//! - Creates a new PositionMeta for the re-created position with the new owner
+//!
+//! Economic terms (margin, direction, size, entry price) come from the pending
+//! operation, where they were bound by the ZK proof in Phase 0.
+//!
+//! Flow:
+//! Phase 0: create_pending_with_proof_transfer_position (proof verified)
+//! Phase 1a: verify_commitment_exists (position commitment)
+//! Phase 1b: verify_position_meta_active (check not liquidated)
+//! Phase 2: create_nullifier (spend old position)
+//! Phase 4a: create_commitment (new position)
+//! Phase 4b (this): mark old Transferred, create new PositionMeta
+//! Final: close_pending_operation
+
+use anchor_lang::prelude::*;
+
+use crate::state::{PerpsPool, PerpsMarket, PendingOperation, PositionStatus};
+use crate::constants::{seeds, operation_types};
+use crate::errors::CloakCraftError;
+use crate::light_cpi::{create_position_meta_account, create_position_status_record};
+use super::{LightCreatePositionMetaParams, LightCreatePositionStatusParams};
+
+/// New position metadata for a transferred position
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TransferredPositionMetaInput {
+    /// Position ID of the re-created position
+    pub position_id: [u8; 32],
+    /// Liquidation price (carried over from the old PositionMeta)
+    pub liquidation_price: u64,
+    /// Entry price (must match the proof)
+    pub entry_price: u64,
+    /// Position size (must match the proof)
+    pub position_size: u64,
+    /// Pre-committed nullifier hash for the re-created position
+    pub nullifier_hash: [u8; 32],
+    /// New owner's stealth pubkey
+    pub owner_stealth_pubkey: [u8; 32],
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct CreatePositionMetaTransfer<'info> {
+    /// Perps pool
+    #[account(
+        seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
+        bump = perps_pool.bump,
+    )]
+    pub perps_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Market of the position
+    #[account(
+        seeds = [seeds::PERPS_MARKET, perps_pool.key().as_ref(), perps_market.market_id.as_ref()],
+        bump = perps_market.bump,
+        constraint = perps_market.pool == perps_pool.key() @ CloakCraftError::PerpsMarketNotFound,
+    )]
+    pub perps_market: Box<Account<'info, PerpsMarket>>,
+
+    /// Pending operation PDA (from Phase 0)
+    #[account(
+        mut,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump = pending_operation.bump,
+        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
+        constraint = pending_operation.operation_type == operation_types::PERPS_TRANSFER_POSITION @ CloakCraftError::InvalidOperationType,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (pays for both compressed accounts)
+    #[account(
+        mut,
+        constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
+    )]
+    pub relayer: Signer<'info>,
+
+    // Light Protocol accounts via remaining_accounts
+}
+
+/// Mark the old position Transferred and create the new owner's PositionMeta
+pub fn create_position_meta_transfer<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePositionMetaTransfer<'info>>,
+    _operation_id: [u8; 32],
+    old_position_id: [u8; 32],
+    position_meta_input: TransferredPositionMetaInput,
+    status_params: LightCreatePositionStatusParams,
+    meta_params: LightCreatePositionMetaParams,
+) -> Result<()> {
+    let perps_pool = &ctx.accounts.perps_pool;
+    let perps_market = &ctx.accounts.perps_market;
+    let pending_op = &ctx.accounts.pending_operation;
+    let clock = Clock::get()?;
+
+    msg!("=== Phase 4b: Transfer Position Metadata ===");
+
+    // Validate pending operation state
+    require!(
+        !pending_op.is_expired(clock.unix_timestamp),
+        CloakCraftError::PendingOperationExpired
+    );
+    require!(
+        old_position_id != position_meta_input.position_id,
+        CloakCraftError::PositionIdMismatch
+    );
+
+    // Terms bound by the proof in Phase 0
+    let margin_amount = pending_op.swap_amount;
+    let position_size = pending_op.output_amount;
+    let entry_price = pending_op.min_output;
+    let is_long = pending_op.swap_a_to_b;
+    require!(
+        position_meta_input.position_size == position_size
+            && position_meta_input.entry_price == entry_price,
+        CloakCraftError::PositionTermsMismatch
+    );
+
+    // A transfer must not reset accrued borrow fees: carry over the snapshot
+    // of the old PositionMeta verified in Phase 1b
+    require!(pending_op.position_meta_verified, CloakCraftError::PositionMetaNotVerified);
+    let entry_borrow_fee = pending_op.position_entry_borrow_fee;
+
+    msg!("  Old Position ID: {:02x?}...", &old_position_id[0..8]);
+    msg!("  New Position ID: {:02x?}...", &position_meta_input.position_id[0..8]);
+    msg!("  Margin: {}", margin_amount);
+    msg!("  Direction: {}", if is_long { "LONG" } else { "SHORT" });
+
+    // 1. Old position can no longer be closed or liquidated
+    create_position_status_record(
+        ctx.accounts.relayer.as_ref(),
+        ctx.remaining_accounts,
+        status_params.validity_proof,
+        status_params.address_tree_info,
+        status_params.output_tree_index,
+        perps_pool.pool_id.to_bytes(),
+        old_position_id,
+        PositionStatus::Transferred,
+    )?;
+
+    // 2. New owner's PositionMeta with unchanged terms
+    create_position_meta_account(
+        ctx.accounts.relayer.as_ref(),
+        ctx.remaining_accounts,
+        meta_params.validity_proof,
+        meta_params.address_tree_info,
+        meta_params.output_tree_index,
+        perps_pool.pool_id.to_bytes(),
+        perps_market.market_id,
+        position_meta_input.position_id,
+        margin_amount,
+        position_meta_input.liquidation_price,
+        is_long,
+        position_size,
+        entry_price,
+        position_meta_input.nullifier_hash,
+        position_meta_input.owner_stealth_pubkey,
+        entry_borrow_fee,
+        pending_op.commitments[0],
+    )?;
+
+    msg!("✅ Phase 4b complete: PositionMeta transferred");
+    msg!("Next: close_pending_operation");
+
+    Ok(())
+}
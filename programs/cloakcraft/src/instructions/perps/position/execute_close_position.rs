@@ -4,8 +4,8 @@
 //! It executes the position closing logic by settling PnL, unlocking tokens, and updating market OI.
 //!
 //! SECURITY: Requires all previous phases completed:
-//! - Phase 0: Proof verified
-//! - Phase 1: Commitment verified
+//! - Phase 0: Proof verified (binds margin, size, entry price and borrow fee)
+//! - Phase 1: Commitment verified, PositionMeta verified (binds entry borrow fee)
 //! - Phase 2: Nullifier created
 //!
 //! Bounded Profit Model:
@@ -14,7 +14,8 @@
 //!
 //! Flow:
 //! Phase 0: Verify ZK proof + Create PendingOperation
-//! Phase 1: Verify commitment exists (position)
+//! Phase 1a: Verify commitment exists (position)
+//! Phase 1b: Verify position meta active (checks the entry borrow fee)
 //! Phase 2: Create nullifier (close position)
 //! Phase 3 (this): Execute close position (settle PnL, unlock tokens)
 //! Phase 4: Create commitment (settlement)
@@ -23,9 +24,10 @@
 use anchor_lang::prelude::*;
 use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
-use crate::state::{Pool, PerpsPool, PerpsMarket, PendingOperation};
+use crate::state::{Pool, PerpsPool, PerpsMarket, PendingOperation, OperationKind};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
+use crate::helpers::fixed::{mul_div, saturating_u64};
 use crate::pyth;
 
 #[derive(Accounts)]
@@ -61,9 +63,6 @@ pub struct ExecuteClosePosition<'info> {
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
         constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
-        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
-        constraint = pending_operation.all_inputs_verified() @ CloakCraftError::CommitmentNotVerified,
-        constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -74,17 +73,50 @@ pub struct ExecuteClosePosition<'info> {
     pub relayer: Signer<'info>,
 
     /// Pyth price update account for the base token
-    pub price_update: Account<'info, PriceUpdateV2>,
+    pub price_update: Box<Account<'info, PriceUpdateV2>>,
+}
+
+/// Settlement before the borrow fee: margin +/- PnL - close fee
+///
+/// Profit must already be capped at the margin. Phase 0 caps the borrow fee
+/// at this amount, and the circuit deducts it from the same expression.
+pub(crate) fn close_gross_settlement(
+    position_margin: u64,
+    pnl_amount: u64,
+    is_profit: bool,
+    close_fee: u64,
+) -> Result<u64> {
+    let gross = if is_profit {
+        position_margin
+            .checked_add(pnl_amount)
+            .ok_or(CloakCraftError::AmountOverflow)?
+    } else {
+        position_margin
+            .checked_sub(pnl_amount)
+            .ok_or(CloakCraftError::InsufficientBalance)?
+    };
+    let settlement = gross
+        .checked_sub(close_fee)
+        .ok_or(CloakCraftError::InsufficientBalance)?;
+    Ok(settlement)
 }
 
 /// Phase 3: Execute close position by settling PnL and unlocking tokens
+///
+/// Position terms come from the pending operation: margin, size, entry
+/// price and borrow fee were bound by the proof in Phase 0, and the entry
+/// borrow fee the fee was computed from was checked against the PositionMeta
+/// by verify_position_meta_active.
 pub fn execute_close_position<'info>(
     ctx: Context<'_, '_, '_, 'info, ExecuteClosePosition<'info>>,
     _operation_id: [u8; 32],
-    position_margin: u64,      // Original margin (from position data)
-    position_size: u64,        // Original position size
-    entry_price: u64,          // Original entry price
 ) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::PerpsClosePosition)?;
+    require!(
+        ctx.accounts.pending_operation.position_meta_verified,
+        CloakCraftError::PositionMetaNotVerified
+    );
+
     let perps_pool = &mut ctx.accounts.perps_pool;
     let perps_market = &mut ctx.accounts.perps_market;
     let pending_op = &ctx.accounts.pending_operation;
@@ -99,14 +131,21 @@ pub fn execute_close_position<'info>(
     let close_fee = pending_op.min_output;
     let is_long = pending_op.swap_a_to_b;
     let is_profit = pending_op.extra_amount == 1;
+    let position_margin = pending_op.position_margin;
+    let position_size = pending_op.position_size;
+    let entry_price = pending_op.position_entry_price;
+    let borrow_fee = pending_op.position_borrow_fee;
 
     // Get base token's Pyth feed ID and validate price
     let base_token_index = perps_market.base_token_index;
     let base_token = perps_pool.get_token(base_token_index)
         .ok_or(CloakCraftError::TokenNotInPool)?;
 
-    // Read and validate exit price from Pyth
-    let exit_price = pyth::get_price(price_update, &base_token.pyth_feed_id, &clock)?;
+    // Read and validate exit price from Pyth (fixed once the market is settled)
+    let exit_price = match perps_market.settlement_price() {
+        Some(price) => price,
+        None => pyth::get_price(price_update, &base_token.pyth_feed_id, &clock)?,
+    };
 
     msg!("Closing {} position: margin={}, size={}, entry={}, exit={}",
         if is_long { "LONG" } else { "SHORT" },
@@ -140,11 +179,8 @@ pub fn execute_close_position<'info>(
         }
     };
 
-    let calculated_pnl = (price_diff as u128)
-        .checked_mul(position_size as u128)
-        .unwrap_or(0)
-        .checked_div(entry_price as u128)
-        .unwrap_or(0) as u64;
+    let calculated_pnl = mul_div(price_diff as u128, position_size as u128, entry_price as u128)
+        .map_or(0, saturating_u64);
 
     // Allow small tolerance for rounding
     let pnl_tolerance = 1;
@@ -161,26 +197,26 @@ pub fn execute_close_position<'info>(
         pnl_amount
     };
 
-    // Calculate settlement amount
-    let settlement_amount = if is_profit {
-        position_margin
-            .checked_add(capped_profit)
-            .ok_or(CloakCraftError::AmountOverflow)?
-            .checked_sub(close_fee)
-            .ok_or(CloakCraftError::InsufficientBalance)?
-    } else {
-        position_margin
-            .checked_sub(pnl_amount)
-            .ok_or(CloakCraftError::InsufficientBalance)?
-            .checked_sub(close_fee)
-            .ok_or(CloakCraftError::InsufficientBalance)?
-    };
+    // Calculate settlement amount (the proof deducted the same borrow fee)
+    let gross_settlement = close_gross_settlement(position_margin, capped_profit, is_profit, close_fee)?;
+    let settlement_amount = gross_settlement
+        .checked_sub(borrow_fee)
+        .ok_or(CloakCraftError::InsufficientBalance)?;
+
+    // Borrow fee stays in the pool (credited to LPs)
+    let borrow_token_index = perps_market.borrow_token_index(is_long);
+    if let Some(borrow_token) = perps_pool.get_token_mut(borrow_token_index) {
+        borrow_token.balance = borrow_token.balance
+            .checked_add(borrow_fee)
+            .ok_or(CloakCraftError::AmountOverflow)?;
+    }
 
-    msg!("Settlement: margin={}, pnl={} ({}), fee={}, result={}",
+    msg!("Settlement: margin={}, pnl={} ({}), fee={}, borrow_fee={}, result={}",
         position_margin,
         capped_profit,
         if is_profit { "profit" } else { "loss" },
         close_fee,
+        borrow_fee,
         settlement_amount);
 
     // Unlock tokens from pool
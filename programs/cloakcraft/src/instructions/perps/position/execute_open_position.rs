@@ -19,7 +19,7 @@
 use anchor_lang::prelude::*;
 use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 
-use crate::state::{Pool, PerpsPool, PerpsMarket, PendingOperation};
+use crate::state::{Pool, PerpsPool, PerpsMarket, MarketStatus, PendingOperation, OperationKind};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 use crate::pyth;
@@ -39,6 +39,7 @@ pub struct ExecuteOpenPosition<'info> {
         mut,
         seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
         bump = perps_pool.bump,
+        constraint = !perps_pool.is_migrating @ CloakCraftError::PerpsPoolMigrating,
     )]
     pub perps_pool: Box<Account<'info, PerpsPool>>,
 
@@ -48,6 +49,7 @@ pub struct ExecuteOpenPosition<'info> {
         seeds = [seeds::PERPS_MARKET, perps_pool.key().as_ref(), perps_market.market_id.as_ref()],
         bump = perps_market.bump,
         constraint = perps_market.pool == perps_pool.key() @ CloakCraftError::PerpsMarketNotFound,
+        constraint = perps_market.status == MarketStatus::Active @ CloakCraftError::PerpsMarketDelisted,
     )]
     pub perps_market: Box<Account<'info, PerpsMarket>>,
 
@@ -57,9 +59,6 @@ pub struct ExecuteOpenPosition<'info> {
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
         constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
-        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
-        constraint = pending_operation.all_inputs_verified() @ CloakCraftError::CommitmentNotVerified,
-        constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -70,7 +69,7 @@ pub struct ExecuteOpenPosition<'info> {
     pub relayer: Signer<'info>,
 
     /// Pyth price update account for the base token
-    pub price_update: Account<'info, PriceUpdateV2>,
+    pub price_update: Box<Account<'info, PriceUpdateV2>>,
 }
 
 /// Phase 3: Execute open position by locking tokens and updating market OI
@@ -79,9 +78,21 @@ pub fn execute_open_position<'info>(
     _operation_id: [u8; 32],
     _entry_price: u64,  // Kept for backwards compatibility, actual price read from Pyth
 ) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::PerpsOpenPosition)?;
+
+    // Sealed opens reveal their terms in execute_open_position_revealed
+    require!(
+        ctx.accounts.pending_operation.call_hash == [0u8; 32],
+        CloakCraftError::PositionNotRevealed
+    );
+    apply_open_position(ctx)
+}
+
+/// Open the stored position at the oracle price, locking tokens and updating OI
+pub(crate) fn apply_open_position<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteOpenPosition<'info>>) -> Result<()> {
     let perps_pool = &mut ctx.accounts.perps_pool;
     let perps_market = &mut ctx.accounts.perps_market;
-    let pending_op = &ctx.accounts.pending_operation;
+    let pending_op = &mut ctx.accounts.pending_operation;
     let price_update = &ctx.accounts.price_update;
     let clock = Clock::get()?;
 
@@ -169,6 +180,15 @@ pub fn execute_open_position<'info>(
     // Update market open interest
     perps_market.add_open_interest(position_size, is_long);
 
+    // Record the opened terms for create_position_meta
+    pending_op.position_margin = margin_amount;
+    pending_op.position_size = position_size;
+    pending_op.position_entry_price = entry_price;
+    pending_op.position_entry_borrow_fee = perps_pool
+        .get_token(perps_market.borrow_token_index(is_long))
+        .ok_or(CloakCraftError::TokenNotInPool)?
+        .cumulative_borrow_fee;
+
     msg!("✅ Position opened");
     msg!("Market OI - Long: {}, Short: {}",
         perps_market.long_open_interest,
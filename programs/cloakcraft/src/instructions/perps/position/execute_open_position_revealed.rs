@@ -0,0 +1,42 @@
+//! Execute Open Position - Phase 3 (Sealed Open Position)
+//!
+//! Reveals the margin, leverage and fee a sealed open hid in Phase 0, checks
+//! them against the stored terms hash and the market order rules, then opens
+//! the position exactly like execute_open_position (same accounts and checks).
+
+use anchor_lang::prelude::*;
+
+use super::create_pending_with_proof_open_position::check_position_terms;
+use super::create_pending_with_proof_open_position_sealed::compute_position_terms_hash;
+use super::execute_open_position::{apply_open_position, ExecuteOpenPosition};
+use crate::errors::CloakCraftError;
+use crate::state::OperationKind;
+
+/// Phase 3: Reveal a sealed open and lock tokens / update market OI
+pub fn execute_open_position_revealed<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteOpenPosition<'info>>,
+    _operation_id: [u8; 32],
+    margin_amount: u64,
+    leverage: u8,
+    position_fee: u64,
+    salt: [u8; 32],
+) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::PerpsOpenPosition)?;
+
+    let pending_op = &mut ctx.accounts.pending_operation;
+    require!(pending_op.call_hash != [0u8; 32], CloakCraftError::SealedPositionTermsMismatch);
+    require!(
+        compute_position_terms_hash(margin_amount, leverage, position_fee, &salt)? == pending_op.call_hash,
+        CloakCraftError::SealedPositionTermsMismatch
+    );
+
+    // Market order checks deferred from Phase 0
+    check_position_terms(&ctx.accounts.perps_pool, margin_amount, leverage, position_fee)?;
+
+    pending_op.swap_amount = margin_amount;
+    pending_op.output_amount = leverage as u64;
+    pending_op.min_output = position_fee;
+    msg!("Position revealed: margin {}, leverage {}x", margin_amount, leverage);
+
+    apply_open_position(ctx)
+}
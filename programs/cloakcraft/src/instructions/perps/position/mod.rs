@@ -3,20 +3,32 @@
 //! Position lifecycle:
 //! - Open: Create private commitment + public PositionMeta
 //! - Close: Verify no liquidation record exists, settle PnL, create status record
+//! - Transfer: Re-commit to a new stealth owner, mark old Transferred, new PositionMeta
+//! - Quote: Read-only valuation at the current oracle price
 //! - Liquidate: Keeper uses PositionMeta for permissionless liquidation
 
 mod create_pending_with_proof_open_position;
 mod execute_open_position;
+mod create_pending_with_proof_open_position_sealed;
+mod execute_open_position_revealed;
 mod create_position_meta;
 mod create_pending_with_proof_close_position;
 mod verify_position_meta_active;
 mod execute_close_position;
 mod create_position_status_closed;
+mod create_pending_with_proof_transfer_position;
+mod create_position_meta_transfer;
+mod quote_position_value;
 
 pub use create_pending_with_proof_open_position::*;
 pub use execute_open_position::*;
+pub use create_pending_with_proof_open_position_sealed::*;
+pub use execute_open_position_revealed::*;
 pub use create_position_meta::*;
 pub use create_pending_with_proof_close_position::*;
 pub use verify_position_meta_active::*;
 pub use execute_close_position::*;
 pub use create_position_status_closed::*;
+pub use create_pending_with_proof_transfer_position::*;
+pub use create_position_meta_transfer::*;
+pub use quote_position_value::*;
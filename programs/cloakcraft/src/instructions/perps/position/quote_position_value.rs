@@ -0,0 +1,158 @@
+//! Quote Position Value (read-only)
+//!
+//! Values a position at the current Pyth price with the same math the close
+//! and liquidation flows use. The result is returned as a `PositionValueQuote`
+//! (return data, readable by CPI callers) and emitted as an event. Lending
+//! integrations and UIs call it via simulation so displayed numbers match
+//! what the program would settle. Nothing is written.
+
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+use crate::state::{PerpsPool, PerpsMarket, PositionData, PositionDirection};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::pyth;
+
+/// Position valuation at the current oracle price
+#[event]
+pub struct PositionValueQuoted {
+    pub perps_pool: Pubkey,
+    pub perps_market: Pubkey,
+    /// Oracle price used for the quote
+    pub oracle_price: u64,
+    /// Margin + capped PnL - accrued borrow fee (what a close would settle before the close fee)
+    pub mark_value: u64,
+    /// Absolute PnL (profit capped at margin)
+    pub pnl: u64,
+    pub is_profit: bool,
+    /// Borrow fee accrued since the position's snapshot
+    pub accrued_borrow_fee: u64,
+    /// Price at which the position becomes liquidatable
+    pub liquidation_price: u64,
+    /// Price move to liquidation as a fraction of the oracle price (0 if liquidatable)
+    pub distance_to_liquidation_bps: u16,
+    pub is_liquidatable: bool,
+    pub timestamp: i64,
+}
+
+/// Position valuation returned by `quote_position_value` (return data)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PositionValueQuote {
+    pub oracle_price: u64,
+    pub mark_value: u64,
+    pub pnl: u64,
+    pub is_profit: bool,
+    pub accrued_borrow_fee: u64,
+    pub liquidation_price: u64,
+    pub distance_to_liquidation_bps: u16,
+    pub is_liquidatable: bool,
+}
+
+#[derive(Accounts)]
+pub struct QuotePositionValue<'info> {
+    /// Perps pool
+    #[account(
+        seeds = [seeds::PERPS_POOL, perps_pool.pool_id.as_ref()],
+        bump = perps_pool.bump,
+    )]
+    pub perps_pool: Box<Account<'info, PerpsPool>>,
+
+    /// Market of the position
+    #[account(
+        seeds = [seeds::PERPS_MARKET, perps_pool.key().as_ref(), perps_market.market_id.as_ref()],
+        bump = perps_market.bump,
+        constraint = perps_market.pool == perps_pool.key() @ CloakCraftError::PerpsMarketNotFound,
+    )]
+    pub perps_market: Box<Account<'info, PerpsMarket>>,
+
+    /// Pyth price update account for the base token
+    pub price_update: Box<Account<'info, PriceUpdateV2>>,
+}
+
+/// Value a position at the current oracle price
+pub fn quote_position_value(
+    ctx: Context<QuotePositionValue>,
+    position_margin: u64,
+    position_size: u64,
+    entry_price: u64,
+    is_long: bool,
+    entry_borrow_fee: u128,
+) -> Result<PositionValueQuote> {
+    let perps_pool = &ctx.accounts.perps_pool;
+    let perps_market = &ctx.accounts.perps_market;
+    let clock = Clock::get()?;
+
+    require!(entry_price > 0, CloakCraftError::InvalidOraclePrice);
+    require!(position_size > 0, CloakCraftError::InvalidAmount);
+
+    let base_token = perps_pool.get_token(perps_market.base_token_index)
+        .ok_or(CloakCraftError::TokenNotInPool)?;
+    let oracle_price = match perps_market.settlement_price() {
+        Some(price) => price,
+        None => pyth::get_price(&ctx.accounts.price_update, &base_token.pyth_feed_id, &clock)?,
+    };
+
+    let position = PositionData {
+        market_id: perps_market.market_id,
+        direction: if is_long { PositionDirection::Long } else { PositionDirection::Short },
+        margin: position_margin,
+        size: position_size,
+        entry_price,
+        entry_cumulative_borrow_fee: entry_borrow_fee,
+        ..Default::default()
+    };
+
+    let (raw_pnl, is_profit) = position.calculate_pnl(oracle_price);
+    // Bounded profit: max profit = margin
+    let pnl = if is_profit { raw_pnl.min(position_margin) } else { raw_pnl };
+
+    let accrued_borrow_fee = perps_pool
+        .get_token(perps_market.borrow_token_index(is_long))
+        .ok_or(CloakCraftError::TokenNotInPool)?
+        .accrued_borrow_fee(position_size, entry_borrow_fee);
+
+    let effective_margin = position.effective_margin(oracle_price);
+    let mark_value = effective_margin.saturating_sub(accrued_borrow_fee);
+
+    let threshold_bps = perps_pool.liquidation_threshold_bps;
+    let event = PositionValueQuoted {
+        perps_pool: perps_pool.key(),
+        perps_market: perps_market.key(),
+        oracle_price,
+        mark_value,
+        pnl,
+        is_profit,
+        accrued_borrow_fee,
+        liquidation_price: position.liquidation_price(threshold_bps),
+        distance_to_liquidation_bps: position.distance_to_liquidation_bps(oracle_price, threshold_bps),
+        is_liquidatable: position.is_liquidatable(oracle_price, threshold_bps),
+        timestamp: clock.unix_timestamp,
+    };
+
+    msg!(
+        "Quote: price={}, mark={}, pnl={} ({}), borrow_fee={}, liq_price={}, distance={}bps",
+        oracle_price,
+        event.mark_value,
+        event.pnl,
+        if is_profit { "profit" } else { "loss" },
+        event.accrued_borrow_fee,
+        event.liquidation_price,
+        event.distance_to_liquidation_bps
+    );
+
+    let quote = PositionValueQuote {
+        oracle_price,
+        mark_value: event.mark_value,
+        pnl,
+        is_profit,
+        accrued_borrow_fee,
+        liquidation_price: event.liquidation_price,
+        distance_to_liquidation_bps: event.distance_to_liquidation_bps,
+        is_liquidatable: event.is_liquidatable,
+    };
+
+    emit!(event);
+
+    Ok(quote)
+}
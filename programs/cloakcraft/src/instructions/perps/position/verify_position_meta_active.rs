@@ -18,8 +18,8 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{PerpsPool, PendingOperation, LightValidityProof, LightAddressTreeInfo, PositionStatus};
-use crate::constants::seeds;
+use crate::state::{Pool, PerpsPool, PendingOperation, LightValidityProof, LightAddressTreeInfo, PositionMeta, PositionStatus};
+use crate::constants::{seeds, operation_types};
 use crate::errors::CloakCraftError;
 use crate::light_cpi::{verify_position_meta_inclusion, PositionMetaMerkleContext};
 
@@ -34,22 +34,6 @@ pub struct LightVerifyPositionMetaParams {
     pub merkle_context: PositionMetaMerkleContext,
 }
 
-/// Position meta data passed from client (read from indexer)
-/// This is verified against the on-chain compressed account
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct PositionMetaData {
-    /// Position ID (must match pending operation)
-    pub position_id: [u8; 32],
-    /// Account hash of the PositionMeta compressed account
-    pub account_hash: [u8; 32],
-    /// Current status (must be Active = 0)
-    pub status: u8,
-    /// Margin amount (for verification)
-    pub margin_amount: u64,
-    /// Is long (for verification)
-    pub is_long: bool,
-}
-
 #[derive(Accounts)]
 #[instruction(operation_id: [u8; 32])]
 pub struct VerifyPositionMetaActive<'info> {
@@ -60,6 +44,14 @@ pub struct VerifyPositionMetaActive<'info> {
     )]
     pub perps_pool: Box<Account<'info, PerpsPool>>,
 
+    /// Position pool (source of the pinned inclusion threshold)
+    #[account(
+        seeds = [seeds::POOL, position_pool.token_mint.as_ref()],
+        bump = position_pool.bump,
+        constraint = position_pool.token_mint == perps_pool.position_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub position_pool: Box<Account<'info, Pool>>,
+
     /// Pending operation PDA (from Phase 0)
     #[account(
         mut,
@@ -81,12 +73,14 @@ pub struct VerifyPositionMetaActive<'info> {
 /// Verify that PositionMeta exists and is Active
 ///
 /// This prevents users from closing positions that have been liquidated.
-/// The verification is done via Light Protocol's inclusion proof.
+/// The verification is done via Light Protocol's inclusion proof over the
+/// full metadata, which must describe the position commitment the Phase 0
+/// proof spends. Close must have computed its borrow fee from the
+/// metadata's `entry_borrow_fee`; liquidation and transfer record it.
 pub fn verify_position_meta_active<'info>(
     ctx: Context<'_, '_, '_, 'info, VerifyPositionMetaActive<'info>>,
     _operation_id: [u8; 32],
-    position_id: [u8; 32],
-    position_meta_data: PositionMetaData,
+    position_meta: PositionMeta,
     light_params: LightVerifyPositionMetaParams,
 ) -> Result<()> {
     let perps_pool = &ctx.accounts.perps_pool;
@@ -100,56 +94,87 @@ pub fn verify_position_meta_active<'info>(
         !pending_op.is_expired(clock.unix_timestamp),
         CloakCraftError::PendingOperationExpired
     );
+    require!(
+        matches!(
+            pending_op.operation_type,
+            operation_types::PERPS_CLOSE_POSITION
+                | operation_types::PERPS_LIQUIDATE
+                | operation_types::PERPS_TRANSFER_POSITION
+        ),
+        CloakCraftError::InvalidOperationType
+    );
 
-    // Verify position_id matches what's expected
-    // The position_id should be part of the ZK proof public inputs
-    // and stored in pending_operation for binding
+    // SECURITY: the metadata must describe the position the proof spends
+    require!(
+        position_meta.pool_id == perps_pool.pool_id.to_bytes(),
+        CloakCraftError::PositionIdMismatch
+    );
     require!(
-        position_id == position_meta_data.position_id,
+        position_meta.position_commitment == pending_op.input_commitments[0],
         CloakCraftError::PositionIdMismatch
     );
 
     msg!("Verifying PositionMeta inclusion...");
-    msg!("  Position ID: {:02x?}...", &position_id[0..8]);
-    msg!("  Account hash: {:02x?}...", &position_meta_data.account_hash[0..8]);
-    msg!("  Status: {}", position_meta_data.status);
+    msg!("  Position ID: {:02x?}...", &position_meta.position_id[0..8]);
+    msg!("  Status: {}", position_meta.status);
+
+    // SECURITY: High-value operations don't trust queue/index context
+    let position_pool = &ctx.accounts.position_pool;
+    if position_pool.requires_pinned_inclusion(pending_op.pool_value_bound(&position_pool.key().to_bytes())) {
+        require!(
+            !light_params.merkle_context.prove_by_index,
+            CloakCraftError::PinnedInclusionRequired
+        );
+        msg!("Pinned inclusion: root index {}", light_params.merkle_context.root_index);
+    }
 
-    // Verify PositionMeta exists in Light Protocol state tree
+    // Verify PositionMeta exists in Light Protocol state tree (hash recomputed from its fields)
     verify_position_meta_inclusion(
         ctx.accounts.relayer.as_ref(),
         ctx.remaining_accounts,
-        position_meta_data.account_hash,
+        &position_meta,
         light_params.merkle_context,
-        perps_pool.pool_id.to_bytes(),
-        position_id,
+        light_params.validity_proof,
+        light_params.address_tree_info,
     )?;
 
     msg!("✅ PositionMeta inclusion verified");
 
-    // Check status is Active
-    require!(
-        position_meta_data.status == PositionStatus::Active as u8,
-        CloakCraftError::PositionNotActive
-    );
-
     // If liquidated, reject with specific error
-    if position_meta_data.status == PositionStatus::Liquidated as u8 {
+    if position_meta.status == PositionStatus::Liquidated as u8 {
         msg!("❌ Position has been liquidated - cannot close");
         return Err(CloakCraftError::PositionAlreadyLiquidated.into());
     }
 
     // If already closed, reject
-    if position_meta_data.status == PositionStatus::Closed as u8 {
+    if position_meta.status == PositionStatus::Closed as u8 {
         msg!("❌ Position has already been closed");
         return Err(CloakCraftError::PositionAlreadyClosed.into());
     }
 
+    // Check status is Active
+    require!(
+        position_meta.status == PositionStatus::Active as u8,
+        CloakCraftError::PositionNotActive
+    );
+
     // Verify direction matches what's in pending operation
     require!(
-        position_meta_data.is_long == pending_op.swap_a_to_b,
+        position_meta.is_long == pending_op.swap_a_to_b,
         CloakCraftError::InvalidPositionDirection
     );
 
+    // Borrow fees accrue from the snapshot taken when the position was opened
+    if pending_op.operation_type == operation_types::PERPS_CLOSE_POSITION {
+        require!(
+            pending_op.position_entry_borrow_fee == position_meta.entry_borrow_fee,
+            CloakCraftError::BorrowFeeSnapshotMismatch
+        );
+    } else {
+        pending_op.position_entry_borrow_fee = position_meta.entry_borrow_fee;
+    }
+    pending_op.position_meta_verified = true;
+
     msg!("✅ Position is Active - close operation can proceed");
     msg!("Phase 1b complete");
     msg!("Next: Phase 2 - create_nullifier");
@@ -0,0 +1,89 @@
+//! Anchor a state tree root checkpoint
+//!
+//! The checkpoint authority signs a state tree root; the program pairs it with
+//! the pool's current commitment count, chains it onto the history and emits
+//! `RootCheckpointAnchored` so indexers can archive notes below the count.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, PoolCommitmentCounter, RootCheckpointHistory};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct AnchorRootCheckpoint<'info> {
+    /// Pool being checkpointed
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Commitment counter (source of the commitment count)
+    #[account(
+        seeds = [PoolCommitmentCounter::SEEDS_PREFIX, pool.key().as_ref()],
+        bump = commitment_counter.bump,
+    )]
+    pub commitment_counter: Box<Account<'info, PoolCommitmentCounter>>,
+
+    /// Root checkpoint history
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_CHECKPOINTS, pool.key().as_ref()],
+        bump = checkpoint_history.bump,
+        has_one = checkpoint_authority @ CloakCraftError::Unauthorized,
+    )]
+    pub checkpoint_history: Box<Account<'info, RootCheckpointHistory>>,
+
+    /// Checkpoint authority (signs the root)
+    pub checkpoint_authority: Signer<'info>,
+}
+
+#[event]
+pub struct RootCheckpointAnchored {
+    pub pool: Pubkey,
+    pub state_tree: Pubkey,
+    pub root: [u8; 32],
+    pub commitment_count: u64,
+    pub checkpoint_index: u64,
+    pub chain_hash: [u8; 32],
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+pub fn anchor_root_checkpoint(
+    ctx: Context<AnchorRootCheckpoint>,
+    state_tree: Pubkey,
+    root: [u8; 32],
+) -> Result<()> {
+    let history = &mut ctx.accounts.checkpoint_history;
+    let commitment_count = ctx.accounts.commitment_counter.next_leaf_index;
+    let clock = Clock::get()?;
+
+    require!(root != [0u8; 32], CloakCraftError::InvalidRootCheckpoint);
+    require!(history.can_checkpoint(clock.unix_timestamp), CloakCraftError::CheckpointTooSoon);
+    if let Some(latest) = history.latest() {
+        require!(
+            commitment_count >= latest.commitment_count,
+            CloakCraftError::InvalidRootCheckpoint
+        );
+    }
+
+    let checkpoint_index = history.total_checkpoints;
+    let checkpoint = history.push(state_tree, root, commitment_count, clock.slot, clock.unix_timestamp);
+
+    emit!(RootCheckpointAnchored {
+        pool: history.pool,
+        state_tree,
+        root,
+        commitment_count,
+        checkpoint_index,
+        chain_hash: checkpoint.chain_hash,
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Root checkpoint {} anchored: {} commitments", checkpoint_index, commitment_count);
+
+    Ok(())
+}
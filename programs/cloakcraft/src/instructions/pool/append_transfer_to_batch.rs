@@ -0,0 +1,213 @@
+//! Append Transfer to Batch - Phase 0b (Transfer batching)
+//!
+//! Verifies another transfer proof and appends its input and outputs to a
+//! transfer PendingOperation created by create_pending_with_proof, so up to
+//! `MAX_BATCH_TRANSFERS` independent transfers share one pending account and
+//! its commitment phases. Phases 1 and 2 then run once per input index, and
+//! Phase 3 and 4+ once for the whole batch.
+//!
+//! Batches hold shielded transfers from a single pool: no member may unshield
+//! or bind a call, since Phase 3 pays a single recipient. Each member's fee is
+//! checked against its own amounts here, so Phase 3 only moves the sum.
+//!
+//! Appending is only possible before Phase 1 starts.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, VerificationKey, PendingOperation, ProtocolConfig, ProgramVersion, RelayerAllowlist, MAX_BATCH_TRANSFERS};
+use crate::constants::{operation_types, seeds};
+use crate::errors::CloakCraftError;
+use crate::helpers::verify_groth16_proof;
+use crate::helpers::field::assert_canonical;
+use super::transfer_public_inputs;
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct AppendTransferToBatch<'info> {
+    /// Pool (must be the batch's pool)
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Verification key for the circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Pending transfer operation to append to
+    #[account(
+        mut,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump = pending_operation.bump,
+        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
+        constraint = pending_operation.operation_type == operation_types::TRANSFER @ CloakCraftError::InvalidOperationType,
+        constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
+        constraint = pending_operation.inputs_verified_mask == 0 @ CloakCraftError::BatchAlreadyStarted,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (must match operation creator)
+    #[account(
+        constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
+    )]
+    pub relayer: Signer<'info>,
+
+    /// Protocol config (per-member fee check)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Relayer allowlist (required while the pool is permissioned)
+    #[account(
+        seeds = [seeds::RELAYER_ALLOWLIST, pool.key().as_ref()],
+        bump = relayer_allowlist.bump,
+    )]
+    pub relayer_allowlist: Option<Box<Account<'info, RelayerAllowlist>>>,
+}
+
+/// Phase 0b: Verify a transfer proof and append it to a pending batch
+#[allow(clippy::too_many_arguments)]
+pub fn append_transfer_to_batch(
+    ctx: Context<AppendTransferToBatch>,
+    _operation_id: [u8; 32],
+    proof: Vec<u8>,
+    merkle_root: [u8; 32],
+    input_commitment: [u8; 32],
+    nullifier: [u8; 32],
+    out_commitments: Vec<[u8; 32]>,
+    output_recipients: Vec<[u8; 32]>,
+    output_amounts: Vec<u64>,
+    output_randomness: Vec<[u8; 32]>,
+    stealth_ephemeral_pubkeys: Vec<[u8; 64]>,
+    transfer_amount: u64,
+    fee_amount: u64,
+    client_version: u32,
+) -> Result<()> {
+    ctx.accounts.program_version.check_client(client_version)?;
+    ctx.accounts.pool.check_relayer(
+        ctx.accounts.relayer_allowlist.as_deref().map(|list| &**list),
+        ctx.accounts.relayer.key,
+    )?;
+
+    assert_canonical(&[input_commitment, nullifier])?;
+    assert_canonical(&out_commitments)?;
+    assert_canonical(&output_recipients)?;
+    assert_canonical(&output_randomness)?;
+
+    let pool = &ctx.accounts.pool;
+    let protocol_config = &ctx.accounts.protocol_config;
+    let pending_op = &mut ctx.accounts.pending_operation;
+
+    msg!("=== Phase 0b: Append Transfer to Batch ===");
+
+    // Single-pool batches of shielded transfers
+    require!(
+        pending_op.input_pools[0] == pool.key().to_bytes(),
+        CloakCraftError::PoolMismatch
+    );
+    require!(
+        pending_op.unshield_amount == 0 && pending_op.call_hash == [0u8; 32],
+        CloakCraftError::BatchUnshieldNotSupported
+    );
+
+    let index = pending_op.num_inputs as usize;
+    require!(index < MAX_BATCH_TRANSFERS, CloakCraftError::BatchFull);
+    let first_output = pending_op.num_commitments as usize;
+    require!(
+        first_output + out_commitments.len() <= crate::state::MAX_PENDING_COMMITMENTS,
+        CloakCraftError::TooManyPendingCommitments
+    );
+    require!(
+        !pending_op.expected_nullifiers[..index].contains(&nullifier),
+        CloakCraftError::DuplicateBatchNullifier
+    );
+
+    let public_inputs = transfer_public_inputs(
+        pool,
+        &ctx.accounts.verification_key.circuit_id,
+        &merkle_root,
+        &nullifier,
+        &out_commitments,
+        &output_amounts,
+        transfer_amount,
+        0,
+        fee_amount,
+        None,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    #[cfg(not(feature = "skip-zk-verify"))]
+    verify_groth16_proof(&proof, &ctx.accounts.verification_key.vk_data, &public_inputs, "Batch transfer")?;
+
+    #[cfg(feature = "skip-zk-verify")]
+    {
+        msg!("WARNING: ZK proof verification skipped (testing mode)");
+        let _ = (&proof, &public_inputs);
+    }
+
+    // Fees are checked per member: the sum of rounded fees can fall short of
+    // the fee on the summed amount, which Phase 3 would otherwise reject
+    if index == 1 {
+        let first_fee = protocol_config.expected_transfer_fee(&pool.token_mint, pending_op.transfer_amount, 0)?;
+        require!(pending_op.fee_amount >= first_fee, CloakCraftError::InsufficientFee);
+    }
+    let expected_fee = protocol_config.expected_transfer_fee(&pool.token_mint, transfer_amount, 0)?;
+    require!(fee_amount >= expected_fee, CloakCraftError::InsufficientFee);
+
+    // Append the input (verified and nullified at this index in Phases 1-2)
+    pending_op.input_commitments[index] = input_commitment;
+    pending_op.expected_nullifiers[index] = nullifier;
+    pending_op.input_pools[index] = pool.key().to_bytes();
+    pending_op.num_inputs += 1;
+
+    // Append the outputs after the batch's existing ones
+    for (i, commitment) in out_commitments.iter().enumerate() {
+        let slot = first_output + i;
+        pending_op.pools[slot] = pool.key().to_bytes();
+        pending_op.commitments[slot] = *commitment;
+        if let Some(recipient) = output_recipients.get(i) {
+            pending_op.output_recipients[slot] = *recipient;
+        }
+        if let Some(amount) = output_amounts.get(i) {
+            pending_op.output_amounts[slot] = *amount;
+        }
+        if let Some(randomness) = output_randomness.get(i) {
+            pending_op.output_randomness[slot] = *randomness;
+        }
+        if let Some(pubkey) = stealth_ephemeral_pubkeys.get(i) {
+            pending_op.stealth_ephemeral_pubkeys[slot] = *pubkey;
+        }
+    }
+    pending_op.num_commitments += out_commitments.len() as u8;
+
+    // Phase 3 moves the summed fee
+    pending_op.fee_amount = pending_op.fee_amount
+        .checked_add(fee_amount)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    pending_op.transfer_amount = pending_op.transfer_amount
+        .checked_add(transfer_amount)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    msg!(
+        "Batch now holds {} transfers, {} commitments (fee_amount: {})",
+        pending_op.num_inputs,
+        pending_op.num_commitments,
+        pending_op.fee_amount
+    );
+
+    Ok(())
+}
@@ -20,11 +20,11 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, VerificationKey, PendingOperation, PENDING_OPERATION_EXPIRY_SECONDS};
-use crate::constants::seeds;
+use crate::state::{Pool, VerificationKey, PendingOperation, MAX_DENOMINATIONS, ProtocolConfig, ProgramVersion, CircuitStats, RelayerAllowlist, OperationCredit};
+use crate::constants::{circuits, operation_types, seeds};
 use crate::errors::CloakCraftError;
-use crate::helpers::verify_groth16_proof;
-use crate::helpers::field::{pubkey_to_field, u64_to_field};
+use crate::helpers::verify_groth16_proof_metered;
+use crate::helpers::field::{bytes_to_field, pubkey_to_field, u64_to_field, assert_canonical};
 
 #[derive(Accounts)]
 #[instruction(operation_id: [u8; 32])]
@@ -40,6 +40,7 @@ pub struct CreatePendingWithProof<'info> {
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
@@ -57,6 +58,38 @@ pub struct CreatePendingWithProof<'info> {
     #[account(mut)]
     pub relayer: Signer<'info>,
 
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Relayer allowlist (required while the pool is permissioned)
+    #[account(
+        seeds = [seeds::RELAYER_ALLOWLIST, pool.key().as_ref()],
+        bump = relayer_allowlist.bump,
+    )]
+    pub relayer_allowlist: Option<Box<Account<'info, RelayerAllowlist>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
     /// System program
     pub system_program: Program<'info, System>,
 }
@@ -95,7 +128,30 @@ pub fn create_pending_with_proof(
     transfer_amount: u64,
     unshield_amount: u64,
     fee_amount: u64,
+    call_hash: Option<[u8; 32]>,
+    client_version: u32,
 ) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Permissioned pools only accept allowlisted relayers
+    ctx.accounts.pool.check_relayer(
+        ctx.accounts.relayer_allowlist.as_deref().map(|list| &**list),
+        ctx.accounts.relayer.key,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[input_commitment, nullifier])?;
+    assert_canonical(&out_commitments)?;
+    assert_canonical(&output_recipients)?;
+    assert_canonical(&output_randomness)?;
+
     let pool = &ctx.accounts.pool;
     let pending_op = &mut ctx.accounts.pending_operation;
     let clock = Clock::get()?;
@@ -106,30 +162,29 @@ pub fn create_pending_with_proof(
     msg!("Nullifier: {:02x?}...", &nullifier[0..8]);
     msg!("Output commitments: {}", out_commitments.len());
 
-    // SECURITY: Validate output count to prevent silent truncation
-    require!(
-        out_commitments.len() <= crate::state::MAX_PENDING_COMMITMENTS,
-        CloakCraftError::TooManyPendingCommitments
-    );
+    let public_inputs = transfer_public_inputs(
+        pool,
+        &ctx.accounts.verification_key.circuit_id,
+        &merkle_root,
+        &nullifier,
+        &out_commitments,
+        &output_amounts,
+        transfer_amount,
+        unshield_amount,
+        fee_amount,
+        call_hash.as_ref(),
+        clock.unix_timestamp,
+    )?;
 
     // SECURITY: Verify ZK proof with public inputs
     #[cfg(not(feature = "skip-zk-verify"))]
     {
-        let public_inputs = build_transact_public_inputs(
-            &merkle_root,
-            &nullifier,
-            &out_commitments,
-            &pool.token_mint,
-            transfer_amount,
-            unshield_amount,
-            fee_amount,
-        );
-
-        verify_groth16_proof(
+        verify_groth16_proof_metered(
             &proof,
             &ctx.accounts.verification_key.vk_data,
             &public_inputs,
             "Transfer",
+            ctx.accounts.circuit_stats.as_deref_mut(),
         )?;
 
         msg!("✅ ZK proof verified (fee_amount: {})", fee_amount);
@@ -138,16 +193,18 @@ pub fn create_pending_with_proof(
     #[cfg(feature = "skip-zk-verify")]
     {
         msg!("WARNING: ZK proof verification skipped (testing mode)");
-        let _ = &proof;
+        let _ = (&proof, &public_inputs);
     }
 
     // Initialize pending operation PDA
     pending_op.bump = ctx.bumps.pending_operation;
-    pending_op.operation_id = operation_id;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
     pending_op.relayer = ctx.accounts.relayer.key();
-    pending_op.operation_type = 0; // Not used
+    pending_op.operation_type = operation_types::TRANSFER;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
     pending_op.created_at = clock.unix_timestamp;
-    pending_op.expires_at = clock.unix_timestamp + PENDING_OPERATION_EXPIRY_SECONDS;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
 
     // SECURITY: Store binding fields from ZK proof
     pending_op.num_inputs = 1; // Single input operation
@@ -196,6 +253,7 @@ pub fn create_pending_with_proof(
     pending_op.unshield_amount = unshield_amount;
     pending_op.transfer_amount = transfer_amount;
     pending_op.fee_processed = false;
+    pending_op.call_hash = call_hash.unwrap_or([0u8; 32]);
 
     msg!("Phase 0 complete: ZK proof verified, PendingOperation created");
     msg!("  transfer_amount: {}", transfer_amount);
@@ -206,8 +264,114 @@ pub fn create_pending_with_proof(
     Ok(())
 }
 
+/// Transfer Phase 0 checks, shared with `simulate_operation`
+///
+/// Enforces the pool's anonymity guard, output count, dust threshold and
+/// circuit-specific rules (denominations, NFT, unshield-and-invoke), and
+/// returns the proof's public inputs.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn transfer_public_inputs(
+    pool: &Pool,
+    circuit_id: &[u8; 32],
+    merkle_root: &[u8; 32],
+    nullifier: &[u8; 32],
+    out_commitments: &[[u8; 32]],
+    output_amounts: &[u64],
+    transfer_amount: u64,
+    unshield_amount: u64,
+    fee_amount: u64,
+    call_hash: Option<&[u8; 32]>,
+    now: i64,
+) -> Result<Vec<[u8; 32]>> {
+    // Anonymity guard: hold back unshields while recent pool activity is too low
+    if unshield_amount > 0 && pool.anonymity_guard_active(now) {
+        msg!(
+            "Anonymity set {} below guard {}",
+            pool.recent_anonymity_set(now),
+            pool.min_anonymity_guard
+        );
+        return Err(CloakCraftError::AnonymityGuardActive.into());
+    }
+
+    // SECURITY: Validate output count to prevent silent truncation
+    require!(
+        out_commitments.len() <= crate::state::MAX_PENDING_COMMITMENTS,
+        CloakCraftError::TooManyPendingCommitments
+    );
+
+    // Change notes (every output after the recipient's) must not be dust
+    require!(
+        output_amounts.iter().skip(1).all(|&amount| !pool.is_dust(amount)),
+        CloakCraftError::DustNote
+    );
+
+    // Denomination pools: outputs must be exact denominations (or zero-amount padding).
+    // The denomination circuit binds each output amount to the set passed as public inputs.
+    let denominations = if pool.has_fixed_denominations() {
+        require!(
+            *circuit_id == circuits::TRANSFER_1X2_DENOM,
+            CloakCraftError::InvalidDenomination
+        );
+        require!(
+            output_amounts.iter().all(|&amount| amount == 0 || pool.is_denomination(amount)),
+            CloakCraftError::InvalidDenomination
+        );
+        Some(&pool.fixed_denominations)
+    } else {
+        None
+    };
+
+    // NFT pools: every note is the pool's NFT (amount 1) or zero-amount padding.
+    // The NFT circuit enforces amount <= 1 and binds the pool's metadata hash.
+    let nft_metadata_hash = if pool.is_nft_pool() {
+        require!(
+            *circuit_id == circuits::TRANSFER_1X2_NFT,
+            CloakCraftError::InvalidNftPool
+        );
+        require!(
+            output_amounts.iter().all(|&amount| amount <= 1)
+                && transfer_amount <= 1
+                && unshield_amount <= 1
+                && fee_amount == 0,
+            CloakCraftError::InvalidNftAmount
+        );
+        Some(&pool.nft_metadata_hash)
+    } else {
+        None
+    };
+
+    // Unshield-and-invoke: the invoke circuit binds a hash of (target program,
+    // recipient, call data) so Phase 3 can only make the call the user proved.
+    // Destination-bound unshields reuse it with a recipient hash instead.
+    let is_invoke_circuit = *circuit_id == circuits::TRANSFER_1X2_INVOKE;
+    if let Some(hash) = call_hash {
+        require!(is_invoke_circuit, CloakCraftError::InvalidPublicInputs);
+        require!(unshield_amount > 0, CloakCraftError::NoUnshieldCall);
+        require!(denominations.is_none(), CloakCraftError::InvalidDenomination);
+        require!(bytes_to_field(hash) == *hash, CloakCraftError::InvalidPublicInputs);
+    } else {
+        require!(!is_invoke_circuit, CloakCraftError::UnshieldCallRequired);
+    }
+
+    Ok(build_transact_public_inputs(
+        merkle_root,
+        nullifier,
+        out_commitments,
+        &pool.token_mint,
+        transfer_amount,
+        unshield_amount,
+        fee_amount,
+        denominations,
+        call_hash,
+        nft_metadata_hash,
+    ))
+}
+
 /// Build public inputs array for proof verification
 /// Order matches circuit: merkle_root, nullifier, out_commitments, token_mint, transfer_amount, unshield_amount, fee_amount
+/// (+ fixed_denominations for denomination pools, + call_hash for unshield-and-invoke,
+/// + nft_metadata_hash for NFT pools)
+#[allow(clippy::too_many_arguments)]
 fn build_transact_public_inputs(
     merkle_root: &[u8; 32],
     nullifier: &[u8; 32],
@@ -216,6 +380,9 @@ fn build_transact_public_inputs(
     transfer_amount: u64,
     unshield_amount: u64,
     fee_amount: u64,
+    denominations: Option<&[u64; MAX_DENOMINATIONS]>,
+    call_hash: Option<&[u8; 32]>,
+    nft_metadata_hash: Option<&[u8; 32]>,
 ) -> Vec<[u8; 32]> {
     let mut inputs = Vec::new();
     inputs.push(*merkle_root);
@@ -227,5 +394,16 @@ fn build_transact_public_inputs(
     inputs.push(u64_to_field(transfer_amount));
     inputs.push(u64_to_field(unshield_amount));
     inputs.push(u64_to_field(fee_amount));
+    if let Some(denominations) = denominations {
+        for denomination in denominations {
+            inputs.push(u64_to_field(*denomination));
+        }
+    }
+    if let Some(call_hash) = call_hash {
+        inputs.push(*call_hash);
+    }
+    if let Some(nft_metadata_hash) = nft_metadata_hash {
+        inputs.push(*nft_metadata_hash);
+    }
     inputs
 }
@@ -21,11 +21,14 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, VerificationKey, PendingOperation, PENDING_OPERATION_EXPIRY_SECONDS, MAX_INPUTS};
-use crate::constants::seeds;
+use crate::state::{Pool, VerificationKey, PendingOperation, ProtocolConfig, ProgramVersion, CircuitStats, RelayerAllowlist, OperationCredit};
+use crate::constants::{operation_types, seeds};
 use crate::errors::CloakCraftError;
-use crate::helpers::verify_groth16_proof;
-use crate::helpers::field::pubkey_to_field;
+use crate::helpers::verify_groth16_proof_metered;
+use crate::helpers::field::{pubkey_to_field, assert_canonical};
+
+/// Inputs the consolidate_3x1 circuit takes
+const CONSOLIDATION_MAX_INPUTS: usize = 3;
 
 #[derive(Accounts)]
 #[instruction(operation_id: [u8; 32])]
@@ -41,6 +44,7 @@ pub struct CreatePendingWithProofConsolidation<'info> {
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
@@ -58,6 +62,38 @@ pub struct CreatePendingWithProofConsolidation<'info> {
     #[account(mut)]
     pub relayer: Signer<'info>,
 
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Relayer allowlist (required while the pool is permissioned)
+    #[account(
+        seeds = [seeds::RELAYER_ALLOWLIST, pool.key().as_ref()],
+        bump = relayer_allowlist.bump,
+    )]
+    pub relayer_allowlist: Option<Box<Account<'info, RelayerAllowlist>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
     /// System program
     pub system_program: Program<'info, System>,
 }
@@ -86,7 +122,28 @@ pub fn create_pending_with_proof_consolidation(
     output_amount: u64,
     output_randomness: [u8; 32],
     stealth_ephemeral_pubkey: [u8; 64],
+    client_version: u32,
 ) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Permissioned pools only accept allowlisted relayers
+    ctx.accounts.pool.check_relayer(
+        ctx.accounts.relayer_allowlist.as_deref().map(|list| &**list),
+        ctx.accounts.relayer.key,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[out_commitment, output_recipient, output_randomness])?;
+    assert_canonical(&input_commitments)?;
+    assert_canonical(&nullifiers)?;
+
     let pool = &ctx.accounts.pool;
     let pending_op = &mut ctx.accounts.pending_operation;
     let clock = Clock::get()?;
@@ -98,7 +155,7 @@ pub fn create_pending_with_proof_consolidation(
 
     // Validate input count
     require!(
-        num_inputs >= 2 && num_inputs as usize <= MAX_INPUTS,
+        num_inputs >= 2 && num_inputs as usize <= CONSOLIDATION_MAX_INPUTS,
         CloakCraftError::InvalidInputCount
     );
     require!(
@@ -110,6 +167,15 @@ pub fn create_pending_with_proof_consolidation(
         CloakCraftError::InvalidInputCount
     );
 
+    // Merged notes would not be a denomination
+    require!(
+        !pool.has_fixed_denominations(),
+        CloakCraftError::InvalidDenomination
+    );
+
+    // An NFT pool holds a single note: nothing to consolidate
+    require!(!pool.is_nft_pool(), CloakCraftError::InvalidNftPool);
+
     // SECURITY: Verify ZK proof with public inputs
     #[cfg(not(feature = "skip-zk-verify"))]
     {
@@ -120,11 +186,12 @@ pub fn create_pending_with_proof_consolidation(
             &pool.token_mint,
         );
 
-        verify_groth16_proof(
+        verify_groth16_proof_metered(
             &proof,
             &ctx.accounts.verification_key.vk_data,
             &public_inputs,
             "Consolidation",
+            ctx.accounts.circuit_stats.as_deref_mut(),
         )?;
 
         msg!("✅ ZK consolidation proof verified");
@@ -138,11 +205,13 @@ pub fn create_pending_with_proof_consolidation(
 
     // Initialize pending operation PDA
     pending_op.bump = ctx.bumps.pending_operation;
-    pending_op.operation_id = operation_id;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
     pending_op.relayer = ctx.accounts.relayer.key();
-    pending_op.operation_type = 1; // 1 = consolidation
+    pending_op.operation_type = operation_types::CONSOLIDATE;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
     pending_op.created_at = clock.unix_timestamp;
-    pending_op.expires_at = clock.unix_timestamp + PENDING_OPERATION_EXPIRY_SECONDS;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
 
     // SECURITY: Store binding fields from ZK proof
     pending_op.num_inputs = num_inputs;
@@ -6,8 +6,9 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 
-use crate::state::Pool;
+use crate::state::{Pool, MAX_DENOMINATIONS, MAX_ENCRYPTED_NOTE_SIZE, NFT_STANDARD_NONE};
 use crate::constants::seeds;
+use crate::errors::CloakCraftError;
 
 #[derive(Accounts)]
 pub struct InitializePool<'info> {
@@ -52,7 +53,11 @@ pub struct InitializePool<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
+pub fn initialize_pool(
+    ctx: Context<InitializePool>,
+    state_tree: Pubkey,
+    address_tree: Pubkey,
+) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
     let clock = Clock::get()?;
 
@@ -63,6 +68,49 @@ pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
     pool.vault_bump = ctx.bumps.token_vault;
     pool.total_shielded = 0;
 
+    // Anonymity-set metrics start in the current epoch, guard disabled
+    pool.activity_epoch = Pool::activity_epoch_at(clock.unix_timestamp);
+    pool.epoch_shields = 0;
+    pool.epoch_spends = 0;
+    pool.prev_epoch_shields = 0;
+    pool.prev_epoch_spends = 0;
+    pool.total_shields = 0;
+    pool.total_spends = 0;
+    pool.min_anonymity_guard = 0;
+    pool.guard_override_until = 0;
+
+    // Flexible amounts until the authority configures denominations
+    pool.fixed_denominations = [0; MAX_DENOMINATIONS];
+
+    // Fungible until the first shield_nft (NFT mints only)
+    pool.nft_standard = NFT_STANDARD_NONE;
+    pool.nft_metadata_hash = [0; 32];
+
+    // Light trees every compressed account of this pool must use
+    // (state tree can later be rolled over with migrate_pool_trees)
+    require!(
+        state_tree != Pubkey::default() && address_tree != Pubkey::default(),
+        CloakCraftError::PoolTreesNotSet
+    );
+    pool.state_tree = state_tree;
+    pool.address_tree = address_tree;
+    pool.next_state_tree = Pubkey::default();
+    pool.tree_cutover_slot = 0;
+
+    // No dust threshold until the authority sets one
+    pool.min_note_amount = 0;
+
+    // Permissionless until the authority enables the relayer allowlist
+    pool.relayer_allowlist_enabled = false;
+
+    // Registry roots never expire until the authority sets a window
+    pool.max_root_age_slots = 0;
+
+    // Notes fit inline until the authority raises the limit
+    pool.max_encrypted_note_size = MAX_ENCRYPTED_NOTE_SIZE as u16;
+    pool.pinned_inclusion_threshold = 0;
+    pool.layout_version = Pool::LAYOUT_VERSION;
+
     // Note: Merkle tree state is now managed by Light Protocol
     // Call initialize_commitment_counter after this to enable commitment tracking
     Ok(())
@@ -0,0 +1,58 @@
+//! Create a pool's statistics account
+//!
+//! Permissionless: anyone may pay for it. Shielded value starts from the
+//! pool's `total_shielded`; deposits, withdrawals and note counts from zero.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, PoolStats};
+use crate::constants::seeds;
+
+#[derive(Accounts)]
+pub struct InitializePoolStats<'info> {
+    /// Pool the stats belong to
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool statistics
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PoolStats::INIT_SPACE,
+        seeds = [seeds::POOL_STATS, pool.key().as_ref()],
+        bump,
+    )]
+    pub pool_stats: Box<Account<'info, PoolStats>>,
+
+    /// Rent payer
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_pool_stats(ctx: Context<InitializePoolStats>) -> Result<()> {
+    let clock = Clock::get()?;
+    let stats = &mut ctx.accounts.pool_stats;
+    stats.pool = ctx.accounts.pool.key();
+    stats.total_shielded = ctx.accounts.pool.total_shielded;
+    stats.lifetime_deposits = 0;
+    stats.lifetime_withdrawals = 0;
+    stats.lifetime_fees = 0;
+    stats.note_count = 0;
+    stats.total_notes_created = 0;
+    stats.epoch = Pool::activity_epoch_at(clock.unix_timestamp);
+    stats.epoch_deposits = 0;
+    stats.epoch_withdrawals = 0;
+    stats.updated_at = clock.unix_timestamp;
+    stats.bump = ctx.bumps.pool_stats;
+
+    msg!("Pool stats initialized for pool {}", stats.pool);
+    msg!("  Shielded value: {}", stats.total_shielded);
+
+    Ok(())
+}
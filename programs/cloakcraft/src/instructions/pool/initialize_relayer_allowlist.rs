@@ -0,0 +1,51 @@
+//! Initialize a pool's relayer allowlist
+//!
+//! Creates the empty RelayerAllowlist PDA for a pool. The pool stays
+//! permissionless until the authority enables the allowlist.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, RelayerAllowlist};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct InitializeRelayerAllowlist<'info> {
+    /// Relayer allowlist (one per pool)
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RelayerAllowlist::INIT_SPACE,
+        seeds = [seeds::RELAYER_ALLOWLIST, pool.key().as_ref()],
+        bump
+    )]
+    pub relayer_allowlist: Account<'info, RelayerAllowlist>,
+
+    /// Pool the allowlist applies to
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+
+    /// Payer for account creation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_relayer_allowlist(ctx: Context<InitializeRelayerAllowlist>) -> Result<()> {
+    let allowlist = &mut ctx.accounts.relayer_allowlist;
+    allowlist.pool = ctx.accounts.pool.key();
+    allowlist.bump = ctx.bumps.relayer_allowlist;
+
+    msg!("Relayer allowlist initialized for pool {}", allowlist.pool);
+
+    Ok(())
+}
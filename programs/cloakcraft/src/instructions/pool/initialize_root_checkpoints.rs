@@ -0,0 +1,60 @@
+//! Create a pool's root checkpoint history
+//!
+//! The pool authority designates the checkpoint authority that signs
+//! `anchor_root_checkpoint`, and the minimum interval between checkpoints.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, RootCheckpointHistory};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct InitializeRootCheckpoints<'info> {
+    /// Pool the history belongs to
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Root checkpoint history
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RootCheckpointHistory::INIT_SPACE,
+        seeds = [seeds::ROOT_CHECKPOINTS, pool.key().as_ref()],
+        bump,
+    )]
+    pub checkpoint_history: Box<Account<'info, RootCheckpointHistory>>,
+
+    /// Pool authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_root_checkpoints(
+    ctx: Context<InitializeRootCheckpoints>,
+    checkpoint_authority: Pubkey,
+    min_interval: i64,
+) -> Result<()> {
+    require!(min_interval >= 0, CloakCraftError::InvalidRootCheckpoint);
+
+    let history = &mut ctx.accounts.checkpoint_history;
+    history.pool = ctx.accounts.pool.key();
+    history.checkpoint_authority = checkpoint_authority;
+    history.min_interval = min_interval;
+    history.total_checkpoints = 0;
+    history.latest_chain_hash = [0u8; 32];
+    history.bump = ctx.bumps.checkpoint_history;
+
+    msg!("Root checkpoints initialized for pool {}", history.pool);
+    msg!("  Checkpoint authority: {}", checkpoint_authority);
+    msg!("  Min interval: {}s", min_interval);
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+//! Create a pool's root registry
+//!
+//! Permissionless: anyone may pay for it. The registry tree starts empty;
+//! every commitment created in the pool afterwards is appended.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, RootRegistry, RegisteredRoot, MAX_REGISTRY_ROOTS};
+use crate::constants::{seeds, MERKLE_TREE_DEPTH};
+
+#[derive(Accounts)]
+pub struct InitializeRootRegistry<'info> {
+    /// Pool the registry belongs to
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Root registry
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RootRegistry::INIT_SPACE,
+        seeds = [seeds::ROOT_REGISTRY, pool.key().as_ref()],
+        bump,
+    )]
+    pub root_registry: Box<Account<'info, RootRegistry>>,
+
+    /// Rent payer
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_root_registry(ctx: Context<InitializeRootRegistry>) -> Result<()> {
+    let registry = &mut ctx.accounts.root_registry;
+    registry.pool = ctx.accounts.pool.key();
+    registry.leaf_count = 0;
+    registry.frontier = [[0u8; 32]; MERKLE_TREE_DEPTH];
+    registry.total_roots = 0;
+    registry.roots = [RegisteredRoot::default(); MAX_REGISTRY_ROOTS];
+    registry.bump = ctx.bumps.root_registry;
+
+    msg!("Root registry initialized for pool {}", registry.pool);
+
+    Ok(())
+}
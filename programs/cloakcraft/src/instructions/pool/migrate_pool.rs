@@ -0,0 +1,115 @@
+//! Migrate a pool account to the current layout
+//!
+//! Pool gained fields (anonymity guard, denominations, NFT custody, tree
+//! pinning, dust threshold, relayer allowlist, root age window, note size
+//! limit, pinned inclusion threshold) after the first pools were created.
+//! Older accounts are too small to deserialize, so this reallocates them to
+//! `Pool::LEN` with the new fields zeroed and stamps `Pool::LAYOUT_VERSION`.
+//!
+//! Zero is the disabled default of every new field except the Light trees:
+//! a pool without trees rejects all nullifier and commitment CPIs until its
+//! authority pins them with `set_pool_trees`.
+//!
+//! The result doesn't depend on the caller, so anyone willing to pay the
+//! extra rent may migrate a pool.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+
+use crate::state::Pool;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+/// Emitted when a pool account is migrated
+#[event]
+pub struct PoolMigrated {
+    pub pool: Pubkey,
+    pub old_len: u64,
+    pub new_len: u64,
+    pub layout_version: u8,
+}
+
+#[derive(Accounts)]
+#[instruction(token_mint: Pubkey)]
+pub struct MigratePool<'info> {
+    /// Pool to migrate (older layout, checked manually)
+    /// CHECK: Discriminator is checked in the handler
+    #[account(
+        mut,
+        seeds = [seeds::POOL, token_mint.as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub pool: UncheckedAccount<'info>,
+
+    /// Payer for reallocation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program for reallocation
+    pub system_program: Program<'info, System>,
+}
+
+/// Reallocate a pool to the current layout
+///
+/// # Arguments
+/// * `token_mint` - Pool's token mint (asset id for cNFT pools)
+pub fn migrate_pool<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigratePool<'info>>,
+    _token_mint: Pubkey,
+) -> Result<()> {
+    let pool_info = ctx.accounts.pool.to_account_info();
+    let old_len = pool_info.data_len();
+
+    {
+        let data = pool_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == Pool::DISCRIMINATOR[..],
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+        // The version byte only exists once the account has the current size,
+        // followed by `surplus_amm_pool` (the former 32 reserved bytes)
+        let migrated = old_len >= Pool::LEN && data[Pool::LEN - 33] == Pool::LAYOUT_VERSION;
+        require!(!migrated, CloakCraftError::AccountAlreadyMigrated);
+    }
+
+    if old_len < Pool::LEN {
+        // Top up rent for the larger account
+        let rent_due = Rent::get()?
+            .minimum_balance(Pool::LEN)
+            .saturating_sub(pool_info.lamports());
+        if rent_due > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: pool_info.clone(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+
+        // New fields are zero-filled (trees are set with set_pool_trees)
+        pool_info.resize(Pool::LEN)?;
+    }
+
+    let mut pool = {
+        let data = pool_info.try_borrow_data()?;
+        Pool::try_deserialize(&mut &data[..])?
+    };
+    pool.layout_version = Pool::LAYOUT_VERSION;
+    pool.try_serialize(&mut &mut pool_info.try_borrow_mut_data()?[..])?;
+
+    emit!(PoolMigrated {
+        pool: pool_info.key(),
+        old_len: old_len as u64,
+        new_len: Pool::LEN as u64,
+        layout_version: Pool::LAYOUT_VERSION,
+    });
+    msg!("Pool {} migrated to layout v{}: {} -> {} bytes", pool_info.key(), Pool::LAYOUT_VERSION, old_len, Pool::LEN);
+
+    Ok(())
+}
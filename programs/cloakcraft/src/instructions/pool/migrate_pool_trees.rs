@@ -0,0 +1,83 @@
+//! Schedule a pool's Light state tree rollover
+//!
+//! New compressed accounts keep going to the current state tree until the
+//! cutover slot, after which only the new tree is accepted. Commitments in
+//! the old tree stay spendable: their addresses are derived in the pool's
+//! address tree, which never changes (nullifier uniqueness depends on it).
+//!
+//! The new tree must be registered in the tree registry, active and below
+//! its near-capacity threshold (see `state::tree_registry`).
+
+use anchor_lang::prelude::*;
+use light_sdk::constants::ACCOUNT_COMPRESSION_PROGRAM_ID;
+
+use crate::state::{Pool, TreeRegistry};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct MigratePoolTrees<'info> {
+    /// Pool to migrate
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// New output state tree (V2 output queue)
+    /// CHECK: Owner checked against the account compression program
+    #[account(
+        constraint = *new_state_tree.owner == Pubkey::new_from_array(ACCOUNT_COMPRESSION_PROGRAM_ID) @ CloakCraftError::InvalidStateTree,
+    )]
+    pub new_state_tree: UncheckedAccount<'info>,
+
+    /// Tree registry (the new tree must be an approved rollover target)
+    #[account(
+        seeds = [seeds::TREE_REGISTRY],
+        bump = tree_registry.bump,
+    )]
+    pub tree_registry: Box<Account<'info, TreeRegistry>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+/// Emitted when a state tree migration is scheduled
+#[event]
+pub struct PoolTreeMigrationScheduled {
+    pub pool: Pubkey,
+    pub old_state_tree: Pubkey,
+    pub new_state_tree: Pubkey,
+    pub cutover_slot: u64,
+}
+
+pub fn migrate_pool_trees(ctx: Context<MigratePoolTrees>, cutover_slot: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let slot = Clock::get()?.slot;
+    let new_state_tree = ctx.accounts.new_state_tree.key();
+
+    // A migration whose cutover has passed is complete
+    pool.promote_state_tree(slot);
+
+    require!(pool.address_tree != Pubkey::default(), CloakCraftError::PoolTreesNotSet);
+    require!(!pool.has_pending_tree_migration(), CloakCraftError::TreeMigrationPending);
+    require!(cutover_slot > slot, CloakCraftError::InvalidTreeCutover);
+    require_keys_neq!(new_state_tree, pool.state_tree, CloakCraftError::InvalidStateTree);
+    ctx.accounts.tree_registry.check_rollover_target(&new_state_tree)?;
+
+    pool.next_state_tree = new_state_tree;
+    pool.tree_cutover_slot = cutover_slot;
+
+    msg!("Pool {} state tree {} -> {} at slot {}", pool.key(), pool.state_tree, new_state_tree, cutover_slot);
+
+    emit!(PoolTreeMigrationScheduled {
+        pool: pool.key(),
+        old_state_tree: pool.state_tree,
+        new_state_tree,
+        cutover_slot,
+    });
+
+    Ok(())
+}
@@ -1,21 +1,74 @@
-//! Pool instructions: initialize, shield, transact (multi-phase append pattern), store_commitment
+//! Pool instructions: initialize, shield (fungible, NFT and CPI-signed), transact (multi-phase append pattern, transfer batches), unshield-and-invoke, store_commitment,
+//! anonymity guard, denomination and relayer allowlist configuration, state tree and layout migration, root checkpoints, root registry, pool stats and solvency checks
 
 mod initialize_pool;
 mod initialize_commitment_counter;
 mod shield;
+mod shield_signed;
+mod shield_pair;
+mod shield_nft;
 mod create_pending_with_proof;
 mod create_pending_with_proof_consolidation;
+mod append_transfer_to_batch;
 mod process_unshield;
+mod process_unshield_and_invoke;
+mod process_unshield_nft;
 mod transact; // DEPRECATED - use append pattern instead
 mod verify_proof_for_transact; // DEPRECATED - use create_pending_with_proof instead
 mod store_commitment;
+mod set_anonymity_guard;
+mod override_anonymity_guard;
+mod set_fixed_denominations;
+mod set_min_note_amount;
+mod set_max_encrypted_note_size;
+mod initialize_relayer_allowlist;
+mod set_allowed_relayer;
+mod set_relayer_allowlist_enabled;
+mod migrate_pool_trees;
+mod set_pool_trees;
+mod migrate_pool;
+mod initialize_root_checkpoints;
+mod anchor_root_checkpoint;
+mod initialize_root_registry;
+mod verify_external_inclusion;
+mod set_max_root_age_slots;
+mod set_pinned_inclusion_threshold;
+mod set_surplus_amm_pool;
+mod initialize_pool_stats;
+mod verify_pool_solvency;
 
 pub use initialize_pool::*;
 pub use initialize_commitment_counter::*;
 pub use shield::*;
+pub use shield_signed::*;
+pub use shield_pair::*;
+pub use shield_nft::*;
 pub use create_pending_with_proof::*;
 pub use create_pending_with_proof_consolidation::*;
+pub use append_transfer_to_batch::*;
 pub use process_unshield::*;
+pub use process_unshield_and_invoke::*;
+pub use process_unshield_nft::*;
 pub use transact::*; // DEPRECATED
 pub use verify_proof_for_transact::*; // DEPRECATED
 pub use store_commitment::*;
+pub use set_anonymity_guard::*;
+pub use override_anonymity_guard::*;
+pub use set_fixed_denominations::*;
+pub use set_min_note_amount::*;
+pub use set_max_encrypted_note_size::*;
+pub use initialize_relayer_allowlist::*;
+pub use set_allowed_relayer::*;
+pub use set_relayer_allowlist_enabled::*;
+pub use migrate_pool_trees::*;
+pub use set_pool_trees::*;
+pub use migrate_pool::*;
+pub use initialize_root_checkpoints::*;
+pub use anchor_root_checkpoint::*;
+pub use initialize_root_registry::*;
+pub use verify_external_inclusion::*;
+pub use set_max_root_age_slots::*;
+pub use set_pinned_inclusion_threshold::*;
+pub use set_surplus_amm_pool::*;
+pub use initialize_pool_stats::*;
+pub use verify_pool_solvency::*;
@@ -0,0 +1,63 @@
+//! Temporarily override a pool's anonymity guard
+//!
+//! Lets the pool authority release held-back unshields (e.g. a new pool that
+//! has not yet built up activity). The override is time-bounded and emits an
+//! event so indexers and wallets can flag unshields made during it.
+
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::constants::{seeds, ANONYMITY_EPOCH_SECONDS};
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct OverrideAnonymityGuard<'info> {
+    /// Pool whose guard is overridden
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+/// Event emitted when the anonymity guard is bypassed
+#[event]
+pub struct AnonymityGuardOverridden {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub recent_anonymity_set: u64,
+    pub min_anonymity_guard: u32,
+    pub override_until: i64,
+}
+
+/// Bypass the guard for `duration_seconds` (at most one activity epoch)
+pub fn override_anonymity_guard(
+    ctx: Context<OverrideAnonymityGuard>,
+    duration_seconds: i64,
+) -> Result<()> {
+    require!(
+        duration_seconds > 0 && duration_seconds <= ANONYMITY_EPOCH_SECONDS,
+        CloakCraftError::InvalidGuardOverride
+    );
+
+    let pool = &mut ctx.accounts.pool;
+    let now = Clock::get()?.unix_timestamp;
+    pool.guard_override_until = now + duration_seconds;
+
+    emit!(AnonymityGuardOverridden {
+        pool: pool.key(),
+        authority: ctx.accounts.authority.key(),
+        recent_anonymity_set: pool.recent_anonymity_set(now),
+        min_anonymity_guard: pool.min_anonymity_guard,
+        override_until: pool.guard_override_until,
+    });
+
+    msg!("Anonymity guard overridden until {}", pool.guard_override_until);
+
+    Ok(())
+}
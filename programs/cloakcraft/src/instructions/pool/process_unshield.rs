@@ -3,6 +3,21 @@
 //! This is Phase 3 of the multi-phase transact operation.
 //! It processes the unshield (if any) and transfers protocol fees to the treasury.
 //!
+//! Unshields can go to an existing token account (`unshield_recipient`) or to
+//! a wallet that has no ATA yet (`recipient_owner` + `recipient_ata`): the ATA
+//! is created with the relayer as payer, and the relayer may be reimbursed in
+//! pool tokens out of the proven fee (never the unshielded amount), capped at
+//! the ATA's rent-exempt minimum. An optional SPL memo is attached for
+//! destinations that require one (e.g. exchange deposits).
+//!
+//! Destination-bound unshields: when Phase 0 stored a `call_hash`, it must be
+//! `compute_recipient_hash` of the recipient (token account or ATA owner), so
+//! a relayer can't redirect the tokens between phases. Those proofs use the
+//! transfer_1x2_invoke circuit, which binds the hash as a public input.
+//! The proven recipient (the owner, for a token account) also receives the
+//! rent refund split when the pending operation closes.
+//! Operations bound to a call go through process_unshield_and_invoke instead.
+//!
 //! Flow:
 //! Phase 0: Verify ZK proof + Create pending operation
 //! Phase 1: Verify commitment exists
@@ -12,12 +27,14 @@
 //! Final: Close pending operation
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::{instruction::Instruction, program::invoke};
+use anchor_spl::associated_token::{self, get_associated_token_address, AssociatedToken, Create};
+use anchor_spl::token::{Mint, Token, TokenAccount};
 
-use crate::state::{Pool, PendingOperation, ProtocolConfig};
-use crate::constants::seeds;
+use crate::state::{Pool, PoolStats, PendingOperation, OperationKind, ProtocolConfig};
+use crate::constants::{seeds, MAX_UNSHIELD_MEMO_LEN, SPL_MEMO_PROGRAM_ID};
 use crate::errors::CloakCraftError;
-use crate::helpers::vault::{transfer_from_vault, update_pool_balance};
+use crate::helpers::vault::{pay_unshield, settle_transfer_fee};
 
 #[derive(Accounts)]
 #[instruction(operation_id: [u8; 32])]
@@ -44,8 +61,6 @@ pub struct ProcessUnshield<'info> {
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
         constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
-        constraint = pending_operation.all_inputs_verified() @ CloakCraftError::CommitmentNotVerified,
-        constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -66,6 +81,28 @@ pub struct ProcessUnshield<'info> {
     #[account(mut)]
     pub unshield_recipient: Option<Box<Account<'info, TokenAccount>>>,
 
+    /// Wallet to unshield to when it may lack an ATA (instead of unshield_recipient)
+    /// CHECK: Only used as the ATA owner
+    pub recipient_owner: Option<UncheckedAccount<'info>>,
+
+    /// Owner's associated token account (created if missing)
+    /// CHECK: Verified to be the owner's ATA for the pool mint
+    #[account(mut)]
+    pub recipient_ata: Option<UncheckedAccount<'info>>,
+
+    /// Pool token mint (required for ATA creation)
+    #[account(
+        constraint = token_mint.key() == pool.token_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub token_mint: Option<Box<Account<'info, Mint>>>,
+
+    /// Relayer token account receiving the ATA reimbursement
+    #[account(
+        mut,
+        constraint = relayer_token_account.mint == pool.token_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub relayer_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
     /// Relayer (must match operation creator)
     #[account(
         mut,
@@ -75,6 +112,42 @@ pub struct ProcessUnshield<'info> {
 
     /// Token program
     pub token_program: Program<'info, Token>,
+
+    /// Associated token program (required for ATA creation)
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    /// System program (required for ATA creation)
+    pub system_program: Option<Program<'info, System>>,
+
+    /// SPL Memo program (required when a memo is attached)
+    /// CHECK: Address checked against the SPL Memo program ID
+    #[account(address = SPL_MEMO_PROGRAM_ID)]
+    pub memo_program: Option<UncheckedAccount<'info>>,
+
+    /// Pool statistics (optional, updated when passed)
+    #[account(
+        mut,
+        seeds = [seeds::POOL_STATS, pool.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Option<Box<Account<'info, PoolStats>>>,
+}
+
+/// Recipient hash bound by a destination-bound unshield
+///
+/// keccak("unshield_recipient" || kind || recipient) with the top 3 bits
+/// cleared (canonical BN254 field element), where kind is 1 when `recipient`
+/// is the owner of the ATA to pay and 0 when it is the token account itself.
+/// The domain keeps it distinct from `compute_call_hash`.
+pub fn compute_recipient_hash(recipient: &Pubkey, is_owner: bool) -> [u8; 32] {
+    let mut hash = solana_keccak_hasher::hashv(&[
+        b"unshield_recipient",
+        &[is_owner as u8],
+        recipient.as_ref(),
+    ])
+    .to_bytes();
+    hash[0] &= 0x1f;
+    hash
 }
 
 /// Phase 3: Process unshield and protocol fees
@@ -84,6 +157,12 @@ pub struct ProcessUnshield<'info> {
 /// 2. Processes unshield if requested (transfer tokens from vault)
 /// 3. Transfers protocol fee to treasury if fee_amount > 0
 ///
+/// `ata_reimbursement` (pool token units) is paid to the relayer out of
+/// `fee_amount`, only when this call created the recipient's ATA. It is capped
+/// at the token account rent-exempt minimum, and the treasury must still
+/// receive the expected protocol fee.
+/// `memo` is emitted via the SPL Memo program just before the unshield transfer.
+///
 /// NO encrypted notes stored - they will be regenerated in Phase 4 from:
 /// - output_recipients (stored in Phase 2)
 /// - output_amounts (stored in Phase 2)
@@ -95,7 +174,15 @@ pub fn process_unshield<'info>(
     ctx: Context<'_, '_, '_, 'info, ProcessUnshield<'info>>,
     _operation_id: [u8; 32],
     unshield_amount: u64,
+    ata_reimbursement: u64,
+    memo: Option<String>,
 ) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::Transfer)?;
+
+    if let Some(memo) = &memo {
+        require!(memo.len() <= MAX_UNSHIELD_MEMO_LEN, CloakCraftError::MemoTooLong);
+    }
+
     let pool = &mut ctx.accounts.pool;
     let pending_op = &mut ctx.accounts.pending_operation;
 
@@ -110,6 +197,19 @@ pub fn process_unshield<'info>(
         CloakCraftError::NullifiersNotCreated
     );
 
+    // NFT pools release their NFT via process_unshield_nft
+    require!(!pool.is_nft_pool(), CloakCraftError::InvalidNftPool);
+
+    // Destination-bound: the full proven amount goes to the bound recipient.
+    // A call hash (process_unshield_and_invoke) never matches a recipient hash.
+    let bound_recipient = (pending_op.call_hash != [0u8; 32]).then_some(pending_op.call_hash);
+    if bound_recipient.is_some() {
+        require!(
+            unshield_amount > 0 && unshield_amount == pending_op.unshield_amount,
+            CloakCraftError::InvalidAmount
+        );
+    }
+
     // Copy pool values for signer seeds (to avoid borrow conflicts)
     let token_mint_bytes = pool.token_mint.to_bytes();
     let pool_bump = pool.bump;
@@ -122,74 +222,128 @@ pub fn process_unshield<'info>(
     ];
     let signer_seeds = &[&pool_seeds[..]];
 
-    // Verify protocol fee is correct (on-chain enforcement)
-    let fee_amount = pending_op.fee_amount;
-    let protocol_config = &ctx.accounts.protocol_config;
-
-    if protocol_config.fees_enabled {
-        // Calculate expected fee based on transfer_amount + unshield_amount
-        // Fee is charged on the total value leaving the sender's control
-        let transfer_amount = pending_op.transfer_amount;
-        let total_taxable = transfer_amount
-            .checked_add(unshield_amount)
-            .ok_or(CloakCraftError::AmountOverflow)?;
-
-        let expected_fee = protocol_config.calculate_fee(total_taxable, protocol_config.transfer_fee_bps);
-
-        msg!("Fee verification: transfer={}, unshield={}, total={}, expected={}, provided={}",
-            transfer_amount, unshield_amount, total_taxable, expected_fee, fee_amount);
-
-        // ENFORCE: fee must be >= expected
-        require!(
-            fee_amount >= expected_fee,
-            CloakCraftError::InsufficientFee
-        );
-    }
-
-    // Process protocol fee if amount > 0 and not already processed
-    if fee_amount > 0 && !pending_op.fee_processed {
-        // Verify treasury account is provided
-        let treasury = ctx.accounts.treasury_token_account.as_ref()
-            .ok_or(CloakCraftError::InvalidTreasury)?;
-
-        msg!("Transferring {} fee to treasury {:?}", fee_amount, treasury.key());
-
-        transfer_from_vault(
-            &ctx.accounts.token_program,
-            &*ctx.accounts.token_vault,
-            &**treasury,
-            &pool.to_account_info(),
-            signer_seeds,
-            fee_amount,
-        )?;
-
-        update_pool_balance(pool, fee_amount, false)?;
-        pending_op.fee_processed = true;
-
-        msg!("✅ Fee transfer complete");
-    }
+    // ATA rent is reimbursed out of the fee (never the unshield), capped at the
+    // rent-exempt minimum, and only when this call creates the ATA (checked below)
+    require!(
+        ata_reimbursement <= Rent::get()?.minimum_balance(TokenAccount::LEN),
+        CloakCraftError::InvalidAtaReimbursement
+    );
 
+    let mut ata_created = false;
     // Process unshield if amount > 0
     if unshield_amount > 0 {
-        let recipient = ctx.accounts.unshield_recipient.as_ref()
-            .ok_or(CloakCraftError::InvalidAmount)?;
-
-        msg!("Unshielding {} tokens to {:?}", unshield_amount, recipient.key());
-
-        transfer_from_vault(
+        // Resolve recipient: existing token account, or the owner's ATA (created if missing)
+        let recipient = match ctx.accounts.unshield_recipient.as_ref() {
+            Some(recipient) => {
+                if let Some(bound) = bound_recipient {
+                    require!(
+                        compute_recipient_hash(&recipient.key(), false) == bound,
+                        CloakCraftError::UnshieldRecipientMismatch
+                    );
+                    pending_op.bind_rent_refund_recipient(recipient.owner);
+                }
+                recipient.to_account_info()
+            }
+            None => {
+                let owner = ctx.accounts.recipient_owner.as_ref()
+                    .ok_or(CloakCraftError::InvalidUnshieldRecipient)?;
+                if let Some(bound) = bound_recipient {
+                    require!(
+                        compute_recipient_hash(&owner.key(), true) == bound,
+                        CloakCraftError::UnshieldRecipientMismatch
+                    );
+                    pending_op.bind_rent_refund_recipient(owner.key());
+                }
+                let ata = ctx.accounts.recipient_ata.as_ref()
+                    .ok_or(CloakCraftError::InvalidUnshieldRecipient)?;
+                require!(
+                    ata.key() == get_associated_token_address(&owner.key(), &pool.token_mint),
+                    CloakCraftError::InvalidUnshieldRecipient
+                );
+
+                if ata.data_is_empty() {
+                    let mint = ctx.accounts.token_mint.as_ref()
+                        .ok_or(CloakCraftError::InvalidUnshieldRecipient)?;
+                    let ata_program = ctx.accounts.associated_token_program.as_ref()
+                        .ok_or(CloakCraftError::InvalidUnshieldRecipient)?;
+                    let system_program = ctx.accounts.system_program.as_ref()
+                        .ok_or(CloakCraftError::InvalidUnshieldRecipient)?;
+
+                    associated_token::create(CpiContext::new(
+                        ata_program.to_account_info(),
+                        Create {
+                            payer: ctx.accounts.relayer.to_account_info(),
+                            associated_token: ata.to_account_info(),
+                            authority: owner.to_account_info(),
+                            mint: mint.to_account_info(),
+                            system_program: system_program.to_account_info(),
+                            token_program: ctx.accounts.token_program.to_account_info(),
+                        },
+                    ))?;
+                    ata_created = true;
+                    msg!("Created recipient ATA {:?} (payer: relayer)", ata.key());
+                }
+
+                ata.to_account_info()
+            }
+        };
+
+        if let Some(memo) = &memo {
+            let memo_program = ctx.accounts.memo_program.as_ref()
+                .ok_or(CloakCraftError::InvalidUnshieldRecipient)?;
+            invoke(
+                &Instruction {
+                    program_id: memo_program.key(),
+                    accounts: vec![],
+                    data: memo.as_bytes().to_vec(),
+                },
+                &[],
+            )?;
+            msg!("Memo attached ({} bytes)", memo.len());
+        }
+
+        // Recipient may be a just-created ATA, so it is paid via AccountInfo
+        pay_unshield(
             &ctx.accounts.token_program,
-            &*ctx.accounts.token_vault,
-            &**recipient,
-            &pool.to_account_info(),
+            &ctx.accounts.token_vault,
+            recipient,
+            pool,
             signer_seeds,
             unshield_amount,
         )?;
 
-        update_pool_balance(pool, unshield_amount, false)?;
-
         msg!("✅ Unshield complete");
     }
 
+    require!(
+        ata_reimbursement == 0 || ata_created,
+        CloakCraftError::InvalidAtaReimbursement
+    );
+
+    // Verify and pay the protocol fee (on-chain enforcement)
+    let reimbursement = match ctx.accounts.relayer_token_account.as_deref() {
+        Some(relayer_token_account) => Some((relayer_token_account, ata_reimbursement)),
+        None if ata_reimbursement > 0 => return err!(CloakCraftError::InvalidAtaReimbursement),
+        None => None,
+    };
+    let fee_paid = settle_transfer_fee(
+        &ctx.accounts.token_program,
+        &ctx.accounts.token_vault,
+        ctx.accounts.treasury_token_account.as_deref(),
+        reimbursement,
+        pool,
+        pending_op,
+        &ctx.accounts.protocol_config,
+        unshield_amount,
+        signer_seeds,
+    )?;
+
+    if let Some(stats) = ctx.accounts.pool_stats.as_mut() {
+        if let Some(rolled) = stats.record_withdrawal(unshield_amount, fee_paid, Clock::get()?.unix_timestamp) {
+            emit!(rolled);
+        }
+    }
+
     msg!("Phase 3 complete: unshield and fees processed");
     msg!("Next: Phase 4+ - create_commitment (SDK regenerates encrypted notes from randomness)");
 
@@ -0,0 +1,262 @@
+//! Process Unshield and Invoke - Phase 3 variant with a proof-bound CPI
+//!
+//! Unshields to a token account and then calls a whitelisted program in the
+//! same transaction (e.g. deposit into a lending market, pay a merchant).
+//!
+//! The ZK proof (transfer_1x2_invoke circuit) commits to
+//! `call_hash = keccak(target_program || unshield_recipient || accounts || call_data)`
+//! with the top 3 bits cleared so it is a canonical BN254 field element.
+//! `accounts` covers every forwarded account's (pubkey, is_signer, is_writable),
+//! so a relayer can neither redirect the tokens nor alter the call or the
+//! accounts it runs against.
+//!
+//! Target programs must be registered (and enabled) as adapt modules.
+//! The CPI is made without pool signer seeds: the target only sees accounts
+//! passed through `remaining_accounts`.
+//!
+//! Flow:
+//! Phase 0: Verify ZK proof (with call_hash) + Create pending operation
+//! Phase 1: Verify commitment exists
+//! Phase 2: Create nullifier via generic instruction
+//! Phase 3 (this): Process unshield + fees, then invoke the target program
+//! Phase 4+: Create output commitments via generic instruction
+//! Final: Close pending operation
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::state::{AdaptModule, Pool, PoolStats, PendingOperation, OperationKind, ProtocolConfig};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::vault::{pay_unshield, settle_transfer_fee};
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct ProcessUnshieldAndInvoke<'info> {
+    /// Pool (boxed to reduce stack usage)
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Token vault (boxed to reduce stack usage)
+    #[account(
+        mut,
+        seeds = [seeds::VAULT, pool.token_mint.as_ref()],
+        bump = pool.vault_bump,
+    )]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Pending operation PDA (boxed to reduce stack usage)
+    #[account(
+        mut,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump = pending_operation.bump,
+        constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Protocol config (required - enforces fee verification)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Treasury token account for receiving fees (required if fee > 0)
+    #[account(mut)]
+    pub treasury_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Unshield recipient (bound by call_hash)
+    #[account(
+        mut,
+        constraint = unshield_recipient.mint == pool.token_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub unshield_recipient: Box<Account<'info, TokenAccount>>,
+
+    /// Adapt module registration for the target program
+    #[account(
+        seeds = [seeds::ADAPT_MODULE, target_program.key().as_ref()],
+        bump = adapt_module.bump,
+        constraint = adapt_module.is_usable() @ CloakCraftError::AdapterDisabled,
+    )]
+    pub adapt_module: Box<Account<'info, AdaptModule>>,
+
+    /// Program to invoke after the unshield (bound by call_hash)
+    /// CHECK: Must be executable and match the adapt module registration
+    #[account(
+        executable,
+        constraint = target_program.key() == adapt_module.program_id @ CloakCraftError::InvalidInvokeTarget,
+    )]
+    pub target_program: UncheckedAccount<'info>,
+
+    /// Relayer (must match operation creator)
+    #[account(
+        mut,
+        constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
+    )]
+    pub relayer: Signer<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Pool statistics (optional, updated when passed)
+    #[account(
+        mut,
+        seeds = [seeds::POOL_STATS, pool.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Option<Box<Account<'info, PoolStats>>>,
+
+    // Accounts for the target instruction are passed via remaining_accounts
+}
+
+/// Compute the call hash bound by the invoke circuit
+///
+/// keccak(target_program || recipient || len_le32(accounts) || accounts ||
+/// call_data), where each account is `pubkey || is_signer || is_writable`,
+/// with the top 3 bits cleared, which keeps it below the BN254 scalar field
+/// modulus.
+pub fn compute_call_hash(
+    target_program: &Pubkey,
+    recipient: &Pubkey,
+    accounts: &[AccountMeta],
+    call_data: &[u8],
+) -> [u8; 32] {
+    let mut encoded_accounts = Vec::with_capacity(4 + accounts.len() * 34);
+    encoded_accounts.extend_from_slice(&(accounts.len() as u32).to_le_bytes());
+    for account in accounts {
+        encoded_accounts.extend_from_slice(account.pubkey.as_ref());
+        encoded_accounts.push(account.is_signer as u8);
+        encoded_accounts.push(account.is_writable as u8);
+    }
+    let mut hash = solana_keccak_hasher::hashv(&[
+        target_program.as_ref(),
+        recipient.as_ref(),
+        &encoded_accounts,
+        call_data,
+    ])
+    .to_bytes();
+    hash[0] &= 0x1f;
+    hash
+}
+
+/// Phase 3 (invoke variant): Process unshield and fees, then CPI the target program
+///
+/// The unshield amount is taken from the PendingOperation (bound by the proof
+/// in Phase 0), not from instruction data. Fees are settled by the same
+/// `settle_transfer_fee` helper as process_unshield.
+pub fn process_unshield_and_invoke<'info>(
+    ctx: Context<'_, '_, '_, 'info, ProcessUnshieldAndInvoke<'info>>,
+    _operation_id: [u8; 32],
+    call_data: Vec<u8>,
+) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::Transfer)?;
+
+    let pool = &mut ctx.accounts.pool;
+    let pending_op = &mut ctx.accounts.pending_operation;
+
+    msg!("=== Phase 3: Process Unshield + Invoke ===");
+    msg!("Unshield amount: {}", pending_op.unshield_amount);
+    msg!("Target program: {:?}", ctx.accounts.target_program.key());
+
+    require!(
+        pending_op.all_nullifiers_created(),
+        CloakCraftError::NullifiersNotCreated
+    );
+    require!(
+        pending_op.call_hash != [0u8; 32] && pending_op.unshield_amount > 0,
+        CloakCraftError::NoUnshieldCall
+    );
+
+    // Accounts forwarded to the target instruction
+    let account_metas: Vec<AccountMeta> = ctx.remaining_accounts
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: account.key(),
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        })
+        .collect();
+
+    // SECURITY: target, recipient, forwarded accounts and call data must match
+    // what the proof committed to
+    let call_hash = compute_call_hash(
+        &ctx.accounts.target_program.key(),
+        &ctx.accounts.unshield_recipient.key(),
+        &account_metas,
+        &call_data,
+    );
+    require!(call_hash == pending_op.call_hash, CloakCraftError::CallHashMismatch);
+
+    // The proven recipient's owner receives the rent refund split on close
+    pending_op.bind_rent_refund_recipient(ctx.accounts.unshield_recipient.owner);
+
+    let unshield_amount = pending_op.unshield_amount;
+
+    // Copy pool values for signer seeds (to avoid borrow conflicts)
+    let token_mint_bytes = pool.token_mint.to_bytes();
+    let pool_bump = pool.bump;
+
+    let pool_seeds = &[
+        seeds::POOL,
+        token_mint_bytes.as_ref(),
+        &[pool_bump],
+    ];
+    let signer_seeds = &[&pool_seeds[..]];
+
+    pay_unshield(
+        &ctx.accounts.token_program,
+        &ctx.accounts.token_vault,
+        ctx.accounts.unshield_recipient.to_account_info(),
+        pool,
+        signer_seeds,
+        unshield_amount,
+    )?;
+
+    // Verify and pay the protocol fee (same rules as process_unshield)
+    let fee_paid = settle_transfer_fee(
+        &ctx.accounts.token_program,
+        &ctx.accounts.token_vault,
+        ctx.accounts.treasury_token_account.as_deref(),
+        None,
+        pool,
+        pending_op,
+        &ctx.accounts.protocol_config,
+        unshield_amount,
+        signer_seeds,
+    )?;
+
+    // Unshield is single-use: a retry cannot pay out (or call) twice
+    pending_op.unshield_amount = 0;
+
+    if let Some(stats) = ctx.accounts.pool_stats.as_mut() {
+        if let Some(rolled) = stats.record_withdrawal(unshield_amount, fee_paid, Clock::get()?.unix_timestamp) {
+            emit!(rolled);
+        }
+    }
+
+    // Invoke the target program with the bound accounts
+    let mut account_infos = ctx.remaining_accounts.to_vec();
+    account_infos.push(ctx.accounts.target_program.to_account_info());
+
+    invoke(
+        &Instruction {
+            program_id: ctx.accounts.target_program.key(),
+            accounts: account_metas,
+            data: call_data,
+        },
+        &account_infos,
+    )?;
+
+    msg!("✅ Unshield and invoke complete");
+    msg!("Next: Phase 4+ - create_commitment");
+
+    Ok(())
+}
@@ -0,0 +1,216 @@
+//! Process Unshield NFT - Phase 3 variant for NFT pools
+//!
+//! Releases the pool's NFT to the recipient wallet's associated token account.
+//! The unshield amount comes from the PendingOperation (bound by the NFT
+//! transfer proof in Phase 0) and must be exactly 1. NFT operations carry no
+//! protocol fee.
+//!
+//! Custody:
+//! - NFT: recipient ATA created if missing (relayer pays), plain SPL transfer
+//! - pNFT: Token Metadata `Transfer` signed by the pool (creates the ATA and
+//!   the recipient's token record)
+//!
+//! Flow:
+//! Phase 0: Verify ZK proof (transfer_1x2_nft) + Create pending operation
+//! Phase 1: Verify commitment exists
+//! Phase 2: Create nullifier via generic instruction
+//! Phase 3 (this): Release the NFT
+//! Phase 4+: Create output commitments via generic instruction
+//! Final: Close pending operation
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::{self, get_associated_token_address, AssociatedToken, Create};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+use crate::state::{Pool, PendingOperation, OperationKind, NFT_STANDARD_CNFT, NFT_STANDARD_PNFT};
+use crate::constants::{seeds, TOKEN_METADATA_PROGRAM_ID};
+use crate::errors::CloakCraftError;
+use crate::helpers::nft::{transfer_programmable_nft, ProgrammableTransferAccounts};
+use crate::helpers::vault::update_pool_balance;
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct ProcessUnshieldNft<'info> {
+    /// NFT pool
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.is_nft_pool() && pool.nft_standard != NFT_STANDARD_CNFT @ CloakCraftError::InvalidNftPool,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Token vault holding the NFT
+    #[account(
+        mut,
+        seeds = [seeds::VAULT, pool.token_mint.as_ref()],
+        bump = pool.vault_bump,
+    )]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Pending operation PDA
+    #[account(
+        mut,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump = pending_operation.bump,
+        constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// NFT mint
+    #[account(
+        constraint = nft_mint.key() == pool.token_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    /// Wallet receiving the NFT
+    /// CHECK: Only used as the ATA owner
+    pub recipient_owner: UncheckedAccount<'info>,
+
+    /// Recipient's associated token account (created if missing)
+    /// CHECK: Verified to be the owner's ATA for the NFT mint
+    #[account(mut)]
+    pub recipient_token_account: UncheckedAccount<'info>,
+
+    /// Relayer (must match operation creator, pays for ATA / token record)
+    #[account(
+        mut,
+        constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
+    )]
+    pub relayer: Signer<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Associated token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Metaplex metadata (pNFT only)
+    /// CHECK: Verified in transfer_programmable_nft
+    #[account(mut)]
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+
+    /// Master edition (pNFT only)
+    /// CHECK: Verified in transfer_programmable_nft
+    pub edition: Option<UncheckedAccount<'info>>,
+
+    /// Vault token record (pNFT only)
+    /// CHECK: Verified in transfer_programmable_nft
+    #[account(mut)]
+    pub vault_token_record: Option<UncheckedAccount<'info>>,
+
+    /// Recipient token record (pNFT only, created by Token Metadata)
+    /// CHECK: Verified in transfer_programmable_nft
+    #[account(mut)]
+    pub recipient_token_record: Option<UncheckedAccount<'info>>,
+
+    /// Token Metadata program (pNFT only)
+    /// CHECK: Address checked against the Token Metadata program ID
+    #[account(address = TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+
+    /// Instructions sysvar (pNFT only)
+    /// CHECK: Address checked against the instructions sysvar
+    #[account(address = sysvar::instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+}
+
+/// Phase 3 (NFT variant): Release the NFT to the recipient
+pub fn process_unshield_nft<'info>(
+    ctx: Context<'_, '_, '_, 'info, ProcessUnshieldNft<'info>>,
+    _operation_id: [u8; 32],
+) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::Transfer)?;
+
+    let pool = &mut ctx.accounts.pool;
+    let pending_op = &mut ctx.accounts.pending_operation;
+
+    msg!("=== Phase 3: Process Unshield (NFT) ===");
+
+    require!(
+        pending_op.all_nullifiers_created(),
+        CloakCraftError::NullifiersNotCreated
+    );
+    require!(
+        pending_op.unshield_amount == 1 && pending_op.fee_amount == 0,
+        CloakCraftError::InvalidNftAmount
+    );
+    require!(
+        ctx.accounts.recipient_token_account.key()
+            == get_associated_token_address(&ctx.accounts.recipient_owner.key(), &pool.token_mint),
+        CloakCraftError::InvalidUnshieldRecipient
+    );
+
+    let token_mint_bytes = pool.token_mint.to_bytes();
+    let pool_bump = pool.bump;
+    let pool_seeds = &[
+        seeds::POOL,
+        token_mint_bytes.as_ref(),
+        &[pool_bump],
+    ];
+    let signer_seeds = &[&pool_seeds[..]];
+
+    if pool.nft_standard == NFT_STANDARD_PNFT {
+        let missing = || error!(CloakCraftError::InvalidNftMetadata);
+        transfer_programmable_nft(
+            ProgrammableTransferAccounts {
+                token: &ctx.accounts.token_vault.to_account_info(),
+                token_owner: &pool.to_account_info(),
+                destination_token: &ctx.accounts.recipient_token_account.to_account_info(),
+                destination_owner: &ctx.accounts.recipient_owner.to_account_info(),
+                mint: &ctx.accounts.nft_mint.to_account_info(),
+                metadata: &ctx.accounts.nft_metadata.as_ref().ok_or_else(missing)?.to_account_info(),
+                edition: &ctx.accounts.edition.as_ref().ok_or_else(missing)?.to_account_info(),
+                owner_token_record: &ctx.accounts.vault_token_record.as_ref().ok_or_else(missing)?.to_account_info(),
+                destination_token_record: &ctx.accounts.recipient_token_record.as_ref().ok_or_else(missing)?.to_account_info(),
+                authority: &pool.to_account_info(),
+                payer: &ctx.accounts.relayer.to_account_info(),
+                system_program: &ctx.accounts.system_program.to_account_info(),
+                sysvar_instructions: &ctx.accounts.sysvar_instructions.as_ref().ok_or_else(missing)?.to_account_info(),
+                token_program: &ctx.accounts.token_program.to_account_info(),
+                associated_token_program: &ctx.accounts.associated_token_program.to_account_info(),
+                token_metadata_program: &ctx.accounts.token_metadata_program.as_ref().ok_or_else(missing)?.to_account_info(),
+            },
+            signer_seeds,
+        )?;
+    } else {
+        if ctx.accounts.recipient_token_account.data_is_empty() {
+            associated_token::create(CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                Create {
+                    payer: ctx.accounts.relayer.to_account_info(),
+                    associated_token: ctx.accounts.recipient_token_account.to_account_info(),
+                    authority: ctx.accounts.recipient_owner.to_account_info(),
+                    mint: ctx.accounts.nft_mint.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+            ))?;
+        }
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.token_vault.to_account_info(),
+                    to: ctx.accounts.recipient_token_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            1,
+        )?;
+    }
+
+    update_pool_balance(pool, 1, false)?;
+    pending_op.unshield_amount = 0;
+
+    msg!("✅ NFT unshielded to {:?}", ctx.accounts.recipient_owner.key());
+    msg!("Next: Phase 4+ - create_commitment");
+
+    Ok(())
+}
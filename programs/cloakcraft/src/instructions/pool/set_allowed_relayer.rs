@@ -0,0 +1,49 @@
+//! Add or remove a relayer on a pool's allowlist
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, RelayerAllowlist};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct SetAllowedRelayer<'info> {
+    /// Relayer allowlist
+    #[account(
+        mut,
+        seeds = [seeds::RELAYER_ALLOWLIST, pool.key().as_ref()],
+        bump = relayer_allowlist.bump,
+    )]
+    pub relayer_allowlist: Account<'info, RelayerAllowlist>,
+
+    /// Pool the allowlist applies to
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+/// Add or remove a relayer
+///
+/// # Arguments
+/// * `relayer` - Account allowed to submit operations for the pool
+/// * `allowed` - true to add, false to remove
+pub fn set_allowed_relayer(ctx: Context<SetAllowedRelayer>, relayer: Pubkey, allowed: bool) -> Result<()> {
+    require!(
+        ctx.accounts.relayer_allowlist.set(relayer, allowed),
+        CloakCraftError::RelayerAllowlistFull
+    );
+    msg!(
+        "Relayer {} {} for pool {}",
+        relayer,
+        if allowed { "allowed" } else { "removed" },
+        ctx.accounts.pool.key()
+    );
+
+    Ok(())
+}
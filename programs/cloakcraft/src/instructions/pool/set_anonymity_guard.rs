@@ -0,0 +1,35 @@
+//! Configure a pool's minimum anonymity guard
+//!
+//! When enabled, unshields are rejected in Phase 0 while the pool's recent
+//! activity (shields + spends over the current and previous epoch) is below
+//! `min_anonymity_guard`. Set to 0 to disable.
+
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct SetAnonymityGuard<'info> {
+    /// Pool to configure
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+pub fn set_anonymity_guard(ctx: Context<SetAnonymityGuard>, min_anonymity_guard: u32) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.min_anonymity_guard = min_anonymity_guard;
+
+    msg!("Anonymity guard for pool {} set to {}", pool.key(), min_anonymity_guard);
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! Configure a pool's fixed denominations
+//!
+//! When any denomination is set, shields must deposit an exact denomination
+//! and transfer outputs must be denominations (enforced by the denomination
+//! transfer circuit). Pass all zeros to return the pool to flexible amounts.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, MAX_DENOMINATIONS};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct SetFixedDenominations<'info> {
+    /// Pool to configure
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+pub fn set_fixed_denominations(
+    ctx: Context<SetFixedDenominations>,
+    denominations: [u64; MAX_DENOMINATIONS],
+) -> Result<()> {
+    require!(
+        Pool::validate_denominations(&denominations),
+        CloakCraftError::InvalidDenominationConfig
+    );
+
+    let pool = &mut ctx.accounts.pool;
+    pool.fixed_denominations = denominations;
+
+    if pool.has_fixed_denominations() {
+        msg!("Pool {} denominations: {:?}", pool.key(), denominations);
+    } else {
+        msg!("Pool {} set to flexible amounts", pool.key());
+    }
+
+    Ok(())
+}
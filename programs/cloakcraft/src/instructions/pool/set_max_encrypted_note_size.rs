@@ -0,0 +1,41 @@
+//! Configure a pool's maximum encrypted note size
+//!
+//! Notes up to `MAX_ENCRYPTED_NOTE_SIZE` are stored inline in the commitment
+//! account. Raising the limit (up to `ENCRYPTED_NOTE_HARD_CAP`) lets
+//! `create_commitment` store extended notes such as memos or multi-asset
+//! notes, with the excess in the account's overflow bytes.
+
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct SetMaxEncryptedNoteSize<'info> {
+    /// Pool to configure
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+pub fn set_max_encrypted_note_size(ctx: Context<SetMaxEncryptedNoteSize>, max_encrypted_note_size: u16) -> Result<()> {
+    require!(
+        Pool::validate_encrypted_note_limit(max_encrypted_note_size),
+        CloakCraftError::InvalidEncryptedNoteLimit
+    );
+
+    let pool = &mut ctx.accounts.pool;
+    pool.max_encrypted_note_size = max_encrypted_note_size;
+
+    msg!("Pool {} encrypted note limit: {} bytes", pool.key(), max_encrypted_note_size);
+
+    Ok(())
+}
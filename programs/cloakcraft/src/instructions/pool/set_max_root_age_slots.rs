@@ -0,0 +1,36 @@
+//! Configure a pool's root registry age window
+//!
+//! The root registry retains its latest roots regardless of age, so a proof
+//! against a long-superseded root keeps verifying until it rotates out.
+//! Roots produced more than `max_root_age_slots` slots ago are rejected by
+//! `verify_external_inclusion`. Set 0 to accept any retained root.
+
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct SetMaxRootAgeSlots<'info> {
+    /// Pool to configure
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+pub fn set_max_root_age_slots(ctx: Context<SetMaxRootAgeSlots>, max_root_age_slots: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.max_root_age_slots = max_root_age_slots;
+
+    msg!("Pool {} root age window: {} slots", pool.key(), max_root_age_slots);
+
+    Ok(())
+}
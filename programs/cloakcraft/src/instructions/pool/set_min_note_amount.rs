@@ -0,0 +1,35 @@
+//! Configure a pool's dust threshold
+//!
+//! Change notes below `min_note_amount` cost more to ever spend than they
+//! are worth and only bloat state, so flows that publish a change amount
+//! reject them. Set 0 to disable.
+
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct SetMinNoteAmount<'info> {
+    /// Pool to configure
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+pub fn set_min_note_amount(ctx: Context<SetMinNoteAmount>, min_note_amount: u64) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.min_note_amount = min_note_amount;
+
+    msg!("Pool {} dust threshold: {}", pool.key(), min_note_amount);
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+//! Configure a pool's pinned inclusion threshold
+//!
+//! Phase 1 normally accepts whatever merkle context the indexer returns,
+//! including `prove_by_index` (the note is still in the output queue and no
+//! ZK proof is checked). Operations moving at least
+//! `pinned_inclusion_threshold` through the pool must instead verify each
+//! input with a validity proof against an explicit root index, so a
+//! manipulated indexer context can't stand in for tree inclusion. Notes
+//! still in the output queue can't be spent by such operations until they
+//! are batched into the tree. Set 0 to disable.
+//!
+//! The threshold covers every inclusion check against the pool: Phase 1 of
+//! its notes, vote commitments over its token, position metadata when it is
+//! a position pool, and LP note registrations (the note's value is private,
+//! so any nonzero threshold applies).
+
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct SetPinnedInclusionThreshold<'info> {
+    /// Pool to configure
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+pub fn set_pinned_inclusion_threshold(
+    ctx: Context<SetPinnedInclusionThreshold>,
+    pinned_inclusion_threshold: u64,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.pinned_inclusion_threshold = pinned_inclusion_threshold;
+
+    msg!("Pool {} pinned inclusion threshold: {}", pool.key(), pinned_inclusion_threshold);
+
+    Ok(())
+}
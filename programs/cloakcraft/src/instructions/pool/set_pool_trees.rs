@@ -0,0 +1,51 @@
+//! Pin the Light trees of a pool migrated from a layout without them
+//!
+//! `migrate_pool` zero-fills `state_tree` and `address_tree`, and every
+//! nullifier and commitment CPI rejects a pool without trees, so the pool
+//! authority sets them once here. The address tree must be the one the pool's
+//! existing commitments and nullifiers were derived in, or spent notes could
+//! be spent again. Once set, the address tree is fixed and the state tree only
+//! changes through `migrate_pool_trees`.
+
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct SetPoolTrees<'info> {
+    /// Pool to configure
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+pub fn set_pool_trees(
+    ctx: Context<SetPoolTrees>,
+    state_tree: Pubkey,
+    address_tree: Pubkey,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+
+    require!(pool.address_tree == Pubkey::default(), CloakCraftError::PoolTreesAlreadySet);
+    require!(
+        state_tree != Pubkey::default() && address_tree != Pubkey::default(),
+        CloakCraftError::PoolTreesNotSet
+    );
+    pool.state_tree = state_tree;
+    pool.address_tree = address_tree;
+    pool.next_state_tree = Pubkey::default();
+    pool.tree_cutover_slot = 0;
+
+    msg!("Pool {} trees: state {}, address {}", pool.key(), state_tree, address_tree);
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+//! Switch a pool between permissionless and allowlisted relayers
+//!
+//! While enabled, Phase 0 of operations spending from the pool only accepts
+//! relayers on its RelayerAllowlist. Requiring the allowlist account here
+//! ensures it exists before the pool is locked down.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, RelayerAllowlist};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct SetRelayerAllowlistEnabled<'info> {
+    /// Pool to configure
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// The pool's relayer allowlist
+    #[account(
+        seeds = [seeds::RELAYER_ALLOWLIST, pool.key().as_ref()],
+        bump = relayer_allowlist.bump,
+    )]
+    pub relayer_allowlist: Account<'info, RelayerAllowlist>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+pub fn set_relayer_allowlist_enabled(ctx: Context<SetRelayerAllowlistEnabled>, enabled: bool) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.relayer_allowlist_enabled = enabled;
+
+    msg!(
+        "Pool {} relayers: {}",
+        pool.key(),
+        if enabled { "allowlisted" } else { "permissionless" }
+    );
+
+    Ok(())
+}
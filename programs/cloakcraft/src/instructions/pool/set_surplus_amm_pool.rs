@@ -0,0 +1,39 @@
+//! Designate the AMM pool that absorbs a vault's surplus
+//!
+//! A token vault may back several AMM pools. When the surplus policy folds
+//! surplus into reserves, `sync_reserves` only credits the AMM pool the
+//! vault designates here, so the caller can't steer donations to a pool it
+//! holds LP in. Set the default pubkey to stop folding this vault's surplus.
+
+use anchor_lang::prelude::*;
+
+use crate::state::Pool;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct SetSurplusAmmPool<'info> {
+    /// Pool to configure
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Pool authority
+    pub authority: Signer<'info>,
+}
+
+pub fn set_surplus_amm_pool(
+    ctx: Context<SetSurplusAmmPool>,
+    surplus_amm_pool: Pubkey,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    pool.surplus_amm_pool = surplus_amm_pool;
+
+    msg!("Pool {} surplus AMM pool: {}", pool.key(), surplus_amm_pool);
+
+    Ok(())
+}
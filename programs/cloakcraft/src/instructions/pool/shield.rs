@@ -1,11 +1,15 @@
 //! Shield tokens - deposit public tokens into the shielded pool
 //!
 //! Uses Light Protocol compressed accounts for commitment storage.
+//!
+//! Nothing ties the commitment to the depositor: it may be addressed to a
+//! third party's stealth address (shield-and-transfer), and the stored
+//! ephemeral pubkey lets that recipient find and spend the note.
 
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
-use crate::state::{Pool, PoolCommitmentCounter, LightValidityProof, LightAddressTreeInfo};
+use crate::state::{Pool, PoolCommitmentCounter, PoolStats, RootRegistry, TreeRegistry, LightValidityProof, LightAddressTreeInfo};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 use crate::helpers::vault::{transfer_to_vault, update_pool_balance};
@@ -27,7 +31,7 @@ pub struct Shield<'info> {
         seeds = [PoolCommitmentCounter::SEEDS_PREFIX, pool.key().as_ref()],
         bump = commitment_counter.bump,
     )]
-    pub commitment_counter: Account<'info, PoolCommitmentCounter>,
+    pub commitment_counter: Box<Account<'info, PoolCommitmentCounter>>,
 
     /// Token vault
     #[account(
@@ -35,11 +39,11 @@ pub struct Shield<'info> {
         seeds = [seeds::VAULT, pool.token_mint.as_ref()],
         bump = pool.vault_bump,
     )]
-    pub token_vault: Account<'info, TokenAccount>,
+    pub token_vault: Box<Account<'info, TokenAccount>>,
 
     /// User's token account (source)
     #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
 
     /// User (pays for compressed account creation)
     #[account(mut)]
@@ -48,6 +52,33 @@ pub struct Shield<'info> {
     /// Token program
     pub token_program: Program<'info, Token>,
 
+    /// Pool statistics (optional, updated when passed)
+    #[account(
+        mut,
+        seeds = [seeds::POOL_STATS, pool.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Option<Box<Account<'info, PoolStats>>>,
+
+    /// Root registry (the commitment is appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, pool.key().as_ref()],
+        bump,
+    )]
+    pub root_registry: UncheckedAccount<'info>,
+
+    /// Tree registry (once initialized, the output tree must be registered
+    /// and its writes are counted toward TreeNearCapacity)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: UncheckedAccount<'info>,
+
     // Light Protocol accounts are passed via remaining_accounts
 }
 
@@ -69,11 +100,20 @@ pub fn shield<'info>(
     stealth_ephemeral_pubkey: [u8; 64],
     encrypted_note: Vec<u8>,
     light_params: Option<LightCommitmentParams>,
+    view_tag: Option<[u8; 8]>,
 ) -> Result<()> {
     let pool = &mut ctx.accounts.pool;
     let commitment_counter = &mut ctx.accounts.commitment_counter;
     let clock = Clock::get()?;
 
+    // NFT pools are shielded into via shield_nft
+    require!(!pool.is_nft_pool(), CloakCraftError::InvalidNftPool);
+
+    // Denomination pools only accept exact denomination deposits
+    if pool.has_fixed_denominations() {
+        require!(pool.is_denomination(amount), CloakCraftError::InvalidDenomination);
+    }
+
     // Transfer tokens to vault
     transfer_to_vault(
         &ctx.accounts.token_program,
@@ -84,13 +124,12 @@ pub fn shield<'info>(
     )?;
 
     // Get leaf index and increment counter
-    let leaf_index = commitment_counter.next_leaf_index;
-    commitment_counter.next_leaf_index += 1;
-    commitment_counter.total_commitments += 1;
+    let leaf_index = commitment_counter.allocate();
 
     // Create commitment compressed account via Light Protocol
     // Encrypted note is stored inline for direct scanning via Light Protocol API
     // Stealth ephemeral pubkey is stored so recipient can derive stealthPrivateKey for decryption
+    // View tag (if provided) lets the recipient skip trial decryption of other notes
     if let Some(params) = light_params {
         let (encrypted_note_arr, encrypted_note_len) = vec_to_fixed_note(&encrypted_note);
         create_commitment_account(
@@ -100,16 +139,28 @@ pub fn shield<'info>(
             params.address_tree_info,
             params.output_tree_index,
             pool.key(),
+            pool.active_trees(Clock::get()?.slot),
             commitment,
             leaf_index,
             stealth_ephemeral_pubkey,
             encrypted_note_arr,
             encrypted_note_len,
+            view_tag.unwrap_or_default(),
         )?;
+
+        TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &pool.active_trees(Clock::get()?.slot).state_tree, 1)?;
+        RootRegistry::append_to(&ctx.accounts.root_registry, commitment, clock.slot)?;
     }
 
     // Update pool totals (merkle tree is now in Light Protocol)
     update_pool_balance(pool, amount, true)?;
+    pool.record_shield(clock.unix_timestamp);
+
+    if let Some(stats) = ctx.accounts.pool_stats.as_mut() {
+        if let Some(rolled) = stats.record_deposit(amount, clock.unix_timestamp) {
+            emit!(rolled);
+        }
+    }
 
     // Emit shielded event (for public tracking)
     Ok(())
@@ -0,0 +1,248 @@
+//! Shield NFT - deposit a single SPL NFT or programmable NFT into its pool
+//!
+//! NFT pools are regular pools keyed by the NFT mint, so every note commits
+//! to the mint like any other note, with amount = 1. The first shield_nft on a
+//! fresh pool turns it into an NFT pool and snapshots the metadata hash, which
+//! the NFT transfer circuit takes as a public input.
+//!
+//! Custody:
+//! - NFT: plain SPL transfer into the pool vault
+//! - pNFT: Token Metadata `Transfer`, which keeps the vault token account frozen
+//!   and moves the token record from the user's account to the vault's
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::state::{Pool, PoolCommitmentCounter, NFT_STANDARD_NFT, NFT_STANDARD_PNFT, RootRegistry, TreeRegistry};
+use crate::constants::{seeds, TOKEN_METADATA_PROGRAM_ID};
+use crate::errors::CloakCraftError;
+use crate::helpers::nft::{
+    compute_metadata_hash, metadata_pda, parse_token_standard, transfer_programmable_nft,
+    ProgrammableTransferAccounts, TOKEN_STANDARD_PROGRAMMABLE_NON_FUNGIBLE,
+};
+use crate::helpers::vault::{transfer_to_vault, update_pool_balance};
+use crate::light_cpi::{create_commitment_account, vec_to_fixed_note};
+
+use super::LightCommitmentParams;
+
+#[derive(Accounts)]
+pub struct ShieldNft<'info> {
+    /// NFT pool (keyed by the NFT mint)
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Commitment counter for this pool
+    #[account(
+        mut,
+        seeds = [PoolCommitmentCounter::SEEDS_PREFIX, pool.key().as_ref()],
+        bump = commitment_counter.bump,
+    )]
+    pub commitment_counter: Box<Account<'info, PoolCommitmentCounter>>,
+
+    /// Token vault
+    #[account(
+        mut,
+        seeds = [seeds::VAULT, pool.token_mint.as_ref()],
+        bump = pool.vault_bump,
+    )]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// NFT mint
+    #[account(
+        constraint = nft_mint.key() == pool.token_mint @ CloakCraftError::InvalidTokenMint,
+        constraint = nft_mint.decimals == 0 && nft_mint.supply == 1 @ CloakCraftError::NotAnNft,
+    )]
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    /// Metaplex metadata for the mint
+    /// CHECK: Address derived from the mint, owner checked against Token Metadata
+    #[account(
+        mut,
+        owner = TOKEN_METADATA_PROGRAM_ID @ CloakCraftError::InvalidNftMetadata,
+        constraint = nft_metadata.key() == metadata_pda(&nft_mint.key()) @ CloakCraftError::InvalidNftMetadata,
+    )]
+    pub nft_metadata: UncheckedAccount<'info>,
+
+    /// User's token account holding the NFT
+    #[account(
+        mut,
+        constraint = user_token_account.mint == pool.token_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// User (pays for compressed account creation)
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Master edition (pNFT only)
+    /// CHECK: Verified in transfer_programmable_nft
+    pub edition: Option<UncheckedAccount<'info>>,
+
+    /// User token record (pNFT only)
+    /// CHECK: Verified in transfer_programmable_nft
+    #[account(mut)]
+    pub owner_token_record: Option<UncheckedAccount<'info>>,
+
+    /// Vault token record (pNFT only, created by Token Metadata)
+    /// CHECK: Verified in transfer_programmable_nft
+    #[account(mut)]
+    pub vault_token_record: Option<UncheckedAccount<'info>>,
+
+    /// Token Metadata program (pNFT only)
+    /// CHECK: Address checked against the Token Metadata program ID
+    #[account(address = TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+
+    /// Associated token program (pNFT only)
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    /// System program (pNFT only)
+    pub system_program: Option<Program<'info, System>>,
+
+    /// Instructions sysvar (pNFT only)
+    /// CHECK: Address checked against the instructions sysvar
+    #[account(address = sysvar::instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    /// Root registry (the commitment is appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, pool.key().as_ref()],
+        bump,
+    )]
+    pub root_registry: UncheckedAccount<'info>,
+
+    /// Tree registry (once initialized, the output tree must be registered
+    /// and its writes are counted toward TreeNearCapacity)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: UncheckedAccount<'info>,
+
+    // Light Protocol accounts are passed via remaining_accounts
+}
+
+/// Emitted for every NFT shield
+#[event]
+pub struct NftShielded {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub nft_standard: u8,
+    pub metadata_hash: [u8; 32],
+    pub leaf_index: u64,
+}
+
+pub fn shield_nft<'info>(
+    ctx: Context<'_, '_, '_, 'info, ShieldNft<'info>>,
+    commitment: [u8; 32],
+    stealth_ephemeral_pubkey: [u8; 64],
+    encrypted_note: Vec<u8>,
+    light_params: Option<LightCommitmentParams>,
+    view_tag: Option<[u8; 8]>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let commitment_counter = &mut ctx.accounts.commitment_counter;
+    let clock = Clock::get()?;
+
+    // First NFT shield turns a fresh pool into an NFT pool
+    if !pool.is_nft_pool() {
+        require!(
+            pool.total_shields == 0 && !pool.has_fixed_denominations(),
+            CloakCraftError::InvalidNftPool
+        );
+
+        let metadata_data = ctx.accounts.nft_metadata.try_borrow_data()?;
+        let programmable = parse_token_standard(&metadata_data)
+            == Some(TOKEN_STANDARD_PROGRAMMABLE_NON_FUNGIBLE);
+        pool.nft_standard = if programmable { NFT_STANDARD_PNFT } else { NFT_STANDARD_NFT };
+        pool.nft_metadata_hash = compute_metadata_hash(&metadata_data);
+
+        msg!("Pool {} is now an NFT pool (standard {})", pool.key(), pool.nft_standard);
+    }
+
+    require!(ctx.accounts.user_token_account.amount == 1, CloakCraftError::InsufficientBalance);
+
+    if pool.nft_standard == NFT_STANDARD_PNFT {
+        let missing = || error!(CloakCraftError::InvalidNftMetadata);
+        transfer_programmable_nft(
+            ProgrammableTransferAccounts {
+                token: &ctx.accounts.user_token_account.to_account_info(),
+                token_owner: &ctx.accounts.user.to_account_info(),
+                destination_token: &ctx.accounts.token_vault.to_account_info(),
+                destination_owner: &pool.to_account_info(),
+                mint: &ctx.accounts.nft_mint.to_account_info(),
+                metadata: &ctx.accounts.nft_metadata.to_account_info(),
+                edition: &ctx.accounts.edition.as_ref().ok_or_else(missing)?.to_account_info(),
+                owner_token_record: &ctx.accounts.owner_token_record.as_ref().ok_or_else(missing)?.to_account_info(),
+                destination_token_record: &ctx.accounts.vault_token_record.as_ref().ok_or_else(missing)?.to_account_info(),
+                authority: &ctx.accounts.user.to_account_info(),
+                payer: &ctx.accounts.user.to_account_info(),
+                system_program: &ctx.accounts.system_program.as_ref().ok_or_else(missing)?.to_account_info(),
+                sysvar_instructions: &ctx.accounts.sysvar_instructions.as_ref().ok_or_else(missing)?.to_account_info(),
+                token_program: &ctx.accounts.token_program.to_account_info(),
+                associated_token_program: &ctx.accounts.associated_token_program.as_ref().ok_or_else(missing)?.to_account_info(),
+                token_metadata_program: &ctx.accounts.token_metadata_program.as_ref().ok_or_else(missing)?.to_account_info(),
+            },
+            &[],
+        )?;
+    } else {
+        transfer_to_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.user_token_account,
+            &ctx.accounts.token_vault,
+            &ctx.accounts.user,
+            1,
+        )?;
+    }
+
+    // Get leaf index and increment counter
+    let leaf_index = commitment_counter.allocate();
+
+    // Note commits to (stealth pubkey, NFT mint, amount = 1, randomness)
+    if let Some(params) = light_params {
+        let (encrypted_note_arr, encrypted_note_len) = vec_to_fixed_note(&encrypted_note);
+        create_commitment_account(
+            &ctx.accounts.user.to_account_info(),
+            ctx.remaining_accounts,
+            params.validity_proof,
+            params.address_tree_info,
+            params.output_tree_index,
+            pool.key(),
+            pool.active_trees(Clock::get()?.slot),
+            commitment,
+            leaf_index,
+            stealth_ephemeral_pubkey,
+            encrypted_note_arr,
+            encrypted_note_len,
+            view_tag.unwrap_or_default(),
+        )?;
+        TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &pool.active_trees(Clock::get()?.slot).state_tree, 1)?;
+        RootRegistry::append_to(&ctx.accounts.root_registry, commitment, Clock::get()?.slot)?;
+    }
+
+    update_pool_balance(pool, 1, true)?;
+    pool.record_shield(clock.unix_timestamp);
+
+    emit!(NftShielded {
+        pool: pool.key(),
+        mint: pool.token_mint,
+        nft_standard: pool.nft_standard,
+        metadata_hash: pool.nft_metadata_hash,
+        leaf_index,
+    });
+
+    Ok(())
+}
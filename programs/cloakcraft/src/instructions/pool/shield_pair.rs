@@ -0,0 +1,274 @@
+//! Shield pair - deposit into two pools in one transaction
+//!
+//! Prepares both legs of an add-liquidity (or any two-token position) at
+//! once: both vault transfers and both commitments happen atomically, so an
+//! LP onboards in one transaction instead of one per token. Each leg gets
+//! the same pool checks and accounting as `shield`.
+//!
+//! Both commitments are created through the same Light Protocol accounts
+//! (remaining_accounts), each with its own validity proof.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::state::{Pool, PoolCommitmentCounter, PoolStats, RootRegistry, TreeRegistry};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::vault::{transfer_to_vault, update_pool_balance};
+use crate::light_cpi::{create_commitment_account, vec_to_fixed_note};
+
+use super::LightCommitmentParams;
+
+/// One leg of a pair shield
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ShieldLeg {
+    pub commitment: [u8; 32],
+    pub amount: u64,
+    pub stealth_ephemeral_pubkey: [u8; 64],
+    pub encrypted_note: Vec<u8>,
+    pub light_params: LightCommitmentParams,
+    pub view_tag: Option<[u8; 8]>,
+}
+
+/// Emitted when both legs of a pair shield land
+#[event]
+pub struct PairShielded {
+    pub pool_a: Pubkey,
+    pub pool_b: Pubkey,
+    pub commitment_a: [u8; 32],
+    pub commitment_b: [u8; 32],
+    pub leaf_index_a: u64,
+    pub leaf_index_b: u64,
+}
+
+#[derive(Accounts)]
+pub struct ShieldPair<'info> {
+    /// First pool to shield into
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool_a.token_mint.as_ref()],
+        bump = pool_a.bump,
+    )]
+    pub pool_a: Box<Account<'info, Pool>>,
+
+    /// Commitment counter for pool A
+    #[account(
+        mut,
+        seeds = [PoolCommitmentCounter::SEEDS_PREFIX, pool_a.key().as_ref()],
+        bump = commitment_counter_a.bump,
+    )]
+    pub commitment_counter_a: Box<Account<'info, PoolCommitmentCounter>>,
+
+    /// Token vault for pool A
+    #[account(
+        mut,
+        seeds = [seeds::VAULT, pool_a.token_mint.as_ref()],
+        bump = pool_a.vault_bump,
+    )]
+    pub token_vault_a: Box<Account<'info, TokenAccount>>,
+
+    /// User's token account for pool A's mint (source)
+    #[account(
+        mut,
+        token::mint = pool_a.token_mint,
+    )]
+    pub user_token_account_a: Box<Account<'info, TokenAccount>>,
+
+    /// Second pool to shield into
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool_b.token_mint.as_ref()],
+        bump = pool_b.bump,
+        constraint = pool_b.key() != pool_a.key() @ CloakCraftError::ShieldPairSamePool,
+    )]
+    pub pool_b: Box<Account<'info, Pool>>,
+
+    /// Commitment counter for pool B
+    #[account(
+        mut,
+        seeds = [PoolCommitmentCounter::SEEDS_PREFIX, pool_b.key().as_ref()],
+        bump = commitment_counter_b.bump,
+    )]
+    pub commitment_counter_b: Box<Account<'info, PoolCommitmentCounter>>,
+
+    /// Token vault for pool B
+    #[account(
+        mut,
+        seeds = [seeds::VAULT, pool_b.token_mint.as_ref()],
+        bump = pool_b.vault_bump,
+    )]
+    pub token_vault_b: Box<Account<'info, TokenAccount>>,
+
+    /// User's token account for pool B's mint (source)
+    #[account(
+        mut,
+        token::mint = pool_b.token_mint,
+    )]
+    pub user_token_account_b: Box<Account<'info, TokenAccount>>,
+
+    /// User (owns both source accounts, pays for compressed account creation)
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Pool A statistics (optional, updated when passed)
+    #[account(
+        mut,
+        seeds = [seeds::POOL_STATS, pool_a.key().as_ref()],
+        bump = pool_stats_a.bump,
+    )]
+    pub pool_stats_a: Option<Box<Account<'info, PoolStats>>>,
+
+    /// Pool B statistics (optional, updated when passed)
+    #[account(
+        mut,
+        seeds = [seeds::POOL_STATS, pool_b.key().as_ref()],
+        bump = pool_stats_b.bump,
+    )]
+    pub pool_stats_b: Option<Box<Account<'info, PoolStats>>>,
+
+    /// Pool A root registry (the commitment is appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, pool_a.key().as_ref()],
+        bump,
+    )]
+    pub root_registry_a: UncheckedAccount<'info>,
+
+    /// Pool B root registry (the commitment is appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, pool_b.key().as_ref()],
+        bump,
+    )]
+    pub root_registry_b: UncheckedAccount<'info>,
+
+    /// Tree registry (once initialized, both output trees must be registered
+    /// and their writes are counted toward TreeNearCapacity)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: UncheckedAccount<'info>,
+
+    // Light Protocol accounts are passed via remaining_accounts
+}
+
+/// Shield into two pools atomically
+///
+/// Light params are required for both legs, as in `shield_signed`.
+pub fn shield_pair<'info>(
+    ctx: Context<'_, '_, '_, 'info, ShieldPair<'info>>,
+    leg_a: ShieldLeg,
+    leg_b: ShieldLeg,
+) -> Result<()> {
+    let accounts = ctx.accounts;
+
+    let leaf_index_a = shield_leg(
+        &mut accounts.pool_a,
+        &mut accounts.commitment_counter_a,
+        &accounts.token_vault_a,
+        &accounts.user_token_account_a,
+        &mut accounts.pool_stats_a,
+        &accounts.root_registry_a,
+        &accounts.tree_registry,
+        &accounts.user,
+        &accounts.token_program,
+        ctx.remaining_accounts,
+        &leg_a,
+    )?;
+    let leaf_index_b = shield_leg(
+        &mut accounts.pool_b,
+        &mut accounts.commitment_counter_b,
+        &accounts.token_vault_b,
+        &accounts.user_token_account_b,
+        &mut accounts.pool_stats_b,
+        &accounts.root_registry_b,
+        &accounts.tree_registry,
+        &accounts.user,
+        &accounts.token_program,
+        ctx.remaining_accounts,
+        &leg_b,
+    )?;
+
+    emit!(PairShielded {
+        pool_a: accounts.pool_a.key(),
+        pool_b: accounts.pool_b.key(),
+        commitment_a: leg_a.commitment,
+        commitment_b: leg_b.commitment,
+        leaf_index_a,
+        leaf_index_b,
+    });
+
+    msg!("Shielded pair: {} + {}", leg_a.amount, leg_b.amount);
+    Ok(())
+}
+
+/// Shield one leg; returns the commitment's leaf index
+#[allow(clippy::too_many_arguments)]
+fn shield_leg<'info>(
+    pool: &mut Account<'info, Pool>,
+    commitment_counter: &mut Account<'info, PoolCommitmentCounter>,
+    token_vault: &Account<'info, TokenAccount>,
+    user_token_account: &Account<'info, TokenAccount>,
+    pool_stats: &mut Option<Box<Account<'info, PoolStats>>>,
+    root_registry: &UncheckedAccount<'info>,
+    tree_registry: &UncheckedAccount<'info>,
+    user: &Signer<'info>,
+    token_program: &Program<'info, Token>,
+    remaining_accounts: &[AccountInfo<'info>],
+    leg: &ShieldLeg,
+) -> Result<u64> {
+    let clock = Clock::get()?;
+
+    require!(leg.amount > 0, CloakCraftError::InvalidAmount);
+
+    // NFT pools are shielded into via shield_nft
+    require!(!pool.is_nft_pool(), CloakCraftError::InvalidNftPool);
+
+    // Denomination pools only accept exact denomination deposits
+    if pool.has_fixed_denominations() {
+        require!(pool.is_denomination(leg.amount), CloakCraftError::InvalidDenomination);
+    }
+
+    transfer_to_vault(token_program, user_token_account, token_vault, user, leg.amount)?;
+
+    let leaf_index = commitment_counter.allocate();
+
+    let (encrypted_note_arr, encrypted_note_len) = vec_to_fixed_note(&leg.encrypted_note);
+    create_commitment_account(
+        &user.to_account_info(),
+        remaining_accounts,
+        leg.light_params.validity_proof.clone(),
+        leg.light_params.address_tree_info.clone(),
+        leg.light_params.output_tree_index,
+        pool.key(),
+        pool.active_trees(clock.slot),
+        leg.commitment,
+        leaf_index,
+        leg.stealth_ephemeral_pubkey,
+        encrypted_note_arr,
+        encrypted_note_len,
+        leg.view_tag.unwrap_or_default(),
+    )?;
+
+    TreeRegistry::record_writes_to(tree_registry, &pool.active_trees(clock.slot).state_tree, 1)?;
+    RootRegistry::append_to(root_registry, leg.commitment, clock.slot)?;
+
+    update_pool_balance(pool, leg.amount, true)?;
+    pool.record_shield(clock.unix_timestamp);
+
+    if let Some(stats) = pool_stats.as_mut() {
+        if let Some(rolled) = stats.record_deposit(leg.amount, clock.unix_timestamp) {
+            emit!(rolled);
+        }
+    }
+
+    Ok(leaf_index)
+}
@@ -0,0 +1,164 @@
+//! Shield signed - deposit tokens on behalf of another program
+//!
+//! CPI entry point for protocols that hold user funds in PDA-owned token
+//! accounts. The depositor authority only signs the token transfer (a PDA
+//! signing through invoke_signed); a separate payer funds the commitment's
+//! compressed account, since PDAs carrying data can't pay for it.
+//!
+//! The `cloakcraft-cpi` crate has the matching account struct and a
+//! `shield_signed` CPI helper.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::state::{Pool, PoolCommitmentCounter, PoolStats, RootRegistry, TreeRegistry};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::vault::{transfer_to_vault, update_pool_balance};
+use crate::light_cpi::{create_commitment_account, vec_to_fixed_note};
+
+use super::LightCommitmentParams;
+
+#[derive(Accounts)]
+pub struct ShieldSigned<'info> {
+    /// Pool to shield into (boxed to reduce stack usage)
+    #[account(
+        mut,
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Commitment counter for this pool
+    #[account(
+        mut,
+        seeds = [PoolCommitmentCounter::SEEDS_PREFIX, pool.key().as_ref()],
+        bump = commitment_counter.bump,
+    )]
+    pub commitment_counter: Box<Account<'info, PoolCommitmentCounter>>,
+
+    /// Token vault
+    #[account(
+        mut,
+        seeds = [seeds::VAULT, pool.token_mint.as_ref()],
+        bump = pool.vault_bump,
+    )]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Depositor's token account (source)
+    #[account(
+        mut,
+        token::mint = pool.token_mint,
+        token::authority = depositor,
+    )]
+    pub depositor_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Token account authority (typically a PDA signing via invoke_signed)
+    pub depositor: Signer<'info>,
+
+    /// Pays for compressed account creation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Pool statistics (optional, updated when passed)
+    #[account(
+        mut,
+        seeds = [seeds::POOL_STATS, pool.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Option<Box<Account<'info, PoolStats>>>,
+
+    /// Root registry (the commitment is appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, pool.key().as_ref()],
+        bump,
+    )]
+    pub root_registry: UncheckedAccount<'info>,
+
+    /// Tree registry (once initialized, the output tree must be registered
+    /// and its writes are counted toward TreeNearCapacity)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: UncheckedAccount<'info>,
+
+    // Light Protocol accounts are passed via remaining_accounts
+}
+
+/// Shield tokens from a depositor authority that isn't the fee payer
+///
+/// Same pool checks and accounting as `shield`, but Light params are
+/// required: callers always get a commitment for the tokens they move.
+pub fn shield_signed<'info>(
+    ctx: Context<'_, '_, '_, 'info, ShieldSigned<'info>>,
+    commitment: [u8; 32],
+    amount: u64,
+    stealth_ephemeral_pubkey: [u8; 64],
+    encrypted_note: Vec<u8>,
+    light_params: LightCommitmentParams,
+    view_tag: Option<[u8; 8]>,
+) -> Result<()> {
+    let pool = &mut ctx.accounts.pool;
+    let commitment_counter = &mut ctx.accounts.commitment_counter;
+    let clock = Clock::get()?;
+
+    require!(amount > 0, CloakCraftError::InvalidAmount);
+
+    // NFT pools are shielded into via shield_nft
+    require!(!pool.is_nft_pool(), CloakCraftError::InvalidNftPool);
+
+    // Denomination pools only accept exact denomination deposits
+    if pool.has_fixed_denominations() {
+        require!(pool.is_denomination(amount), CloakCraftError::InvalidDenomination);
+    }
+
+    transfer_to_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.depositor_token_account,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.depositor,
+        amount,
+    )?;
+
+    let leaf_index = commitment_counter.allocate();
+
+    let (encrypted_note_arr, encrypted_note_len) = vec_to_fixed_note(&encrypted_note);
+    create_commitment_account(
+        &ctx.accounts.payer.to_account_info(),
+        ctx.remaining_accounts,
+        light_params.validity_proof,
+        light_params.address_tree_info,
+        light_params.output_tree_index,
+        pool.key(),
+        pool.active_trees(clock.slot),
+        commitment,
+        leaf_index,
+        stealth_ephemeral_pubkey,
+        encrypted_note_arr,
+        encrypted_note_len,
+        view_tag.unwrap_or_default(),
+    )?;
+
+    TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &pool.active_trees(clock.slot).state_tree, 1)?;
+    RootRegistry::append_to(&ctx.accounts.root_registry, commitment, clock.slot)?;
+
+    update_pool_balance(pool, amount, true)?;
+    pool.record_shield(clock.unix_timestamp);
+
+    if let Some(stats) = ctx.accounts.pool_stats.as_mut() {
+        if let Some(rolled) = stats.record_deposit(amount, clock.unix_timestamp) {
+            emit!(rolled);
+        }
+    }
+
+    msg!("Shielded {} tokens for depositor {}", amount, ctx.accounts.depositor.key());
+    Ok(())
+}
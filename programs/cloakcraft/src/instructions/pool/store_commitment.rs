@@ -9,7 +9,7 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, LightValidityProof, LightAddressTreeInfo};
+use crate::state::{Pool, PoolCommitmentCounter, LightValidityProof, LightAddressTreeInfo, RootRegistry, TreeRegistry};
 use crate::constants::seeds;
 use crate::light_cpi::{create_commitment_account, vec_to_fixed_note};
 
@@ -20,12 +20,39 @@ pub struct StoreCommitment<'info> {
         seeds = [seeds::POOL, pool.token_mint.as_ref()],
         bump = pool.bump,
     )]
-    pub pool: Account<'info, Pool>,
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Commitment counter for this pool (assigns the commitment index)
+    #[account(
+        mut,
+        seeds = [PoolCommitmentCounter::SEEDS_PREFIX, pool.key().as_ref()],
+        bump = commitment_counter.bump,
+    )]
+    pub commitment_counter: Box<Account<'info, PoolCommitmentCounter>>,
 
     /// Relayer/submitter (pays for compressed account creation)
     #[account(mut)]
     pub relayer: Signer<'info>,
 
+    /// Root registry (the commitment is appended once initialized)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::ROOT_REGISTRY, pool.key().as_ref()],
+        bump,
+    )]
+    pub root_registry: UncheckedAccount<'info>,
+
+    /// Tree registry (once initialized, the output tree must be registered
+    /// and its writes are counted toward TreeNearCapacity)
+    /// CHECK: Address checked by seeds; empty until the registry is initialized
+    #[account(
+        mut,
+        seeds = [seeds::TREE_REGISTRY],
+        bump,
+    )]
+    pub tree_registry: UncheckedAccount<'info>,
+
     // Light Protocol accounts are passed via remaining_accounts
 }
 
@@ -34,13 +61,13 @@ pub struct StoreCommitment<'info> {
 pub struct StoreCommitmentParams {
     /// The commitment hash
     pub commitment: [u8; 32],
-    /// Leaf index (read from commitment counter before transact)
-    pub leaf_index: u64,
     /// Stealth ephemeral pubkey for deriving decryption key (64 bytes: X + Y)
     /// If all zeros, decrypt with original spending key (internal operations)
     pub stealth_ephemeral_pubkey: [u8; 64],
     /// Encrypted note data (Vec to avoid heap issues during deserialization)
     pub encrypted_note: Vec<u8>,
+    /// Optional view tag for note discovery (stored as zeros if absent)
+    pub view_tag: Option<[u8; 8]>,
     /// Validity proof for the commitment address (non-inclusion)
     pub validity_proof: LightValidityProof,
     /// Address tree info
@@ -54,6 +81,7 @@ pub fn store_commitment<'info>(
     params: StoreCommitmentParams,
 ) -> Result<()> {
     let pool = &ctx.accounts.pool;
+    let leaf_index = ctx.accounts.commitment_counter.allocate();
 
     // Convert Vec to fixed array (avoids heap issues during deserialization)
     let (encrypted_note_arr, encrypted_note_len) = vec_to_fixed_note(&params.encrypted_note);
@@ -67,14 +95,18 @@ pub fn store_commitment<'info>(
         params.address_tree_info,
         params.output_tree_index,
         pool.key(),
+        pool.active_trees(Clock::get()?.slot),
         params.commitment,
-        params.leaf_index,
+        leaf_index,
         params.stealth_ephemeral_pubkey,
         encrypted_note_arr,
         encrypted_note_len,
+        params.view_tag.unwrap_or_default(),
     )?;
+    TreeRegistry::record_writes_to(&ctx.accounts.tree_registry, &pool.active_trees(Clock::get()?.slot).state_tree, 1)?;
+    RootRegistry::append_to(&ctx.accounts.root_registry, params.commitment, Clock::get()?.slot)?;
 
-    msg!("Commitment stored: leaf_index={}", params.leaf_index);
+    msg!("Commitment stored: leaf_index={}", leaf_index);
 
     Ok(())
 }
@@ -12,7 +12,7 @@ use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
 use crate::state::{
     Pool, VerificationKey, PoolCommitmentCounter,
-    LightValidityProof, LightAddressTreeInfo, PendingOperation,
+    LightValidityProof, LightAddressTreeInfo, PendingOperation, ProtocolConfig,
     PENDING_OPERATION_EXPIRY_SECONDS,
 };
 use crate::constants::seeds;
@@ -27,10 +27,13 @@ use crate::helpers::vault::{transfer_from_vault, update_pool_balance};
 #[instruction(operation_id: [u8; 32])]
 pub struct Transact<'info> {
     /// Pool (boxed to reduce stack usage)
+    /// Denomination and NFT pools only accept their own transfer circuits
     #[account(
         mut,
         seeds = [seeds::POOL, pool.token_mint.as_ref()],
         bump = pool.bump,
+        constraint = !pool.has_fixed_denominations() @ CloakCraftError::InvalidDenomination,
+        constraint = !pool.is_nft_pool() @ CloakCraftError::InvalidNftPool,
     )]
     pub pool: Box<Account<'info, Pool>>,
 
@@ -50,12 +53,13 @@ pub struct Transact<'info> {
         seeds = [seeds::VAULT, pool.token_mint.as_ref()],
         bump = pool.vault_bump,
     )]
-    pub token_vault: Account<'info, TokenAccount>,
+    pub token_vault: Box<Account<'info, TokenAccount>>,
 
     /// Verification key for the circuit (boxed to reduce stack usage)
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
@@ -71,6 +75,13 @@ pub struct Transact<'info> {
     /// Token program
     pub token_program: Program<'info, Token>,
 
+    /// Protocol config (operation IDs must carry the current operation epoch)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
     /// System program
     pub system_program: Program<'info, System>,
 
@@ -138,6 +149,12 @@ pub fn transact<'info>(
     let pending_op = &mut ctx.accounts.pending_operation;
     let clock = Clock::get()?;
 
+    // Anonymity guard: hold back unshields while recent pool activity is too low
+    require!(
+        unshield_amount == 0 || !pool.anonymity_guard_active(clock.unix_timestamp),
+        CloakCraftError::AnonymityGuardActive
+    );
+
     // 1. Verify ZK proof (SECURITY CRITICAL)
     // The ZK circuit proves:
     // - Input commitment exists in merkle tree with given root
@@ -196,9 +213,10 @@ pub fn transact<'info>(
 
     // 3. Initialize pending operation PDA
     pending_op.bump = ctx.bumps.pending_operation;
-    pending_op.operation_id = operation_id;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
     pending_op.relayer = ctx.accounts.relayer.key();
     pending_op.operation_type = OP_TYPE_TRANSACT;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
     pending_op.created_at = clock.unix_timestamp;
     pending_op.expires_at = clock.unix_timestamp + PENDING_OPERATION_EXPIRY_SECONDS;
 
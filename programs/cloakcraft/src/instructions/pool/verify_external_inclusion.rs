@@ -0,0 +1,67 @@
+//! Verify a commitment against the root registry (read-only, CPI entrypoint)
+//!
+//! Lets other programs check that a CloakCraft commitment exists in a pool
+//! without a Light Protocol CPI: the caller supplies the registry leaf index,
+//! a retained root and the merkle path. Fails unless the path is valid and
+//! the root is within the pool's `max_root_age_slots` window (tightened by
+//! the caller's own `max_root_age_slots`, if given); returns the slot the
+//! root was produced in so callers can apply further freshness rules.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, RootRegistry};
+use crate::constants::{seeds, MERKLE_TREE_DEPTH};
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct VerifyExternalInclusion<'info> {
+    /// Pool the commitment belongs to
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Root registry
+    #[account(
+        seeds = [seeds::ROOT_REGISTRY, pool.key().as_ref()],
+        bump = root_registry.bump,
+    )]
+    pub root_registry: Box<Account<'info, RootRegistry>>,
+}
+
+pub fn verify_external_inclusion(
+    ctx: Context<VerifyExternalInclusion>,
+    commitment: [u8; 32],
+    leaf_index: u32,
+    root: [u8; 32],
+    path: [[u8; 32]; MERKLE_TREE_DEPTH],
+    max_root_age_slots: Option<u64>,
+) -> Result<u64> {
+    let slot = Clock::get()?.slot;
+    let entry = ctx.accounts.root_registry.verify_inclusion(
+        &commitment,
+        leaf_index,
+        &root,
+        &path,
+        slot,
+        ctx.accounts.pool.max_root_age_slots,
+    )?;
+
+    // The caller may only tighten the pool's window
+    if let Some(max_age) = max_root_age_slots {
+        require!(
+            slot.saturating_sub(entry.slot) <= max_age,
+            CloakCraftError::StaleRegistryRoot
+        );
+    }
+
+    msg!(
+        "Commitment {:02x?}... included at leaf {} (root slot {})",
+        &commitment[0..8],
+        leaf_index,
+        entry.slot
+    );
+
+    Ok(entry.slot)
+}
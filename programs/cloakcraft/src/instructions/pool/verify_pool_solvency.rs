@@ -0,0 +1,121 @@
+//! Verify pool solvency (permissionless)
+//!
+//! Checks that the vault holds at least the recorded shielded supply minus
+//! outflows still pending in Phase 3 (unshields and unprocessed fees of the
+//! PendingOperations passed in remaining accounts). The supply comes from
+//! `PoolStats` when passed, otherwise from `pool.total_shielded`.
+//!
+//! Never fails on a shortfall: it emits `PoolInsolvencyDetected` so monitors
+//! see it, and returns whether the pool is solvent.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::state::{Pool, PoolStats, PendingOperation};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::vault_shortfall;
+
+#[derive(Accounts)]
+pub struct VerifyPoolSolvency<'info> {
+    /// Pool to check
+    #[account(
+        seeds = [seeds::POOL, pool.token_mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    /// Token vault
+    #[account(
+        seeds = [seeds::VAULT, pool.token_mint.as_ref()],
+        bump = pool.vault_bump,
+    )]
+    pub token_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Pool statistics (optional, preferred source of the shielded supply)
+    #[account(
+        seeds = [seeds::POOL_STATS, pool.key().as_ref()],
+        bump = pool_stats.bump,
+    )]
+    pub pool_stats: Option<Box<Account<'info, PoolStats>>>,
+
+    // Pending operations with outflows from this pool via remaining_accounts
+}
+
+/// Emitted on every check
+#[event]
+pub struct PoolSolvencyChecked {
+    pub pool: Pubkey,
+    pub vault_balance: u64,
+    pub shielded_supply: u64,
+    pub pending_outflows: u64,
+    pub solvent: bool,
+}
+
+/// Emitted when the vault holds less than it should
+#[event]
+pub struct PoolInsolvencyDetected {
+    pub pool: Pubkey,
+    pub vault_balance: u64,
+    pub shielded_supply: u64,
+    pub pending_outflows: u64,
+    /// Required balance minus vault balance
+    pub shortfall: u64,
+}
+
+pub fn verify_pool_solvency(ctx: Context<VerifyPoolSolvency>) -> Result<bool> {
+    let pool_key = ctx.accounts.pool.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    // Sum outflows of live pending operations spending from this pool
+    let mut pending_outflows: u64 = 0;
+    for (i, info) in ctx.remaining_accounts.iter().enumerate() {
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let pending_op = PendingOperation::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(
+            pending_op.input_pools[0] == pool_key.to_bytes(),
+            CloakCraftError::PoolMismatch
+        );
+        // Duplicates would inflate the allowance
+        require!(
+            ctx.remaining_accounts[..i].iter().all(|other| other.key() != info.key()),
+            CloakCraftError::DuplicatePendingOperation
+        );
+        if !pending_op.is_expired(now) {
+            pending_outflows = pending_outflows.saturating_add(pending_op.pending_outflow());
+        }
+    }
+
+    let vault_balance = ctx.accounts.token_vault.amount;
+    let shielded_supply = match ctx.accounts.pool_stats.as_ref() {
+        Some(stats) => stats.total_shielded,
+        None => ctx.accounts.pool.total_shielded,
+    };
+    let shortfall = vault_shortfall(vault_balance, shielded_supply, pending_outflows);
+    let solvent = shortfall == 0;
+
+    msg!(
+        "Solvency: vault {} >= supply {} - pending {}: {}",
+        vault_balance, shielded_supply, pending_outflows, solvent
+    );
+
+    emit!(PoolSolvencyChecked {
+        pool: pool_key,
+        vault_balance,
+        shielded_supply,
+        pending_outflows,
+        solvent,
+    });
+
+    if !solvent {
+        emit!(PoolInsolvencyDetected {
+            pool: pool_key,
+            vault_balance,
+            shielded_supply,
+            pending_outflows,
+            shortfall,
+        });
+    }
+
+    Ok(solvent)
+}
@@ -19,6 +19,7 @@ use anchor_lang::prelude::*;
 
 use crate::state::{Pool, VerificationKey};
 use crate::constants::seeds;
+use crate::errors::CloakCraftError;
 use crate::helpers::verify_groth16_proof;
 use crate::helpers::field::{pubkey_to_field, u64_to_field};
 
@@ -35,6 +36,7 @@ pub struct VerifyProofForTransact<'info> {
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 }
@@ -0,0 +1,209 @@
+//! Create Pending Operation with Proof - Phase 0 (Savings Vault Withdraw)
+//!
+//! Spends a vault share note and commits the LP it redeems for at the
+//! current share price, using the same conversion circuit as deposits.
+//! Withdrawals are accepted while deposits are paused.
+//!
+//! Flow:
+//! Phase 0 (this): Verify ZK proof + Create PendingOperation
+//! Phase 1: Verify commitment exists (share note)
+//! Phase 2: Create nullifier (spend share note)
+//! Phase 3: execute_vault_withdraw - Record the redemption in the vault
+//! Phase 4: Create commitment (LP note)
+//! Final: Close pending operation
+
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    Pool, SavingsVault, VerificationKey, PendingOperation, ProtocolConfig, ProgramVersion, CircuitStats,
+    RelayerAllowlist, OperationCredit,
+};
+use crate::constants::{seeds, operation_types};
+use crate::errors::CloakCraftError;
+use crate::helpers::verify_groth16_proof_metered;
+use crate::helpers::field::{pubkey_to_field, u64_to_field, assert_canonical};
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct CreatePendingWithProofVaultWithdraw<'info> {
+    /// Savings vault
+    #[account(
+        seeds = [seeds::SAVINGS_VAULT, savings_vault.source_pool.as_ref()],
+        bump = savings_vault.bump,
+    )]
+    pub savings_vault: Box<Account<'info, SavingsVault>>,
+
+    /// Vault share pool (share note input)
+    #[account(
+        seeds = [seeds::POOL, share_pool.token_mint.as_ref()],
+        bump = share_pool.bump,
+        constraint = share_pool.token_mint == savings_vault.share_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub share_pool: Box<Account<'info, Pool>>,
+
+    /// LP token pool (LP note output)
+    #[account(
+        seeds = [seeds::POOL, lp_pool.token_mint.as_ref()],
+        bump = lp_pool.bump,
+        constraint = lp_pool.token_mint == savings_vault.lp_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub lp_pool: Box<Account<'info, Pool>>,
+
+    /// Verification key for the LP conversion circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, crate::constants::circuits::SWAP_CONVERT_LP.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Pending operation PDA (created in this instruction)
+    #[account(
+        init,
+        payer = relayer,
+        space = PendingOperation::SPACE,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (pays for PDA creation)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Share pool's relayer allowlist (required while it is permissioned)
+    #[account(
+        seeds = [seeds::RELAYER_ALLOWLIST, share_pool.key().as_ref()],
+        bump = relayer_allowlist.bump,
+    )]
+    pub relayer_allowlist: Option<Box<Account<'info, RelayerAllowlist>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Phase 0: Verify ZK proof and create PendingOperation for a vault withdrawal
+#[allow(clippy::too_many_arguments)]
+pub fn create_pending_with_proof_vault_withdraw<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofVaultWithdraw<'info>>,
+    operation_id: [u8; 32],
+    proof: Vec<u8>,
+    merkle_root: [u8; 32],
+    share_commitment: [u8; 32],
+    share_nullifier: [u8; 32],
+    lp_commitment: [u8; 32],
+    share_amount: u64,
+    lp_amount: u64,
+    client_version: u32,
+) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Permissioned pools only accept allowlisted relayers
+    ctx.accounts.share_pool.check_relayer(
+        ctx.accounts.relayer_allowlist.as_deref().map(|list| &**list),
+        ctx.accounts.relayer.key,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[share_commitment, share_nullifier, lp_commitment])?;
+
+    let vault = &ctx.accounts.savings_vault;
+    let pending_op = &mut ctx.accounts.pending_operation;
+    let clock = Clock::get()?;
+
+    msg!("=== Phase 0: Verify Proof + Create Pending (Vault Withdraw) ===");
+
+    // LP at most what the current price gives (re-checked in Phase 3)
+    require!(share_amount > 0 && lp_amount > 0, CloakCraftError::InvalidAmount);
+    let quoted = vault.lp_for_withdraw(share_amount).ok_or(CloakCraftError::VaultConversionMismatch)?;
+    require!(lp_amount <= quoted, CloakCraftError::VaultConversionMismatch);
+
+    // 1. Verify ZK proof (7 public inputs matching Circom circuit)
+    let public_inputs = vec![
+        merkle_root,
+        share_nullifier,
+        pubkey_to_field(&vault.share_mint),
+        pubkey_to_field(&vault.lp_mint),
+        lp_commitment,
+        u64_to_field(share_amount),
+        u64_to_field(lp_amount),
+    ];
+
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "VaultWithdraw",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
+    msg!("✅ ZK proof verified");
+
+    // 2. Initialize pending operation PDA with binding fields
+    pending_op.bump = ctx.bumps.pending_operation;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
+    pending_op.relayer = ctx.accounts.relayer.key();
+    pending_op.operation_type = operation_types::VAULT_WITHDRAW;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
+    pending_op.created_at = clock.unix_timestamp;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
+
+    // SECURITY: Store binding fields from ZK proof
+    pending_op.num_inputs = 1;
+    pending_op.input_commitments[0] = share_commitment;
+    pending_op.expected_nullifiers[0] = share_nullifier;
+    pending_op.input_pools[0] = ctx.accounts.share_pool.key().to_bytes();
+    pending_op.inputs_verified_mask = 0;
+    pending_op.proof_verified = true;
+
+    // Redeemed LP note
+    pending_op.num_commitments = 1;
+    pending_op.pools[0] = ctx.accounts.lp_pool.key().to_bytes();
+    pending_op.commitments[0] = lp_commitment;
+    pending_op.output_amounts[0] = lp_amount;
+
+    // Redemption amounts for Phase 3
+    pending_op.swap_amount = share_amount;
+    pending_op.output_amount = lp_amount;
+
+    pending_op.nullifier_completed_mask = 0;
+    pending_op.completed_mask = 0;
+
+    msg!("Redeeming {} shares for {} LP", share_amount, lp_amount);
+    msg!("Phase 0 complete: ZK proof verified, pending operation created");
+    msg!("Next: Phase 1 - verify_commitment_exists");
+
+    Ok(())
+}
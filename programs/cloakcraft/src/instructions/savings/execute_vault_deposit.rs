@@ -0,0 +1,121 @@
+//! Execute Savings Vault Deposit (Phase 3)
+//!
+//! Records the spent LP in the vault and mints the shares committed in
+//! Phase 4. The share price may have moved since Phase 0 (a harvest in
+//! between), so the shares are re-checked against the current price.
+//! Called after the LP note nullifier is created (Phase 2).
+
+use anchor_lang::prelude::*;
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{EmissionsSchedule, PendingOperation, OperationKind, Pool, SavingsVault};
+
+/// Emitted when LP is deposited into a savings vault
+#[event]
+pub struct SavingsVaultDeposited {
+    pub vault: Pubkey,
+    pub lp_amount: u64,
+    pub shares: u64,
+    pub share_price: u128,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct ExecuteVaultDeposit<'info> {
+    /// Savings vault
+    #[account(
+        mut,
+        seeds = [seeds::SAVINGS_VAULT, savings_vault.source_pool.as_ref()],
+        bump = savings_vault.bump,
+    )]
+    pub savings_vault: Box<Account<'info, SavingsVault>>,
+
+    /// Vault share pool (binds the pending operation to the vault)
+    #[account(
+        seeds = [seeds::POOL, share_pool.token_mint.as_ref()],
+        bump = share_pool.bump,
+        constraint = share_pool.token_mint == savings_vault.share_mint @ CloakCraftError::InvalidTokenMint,
+        constraint = pending_operation.pools[0] == share_pool.key().to_bytes() @ CloakCraftError::PoolMismatch,
+    )]
+    pub share_pool: Box<Account<'info, Pool>>,
+
+    /// Attached emissions schedule (required once the vault has one)
+    #[account(
+        constraint = emissions_schedule.key() == savings_vault.emissions_schedule @ CloakCraftError::VaultEmissionsMismatch,
+    )]
+    pub emissions_schedule: Option<Box<Account<'info, EmissionsSchedule>>>,
+
+    /// Pending operation PDA (from Phase 0)
+    #[account(
+        mut,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump = pending_operation.bump,
+        constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (must match pending operation)
+    #[account(
+        constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
+    )]
+    pub relayer: Signer<'info>,
+}
+
+/// Phase 3: Record the deposit in the vault
+pub fn execute_vault_deposit(
+    ctx: Context<ExecuteVaultDeposit>,
+    _operation_id: [u8; 32],
+) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::VaultDeposit)?;
+
+    let vault = &mut ctx.accounts.savings_vault;
+    let pending_op = &mut ctx.accounts.pending_operation;
+
+    msg!("=== Phase 3: Execute Vault Deposit ===");
+
+    // swap_amount is cleared after execution so the deposit cannot repeat
+    let lp_amount = pending_op.swap_amount;
+    let shares = pending_op.output_amount;
+    require!(lp_amount > 0, CloakCraftError::InvalidAmount);
+
+    let quoted = vault.shares_for_deposit(lp_amount).ok_or(CloakCraftError::AmountOverflow)?;
+    require!(shares <= quoted, CloakCraftError::VaultConversionMismatch);
+
+    settle_vault_rewards(vault, ctx.accounts.emissions_schedule.as_deref())?;
+    vault.deposit(lp_amount, shares).ok_or(CloakCraftError::AmountOverflow)?;
+    let share_price = vault
+        .record_share_price(Clock::get()?.unix_timestamp)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    pending_op.swap_amount = 0;
+
+    emit!(SavingsVaultDeposited {
+        vault: vault.key(),
+        lp_amount,
+        shares,
+        share_price,
+    });
+
+    msg!("✅ Deposited {} LP for {} shares", lp_amount, shares);
+    msg!("Vault: {} LP, {} shares", vault.total_lp, vault.total_shares);
+    msg!("Phase 3 complete");
+    msg!("Next: Phase 4 - create_commitment for the share note");
+
+    Ok(())
+}
+
+/// Settle the vault's emissions rewards before its LP changes
+///
+/// The schedule is required once the vault has one attached.
+pub(crate) fn settle_vault_rewards(
+    vault: &mut SavingsVault,
+    emissions_schedule: Option<&Account<EmissionsSchedule>>,
+) -> Result<()> {
+    if !vault.has_emissions() {
+        return Ok(());
+    }
+    let schedule = emissions_schedule.ok_or(CloakCraftError::VaultEmissionsMismatch)?;
+    vault.settle_rewards(schedule).ok_or(CloakCraftError::AmountOverflow)?;
+    Ok(())
+}
@@ -0,0 +1,107 @@
+//! Execute Savings Vault Withdraw (Phase 3)
+//!
+//! Burns the redeemed shares and releases the LP committed in Phase 4. The
+//! LP is re-checked against the current share price.
+//! Called after the share note nullifier is created (Phase 2).
+
+use anchor_lang::prelude::*;
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{EmissionsSchedule, PendingOperation, OperationKind, Pool, SavingsVault};
+
+use super::settle_vault_rewards;
+
+/// Emitted when vault shares are redeemed for LP
+#[event]
+pub struct SavingsVaultWithdrawn {
+    pub vault: Pubkey,
+    pub shares: u64,
+    pub lp_amount: u64,
+    pub share_price: u128,
+}
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct ExecuteVaultWithdraw<'info> {
+    /// Savings vault
+    #[account(
+        mut,
+        seeds = [seeds::SAVINGS_VAULT, savings_vault.source_pool.as_ref()],
+        bump = savings_vault.bump,
+    )]
+    pub savings_vault: Box<Account<'info, SavingsVault>>,
+
+    /// Vault share pool (binds the pending operation to the vault)
+    #[account(
+        seeds = [seeds::POOL, share_pool.token_mint.as_ref()],
+        bump = share_pool.bump,
+        constraint = share_pool.token_mint == savings_vault.share_mint @ CloakCraftError::InvalidTokenMint,
+        constraint = pending_operation.input_pools[0] == share_pool.key().to_bytes() @ CloakCraftError::PoolMismatch,
+    )]
+    pub share_pool: Box<Account<'info, Pool>>,
+
+    /// Attached emissions schedule (required once the vault has one)
+    #[account(
+        constraint = emissions_schedule.key() == savings_vault.emissions_schedule @ CloakCraftError::VaultEmissionsMismatch,
+    )]
+    pub emissions_schedule: Option<Box<Account<'info, EmissionsSchedule>>>,
+
+    /// Pending operation PDA (from Phase 0)
+    #[account(
+        mut,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump = pending_operation.bump,
+        constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (must match pending operation)
+    #[account(
+        constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
+    )]
+    pub relayer: Signer<'info>,
+}
+
+/// Phase 3: Record the redemption in the vault
+pub fn execute_vault_withdraw(
+    ctx: Context<ExecuteVaultWithdraw>,
+    _operation_id: [u8; 32],
+) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::VaultWithdraw)?;
+
+    let vault = &mut ctx.accounts.savings_vault;
+    let pending_op = &mut ctx.accounts.pending_operation;
+
+    msg!("=== Phase 3: Execute Vault Withdraw ===");
+
+    // swap_amount is cleared after execution so the redemption cannot repeat
+    let shares = pending_op.swap_amount;
+    let lp_amount = pending_op.output_amount;
+    require!(shares > 0, CloakCraftError::InvalidAmount);
+
+    let quoted = vault.lp_for_withdraw(shares).ok_or(CloakCraftError::VaultConversionMismatch)?;
+    require!(lp_amount <= quoted, CloakCraftError::VaultConversionMismatch);
+
+    settle_vault_rewards(vault, ctx.accounts.emissions_schedule.as_deref())?;
+    vault.withdraw(shares, lp_amount).ok_or(CloakCraftError::VaultConversionMismatch)?;
+    let share_price = vault
+        .record_share_price(Clock::get()?.unix_timestamp)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    pending_op.swap_amount = 0;
+
+    emit!(SavingsVaultWithdrawn {
+        vault: vault.key(),
+        shares,
+        lp_amount,
+        share_price,
+    });
+
+    msg!("✅ Redeemed {} shares for {} LP", shares, lp_amount);
+    msg!("Vault: {} LP, {} shares", vault.total_lp, vault.total_shares);
+    msg!("Phase 3 complete");
+    msg!("Next: Phase 4 - create_commitment for the LP note");
+
+    Ok(())
+}
@@ -0,0 +1,190 @@
+//! Harvest a savings vault (keeper)
+//!
+//! Compounds the emissions rewards owed to the vault's LP: the rewards move
+//! from the schedule's reward vault into the reward token pool vault and are
+//! swapped for LP through an AMM pool pairing the reward token with the
+//! vault's LP token. The LP bought is added to the vault, raising the share
+//! price for every share holder.
+//!
+//! Only the vault authority may harvest. Each harvest must meet `min_lp_out`
+//! and stay within the vault's price impact bound, but that bound is relative
+//! to the pool's spot price, which a permissionless caller could move first;
+//! the authority prices `min_lp_out` off an external reference instead.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::state::{AmmPool, EmissionsSchedule, Pool, SavingsVault};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::instructions::emissions::load_source_pool;
+use crate::instructions::swap::quote_keeper_swap;
+
+#[derive(Accounts)]
+pub struct HarvestSavingsVault<'info> {
+    /// Savings vault
+    #[account(
+        mut,
+        seeds = [seeds::SAVINGS_VAULT, savings_vault.source_pool.as_ref()],
+        bump = savings_vault.bump,
+    )]
+    pub savings_vault: Box<Account<'info, SavingsVault>>,
+
+    /// Vault's emissions schedule (accumulator advanced here)
+    #[account(
+        mut,
+        seeds = [seeds::EMISSIONS, savings_vault.source_pool.as_ref()],
+        bump = emissions_schedule.bump,
+        constraint = emissions_schedule.key() == savings_vault.emissions_schedule @ CloakCraftError::VaultEmissionsMismatch,
+    )]
+    pub emissions_schedule: Box<Account<'info, EmissionsSchedule>>,
+
+    /// Vault's source pool (read for LP supply)
+    /// CHECK: Validated against the vault and deserialized via `load_source_pool`
+    #[account(
+        constraint = source_pool.key() == savings_vault.source_pool @ CloakCraftError::EmissionsPoolMismatch,
+    )]
+    pub source_pool: UncheckedAccount<'info>,
+
+    /// Schedule reward vault (source of the rewards)
+    #[account(
+        mut,
+        seeds = [seeds::EMISSIONS_VAULT, emissions_schedule.key().as_ref()],
+        bump = emissions_schedule.vault_bump,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Rewards token pool (receives the swapped rewards)
+    #[account(
+        mut,
+        seeds = [seeds::POOL, reward_pool.token_mint.as_ref()],
+        bump = reward_pool.bump,
+        constraint = reward_pool.token_mint == emissions_schedule.reward_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub reward_pool: Box<Account<'info, Pool>>,
+
+    /// Rewards token pool vault
+    #[account(
+        mut,
+        seeds = [seeds::VAULT, reward_pool.token_mint.as_ref()],
+        bump = reward_pool.vault_bump,
+    )]
+    pub reward_pool_vault: Box<Account<'info, TokenAccount>>,
+
+    /// AMM pool pairing the reward token with the vault LP token
+    #[account(
+        mut,
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
+        bump = amm_pool.bump,
+        constraint = amm_pool.is_active @ CloakCraftError::AmmPoolNotActive,
+    )]
+    pub amm_pool: Box<Account<'info, AmmPool>>,
+
+    /// Keeper (the vault authority)
+    #[account(
+        constraint = keeper.key() == savings_vault.authority @ CloakCraftError::Unauthorized,
+    )]
+    pub keeper: Signer<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Emitted when a savings vault compounds its rewards
+#[event]
+pub struct SavingsVaultHarvested {
+    pub vault: Pubkey,
+    pub amm_pool: Pubkey,
+    pub rewards: u64,
+    pub lp_bought: u64,
+    pub price_impact_bps: u16,
+    pub share_price: u128,
+    pub keeper: Pubkey,
+}
+
+/// Compound the vault's emissions rewards into LP
+///
+/// # Arguments
+/// * `min_lp_out` - Slippage bound on the LP bought
+pub fn harvest_savings_vault(ctx: Context<HarvestSavingsVault>, min_lp_out: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    let vault = &mut ctx.accounts.savings_vault;
+    let schedule = &mut ctx.accounts.emissions_schedule;
+
+    // Advance the accumulator, then settle what the vault's LP earned
+    let source = load_source_pool(&ctx.accounts.source_pool, schedule.pool_kind)?;
+    schedule.accrue(clock.unix_timestamp, source.lp_supply)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    vault.settle_rewards(schedule).ok_or(CloakCraftError::AmountOverflow)?;
+
+    let rewards = vault.pending_rewards;
+    require!(rewards > 0, CloakCraftError::NothingToHarvest);
+    require!(
+        ctx.accounts.reward_vault.amount >= rewards,
+        CloakCraftError::InsufficientBalance
+    );
+
+    // Single hop: the AMM pool must pair exactly the reward token and the LP token
+    let swap_a_to_b = ctx.accounts.amm_pool
+        .swap_direction(&schedule.reward_mint, &vault.lp_mint)
+        .ok_or(CloakCraftError::InvalidHarvestRoute)?;
+    let (lp_bought, price_impact_bps) = quote_keeper_swap(
+        &ctx.accounts.amm_pool,
+        swap_a_to_b,
+        rewards,
+        min_lp_out,
+        vault.max_price_impact_bps,
+    )?;
+
+    // Rewards in: claimed from the schedule like a note holder's claim
+    let source_pool = schedule.source_pool;
+    let signer_seeds: &[&[&[u8]]] = &[&[seeds::EMISSIONS, source_pool.as_ref(), &[schedule.bump]]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                to: ctx.accounts.reward_pool_vault.to_account_info(),
+                authority: schedule.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        rewards,
+    )?;
+    schedule.total_claimed = schedule.total_claimed
+        .checked_add(rewards)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    // The rewards join the AMM reserve, which counts as shielded supply;
+    // the LP leaves the reserve for the vault, which holds it on-protocol
+    ctx.accounts.amm_pool
+        .apply_direct_swap(rewards, lp_bought, swap_a_to_b)
+        .ok_or(CloakCraftError::InsufficientLiquidity)?;
+    let reward_pool = &mut ctx.accounts.reward_pool;
+    reward_pool.total_shielded = reward_pool.total_shielded
+        .checked_add(rewards)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    vault.compound(lp_bought).ok_or(CloakCraftError::AmountOverflow)?;
+    let share_price = vault
+        .record_share_price(clock.unix_timestamp)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    emit!(SavingsVaultHarvested {
+        vault: vault.key(),
+        amm_pool: ctx.accounts.amm_pool.key(),
+        rewards,
+        lp_bought,
+        price_impact_bps,
+        share_price,
+        keeper: ctx.accounts.keeper.key(),
+    });
+
+    msg!(
+        "Savings vault harvested: {} rewards -> {} LP (price impact {} bps)",
+        rewards, lp_bought, price_impact_bps
+    );
+    msg!("Vault: {} LP, {} shares", vault.total_lp, vault.total_shares);
+
+    Ok(())
+}
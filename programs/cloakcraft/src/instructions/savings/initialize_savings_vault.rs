@@ -0,0 +1,100 @@
+//! Initialize a savings vault over an AMM or perps pool's LP
+//!
+//! The source pool authority creates the vault and its share mint. Share
+//! notes live in the share mint's shielded pool, which is initialized like
+//! any other pool. Deposits are accepted from the start.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+use crate::state::{EmissionsPoolKind, SavingsVault};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::fixed::RATE_SCALE;
+use crate::instructions::emissions::load_source_pool;
+
+#[derive(Accounts)]
+pub struct InitializeSavingsVault<'info> {
+    /// Savings vault (one per source pool)
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SavingsVault::INIT_SPACE,
+        seeds = [seeds::SAVINGS_VAULT, source_pool.key().as_ref()],
+        bump,
+    )]
+    pub savings_vault: Box<Account<'info, SavingsVault>>,
+
+    /// Vault share mint (accounting only, the vault is its authority)
+    #[account(
+        init,
+        payer = authority,
+        seeds = [seeds::VAULT_SHARE_MINT, savings_vault.key().as_ref()],
+        bump,
+        mint::decimals = lp_mint.decimals,
+        mint::authority = savings_vault,
+    )]
+    pub share_mint: Box<Account<'info, Mint>>,
+
+    /// AMM or perps pool whose LP the vault holds
+    /// CHECK: Owner and type validated in handler via `load_source_pool`
+    pub source_pool: UncheckedAccount<'info>,
+
+    /// Source pool LP mint
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    /// Source pool authority (pays for the accounts)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Initialize a savings vault
+///
+/// # Arguments
+/// * `pool_kind` - Kind of the source pool
+/// * `max_price_impact_bps` - Highest price impact of a harvest swap
+pub fn initialize_savings_vault(
+    ctx: Context<InitializeSavingsVault>,
+    pool_kind: EmissionsPoolKind,
+    max_price_impact_bps: u16,
+) -> Result<()> {
+    let source = load_source_pool(&ctx.accounts.source_pool, pool_kind)?;
+
+    require!(
+        ctx.accounts.authority.key() == source.authority,
+        CloakCraftError::Unauthorized
+    );
+    require!(
+        ctx.accounts.lp_mint.key() == source.lp_mint,
+        CloakCraftError::InvalidTokenMint
+    );
+    require!(
+        SavingsVault::validate_policy(max_price_impact_bps),
+        CloakCraftError::InvalidVaultPolicy
+    );
+
+    let vault = &mut ctx.accounts.savings_vault;
+    vault.source_pool = ctx.accounts.source_pool.key();
+    vault.pool_kind = pool_kind;
+    vault.lp_mint = source.lp_mint;
+    vault.share_mint = ctx.accounts.share_mint.key();
+    vault.emissions_schedule = Pubkey::default();
+    vault.authority = ctx.accounts.authority.key();
+    vault.share_price = RATE_SCALE;
+    vault.share_price_updated_at = Clock::get()?.unix_timestamp;
+    vault.max_price_impact_bps = max_price_impact_bps;
+    vault.is_active = true;
+    vault.bump = ctx.bumps.savings_vault;
+    vault.share_mint_bump = ctx.bumps.share_mint;
+
+    msg!("Savings vault created for pool {}", vault.source_pool);
+    msg!("  LP mint: {}, share mint: {}", vault.lp_mint, vault.share_mint);
+
+    Ok(())
+}
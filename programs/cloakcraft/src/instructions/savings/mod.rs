@@ -0,0 +1,24 @@
+//! Savings vaults: auto-compounding LP shares
+//!
+//! - Initialize / set policy: Source pool authority creates and configures a vault
+//! - Deposit (multi-phase): Convert an LP note into a vault share note
+//! - Withdraw (multi-phase): Convert a vault share note back into an LP note
+//! - Harvest (keeper): Compound the vault's emissions rewards into LP
+
+mod initialize_savings_vault;
+mod set_savings_vault_policy;
+mod harvest_savings_vault;
+
+// Deposit / withdraw (multi-phase)
+mod create_pending_with_proof_vault_deposit;
+mod execute_vault_deposit;
+mod create_pending_with_proof_vault_withdraw;
+mod execute_vault_withdraw;
+
+pub use initialize_savings_vault::*;
+pub use set_savings_vault_policy::*;
+pub use harvest_savings_vault::*;
+pub use create_pending_with_proof_vault_deposit::*;
+pub use execute_vault_deposit::*;
+pub use create_pending_with_proof_vault_withdraw::*;
+pub use execute_vault_withdraw::*;
@@ -0,0 +1,64 @@
+//! Update a savings vault's policy
+//!
+//! Pausing stops deposits; withdrawals and harvests continue. Passing the
+//! source pool's emissions schedule attaches it (once): the vault earns
+//! rewards from the schedule's current accumulator onwards.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{EmissionsSchedule, SavingsVault};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct SetSavingsVaultPolicy<'info> {
+    /// Savings vault
+    #[account(
+        mut,
+        seeds = [seeds::SAVINGS_VAULT, savings_vault.source_pool.as_ref()],
+        bump = savings_vault.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub savings_vault: Box<Account<'info, SavingsVault>>,
+
+    /// Source pool emissions schedule to attach (optional)
+    #[account(
+        seeds = [seeds::EMISSIONS, savings_vault.source_pool.as_ref()],
+        bump = emissions_schedule.bump,
+    )]
+    pub emissions_schedule: Option<Box<Account<'info, EmissionsSchedule>>>,
+
+    /// Vault authority
+    pub authority: Signer<'info>,
+}
+
+/// Update the savings vault policy
+///
+/// # Arguments
+/// * `is_active` - Accept deposits
+/// * `max_price_impact_bps` - Highest price impact of a harvest swap
+pub fn set_savings_vault_policy(
+    ctx: Context<SetSavingsVaultPolicy>,
+    is_active: bool,
+    max_price_impact_bps: u16,
+) -> Result<()> {
+    require!(
+        SavingsVault::validate_policy(max_price_impact_bps),
+        CloakCraftError::InvalidVaultPolicy
+    );
+
+    let vault = &mut ctx.accounts.savings_vault;
+    vault.is_active = is_active;
+    vault.max_price_impact_bps = max_price_impact_bps;
+
+    if let Some(schedule) = ctx.accounts.emissions_schedule.as_deref() {
+        require!(!vault.has_emissions(), CloakCraftError::VaultEmissionsMismatch);
+        vault.emissions_schedule = schedule.key();
+        vault.reward_checkpoint = schedule.reward_per_lp;
+        msg!("Emissions schedule attached: {}", vault.emissions_schedule);
+    }
+
+    msg!("Savings vault policy updated: active {}, max price impact {} bps", is_active, max_price_impact_bps);
+
+    Ok(())
+}
@@ -13,7 +13,7 @@ use anchor_lang::prelude::*;
 
 use crate::state::{
     Pool, AmmPool, VerificationKey, PoolCommitmentCounter,
-    LightValidityProof, LightAddressTreeInfo, PendingOperation,
+    LightValidityProof, LightAddressTreeInfo, PendingOperation, ProtocolConfig,
     PENDING_OPERATION_EXPIRY_SECONDS,
 };
 use crate::constants::seeds;
@@ -85,8 +85,9 @@ pub struct AddLiquidity<'info> {
     /// AMM pool state
     #[account(
         mut,
-        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref()],
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
         bump = amm_pool.bump,
+        constraint = amm_pool.is_active @ CloakCraftError::AmmPoolNotActive,
     )]
     pub amm_pool: Box<Account<'info, AmmPool>>,
 
@@ -94,6 +95,7 @@ pub struct AddLiquidity<'info> {
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
@@ -111,6 +113,13 @@ pub struct AddLiquidity<'info> {
     #[account(mut)]
     pub relayer: Signer<'info>,
 
+    /// Protocol config (operation IDs must carry the current operation epoch)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
     /// System program
     pub system_program: Program<'info, System>,
 }
@@ -217,9 +226,10 @@ pub fn add_liquidity<'info>(
 
     // 6. Initialize pending operation PDA
     pending_op.bump = ctx.bumps.pending_operation;
-    pending_op.operation_id = operation_id;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
     pending_op.relayer = ctx.accounts.relayer.key();
     pending_op.operation_type = OP_TYPE_ADD_LIQUIDITY;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
     pending_op.created_at = clock.unix_timestamp;
     pending_op.expires_at = clock.unix_timestamp + PENDING_OPERATION_EXPIRY_SECONDS;
 
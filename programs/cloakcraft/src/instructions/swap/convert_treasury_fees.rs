@@ -0,0 +1,264 @@
+//! Convert treasury fees to the canonical treasury asset (keeper)
+//!
+//! Protocol fees accrue in the treasury's token account for each fee mint.
+//! This crank swaps a fee balance into `ProtocolConfig.treasury_conversion_mint`
+//! through an internal AMM pool pairing the two, so the treasury ends up
+//! holding one asset.
+//!
+//! The treasury opts in by approving the `treasury_converter` PDA as SPL
+//! delegate of its fee token accounts; the delegated amount caps what can be
+//! converted. Each conversion must meet `min_amount_out` and stay within the
+//! configured price impact bound.
+//!
+//! Only the protocol authority may convert. Price impact is measured against
+//! the pool's spot price, which a caller can move in the same transaction, so
+//! a permissionless crank could be sandwiched with a zero `min_amount_out`.
+//! The authority prices `min_amount_out` off an external reference instead.
+//!
+//! The swap is public: the input is deposited into the input pool vault and
+//! the output paid from the output pool vault, with reserves and shielded
+//! supply updated like a shielded swap. No protocol fee share is taken (it
+//! would go to the treasury anyway).
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::state::{Pool, AmmPool, ProtocolConfig};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct ConvertTreasuryFees<'info> {
+    /// AMM pool pairing the fee mint with the canonical asset
+    #[account(
+        mut,
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
+        bump = amm_pool.bump,
+        constraint = amm_pool.is_active @ CloakCraftError::AmmPoolNotActive,
+    )]
+    pub amm_pool: Box<Account<'info, AmmPool>>,
+
+    /// Fee mint pool (receives the converted fees)
+    #[account(
+        mut,
+        seeds = [seeds::POOL, input_pool.token_mint.as_ref()],
+        bump = input_pool.bump,
+        constraint = input_pool.token_mint == treasury_source.mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub input_pool: Box<Account<'info, Pool>>,
+
+    /// Canonical asset pool (authority for output_vault transfers)
+    #[account(
+        mut,
+        seeds = [seeds::POOL, output_pool.token_mint.as_ref()],
+        bump = output_pool.bump,
+        constraint = output_pool.token_mint == protocol_config.treasury_conversion_mint @ CloakCraftError::InvalidConversionRoute,
+    )]
+    pub output_pool: Box<Account<'info, Pool>>,
+
+    /// Fee mint vault
+    #[account(
+        mut,
+        constraint = input_vault.key() == input_pool.token_vault @ CloakCraftError::InvalidVault,
+    )]
+    pub input_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Canonical asset vault
+    #[account(
+        mut,
+        constraint = output_vault.key() == output_pool.token_vault @ CloakCraftError::InvalidVault,
+    )]
+    pub output_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Protocol config (treasury and conversion policy)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Treasury token account holding the fees to convert
+    #[account(
+        mut,
+        constraint = treasury_source.owner == protocol_config.treasury @ CloakCraftError::InvalidTreasury,
+    )]
+    pub treasury_source: Box<Account<'info, TokenAccount>>,
+
+    /// Treasury token account of the canonical asset
+    #[account(
+        mut,
+        constraint = treasury_destination.owner == protocol_config.treasury @ CloakCraftError::InvalidTreasury,
+        constraint = treasury_destination.mint == protocol_config.treasury_conversion_mint @ CloakCraftError::InvalidTreasury,
+    )]
+    pub treasury_destination: Box<Account<'info, TokenAccount>>,
+
+    /// Converter PDA, the treasury's SPL delegate on `treasury_source`
+    /// CHECK: PDA signer only; the token program enforces the delegation
+    #[account(
+        seeds = [seeds::TREASURY_CONVERTER],
+        bump,
+    )]
+    pub treasury_converter: UncheckedAccount<'info>,
+
+    /// Keeper (the protocol authority)
+    #[account(
+        constraint = keeper.key() == protocol_config.authority @ CloakCraftError::Unauthorized,
+    )]
+    pub keeper: Signer<'info>,
+
+    /// Token program for transfers
+    pub token_program: Program<'info, Token>,
+}
+
+/// Emitted when treasury fees are converted to the canonical asset
+#[event]
+pub struct TreasuryFeesConverted {
+    pub amm_pool: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub price_impact_bps: u16,
+    pub keeper: Pubkey,
+}
+
+/// Convert `amount_in` of treasury fees to the canonical asset
+///
+/// # Arguments
+/// * `amount_in` - Fee amount to convert (at most the delegated amount)
+/// * `min_amount_out` - Slippage bound on the canonical asset received
+pub fn convert_treasury_fees(
+    ctx: Context<ConvertTreasuryFees>,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    let protocol_config = &ctx.accounts.protocol_config;
+    let input_mint = ctx.accounts.input_pool.token_mint;
+    let output_mint = ctx.accounts.output_pool.token_mint;
+
+    require!(
+        protocol_config.can_convert_treasury_fees(&input_mint),
+        CloakCraftError::TreasuryConversionDisabled
+    );
+    require!(amount_in > 0, CloakCraftError::InvalidAmount);
+
+    // Single hop: the AMM pool must pair exactly the fee mint and the canonical asset
+    let swap_a_to_b = ctx.accounts.amm_pool
+        .swap_direction(&input_mint, &output_mint)
+        .ok_or(CloakCraftError::InvalidConversionRoute)?;
+    let (amount_out, price_impact_bps) = quote_keeper_swap(
+        &ctx.accounts.amm_pool,
+        swap_a_to_b,
+        amount_in,
+        min_amount_out,
+        protocol_config.max_conversion_price_impact_bps,
+    )?;
+
+    // Fees in: pulled from the treasury by its delegate
+    pull_treasury_fees(
+        &ctx.accounts.token_program,
+        &ctx.accounts.treasury_source,
+        &ctx.accounts.input_vault,
+        &ctx.accounts.treasury_converter,
+        ctx.bumps.treasury_converter,
+        amount_in,
+    )?;
+
+    // Canonical asset out: paid from the vault, signed by the pool PDA
+    let output_pool = &ctx.accounts.output_pool;
+    let pool_seeds: &[&[&[u8]]] = &[&[seeds::POOL, output_pool.token_mint.as_ref(), &[output_pool.bump]]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.output_vault.to_account_info(),
+                to: ctx.accounts.treasury_destination.to_account_info(),
+                authority: output_pool.to_account_info(),
+            },
+            pool_seeds,
+        ),
+        amount_out,
+    )?;
+
+    // The LP fee stays in the input reserve
+    ctx.accounts.amm_pool
+        .apply_direct_swap(amount_in, amount_out, swap_a_to_b)
+        .ok_or(CloakCraftError::InsufficientLiquidity)?;
+
+    // Reserves count as shielded supply, so the vault balances stay backed
+    let input_pool = &mut ctx.accounts.input_pool;
+    input_pool.total_shielded = input_pool.total_shielded
+        .checked_add(amount_in)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+    let output_pool = &mut ctx.accounts.output_pool;
+    output_pool.total_shielded = output_pool.total_shielded
+        .checked_sub(amount_out)
+        .ok_or(CloakCraftError::InsufficientLiquidity)?;
+
+    emit!(TreasuryFeesConverted {
+        amm_pool: ctx.accounts.amm_pool.key(),
+        input_mint,
+        output_mint,
+        amount_in,
+        amount_out,
+        price_impact_bps,
+        keeper: ctx.accounts.keeper.key(),
+    });
+
+    msg!(
+        "Treasury fees converted: {} -> {} (price impact {} bps)",
+        amount_in, amount_out, price_impact_bps
+    );
+
+    Ok(())
+}
+
+/// Quote a keeper swap against an AMM pool and enforce its bounds
+///
+/// Returns (amount_out, price_impact_bps). Fails without a `min_amount_out`,
+/// below it, or above `max_price_impact_bps`. Price impact is relative to the
+/// current spot price, so only `min_amount_out` (set by an authorized keeper
+/// from an external reference) protects against a manipulated pool.
+pub(crate) fn quote_keeper_swap(
+    amm_pool: &AmmPool,
+    swap_a_to_b: bool,
+    amount_in: u64,
+    min_amount_out: u64,
+    max_price_impact_bps: u16,
+) -> Result<(u64, u16)> {
+    require!(min_amount_out > 0, CloakCraftError::SlippageExceeded);
+    let (amount_out, _lp_fee) = amm_pool
+        .calculate_swap_output(amount_in, swap_a_to_b)
+        .ok_or(CloakCraftError::InvalidSwapOutput)?;
+    require!(amount_out > 0 && amount_out >= min_amount_out, CloakCraftError::SlippageExceeded);
+    let price_impact_bps = amm_pool.price_impact_bps(amount_in, amount_out, swap_a_to_b);
+    require!(
+        price_impact_bps <= max_price_impact_bps,
+        CloakCraftError::ConversionPriceImpactTooHigh
+    );
+    Ok((amount_out, price_impact_bps))
+}
+
+/// Move treasury fees into a pool vault, signed by the treasury's delegate
+pub(crate) fn pull_treasury_fees<'info>(
+    token_program: &Program<'info, Token>,
+    treasury_source: &Account<'info, TokenAccount>,
+    vault: &Account<'info, TokenAccount>,
+    treasury_converter: &UncheckedAccount<'info>,
+    converter_bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let converter_seeds: &[&[&[u8]]] = &[&[seeds::TREASURY_CONVERTER, &[converter_bump]]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: treasury_source.to_account_info(),
+                to: vault.to_account_info(),
+                authority: treasury_converter.to_account_info(),
+            },
+            converter_seeds,
+        ),
+        amount,
+    )
+}
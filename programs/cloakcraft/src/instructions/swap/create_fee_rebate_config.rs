@@ -0,0 +1,88 @@
+//! Create a swap fee rebate config for an AMM pool
+//!
+//! Rebates are paid in the pool's token A from a config-owned vault.
+//! The vault is funded by plain SPL transfers.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{AmmPool, FeeRebateConfig, RebateTier, MAX_REBATE_TIERS};
+
+#[derive(Accounts)]
+pub struct CreateFeeRebateConfig<'info> {
+    /// Fee rebate config to create (one per AMM pool)
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + FeeRebateConfig::INIT_SPACE,
+        seeds = [seeds::FEE_REBATE, amm_pool.key().as_ref()],
+        bump,
+    )]
+    pub fee_rebate_config: Box<Account<'info, FeeRebateConfig>>,
+
+    /// AMM pool
+    #[account(
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
+        bump = amm_pool.bump,
+        has_one = authority @ CloakCraftError::Unauthorized,
+    )]
+    pub amm_pool: Box<Account<'info, AmmPool>>,
+
+    /// Token A mint (rebate token)
+    #[account(
+        constraint = rebate_mint.key() == amm_pool.token_a_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub rebate_mint: Box<Account<'info, Mint>>,
+
+    /// Rebate vault (PDA owned by the config)
+    #[account(
+        init,
+        payer = authority,
+        seeds = [seeds::FEE_REBATE_VAULT, fee_rebate_config.key().as_ref()],
+        bump,
+        token::mint = rebate_mint,
+        token::authority = fee_rebate_config,
+    )]
+    pub rebate_vault: Box<Account<'info, TokenAccount>>,
+
+    /// AMM pool authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn create_fee_rebate_config(
+    ctx: Context<CreateFeeRebateConfig>,
+    genesis_time: i64,
+    epoch_duration: i64,
+    tiers: [RebateTier; MAX_REBATE_TIERS],
+) -> Result<()> {
+    require!(
+        epoch_duration > 0 && FeeRebateConfig::validate_tiers(&tiers),
+        CloakCraftError::InvalidRebateConfig
+    );
+
+    let config = &mut ctx.accounts.fee_rebate_config;
+    config.amm_pool = ctx.accounts.amm_pool.key();
+    config.rebate_mint = ctx.accounts.rebate_mint.key();
+    config.rebate_vault = ctx.accounts.rebate_vault.key();
+    config.authority = ctx.accounts.authority.key();
+    config.genesis_time = genesis_time;
+    config.epoch_duration = epoch_duration;
+    config.tiers = tiers;
+    config.total_rebated = 0;
+    config.bump = ctx.bumps.fee_rebate_config;
+    config.vault_bump = ctx.bumps.rebate_vault;
+
+    msg!("Fee rebate config created for AMM pool {}", config.amm_pool);
+    msg!("  Epoch: {}s from {}", epoch_duration, genesis_time);
+
+    Ok(())
+}
@@ -10,6 +10,9 @@
 //! - expected_nullifiers[1] (nullifier B)
 //! - output commitments (lp_commitment, change_a_commitment, change_b_commitment)
 //!
+//! When the AMM pool has an LP lock configured, it also records an LpLock for
+//! the LP commitment (checked by remove liquidity to deter JIT liquidity).
+//!
 //! Flow:
 //! Phase 0 (this): Verify ZK proof + Create PendingOperation (NO Light CPI)
 //! Phase 1a: Verify deposit A commitment exists
@@ -22,17 +25,25 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, AmmPool, VerificationKey, PendingOperation, PENDING_OPERATION_EXPIRY_SECONDS};
+use crate::state::{Pool, AmmPool, LpLock, VerificationKey, PendingOperation, ProtocolConfig, ProgramVersion, CircuitStats, RelayerAllowlist, OperationCredit};
 use crate::constants::seeds;
-use crate::helpers::verify_groth16_proof;
-use crate::helpers::field::pubkey_to_field;
+use crate::helpers::verify_groth16_proof_metered;
+use crate::helpers::field::{pubkey_to_field, assert_canonical};
 use crate::errors::CloakCraftError;
 
 /// Operation type constant for add liquidity
 pub const OP_TYPE_ADD_LIQUIDITY: u8 = 2;
 
 #[derive(Accounts)]
-#[instruction(operation_id: [u8; 32])]
+#[instruction(
+    operation_id: [u8; 32],
+    proof: Vec<u8>,
+    input_commitment_a: [u8; 32],
+    input_commitment_b: [u8; 32],
+    nullifier_a: [u8; 32],
+    nullifier_b: [u8; 32],
+    lp_commitment: [u8; 32],
+)]
 pub struct CreatePendingWithProofAddLiquidity<'info> {
     /// Token A pool (for deposit A)
     #[account(
@@ -57,8 +68,9 @@ pub struct CreatePendingWithProofAddLiquidity<'info> {
 
     /// AMM pool state
     #[account(
-        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref()],
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
         bump = amm_pool.bump,
+        constraint = amm_pool.is_active @ CloakCraftError::AmmPoolNotActive,
     )]
     pub amm_pool: Box<Account<'info, AmmPool>>,
 
@@ -66,6 +78,7 @@ pub struct CreatePendingWithProofAddLiquidity<'info> {
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
@@ -83,6 +96,55 @@ pub struct CreatePendingWithProofAddLiquidity<'info> {
     #[account(mut)]
     pub relayer: Signer<'info>,
 
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// LP lock for the minted LP commitment (required when the pool has an LP lock)
+    #[account(
+        init,
+        payer = relayer,
+        space = 8 + LpLock::INIT_SPACE,
+        seeds = [seeds::LP_LOCK, lp_commitment.as_ref()],
+        bump,
+    )]
+    pub lp_lock: Option<Box<Account<'info, LpLock>>>,
+
+    /// Token A pool's relayer allowlist (required while it is permissioned)
+    #[account(
+        seeds = [seeds::RELAYER_ALLOWLIST, pool_a.key().as_ref()],
+        bump = relayer_allowlist_a.bump,
+    )]
+    pub relayer_allowlist_a: Option<Box<Account<'info, RelayerAllowlist>>>,
+
+    /// Token B pool's relayer allowlist (required while it is permissioned)
+    #[account(
+        seeds = [seeds::RELAYER_ALLOWLIST, pool_b.key().as_ref()],
+        bump = relayer_allowlist_b.bump,
+    )]
+    pub relayer_allowlist_b: Option<Box<Account<'info, RelayerAllowlist>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
     /// System program
     pub system_program: Program<'info, System>,
 }
@@ -105,7 +167,38 @@ pub fn create_pending_with_proof_add_liquidity<'info>(
     lp_amount: u64,
     min_lp_amount: u64,
     num_commitments: u8,
+    client_version: u32,
 ) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Permissioned pools only accept allowlisted relayers
+    ctx.accounts.pool_a.check_relayer(
+        ctx.accounts.relayer_allowlist_a.as_deref().map(|list| &**list),
+        ctx.accounts.relayer.key,
+    )?;
+    ctx.accounts.pool_b.check_relayer(
+        ctx.accounts.relayer_allowlist_b.as_deref().map(|list| &**list),
+        ctx.accounts.relayer.key,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[
+        input_commitment_a,
+        input_commitment_b,
+        nullifier_a,
+        nullifier_b,
+        lp_commitment,
+        change_a_commitment,
+        change_b_commitment,
+    ])?;
+
     let pool_a = &ctx.accounts.pool_a;
     let pool_b = &ctx.accounts.pool_b;
     let lp_pool = &ctx.accounts.lp_pool;
@@ -125,16 +218,24 @@ pub fn create_pending_with_proof_add_liquidity<'info>(
         change_b_commitment,
     ];
 
-    verify_groth16_proof(&proof, &ctx.accounts.verification_key.vk_data, &public_inputs, "AddLiquidity")?;
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "AddLiquidity",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
     msg!("✅ ZK proof verified");
 
     // 2. Initialize pending operation PDA with binding fields
     pending_op.bump = ctx.bumps.pending_operation;
-    pending_op.operation_id = operation_id;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
     pending_op.relayer = ctx.accounts.relayer.key();
     pending_op.operation_type = OP_TYPE_ADD_LIQUIDITY;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
     pending_op.created_at = clock.unix_timestamp;
-    pending_op.expires_at = clock.unix_timestamp + PENDING_OPERATION_EXPIRY_SECONDS;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
 
     // SECURITY: Store binding fields from ZK proof (TWO inputs!)
     pending_op.num_inputs = 2; // Two-input operation
@@ -190,6 +291,19 @@ pub fn create_pending_with_proof_add_liquidity<'info>(
     pending_op.output_amounts[1] = 1; // Change A placeholder (non-zero = not dummy)
     pending_op.output_amounts[2] = 1; // Change B placeholder (non-zero = not dummy)
 
+    // Record the LP lock so an immediate removal can be detected
+    if amm_pool.min_lp_lock_slots > 0 {
+        let lp_lock = ctx.accounts.lp_lock.as_mut()
+            .ok_or(CloakCraftError::LpLockRequired)?;
+        lp_lock.amm_pool = amm_pool.key();
+        lp_lock.lp_commitment = lp_commitment;
+        lp_lock.created_slot = clock.slot;
+        lp_lock.unlock_slot = clock.slot.saturating_add(amm_pool.min_lp_lock_slots);
+        lp_lock.payer = ctx.accounts.relayer.key();
+        lp_lock.bump = ctx.bumps.lp_lock.ok_or(CloakCraftError::LpLockRequired)?;
+        msg!("LP lock recorded: unlock at slot {}", lp_lock.unlock_slot);
+    }
+
     // Store add liquidity-specific data for Phase 3
     pending_op.swap_amount = deposit_a; // Deposit A amount
     pending_op.output_amount = deposit_b; // Deposit B amount
@@ -0,0 +1,200 @@
+//! Create Pending with Proof - Claim Fee Rebate (Phase 0)
+//!
+//! After an epoch ends, the trader proves knowledge of the `volume_key`
+//! behind a swap volume tag (`tag = hash(volume_key, epoch)`) and receives a
+//! rebate note for the tier their epoch volume reached. No input notes are
+//! spent; the volume record is marked claimed in Phase 3.
+//!
+//! Flow:
+//! Phase 0 (this): Verify ZK proof + rebate amount + Create PendingOperation
+//! Phase 3: execute_claim_fee_rebate - Move rebate into the shielded pool
+//! Phase 4: create_commitment for rebate note
+//! Final: close_pending_operation
+
+use anchor_lang::prelude::*;
+
+use crate::constants::{circuits, operation_types, seeds};
+use crate::errors::CloakCraftError;
+use crate::helpers::field::{pubkey_to_field, assert_canonical};
+use crate::helpers::verify_groth16_proof_metered;
+use crate::state::{
+    FeeRebateConfig, PendingOperation, Pool, SwapVolume, VerificationKey,
+    ProtocolConfig, ProgramVersion, CircuitStats, OperationCredit,
+};
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32], volume_tag: [u8; 32], epoch: u64)]
+pub struct CreatePendingWithProofClaimFeeRebate<'info> {
+    /// Fee rebate config
+    #[account(
+        seeds = [seeds::FEE_REBATE, fee_rebate_config.amm_pool.as_ref()],
+        bump = fee_rebate_config.bump,
+    )]
+    pub fee_rebate_config: Box<Account<'info, FeeRebateConfig>>,
+
+    /// Swap volume record being claimed
+    #[account(
+        seeds = [seeds::SWAP_VOLUME, fee_rebate_config.key().as_ref(), &epoch.to_le_bytes(), volume_tag.as_ref()],
+        bump = swap_volume.bump,
+        constraint = !swap_volume.claimed @ CloakCraftError::RebateAlreadyClaimed,
+    )]
+    pub swap_volume: Box<Account<'info, SwapVolume>>,
+
+    /// Rebate token pool (rebate note)
+    #[account(
+        seeds = [seeds::POOL, rebate_pool.token_mint.as_ref()],
+        bump = rebate_pool.bump,
+        constraint = rebate_pool.token_mint == fee_rebate_config.rebate_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub rebate_pool: Box<Account<'info, Pool>>,
+
+    /// Verification key for the fee rebate claim circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, circuits::FEE_REBATE_CLAIM.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Pending operation PDA (created in this instruction)
+    #[account(
+        init,
+        payer = relayer,
+        space = PendingOperation::SPACE,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (pays for PDA creation)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// Phase 0: Verify ZK proof and create PendingOperation for a fee rebate claim
+#[allow(clippy::too_many_arguments)]
+pub fn create_pending_with_proof_claim_fee_rebate<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofClaimFeeRebate<'info>>,
+    operation_id: [u8; 32],
+    volume_tag: [u8; 32],
+    epoch: u64,
+    proof: Vec<u8>,
+    rebate_commitment: [u8; 32],
+    rebate_amount: u64,
+    client_version: u32,
+) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[rebate_commitment])?;
+
+    let config = &ctx.accounts.fee_rebate_config;
+    let swap_volume = &ctx.accounts.swap_volume;
+    let clock = Clock::get()?;
+
+    msg!("=== Phase 0: Verify Proof + Create Pending (Claim Fee Rebate) ===");
+
+    // 1. Epoch must be over and the volume must reach a tier
+    require!(
+        config.is_epoch_over(epoch, clock.unix_timestamp),
+        CloakCraftError::RebateEpochNotOver
+    );
+    let expected_rebate = config.rebate_for(swap_volume.volume);
+    require!(expected_rebate > 0, CloakCraftError::NoRebateOwed);
+    require!(rebate_amount == expected_rebate, CloakCraftError::InvalidAmount);
+
+    // 2. Verify ZK proof (knowledge of volume_key behind the tag; rebate note in the pool token)
+    let mut epoch_bytes = [0u8; 32];
+    epoch_bytes[24..].copy_from_slice(&epoch.to_be_bytes());
+
+    let mut rebate_bytes = [0u8; 32];
+    rebate_bytes[24..].copy_from_slice(&rebate_amount.to_be_bytes());
+
+    let public_inputs = vec![
+        volume_tag,
+        epoch_bytes,
+        pubkey_to_field(&config.key()),
+        rebate_commitment,
+        rebate_bytes,
+        pubkey_to_field(&ctx.accounts.rebate_pool.token_mint),
+    ];
+
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "ClaimFeeRebate",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
+    msg!("✅ ZK proof verified");
+
+    // 3. Initialize pending operation PDA
+    let pending_op = &mut ctx.accounts.pending_operation;
+    pending_op.bump = ctx.bumps.pending_operation;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
+    pending_op.relayer = ctx.accounts.relayer.key();
+    pending_op.operation_type = operation_types::CLAIM_FEE_REBATE;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
+    pending_op.created_at = clock.unix_timestamp;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
+    pending_op.proof_verified = true;
+
+    // No input notes; store swap volume record as input pool (binds Phase 3)
+    pending_op.num_inputs = 0;
+    pending_op.input_pools[0] = swap_volume.key().to_bytes();
+    pending_op.inputs_verified_mask = 0;
+    pending_op.nullifier_completed_mask = 0;
+
+    // Store rebate note as output
+    pending_op.num_commitments = 1;
+    pending_op.pools[0] = ctx.accounts.rebate_pool.key().to_bytes();
+    pending_op.commitments[0] = rebate_commitment;
+    pending_op.output_amounts[0] = rebate_amount;
+    pending_op.completed_mask = 0;
+
+    // output_amount = rebate_amount, swap_amount = epoch volume
+    pending_op.output_amount = rebate_amount;
+    pending_op.swap_amount = swap_volume.volume;
+
+    msg!("Epoch {} volume: {}, rebate: {}", epoch, swap_volume.volume, rebate_amount);
+    msg!("Phase 0 complete: ZK proof verified, pending operation created");
+    msg!("Next: Phase 3 - execute_claim_fee_rebate");
+
+    Ok(())
+}
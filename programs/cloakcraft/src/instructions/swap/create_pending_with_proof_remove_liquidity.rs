@@ -18,10 +18,10 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, AmmPool, VerificationKey, PendingOperation, PENDING_OPERATION_EXPIRY_SECONDS};
+use crate::state::{Pool, AmmPool, LpLock, VerificationKey, PendingOperation, ProtocolConfig, ProgramVersion, CircuitStats, RelayerAllowlist, OperationCredit};
 use crate::constants::seeds;
-use crate::helpers::verify_groth16_proof;
-use crate::helpers::field::pubkey_to_field;
+use crate::helpers::verify_groth16_proof_metered;
+use crate::helpers::field::{pubkey_to_field, assert_canonical};
 use crate::errors::CloakCraftError;
 
 /// Operation type constant for remove liquidity
@@ -37,7 +37,7 @@ fn to_field_element(hash: &[u8; 32]) -> [u8; 32] {
 }
 
 #[derive(Accounts)]
-#[instruction(operation_id: [u8; 32])]
+#[instruction(operation_id: [u8; 32], proof: Vec<u8>, lp_input_commitment: [u8; 32])]
 pub struct CreatePendingWithProofRemoveLiquidity<'info> {
     /// LP token pool (where LP tokens are burned from)
     #[account(
@@ -62,8 +62,9 @@ pub struct CreatePendingWithProofRemoveLiquidity<'info> {
 
     /// AMM pool state
     #[account(
-        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref()],
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
         bump = amm_pool.bump,
+        constraint = amm_pool.is_active @ CloakCraftError::AmmPoolNotActive,
     )]
     pub amm_pool: Box<Account<'info, AmmPool>>,
 
@@ -71,6 +72,7 @@ pub struct CreatePendingWithProofRemoveLiquidity<'info> {
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
@@ -88,6 +90,52 @@ pub struct CreatePendingWithProofRemoveLiquidity<'info> {
     #[account(mut)]
     pub relayer: Signer<'info>,
 
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// LP lock PDA for the LP input (may be uninitialized: no lock recorded)
+    /// CHECK: Address derived from the LP commitment; deserialized only if initialized
+    #[account(
+        mut,
+        seeds = [seeds::LP_LOCK, lp_input_commitment.as_ref()],
+        bump,
+    )]
+    pub lp_lock: UncheckedAccount<'info>,
+
+    /// Receives the LP lock's rent when it is closed (must be the lock payer)
+    /// CHECK: Checked against the lock's recorded payer
+    #[account(mut)]
+    pub lp_lock_payer: Option<UncheckedAccount<'info>>,
+
+    /// LP pool's relayer allowlist (required while it is permissioned)
+    #[account(
+        seeds = [seeds::RELAYER_ALLOWLIST, lp_pool.key().as_ref()],
+        bump = relayer_allowlist.bump,
+    )]
+    pub relayer_allowlist: Option<Box<Account<'info, RelayerAllowlist>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
     /// System program
     pub system_program: Program<'info, System>,
 }
@@ -108,7 +156,26 @@ pub fn create_pending_with_proof_remove_liquidity<'info>(
     withdraw_a_amount: u64,
     withdraw_b_amount: u64,
     num_commitments: u8,
+    client_version: u32,
 ) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Permissioned pools only accept allowlisted relayers
+    ctx.accounts.lp_pool.check_relayer(
+        ctx.accounts.relayer_allowlist.as_deref().map(|list| &**list),
+        ctx.accounts.relayer.key,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[lp_input_commitment, lp_nullifier, out_a_commitment, out_b_commitment])?;
+
     let lp_pool = &ctx.accounts.lp_pool;
     let pool_a = &ctx.accounts.pool_a;
     let pool_b = &ctx.accounts.pool_b;
@@ -125,7 +192,19 @@ pub fn create_pending_with_proof_remove_liquidity<'info>(
     );
     msg!("✅ AMM state hash verified");
 
-    // 2. Verify ZK proof (6 public inputs)
+    // 2. JIT guard: LP minted inside the lock window pays the penalty (or cannot leave)
+    check_lp_lock(
+        &ctx.accounts.lp_lock,
+        ctx.accounts.lp_lock_payer.as_ref(),
+        amm_pool,
+        clock.slot,
+        lp_amount_burned,
+        withdraw_a_amount,
+        withdraw_b_amount,
+    )?;
+
+    // 3. Verify ZK proof (7 public inputs; the LP commitment is the proven
+    // LP note, so the lock checked above is the spent note's lock)
     let public_inputs = vec![
         lp_nullifier,
         pubkey_to_field(&amm_pool.pool_id),
@@ -133,18 +212,27 @@ pub fn create_pending_with_proof_remove_liquidity<'info>(
         out_b_commitment,
         to_field_element(&old_state_hash),
         to_field_element(&new_state_hash),
+        lp_input_commitment,
     ];
 
-    verify_groth16_proof(&proof, &ctx.accounts.verification_key.vk_data, &public_inputs, "RemoveLiquidity")?;
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "RemoveLiquidity",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
     msg!("✅ ZK proof verified");
 
-    // 3. Initialize pending operation PDA with binding fields
+    // 4. Initialize pending operation PDA with binding fields
     pending_op.bump = ctx.bumps.pending_operation;
-    pending_op.operation_id = operation_id;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
     pending_op.relayer = ctx.accounts.relayer.key();
     pending_op.operation_type = OP_TYPE_REMOVE_LIQUIDITY;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
     pending_op.created_at = clock.unix_timestamp;
-    pending_op.expires_at = clock.unix_timestamp + PENDING_OPERATION_EXPIRY_SECONDS;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
 
     // SECURITY: Store binding fields from ZK proof
     pending_op.num_inputs = 1; // Single input operation
@@ -190,3 +278,65 @@ pub fn create_pending_with_proof_remove_liquidity<'info>(
 
     Ok(())
 }
+
+/// Enforce the AMM's LP lock, closing the lock record once it has expired
+///
+/// While the pool has an LP lock configured, a note without a record
+/// (transferred, converted or minted before the lock) counts as locked: its
+/// holder registers one with `register_lp_lock` and waits it out. Inside the
+/// lock window the withdrawal must leave `jit_penalty_bps` of the pro-rata
+/// share in reserves; with no penalty configured, removal is rejected until
+/// unlock. The record is kept while locked so an abandoned removal cannot
+/// clear it.
+fn check_lp_lock<'info>(
+    lp_lock_info: &UncheckedAccount<'info>,
+    lp_lock_payer: Option<&UncheckedAccount<'info>>,
+    amm_pool: &AmmPool,
+    slot: u64,
+    lp_amount_burned: u64,
+    withdraw_a_amount: u64,
+    withdraw_b_amount: u64,
+) -> Result<()> {
+    let lp_lock = if lp_lock_info.data_is_empty() || lp_lock_info.owner != &crate::ID {
+        None
+    } else {
+        let data = lp_lock_info.try_borrow_data()?;
+        Some(LpLock::try_deserialize(&mut &data[..])?)
+    };
+
+    let locked = match &lp_lock {
+        Some(lp_lock) => {
+            require!(lp_lock.amm_pool == amm_pool.pool_id, CloakCraftError::InvalidPoolState);
+            lp_lock.is_locked(slot)
+        }
+        None => amm_pool.min_lp_lock_slots > 0,
+    };
+
+    if locked {
+        require!(amm_pool.jit_penalty_bps > 0, CloakCraftError::LpLocked);
+        let (max_a, max_b) = amm_pool
+            .max_withdrawal(lp_amount_burned, amm_pool.jit_penalty_bps)
+            .ok_or(CloakCraftError::InvalidPoolState)?;
+        require!(
+            withdraw_a_amount <= max_a && withdraw_b_amount <= max_b,
+            CloakCraftError::JitPenaltyNotApplied
+        );
+        msg!("JIT penalty {}bps applied", amm_pool.jit_penalty_bps);
+        return Ok(());
+    }
+
+    let Some(lp_lock) = lp_lock else {
+        return Ok(());
+    };
+
+    // Close the lock record, refunding its payer
+    let payer = lp_lock_payer.ok_or(CloakCraftError::InvalidLpLockPayer)?;
+    require!(payer.key() == lp_lock.payer, CloakCraftError::InvalidLpLockPayer);
+    let lamports = lp_lock_info.lamports();
+    **lp_lock_info.try_borrow_mut_lamports()? -= lamports;
+    **payer.try_borrow_mut_lamports()? += lamports;
+    lp_lock_info.assign(&anchor_lang::system_program::ID);
+    lp_lock_info.resize(0)?;
+
+    Ok(())
+}
@@ -7,6 +7,7 @@
 //! - input_commitment (from proof public inputs)
 //! - expected_nullifier (from proof public inputs)
 //! - output commitments (out_commitment, change_commitment)
+//! - the swap volume record credited in Phase 3 (its tag is a proof input)
 //!
 //! These values bind all subsequent phases together, preventing swap attacks.
 //!
@@ -20,11 +21,11 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, AmmPool, VerificationKey, PendingOperation, PENDING_OPERATION_EXPIRY_SECONDS};
-use crate::constants::seeds;
+use crate::state::{Pool, AmmPool, VerificationKey, PendingOperation, ProtocolConfig, ProgramVersion, CircuitStats, RelayerAllowlist, OperationCredit, SwapVolume};
+use crate::constants::{circuits, seeds};
 use crate::errors::CloakCraftError;
-use crate::helpers::verify_groth16_proof;
-use crate::helpers::field::pubkey_to_field;
+use crate::helpers::verify_groth16_proof_metered;
+use crate::helpers::field::{pubkey_to_field, assert_canonical};
 
 /// Operation type constant for swap
 pub const OP_TYPE_SWAP: u8 = 1;
@@ -48,8 +49,9 @@ pub struct CreatePendingWithProofSwap<'info> {
 
     /// AMM pool state
     #[account(
-        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref()],
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
         bump = amm_pool.bump,
+        constraint = amm_pool.is_active @ CloakCraftError::AmmPoolNotActive,
     )]
     pub amm_pool: Box<Account<'info, AmmPool>>,
 
@@ -57,6 +59,7 @@ pub struct CreatePendingWithProofSwap<'info> {
     #[account(
         seeds = [seeds::VERIFICATION_KEY, verification_key.circuit_id.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
     pub verification_key: Box<Account<'info, VerificationKey>>,
 
@@ -74,6 +77,42 @@ pub struct CreatePendingWithProofSwap<'info> {
     #[account(mut)]
     pub relayer: Signer<'info>,
 
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Input pool's relayer allowlist (required while it is permissioned)
+    #[account(
+        seeds = [seeds::RELAYER_ALLOWLIST, input_pool.key().as_ref()],
+        bump = relayer_allowlist.bump,
+    )]
+    pub relayer_allowlist: Option<Box<Account<'info, RelayerAllowlist>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
+    /// Trader's swap volume record credited in execute_swap (optional; its
+    /// volume tag is bound by the proof)
+    pub swap_volume: Option<Box<Account<'info, SwapVolume>>>,
+
     /// System program
     pub system_program: Program<'info, System>,
 }
@@ -101,7 +140,69 @@ pub fn create_pending_with_proof_swap<'info>(
     output_amount: u64,
     swap_a_to_b: bool,
     num_commitments: u8,
+    client_version: u32,
+) -> Result<()> {
+    create_pending_swap(
+        ctx,
+        operation_id,
+        proof,
+        merkle_root,
+        input_commitment,
+        nullifier,
+        out_commitment,
+        change_commitment,
+        min_output,
+        SwapTerms::Public { swap_amount, output_amount, swap_a_to_b },
+        num_commitments,
+        client_version,
+    )
+}
+
+/// Swap parameters given in Phase 0
+pub(crate) enum SwapTerms {
+    /// Stored as is for execute_swap
+    Public { swap_amount: u64, output_amount: u64, swap_a_to_b: bool },
+    /// Only the commitment (bound by the swap_committed proof, min_output
+    /// included) is stored; revealed in execute_swap_revealed
+    Committed([u8; 32]),
+    /// Committed, with the commitment also bound by the swap_sealed proof
+    Sealed([u8; 32]),
+}
+
+/// Shared Phase 0 of public, committed and sealed swaps
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_pending_swap<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofSwap<'info>>,
+    operation_id: [u8; 32],
+    proof: Vec<u8>,
+    merkle_root: [u8; 32],
+    input_commitment: [u8; 32],
+    nullifier: [u8; 32],
+    out_commitment: [u8; 32],
+    change_commitment: [u8; 32],
+    min_output: u64,
+    terms: SwapTerms,
+    num_commitments: u8,
+    client_version: u32,
 ) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Permissioned pools only accept allowlisted relayers
+    ctx.accounts.input_pool.check_relayer(
+        ctx.accounts.relayer_allowlist.as_deref().map(|list| &**list),
+        ctx.accounts.relayer.key,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[input_commitment, nullifier, out_commitment, change_commitment])?;
+
     let input_pool = &ctx.accounts.input_pool;
     let output_pool = &ctx.accounts.output_pool;
     let amm_pool = &ctx.accounts.amm_pool;
@@ -110,35 +211,73 @@ pub fn create_pending_with_proof_swap<'info>(
 
     msg!("=== Phase 0: Verify Proof + Create Pending (Swap) ===");
 
-    // 1. Verify ZK proof (6 public inputs matching Circom circuit)
+    // 1. Verify ZK proof (public inputs matching the Circom circuit)
     let mut min_output_bytes = [0u8; 32];
     min_output_bytes[24..].copy_from_slice(&min_output.to_be_bytes());
 
-    let public_inputs = vec![
+    let mut public_inputs = vec![
         merkle_root,
         nullifier,
         pubkey_to_field(&amm_pool.pool_id),
         out_commitment,
         change_commitment,
-        min_output_bytes,
     ];
 
-    verify_groth16_proof(&proof, &ctx.accounts.verification_key.vk_data, &public_inputs, "Swap")?;
+    // Fee rebate volume tag (0 = no volume tracking), the last input of every swap circuit
+    let volume_tag = ctx.accounts.swap_volume.as_ref().map_or([0u8; 32], |record| record.volume_tag);
+    assert_canonical(&[volume_tag])?;
+
+    match terms {
+        SwapTerms::Public { .. } => public_inputs.push(min_output_bytes),
+        // Committed swaps: the swap_committed circuit hides min_output in the
+        // swap commitment, its only terms input
+        SwapTerms::Committed(swap_commitment) => {
+            require!(
+                ctx.accounts.verification_key.circuit_id == circuits::SWAP_COMMITTED,
+                CloakCraftError::CommittedSwapCircuitRequired
+            );
+            assert_canonical(&[swap_commitment])?;
+            public_inputs.push(swap_commitment);
+        }
+        // Sealed swaps: the swap_sealed circuit adds the terms hash as a 7th input
+        SwapTerms::Sealed(terms_hash) => {
+            require!(
+                ctx.accounts.verification_key.circuit_id == circuits::SWAP_SEALED,
+                CloakCraftError::SealedSwapCircuitRequired
+            );
+            assert_canonical(&[terms_hash])?;
+            public_inputs.push(min_output_bytes);
+            public_inputs.push(terms_hash);
+        }
+    }
+    public_inputs.push(volume_tag);
+
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "Swap",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
     msg!("✅ ZK proof verified");
 
     // 2. Initialize pending operation PDA with binding fields
     pending_op.bump = ctx.bumps.pending_operation;
-    pending_op.operation_id = operation_id;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
     pending_op.relayer = ctx.accounts.relayer.key();
     pending_op.operation_type = OP_TYPE_SWAP;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
     pending_op.created_at = clock.unix_timestamp;
-    pending_op.expires_at = clock.unix_timestamp + PENDING_OPERATION_EXPIRY_SECONDS;
+    pending_op.expires_at = clock.unix_timestamp + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
 
     // SECURITY: Store binding fields from ZK proof
     pending_op.num_inputs = 1; // Single input operation
     pending_op.input_commitments[0] = input_commitment;
     pending_op.expected_nullifiers[0] = nullifier;
     pending_op.input_pools[0] = input_pool.key().to_bytes(); // SECURITY: Bind input to input pool
+    // Unused input slot stores the swap volume record (binds Phase 3)
+    pending_op.input_pools[1] = ctx.accounts.swap_volume.as_ref().map_or([0u8; 32], |record| record.key().to_bytes());
     pending_op.inputs_verified_mask = 0; // Will be set in Phase 1
     pending_op.proof_verified = true;
 
@@ -159,19 +298,33 @@ pub fn create_pending_with_proof_swap<'info>(
     pending_op.pools[1] = input_pool.key().to_bytes(); // Change commitment (remaining input)
     pending_op.commitments[1] = change_commitment;
 
-    // CRITICAL FIX: Store output amounts for create_commitment validation
-    // Without these, create_commitment skips commitments as "zero-amount dummies"
-    pending_op.output_amounts[0] = output_amount; // Swap output amount
     pending_op.output_amounts[1] = 1; // Change placeholder (non-zero = not dummy, actual amount in encrypted note)
 
     pending_op.nullifier_completed_mask = 0;
     pending_op.completed_mask = 0;
 
     // Store swap-specific data for Phase 3
-    pending_op.swap_amount = swap_amount;
-    pending_op.output_amount = output_amount; // Client's expected output (may be recalculated)
-    pending_op.min_output = min_output; // Slippage protection - recalculated output must be >= this
-    pending_op.swap_a_to_b = swap_a_to_b;
+    pending_op.min_output = min_output; // Slippage protection - recalculated output must be >= this (committed swaps: set on reveal)
+    match terms {
+        SwapTerms::Public { swap_amount, output_amount, swap_a_to_b } => {
+            // CRITICAL FIX: Store output amounts for create_commitment validation
+            // Without these, create_commitment skips commitments as "zero-amount dummies"
+            pending_op.output_amounts[0] = output_amount; // Swap output amount
+            pending_op.swap_amount = swap_amount;
+            pending_op.output_amount = output_amount; // Client's expected output (re-checked against live reserves in Phase 3)
+            pending_op.swap_a_to_b = swap_a_to_b;
+            pending_op.call_hash = [0u8; 32];
+        }
+        SwapTerms::Committed(swap_commitment) | SwapTerms::Sealed(swap_commitment) => {
+            // Amounts and direction stay hidden until execute_swap_revealed
+            require!(swap_commitment != [0u8; 32], CloakCraftError::SwapCommitmentMismatch);
+            pending_op.output_amounts[0] = 1; // Placeholder until revealed
+            pending_op.swap_amount = 0;
+            pending_op.output_amount = 0;
+            pending_op.swap_a_to_b = false;
+            pending_op.call_hash = swap_commitment;
+        }
+    }
 
     msg!("Phase 0 complete: ZK proof verified, pending operation created");
     msg!("Next: Phase 1 - verify_commitment_exists");
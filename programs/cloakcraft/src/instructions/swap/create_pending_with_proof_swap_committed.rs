@@ -0,0 +1,92 @@
+//! Create Pending Operation with Proof - Phase 0 (Committed Swap)
+//!
+//! Same as create_pending_with_proof_swap, but the swap amount, expected
+//! output, minimum output and direction are replaced by a salted hash of
+//! them (see `compute_swap_commitment`). The swap_committed circuit takes the
+//! hash as its only terms input, so it is bound by the proof and min_output
+//! is no longer public. The terms are revealed in execute_swap_revealed, so
+//! observers can't size a sandwich around the swap from its Phase 0.
+//!
+//! The input/output pool accounts still hint at the direction.
+
+use anchor_lang::prelude::*;
+use light_hasher::{Hasher, Poseidon};
+
+use super::create_pending_with_proof_swap::{create_pending_swap, CreatePendingWithProofSwap, SwapTerms};
+use crate::errors::CloakCraftError;
+use crate::helpers::field::u64_to_field;
+
+/// Poseidon domain of swap commitments (matches the swap_committed and swap_sealed circuits)
+pub const SWAP_TERMS_DOMAIN: u64 = 0x20;
+
+/// Commitment to a swap's hidden parameters
+///
+/// Poseidon(SWAP_TERMS_DOMAIN, swap_amount, output_amount, min_output,
+/// swap_a_to_b, salt). The random salt keeps the low-entropy amounts from
+/// being brute-forced and must be a canonical field element. Poseidon lets
+/// the swap_committed and swap_sealed circuits bind the same hash.
+pub fn compute_swap_commitment(
+    swap_amount: u64,
+    output_amount: u64,
+    min_output: u64,
+    swap_a_to_b: bool,
+    salt: &[u8; 32],
+) -> Result<[u8; 32]> {
+    Poseidon::hashv(&[
+        &u64_to_field(SWAP_TERMS_DOMAIN),
+        &u64_to_field(swap_amount),
+        &u64_to_field(output_amount),
+        &u64_to_field(min_output),
+        &u64_to_field(swap_a_to_b as u64),
+        salt,
+    ])
+    .map_err(|_| CloakCraftError::SwapCommitmentMismatch.into())
+}
+
+/// Phase 0: Verify ZK proof and create PendingOperation for a committed swap
+#[allow(clippy::too_many_arguments)]
+pub fn create_pending_with_proof_swap_committed<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofSwap<'info>>,
+    operation_id: [u8; 32],
+    proof: Vec<u8>,
+    merkle_root: [u8; 32],
+    input_commitment: [u8; 32],
+    nullifier: [u8; 32],
+    out_commitment: [u8; 32],
+    change_commitment: [u8; 32],
+    swap_commitment: [u8; 32],
+    num_commitments: u8,
+    client_version: u32,
+) -> Result<()> {
+    create_pending_swap(
+        ctx,
+        operation_id,
+        proof,
+        merkle_root,
+        input_commitment,
+        nullifier,
+        out_commitment,
+        change_commitment,
+        0, // Revealed with the other terms
+        SwapTerms::Committed(swap_commitment),
+        num_commitments,
+        client_version,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_commitment() {
+        let salt = u64_to_field(7);
+        let commitment = compute_swap_commitment(100, 95, 90, true, &salt).unwrap();
+        assert_eq!(compute_swap_commitment(100, 95, 90, true, &salt).unwrap(), commitment);
+        assert_ne!(compute_swap_commitment(100, 95, 90, false, &salt).unwrap(), commitment);
+        assert_ne!(compute_swap_commitment(100, 95, 90, true, &u64_to_field(8)).unwrap(), commitment);
+
+        // Salt must be a canonical field element
+        assert!(compute_swap_commitment(100, 95, 90, true, &[0xff; 32]).is_err());
+    }
+}
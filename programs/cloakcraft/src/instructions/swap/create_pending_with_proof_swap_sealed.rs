@@ -0,0 +1,46 @@
+//! Create Pending Operation with Proof - Phase 0 (Sealed Swap)
+//!
+//! A committed swap whose commitment (`terms_hash`, see
+//! `compute_swap_commitment`) is also a public input of the proof, via the
+//! swap_sealed circuit. The user seals the terms and salt to the relayer's
+//! session key (SDK `encryptSwapTerms`), so only the relayer learns them
+//! before Phase 3. Because the proof binds the hash, the relayer can't swap
+//! in terms of its own: execute_swap_revealed only accepts the user's.
+//!
+//! As with committed swaps, `min_output` and the pool accounts stay visible.
+
+use anchor_lang::prelude::*;
+
+use super::create_pending_with_proof_swap::{create_pending_swap, CreatePendingWithProofSwap, SwapTerms};
+
+/// Phase 0: Verify ZK proof and create PendingOperation for a sealed swap
+#[allow(clippy::too_many_arguments)]
+pub fn create_pending_with_proof_swap_sealed<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofSwap<'info>>,
+    operation_id: [u8; 32],
+    proof: Vec<u8>,
+    merkle_root: [u8; 32],
+    input_commitment: [u8; 32],
+    nullifier: [u8; 32],
+    out_commitment: [u8; 32],
+    change_commitment: [u8; 32],
+    min_output: u64,
+    terms_hash: [u8; 32],
+    num_commitments: u8,
+    client_version: u32,
+) -> Result<()> {
+    create_pending_swap(
+        ctx,
+        operation_id,
+        proof,
+        merkle_root,
+        input_commitment,
+        nullifier,
+        out_commitment,
+        change_commitment,
+        min_output,
+        SwapTerms::Sealed(terms_hash),
+        num_commitments,
+        client_version,
+    )
+}
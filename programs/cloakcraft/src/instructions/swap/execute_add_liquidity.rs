@@ -15,7 +15,7 @@
 
 use anchor_lang::prelude::*;
 
-use crate::state::{Pool, AmmPool, PendingOperation};
+use crate::state::{Pool, AmmPool, PendingOperation, OperationKind};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 use crate::helpers::amm_math::{calculate_initial_lp, calculate_proportional_lp, validate_lp_amount};
@@ -47,8 +47,9 @@ pub struct ExecuteAddLiquidity<'info> {
     /// AMM pool state (will be updated)
     #[account(
         mut,
-        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref()],
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
         bump = amm_pool.bump,
+        constraint = amm_pool.is_active @ CloakCraftError::AmmPoolNotActive,
     )]
     pub amm_pool: Box<Account<'info, AmmPool>>,
 
@@ -58,9 +59,6 @@ pub struct ExecuteAddLiquidity<'info> {
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
         constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
-        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
-        constraint = pending_operation.all_inputs_verified() @ CloakCraftError::CommitmentNotVerified,
-        constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -77,6 +75,8 @@ pub fn execute_add_liquidity<'info>(
     _operation_id: [u8; 32],
     min_lp_amount: u64,
 ) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::AddLiquidity)?;
+
     let amm_pool = &mut ctx.accounts.amm_pool;
     let pending_op = &ctx.accounts.pending_operation;
 
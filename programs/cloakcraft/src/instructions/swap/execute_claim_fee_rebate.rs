@@ -0,0 +1,127 @@
+//! Execute Claim Fee Rebate (Phase 3)
+//!
+//! Marks the swap volume record claimed and moves the rebate from the
+//! rebate vault into the shielded pool vault, backing the rebate note
+//! created in Phase 4.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{FeeRebateConfig, PendingOperation, OperationKind, Pool, SwapVolume};
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32])]
+pub struct ExecuteClaimFeeRebate<'info> {
+    /// Fee rebate config
+    #[account(
+        mut,
+        seeds = [seeds::FEE_REBATE, fee_rebate_config.amm_pool.as_ref()],
+        bump = fee_rebate_config.bump,
+    )]
+    pub fee_rebate_config: Box<Account<'info, FeeRebateConfig>>,
+
+    /// Rebate vault (source of rebate)
+    #[account(
+        mut,
+        seeds = [seeds::FEE_REBATE_VAULT, fee_rebate_config.key().as_ref()],
+        bump = fee_rebate_config.vault_bump,
+    )]
+    pub rebate_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Swap volume record bound in Phase 0
+    #[account(
+        mut,
+        constraint = swap_volume.key().to_bytes() == pending_operation.input_pools[0] @ CloakCraftError::InvalidRebateConfig,
+        constraint = !swap_volume.claimed @ CloakCraftError::RebateAlreadyClaimed,
+    )]
+    pub swap_volume: Box<Account<'info, SwapVolume>>,
+
+    /// Rebate token pool (receives the rebate note)
+    #[account(
+        mut,
+        seeds = [seeds::POOL, rebate_pool.token_mint.as_ref()],
+        bump = rebate_pool.bump,
+        constraint = pending_operation.pools[0] == rebate_pool.key().to_bytes() @ CloakCraftError::PoolMismatch,
+    )]
+    pub rebate_pool: Box<Account<'info, Pool>>,
+
+    /// Rebate token pool vault
+    #[account(
+        mut,
+        seeds = [seeds::VAULT, rebate_pool.token_mint.as_ref()],
+        bump = rebate_pool.vault_bump,
+    )]
+    pub rebate_pool_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Pending operation PDA (from Phase 0)
+    #[account(
+        mut,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump = pending_operation.bump,
+        constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer (must match pending operation)
+    #[account(
+        constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
+    )]
+    pub relayer: Signer<'info>,
+
+    /// Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Phase 3: Transfer rebate into the shielded pool
+pub fn execute_claim_fee_rebate(
+    ctx: Context<ExecuteClaimFeeRebate>,
+    _operation_id: [u8; 32],
+) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::ClaimFeeRebate)?;
+
+    let config = &mut ctx.accounts.fee_rebate_config;
+    let rebate_amount = ctx.accounts.pending_operation.output_amount;
+
+    msg!("=== Phase 3: Execute Claim Fee Rebate ===");
+
+    require!(
+        ctx.accounts.rebate_vault.amount >= rebate_amount,
+        CloakCraftError::InsufficientBalance
+    );
+
+    // Mark claimed before transfer (prevents a second claim for this record)
+    ctx.accounts.swap_volume.claimed = true;
+
+    let amm_pool = config.amm_pool;
+    let signer_seeds: &[&[&[u8]]] = &[&[seeds::FEE_REBATE, amm_pool.as_ref(), &[config.bump]]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.rebate_vault.to_account_info(),
+                to: ctx.accounts.rebate_pool_vault.to_account_info(),
+                authority: config.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        rebate_amount,
+    )?;
+
+    config.total_rebated = config.total_rebated
+        .checked_add(rebate_amount)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    let rebate_pool = &mut ctx.accounts.rebate_pool;
+    rebate_pool.total_shielded = rebate_pool.total_shielded
+        .checked_add(rebate_amount)
+        .ok_or(CloakCraftError::AmountOverflow)?;
+
+    msg!("✅ Fee rebate paid: {}", rebate_amount);
+    msg!("Phase 3 complete");
+    msg!("Next: Phase 4 - create_commitment for rebate note");
+
+    Ok(())
+}
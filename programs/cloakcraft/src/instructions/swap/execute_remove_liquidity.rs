@@ -14,9 +14,10 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
-use crate::state::{Pool, AmmPool, PendingOperation, ProtocolConfig};
+use crate::state::{Pool, PoolStats, AmmPool, PendingOperation, OperationKind, ProtocolConfig};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
+use crate::helpers::debit_protocol_fee;
 
 /// Convert [u8; 32] to field element by zeroing MSB
 /// keccak256 outputs big-endian bytes, so byte[0] is the MSB
@@ -36,15 +37,17 @@ pub struct ExecuteRemoveLiquidity<'info> {
     )]
     pub lp_pool: Box<Account<'info, Pool>>,
 
-    /// Token A pool (authority for vault_a transfers)
+    /// Token A pool (authority for vault_a transfers, debited by fee A)
     #[account(
+        mut,
         seeds = [seeds::POOL, pool_a.token_mint.as_ref()],
         bump = pool_a.bump,
     )]
     pub pool_a: Box<Account<'info, Pool>>,
 
-    /// Token B pool (authority for vault_b transfers)
+    /// Token B pool (authority for vault_b transfers, debited by fee B)
     #[account(
+        mut,
         seeds = [seeds::POOL, pool_b.token_mint.as_ref()],
         bump = pool_b.bump,
     )]
@@ -53,8 +56,9 @@ pub struct ExecuteRemoveLiquidity<'info> {
     /// AMM pool state (will be updated)
     #[account(
         mut,
-        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref()],
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
         bump = amm_pool.bump,
+        constraint = amm_pool.is_active @ CloakCraftError::AmmPoolNotActive,
     )]
     pub amm_pool: Box<Account<'info, AmmPool>>,
 
@@ -78,9 +82,6 @@ pub struct ExecuteRemoveLiquidity<'info> {
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
         constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
-        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
-        constraint = pending_operation.all_inputs_verified() @ CloakCraftError::CommitmentNotVerified,
-        constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -107,6 +108,22 @@ pub struct ExecuteRemoveLiquidity<'info> {
 
     /// Token program for transfers
     pub token_program: Program<'info, Token>,
+
+    /// Token A pool statistics (optional, debited by fee A)
+    #[account(
+        mut,
+        seeds = [seeds::POOL_STATS, pool_a.key().as_ref()],
+        bump = pool_a_stats.bump,
+    )]
+    pub pool_a_stats: Option<Box<Account<'info, PoolStats>>>,
+
+    /// Token B pool statistics (optional, debited by fee B)
+    #[account(
+        mut,
+        seeds = [seeds::POOL_STATS, pool_b.key().as_ref()],
+        bump = pool_b_stats.bump,
+    )]
+    pub pool_b_stats: Option<Box<Account<'info, PoolStats>>>,
 }
 
 /// Phase 3: Execute remove liquidity by updating AMM pool state
@@ -118,7 +135,7 @@ pub struct ExecuteRemoveLiquidity<'info> {
 ///
 /// This phase:
 /// 1. Calculates protocol fee (percentage of withdrawn amounts)
-/// 2. Transfers protocol fees from vaults to treasury
+/// 2. Transfers protocol fees from vaults to treasury (debiting the shielded supplies)
 /// 3. Updates AMM pool reserves and LP supply
 /// 4. Updates state hash
 pub fn execute_remove_liquidity<'info>(
@@ -126,11 +143,14 @@ pub fn execute_remove_liquidity<'info>(
     _operation_id: [u8; 32],
     new_state_hash: [u8; 32],
 ) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::RemoveLiquidity)?;
+
     let amm_pool = &mut ctx.accounts.amm_pool;
     let pending_op = &ctx.accounts.pending_operation;
-    let pool_a = &ctx.accounts.pool_a;
-    let pool_b = &ctx.accounts.pool_b;
+    let pool_a = &mut ctx.accounts.pool_a;
+    let pool_b = &mut ctx.accounts.pool_b;
     let protocol_config = &ctx.accounts.protocol_config;
+    let timestamp = Clock::get()?.unix_timestamp;
 
     msg!("=== Phase 3: Execute Remove Liquidity ===");
 
@@ -144,13 +164,13 @@ pub fn execute_remove_liquidity<'info>(
 
     // Calculate protocol fees for both tokens
     let (fee_a, _) = if protocol_config.fees_enabled {
-        protocol_config.calculate_remove_liquidity_fee(withdraw_a_amount)
+        protocol_config.calculate_remove_liquidity_fee(&pool_a.token_mint, withdraw_a_amount)
     } else {
         (0, withdraw_a_amount)
     };
 
     let (fee_b, _) = if protocol_config.fees_enabled {
-        protocol_config.calculate_remove_liquidity_fee(withdraw_b_amount)
+        protocol_config.calculate_remove_liquidity_fee(&pool_b.token_mint, withdraw_b_amount)
     } else {
         (0, withdraw_b_amount)
     };
@@ -180,6 +200,8 @@ pub fn execute_remove_liquidity<'info>(
             signer_seeds_a,
         );
         token::transfer(transfer_ctx_a, fee_a)?;
+        let pool_a_stats = ctx.accounts.pool_a_stats.as_deref_mut().map(|stats| &mut **stats);
+        debit_protocol_fee(pool_a, pool_a_stats, fee_a, timestamp)?;
 
         msg!("Protocol fee A transferred: {} to treasury", fee_a);
     }
@@ -207,6 +229,8 @@ pub fn execute_remove_liquidity<'info>(
             signer_seeds_b,
         );
         token::transfer(transfer_ctx_b, fee_b)?;
+        let pool_b_stats = ctx.accounts.pool_b_stats.as_deref_mut().map(|stats| &mut **stats);
+        debit_protocol_fee(pool_b, pool_b_stats, fee_b, timestamp)?;
 
         msg!("Protocol fee B transferred: {} to treasury", fee_b);
     }
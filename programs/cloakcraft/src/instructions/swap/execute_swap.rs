@@ -13,21 +13,24 @@
 //! Phase 1: Verify commitment exists
 //! Phase 2: Create nullifier (CRITICAL POINT - commitment now spent)
 //! Phase 3 (this): Execute swap logic + transfer protocol fees to treasury
+//!                  (+ optional fee rebate volume tracking)
 //! Phase 4+: Create commitments
 //! Final: Close pending operation
 
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
-use crate::state::{Pool, AmmPool, PendingOperation, ProtocolConfig};
+use crate::state::{Pool, PoolStats, AmmPool, PendingOperation, OperationKind, ProtocolConfig, FeeRebateConfig, SwapVolume};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
+use crate::helpers::debit_protocol_fee;
 
 #[derive(Accounts)]
 #[instruction(operation_id: [u8; 32])]
 pub struct ExecuteSwap<'info> {
-    /// Input token pool (has vault for input token)
+    /// Input token pool (has vault for input token, debited by the protocol fee)
     #[account(
+        mut,
         seeds = [seeds::POOL, input_pool.token_mint.as_ref()],
         bump = input_pool.bump,
     )]
@@ -43,8 +46,9 @@ pub struct ExecuteSwap<'info> {
     /// AMM pool state (will be updated)
     #[account(
         mut,
-        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref()],
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
         bump = amm_pool.bump,
+        constraint = amm_pool.is_active @ CloakCraftError::AmmPoolNotActive,
     )]
     pub amm_pool: Box<Account<'info, AmmPool>>,
 
@@ -61,9 +65,6 @@ pub struct ExecuteSwap<'info> {
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
         constraint = !pending_operation.is_expired(Clock::get()?.unix_timestamp) @ CloakCraftError::PendingOperationExpired,
-        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
-        constraint = pending_operation.all_inputs_verified() @ CloakCraftError::CommitmentNotVerified,
-        constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -85,8 +86,24 @@ pub struct ExecuteSwap<'info> {
     #[account(mut)]
     pub treasury_ata: Option<Box<Account<'info, TokenAccount>>>,
 
+    /// Fee rebate config for this AMM pool (optional, with swap_volume)
+    pub fee_rebate_config: Option<Box<Account<'info, FeeRebateConfig>>>,
+
+    /// Trader's swap volume record for the current epoch (required iff one
+    /// was bound in Phase 0)
+    #[account(mut)]
+    pub swap_volume: Option<Box<Account<'info, SwapVolume>>>,
+
     /// Token program for transfers
     pub token_program: Program<'info, Token>,
+
+    /// Input pool statistics (optional, debited by the protocol fee)
+    #[account(
+        mut,
+        seeds = [seeds::POOL_STATS, input_pool.key().as_ref()],
+        bump = input_pool_stats.bump,
+    )]
+    pub input_pool_stats: Option<Box<Account<'info, PoolStats>>>,
 }
 
 /// Phase 3: Execute swap by updating AMM pool reserves
@@ -97,19 +114,32 @@ pub struct ExecuteSwap<'info> {
 /// - Phase 2: Nullifier created (commitment now spent)
 ///
 /// This phase:
-/// 1. Calculates protocol fee (percentage of LP fees)
-/// 2. Transfers protocol fee from vault to treasury
-/// 3. Updates AMM pool reserves (minus protocol fee)
-/// 4. Updates state hash
+/// 1. Re-checks the swap output against live reserves (slippage + drift)
+/// 2. Calculates protocol fee (percentage of LP fees)
+/// 3. Transfers protocol fee from vault to treasury (debiting the shielded supply)
+/// 4. Updates AMM pool reserves (minus protocol fee)
+/// 5. Updates state hash
 ///
 /// NO Light Protocol CPI calls (those were in Phases 1 & 2)
 pub fn execute_swap<'info>(
     ctx: Context<'_, '_, '_, 'info, ExecuteSwap<'info>>,
     _operation_id: [u8; 32],
 ) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::Swap)?;
+
+    // Committed swaps reveal their parameters in execute_swap_revealed
+    require!(
+        ctx.accounts.pending_operation.call_hash == [0u8; 32],
+        CloakCraftError::SwapNotRevealed
+    );
+    apply_swap(ctx)
+}
+
+/// Re-check the stored swap against live reserves and apply it
+pub(crate) fn apply_swap<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteSwap<'info>>) -> Result<()> {
     let amm_pool = &mut ctx.accounts.amm_pool;
     let pending_op = &ctx.accounts.pending_operation;
-    let input_pool = &ctx.accounts.input_pool;
+    let input_pool = &mut ctx.accounts.input_pool;
 
     msg!("=== Phase 3: Execute Swap ===");
 
@@ -121,21 +151,17 @@ pub fn execute_swap<'info>(
     msg!("Swap direction: {}, amount: {}, min_output: {}",
         if swap_a_to_b { "A->B" } else { "B->A" }, swap_amount, min_output);
 
-    // FLEXIBLE RECALCULATION: Calculate output using CURRENT pool reserves
-    // This handles concurrent swaps gracefully:
-    // - If price moved favorably → user gets more, tx succeeds
-    // - If price moved within slippage → tx succeeds with slightly less
-    // - If price moved beyond slippage → tx fails (correct behavior)
-    let (output_amount, _fee_amount) = amm_pool.calculate_swap_output(swap_amount, swap_a_to_b)
-        .ok_or(CloakCraftError::InvalidSwapOutput)?;
-
-    // SECURITY: Verify recalculated output meets minimum (slippage protection)
-    require!(
-        output_amount >= min_output,
-        CloakCraftError::SlippageExceeded
-    );
-    msg!("✅ Swap output calculated: {} (min: {}) using {} formula",
-        output_amount, min_output,
+    // SLIPPAGE RE-CHECK: Recalculate output using CURRENT pool reserves
+    // Reserves may have moved since Phase 0 (concurrent swaps/liquidity):
+    // - If recomputed output < min_output → tx fails (slippage)
+    // - If recomputed output < the Phase 0 output, or exceeds it beyond tolerance
+    //   → tx fails; the operation expires and is recovered via close_pending_operation
+    // Reserves are debited by the Phase 0 output (what the note pays), so any
+    // recompute surplus stays with LPs
+    let output_amount = pending_op.output_amount;
+    let recomputed_output = amm_pool.check_swap_output(swap_amount, swap_a_to_b, output_amount, min_output)?;
+    msg!("✅ Swap output re-checked: {} (recomputed: {}, min: {}) using {} formula",
+        output_amount, recomputed_output, min_output,
         if amm_pool.pool_type == crate::state::PoolType::StableSwap { "StableSwap" } else { "ConstantProduct" });
 
     // Calculate protocol fee (percentage of LP fees)
@@ -177,6 +203,14 @@ pub fn execute_swap<'info>(
         );
         token::transfer(transfer_ctx, protocol_fee)?;
 
+        // The fee left the vault: keep the shielded supply in step
+        debit_protocol_fee(
+            input_pool,
+            ctx.accounts.input_pool_stats.as_deref_mut().map(|stats| &mut **stats),
+            protocol_fee,
+            Clock::get()?.unix_timestamp,
+        )?;
+
         msg!("Protocol fee transferred: {} to treasury", protocol_fee);
     }
 
@@ -207,6 +241,32 @@ pub fn execute_swap<'info>(
     // Update state hash
     amm_pool.state_hash = amm_pool.compute_state_hash();
 
+    // Only the volume record bound in Phase 0 may be credited
+    let bound_volume = pending_op.input_pools[1];
+    require!(
+        ctx.accounts.swap_volume.as_ref().map_or([0u8; 32], |record| record.key().to_bytes()) == bound_volume,
+        CloakCraftError::SwapVolumeMismatch
+    );
+
+    // Accumulate volume (token A side) for fee rebate tiers
+    if let Some(swap_volume) = ctx.accounts.swap_volume.as_mut() {
+        let config = ctx.accounts.fee_rebate_config.as_ref()
+            .ok_or(CloakCraftError::InvalidRebateConfig)?;
+        require!(
+            config.amm_pool == amm_pool.key() && swap_volume.config == config.key(),
+            CloakCraftError::InvalidRebateConfig
+        );
+        let clock = Clock::get()?;
+        require!(
+            config.epoch_at(clock.unix_timestamp) == Some(swap_volume.epoch),
+            CloakCraftError::VolumeEpochMismatch
+        );
+
+        let volume = if swap_a_to_b { swap_amount } else { output_amount };
+        swap_volume.volume = swap_volume.volume.saturating_add(volume);
+        msg!("Swap volume recorded: {} (epoch {} total {})", volume, swap_volume.epoch, swap_volume.volume);
+    }
+
     msg!("✅ Swap executed");
     msg!("Amount to pool: {}, Protocol fee: {}", amount_to_pool, protocol_fee);
     msg!("New reserves: reserve_a={}, reserve_b={}", amm_pool.reserve_a, amm_pool.reserve_b);
@@ -0,0 +1,44 @@
+//! Execute Swap - Phase 3 (Committed or Sealed Swap)
+//!
+//! Reveals the parameters a committed or sealed swap hid in Phase 0 (for
+//! committed swaps including min_output), checks them against the stored
+//! commitment, then executes the swap exactly like
+//! execute_swap (same accounts and checks).
+
+use anchor_lang::prelude::*;
+
+use super::create_pending_with_proof_swap_committed::compute_swap_commitment;
+use super::execute_swap::{apply_swap, ExecuteSwap};
+use crate::errors::CloakCraftError;
+use crate::state::OperationKind;
+
+/// Phase 3: Reveal a committed swap and update AMM pool reserves
+pub fn execute_swap_revealed<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteSwap<'info>>,
+    _operation_id: [u8; 32],
+    swap_amount: u64,
+    output_amount: u64,
+    min_output: u64,
+    swap_a_to_b: bool,
+    salt: [u8; 32],
+) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::Swap)?;
+
+    let pending_op = &mut ctx.accounts.pending_operation;
+    require!(pending_op.call_hash != [0u8; 32], CloakCraftError::SwapCommitmentMismatch);
+    require!(
+        compute_swap_commitment(swap_amount, output_amount, min_output, swap_a_to_b, &salt)?
+            == pending_op.call_hash,
+        CloakCraftError::SwapCommitmentMismatch
+    );
+
+    // Sealed swaps already stored min_output, which their hash binds too
+    pending_op.min_output = min_output;
+    pending_op.swap_amount = swap_amount;
+    pending_op.output_amount = output_amount;
+    pending_op.swap_a_to_b = swap_a_to_b;
+    pending_op.output_amounts[0] = output_amount;
+    msg!("Swap revealed: amount {}, output {}", swap_amount, output_amount);
+
+    apply_swap(ctx)
+}
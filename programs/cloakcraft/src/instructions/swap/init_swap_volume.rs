@@ -0,0 +1,62 @@
+//! Initialize a swap volume record for a blinded tag
+//!
+//! Permissionless. Must be created for the current epoch before passing it
+//! to `execute_swap` to accumulate volume.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{FeeRebateConfig, SwapVolume};
+
+#[derive(Accounts)]
+#[instruction(volume_tag: [u8; 32], epoch: u64)]
+pub struct InitSwapVolume<'info> {
+    /// Fee rebate config
+    #[account(
+        seeds = [seeds::FEE_REBATE, fee_rebate_config.amm_pool.as_ref()],
+        bump = fee_rebate_config.bump,
+    )]
+    pub fee_rebate_config: Box<Account<'info, FeeRebateConfig>>,
+
+    /// Swap volume record to create
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + SwapVolume::INIT_SPACE,
+        seeds = [seeds::SWAP_VOLUME, fee_rebate_config.key().as_ref(), &epoch.to_le_bytes(), volume_tag.as_ref()],
+        bump,
+    )]
+    pub swap_volume: Box<Account<'info, SwapVolume>>,
+
+    /// Payer for account creation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn init_swap_volume(
+    ctx: Context<InitSwapVolume>,
+    volume_tag: [u8; 32],
+    epoch: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(
+        ctx.accounts.fee_rebate_config.epoch_at(clock.unix_timestamp) == Some(epoch),
+        CloakCraftError::VolumeEpochMismatch
+    );
+
+    let record = &mut ctx.accounts.swap_volume;
+    record.config = ctx.accounts.fee_rebate_config.key();
+    record.volume_tag = volume_tag;
+    record.epoch = epoch;
+    record.volume = 0;
+    record.claimed = false;
+    record.bump = ctx.bumps.swap_volume;
+
+    msg!("Swap volume record initialized for epoch {}", epoch);
+
+    Ok(())
+}
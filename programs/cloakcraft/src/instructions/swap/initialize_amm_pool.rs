@@ -3,11 +3,15 @@
 //! Supports two pool types:
 //! - ConstantProduct (default): x * y = k formula, best for volatile pairs
 //! - StableSwap: Curve-style formula, best for pegged assets (stablecoins)
+//!
+//! Creation is gated by ProtocolConfig: in allowlisted mode the authority must
+//! be on the PoolCreatorAllowlist, and any SOL creation fee goes to the treasury.
 
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
 use anchor_spl::token::{Mint, Token};
 
-use crate::state::{AmmPool, PoolType};
+use crate::state::{AmmPool, PoolType, ProtocolConfig, PoolCreatorAllowlist, AMM_CREATION_ALLOWLISTED};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 
@@ -45,10 +49,29 @@ pub struct InitializeAmmPool<'info> {
     /// Authority
     pub authority: Signer<'info>,
 
-    /// Payer
+    /// Payer (also pays the creation fee)
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    /// Protocol config (creation mode and fee)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Pool creator allowlist (required in allowlisted mode)
+    #[account(
+        seeds = [seeds::POOL_CREATOR_ALLOWLIST],
+        bump = pool_creator_allowlist.bump,
+    )]
+    pub pool_creator_allowlist: Option<Box<Account<'info, PoolCreatorAllowlist>>>,
+
+    /// Treasury (receives the creation fee, required if the fee is > 0)
+    /// CHECK: Address checked against protocol_config.treasury
+    #[account(mut)]
+    pub treasury: Option<UncheckedAccount<'info>>,
+
     /// System program
     pub system_program: Program<'info, System>,
 
@@ -81,6 +104,33 @@ pub fn initialize_amm_pool(
         );
     }
 
+    // Creation permissioning
+    let protocol_config = &ctx.accounts.protocol_config;
+    if protocol_config.amm_creation_mode == AMM_CREATION_ALLOWLISTED {
+        let allowlist = ctx.accounts.pool_creator_allowlist.as_ref()
+            .ok_or(CloakCraftError::PoolCreatorNotAllowed)?;
+        require!(
+            allowlist.contains(&ctx.accounts.authority.key()),
+            CloakCraftError::PoolCreatorNotAllowed
+        );
+    }
+    if protocol_config.amm_creation_fee_lamports > 0 {
+        let treasury = ctx.accounts.treasury.as_ref()
+            .ok_or(CloakCraftError::InvalidTreasury)?;
+        require_keys_eq!(treasury.key(), protocol_config.treasury, CloakCraftError::InvalidTreasury);
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: treasury.to_account_info(),
+                },
+            ),
+            protocol_config.amm_creation_fee_lamports,
+        )?;
+        msg!("AMM creation fee paid: {} lamports", protocol_config.amm_creation_fee_lamports);
+    }
+
     let amm_pool = &mut ctx.accounts.amm_pool;
 
     // LP mint is now initialized by Anchor via the init macro
@@ -0,0 +1,106 @@
+//! Migrate an AMM pool account to the current layout
+//!
+//! AmmPool gained fields (LP lock window, JIT penalty, pool version) after
+//! the first pools were created. Older accounts are too small to
+//! deserialize, so this reallocates them to `AmmPool::LEN` with the new
+//! fields zeroed: no LP lock, no JIT penalty, and version 0 (the original
+//! pool, whose PDA seeds carry no version suffix).
+//!
+//! The result doesn't depend on the caller, so anyone willing to pay the
+//! extra rent may migrate a pool.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+
+use crate::state::AmmPool;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+/// Emitted when an AMM pool account is reallocated to the current layout
+#[event]
+pub struct AmmPoolLayoutMigrated {
+    pub amm_pool: Pubkey,
+    pub old_len: u64,
+    pub new_len: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(token_a_mint: Pubkey, token_b_mint: Pubkey)]
+pub struct MigrateAmmPoolLayout<'info> {
+    /// AMM pool to migrate (older layout, checked manually)
+    /// CHECK: Discriminator is checked in the handler. Only original (version 0)
+    /// pools predate the current layout, so the seeds carry no version suffix.
+    #[account(
+        mut,
+        seeds = [seeds::AMM_POOL, token_a_mint.as_ref(), token_b_mint.as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub amm_pool: UncheckedAccount<'info>,
+
+    /// Payer for reallocation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program for reallocation
+    pub system_program: Program<'info, System>,
+}
+
+/// Reallocate an AMM pool to the current layout
+///
+/// # Arguments
+/// * `token_a_mint` - Pool's token A mint
+/// * `token_b_mint` - Pool's token B mint
+pub fn migrate_amm_pool_layout<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateAmmPoolLayout<'info>>,
+    _token_a_mint: Pubkey,
+    _token_b_mint: Pubkey,
+) -> Result<()> {
+    let pool_info = ctx.accounts.amm_pool.to_account_info();
+    let old_len = pool_info.data_len();
+
+    {
+        let data = pool_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == AmmPool::DISCRIMINATOR[..],
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+    }
+    require!(old_len < AmmPool::LEN, CloakCraftError::AccountAlreadyMigrated);
+
+    // Top up rent for the larger account
+    let rent_due = Rent::get()?
+        .minimum_balance(AmmPool::LEN)
+        .saturating_sub(pool_info.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: pool_info.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    // New fields are zero-filled, which is their disabled default
+    pool_info.resize(AmmPool::LEN)?;
+
+    // Sanity check: the reallocated account must deserialize
+    {
+        let data = pool_info.try_borrow_data()?;
+        AmmPool::try_deserialize(&mut &data[..])?;
+    }
+
+    emit!(AmmPoolLayoutMigrated {
+        amm_pool: pool_info.key(),
+        old_len: old_len as u64,
+        new_len: AmmPool::LEN as u64,
+    });
+    msg!("AMM pool {} migrated: {} -> {} bytes", pool_info.key(), old_len, AmmPool::LEN);
+
+    Ok(())
+}
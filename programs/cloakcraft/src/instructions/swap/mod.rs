@@ -1,23 +1,49 @@
 //! Swap instructions for internal AMM
 
 mod initialize_amm_pool;
+mod migrate_amm_pool_layout;
 mod add_liquidity;
 mod remove_liquidity;
 mod swap;
 mod create_pending_with_proof_swap;
 mod execute_swap;
+mod create_pending_with_proof_swap_committed;
+mod create_pending_with_proof_swap_sealed;
+mod execute_swap_revealed;
 mod create_pending_with_proof_remove_liquidity;
 mod execute_remove_liquidity;
+mod create_pending_with_proof_convert_amm_lp_note;
 mod create_pending_with_proof_add_liquidity;
 mod execute_add_liquidity;
+mod create_fee_rebate_config;
+mod init_swap_volume;
+mod create_pending_with_proof_claim_fee_rebate;
+mod execute_claim_fee_rebate;
+mod register_lp_lock;
+mod sync_reserves;
+mod convert_treasury_fees;
+mod quote_swap;
 
 pub use initialize_amm_pool::*;
+pub use migrate_amm_pool_layout::*;
 pub use add_liquidity::*;
 pub use remove_liquidity::*;
 pub use swap::*;
 pub use create_pending_with_proof_swap::*;
 pub use execute_swap::*;
+pub use create_pending_with_proof_swap_committed::*;
+pub use create_pending_with_proof_swap_sealed::*;
+pub use execute_swap_revealed::*;
 pub use create_pending_with_proof_remove_liquidity::*;
 pub use execute_remove_liquidity::*;
+pub use create_pending_with_proof_convert_amm_lp_note::*;
 pub use create_pending_with_proof_add_liquidity::*;
 pub use execute_add_liquidity::*;
+pub use create_fee_rebate_config::*;
+pub use init_swap_volume::*;
+pub use create_pending_with_proof_claim_fee_rebate::*;
+pub use execute_claim_fee_rebate::*;
+pub use register_lp_lock::*;
+pub use sync_reserves::*;
+pub use convert_treasury_fees::*;
+pub use quote_swap::*;
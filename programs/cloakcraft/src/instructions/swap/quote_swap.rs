@@ -0,0 +1,71 @@
+//! Quote Swap (read-only)
+//!
+//! Computes a swap's output, fees and price impact against current reserves
+//! with the same math `create_pending_with_proof_swap` and `execute_swap`
+//! use, and returns it as a `SwapQuote` (return data). UIs call it via
+//! simulation instead of re-implementing the formulas. Nothing is written.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{AmmPool, ProtocolConfig};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+/// Swap quote returned by `quote_swap` (return data)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// Output amount at current reserves (before slippage tolerance)
+    pub output_amount: u64,
+    /// Total pool fee on the input (LP share + protocol share)
+    pub fee_amount: u64,
+    /// Protocol's share of `fee_amount` (0 while fees are disabled)
+    pub protocol_fee: u64,
+    /// Rate shortfall against the spot rate, in bps
+    pub price_impact_bps: u16,
+}
+
+#[derive(Accounts)]
+pub struct QuoteSwap<'info> {
+    /// AMM pool
+    #[account(
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
+        bump = amm_pool.bump,
+    )]
+    pub amm_pool: Box<Account<'info, AmmPool>>,
+
+    /// Protocol config (protocol fee share)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+}
+
+/// Quote swapping `input_amount` in the given direction
+pub fn quote_swap(ctx: Context<QuoteSwap>, input_amount: u64, swap_a_to_b: bool) -> Result<SwapQuote> {
+    let amm_pool = &ctx.accounts.amm_pool;
+
+    let (output_amount, fee_amount) = amm_pool
+        .calculate_swap_output(input_amount, swap_a_to_b)
+        .ok_or(CloakCraftError::InvalidSwapOutput)?;
+    let (protocol_fee, _lp_fee) = ctx.accounts.protocol_config
+        .calculate_swap_fee(input_amount, amm_pool.fee_bps);
+
+    let quote = SwapQuote {
+        output_amount,
+        fee_amount,
+        protocol_fee,
+        price_impact_bps: amm_pool.price_impact_bps(input_amount, output_amount, swap_a_to_b),
+    };
+
+    msg!(
+        "Swap quote: in={}, out={}, fee={}, protocol_fee={}, impact={}bps",
+        input_amount,
+        quote.output_amount,
+        quote.fee_amount,
+        quote.protocol_fee,
+        quote.price_impact_bps
+    );
+
+    Ok(quote)
+}
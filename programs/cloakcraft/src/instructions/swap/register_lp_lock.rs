@@ -0,0 +1,101 @@
+//! Register an LP lock for an existing LP note
+//!
+//! Remove liquidity treats an LP note without a lock record as locked while the
+//! AMM pool has an LP lock configured, so notes not minted by add liquidity
+//! (transferred, converted from a previous pool version, or minted before the
+//! lock was configured) can't skip the lock window. Their holder registers a
+//! lock here, starting now, and removes penalty-free once it expires.
+//!
+//! The LP note must already exist in the LP pool, so a lock can't be
+//! registered ahead of time for a note that is only created later (e.g. the
+//! output of a transfer out of a freshly minted note).
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Pool, AmmPool, LpLock};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::instructions::generic::LightVerifyCommitmentParams;
+
+#[derive(Accounts)]
+#[instruction(lp_commitment: [u8; 32])]
+pub struct RegisterLpLock<'info> {
+    /// LP token pool the note belongs to
+    #[account(
+        seeds = [seeds::POOL, lp_pool.token_mint.as_ref()],
+        bump = lp_pool.bump,
+        constraint = lp_pool.token_mint == amm_pool.lp_mint @ CloakCraftError::InvalidTokenMint,
+    )]
+    pub lp_pool: Box<Account<'info, Pool>>,
+
+    /// AMM pool the LP tokens belong to
+    #[account(
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
+        bump = amm_pool.bump,
+    )]
+    pub amm_pool: Box<Account<'info, AmmPool>>,
+
+    /// LP lock for the note (created here)
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + LpLock::INIT_SPACE,
+        seeds = [seeds::LP_LOCK, lp_commitment.as_ref()],
+        bump,
+    )]
+    pub lp_lock: Box<Account<'info, LpLock>>,
+
+    /// Pays for the lock (refunded when remove liquidity closes it)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    // Light Protocol accounts via remaining_accounts (~8 accounts)
+}
+
+/// Register a lock, starting now, for an existing LP note
+pub fn register_lp_lock<'info>(
+    ctx: Context<'_, '_, '_, 'info, RegisterLpLock<'info>>,
+    lp_commitment: [u8; 32],
+    light_params: LightVerifyCommitmentParams,
+) -> Result<()> {
+    let lp_pool = &ctx.accounts.lp_pool;
+    let amm_pool = &ctx.accounts.amm_pool;
+    let slot = Clock::get()?.slot;
+
+    // SECURITY: The note's value is private, so any pinned threshold applies
+    if lp_pool.requires_pinned_inclusion(u64::MAX) {
+        require!(
+            !light_params.commitment_merkle_context.prove_by_index,
+            CloakCraftError::PinnedInclusionRequired
+        );
+    }
+
+    // The note must exist before it can be locked
+    crate::light_cpi::verify_commitment_inclusion(
+        &ctx.accounts.payer.to_account_info(),
+        ctx.remaining_accounts,
+        light_params.commitment_account_hash,
+        light_params.commitment_merkle_context,
+        light_params.commitment_inclusion_proof,
+        light_params.commitment_address_tree_info,
+        lp_commitment,
+        lp_pool.key(),
+        lp_pool.address_tree,
+        light_params.commitment_data_hash,
+    )?;
+
+    let lp_lock = &mut ctx.accounts.lp_lock;
+    lp_lock.amm_pool = amm_pool.key();
+    lp_lock.lp_commitment = lp_commitment;
+    lp_lock.created_slot = slot;
+    lp_lock.unlock_slot = slot.saturating_add(amm_pool.min_lp_lock_slots);
+    lp_lock.payer = ctx.accounts.payer.key();
+    lp_lock.bump = ctx.bumps.lp_lock;
+
+    msg!("LP lock registered: unlock at slot {}", lp_lock.unlock_slot);
+
+    Ok(())
+}
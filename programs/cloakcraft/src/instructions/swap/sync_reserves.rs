@@ -0,0 +1,195 @@
+//! Sync AMM reserves with surplus vault balance (permissionless)
+//!
+//! Tokens sent straight to a pool vault (donations, mistaken transfers) are
+//! not backed by any note, so the vault holds more than `pool.total_shielded`.
+//! Per `ProtocolConfig.surplus_policy` the surplus is either folded into this
+//! AMM pool's reserves (raising the LP share value) or swept to the treasury.
+//!
+//! Vaults are per token and may back several AMM pools. A vault's surplus is
+//! only folded into the AMM pool its token pool designates
+//! (`Pool.surplus_amm_pool`); a vault designating another pool is left as is,
+//! so the caller cannot pick which pool absorbs it.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+use crate::state::{Pool, AmmPool, ProtocolConfig, SURPLUS_POLICY_TREASURY};
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+#[derive(Accounts)]
+pub struct SyncReserves<'info> {
+    /// AMM pool state (reserves updated when folding)
+    #[account(
+        mut,
+        seeds = [seeds::AMM_POOL, amm_pool.token_a_mint.as_ref(), amm_pool.token_b_mint.as_ref(), amm_pool.version_seed()],
+        bump = amm_pool.bump,
+        constraint = amm_pool.is_active @ CloakCraftError::AmmPoolNotActive,
+    )]
+    pub amm_pool: Box<Account<'info, AmmPool>>,
+
+    /// Token A pool (authority for vault_a transfers)
+    #[account(
+        mut,
+        seeds = [seeds::POOL, amm_pool.token_a_mint.as_ref()],
+        bump = pool_a.bump,
+    )]
+    pub pool_a: Box<Account<'info, Pool>>,
+
+    /// Token B pool (authority for vault_b transfers)
+    #[account(
+        mut,
+        seeds = [seeds::POOL, amm_pool.token_b_mint.as_ref()],
+        bump = pool_b.bump,
+    )]
+    pub pool_b: Box<Account<'info, Pool>>,
+
+    /// Token A vault
+    #[account(
+        mut,
+        constraint = vault_a.key() == pool_a.token_vault @ CloakCraftError::InvalidVault,
+    )]
+    pub vault_a: Box<Account<'info, TokenAccount>>,
+
+    /// Token B vault
+    #[account(
+        mut,
+        constraint = vault_b.key() == pool_b.token_vault @ CloakCraftError::InvalidVault,
+    )]
+    pub vault_b: Box<Account<'info, TokenAccount>>,
+
+    /// Protocol config (surplus policy and treasury)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Treasury token A account (required when sweeping a token A surplus)
+    #[account(
+        mut,
+        constraint = treasury_ata_a.owner == protocol_config.treasury @ CloakCraftError::InvalidTreasury,
+        constraint = treasury_ata_a.mint == amm_pool.token_a_mint @ CloakCraftError::InvalidTreasury,
+    )]
+    pub treasury_ata_a: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Treasury token B account (required when sweeping a token B surplus)
+    #[account(
+        mut,
+        constraint = treasury_ata_b.owner == protocol_config.treasury @ CloakCraftError::InvalidTreasury,
+        constraint = treasury_ata_b.mint == amm_pool.token_b_mint @ CloakCraftError::InvalidTreasury,
+    )]
+    pub treasury_ata_b: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Token program for transfers
+    pub token_program: Program<'info, Token>,
+}
+
+/// Emitted when surplus vault balance is absorbed
+#[event]
+pub struct ReservesSynced {
+    pub amm_pool: Pubkey,
+    pub surplus_a: u64,
+    pub surplus_b: u64,
+    /// True if swept to the treasury, false if folded into reserves
+    pub swept: bool,
+}
+
+pub fn sync_reserves(ctx: Context<SyncReserves>) -> Result<()> {
+    let mut surplus_a = ctx.accounts.vault_a.amount.saturating_sub(ctx.accounts.pool_a.total_shielded);
+    let mut surplus_b = ctx.accounts.vault_b.amount.saturating_sub(ctx.accounts.pool_b.total_shielded);
+    let swept = ctx.accounts.protocol_config.surplus_policy == SURPLUS_POLICY_TREASURY;
+
+    msg!("Vault surplus: a={}, b={}", surplus_a, surplus_b);
+    if surplus_a == 0 && surplus_b == 0 {
+        return Ok(());
+    }
+
+    if swept {
+        sweep_surplus(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_a,
+            &ctx.accounts.vault_a,
+            ctx.accounts.treasury_ata_a.as_deref(),
+            surplus_a,
+        )?;
+        sweep_surplus(
+            &ctx.accounts.token_program,
+            &ctx.accounts.pool_b,
+            &ctx.accounts.vault_b,
+            ctx.accounts.treasury_ata_b.as_deref(),
+            surplus_b,
+        )?;
+        msg!("Surplus swept to treasury");
+    } else {
+        // Only the AMM pool a vault designates absorbs its surplus
+        let amm_pool_key = ctx.accounts.amm_pool.key();
+        if ctx.accounts.pool_a.surplus_amm_pool != amm_pool_key {
+            surplus_a = 0;
+        }
+        if ctx.accounts.pool_b.surplus_amm_pool != amm_pool_key {
+            surplus_b = 0;
+        }
+        msg!("Designated surplus: a={}, b={}", surplus_a, surplus_b);
+        if surplus_a == 0 && surplus_b == 0 {
+            return Ok(());
+        }
+
+        // The surplus is now owed to LPs, so it counts as shielded supply
+        ctx.accounts.amm_pool.absorb_surplus(surplus_a, surplus_b)
+            .ok_or(CloakCraftError::AmountOverflow)?;
+        let pool_a = &mut ctx.accounts.pool_a;
+        pool_a.total_shielded = pool_a.total_shielded
+            .checked_add(surplus_a)
+            .ok_or(CloakCraftError::AmountOverflow)?;
+        let pool_b = &mut ctx.accounts.pool_b;
+        pool_b.total_shielded = pool_b.total_shielded
+            .checked_add(surplus_b)
+            .ok_or(CloakCraftError::AmountOverflow)?;
+        msg!(
+            "Surplus folded into reserves: reserve_a={}, reserve_b={}",
+            ctx.accounts.amm_pool.reserve_a, ctx.accounts.amm_pool.reserve_b
+        );
+    }
+
+    emit!(ReservesSynced {
+        amm_pool: ctx.accounts.amm_pool.key(),
+        surplus_a,
+        surplus_b,
+        swept,
+    });
+
+    Ok(())
+}
+
+/// Transfer a vault surplus to the treasury, signed by the pool PDA
+fn sweep_surplus<'info>(
+    token_program: &Program<'info, Token>,
+    pool: &Account<'info, Pool>,
+    vault: &Account<'info, TokenAccount>,
+    treasury_ata: Option<&Account<'info, TokenAccount>>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let treasury_ata = treasury_ata.ok_or(CloakCraftError::InvalidTreasury)?;
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        seeds::POOL,
+        pool.token_mint.as_ref(),
+        &[pool.bump],
+    ]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: treasury_ata.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
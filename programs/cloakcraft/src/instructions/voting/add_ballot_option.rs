@@ -0,0 +1,88 @@
+//! Add an option to an open ballot (write-ins)
+//!
+//! Ballots created with option registration enabled accept new options until
+//! end_time, from their registrar or from anyone (see `OptionRegistration`),
+//! up to max_options. The option's metadata (name, description) stays off
+//! chain; only its hash is published, in a `BallotOptionAdded` event.
+//!
+//! Vote instructions check choices against the current num_options, so an
+//! option can be voted for as soon as it is added. Encrypted votes proven
+//! before it was added carry no contribution for it, which tallies as zero.
+//! Options past the first 16 need their options page created
+//! (create_ballot_options_page) before votes for them can be tallied.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{Ballot, BallotStatus, OptionRegistration};
+
+/// Emitted when an option is added to an open ballot
+#[event]
+pub struct BallotOptionAdded {
+    pub ballot_id: [u8; 32],
+    pub option: u8,
+    pub metadata_hash: [u8; 32],
+    pub registrar: Pubkey,
+}
+
+#[derive(Accounts)]
+#[instruction(ballot_id: [u8; 32])]
+pub struct AddBallotOption<'info> {
+    /// Ballot to add the option to
+    #[account(
+        mut,
+        seeds = [seeds::BALLOT, ballot_id.as_ref()],
+        bump = ballot.bump,
+    )]
+    pub ballot: Box<Account<'info, Ballot>>,
+
+    /// Ballot's option registrar, or anyone on Open ballots
+    pub registrar: Signer<'info>,
+}
+
+pub fn add_ballot_option(
+    ctx: Context<AddBallotOption>,
+    ballot_id: [u8; 32],
+    metadata_hash: [u8; 32],
+) -> Result<()> {
+    let ballot = &mut ctx.accounts.ballot;
+    let registrar = ctx.accounts.registrar.key();
+    let current_time = Clock::get()?.unix_timestamp;
+
+    match ballot.option_registration {
+        OptionRegistration::Closed => return Err(CloakCraftError::OptionRegistrationClosed.into()),
+        OptionRegistration::Registrar => {
+            require_keys_eq!(
+                registrar,
+                ballot.option_registrar,
+                CloakCraftError::UnauthorizedOptionRegistrar
+            );
+        }
+        OptionRegistration::Open => {}
+    }
+
+    // Options can be nominated before and during voting
+    if !matches!(ballot.status, BallotStatus::Pending | BallotStatus::Active)
+        || current_time >= ballot.end_time
+    {
+        return Err(CloakCraftError::VotingEnded.into());
+    }
+    if ballot.num_options >= ballot.max_options {
+        return Err(CloakCraftError::BallotOptionsFull.into());
+    }
+
+    let option = ballot.num_options;
+    ballot.num_options += 1;
+
+    emit!(BallotOptionAdded {
+        ballot_id,
+        option,
+        metadata_hash,
+        registrar,
+    });
+
+    msg!("Ballot option {} added by {}", option, registrar);
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! Append commitments to a ballot's snapshot tree
+//!
+//! The ballot's indexer or authority attests the commitments that existed at
+//! `snapshot_slot`, in batches. Every batch records a new retained root that
+//! snapshot votes passing the tree may prove against.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::helpers::field::assert_canonical;
+use crate::state::{Ballot, SnapshotLeavesAppended, SnapshotTree};
+
+#[derive(Accounts)]
+#[instruction(ballot_id: [u8; 32])]
+pub struct AppendSnapshotLeaves<'info> {
+    /// Ballot the tree belongs to
+    #[account(
+        seeds = [seeds::BALLOT, ballot_id.as_ref()],
+        bump = ballot.bump,
+    )]
+    pub ballot: Box<Account<'info, Ballot>>,
+
+    /// Snapshot tree
+    #[account(
+        mut,
+        seeds = [seeds::SNAPSHOT_TREE, ballot_id.as_ref()],
+        bump = snapshot_tree.bump,
+    )]
+    pub snapshot_tree: Box<Account<'info, SnapshotTree>>,
+
+    /// Ballot indexer or authority
+    #[account(
+        constraint = attester.key() == ballot.indexer_pubkey
+            || attester.key() == ballot.authority @ CloakCraftError::Unauthorized,
+    )]
+    pub attester: Signer<'info>,
+}
+
+pub fn append_snapshot_leaves(
+    ctx: Context<AppendSnapshotLeaves>,
+    ballot_id: [u8; 32],
+    leaves: Vec<[u8; 32]>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    if clock.slot < ctx.accounts.ballot.snapshot_slot {
+        return Err(CloakCraftError::SnapshotSlotNotReached.into());
+    }
+
+    // Leaves are commitments: field elements in canonical encoding
+    assert_canonical(&leaves)?;
+
+    let tree = &mut ctx.accounts.snapshot_tree;
+    let first_leaf_index = tree.leaf_count;
+    let root = tree.append(&leaves)?;
+
+    msg!("Appended {} snapshot leaves ({} total)", leaves.len(), tree.leaf_count);
+
+    emit!(SnapshotLeavesAppended {
+        ballot_id,
+        first_leaf_index,
+        leaves,
+        root,
+    });
+
+    Ok(())
+}
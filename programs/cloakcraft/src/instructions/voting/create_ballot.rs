@@ -4,13 +4,14 @@
 //! For SpendToVote mode, also creates a token vault.
 
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
 use anchor_spl::token::{Mint, Token, TokenAccount};
 
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 use crate::state::{
-    Ballot, BallotConfigInput, BallotStatus, RevealMode, VoteBindingMode,
-    MAX_BALLOT_OPTIONS, MAX_WEIGHT_FORMULA_OPS, MAX_WEIGHT_PARAMS,
+    Ballot, BallotConfigInput, BallotStatus, VoteBindingMode,
+    ELGAMAL_CIPHERTEXT_SIZE, MAX_BALLOT_OPTIONS, MAX_WEIGHT_FORMULA_OPS, MAX_WEIGHT_PARAMS,
 };
 
 #[derive(Accounts)]
@@ -41,7 +42,8 @@ pub struct CreateBallot<'info> {
     )]
     pub ballot_vault: Option<Account<'info, TokenAccount>>,
 
-    /// Authority who creates and can manage the ballot
+    /// Authority who creates and can manage the ballot (locks the proposal bond)
+    #[account(mut)]
     pub authority: Signer<'info>,
 
     /// Payer for account creation
@@ -64,7 +66,7 @@ pub fn create_ballot(
     let current_time = clock.unix_timestamp;
 
     // Validate configuration
-    validate_config(&config, current_time)?;
+    config.validate(current_time)?;
 
     let ballot = &mut ctx.accounts.ballot;
 
@@ -117,6 +119,23 @@ pub fn create_ballot(
         ballot.has_eligibility_root = false;
     }
 
+    // Snapshot root is registered after snapshot_slot via register_snapshot_root
+    ballot.snapshot_root = [0u8; 32];
+    ballot.has_snapshot_root = false;
+
+    // LP vote sources are added via register_lp_vote_source
+    ballot.lp_vote_source_count = 0;
+
+    // TWAB roots are registered via register_snapshot_root
+    ballot.twab_mode = config.twab_mode;
+    ballot.twab_num_roots = config.twab_num_roots;
+    ballot.twab_interval_slots = config.twab_interval_slots;
+    ballot.twab_root_count = 0;
+
+    // Lock the proposal bond on the ballot account (settled in resolve_ballot)
+    ballot.proposal_bond = config.proposal_bond;
+    ballot.bond_settled = false;
+
     // Set weight formula
     let formula_len = config.weight_formula.len().min(MAX_WEIGHT_FORMULA_OPS);
     for i in 0..formula_len {
@@ -172,77 +191,49 @@ pub fn create_ballot(
     }
 
     ballot.claim_deadline = config.claim_deadline;
-    ballot.bump = ctx.bumps.ballot;
+    // Options past the first 16 are paged (see create_ballot_options_page)
+    ballot.option_page_count = 0;
 
-    msg!("Ballot created: {:?}", ballot_id);
-    msg!("  Binding mode: {:?}", config.binding_mode);
-    msg!("  Reveal mode: {:?}", config.reveal_mode);
-    msg!("  Vote type: {:?}", config.vote_type);
-    msg!("  Resolution mode: {:?}", config.resolution_mode);
-    msg!("  Num options: {}", config.num_options);
+    // Write-ins (see add_ballot_option)
+    ballot.option_registration = config.option_registration;
+    ballot.option_registrar = config.option_registrar.unwrap_or_default();
+    ballot.max_options = config.max_options.max(config.num_options);
 
-    Ok(())
-}
+    // Turnout privacy (see execute_vote_snapshot)
+    ballot.hide_turnout = config.hide_turnout;
+    ballot.encrypted_vote_count = [0u8; ELGAMAL_CIPHERTEXT_SIZE];
 
-/// Validate ballot configuration
-fn validate_config(config: &BallotConfigInput, current_time: i64) -> Result<()> {
-    // Validate timing
-    if config.start_time >= config.end_time {
-        return Err(CloakCraftError::InvalidBallotTiming.into());
-    }
+    // Loser refunds (see create_pending_with_proof_claim_refund)
+    ballot.loser_refund_bps = config.loser_refund_bps;
 
-    // Validate num_options
-    if config.num_options == 0 || config.num_options as usize > MAX_BALLOT_OPTIONS {
-        return Err(CloakCraftError::InvalidNumOptions.into());
-    }
-
-    // Validate protocol fee
-    if config.protocol_fee_bps > 10000 {
-        return Err(CloakCraftError::ProtocolFeeExceedsMax.into());
-    }
+    // Oracle disputes (see dispute_resolution)
+    ballot.dispute_window_seconds = config.dispute_window_seconds;
+    ballot.dispute_bond = config.dispute_bond;
+    ballot.resolved_at = 0;
+    ballot.challenger = Pubkey::default();
 
-    // Validate weight formula length
-    if config.weight_formula.len() > MAX_WEIGHT_FORMULA_OPS {
-        return Err(CloakCraftError::WeightFormulaTooLong.into());
-    }
-
-    // Validate weight params length
-    if config.weight_params.len() > MAX_WEIGHT_PARAMS {
-        return Err(CloakCraftError::TooManyWeightParams.into());
-    }
-
-    // Validate timelock settings for encrypted modes
-    if config.reveal_mode == RevealMode::TimeLocked
-        || config.reveal_mode == RevealMode::PermanentPrivate
-    {
-        // time_lock_pubkey must be set
-        if config.time_lock_pubkey == [0u8; 32] {
-            return Err(CloakCraftError::InvalidDecryptionKey.into());
-        }
-
-        // unlock_slot must be after end_time (converted to slot estimate)
-        // Note: This is a rough check; actual slot timing may vary
-        if config.unlock_slot == 0 {
-            return Err(CloakCraftError::TimelockNotExpired.into());
-        }
-    }
+    ballot.bump = ctx.bumps.ballot;
 
-    // Validate snapshot_slot for Snapshot mode
-    if config.binding_mode == VoteBindingMode::Snapshot {
-        // For Snapshot mode, snapshot_slot should be set
-        // (could be in the past or future depending on use case)
-        if config.snapshot_slot == 0 {
-            return Err(CloakCraftError::InvalidSnapshotSlot.into());
-        }
+    if config.proposal_bond > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.ballot.to_account_info(),
+                },
+            ),
+            config.proposal_bond,
+        )?;
     }
 
-    // Validate claim_deadline for SpendToVote mode
-    if config.binding_mode == VoteBindingMode::SpendToVote {
-        // claim_deadline should be after end_time if set
-        if config.claim_deadline != 0 && config.claim_deadline <= config.end_time {
-            return Err(CloakCraftError::InvalidBallotTiming.into());
-        }
-    }
+    msg!("Ballot created: {:?}", ballot_id);
+    msg!("  Binding mode: {:?}", config.binding_mode);
+    msg!("  Reveal mode: {:?}", config.reveal_mode);
+    msg!("  Vote type: {:?}", config.vote_type);
+    msg!("  Resolution mode: {:?}", config.resolution_mode);
+    msg!("  Num options: {}", config.num_options);
+    msg!("  Proposal bond: {}", config.proposal_bond);
 
     Ok(())
 }
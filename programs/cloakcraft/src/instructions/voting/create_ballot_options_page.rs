@@ -0,0 +1,67 @@
+//! Create a page of ballot options
+//!
+//! Ballots with more than 16 options tally options 16-63 in
+//! `BallotOptionsPage` accounts (see `state::ballot_options_page`).
+//! Permissionless, so a ballot can't be left unresolvable for want of a
+//! page; pages start empty and may be created in any order.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{Ballot, BallotOptionsPage, OPTIONS_PER_PAGE};
+
+#[derive(Accounts)]
+#[instruction(ballot_id: [u8; 32], page: u8)]
+pub struct CreateBallotOptionsPage<'info> {
+    /// Ballot the page belongs to
+    #[account(
+        mut,
+        seeds = [seeds::BALLOT, ballot_id.as_ref()],
+        bump = ballot.bump,
+        constraint = page >= 1 && page <= ballot.option_pages_required() @ CloakCraftError::InvalidBallotOptionsPage,
+    )]
+    pub ballot: Box<Account<'info, Ballot>>,
+
+    /// Options page
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + BallotOptionsPage::INIT_SPACE,
+        seeds = [seeds::BALLOT_OPTIONS, ballot_id.as_ref(), &[page]],
+        bump,
+    )]
+    pub options_page: Box<Account<'info, BallotOptionsPage>>,
+
+    /// Payer for the page
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_ballot_options_page(
+    ctx: Context<CreateBallotOptionsPage>,
+    ballot_id: [u8; 32],
+    page: u8,
+) -> Result<()> {
+    let options_page = &mut ctx.accounts.options_page;
+    options_page.ballot_id = ballot_id;
+    options_page.page = page;
+    options_page.option_weights = [0u64; OPTIONS_PER_PAGE];
+    options_page.option_amounts = [0u64; OPTIONS_PER_PAGE];
+    options_page.bump = ctx.bumps.options_page;
+
+    let ballot = &mut ctx.accounts.ballot;
+    ballot.option_page_count += 1;
+
+    msg!(
+        "Ballot options page {} created ({}/{})",
+        page,
+        ballot.option_page_count,
+        ballot.option_pages_required()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,328 @@
+//! Create Pending with Proof - Multi-Position Claim (Phase 0)
+//!
+//! SpendToVote mode only: claims the payouts of 2-3 positions of the same
+//! owner into a single payout commitment (claim_multi circuit), the way
+//! consolidation merges notes. Each position's payout is checked against
+//! `calculate_payout` of its own weight, so rounding matches separate claims.
+//!
+//! Flow:
+//! Phase 0 (this): Verify ZK proof + Create PendingOperation
+//! Phase 1: verify_vote_commitment_exists (for each position)
+//! Phase 2: create_nullifier_and_pending (for each position_nullifier)
+//! Phase 3: execute_claim - Transfer the summed fee
+//! Phase 4: create_commitment for payout_commitment
+//! Phase 5: close_pending_operation
+
+use anchor_lang::prelude::*;
+
+use crate::constants::{operation_types, seeds, GROTH16_PROOF_SIZE};
+use crate::errors::CloakCraftError;
+use crate::helpers::proof::verify_groth16_proof_metered;
+use crate::helpers::field::{pubkey_to_field, assert_canonical};
+use crate::state::{
+    Ballot, BallotStatus, PendingOperation, RevealMode, VoteBindingMode, VerificationKey,
+    ProtocolConfig, ProgramVersion, CircuitStats, OperationCredit,
+};
+
+/// Positions the claim_multi circuit takes
+pub const CLAIM_MULTI_MAX_POSITIONS: usize = 3;
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32], ballot_id: [u8; 32])]
+pub struct CreatePendingWithProofClaimMulti<'info> {
+    /// Ballot (must be resolved)
+    #[account(
+        seeds = [seeds::BALLOT, ballot_id.as_ref()],
+        bump = ballot.bump,
+        constraint = ballot.binding_mode == VoteBindingMode::SpendToVote @ CloakCraftError::ClaimsNotAllowed,
+        constraint = ballot.status == BallotStatus::Resolved @ CloakCraftError::BallotNotResolved,
+    )]
+    pub ballot: Box<Account<'info, Ballot>>,
+
+    /// Verification key for the claim_multi circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, crate::constants::circuits::CLAIM_MULTI.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Pending operation account (created)
+    #[account(
+        init,
+        payer = payer,
+        space = PendingOperation::SPACE,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer executing the transaction
+    pub relayer: Signer<'info>,
+
+    /// Payer for account creation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+/// One position of a multi-position claim
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ClaimPosition {
+    pub position_commitment: [u8; 32],
+    pub position_nullifier: [u8; 32],
+    /// Vote choice (Public/TimeLocked modes, 0 otherwise)
+    pub user_vote_choice: u64,
+    pub user_weight: u64,
+    /// Gross payout of this position (0 if it didn't vote for the winner)
+    pub gross_payout: u64,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_pending_with_proof_claim_multi<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofClaimMulti<'info>>,
+    operation_id: [u8; 32],
+    ballot_id: [u8; 32],
+    proof: Vec<u8>,
+    positions: Vec<ClaimPosition>,
+    payout_commitment: [u8; 32],
+    net_payout: u64,
+    output_randomness: [u8; 32],
+    client_version: u32,
+) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    require!(
+        positions.len() >= 2 && positions.len() <= CLAIM_MULTI_MAX_POSITIONS,
+        CloakCraftError::InvalidClaimPositionCount
+    );
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[payout_commitment, output_randomness])?;
+    for position in &positions {
+        assert_canonical(&[position.position_commitment, position.position_nullifier])?;
+    }
+
+    for (i, position) in positions.iter().enumerate() {
+        require!(
+            !positions[..i].iter().any(|other| other.position_nullifier == position.position_nullifier
+                || other.position_commitment == position.position_commitment),
+            CloakCraftError::DuplicateClaimPosition
+        );
+    }
+
+    let ballot = &ctx.accounts.ballot;
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp;
+
+    // Oracle outcomes are claimable once they can no longer be disputed
+    if !ballot.outcome_final(current_time) {
+        return Err(CloakCraftError::DisputeWindowOpen.into());
+    }
+
+    // Verify claim deadline hasn't passed
+    if ballot.claim_deadline > 0 && current_time >= ballot.claim_deadline {
+        return Err(CloakCraftError::ClaimDeadlinePassed.into());
+    }
+
+    // Verify proof length
+    if proof.len() != GROTH16_PROOF_SIZE {
+        return Err(CloakCraftError::InvalidProofLength.into());
+    }
+
+    // Verify ballot has an outcome
+    if !ballot.has_outcome {
+        return Err(CloakCraftError::BallotNotResolved.into());
+    }
+
+    let public_choices =
+        ballot.reveal_mode == RevealMode::Public || ballot.reveal_mode == RevealMode::TimeLocked;
+
+    // Verify each position's payout: the circuit proves losers are paid 0,
+    // and public choices let us check the winners here too
+    let mut gross_payout = 0u64;
+    let mut expected_net = 0u64;
+    let mut total_weight = 0u64;
+    for position in &positions {
+        let (expected_gross, position_net) = ballot.calculate_payout(position.user_weight);
+        let winner = ballot.is_winner(position.user_vote_choice, ballot.outcome);
+
+        if public_choices && !winner && position.gross_payout != 0 {
+            return Err(CloakCraftError::InvalidOutcomeValue.into());
+        }
+        if position.gross_payout != 0 || (public_choices && winner) {
+            // Allow small rounding differences (1 token)
+            if position.gross_payout.abs_diff(expected_gross) > 1 {
+                msg!("Gross payout mismatch: expected {}, got {}", expected_gross, position.gross_payout);
+                return Err(CloakCraftError::InvalidOutcomeValue.into());
+            }
+            expected_net = expected_net.saturating_add(position_net);
+        }
+
+        gross_payout = gross_payout
+            .checked_add(position.gross_payout)
+            .ok_or(CloakCraftError::AmountOverflow)?;
+        total_weight = total_weight.saturating_add(position.user_weight);
+    }
+
+    if net_payout > gross_payout || net_payout.abs_diff(expected_net) > positions.len() as u64 {
+        msg!("Net payout mismatch: expected {}, got {}", expected_net, net_payout);
+        return Err(CloakCraftError::InvalidOutcomeValue.into());
+    }
+
+    // Build public inputs for ZK proof verification
+    let public_inputs = build_public_inputs(
+        ballot,
+        &ballot_id,
+        &positions,
+        &payout_commitment,
+        gross_payout,
+        net_payout,
+    );
+
+    // Verify ZK proof
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "claim_multi",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
+
+    // Initialize pending operation
+    let pending_op = &mut ctx.accounts.pending_operation;
+    pending_op.bump = ctx.bumps.pending_operation;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
+    pending_op.relayer = ctx.accounts.relayer.key();
+    pending_op.operation_type = operation_types::CLAIM;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
+    pending_op.proof_verified = true;
+
+    // Store positions as inputs (ballot_id as their input pool)
+    pending_op.num_inputs = positions.len() as u8;
+    for (i, position) in positions.iter().enumerate() {
+        pending_op.input_commitments[i] = position.position_commitment;
+        pending_op.expected_nullifiers[i] = position.position_nullifier;
+        pending_op.input_pools[i] = ballot_id;
+    }
+    pending_op.inputs_verified_mask = 0;
+    pending_op.nullifier_completed_mask = 0;
+
+    // Store payout_commitment as the single output
+    pending_op.commitments[0] = payout_commitment;
+    pending_op.num_commitments = 1;
+    pending_op.completed_mask = 0;
+
+    // Store output data
+    pending_op.output_randomness[0] = output_randomness;
+    pending_op.output_amounts[0] = net_payout;
+
+    // Same layout as a single claim, so execute_claim applies unchanged
+    // swap_amount = gross_payout, output_amount = net_payout, extra_amount = total weight
+    pending_op.swap_amount = gross_payout;
+    pending_op.output_amount = net_payout;
+    pending_op.extra_amount = total_weight;
+
+    // Store fee amount
+    pending_op.fee_amount = gross_payout.saturating_sub(net_payout);
+
+    // Set expiry
+    pending_op.created_at = current_time;
+    pending_op.expires_at = current_time + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
+
+    msg!("Multi-position claim pending operation created");
+    msg!("  Positions: {}", positions.len());
+    msg!("  Total weight: {}", total_weight);
+    msg!("  Gross payout: {}, Net payout: {}", gross_payout, net_payout);
+
+    Ok(())
+}
+
+/// Build public inputs array for ZK proof verification
+///
+/// Unused position slots are zero, as the circuit expects.
+fn build_public_inputs(
+    ballot: &Ballot,
+    ballot_id: &[u8; 32],
+    positions: &[ClaimPosition],
+    payout_commitment: &[u8; 32],
+    gross_payout: u64,
+    net_payout: u64,
+) -> Vec<[u8; 32]> {
+    let field = |value: u64| {
+        let mut bytes = [0u8; 32];
+        bytes[24..32].copy_from_slice(&value.to_be_bytes());
+        bytes
+    };
+    let per_position = |get: &dyn Fn(&ClaimPosition) -> [u8; 32]| {
+        (0..CLAIM_MULTI_MAX_POSITIONS)
+            .map(|i| positions.get(i).map_or([0u8; 32], get))
+            .collect::<Vec<_>>()
+    };
+    let public_choices =
+        ballot.reveal_mode == RevealMode::Public || ballot.reveal_mode == RevealMode::TimeLocked;
+
+    let mut inputs = Vec::new();
+
+    inputs.push(*ballot_id);
+    inputs.extend(per_position(&|p| p.position_commitment));
+    inputs.extend(per_position(&|p| p.position_nullifier));
+    inputs.push(*payout_commitment);
+
+    // Payout amounts
+    inputs.push(field(gross_payout));
+    inputs.push(field(net_payout));
+
+    // Vote type (needed for circuit to know which winner check to perform)
+    inputs.push(field(ballot.vote_type as u64));
+
+    // Choices are public in Public/TimeLocked modes, private otherwise
+    inputs.extend(per_position(&|p| field(if public_choices { p.user_vote_choice } else { 0 })));
+    inputs.push(field(!public_choices as u64));
+
+    inputs.extend(per_position(&|p| field(p.user_weight)));
+    inputs.extend(per_position(&|p| field(p.gross_payout)));
+
+    inputs.push(field(ballot.outcome as u64));
+    inputs.push(field(ballot.pool_balance));
+    inputs.push(field(ballot.winner_weight));
+    inputs.push(field(ballot.protocol_fee_bps as u64));
+    inputs.push(pubkey_to_field(&ballot.token_mint));
+
+    inputs
+}
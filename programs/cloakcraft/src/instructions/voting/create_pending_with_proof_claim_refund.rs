@@ -0,0 +1,261 @@
+//! Create Pending with Proof - Claim Refund (Phase 0)
+//!
+//! SpendToVote ballots with `loser_refund_bps > 0`: losing positions claim
+//! back that share of their stake, so a lost vote is locked rather than lost.
+//! Refund = (user_weight / total_weight) * total_pool * loser_refund_bps,
+//! reserved out of the pool before winners share the rest (see
+//! `Ballot::calculate_refund`). Refunds pay no protocol fee.
+//!
+//! The claim_refund circuit proves the position did not vote for the winner,
+//! and it nullifies the position like a claim does, so each position gets a
+//! payout or a refund, never both.
+//!
+//! Flow:
+//! Phase 0 (this): Verify ZK proof + Create PendingOperation
+//! Phase 1: verify_vote_commitment_exists for position
+//! Phase 2: create_nullifier_and_pending for position_nullifier
+//! Phase 3: execute_claim - Track the refund as distributed
+//! Phase 4: create_commitment for refund_commitment
+//! Phase 5: close_pending_operation
+
+use anchor_lang::prelude::*;
+
+use crate::constants::{operation_types, seeds, GROTH16_PROOF_SIZE};
+use crate::errors::CloakCraftError;
+use crate::helpers::proof::verify_groth16_proof_metered;
+use crate::helpers::field::{pubkey_to_field, u64_to_field, assert_canonical};
+use crate::state::{
+    Ballot, BallotStatus, PendingOperation, RevealMode, VoteBindingMode, VerificationKey,
+    ProtocolConfig, ProgramVersion, CircuitStats, OperationCredit,
+};
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32], ballot_id: [u8; 32])]
+pub struct CreatePendingWithProofClaimRefund<'info> {
+    /// Ballot (must be resolved)
+    #[account(
+        seeds = [seeds::BALLOT, ballot_id.as_ref()],
+        bump = ballot.bump,
+        constraint = ballot.binding_mode == VoteBindingMode::SpendToVote @ CloakCraftError::ClaimsNotAllowed,
+        constraint = ballot.status == BallotStatus::Resolved @ CloakCraftError::BallotNotResolved,
+    )]
+    pub ballot: Box<Account<'info, Ballot>>,
+
+    /// Verification key for the claim_refund circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, crate::constants::circuits::CLAIM_REFUND.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Pending operation account (created)
+    #[account(
+        init,
+        payer = payer,
+        space = PendingOperation::SPACE,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer executing the transaction
+    pub relayer: Signer<'info>,
+
+    /// Payer for account creation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_pending_with_proof_claim_refund<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofClaimRefund<'info>>,
+    operation_id: [u8; 32],
+    ballot_id: [u8; 32],
+    proof: Vec<u8>,
+    // Public inputs from ZK proof
+    position_commitment: [u8; 32],
+    position_nullifier: [u8; 32],
+    refund_commitment: [u8; 32],
+    user_vote_choice: u64,          // For public/TimeLocked modes
+    user_weight: u64,
+    refund_amount: u64,
+    // Output data
+    output_randomness: [u8; 32],
+    client_version: u32,
+) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[position_commitment, position_nullifier, refund_commitment, output_randomness])?;
+
+    let ballot = &ctx.accounts.ballot;
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp;
+
+    require!(ballot.loser_refund_bps > 0, CloakCraftError::LoserRefundsDisabled);
+
+    // Oracle outcomes are claimable once they can no longer be disputed
+    if !ballot.outcome_final(current_time) {
+        return Err(CloakCraftError::DisputeWindowOpen.into());
+    }
+
+    // Verify claim deadline hasn't passed
+    if ballot.claim_deadline > 0 && current_time >= ballot.claim_deadline {
+        return Err(CloakCraftError::ClaimDeadlinePassed.into());
+    }
+
+    // Verify proof length
+    if proof.len() != GROTH16_PROOF_SIZE {
+        return Err(CloakCraftError::InvalidProofLength.into());
+    }
+
+    // Verify ballot has an outcome
+    if !ballot.has_outcome {
+        return Err(CloakCraftError::BallotNotResolved.into());
+    }
+
+    // For public/TimeLocked modes, the choice is known: winners claim payouts
+    let public_choice =
+        ballot.reveal_mode == RevealMode::Public || ballot.reveal_mode == RevealMode::TimeLocked;
+    if public_choice && ballot.is_winner(user_vote_choice, ballot.outcome) {
+        return Err(CloakCraftError::RefundForWinner.into());
+    }
+
+    // Verify refund calculation (1 token rounding tolerance, like claims)
+    let expected_refund = ballot.calculate_refund(user_weight);
+    if refund_amount.abs_diff(expected_refund) > 1 || refund_amount > ballot.loser_refund_reserve() {
+        msg!("Refund mismatch: expected {}, got {}", expected_refund, refund_amount);
+        return Err(CloakCraftError::InvalidOutcomeValue.into());
+    }
+
+    // Build public inputs for ZK proof verification
+    let public_inputs = build_public_inputs(
+        ballot,
+        &ballot_id,
+        &position_commitment,
+        &position_nullifier,
+        &refund_commitment,
+        if public_choice { user_vote_choice } else { 0 },
+        user_weight,
+        refund_amount,
+    );
+
+    // Verify ZK proof
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "claim_refund",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
+
+    // Initialize pending operation
+    let pending_op = &mut ctx.accounts.pending_operation;
+    pending_op.bump = ctx.bumps.pending_operation;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
+    pending_op.relayer = ctx.accounts.relayer.key();
+    pending_op.operation_type = operation_types::CLAIM;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
+    pending_op.proof_verified = true;
+
+    // Store position as input commitment (ballot_id as its input pool)
+    pending_op.input_commitments[0] = position_commitment;
+    pending_op.expected_nullifiers[0] = position_nullifier;
+    pending_op.num_inputs = 1;
+    pending_op.inputs_verified_mask = 0;
+    pending_op.nullifier_completed_mask = 0;
+    pending_op.input_pools[0] = ballot_id;
+
+    // Store refund_commitment as output
+    pending_op.commitments[0] = refund_commitment;
+    pending_op.num_commitments = 1;
+    pending_op.completed_mask = 0;
+
+    // Store output data
+    pending_op.output_randomness[0] = output_randomness;
+    pending_op.output_amounts[0] = refund_amount;
+
+    // Same layout as a claim with no fee, so execute_claim applies unchanged
+    pending_op.swap_amount = refund_amount;
+    pending_op.output_amount = refund_amount;
+    pending_op.extra_amount = user_weight;
+    pending_op.fee_amount = 0;
+
+    // Set expiry
+    pending_op.created_at = current_time;
+    pending_op.expires_at = current_time + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
+
+    msg!("Claim refund pending operation created");
+    msg!("  Position: {:?}", position_commitment);
+    msg!("  User weight: {}", user_weight);
+    msg!("  Refund: {}", refund_amount);
+
+    Ok(())
+}
+
+/// Build public inputs array for ZK proof verification
+/// Must match the claim_refund circuit's public inputs in order
+#[allow(clippy::too_many_arguments)]
+fn build_public_inputs(
+    ballot: &Ballot,
+    ballot_id: &[u8; 32],
+    position_commitment: &[u8; 32],
+    position_nullifier: &[u8; 32],
+    refund_commitment: &[u8; 32],
+    user_vote_choice: u64,
+    user_weight: u64,
+    refund_amount: u64,
+) -> Vec<[u8; 32]> {
+    let is_private = ballot.reveal_mode == RevealMode::PermanentPrivate;
+
+    vec![
+        *ballot_id,
+        *position_commitment,
+        *position_nullifier,
+        *refund_commitment,
+        u64_to_field(refund_amount),
+        u64_to_field(ballot.vote_type as u64),
+        u64_to_field(user_vote_choice),
+        u64_to_field(is_private as u64),
+        u64_to_field(user_weight),
+        u64_to_field(ballot.outcome as u64),
+        pubkey_to_field(&ballot.token_mint),
+    ]
+}
@@ -14,11 +14,12 @@ use anchor_lang::prelude::*;
 
 use crate::constants::{operation_types, seeds, GROTH16_PROOF_SIZE};
 use crate::errors::CloakCraftError;
-use crate::helpers::field::{bytes_to_field, pubkey_to_field, u64_to_field};
-use crate::helpers::proof::verify_groth16_proof;
+use crate::helpers::field::{bytes_to_field, pubkey_to_field, u64_to_field, assert_canonical};
+use crate::helpers::proof::verify_groth16_proof_metered;
 use crate::state::{
-    Ballot, BallotStatus, PendingOperation, RevealMode, VoteBindingMode, VerificationKey,
-    MAX_PENDING_COMMITMENTS, PENDING_OPERATION_EXPIRY_SECONDS,
+    Ballot, BallotStatus, PendingOperation, RevealMode, TwabMode, VoteBindingMode, VerificationKey,
+    ProtocolConfig, ProgramVersion, CircuitStats,
+    SnapshotTree, OperationCredit,
 };
 
 /// Encrypted contributions for tally update (encrypted modes only)
@@ -45,8 +46,9 @@ pub struct CreatePendingWithProofVoteSnapshot<'info> {
     #[account(
         seeds = [seeds::VERIFICATION_KEY, crate::constants::circuits::VOTE_SNAPSHOT.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
-    pub verification_key: Account<'info, VerificationKey>,
+    pub verification_key: Box<Account<'info, VerificationKey>>,
 
     /// Pending operation account (created)
     #[account(
@@ -65,6 +67,38 @@ pub struct CreatePendingWithProofVoteSnapshot<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Snapshot tree (optional, accepts any retained tree root instead of the registered root)
+    #[account(
+        seeds = [seeds::SNAPSHOT_TREE, ballot_id.as_ref()],
+        bump = snapshot_tree.bump,
+    )]
+    pub snapshot_tree: Option<Box<Account<'info, SnapshotTree>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
     /// System program
     pub system_program: Program<'info, System>,
 }
@@ -89,7 +123,22 @@ pub fn create_pending_with_proof_vote_snapshot<'info>(
     encrypted_preimage: Option<Vec<u8>>,
     // Output data
     output_randomness: [u8; 32],
+    // LP mint of the note (None = ballot token)
+    lp_mint: Option<Pubkey>,
+    client_version: u32,
 ) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[note_commitment, vote_nullifier, vote_commitment, output_randomness])?;
+
     let ballot = &ctx.accounts.ballot;
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp;
@@ -131,9 +180,31 @@ pub fn create_pending_with_proof_vote_snapshot<'info>(
         return Err(CloakCraftError::ZeroAmount.into());
     }
 
+    // TWAB ballots average several roots (create_pending_with_proof_vote_twab)
+    if ballot.twab_mode != TwabMode::Disabled {
+        return Err(CloakCraftError::TwabVoteRequired.into());
+    }
+
+    // Votes prove against a retained snapshot tree root when the tree is
+    // passed, otherwise against the registered archived root
+    match ctx.accounts.snapshot_tree.as_ref() {
+        Some(tree) => tree.check_root(&snapshot_merkle_root)?,
+        None => ballot.check_snapshot_root(&snapshot_merkle_root)?,
+    }
+
+    // LP notes count at the registered rate in ballot token units
+    let (note_mint, counted_amount) = match lp_mint {
+        Some(mint) => (mint, ballot.convert_lp_amount(&mint, amount)?),
+        None => (ballot.token_mint, amount),
+    };
+    if counted_amount == 0 {
+        return Err(CloakCraftError::ZeroAmount.into());
+    }
+
     // Build public inputs for ZK proof verification
     let public_inputs = build_public_inputs(
         ballot,
+        &note_mint,
         &ballot_id,
         &snapshot_merkle_root,
         &note_commitment,
@@ -145,19 +216,21 @@ pub fn create_pending_with_proof_vote_snapshot<'info>(
     )?;
 
     // Verify ZK proof
-    verify_groth16_proof(
+    verify_groth16_proof_metered(
         &proof,
         &ctx.accounts.verification_key.vk_data,
         &public_inputs,
         "vote_snapshot",
+        ctx.accounts.circuit_stats.as_deref_mut(),
     )?;
 
     // Initialize pending operation
     let pending_op = &mut ctx.accounts.pending_operation;
     pending_op.bump = ctx.bumps.pending_operation;
-    pending_op.operation_id = operation_id;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
     pending_op.relayer = ctx.accounts.relayer.key();
     pending_op.operation_type = operation_types::VOTE_SNAPSHOT;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
     pending_op.proof_verified = true;
 
     // Store note_commitment as input commitment (verified in Phase 1 via Light Protocol)
@@ -186,16 +259,18 @@ pub fn create_pending_with_proof_vote_snapshot<'info>(
 
     // Store vote-specific data in operation fields
     // Using swap_amount for vote_choice, output_amount for weight, extra_amount for amount
+    // (LP amounts converted to ballot token units)
     pending_op.swap_amount = vote_choice;
     pending_op.output_amount = weight;
-    pending_op.extra_amount = amount;
+    pending_op.extra_amount = counted_amount;
 
-    // Note: snapshot_merkle_root is verified in the ZK circuit, not stored on-chain
-    // The circuit proves the note exists in the merkle tree at snapshot slot
+    // Note: the circuit proves the note exists under snapshot_merkle_root, which
+    // is pinned to ballot.snapshot_root (or a retained snapshot tree root)
 
     // Set expiry
     pending_op.created_at = current_time;
-    pending_op.expires_at = current_time + PENDING_OPERATION_EXPIRY_SECONDS;
+    pending_op.expires_at = current_time + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
 
     msg!("Vote snapshot pending operation created (note-based)");
     msg!("  Operation ID: {:?}", operation_id);
@@ -225,6 +300,7 @@ pub fn create_pending_with_proof_vote_snapshot<'info>(
 /// All 32-byte inputs are reduced modulo BN254 scalar field to match circuit field elements.
 fn build_public_inputs(
     ballot: &Ballot,
+    note_mint: &Pubkey,
     ballot_id: &[u8; 32],
     snapshot_merkle_root: &[u8; 32],
     note_commitment: &[u8; 32],
@@ -257,8 +333,8 @@ fn build_public_inputs(
     // 7. weight - u64 is always < field modulus
     inputs.push(u64_to_field(weight));
 
-    // 8. token_mint - Solana pubkey, reduce to field element
-    inputs.push(pubkey_to_field(&ballot.token_mint));
+    // 8. token_mint - ballot token or registered LP mint, reduce to field element
+    inputs.push(pubkey_to_field(note_mint));
 
     // 9. eligibility_root (always included, 0 if no eligibility)
     inputs.push(bytes_to_field(&ballot.eligibility_root));
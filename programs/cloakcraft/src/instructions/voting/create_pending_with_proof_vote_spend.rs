@@ -15,10 +15,11 @@ use anchor_lang::prelude::*;
 
 use crate::constants::{operation_types, seeds, GROTH16_PROOF_SIZE};
 use crate::errors::CloakCraftError;
-use crate::helpers::proof::verify_groth16_proof;
+use crate::helpers::proof::verify_groth16_proof_metered;
+use crate::helpers::field::assert_canonical;
 use crate::state::{
     Ballot, BallotStatus, PendingOperation, Pool, RevealMode, VoteBindingMode, VerificationKey,
-    PENDING_OPERATION_EXPIRY_SECONDS,
+    ProtocolConfig, ProgramVersion, CircuitStats, OperationCredit,
 };
 
 use super::create_pending_with_proof_vote_snapshot::EncryptedContributions;
@@ -45,8 +46,9 @@ pub struct CreatePendingWithProofVoteSpend<'info> {
     #[account(
         seeds = [seeds::VERIFICATION_KEY, crate::constants::circuits::VOTE_SPEND.as_ref()],
         bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
     )]
-    pub verification_key: Account<'info, VerificationKey>,
+    pub verification_key: Box<Account<'info, VerificationKey>>,
 
     /// Pending operation account (created)
     #[account(
@@ -65,6 +67,31 @@ pub struct CreatePendingWithProofVoteSpend<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
     /// System program
     pub system_program: Program<'info, System>,
 }
@@ -89,7 +116,20 @@ pub fn create_pending_with_proof_vote_spend<'info>(
     encrypted_preimage: Option<Vec<u8>>,
     // Output data
     output_randomness: [u8; 32],
+    client_version: u32,
 ) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[input_commitment, spending_nullifier, position_commitment, output_randomness])?;
+
     let ballot = &ctx.accounts.ballot;
     let pool = &ctx.accounts.pool;
     let clock = Clock::get()?;
@@ -150,19 +190,21 @@ pub fn create_pending_with_proof_vote_spend<'info>(
     )?;
 
     // Verify ZK proof
-    verify_groth16_proof(
+    verify_groth16_proof_metered(
         &proof,
         &ctx.accounts.verification_key.vk_data,
         &public_inputs,
         "vote_spend",
+        ctx.accounts.circuit_stats.as_deref_mut(),
     )?;
 
     // Initialize pending operation
     let pending_op = &mut ctx.accounts.pending_operation;
     pending_op.bump = ctx.bumps.pending_operation;
-    pending_op.operation_id = operation_id;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
     pending_op.relayer = ctx.accounts.relayer.key();
     pending_op.operation_type = operation_types::VOTE_SPEND;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
     pending_op.proof_verified = true;
 
     // Store input commitment and nullifier
@@ -194,7 +236,8 @@ pub fn create_pending_with_proof_vote_spend<'info>(
 
     // Set expiry
     pending_op.created_at = current_time;
-    pending_op.expires_at = current_time + PENDING_OPERATION_EXPIRY_SECONDS;
+    pending_op.expires_at = current_time + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
 
     msg!("Vote spend pending operation created");
     msg!("  Operation ID: {:?}", operation_id);
@@ -0,0 +1,292 @@
+//! Create Pending with Proof - Vote TWAB (Phase 0)
+//!
+//! Snapshot voting for TWAB ballots. The user proves ownership of a shielded
+//! note and its inclusion in each of the ballot's registered snapshot roots
+//! where it existed; voting power is the note amount averaged over all roots.
+//! A note acquired just before the window closes is included in few roots and
+//! carries proportionally little weight, defeating borrow-to-vote.
+//!
+//! Flow (same phases as vote_snapshot after Phase 0):
+//! Phase 0 (this): Verify ZK proof + Create PendingOperation
+//! Phase 1: create_nullifier_and_pending for vote_nullifier
+//! Phase 2: execute_vote_snapshot - Update tally
+//! Phase 3: create_commitment for vote_commitment
+//! Phase 4: close_pending_operation
+
+use anchor_lang::prelude::*;
+
+use crate::constants::{operation_types, seeds, GROTH16_PROOF_SIZE};
+use crate::errors::CloakCraftError;
+use crate::helpers::field::{bytes_to_field, pubkey_to_field, u64_to_field, assert_canonical};
+use crate::helpers::proof::verify_groth16_proof_metered;
+use crate::state::{
+    Ballot, BallotStatus, PendingOperation, RevealMode, TwabMode, VoteBindingMode, VerificationKey,
+    ProtocolConfig, ProgramVersion, CircuitStats, MAX_TWAB_ROOTS, OperationCredit,
+};
+
+use super::create_pending_with_proof_vote_snapshot::EncryptedContributions;
+
+#[derive(Accounts)]
+#[instruction(operation_id: [u8; 32], ballot_id: [u8; 32])]
+pub struct CreatePendingWithProofVoteTwab<'info> {
+    /// Ballot being voted on
+    #[account(
+        seeds = [seeds::BALLOT, ballot_id.as_ref()],
+        bump = ballot.bump,
+        constraint = ballot.binding_mode == VoteBindingMode::Snapshot @ CloakCraftError::InvalidBindingMode,
+        constraint = ballot.twab_mode == TwabMode::Average @ CloakCraftError::TwabNotEnabled,
+    )]
+    pub ballot: Box<Account<'info, Ballot>>,
+
+    /// Verification key for vote_twab circuit
+    #[account(
+        seeds = [seeds::VERIFICATION_KEY, crate::constants::circuits::VOTE_TWAB.as_ref()],
+        bump = verification_key.bump,
+        constraint = verification_key.is_finalized @ CloakCraftError::VerificationKeyNotFinalized,
+    )]
+    pub verification_key: Box<Account<'info, VerificationKey>>,
+
+    /// Pending operation account (created)
+    #[account(
+        init,
+        payer = payer,
+        space = PendingOperation::SPACE,
+        seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
+        bump
+    )]
+    pub pending_operation: Box<Account<'info, PendingOperation>>,
+
+    /// Relayer executing the transaction
+    pub relayer: Signer<'info>,
+
+    /// Payer for account creation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Protocol config (resolves the pending operation expiry)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Program version (rejects stale clients)
+    #[account(
+        seeds = [seeds::PROGRAM_VERSION],
+        bump = program_version.bump,
+    )]
+    pub program_version: Box<Account<'info, ProgramVersion>>,
+
+    /// Circuit statistics (optional, counts verifications and rejected proofs)
+    #[account(
+        mut,
+        seeds = [seeds::CIRCUIT_STATS, verification_key.circuit_id.as_ref()],
+        bump = circuit_stats.bump,
+    )]
+    pub circuit_stats: Option<Box<Account<'info, CircuitStats>>>,
+
+    /// Credit spent for this operation (required while operation credits are required)
+    pub operation_credit: Option<Box<Account<'info, OperationCredit>>>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_pending_with_proof_vote_twab<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofVoteTwab<'info>>,
+    operation_id: [u8; 32],
+    ballot_id: [u8; 32],
+    proof: Vec<u8>,
+    // Public inputs from ZK proof (roots come from the ballot)
+    note_commitment: [u8; 32],       // The shielded note being used
+    vote_nullifier: [u8; 32],
+    vote_commitment: [u8; 32],
+    vote_choice: u64,                // For public mode, actual choice; for encrypted, 0
+    twab_amount: u64,                // Note amount averaged over the TWAB roots
+    weight: u64,
+    // Encrypted contributions (for TimeLocked/PermanentPrivate modes)
+    encrypted_contributions: Option<EncryptedContributions>,
+    // Output data
+    output_randomness: [u8; 32],
+    // LP mint of the note (None = ballot token)
+    lp_mint: Option<Pubkey>,
+    client_version: u32,
+) -> Result<()> {
+    // Reject clients built for an incompatible program version
+    ctx.accounts.program_version.check_client(client_version)?;
+
+    // Rate-limited deployments require a credit spent for this operation
+    ctx.accounts.protocol_config.check_operation_credit(
+        ctx.accounts.operation_credit.as_deref().map(|credit| &**credit),
+        &operation_id,
+    )?;
+
+    // Bound field elements must use their canonical encoding
+    assert_canonical(&[note_commitment, vote_nullifier, vote_commitment, output_randomness])?;
+
+    let ballot = &ctx.accounts.ballot;
+    let clock = Clock::get()?;
+    let current_time = clock.unix_timestamp;
+
+    // Verify ballot is active
+    if !ballot.is_active(current_time) {
+        if ballot.status == BallotStatus::Pending {
+            return Err(CloakCraftError::VotingNotStarted.into());
+        }
+        return Err(CloakCraftError::VotingEnded.into());
+    }
+
+    // All roots of the averaging window must be registered
+    if !ballot.has_snapshot_root {
+        return Err(CloakCraftError::TwabRootsIncomplete.into());
+    }
+
+    // Verify proof length
+    if proof.len() != GROTH16_PROOF_SIZE {
+        return Err(CloakCraftError::InvalidProofLength.into());
+    }
+
+    // Verify vote_choice is valid for public mode
+    if ballot.reveal_mode == RevealMode::Public && vote_choice >= ballot.num_options as u64 {
+        return Err(CloakCraftError::InvalidVoteOptionRange.into());
+    }
+
+    // Verify encrypted contributions for encrypted modes
+    if ballot.reveal_mode != RevealMode::Public {
+        let contributions = encrypted_contributions
+            .as_ref()
+            .ok_or(CloakCraftError::InvalidPublicInputs)?;
+        if contributions.ciphertexts.len() != ballot.num_options as usize {
+            return Err(CloakCraftError::InvalidPublicInputs.into());
+        }
+    }
+
+    // LP notes count at the registered rate in ballot token units
+    let (note_mint, counted_amount) = match lp_mint {
+        Some(mint) => (mint, ballot.convert_lp_amount(&mint, twab_amount)?),
+        None => (ballot.token_mint, twab_amount),
+    };
+    if counted_amount == 0 {
+        return Err(CloakCraftError::ZeroAmount.into());
+    }
+
+    // Build public inputs for ZK proof verification
+    let public_inputs = build_public_inputs(
+        ballot,
+        &note_mint,
+        &ballot_id,
+        &note_commitment,
+        &vote_nullifier,
+        &vote_commitment,
+        vote_choice,
+        twab_amount,
+        weight,
+    );
+
+    // Verify ZK proof
+    verify_groth16_proof_metered(
+        &proof,
+        &ctx.accounts.verification_key.vk_data,
+        &public_inputs,
+        "vote_twab",
+        ctx.accounts.circuit_stats.as_deref_mut(),
+    )?;
+
+    // Initialize pending operation (continues as a vote_snapshot operation)
+    let pending_op = &mut ctx.accounts.pending_operation;
+    pending_op.bump = ctx.bumps.pending_operation;
+    pending_op.set_operation_id(operation_id, &ctx.accounts.protocol_config)?;
+    pending_op.relayer = ctx.accounts.relayer.key();
+    pending_op.operation_type = operation_types::VOTE_SNAPSHOT;
+    pending_op.bind_circuit(&ctx.accounts.verification_key.circuit_id);
+    pending_op.proof_verified = true;
+
+    // Store note_commitment as input commitment
+    pending_op.input_commitments[0] = note_commitment;
+    pending_op.num_inputs = 1;
+    pending_op.inputs_verified_mask = 0;
+
+    // Store vote_nullifier as expected nullifier
+    pending_op.expected_nullifiers[0] = vote_nullifier;
+    pending_op.nullifier_completed_mask = 0;
+    pending_op.input_pools[0] = ballot_id;
+
+    // Store vote_commitment as output commitment
+    pending_op.commitments[0] = vote_commitment;
+    pending_op.num_commitments = 1;
+    pending_op.completed_mask = 0;
+    pending_op.pools[0] = ballot_id;
+    pending_op.output_randomness[0] = output_randomness;
+    pending_op.output_amounts[0] = weight;
+
+    // Vote data: swap_amount = vote_choice, output_amount = weight,
+    // extra_amount = averaged amount (LP amounts converted to ballot token units)
+    pending_op.swap_amount = vote_choice;
+    pending_op.output_amount = weight;
+    pending_op.extra_amount = counted_amount;
+
+    // Set expiry
+    pending_op.created_at = current_time;
+    pending_op.expires_at = current_time + ctx.accounts.protocol_config.pending_expiry_seconds(pending_op.operation_type);
+    pending_op.set_rent_refund_bps(&ctx.accounts.protocol_config);
+
+    msg!("Vote TWAB pending operation created");
+    msg!("  Operation ID: {:?}", operation_id);
+    msg!("  Roots averaged: {}", ballot.twab_num_roots);
+    msg!("  TWAB amount: {}", twab_amount);
+    msg!("  Weight: {}", weight);
+
+    Ok(())
+}
+
+/// Build public inputs array for ZK proof verification
+/// Must match the circuit's public inputs exactly in order (20 field elements):
+/// 1. ballot_id
+/// 2. twab_roots (8 elements: registered roots, zero padded)
+/// 3. num_roots
+/// 4. note_commitment
+/// 5. vote_nullifier
+/// 6. vote_commitment
+/// 7. twab_amount
+/// 8. weight
+/// 9. token_mint
+/// 10. eligibility_root
+/// 11. has_eligibility
+/// 12. vote_choice
+/// 13. is_public_mode
+#[allow(clippy::too_many_arguments)]
+fn build_public_inputs(
+    ballot: &Ballot,
+    note_mint: &Pubkey,
+    ballot_id: &[u8; 32],
+    note_commitment: &[u8; 32],
+    vote_nullifier: &[u8; 32],
+    vote_commitment: &[u8; 32],
+    vote_choice: u64,
+    twab_amount: u64,
+    weight: u64,
+) -> Vec<[u8; 32]> {
+    let mut inputs = vec![bytes_to_field(ballot_id)];
+
+    // Registered roots, oldest first; unused slots are zero
+    let mut roots = [[0u8; 32]; MAX_TWAB_ROOTS];
+    for (root, entry) in roots.iter_mut().zip(ballot.twab_roots()) {
+        *root = entry.root;
+    }
+    inputs.extend(roots.iter().map(bytes_to_field));
+    inputs.push(u64_to_field(ballot.twab_num_roots as u64));
+
+    inputs.push(bytes_to_field(note_commitment));
+    inputs.push(bytes_to_field(vote_nullifier));
+    inputs.push(bytes_to_field(vote_commitment));
+    inputs.push(u64_to_field(twab_amount));
+    inputs.push(u64_to_field(weight));
+    inputs.push(pubkey_to_field(note_mint));
+    inputs.push(bytes_to_field(&ballot.eligibility_root));
+    inputs.push(u64_to_field(ballot.has_eligibility_root as u64));
+    inputs.push(u64_to_field(vote_choice));
+    inputs.push(u64_to_field((ballot.reveal_mode == RevealMode::Public) as u64));
+
+    inputs
+}
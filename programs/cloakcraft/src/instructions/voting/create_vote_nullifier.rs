@@ -18,7 +18,7 @@ use anchor_lang::prelude::*;
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 use crate::light_cpi::create_action_nullifier_account;
-use crate::state::{Ballot, PendingOperation, LightValidityProof, LightAddressTreeInfo};
+use crate::state::{Ballot, PendingOperation, ProtocolConfig, LightValidityProof, LightAddressTreeInfo};
 
 /// Parameters for Light Protocol vote nullifier creation
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -56,6 +56,14 @@ pub struct CreateVoteNullifier<'info> {
     )]
     pub relayer: Signer<'info>,
 
+    /// Protocol config (operations from a previous operation epoch may not spend)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+        constraint = protocol_config.is_current_operation(&pending_operation.operation_id) @ CloakCraftError::StaleOperationEpoch,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
     // Light Protocol accounts via remaining_accounts (~8 accounts)
 }
 
@@ -107,6 +115,11 @@ pub fn create_vote_nullifier<'info>(
         CloakCraftError::PoolMismatch
     );
 
+    // Vote and vote-position nullifiers are kept apart by domain
+    let domain = pending_op.nullifier_domain()
+        .filter(|d| d.is_action())
+        .ok_or(CloakCraftError::InvalidNullifierDomain)?;
+
     // Create the vote nullifier via Light Protocol CPI
     // Uses action_nullifier with ballot_id as aggregation_id
     create_action_nullifier_account(
@@ -116,6 +129,7 @@ pub fn create_vote_nullifier<'info>(
         light_params.address_tree_info,
         light_params.output_tree_index,
         ballot_id,
+        domain,
         expected_nullifier,
     )?;
 
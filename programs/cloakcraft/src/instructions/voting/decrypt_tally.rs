@@ -4,6 +4,9 @@
 //! Decrypts the homomorphic tally to reveal aggregate vote counts.
 //!
 //! For PermanentPrivate mode, this reveals ONLY aggregates, not individual votes.
+//!
+//! Hidden-turnout ballots also decrypt their encrypted_vote_count here, which
+//! sets vote_count; total_weight is the sum of the decrypted weights.
 
 use anchor_lang::prelude::*;
 
@@ -44,6 +47,7 @@ pub fn decrypt_tally(
     _ballot_id: [u8; 32],
     decryption_key: [u8; 32],
     decrypted_weights: Vec<u64>,
+    decrypted_vote_count: Option<u64>,
 ) -> Result<()> {
     let ballot = &mut ctx.accounts.ballot;
     let clock = Clock::get()?;
@@ -60,7 +64,7 @@ pub fn decrypt_tally(
         BallotStatus::Pending => {
             return Err(CloakCraftError::VotingNotStarted.into());
         }
-        BallotStatus::Resolved | BallotStatus::Finalized => {
+        BallotStatus::Resolved | BallotStatus::Finalized | BallotStatus::Disputed => {
             // Already decrypted and resolved
             return Err(CloakCraftError::BallotAlreadyResolved.into());
         }
@@ -107,6 +111,16 @@ pub fn decrypt_tally(
         return Err(CloakCraftError::InvalidDecryptionKey.into());
     }
 
+    // Hidden turnout: the vote count is revealed with the tally
+    if ballot.hide_turnout {
+        let vote_count = decrypted_vote_count.ok_or(CloakCraftError::InvalidOutcomeValue)?;
+        if !decrypts_to(&ballot.encrypted_vote_count, &bytes_to_limbs(&decryption_key), vote_count) {
+            return Err(CloakCraftError::InvalidDecryptionKey.into());
+        }
+        ballot.vote_count = vote_count;
+        ballot.encrypted_vote_count = [0u8; ELGAMAL_CIPHERTEXT_SIZE];
+    }
+
     // Update option_weights with decrypted values
     for (i, weight) in decrypted_weights.iter().enumerate() {
         ballot.option_weights[i] = *weight;
@@ -144,7 +158,7 @@ pub fn decrypt_tally(
 /// Alternative schemes (e.g., VDF-based, BLS-based) would use different
 /// verification logic, but the basic check ensures the caller provides
 /// the correct key that was used for encryption.
-fn verify_decryption_key(decryption_key: &[u8; 32], time_lock_pubkey: &[u8; 32]) -> bool {
+pub(crate) fn verify_decryption_key(decryption_key: &[u8; 32], time_lock_pubkey: &[u8; 32]) -> bool {
     // Check that neither key is zero
     if decryption_key.iter().all(|&b| b == 0) {
         return false;
@@ -171,7 +185,7 @@ fn verify_decryption_key(decryption_key: &[u8; 32], time_lock_pubkey: &[u8; 32])
 /// - Decryption: m = C2 - C1 * key
 ///
 /// All operations are in the BN254 scalar field.
-fn verify_decryption(
+pub(crate) fn verify_decryption(
     encrypted_tally: &[[u8; 64]; 16],
     decryption_key: &[u8; 32],
     decrypted_weights: &[u64],
@@ -179,45 +193,35 @@ fn verify_decryption(
 ) -> bool {
     let key_limbs = bytes_to_limbs(decryption_key);
 
-    for i in 0..num_options as usize {
-        let ct = &encrypted_tally[i];
-
-        // Check for identity ciphertext (all zeros = encrypt(0) with r=0)
-        let is_zero_ct = ct.iter().all(|&b| b == 0);
-        if is_zero_ct {
-            // Zero ciphertext decrypts to zero
-            if decrypted_weights[i] != 0 {
-                return false;
-            }
-            continue;
-        }
-
-        // Extract C1 (bytes 0-31) and C2 (bytes 32-63)
-        let c1 = &ct[0..32];
-        let c2 = &ct[32..64];
+    (0..num_options as usize).all(|i| decrypts_to(&encrypted_tally[i], &key_limbs, decrypted_weights[i]))
+}
 
-        // Convert to limbs
-        let c1_limbs = bytes_to_limbs(c1);
-        let c2_limbs = bytes_to_limbs(c2);
+/// Check that `ct` decrypts to `expected` under `key_limbs`
+fn decrypts_to(ct: &[u8; 64], key_limbs: &[u64; 4], expected: u64) -> bool {
+    // Check for identity ciphertext (all zeros = encrypt(0) with r=0)
+    let is_zero_ct = ct.iter().all(|&b| b == 0);
+    if is_zero_ct {
+        // Zero ciphertext decrypts to zero
+        return expected == 0;
+    }
 
-        // Compute C1 * key (mod r)
-        let c1_times_key = mul_bn254_scalars(&c1_limbs, &key_limbs);
+    // Extract C1 (bytes 0-31) and C2 (bytes 32-63)
+    let c1 = &ct[0..32];
+    let c2 = &ct[32..64];
 
-        // Compute m = C2 - C1 * key (mod r)
-        let decrypted = sub_bn254_scalars(&c2_limbs, &c1_times_key);
+    // Convert to limbs
+    let c1_limbs = bytes_to_limbs(c1);
+    let c2_limbs = bytes_to_limbs(c2);
 
-        // Convert decrypted to u64 (should be small for vote weights)
-        // The weight should fit in the first limb, and other limbs should be 0
-        let expected_weight = decrypted_weights[i];
+    // Compute C1 * key (mod r)
+    let c1_times_key = mul_bn254_scalars(&c1_limbs, key_limbs);
 
-        // For valid decryption, the result should equal the expected weight
-        // Since weights are u64, they fit in the first limb
-        if decrypted[0] != expected_weight || decrypted[1] != 0 || decrypted[2] != 0 || decrypted[3] != 0 {
-            return false;
-        }
-    }
+    // Compute m = C2 - C1 * key (mod r)
+    let decrypted = sub_bn254_scalars(&c2_limbs, &c1_times_key);
 
-    true
+    // For valid decryption, the result should equal the expected value
+    // Since values are u64, they fit in the first limb
+    decrypted[0] == expected && decrypted[1] == 0 && decrypted[2] == 0 && decrypted[3] == 0
 }
 
 /// Multiply two BN254 scalar field elements
@@ -0,0 +1,95 @@
+//! Dispute an Oracle ballot's outcome
+//!
+//! Oracle ballots created with a `dispute_window_seconds` can be disputed by
+//! anyone for that long after resolution. The challenger locks `dispute_bond`
+//! lamports on the ballot account, and the ballot moves to Disputed until its
+//! resolver (an authority or committee multisig) settles it with
+//! settle_dispute. Each ballot can be disputed once.
+//!
+//! Claims and finalization wait for the window to pass or the dispute to be
+//! settled (see `Ballot::outcome_final`).
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{Ballot, BallotStatus, ResolutionMode};
+
+/// Emitted when an oracle outcome is disputed
+#[event]
+pub struct ResolutionDisputed {
+    pub ballot_id: [u8; 32],
+    pub challenger: Pubkey,
+    /// Outcome being disputed (None if the oracle set no winner)
+    pub outcome: Option<u8>,
+    pub bond: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(ballot_id: [u8; 32])]
+pub struct DisputeResolution<'info> {
+    /// Resolved Oracle ballot
+    #[account(
+        mut,
+        seeds = [seeds::BALLOT, ballot_id.as_ref()],
+        bump = ballot.bump,
+    )]
+    pub ballot: Box<Account<'info, Ballot>>,
+
+    /// Challenger (locks the dispute bond)
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn dispute_resolution(ctx: Context<DisputeResolution>, ballot_id: [u8; 32]) -> Result<()> {
+    let ballot = &mut ctx.accounts.ballot;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    if ballot.resolution_mode != ResolutionMode::Oracle || ballot.dispute_window_seconds == 0 {
+        return Err(CloakCraftError::DisputesNotEnabled.into());
+    }
+    match ballot.status {
+        BallotStatus::Resolved => {}
+        BallotStatus::Disputed => return Err(CloakCraftError::BallotAlreadyDisputed.into()),
+        _ => return Err(CloakCraftError::BallotNotResolved.into()),
+    }
+    if ballot.challenger != Pubkey::default() {
+        return Err(CloakCraftError::BallotAlreadyDisputed.into());
+    }
+    if current_time >= ballot.resolved_at.saturating_add(ballot.dispute_window_seconds) {
+        return Err(CloakCraftError::DisputeWindowClosed.into());
+    }
+
+    // Lock the bond on the ballot account
+    let bond = ballot.dispute_bond;
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.challenger.to_account_info(),
+                to: ballot.to_account_info(),
+            },
+        ),
+        bond,
+    )?;
+
+    let challenger = ctx.accounts.challenger.key();
+    ballot.challenger = challenger;
+    ballot.status = BallotStatus::Disputed;
+
+    emit!(ResolutionDisputed {
+        ballot_id,
+        challenger,
+        outcome: ballot.has_outcome.then_some(ballot.outcome),
+        bond,
+    });
+
+    msg!("Ballot outcome disputed by {}", challenger);
+    msg!("  Bond: {} lamports, escalated to {}", bond, ballot.resolver);
+
+    Ok(())
+}
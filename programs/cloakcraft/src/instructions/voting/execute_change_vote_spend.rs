@@ -10,7 +10,7 @@ use anchor_lang::prelude::*;
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 use crate::state::{
-    Ballot, BallotStatus, PendingOperation, RevealMode, VoteBindingMode,
+    Ballot, BallotOptionsPage, PendingOperation, OperationKind, RevealMode, VoteBindingMode,
     ELGAMAL_CIPHERTEXT_SIZE, MAX_BALLOT_OPTIONS,
 };
 
@@ -33,9 +33,6 @@ pub struct ExecuteChangeVoteSpend<'info> {
         mut,
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
-        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
-        constraint = pending_operation.all_inputs_verified() @ CloakCraftError::CommitmentNotVerified,
-        constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -44,6 +41,22 @@ pub struct ExecuteChangeVoteSpend<'info> {
         constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
     )]
     pub relayer: Signer<'info>,
+
+    /// Page of the old option (Public ballots with more than 16 options)
+    #[account(
+        mut,
+        seeds = [seeds::BALLOT_OPTIONS, ballot_id.as_ref(), &[options_page.page]],
+        bump = options_page.bump,
+    )]
+    pub options_page: Option<Box<Account<'info, BallotOptionsPage>>>,
+
+    /// Page of the new option, when not on the old option's page
+    #[account(
+        mut,
+        seeds = [seeds::BALLOT_OPTIONS, ballot_id.as_ref(), &[new_options_page.page]],
+        bump = new_options_page.bump,
+    )]
+    pub new_options_page: Option<Box<Account<'info, BallotOptionsPage>>>,
 }
 
 pub fn execute_change_vote_spend(
@@ -53,7 +66,13 @@ pub fn execute_change_vote_spend(
     old_encrypted_contributions: Option<EncryptedContributions>,
     new_encrypted_contributions: Option<EncryptedContributions>,
 ) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::ChangeVoteSpend)?;
+
     let ballot = &mut ctx.accounts.ballot;
+    let mut options_pages = [
+        ctx.accounts.options_page.as_deref_mut().map(|page| &mut **page),
+        ctx.accounts.new_options_page.as_deref_mut().map(|page| &mut **page),
+    ];
     let pending_op = &ctx.accounts.pending_operation;
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp;
@@ -73,7 +92,7 @@ pub fn execute_change_vote_spend(
     match ballot.reveal_mode {
         RevealMode::Public => {
             // Direct tally update: decrement old, increment new
-            update_public_tally_change(ballot, old_vote_choice, new_vote_choice, weight, amount)?;
+            update_public_tally_change(ballot, &mut options_pages, old_vote_choice, new_vote_choice, weight, amount)?;
         }
         RevealMode::TimeLocked | RevealMode::PermanentPrivate => {
             // Homomorphic tally update
@@ -98,25 +117,27 @@ pub fn execute_change_vote_spend(
 /// Update tally for public mode vote change in SpendToVote
 fn update_public_tally_change(
     ballot: &mut Ballot,
+    options_pages: &mut [Option<&mut BallotOptionsPage>],
     old_vote_choice: u8,
     new_vote_choice: u8,
     weight: u64,
     amount: u64,
 ) -> Result<()> {
-    if old_vote_choice >= ballot.num_options || new_vote_choice >= ballot.num_options {
-        return Err(CloakCraftError::InvalidVoteOptionRange.into());
-    }
-
-    let old_idx = old_vote_choice as usize;
-    let new_idx = new_vote_choice as usize;
-
     // Decrement old choice
-    ballot.option_weights[old_idx] = ballot.option_weights[old_idx].saturating_sub(weight);
-    ballot.option_amounts[old_idx] = ballot.option_amounts[old_idx].saturating_sub(amount);
+    let (old_weight, old_amount) = ballot.option_tally(
+        BallotOptionsPage::find(options_pages, old_vote_choice),
+        old_vote_choice,
+    )?;
+    *old_weight = old_weight.saturating_sub(weight);
+    *old_amount = old_amount.saturating_sub(amount);
 
     // Increment new choice
-    ballot.option_weights[new_idx] = ballot.option_weights[new_idx].saturating_add(weight);
-    ballot.option_amounts[new_idx] = ballot.option_amounts[new_idx].saturating_add(amount);
+    let (new_weight, new_amount) = ballot.option_tally(
+        BallotOptionsPage::find(options_pages, new_vote_choice),
+        new_vote_choice,
+    )?;
+    *new_weight = new_weight.saturating_add(weight);
+    *new_amount = new_amount.saturating_add(amount);
 
     msg!("  Option {}: weight -{}, amount -{}", old_vote_choice, weight, amount);
     msg!("  Option {}: weight +{}, amount +{}", new_vote_choice, weight, amount);
@@ -130,8 +151,9 @@ fn update_encrypted_tally_change(
     old_contributions: &EncryptedContributions,
     new_contributions: &EncryptedContributions,
 ) -> Result<()> {
-    if old_contributions.ciphertexts.len() != ballot.num_options as usize
-        || new_contributions.ciphertexts.len() != ballot.num_options as usize
+    // Contributions proven before a write-in was added don't cover it
+    if old_contributions.ciphertexts.len() > ballot.num_options as usize
+        || new_contributions.ciphertexts.len() > ballot.num_options as usize
     {
         return Err(CloakCraftError::InvalidPublicInputs.into());
     }
@@ -144,13 +166,13 @@ fn update_encrypted_tally_change(
         // Add old contributions (which contain negated weights for subtraction)
         let after_old = add_elgamal_ciphertexts(
             &ballot.encrypted_tally[i],
-            &old_contributions.ciphertexts[i],
+            old_contributions.ciphertexts.get(i).unwrap_or(&[0u8; 64]),
         )?;
 
         // Add new contributions (which contain positive weights for addition)
         let after_new = add_elgamal_ciphertexts(
             &after_old,
-            &new_contributions.ciphertexts[i],
+            new_contributions.ciphertexts.get(i).unwrap_or(&[0u8; 64]),
         )?;
 
         ballot.encrypted_tally[i] = after_new;
@@ -8,7 +8,7 @@ use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
-use crate::state::{Ballot, BallotStatus, PendingOperation, VoteBindingMode};
+use crate::state::{Ballot, BallotStatus, PendingOperation, OperationKind, VoteBindingMode};
 
 #[derive(Accounts)]
 #[instruction(operation_id: [u8; 32], ballot_id: [u8; 32])]
@@ -31,23 +31,20 @@ pub struct ExecuteClaim<'info> {
         token::mint = ballot.token_mint,
         token::authority = ballot,
     )]
-    pub ballot_vault: Account<'info, TokenAccount>,
+    pub ballot_vault: Box<Account<'info, TokenAccount>>,
 
     /// Protocol treasury (receives fee)
     #[account(
         mut,
         constraint = protocol_treasury.key() == ballot.protocol_treasury @ CloakCraftError::InvalidTreasury,
     )]
-    pub protocol_treasury: Account<'info, TokenAccount>,
+    pub protocol_treasury: Box<Account<'info, TokenAccount>>,
 
     /// Pending operation
     #[account(
         mut,
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
-        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
-        constraint = pending_operation.all_inputs_verified() @ CloakCraftError::CommitmentNotVerified,
-        constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -66,6 +63,8 @@ pub fn execute_claim(
     _operation_id: [u8; 32],
     ballot_id: [u8; 32],
 ) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::Claim)?;
+
     let ballot = &mut ctx.accounts.ballot;
     let pending_op = &ctx.accounts.pending_operation;
     let clock = Clock::get()?;
@@ -8,7 +8,7 @@ use anchor_lang::prelude::*;
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 use crate::state::{
-    Ballot, BallotStatus, PendingOperation, RevealMode, VoteBindingMode,
+    Ballot, BallotOptionsPage, PendingOperation, OperationKind, RevealMode, VoteBindingMode,
     ELGAMAL_CIPHERTEXT_SIZE, MAX_BALLOT_OPTIONS,
 };
 
@@ -31,9 +31,6 @@ pub struct ExecuteCloseVotePosition<'info> {
         mut,
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
-        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
-        constraint = pending_operation.all_inputs_verified() @ CloakCraftError::CommitmentNotVerified,
-        constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -42,6 +39,14 @@ pub struct ExecuteCloseVotePosition<'info> {
         constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
     )]
     pub relayer: Signer<'info>,
+
+    /// Page of the voted option (Public ballots with more than 16 options)
+    #[account(
+        mut,
+        seeds = [seeds::BALLOT_OPTIONS, ballot_id.as_ref(), &[options_page.page]],
+        bump = options_page.bump,
+    )]
+    pub options_page: Option<Box<Account<'info, BallotOptionsPage>>>,
 }
 
 pub fn execute_close_vote_position(
@@ -50,7 +55,10 @@ pub fn execute_close_vote_position(
     _ballot_id: [u8; 32],
     encrypted_contributions: Option<EncryptedContributions>,
 ) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::CloseVotePosition)?;
+
     let ballot = &mut ctx.accounts.ballot;
+    let options_page = ctx.accounts.options_page.as_deref_mut().map(|page| &mut **page);
     let pending_op = &ctx.accounts.pending_operation;
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp;
@@ -69,7 +77,7 @@ pub fn execute_close_vote_position(
     match ballot.reveal_mode {
         RevealMode::Public => {
             // Direct tally decrement
-            decrement_public_tally(ballot, vote_choice, weight, amount)?;
+            decrement_public_tally(ballot, options_page, vote_choice, weight, amount)?;
         }
         RevealMode::TimeLocked | RevealMode::PermanentPrivate => {
             // Homomorphic tally update (contributions contain negated weights)
@@ -97,17 +105,14 @@ pub fn execute_close_vote_position(
 /// Decrement tally for public mode
 fn decrement_public_tally(
     ballot: &mut Ballot,
+    options_page: Option<&mut BallotOptionsPage>,
     vote_choice: u8,
     weight: u64,
     amount: u64,
 ) -> Result<()> {
-    if vote_choice >= ballot.num_options {
-        return Err(CloakCraftError::InvalidVoteOptionRange.into());
-    }
-
-    let idx = vote_choice as usize;
-    ballot.option_weights[idx] = ballot.option_weights[idx].saturating_sub(weight);
-    ballot.option_amounts[idx] = ballot.option_amounts[idx].saturating_sub(amount);
+    let (option_weight, option_amount) = ballot.option_tally(options_page, vote_choice)?;
+    *option_weight = option_weight.saturating_sub(weight);
+    *option_amount = option_amount.saturating_sub(amount);
 
     msg!("  Option {}: weight -{}, amount -{}", vote_choice, weight, amount);
 
@@ -119,7 +124,8 @@ fn update_encrypted_tally(
     ballot: &mut Ballot,
     contributions: &EncryptedContributions,
 ) -> Result<()> {
-    if contributions.ciphertexts.len() != ballot.num_options as usize {
+    // Votes proven before a write-in was added carry no contribution for it
+    if contributions.ciphertexts.len() > ballot.num_options as usize {
         return Err(CloakCraftError::InvalidPublicInputs.into());
     }
 
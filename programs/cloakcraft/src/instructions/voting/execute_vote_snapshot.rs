@@ -5,13 +5,19 @@
 //!
 //! For Public mode: Updates option_weights[vote_choice] directly
 //! For Encrypted modes: Adds encrypted_contributions to encrypted_tally
+//!
+//! Ballots with hidden turnout leave vote_count, total_weight and
+//! total_amount untouched: each vote adds an encryption of 1 to
+//! encrypted_vote_count instead, and decrypt_tally reveals the count and the
+//! total weight. The vote's transaction and its pending operation remain
+//! visible, so this hides the running counters, not the fact of a vote.
 
 use anchor_lang::prelude::*;
 
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 use crate::state::{
-    Ballot, BallotStatus, PendingOperation, RevealMode, VoteBindingMode,
+    Ballot, BallotOptionsPage, PendingOperation, OperationKind, RevealMode, VoteBindingMode,
     ELGAMAL_CIPHERTEXT_SIZE, MAX_BALLOT_OPTIONS,
 };
 
@@ -34,8 +40,6 @@ pub struct ExecuteVoteSnapshot<'info> {
         mut,
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
-        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
-        constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -44,6 +48,14 @@ pub struct ExecuteVoteSnapshot<'info> {
         constraint = relayer.key() == pending_operation.relayer @ CloakCraftError::InvalidRelayer,
     )]
     pub relayer: Signer<'info>,
+
+    /// Page of the voted option (Public ballots with more than 16 options)
+    #[account(
+        mut,
+        seeds = [seeds::BALLOT_OPTIONS, ballot_id.as_ref(), &[options_page.page]],
+        bump = options_page.bump,
+    )]
+    pub options_page: Option<Box<Account<'info, BallotOptionsPage>>>,
 }
 
 pub fn execute_vote_snapshot(
@@ -51,8 +63,12 @@ pub fn execute_vote_snapshot(
     _operation_id: [u8; 32],
     _ballot_id: [u8; 32],
     encrypted_contributions: Option<EncryptedContributions>,
+    encrypted_turnout: Option<[u8; ELGAMAL_CIPHERTEXT_SIZE]>,
 ) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::VoteSnapshot)?;
+
     let ballot = &mut ctx.accounts.ballot;
+    let options_page = ctx.accounts.options_page.as_deref_mut().map(|page| &mut **page);
     let pending_op = &ctx.accounts.pending_operation;
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp;
@@ -71,7 +87,7 @@ pub fn execute_vote_snapshot(
     match ballot.reveal_mode {
         RevealMode::Public => {
             // Direct tally update
-            update_public_tally(ballot, vote_choice as u8, weight, total_amount)?;
+            update_public_tally(ballot, options_page, vote_choice as u8, weight, total_amount)?;
         }
         RevealMode::TimeLocked | RevealMode::PermanentPrivate => {
             // Homomorphic tally update
@@ -81,6 +97,15 @@ pub fn execute_vote_snapshot(
         }
     }
 
+    // Hidden turnout: count the vote homomorphically; total_weight is the
+    // sum of the decrypted option weights
+    if ballot.hide_turnout {
+        let turnout = encrypted_turnout.ok_or(CloakCraftError::MissingTurnoutCiphertext)?;
+        ballot.encrypted_vote_count = add_elgamal_ciphertexts(&ballot.encrypted_vote_count, &turnout)?;
+        msg!("Vote snapshot executed (turnout encrypted)");
+        return Ok(());
+    }
+
     // Update aggregate stats
     ballot.total_weight = ballot.total_weight.saturating_add(weight);
     ballot.total_amount = ballot.total_amount.saturating_add(total_amount);
@@ -97,17 +122,14 @@ pub fn execute_vote_snapshot(
 /// Update tally for public mode
 fn update_public_tally(
     ballot: &mut Ballot,
+    options_page: Option<&mut BallotOptionsPage>,
     vote_choice: u8,
     weight: u64,
     amount: u64,
 ) -> Result<()> {
-    if vote_choice >= ballot.num_options {
-        return Err(CloakCraftError::InvalidVoteOptionRange.into());
-    }
-
-    let idx = vote_choice as usize;
-    ballot.option_weights[idx] = ballot.option_weights[idx].saturating_add(weight);
-    ballot.option_amounts[idx] = ballot.option_amounts[idx].saturating_add(amount);
+    let (option_weight, option_amount) = ballot.option_tally(options_page, vote_choice)?;
+    *option_weight = option_weight.saturating_add(weight);
+    *option_amount = option_amount.saturating_add(amount);
 
     msg!("  Option {}: weight +{}", vote_choice, weight);
 
@@ -123,7 +145,8 @@ fn update_encrypted_tally(
     ballot: &mut Ballot,
     contributions: &EncryptedContributions,
 ) -> Result<()> {
-    if contributions.ciphertexts.len() != ballot.num_options as usize {
+    // Votes proven before a write-in was added carry no contribution for it
+    if contributions.ciphertexts.len() > ballot.num_options as usize {
         return Err(CloakCraftError::InvalidPublicInputs.into());
     }
 
@@ -154,7 +177,7 @@ fn update_encrypted_tally(
 ///
 /// For encrypted voting, ciphertexts are encoded as BN254 scalar field elements.
 /// This function performs homomorphic addition in the scalar field.
-fn add_elgamal_ciphertexts(
+pub(crate) fn add_elgamal_ciphertexts(
     ct_a: &[u8; ELGAMAL_CIPHERTEXT_SIZE],
     ct_b: &[u8; 64],
 ) -> Result<[u8; ELGAMAL_CIPHERTEXT_SIZE]> {
@@ -9,7 +9,7 @@ use anchor_spl::token::{Token, TokenAccount};
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
 use crate::state::{
-    Ballot, BallotStatus, PendingOperation, RevealMode, VoteBindingMode,
+    Ballot, BallotOptionsPage, PendingOperation, OperationKind, RevealMode, VoteBindingMode,
     ELGAMAL_CIPHERTEXT_SIZE, MAX_BALLOT_OPTIONS,
 };
 
@@ -37,16 +37,13 @@ pub struct ExecuteVoteSpend<'info> {
         token::mint = ballot.token_mint,
         token::authority = ballot,
     )]
-    pub ballot_vault: Account<'info, TokenAccount>,
+    pub ballot_vault: Box<Account<'info, TokenAccount>>,
 
     /// Pending operation (must have proof verified, input verified, nullifier created)
     #[account(
         mut,
         seeds = [PendingOperation::SEEDS_PREFIX, operation_id.as_ref()],
         bump = pending_operation.bump,
-        constraint = pending_operation.proof_verified @ CloakCraftError::ProofNotVerified,
-        constraint = pending_operation.all_inputs_verified() @ CloakCraftError::CommitmentNotVerified,
-        constraint = pending_operation.all_expected_nullifiers_created() @ CloakCraftError::NullifierNotCreated,
     )]
     pub pending_operation: Box<Account<'info, PendingOperation>>,
 
@@ -58,6 +55,14 @@ pub struct ExecuteVoteSpend<'info> {
 
     /// Token program
     pub token_program: Program<'info, Token>,
+
+    /// Page of the voted option (Public ballots with more than 16 options)
+    #[account(
+        mut,
+        seeds = [seeds::BALLOT_OPTIONS, ballot_id.as_ref(), &[options_page.page]],
+        bump = options_page.bump,
+    )]
+    pub options_page: Option<Box<Account<'info, BallotOptionsPage>>>,
 }
 
 pub fn execute_vote_spend(
@@ -66,7 +71,10 @@ pub fn execute_vote_spend(
     _ballot_id: [u8; 32],
     encrypted_contributions: Option<EncryptedContributions>,
 ) -> Result<()> {
+    ctx.accounts.pending_operation.check_phase3(OperationKind::VoteSpend)?;
+
     let ballot = &mut ctx.accounts.ballot;
+    let options_page = ctx.accounts.options_page.as_deref_mut().map(|page| &mut **page);
     let pending_op = &ctx.accounts.pending_operation;
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp;
@@ -85,7 +93,7 @@ pub fn execute_vote_spend(
     match ballot.reveal_mode {
         RevealMode::Public => {
             // Direct tally update
-            update_public_tally(ballot, vote_choice as u8, weight, amount)?;
+            update_public_tally(ballot, options_page, vote_choice as u8, weight, amount)?;
         }
         RevealMode::TimeLocked | RevealMode::PermanentPrivate => {
             // Homomorphic tally update
@@ -113,17 +121,14 @@ pub fn execute_vote_spend(
 /// Update tally for public mode
 fn update_public_tally(
     ballot: &mut Ballot,
+    options_page: Option<&mut BallotOptionsPage>,
     vote_choice: u8,
     weight: u64,
     amount: u64,
 ) -> Result<()> {
-    if vote_choice >= ballot.num_options {
-        return Err(CloakCraftError::InvalidVoteOptionRange.into());
-    }
-
-    let idx = vote_choice as usize;
-    ballot.option_weights[idx] = ballot.option_weights[idx].saturating_add(weight);
-    ballot.option_amounts[idx] = ballot.option_amounts[idx].saturating_add(amount);
+    let (option_weight, option_amount) = ballot.option_tally(options_page, vote_choice)?;
+    *option_weight = option_weight.saturating_add(weight);
+    *option_amount = option_amount.saturating_add(amount);
 
     msg!("  Option {}: weight +{}, amount +{}", vote_choice, weight, amount);
 
@@ -135,7 +140,8 @@ fn update_encrypted_tally(
     ballot: &mut Ballot,
     contributions: &EncryptedContributions,
 ) -> Result<()> {
-    if contributions.ciphertexts.len() != ballot.num_options as usize {
+    // Votes proven before a write-in was added carry no contribution for it
+    if contributions.ciphertexts.len() > ballot.num_options as usize {
         return Err(CloakCraftError::InvalidPublicInputs.into());
     }
 
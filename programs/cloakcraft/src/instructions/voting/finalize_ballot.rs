@@ -57,6 +57,16 @@ pub fn finalize_ballot(ctx: Context<FinalizeBallot>, ballot_id: [u8; 32]) -> Res
         return Err(CloakCraftError::BallotNotResolved.into());
     }
 
+    // Disputable outcomes aren't final yet
+    if !ballot.outcome_final(current_time) {
+        return Err(CloakCraftError::DisputeWindowOpen.into());
+    }
+
+    // Proposal bond is settled at resolution
+    if ballot.proposal_bond > 0 && !ballot.bond_settled {
+        return Err(CloakCraftError::BondNotSettled.into());
+    }
+
     // Verify claim deadline has passed
     if ballot.claim_deadline > 0 && current_time < ballot.claim_deadline {
         return Err(CloakCraftError::ClaimDeadlinePassed.into());
@@ -0,0 +1,60 @@
+//! Create a Snapshot-mode ballot's snapshot tree
+//!
+//! Called by the ballot's indexer or authority. The tree starts empty; the
+//! same attester fills it with `append_snapshot_leaves`.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::{seeds, MERKLE_TREE_DEPTH};
+use crate::errors::CloakCraftError;
+use crate::state::{Ballot, SnapshotTree, VoteBindingMode, MAX_SNAPSHOT_ROOTS};
+
+#[derive(Accounts)]
+#[instruction(ballot_id: [u8; 32])]
+pub struct InitializeSnapshotTree<'info> {
+    /// Ballot the tree belongs to
+    #[account(
+        seeds = [seeds::BALLOT, ballot_id.as_ref()],
+        bump = ballot.bump,
+        constraint = ballot.binding_mode == VoteBindingMode::Snapshot @ CloakCraftError::InvalidBindingMode,
+    )]
+    pub ballot: Box<Account<'info, Ballot>>,
+
+    /// Snapshot tree
+    #[account(
+        init,
+        payer = attester,
+        space = 8 + SnapshotTree::INIT_SPACE,
+        seeds = [seeds::SNAPSHOT_TREE, ballot_id.as_ref()],
+        bump,
+    )]
+    pub snapshot_tree: Box<Account<'info, SnapshotTree>>,
+
+    /// Ballot indexer or authority (pays for the tree)
+    #[account(
+        mut,
+        constraint = attester.key() == ballot.indexer_pubkey
+            || attester.key() == ballot.authority @ CloakCraftError::Unauthorized,
+    )]
+    pub attester: Signer<'info>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_snapshot_tree(
+    ctx: Context<InitializeSnapshotTree>,
+    ballot_id: [u8; 32],
+) -> Result<()> {
+    let tree = &mut ctx.accounts.snapshot_tree;
+    tree.ballot_id = ballot_id;
+    tree.leaf_count = 0;
+    tree.frontier = [[0u8; 32]; MERKLE_TREE_DEPTH];
+    tree.total_roots = 0;
+    tree.roots = [[0u8; 32]; MAX_SNAPSHOT_ROOTS];
+    tree.bump = ctx.bumps.snapshot_tree;
+
+    msg!("Snapshot tree initialized");
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! Migrate a ballot account to the current layout
+//!
+//! Ballot gained fields (snapshot roots, LP vote sources, TWAB roots,
+//! proposal bond, option registration, hidden turnout, loser refunds, oracle
+//! disputes) after the first ballots were created. They all follow `bump`, so
+//! an older account keeps its bytes in place but is too small to deserialize.
+//! This reallocates it to `Ballot::SPACE` with the new fields zeroed, which is
+//! each field's disabled default, so live ballots (including SpendToVote
+//! ballots holding locked tokens) can be voted on, resolved and claimed again.
+//!
+//! The result doesn't depend on the caller, so anyone willing to pay the
+//! extra rent may migrate a ballot.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+
+use crate::state::Ballot;
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+
+/// Emitted when a ballot account is migrated
+#[event]
+pub struct BallotMigrated {
+    pub ballot: Pubkey,
+    pub old_len: u64,
+    pub new_len: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(ballot_id: [u8; 32])]
+pub struct MigrateBallot<'info> {
+    /// Ballot to migrate (older layout, checked manually)
+    /// CHECK: Discriminator is checked in the handler
+    #[account(
+        mut,
+        seeds = [seeds::BALLOT, ballot_id.as_ref()],
+        bump,
+        owner = crate::ID,
+    )]
+    pub ballot: UncheckedAccount<'info>,
+
+    /// Payer for reallocation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// System program for reallocation
+    pub system_program: Program<'info, System>,
+}
+
+/// Reallocate a ballot to the current layout
+///
+/// # Arguments
+/// * `ballot_id` - Ballot identifier (PDA seed)
+pub fn migrate_ballot<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateBallot<'info>>,
+    _ballot_id: [u8; 32],
+) -> Result<()> {
+    let ballot_info = ctx.accounts.ballot.to_account_info();
+    let old_len = ballot_info.data_len();
+
+    {
+        let data = ballot_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == Ballot::DISCRIMINATOR[..],
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+    }
+    require!(old_len < Ballot::SPACE, CloakCraftError::AccountAlreadyMigrated);
+
+    // Top up rent for the larger account
+    let rent_due = Rent::get()?
+        .minimum_balance(Ballot::SPACE)
+        .saturating_sub(ballot_info.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: ballot_info.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    // New fields are zero-filled, which is their disabled default
+    ballot_info.resize(Ballot::SPACE)?;
+
+    // Sanity check: the reallocated account must deserialize
+    {
+        let data = ballot_info.try_borrow_data()?;
+        Ballot::try_deserialize(&mut &data[..])?;
+    }
+
+    emit!(BallotMigrated {
+        ballot: ballot_info.key(),
+        old_len: old_len as u64,
+        new_len: Ballot::SPACE as u64,
+    });
+    msg!("Ballot {} migrated: {} -> {} bytes", ballot_info.key(), old_len, Ballot::SPACE);
+
+    Ok(())
+}
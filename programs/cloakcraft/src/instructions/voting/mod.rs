@@ -9,14 +9,24 @@
 // Admin instructions
 mod create_ballot;
 mod resolve_ballot;
+mod dispute_resolution;
+mod settle_dispute;
 mod finalize_ballot;
 mod decrypt_tally;
+mod register_snapshot_root;
+mod register_lp_vote_source;
+mod initialize_snapshot_tree;
+mod append_snapshot_leaves;
+mod create_ballot_options_page;
+mod add_ballot_option;
+mod migrate_ballot;
 
 // Snapshot voting (multi-phase)
 mod create_pending_with_proof_vote_snapshot;
 mod create_vote_nullifier;
 mod execute_vote_snapshot;
 mod create_vote_commitment;
+mod create_pending_with_proof_vote_twab;
 
 // Snapshot vote change (atomic, multi-phase)
 mod create_pending_with_proof_change_vote_snapshot;
@@ -37,19 +47,32 @@ mod execute_close_vote_position;
 
 // Claim (multi-phase, SpendToVote only)
 mod create_pending_with_proof_claim;
+mod create_pending_with_proof_claim_multi;
+mod create_pending_with_proof_claim_refund;
 mod execute_claim;
+mod quote_claim_payout;
 
 // Admin exports
 pub use create_ballot::*;
 pub use resolve_ballot::*;
+pub use dispute_resolution::*;
+pub use settle_dispute::*;
 pub use finalize_ballot::*;
 pub use decrypt_tally::*;
+pub use register_snapshot_root::*;
+pub use register_lp_vote_source::*;
+pub use initialize_snapshot_tree::*;
+pub use append_snapshot_leaves::*;
+pub use create_ballot_options_page::*;
+pub use add_ballot_option::*;
+pub use migrate_ballot::*;
 
 // Snapshot voting exports
 pub use create_pending_with_proof_vote_snapshot::*;
 pub use create_vote_nullifier::*;
 pub use execute_vote_snapshot::*;
 pub use create_vote_commitment::*;
+pub use create_pending_with_proof_vote_twab::*;
 
 // Snapshot vote change exports
 pub use create_pending_with_proof_change_vote_snapshot::*;
@@ -70,4 +93,7 @@ pub use execute_close_vote_position::*;
 
 // Claim exports
 pub use create_pending_with_proof_claim::*;
+pub use create_pending_with_proof_claim_multi::*;
+pub use create_pending_with_proof_claim_refund::*;
 pub use execute_claim::*;
+pub use quote_claim_payout::*;
@@ -0,0 +1,59 @@
+//! Quote Claim Payout (read-only)
+//!
+//! Computes the payout a winning SpendToVote position would receive with the
+//! same math `create_pending_with_proof_claim` enforces, and returns it as a
+//! `ClaimPayoutQuote` (return data). Wallets call it via simulation and CPI
+//! callers read it with `get_return_data`. Nothing is written.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::seeds;
+use crate::state::Ballot;
+
+/// Claim payout returned by `quote_claim_payout` (return data)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ClaimPayoutQuote {
+    /// Share of the pool before the protocol fee
+    pub gross_payout: u64,
+    /// Payout after the protocol fee (what the claim note receives)
+    pub net_payout: u64,
+    /// Whether claims are currently open
+    pub claims_allowed: bool,
+}
+
+#[derive(Accounts)]
+#[instruction(ballot_id: [u8; 32])]
+pub struct QuoteClaimPayout<'info> {
+    /// Ballot
+    #[account(
+        seeds = [seeds::BALLOT, ballot_id.as_ref()],
+        bump = ballot.bump,
+    )]
+    pub ballot: Box<Account<'info, Ballot>>,
+}
+
+/// Quote the payout for a winning position of `user_weight`
+pub fn quote_claim_payout(
+    ctx: Context<QuoteClaimPayout>,
+    _ballot_id: [u8; 32],
+    user_weight: u64,
+) -> Result<ClaimPayoutQuote> {
+    let ballot = &ctx.accounts.ballot;
+    let (gross_payout, net_payout) = ballot.calculate_payout(user_weight);
+
+    let quote = ClaimPayoutQuote {
+        gross_payout,
+        net_payout,
+        claims_allowed: ballot.claims_allowed(Clock::get()?.unix_timestamp),
+    };
+
+    msg!(
+        "Claim quote: weight={}, gross={}, net={}, claims_allowed={}",
+        user_weight,
+        gross_payout,
+        net_payout,
+        quote.claims_allowed
+    );
+
+    Ok(quote)
+}
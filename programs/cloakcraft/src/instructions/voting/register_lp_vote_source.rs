@@ -0,0 +1,80 @@
+//! Register an LP mint for snapshot voting
+//!
+//! Lets AMM and perps liquidity providers vote with their LP notes. The
+//! ballot's indexer (or its authority) attests the LP-to-token conversion rate
+//! at the snapshot; LP note amounts are converted at that rate when tallied.
+//!
+//! Sources can only be added before the first vote so every vote on the
+//! ballot is counted at the same rates.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{Ballot, LpVoteSource, VoteBindingMode, MAX_LP_VOTE_SOURCES};
+
+/// Emitted when an LP mint is registered for a ballot
+#[event]
+pub struct LpVoteSourceRegistered {
+    pub ballot_id: [u8; 32],
+    pub lp_mint: Pubkey,
+    pub rate: u128,
+    pub attester: Pubkey,
+}
+
+#[derive(Accounts)]
+#[instruction(ballot_id: [u8; 32])]
+pub struct RegisterLpVoteSource<'info> {
+    /// Ballot to register the LP mint on
+    #[account(
+        mut,
+        seeds = [seeds::BALLOT, ballot_id.as_ref()],
+        bump = ballot.bump,
+        constraint = ballot.binding_mode == VoteBindingMode::Snapshot @ CloakCraftError::InvalidBindingMode,
+    )]
+    pub ballot: Box<Account<'info, Ballot>>,
+
+    /// Ballot indexer or authority
+    #[account(
+        constraint = attester.key() == ballot.indexer_pubkey
+            || attester.key() == ballot.authority @ CloakCraftError::Unauthorized,
+    )]
+    pub attester: Signer<'info>,
+}
+
+pub fn register_lp_vote_source(
+    ctx: Context<RegisterLpVoteSource>,
+    ballot_id: [u8; 32],
+    lp_mint: Pubkey,
+    rate: u128,
+) -> Result<()> {
+    let ballot = &mut ctx.accounts.ballot;
+
+    if ballot.has_votes() {
+        return Err(CloakCraftError::LpVoteSourceAfterVotes.into());
+    }
+    if rate == 0 || lp_mint == ballot.token_mint {
+        return Err(CloakCraftError::InvalidLpVoteSource.into());
+    }
+    if ballot.lp_vote_sources().iter().any(|s| s.lp_mint == lp_mint) {
+        return Err(CloakCraftError::LpVoteSourceAlreadyRegistered.into());
+    }
+
+    let index = ballot.lp_vote_source_count as usize;
+    if index >= MAX_LP_VOTE_SOURCES {
+        return Err(CloakCraftError::TooManyLpVoteSources.into());
+    }
+    ballot.lp_vote_sources[index] = LpVoteSource { lp_mint, rate };
+    ballot.lp_vote_source_count += 1;
+
+    emit!(LpVoteSourceRegistered {
+        ballot_id,
+        lp_mint,
+        rate,
+        attester: ctx.accounts.attester.key(),
+    });
+
+    msg!("LP vote source registered: {} at rate {}", lp_mint, rate);
+
+    Ok(())
+}
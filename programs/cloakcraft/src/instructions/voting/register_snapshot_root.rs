@@ -0,0 +1,84 @@
+//! Register the snapshot root for a Snapshot-mode ballot
+//!
+//! The ballot's trusted indexer (or its authority) attests the commitment tree
+//! root archived at `snapshot_slot`. Once registered, snapshot votes must prove
+//! note ownership against this root, so notes spent after the snapshot still
+//! carry their voting power and notes created after it carry none.
+//!
+//! The root can be registered once, after `snapshot_slot` and before any vote
+//! is cast, so every vote on the ballot is checked against the same root.
+//!
+//! TWAB ballots register `twab_num_roots` distinct roots, oldest first, one
+//! per call and at least `twab_interval_slots` apart, before the window
+//! closes at `snapshot_slot + twab_interval_slots * twab_num_roots`. Each root
+//! is stored with its registration slot; voting opens once all are registered.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{Ballot, VoteBindingMode};
+
+/// Emitted when a ballot's snapshot root is registered
+#[event]
+pub struct SnapshotRootRegistered {
+    pub ballot_id: [u8; 32],
+    pub snapshot_slot: u64,
+    pub snapshot_root: [u8; 32],
+    /// Slot the root was registered at
+    pub registered_slot: u64,
+    /// Roots registered so far (TWAB mode, 0 otherwise)
+    pub twab_root_count: u8,
+    pub attester: Pubkey,
+}
+
+#[derive(Accounts)]
+#[instruction(ballot_id: [u8; 32])]
+pub struct RegisterSnapshotRoot<'info> {
+    /// Ballot to register the root on
+    #[account(
+        mut,
+        seeds = [seeds::BALLOT, ballot_id.as_ref()],
+        bump = ballot.bump,
+        constraint = ballot.binding_mode == VoteBindingMode::Snapshot @ CloakCraftError::InvalidBindingMode,
+    )]
+    pub ballot: Box<Account<'info, Ballot>>,
+
+    /// Ballot indexer or authority
+    #[account(
+        constraint = attester.key() == ballot.indexer_pubkey
+            || attester.key() == ballot.authority @ CloakCraftError::Unauthorized,
+    )]
+    pub attester: Signer<'info>,
+}
+
+pub fn register_snapshot_root(
+    ctx: Context<RegisterSnapshotRoot>,
+    ballot_id: [u8; 32],
+    snapshot_root: [u8; 32],
+) -> Result<()> {
+    let ballot = &mut ctx.accounts.ballot;
+    let clock = Clock::get()?;
+
+    if clock.slot < ballot.snapshot_slot {
+        return Err(CloakCraftError::SnapshotSlotNotReached.into());
+    }
+    if ballot.has_votes() {
+        return Err(CloakCraftError::SnapshotRootAfterVotes.into());
+    }
+
+    ballot.register_snapshot_root(snapshot_root, clock.slot)?;
+
+    emit!(SnapshotRootRegistered {
+        ballot_id,
+        snapshot_slot: ballot.snapshot_slot,
+        snapshot_root,
+        registered_slot: clock.slot,
+        twab_root_count: ballot.twab_root_count,
+        attester: ctx.accounts.attester.key(),
+    });
+
+    msg!("Snapshot root registered at slot {}", ballot.snapshot_slot);
+
+    Ok(())
+}
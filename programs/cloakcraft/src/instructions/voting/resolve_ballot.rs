@@ -4,12 +4,33 @@
 //! - TallyBased: Winner = argmax(option_weights[])
 //! - Oracle: Reads outcome from oracle account
 //! - Authority: Outcome set by designated resolver
+//!
+//! Ballots with more than 16 options pass their `BallotOptionsPage` accounts,
+//! in page order, as remaining accounts: TallyBased needs all of them, and
+//! the other modes need the page of the outcome.
+//!
+//! Ballots with a proposal bond settle it here: the bond is refunded to the
+//! creator if quorum was reached and slashed to the protocol treasury otherwise.
+//!
+//! Oracle ballots with a dispute window can be disputed for
+//! `dispute_window_seconds` after resolution (see dispute_resolution); claims
+//! and finalization wait for the window to pass.
 
 use anchor_lang::prelude::*;
 
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
-use crate::state::{Ballot, BallotStatus, ResolutionMode, RevealMode, VoteBindingMode};
+use crate::state::{Ballot, BallotOptionsPage, BallotStatus, ProtocolConfig, ResolutionMode, RevealMode, VoteBindingMode};
+
+/// Emitted when a ballot's proposal bond is refunded or slashed
+#[event]
+pub struct ProposalBondSettled {
+    pub ballot_id: [u8; 32],
+    pub amount: u64,
+    pub recipient: Pubkey,
+    /// True if refunded to the creator, false if slashed to treasury
+    pub refunded: bool,
+}
 
 #[derive(Accounts)]
 #[instruction(ballot_id: [u8; 32])]
@@ -28,13 +49,27 @@ pub struct ResolveBallot<'info> {
 
     /// Authority (required for non-Authority modes if resolver not set)
     pub authority: Signer<'info>,
+
+    /// Protocol config (required if the ballot has a proposal bond)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Option<Box<Account<'info, ProtocolConfig>>>,
+
+    /// Bond recipient: ballot authority if quorum is met, protocol treasury otherwise
+    /// CHECK: Validated against the ballot and protocol config in the handler
+    #[account(mut)]
+    pub bond_recipient: Option<UncheckedAccount<'info>>,
 }
 
 pub fn resolve_ballot(
     ctx: Context<ResolveBallot>,
-    _ballot_id: [u8; 32],
+    ballot_id: [u8; 32],
     outcome: Option<u8>,
 ) -> Result<()> {
+    let options_pages = load_options_pages(ctx.remaining_accounts)?;
+
     let ballot = &mut ctx.accounts.ballot;
     let clock = Clock::get()?;
     let current_time = clock.unix_timestamp;
@@ -54,7 +89,7 @@ pub fn resolve_ballot(
         BallotStatus::Closed => {
             // Already closed, proceed with resolution
         }
-        BallotStatus::Resolved | BallotStatus::Finalized => {
+        BallotStatus::Resolved | BallotStatus::Finalized | BallotStatus::Disputed => {
             return Err(CloakCraftError::BallotAlreadyResolved.into());
         }
         BallotStatus::Pending => {
@@ -69,20 +104,24 @@ pub fn resolve_ballot(
         // Check if tally has been decrypted (option_weights should be non-zero if votes exist)
         // This is a simplified check; actual implementation may need a dedicated flag
         let encrypted_tally_zeroed = ballot.encrypted_tally.iter().all(|ct| ct == &[0u8; 64]);
-        let has_votes = ballot.vote_count > 0;
+        let has_votes = ballot.has_votes();
 
         // If there are votes but encrypted tally is not zeroed out (decrypted),
         // and option_weights are all zero, decryption hasn't happened
         if has_votes && !encrypted_tally_zeroed && ballot.total_weight == 0 {
             return Err(CloakCraftError::TallyNotDecrypted.into());
         }
+        // Hidden turnout is cleared by decrypt_tally
+        if ballot.encrypted_vote_count != [0u8; 64] {
+            return Err(CloakCraftError::TallyNotDecrypted.into());
+        }
     }
 
     // Determine outcome based on resolution mode
     let winning_option = match ballot.resolution_mode {
         ResolutionMode::TallyBased => {
             // Find option with maximum weight
-            ballot.tally_based_winner()
+            ballot.paged_winner(&options_pages)?
         }
         ResolutionMode::Oracle => {
             // Oracle must have submitted outcome
@@ -111,6 +150,76 @@ pub fn resolve_ballot(
         }
     };
 
+    // Check quorum and set outcome
+    let quorum_met = apply_outcome(ballot, &options_pages, winning_option)?;
+
+    // Refund or slash the proposal bond
+    let treasury = ctx
+        .accounts
+        .protocol_config
+        .as_ref()
+        .map(|c| c.treasury)
+        .unwrap_or_default();
+    if let Some(expected) = ballot.bond_recipient(quorum_met, treasury) {
+        if !quorum_met && ctx.accounts.protocol_config.is_none() {
+            return Err(CloakCraftError::MissingBondRecipient.into());
+        }
+        let recipient = ctx
+            .accounts
+            .bond_recipient
+            .as_ref()
+            .ok_or(CloakCraftError::MissingBondRecipient)?;
+        if recipient.key() != expected {
+            return Err(CloakCraftError::InvalidBondRecipient.into());
+        }
+
+        let amount = ballot.proposal_bond;
+        **ballot.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **recipient.to_account_info().try_borrow_mut_lamports()? += amount;
+        ballot.bond_settled = true;
+
+        emit!(ProposalBondSettled {
+            ballot_id,
+            amount,
+            recipient: expected,
+            refunded: quorum_met,
+        });
+        msg!("  Proposal bond {} to {}", if quorum_met { "refunded" } else { "slashed" }, expected);
+    }
+
+    // Transition to Resolved (opens the dispute window, if any)
+    ballot.status = BallotStatus::Resolved;
+    ballot.resolved_at = current_time;
+
+    msg!("Ballot resolved");
+    msg!("  Quorum met: {}", quorum_met);
+    msg!("  Has outcome: {}", ballot.has_outcome);
+    if ballot.has_outcome {
+        msg!("  Winning option: {}", ballot.outcome);
+        msg!("  Winner weight: {}", ballot.winner_weight);
+    }
+
+    Ok(())
+}
+
+/// Options pages of paged ballots, in page order (remaining accounts)
+pub(crate) fn load_options_pages(accounts: &[AccountInfo]) -> Result<Vec<BallotOptionsPage>> {
+    accounts
+        .iter()
+        .map(|info| {
+            require_keys_eq!(*info.owner, crate::ID, CloakCraftError::InvalidBallotOptionsPage);
+            BallotOptionsPage::try_deserialize(&mut &info.try_borrow_data()?[..])
+        })
+        .collect()
+}
+
+/// Set the ballot's outcome to `winning_option` if quorum was met, and to no
+/// winner otherwise. Returns whether quorum was met.
+pub(crate) fn apply_outcome(
+    ballot: &mut Ballot,
+    options_pages: &[BallotOptionsPage],
+    winning_option: Option<u8>,
+) -> Result<bool> {
     // Validate outcome if provided
     if let Some(opt) = winning_option {
         if opt >= ballot.num_options {
@@ -118,15 +227,12 @@ pub fn resolve_ballot(
         }
     }
 
-    // Check quorum
     let quorum_met = ballot.quorum_met();
-
-    // Set outcome
     if quorum_met {
         if let Some(opt) = winning_option {
             ballot.outcome = opt;
             ballot.has_outcome = true;
-            ballot.winner_weight = ballot.option_weights[opt as usize];
+            ballot.winner_weight = ballot.option_weight(options_pages, opt)?;
         } else {
             // No winner (e.g., no votes)
             ballot.outcome = 0;
@@ -147,16 +253,5 @@ pub fn resolve_ballot(
              });
     }
 
-    // Transition to Resolved
-    ballot.status = BallotStatus::Resolved;
-
-    msg!("Ballot resolved");
-    msg!("  Quorum met: {}", quorum_met);
-    msg!("  Has outcome: {}", ballot.has_outcome);
-    if ballot.has_outcome {
-        msg!("  Winning option: {}", ballot.outcome);
-        msg!("  Winner weight: {}", ballot.winner_weight);
-    }
-
-    Ok(())
+    Ok(quorum_met)
 }
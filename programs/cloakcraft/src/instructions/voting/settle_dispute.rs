@@ -0,0 +1,97 @@
+//! Settle a disputed Oracle outcome
+//!
+//! The ballot's resolver sets the final outcome of a disputed ballot (see
+//! dispute_resolution). The challenger's bond goes back to them if the
+//! outcome changes, and is slashed to the protocol treasury if the oracle's
+//! outcome is upheld. The outcome is final once settled.
+//!
+//! Paged ballots pass the `BallotOptionsPage` of the outcome as a remaining
+//! account, as for resolve_ballot.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::seeds;
+use crate::errors::CloakCraftError;
+use crate::state::{Ballot, BallotStatus, ProtocolConfig};
+use super::resolve_ballot::{apply_outcome, load_options_pages};
+
+/// Emitted when a disputed outcome is settled
+#[event]
+pub struct DisputeSettled {
+    pub ballot_id: [u8; 32],
+    /// Final outcome (None if no winner)
+    pub outcome: Option<u8>,
+    /// True if the oracle's outcome stood (bond slashed)
+    pub upheld: bool,
+    pub bond: u64,
+    pub bond_recipient: Pubkey,
+}
+
+#[derive(Accounts)]
+#[instruction(ballot_id: [u8; 32])]
+pub struct SettleDispute<'info> {
+    /// Disputed ballot
+    #[account(
+        mut,
+        seeds = [seeds::BALLOT, ballot_id.as_ref()],
+        bump = ballot.bump,
+        constraint = ballot.status == BallotStatus::Disputed @ CloakCraftError::BallotNotDisputed,
+    )]
+    pub ballot: Box<Account<'info, Ballot>>,
+
+    /// Ballot resolver (authority or committee the dispute escalates to)
+    #[account(
+        constraint = ballot.has_resolver && resolver.key() == ballot.resolver @ CloakCraftError::UnauthorizedResolver,
+    )]
+    pub resolver: Signer<'info>,
+
+    /// Protocol config (treasury receiving slashed bonds)
+    #[account(
+        seeds = [seeds::PROTOCOL_CONFIG],
+        bump = protocol_config.bump,
+    )]
+    pub protocol_config: Box<Account<'info, ProtocolConfig>>,
+
+    /// Bond recipient: the challenger if overturned, the protocol treasury if upheld
+    /// CHECK: Validated against the ballot and protocol config in the handler
+    #[account(mut)]
+    pub bond_recipient: UncheckedAccount<'info>,
+}
+
+pub fn settle_dispute(
+    ctx: Context<SettleDispute>,
+    ballot_id: [u8; 32],
+    outcome: Option<u8>,
+) -> Result<()> {
+    let options_pages = load_options_pages(ctx.remaining_accounts)?;
+    let ballot = &mut ctx.accounts.ballot;
+
+    let disputed = ballot.has_outcome.then_some(ballot.outcome);
+    apply_outcome(ballot, &options_pages, outcome)?;
+    let upheld = ballot.has_outcome.then_some(ballot.outcome) == disputed;
+
+    let expected = if upheld { ctx.accounts.protocol_config.treasury } else { ballot.challenger };
+    if ctx.accounts.bond_recipient.key() != expected {
+        return Err(CloakCraftError::InvalidBondRecipient.into());
+    }
+
+    let bond = ballot.dispute_bond;
+    **ballot.to_account_info().try_borrow_mut_lamports()? -= bond;
+    **ctx.accounts.bond_recipient.to_account_info().try_borrow_mut_lamports()? += bond;
+
+    // challenger stays set, so the outcome is final (see Ballot::outcome_final)
+    ballot.status = BallotStatus::Resolved;
+
+    emit!(DisputeSettled {
+        ballot_id,
+        outcome: ballot.has_outcome.then_some(ballot.outcome),
+        upheld,
+        bond,
+        bond_recipient: expected,
+    });
+
+    msg!("Dispute settled: oracle outcome {}", if upheld { "upheld" } else { "overturned" });
+    msg!("  Bond {} to {}", bond, expected);
+
+    Ok(())
+}
@@ -10,12 +10,20 @@
 //! - claim: Verify position commitment exists
 //!
 //! Unlike the generic verify_commitment_exists (which uses Pool), this uses Ballot.
+//!
+//! SECURITY BINDING: The account hash is recomputed on-chain from the vote
+//! commitment's address (ballot_id + commitment), so a hash for another
+//! ballot's account is rejected.
+//!
+//! PINNED MODE: The pinned inclusion threshold of the ballot token's shielded
+//! pool applies as in verify_commitment_exists, against the vote amount or
+//! payout (see `PendingOperation::ballot_value_bound`).
 
 use anchor_lang::prelude::*;
 
 use crate::constants::seeds;
 use crate::errors::CloakCraftError;
-use crate::state::{Ballot, LightAddressTreeInfo, LightValidityProof, PendingOperation};
+use crate::state::{Ballot, LightAddressTreeInfo, LightValidityProof, PendingOperation, Pool};
 
 /// Merkle context for commitment verification
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -24,6 +32,7 @@ pub struct VoteCommitmentMerkleContext {
     pub queue_pubkey_index: u8,
     pub leaf_index: u32,
     pub root_index: u16,
+    pub prove_by_index: bool,
 }
 
 /// Parameters for vote commitment inclusion verification
@@ -31,6 +40,9 @@ pub struct VoteCommitmentMerkleContext {
 pub struct LightVerifyVoteCommitmentParams {
     /// Account hash of commitment to verify (from Light Protocol indexer)
     pub commitment_account_hash: [u8; 32],
+    /// Data hash of the commitment account (from the indexer), used to
+    /// recompute the account hash on-chain
+    pub commitment_data_hash: [u8; 32],
     /// Merkle context proving commitment exists
     pub commitment_merkle_context: VoteCommitmentMerkleContext,
     /// Inclusion proof for commitment
@@ -49,6 +61,14 @@ pub struct VerifyVoteCommitmentExists<'info> {
     )]
     pub ballot: Box<Account<'info, Ballot>>,
 
+    /// Shielded pool of the ballot's token (source of the pinned inclusion threshold)
+    /// CHECK: Address checked by seeds; empty when the token has no pool
+    #[account(
+        seeds = [seeds::POOL, ballot.token_mint.as_ref()],
+        bump,
+    )]
+    pub token_pool: UncheckedAccount<'info>,
+
     /// Pending operation PDA (from Phase 0)
     #[account(
         mut,
@@ -135,6 +155,19 @@ pub fn verify_vote_commitment_exists<'info>(
         &light_params.commitment_account_hash[0..8]
     );
 
+    // SECURITY: High-value operations don't trust queue/index context
+    let token_pool = &ctx.accounts.token_pool;
+    if !token_pool.data_is_empty() {
+        let pool = Pool::try_deserialize(&mut &token_pool.try_borrow_data()?[..])?;
+        if pool.requires_pinned_inclusion(pending_op.ballot_value_bound()) {
+            require!(
+                !light_params.commitment_merkle_context.prove_by_index,
+                CloakCraftError::PinnedInclusionRequired
+            );
+            msg!("Pinned inclusion: root index {}", light_params.commitment_merkle_context.root_index);
+        }
+    }
+
     // SECURITY: Verify THIS EXACT commitment exists in Light Protocol state tree
     // For voting, we use the ballot key as the "pool" context
     crate::light_cpi::verify_vote_commitment_inclusion(
@@ -146,6 +179,8 @@ pub fn verify_vote_commitment_exists<'info>(
         light_params.commitment_address_tree_info,
         input_commitment,
         ballot.key(),
+        ballot_id,
+        light_params.commitment_data_hash,
     )?;
 
     msg!("Vote commitment verified - exists in state tree");
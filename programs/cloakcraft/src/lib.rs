@@ -25,17 +25,23 @@ use instructions::perps::{
     InitializePerpsPool, InitializePerpsPoolParams,
     AddTokenToPool, AddMarket,
     UpdatePoolConfig, UpdatePoolConfigParams,
-    UpdateTokenStatus, UpdateMarketStatus,
+    UpdateTokenStatus, UpdateMarketStatus, UpdateTokenTargetWeight,
+    DelistMarket, BeginPoolMigration, MigrateTokenVault, FinalizePoolMigration,
     // Position
     CreatePendingWithProofOpenPosition, ExecuteOpenPosition,
     CreatePendingWithProofClosePosition, ExecuteClosePosition,
+    CreatePendingWithProofTransferPosition, QuotePositionValue,
+    CreatePositionMeta, VerifyPositionMetaActive, CreatePositionMetaTransfer,
     // Liquidity
     CreatePendingWithProofAddPerpsLiquidity, ExecuteAddPerpsLiquidity,
     CreatePendingWithProofRemovePerpsLiquidity, ExecuteRemovePerpsLiquidity,
+    CreatePendingWithProofConvertLpNote,
     // Keeper
-    UpdateBorrowFees,
+    UpdateBorrowFees, RebalancePool, ForceSettleMarket,
     CreatePendingWithProofLiquidate, ExecuteLiquidate,
     CheckProfitBound, EmitProfitBoundEvent,
+    // Limit orders
+    CreatePerpOrder, ExecutePerpOrderFill, CloseExpiredPerpOrder,
 };
 
 declare_id!("2VWF9TxMFgzHwbd5WPpYKoqHvtzk3fN66Ka3tVV82nZG");
@@ -51,8 +57,192 @@ pub mod cloakcraft {
     // ============ Pool Operations ============
 
     /// Initialize a new shielded pool for a token
-    pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
-        pool::initialize_pool(ctx)
+    ///
+    /// Pins the pool to a Light output state tree and address tree.
+    pub fn initialize_pool(
+        ctx: Context<InitializePool>,
+        state_tree: Pubkey,
+        address_tree: Pubkey,
+    ) -> Result<()> {
+        pool::initialize_pool(ctx, state_tree, address_tree)
+    }
+
+    /// Set the minimum recent anonymity set required before unshields
+    ///
+    /// Only callable by the pool authority. 0 disables the guard.
+    pub fn set_anonymity_guard(ctx: Context<SetAnonymityGuard>, min_anonymity_guard: u32) -> Result<()> {
+        pool::set_anonymity_guard(ctx, min_anonymity_guard)
+    }
+
+    /// Bypass the anonymity guard for a bounded period
+    ///
+    /// Only callable by the pool authority. Emits `AnonymityGuardOverridden`.
+    pub fn override_anonymity_guard(ctx: Context<OverrideAnonymityGuard>, duration_seconds: i64) -> Result<()> {
+        pool::override_anonymity_guard(ctx, duration_seconds)
+    }
+
+    /// Restrict a pool to fixed note denominations (all zeros = flexible)
+    ///
+    /// Only callable by the pool authority. Transfers in denomination pools
+    /// must use the denomination transfer circuit.
+    pub fn set_fixed_denominations(
+        ctx: Context<SetFixedDenominations>,
+        denominations: [u64; state::MAX_DENOMINATIONS],
+    ) -> Result<()> {
+        pool::set_fixed_denominations(ctx, denominations)
+    }
+
+    /// Set a pool's dust threshold for change notes (0 = disabled)
+    ///
+    /// Only callable by the pool authority.
+    pub fn set_min_note_amount(ctx: Context<SetMinNoteAmount>, min_note_amount: u64) -> Result<()> {
+        pool::set_min_note_amount(ctx, min_note_amount)
+    }
+
+    /// Set the largest encrypted note create_commitment stores
+    ///
+    /// Only callable by the pool authority. Bounded by the inline capacity
+    /// below and the protocol hard cap above.
+    pub fn set_max_encrypted_note_size(ctx: Context<SetMaxEncryptedNoteSize>, max_encrypted_note_size: u16) -> Result<()> {
+        pool::set_max_encrypted_note_size(ctx, max_encrypted_note_size)
+    }
+
+    /// Create a pool's (empty) relayer allowlist
+    ///
+    /// Only callable by the pool authority.
+    pub fn initialize_relayer_allowlist(ctx: Context<InitializeRelayerAllowlist>) -> Result<()> {
+        pool::initialize_relayer_allowlist(ctx)
+    }
+
+    /// Add or remove a relayer on a pool's allowlist
+    ///
+    /// Only callable by the pool authority.
+    pub fn set_allowed_relayer(ctx: Context<SetAllowedRelayer>, relayer: Pubkey, allowed: bool) -> Result<()> {
+        pool::set_allowed_relayer(ctx, relayer, allowed)
+    }
+
+    /// Restrict who may submit operations for a pool (false = permissionless)
+    ///
+    /// Only callable by the pool authority. While enabled, Phase 0 of
+    /// transfers, consolidations, swaps and liquidity changes spending from
+    /// the pool requires the relayer to be on the pool's allowlist.
+    pub fn set_relayer_allowlist_enabled(ctx: Context<SetRelayerAllowlistEnabled>, enabled: bool) -> Result<()> {
+        pool::set_relayer_allowlist_enabled(ctx, enabled)
+    }
+
+    /// Schedule a rollover of the pool's Light state tree
+    ///
+    /// Only callable by the pool authority. From `cutover_slot` on, new
+    /// commitments and nullifiers must be written to `new_state_tree`, which
+    /// must be registered in the tree registry with headroom.
+    pub fn migrate_pool_trees(ctx: Context<MigratePoolTrees>, cutover_slot: u64) -> Result<()> {
+        pool::migrate_pool_trees(ctx, cutover_slot)
+    }
+
+    /// Set the Light trees of a pool migrated without them
+    ///
+    /// Only callable by the pool authority, and only while the trees are
+    /// unset. `address_tree` must be the tree the pool's existing
+    /// commitments and nullifiers were derived in.
+    pub fn set_pool_trees(ctx: Context<SetPoolTrees>, state_tree: Pubkey, address_tree: Pubkey) -> Result<()> {
+        pool::set_pool_trees(ctx, state_tree, address_tree)
+    }
+
+    /// Reallocate a pool created with an older layout to the current one
+    ///
+    /// Permissionless: the added fields are zeroed (disabled) and the payer
+    /// covers the extra rent.
+    pub fn migrate_pool<'info>(ctx: Context<'_, '_, '_, 'info, MigratePool<'info>>, token_mint: Pubkey) -> Result<()> {
+        pool::migrate_pool(ctx, token_mint)
+    }
+
+    /// Create a pool's root checkpoint history
+    ///
+    /// Only callable by the pool authority, who designates the checkpoint authority.
+    pub fn initialize_root_checkpoints(
+        ctx: Context<InitializeRootCheckpoints>,
+        checkpoint_authority: Pubkey,
+        min_interval: i64,
+    ) -> Result<()> {
+        pool::initialize_root_checkpoints(ctx, checkpoint_authority, min_interval)
+    }
+
+    /// Anchor a signed state tree root checkpoint
+    ///
+    /// Records root + commitment count in the pool's hash-chained history.
+    /// Emits `RootCheckpointAnchored`.
+    pub fn anchor_root_checkpoint(
+        ctx: Context<AnchorRootCheckpoint>,
+        state_tree: Pubkey,
+        root: [u8; 32],
+    ) -> Result<()> {
+        pool::anchor_root_checkpoint(ctx, state_tree, root)
+    }
+
+    /// Create a pool's root registry (permissionless)
+    ///
+    /// Once created, pass it to shield / shield_signed / create_commitment to
+    /// append new commitments to the on-chain registry tree.
+    pub fn initialize_root_registry(ctx: Context<InitializeRootRegistry>) -> Result<()> {
+        pool::initialize_root_registry(ctx)
+    }
+
+    /// Check a commitment's merkle path against a retained registry root (read-only)
+    ///
+    /// CPI entrypoint for other programs. Returns the slot the root was produced in.
+    pub fn verify_external_inclusion(
+        ctx: Context<VerifyExternalInclusion>,
+        commitment: [u8; 32],
+        leaf_index: u32,
+        root: [u8; 32],
+        path: [[u8; 32]; constants::MERKLE_TREE_DEPTH],
+        max_root_age_slots: Option<u64>,
+    ) -> Result<u64> {
+        pool::verify_external_inclusion(ctx, commitment, leaf_index, root, path, max_root_age_slots)
+    }
+
+    /// Set how many slots a root registry root stays valid (0 = no limit)
+    ///
+    /// Only callable by the pool authority.
+    pub fn set_max_root_age_slots(ctx: Context<SetMaxRootAgeSlots>, max_root_age_slots: u64) -> Result<()> {
+        pool::set_max_root_age_slots(ctx, max_root_age_slots)
+    }
+
+    /// Set the operation value from which Phase 1 must verify inputs against
+    /// a pinned root with a validity proof (0 = never)
+    ///
+    /// Only callable by the pool authority.
+    pub fn set_pinned_inclusion_threshold(
+        ctx: Context<SetPinnedInclusionThreshold>,
+        pinned_inclusion_threshold: u64,
+    ) -> Result<()> {
+        pool::set_pinned_inclusion_threshold(ctx, pinned_inclusion_threshold)
+    }
+
+    /// Designate the AMM pool sync_reserves folds this vault's surplus into
+    ///
+    /// Only callable by the pool authority.
+    pub fn set_surplus_amm_pool(
+        ctx: Context<SetSurplusAmmPool>,
+        surplus_amm_pool: Pubkey,
+    ) -> Result<()> {
+        pool::set_surplus_amm_pool(ctx, surplus_amm_pool)
+    }
+
+    /// Create a pool's statistics account (permissionless)
+    ///
+    /// Once created, pass it to shield / unshield / create_commitment /
+    /// create_nullifier_and_pending to keep TVL, volume and note count current.
+    pub fn initialize_pool_stats(ctx: Context<InitializePoolStats>) -> Result<()> {
+        pool::initialize_pool_stats(ctx)
+    }
+
+    /// Check vault balance >= shielded supply - pending Phase 3 outflows (permissionless)
+    ///
+    /// Pass live PendingOperations spending from the pool as remaining accounts.
+    /// Emits `PoolInsolvencyDetected` on a shortfall; returns whether the pool is solvent.
+    pub fn verify_pool_solvency(ctx: Context<VerifyPoolSolvency>) -> Result<bool> {
+        pool::verify_pool_solvency(ctx)
     }
 
     /// Shield tokens - deposit public tokens into the shielded pool
@@ -61,6 +251,7 @@ pub mod cloakcraft {
     /// The light_params enable on-chain commitment storage via Light Protocol.
     /// The stealth_ephemeral_pubkey is stored so recipient can derive
     /// the stealth private key for decryption.
+    /// The optional view_tag lets scanners skip notes that aren't theirs.
     pub fn shield<'info>(
         ctx: Context<'_, '_, '_, 'info, Shield<'info>>,
         commitment: [u8; 32],
@@ -68,8 +259,52 @@ pub mod cloakcraft {
         stealth_ephemeral_pubkey: [u8; 64],
         encrypted_note: Vec<u8>,
         light_params: Option<pool::LightCommitmentParams>,
+        view_tag: Option<[u8; 8]>,
+    ) -> Result<()> {
+        pool::shield(ctx, commitment, amount, stealth_ephemeral_pubkey, encrypted_note, light_params, view_tag)
+    }
+
+    /// Shield signed - deposit tokens whose authority is another program's PDA
+    ///
+    /// CPI entry point: `depositor` signs the token transfer via invoke_signed,
+    /// `payer` funds the commitment. See the `cloakcraft-cpi` crate.
+    pub fn shield_signed<'info>(
+        ctx: Context<'_, '_, '_, 'info, ShieldSigned<'info>>,
+        commitment: [u8; 32],
+        amount: u64,
+        stealth_ephemeral_pubkey: [u8; 64],
+        encrypted_note: Vec<u8>,
+        light_params: pool::LightCommitmentParams,
+        view_tag: Option<[u8; 8]>,
+    ) -> Result<()> {
+        pool::shield_signed(ctx, commitment, amount, stealth_ephemeral_pubkey, encrypted_note, light_params, view_tag)
+    }
+
+    /// Shield pair - deposit into two pools in one transaction
+    ///
+    /// Both vault transfers and both commitments land atomically, e.g. to
+    /// prepare both legs of an add-liquidity. Emits `PairShielded`.
+    pub fn shield_pair<'info>(
+        ctx: Context<'_, '_, '_, 'info, ShieldPair<'info>>,
+        leg_a: pool::ShieldLeg,
+        leg_b: pool::ShieldLeg,
+    ) -> Result<()> {
+        pool::shield_pair(ctx, leg_a, leg_b)
+    }
+
+    /// Shield NFT - deposit a single SPL NFT or pNFT into its pool (amount = 1 note)
+    ///
+    /// The first NFT shield turns a fresh pool into an NFT pool and snapshots
+    /// the metadata hash. pNFT custody goes through Token Metadata token records.
+    pub fn shield_nft<'info>(
+        ctx: Context<'_, '_, '_, 'info, ShieldNft<'info>>,
+        commitment: [u8; 32],
+        stealth_ephemeral_pubkey: [u8; 64],
+        encrypted_note: Vec<u8>,
+        light_params: Option<pool::LightCommitmentParams>,
+        view_tag: Option<[u8; 8]>,
     ) -> Result<()> {
-        pool::shield(ctx, commitment, amount, stealth_ephemeral_pubkey, encrypted_note, light_params)
+        pool::shield_nft(ctx, commitment, stealth_ephemeral_pubkey, encrypted_note, light_params, view_tag)
     }
 
     /// Initialize commitment counter for a pool
@@ -79,6 +314,14 @@ pub mod cloakcraft {
         pool::initialize_commitment_counter(ctx)
     }
 
+    /// Move a pool's commitment counter past indices issued outside it (admin only)
+    pub fn reconcile_commitment_counter<'info>(
+        ctx: Context<'_, '_, '_, 'info, ReconcileCommitmentCounter<'info>>,
+        next_index: u64,
+    ) -> Result<()> {
+        admin::reconcile_commitment_counter(ctx, next_index)
+    }
+
     /// Create Pending with Proof Phase 0 - verify ZK proof and create PendingOperation (Transfer-specific)
     ///
     /// Append Pattern multi-phase operation flow:
@@ -107,8 +350,36 @@ pub mod cloakcraft {
         transfer_amount: u64,
         unshield_amount: u64,
         fee_amount: u64,
+        call_hash: Option<[u8; 32]>,
+        client_version: u32,
+    ) -> Result<()> {
+        pool::create_pending_with_proof(ctx, operation_id, proof, merkle_root, input_commitment, nullifier, out_commitments, output_recipients, output_amounts, output_randomness, stealth_ephemeral_pubkeys, transfer_amount, unshield_amount, fee_amount, call_hash, client_version)
+    }
+
+    /// Append Transfer to Batch Phase 0b (Append Pattern)
+    ///
+    /// Verifies another transfer proof and appends it to a pending transfer
+    /// (up to MAX_BATCH_TRANSFERS), before Phase 1 starts. Phases 1-2 then
+    /// run per input index; Phase 3, 4+ and close once for the batch.
+    /// Members must be shielded transfers from the same pool.
+    #[allow(clippy::too_many_arguments)]
+    pub fn append_transfer_to_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, AppendTransferToBatch<'info>>,
+        operation_id: [u8; 32],
+        proof: Vec<u8>,
+        merkle_root: [u8; 32],
+        input_commitment: [u8; 32],
+        nullifier: [u8; 32],
+        out_commitments: Vec<[u8; 32]>,
+        output_recipients: Vec<[u8; 32]>,
+        output_amounts: Vec<u64>,
+        output_randomness: Vec<[u8; 32]>,
+        stealth_ephemeral_pubkeys: Vec<[u8; 64]>,
+        transfer_amount: u64,
+        fee_amount: u64,
+        client_version: u32,
     ) -> Result<()> {
-        pool::create_pending_with_proof(ctx, operation_id, proof, merkle_root, input_commitment, nullifier, out_commitments, output_recipients, output_amounts, output_randomness, stealth_ephemeral_pubkeys, transfer_amount, unshield_amount, fee_amount)
+        pool::append_transfer_to_batch(ctx, operation_id, proof, merkle_root, input_commitment, nullifier, out_commitments, output_recipients, output_amounts, output_randomness, stealth_ephemeral_pubkeys, transfer_amount, fee_amount, client_version)
     }
 
     /// Create Pending with Proof Phase 0 - Consolidation (Append Pattern)
@@ -137,8 +408,9 @@ pub mod cloakcraft {
         output_amount: u64,
         output_randomness: [u8; 32],
         stealth_ephemeral_pubkey: [u8; 64],
+        client_version: u32,
     ) -> Result<()> {
-        pool::create_pending_with_proof_consolidation(ctx, operation_id, proof, merkle_root, num_inputs, input_commitments, nullifiers, out_commitment, output_recipient, output_amount, output_randomness, stealth_ephemeral_pubkey)
+        pool::create_pending_with_proof_consolidation(ctx, operation_id, proof, merkle_root, num_inputs, input_commitments, nullifiers, out_commitment, output_recipient, output_amount, output_randomness, stealth_ephemeral_pubkey, client_version)
     }
 
     /// Process Unshield Phase 3 - process unshield only (Transfer-specific)
@@ -148,12 +420,73 @@ pub mod cloakcraft {
     ///
     /// NOTE: Encrypted notes are NOT stored in PDA (saves ~1680 bytes).
     /// SDK must regenerate encrypted notes in Phase 4 from randomness stored in PendingOperation.
+    ///
+    /// Can create the recipient's ATA (relayer pays, optionally reimbursed out of
+    /// the fee via ata_reimbursement) and attach an SPL memo for exchange deposits.
+    /// Operations whose proof binds the recipient (call_hash set to
+    /// `compute_recipient_hash`) can only pay that recipient.
     pub fn process_unshield<'info>(
         ctx: Context<'_, '_, '_, 'info, ProcessUnshield<'info>>,
         operation_id: [u8; 32],
         unshield_amount: u64,
+        ata_reimbursement: u64,
+        memo: Option<String>,
+    ) -> Result<()> {
+        pool::process_unshield(ctx, operation_id, unshield_amount, ata_reimbursement, memo)
+    }
+
+    /// Process Unshield and Invoke Phase 3 - unshield then CPI a whitelisted program
+    ///
+    /// Replaces process_unshield for operations created with a call_hash.
+    /// The target program, recipient and call data must hash to the call_hash
+    /// bound by the ZK proof; target accounts are passed via remaining_accounts.
+    pub fn process_unshield_and_invoke<'info>(
+        ctx: Context<'_, '_, '_, 'info, ProcessUnshieldAndInvoke<'info>>,
+        operation_id: [u8; 32],
+        call_data: Vec<u8>,
+    ) -> Result<()> {
+        pool::process_unshield_and_invoke(ctx, operation_id, call_data)
+    }
+
+    /// Process Unshield NFT Phase 3 - release an NFT pool's NFT to a wallet
+    ///
+    /// Replaces process_unshield for NFT pools. The recipient ATA (and for
+    /// pNFTs the token record) is created with the relayer as payer.
+    pub fn process_unshield_nft<'info>(
+        ctx: Context<'_, '_, '_, 'info, ProcessUnshieldNft<'info>>,
+        operation_id: [u8; 32],
+    ) -> Result<()> {
+        pool::process_unshield_nft(ctx, operation_id)
+    }
+
+    /// Shield cNFT - move a Bubblegum compressed NFT into custody (amount = 1 note)
+    ///
+    /// Creates the asset's pool on first shield. Remaining accounts: leaf proof
+    /// nodes (proof_len), then Light Protocol accounts.
+    #[allow(clippy::too_many_arguments)]
+    pub fn shield_cnft<'info>(
+        ctx: Context<'_, '_, '_, 'info, ShieldCnft<'info>>,
+        asset_id: Pubkey,
+        leaf: helpers::bubblegum::CnftLeaf,
+        proof_len: u8,
+        commitment: [u8; 32],
+        stealth_ephemeral_pubkey: [u8; 64],
+        encrypted_note: Vec<u8>,
+        light_params: pool::LightCommitmentParams,
+        view_tag: Option<[u8; 8]>,
     ) -> Result<()> {
-        pool::process_unshield(ctx, operation_id, unshield_amount)
+        adapter::shield_cnft(ctx, asset_id, leaf, proof_len, commitment, stealth_ephemeral_pubkey, encrypted_note, light_params, view_tag)
+    }
+
+    /// Process Unshield cNFT Phase 3 - release a cNFT pool's asset from custody
+    ///
+    /// Replaces process_unshield for cNFT pools. Remaining accounts: leaf proof nodes.
+    pub fn process_unshield_cnft<'info>(
+        ctx: Context<'_, '_, '_, 'info, ProcessUnshieldCnft<'info>>,
+        operation_id: [u8; 32],
+        leaf: helpers::bubblegum::CnftLeaf,
+    ) -> Result<()> {
+        adapter::process_unshield_cnft(ctx, operation_id, leaf)
     }
 
     /// Transact Phase 1 (DEPRECATED) - private transfer with optional unshield
@@ -257,6 +590,106 @@ pub mod cloakcraft {
         market::cancel_order(ctx, proof, escrow_nullifier, order_id, refund_commitment, encrypted_note, light_params)
     }
 
+    /// Modify an open order's terms and expiry
+    ///
+    /// Keeps the same escrow commitment; the maker proof binds the old terms
+    /// to the new ones, avoiding a cancel + create round trip.
+    pub fn modify_order(
+        ctx: Context<ModifyOrder>,
+        proof: Vec<u8>,
+        order_id: [u8; 32],
+        new_terms_hash: [u8; 32],
+        new_expiry: i64,
+    ) -> Result<()> {
+        market::modify_order(ctx, proof, order_id, new_terms_hash, new_expiry)
+    }
+
+    /// Create an escrow yield policy for a market (offer token) pool
+    ///
+    /// Sets the yield adapter program that idle order escrow may be deployed
+    /// to, and the share of opted-in escrow that may be deployed at once.
+    pub fn create_escrow_yield_policy(
+        ctx: Context<CreateEscrowYieldPolicy>,
+        max_deploy_bps: u16,
+    ) -> Result<()> {
+        market::create_escrow_yield_policy(ctx, max_deploy_bps)
+    }
+
+    /// Update an escrow yield policy (policy authority only)
+    pub fn update_escrow_yield_policy(
+        ctx: Context<UpdateEscrowYieldPolicy>,
+        enabled: bool,
+        max_deploy_bps: u16,
+    ) -> Result<()> {
+        market::update_escrow_yield_policy(ctx, enabled, max_deploy_bps)
+    }
+
+    /// Opt an open order's escrow into yield deployment
+    ///
+    /// The maker proof reveals the escrow amount and binds a yield tag used
+    /// to claim the accrued yield after the order settles.
+    pub fn opt_in_escrow_yield(
+        ctx: Context<OptInEscrowYield>,
+        order_id: [u8; 32],
+        proof: Vec<u8>,
+        escrow_amount: u64,
+        yield_tag: [u8; 32],
+    ) -> Result<()> {
+        market::opt_in_escrow_yield(ctx, order_id, proof, escrow_amount, yield_tag)
+    }
+
+    /// Deploy idle escrow to the policy's yield adapter (policy authority only)
+    ///
+    /// Adapter accounts are passed as remaining accounts.
+    pub fn deploy_escrow_yield<'info>(
+        ctx: Context<'_, '_, '_, 'info, DeployEscrowYield<'info>>,
+        amount: u64,
+        adapter_data: Vec<u8>,
+    ) -> Result<()> {
+        market::deploy_escrow_yield(ctx, amount, adapter_data)
+    }
+
+    /// Recall deployed escrow and realized yield from the yield adapter
+    ///
+    /// Anything returned above the recalled principal accrues to opted-in orders.
+    pub fn recall_escrow_yield<'info>(
+        ctx: Context<'_, '_, '_, 'info, RecallEscrowYield<'info>>,
+        principal: u64,
+        adapter_data: Vec<u8>,
+    ) -> Result<()> {
+        market::recall_escrow_yield(ctx, principal, adapter_data)
+    }
+
+    /// Create Pending with Proof Phase 0 - Claim Escrow Yield
+    ///
+    /// Flow:
+    /// Phase 0 (this): Verify ZK proof (knowledge of the yield tag key) + Create PendingOperation
+    /// Phase 3: execute_claim_escrow_yield
+    /// Phase 4: create_commitment for bonus note
+    /// Final: close_pending_operation
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_claim_escrow_yield<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofClaimEscrowYield<'info>>,
+        operation_id: [u8; 32],
+        order_id: [u8; 32],
+        proof: Vec<u8>,
+        bonus_commitment: [u8; 32],
+        yield_amount: u64,
+        client_version: u32,
+    ) -> Result<()> {
+        market::create_pending_with_proof_claim_escrow_yield(
+            ctx, operation_id, order_id, proof, bonus_commitment, yield_amount, client_version
+        )
+    }
+
+    /// Execute Claim Escrow Yield (Phase 3)
+    pub fn execute_claim_escrow_yield(
+        ctx: Context<ExecuteClaimEscrowYield>,
+        operation_id: [u8; 32],
+    ) -> Result<()> {
+        market::execute_claim_escrow_yield(ctx, operation_id)
+    }
+
     // ============ Swap Operations (Internal AMM) ============
 
     /// Initialize a liquidity pool
@@ -278,6 +711,18 @@ pub mod cloakcraft {
         swap::initialize_amm_pool(ctx, token_a_mint, token_b_mint, fee_bps, pool_type, amplification)
     }
 
+    /// Reallocate an AMM pool created with an older layout to the current one
+    ///
+    /// Permissionless: the added fields are zeroed (no LP lock, no JIT penalty,
+    /// version 0) and the payer covers the extra rent.
+    pub fn migrate_amm_pool_layout<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateAmmPoolLayout<'info>>,
+        token_a_mint: Pubkey,
+        token_b_mint: Pubkey,
+    ) -> Result<()> {
+        swap::migrate_amm_pool_layout(ctx, token_a_mint, token_b_mint)
+    }
+
     // ============ Append Pattern Swap Operations ============
 
     /// Create Pending with Proof Phase 0 - Swap (Append Pattern)
@@ -304,8 +749,9 @@ pub mod cloakcraft {
         output_amount: u64,
         swap_a_to_b: bool,
         num_commitments: u8,
+        client_version: u32,
     ) -> Result<()> {
-        swap::create_pending_with_proof_swap(ctx, operation_id, proof, merkle_root, input_commitment, nullifier, out_commitment, change_commitment, min_output, swap_amount, output_amount, swap_a_to_b, num_commitments)
+        swap::create_pending_with_proof_swap(ctx, operation_id, proof, merkle_root, input_commitment, nullifier, out_commitment, change_commitment, min_output, swap_amount, output_amount, swap_a_to_b, num_commitments, client_version)
     }
 
     /// Execute Swap Phase 3 - Update AMM state (Append Pattern)
@@ -319,6 +765,164 @@ pub mod cloakcraft {
         swap::execute_swap(ctx, operation_id)
     }
 
+    /// Create Pending with Proof Phase 0 - Committed Swap (Append Pattern)
+    ///
+    /// Like create_pending_with_proof_swap, but the swap amount, expected
+    /// output, minimum output and direction are only committed to
+    /// (`swap_commitment`, see `compute_swap_commitment`, bound by the
+    /// swap_committed proof) and revealed in execute_swap_revealed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_swap_committed<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofSwap<'info>>,
+        operation_id: [u8; 32],
+        proof: Vec<u8>,
+        merkle_root: [u8; 32],
+        input_commitment: [u8; 32],
+        nullifier: [u8; 32],
+        out_commitment: [u8; 32],
+        change_commitment: [u8; 32],
+        swap_commitment: [u8; 32],
+        num_commitments: u8,
+        client_version: u32,
+    ) -> Result<()> {
+        swap::create_pending_with_proof_swap_committed(ctx, operation_id, proof, merkle_root, input_commitment, nullifier, out_commitment, change_commitment, swap_commitment, num_commitments, client_version)
+    }
+
+    /// Create Pending with Proof Phase 0 - Sealed Swap (Append Pattern)
+    ///
+    /// Like create_pending_with_proof_swap_committed, but `terms_hash` is
+    /// also bound by the proof (swap_sealed circuit), so the relayer the
+    /// terms were sealed to can't substitute its own.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_swap_sealed<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofSwap<'info>>,
+        operation_id: [u8; 32],
+        proof: Vec<u8>,
+        merkle_root: [u8; 32],
+        input_commitment: [u8; 32],
+        nullifier: [u8; 32],
+        out_commitment: [u8; 32],
+        change_commitment: [u8; 32],
+        min_output: u64,
+        terms_hash: [u8; 32],
+        num_commitments: u8,
+        client_version: u32,
+    ) -> Result<()> {
+        swap::create_pending_with_proof_swap_sealed(ctx, operation_id, proof, merkle_root, input_commitment, nullifier, out_commitment, change_commitment, min_output, terms_hash, num_commitments, client_version)
+    }
+
+    /// Execute Swap Phase 3 - Reveal and execute a committed or sealed swap (Append Pattern)
+    ///
+    /// Takes the same accounts as execute_swap.
+    pub fn execute_swap_revealed<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteSwap<'info>>,
+        operation_id: [u8; 32],
+        swap_amount: u64,
+        output_amount: u64,
+        min_output: u64,
+        swap_a_to_b: bool,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        swap::execute_swap_revealed(ctx, operation_id, swap_amount, output_amount, min_output, swap_a_to_b, salt)
+    }
+
+    /// Create a swap fee rebate config for an AMM pool
+    pub fn create_fee_rebate_config(
+        ctx: Context<CreateFeeRebateConfig>,
+        genesis_time: i64,
+        epoch_duration: i64,
+        tiers: [state::RebateTier; state::MAX_REBATE_TIERS],
+    ) -> Result<()> {
+        swap::create_fee_rebate_config(ctx, genesis_time, epoch_duration, tiers)
+    }
+
+    /// Initialize a swap volume record for a blinded tag in the current epoch
+    pub fn init_swap_volume(
+        ctx: Context<InitSwapVolume>,
+        volume_tag: [u8; 32],
+        epoch: u64,
+    ) -> Result<()> {
+        swap::init_swap_volume(ctx, volume_tag, epoch)
+    }
+
+    /// Create Pending with Proof Phase 0 - Claim Fee Rebate
+    ///
+    /// Flow:
+    /// Phase 0 (this): Verify ZK proof + Create PendingOperation
+    /// Phase 3: execute_claim_fee_rebate
+    /// Phase 4: create_commitment for rebate note
+    /// Final: close_pending_operation
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_claim_fee_rebate<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofClaimFeeRebate<'info>>,
+        operation_id: [u8; 32],
+        volume_tag: [u8; 32],
+        epoch: u64,
+        proof: Vec<u8>,
+        rebate_commitment: [u8; 32],
+        rebate_amount: u64,
+        client_version: u32,
+    ) -> Result<()> {
+        swap::create_pending_with_proof_claim_fee_rebate(
+            ctx, operation_id, volume_tag, epoch, proof, rebate_commitment, rebate_amount, client_version
+        )
+    }
+
+    /// Execute Claim Fee Rebate (Phase 3)
+    pub fn execute_claim_fee_rebate(
+        ctx: Context<ExecuteClaimFeeRebate>,
+        operation_id: [u8; 32],
+    ) -> Result<()> {
+        swap::execute_claim_fee_rebate(ctx, operation_id)
+    }
+
+    /// Register an LP lock, starting now, for an existing LP note
+    ///
+    /// For LP notes not minted by add liquidity, which remove liquidity
+    /// otherwise treats as locked while the pool has an LP lock configured.
+    pub fn register_lp_lock<'info>(
+        ctx: Context<'_, '_, '_, 'info, RegisterLpLock<'info>>,
+        lp_commitment: [u8; 32],
+        light_params: generic::LightVerifyCommitmentParams,
+    ) -> Result<()> {
+        swap::register_lp_lock(ctx, lp_commitment, light_params)
+    }
+
+    /// Sync AMM reserves with surplus vault balance
+    ///
+    /// Folds tokens sent directly to the pool vaults into reserves, or sweeps
+    /// them to the treasury, per ProtocolConfig.surplus_policy. Permissionless;
+    /// a vault's surplus is only folded into the AMM pool its token pool
+    /// designates (set_surplus_amm_pool).
+    pub fn sync_reserves(ctx: Context<SyncReserves>) -> Result<()> {
+        swap::sync_reserves(ctx)
+    }
+
+    /// Convert treasury fees to the canonical treasury asset (protocol authority)
+    ///
+    /// Swaps fees from a treasury token account (delegated to the
+    /// treasury_converter PDA) through the AMM pool pairing its mint with
+    /// ProtocolConfig.treasury_conversion_mint, within slippage and price
+    /// impact bounds. min_amount_out must be set from an external price.
+    pub fn convert_treasury_fees(
+        ctx: Context<ConvertTreasuryFees>,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        swap::convert_treasury_fees(ctx, amount_in, min_amount_out)
+    }
+
+    /// Quote a swap against current reserves (read-only)
+    ///
+    /// Returns a `SwapQuote` (output, fees, price impact) as return data.
+    pub fn quote_swap(
+        ctx: Context<QuoteSwap>,
+        input_amount: u64,
+        swap_a_to_b: bool,
+    ) -> Result<swap::SwapQuote> {
+        swap::quote_swap(ctx, input_amount, swap_a_to_b)
+    }
+
     /// Create Pending with Proof Phase 0 - Remove Liquidity (Append Pattern)
     ///
     /// Flow:
@@ -343,8 +947,9 @@ pub mod cloakcraft {
         withdraw_a_amount: u64,
         withdraw_b_amount: u64,
         num_commitments: u8,
+        client_version: u32,
     ) -> Result<()> {
-        swap::create_pending_with_proof_remove_liquidity(ctx, operation_id, proof, lp_input_commitment, lp_nullifier, out_a_commitment, out_b_commitment, old_state_hash, new_state_hash, lp_amount_burned, withdraw_a_amount, withdraw_b_amount, num_commitments)
+        swap::create_pending_with_proof_remove_liquidity(ctx, operation_id, proof, lp_input_commitment, lp_nullifier, out_a_commitment, out_b_commitment, old_state_hash, new_state_hash, lp_amount_burned, withdraw_a_amount, withdraw_b_amount, num_commitments, client_version)
     }
 
     /// Execute Remove Liquidity Phase 3 - Update AMM state (Append Pattern)
@@ -359,6 +964,29 @@ pub mod cloakcraft {
         swap::execute_remove_liquidity(ctx, operation_id, new_state_hash)
     }
 
+    /// Create Pending with Proof Phase 0 - Convert AMM LP Note
+    ///
+    /// Converts an LP note of a migrated AMM pool into its successor's LP.
+    /// No Phase 3: Phase 1, Phase 2, then create_commitment for the new LP.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_convert_amm_lp_note<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofConvertAmmLpNote<'info>>,
+        operation_id: [u8; 32],
+        proof: Vec<u8>,
+        merkle_root: [u8; 32],
+        lp_commitment: [u8; 32],
+        lp_nullifier: [u8; 32],
+        out_commitment: [u8; 32],
+        lp_amount: u64,
+        converted_amount: u64,
+        client_version: u32,
+    ) -> Result<()> {
+        swap::create_pending_with_proof_convert_amm_lp_note(
+            ctx, operation_id, proof, merkle_root, lp_commitment, lp_nullifier,
+            out_commitment, lp_amount, converted_amount, client_version
+        )
+    }
+
     /// Create Pending with Proof Phase 0 - Add Liquidity (Append Pattern)
     ///
     /// Flow:
@@ -387,8 +1015,9 @@ pub mod cloakcraft {
         lp_amount: u64,
         min_lp_amount: u64,
         num_commitments: u8,
+        client_version: u32,
     ) -> Result<()> {
-        swap::create_pending_with_proof_add_liquidity(ctx, operation_id, proof, input_commitment_a, input_commitment_b, nullifier_a, nullifier_b, lp_commitment, change_a_commitment, change_b_commitment, deposit_a, deposit_b, lp_amount, min_lp_amount, num_commitments)
+        swap::create_pending_with_proof_add_liquidity(ctx, operation_id, proof, input_commitment_a, input_commitment_b, nullifier_a, nullifier_b, lp_commitment, change_a_commitment, change_b_commitment, deposit_a, deposit_b, lp_amount, min_lp_amount, num_commitments, client_version)
     }
 
     /// Execute Add Liquidity Phase 3 - Update AMM state (Append Pattern)
@@ -444,15 +1073,81 @@ pub mod cloakcraft {
         nullifier_index: u8,
         light_params: generic::LightCreateNullifierAndPendingParams,
     ) -> Result<()> {
-        generic::create_nullifier_and_pending(ctx, operation_id, nullifier_index, light_params)
+        generic::create_nullifier_and_pending(ctx, operation_id, nullifier_index, light_params)
+    }
+
+    /// Close pending operation after all nullifiers and commitments created or expired
+    ///
+    /// Rent goes to the relayer, minus the share recorded in Phase 0 for the
+    /// user's unshield recipient bound in Phase 3. A completed consolidation that
+    /// swept dust notes also pays its relayer the dust sweep bounty when the
+    /// pool and dust sweep ledger are passed.
+    pub fn close_pending_operation(
+        ctx: Context<ClosePendingOperation>,
+        operation_id: [u8; 32],
+    ) -> Result<()> {
+        generic::close_pending_operation(ctx, operation_id)
+    }
+
+    /// Estimate compute units and rent for each phase of an operation (read-only)
+    ///
+    /// Returned via return data; call with simulate / `.view()`.
+    pub fn estimate_operation_cost(
+        ctx: Context<EstimateOperationCost>,
+        shape: state::OperationShape,
+    ) -> Result<state::OperationCostEstimate> {
+        generic::estimate_operation_cost(ctx, shape)
+    }
+
+    /// Dry-run a transfer's or swap's Phase 0 (read-only)
+    ///
+    /// Always fails with SimulationComplete after setting a SimulationResult
+    /// as return data; call with simulate and read the return data.
+    pub fn simulate_operation(
+        ctx: Context<SimulateOperation>,
+        proof: Vec<u8>,
+        operation: generic::SimulatedOperation,
+        client_version: u32,
+    ) -> Result<()> {
+        generic::simulate_operation(ctx, proof, operation, client_version)
     }
 
-    /// Close pending operation after all nullifiers and commitments created or expired
-    pub fn close_pending_operation(
-        ctx: Context<ClosePendingOperation>,
-        operation_id: [u8; 32],
+    /// Create a circuit's statistics account (permissionless)
+    ///
+    /// Once created, pass it to the circuit's create_pending_with_proof_*
+    /// instruction to meter verifications; record_proof_failure counts rejections.
+    pub fn initialize_circuit_stats(ctx: Context<InitializeCircuitStats>) -> Result<()> {
+        generic::initialize_circuit_stats(ctx)
+    }
+
+    /// Count a rejected Phase 0 proof in a circuit's statistics
+    ///
+    /// Re-verifies the proof and counts it only if it fails.
+    pub fn record_proof_failure(
+        ctx: Context<RecordProofFailure>,
+        proof: Vec<u8>,
+        public_inputs: Vec<[u8; 32]>,
+        client_version: u32,
     ) -> Result<()> {
-        generic::close_pending_operation(ctx, operation_id)
+        generic::record_proof_failure(ctx, proof, public_inputs, client_version)
+    }
+
+    /// Bond SOL as relayer stake (creates the stake account on first use)
+    ///
+    /// Whoever completes a transfer or consolidation the relayer left stranded
+    /// past expiry is paid RELAYER_SLASH_LAMPORTS from the stake.
+    pub fn bond_relayer_stake(ctx: Context<BondRelayerStake>, amount: u64) -> Result<()> {
+        generic::bond_relayer_stake(ctx, amount)
+    }
+
+    /// Start unbonding relayer stake (stays slashable until withdrawn)
+    pub fn unbond_relayer_stake(ctx: Context<UnbondRelayerStake>, amount: u64) -> Result<()> {
+        generic::unbond_relayer_stake(ctx, amount)
+    }
+
+    /// Withdraw relayer stake whose unbonding delay has passed
+    pub fn withdraw_relayer_stake(ctx: Context<WithdrawRelayerStake>) -> Result<()> {
+        generic::withdraw_relayer_stake(ctx)
     }
 
     // ============ Generic Light Protocol Operations ============
@@ -481,6 +1176,10 @@ pub mod cloakcraft {
     /// within transaction size limits.
     ///
     /// IMPORTANT: All nullifiers must be created before any commitments.
+    ///
+    /// Pass `payment_receipt` to record which invoice this commitment pays.
+    /// Stranded transfers and consolidations can be completed by anyone; the
+    /// last commitment slashes the original relayer's stake when it is passed.
     pub fn create_commitment<'info>(
         ctx: Context<'_, '_, '_, 'info, CreateCommitment<'info>>,
         operation_id: [u8; 32],
@@ -488,28 +1187,48 @@ pub mod cloakcraft {
         stealth_ephemeral_pubkey: [u8; 64],
         encrypted_note: Vec<u8>,
         light_params: generic::LightCreateCommitmentParams,
+        view_tag: Option<[u8; 8]>,
+        payment_receipt: Option<generic::PaymentReceiptParams>,
     ) -> Result<()> {
-        generic::create_commitment(ctx, operation_id, commitment_index, stealth_ephemeral_pubkey, encrypted_note, light_params)
+        generic::create_commitment(ctx, operation_id, commitment_index, stealth_ephemeral_pubkey, encrypted_note, light_params, view_tag, payment_receipt)
+    }
+
+    /// Create a pending operation's remaining nullifiers and commitments in one pool
+    ///
+    /// Phases 2 and 4 share one Light CPI (up to light_cpi::MAX_BATCHED_ACCOUNTS
+    /// accounts), saving the per-account CPI and transactions. Nullifiers and
+    /// commitments only share a batch when no Phase 3 runs between them; with
+    /// every nullifier created it batches Phase 4 for any operation.
+    pub fn create_nullifiers_and_commitments<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateNullifiersAndCommitments<'info>>,
+        operation_id: [u8; 32],
+        outputs: Vec<generic::BatchedCommitmentParams>,
+        light_params: generic::LightBatchParams,
+    ) -> Result<()> {
+        generic::create_nullifiers_and_commitments(ctx, operation_id, outputs, light_params)
     }
 
     // ============ Admin Operations ============
+    //
+    // Every admin instruction writes an AdminActionRecord compressed account;
+    // pass the Light system accounts (output queue first) as remaining accounts.
 
     /// Register an adapter module
-    pub fn register_adapt_module(
-        ctx: Context<RegisterAdaptModule>,
+    pub fn register_adapt_module<'info>(
+        ctx: Context<'_, '_, '_, 'info, RegisterAdaptModule<'info>>,
         interface_version: u8,
     ) -> Result<()> {
         admin::register_adapt_module(ctx, interface_version)
     }
 
     /// Disable an adapter module
-    pub fn disable_adapt_module(ctx: Context<DisableAdaptModule>) -> Result<()> {
+    pub fn disable_adapt_module<'info>(ctx: Context<'_, '_, '_, 'info, DisableAdaptModule<'info>>) -> Result<()> {
         admin::disable_adapt_module(ctx)
     }
 
     /// Register a verification key for a circuit
-    pub fn register_verification_key(
-        ctx: Context<RegisterVerificationKey>,
+    pub fn register_verification_key<'info>(
+        ctx: Context<'_, '_, '_, 'info, RegisterVerificationKey<'info>>,
         circuit_id: [u8; 32],
         vk_data: Vec<u8>,
     ) -> Result<()> {
@@ -518,8 +1237,8 @@ pub mod cloakcraft {
 
     /// Set verification key data on an existing account
     /// Used for large VKs that exceed transaction size limits
-    pub fn set_verification_key_data(
-        ctx: Context<SetVerificationKeyData>,
+    pub fn set_verification_key_data<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetVerificationKeyData<'info>>,
         circuit_id: [u8; 32],
         vk_data: Vec<u8>,
     ) -> Result<()> {
@@ -528,17 +1247,29 @@ pub mod cloakcraft {
 
     /// Append verification key data to an existing account
     /// Used for chunked upload of large VKs
-    pub fn append_verification_key_data(
-        ctx: Context<AppendVerificationKeyData>,
+    pub fn append_verification_key_data<'info>(
+        ctx: Context<'_, '_, '_, 'info, AppendVerificationKeyData<'info>>,
         circuit_id: [u8; 32],
         data_chunk: Vec<u8>,
     ) -> Result<()> {
         admin::append_verification_key_data(ctx, circuit_id, data_chunk)
     }
 
+    /// Check uploaded VK data against its expected keccak hash and freeze it
+    ///
+    /// Proof-verifying instructions reject keys until they are finalized.
+    /// Emits `VerificationKeyFinalized`.
+    pub fn finalize_verification_key<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeVerificationKey<'info>>,
+        circuit_id: [u8; 32],
+        expected_hash: [u8; 32],
+    ) -> Result<()> {
+        admin::finalize_verification_key(ctx, circuit_id, expected_hash)
+    }
+
     /// Register a threshold committee
-    pub fn register_threshold_committee(
-        ctx: Context<RegisterThresholdCommittee>,
+    pub fn register_threshold_committee<'info>(
+        ctx: Context<'_, '_, '_, 'info, RegisterThresholdCommittee<'info>>,
         committee_id: [u8; 32],
         members: Vec<Pubkey>,
         threshold_pubkey: [u8; 32],
@@ -559,18 +1290,46 @@ pub mod cloakcraft {
 
     /// Reset AMM pool state (admin only)
     /// Used to fix corrupted pool state
-    pub fn reset_amm_pool(ctx: Context<ResetAmmPool>) -> Result<()> {
+    pub fn reset_amm_pool<'info>(ctx: Context<'_, '_, '_, 'info, ResetAmmPool<'info>>) -> Result<()> {
         admin::reset_amm_pool(ctx)
     }
 
+    /// Set the AMM pool LP lock and JIT penalty (admin only)
+    ///
+    /// Deters just-in-time liquidity: LP minted less than `min_lp_lock_slots`
+    /// ago pays `jit_penalty_bps` on removal (or cannot be removed if 0).
+    pub fn set_amm_lp_lock<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetAmmLpLock<'info>>,
+        min_lp_lock_slots: u64,
+        jit_penalty_bps: u16,
+    ) -> Result<()> {
+        admin::set_amm_lp_lock(ctx, min_lp_lock_slots, jit_penalty_bps)
+    }
+
+    /// Migrate an AMM pool to a successor pool (admin only)
+    ///
+    /// Creates the pair's next-version pool with the given formula and fee,
+    /// exports the reserves into it, reserves `new_lp_supply` successor LP
+    /// for the old LP supply and deactivates the old pool. Pools created with an
+    /// older layout must go through `migrate_amm_pool_layout` first.
+    pub fn migrate_amm_pool<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateAmmPool<'info>>,
+        fee_bps: u16,
+        pool_type: state::PoolType,
+        amplification: u64,
+        new_lp_supply: u64,
+    ) -> Result<()> {
+        admin::migrate_amm_pool(ctx, fee_bps, pool_type, amplification, new_lp_supply)
+    }
+
     // ============ Protocol Fee Configuration ============
 
     /// Initialize protocol configuration with fee rates
     ///
     /// Creates the global ProtocolConfig account. Can only be called once.
     /// Fee rates are in basis points. swap_fee_share_bps is protocol's share of LP fees (2000 = 20%).
-    pub fn initialize_protocol_config(
-        ctx: Context<InitializeProtocolConfig>,
+    pub fn initialize_protocol_config<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeProtocolConfig<'info>>,
         transfer_fee_bps: u16,
         unshield_fee_bps: u16,
         swap_fee_share_bps: u16,
@@ -587,12 +1346,36 @@ pub mod cloakcraft {
         )
     }
 
+    /// Set or clear a token mint's fee override
+    ///
+    /// Only callable by the protocol authority. The mint's transfer, unshield
+    /// and remove liquidity fees then use these rates instead of the global ones.
+    pub fn set_fee_override<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetFeeOverride<'info>>,
+        mint: Pubkey,
+        fee_override: Option<state::FeeOverride>,
+    ) -> Result<()> {
+        admin::set_fee_override(ctx, mint, fee_override)
+    }
+
+    /// Set the floor and ceiling for fee rates
+    ///
+    /// Only callable by the protocol authority. Enforced whenever a global or
+    /// per-mint fee rate is set.
+    pub fn set_fee_bounds<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetFeeBounds<'info>>,
+        min_fee_bps: u16,
+        max_fee_bps: u16,
+    ) -> Result<()> {
+        admin::set_fee_bounds(ctx, min_fee_bps, max_fee_bps)
+    }
+
     /// Update protocol fee rates
     ///
     /// Only callable by the protocol authority. Allows updating individual
     /// fee rates or toggling fees on/off.
-    pub fn update_protocol_fees(
-        ctx: Context<UpdateProtocolFees>,
+    pub fn update_protocol_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateProtocolFees<'info>>,
         transfer_fee_bps: Option<u16>,
         unshield_fee_bps: Option<u16>,
         swap_fee_share_bps: Option<u16>,
@@ -612,17 +1395,168 @@ pub mod cloakcraft {
     /// Update protocol treasury address
     ///
     /// Only callable by the protocol authority. Changes where fees are sent.
-    pub fn update_treasury(ctx: Context<UpdateTreasury>) -> Result<()> {
+    pub fn update_treasury<'info>(ctx: Context<'_, '_, '_, 'info, UpdateTreasury<'info>>) -> Result<()> {
         admin::update_treasury(ctx)
     }
 
     /// Transfer protocol authority to a new account
     ///
     /// Only callable by the current authority.
-    pub fn update_protocol_authority(ctx: Context<UpdateProtocolAuthority>) -> Result<()> {
+    pub fn update_protocol_authority<'info>(ctx: Context<'_, '_, '_, 'info, UpdateProtocolAuthority<'info>>) -> Result<()> {
         admin::update_protocol_authority(ctx)
     }
 
+    /// Set the PendingOperation expiry
+    ///
+    /// Only callable by the protocol authority. `operation_type = None` sets
+    /// the default; otherwise sets (or clears, with 0) that type's override.
+    pub fn set_pending_expiry<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetPendingExpiry<'info>>,
+        operation_type: Option<u8>,
+        expiry_seconds: u32,
+    ) -> Result<()> {
+        admin::set_pending_expiry(ctx, operation_type, expiry_seconds)
+    }
+
+    /// Set the share of pending operation rent refunded to the user
+    ///
+    /// Only callable by the protocol authority. The rest goes to the relayer.
+    pub fn set_rent_refund_bps<'info>(ctx: Context<'_, '_, '_, 'info, SetRentRefundBps<'info>>, rent_refund_bps: u16) -> Result<()> {
+        admin::set_rent_refund_bps(ctx, rent_refund_bps)
+    }
+
+    /// Set what sync_reserves does with surplus vault balance
+    ///
+    /// Only callable by the protocol authority. 0 = fold into AMM reserves,
+    /// 1 = sweep to the treasury.
+    pub fn set_surplus_policy<'info>(ctx: Context<'_, '_, '_, 'info, SetSurplusPolicy<'info>>, surplus_policy: u8) -> Result<()> {
+        admin::set_surplus_policy(ctx, surplus_policy)
+    }
+
+    /// Set the canonical asset treasury fees convert into
+    ///
+    /// Only callable by the protocol authority. A default mint disables
+    /// convert_treasury_fees; max_price_impact_bps bounds each conversion.
+    pub fn set_treasury_conversion<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetTreasuryConversion<'info>>,
+        conversion_mint: Pubkey,
+        max_price_impact_bps: u16,
+    ) -> Result<()> {
+        admin::set_treasury_conversion(ctx, conversion_mint, max_price_impact_bps)
+    }
+
+    /// Reallocate the protocol config to the current layout
+    ///
+    /// Only callable by the protocol authority. Configs created before the
+    /// fee bounds, fee overrides and treasury conversion fields were added
+    /// are grown to the current size with those fields zeroed.
+    pub fn migrate_protocol_config<'info>(ctx: Context<'_, '_, '_, 'info, MigrateProtocolConfig<'info>>) -> Result<()> {
+        admin::migrate_protocol_config(ctx)
+    }
+
+    /// Initialize the registry of approved output state trees
+    ///
+    /// Only callable by the protocol authority. Pool tree rollovers must then
+    /// target a registered tree.
+    pub fn initialize_tree_registry<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeTreeRegistry<'info>>,
+        near_capacity_bps: u16,
+    ) -> Result<()> {
+        admin::initialize_tree_registry(ctx, near_capacity_bps)
+    }
+
+    /// Register an output state tree, or update its capacity hint
+    ///
+    /// Only callable by the protocol authority.
+    pub fn register_state_tree<'info>(
+        ctx: Context<'_, '_, '_, 'info, RegisterStateTree<'info>>,
+        capacity: u64,
+    ) -> Result<()> {
+        admin::register_state_tree(ctx, capacity)
+    }
+
+    /// Retire a registered output state tree
+    ///
+    /// Only callable by the protocol authority. Commitments in it stay spendable.
+    pub fn retire_state_tree<'info>(
+        ctx: Context<'_, '_, '_, 'info, RetireStateTree<'info>>,
+        state_tree: Pubkey,
+    ) -> Result<()> {
+        admin::retire_state_tree(ctx, state_tree)
+    }
+
+    /// Bump the operation epoch (incident response)
+    ///
+    /// Invalidates every in-flight operation whose nullifiers are not yet
+    /// created; clients derive new operation IDs for the new epoch.
+    pub fn bump_operation_epoch<'info>(ctx: Context<'_, '_, '_, 'info, BumpOperationEpoch<'info>>) -> Result<()> {
+        admin::bump_operation_epoch(ctx)
+    }
+
+    /// Set the AMM pool creation mode and SOL creation fee
+    ///
+    /// Only callable by the protocol authority. Mode 0 = permissionless,
+    /// 1 = allowlisted (PoolCreatorAllowlist).
+    pub fn set_amm_creation_policy<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetAmmCreationPolicy<'info>>,
+        amm_creation_mode: u8,
+        amm_creation_fee_lamports: u64,
+    ) -> Result<()> {
+        admin::set_amm_creation_policy(ctx, amm_creation_mode, amm_creation_fee_lamports)
+    }
+
+    /// Initialize the AMM pool creator allowlist
+    pub fn initialize_pool_creator_allowlist<'info>(ctx: Context<'_, '_, '_, 'info, InitializePoolCreatorAllowlist<'info>>) -> Result<()> {
+        admin::initialize_pool_creator_allowlist(ctx)
+    }
+
+    /// Add (`allowed = true`) or remove an AMM pool creator
+    pub fn set_pool_creator<'info>(ctx: Context<'_, '_, '_, 'info, SetPoolCreator<'info>>, creator: Pubkey, allowed: bool) -> Result<()> {
+        admin::set_pool_creator(ctx, creator, allowed)
+    }
+
+    /// Initialize the program version account
+    ///
+    /// Only callable by the protocol authority.
+    pub fn initialize_program_version<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeProgramVersion<'info>>,
+        version: u32,
+        min_client_version: u32,
+    ) -> Result<()> {
+        admin::initialize_program_version(ctx, version, min_client_version)
+    }
+
+    /// Bump the program version after a deploy
+    ///
+    /// Only callable by the protocol authority. Phase 0 instructions reject
+    /// clients below `min_client_version`.
+    pub fn set_program_version<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetProgramVersion<'info>>,
+        version: u32,
+        min_client_version: u32,
+    ) -> Result<()> {
+        admin::set_program_version(ctx, version, min_client_version)
+    }
+
+    /// Initialize the dust sweep ledger
+    ///
+    /// Only callable by the protocol authority. Funded with SOL transfers;
+    /// pays relayers a bounty for consolidations that sweep dust notes.
+    pub fn initialize_dust_sweep_ledger<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeDustSweepLedger<'info>>,
+        bounty_lamports: u64,
+    ) -> Result<()> {
+        admin::initialize_dust_sweep_ledger(ctx, bounty_lamports)
+    }
+
+    /// Set the dust sweep bounty (0 = paused)
+    pub fn set_dust_sweep_bounty<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetDustSweepBounty<'info>>,
+        bounty_lamports: u64,
+    ) -> Result<()> {
+        admin::set_dust_sweep_bounty(ctx, bounty_lamports)
+    }
+
     // ============ Perpetual Futures Operations ============
 
     /// Initialize a perpetual futures pool
@@ -682,6 +1616,48 @@ pub mod cloakcraft {
         perps::update_market_status(ctx, is_active)
     }
 
+    /// Delist a market into settle-only mode
+    ///
+    /// Stops new opens; closes and liquidations continue. The market can be
+    /// force-settled once `grace_period_seconds` have passed.
+    pub fn delist_market(ctx: Context<DelistMarket>, grace_period_seconds: i64) -> Result<()> {
+        perps::delist_market(ctx, grace_period_seconds)
+    }
+
+    /// Begin migrating a perps pool to a new pool
+    ///
+    /// Freezes both pools; the old pool must have no open positions.
+    pub fn begin_pool_migration(ctx: Context<BeginPoolMigration>) -> Result<()> {
+        perps::begin_pool_migration(ctx)
+    }
+
+    /// Move one token vault of a migrating perps pool to the new pool
+    pub fn migrate_token_vault(ctx: Context<MigrateTokenVault>, token_index: u8) -> Result<()> {
+        perps::migrate_token_vault(ctx, token_index)
+    }
+
+    /// Finalize a perps pool migration
+    ///
+    /// Registers the LP conversion rate (`new_lp_supply` for the old LP
+    /// supply) and unfreezes the new pool.
+    pub fn finalize_pool_migration(ctx: Context<FinalizePoolMigration>, new_lp_supply: u64) -> Result<()> {
+        perps::finalize_pool_migration(ctx, new_lp_supply)
+    }
+
+    /// Update token target weight in perps pool
+    pub fn update_perps_token_target_weight(
+        ctx: Context<UpdateTokenTargetWeight>,
+        token_index: u8,
+        target_weight_bps: u16,
+    ) -> Result<()> {
+        perps::update_token_target_weight(ctx, token_index, target_weight_bps)
+    }
+
+    /// Create the perps pool's borrow fee checkpoint history
+    pub fn initialize_borrow_fee_history(ctx: Context<InitializeBorrowFeeHistory>) -> Result<()> {
+        perps::initialize_borrow_fee_history(ctx)
+    }
+
     // ============ Perps Position Operations (Append Pattern) ============
 
     /// Create Pending with Proof Phase 0 - Open Position
@@ -708,10 +1684,11 @@ pub mod cloakcraft {
         leverage: u8,
         position_fee: u64,
         change_amount: u64,
+        client_version: u32,
     ) -> Result<()> {
         perps::create_pending_with_proof_open_position(
             ctx, operation_id, proof, merkle_root, input_commitment, nullifier,
-            position_commitment, change_commitment, is_long, margin_amount, leverage, position_fee, change_amount
+            position_commitment, change_commitment, is_long, margin_amount, leverage, position_fee, change_amount, client_version
         )
     }
 
@@ -724,7 +1701,63 @@ pub mod cloakcraft {
         perps::execute_open_position(ctx, operation_id, entry_price)
     }
 
+    /// Create Pending with Proof Phase 0 - Sealed Open Position
+    ///
+    /// Margin, leverage and position fee are only bound as `terms_hash` by
+    /// the open_position_sealed proof and revealed in
+    /// execute_open_position_revealed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_open_position_sealed<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofOpenPosition<'info>>,
+        operation_id: [u8; 32],
+        proof: Vec<u8>,
+        merkle_root: [u8; 32],
+        input_commitment: [u8; 32],
+        nullifier: [u8; 32],
+        position_commitment: [u8; 32],
+        change_commitment: [u8; 32],
+        is_long: bool,
+        change_amount: u64,
+        terms_hash: [u8; 32],
+        client_version: u32,
+    ) -> Result<()> {
+        perps::create_pending_with_proof_open_position_sealed(
+            ctx, operation_id, proof, merkle_root, input_commitment, nullifier,
+            position_commitment, change_commitment, is_long, change_amount, terms_hash, client_version
+        )
+    }
+
+    /// Execute Open Position Phase 3 - Reveal and execute a sealed open
+    ///
+    /// Takes the same accounts as execute_open_position.
+    pub fn execute_open_position_revealed<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteOpenPosition<'info>>,
+        operation_id: [u8; 32],
+        margin_amount: u64,
+        leverage: u8,
+        position_fee: u64,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        perps::execute_open_position_revealed(ctx, operation_id, margin_amount, leverage, position_fee, salt)
+    }
+
+    /// Create Position Meta Phase 4b - Open Position
+    ///
+    /// Publishes the liquidation metadata of the opened position with the
+    /// terms and borrow fee snapshot recorded by execute_open_position.
+    pub fn create_position_meta<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePositionMeta<'info>>,
+        operation_id: [u8; 32],
+        position_meta_input: perps::PositionMetaInput,
+        light_params: perps::LightCreatePositionMetaParams,
+    ) -> Result<()> {
+        perps::create_position_meta(ctx, operation_id, position_meta_input, light_params)
+    }
+
     /// Create Pending with Proof Phase 0 - Close Position
+    ///
+    /// The borrow fee the proof deducts is computed on-chain from
+    /// `entry_borrow_fee`, which must match the position's PositionMeta.
     #[allow(clippy::too_many_arguments)]
     pub fn create_pending_with_proof_close_position<'info>(
         ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofClosePosition<'info>>,
@@ -739,22 +1772,153 @@ pub mod cloakcraft {
         close_fee: u64,
         pnl_amount: u64,
         is_profit: bool,
+        position_margin: u64,
+        position_size: u64,
+        entry_price: u64,
+        entry_borrow_fee: u128,
+        client_version: u32,
     ) -> Result<()> {
         perps::create_pending_with_proof_close_position(
             ctx, operation_id, proof, merkle_root, position_commitment, position_nullifier,
-            settlement_commitment, is_long, exit_price, close_fee, pnl_amount, is_profit
+            settlement_commitment, is_long, exit_price, close_fee, pnl_amount, is_profit,
+            position_margin, position_size, entry_price, entry_borrow_fee, client_version
         )
     }
 
     /// Execute Close Position Phase 3
+    ///
+    /// Position terms come from the pending operation (Phase 0 proof and
+    /// verify_position_meta_active), not from instruction data.
     pub fn execute_close_position<'info>(
         ctx: Context<'_, '_, '_, 'info, ExecuteClosePosition<'info>>,
         operation_id: [u8; 32],
+    ) -> Result<()> {
+        perps::execute_close_position(ctx, operation_id)
+    }
+
+    /// Verify Position Meta Active Phase 1b - Close/Liquidate/Transfer Position
+    ///
+    /// Proves the PositionMeta of the spent position commitment is Active and
+    /// records its entry borrow fee in the pending operation.
+    pub fn verify_position_meta_active<'info>(
+        ctx: Context<'_, '_, '_, 'info, VerifyPositionMetaActive<'info>>,
+        operation_id: [u8; 32],
+        position_meta: state::PositionMeta,
+        light_params: perps::LightVerifyPositionMetaParams,
+    ) -> Result<()> {
+        perps::verify_position_meta_active(ctx, operation_id, position_meta, light_params)
+    }
+
+    /// Create Pending with Proof Phase 0 - Transfer Position
+    ///
+    /// Re-commits an open position to a new stealth owner with unchanged terms.
+    /// No execute phase: continue with verify_commitment_exists, create_nullifier,
+    /// create_commitment and close_pending_operation.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_transfer_position<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofTransferPosition<'info>>,
+        operation_id: [u8; 32],
+        proof: Vec<u8>,
+        merkle_root: [u8; 32],
+        position_commitment: [u8; 32],
+        position_nullifier: [u8; 32],
+        new_position_commitment: [u8; 32],
+        is_long: bool,
+        margin_amount: u64,
+        position_size: u64,
+        entry_price: u64,
+        client_version: u32,
+    ) -> Result<()> {
+        perps::create_pending_with_proof_transfer_position(
+            ctx, operation_id, proof, merkle_root, position_commitment, position_nullifier,
+            new_position_commitment, is_long, margin_amount, position_size, entry_price, client_version
+        )
+    }
+
+    /// Create Position Meta Phase 4b - Transfer Position
+    ///
+    /// Marks the old PositionMeta Transferred and creates the new owner's,
+    /// carrying over the entry borrow fee verified in Phase 1b.
+    pub fn create_position_meta_transfer<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePositionMetaTransfer<'info>>,
+        operation_id: [u8; 32],
+        old_position_id: [u8; 32],
+        position_meta_input: perps::TransferredPositionMetaInput,
+        status_params: perps::LightCreatePositionStatusParams,
+        meta_params: perps::LightCreatePositionMetaParams,
+    ) -> Result<()> {
+        perps::create_position_meta_transfer(
+            ctx, operation_id, old_position_id, position_meta_input, status_params, meta_params
+        )
+    }
+
+    /// Value a position at the current oracle price (read-only)
+    ///
+    /// Returns a `PositionValueQuote` (mark value, accrued borrow fee, PnL and
+    /// distance to liquidation) and emits `PositionValueQuoted`; call via
+    /// simulation or CPI.
+    pub fn quote_position_value(
+        ctx: Context<QuotePositionValue>,
         position_margin: u64,
         position_size: u64,
         entry_price: u64,
+        is_long: bool,
+        entry_borrow_fee: u128,
+    ) -> Result<perps::PositionValueQuote> {
+        perps::quote_position_value(ctx, position_margin, position_size, entry_price, is_long, entry_borrow_fee)
+    }
+
+    // ============ Perps Limit Orders ============
+
+    /// Create a resting limit order to open a position
+    ///
+    /// Verifies the open-position proof now; nothing is spent until filled.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_perp_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePerpOrder<'info>>,
+        order_id: [u8; 32],
+        proof: Vec<u8>,
+        merkle_root: [u8; 32],
+        input_commitment: [u8; 32],
+        nullifier: [u8; 32],
+        position_commitment: [u8; 32],
+        change_commitment: [u8; 32],
+        is_long: bool,
+        margin_amount: u64,
+        leverage: u8,
+        position_fee: u64,
+        change_amount: u64,
+        limit_price: u64,
+        expiry: i64,
+        stealth_ephemeral_pubkeys: [[u8; 64]; 2],
+        encrypted_notes: Vec<Vec<u8>>,
+        client_version: u32,
+    ) -> Result<()> {
+        perps::create_perp_order(
+            ctx, order_id, proof, merkle_root, input_commitment, nullifier, position_commitment,
+            change_commitment, is_long, margin_amount, leverage, position_fee, change_amount,
+            limit_price, expiry, stealth_ephemeral_pubkeys, encrypted_notes, client_version
+        )
+    }
+
+    /// Fill a limit order once the oracle crosses its limit
+    ///
+    /// Keeper instruction - creates the open-position pending operation
+    /// (Phases 1-4 follow as for a market order).
+    pub fn execute_perp_order_fill<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecutePerpOrderFill<'info>>,
+        order_id: [u8; 32],
+        operation_id: [u8; 32],
+    ) -> Result<()> {
+        perps::execute_perp_order_fill(ctx, order_id, operation_id)
+    }
+
+    /// Close an expired limit order and refund its rent
+    pub fn close_expired_perp_order(
+        ctx: Context<CloseExpiredPerpOrder>,
+        order_id: [u8; 32],
     ) -> Result<()> {
-        perps::execute_close_position(ctx, operation_id, position_margin, position_size, entry_price)
+        perps::close_expired_perp_order(ctx, order_id)
     }
 
     // ============ Perps Liquidity Operations (Append Pattern) ============
@@ -773,10 +1937,11 @@ pub mod cloakcraft {
         deposit_amount: u64,
         lp_amount_minted: u64,
         fee_amount: u64,
+        client_version: u32,
     ) -> Result<()> {
         perps::create_pending_with_proof_add_perps_liquidity(
             ctx, operation_id, proof, merkle_root, input_commitment, nullifier,
-            lp_commitment, token_index, deposit_amount, lp_amount_minted, fee_amount
+            lp_commitment, token_index, deposit_amount, lp_amount_minted, fee_amount, client_version
         )
     }
 
@@ -804,10 +1969,11 @@ pub mod cloakcraft {
         withdraw_amount: u64,
         lp_amount_burned: u64,
         fee_amount: u64,
+        client_version: u32,
     ) -> Result<()> {
         perps::create_pending_with_proof_remove_perps_liquidity(
             ctx, operation_id, proof, merkle_root, lp_commitment, lp_nullifier,
-            out_commitment, change_lp_commitment, token_index, withdraw_amount, lp_amount_burned, fee_amount
+            out_commitment, change_lp_commitment, token_index, withdraw_amount, lp_amount_burned, fee_amount, client_version
         )
     }
 
@@ -820,6 +1986,28 @@ pub mod cloakcraft {
         perps::execute_remove_perps_liquidity(ctx, operation_id, oracle_prices)
     }
 
+    /// Create Pending with Proof Phase 0 - Convert LP Note
+    ///
+    /// Converts an LP note of a migrated perps pool into the new pool's LP.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_convert_lp_note<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofConvertLpNote<'info>>,
+        operation_id: [u8; 32],
+        proof: Vec<u8>,
+        merkle_root: [u8; 32],
+        lp_commitment: [u8; 32],
+        lp_nullifier: [u8; 32],
+        out_commitment: [u8; 32],
+        lp_amount: u64,
+        converted_amount: u64,
+        client_version: u32,
+    ) -> Result<()> {
+        perps::create_pending_with_proof_convert_lp_note(
+            ctx, operation_id, proof, merkle_root, lp_commitment, lp_nullifier,
+            out_commitment, lp_amount, converted_amount, client_version
+        )
+    }
+
     // ============ Perps Keeper Operations ============
 
     /// Update borrow fee accumulators for all tokens
@@ -829,6 +2017,28 @@ pub mod cloakcraft {
         perps::update_borrow_fees(ctx)
     }
 
+    /// Rebalance perps pool toward target token weights
+    ///
+    /// Pool authority only - swaps between vaults at oracle prices, less the
+    /// pool's rebalance fee. Pass one Pyth price update per active pool token
+    /// as remaining accounts.
+    pub fn rebalance_perps_pool<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RebalancePool<'info>>,
+        token_in_index: u8,
+        token_out_index: u8,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        perps::rebalance_pool(ctx, token_in_index, token_out_index, amount_in, min_amount_out)
+    }
+
+    /// Force-settle a delisted market at the oracle price
+    ///
+    /// Keeper instruction - anyone can call once the grace period has passed.
+    pub fn force_settle_market(ctx: Context<ForceSettleMarket>) -> Result<()> {
+        perps::force_settle_market(ctx)
+    }
+
     /// Create Pending with Proof Phase 0 - Liquidate
     #[allow(clippy::too_many_arguments)]
     pub fn create_pending_with_proof_liquidate<'info>(
@@ -843,25 +2053,33 @@ pub mod cloakcraft {
         current_price: u64,
         liquidator_reward: u64,
         owner_remainder: u64,
+        is_long: bool,
+        position_margin: u64,
+        position_size: u64,
+        entry_price: u64,
+        client_version: u32,
     ) -> Result<()> {
         perps::create_pending_with_proof_liquidate(
             ctx, operation_id, proof, merkle_root, position_commitment, position_nullifier,
-            owner_commitment, liquidator_commitment, current_price, liquidator_reward, owner_remainder
+            owner_commitment, liquidator_commitment, current_price, liquidator_reward, owner_remainder,
+            is_long, position_margin, position_size, entry_price, client_version
         )
     }
 
     /// Execute Liquidate Phase 3
+    ///
+    /// Position terms come from the pending operation (Phase 0 proof and
+    /// verify_position_meta_active), not from instruction data.
     pub fn execute_liquidate<'info>(
-        ctx: Context<'_, '_, '_, 'info, ExecuteLiquidate<'info>>,
-        operation_id: [u8; 32],
-        position_margin: u64,
-        position_size: u64,
-        is_long: bool,
+        ctx: Context<'_, '_, '_, 'info, ExecuteLiquidate<'info>>,
+        operation_id: [u8; 32],
     ) -> Result<()> {
-        perps::execute_liquidate(ctx, operation_id, position_margin, position_size, is_long)
+        perps::execute_liquidate(ctx, operation_id)
     }
 
-    /// Check if a position is at profit bound
+    /// Check if a position is at profit bound (read-only)
+    ///
+    /// Returns a `ProfitBoundCheck` as return data.
     pub fn check_perps_profit_bound(
         ctx: Context<CheckProfitBound>,
         position_margin: u64,
@@ -869,7 +2087,7 @@ pub mod cloakcraft {
         entry_price: u64,
         is_long: bool,
         current_price: u64,
-    ) -> Result<bool> {
+    ) -> Result<perps::ProfitBoundCheck> {
         perps::check_profit_bound(ctx, position_margin, position_size, entry_price, is_long, current_price)
     }
 
@@ -899,6 +2117,17 @@ pub mod cloakcraft {
         voting::create_ballot(ctx, ballot_id, config)
     }
 
+    /// Reallocate a ballot created with an older layout to the current one
+    ///
+    /// Permissionless: the added fields are zeroed (disabled) and the payer
+    /// covers the extra rent.
+    pub fn migrate_ballot<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateBallot<'info>>,
+        ballot_id: [u8; 32],
+    ) -> Result<()> {
+        voting::migrate_ballot(ctx, ballot_id)
+    }
+
     /// Resolve a voting ballot
     ///
     /// Determines the outcome based on the configured resolution mode:
@@ -913,6 +2142,29 @@ pub mod cloakcraft {
         voting::resolve_ballot(ctx, ballot_id, outcome)
     }
 
+    /// Dispute an Oracle ballot's outcome
+    ///
+    /// Within the ballot's dispute window; the challenger locks the dispute
+    /// bond and the outcome waits for the resolver (settle_dispute).
+    pub fn dispute_resolution(
+        ctx: Context<DisputeResolution>,
+        ballot_id: [u8; 32],
+    ) -> Result<()> {
+        voting::dispute_resolution(ctx, ballot_id)
+    }
+
+    /// Settle a disputed outcome (ballot resolver only)
+    ///
+    /// Refunds the challenger's bond if the outcome changes, slashes it to the
+    /// protocol treasury otherwise.
+    pub fn settle_dispute(
+        ctx: Context<SettleDispute>,
+        ballot_id: [u8; 32],
+        outcome: Option<u8>,
+    ) -> Result<()> {
+        voting::settle_dispute(ctx, ballot_id, outcome)
+    }
+
     /// Finalize a voting ballot
     ///
     /// Called after claim period expires (SpendToVote only).
@@ -924,18 +2176,90 @@ pub mod cloakcraft {
         voting::finalize_ballot(ctx, ballot_id)
     }
 
+    /// Register the snapshot root for a Snapshot-mode ballot
+    ///
+    /// Called by the ballot's indexer or authority after snapshot_slot.
+    /// Snapshot votes then prove note ownership against this archived root.
+    pub fn register_snapshot_root(
+        ctx: Context<RegisterSnapshotRoot>,
+        ballot_id: [u8; 32],
+        snapshot_root: [u8; 32],
+    ) -> Result<()> {
+        voting::register_snapshot_root(ctx, ballot_id, snapshot_root)
+    }
+
+    /// Create a Snapshot-mode ballot's snapshot tree
+    ///
+    /// Called by the ballot's indexer or authority.
+    pub fn initialize_snapshot_tree(
+        ctx: Context<InitializeSnapshotTree>,
+        ballot_id: [u8; 32],
+    ) -> Result<()> {
+        voting::initialize_snapshot_tree(ctx, ballot_id)
+    }
+
+    /// Append snapshot commitments to a ballot's snapshot tree
+    ///
+    /// Called by the ballot's indexer or authority after snapshot_slot.
+    /// Snapshot votes passing the tree may prove against any retained root.
+    pub fn append_snapshot_leaves(
+        ctx: Context<AppendSnapshotLeaves>,
+        ballot_id: [u8; 32],
+        leaves: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        voting::append_snapshot_leaves(ctx, ballot_id, leaves)
+    }
+
+    /// Register an LP mint for snapshot voting
+    ///
+    /// Called by the ballot's indexer or authority before the first vote.
+    /// LP note amounts are converted to ballot token units at `rate` (1e18).
+    pub fn register_lp_vote_source(
+        ctx: Context<RegisterLpVoteSource>,
+        ballot_id: [u8; 32],
+        lp_mint: Pubkey,
+        rate: u128,
+    ) -> Result<()> {
+        voting::register_lp_vote_source(ctx, ballot_id, lp_mint, rate)
+    }
+
+    /// Create a page tallying 16 more options of a ballot
+    ///
+    /// Permissionless. Page n (1-3) holds options 16n-16n+15.
+    pub fn create_ballot_options_page(
+        ctx: Context<CreateBallotOptionsPage>,
+        ballot_id: [u8; 32],
+        page: u8,
+    ) -> Result<()> {
+        voting::create_ballot_options_page(ctx, ballot_id, page)
+    }
+
+    /// Add an option to an open ballot (write-ins)
+    ///
+    /// Signed by the ballot's option registrar, or by anyone if registration
+    /// is Open. Allowed until end_time, up to the ballot's max_options.
+    pub fn add_ballot_option(
+        ctx: Context<AddBallotOption>,
+        ballot_id: [u8; 32],
+        metadata_hash: [u8; 32],
+    ) -> Result<()> {
+        voting::add_ballot_option(ctx, ballot_id, metadata_hash)
+    }
+
     /// Decrypt voting tally
     ///
     /// Called after timelock expires for TimeLocked and PermanentPrivate modes.
     /// Decrypts the homomorphic tally to reveal aggregate vote counts.
     /// For PermanentPrivate mode, this reveals ONLY aggregates, not individual votes.
+    /// Hidden-turnout ballots also reveal their vote count here.
     pub fn decrypt_tally(
         ctx: Context<DecryptTally>,
         ballot_id: [u8; 32],
         decryption_key: [u8; 32],
         decrypted_weights: Vec<u64>,
+        decrypted_vote_count: Option<u64>,
     ) -> Result<()> {
-        voting::decrypt_tally(ctx, ballot_id, decryption_key, decrypted_weights)
+        voting::decrypt_tally(ctx, ballot_id, decryption_key, decrypted_weights, decrypted_vote_count)
     }
 
     // ============ Snapshot Voting (Multi-Phase) ============
@@ -960,11 +2284,13 @@ pub mod cloakcraft {
         encrypted_contributions: Option<voting::EncryptedContributions>,
         encrypted_preimage: Option<Vec<u8>>,
         output_randomness: [u8; 32],
+        lp_mint: Option<Pubkey>,
+        client_version: u32,
     ) -> Result<()> {
         voting::create_pending_with_proof_vote_snapshot(
             ctx, operation_id, ballot_id, proof, snapshot_merkle_root, note_commitment,
             vote_nullifier, vote_commitment, vote_choice, amount, weight,
-            encrypted_contributions, encrypted_preimage, output_randomness
+            encrypted_contributions, encrypted_preimage, output_randomness, lp_mint, client_version
         )
     }
 
@@ -982,16 +2308,47 @@ pub mod cloakcraft {
         voting::create_vote_nullifier(ctx, operation_id, ballot_id, nullifier_index, light_params)
     }
 
+    /// Create Pending with Proof - Vote TWAB (Phase 0)
+    ///
+    /// Snapshot voting for TWAB ballots: weight is the note amount averaged
+    /// over the ballot's registered snapshot roots. Later phases are the
+    /// same as vote_snapshot.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_vote_twab<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofVoteTwab<'info>>,
+        operation_id: [u8; 32],
+        ballot_id: [u8; 32],
+        proof: Vec<u8>,
+        note_commitment: [u8; 32],
+        vote_nullifier: [u8; 32],
+        vote_commitment: [u8; 32],
+        vote_choice: u64,
+        twab_amount: u64,
+        weight: u64,
+        encrypted_contributions: Option<voting::EncryptedContributions>,
+        output_randomness: [u8; 32],
+        lp_mint: Option<Pubkey>,
+        client_version: u32,
+    ) -> Result<()> {
+        voting::create_pending_with_proof_vote_twab(
+            ctx, operation_id, ballot_id, proof, note_commitment, vote_nullifier,
+            vote_commitment, vote_choice, twab_amount, weight, encrypted_contributions,
+            output_randomness, lp_mint, client_version
+        )
+    }
+
     /// Execute Vote Snapshot (Phase 2)
     ///
-    /// Updates ballot tally based on the verified vote.
+    /// Updates ballot tally based on the verified vote. Hidden-turnout
+    /// ballots also take an ElGamal encryption of 1 to count the vote.
     pub fn execute_vote_snapshot(
         ctx: Context<ExecuteVoteSnapshot>,
         operation_id: [u8; 32],
         ballot_id: [u8; 32],
         encrypted_contributions: Option<voting::EncryptedContributions>,
+        encrypted_turnout: Option<[u8; 64]>,
     ) -> Result<()> {
-        voting::execute_vote_snapshot(ctx, operation_id, ballot_id, encrypted_contributions)
+        voting::execute_vote_snapshot(ctx, operation_id, ballot_id, encrypted_contributions, encrypted_turnout)
     }
 
     /// Create Vote Commitment (Phase 3)
@@ -1043,12 +2400,13 @@ pub mod cloakcraft {
         old_encrypted_contributions: Option<voting::EncryptedContributions>,
         new_encrypted_contributions: Option<voting::EncryptedContributions>,
         output_randomness: [u8; 32],
+        client_version: u32,
     ) -> Result<()> {
         voting::create_pending_with_proof_change_vote_snapshot(
             ctx, operation_id, ballot_id, proof, old_vote_commitment,
             old_vote_commitment_nullifier, new_vote_commitment, vote_nullifier,
             old_vote_choice, new_vote_choice, weight,
-            old_encrypted_contributions, new_encrypted_contributions, output_randomness
+            old_encrypted_contributions, new_encrypted_contributions, output_randomness, client_version
         )
     }
 
@@ -1088,11 +2446,12 @@ pub mod cloakcraft {
         encrypted_contributions: Option<voting::EncryptedContributions>,
         encrypted_preimage: Option<Vec<u8>>,
         output_randomness: [u8; 32],
+        client_version: u32,
     ) -> Result<()> {
         voting::create_pending_with_proof_vote_spend(
             ctx, operation_id, ballot_id, proof, merkle_root, input_commitment,
             spending_nullifier, position_commitment, vote_choice, amount, weight,
-            encrypted_contributions, encrypted_preimage, output_randomness
+            encrypted_contributions, encrypted_preimage, output_randomness, client_version
         )
     }
 
@@ -1129,12 +2488,13 @@ pub mod cloakcraft {
         old_encrypted_contributions: Option<voting::EncryptedContributions>,
         new_encrypted_contributions: Option<voting::EncryptedContributions>,
         output_randomness: [u8; 32],
+        client_version: u32,
     ) -> Result<()> {
         voting::create_pending_with_proof_change_vote_spend(
             ctx, operation_id, ballot_id, proof,
             old_position_commitment, old_position_nullifier, new_position_commitment,
             old_vote_choice, new_vote_choice, amount, weight,
-            old_encrypted_contributions, new_encrypted_contributions, output_randomness
+            old_encrypted_contributions, new_encrypted_contributions, output_randomness, client_version
         )
     }
 
@@ -1172,10 +2532,11 @@ pub mod cloakcraft {
         weight: u64,
         encrypted_contributions: Option<voting::EncryptedContributions>,
         output_randomness: [u8; 32],
+        client_version: u32,
     ) -> Result<()> {
         voting::create_pending_with_proof_close_vote_position(
             ctx, operation_id, ballot_id, proof, position_commitment, position_nullifier,
-            token_commitment, vote_choice, amount, weight, encrypted_contributions, output_randomness
+            token_commitment, vote_choice, amount, weight, encrypted_contributions, output_randomness, client_version
         )
     }
 
@@ -1210,11 +2571,60 @@ pub mod cloakcraft {
         gross_payout: u64,
         net_payout: u64,
         output_randomness: [u8; 32],
+        client_version: u32,
     ) -> Result<()> {
         voting::create_pending_with_proof_claim(
             ctx, operation_id, ballot_id, proof, position_commitment, position_nullifier,
             payout_commitment, user_vote_choice, user_weight, gross_payout, net_payout,
-            output_randomness
+            output_randomness, client_version
+        )
+    }
+
+    /// Create Pending with Proof - Multi-Position Claim (Phase 0)
+    ///
+    /// Claims 2-3 winning positions into a single payout commitment.
+    /// Phases 1-2 then run once per position; execute_claim is shared.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_claim_multi<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofClaimMulti<'info>>,
+        operation_id: [u8; 32],
+        ballot_id: [u8; 32],
+        proof: Vec<u8>,
+        positions: Vec<voting::ClaimPosition>,
+        payout_commitment: [u8; 32],
+        net_payout: u64,
+        output_randomness: [u8; 32],
+        client_version: u32,
+    ) -> Result<()> {
+        voting::create_pending_with_proof_claim_multi(
+            ctx, operation_id, ballot_id, proof, positions, payout_commitment, net_payout,
+            output_randomness, client_version
+        )
+    }
+
+    /// Create Pending with Proof - Claim Refund (Phase 0)
+    ///
+    /// Lets losers claim back `loser_refund_bps` of their stake.
+    /// Phases 1-5 are shared with claims.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_claim_refund<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofClaimRefund<'info>>,
+        operation_id: [u8; 32],
+        ballot_id: [u8; 32],
+        proof: Vec<u8>,
+        position_commitment: [u8; 32],
+        position_nullifier: [u8; 32],
+        refund_commitment: [u8; 32],
+        user_vote_choice: u64,
+        user_weight: u64,
+        refund_amount: u64,
+        output_randomness: [u8; 32],
+        client_version: u32,
+    ) -> Result<()> {
+        voting::create_pending_with_proof_claim_refund(
+            ctx, operation_id, ballot_id, proof, position_commitment, position_nullifier,
+            refund_commitment, user_vote_choice, user_weight, refund_amount,
+            output_randomness, client_version
         )
     }
 
@@ -1228,4 +2638,387 @@ pub mod cloakcraft {
     ) -> Result<()> {
         voting::execute_claim(ctx, operation_id, ballot_id)
     }
+
+    /// Quote the payout for a winning SpendToVote position (read-only)
+    ///
+    /// Returns a `ClaimPayoutQuote` as return data.
+    pub fn quote_claim_payout(
+        ctx: Context<QuoteClaimPayout>,
+        ballot_id: [u8; 32],
+        user_weight: u64,
+    ) -> Result<voting::ClaimPayoutQuote> {
+        voting::quote_claim_payout(ctx, ballot_id, user_weight)
+    }
+
+    // ============ Liquidity Mining Emissions ============
+
+    /// Create an emissions schedule for an AMM or perps pool
+    ///
+    /// Funds `reward_rate * (end_time - start_time)` rewards up front.
+    pub fn create_emissions_schedule(
+        ctx: Context<CreateEmissionsSchedule>,
+        pool_kind: state::EmissionsPoolKind,
+        reward_rate: u64,
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        emissions::create_emissions_schedule(ctx, pool_kind, reward_rate, start_time, end_time)
+    }
+
+    /// Register a reward checkpoint for an existing LP note
+    ///
+    /// Records the current accumulator as the note's `entry_reward_per_lp`.
+    /// Claims roll the checkpoint forward to the re-created LP note.
+    pub fn register_reward_checkpoint<'info>(
+        ctx: Context<'_, '_, '_, 'info, RegisterRewardCheckpoint<'info>>,
+        lp_commitment: [u8; 32],
+        light_params: generic::LightVerifyCommitmentParams,
+    ) -> Result<()> {
+        emissions::register_reward_checkpoint(ctx, lp_commitment, light_params)
+    }
+
+    /// Create Pending with Proof Phase 0 - Claim Rewards
+    ///
+    /// Flow:
+    /// Phase 0 (this): Accrue schedule + Verify ZK proof + Create PendingOperation
+    /// Phase 1: verify_commitment_exists for LP note
+    /// Phase 2: create_nullifier_and_pending for LP note
+    /// Phase 3: execute_claim_rewards to move rewards into the shielded pool
+    /// Phase 4: create_commitment for new LP note + rewards note
+    /// Final: close_pending_operation
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_claim_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofClaimRewards<'info>>,
+        operation_id: [u8; 32],
+        proof: Vec<u8>,
+        merkle_root: [u8; 32],
+        lp_commitment: [u8; 32],
+        lp_nullifier: [u8; 32],
+        new_lp_commitment: [u8; 32],
+        reward_commitment: [u8; 32],
+        lp_amount: u64,
+        reward_amount: u64,
+        client_version: u32,
+    ) -> Result<()> {
+        emissions::create_pending_with_proof_claim_rewards(
+            ctx, operation_id, proof, merkle_root, lp_commitment, lp_nullifier,
+            new_lp_commitment, reward_commitment, lp_amount, reward_amount, client_version
+        )
+    }
+
+    /// Execute Claim Rewards (Phase 3)
+    ///
+    /// Transfers rewards from the schedule vault into the reward pool vault.
+    pub fn execute_claim_rewards(
+        ctx: Context<ExecuteClaimRewards>,
+        operation_id: [u8; 32],
+    ) -> Result<()> {
+        emissions::execute_claim_rewards(ctx, operation_id)
+    }
+
+    // ============ Quadratic Funding ============
+
+    /// Create a quadratic funding matching round
+    ///
+    /// Funds the matching pool up front. Pass a threshold committee to
+    /// restrict who may submit the decrypted tally.
+    pub fn create_matching_round(
+        ctx: Context<CreateMatchingRound>,
+        round_id: [u8; 32],
+        config: state::MatchingRoundConfigInput,
+    ) -> Result<()> {
+        funding::create_matching_round(ctx, round_id, config)
+    }
+
+    /// Create Pending with Proof Phase 0 - Donate
+    ///
+    /// Flow:
+    /// Phase 0 (this): Verify ZK proof + Create PendingOperation
+    /// Phase 1: verify_commitment_exists for input note
+    /// Phase 2: create_nullifier_and_pending for input note
+    /// Phase 3: execute_donate to add the encrypted contribution to the tally
+    /// Phase 4: create_commitment for donation note + change note
+    /// Final: close_pending_operation
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_donate<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofDonate<'info>>,
+        operation_id: [u8; 32],
+        round_id: [u8; 32],
+        proof: Vec<u8>,
+        merkle_root: [u8; 32],
+        input_commitment: [u8; 32],
+        nullifier: [u8; 32],
+        donation_commitment: [u8; 32],
+        change_commitment: [u8; 32],
+        contributions_hash: [u8; 32],
+        client_version: u32,
+    ) -> Result<()> {
+        funding::create_pending_with_proof_donate(
+            ctx, operation_id, round_id, proof, merkle_root, input_commitment,
+            nullifier, donation_commitment, change_commitment, contributions_hash, client_version
+        )
+    }
+
+    /// Execute Donate (Phase 3)
+    ///
+    /// Adds one ciphertext per project to the round's encrypted tally.
+    pub fn execute_donate(
+        ctx: Context<ExecuteDonate>,
+        operation_id: [u8; 32],
+        round_id: [u8; 32],
+        encrypted_contributions: voting::EncryptedContributions,
+    ) -> Result<()> {
+        funding::execute_donate(ctx, operation_id, round_id, encrypted_contributions)
+    }
+
+    /// Compute quadratic matching after the round ends
+    ///
+    /// Verifies the submitted sqrt sums against the encrypted tally and
+    /// allocates the matching pool proportionally to `sqrt_sum^2`.
+    pub fn compute_matching(
+        ctx: Context<ComputeMatching>,
+        round_id: [u8; 32],
+        decryption_key: [u8; 32],
+        sqrt_sums: Vec<u64>,
+    ) -> Result<()> {
+        funding::compute_matching(ctx, round_id, decryption_key, sqrt_sums)
+    }
+
+    /// Pay a project's matching allocation to its recipient
+    pub fn claim_matching(
+        ctx: Context<ClaimMatching>,
+        round_id: [u8; 32],
+        project_index: u8,
+    ) -> Result<()> {
+        funding::claim_matching(ctx, round_id, project_index)
+    }
+
+    // ============ Protocol Token Buyback ============
+
+    /// Initialize the protocol token buyback (paused)
+    ///
+    /// Only callable by the protocol authority. Creates the buyback config,
+    /// stake registry and stake vault.
+    pub fn initialize_buyback<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeBuyback<'info>>,
+        buyback_bps: u16,
+        interval_seconds: i64,
+        max_price_impact_bps: u16,
+    ) -> Result<()> {
+        buyback::initialize_buyback(ctx, buyback_bps, interval_seconds, max_price_impact_bps)
+    }
+
+    /// Set the buyback share, interval, price impact bound and active flag
+    ///
+    /// Only callable by the protocol authority.
+    pub fn set_buyback_policy<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetBuybackPolicy<'info>>,
+        buyback_bps: u16,
+        interval_seconds: i64,
+        max_price_impact_bps: u16,
+        is_active: bool,
+    ) -> Result<()> {
+        buyback::set_buyback_policy(ctx, buyback_bps, interval_seconds, max_price_impact_bps, is_active)
+    }
+
+    /// Stake protocol tokens and register the distribution stealth key
+    pub fn stake_protocol_token(
+        ctx: Context<StakeProtocolToken>,
+        amount: u64,
+        stealth_pub_x: [u8; 32],
+    ) -> Result<()> {
+        buyback::stake_protocol_token(ctx, amount, stealth_pub_x)
+    }
+
+    /// Unstake protocol tokens (owed rewards are still distributed)
+    pub fn unstake_protocol_token(ctx: Context<UnstakeProtocolToken>, amount: u64) -> Result<()> {
+        buyback::unstake_protocol_token(ctx, amount)
+    }
+
+    /// Spend a share of treasury fees on the protocol token (protocol authority)
+    ///
+    /// Swaps through the AMM pool pairing the fee mint with the protocol
+    /// token and credits the bought tokens to stakers. min_amount_out must be
+    /// set from an external price.
+    pub fn execute_buyback(ctx: Context<ExecuteBuyback>, min_amount_out: u64) -> Result<()> {
+        buyback::execute_buyback(ctx, min_amount_out)
+    }
+
+    /// Commit a staker's owed buyback share as a shielded note (permissionless)
+    pub fn distribute_buyback<'info>(
+        ctx: Context<'_, '_, '_, 'info, DistributeBuyback<'info>>,
+        staker: Pubkey,
+        params: DistributeBuybackParams,
+    ) -> Result<()> {
+        buyback::distribute_buyback(ctx, staker, params)
+    }
+
+    // ============ Savings Vaults ============
+
+    /// Create a savings vault over an AMM or perps pool's LP token
+    ///
+    /// Only callable by the source pool's authority. Creates the vault share
+    /// mint; the share pool is initialized like any other pool.
+    pub fn initialize_savings_vault(
+        ctx: Context<InitializeSavingsVault>,
+        pool_kind: state::EmissionsPoolKind,
+        max_price_impact_bps: u16,
+    ) -> Result<()> {
+        savings::initialize_savings_vault(ctx, pool_kind, max_price_impact_bps)
+    }
+
+    /// Pause or resume deposits, set the harvest price impact bound and
+    /// attach the source pool's emissions schedule
+    pub fn set_savings_vault_policy(
+        ctx: Context<SetSavingsVaultPolicy>,
+        is_active: bool,
+        max_price_impact_bps: u16,
+    ) -> Result<()> {
+        savings::set_savings_vault_policy(ctx, is_active, max_price_impact_bps)
+    }
+
+    /// Create Pending with Proof Phase 0 - Savings Vault Deposit
+    ///
+    /// Flow:
+    /// Phase 0 (this): Verify ZK proof + Create PendingOperation
+    /// Phase 1: verify_commitment_exists for LP note
+    /// Phase 2: create_nullifier_and_pending for LP note
+    /// Phase 3: execute_vault_deposit to mint vault shares
+    /// Phase 4: create_commitment for vault share note
+    /// Final: close_pending_operation
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_vault_deposit<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofVaultDeposit<'info>>,
+        operation_id: [u8; 32],
+        proof: Vec<u8>,
+        merkle_root: [u8; 32],
+        lp_commitment: [u8; 32],
+        lp_nullifier: [u8; 32],
+        share_commitment: [u8; 32],
+        lp_amount: u64,
+        share_amount: u64,
+        client_version: u32,
+    ) -> Result<()> {
+        savings::create_pending_with_proof_vault_deposit(
+            ctx, operation_id, proof, merkle_root, lp_commitment, lp_nullifier,
+            share_commitment, lp_amount, share_amount, client_version
+        )
+    }
+
+    /// Execute Vault Deposit (Phase 3)
+    pub fn execute_vault_deposit(
+        ctx: Context<ExecuteVaultDeposit>,
+        operation_id: [u8; 32],
+    ) -> Result<()> {
+        savings::execute_vault_deposit(ctx, operation_id)
+    }
+
+    /// Create Pending with Proof Phase 0 - Savings Vault Withdraw
+    ///
+    /// Flow:
+    /// Phase 0 (this): Verify ZK proof + Create PendingOperation
+    /// Phase 1: verify_commitment_exists for share note
+    /// Phase 2: create_nullifier_and_pending for share note
+    /// Phase 3: execute_vault_withdraw to burn vault shares
+    /// Phase 4: create_commitment for LP note
+    /// Final: close_pending_operation
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_pending_with_proof_vault_withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreatePendingWithProofVaultWithdraw<'info>>,
+        operation_id: [u8; 32],
+        proof: Vec<u8>,
+        merkle_root: [u8; 32],
+        share_commitment: [u8; 32],
+        share_nullifier: [u8; 32],
+        lp_commitment: [u8; 32],
+        share_amount: u64,
+        lp_amount: u64,
+        client_version: u32,
+    ) -> Result<()> {
+        savings::create_pending_with_proof_vault_withdraw(
+            ctx, operation_id, proof, merkle_root, share_commitment, share_nullifier,
+            lp_commitment, share_amount, lp_amount, client_version
+        )
+    }
+
+    /// Execute Vault Withdraw (Phase 3)
+    pub fn execute_vault_withdraw(
+        ctx: Context<ExecuteVaultWithdraw>,
+        operation_id: [u8; 32],
+    ) -> Result<()> {
+        savings::execute_vault_withdraw(ctx, operation_id)
+    }
+
+    /// Compound a savings vault's emissions rewards into LP (vault authority)
+    ///
+    /// Swaps the rewards through the AMM pool pairing the reward token with
+    /// the vault's LP token, raising the share price. min_lp_out must be set
+    /// from an external price.
+    pub fn harvest_savings_vault(ctx: Context<HarvestSavingsVault>, min_lp_out: u64) -> Result<()> {
+        savings::harvest_savings_vault(ctx, min_lp_out)
+    }
+
+    // ============ Operation Credits ============
+
+    /// Initialize anonymous operation credits
+    ///
+    /// Only callable by the protocol authority. Credits are not required
+    /// until enabled with set_credit_policy.
+    pub fn initialize_credit_config<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeCreditConfig<'info>>,
+        epoch_seconds: i64,
+        credits_per_epoch: u16,
+        min_stake: u64,
+        fee_lamports: u64,
+    ) -> Result<()> {
+        credits::initialize_credit_config(ctx, epoch_seconds, credits_per_epoch, min_stake, fee_lamports)
+    }
+
+    /// Set the staker allowance, credit price and whether Phase 0 requires a credit
+    ///
+    /// Only callable by the protocol authority.
+    pub fn set_credit_policy<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetCreditPolicy<'info>>,
+        credits_per_epoch: u16,
+        min_stake: u64,
+        fee_lamports: u64,
+        require_credits: bool,
+    ) -> Result<()> {
+        credits::set_credit_policy(ctx, credits_per_epoch, min_stake, fee_lamports, require_credits)
+    }
+
+    /// Claim this epoch's stake-backed operation credits
+    pub fn claim_staked_credits(
+        ctx: Context<ClaimStakedCredits>,
+        epoch: u64,
+        leaves: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        credits::claim_staked_credits(ctx, epoch, leaves)
+    }
+
+    /// Buy operation credits for this epoch
+    pub fn purchase_credits(
+        ctx: Context<PurchaseCredits>,
+        epoch: u64,
+        leaves: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        credits::purchase_credits(ctx, epoch, leaves)
+    }
+
+    /// Spend an operation credit for an operation ID (before its Phase 0)
+    pub fn consume_operation_credit(
+        ctx: Context<ConsumeOperationCredit>,
+        operation_id: [u8; 32],
+        epoch: u64,
+        proof: Vec<u8>,
+        credit_root: [u8; 32],
+        credit_nullifier: [u8; 32],
+    ) -> Result<()> {
+        credits::consume_operation_credit(ctx, operation_id, epoch, proof, credit_root, credit_nullifier)
+    }
+
+    /// Close a spent operation credit after its epoch (refunds the relayer)
+    pub fn close_operation_credit(ctx: Context<CloseOperationCredit>) -> Result<()> {
+        credits::close_operation_credit(ctx)
+    }
 }
@@ -0,0 +1,95 @@
+//! Light error mapping
+//!
+//! Light SDK failures come back as numeric codes (`LightSdkError` 16xxx,
+//! `LightSdkTypesError` 14xxx, hasher 7xxx, compressed account 12xxx,
+//! zero-copy 15xxx). `light_error` decodes them into distinct CloakCraft
+//! errors and emits `LightCpiFailed` with both codes, so failed transaction
+//! logs say what went wrong instead of a bare `LightCpiError`.
+//!
+//! Codes without a specific mapping fall back to the caller's context error
+//! (e.g. `NullifierAlreadySpent`). Failures inside the Light system program
+//! itself (address exists, stale root, full tree, invalid validity proof)
+//! abort the transaction in the CPI and are reported by that program's own
+//! error code in the logs.
+
+use anchor_lang::prelude::*;
+use light_sdk::error::LightSdkError;
+
+use crate::errors::CloakCraftError;
+
+/// Emitted when a Light SDK call fails, before the mapped error is returned
+#[event]
+pub struct LightCpiFailed {
+    /// Raw Light error code
+    pub light_error_code: u32,
+    /// CloakCraft error code returned for it
+    pub error_code: u32,
+}
+
+/// Decode a Light error code into a specific CloakCraft error
+pub fn classify_light_error(code: u32) -> Option<CloakCraftError> {
+    match code {
+        // LightSdkTypesError (14xxx) mirrors LightSdkError (16xxx)
+        14_000..=14_999 | 16_000..=16_999 => match code % 1000 {
+            // ExpectedAddressRootIndex, ExpectedRootIndex
+            5 | 11 => Some(CloakCraftError::LightInvalidRootIndex),
+            // Missing tree info, bad tree index, missing output tree
+            4 | 10 | 20 | 31 | 37 | 41 => Some(CloakCraftError::LightInvalidTreeAccount),
+            // Missing / wrong Light system accounts
+            2 | 3 | 9 | 17 | 18 | 32 | 33 | 34 => Some(CloakCraftError::LightMissingSystemAccounts),
+            // Borsh
+            16 => Some(CloakCraftError::LightAccountEncodingFailed),
+            _ => None,
+        },
+        // Hasher, compressed account and zero-copy errors
+        7_000..=7_999 | 12_000..=12_999 | 15_000..=15_999 => {
+            Some(CloakCraftError::LightAccountEncodingFailed)
+        }
+        _ => None,
+    }
+}
+
+/// `map_err` adapter for Light SDK results
+///
+/// Usage: `.map_err(light_error(CloakCraftError::CommitmentCreationFailed))?`
+pub fn light_error<E: Into<LightSdkError>>(fallback: CloakCraftError) -> impl FnOnce(E) -> Error {
+    move |err| {
+        let err: LightSdkError = err.into();
+        let missing_accounts = matches!(
+            err,
+            LightSdkError::ProgramError(ProgramError::NotEnoughAccountKeys)
+        );
+        let light_error_code = u32::from(err);
+        let mapped = if missing_accounts {
+            CloakCraftError::LightMissingSystemAccounts
+        } else {
+            classify_light_error(light_error_code).unwrap_or(fallback)
+        };
+
+        msg!("Light error {} -> {:?}", light_error_code, mapped);
+        emit!(LightCpiFailed {
+            light_error_code,
+            error_code: mapped.into(),
+        });
+        mapped.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(code: u32) -> Option<u32> {
+        classify_light_error(code).map(u32::from)
+    }
+
+    #[test]
+    fn test_classify_light_error() {
+        assert_eq!(classify(16011), Some(CloakCraftError::LightInvalidRootIndex.into()));
+        assert_eq!(classify(14031), Some(CloakCraftError::LightInvalidTreeAccount.into()));
+        assert_eq!(classify(16017), Some(CloakCraftError::LightMissingSystemAccounts.into()));
+        assert_eq!(classify(7001), Some(CloakCraftError::LightAccountEncodingFailed.into()));
+        assert_eq!(classify(16012), None);
+        assert_eq!(classify(6000), None);
+    }
+}
@@ -9,18 +9,78 @@
 //! - Helius Photon provides merkle proofs
 //! - Scales to millions of entries
 //! - V2 batch trees for better throughput
+//!
+//! Light SDK errors are decoded into specific CloakCraft errors (see `errors`).
 
 use anchor_lang::prelude::*;
 use light_sdk::{
     account::LightAccount,
-    address::v2::{derive_address, derive_address_seed},
+    address::{v2::derive_address, AddressSeed},
     cpi::{v2::{LightSystemProgramCpi, CpiAccounts}, InvokeLightSystemProgram, LightCpiInstruction},
     instruction::{PackedAddressTreeInfo, ValidityProof},
 };
 
-use crate::state::{SpendNullifierAccount, ActionNullifierAccount, CommitmentAccount, PositionMeta, PositionStatus, LightValidityProof, LightAddressTreeInfo};
+use crate::state::{AdminActionRecord, NullifierDomain, PaymentReceipt, PoolTrees, SpendNullifierAccount, ActionNullifierAccount, CommitmentAccount, PositionMeta, PositionStatus, LightValidityProof, LightAddressTreeInfo, VIEW_TAG_SIZE};
 use crate::errors::CloakCraftError;
 use crate::LIGHT_CPI_SIGNER;
+use crate::merkle::smt::{self, SmtProof};
+
+mod errors;
+
+pub use errors::*;
+
+/// Resolve the address tree and output state tree a Light CPI would use
+pub fn resolve_trees<'info>(
+    fee_payer: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    address_tree_info: &LightAddressTreeInfo,
+    output_tree_index: u8,
+) -> Result<PoolTrees> {
+    let light_cpi_accounts = CpiAccounts::new(
+        fee_payer,
+        remaining_accounts,
+        LIGHT_CPI_SIGNER,
+    );
+    let address_tree_info: PackedAddressTreeInfo = address_tree_info.clone().into();
+    tree_pubkeys(&light_cpi_accounts, &address_tree_info, output_tree_index)
+}
+
+fn tree_pubkeys(
+    light_cpi_accounts: &CpiAccounts<'_, '_>,
+    address_tree_info: &PackedAddressTreeInfo,
+    output_tree_index: u8,
+) -> Result<PoolTrees> {
+    let address_tree = address_tree_info.get_tree_pubkey(light_cpi_accounts)
+        .map_err(light_error(CloakCraftError::LightCpiError))?;
+    let state_tree = light_cpi_accounts.get_tree_account_info(output_tree_index as usize)
+        .map_err(light_error(CloakCraftError::LightCpiError))?
+        .key();
+    Ok(PoolTrees { state_tree, address_tree })
+}
+
+/// Derive a nullifier address in `domain`, returning (address, address_seed)
+///
+/// Untagged domains keep the original `[prefix, scope, nullifier]` seeds.
+fn derive_nullifier_address(
+    prefix: &[u8],
+    domain: NullifierDomain,
+    scope: &[u8],
+    nullifier: &[u8; 32],
+    address_tree: &Pubkey,
+) -> ([u8; 32], AddressSeed) {
+    match domain.seed_tag() {
+        Some(tag) => derive_address(&[prefix, &[tag], scope, nullifier.as_ref()], address_tree, &crate::ID),
+        None => derive_address(&[prefix, scope, nullifier.as_ref()], address_tree, &crate::ID),
+    }
+}
+
+/// Reject CPIs that would write a pool's accounts into foreign trees
+fn require_pool_trees(actual: &PoolTrees, expected: &PoolTrees) -> Result<()> {
+    require!(expected.address_tree != Pubkey::default(), CloakCraftError::PoolTreesNotSet);
+    require_keys_eq!(actual.address_tree, expected.address_tree, CloakCraftError::InvalidAddressTree);
+    require_keys_eq!(actual.state_tree, expected.state_tree, CloakCraftError::InvalidStateTree);
+    Ok(())
+}
 
 /// Create a spend nullifier compressed account
 ///
@@ -32,7 +92,9 @@ use crate::LIGHT_CPI_SIGNER;
 /// If the nullifier has already been spent in this pool, the validity proof will fail,
 /// preventing double-spending.
 ///
-/// Seeds: ["spend_nullifier", pool, nullifier]
+/// Seeds: ["spend_nullifier", pool, nullifier] (Spend domain)
+///        ["spend_nullifier", [domain], pool, nullifier] (other domains)
+#[allow(clippy::too_many_arguments)]
 pub fn create_spend_nullifier_account<'info>(
     fee_payer: &AccountInfo<'info>,
     remaining_accounts: &[AccountInfo<'info>],
@@ -40,8 +102,12 @@ pub fn create_spend_nullifier_account<'info>(
     address_tree_info: LightAddressTreeInfo,
     output_tree_index: u8,
     pool: Pubkey,
+    pool_trees: PoolTrees,
+    domain: NullifierDomain,
     nullifier: [u8; 32],
 ) -> Result<()> {
+    require!(!domain.is_action(), CloakCraftError::InvalidNullifierDomain);
+
     // Convert IDL-safe types to Light SDK types
     let proof: ValidityProof = proof.into();
     let address_tree_info: PackedAddressTreeInfo = address_tree_info.into();
@@ -53,20 +119,19 @@ pub fn create_spend_nullifier_account<'info>(
         LIGHT_CPI_SIGNER,
     );
 
-    // Get address tree pubkey
-    let address_tree_pubkey = address_tree_info.get_tree_pubkey(&light_cpi_accounts)
-        .map_err(|_| CloakCraftError::LightCpiError)?;
-
-    // Derive address from pool + nullifier hash
-    // Seeds: ["spend_nullifier", pool, nullifier]
-    let (address, address_seed) = derive_address(
-        &[
-            SpendNullifierAccount::SEED_PREFIX,
-            pool.as_ref(),
-            nullifier.as_ref(),
-        ],
+    // Nullifiers must live in the pool's address tree, or the same nullifier
+    // could be created again in another tree
+    let trees = tree_pubkeys(&light_cpi_accounts, &address_tree_info, output_tree_index)?;
+    require_pool_trees(&trees, &pool_trees)?;
+    let address_tree_pubkey = trees.address_tree;
+
+    // Derive address from domain + pool + nullifier hash
+    let (address, address_seed) = derive_nullifier_address(
+        SpendNullifierAccount::SEED_PREFIX,
+        domain,
+        pool.as_ref(),
+        &nullifier,
         &address_tree_pubkey,
-        &crate::ID,
     );
 
     // Create new address params for the compressed account (V2 format)
@@ -89,10 +154,10 @@ pub fn create_spend_nullifier_account<'info>(
     // This will fail if the address already exists (nullifier already spent)
     LightSystemProgramCpi::new_cpi(LIGHT_CPI_SIGNER, proof)
         .with_light_account(nullifier_account)
-        .map_err(|_| CloakCraftError::LightCpiError)?
+        .map_err(light_error(CloakCraftError::LightCpiError))?
         .with_new_addresses(&[new_address_params])
         .invoke(light_cpi_accounts)
-        .map_err(|_| CloakCraftError::NullifierAlreadySpent)?;
+        .map_err(light_error(CloakCraftError::NullifierAlreadySpent))?;
 
     Ok(())
 }
@@ -107,7 +172,9 @@ pub fn create_spend_nullifier_account<'info>(
 /// If the nullifier has already been used in this aggregation, the validity proof will fail,
 /// preventing double-voting.
 ///
-/// Seeds: ["action_nullifier", aggregation, nullifier]
+/// Seeds: ["action_nullifier", aggregation, nullifier] (Vote domain)
+///        ["action_nullifier", [domain], aggregation, nullifier] (other domains)
+#[allow(clippy::too_many_arguments)]
 pub fn create_action_nullifier_account<'info>(
     fee_payer: &AccountInfo<'info>,
     remaining_accounts: &[AccountInfo<'info>],
@@ -115,8 +182,11 @@ pub fn create_action_nullifier_account<'info>(
     address_tree_info: LightAddressTreeInfo,
     output_tree_index: u8,
     aggregation_id: [u8; 32],
+    domain: NullifierDomain,
     nullifier: [u8; 32],
 ) -> Result<()> {
+    require!(domain.is_action(), CloakCraftError::InvalidNullifierDomain);
+
     // Convert IDL-safe types to Light SDK types
     let proof: ValidityProof = proof.into();
     let address_tree_info: PackedAddressTreeInfo = address_tree_info.into();
@@ -130,18 +200,15 @@ pub fn create_action_nullifier_account<'info>(
 
     // Get address tree pubkey
     let address_tree_pubkey = address_tree_info.get_tree_pubkey(&light_cpi_accounts)
-        .map_err(|_| CloakCraftError::LightCpiError)?;
-
-    // Derive address from aggregation + nullifier hash
-    // Seeds: ["action_nullifier", aggregation, nullifier]
-    let (address, address_seed) = derive_address(
-        &[
-            ActionNullifierAccount::SEED_PREFIX,
-            aggregation_id.as_ref(),
-            nullifier.as_ref(),
-        ],
+        .map_err(light_error(CloakCraftError::LightCpiError))?;
+
+    // Derive address from domain + aggregation + nullifier hash
+    let (address, address_seed) = derive_nullifier_address(
+        ActionNullifierAccount::SEED_PREFIX,
+        domain,
+        aggregation_id.as_ref(),
+        &nullifier,
         &address_tree_pubkey,
-        &crate::ID,
     );
 
     // Create new address params for the compressed account (V2 format)
@@ -164,10 +231,10 @@ pub fn create_action_nullifier_account<'info>(
     // This will fail if the address already exists (already voted)
     LightSystemProgramCpi::new_cpi(LIGHT_CPI_SIGNER, proof)
         .with_light_account(nullifier_account)
-        .map_err(|_| CloakCraftError::LightCpiError)?
+        .map_err(light_error(CloakCraftError::LightCpiError))?
         .with_new_addresses(&[new_address_params])
         .invoke(light_cpi_accounts)
-        .map_err(|_| CloakCraftError::ActionNullifierAlreadyUsed)?;
+        .map_err(light_error(CloakCraftError::ActionNullifierAlreadyUsed))?;
 
     Ok(())
 }
@@ -178,22 +245,14 @@ pub fn create_action_nullifier_account<'info>(
 /// This is useful for clients to compute the expected address
 /// and query the indexer for existence.
 ///
-/// Seeds: ["spend_nullifier", pool, nullifier]
+/// Seeds: see `create_spend_nullifier_account`
 pub fn derive_spend_nullifier_address(
     pool: &Pubkey,
+    domain: NullifierDomain,
     nullifier: &[u8; 32],
     address_tree: &Pubkey,
 ) -> [u8; 32] {
-    let (address, _) = derive_address(
-        &[
-            SpendNullifierAccount::SEED_PREFIX,
-            pool.as_ref(),
-            nullifier.as_ref(),
-        ],
-        address_tree,
-        &crate::ID,
-    );
-    address
+    derive_nullifier_address(SpendNullifierAccount::SEED_PREFIX, domain, pool.as_ref(), nullifier, address_tree).0
 }
 
 /// Derive the compressed account address for an action nullifier
@@ -201,22 +260,14 @@ pub fn derive_spend_nullifier_address(
 /// This is useful for clients to compute the expected address
 /// and query the indexer for existence.
 ///
-/// Seeds: ["action_nullifier", aggregation, nullifier]
+/// Seeds: see `create_action_nullifier_account`
 pub fn derive_action_nullifier_address(
     aggregation_id: &[u8; 32],
+    domain: NullifierDomain,
     nullifier: &[u8; 32],
     address_tree: &Pubkey,
 ) -> [u8; 32] {
-    let (address, _) = derive_address(
-        &[
-            ActionNullifierAccount::SEED_PREFIX,
-            aggregation_id.as_ref(),
-            nullifier.as_ref(),
-        ],
-        address_tree,
-        &crate::ID,
-    );
-    address
+    derive_nullifier_address(ActionNullifierAccount::SEED_PREFIX, domain, aggregation_id.as_ref(), nullifier, address_tree).0
 }
 
 
@@ -237,6 +288,17 @@ pub fn vec_to_fixed_note(note: &[u8]) -> ([u8; MAX_ENCRYPTED_NOTE_SIZE], u16) {
     (arr, len)
 }
 
+/// Split an encrypted note into the inline array, its full length and the
+/// overflow past `MAX_ENCRYPTED_NOTE_SIZE`
+///
+/// Unlike `vec_to_fixed_note`, nothing is truncated: the caller checks the
+/// note against the pool's maximum first.
+pub fn split_encrypted_note(note: &[u8]) -> ([u8; MAX_ENCRYPTED_NOTE_SIZE], u16, Vec<u8>) {
+    let (arr, _) = vec_to_fixed_note(note);
+    let overflow = note.get(MAX_ENCRYPTED_NOTE_SIZE..).unwrap_or_default().to_vec();
+    (arr, note.len() as u16, overflow)
+}
+
 /// Create a commitment compressed account
 ///
 /// This stores a note commitment in Light Protocol's state tree.
@@ -252,12 +314,56 @@ pub fn create_commitment_account<'info>(
     address_tree_info: LightAddressTreeInfo,
     output_tree_index: u8,
     pool: Pubkey,
+    pool_trees: PoolTrees,
     commitment: [u8; 32],
     leaf_index: u64,
     stealth_ephemeral_pubkey: [u8; 64],
     encrypted_note: [u8; MAX_ENCRYPTED_NOTE_SIZE],
     encrypted_note_len: u16,
+    view_tag: [u8; VIEW_TAG_SIZE],
 ) -> Result<()> {
+    create_commitment_account_with_overflow(
+        fee_payer,
+        remaining_accounts,
+        proof,
+        address_tree_info,
+        output_tree_index,
+        pool,
+        pool_trees,
+        commitment,
+        leaf_index,
+        stealth_ephemeral_pubkey,
+        encrypted_note,
+        encrypted_note_len,
+        view_tag,
+        Vec::new(),
+    )
+}
+
+/// Create a commitment compressed account whose encrypted note may exceed
+/// the inline capacity
+///
+/// `encrypted_note_len` is the full note length; `encrypted_note_overflow`
+/// holds the bytes past `MAX_ENCRYPTED_NOTE_SIZE` (see `split_encrypted_note`).
+#[allow(clippy::too_many_arguments)]
+pub fn create_commitment_account_with_overflow<'info>(
+    fee_payer: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    proof: LightValidityProof,
+    address_tree_info: LightAddressTreeInfo,
+    output_tree_index: u8,
+    pool: Pubkey,
+    pool_trees: PoolTrees,
+    commitment: [u8; 32],
+    leaf_index: u64,
+    stealth_ephemeral_pubkey: [u8; 64],
+    encrypted_note: [u8; MAX_ENCRYPTED_NOTE_SIZE],
+    encrypted_note_len: u16,
+    view_tag: [u8; VIEW_TAG_SIZE],
+    encrypted_note_overflow: Vec<u8>,
+) -> Result<()> {
+    require_note_overflow(encrypted_note_len, &encrypted_note_overflow)?;
+
     // Convert IDL-safe types to Light SDK types
     let proof: ValidityProof = proof.into();
     let address_tree_info: PackedAddressTreeInfo = address_tree_info.into();
@@ -269,9 +375,10 @@ pub fn create_commitment_account<'info>(
         LIGHT_CPI_SIGNER,
     );
 
-    // Get address tree pubkey
-    let address_tree_pubkey = address_tree_info.get_tree_pubkey(&light_cpi_accounts)
-        .map_err(|_| CloakCraftError::LightCpiError)?;
+    // Commitments go to the pool's active state tree and address tree
+    let trees = tree_pubkeys(&light_cpi_accounts, &address_tree_info, output_tree_index)?;
+    require_pool_trees(&trees, &pool_trees)?;
+    let address_tree_pubkey = trees.address_tree;
 
     // Derive address from commitment hash
     // Address = hash(SEED_PREFIX || pool || commitment || address_tree || program_id)
@@ -306,18 +413,30 @@ pub fn create_commitment_account<'info>(
     commitment_account.encrypted_note = encrypted_note;
     commitment_account.encrypted_note_len = encrypted_note_len;
     commitment_account.created_at = clock.unix_timestamp;
+    commitment_account.view_tag = view_tag;
+    commitment_account.encrypted_note_overflow = encrypted_note_overflow;
 
     // Invoke Light System Program to create the compressed account
     LightSystemProgramCpi::new_cpi(LIGHT_CPI_SIGNER, proof)
         .with_light_account(commitment_account)
-        .map_err(|_| CloakCraftError::LightCpiError)?
+        .map_err(light_error(CloakCraftError::LightCpiError))?
         .with_new_addresses(&[new_address_params])
         .invoke(light_cpi_accounts)
-        .map_err(|_| CloakCraftError::CommitmentCreationFailed)?;
+        .map_err(light_error(CloakCraftError::CommitmentCreationFailed))?;
 
     Ok(())
 }
 
+/// The stored length must account for every overflow byte
+fn require_note_overflow(encrypted_note_len: u16, encrypted_note_overflow: &[u8]) -> Result<()> {
+    require!(
+        encrypted_note_len as usize
+            == (encrypted_note_len as usize).min(MAX_ENCRYPTED_NOTE_SIZE) + encrypted_note_overflow.len(),
+        CloakCraftError::InvalidEncryptedNote
+    );
+    Ok(())
+}
+
 /// Derive the compressed account address for a commitment
 pub fn derive_commitment_address(
     pool: &Pubkey,
@@ -336,6 +455,307 @@ pub fn derive_commitment_address(
     address
 }
 
+// =============================================================================
+// Batched Creation
+// =============================================================================
+
+/// Most compressed accounts `create_accounts_batch` creates in one CPI
+///
+/// Bounded by the number of new addresses one Light validity proof covers.
+pub const MAX_BATCHED_ACCOUNTS: usize = 4;
+
+/// A compressed account created by `create_accounts_batch`
+pub enum BatchedAccount {
+    /// Spend nullifier (see `create_spend_nullifier_account`)
+    SpendNullifier {
+        domain: NullifierDomain,
+        nullifier: [u8; 32],
+    },
+    /// Commitment (see `create_commitment_account_with_overflow`)
+    Commitment(Box<BatchedCommitment>),
+}
+
+/// Commitment data of `BatchedAccount::Commitment` (boxed: the inline note is large)
+pub struct BatchedCommitment {
+    pub commitment: [u8; 32],
+    pub leaf_index: u64,
+    pub stealth_ephemeral_pubkey: [u8; 64],
+    pub encrypted_note: [u8; MAX_ENCRYPTED_NOTE_SIZE],
+    pub encrypted_note_len: u16,
+    pub view_tag: [u8; VIEW_TAG_SIZE],
+    pub encrypted_note_overflow: Vec<u8>,
+}
+
+/// Create several of a pool's nullifiers and commitments in one Light CPI
+///
+/// Every account goes to the pool's trees, so one validity proof covers all
+/// of their addresses and the Light system program is invoked once instead
+/// of once per account. `address_tree_infos[i]` is the address tree info
+/// (root index) of `accounts[i]` in the proof.
+///
+/// Addresses and account data are derived exactly as by the single-account
+/// functions, so batched and unbatched accounts are interchangeable for
+/// clients and for later inclusion checks.
+#[allow(clippy::too_many_arguments)]
+pub fn create_accounts_batch<'info>(
+    fee_payer: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    proof: LightValidityProof,
+    address_tree_infos: Vec<LightAddressTreeInfo>,
+    output_tree_index: u8,
+    pool: Pubkey,
+    pool_trees: PoolTrees,
+    accounts: Vec<BatchedAccount>,
+) -> Result<()> {
+    require!(
+        !accounts.is_empty()
+            && accounts.len() <= MAX_BATCHED_ACCOUNTS
+            && address_tree_infos.len() == accounts.len(),
+        CloakCraftError::InvalidLightBatch
+    );
+
+    // Convert IDL-safe types to Light SDK types
+    let proof: ValidityProof = proof.into();
+
+    // Setup Light CPI accounts (v2)
+    let light_cpi_accounts = CpiAccounts::new(
+        fee_payer,
+        remaining_accounts,
+        LIGHT_CPI_SIGNER,
+    );
+
+    let created_at = Clock::get()?.unix_timestamp;
+    let mut cpi = LightSystemProgramCpi::new_cpi(LIGHT_CPI_SIGNER, proof);
+    let mut new_address_params = Vec::with_capacity(accounts.len());
+    let mut creates_nullifier = false;
+
+    for (index, (account, address_tree_info)) in accounts.into_iter().zip(address_tree_infos).enumerate() {
+        let address_tree_info: PackedAddressTreeInfo = address_tree_info.into();
+
+        // Each address must resolve to the pool's trees, as in the single-account CPIs
+        let trees = tree_pubkeys(&light_cpi_accounts, &address_tree_info, output_tree_index)?;
+        require_pool_trees(&trees, &pool_trees)?;
+
+        let address_seed = match account {
+            BatchedAccount::SpendNullifier { domain, nullifier } => {
+                require!(!domain.is_action(), CloakCraftError::InvalidNullifierDomain);
+                let (address, address_seed) = derive_nullifier_address(
+                    SpendNullifierAccount::SEED_PREFIX,
+                    domain,
+                    pool.as_ref(),
+                    &nullifier,
+                    &trees.address_tree,
+                );
+
+                let mut nullifier_account = LightAccount::<SpendNullifierAccount>::new_init(
+                    &crate::ID,
+                    Some(address),
+                    output_tree_index,
+                );
+                nullifier_account.pool = pool.to_bytes();
+                nullifier_account.spent_at = created_at;
+
+                cpi = cpi.with_light_account(nullifier_account)
+                    .map_err(light_error(CloakCraftError::LightCpiError))?;
+                creates_nullifier = true;
+                address_seed
+            }
+            BatchedAccount::Commitment(batched) => {
+                let BatchedCommitment {
+                    commitment,
+                    leaf_index,
+                    stealth_ephemeral_pubkey,
+                    encrypted_note,
+                    encrypted_note_len,
+                    view_tag,
+                    encrypted_note_overflow,
+                } = *batched;
+                require_note_overflow(encrypted_note_len, &encrypted_note_overflow)?;
+                let (address, address_seed) = derive_address(
+                    &[
+                        CommitmentAccount::SEED_PREFIX,
+                        pool.as_ref(),
+                        commitment.as_ref(),
+                    ],
+                    &trees.address_tree,
+                    &crate::ID,
+                );
+
+                let mut commitment_account = LightAccount::<CommitmentAccount>::new_init(
+                    &crate::ID,
+                    Some(address),
+                    output_tree_index,
+                );
+                commitment_account.pool = pool.to_bytes();
+                commitment_account.commitment = commitment;
+                commitment_account.leaf_index = leaf_index;
+                commitment_account.stealth_ephemeral_pubkey = stealth_ephemeral_pubkey;
+                commitment_account.encrypted_note = encrypted_note;
+                commitment_account.encrypted_note_len = encrypted_note_len;
+                commitment_account.created_at = created_at;
+                commitment_account.view_tag = view_tag;
+                commitment_account.encrypted_note_overflow = encrypted_note_overflow;
+
+                cpi = cpi.with_light_account(commitment_account)
+                    .map_err(light_error(CloakCraftError::LightCpiError))?;
+                address_seed
+            }
+        };
+
+        // Address i is assigned to output account i
+        new_address_params.push(
+            address_tree_info.into_new_address_params_assigned_packed(address_seed, Some(index as u8)),
+        );
+    }
+
+    // An existing address fails the whole batch; with a nullifier in it,
+    // that is almost always a double spend
+    let fallback = if creates_nullifier {
+        CloakCraftError::NullifierAlreadySpent
+    } else {
+        CloakCraftError::CommitmentCreationFailed
+    };
+    cpi.with_new_addresses(&new_address_params)
+        .invoke(light_cpi_accounts)
+        .map_err(light_error(fallback))?;
+
+    Ok(())
+}
+
+/// Create a payment receipt compressed account
+///
+/// The receipt goes to the pool's trees like the commitment it points at.
+/// The validity proof fails if the reference id already has a receipt.
+///
+/// Seeds: ["payment_receipt", reference_id]
+#[allow(clippy::too_many_arguments)]
+pub fn create_payment_receipt_account<'info>(
+    fee_payer: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    proof: LightValidityProof,
+    address_tree_info: LightAddressTreeInfo,
+    output_tree_index: u8,
+    pool: Pubkey,
+    pool_trees: PoolTrees,
+    reference_id: [u8; 32],
+    commitment: [u8; 32],
+    leaf_index: u64,
+) -> Result<()> {
+    let proof: ValidityProof = proof.into();
+    let address_tree_info: PackedAddressTreeInfo = address_tree_info.into();
+
+    let light_cpi_accounts = CpiAccounts::new(
+        fee_payer,
+        remaining_accounts,
+        LIGHT_CPI_SIGNER,
+    );
+
+    let trees = tree_pubkeys(&light_cpi_accounts, &address_tree_info, output_tree_index)?;
+    require_pool_trees(&trees, &pool_trees)?;
+
+    let (address, address_seed) = derive_address(
+        &[PaymentReceipt::SEED_PREFIX, reference_id.as_ref()],
+        &trees.address_tree,
+        &crate::ID,
+    );
+    let new_address_params = address_tree_info
+        .into_new_address_params_assigned_packed(address_seed, Some(output_tree_index));
+
+    let mut receipt = LightAccount::<PaymentReceipt>::new_init(
+        &crate::ID,
+        Some(address),
+        output_tree_index,
+    );
+    receipt.reference_id = reference_id;
+    receipt.pool = pool.to_bytes();
+    receipt.commitment = commitment;
+    receipt.leaf_index = leaf_index;
+    receipt.paid_at = Clock::get()?.unix_timestamp;
+
+    LightSystemProgramCpi::new_cpi(LIGHT_CPI_SIGNER, proof)
+        .with_light_account(receipt)
+        .map_err(light_error(CloakCraftError::LightCpiError))?
+        .with_new_addresses(&[new_address_params])
+        .invoke(light_cpi_accounts)
+        .map_err(light_error(CloakCraftError::PaymentReceiptCreationFailed))?;
+
+    Ok(())
+}
+
+/// Derive the compressed account address for a payment receipt
+pub fn derive_payment_receipt_address(
+    reference_id: &[u8; 32],
+    address_tree: &Pubkey,
+) -> [u8; 32] {
+    let (address, _) = derive_address(
+        &[PaymentReceipt::SEED_PREFIX, reference_id.as_ref()],
+        address_tree,
+        &crate::ID,
+    );
+    address
+}
+
+/// Light V2 hash of a commitment compressed account at `address`
+///
+/// `address` is the commitment's derived address (pool + commitment in the
+/// pool's address tree, see `derive_commitment_address`), so a hash taken from
+/// another pool's account, or another program's account, never matches.
+/// `data_hash` covers the remaining stored fields and comes from the indexer;
+/// it cannot change the address the hash commits to.
+pub fn commitment_account_hash_at(
+    address: &[u8; 32],
+    state_tree: &Pubkey,
+    leaf_index: u32,
+    data_hash: &[u8; 32],
+) -> Result<[u8; 32]> {
+    account_hash_at(&CommitmentAccount::LIGHT_DISCRIMINATOR, address, state_tree, leaf_index, data_hash)
+}
+
+/// Light V2 hash of one of this program's compressed accounts
+fn account_hash_at(
+    discriminator: &[u8; 8],
+    address: &[u8; 32],
+    state_tree: &Pubkey,
+    leaf_index: u32,
+    data_hash: &[u8; 32],
+) -> Result<[u8; 32]> {
+    use light_compressed_account::compressed_account::hash_with_hashed_values;
+    use light_hasher::hash_to_field_size::hash_to_bn254_field_size_be;
+
+    hash_with_hashed_values(
+        &0,
+        Some(address.as_slice()),
+        Some((discriminator.as_slice(), data_hash.as_slice())),
+        &hash_to_bn254_field_size_be(crate::ID.as_ref()),
+        &hash_to_bn254_field_size_be(state_tree.as_ref()),
+        &leaf_index,
+        true,
+    )
+    .map_err(light_error(CloakCraftError::LightCpiError))
+}
+
+/// Validity proof for a read-only inclusion check
+///
+/// - prove_by_index=true: account is in output queue, no ZK proof needed
+/// - prove_by_index=false: account is batched in state tree; Light checks
+///   the proof against the root at the context's `root_index`
+fn read_only_inclusion_proof(
+    prove_by_index: bool,
+    inclusion_proof: LightValidityProof,
+) -> Option<light_compressed_account::instruction_data::compressed_proof::CompressedProof> {
+    use light_compressed_account::instruction_data::compressed_proof::CompressedProof;
+
+    if prove_by_index {
+        None
+    } else {
+        Some(CompressedProof {
+            a: inclusion_proof.a,
+            b: inclusion_proof.b,
+            c: inclusion_proof.c,
+        })
+    }
+}
+
 /// Verify that a commitment exists in the Light Protocol state tree
 ///
 /// SECURITY CRITICAL: This prevents spending non-existent commitments.
@@ -352,6 +772,9 @@ pub fn derive_commitment_address(
 /// 4. Withdraw tokens never deposited
 ///
 /// This function closes that attack vector by requiring on-chain merkle proof verification.
+///
+/// The state tree is not restricted to the pool's active tree: notes written
+/// before a `migrate_pool_trees` cutover remain spendable from the old tree.
 pub fn verify_commitment_inclusion<'info>(
     fee_payer: &AccountInfo<'info>,
     remaining_accounts: &[AccountInfo<'info>],
@@ -361,6 +784,8 @@ pub fn verify_commitment_inclusion<'info>(
     address_tree_info: LightAddressTreeInfo,
     commitment: [u8; 32],
     pool: Pubkey,
+    address_tree: Pubkey,
+    commitment_data_hash: [u8; 32],
 ) -> Result<()> {
     msg!("=== Verify Commitment Inclusion (SECURITY CHECK) ===");
     msg!("Pool: {:?}", pool);
@@ -377,14 +802,28 @@ pub fn verify_commitment_inclusion<'info>(
         LIGHT_CPI_SIGNER,
     );
 
-    // Use the provided commitment_account_hash directly from the scanner
+    // SECURITY: The scanner-supplied hash must be this pool's commitment account
+    let state_tree = light_cpi_accounts
+        .get_tree_account_info(commitment_merkle_context.merkle_tree_pubkey_index as usize)
+        .map_err(light_error(CloakCraftError::LightCpiError))?
+        .key();
+    let expected_hash = commitment_account_hash_at(
+        &derive_commitment_address(&pool, &commitment, &address_tree),
+        &state_tree,
+        commitment_merkle_context.leaf_index,
+        &commitment_data_hash,
+    )?;
+    require!(
+        expected_hash == commitment_account_hash,
+        CloakCraftError::CommitmentAccountMismatch
+    );
+
     let commitment_address = commitment_account_hash;
 
     msg!("Using commitment address: {:02x?}...", &commitment_address[0..8]);
 
     // Build the packed read-only account for verification
     use light_compressed_account::compressed_account::{PackedReadOnlyCompressedAccount, PackedMerkleContext};
-    use light_compressed_account::instruction_data::compressed_proof::CompressedProof;
     use light_compressed_account::instruction_data::with_readonly::InstructionDataInvokeCpiWithReadOnly;
 
     let prove_by_index = commitment_merkle_context.prove_by_index;
@@ -402,23 +841,10 @@ pub fn verify_commitment_inclusion<'info>(
 
     msg!("Verifying commitment with Light Protocol CPI (prove_by_index={})...", prove_by_index);
 
-    // Build the proof parameter:
-    // - prove_by_index=true: account is in output queue, no ZK proof needed
-    // - prove_by_index=false: account is batched in state tree, need validity proof
-    let cpi_proof = if prove_by_index {
-        None
-    } else {
-        Some(CompressedProof {
-            a: inclusion_proof.a,
-            b: inclusion_proof.b,
-            c: inclusion_proof.c,
-        })
-    };
-
     let cpi_instruction = InstructionDataInvokeCpiWithReadOnly {
         bump: LIGHT_CPI_SIGNER.bump,
         invoking_program_id: LIGHT_CPI_SIGNER.program_id.into(),
-        proof: cpi_proof,
+        proof: read_only_inclusion_proof(prove_by_index, inclusion_proof),
         mode: 1, // v2 mode
         read_only_accounts: vec![read_only_account],
         ..Default::default()
@@ -515,7 +941,7 @@ pub fn create_vote_commitment_account<'info>(
 
     // Get address tree pubkey
     let address_tree_pubkey = address_tree_info.get_tree_pubkey(&light_cpi_accounts)
-        .map_err(|_| CloakCraftError::LightCpiError)?;
+        .map_err(light_error(CloakCraftError::LightCpiError))?;
 
     // Derive address from ballot_id + commitment hash
     // Seeds: ["vote_commitment", ballot_id, commitment]
@@ -552,10 +978,10 @@ pub fn create_vote_commitment_account<'info>(
     // Invoke Light System Program to create the compressed account
     LightSystemProgramCpi::new_cpi(LIGHT_CPI_SIGNER, proof)
         .with_light_account(commitment_account)
-        .map_err(|_| CloakCraftError::LightCpiError)?
+        .map_err(light_error(CloakCraftError::LightCpiError))?
         .with_new_addresses(&[new_address_params])
         .invoke(light_cpi_accounts)
-        .map_err(|_| CloakCraftError::CommitmentCreationFailed)?;
+        .map_err(light_error(CloakCraftError::CommitmentCreationFailed))?;
 
     Ok(())
 }
@@ -578,19 +1004,36 @@ pub fn derive_vote_commitment_address(
     address
 }
 
+/// Light V2 hash of a vote commitment compressed account at `address`
+///
+/// `address` is the vote commitment's derived address (ballot_id + commitment,
+/// see `derive_vote_commitment_address`), so a hash taken from another
+/// ballot's account never matches.
+pub fn vote_commitment_account_hash_at(
+    address: &[u8; 32],
+    state_tree: &Pubkey,
+    leaf_index: u32,
+    data_hash: &[u8; 32],
+) -> Result<[u8; 32]> {
+    account_hash_at(&VoteCommitmentAccount::LIGHT_DISCRIMINATOR, address, state_tree, leaf_index, data_hash)
+}
+
 /// Verify a vote commitment exists in Light Protocol state tree
 ///
 /// This is the voting-specific version of verify_commitment_inclusion.
 /// Uses ballot_key as context instead of pool.
+#[allow(clippy::too_many_arguments)]
 pub fn verify_vote_commitment_inclusion<'info>(
     fee_payer: &AccountInfo<'info>,
     remaining_accounts: &[AccountInfo<'info>],
     commitment_account_hash: [u8; 32],
     commitment_merkle_context: crate::instructions::voting::VoteCommitmentMerkleContext,
     inclusion_proof: LightValidityProof,
-    _address_tree_info: LightAddressTreeInfo,
+    address_tree_info: LightAddressTreeInfo,
     commitment: [u8; 32],
     ballot: Pubkey,
+    ballot_id: [u8; 32],
+    commitment_data_hash: [u8; 32],
 ) -> Result<()> {
     msg!("=== Verify Vote Commitment Inclusion ===");
     msg!("Ballot: {:?}", ballot);
@@ -600,9 +1043,6 @@ pub fn verify_vote_commitment_inclusion<'info>(
     msg!("Root index: {}", commitment_merkle_context.root_index);
     msg!("State tree index: {}", commitment_merkle_context.merkle_tree_pubkey_index);
 
-    // Convert IDL-safe types to Light SDK types
-    let _proof: ValidityProof = inclusion_proof.into();
-
     // Setup Light CPI accounts
     let light_cpi_accounts = CpiAccounts::new(
         fee_payer,
@@ -610,7 +1050,25 @@ pub fn verify_vote_commitment_inclusion<'info>(
         LIGHT_CPI_SIGNER,
     );
 
-    // Use the provided commitment_account_hash directly from the scanner
+    // SECURITY: The scanner-supplied hash must be this ballot's vote commitment account
+    let address_tree_info: PackedAddressTreeInfo = address_tree_info.into();
+    let address_tree = address_tree_info.get_tree_pubkey(&light_cpi_accounts)
+        .map_err(light_error(CloakCraftError::LightCpiError))?;
+    let state_tree = light_cpi_accounts
+        .get_tree_account_info(commitment_merkle_context.merkle_tree_pubkey_index as usize)
+        .map_err(light_error(CloakCraftError::LightCpiError))?
+        .key();
+    let expected_hash = vote_commitment_account_hash_at(
+        &derive_vote_commitment_address(&ballot_id, &commitment, &address_tree),
+        &state_tree,
+        commitment_merkle_context.leaf_index,
+        &commitment_data_hash,
+    )?;
+    require!(
+        expected_hash == commitment_account_hash,
+        CloakCraftError::CommitmentAccountMismatch
+    );
+
     let commitment_address = commitment_account_hash;
 
     msg!("Using commitment address: {:02x?}...", &commitment_address[0..8]);
@@ -618,18 +1076,20 @@ pub fn verify_vote_commitment_inclusion<'info>(
     // Build the packed read-only account for verification
     use light_compressed_account::compressed_account::{PackedReadOnlyCompressedAccount, PackedMerkleContext};
 
+    let prove_by_index = commitment_merkle_context.prove_by_index;
+
     let read_only_account = PackedReadOnlyCompressedAccount {
         account_hash: commitment_address,
         merkle_context: PackedMerkleContext {
             merkle_tree_pubkey_index: commitment_merkle_context.merkle_tree_pubkey_index,
             queue_pubkey_index: commitment_merkle_context.queue_pubkey_index,
             leaf_index: commitment_merkle_context.leaf_index,
-            prove_by_index: true,
+            prove_by_index,
         },
         root_index: commitment_merkle_context.root_index,
     };
 
-    msg!("Verifying vote commitment with Light Protocol CPI...");
+    msg!("Verifying vote commitment with Light Protocol CPI (prove_by_index={})...", prove_by_index);
 
     // Build CPI instruction for read-only verification
     use light_compressed_account::instruction_data::with_account_info::InstructionDataInvokeCpiWithAccountInfo;
@@ -637,7 +1097,7 @@ pub fn verify_vote_commitment_inclusion<'info>(
     let cpi_instruction = InstructionDataInvokeCpiWithAccountInfo {
         bump: LIGHT_CPI_SIGNER.bump,
         invoking_program_id: LIGHT_CPI_SIGNER.program_id.into(),
-        proof: None, // No ZK proof needed - merkle context in read_only_account is the verification
+        proof: read_only_inclusion_proof(prove_by_index, inclusion_proof),
         mode: 1, // v2 mode
         read_only_accounts: vec![read_only_account],
         ..Default::default()
@@ -693,6 +1153,8 @@ pub fn create_position_meta_account<'info>(
     entry_price: u64,
     nullifier_hash: [u8; 32],
     owner_stealth_pubkey: [u8; 32],
+    entry_borrow_fee: u128,
+    position_commitment: [u8; 32],
 ) -> Result<()> {
     // Convert IDL-safe types to Light SDK types
     let proof: ValidityProof = proof.into();
@@ -707,7 +1169,7 @@ pub fn create_position_meta_account<'info>(
 
     // Get address tree pubkey
     let address_tree_pubkey = address_tree_info.get_tree_pubkey(&light_cpi_accounts)
-        .map_err(|_| CloakCraftError::LightCpiError)?;
+        .map_err(light_error(CloakCraftError::LightCpiError))?;
 
     // Derive address from pool_id + position_id
     // Seeds: ["position_meta", pool_id, position_id]
@@ -747,6 +1209,8 @@ pub fn create_position_meta_account<'info>(
     position_meta.created_at = clock.unix_timestamp;
     position_meta.updated_at = clock.unix_timestamp;
     position_meta.owner_stealth_pubkey = owner_stealth_pubkey;
+    position_meta.entry_borrow_fee = entry_borrow_fee;
+    position_meta.position_commitment = position_commitment;
 
     msg!("Creating PositionMeta compressed account");
     msg!("  Position ID: {:02x?}...", &position_id[0..8]);
@@ -759,10 +1223,10 @@ pub fn create_position_meta_account<'info>(
     // Invoke Light System Program to create the compressed account
     LightSystemProgramCpi::new_cpi(LIGHT_CPI_SIGNER, proof)
         .with_light_account(position_meta)
-        .map_err(|_| CloakCraftError::LightCpiError)?
+        .map_err(light_error(CloakCraftError::LightCpiError))?
         .with_new_addresses(&[new_address_params])
         .invoke(light_cpi_accounts)
-        .map_err(|_| CloakCraftError::PositionMetaCreationFailed)?;
+        .map_err(light_error(CloakCraftError::PositionMetaCreationFailed))?;
 
     msg!("✅ PositionMeta created successfully");
 
@@ -794,24 +1258,56 @@ pub struct PositionMetaMerkleContext {
     pub queue_pubkey_index: u8,
     pub leaf_index: u32,
     pub root_index: u16,
+    pub prove_by_index: bool,
 }
 
-/// Read PositionMeta from compressed account for liquidation check
+/// Light account hash of a PositionMeta stored at `address`
 ///
-/// This verifies the PositionMeta exists and returns its data.
-/// Used by liquidation instructions to check if position is underwater.
+/// PositionMeta is a SHA256 flat-hashed Light account: the data hash is
+/// sha256(borsh(meta)) with the first byte zeroed. The owner is this program,
+/// so the hash only matches metadata this program wrote, with exactly these
+/// field values.
+pub fn position_meta_account_hash(
+    position_meta: &PositionMeta,
+    address: &[u8; 32],
+    state_tree: &Pubkey,
+    leaf_index: u32,
+) -> Result<[u8; 32]> {
+    use light_compressed_account::compressed_account::hash_with_hashed_values;
+    use light_hasher::{hash_to_field_size::hash_to_bn254_field_size_be, Hasher, Sha256};
+
+    let data = position_meta.try_to_vec()?;
+    let mut data_hash = Sha256::hash(&data).map_err(light_error(CloakCraftError::LightCpiError))?;
+    data_hash[0] = 0;
+
+    hash_with_hashed_values(
+        &0,
+        Some(address.as_slice()),
+        Some((PositionMeta::LIGHT_DISCRIMINATOR.as_slice(), data_hash.as_slice())),
+        &hash_to_bn254_field_size_be(crate::ID.as_ref()),
+        &hash_to_bn254_field_size_be(state_tree.as_ref()),
+        &leaf_index,
+        true,
+    )
+    .map_err(light_error(CloakCraftError::LightCpiError))
+}
+
+/// Verify a PositionMeta exists with exactly the given field values
+///
+/// The account hash is recomputed from `position_meta` (address derived from
+/// its pool_id and position_id in the given address tree), so callers can
+/// trust every field, not just the ones the indexer reported.
 pub fn verify_position_meta_inclusion<'info>(
     fee_payer: &AccountInfo<'info>,
     remaining_accounts: &[AccountInfo<'info>],
-    position_meta_hash: [u8; 32],
+    position_meta: &PositionMeta,
     merkle_context: PositionMetaMerkleContext,
-    pool_id: [u8; 32],
-    position_id: [u8; 32],
+    inclusion_proof: LightValidityProof,
+    address_tree_info: LightAddressTreeInfo,
 ) -> Result<()> {
     msg!("=== Verify PositionMeta Inclusion ===");
-    msg!("Pool ID: {:02x?}...", &pool_id[0..8]);
-    msg!("Position ID: {:02x?}...", &position_id[0..8]);
-    msg!("Account hash: {:02x?}...", &position_meta_hash[0..8]);
+    msg!("Pool ID: {:02x?}...", &position_meta.pool_id[0..8]);
+    msg!("Position ID: {:02x?}...", &position_meta.position_id[0..8]);
 
     // Setup Light CPI accounts
     let light_cpi_accounts = CpiAccounts::new(
@@ -820,6 +1316,21 @@ pub fn verify_position_meta_inclusion<'info>(
         LIGHT_CPI_SIGNER,
     );
 
+    let address_tree_info: PackedAddressTreeInfo = address_tree_info.into();
+    let address_tree = address_tree_info.get_tree_pubkey(&light_cpi_accounts)
+        .map_err(light_error(CloakCraftError::LightCpiError))?;
+    let state_tree = light_cpi_accounts
+        .get_tree_account_info(merkle_context.merkle_tree_pubkey_index as usize)
+        .map_err(light_error(CloakCraftError::LightCpiError))?
+        .key();
+    let position_meta_hash = position_meta_account_hash(
+        position_meta,
+        &derive_position_meta_address(&position_meta.pool_id, &position_meta.position_id, &address_tree),
+        &state_tree,
+        merkle_context.leaf_index,
+    )?;
+    msg!("Account hash: {:02x?}...", &position_meta_hash[0..8]);
+
     // Build the packed read-only account for verification
     use light_compressed_account::compressed_account::{PackedReadOnlyCompressedAccount, PackedMerkleContext};
 
@@ -829,7 +1340,7 @@ pub fn verify_position_meta_inclusion<'info>(
             merkle_tree_pubkey_index: merkle_context.merkle_tree_pubkey_index,
             queue_pubkey_index: merkle_context.queue_pubkey_index,
             leaf_index: merkle_context.leaf_index,
-            prove_by_index: true,
+            prove_by_index: merkle_context.prove_by_index,
         },
         root_index: merkle_context.root_index,
     };
@@ -840,7 +1351,7 @@ pub fn verify_position_meta_inclusion<'info>(
     let cpi_instruction = InstructionDataInvokeCpiWithAccountInfo {
         bump: LIGHT_CPI_SIGNER.bump,
         invoking_program_id: LIGHT_CPI_SIGNER.program_id.into(),
-        proof: None,
+        proof: read_only_inclusion_proof(merkle_context.prove_by_index, inclusion_proof),
         mode: 1,
         read_only_accounts: vec![read_only_account],
         ..Default::default()
@@ -927,7 +1438,7 @@ pub fn create_position_status_record<'info>(
 
     // Get address tree pubkey
     let address_tree_pubkey = address_tree_info.get_tree_pubkey(&light_cpi_accounts)
-        .map_err(|_| CloakCraftError::LightCpiError)?;
+        .map_err(light_error(CloakCraftError::LightCpiError))?;
 
     // Derive address
     let (address, address_seed) = derive_address(
@@ -961,64 +1472,37 @@ pub fn create_position_status_record<'info>(
     // Create the status record
     LightSystemProgramCpi::new_cpi(LIGHT_CPI_SIGNER, proof)
         .with_light_account(status_record)
-        .map_err(|_| CloakCraftError::LightCpiError)?
+        .map_err(light_error(CloakCraftError::LightCpiError))?
         .with_new_addresses(&[new_address_params])
         .invoke(light_cpi_accounts)
-        .map_err(|_| CloakCraftError::PositionMetaUpdateFailed)?;
+        .map_err(light_error(CloakCraftError::PositionMetaUpdateFailed))?;
 
     msg!("✅ Position status record created: {:?}", status);
 
     Ok(())
 }
 
-/// Verify that a position status record does NOT exist (position is still active)
+/// Verify that a position has NOT been liquidated (position is still active)
 ///
 /// This is used in the close position flow to ensure the position
-/// hasn't been liquidated. If the record exists, the position cannot be closed.
-pub fn verify_position_not_liquidated<'info>(
-    fee_payer: &AccountInfo<'info>,
-    remaining_accounts: &[AccountInfo<'info>],
-    pool_id: [u8; 32],
+/// hasn't been liquidated. Liquidated position ids are keys of a sparse
+/// merkle tree (`merkle::smt`); the caller supplies the root it trusts for
+/// the pool and an exclusion proof for the position id.
+pub fn verify_position_not_liquidated(
+    liquidated_root: &[u8; 32],
     position_id: [u8; 32],
-    address_tree_info: LightAddressTreeInfo,
+    proof: &SmtProof,
 ) -> Result<()> {
     msg!("=== Verify Position Not Liquidated ===");
     msg!("Position ID: {:02x?}...", &position_id[0..8]);
 
-    // Convert IDL-safe types
-    let address_tree_info: PackedAddressTreeInfo = address_tree_info.into();
-
-    // Setup Light CPI accounts
-    let light_cpi_accounts = CpiAccounts::new(
-        fee_payer,
-        remaining_accounts,
-        LIGHT_CPI_SIGNER,
+    require!(
+        proof.occupant.map(|leaf| leaf.key) != Some(position_id),
+        CloakCraftError::PositionAlreadyLiquidated
     );
+    smt::verify_non_membership(liquidated_root, &position_id, proof)?;
 
-    // Get address tree pubkey
-    let address_tree_pubkey = address_tree_info.get_tree_pubkey(&light_cpi_accounts)
-        .map_err(|_| CloakCraftError::LightCpiError)?;
-
-    // Derive the expected address for status record
-    let (expected_address, _) = derive_address(
-        &[
-            PositionStatusRecord::SEED_PREFIX,
-            pool_id.as_ref(),
-            position_id.as_ref(),
-        ],
-        &address_tree_pubkey,
-        &crate::ID,
-    );
-
-    // TODO: Verify non-inclusion via Light Protocol
-    // For now, we log the expected address - the actual verification
-    // would require a non-inclusion proof from the indexer
-    msg!("Expected status record address: {:02x?}...", &expected_address[0..8]);
-    msg!("(Non-inclusion verification delegated to client-side proof)");
-
-    // The actual non-inclusion proof is provided by the client
-    // and verified implicitly by Light Protocol when creating the close nullifier
-    // If a status record exists at this address, Light will reject the operation
+    msg!("✅ Position absent from liquidation tree");
 
     Ok(())
 }
@@ -1061,7 +1545,7 @@ pub fn create_liquidation_nullifier<'info>(
 
     // Get address tree pubkey
     let address_tree_pubkey = address_tree_info.get_tree_pubkey(&light_cpi_accounts)
-        .map_err(|_| CloakCraftError::LightCpiError)?;
+        .map_err(light_error(CloakCraftError::LightCpiError))?;
 
     // Derive address using nullifier_hash
     // Note: We use the same SpendNullifierAccount but with nullifier_hash instead of nullifier
@@ -1095,12 +1579,53 @@ pub fn create_liquidation_nullifier<'info>(
     // Create the nullifier
     LightSystemProgramCpi::new_cpi(LIGHT_CPI_SIGNER, proof)
         .with_light_account(nullifier_account)
-        .map_err(|_| CloakCraftError::LightCpiError)?
+        .map_err(light_error(CloakCraftError::LightCpiError))?
         .with_new_addresses(&[new_address_params])
         .invoke(light_cpi_accounts)
-        .map_err(|_| CloakCraftError::LiquidationNullifierFailed)?;
+        .map_err(light_error(CloakCraftError::LiquidationNullifierFailed))?;
 
     msg!("✅ Liquidation nullifier created");
 
     Ok(())
 }
+
+/// Output tree index for admin action records
+///
+/// Admin instructions take no Light params: the output queue must be the
+/// first tree account after the Light system accounts.
+pub const ADMIN_AUDIT_OUTPUT_TREE_INDEX: u8 = 0;
+
+/// Create an admin action record compressed account
+///
+/// Records have no address, so no validity proof is needed.
+pub fn create_admin_action_record<'info>(
+    fee_payer: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    record: AdminActionRecord,
+) -> Result<()> {
+    let light_cpi_accounts = CpiAccounts::new(
+        fee_payer,
+        remaining_accounts,
+        LIGHT_CPI_SIGNER,
+    );
+
+    let mut account = LightAccount::<AdminActionRecord>::new_init(
+        &crate::ID,
+        None,
+        ADMIN_AUDIT_OUTPUT_TREE_INDEX,
+    );
+    account.actor = record.actor;
+    account.action = record.action;
+    account.target = record.target;
+    account.old_value_hash = record.old_value_hash;
+    account.new_value_hash = record.new_value_hash;
+    account.timestamp = record.timestamp;
+
+    LightSystemProgramCpi::new_cpi(LIGHT_CPI_SIGNER, ValidityProof::new(None))
+        .with_light_account(account)
+        .map_err(light_error(CloakCraftError::LightCpiError))?
+        .invoke(light_cpi_accounts)
+        .map_err(light_error(CloakCraftError::AdminAuditFailed))?;
+
+    Ok(())
+}
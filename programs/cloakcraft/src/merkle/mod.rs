@@ -7,6 +7,8 @@ use light_hasher::{Hasher, Poseidon};
 
 use crate::constants::MERKLE_TREE_DEPTH;
 
+pub mod smt;
+
 /// Empty leaf hash (Poseidon hash of 32 zero bytes)
 /// Pre-computed for efficiency - this is Poseidon([0u8; 32])
 /// Computed from Light Protocol's light-hasher Poseidon implementation
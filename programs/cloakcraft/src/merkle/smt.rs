@@ -0,0 +1,257 @@
+//! Sparse merkle tree
+//!
+//! Poseidon sparse merkle tree keyed by field elements, compatible with
+//! circomlib's `SMTVerifier` / `SMTProcessor`:
+//! - an empty subtree is 0
+//! - a leaf is `Poseidon(key, value, 1)`
+//! - an inner node is `Poseidon(left, right)`
+//! - the path of a key is its bits, least significant first
+//!
+//! Leaves sit at the shallowest depth where their path is unique, so a proof
+//! carries one sibling per level down to the key's slot (root first). The
+//! slot is either empty or holds the one leaf sharing the key's path prefix.
+//! That makes non-membership provable on-chain: show the slot is empty, or
+//! that it holds a different key. Used for deny-lists, liquidation status
+//! and eligibility exclusions, where the tree holds what is *not* allowed.
+//!
+//! Roots are maintained off-chain (or via `insert` with an exclusion proof);
+//! the program only stores a root and checks paths against it.
+
+use anchor_lang::prelude::*;
+use light_hasher::{Hasher, Poseidon};
+
+use crate::errors::CloakCraftError;
+use crate::helpers::field::is_canonical;
+
+/// Deepest slot a proof may reach (bounds the Poseidon hashes per check)
+pub const SMT_MAX_DEPTH: usize = 64;
+
+/// Empty subtree
+pub const SMT_EMPTY: [u8; 32] = [0u8; 32];
+
+/// Leaf occupying a key's slot
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SmtLeaf {
+    pub key: [u8; 32],
+    pub value: [u8; 32],
+}
+
+/// Path from the root to the slot of a key
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SmtProof {
+    /// Siblings from the root down to the slot
+    pub siblings: Vec<[u8; 32]>,
+    /// Leaf in the slot (None = empty)
+    pub occupant: Option<SmtLeaf>,
+}
+
+/// Bit `index` of a big-endian field element
+fn key_bit(key: &[u8; 32], index: usize) -> bool {
+    (key[31 - index / 8] >> (index % 8)) & 1 == 1
+}
+
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    Poseidon::hashv(&[left, right]).unwrap()
+}
+
+/// Leaf hash `Poseidon(key, value, 1)`
+pub fn hash_leaf(key: &[u8; 32], value: &[u8; 32]) -> [u8; 32] {
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    Poseidon::hashv(&[key, value, &one]).unwrap()
+}
+
+/// Hash `node` at depth `siblings.len()` on `key`'s path up to the root
+fn fold_path(key: &[u8; 32], node: [u8; 32], siblings: &[[u8; 32]]) -> [u8; 32] {
+    siblings
+        .iter()
+        .enumerate()
+        .rev()
+        .fold(node, |node, (level, sibling)| {
+            if key_bit(key, level) {
+                hash_node(sibling, &node)
+            } else {
+                hash_node(&node, sibling)
+            }
+        })
+}
+
+/// Check the proof's shape and return the hash of the slot it opens
+fn slot_hash(key: &[u8; 32], proof: &SmtProof) -> Result<[u8; 32]> {
+    require!(
+        proof.siblings.len() <= SMT_MAX_DEPTH,
+        CloakCraftError::SmtDepthExceeded
+    );
+    require!(is_canonical(key), CloakCraftError::NonCanonicalFieldElement);
+
+    match &proof.occupant {
+        None => Ok(SMT_EMPTY),
+        Some(leaf) => {
+            // The occupant must live on the key's path
+            let depth = proof.siblings.len();
+            require!(
+                (0..depth).all(|i| key_bit(key, i) == key_bit(&leaf.key, i)),
+                CloakCraftError::InvalidSmtProof
+            );
+            Ok(hash_leaf(&leaf.key, &leaf.value))
+        }
+    }
+}
+
+/// Root implied by a proof for `key`
+pub fn compute_root(key: &[u8; 32], proof: &SmtProof) -> Result<[u8; 32]> {
+    let slot = slot_hash(key, proof)?;
+    Ok(fold_path(key, slot, &proof.siblings))
+}
+
+/// Verify `key` is absent from the tree with `root`
+pub fn verify_non_membership(root: &[u8; 32], key: &[u8; 32], proof: &SmtProof) -> Result<()> {
+    if let Some(leaf) = &proof.occupant {
+        require!(leaf.key != *key, CloakCraftError::SmtKeyPresent);
+    }
+    require!(
+        compute_root(key, proof)? == *root,
+        CloakCraftError::InvalidSmtProof
+    );
+    Ok(())
+}
+
+/// Verify `key` maps to `value` in the tree with `root`
+pub fn verify_membership(
+    root: &[u8; 32],
+    key: &[u8; 32],
+    value: &[u8; 32],
+    proof: &SmtProof,
+) -> Result<()> {
+    require!(
+        proof.occupant == Some(SmtLeaf { key: *key, value: *value }),
+        CloakCraftError::InvalidSmtProof
+    );
+    require!(
+        compute_root(key, proof)? == *root,
+        CloakCraftError::InvalidSmtProof
+    );
+    Ok(())
+}
+
+/// Insert `key` using its exclusion proof against `root`, returning the new root
+///
+/// If the slot holds another leaf, both are pushed down to the first level
+/// where their paths split.
+pub fn insert(
+    root: &[u8; 32],
+    key: &[u8; 32],
+    value: &[u8; 32],
+    proof: &SmtProof,
+) -> Result<[u8; 32]> {
+    verify_non_membership(root, key, proof)?;
+
+    let depth = proof.siblings.len();
+    let new_leaf = hash_leaf(key, value);
+
+    let node = match &proof.occupant {
+        None => new_leaf,
+        Some(old) => {
+            let split = (depth..SMT_MAX_DEPTH)
+                .find(|&i| key_bit(key, i) != key_bit(&old.key, i))
+                .ok_or(CloakCraftError::SmtDepthExceeded)?;
+            let old_leaf = hash_leaf(&old.key, &old.value);
+            let mut node = if key_bit(key, split) {
+                hash_node(&old_leaf, &new_leaf)
+            } else {
+                hash_node(&new_leaf, &old_leaf)
+            };
+            for level in (depth..split).rev() {
+                node = if key_bit(key, level) {
+                    hash_node(&SMT_EMPTY, &node)
+                } else {
+                    hash_node(&node, &SMT_EMPTY)
+                };
+            }
+            node
+        }
+    };
+
+    Ok(fold_path(key, node, &proof.siblings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(n: u8) -> [u8; 32] {
+        let mut k = [0u8; 32];
+        k[31] = n;
+        k
+    }
+
+    fn value() -> [u8; 32] {
+        key(1)
+    }
+
+    #[test]
+    fn test_smt_insert_and_prove() {
+        // Empty tree: root 0, every slot empty at depth 0
+        let empty = SmtProof { siblings: vec![], occupant: None };
+        assert!(verify_non_membership(&SMT_EMPTY, &key(0b0101), &empty).is_ok());
+
+        // First key becomes the root leaf
+        let root1 = insert(&SMT_EMPTY, &key(0b0101), &value(), &empty).unwrap();
+        assert_eq!(root1, hash_leaf(&key(0b0101), &value()));
+        let at_root = SmtProof {
+            siblings: vec![],
+            occupant: Some(SmtLeaf { key: key(0b0101), value: value() }),
+        };
+        assert!(verify_membership(&root1, &key(0b0101), &value(), &at_root).is_ok());
+        assert_eq!(
+            verify_non_membership(&root1, &key(0b0101), &at_root).unwrap_err(),
+            CloakCraftError::SmtKeyPresent.into()
+        );
+
+        // 0b0001 shares bits 0-1 with 0b0101 and splits at bit 2
+        let root2 = insert(&root1, &key(0b0001), &value(), &at_root).unwrap();
+        let leaf_a = hash_leaf(&key(0b0101), &value());
+        let leaf_b = hash_leaf(&key(0b0001), &value());
+        let split = hash_node(&leaf_b, &leaf_a);
+        let expected = hash_node(&SMT_EMPTY, &hash_node(&split, &SMT_EMPTY));
+        assert_eq!(root2, expected);
+
+        let proof_b = SmtProof {
+            siblings: vec![SMT_EMPTY, SMT_EMPTY, leaf_a],
+            occupant: Some(SmtLeaf { key: key(0b0001), value: value() }),
+        };
+        assert!(verify_membership(&root2, &key(0b0001), &value(), &proof_b).is_ok());
+
+        // 0b0011 lands in the empty slot at depth 2; 0b1101 is shadowed by 0b0101
+        let empty_slot = SmtProof { siblings: vec![SMT_EMPTY, split], occupant: None };
+        assert!(verify_non_membership(&root2, &key(0b0011), &empty_slot).is_ok());
+        let other_leaf = SmtProof {
+            siblings: vec![SMT_EMPTY, SMT_EMPTY, leaf_b],
+            occupant: Some(SmtLeaf { key: key(0b0101), value: value() }),
+        };
+        assert!(verify_non_membership(&root2, &key(0b1101), &other_leaf).is_ok());
+
+        // Claiming an occupied slot is empty fails
+        let lie = SmtProof { siblings: vec![SMT_EMPTY, SMT_EMPTY, leaf_b], occupant: None };
+        assert_eq!(
+            verify_non_membership(&root2, &key(0b0101), &lie).unwrap_err(),
+            CloakCraftError::InvalidSmtProof.into()
+        );
+
+        // Occupant off the key's path is rejected
+        let off_path = SmtProof {
+            siblings: vec![SMT_EMPTY, SMT_EMPTY, leaf_b],
+            occupant: Some(SmtLeaf { key: key(0b0100), value: value() }),
+        };
+        assert_eq!(
+            verify_non_membership(&root2, &key(0b0101), &off_path).unwrap_err(),
+            CloakCraftError::InvalidSmtProof.into()
+        );
+
+        let too_deep = SmtProof { siblings: vec![SMT_EMPTY; SMT_MAX_DEPTH + 1], occupant: None };
+        assert_eq!(
+            verify_non_membership(&root2, &key(0), &too_deep).unwrap_err(),
+            CloakCraftError::SmtDepthExceeded.into()
+        );
+    }
+}
@@ -0,0 +1,100 @@
+//! Admin audit log using Light Protocol compressed accounts
+//!
+//! Every admin instruction writes one `AdminActionRecord`: who acted, what
+//! they did, which account they changed, and hashes of that account's state
+//! before and after. Records have no address (nothing ever reads them
+//! on-chain); indexers query them by owner program and discriminator.
+
+use anchor_lang::prelude::*;
+use light_sdk::LightDiscriminator;
+
+/// Admin instruction that produced a record
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AdminAction {
+    InitializeProtocolConfig = 0,
+    UpdateProtocolFees = 1,
+    UpdateTreasury = 2,
+    UpdateProtocolAuthority = 3,
+    SetPendingExpiry = 4,
+    SetRentRefundBps = 5,
+    SetSurplusPolicy = 6,
+    BumpOperationEpoch = 7,
+    SetAmmCreationPolicy = 8,
+    InitializePoolCreatorAllowlist = 9,
+    SetPoolCreator = 10,
+    InitializeProgramVersion = 11,
+    SetProgramVersion = 12,
+    RegisterVerificationKey = 13,
+    SetVerificationKeyData = 14,
+    AppendVerificationKeyData = 15,
+    RegisterAdaptModule = 16,
+    DisableAdaptModule = 17,
+    RegisterThresholdCommittee = 18,
+    ResetAmmPool = 19,
+    SetAmmLpLock = 20,
+    FinalizeVerificationKey = 21,
+    InitializeDustSweepLedger = 22,
+    SetDustSweepBounty = 23,
+    MigrateAmmPool = 24,
+    ReconcileCommitmentCounter = 25,
+    SetFeeOverride = 26,
+    SetFeeBounds = 27,
+    SetTreasuryConversion = 28,
+    InitializeBuyback = 29,
+    SetBuybackPolicy = 30,
+    InitializeCreditConfig = 31,
+    SetCreditPolicy = 32,
+    InitializeTreeRegistry = 33,
+    RegisterStateTree = 34,
+    RetireStateTree = 35,
+    MigrateProtocolConfig = 36,
+}
+
+/// Admin action compressed account data
+#[derive(Clone, Debug, Default, LightDiscriminator, AnchorSerialize, AnchorDeserialize)]
+pub struct AdminActionRecord {
+    /// Signing authority (32 bytes)
+    pub actor: [u8; 32],
+
+    /// `AdminAction` discriminant (1 byte)
+    pub action: u8,
+
+    /// Account the action changed (32 bytes)
+    pub target: [u8; 32],
+
+    /// keccak of the target's state before the action (zero for creations)
+    pub old_value_hash: [u8; 32],
+
+    /// keccak of the target's state after the action
+    pub new_value_hash: [u8; 32],
+
+    /// Timestamp of the action (8 bytes)
+    pub timestamp: i64,
+}
+
+impl AdminActionRecord {
+    /// Hash of an account's serialized state
+    pub fn value_hash<T: AnchorSerialize>(value: &T) -> [u8; 32] {
+        let mut data = Vec::new();
+        // Serializing into a Vec cannot fail
+        value.serialize(&mut data).unwrap_or_default();
+        solana_keccak_hasher::hash(&data).to_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_hash() {
+        let before = (10u16, false);
+        let after = (25u16, false);
+        assert_eq!(AdminActionRecord::value_hash(&before), AdminActionRecord::value_hash(&(10u16, false)));
+        assert_ne!(AdminActionRecord::value_hash(&before), AdminActionRecord::value_hash(&after));
+        assert_eq!(AdminAction::SetAmmLpLock as u8, 20);
+        assert_eq!(AdminAction::FinalizeVerificationKey as u8, 21);
+        assert_eq!(AdminAction::SetDustSweepBounty as u8, 23);
+    }
+}
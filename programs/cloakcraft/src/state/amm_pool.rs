@@ -6,6 +6,10 @@
 
 use anchor_lang::prelude::*;
 
+use crate::errors::CloakCraftError;
+use crate::helpers::fixed::{apply_bps, apply_bps_ceil, mul_div, to_u64, BPS_SCALE};
+use crate::helpers::wide::U256;
+
 /// Pool type determining which AMM formula to use
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default, InitSpace)]
 pub enum PoolType {
@@ -18,6 +22,12 @@ pub enum PoolType {
     StableSwap,
 }
 
+/// Maximum JIT penalty (10%)
+pub const MAX_JIT_PENALTY_BPS: u16 = 1_000;
+
+/// Maximum amount the Phase 3 recompute may exceed the Phase 0 swap output (0.5%)
+pub const MAX_SWAP_OUTPUT_DRIFT_BPS: u16 = 50;
+
 /// AMM pool for a token pair
 #[account]
 #[derive(Default, InitSpace)]
@@ -70,6 +80,15 @@ pub struct AmmPool {
     /// Typical values: 100-1000 for stablecoins
     /// Stored as actual value (not scaled)
     pub amplification: u64,
+
+    /// Slots newly minted LP commitments stay locked (0 = no lock)
+    pub min_lp_lock_slots: u64,
+
+    /// Penalty on removals inside the lock window, left in reserves (0 = removal rejected)
+    pub jit_penalty_bps: u16,
+
+    /// Pool version (0 for the original pool, +1 per migration to a successor)
+    pub version: u8,
 }
 
 impl AmmPool {
@@ -89,7 +108,21 @@ impl AmmPool {
         + 1   // bump
         + 1   // lp_mint_bump
         + 1   // pool_type (enum = 1 byte)
-        + 8;  // amplification
+        + 8   // amplification
+        + 8   // min_lp_lock_slots
+        + 2   // jit_penalty_bps
+        + 1;  // version
+
+    /// Version seed appended to the pool and LP mint PDA seeds
+    ///
+    /// Empty for the original pool, so its addresses are unchanged.
+    pub fn version_seed(&self) -> &[u8] {
+        if self.version == 0 {
+            &[]
+        } else {
+            std::slice::from_ref(&self.version)
+        }
+    }
 
     /// Returns tokens in canonical order (sorted by bytes).
     /// This ensures USDC-SOL and SOL-USDC always derive the same pool PDA.
@@ -101,6 +134,103 @@ impl AmmPool {
         }
     }
 
+    /// Whether a recomputed swap output stays within the drift tolerance
+    ///
+    /// `expected` is the Phase 0 output (the amount in the output note, and
+    /// what reserves are debited). It may never exceed the curve output, or
+    /// every swap could take up to the tolerance from LPs; the curve may
+    /// exceed it by at most the tolerance, the difference staying in reserves.
+    pub fn swap_output_within_drift(expected: u64, recomputed: u64) -> bool {
+        expected <= recomputed
+            && recomputed - expected <= apply_bps_ceil(expected, MAX_SWAP_OUTPUT_DRIFT_BPS)
+    }
+
+    /// Re-check a swap's Phase 0 output against current reserves
+    ///
+    /// Fails if the recomputed output is below `min_output` (slippage), below
+    /// `output_amount`, or above it by more than the drift tolerance.
+    /// Returns the recomputed output.
+    pub fn check_swap_output(
+        &self,
+        swap_amount: u64,
+        swap_a_to_b: bool,
+        output_amount: u64,
+        min_output: u64,
+    ) -> Result<u64> {
+        let (recomputed_output, _fee_amount) = self
+            .calculate_swap_output(swap_amount, swap_a_to_b)
+            .ok_or(CloakCraftError::InvalidSwapOutput)?;
+        require!(recomputed_output >= min_output, CloakCraftError::SlippageExceeded);
+        require!(
+            Self::swap_output_within_drift(output_amount, recomputed_output),
+            CloakCraftError::SwapOutputDrift
+        );
+        Ok(recomputed_output)
+    }
+
+    /// Fold surplus vault balance into reserves without minting LP tokens
+    ///
+    /// Returns None on overflow (reserves unchanged).
+    pub fn absorb_surplus(&mut self, surplus_a: u64, surplus_b: u64) -> Option<()> {
+        let reserve_a = self.reserve_a.checked_add(surplus_a)?;
+        let reserve_b = self.reserve_b.checked_add(surplus_b)?;
+        self.reserve_a = reserve_a;
+        self.reserve_b = reserve_b;
+        self.state_hash = self.compute_state_hash();
+        Some(())
+    }
+
+    /// Direction of a swap from `input_mint` into `output_mint`
+    ///
+    /// Returns `swap_a_to_b`, or None if the pool doesn't pair the two mints.
+    pub fn swap_direction(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> Option<bool> {
+        if self.token_a_mint == *input_mint && self.token_b_mint == *output_mint {
+            Some(true)
+        } else if self.token_b_mint == *input_mint && self.token_a_mint == *output_mint {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Apply a swap settled outside the note flow (no protocol fee share)
+    ///
+    /// The whole input, LP fee included, joins the input reserve. Returns
+    /// None on overflow or insufficient output reserve (reserves unchanged).
+    pub fn apply_direct_swap(&mut self, amount_in: u64, amount_out: u64, swap_a_to_b: bool) -> Option<()> {
+        let (reserve_in, reserve_out) = if swap_a_to_b {
+            (self.reserve_a, self.reserve_b)
+        } else {
+            (self.reserve_b, self.reserve_a)
+        };
+        let reserve_in = reserve_in.checked_add(amount_in)?;
+        let reserve_out = reserve_out.checked_sub(amount_out)?;
+        if swap_a_to_b {
+            self.reserve_a = reserve_in;
+            self.reserve_b = reserve_out;
+        } else {
+            self.reserve_b = reserve_in;
+            self.reserve_a = reserve_out;
+        }
+        self.state_hash = self.compute_state_hash();
+        Some(())
+    }
+
+    /// Maximum withdrawal for burning `lp_amount`, less `penalty_bps`
+    ///
+    /// Returns (max_a, max_b); the withheld share stays in reserves.
+    pub fn max_withdrawal(&self, lp_amount: u64, penalty_bps: u16) -> Option<(u64, u64)> {
+        if self.lp_supply == 0 {
+            return None;
+        }
+        let share_a = to_u64(mul_div(lp_amount as u128, self.reserve_a as u128, self.lp_supply as u128)?)?;
+        let share_b = to_u64(mul_div(lp_amount as u128, self.reserve_b as u128, self.lp_supply as u128)?)?;
+        Some((
+            share_a - apply_bps_ceil(share_a, penalty_bps),
+            share_b - apply_bps_ceil(share_b, penalty_bps),
+        ))
+    }
+
     /// Compute state hash from reserves
     pub fn compute_state_hash(&self) -> [u8; 32] {
         let mut data = Vec::with_capacity(32);
@@ -133,6 +263,32 @@ impl AmmPool {
         }
     }
 
+    /// Price impact of swapping `input_amount` for `output_amount`, in bps
+    ///
+    /// Compares the trade's rate with the rate of a reference swap of 0.01%
+    /// of the input reserve (the spot rate, fee included in both), so it
+    /// works for either formula. Returns 0 when the reference swap rounds to
+    /// nothing.
+    pub fn price_impact_bps(&self, input_amount: u64, output_amount: u64, swap_a_to_b: bool) -> u16 {
+        let reserve_in = if swap_a_to_b { self.reserve_a } else { self.reserve_b };
+        let reference_input = (reserve_in / BPS_SCALE as u64).max(1);
+        let Some((reference_output, _)) = self.calculate_swap_output(reference_input, swap_a_to_b) else {
+            return 0;
+        };
+        if reference_output == 0 || input_amount == 0 {
+            return 0;
+        }
+
+        // rate / spot_rate = (output / input) / (reference_output / reference_input)
+        let relative_rate = mul_div(
+            output_amount as u128 * reference_input as u128,
+            BPS_SCALE,
+            input_amount as u128 * reference_output as u128,
+        )
+        .unwrap_or(BPS_SCALE);
+        BPS_SCALE.saturating_sub(relative_rate) as u16
+    }
+
     /// Verify that the claimed output amount is correct for the given input
     /// Returns true if output_amount matches the formula (within tolerance)
     pub fn verify_swap_output(
@@ -167,16 +323,14 @@ impl AmmPool {
         }
 
         // Calculate fee: fee = input * fee_bps / 10000
-        let fee_amount = (input_amount as u128)
-            .checked_mul(self.fee_bps as u128)?
-            .checked_div(10000)? as u64;
+        let fee_amount = apply_bps(input_amount, self.fee_bps);
 
         let input_with_fee = input_amount.checked_sub(fee_amount)?;
 
         // output = (reserve_out * input_with_fee) / (reserve_in + input_with_fee)
         let numerator = (reserve_out as u128).checked_mul(input_with_fee as u128)?;
         let denominator = (reserve_in as u128).checked_add(input_with_fee as u128)?;
-        let output_amount = numerator.checked_div(denominator)? as u64;
+        let output_amount = to_u64(numerator.checked_div(denominator)?)?;
 
         Some((output_amount, fee_amount))
     }
@@ -205,9 +359,7 @@ impl AmmPool {
         }
 
         // Calculate fee
-        let fee_amount = (input_amount as u128)
-            .checked_mul(self.fee_bps as u128)?
-            .checked_div(10000)? as u64;
+        let fee_amount = apply_bps(input_amount, self.fee_bps);
 
         let input_with_fee = input_amount.checked_sub(fee_amount)?;
 
@@ -229,55 +381,61 @@ impl AmmPool {
 
         // Output amount = old_y - new_y
         let output_scaled = y.checked_sub(new_y)?;
-        let output_amount = output_scaled.checked_div(PRECISION)? as u64;
+        let output_amount = to_u64(output_scaled.checked_div(PRECISION)?)?;
 
         Some((output_amount, fee_amount))
     }
 
     /// Calculate D (the StableSwap invariant) using Newton-Raphson
     /// D is the total value of the pool in the "ideal" balanced state
+    ///
+    /// Inputs are 1e18-scaled reserves, so D^2 and D^3 / (4xy) run in U256;
+    /// D itself never exceeds x + y and narrows back to u128.
     fn get_d(&self, x: u128, y: u128, amp: u128) -> Option<u128> {
+        let (x, y) = (U256::from(x), U256::from(y));
+        let (two, three) = (U256::from(2u64), U256::from(3u64));
+
         // D = sum(x) when pool is balanced
         // Starting approximation
         let sum = x.checked_add(y)?;
-        if sum == 0 {
+        if sum.is_zero() {
             return Some(0);
         }
 
-        let ann = amp.checked_mul(4)?; // A * n^n where n=2
+        let ann = U256::from(amp.checked_mul(4)?); // A * n^n where n=2
 
         let mut d = sum;
-        let mut d_prev: u128;
+        let mut d_prev: U256;
 
         // Newton-Raphson iteration (typically converges in < 10 iterations)
         for _ in 0..255 {
             // D_P = D^3 / (4 * x * y)
             let mut d_p = d;
-            d_p = d_p.checked_mul(d)?.checked_div(x.checked_mul(2)?)?;
-            d_p = d_p.checked_mul(d)?.checked_div(y.checked_mul(2)?)?;
+            d_p = d_p.checked_mul(d)?.checked_div(x.checked_mul(two)?)?;
+            d_p = d_p.checked_mul(d)?.checked_div(y.checked_mul(two)?)?;
 
             d_prev = d;
 
             // d = (ann * sum + d_p * 2) * d / ((ann - 1) * d + 3 * d_p)
             let numerator = ann
                 .checked_mul(sum)?
-                .checked_add(d_p.checked_mul(2)?)?
+                .checked_add(d_p.checked_mul(two)?)?
                 .checked_mul(d)?;
 
             let denominator = ann
-                .checked_sub(1)?
+                .checked_sub(U256::ONE)?
                 .checked_mul(d)?
-                .checked_add(d_p.checked_mul(3)?)?;
+                .checked_add(d_p.checked_mul(three)?)?;
 
             d = numerator.checked_div(denominator)?;
 
             // Check convergence (within 1)
             if d > d_prev {
-                if d.checked_sub(d_prev)? <= 1 {
-                    return Some(d);
+                if d.checked_sub(d_prev)? <= U256::ONE {
+                    return d.to_u128();
                 }
-            } else if d_prev.checked_sub(d)? <= 1 {
-                return Some(d);
+            } else if d_prev.checked_sub(d)? <= U256::ONE {
+                return d.to_u128();
             }
         }
 
@@ -285,22 +443,24 @@ impl AmmPool {
     }
 
     /// Calculate y given x and D using Newton-Raphson
-    /// Solves for y in the StableSwap invariant
+    /// Solves for y in the StableSwap invariant (y^2 and D^3 terms in U256)
     fn get_y(&self, x: u128, d: u128, amp: u128) -> Option<u128> {
-        let ann = amp.checked_mul(4)?; // A * n^n where n=2
+        let (x, d) = (U256::from(x), U256::from(d));
+        let two = U256::from(2u64);
+        let ann = U256::from(amp.checked_mul(4)?); // A * n^n where n=2
 
         // c = D^3 / (4 * x * ann)
         let c = d
             .checked_mul(d)?
-            .checked_div(x.checked_mul(2)?)?
+            .checked_div(x.checked_mul(two)?)?
             .checked_mul(d)?
-            .checked_div(ann.checked_mul(2)?)?;
+            .checked_div(ann.checked_mul(two)?)?;
 
         // b = x + D / ann
         let b = x.checked_add(d.checked_div(ann)?)?;
 
         let mut y = d;
-        let mut y_prev: u128;
+        let mut y_prev: U256;
 
         // Newton-Raphson iteration
         for _ in 0..255 {
@@ -308,20 +468,271 @@ impl AmmPool {
 
             // y = (y^2 + c) / (2*y + b - D)
             let numerator = y.checked_mul(y)?.checked_add(c)?;
-            let denominator = y.checked_mul(2)?.checked_add(b)?.checked_sub(d)?;
+            let denominator = y.checked_mul(two)?.checked_add(b)?.checked_sub(d)?;
 
             y = numerator.checked_div(denominator)?;
 
             // Check convergence
             if y > y_prev {
-                if y.checked_sub(y_prev)? <= 1 {
-                    return Some(y);
+                if y.checked_sub(y_prev)? <= U256::ONE {
+                    return y.to_u128();
                 }
-            } else if y_prev.checked_sub(y)? <= 1 {
-                return Some(y);
+            } else if y_prev.checked_sub(y)? <= U256::ONE {
+                return y.to_u128();
             }
         }
 
         None // Failed to converge
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Iterations per property (deterministic, seeded)
+    const ITERATIONS: usize = 5_000;
+
+    /// StableSwap input envelope: reserves and amplification for which
+    /// get_d/get_y are expected to converge. Below STABLE_MIN_AMP, get_d can
+    /// oscillate and return None once a pool is ~1000x imbalanced.
+    const STABLE_MAX_RESERVE: u64 = 1_000_000_000_000_000; // 1e15 base units
+    const STABLE_MIN_AMP: u64 = 10;
+    const STABLE_MAX_AMP: u64 = 10_000;
+
+    /// xorshift64* - deterministic so failures reproduce across CI runs
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+        }
+
+        /// Log-uniform in [1, max] so small and extreme values both get coverage
+        fn amount(&mut self, max: u64) -> u64 {
+            let bits = 64 - max.leading_zeros();
+            let shift = self.next() % bits as u64;
+            let v = self.next() >> (63 - shift);
+            v.clamp(1, max)
+        }
+    }
+
+    fn pool(pool_type: PoolType, reserve_a: u64, reserve_b: u64, fee_bps: u16, amp: u64) -> AmmPool {
+        AmmPool {
+            reserve_a,
+            reserve_b,
+            fee_bps,
+            pool_type,
+            amplification: amp,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_constant_product_invariant() {
+        let mut rng = Rng(0x0123_4567_89AB_CDEF);
+        for _ in 0..ITERATIONS {
+            let (ra, rb) = (rng.amount(u64::MAX), rng.amount(u64::MAX));
+            let input = rng.amount(u64::MAX);
+            let fee_bps = (rng.next() % 1_000) as u16;
+            let p = pool(PoolType::ConstantProduct, ra, rb, fee_bps, 0);
+
+            // Must never panic; None only on overflow
+            let Some((out, fee)) = p.calculate_swap_output(input, true) else { continue };
+
+            assert!(out < rb, "drained reserve: ra={ra} rb={rb} in={input}");
+            assert!(fee <= input);
+
+            let k_before = (ra as u128) * (rb as u128);
+            let Some(new_a) = (ra as u128).checked_add(input as u128) else { continue };
+            let k_after = new_a.checked_mul((rb - out) as u128);
+            if let Some(k_after) = k_after {
+                assert!(k_after >= k_before, "k decreased: ra={ra} rb={rb} in={input}");
+            }
+            assert!(p.verify_swap_output(input, out, true));
+        }
+    }
+
+    #[test]
+    fn test_constant_product_monotonic() {
+        let mut rng = Rng(0xDEAD_BEEF_CAFE_F00D);
+        for _ in 0..ITERATIONS {
+            let p = pool(PoolType::ConstantProduct, rng.amount(1 << 48), rng.amount(1 << 48), 30, 0);
+            let a = rng.amount(1 << 40);
+            let b = a.saturating_add(rng.amount(1 << 40));
+            let out_a = p.calculate_swap_output(a, false).map(|(o, _)| o).unwrap_or(0);
+            let out_b = p.calculate_swap_output(b, false).map(|(o, _)| o).unwrap_or(0);
+            assert!(out_b >= out_a);
+        }
+    }
+
+    #[test]
+    fn test_stable_swap_no_panic_extreme() {
+        let mut rng = Rng(0x5151_5151_5151_5151);
+        for _ in 0..ITERATIONS {
+            let p = pool(
+                PoolType::StableSwap,
+                rng.amount(u64::MAX),
+                rng.amount(u64::MAX),
+                (rng.next() % 1_000) as u16,
+                rng.amount(u64::MAX),
+            );
+            let _ = p.calculate_swap_output(rng.amount(u64::MAX), rng.next() % 2 == 0);
+        }
+    }
+
+    #[test]
+    fn test_stable_swap_envelope_converges() {
+        let p = AmmPool::default();
+        let mut rng = Rng(0x0BAD_5EED_0BAD_5EED);
+        for _ in 0..ITERATIONS {
+            let ra = rng.amount(STABLE_MAX_RESERVE);
+            // Keep imbalance within 100x so the pool is a realistic pegged pair
+            let rb = (ra / 100).max(1) + rng.amount(ra.saturating_mul(100).min(STABLE_MAX_RESERVE));
+            let amp = rng.amount(STABLE_MAX_AMP).max(STABLE_MIN_AMP) as u128;
+            let new_x = (ra + rng.amount(ra)) as u128;
+
+            let d = p
+                .get_d(ra as u128, rb as u128, amp)
+                .unwrap_or_else(|| panic!("D: ra={ra} rb={rb} amp={amp}"));
+            let new_y = p
+                .get_y(new_x, d, amp)
+                .unwrap_or_else(|| panic!("y: ra={ra} rb={rb} amp={amp} x={new_x}"));
+
+            // Adding to x never grows y, and y' is within one unit of the curve
+            assert!(new_y <= rb as u128, "y grew: ra={ra} rb={rb} amp={amp} x={new_x}");
+            // (D is undefined at y = 0)
+            let d_below = new_y.checked_sub(1).and_then(|y| p.get_d(new_x, y, amp));
+            let d_above = p.get_d(new_x, new_y + 1, amp).unwrap();
+            assert!(
+                d_below.map_or(true, |d_below| d_below <= d + 1) && d <= d_above + 1,
+                "off curve: ra={ra} rb={rb} amp={amp} x={new_x}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_d_balanced() {
+        let p = AmmPool::default();
+        for &r in &[1u128, 1_000, 1_000_000_000, STABLE_MAX_RESERVE as u128] {
+            for &amp in &[1u128, 100, STABLE_MAX_AMP as u128] {
+                let d = p.get_d(r, r, amp).unwrap();
+                assert!(d.abs_diff(2 * r) <= 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stable_swap_realistic_reserves() {
+        // 1k to 1T tokens a side at 6 decimals, Curve-typical amplification
+        for &r in &[1_000_000_000u64, 1_000_000_000_000, 1_000_000_000_000_000, 1_000_000_000_000_000_000] {
+            let p = pool(PoolType::StableSwap, r, r, 4, 200);
+            let input = r / 100;
+            let (out, fee) = p
+                .calculate_swap_output(input, true)
+                .unwrap_or_else(|| panic!("balanced swap failed: r={r}"));
+            assert_eq!(fee, apply_bps(input, 4));
+
+            // 1% of a balanced pool trades within 1bp of peg, well above constant product
+            let after_fee = input - fee;
+            assert!(out <= after_fee && after_fee - out <= after_fee / 10_000, "r={r} out={out}");
+            let (cp_out, _) = pool(PoolType::ConstantProduct, r, r, 4, 0).calculate_swap_output(input, true).unwrap();
+            assert!(out > cp_out);
+            assert!(p.verify_swap_output(input, out, true));
+
+            // 3:1 imbalance: buying the scarce side costs more, selling it pays more
+            let p = pool(PoolType::StableSwap, r, r.saturating_mul(3), 4, 200);
+            let (scarce_out, _) = p.calculate_swap_output(input, false).unwrap();
+            let (plentiful_out, _) = p.calculate_swap_output(input, true).unwrap();
+            assert!(scarce_out < after_fee && plentiful_out > after_fee, "r={r}");
+        }
+
+        // The whole envelope solves end to end
+        let mut rng = Rng(0x57AB_1E57_AB1E_57AB);
+        for _ in 0..ITERATIONS {
+            let ra = rng.amount(STABLE_MAX_RESERVE);
+            let rb = (ra / 100).max(1) + rng.amount(ra.saturating_mul(100).min(STABLE_MAX_RESERVE));
+            let amp = rng.amount(STABLE_MAX_AMP).max(STABLE_MIN_AMP);
+            let p = pool(PoolType::StableSwap, ra, rb, 4, amp);
+            let input = rng.amount(ra);
+            let (out, _) = p
+                .calculate_swap_output(input, true)
+                .unwrap_or_else(|| panic!("ra={ra} rb={rb} amp={amp} in={input}"));
+            assert!(out < rb);
+        }
+    }
+
+    #[test]
+    fn test_swap_output_drift() {
+        assert!(AmmPool::swap_output_within_drift(1_000_000, 1_000_000));
+        assert!(AmmPool::swap_output_within_drift(1_000_000, 1_005_000));
+        assert!(!AmmPool::swap_output_within_drift(1_000_000, 1_005_001));
+        // Never more than the curve pays
+        assert!(!AmmPool::swap_output_within_drift(1_000_000, 999_999));
+        // Rounds up so dust outputs keep a 1 unit tolerance
+        assert!(AmmPool::swap_output_within_drift(10, 11));
+    }
+
+    #[test]
+    fn test_price_impact() {
+        let p = pool(PoolType::ConstantProduct, 1_000_000_000, 1_000_000_000, 30, 0);
+        // Small trade: close to spot
+        let (out, _) = p.calculate_swap_output(100_000, true).unwrap();
+        assert!(p.price_impact_bps(100_000, out, true) <= 10);
+        // 10% of reserves moves the price ~9%
+        let (out, _) = p.calculate_swap_output(100_000_000, true).unwrap();
+        let impact = p.price_impact_bps(100_000_000, out, true);
+        assert!((850..=950).contains(&impact), "impact={impact}");
+
+        // Empty pool
+        let empty = pool(PoolType::ConstantProduct, 0, 0, 30, 0);
+        assert_eq!(empty.price_impact_bps(100, 0, true), 0);
+    }
+
+    #[test]
+    fn test_absorb_surplus() {
+        let mut p = pool(PoolType::ConstantProduct, 1_000, 2_000, 30, 0);
+        p.absorb_surplus(10, 0).unwrap();
+        assert_eq!((p.reserve_a, p.reserve_b), (1_010, 2_000));
+        assert!(p.verify_state_hash(&p.compute_state_hash()));
+
+        let before = p.state_hash;
+        assert!(p.absorb_surplus(0, u64::MAX).is_none());
+        assert_eq!((p.reserve_a, p.reserve_b, p.state_hash), (1_010, 2_000, before));
+    }
+
+    #[test]
+    fn test_direct_swap() {
+        let mut p = pool(PoolType::ConstantProduct, 1_000, 2_000, 30, 0);
+        p.token_a_mint = Pubkey::new_unique();
+        p.token_b_mint = Pubkey::new_unique();
+        let (a, b) = (p.token_a_mint, p.token_b_mint);
+        assert_eq!(p.swap_direction(&a, &b), Some(true));
+        assert_eq!(p.swap_direction(&b, &a), Some(false));
+        assert_eq!(p.swap_direction(&a, &Pubkey::new_unique()), None);
+
+        p.apply_direct_swap(100, 50, false).unwrap();
+        assert_eq!((p.reserve_a, p.reserve_b), (950, 2_100));
+        assert!(p.verify_state_hash(&p.compute_state_hash()));
+
+        // Output beyond the reserve leaves the pool untouched
+        assert!(p.apply_direct_swap(1, 2_101, true).is_none());
+        assert_eq!((p.reserve_a, p.reserve_b), (950, 2_100));
+    }
+
+    #[test]
+    fn test_max_withdrawal_penalty() {
+        let mut p = pool(PoolType::ConstantProduct, 1_000_000, 2_000_000, 30, 0);
+        p.lp_supply = 1_000;
+
+        assert_eq!(p.max_withdrawal(100, 0), Some((100_000, 200_000)));
+        // 1% penalty stays in reserves
+        assert_eq!(p.max_withdrawal(100, 100), Some((99_000, 198_000)));
+
+        p.lp_supply = 0;
+        assert_eq!(p.max_withdrawal(100, 0), None);
+    }
+}
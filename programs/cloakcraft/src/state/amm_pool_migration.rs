@@ -0,0 +1,83 @@
+//! AMM pool migration (old pool -> successor pool)
+//!
+//! `reset_amm_pool` can only zero a pool. To change a pool's formula or
+//! layout without losing its liquidity, `migrate_amm_pool` creates a
+//! successor `AmmPool` for the same pair (next `version`, with its own LP
+//! mint), exports the old pool's reserves into it and deactivates the old
+//! pool for good. The old pool's LP supply is reserved in the successor at a
+//! fixed conversion rate, and old LP notes are then converted one by one with
+//! `create_pending_with_proof_convert_amm_lp_note`.
+//!
+//! Reserves are accounting only (the tokens stay in the pair's shielded pool
+//! vaults), so no tokens move.
+
+use anchor_lang::prelude::*;
+
+use crate::helpers::fixed::{mul_div, saturating_u64};
+
+/// Migration of one AMM pool, keyed by the old pool
+#[account]
+#[derive(Default, InitSpace)]
+pub struct AmmPoolMigration {
+    /// Pool migrated away from (deactivated)
+    pub old_pool: Pubkey,
+
+    /// Successor pool holding the exported reserves
+    pub new_pool: Pubkey,
+
+    /// Old pool LP mint (converted notes)
+    pub old_lp_mint: Pubkey,
+
+    /// Successor pool LP mint
+    pub new_lp_mint: Pubkey,
+
+    /// When the reserves were exported
+    pub migrated_at: i64,
+
+    /// Exported token A reserve
+    pub reserve_a: u64,
+
+    /// Exported token B reserve
+    pub reserve_b: u64,
+
+    /// Old pool LP supply at migration
+    pub old_lp_supply: u64,
+
+    /// Successor LP reserved for it (rate = new_lp_supply / old_lp_supply)
+    pub new_lp_supply: u64,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl AmmPoolMigration {
+    /// Seeds prefix: ["amm_migration", old_pool]
+    pub const SEEDS_PREFIX: &'static [u8] = b"amm_migration";
+
+    /// Successor LP an old LP note of `amount` converts to (rounded down)
+    pub fn convert_lp_amount(&self, amount: u64) -> Option<u64> {
+        if self.old_lp_supply == 0 {
+            return None;
+        }
+        mul_div(amount as u128, self.new_lp_supply as u128, self.old_lp_supply as u128).map(saturating_u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amm_lp_conversion() {
+        let mut migration = AmmPoolMigration::default();
+        // Nothing to convert from an empty pool
+        assert_eq!(migration.convert_lp_amount(100), None);
+
+        migration.old_lp_supply = 1_000;
+        migration.new_lp_supply = 2_500;
+        assert_eq!(migration.convert_lp_amount(1_000), Some(2_500));
+        // Rounds down, so conversions never exceed the reserved supply
+        assert_eq!(migration.convert_lp_amount(3), Some(7));
+        assert_eq!(migration.convert_lp_amount(0), Some(0));
+    }
+}
@@ -8,7 +8,12 @@
 
 use anchor_lang::prelude::*;
 
-/// Maximum number of voting options per ballot
+use crate::errors::CloakCraftError;
+use crate::helpers::fixed::{apply_bps, apply_rate, mul_div, saturating_u64};
+use super::ballot_options_page::MAX_PAGED_BALLOT_OPTIONS;
+
+/// Maximum number of voting options tallied on the ballot itself
+/// (see `ballot_options_page` for more)
 pub const MAX_BALLOT_OPTIONS: usize = 16;
 
 /// Maximum number of weight formula operations
@@ -17,6 +22,12 @@ pub const MAX_WEIGHT_FORMULA_OPS: usize = 16;
 /// Maximum number of weight formula parameters
 pub const MAX_WEIGHT_PARAMS: usize = 8;
 
+/// Maximum number of snapshot roots averaged in TWAB mode
+pub const MAX_TWAB_ROOTS: usize = 8;
+
+/// Maximum number of LP mints accepted for snapshot voting
+pub const MAX_LP_VOTE_SOURCES: usize = 4;
+
 /// ElGamal ciphertext size (C1 + C2, each 32 bytes)
 pub const ELGAMAL_CIPHERTEXT_SIZE: usize = 64;
 
@@ -77,6 +88,38 @@ pub enum ResolutionMode {
     Authority,
 }
 
+/// Time-weighted balance mode (Snapshot binding only)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace, Debug)]
+pub enum TwabMode {
+    /// Voting power is the note amount at the single snapshot root
+    #[default]
+    Disabled,
+    /// Voting power is the note amount averaged over twab_num_roots snapshot
+    /// roots (a note counts only at the roots it was included in)
+    Average,
+}
+
+/// TWAB snapshot root with the slot it was registered at
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace, Debug)]
+pub struct TwabRoot {
+    /// Commitment tree root attested at `slot`
+    pub root: [u8; 32],
+    /// Slot the root was registered at
+    pub slot: u64,
+}
+
+/// Who may add options while a ballot is open (write-ins)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace, Debug)]
+pub enum OptionRegistration {
+    /// Options are fixed at creation
+    #[default]
+    Closed,
+    /// Only the ballot's option registrar may add options
+    Registrar,
+    /// Anyone may add options, up to max_options
+    Open,
+}
+
 /// Ballot status lifecycle
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace, Debug)]
 pub enum BallotStatus {
@@ -91,6 +134,8 @@ pub enum BallotStatus {
     Resolved,
     /// Claims period ended, remaining funds sent to treasury (SpendToVote only)
     Finalized,
+    /// Oracle outcome disputed, awaiting the resolver (see dispute_resolution)
+    Disputed,
 }
 
 /// Weight formula operation for stack-based DSL
@@ -98,6 +143,9 @@ pub enum BallotStatus {
 /// Weight = evaluate(amount, weight_params) using these operations
 /// Example: weight = sqrt(amount) → [PushAmount, Sqrt]
 /// Example: weight = min(amount, 1000) → [PushAmount, PushConst(0), Min] where weight_params[0] = 1000
+///
+/// Encoded one byte per op, except PushConst, whose weight_params index
+/// follows in the next byte. An empty formula means weight = amount.
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace, Debug)]
 pub enum WeightOp {
     /// Push the input amount onto the stack
@@ -123,6 +171,79 @@ pub enum WeightOp {
     Max,
 }
 
+impl WeightOp {
+    /// Decode an op byte
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => WeightOp::PushAmount,
+            1 => WeightOp::PushConst,
+            2 => WeightOp::PushUserData,
+            3 => WeightOp::Add,
+            4 => WeightOp::Sub,
+            5 => WeightOp::Mul,
+            6 => WeightOp::Div,
+            7 => WeightOp::Sqrt,
+            8 => WeightOp::Min,
+            9 => WeightOp::Max,
+            _ => return None,
+        })
+    }
+
+    /// Values popped from the stack
+    fn pops(self) -> usize {
+        match self {
+            WeightOp::PushAmount | WeightOp::PushConst | WeightOp::PushUserData => 0,
+            WeightOp::Sqrt => 1,
+            _ => 2,
+        }
+    }
+}
+
+/// Check that a weight formula decodes and leaves exactly one value
+///
+/// Every op must be known, PushConst must index one of `num_params`
+/// weight_params, no op may pop an empty stack, and the result must be a
+/// single value. PushUserData is reserved and rejected.
+pub fn validate_weight_formula(formula: &[u8], num_params: usize) -> Result<()> {
+    if formula.is_empty() {
+        return Ok(());
+    }
+
+    let mut depth = 0usize;
+    let mut ops = formula.iter();
+    while let Some(&byte) = ops.next() {
+        let op = WeightOp::from_u8(byte).ok_or(CloakCraftError::InvalidWeightOp)?;
+        match op {
+            WeightOp::PushUserData => return Err(CloakCraftError::InvalidWeightOp.into()),
+            WeightOp::PushConst => {
+                let index = ops.next().ok_or(CloakCraftError::WeightParamOutOfRange)?;
+                require!((*index as usize) < num_params, CloakCraftError::WeightParamOutOfRange);
+            }
+            _ => {}
+        }
+        depth = depth
+            .checked_sub(op.pops())
+            .ok_or(CloakCraftError::WeightFormulaUnbalanced)?
+            + 1;
+    }
+
+    require!(depth == 1, CloakCraftError::WeightFormulaUnbalanced);
+    Ok(())
+}
+
+/// LP mint accepted for snapshot voting, with its conversion rate
+///
+/// AMM and perps LP holders vote with their LP notes; the LP amount is
+/// converted to ballot token units at `rate` (1e18 scale, attested by the
+/// ballot's indexer or authority at the snapshot).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace, Debug)]
+pub struct LpVoteSource {
+    /// AMM or perps LP token mint
+    pub lp_mint: Pubkey,
+    /// Ballot token units per LP token (1e18 scale)
+    pub rate: u128,
+}
+
 /// Vote preimage account for encrypted modes
 /// Stored as Light Protocol compressed account alongside vote_commitment
 ///
@@ -175,7 +296,7 @@ pub struct Ballot {
     /// Current ballot status
     pub status: BallotStatus,
 
-    /// Number of voting options (max 16)
+    /// Number of voting options (max 64; options past 16 are tallied in BallotOptionsPage accounts)
     pub num_options: u8,
     /// Minimum weight/amount required for valid outcome (0 = no quorum)
     pub quorum_threshold: u64,
@@ -268,6 +389,82 @@ pub struct Ballot {
 
     /// PDA bump seed
     pub bump: u8,
+
+    // Fields below were added after the first ballots were created; older
+    // accounts get them zeroed by migrate_ballot
+
+    // =========================================================================
+    // Snapshot Roots
+    // =========================================================================
+    /// Commitment tree root archived at snapshot_slot (Snapshot mode)
+    pub snapshot_root: [u8; 32],
+    /// Whether snapshot_root is registered (votes must then prove against it)
+    pub has_snapshot_root: bool,
+    /// LP mints accepted for snapshot voting
+    pub lp_vote_sources: [LpVoteSource; MAX_LP_VOTE_SOURCES],
+    /// Number of registered lp_vote_sources
+    pub lp_vote_source_count: u8,
+    /// Time-weighted balance mode
+    pub twab_mode: TwabMode,
+    /// Number of snapshot roots averaged in TWAB mode
+    pub twab_num_roots: u8,
+    /// Minimum slots between TWAB root registrations
+    pub twab_interval_slots: u64,
+    /// Snapshot roots registered so far, oldest first (TWAB mode)
+    pub twab_roots: [TwabRoot; MAX_TWAB_ROOTS],
+    /// Number of registered twab_roots
+    pub twab_root_count: u8,
+
+    // =========================================================================
+    // Proposal Bond
+    // =========================================================================
+    /// Lamports locked by the creator on the ballot account (0 = no bond)
+    /// Refunded if quorum is reached at resolution, slashed to treasury otherwise
+    pub proposal_bond: u64,
+    /// Whether the bond has been refunded or slashed
+    pub bond_settled: bool,
+
+    /// BallotOptionsPage accounts created (see `option_pages_required`)
+    pub option_page_count: u8,
+
+    // =========================================================================
+    // Option Registration
+    // =========================================================================
+    /// Who may add options before end_time (see add_ballot_option)
+    pub option_registration: OptionRegistration,
+    /// Signer allowed to add options in Registrar mode
+    pub option_registrar: Pubkey,
+    /// Cap on num_options reachable by registration
+    pub max_options: u8,
+
+    // =========================================================================
+    // Turnout Privacy
+    // =========================================================================
+    /// Keep vote_count and total_weight encrypted until decrypt_tally
+    pub hide_turnout: bool,
+    /// ElGamal sum of the votes' turnout ciphertexts (each encrypts 1)
+    pub encrypted_vote_count: [u8; ELGAMAL_CIPHERTEXT_SIZE],
+
+    // =========================================================================
+    // Loser Refunds (SpendToVote only)
+    // =========================================================================
+    /// Share of their pool stake losers can claim back (0 = winners take all)
+    pub loser_refund_bps: u16,
+
+    // =========================================================================
+    // Oracle Disputes
+    // =========================================================================
+    /// Seconds after resolution an Oracle outcome can be disputed (0 = final at once)
+    pub dispute_window_seconds: i64,
+    /// Lamports a challenger locks to dispute the outcome
+    pub dispute_bond: u64,
+    /// When the ballot was resolved (opens the dispute window)
+    pub resolved_at: i64,
+    /// Challenger of the oracle outcome (default if undisputed)
+    pub challenger: Pubkey,
+
+    /// Space for future fields, so they don't need another migration
+    pub _reserved: [u8; 128],
 }
 
 impl Ballot {
@@ -328,8 +525,36 @@ impl Ballot {
         32 + // oracle
         1 + // has_oracle
         8 + // claim_deadline
-        1; // bump
-        // Total: ~1,768 bytes
+        1 + // bump
+        // Snapshot roots
+        32 + // snapshot_root
+        1 + // has_snapshot_root
+        (LpVoteSource::INIT_SPACE * MAX_LP_VOTE_SOURCES) + // lp_vote_sources (192 bytes)
+        1 + // lp_vote_source_count
+        1 + // twab_mode
+        1 + // twab_num_roots
+        8 + // twab_interval_slots
+        (TwabRoot::INIT_SPACE * MAX_TWAB_ROOTS) + // twab_roots (320 bytes)
+        1 + // twab_root_count
+        // Proposal bond
+        8 + // proposal_bond
+        1 + // bond_settled
+        1 + // option_page_count
+        // Option registration
+        1 + // option_registration
+        32 + // option_registrar
+        1 + // max_options
+        // Turnout privacy
+        1 + // hide_turnout
+        ELGAMAL_CIPHERTEXT_SIZE + // encrypted_vote_count
+        // Loser refunds
+        2 + // loser_refund_bps
+        // Oracle disputes
+        8 + // dispute_window_seconds
+        8 + // dispute_bond
+        8 + // resolved_at
+        32 + // challenger
+        128; // _reserved
 
     /// Check if ballot is currently active for voting
     pub fn is_active(&self, current_time: i64) -> bool {
@@ -338,6 +563,11 @@ impl Ballot {
             && current_time < self.end_time
     }
 
+    /// Whether any vote has been tallied (counted or, with hidden turnout, encrypted)
+    pub fn has_votes(&self) -> bool {
+        self.vote_count > 0 || self.encrypted_vote_count != [0u8; ELGAMAL_CIPHERTEXT_SIZE]
+    }
+
     /// Check if voting period has ended
     pub fn is_voting_ended(&self, current_time: i64) -> bool {
         current_time >= self.end_time
@@ -369,52 +599,64 @@ impl Ballot {
     /// Check if claims are allowed (SpendToVote only, after resolution)
     pub fn claims_allowed(&self, current_time: i64) -> bool {
         self.binding_mode == VoteBindingMode::SpendToVote
-            && self.status == BallotStatus::Resolved
+            && self.outcome_final(current_time)
             && (self.claim_deadline == 0 || current_time < self.claim_deadline)
     }
 
+    /// Whether the ballot is resolved and its outcome can no longer be
+    /// disputed: the dispute window has passed, or its dispute was settled
+    pub fn outcome_final(&self, current_time: i64) -> bool {
+        self.status == BallotStatus::Resolved
+            && (self.dispute_window_seconds == 0
+                || self.challenger != Pubkey::default()
+                || current_time >= self.resolved_at.saturating_add(self.dispute_window_seconds))
+    }
+
     /// Calculate payout for a winner
-    /// payout = (user_weight / winner_weight) * total_pool
+    /// payout = (user_weight / winner_weight) * winners_pool
     /// Returns (gross_payout, net_payout) after fee deduction
     pub fn calculate_payout(&self, user_weight: u64) -> (u64, u64) {
         if self.winner_weight == 0 || !self.has_outcome {
             return (0, 0);
         }
 
-        // gross_payout = (user_weight * pool_balance) / winner_weight
-        // Use u128 to prevent overflow
-        let gross_payout = ((user_weight as u128) * (self.pool_balance as u128)
-            / (self.winner_weight as u128)) as u64;
+        // gross_payout = (user_weight * winners_pool) / winner_weight
+        // Capped at the winners' pool so an oversized weight cannot drain more than it
+        let winners_pool = self.pool_balance.saturating_sub(self.loser_refund_reserve());
+        let gross_payout = mul_div(user_weight as u128, winners_pool as u128, self.winner_weight as u128)
+            .map_or(0, saturating_u64)
+            .min(winners_pool);
 
         // fee = gross_payout * protocol_fee_bps / 10000
-        let fee = (gross_payout as u128 * self.protocol_fee_bps as u128 / 10000) as u64;
+        let fee = apply_bps(gross_payout, self.protocol_fee_bps);
         let net_payout = gross_payout.saturating_sub(fee);
 
         (gross_payout, net_payout)
     }
 
-    /// Determine winner based on TallyBased resolution
-    /// Returns winning option index (lowest index on tie)
-    pub fn tally_based_winner(&self) -> Option<u8> {
-        if self.num_options == 0 {
-            return None;
-        }
-
-        let mut max_weight = 0u64;
-        let mut winner = 0u8;
-
-        for i in 0..self.num_options as usize {
-            if self.option_weights[i] > max_weight {
-                max_weight = self.option_weights[i];
-                winner = i as u8;
-            }
+    /// Pool set aside for loser refunds: `loser_refund_bps` of the losing
+    /// weight's share of the pool
+    pub fn loser_refund_reserve(&self) -> u64 {
+        if self.loser_refund_bps == 0 || self.total_weight == 0 || !self.has_outcome {
+            return 0;
         }
+        let loser_weight = self.total_weight.saturating_sub(self.winner_weight);
+        let loser_stake = mul_div(loser_weight as u128, self.pool_balance as u128, self.total_weight as u128)
+            .map_or(0, saturating_u64);
+        apply_bps(loser_stake, self.loser_refund_bps).min(self.pool_balance)
+    }
 
-        if max_weight == 0 {
-            return None;
+    /// Calculate the refund for a losing position
+    /// refund = (user_weight / total_weight) * total_pool * loser_refund_bps
+    ///
+    /// Refunds return the loser's own stake, so no protocol fee applies.
+    pub fn calculate_refund(&self, user_weight: u64) -> u64 {
+        if self.loser_refund_bps == 0 || self.total_weight == 0 || !self.has_outcome {
+            return 0;
         }
-
-        Some(winner)
+        let stake = mul_div(user_weight as u128, self.pool_balance as u128, self.total_weight as u128)
+            .map_or(0, saturating_u64);
+        apply_bps(stake, self.loser_refund_bps).min(self.loser_refund_reserve())
     }
 
     /// Check if a vote choice won (for claims)
@@ -443,6 +685,89 @@ impl Ballot {
         }
     }
 
+    /// Check a vote's merkle root against the registered snapshot root
+    ///
+    /// Votes are rejected until the root is registered, so no vote can be
+    /// counted against a live root.
+    pub fn check_snapshot_root(&self, merkle_root: &[u8; 32]) -> Result<()> {
+        require!(self.has_snapshot_root, CloakCraftError::SnapshotRootNotRegistered);
+        require!(*merkle_root == self.snapshot_root, CloakCraftError::SnapshotRootMismatch);
+        Ok(())
+    }
+
+    /// Record a snapshot root registered at `slot`
+    ///
+    /// Without TWAB the root is the snapshot root. In TWAB mode roots are
+    /// appended oldest first, each at least twab_interval_slots after the
+    /// previous one and before twab_window_end, and the ballot's snapshot
+    /// root is set (votes open) once all twab_num_roots are registered.
+    pub fn register_snapshot_root(&mut self, root: [u8; 32], slot: u64) -> Result<()> {
+        if self.has_snapshot_root {
+            return Err(CloakCraftError::SnapshotRootAlreadySet.into());
+        }
+        if self.twab_mode == TwabMode::Average {
+            require!(slot < self.twab_window_end(), CloakCraftError::TwabWindowClosed);
+            if let Some(previous) = self.twab_roots().last() {
+                require!(
+                    slot >= previous.slot.saturating_add(self.twab_interval_slots),
+                    CloakCraftError::TwabRootTooEarly
+                );
+            }
+            require!(
+                self.twab_roots().iter().all(|entry| entry.root != root),
+                CloakCraftError::DuplicateTwabRoot
+            );
+
+            let index = self.twab_root_count as usize;
+            self.twab_roots[index] = TwabRoot { root, slot };
+            self.twab_root_count += 1;
+            if self.twab_root_count < self.twab_num_roots {
+                return Ok(());
+            }
+        }
+        self.snapshot_root = root;
+        self.has_snapshot_root = true;
+        Ok(())
+    }
+
+    /// Registered TWAB roots
+    pub fn twab_roots(&self) -> &[TwabRoot] {
+        &self.twab_roots[..self.twab_root_count as usize]
+    }
+
+    /// First slot after the TWAB window
+    ///
+    /// The window opens at snapshot_slot and spans one interval per root, so
+    /// spaced registrations cover at least (twab_num_roots - 1) intervals.
+    pub fn twab_window_end(&self) -> u64 {
+        self.snapshot_slot
+            .saturating_add(self.twab_interval_slots.saturating_mul(self.twab_num_roots as u64))
+    }
+
+    /// Bond recipient at resolution: the creator if quorum was reached,
+    /// the protocol treasury otherwise. None if there is no bond to settle.
+    pub fn bond_recipient(&self, quorum_met: bool, treasury: Pubkey) -> Option<Pubkey> {
+        if self.proposal_bond == 0 || self.bond_settled {
+            return None;
+        }
+        Some(if quorum_met { self.authority } else { treasury })
+    }
+
+    /// Registered LP vote sources
+    pub fn lp_vote_sources(&self) -> &[LpVoteSource] {
+        &self.lp_vote_sources[..self.lp_vote_source_count as usize]
+    }
+
+    /// Convert an LP note amount to ballot token units
+    pub fn convert_lp_amount(&self, lp_mint: &Pubkey, amount: u64) -> Result<u64> {
+        let source = self
+            .lp_vote_sources()
+            .iter()
+            .find(|s| s.lp_mint == *lp_mint)
+            .ok_or(CloakCraftError::LpVoteSourceNotFound)?;
+        apply_rate(amount, source.rate).ok_or(CloakCraftError::AmountOverflow.into())
+    }
+
     /// Get the weight formula as a slice
     pub fn get_weight_formula(&self) -> &[u8] {
         &self.weight_formula[..self.weight_formula_len as usize]
@@ -465,6 +790,10 @@ pub struct BallotConfigInput {
     pub snapshot_slot: u64,
     pub indexer_pubkey: Pubkey,
     pub eligibility_root: Option<[u8; 32]>,
+    pub twab_mode: TwabMode,
+    pub twab_num_roots: u8,
+    /// Minimum slots between TWAB root registrations (0 when Disabled)
+    pub twab_interval_slots: u64,
     pub weight_formula: Vec<u8>,
     pub weight_params: Vec<u64>,
     pub time_lock_pubkey: [u8; 32],
@@ -472,4 +801,402 @@ pub struct BallotConfigInput {
     pub resolver: Option<Pubkey>,
     pub oracle: Option<Pubkey>,
     pub claim_deadline: i64,
+    /// Lamports the creator locks as a spam bond (0 = none)
+    pub proposal_bond: u64,
+    /// Who may add options while the ballot is open
+    pub option_registration: OptionRegistration,
+    /// Registrar for Registrar mode
+    pub option_registrar: Option<Pubkey>,
+    /// Most options registration may reach (0 when Closed)
+    pub max_options: u8,
+    /// Keep turnout encrypted until decrypt_tally (encrypted Snapshot ballots)
+    pub hide_turnout: bool,
+    /// Share of their stake losers can claim back (SpendToVote, 0 = none)
+    pub loser_refund_bps: u16,
+    /// Seconds an Oracle outcome can be disputed, escalating to the resolver (0 = none)
+    pub dispute_window_seconds: i64,
+    /// Lamports a challenger locks to dispute
+    pub dispute_bond: u64,
+}
+
+impl BallotConfigInput {
+    /// Check the config describes a ballot that can run to resolution
+    pub fn validate(&self, current_time: i64) -> Result<()> {
+        // Timing: a non-empty voting period that hasn't already ended
+        require!(self.start_time < self.end_time, CloakCraftError::InvalidBallotTiming);
+        require!(self.end_time > current_time, CloakCraftError::BallotAlreadyEnded);
+
+        // Options registered while open count against the same limits
+        match self.option_registration {
+            OptionRegistration::Closed => {
+                require!(self.max_options == 0, CloakCraftError::InvalidOptionRegistration);
+            }
+            OptionRegistration::Registrar | OptionRegistration::Open => {
+                require!(
+                    self.max_options > self.num_options,
+                    CloakCraftError::InvalidOptionRegistration
+                );
+                if self.option_registration == OptionRegistration::Registrar {
+                    require!(
+                        self.option_registrar.is_some_and(|registrar| registrar != Pubkey::default()),
+                        CloakCraftError::MissingOptionRegistrar
+                    );
+                }
+            }
+        }
+        let capacity = self.num_options.max(self.max_options) as usize;
+
+        require!(
+            self.num_options > 0 && capacity <= MAX_PAGED_BALLOT_OPTIONS,
+            CloakCraftError::InvalidNumOptions
+        );
+        // Paged options are tallied in the clear, one index per vote
+        require!(
+            capacity <= MAX_BALLOT_OPTIONS
+                || (self.reveal_mode == RevealMode::Public
+                    && matches!(self.vote_type, VoteType::Single | VoteType::Weighted)),
+            CloakCraftError::PagedBallotUnsupportedMode
+        );
+
+        // Protocol fee (charged on SpendToVote claims) needs somewhere to go
+        require!(self.protocol_fee_bps <= 10_000, CloakCraftError::ProtocolFeeExceedsMax);
+        require!(
+            self.protocol_fee_bps == 0 || self.protocol_treasury != Pubkey::default(),
+            CloakCraftError::MissingProtocolTreasury
+        );
+
+        // Weight formula
+        require!(
+            self.weight_formula.len() <= MAX_WEIGHT_FORMULA_OPS,
+            CloakCraftError::WeightFormulaTooLong
+        );
+        require!(
+            self.weight_params.len() <= MAX_WEIGHT_PARAMS,
+            CloakCraftError::TooManyWeightParams
+        );
+        validate_weight_formula(&self.weight_formula, self.weight_params.len())?;
+
+        // Encrypted modes need a timelock key and an unlock slot
+        if self.reveal_mode != RevealMode::Public {
+            require!(self.time_lock_pubkey != [0u8; 32], CloakCraftError::MissingTimeLockPubkey);
+            require!(self.unlock_slot != 0, CloakCraftError::InvalidUnlockSlot);
+        }
+
+        // Hidden turnout: SpendToVote moves every vote's tokens in the clear
+        require!(
+            !self.hide_turnout
+                || (self.reveal_mode != RevealMode::Public
+                    && self.binding_mode == VoteBindingMode::Snapshot),
+            CloakCraftError::InvalidTurnoutPrivacy
+        );
+
+        // Loser refunds come out of the SpendToVote pool
+        require!(
+            self.loser_refund_bps <= 10_000
+                && (self.loser_refund_bps == 0 || self.binding_mode == VoteBindingMode::SpendToVote),
+            CloakCraftError::InvalidLoserRefund
+        );
+
+        // Oracle disputes escalate to the resolver, against a challenger bond
+        require!(
+            if self.dispute_window_seconds == 0 {
+                self.dispute_bond == 0
+            } else {
+                self.dispute_window_seconds > 0
+                    && self.dispute_bond > 0
+                    && self.resolution_mode == ResolutionMode::Oracle
+                    && self.resolver.is_some_and(|resolver| resolver != Pubkey::default())
+            },
+            CloakCraftError::InvalidDisputeConfig
+        );
+
+        // Resolution modes other than TallyBased need their resolver
+        match self.resolution_mode {
+            ResolutionMode::TallyBased => {}
+            ResolutionMode::Oracle => {
+                require!(
+                    self.oracle.is_some_and(|oracle| oracle != Pubkey::default()),
+                    CloakCraftError::MissingBallotOracle
+                );
+            }
+            ResolutionMode::Authority => {
+                require!(
+                    self.resolver.is_some_and(|resolver| resolver != Pubkey::default()),
+                    CloakCraftError::MissingBallotResolver
+                );
+            }
+        }
+
+        match self.binding_mode {
+            VoteBindingMode::Snapshot => {
+                // Snapshot slot may be in the past or future depending on use case
+                require!(self.snapshot_slot != 0, CloakCraftError::InvalidSnapshotSlot);
+                require!(self.claim_deadline == 0, CloakCraftError::InvalidClaimDeadline);
+            }
+            VoteBindingMode::SpendToVote => {
+                require!(
+                    self.claim_deadline == 0 || self.claim_deadline > self.end_time,
+                    CloakCraftError::InvalidClaimDeadline
+                );
+            }
+        }
+
+        // TWAB: Snapshot only, at least two roots to average, spaced apart
+        match self.twab_mode {
+            TwabMode::Disabled => {
+                require!(
+                    self.twab_num_roots == 0 && self.twab_interval_slots == 0,
+                    CloakCraftError::InvalidTwabConfig
+                );
+            }
+            TwabMode::Average => {
+                require!(
+                    self.binding_mode == VoteBindingMode::Snapshot
+                        && self.twab_num_roots >= 2
+                        && self.twab_num_roots as usize <= MAX_TWAB_ROOTS
+                        && self.twab_interval_slots > 0,
+                    CloakCraftError::InvalidTwabConfig
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> BallotConfigInput {
+        BallotConfigInput {
+            binding_mode: VoteBindingMode::Snapshot,
+            reveal_mode: RevealMode::Public,
+            vote_type: VoteType::Single,
+            resolution_mode: ResolutionMode::TallyBased,
+            num_options: 2,
+            quorum_threshold: 0,
+            protocol_fee_bps: 0,
+            protocol_treasury: Pubkey::default(),
+            start_time: 100,
+            end_time: 200,
+            snapshot_slot: 1,
+            indexer_pubkey: Pubkey::default(),
+            eligibility_root: None,
+            twab_mode: TwabMode::Disabled,
+            twab_num_roots: 0,
+            twab_interval_slots: 0,
+            weight_formula: vec![],
+            weight_params: vec![],
+            time_lock_pubkey: [0u8; 32],
+            unlock_slot: 0,
+            resolver: None,
+            oracle: None,
+            claim_deadline: 0,
+            proposal_bond: 0,
+            option_registration: OptionRegistration::Closed,
+            option_registrar: None,
+            max_options: 0,
+            hide_turnout: false,
+            loser_refund_bps: 0,
+            dispute_window_seconds: 0,
+            dispute_bond: 0,
+        }
+    }
+
+    #[test]
+    fn test_weight_formula_validation() {
+        let (amount, konst, user, sub, sqrt, min) = (0u8, 1u8, 2u8, 4u8, 7u8, 8u8);
+        assert!(validate_weight_formula(&[], 0).is_ok());
+        assert!(validate_weight_formula(&[amount, sqrt], 0).is_ok());
+        assert!(validate_weight_formula(&[amount, konst, 0, min], 1).is_ok());
+
+        // PushConst index must name a param
+        assert!(validate_weight_formula(&[amount, konst, 1, min], 1).is_err());
+        assert!(validate_weight_formula(&[amount, konst], 1).is_err());
+        // Unknown and reserved ops
+        assert!(validate_weight_formula(&[10], 0).is_err());
+        assert!(validate_weight_formula(&[user], 0).is_err());
+        // Stack underflow and leftovers
+        assert!(validate_weight_formula(&[amount, sub], 0).is_err());
+        assert!(validate_weight_formula(&[amount, amount], 0).is_err());
+    }
+
+    #[test]
+    fn test_ballot_config_validation() {
+        assert!(config().validate(150).is_ok());
+        assert!(config().validate(200).is_err());
+
+        let mut c = config();
+        c.protocol_fee_bps = 100;
+        assert!(c.validate(0).is_err());
+        c.protocol_treasury = Pubkey::new_unique();
+        assert!(c.validate(0).is_ok());
+
+        let mut c = config();
+        c.resolution_mode = ResolutionMode::Authority;
+        assert!(c.validate(0).is_err());
+        c.resolver = Some(Pubkey::new_unique());
+        assert!(c.validate(0).is_ok());
+
+        let mut c = config();
+        c.reveal_mode = RevealMode::TimeLocked;
+        c.time_lock_pubkey = [1u8; 32];
+        assert!(c.validate(0).is_err());
+        c.unlock_slot = 10;
+        assert!(c.validate(0).is_ok());
+
+        // Claim deadlines are SpendToVote only, after end_time
+        let mut c = config();
+        c.claim_deadline = 300;
+        assert!(c.validate(0).is_err());
+        c.binding_mode = VoteBindingMode::SpendToVote;
+        assert!(c.validate(0).is_ok());
+        c.claim_deadline = 200;
+        assert!(c.validate(0).is_err());
+
+        let mut c = config();
+        c.twab_num_roots = 4;
+        assert!(c.validate(0).is_err());
+        c.twab_mode = TwabMode::Average;
+        assert!(c.validate(0).is_err());
+        c.twab_interval_slots = 100;
+        assert!(c.validate(0).is_ok());
+
+        // Write-ins need headroom, a registrar in Registrar mode, and the
+        // paged limits at max_options
+        let mut c = config();
+        c.max_options = 4;
+        assert!(c.validate(0).is_err());
+        c.option_registration = OptionRegistration::Registrar;
+        assert!(c.validate(0).is_err());
+        c.option_registrar = Some(Pubkey::new_unique());
+        assert!(c.validate(0).is_ok());
+        c.max_options = 2;
+        assert!(c.validate(0).is_err());
+        c.option_registration = OptionRegistration::Open;
+        c.max_options = 64;
+        assert!(c.validate(0).is_ok());
+        c.vote_type = VoteType::Approval;
+        assert!(c.validate(0).is_err());
+
+        // Hidden turnout needs an encrypted Snapshot ballot
+        let mut c = config();
+        c.hide_turnout = true;
+        assert!(c.validate(0).is_err());
+        c.reveal_mode = RevealMode::PermanentPrivate;
+        c.time_lock_pubkey = [1u8; 32];
+        c.unlock_slot = 10;
+        assert!(c.validate(0).is_ok());
+        c.binding_mode = VoteBindingMode::SpendToVote;
+        assert!(c.validate(0).is_err());
+
+        // Loser refunds: SpendToVote only, at most the whole stake
+        let mut c = config();
+        c.loser_refund_bps = 5_000;
+        assert!(c.validate(0).is_err());
+        c.binding_mode = VoteBindingMode::SpendToVote;
+        assert!(c.validate(0).is_ok());
+        c.loser_refund_bps = 10_001;
+        assert!(c.validate(0).is_err());
+
+        // Disputes: Oracle ballots with a bond and a resolver to escalate to
+        let mut c = config();
+        c.resolution_mode = ResolutionMode::Oracle;
+        c.oracle = Some(Pubkey::new_unique());
+        c.dispute_window_seconds = 3_600;
+        c.dispute_bond = 1_000;
+        assert!(c.validate(0).is_err());
+        c.resolver = Some(Pubkey::new_unique());
+        assert!(c.validate(0).is_ok());
+        c.dispute_bond = 0;
+        assert!(c.validate(0).is_err());
+        c.dispute_window_seconds = 0;
+        assert!(c.validate(0).is_ok());
+    }
+
+    #[test]
+    fn test_twab_root_spacing() {
+        let zeroed = vec![0u8; Ballot::SPACE];
+        let mut ballot = Ballot::deserialize(&mut &zeroed[8..]).unwrap();
+        ballot.snapshot_slot = 1_000;
+        ballot.twab_mode = TwabMode::Average;
+        ballot.twab_num_roots = 3;
+        ballot.twab_interval_slots = 100;
+        assert_eq!(ballot.twab_window_end(), 1_300);
+
+        ballot.register_snapshot_root([1u8; 32], 1_000).unwrap();
+
+        // Back-to-back registrations collapse the average to one point
+        assert_eq!(
+            ballot.register_snapshot_root([2u8; 32], 1_000).unwrap_err(),
+            CloakCraftError::TwabRootTooEarly.into()
+        );
+        assert_eq!(
+            ballot.register_snapshot_root([2u8; 32], 1_099).unwrap_err(),
+            CloakCraftError::TwabRootTooEarly.into()
+        );
+        assert_eq!(
+            ballot.register_snapshot_root([1u8; 32], 1_100).unwrap_err(),
+            CloakCraftError::DuplicateTwabRoot.into()
+        );
+        ballot.register_snapshot_root([2u8; 32], 1_100).unwrap();
+        assert_eq!(
+            ballot.register_snapshot_root([3u8; 32], 1_300).unwrap_err(),
+            CloakCraftError::TwabWindowClosed.into()
+        );
+        assert!(!ballot.has_snapshot_root);
+
+        ballot.register_snapshot_root([3u8; 32], 1_299).unwrap();
+        assert!(ballot.has_snapshot_root);
+        assert_eq!(ballot.snapshot_root, [3u8; 32]);
+        assert_eq!(ballot.twab_roots()[1], TwabRoot { root: [2u8; 32], slot: 1_100 });
+    }
+
+    #[test]
+    fn test_outcome_final() {
+        let zeroed = vec![0u8; Ballot::SPACE];
+        let mut ballot = Ballot::deserialize(&mut &zeroed[8..]).unwrap();
+        ballot.status = BallotStatus::Resolved;
+        ballot.resolved_at = 1_000;
+        assert!(ballot.outcome_final(1_000));
+
+        ballot.dispute_window_seconds = 100;
+        assert!(!ballot.outcome_final(1_099));
+        assert!(ballot.outcome_final(1_100));
+
+        // Disputed ballots wait for the resolver; settled ones are final
+        ballot.status = BallotStatus::Disputed;
+        ballot.challenger = Pubkey::new_unique();
+        assert!(!ballot.outcome_final(1_050));
+        ballot.status = BallotStatus::Resolved;
+        assert!(ballot.outcome_final(1_050));
+    }
+
+    #[test]
+    fn test_loser_refund_payouts() {
+        let zeroed = vec![0u8; Ballot::SPACE];
+        let mut ballot = Ballot::deserialize(&mut &zeroed[8..]).unwrap();
+        ballot.has_outcome = true;
+        ballot.pool_balance = 1_000;
+        ballot.total_weight = 1_000;
+        ballot.winner_weight = 600;
+
+        // Winners take all by default
+        assert_eq!(ballot.calculate_payout(600), (1_000, 1_000));
+        assert_eq!(ballot.calculate_refund(400), 0);
+
+        // Half of the losers' 400 goes back to them, winners share the rest
+        ballot.loser_refund_bps = 5_000;
+        assert_eq!(ballot.loser_refund_reserve(), 200);
+        assert_eq!(ballot.calculate_refund(400), 200);
+        assert_eq!(ballot.calculate_refund(100), 50);
+        assert_eq!(ballot.calculate_payout(600), (800, 800));
+        assert_eq!(ballot.calculate_payout(300), (400, 400));
+
+        // A full refund leaves winners their own stake
+        ballot.loser_refund_bps = 10_000;
+        assert_eq!(ballot.calculate_payout(600), (600, 600));
+        assert_eq!(ballot.calculate_refund(400), 400);
+    }
 }
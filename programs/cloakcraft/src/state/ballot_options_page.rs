@@ -0,0 +1,167 @@
+//! Ballot options pages
+//!
+//! A ballot tallies its first `MAX_BALLOT_OPTIONS` options inline. Ballots
+//! with more options (up to `MAX_PAGED_BALLOT_OPTIONS`) tally the rest in
+//! `BallotOptionsPage` accounts of `OPTIONS_PER_PAGE` options each: page `n`
+//! (from 1) holds options `16n..16n+15`. Anyone may create a ballot's pages;
+//! votes for an option need its page, and TallyBased resolution needs all of
+//! them.
+//!
+//! Paged ballots are Public reveal with Single or Weighted votes: encrypted
+//! tallies keep one ciphertext per option on the ballot, and Approval and
+//! Ranked choices only encode 16 options.
+
+use anchor_lang::prelude::*;
+
+use crate::errors::CloakCraftError;
+use super::ballot::{Ballot, MAX_BALLOT_OPTIONS};
+
+/// Options tallied per page
+pub const OPTIONS_PER_PAGE: usize = MAX_BALLOT_OPTIONS;
+
+/// Maximum number of voting options on a paged ballot
+pub const MAX_PAGED_BALLOT_OPTIONS: usize = 64;
+
+/// Tally of 16 options of a ballot (see module docs)
+#[account]
+#[derive(Default, InitSpace)]
+pub struct BallotOptionsPage {
+    /// Ballot the page belongs to
+    pub ballot_id: [u8; 32],
+    /// Page number (1-3; page 0 is the ballot itself)
+    pub page: u8,
+    /// Weight per option of the page
+    pub option_weights: [u64; OPTIONS_PER_PAGE],
+    /// Raw amounts per option of the page
+    pub option_amounts: [u64; OPTIONS_PER_PAGE],
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl BallotOptionsPage {
+    /// Seeds prefix: ["ballot_options", ballot_id, page]
+    pub const SEEDS_PREFIX: &'static [u8] = b"ballot_options";
+
+    /// Page holding `option`
+    pub fn page_of(option: u8) -> u8 {
+        (option as usize / OPTIONS_PER_PAGE) as u8
+    }
+
+    /// The one of `pages` holding `option`, if any
+    pub fn find<'a>(
+        pages: &'a mut [Option<&mut BallotOptionsPage>],
+        option: u8,
+    ) -> Option<&'a mut BallotOptionsPage> {
+        let number = Self::page_of(option);
+        pages
+            .iter_mut()
+            .filter_map(|page| page.as_deref_mut())
+            .find(|page| page.page == number)
+    }
+}
+
+impl Ballot {
+    /// Pages needed beyond the ballot itself
+    pub fn option_pages_required(&self) -> u8 {
+        (self.num_options.saturating_sub(1) as usize / OPTIONS_PER_PAGE) as u8
+    }
+
+    /// Tally slots (weight, amount) of `option`, on the ballot or on `page`
+    pub fn option_tally<'a>(
+        &'a mut self,
+        page: Option<&'a mut BallotOptionsPage>,
+        option: u8,
+    ) -> Result<(&'a mut u64, &'a mut u64)> {
+        require!(option < self.num_options, CloakCraftError::InvalidVoteOptionRange);
+
+        let slot = option as usize % OPTIONS_PER_PAGE;
+        match BallotOptionsPage::page_of(option) {
+            0 => Ok((&mut self.option_weights[slot], &mut self.option_amounts[slot])),
+            number => {
+                let page = page
+                    .filter(|page| page.page == number && page.ballot_id == self.ballot_id)
+                    .ok_or(CloakCraftError::BallotOptionsPageRequired)?;
+                Ok((&mut page.option_weights[slot], &mut page.option_amounts[slot]))
+            }
+        }
+    }
+
+    /// Winning option over the ballot and its pages (lowest index on tie)
+    ///
+    /// `pages` must hold every page, in order.
+    pub fn paged_winner(&self, pages: &[BallotOptionsPage]) -> Result<Option<u8>> {
+        require!(
+            pages.len() == self.option_pages_required() as usize
+                && pages
+                    .iter()
+                    .enumerate()
+                    .all(|(i, page)| page.page as usize == i + 1 && page.ballot_id == self.ballot_id),
+            CloakCraftError::BallotOptionsPageRequired
+        );
+
+        let weights = self.option_weights.iter().chain(pages.iter().flat_map(|page| page.option_weights.iter()));
+        let mut winner = None;
+        let mut max_weight = 0u64;
+        for (option, weight) in weights.take(self.num_options as usize).enumerate() {
+            if *weight > max_weight {
+                max_weight = *weight;
+                winner = Some(option as u8);
+            }
+        }
+        Ok(winner)
+    }
+
+    /// Weight of `option`, on the ballot or among `pages`
+    pub fn option_weight(&self, pages: &[BallotOptionsPage], option: u8) -> Result<u64> {
+        let slot = option as usize % OPTIONS_PER_PAGE;
+        match BallotOptionsPage::page_of(option) {
+            0 => Ok(self.option_weights[slot]),
+            number => pages
+                .iter()
+                .find(|page| page.page == number && page.ballot_id == self.ballot_id)
+                .map(|page| page.option_weights[slot])
+                .ok_or(CloakCraftError::BallotOptionsPageRequired.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(ballot_id: [u8; 32], number: u8) -> BallotOptionsPage {
+        BallotOptionsPage {
+            ballot_id,
+            page: number,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_paged_tally() {
+        let zeroed = vec![0u8; Ballot::SPACE];
+        let mut ballot = Ballot::deserialize(&mut &zeroed[8..]).unwrap();
+        ballot.ballot_id = [7u8; 32];
+        ballot.num_options = 40;
+        assert_eq!(ballot.option_pages_required(), 2);
+
+        let mut pages = [page([7u8; 32], 1), page([7u8; 32], 2)];
+
+        *ballot.option_tally(None, 3).unwrap().0 += 10;
+        // Option 20 lives on page 1
+        assert!(ballot.option_tally(None, 20).is_err());
+        assert!(ballot.option_tally(Some(&mut pages[1]), 20).is_err());
+        *ballot.option_tally(Some(&mut pages[0]), 20).unwrap().0 += 25;
+        *ballot.option_tally(Some(&mut pages[1]), 39).unwrap().0 += 25;
+        assert!(ballot.option_tally(Some(&mut pages[1]), 40).is_err());
+
+        // Ties go to the lowest index
+        assert_eq!(ballot.paged_winner(&pages).unwrap(), Some(20));
+        assert_eq!(ballot.option_weight(&pages, 39).unwrap(), 25);
+        assert!(ballot.paged_winner(&pages[..1]).is_err());
+
+        // Pages of another ballot don't count
+        let foreign = [page([8u8; 32], 1), page([7u8; 32], 2)];
+        assert!(ballot.paged_winner(&foreign).is_err());
+    }
+}
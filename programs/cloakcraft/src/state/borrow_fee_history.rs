@@ -0,0 +1,140 @@
+//! Borrow fee checkpoints (per perps pool)
+//!
+//! `update_perps_borrow_fees` only keeps the latest `cumulative_borrow_fee`
+//! of each token. When the pool's history account is passed, the first
+//! update in every hour also records all tokens' accumulators here, so
+//! clients and the indexer can price borrow fees between any two retained
+//! hours (`cumulative(later) - cumulative(earlier)`) without replaying every
+//! update. Each recorded checkpoint emits `BorrowFeeCheckpointed`, which
+//! extends the history past the ring buffer.
+
+use anchor_lang::prelude::*;
+
+use super::MAX_PERPS_TOKENS;
+
+/// Number of checkpoints kept on-chain (ring buffer, three days of hours)
+pub const MAX_BORROW_FEE_CHECKPOINTS: usize = 72;
+
+/// Seconds per checkpoint bucket
+pub const BORROW_FEE_CHECKPOINT_INTERVAL: i64 = 3600;
+
+/// Emitted for every recorded borrow fee checkpoint
+#[event]
+pub struct BorrowFeeCheckpointed {
+    pub perps_pool: Pubkey,
+    pub checkpoint_index: u64,
+    pub timestamp: i64,
+    pub cumulative_borrow_fees: [u128; MAX_PERPS_TOKENS],
+}
+
+/// Token accumulators at one point in time
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct BorrowFeeCheckpoint {
+    /// Timestamp of the update that produced the accumulators
+    pub timestamp: i64,
+
+    /// `PerpsToken::cumulative_borrow_fee` by token index
+    pub cumulative_borrow_fees: [u128; MAX_PERPS_TOKENS],
+}
+
+/// Borrow fee checkpoint history for one perps pool
+#[account]
+#[derive(InitSpace)]
+pub struct BorrowFeeHistory {
+    /// Perps pool (PDA seed)
+    pub perps_pool: Pubkey,
+
+    /// Total checkpoints recorded (next ring buffer slot = total % MAX)
+    pub total_checkpoints: u64,
+
+    /// Recent checkpoints (ring buffer)
+    pub checkpoints: [BorrowFeeCheckpoint; MAX_BORROW_FEE_CHECKPOINTS],
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Default for BorrowFeeHistory {
+    fn default() -> Self {
+        Self {
+            perps_pool: Pubkey::default(),
+            total_checkpoints: 0,
+            checkpoints: [BorrowFeeCheckpoint::default(); MAX_BORROW_FEE_CHECKPOINTS],
+            bump: 0,
+        }
+    }
+}
+
+impl BorrowFeeHistory {
+    /// Most recent checkpoint, if any
+    pub fn latest(&self) -> Option<&BorrowFeeCheckpoint> {
+        if self.total_checkpoints == 0 {
+            return None;
+        }
+        let index = ((self.total_checkpoints - 1) % MAX_BORROW_FEE_CHECKPOINTS as u64) as usize;
+        Some(&self.checkpoints[index])
+    }
+
+    /// Record a checkpoint unless one exists for `timestamp`'s hour
+    ///
+    /// Returns the recorded checkpoint's index.
+    pub fn record(
+        &mut self,
+        timestamp: i64,
+        cumulative_borrow_fees: [u128; MAX_PERPS_TOKENS],
+    ) -> Option<u64> {
+        let hour = timestamp.div_euclid(BORROW_FEE_CHECKPOINT_INTERVAL);
+        if let Some(latest) = self.latest() {
+            if hour <= latest.timestamp.div_euclid(BORROW_FEE_CHECKPOINT_INTERVAL) {
+                return None;
+            }
+        }
+
+        let checkpoint_index = self.total_checkpoints;
+        let index = (checkpoint_index % MAX_BORROW_FEE_CHECKPOINTS as u64) as usize;
+        self.checkpoints[index] = BorrowFeeCheckpoint {
+            timestamp,
+            cumulative_borrow_fees,
+        };
+        self.total_checkpoints += 1;
+
+        Some(checkpoint_index)
+    }
+
+    /// Latest retained checkpoint at or before `timestamp`
+    pub fn at(&self, timestamp: i64) -> Option<&BorrowFeeCheckpoint> {
+        let retained = self.total_checkpoints.min(MAX_BORROW_FEE_CHECKPOINTS as u64) as usize;
+        self.checkpoints[..retained]
+            .iter()
+            .filter(|c| c.timestamp <= timestamp)
+            .max_by_key(|c| c.timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hourly_checkpoints() {
+        let mut history = BorrowFeeHistory::default();
+        let fees = |n: u128| [n; MAX_PERPS_TOKENS];
+
+        assert_eq!(history.record(3_600, fees(1)), Some(0));
+        // Same hour: skipped
+        assert_eq!(history.record(7_199, fees(2)), None);
+        assert_eq!(history.record(7_200, fees(3)), Some(1));
+        assert_eq!(history.latest().unwrap().cumulative_borrow_fees, fees(3));
+
+        assert!(history.at(3_599).is_none());
+        assert_eq!(history.at(7_199).unwrap().timestamp, 3_600);
+        assert_eq!(history.at(10_000).unwrap().timestamp, 7_200);
+
+        // Oldest hours rotate out
+        for hour in 3..(MAX_BORROW_FEE_CHECKPOINTS as i64 + 3) {
+            history.record(hour * 3_600, fees(hour as u128)).unwrap();
+        }
+        assert!(history.at(10_799).is_none());
+        assert_eq!(history.at(10_800).unwrap().cumulative_borrow_fees, fees(3));
+    }
+}
@@ -0,0 +1,304 @@
+//! Protocol token buyback and staker distribution
+//!
+//! The protocol authority periodically spends `buyback_bps` of a treasury
+//! fee balance to buy the protocol token through the internal AMM
+//! (`execute_buyback`). The bought tokens stay in the protocol token pool
+//! vault and are owed to the stakers registered in the `StakeRegistry`, pro
+//! rata to their stake.
+//!
+//! Rewards use an accumulator (`reward_per_stake`, scaled by 1e18) that each
+//! buyback grows by `bought / total_stake`. Each staker carries the value it
+//! last settled at, and `distribute_buyback` pays what it is owed as a
+//! shielded note to the staker's registered stealth key.
+
+use anchor_lang::prelude::*;
+
+use crate::helpers::fixed::{apply_rate, mul_div, RATE_SCALE};
+use super::protocol_config::MAX_CONVERSION_PRICE_IMPACT_BPS;
+
+/// Maximum number of registered stakers
+pub const MAX_STAKERS: usize = 32;
+
+/// Highest share of a treasury fee balance one buyback may spend (50%)
+pub const MAX_BUYBACK_BPS: u16 = 5000;
+
+/// Protocol token buyback policy (singleton)
+#[account]
+#[derive(Default, InitSpace)]
+pub struct BuybackConfig {
+    /// Protocol token bought back and distributed
+    pub protocol_token_mint: Pubkey,
+
+    /// Share of the delegated treasury fee balance spent per buyback, in basis points
+    pub buyback_bps: u16,
+
+    /// Minimum seconds between buybacks
+    pub interval_seconds: i64,
+
+    /// Highest price impact a buyback may have, in basis points
+    pub max_price_impact_bps: u16,
+
+    /// Whether keepers may execute buybacks
+    pub is_active: bool,
+
+    /// Timestamp of the last buyback
+    pub last_buyback_at: i64,
+
+    /// Total protocol tokens bought
+    pub total_bought: u64,
+
+    /// Total protocol tokens distributed to stakers
+    pub total_distributed: u64,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl BuybackConfig {
+    /// Whether a buyback policy is in range
+    pub fn validate_policy(buyback_bps: u16, interval_seconds: i64, max_price_impact_bps: u16) -> bool {
+        (1..=MAX_BUYBACK_BPS).contains(&buyback_bps)
+            && interval_seconds > 0
+            && max_price_impact_bps <= MAX_CONVERSION_PRICE_IMPACT_BPS
+    }
+
+    /// Whether a buyback may run at `now`
+    pub fn is_due(&self, now: i64) -> bool {
+        self.is_active && now >= self.last_buyback_at.saturating_add(self.interval_seconds)
+    }
+
+    /// Bought tokens not yet distributed (held in the protocol token pool vault)
+    pub fn undistributed(&self) -> u64 {
+        self.total_bought.saturating_sub(self.total_distributed)
+    }
+}
+
+/// One registered staker (`staker == Pubkey::default()` = free slot)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct StakeEntry {
+    /// Staker wallet (withdraws the stake)
+    pub staker: Pubkey,
+
+    /// Stealth public key X that distribution notes are committed to
+    pub stealth_pub_x: [u8; 32],
+
+    /// Protocol tokens staked
+    pub stake: u64,
+
+    /// `reward_per_stake` at the last settlement
+    pub reward_checkpoint: u128,
+
+    /// Settled rewards not yet distributed
+    pub pending_reward: u64,
+}
+
+/// Protocol token stakers earning buyback distributions (singleton)
+#[account]
+#[derive(InitSpace)]
+pub struct StakeRegistry {
+    /// Token account holding the staked protocol tokens (PDA owned by the registry)
+    pub stake_vault: Pubkey,
+
+    /// Sum of all stakes
+    pub total_stake: u64,
+
+    /// Cumulative bought tokens per staked unit (scaled by 1e18)
+    pub reward_per_stake: u128,
+
+    /// Distributions made so far (nonce of the next distribution note)
+    pub distribution_nonce: u64,
+
+    /// Registered stakers
+    pub stakers: [StakeEntry; MAX_STAKERS],
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Stake vault bump
+    pub vault_bump: u8,
+}
+
+impl Default for StakeRegistry {
+    fn default() -> Self {
+        Self {
+            stake_vault: Pubkey::default(),
+            total_stake: 0,
+            reward_per_stake: 0,
+            distribution_nonce: 0,
+            stakers: [StakeEntry::default(); MAX_STAKERS],
+            bump: 0,
+            vault_bump: 0,
+        }
+    }
+}
+
+impl StakeRegistry {
+    /// Registered entry of `staker`
+    pub fn entry_mut(&mut self, staker: &Pubkey) -> Option<&mut StakeEntry> {
+        if *staker == Pubkey::default() {
+            return None;
+        }
+        self.stakers.iter_mut().find(|e| e.staker == *staker)
+    }
+
+    /// Credit `bought` tokens to the current stakers
+    ///
+    /// Returns None on overflow or with nothing staked.
+    pub fn credit(&mut self, bought: u64) -> Option<()> {
+        if self.total_stake == 0 {
+            return None;
+        }
+        let increment = mul_div(bought as u128, RATE_SCALE, self.total_stake as u128)?;
+        self.reward_per_stake = self.reward_per_stake.checked_add(increment)?;
+        Some(())
+    }
+
+    /// Settle an entry's rewards up to the current accumulator
+    fn settle(entry: &mut StakeEntry, reward_per_stake: u128) -> Option<()> {
+        let delta = reward_per_stake.checked_sub(entry.reward_checkpoint)?;
+        let earned = apply_rate(entry.stake, delta)?;
+        entry.pending_reward = entry.pending_reward.checked_add(earned)?;
+        entry.reward_checkpoint = reward_per_stake;
+        Some(())
+    }
+
+    /// Add `amount` to `staker`'s stake, registering it if new
+    ///
+    /// `stealth_pub_x` replaces the entry's distribution key. Returns None if
+    /// the registry is full or on overflow.
+    pub fn stake(&mut self, staker: Pubkey, stealth_pub_x: [u8; 32], amount: u64) -> Option<()> {
+        if staker == Pubkey::default() {
+            return None;
+        }
+        let reward_per_stake = self.reward_per_stake;
+        let total_stake = self.total_stake.checked_add(amount)?;
+        let entry = match self.stakers.iter().position(|e| e.staker == staker) {
+            Some(i) => &mut self.stakers[i],
+            None => {
+                let slot = self.stakers.iter_mut().find(|e| e.staker == Pubkey::default())?;
+                *slot = StakeEntry { staker, reward_checkpoint: reward_per_stake, ..Default::default() };
+                slot
+            }
+        };
+        Self::settle(entry, reward_per_stake)?;
+        entry.stake = entry.stake.checked_add(amount)?;
+        entry.stealth_pub_x = stealth_pub_x;
+        self.total_stake = total_stake;
+        Some(())
+    }
+
+    /// Remove `amount` from `staker`'s stake
+    ///
+    /// Earned rewards stay owed; the slot is freed once nothing is staked or
+    /// owed. Returns None for unknown stakers or amounts above the stake.
+    pub fn unstake(&mut self, staker: &Pubkey, amount: u64) -> Option<()> {
+        let reward_per_stake = self.reward_per_stake;
+        let entry = self.entry_mut(staker)?;
+        Self::settle(entry, reward_per_stake)?;
+        entry.stake = entry.stake.checked_sub(amount)?;
+        if entry.stake == 0 && entry.pending_reward == 0 {
+            *entry = StakeEntry::default();
+        }
+        self.total_stake = self.total_stake.checked_sub(amount)?;
+        Some(())
+    }
+
+    /// Take `staker`'s owed rewards for distribution
+    ///
+    /// Returns (amount, stealth_pub_x, nonce) and advances the nonce; the
+    /// slot is freed if nothing remains staked.
+    pub fn take_reward(&mut self, staker: &Pubkey) -> Option<(u64, [u8; 32], u64)> {
+        let reward_per_stake = self.reward_per_stake;
+        let nonce = self.distribution_nonce;
+        let entry = self.entry_mut(staker)?;
+        Self::settle(entry, reward_per_stake)?;
+        let amount = entry.pending_reward;
+        let stealth_pub_x = entry.stealth_pub_x;
+        entry.pending_reward = 0;
+        if entry.stake == 0 {
+            *entry = StakeEntry::default();
+        }
+        self.distribution_nonce = nonce.checked_add(1)?;
+        Some((amount, stealth_pub_x, nonce))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buyback_policy_bounds() {
+        assert!(BuybackConfig::validate_policy(1_000, 86_400, 100));
+        assert!(!BuybackConfig::validate_policy(0, 86_400, 100));
+        assert!(!BuybackConfig::validate_policy(MAX_BUYBACK_BPS + 1, 86_400, 100));
+        assert!(!BuybackConfig::validate_policy(1_000, 0, 100));
+        assert!(!BuybackConfig::validate_policy(1_000, 86_400, MAX_CONVERSION_PRICE_IMPACT_BPS + 1));
+    }
+
+    #[test]
+    fn test_buyback_schedule() {
+        let mut config = BuybackConfig { interval_seconds: 3_600, ..Default::default() };
+        assert!(!config.is_due(10_000));
+
+        config.is_active = true;
+        config.last_buyback_at = 10_000;
+        assert!(!config.is_due(13_599));
+        assert!(config.is_due(13_600));
+
+        config.total_bought = 500;
+        config.total_distributed = 120;
+        assert_eq!(config.undistributed(), 380);
+    }
+
+    #[test]
+    fn test_stake_rewards_pro_rata() {
+        let mut registry = StakeRegistry::default();
+        let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        // Nothing to credit without stakers
+        assert!(registry.credit(100).is_none());
+
+        registry.stake(alice, [1u8; 32], 300).unwrap();
+        registry.stake(bob, [2u8; 32], 100).unwrap();
+        registry.credit(400).unwrap();
+
+        // Bob joins late: only the next buyback reaches the added stake
+        registry.stake(bob, [3u8; 32], 200).unwrap();
+        registry.credit(600).unwrap();
+
+        let (amount, key, nonce) = registry.take_reward(&alice).unwrap();
+        assert_eq!((amount, key, nonce), (300 + 300, [1u8; 32], 0));
+        let (amount, key, nonce) = registry.take_reward(&bob).unwrap();
+        assert_eq!((amount, key, nonce), (100 + 300, [3u8; 32], 1));
+
+        // Nothing more until the next buyback
+        assert_eq!(registry.take_reward(&alice).unwrap().0, 0);
+    }
+
+    #[test]
+    fn test_unstake_keeps_owed_rewards() {
+        let mut registry = StakeRegistry::default();
+        let alice = Pubkey::new_unique();
+        registry.stake(alice, [1u8; 32], 100).unwrap();
+        registry.credit(50).unwrap();
+
+        assert!(registry.unstake(&alice, 101).is_none());
+        registry.unstake(&alice, 100).unwrap();
+        assert_eq!(registry.total_stake, 0);
+
+        // The slot is kept until the owed rewards are distributed
+        assert_eq!(registry.take_reward(&alice).unwrap().0, 50);
+        assert!(registry.entry_mut(&alice).is_none());
+    }
+
+    #[test]
+    fn test_registry_capacity() {
+        let mut registry = StakeRegistry::default();
+        for _ in 0..MAX_STAKERS {
+            registry.stake(Pubkey::new_unique(), [0u8; 32], 1).unwrap();
+        }
+        assert!(registry.stake(Pubkey::new_unique(), [0u8; 32], 1).is_none());
+        assert!(registry.stake(Pubkey::default(), [0u8; 32], 1).is_none());
+    }
+}
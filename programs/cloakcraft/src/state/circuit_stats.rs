@@ -0,0 +1,137 @@
+//! Circuit statistics
+//!
+//! Optional per-circuit PDA (`["circuit_stats", circuit_id]`) counting Phase 0
+//! proof verifications and rejections, and metering the compute spent in
+//! Groth16 verification. A spike in failures tagged with one client version
+//! points at a broken client release; the compute figures show which circuits
+//! are worth optimizing.
+//!
+//! Verifications are counted when the account is passed to a
+//! `create_pending_with_proof_*` instruction. A rejected proof still fails
+//! Phase 0 (rolling back any write here), so rejections are reported
+//! separately through `record_proof_failure`, which re-verifies the proof.
+//! Failure counts are advisory: anyone can report a bad proof.
+
+use anchor_lang::prelude::*;
+
+/// Emitted when a rejected proof is recorded with `record_proof_failure`
+#[event]
+pub struct CircuitProofRejected {
+    pub circuit_id: [u8; 32],
+    /// Client version the relayer submitted with the proof
+    pub client_version: u32,
+    /// Lifetime failures including this one
+    pub failures: u64,
+    pub timestamp: i64,
+}
+
+/// Circuit statistics account
+#[account]
+#[derive(InitSpace)]
+pub struct CircuitStats {
+    /// Circuit these stats belong to (verification key PDA seed)
+    pub circuit_id: [u8; 32],
+
+    /// Proofs verified
+    pub verifications: u64,
+
+    /// Proofs rejected
+    pub failures: u64,
+
+    /// Compute units spent in successful verifications
+    pub total_compute_units: u64,
+
+    /// Most compute units spent in a single verification
+    pub max_compute_units: u64,
+
+    /// Client version of the most recent rejected proof
+    pub last_failure_client_version: u32,
+
+    /// Timestamp of the most recent rejected proof (0 = none)
+    pub last_failure_at: i64,
+
+    /// Last update timestamp
+    pub updated_at: i64,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl CircuitStats {
+    /// Count a verified proof and the compute units it took
+    pub fn record_verification(&mut self, compute_units: u64, timestamp: i64) {
+        self.verifications = self.verifications.saturating_add(1);
+        self.total_compute_units = self.total_compute_units.saturating_add(compute_units);
+        self.max_compute_units = self.max_compute_units.max(compute_units);
+        self.updated_at = timestamp;
+    }
+
+    /// Count a rejected proof, returning the event to emit
+    pub fn record_failure(&mut self, client_version: u32, timestamp: i64) -> CircuitProofRejected {
+        self.failures = self.failures.saturating_add(1);
+        self.last_failure_client_version = client_version;
+        self.last_failure_at = timestamp;
+        self.updated_at = timestamp;
+
+        CircuitProofRejected {
+            circuit_id: self.circuit_id,
+            client_version,
+            failures: self.failures,
+            timestamp,
+        }
+    }
+
+    /// Mean compute units per verification (0 before the first one)
+    pub fn average_compute_units(&self) -> u64 {
+        self.total_compute_units.checked_div(self.verifications).unwrap_or(0)
+    }
+
+    /// Rejected share of all proofs seen, in basis points
+    pub fn failure_rate_bps(&self) -> u16 {
+        let total = self.verifications.saturating_add(self.failures);
+        if total == 0 {
+            return 0;
+        }
+        ((self.failures as u128 * 10_000) / total as u128) as u16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_stats_accounting() {
+        let mut s = CircuitStats {
+            circuit_id: [7u8; 32],
+            verifications: 0,
+            failures: 0,
+            total_compute_units: 0,
+            max_compute_units: 0,
+            last_failure_client_version: 0,
+            last_failure_at: 0,
+            updated_at: 0,
+            bump: 0,
+        };
+        assert_eq!(s.average_compute_units(), 0);
+        assert_eq!(s.failure_rate_bps(), 0);
+
+        s.record_verification(200_000, 10);
+        s.record_verification(240_000, 20);
+        s.record_verification(190_000, 30);
+        assert_eq!(s.verifications, 3);
+        assert_eq!(s.average_compute_units(), 210_000);
+        assert_eq!(s.max_compute_units, 240_000);
+
+        let event = s.record_failure(0x0102_0003, 40);
+        assert_eq!(event.circuit_id, [7u8; 32]);
+        assert_eq!(event.client_version, 0x0102_0003);
+        assert_eq!(event.failures, 1);
+        assert_eq!(s.last_failure_at, 40);
+        assert_eq!(s.updated_at, 40);
+        // 1 of 4 rejected
+        assert_eq!(s.failure_rate_bps(), 2_500);
+        // Rejections don't touch compute metering
+        assert_eq!(s.average_compute_units(), 210_000);
+    }
+}
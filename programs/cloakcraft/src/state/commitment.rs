@@ -15,8 +15,20 @@ use light_sdk::LightDiscriminator;
 /// Maximum encrypted note size (fixed to avoid heap allocation issues)
 /// Contains: ECIES ephemeral pubkey (64) + ciphertext (~up to 140) + tag (16) = ~220 bytes
 /// Using 250 bytes to support position notes (126 bytes plaintext) and LP notes (108 bytes)
+///
+/// This is the inline capacity of `CommitmentAccount` and the default
+/// per-pool maximum (`Pool::max_encrypted_note_size`).
 pub const MAX_ENCRYPTED_NOTE_SIZE: usize = 250;
 
+/// Protocol hard cap on a pool's encrypted note size
+/// Bytes past the inline capacity go to `CommitmentAccount::encrypted_note_overflow`;
+/// raising the cap takes a program upgrade.
+pub const ENCRYPTED_NOTE_HARD_CAP: usize = 512;
+
+/// View tag size (note discovery hint)
+/// First bytes of H(DOMAIN_VIEW_TAG, sharedSecret.x), computed by the sender
+pub const VIEW_TAG_SIZE: usize = 8;
+
 /// Commitment compressed account data
 ///
 /// Stores a note commitment in the Light Protocol state tree.
@@ -31,8 +43,10 @@ pub struct CommitmentAccount {
     /// This is also encoded in the address, but stored for easy retrieval
     pub commitment: [u8; 32],
 
-    /// Leaf index in the merkle tree (8 bytes)
-    /// Assigned sequentially per pool
+    /// Commitment index (8 bytes)
+    /// Assigned sequentially per pool by `PoolCommitmentCounter` and bound
+    /// into the note's spending nullifier. Not the position in Light's state
+    /// tree (shared by all pools), which only matters for inclusion proofs.
     pub leaf_index: u64,
 
     /// Stealth address ephemeral pubkey (64 bytes: X + Y coordinates)
@@ -46,11 +60,23 @@ pub struct CommitmentAccount {
     /// Scannable via Light Protocol API without external indexer
     pub encrypted_note: [u8; MAX_ENCRYPTED_NOTE_SIZE],
 
-    /// Actual length of encrypted note data
+    /// Actual length of encrypted note data (including `encrypted_note_overflow`)
     pub encrypted_note_len: u16,
 
     /// Timestamp when commitment was created (8 bytes)
     pub created_at: i64,
+
+    /// View tag for note discovery (8 bytes)
+    /// Scanners compare this against the tag derived from their own shared
+    /// secret and skip trial decryption on mismatch.
+    /// All zeros if the sender provided no tag (scanners must trial-decrypt).
+    /// Appended last so older accounts keep their layout.
+    pub view_tag: [u8; VIEW_TAG_SIZE],
+
+    /// Encrypted note bytes past the inline capacity (memos, multi-asset notes)
+    /// Empty unless the pool allows notes larger than `MAX_ENCRYPTED_NOTE_SIZE`.
+    /// Appended after `view_tag` so its offset stays fixed.
+    pub encrypted_note_overflow: Vec<u8>,
 }
 
 impl Default for CommitmentAccount {
@@ -63,6 +89,8 @@ impl Default for CommitmentAccount {
             encrypted_note: [0u8; MAX_ENCRYPTED_NOTE_SIZE],
             encrypted_note_len: 0,
             created_at: 0,
+            view_tag: [0u8; VIEW_TAG_SIZE],
+            encrypted_note_overflow: Vec::new(),
         }
     }
 }
@@ -74,20 +102,53 @@ impl CommitmentAccount {
     /// Maximum encrypted note size to store inline
     /// Larger notes should use off-chain storage with hash reference
     pub const MAX_INLINE_NOTE_SIZE: usize = 256;
+
+    /// Byte offset of `view_tag` in the serialized account data (after discriminator)
+    /// pool(32) + commitment(32) + leaf_index(8) + stealth_ephemeral(64)
+    ///   + encrypted_note(250) + encrypted_note_len(2) + created_at(8) = 396
+    ///
+    /// Indexers can filter on this offset to serve tag-matched notes only.
+    pub const VIEW_TAG_OFFSET: usize = 32 + 32 + 8 + 64 + MAX_ENCRYPTED_NOTE_SIZE + 2 + 8;
+
+    /// Byte offset of `leaf_index` in the serialized account data: pool(32) + commitment(32)
+    pub const LEAF_INDEX_OFFSET: usize = 32 + 32;
+
+    /// Commitment index stored in serialized account data (after discriminator)
+    ///
+    /// This is what `get_commitment_index` means everywhere (SDK, indexer,
+    /// interface crate): the value nullifiers bind, not Light's leaf position.
+    pub fn commitment_index(data: &[u8]) -> Option<u64> {
+        let bytes = data.get(Self::LEAF_INDEX_OFFSET..Self::LEAF_INDEX_OFFSET + 8)?;
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    /// Full encrypted note (inline bytes followed by the overflow)
+    pub fn full_encrypted_note(&self) -> Vec<u8> {
+        let inline = (self.encrypted_note_len as usize).min(MAX_ENCRYPTED_NOTE_SIZE);
+        let mut note = self.encrypted_note[..inline].to_vec();
+        note.extend_from_slice(&self.encrypted_note_overflow);
+        note
+    }
+
+    /// Whether the sender attached a view tag
+    pub fn has_view_tag(&self) -> bool {
+        self.view_tag != [0u8; VIEW_TAG_SIZE]
+    }
 }
 
-/// Pool commitment counter - tracks next leaf index
+/// Pool commitment counter - tracks next commitment index
 ///
 /// This is a regular PDA (not compressed) that tracks the
-/// next available leaf index for each pool. Needed for
-/// sequential leaf index assignment.
+/// next available commitment index for each pool. Every instruction that
+/// creates a `CommitmentAccount` takes its index from `allocate`, so indices
+/// are never supplied by callers and never reused.
 #[account]
 #[derive(Default, InitSpace)]
 pub struct PoolCommitmentCounter {
     /// Pool this counter belongs to
     pub pool: Pubkey,
 
-    /// Next available leaf index
+    /// Next available commitment index
     pub next_leaf_index: u64,
 
     /// Total commitments created
@@ -100,4 +161,67 @@ pub struct PoolCommitmentCounter {
 impl PoolCommitmentCounter {
     /// Seeds for PDA derivation
     pub const SEEDS_PREFIX: &'static [u8] = b"commitment_counter";
+
+    /// Take the next commitment index
+    pub fn allocate(&mut self) -> u64 {
+        let index = self.next_leaf_index;
+        self.next_leaf_index += 1;
+        self.total_commitments += 1;
+        index
+    }
+
+    /// Move the next index past indices issued outside the counter
+    ///
+    /// Forward only: an index handed out twice would give two notes the
+    /// same nullifier input.
+    pub fn reconcile(&mut self, next_index: u64) -> bool {
+        if next_index <= self.next_leaf_index {
+            return false;
+        }
+        self.next_leaf_index = next_index;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_tag_offset() {
+        let account = CommitmentAccount {
+            view_tag: [7u8; VIEW_TAG_SIZE],
+            ..Default::default()
+        };
+        let data = account.try_to_vec().unwrap();
+
+        // Followed only by the (empty) overflow's length prefix
+        assert_eq!(data.len(), CommitmentAccount::VIEW_TAG_OFFSET + VIEW_TAG_SIZE + 4);
+        assert_eq!(
+            &data[CommitmentAccount::VIEW_TAG_OFFSET..CommitmentAccount::VIEW_TAG_OFFSET + VIEW_TAG_SIZE],
+            &[7u8; VIEW_TAG_SIZE]
+        );
+        assert!(account.has_view_tag());
+        assert!(!CommitmentAccount::default().has_view_tag());
+    }
+
+    #[test]
+    fn test_commitment_index() {
+        let account = CommitmentAccount {
+            leaf_index: 0x0102_0304,
+            ..Default::default()
+        };
+        let data = account.try_to_vec().unwrap();
+        assert_eq!(CommitmentAccount::commitment_index(&data), Some(0x0102_0304));
+        assert_eq!(CommitmentAccount::commitment_index(&data[..70]), None);
+
+        let mut counter = PoolCommitmentCounter::default();
+        assert_eq!(counter.allocate(), 0);
+        assert_eq!(counter.allocate(), 1);
+        // Never moves back onto issued indices
+        assert!(!counter.reconcile(1));
+        assert!(counter.reconcile(10));
+        assert_eq!(counter.allocate(), 10);
+        assert_eq!(counter.total_commitments, 3);
+    }
 }
@@ -0,0 +1,108 @@
+//! Dust sweep ledger
+//!
+//! Singleton PDA holding lamports the protocol sets aside to subsidize dust
+//! consolidation. When a consolidation merges notes whose total is below
+//! `num_inputs * pool.min_note_amount`, at least one input was a dust note,
+//! and closing the completed operation with the ledger pays its relayer
+//! `bounty_lamports`. Each sweep spends two or three notes into one, which
+//! keeps tree growth and wallet scanning in check.
+//!
+//! Anyone can mint dust and sweep it back, so bounties are capped: the relayer
+//! must pass its `RelayerStake` with at least `RELAYER_SLASH_LAMPORTS` bonded,
+//! and is paid at most `MAX_DUST_SWEEPS_PER_EPOCH` bounties per epoch. Farming
+//! past the cap takes a fresh bonded stake per identity.
+//!
+//! The protocol authority funds the ledger with plain SOL transfers. Once the
+//! balance would drop below rent exemption, sweeps still close but go unpaid.
+
+use anchor_lang::prelude::*;
+
+/// Upper bound on the per-sweep bounty (0.01 SOL)
+pub const MAX_DUST_SWEEP_BOUNTY_LAMPORTS: u64 = 10_000_000;
+
+/// Length of a dust sweep bounty epoch (1 day)
+pub const DUST_SWEEP_EPOCH_SECONDS: i64 = 86_400;
+
+/// Bounties paid per relayer per epoch
+pub const MAX_DUST_SWEEPS_PER_EPOCH: u32 = 20;
+
+/// Emitted when a dust sweep bounty is paid
+#[event]
+pub struct DustSweepBountyPaid {
+    pub operation_id: [u8; 32],
+    pub relayer: Pubkey,
+    pub pool: Pubkey,
+    pub num_inputs: u8,
+    pub bounty_lamports: u64,
+}
+
+/// Dust sweep bounty ledger (singleton PDA)
+#[account]
+#[derive(Default, InitSpace)]
+pub struct DustSweepLedger {
+    /// Lamports paid per qualifying consolidation (0 = paused)
+    pub bounty_lamports: u64,
+
+    /// Bounties paid
+    pub total_sweeps: u64,
+
+    /// Lamports paid out
+    pub total_paid: u64,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl DustSweepLedger {
+    /// Whether a consolidation of `num_inputs` notes into `output_amount`
+    /// swept dust
+    ///
+    /// If every input were at or above `min_note_amount`, the output would be
+    /// at least `num_inputs * min_note_amount`.
+    pub fn is_dust_sweep(min_note_amount: u64, num_inputs: u8, output_amount: u64) -> bool {
+        min_note_amount > 0
+            && num_inputs >= 2
+            && (output_amount as u128) < num_inputs as u128 * min_note_amount as u128
+    }
+
+    /// Bounty the ledger can pay out of `balance` while staying rent exempt
+    pub fn payable_bounty(&self, balance: u64, rent_exempt_minimum: u64) -> u64 {
+        if balance.saturating_sub(self.bounty_lamports) < rent_exempt_minimum {
+            return 0;
+        }
+        self.bounty_lamports
+    }
+
+    /// Record a paid bounty
+    pub fn record_payment(&mut self, amount: u64) {
+        self.total_sweeps += 1;
+        self.total_paid = self.total_paid.saturating_add(amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dust_sweep_bounty() {
+        // Threshold unset: nothing qualifies
+        assert!(!DustSweepLedger::is_dust_sweep(0, 3, 1));
+        // Output below 3 * 100: some input was dust
+        assert!(DustSweepLedger::is_dust_sweep(100, 3, 299));
+        assert!(!DustSweepLedger::is_dust_sweep(100, 3, 300));
+        assert!(!DustSweepLedger::is_dust_sweep(100, 1, 50));
+        assert!(DustSweepLedger::is_dust_sweep(u64::MAX, 2, u64::MAX));
+
+        let mut ledger = DustSweepLedger {
+            bounty_lamports: 5_000,
+            ..Default::default()
+        };
+        assert_eq!(ledger.payable_bounty(1_005_000, 1_000_000), 5_000);
+        // Paying would leave the ledger below rent exemption
+        assert_eq!(ledger.payable_bounty(1_004_999, 1_000_000), 0);
+
+        ledger.record_payment(5_000);
+        assert_eq!((ledger.total_sweeps, ledger.total_paid), (1, 5_000));
+    }
+}
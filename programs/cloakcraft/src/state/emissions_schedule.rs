@@ -0,0 +1,156 @@
+//! Liquidity mining emissions schedule
+//!
+//! Streams a rewards token to LP note holders of one AMM or perps pool at a
+//! fixed rate between `start_time` and `end_time`.
+//!
+//! Rewards are tracked with a global accumulator (`reward_per_lp`, scaled by
+//! 1e18) that grows by `reward_rate * elapsed / lp_supply`. Each LP note's
+//! `RewardCheckpoint` records the accumulator value it earns from; a claim
+//! proves ownership of the note and receives `lp_amount * (current - checkpoint)`.
+
+use anchor_lang::prelude::*;
+
+use crate::helpers::fixed::{apply_rate, mul_div, RATE_SCALE};
+
+/// Which pool's LP token earns emissions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default, InitSpace)]
+pub enum EmissionsPoolKind {
+    /// Internal AMM pool (`AmmPool`)
+    #[default]
+    Amm,
+    /// Perps liquidity pool (`PerpsPool`)
+    Perps,
+}
+
+/// Emissions schedule for a single LP pool
+#[account]
+#[derive(Default, InitSpace)]
+pub struct EmissionsSchedule {
+    /// AMM or perps pool whose LP notes earn rewards (PDA seed)
+    pub source_pool: Pubkey,
+
+    /// Kind of `source_pool`
+    pub pool_kind: EmissionsPoolKind,
+
+    /// LP token mint of the source pool
+    pub lp_mint: Pubkey,
+
+    /// Rewards token mint
+    pub reward_mint: Pubkey,
+
+    /// Token account holding undistributed rewards (PDA owned by schedule)
+    pub reward_vault: Pubkey,
+
+    /// Schedule authority
+    pub authority: Pubkey,
+
+    /// Rewards emitted per second (reward token base units)
+    pub reward_rate: u64,
+
+    /// Emissions start timestamp
+    pub start_time: i64,
+
+    /// Emissions end timestamp
+    pub end_time: i64,
+
+    /// Timestamp the accumulator was last advanced to
+    pub last_update_time: i64,
+
+    /// Cumulative rewards per LP unit (scaled by 1e18)
+    pub reward_per_lp: u128,
+
+    /// Total rewards paid out to claimants
+    pub total_claimed: u64,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Reward vault bump
+    pub vault_bump: u8,
+}
+
+impl EmissionsSchedule {
+    /// Total rewards emitted over the full schedule
+    pub fn total_rewards(&self) -> Option<u64> {
+        let duration = u64::try_from(self.end_time.checked_sub(self.start_time)?).ok()?;
+        self.reward_rate.checked_mul(duration)
+    }
+
+    /// Advance the accumulator to `now` (clamped to the schedule window)
+    ///
+    /// Rewards for periods with no LP supply are not distributed.
+    pub fn accrue(&mut self, now: i64, lp_supply: u64) -> Option<()> {
+        let until = now.min(self.end_time);
+        if until <= self.last_update_time {
+            return Some(());
+        }
+        let elapsed = (until - self.last_update_time) as u128;
+
+        if lp_supply > 0 {
+            let emitted = (self.reward_rate as u128).checked_mul(elapsed)?;
+            let increment = mul_div(emitted, RATE_SCALE, lp_supply as u128)?;
+            self.reward_per_lp = self.reward_per_lp.checked_add(increment)?;
+        }
+        self.last_update_time = until;
+        Some(())
+    }
+
+    /// Rewards owed to `lp_amount` LP units checkpointed at `entry_reward_per_lp`
+    pub fn pending_reward(&self, lp_amount: u64, entry_reward_per_lp: u128) -> Option<u64> {
+        let delta = self.reward_per_lp.checked_sub(entry_reward_per_lp)?;
+        apply_rate(lp_amount, delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> EmissionsSchedule {
+        EmissionsSchedule {
+            reward_rate: 100,
+            start_time: 1_000,
+            end_time: 2_000,
+            last_update_time: 1_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_accrue_clamps_to_window() {
+        let mut s = schedule();
+        assert_eq!(s.total_rewards(), Some(100_000));
+
+        s.accrue(500, 1_000).unwrap();
+        assert_eq!(s.reward_per_lp, 0);
+
+        // 500 seconds * 100/s over 1000 LP = 50 per LP
+        s.accrue(1_500, 1_000).unwrap();
+        assert_eq!(s.pending_reward(1_000, 0), Some(50_000));
+
+        // Past the end only the remaining 500 seconds accrue
+        s.accrue(10_000, 1_000).unwrap();
+        assert_eq!(s.pending_reward(1_000, 0), Some(100_000));
+        assert_eq!(s.last_update_time, 2_000);
+    }
+
+    #[test]
+    fn test_checkpoint_limits_claim() {
+        let mut s = schedule();
+        s.accrue(1_500, 1_000).unwrap();
+        let checkpoint = s.reward_per_lp;
+        s.accrue(2_000, 1_000).unwrap();
+
+        assert_eq!(s.pending_reward(400, checkpoint), Some(20_000));
+        // A checkpoint ahead of the accumulator is invalid
+        assert_eq!(s.pending_reward(400, s.reward_per_lp + 1), None);
+    }
+
+    #[test]
+    fn test_no_accrual_without_lp_supply() {
+        let mut s = schedule();
+        s.accrue(1_500, 0).unwrap();
+        assert_eq!(s.reward_per_lp, 0);
+        assert_eq!(s.last_update_time, 1_500);
+    }
+}
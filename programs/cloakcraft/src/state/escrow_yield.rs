@@ -0,0 +1,180 @@
+//! Market escrow yield
+//!
+//! Escrowed order funds otherwise sit idle in the offer token's vault. A
+//! per-market (offer token pool) policy lets the pool authority deploy part
+//! of the opted-in escrow into a registered yield adapter. Makers opt in per
+//! order by proving their escrow amount; yield is distributed pro rata over
+//! opted-in principal and claimed as a bonus note once the order settles.
+//!
+//! Principal availability: at most `max_deploy_bps` of the opted-in principal
+//! may be deployed, and an order cannot be filled or cancelled if removing its
+//! principal would leave the deployment uncovered (recall first).
+
+use anchor_lang::prelude::*;
+
+use crate::helpers::fixed::{apply_bps, mul_div, to_u64, RATE_SCALE};
+
+/// Maximum share of opted-in principal that may be deployed (90%)
+pub const MAX_ESCROW_DEPLOY_BPS: u16 = 9_000;
+
+/// Escrow yield policy for one market (offer token pool)
+#[account]
+#[derive(Default, InitSpace)]
+pub struct EscrowYieldPolicy {
+    /// Offer token pool (PDA seed)
+    pub pool: Pubkey,
+
+    /// Registered yield adapter program
+    pub adapter_program: Pubkey,
+
+    /// Policy authority (the pool authority); deploys and recalls
+    pub authority: Pubkey,
+
+    /// Whether new opt-ins and deployments are accepted
+    pub enabled: bool,
+
+    /// Share of opted-in principal that may be deployed
+    pub max_deploy_bps: u16,
+
+    /// Principal of open opted-in orders
+    pub total_principal: u64,
+
+    /// Principal currently held by the adapter
+    pub deployed: u64,
+
+    /// Cumulative yield per unit of principal (RATE_SCALE)
+    pub yield_per_principal: u128,
+
+    /// Lifetime yield distributed to makers
+    pub total_yield: u64,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl EscrowYieldPolicy {
+    /// Maximum deployment for the current opted-in principal
+    pub fn deployable(&self) -> u64 {
+        apply_bps(self.total_principal, self.max_deploy_bps)
+    }
+
+    /// Whether the deployment is covered by the opted-in principal
+    pub fn is_covered(&self) -> bool {
+        self.deployed <= self.deployable()
+    }
+
+    /// Distribute harvested yield over the opted-in principal
+    ///
+    /// Returns the amount distributed (0 with no principal to attribute it to).
+    pub fn accrue(&mut self, yield_amount: u64) -> Option<u64> {
+        if self.total_principal == 0 || yield_amount == 0 {
+            return Some(0);
+        }
+        let increment = mul_div(yield_amount as u128, RATE_SCALE, self.total_principal as u128)?;
+        self.yield_per_principal = self.yield_per_principal.checked_add(increment)?;
+        self.total_yield = self.total_yield.checked_add(yield_amount)?;
+        Some(yield_amount)
+    }
+
+    /// Yield accrued on `principal` since `entry_yield_per_principal`
+    pub fn accrued_since(&self, principal: u64, entry_yield_per_principal: u128) -> Option<u64> {
+        let delta = self.yield_per_principal.checked_sub(entry_yield_per_principal)?;
+        to_u64(mul_div(principal as u128, delta, RATE_SCALE)?)
+    }
+}
+
+/// Escrow yield record for one opted-in order
+#[account]
+#[derive(Default, InitSpace)]
+pub struct OrderYield {
+    /// Order ID (PDA seed)
+    pub order_id: [u8; 32],
+
+    /// Escrow yield policy
+    pub policy: Pubkey,
+
+    /// Escrowed principal (proven at opt-in)
+    pub principal: u64,
+
+    /// Policy yield_per_principal at opt-in
+    pub entry_yield_per_principal: u128,
+
+    /// Claim tag: hash(yield_key, order_id)
+    pub yield_tag: [u8; 32],
+
+    /// Yield owed to the maker (set on settlement)
+    pub accrued: u64,
+
+    /// Settled on fill or cancel
+    pub settled: bool,
+
+    /// Bonus note claimed
+    pub claimed: bool,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl OrderYield {
+    /// Settle on fill/cancel: stop accruing and remove the principal
+    pub fn settle(&mut self, policy: &mut EscrowYieldPolicy) -> Option<u64> {
+        let accrued = policy.accrued_since(self.principal, self.entry_yield_per_principal)?;
+        policy.total_principal = policy.total_principal.checked_sub(self.principal)?;
+        self.accrued = accrued;
+        self.settled = true;
+        Some(accrued)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> EscrowYieldPolicy {
+        EscrowYieldPolicy { enabled: true, max_deploy_bps: 5_000, ..Default::default() }
+    }
+
+    fn opt_in(policy: &mut EscrowYieldPolicy, principal: u64) -> OrderYield {
+        policy.total_principal += principal;
+        OrderYield {
+            principal,
+            entry_yield_per_principal: policy.yield_per_principal,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_yield_pro_rata() {
+        let mut p = policy();
+        let mut a = opt_in(&mut p, 300);
+        assert_eq!(p.accrue(30), Some(30));
+        // Late opt-in does not share earlier yield
+        let mut b = opt_in(&mut p, 100);
+        assert_eq!(p.accrue(40), Some(40));
+
+        assert_eq!(a.settle(&mut p), Some(60));
+        assert_eq!(b.settle(&mut p), Some(10));
+        assert_eq!(p.total_principal, 0);
+        assert!(a.settled && b.settled);
+
+        // Nothing to attribute yield to
+        assert_eq!(p.accrue(5), Some(0));
+        assert_eq!(p.total_yield, 70);
+    }
+
+    #[test]
+    fn test_deployment_coverage() {
+        let mut p = policy();
+        let mut a = opt_in(&mut p, 1_000);
+        let _b = opt_in(&mut p, 1_000);
+        assert_eq!(p.deployable(), 1_000);
+
+        p.deployed = 600;
+        assert!(p.is_covered());
+        // Removing one order leaves 500 deployable < 600 deployed
+        a.settle(&mut p).unwrap();
+        assert!(!p.is_covered());
+        p.deployed = 500;
+        assert!(p.is_covered());
+    }
+}
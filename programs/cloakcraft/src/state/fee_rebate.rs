@@ -0,0 +1,169 @@
+//! Swap fee rebates for high-volume traders
+//!
+//! Traders may attach a blinded volume tag to swaps. The tag is
+//! `hash(volume_key, epoch)`, so volume records from different epochs are
+//! unlinkable. Swap volume (in token A units) accumulates per (tag, epoch);
+//! after the epoch ends the trader proves knowledge of `volume_key` and
+//! receives a rebate note sized by the volume tier reached.
+
+use anchor_lang::prelude::*;
+
+use crate::helpers::fixed::apply_bps;
+
+/// Maximum number of volume tiers per rebate config
+pub const MAX_REBATE_TIERS: usize = 4;
+
+/// Volume threshold and rebate rate
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct RebateTier {
+    /// Minimum epoch volume (token A base units) to reach this tier
+    pub min_volume: u64,
+
+    /// Rebate on epoch volume in basis points
+    pub rebate_bps: u16,
+}
+
+/// Fee rebate program for one AMM pool
+#[account]
+#[derive(Default, InitSpace)]
+pub struct FeeRebateConfig {
+    /// AMM pool (PDA seed)
+    pub amm_pool: Pubkey,
+
+    /// Rebate token mint (the pool's token A)
+    pub rebate_mint: Pubkey,
+
+    /// Token account holding rebate funds (PDA owned by config)
+    pub rebate_vault: Pubkey,
+
+    /// Config authority
+    pub authority: Pubkey,
+
+    /// Timestamp of epoch 0
+    pub genesis_time: i64,
+
+    /// Epoch length in seconds
+    pub epoch_duration: i64,
+
+    /// Volume tiers, ascending by `min_volume` (unused tiers are zeroed)
+    pub tiers: [RebateTier; MAX_REBATE_TIERS],
+
+    /// Total rebates paid out
+    pub total_rebated: u64,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Rebate vault bump
+    pub vault_bump: u8,
+}
+
+impl FeeRebateConfig {
+    /// Epoch index containing `timestamp` (None before genesis)
+    pub fn epoch_at(&self, timestamp: i64) -> Option<u64> {
+        if timestamp < self.genesis_time || self.epoch_duration <= 0 {
+            return None;
+        }
+        Some(((timestamp - self.genesis_time) / self.epoch_duration) as u64)
+    }
+
+    /// Whether `epoch` has fully elapsed at `timestamp`
+    pub fn is_epoch_over(&self, epoch: u64, timestamp: i64) -> bool {
+        self.epoch_at(timestamp).is_some_and(|current| epoch < current)
+    }
+
+    /// Tiers must be ascending and rebates at most 100%
+    pub fn validate_tiers(tiers: &[RebateTier; MAX_REBATE_TIERS]) -> bool {
+        let mut last_volume = 0u64;
+        for (i, tier) in tiers.iter().enumerate() {
+            if tier.rebate_bps == 0 {
+                continue;
+            }
+            if tier.rebate_bps > 10_000 || (i > 0 && tier.min_volume <= last_volume) {
+                return false;
+            }
+            last_volume = tier.min_volume;
+        }
+        true
+    }
+
+    /// Rebate rate for the highest tier reached by `volume`
+    pub fn rebate_bps_for(&self, volume: u64) -> u16 {
+        self.tiers
+            .iter()
+            .filter(|tier| tier.rebate_bps > 0 && volume >= tier.min_volume)
+            .map(|tier| tier.rebate_bps)
+            .next_back()
+            .unwrap_or(0)
+    }
+
+    /// Rebate owed for an epoch's volume
+    pub fn rebate_for(&self, volume: u64) -> u64 {
+        apply_bps(volume, self.rebate_bps_for(volume))
+    }
+}
+
+/// Swap volume accumulated by one blinded tag in one epoch
+#[account]
+#[derive(Default, InitSpace)]
+pub struct SwapVolume {
+    /// Rebate config this record belongs to
+    pub config: Pubkey,
+
+    /// Blinded volume tag: hash(volume_key, epoch)
+    pub volume_tag: [u8; 32],
+
+    /// Epoch index
+    pub epoch: u64,
+
+    /// Accumulated swap volume (token A base units)
+    pub volume: u64,
+
+    /// Whether the rebate for this record has been paid
+    pub claimed: bool,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> FeeRebateConfig {
+        let mut tiers = [RebateTier::default(); MAX_REBATE_TIERS];
+        tiers[0] = RebateTier { min_volume: 1_000, rebate_bps: 5 };
+        tiers[1] = RebateTier { min_volume: 100_000, rebate_bps: 10 };
+        tiers[2] = RebateTier { min_volume: 1_000_000, rebate_bps: 20 };
+        FeeRebateConfig {
+            genesis_time: 1_000,
+            epoch_duration: 100,
+            tiers,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_epochs() {
+        let c = config();
+        assert_eq!(c.epoch_at(999), None);
+        assert_eq!(c.epoch_at(1_000), Some(0));
+        assert_eq!(c.epoch_at(1_199), Some(1));
+        assert!(!c.is_epoch_over(1, 1_199));
+        assert!(c.is_epoch_over(1, 1_200));
+    }
+
+    #[test]
+    fn test_tier_selection() {
+        let c = config();
+        assert_eq!(c.rebate_bps_for(999), 0);
+        assert_eq!(c.rebate_bps_for(1_000), 5);
+        assert_eq!(c.rebate_bps_for(500_000), 10);
+        assert_eq!(c.rebate_for(2_000_000), 4_000);
+        assert!(FeeRebateConfig::validate_tiers(&c.tiers));
+
+        let mut bad = c.tiers;
+        bad[1].min_volume = 500;
+        assert!(!FeeRebateConfig::validate_tiers(&bad));
+    }
+}
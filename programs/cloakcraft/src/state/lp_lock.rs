@@ -0,0 +1,38 @@
+//! AMM LP lock
+//!
+//! Records when an LP commitment was minted by add liquidity (or registered
+//! with `register_lp_lock`) so remove liquidity can tell just-in-time
+//! liquidity from passive liquidity. Keyed by the LP commitment, which is
+//! public in both proofs; closed on removal.
+
+use anchor_lang::prelude::*;
+
+/// Lock on one LP commitment
+#[account]
+#[derive(Default, InitSpace)]
+pub struct LpLock {
+    /// AMM pool the LP tokens belong to
+    pub amm_pool: Pubkey,
+
+    /// LP commitment being locked
+    pub lp_commitment: [u8; 32],
+
+    /// Slot the lock was recorded at (add liquidity Phase 0 or registration)
+    pub created_slot: u64,
+
+    /// First slot removal is penalty-free
+    pub unlock_slot: u64,
+
+    /// Paid the rent; refunded when the lock is closed
+    pub payer: Pubkey,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl LpLock {
+    /// Whether removal at `slot` falls inside the lock window
+    pub fn is_locked(&self, slot: u64) -> bool {
+        slot < self.unlock_slot
+    }
+}
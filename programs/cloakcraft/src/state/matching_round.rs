@@ -0,0 +1,212 @@
+//! Quadratic funding matching rounds
+//!
+//! Donors send shielded notes to projects. Each donation also contributes an
+//! encrypted one-hot vector of `sqrt(amount)` to the round's homomorphic tally
+//! (same ElGamal scheme as encrypted ballots), so neither the project nor the
+//! amount of an individual donation is revealed. After the round ends the
+//! tally is decrypted into per-project sqrt sums and the matching pool is
+//! split proportionally to `sqrt_sum^2`.
+
+use anchor_lang::prelude::*;
+
+use crate::helpers::fixed::{mul_div, to_u64};
+use crate::state::ELGAMAL_CIPHERTEXT_SIZE;
+
+/// Maximum number of projects per round (matches the ballot tally width)
+pub const MAX_ROUND_PROJECTS: usize = 16;
+
+/// Matching round lifecycle
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, InitSpace, Debug)]
+pub enum RoundStatus {
+    /// Accepting donations until `end_time`
+    #[default]
+    Active,
+    /// Tally decrypted, matching amounts fixed and claimable
+    Tallied,
+}
+
+/// Quadratic funding round
+#[account]
+#[derive(InitSpace)]
+pub struct MatchingRound {
+    /// Round identifier (PDA seed)
+    pub round_id: [u8; 32],
+
+    /// Round authority
+    pub authority: Pubkey,
+
+    /// Donation and matching token mint
+    pub token_mint: Pubkey,
+
+    /// Token account holding the matching pool (PDA owned by round)
+    pub matching_vault: Pubkey,
+
+    /// Matching pool size
+    pub matching_pool: u64,
+
+    /// Number of projects
+    pub num_projects: u8,
+
+    /// Token accounts receiving each project's matching payout
+    pub project_recipients: [Pubkey; MAX_ROUND_PROJECTS],
+
+    /// ElGamal public key donations are encrypted to
+    pub tally_pubkey: [u8; 32],
+
+    /// Threshold committee allowed to submit the decrypted tally
+    /// (Pubkey::default() = anyone holding the decryption key)
+    pub committee: Pubkey,
+
+    /// Donations accepted from
+    pub start_time: i64,
+
+    /// Donations accepted until
+    pub end_time: i64,
+
+    /// Homomorphic tally of sqrt(donation) per project
+    pub encrypted_tally: [[u8; ELGAMAL_CIPHERTEXT_SIZE]; MAX_ROUND_PROJECTS],
+
+    /// Decrypted sum of sqrt(donation) per project
+    pub sqrt_sums: [u64; MAX_ROUND_PROJECTS],
+
+    /// Matching allocated to each project
+    pub matching_amounts: [u64; MAX_ROUND_PROJECTS],
+
+    /// Bitmask of projects that have claimed matching
+    pub claimed_mask: u16,
+
+    /// Number of donations
+    pub donation_count: u64,
+
+    /// Round status
+    pub status: RoundStatus,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Matching vault bump
+    pub vault_bump: u8,
+}
+
+impl Default for MatchingRound {
+    fn default() -> Self {
+        Self {
+            round_id: [0u8; 32],
+            authority: Pubkey::default(),
+            token_mint: Pubkey::default(),
+            matching_vault: Pubkey::default(),
+            matching_pool: 0,
+            num_projects: 0,
+            project_recipients: [Pubkey::default(); MAX_ROUND_PROJECTS],
+            tally_pubkey: [0u8; 32],
+            committee: Pubkey::default(),
+            start_time: 0,
+            end_time: 0,
+            encrypted_tally: [[0u8; ELGAMAL_CIPHERTEXT_SIZE]; MAX_ROUND_PROJECTS],
+            sqrt_sums: [0u64; MAX_ROUND_PROJECTS],
+            matching_amounts: [0u64; MAX_ROUND_PROJECTS],
+            claimed_mask: 0,
+            donation_count: 0,
+            status: RoundStatus::Active,
+            bump: 0,
+            vault_bump: 0,
+        }
+    }
+}
+
+/// Matching round creation parameters
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MatchingRoundConfigInput {
+    /// Matching pool funded from the authority at creation
+    pub matching_pool: u64,
+    /// Token accounts receiving each project's matching payout
+    pub project_recipients: Vec<Pubkey>,
+    /// ElGamal public key donations are encrypted to
+    /// (must equal the committee's threshold key when a committee is set)
+    pub tally_pubkey: [u8; 32],
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+impl MatchingRound {
+    /// Whether donations are accepted at `current_time`
+    pub fn is_active(&self, current_time: i64) -> bool {
+        self.status == RoundStatus::Active
+            && current_time >= self.start_time
+            && current_time < self.end_time
+    }
+
+    /// Whether a project's matching has been claimed
+    pub fn is_claimed(&self, project_index: u8) -> bool {
+        self.claimed_mask & (1 << project_index) != 0
+    }
+
+    /// Allocate the matching pool quadratically
+    ///
+    /// match_p = pool * sqrt_sum_p^2 / sum(sqrt_sum^2). Rounding dust stays in
+    /// the vault. Returns None on overflow.
+    pub fn compute_matching(&self) -> Option<[u64; MAX_ROUND_PROJECTS]> {
+        let n = self.num_projects as usize;
+        let mut squares = [0u128; MAX_ROUND_PROJECTS];
+        let mut total = 0u128;
+        for (square, sqrt_sum) in squares.iter_mut().zip(&self.sqrt_sums[..n]) {
+            *square = (*sqrt_sum as u128).checked_mul(*sqrt_sum as u128)?;
+            total = total.checked_add(*square)?;
+        }
+
+        let mut amounts = [0u64; MAX_ROUND_PROJECTS];
+        if total == 0 {
+            return Some(amounts);
+        }
+        for (amount, square) in amounts.iter_mut().zip(&squares[..n]) {
+            *amount = to_u64(mul_div(self.matching_pool as u128, *square, total)?)?;
+        }
+        Some(amounts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round(sqrt_sums: &[u64]) -> MatchingRound {
+        let mut r = MatchingRound {
+            matching_pool: 1_000_000,
+            num_projects: sqrt_sums.len() as u8,
+            ..Default::default()
+        };
+        r.sqrt_sums[..sqrt_sums.len()].copy_from_slice(sqrt_sums);
+        r
+    }
+
+    #[test]
+    fn test_quadratic_allocation() {
+        // Project 0: 100 donors of 1 (sqrt sum 100), project 1: one donor of 10_000 (sqrt sum 100)
+        let amounts = round(&[100, 100]).compute_matching().unwrap();
+        assert_eq!(amounts[0], 500_000);
+        assert_eq!(amounts[1], 500_000);
+
+        // Breadth of support wins: sqrt sums 30 vs 10 -> 900:100
+        let amounts = round(&[30, 10, 0]).compute_matching().unwrap();
+        assert_eq!(amounts[0], 900_000);
+        assert_eq!(amounts[1], 100_000);
+        assert_eq!(amounts[2], 0);
+    }
+
+    #[test]
+    fn test_no_donations_and_rounding() {
+        assert_eq!(round(&[0, 0]).compute_matching().unwrap(), [0u64; MAX_ROUND_PROJECTS]);
+
+        let amounts = round(&[1, 1, 1]).compute_matching().unwrap();
+        assert_eq!(amounts[..3], [333_333, 333_333, 333_333]);
+    }
+
+    #[test]
+    fn test_claimed_mask() {
+        let mut r = round(&[1, 1]);
+        assert!(!r.is_claimed(1));
+        r.claimed_mask |= 1 << 1;
+        assert!(r.is_claimed(1));
+        assert!(!r.is_claimed(0));
+    }
+}
@@ -1,8 +1,10 @@
 //! CloakCraft state accounts
 
 pub mod pool;
+pub mod pool_stats;
 pub mod order;
 pub mod amm_pool;
+pub mod amm_pool_migration;
 pub mod verification_key;
 pub mod adapt_module;
 pub mod committee;
@@ -11,14 +13,42 @@ pub mod commitment;
 pub mod light_types;
 pub mod pending_operation;
 pub mod protocol_config;
+pub mod program_version;
 pub mod perps_pool;
 pub mod perps_market;
+pub mod perps_pool_migration;
+pub mod perp_order;
 pub mod ballot;
+pub mod ballot_options_page;
 pub mod position_meta;
+pub mod emissions_schedule;
+pub mod fee_rebate;
+pub mod matching_round;
+pub mod root_checkpoint;
+pub mod operation_cost;
+pub mod lp_lock;
+pub mod reward_checkpoint;
+pub mod pool_creator_allowlist;
+pub mod escrow_yield;
+pub mod admin_action;
+pub mod payment_receipt;
+pub mod root_registry;
+pub mod circuit_stats;
+pub mod snapshot_tree;
+pub mod borrow_fee_history;
+pub mod dust_sweep_ledger;
+pub mod relayer_allowlist;
+pub mod relayer_stake;
+pub mod buyback;
+pub mod savings_vault;
+pub mod operation_credit;
+pub mod tree_registry;
 
 pub use pool::*;
+pub use pool_stats::*;
 pub use order::*;
 pub use amm_pool::*;
+pub use amm_pool_migration::*;
 pub use verification_key::*;
 pub use adapt_module::*;
 pub use committee::*;
@@ -27,7 +57,33 @@ pub use commitment::*;
 pub use light_types::*;
 pub use pending_operation::*;
 pub use protocol_config::*;
+pub use program_version::*;
 pub use perps_pool::*;
 pub use perps_market::*;
+pub use perps_pool_migration::*;
+pub use perp_order::*;
 pub use ballot::*;
+pub use ballot_options_page::*;
 pub use position_meta::*;
+pub use emissions_schedule::*;
+pub use fee_rebate::*;
+pub use matching_round::*;
+pub use root_checkpoint::*;
+pub use operation_cost::*;
+pub use lp_lock::*;
+pub use reward_checkpoint::*;
+pub use pool_creator_allowlist::*;
+pub use escrow_yield::*;
+pub use admin_action::*;
+pub use payment_receipt::*;
+pub use root_registry::*;
+pub use circuit_stats::*;
+pub use snapshot_tree::*;
+pub use borrow_fee_history::*;
+pub use dust_sweep_ledger::*;
+pub use relayer_allowlist::*;
+pub use relayer_stake::*;
+pub use buyback::*;
+pub use savings_vault::*;
+pub use operation_credit::*;
+pub use tree_registry::*;
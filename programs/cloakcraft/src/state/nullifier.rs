@@ -7,10 +7,127 @@
 //! Each type has its own namespace to avoid collisions:
 //! - Spend: ["spend_nullifier", pool, nullifier]
 //! - Action: ["action_nullifier", aggregation, nullifier]
+//!
+//! Nullifier domains added after launch get a domain tag after the prefix
+//! (see `NullifierDomain::seed_tag`):
+//! - ["spend_nullifier", [domain], pool, nullifier]
+//! - ["action_nullifier", [domain], aggregation, nullifier]
 
 use anchor_lang::prelude::*;
 use light_sdk::LightDiscriminator;
 
+use crate::constants::circuits;
+
+/// Nullifier domain, bound to the circuit that produced it
+///
+/// All note spends share `Spend`, so a note is spent at most once whether it
+/// goes into a transfer, swap, LP deposit, perps margin, donation or vote_spend.
+/// Position and vote nullifiers live in their own domains and can never
+/// collide with, or be replayed as, a note spend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NullifierDomain {
+    /// Note spends (spend nullifier namespace)
+    Spend,
+    /// Perps position notes: close / liquidate (spend nullifier namespace)
+    PerpsPosition,
+    /// One vote per ballot: snapshot vote / change vote (action namespace)
+    Vote,
+    /// SpendToVote positions: change vote / close / claim (action namespace)
+    VotePosition,
+}
+
+/// Circuit registry: the nullifier domain each circuit's nullifiers belong to
+///
+/// Phase 0 records the domain of the circuit whose verification key checked
+/// the proof (`PendingOperation::bind_circuit`), and Phase 2 creates the
+/// nullifiers in that domain. Circuits without nullifier inputs are absent.
+const CIRCUIT_DOMAINS: &[([u8; 32], NullifierDomain)] = &[
+    (circuits::TRANSFER_1X2, NullifierDomain::Spend),
+    (circuits::TRANSFER_1X2_DENOM, NullifierDomain::Spend),
+    (circuits::TRANSFER_1X2_INVOKE, NullifierDomain::Spend),
+    (circuits::TRANSFER_1X2_NFT, NullifierDomain::Spend),
+    (circuits::CONSOLIDATE_3X1, NullifierDomain::Spend),
+    (circuits::ADAPTER_1X1, NullifierDomain::Spend),
+    (circuits::ADAPTER_1X2, NullifierDomain::Spend),
+    (circuits::MARKET_ORDER_CREATE, NullifierDomain::Spend),
+    (circuits::MARKET_ORDER_FILL, NullifierDomain::Spend),
+    (circuits::MARKET_ORDER_CANCEL, NullifierDomain::Spend),
+    (circuits::SWAP_ADD_LIQUIDITY, NullifierDomain::Spend),
+    (circuits::SWAP_REMOVE_LIQUIDITY, NullifierDomain::Spend),
+    (circuits::SWAP_SWAP, NullifierDomain::Spend),
+    (circuits::SWAP_SEALED, NullifierDomain::Spend),
+    (circuits::SWAP_COMMITTED, NullifierDomain::Spend),
+    (circuits::SWAP_CONVERT_LP, NullifierDomain::Spend),
+    (circuits::PERPS_OPEN_POSITION, NullifierDomain::Spend),
+    (circuits::PERPS_OPEN_POSITION_SEALED, NullifierDomain::Spend),
+    (circuits::PERPS_ADD_LIQUIDITY, NullifierDomain::Spend),
+    (circuits::PERPS_REMOVE_LIQUIDITY, NullifierDomain::Spend),
+    (circuits::PERPS_CONVERT_LP, NullifierDomain::Spend),
+    (circuits::VOTE_SPEND, NullifierDomain::Spend),
+    (circuits::CLAIM_REWARDS, NullifierDomain::Spend),
+    (circuits::DONATE, NullifierDomain::Spend),
+    (circuits::PERPS_CLOSE_POSITION, NullifierDomain::PerpsPosition),
+    (circuits::PERPS_LIQUIDATE, NullifierDomain::PerpsPosition),
+    (circuits::PERPS_TRANSFER_POSITION, NullifierDomain::PerpsPosition),
+    (circuits::VOTE_SNAPSHOT, NullifierDomain::Vote),
+    (circuits::VOTE_TWAB, NullifierDomain::Vote),
+    (circuits::CHANGE_VOTE_SNAPSHOT, NullifierDomain::Vote),
+    (circuits::CHANGE_VOTE_SPEND, NullifierDomain::VotePosition),
+    (circuits::CLOSE_POSITION, NullifierDomain::VotePosition),
+    (circuits::CLAIM, NullifierDomain::VotePosition),
+    (circuits::CLAIM_MULTI, NullifierDomain::VotePosition),
+    (circuits::CLAIM_REFUND, NullifierDomain::VotePosition),
+];
+
+impl NullifierDomain {
+    /// Registered domain of a circuit's nullifiers, `None` for circuits without nullifiers
+    pub fn for_circuit(circuit_id: &[u8; 32]) -> Option<Self> {
+        CIRCUIT_DOMAINS
+            .iter()
+            .find(|(id, _)| id == circuit_id)
+            .map(|(_, domain)| *domain)
+    }
+
+    /// Stored form (0 is reserved for "no domain")
+    pub fn code(self) -> u8 {
+        match self {
+            Self::Spend => 1,
+            Self::PerpsPosition => 2,
+            Self::Vote => 3,
+            Self::VotePosition => 4,
+        }
+    }
+
+    /// Domain from its stored form
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(Self::Spend),
+            2 => Some(Self::PerpsPosition),
+            3 => Some(Self::Vote),
+            4 => Some(Self::VotePosition),
+            _ => None,
+        }
+    }
+
+    /// Whether nullifiers of this domain are action nullifiers (ballot-scoped)
+    pub fn is_action(self) -> bool {
+        matches!(self, Self::Vote | Self::VotePosition)
+    }
+
+    /// Address seed tag, `None` for domains that predate tagging
+    ///
+    /// Every current domain already has nullifiers on chain at the untagged
+    /// address (closed positions, claimed ballots), so moving one to a tagged
+    /// address would let those be nullified again. Their separation comes
+    /// from the circuits (distinct nullifier preimages). Domains added later
+    /// must use a new, unused tag here.
+    pub fn seed_tag(self) -> Option<u8> {
+        match self {
+            Self::Spend | Self::PerpsPosition | Self::Vote | Self::VotePosition => None,
+        }
+    }
+}
+
 /// Spend Nullifier compressed account data
 ///
 /// Created when a note is spent in a transact operation.
@@ -49,3 +166,51 @@ impl ActionNullifierAccount {
     pub const SEED_PREFIX: &'static [u8] = b"action_nullifier";
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nullifier_domains() {
+        // Every note-consuming circuit shares the spend domain
+        for circuit in [
+            circuits::TRANSFER_1X2,
+            circuits::SWAP_SWAP,
+            circuits::SWAP_CONVERT_LP,
+            circuits::PERPS_OPEN_POSITION,
+            circuits::VOTE_SPEND,
+            circuits::DONATE,
+        ] {
+            assert_eq!(NullifierDomain::for_circuit(&circuit), Some(NullifierDomain::Spend));
+        }
+        assert_eq!(
+            NullifierDomain::for_circuit(&circuits::PERPS_CLOSE_POSITION),
+            Some(NullifierDomain::PerpsPosition)
+        );
+        // Transferring a position spends it in the same domain as closing it
+        assert_eq!(
+            NullifierDomain::for_circuit(&circuits::PERPS_TRANSFER_POSITION),
+            Some(NullifierDomain::PerpsPosition)
+        );
+        assert_eq!(
+            NullifierDomain::for_circuit(&circuits::CLAIM),
+            Some(NullifierDomain::VotePosition)
+        );
+        assert_eq!(NullifierDomain::for_circuit(&circuits::FEE_REBATE_CLAIM), None);
+
+        assert!(NullifierDomain::Vote.is_action());
+        assert!(!NullifierDomain::PerpsPosition.is_action());
+        for domain in [
+            NullifierDomain::Spend,
+            NullifierDomain::PerpsPosition,
+            NullifierDomain::Vote,
+            NullifierDomain::VotePosition,
+        ] {
+            assert_eq!(NullifierDomain::from_code(domain.code()), Some(domain));
+            // Existing domains keep their pre-tagging addresses
+            assert_eq!(domain.seed_tag(), None);
+        }
+        assert_eq!(NullifierDomain::from_code(0), None);
+    }
+}
@@ -0,0 +1,241 @@
+//! Compute and rent estimates for multi-phase operations
+//!
+//! Relayers size compute budgets and priority fees per transaction. The
+//! per-phase figures below are measured upper bounds (localnet, Light V2 trees)
+//! and are returned by `estimate_operation_cost` so relayers don't have to
+//! hardcode them. Rent covers the PendingOperation PDA only; Light network fees
+//! for compressed accounts are charged separately by the Light system program.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::operation_types;
+use crate::helpers::fixed::apply_bps;
+use super::pending_operation::{MAX_INPUTS, MAX_PENDING_COMMITMENTS};
+
+/// Phase 0: PendingOperation init + bookkeeping (excluding proof verification)
+pub const PENDING_CREATE_CU: u32 = 50_000;
+
+/// Phase 0: Groth16 verification (including the single-pair pairing that
+/// checks proof B is in the G2 subgroup)
+pub const PROOF_VERIFY_CU: u32 = 300_000;
+
+/// Phase 0: extra public inputs and oracle reads for perps circuits
+pub const PERPS_PROOF_EXTRA_CU: u32 = 150_000;
+
+/// Phase 1: Light inclusion proof CPI (per input)
+pub const VERIFY_COMMITMENT_CU: u32 = 150_000;
+
+/// Phase 2: Light address creation CPI (per nullifier)
+pub const CREATE_NULLIFIER_CU: u32 = 150_000;
+
+/// Phase 4: Light compressed account creation CPI (per output)
+pub const CREATE_COMMITMENT_CU: u32 = 100_000;
+
+/// Final: close_pending_operation
+pub const CLOSE_PENDING_CU: u32 = 15_000;
+
+/// Headroom added to every phase estimate (20%)
+pub const COMPUTE_HEADROOM_BPS: u16 = 2_000;
+
+/// Solana per-transaction compute limit
+pub const MAX_TRANSACTION_CU: u32 = 1_400_000;
+
+/// Operation shape to estimate
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OperationShape {
+    /// Operation type (see `constants::operation_types`)
+    pub operation_type: u8,
+    /// Input notes (one Phase 1 + one Phase 2 transaction each)
+    pub num_inputs: u8,
+    /// Output commitments (one Phase 4 transaction each)
+    pub num_outputs: u8,
+}
+
+/// Cost of one phase
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PhaseCost {
+    /// Compute unit limit to request per transaction (includes headroom)
+    pub compute_unit_limit: u32,
+    /// Number of transactions for this phase
+    pub transactions: u8,
+    /// Rent paid by the relayer in this phase
+    pub rent_lamports: u64,
+}
+
+/// Per-phase cost estimate for an operation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OperationCostEstimate {
+    /// Phase 0: create_pending_with_proof_*
+    pub proof: PhaseCost,
+    /// Phase 1: verify_commitment_exists
+    pub verify_commitments: PhaseCost,
+    /// Phase 2: create_nullifier_and_pending
+    pub nullifiers: PhaseCost,
+    /// Phase 3: execute_* (zero transactions if the operation has none)
+    pub execute: PhaseCost,
+    /// Phase 4: create_commitment
+    pub commitments: PhaseCost,
+    /// Final: close_pending_operation
+    pub close: PhaseCost,
+    /// Sum of compute unit limits over all transactions
+    pub total_compute_units: u64,
+    /// Total number of transactions
+    pub total_transactions: u8,
+    /// Rent returned on close to the rent refund recipient (if one is set)
+    pub rent_refund_lamports: u64,
+    /// Rent returned on close to the relayer
+    pub relayer_rent_lamports: u64,
+}
+
+/// Phase 0 compute for an operation type, None for unknown types
+fn proof_cu(operation_type: u8) -> Option<u32> {
+    execute_cu(operation_type)?;
+    let extra = match operation_type {
+        operation_types::PERPS_OPEN_POSITION
+        | operation_types::PERPS_CLOSE_POSITION
+        | operation_types::PERPS_LIQUIDATE
+        | operation_types::PERPS_ADD_LIQUIDITY
+        | operation_types::PERPS_REMOVE_LIQUIDITY
+        | operation_types::PERPS_TRANSFER_POSITION
+        | operation_types::PERPS_CONVERT_LP => PERPS_PROOF_EXTRA_CU,
+        _ => 0,
+    };
+    Some(PENDING_CREATE_CU + PROOF_VERIFY_CU + extra)
+}
+
+/// Phase 3 compute for an operation type (0 = no execute phase), None for unknown types
+fn execute_cu(operation_type: u8) -> Option<u32> {
+    match operation_type {
+        // Optional unshield
+        operation_types::TRANSFER => Some(60_000),
+        operation_types::SWAP
+        | operation_types::ADD_LIQUIDITY
+        | operation_types::REMOVE_LIQUIDITY => Some(120_000),
+        operation_types::CONSOLIDATE
+        | operation_types::AMM_CONVERT_LP
+        | operation_types::PERPS_TRANSFER_POSITION
+        | operation_types::PERPS_CONVERT_LP => Some(0),
+        // Oracle reads + position meta CPI
+        operation_types::PERPS_OPEN_POSITION
+        | operation_types::PERPS_CLOSE_POSITION
+        | operation_types::PERPS_LIQUIDATE
+        | operation_types::PERPS_ADD_LIQUIDITY
+        | operation_types::PERPS_REMOVE_LIQUIDITY => Some(250_000),
+        // Encrypted tally updates
+        operation_types::VOTE_SNAPSHOT
+        | operation_types::VOTE_SPEND
+        | operation_types::CLOSE_VOTE_POSITION => Some(100_000),
+        operation_types::CHANGE_VOTE_SNAPSHOT
+        | operation_types::CHANGE_VOTE_SPEND
+        | operation_types::DONATE => Some(150_000),
+        operation_types::CLAIM
+        | operation_types::CLAIM_FEE_REBATE
+        | operation_types::CLAIM_ESCROW_YIELD => Some(80_000),
+        operation_types::CLAIM_REWARDS => Some(100_000),
+        operation_types::VAULT_DEPOSIT
+        | operation_types::VAULT_WITHDRAW => Some(60_000),
+        _ => None,
+    }
+}
+
+/// Compute unit limit for one transaction, with headroom
+fn with_headroom(compute_units: u32) -> u32 {
+    compute_units
+        .saturating_add(apply_bps(compute_units as u64, COMPUTE_HEADROOM_BPS) as u32)
+        .min(MAX_TRANSACTION_CU)
+}
+
+fn phase(compute_units: u32, transactions: u8) -> PhaseCost {
+    PhaseCost {
+        compute_unit_limit: if transactions == 0 { 0 } else { with_headroom(compute_units) },
+        transactions,
+        rent_lamports: 0,
+    }
+}
+
+impl OperationCostEstimate {
+    /// Estimate an operation's cost
+    ///
+    /// `pending_rent` is the PendingOperation rent, `rent_refund_bps` the
+    /// ProtocolConfig split applied on close. Returns None for unknown
+    /// operation types or shapes the PendingOperation can't hold.
+    pub fn estimate(shape: &OperationShape, pending_rent: u64, rent_refund_bps: u16) -> Option<Self> {
+        if shape.num_inputs as usize > MAX_INPUTS
+            || shape.num_outputs == 0
+            || shape.num_outputs as usize > MAX_PENDING_COMMITMENTS
+        {
+            return None;
+        }
+
+        let execute = execute_cu(shape.operation_type)?;
+        let mut proof = phase(proof_cu(shape.operation_type)?, 1);
+        proof.rent_lamports = pending_rent;
+
+        let mut estimate = Self {
+            proof,
+            verify_commitments: phase(VERIFY_COMMITMENT_CU, shape.num_inputs),
+            nullifiers: phase(CREATE_NULLIFIER_CU, shape.num_inputs),
+            execute: phase(execute, (execute > 0) as u8),
+            commitments: phase(CREATE_COMMITMENT_CU, shape.num_outputs),
+            close: phase(CLOSE_PENDING_CU, 1),
+            rent_refund_lamports: apply_bps(pending_rent, rent_refund_bps),
+            ..Default::default()
+        };
+        estimate.relayer_rent_lamports = pending_rent - estimate.rent_refund_lamports;
+
+        for p in estimate.phases() {
+            estimate.total_compute_units += p.compute_unit_limit as u64 * p.transactions as u64;
+            estimate.total_transactions += p.transactions;
+        }
+
+        Some(estimate)
+    }
+
+    /// Phases in execution order
+    pub fn phases(&self) -> [PhaseCost; 6] {
+        [
+            self.proof,
+            self.verify_commitments,
+            self.nullifiers,
+            self.execute,
+            self.commitments,
+            self.close,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shape(operation_type: u8, num_inputs: u8, num_outputs: u8) -> OperationShape {
+        OperationShape { operation_type, num_inputs, num_outputs }
+    }
+
+    #[test]
+    fn test_estimate_operation_cost() {
+        let transfer = OperationCostEstimate::estimate(&shape(operation_types::TRANSFER, 1, 2), 1_000_000, 2_500).unwrap();
+        assert_eq!(transfer.total_transactions, 1 + 1 + 1 + 1 + 2 + 1);
+        assert_eq!(transfer.proof.compute_unit_limit, 420_000);
+        assert_eq!(transfer.proof.rent_lamports, 1_000_000);
+        assert_eq!(transfer.commitments.compute_unit_limit, 120_000);
+        assert_eq!(transfer.rent_refund_lamports, 250_000);
+        assert_eq!(transfer.relayer_rent_lamports, 750_000);
+
+        // Consolidation skips Phase 3
+        let consolidate = OperationCostEstimate::estimate(&shape(operation_types::CONSOLIDATE, 3, 1), 0, 0).unwrap();
+        assert_eq!(consolidate.execute, PhaseCost::default());
+        assert_eq!(consolidate.total_transactions, 1 + 3 + 3 + 1 + 1);
+
+        // Perps proofs cost more
+        let perps = OperationCostEstimate::estimate(&shape(operation_types::PERPS_OPEN_POSITION, 1, 2), 0, 0).unwrap();
+        assert!(perps.proof.compute_unit_limit > transfer.proof.compute_unit_limit);
+        assert!(perps.proof.compute_unit_limit <= MAX_TRANSACTION_CU);
+
+        // Unknown types and oversized shapes
+        assert!(OperationCostEstimate::estimate(&shape(99, 1, 2), 0, 0).is_none());
+        assert!(OperationCostEstimate::estimate(&shape(operation_types::TRANSFER, 5, 2), 0, 0).is_none());
+        assert!(OperationCostEstimate::estimate(&shape(operation_types::TRANSFER, 1, 9), 0, 0).is_none());
+        assert!(OperationCostEstimate::estimate(&shape(operation_types::TRANSFER, 1, 0), 0, 0).is_none());
+    }
+}
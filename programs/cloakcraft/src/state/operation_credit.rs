@@ -0,0 +1,300 @@
+//! Anonymous operation credits (per-user rate limiting)
+//!
+//! Each epoch, users obtain operation credits either against protocol token
+//! stake (up to `credits_per_epoch` per staker) or for `fee_lamports` each.
+//! A credit is a leaf `Poseidon(OPERATION_CREDIT, secret)` appended to the
+//! epoch's credit tree: the issuer sees only the leaf. Spending a credit
+//! proves membership in the tree and reveals
+//! `Poseidon(CREDIT_NULLIFIER, secret)` bound to one operation ID, so a spent
+//! credit cannot be linked to the issuance it came from (within the epoch's
+//! credits) and cannot be spent twice.
+//!
+//! While `ProtocolConfig::require_operation_credits` is set, every Phase 0
+//! requires a credit spent for its operation ID. Credits expire with their epoch, after which spent
+//! credit accounts can be closed.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::MERKLE_TREE_DEPTH;
+use crate::errors::CloakCraftError;
+use crate::merkle;
+use crate::state::ProtocolConfig;
+
+/// Number of roots a credit tree keeps on-chain (ring buffer)
+pub const MAX_CREDIT_ROOTS: usize = 64;
+
+/// Maximum credits issued per instruction
+pub const MAX_CREDITS_PER_ISSUE: usize = 8;
+
+/// Emitted for every batch of credits issued
+#[event]
+pub struct OperationCreditsIssued {
+    pub epoch: u64,
+    /// Leaf index of the first issued credit
+    pub first_leaf_index: u32,
+    pub leaves: Vec<[u8; 32]>,
+    /// Credit tree root after the append
+    pub root: [u8; 32],
+    /// Issued against stake (otherwise bought)
+    pub stake_backed: bool,
+}
+
+/// Emitted when a credit is spent for an operation
+#[event]
+pub struct OperationCreditConsumed {
+    pub epoch: u64,
+    pub credit_nullifier: [u8; 32],
+    pub operation_id: [u8; 32],
+}
+
+/// Operation credit policy (singleton)
+#[account]
+#[derive(Default, InitSpace)]
+pub struct CreditConfig {
+    /// Credit epoch length (fixed at initialization)
+    pub epoch_seconds: i64,
+
+    /// Credits a staker may obtain per epoch (0 = no stake-backed credits)
+    pub credits_per_epoch: u16,
+
+    /// Protocol token stake required for stake-backed credits
+    pub min_stake: u64,
+
+    /// Lamports paid to the treasury per bought credit (0 = not sold)
+    pub fee_lamports: u64,
+
+    /// Lifetime credits issued
+    pub total_issued: u64,
+
+    /// Lifetime credits spent
+    pub total_consumed: u64,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl CreditConfig {
+    /// Longest credit epoch (one week)
+    pub const MAX_EPOCH_SECONDS: i64 = 7 * 86_400;
+
+    /// Whether an epoch length is in range
+    pub fn validate_epoch(epoch_seconds: i64) -> bool {
+        epoch_seconds > 0 && epoch_seconds <= Self::MAX_EPOCH_SECONDS
+    }
+
+    /// Credit epoch containing `timestamp`
+    pub fn epoch_at(&self, timestamp: i64) -> u64 {
+        (timestamp.max(0) / self.epoch_seconds) as u64
+    }
+}
+
+/// Credit tree for one epoch
+#[account]
+#[derive(InitSpace)]
+pub struct CreditTree {
+    /// Credit epoch (PDA seed)
+    pub epoch: u64,
+
+    /// Credits issued (next leaf index)
+    pub leaf_count: u32,
+
+    /// Merkle frontier (left siblings on the insertion path)
+    pub frontier: [[u8; 32]; MERKLE_TREE_DEPTH],
+
+    /// Total roots produced (next ring buffer slot = total % MAX)
+    pub total_roots: u64,
+
+    /// Recent roots (ring buffer)
+    pub roots: [[u8; 32]; MAX_CREDIT_ROOTS],
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Default for CreditTree {
+    fn default() -> Self {
+        Self {
+            epoch: 0,
+            leaf_count: 0,
+            frontier: [[0u8; 32]; MERKLE_TREE_DEPTH],
+            total_roots: 0,
+            roots: [[0u8; 32]; MAX_CREDIT_ROOTS],
+            bump: 0,
+        }
+    }
+}
+
+impl CreditTree {
+    /// Maximum credits an epoch can issue
+    pub const CAPACITY: u64 = 1u64 << MERKLE_TREE_DEPTH;
+
+    /// Append credit leaves, recording one root for the batch
+    pub fn append(&mut self, leaves: &[[u8; 32]]) -> Result<[u8; 32]> {
+        require!(
+            !leaves.is_empty() && leaves.len() <= MAX_CREDITS_PER_ISSUE,
+            CloakCraftError::InvalidAmount
+        );
+        require!(
+            self.leaf_count as u64 + leaves.len() as u64 <= Self::CAPACITY,
+            CloakCraftError::CreditTreeFull
+        );
+
+        let mut root = [0u8; 32];
+        for leaf in leaves {
+            root = merkle::insert_leaf(&mut self.frontier, self.leaf_count, *leaf)?;
+            self.leaf_count += 1;
+        }
+
+        let index = (self.total_roots % MAX_CREDIT_ROOTS as u64) as usize;
+        self.roots[index] = root;
+        self.total_roots += 1;
+
+        Ok(root)
+    }
+
+    /// Check a credit proof's root is a retained root of this tree
+    pub fn check_root(&self, root: &[u8; 32]) -> Result<()> {
+        let retained = self.total_roots.min(MAX_CREDIT_ROOTS as u64) as usize;
+        require!(
+            merkle::is_known_root(&self.roots[..retained], root),
+            CloakCraftError::UnknownCreditRoot
+        );
+        Ok(())
+    }
+}
+
+/// Stake-backed credits a staker has obtained in the current epoch
+#[account]
+#[derive(Default, InitSpace)]
+pub struct CreditIssuance {
+    /// Staker (PDA seed)
+    pub staker: Pubkey,
+
+    /// Epoch `issued` counts
+    pub epoch: u64,
+
+    /// Credits issued in `epoch`
+    pub issued: u16,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl CreditIssuance {
+    /// Count `count` more credits in `epoch` against `allowance`
+    ///
+    /// The count restarts each epoch. Returns false if the allowance would
+    /// be exceeded.
+    pub fn take(&mut self, epoch: u64, count: u16, allowance: u16) -> bool {
+        if self.epoch != epoch {
+            self.epoch = epoch;
+            self.issued = 0;
+        }
+        match self.issued.checked_add(count) {
+            Some(issued) if issued <= allowance => {
+                self.issued = issued;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A spent credit (its PDA is the credit nullifier)
+#[account]
+#[derive(Default, InitSpace)]
+pub struct OperationCredit {
+    /// Credit nullifier (PDA seed)
+    pub credit_nullifier: [u8; 32],
+
+    /// Operation the credit was spent for
+    pub operation_id: [u8; 32],
+
+    /// Epoch the credit was issued in
+    pub epoch: u64,
+
+    /// Relayer that spent the credit (paid the rent, refunded on close)
+    pub relayer: Pubkey,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl ProtocolConfig {
+    /// Check an operation carries a credit while credits are required
+    ///
+    /// The caller passes the spent credit account; only `consume_operation_credit`
+    /// creates them, so a matching operation ID proves one was spent for it.
+    pub fn check_operation_credit(
+        &self,
+        credit: Option<&OperationCredit>,
+        operation_id: &[u8; 32],
+    ) -> Result<()> {
+        if !self.require_operation_credits {
+            return Ok(());
+        }
+        let spent = credit.is_some_and(|credit| credit.operation_id == *operation_id);
+        require!(spent, CloakCraftError::OperationCreditRequired);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credit_issuance_allowance() {
+        let mut issuance = CreditIssuance::default();
+        assert!(issuance.take(5, 3, 4));
+        assert!(!issuance.take(5, 2, 4));
+        assert!(issuance.take(5, 1, 4));
+        assert_eq!(issuance.issued, 4);
+
+        // The allowance restarts in the next epoch
+        assert!(issuance.take(6, 4, 4));
+        assert_eq!((issuance.epoch, issuance.issued), (6, 4));
+        assert!(!issuance.take(7, 1, 0));
+    }
+
+    #[test]
+    fn test_credit_tree_roots() {
+        let mut tree = CreditTree::default();
+        assert!(tree.check_root(&[0u8; 32]).is_err());
+
+        let first = tree.append(&[[1u8; 32], [2u8; 32]]).unwrap();
+        assert_eq!(tree.leaf_count, 2);
+        assert!(tree.check_root(&first).is_ok());
+
+        assert!(tree.append(&[]).is_err());
+        assert!(tree.append(&[[3u8; 32]; MAX_CREDITS_PER_ISSUE + 1]).is_err());
+
+        for i in 0..MAX_CREDIT_ROOTS as u8 {
+            tree.append(&[[i + 3; 32]]).unwrap();
+        }
+        assert_eq!(
+            tree.check_root(&first).unwrap_err(),
+            CloakCraftError::UnknownCreditRoot.into()
+        );
+    }
+
+    #[test]
+    fn test_operation_credit_check() {
+        let mut config = ProtocolConfig::default();
+        let operation_id = [9u8; 32];
+        assert!(config.check_operation_credit(None, &operation_id).is_ok());
+
+        config.require_operation_credits = true;
+        assert!(config.check_operation_credit(None, &operation_id).is_err());
+
+        let credit = OperationCredit { operation_id, ..Default::default() };
+        assert!(config.check_operation_credit(Some(&credit), &operation_id).is_ok());
+        assert!(config.check_operation_credit(Some(&credit), &[8u8; 32]).is_err());
+
+        let config = CreditConfig { epoch_seconds: 3_600, ..Default::default() };
+        assert_eq!(config.epoch_at(7_199), 1);
+        assert_eq!(config.epoch_at(-5), 0);
+        assert!(!CreditConfig::validate_epoch(0));
+        assert!(!CreditConfig::validate_epoch(CreditConfig::MAX_EPOCH_SECONDS + 1));
+    }
+}
@@ -0,0 +1,42 @@
+//! Payment receipts for invoice fulfillment
+//!
+//! A payer settling a `PaymentRequest` can have `create_commitment` write a
+//! `PaymentReceipt` binding the invoice's reference id to the output
+//! commitment. The receipt reveals nothing about amount, mint or recipient:
+//! the merchant looks it up by reference id, then decrypts the note at
+//! `commitment` to check it pays the invoiced amount to their stealth meta
+//! address.
+//!
+//! The address is derived from the reference id alone, so each invoice can be
+//! receipted once per address tree. Anyone who knows a reference id can claim
+//! it with an unrelated commitment; such a receipt fails the merchant's note
+//! check and the invoice should be reissued under a fresh id.
+
+use anchor_lang::prelude::*;
+use light_sdk::LightDiscriminator;
+
+/// Payment receipt compressed account data
+///
+/// Seeds: ["payment_receipt", reference_id]
+#[derive(Clone, Debug, Default, LightDiscriminator, AnchorSerialize, AnchorDeserialize)]
+pub struct PaymentReceipt {
+    /// Invoice reference id (32 bytes)
+    pub reference_id: [u8; 32],
+
+    /// Pool the payment note was created in (32 bytes)
+    pub pool: [u8; 32],
+
+    /// Commitment of the payment note (32 bytes)
+    pub commitment: [u8; 32],
+
+    /// Leaf index of the payment note (8 bytes)
+    pub leaf_index: u64,
+
+    /// Timestamp of the payment (8 bytes)
+    pub paid_at: i64,
+}
+
+impl PaymentReceipt {
+    /// Seeds prefix for receipt address derivation
+    pub const SEED_PREFIX: &'static [u8] = b"payment_receipt";
+}
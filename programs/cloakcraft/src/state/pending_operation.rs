@@ -16,15 +16,24 @@
 
 use anchor_lang::prelude::*;
 use super::commitment::MAX_ENCRYPTED_NOTE_SIZE;
+use super::protocol_config::ProtocolConfig;
+use super::nullifier::NullifierDomain;
+use crate::helpers::fixed::apply_bps;
+use crate::constants::operation_types;
+use crate::errors::CloakCraftError;
 
 /// Maximum number of pending commitments per operation
 /// 8 outputs allows flexibility for change, fees, multi-recipient transfers
 pub const MAX_PENDING_COMMITMENTS: usize = 8;
 
 /// Maximum number of inputs per operation (each input = 1 commitment + 1 nullifier)
-/// 3 inputs allows note consolidation (combine 3 notes into 1)
+/// 3 inputs allows note consolidation (combine 3 notes into 1), 4 a full
+/// transfer batch (see append_transfer_to_batch)
 /// For more notes, use recursive consolidation: (1+2+3)→A, (A+4+5)→B, etc.
-pub const MAX_INPUTS: usize = 3;
+pub const MAX_INPUTS: usize = 4;
+
+/// Maximum number of transfers sharing one PendingOperation (one input each)
+pub const MAX_BATCH_TRANSFERS: usize = MAX_INPUTS;
 
 /// Pending operation for multi-phase commit with append pattern
 ///
@@ -51,12 +60,13 @@ pub struct PendingOperation {
     pub bump: u8,
 
     /// Operation ID (unique per operation, derived from inputs)
+    /// First 4 bytes: operation epoch (little-endian, see ProtocolConfig)
     pub operation_id: [u8; 32],
 
     /// Relayer who initiated the operation
     pub relayer: Pubkey,
 
-    /// Operation type (not used for logic, only for logging/debugging)
+    /// Operation type (see `constants::operation_types` and `OperationKind`)
     pub operation_type: u8,
 
     /// SECURITY: State machine flags (prevent phase skipping)
@@ -143,13 +153,14 @@ pub struct PendingOperation {
     /// Remove Liquidity: LP tokens burned
     pub swap_amount: u64,
 
-    /// Swap: Output amount received (recalculated on-chain for flexibility)
+    /// Swap: Output amount received (re-checked on-chain against live reserves)
     /// Add Liquidity: Token B deposit amount
     /// Remove Liquidity: Token A withdrawn
     pub output_amount: u64,
 
     /// Swap: Minimum acceptable output (slippage protection)
     /// Verified on-chain: recalculated_output >= min_output
+    /// (and at most MAX_SWAP_OUTPUT_DRIFT_BPS above output_amount)
     /// Add Liquidity/Remove Liquidity: unused
     pub min_output: u64,
 
@@ -182,6 +193,51 @@ pub struct PendingOperation {
 
     /// Whether fee has been processed (transferred to treasury)
     pub fee_processed: bool,
+
+    /// Unshield-and-invoke: hash of (target program, recipient, call data)
+    /// bound by the ZK proof; destination-bound unshields: the recipient
+    /// hash; committed and sealed swaps: the swap commitment (see
+    /// `compute_swap_commitment`). All zeros otherwise.
+    pub call_hash: [u8; 32],
+
+    /// User's proven unshield recipient, refunded part of the rent on close
+    /// (Pubkey::default() = all rent goes to the relayer)
+    pub rent_refund_recipient: Pubkey,
+
+    /// Share of the rent refunded to `rent_refund_recipient`, in basis points
+    /// (resolved from ProtocolConfig in Phase 0)
+    pub rent_refund_bps: u16,
+
+    /// Nullifier domain of the Phase 0 circuit (`NullifierDomain::code`, 0 = none)
+    pub nullifier_domain: u8,
+
+    // =============================================================================
+    // Perps position fields
+    // =============================================================================
+
+    /// Close/Liquidate: position margin (ZK public input)
+    pub position_margin: u64,
+
+    /// Close/Liquidate: position size (ZK public input).
+    /// Open: size locked by execute_open_position
+    pub position_size: u64,
+
+    /// Close/Liquidate: position entry price (ZK public input).
+    /// Open: oracle price read by execute_open_position
+    pub position_entry_price: u64,
+
+    /// Liquidate/Transfer: `entry_borrow_fee` of the spent position's
+    /// PositionMeta (recorded by verify_position_meta_active).
+    /// Close: claimed in Phase 0, checked against the PositionMeta.
+    /// Open: borrow fee accumulator snapshot taken by execute_open_position
+    pub position_entry_borrow_fee: u128,
+
+    /// Close: borrow fee the proof deducts from the settlement (ZK public
+    /// input, computed on-chain in Phase 0)
+    pub position_borrow_fee: u64,
+
+    /// Whether verify_position_meta_active bound the spent position's PositionMeta
+    pub position_meta_verified: bool,
 }
 
 impl PendingOperation {
@@ -197,9 +253,9 @@ impl PendingOperation {
         32 + // relayer
         1 + // operation_type
         1 + // proof_verified
-        (32 * MAX_INPUTS) + // input_commitments (3 × 32 = 96) (SECURITY: binds Phase 0 to Phase 1)
-        (32 * MAX_INPUTS) + // expected_nullifiers (3 × 32 = 96) (SECURITY: binds Phase 0 to Phase 2)
-        (32 * MAX_INPUTS) + // input_pools (3 × 32 = 96) (SECURITY: binds Phase 1/2 to correct pool)
+        (32 * MAX_INPUTS) + // input_commitments (4 × 32 = 128) (SECURITY: binds Phase 0 to Phase 1)
+        (32 * MAX_INPUTS) + // expected_nullifiers (4 × 32 = 128) (SECURITY: binds Phase 0 to Phase 2)
+        (32 * MAX_INPUTS) + // input_pools (4 × 32 = 128) (SECURITY: binds Phase 1/2 to correct pool)
         1 + // num_inputs
         1 + // inputs_verified_mask
         1 + // nullifier_completed_mask
@@ -222,12 +278,27 @@ impl PendingOperation {
         8 + // fee_amount (protocol fee)
         8 + // unshield_amount
         8 + // transfer_amount (public for fee verification)
-        1; // fee_processed
-        // Total: ~2,083 bytes with 3 inputs + 8 outputs (safe for 4KB stack)
+        1 + // fee_processed
+        32 + // call_hash (unshield-and-invoke binding)
+        32 + // rent_refund_recipient
+        2 + // rent_refund_bps
+        1 + // nullifier_domain
+        8 + // position_margin
+        8 + // position_size
+        8 + // position_entry_price
+        16 + // position_entry_borrow_fee
+        8 + // position_borrow_fee
+        1; // position_meta_verified
+        // Total: ~2,179 bytes with 4 inputs + 8 outputs (safe for 4KB stack)
+
+    /// Bitmask with the low `count` bits set (count may be 8)
+    fn full_mask(count: u8) -> u8 {
+        if count >= 8 { u8::MAX } else { (1u8 << count) - 1 }
+    }
 
     /// Check if all input commitments have been verified
     pub fn all_inputs_verified(&self) -> bool {
-        let mask = (1u8 << self.num_inputs) - 1;
+        let mask = Self::full_mask(self.num_inputs);
         self.inputs_verified_mask == mask
     }
 
@@ -238,7 +309,7 @@ impl PendingOperation {
         if self.num_inputs == 0 {
             return true;
         }
-        let mask = (1u8 << self.num_inputs) - 1;
+        let mask = Self::full_mask(self.num_inputs);
         (self.nullifier_completed_mask & mask) == mask
     }
 
@@ -253,7 +324,7 @@ impl PendingOperation {
         if self.num_commitments == 0 {
             return true;
         }
-        let mask = (1u8 << self.num_commitments) - 1;
+        let mask = Self::full_mask(self.num_commitments);
         self.completed_mask == mask
     }
 
@@ -262,11 +333,151 @@ impl PendingOperation {
         self.all_nullifiers_created() && self.all_commitments_created()
     }
 
+    /// Typed operation kind, None for unknown types
+    pub fn kind(&self) -> Option<OperationKind> {
+        OperationKind::from_type(self.operation_type)
+    }
+
+    /// Check the operation may run its Phase 3 instruction as `kind`
+    ///
+    /// Every execute_* and process_unshield* instruction calls this first, so
+    /// the phase transition rules live in one place (`PhaseRequirements`)
+    /// rather than in each instruction's account constraints. Expiry is not
+    /// part of it: instructions that reject expired operations keep their own
+    /// `is_expired` constraint.
+    pub fn check_phase3(&self, kind: OperationKind) -> Result<()> {
+        require!(self.kind() == Some(kind), CloakCraftError::InvalidOperationType);
+        let requirements = kind.requirements();
+        require!(requirements.has_phase3(), CloakCraftError::InvalidOperationType);
+        require!(self.proof_verified, CloakCraftError::ProofNotVerified);
+        require!(self.num_inputs <= requirements.nullifiers, CloakCraftError::InvalidInputCount);
+        require!(
+            self.num_commitments <= requirements.outputs,
+            CloakCraftError::TooManyPendingCommitments
+        );
+        require!(
+            requirements.inputs == 0 || self.all_inputs_verified(),
+            CloakCraftError::CommitmentNotVerified
+        );
+        require!(self.all_expected_nullifiers_created(), CloakCraftError::NullifierNotCreated);
+        Ok(())
+    }
+
+    /// Whether this is a transfer batch (transfers appended after Phase 0)
+    pub fn is_batch(&self) -> bool {
+        self.operation_type == operation_types::TRANSFER && self.num_inputs > 1
+    }
+
     /// Check if operation has expired
     pub fn is_expired(&self, current_time: i64) -> bool {
         current_time > self.expires_at
     }
 
+    /// Whether anyone may complete this operation's commitments
+    ///
+    /// An expired transfer or consolidation whose nullifiers were created but
+    /// whose commitments were not is stranded: the inputs are spent and the
+    /// outputs don't exist yet. Its commitments are fully determined by the
+    /// proof, so completing them is safe for any caller. Other operation types
+    /// depend on an execute phase that isn't tracked here.
+    pub fn is_rescuable(&self, current_time: i64) -> bool {
+        matches!(self.operation_type, operation_types::TRANSFER | operation_types::CONSOLIDATE)
+            && self.proof_verified
+            && self.is_expired(current_time)
+            && self.all_expected_nullifiers_created()
+            && !self.all_commitments_created()
+    }
+
+    /// Record the operation ID, rejecting IDs from a previous operation epoch
+    pub fn set_operation_id(&mut self, operation_id: [u8; 32], config: &ProtocolConfig) -> Result<()> {
+        require!(
+            config.is_current_operation(&operation_id),
+            CloakCraftError::StaleOperationEpoch
+        );
+        self.operation_id = operation_id;
+        Ok(())
+    }
+
+    /// Record the nullifier domain the circuit registry assigns `circuit_id`
+    pub fn bind_circuit(&mut self, circuit_id: &[u8; 32]) {
+        self.nullifier_domain = NullifierDomain::for_circuit(circuit_id).map_or(0, NullifierDomain::code);
+    }
+
+    /// Nullifier domain recorded in Phase 0
+    pub fn nullifier_domain(&self) -> Option<NullifierDomain> {
+        NullifierDomain::from_code(self.nullifier_domain)
+    }
+
+    /// Record the rent refund split in effect at Phase 0
+    ///
+    /// No recipient yet: the relayer builds Phase 0, so any account it named
+    /// would be its own choice. See `bind_rent_refund_recipient`.
+    pub fn set_rent_refund_bps(&mut self, config: &ProtocolConfig) {
+        self.rent_refund_recipient = Pubkey::default();
+        self.rent_refund_bps = config.rent_refund_bps.min(ProtocolConfig::MAX_RENT_REFUND_BPS);
+    }
+
+    /// Refund the rent split to the user's unshield recipient
+    ///
+    /// Only called once the recipient matched the hash the Phase 0 proof
+    /// bound (destination-bound unshields, unshield-and-invoke).
+    pub fn bind_rent_refund_recipient(&mut self, recipient: Pubkey) {
+        self.rent_refund_recipient = recipient;
+    }
+
+    /// Lamports of `rent` owed to the rent refund recipient on close
+    pub fn rent_refund_amount(&self, rent: u64) -> u64 {
+        if self.rent_refund_recipient == Pubkey::default() {
+            return 0;
+        }
+        apply_bps(rent, self.rent_refund_bps)
+    }
+
+    /// Whether commitment `index` may be created with this stealth ephemeral pubkey
+    ///
+    /// Transfers and consolidations store each output's ephemeral pubkey in
+    /// Phase 0, and the commitment must then carry that one, so neither the
+    /// relayer nor a rescuer can detach a change note from the key it was
+    /// derived for. Outputs stored without one (zeros) accept any pubkey.
+    pub fn stealth_ephemeral_matches(&self, index: u8, pubkey: &[u8; 64]) -> bool {
+        let stored = &self.stealth_ephemeral_pubkeys[index as usize];
+        *stored == [0u8; 64] || stored == pubkey
+    }
+
+    /// Tokens still to leave the vault in Phase 3 (unshield + unprocessed fee)
+    pub fn pending_outflow(&self) -> u64 {
+        let fee = if self.fee_processed { 0 } else { self.fee_amount };
+        self.unshield_amount.saturating_add(fee)
+    }
+
+    /// Public value the operation moves through `pool`
+    ///
+    /// Outputs committed to the pool plus the operation's public amounts
+    /// (unshield, fee, swap or deposit input). Amounts of multi-pool
+    /// operations are counted against every input pool, so this can only
+    /// overestimate an input's value.
+    pub fn pool_value_bound(&self, pool: &[u8; 32]) -> u64 {
+        let outputs = (0..self.num_commitments as usize)
+            .filter(|&index| self.pools[index] == *pool)
+            .fold(0u64, |total, index| total.saturating_add(self.output_amounts[index]));
+        outputs
+            .saturating_add(self.unshield_amount)
+            .saturating_add(self.fee_amount)
+            .saturating_add(self.swap_amount)
+    }
+
+    /// Token value a voting operation moves
+    ///
+    /// Voting Phase 0s keep the vote amount, weight or payout in the outputs,
+    /// `extra_amount` or `swap_amount` (which may also hold a vote choice),
+    /// so the largest of them bounds it.
+    pub fn ballot_value_bound(&self) -> u64 {
+        let outputs = self.output_amounts[..self.num_commitments as usize]
+            .iter()
+            .fold(0u64, |total, amount| total.saturating_add(*amount));
+        outputs.max(self.extra_amount).max(self.swap_amount)
+    }
+
     /// Mark a nullifier as created
     pub fn mark_nullifier_created(&mut self, index: u8) {
         self.nullifier_completed_mask |= 1u8 << index;
@@ -298,5 +509,245 @@ impl PendingOperation {
     }
 }
 
-/// Expiry duration for pending operations (5 minutes)
+/// Typed view of `PendingOperation::operation_type`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OperationKind {
+    Transfer,
+    Swap,
+    AddLiquidity,
+    RemoveLiquidity,
+    Consolidate,
+    AmmConvertLp,
+    PerpsOpenPosition,
+    PerpsClosePosition,
+    PerpsLiquidate,
+    PerpsAddLiquidity,
+    PerpsRemoveLiquidity,
+    PerpsTransferPosition,
+    PerpsConvertLp,
+    VoteSnapshot,
+    ChangeVoteSnapshot,
+    VoteSpend,
+    ChangeVoteSpend,
+    CloseVotePosition,
+    Claim,
+    ClaimRewards,
+    ClaimFeeRebate,
+    Donate,
+    ClaimEscrowYield,
+    VaultDeposit,
+    VaultWithdraw,
+}
+
+/// What an operation kind goes through before and in Phase 3
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PhaseRequirements {
+    /// Most input commitments verified in Phase 1 (snapshot votes prove
+    /// their note against the snapshot root in the circuit instead)
+    pub inputs: u8,
+    /// Most nullifiers created in Phase 2 (one per stored input)
+    pub nullifiers: u8,
+    /// Most output commitments created in Phase 4
+    pub outputs: u8,
+    /// Phase 3 is process_unshield* (skipped when nothing is unshielded)
+    pub needs_unshield: bool,
+    /// Phase 3 is an execute_* instruction
+    pub needs_execute: bool,
+}
+
+impl PhaseRequirements {
+    const fn new(inputs: u8, nullifiers: u8, outputs: u8, needs_unshield: bool, needs_execute: bool) -> Self {
+        Self { inputs, nullifiers, outputs, needs_unshield, needs_execute }
+    }
+
+    /// Whether the operation has a Phase 3 instruction at all
+    pub fn has_phase3(&self) -> bool {
+        self.needs_unshield || self.needs_execute
+    }
+}
+
+/// Most notes merged by one consolidation
+const CONSOLIDATION_MAX_INPUTS: u8 = 3;
+
+/// Most vote positions redeemed by one claim (create_pending_with_proof_claim_multi)
+const CLAIM_MAX_POSITIONS: u8 = 3;
+
+impl OperationKind {
+    /// Kind of an `operation_types` value, None for unknown types
+    pub fn from_type(operation_type: u8) -> Option<Self> {
+        use operation_types::*;
+        Some(match operation_type {
+            TRANSFER => Self::Transfer,
+            SWAP => Self::Swap,
+            ADD_LIQUIDITY => Self::AddLiquidity,
+            REMOVE_LIQUIDITY => Self::RemoveLiquidity,
+            CONSOLIDATE => Self::Consolidate,
+            AMM_CONVERT_LP => Self::AmmConvertLp,
+            PERPS_OPEN_POSITION => Self::PerpsOpenPosition,
+            PERPS_CLOSE_POSITION => Self::PerpsClosePosition,
+            PERPS_LIQUIDATE => Self::PerpsLiquidate,
+            PERPS_ADD_LIQUIDITY => Self::PerpsAddLiquidity,
+            PERPS_REMOVE_LIQUIDITY => Self::PerpsRemoveLiquidity,
+            PERPS_TRANSFER_POSITION => Self::PerpsTransferPosition,
+            PERPS_CONVERT_LP => Self::PerpsConvertLp,
+            VOTE_SNAPSHOT => Self::VoteSnapshot,
+            CHANGE_VOTE_SNAPSHOT => Self::ChangeVoteSnapshot,
+            VOTE_SPEND => Self::VoteSpend,
+            CHANGE_VOTE_SPEND => Self::ChangeVoteSpend,
+            CLOSE_VOTE_POSITION => Self::CloseVotePosition,
+            CLAIM => Self::Claim,
+            CLAIM_REWARDS => Self::ClaimRewards,
+            CLAIM_FEE_REBATE => Self::ClaimFeeRebate,
+            DONATE => Self::Donate,
+            CLAIM_ESCROW_YIELD => Self::ClaimEscrowYield,
+            VAULT_DEPOSIT => Self::VaultDeposit,
+            VAULT_WITHDRAW => Self::VaultWithdraw,
+            _ => return None,
+        })
+    }
+
+    /// The `operation_types` value stored for this kind
+    pub fn operation_type(self) -> u8 {
+        use operation_types::*;
+        match self {
+            Self::Transfer => TRANSFER,
+            Self::Swap => SWAP,
+            Self::AddLiquidity => ADD_LIQUIDITY,
+            Self::RemoveLiquidity => REMOVE_LIQUIDITY,
+            Self::Consolidate => CONSOLIDATE,
+            Self::AmmConvertLp => AMM_CONVERT_LP,
+            Self::PerpsOpenPosition => PERPS_OPEN_POSITION,
+            Self::PerpsClosePosition => PERPS_CLOSE_POSITION,
+            Self::PerpsLiquidate => PERPS_LIQUIDATE,
+            Self::PerpsAddLiquidity => PERPS_ADD_LIQUIDITY,
+            Self::PerpsRemoveLiquidity => PERPS_REMOVE_LIQUIDITY,
+            Self::PerpsTransferPosition => PERPS_TRANSFER_POSITION,
+            Self::PerpsConvertLp => PERPS_CONVERT_LP,
+            Self::VoteSnapshot => VOTE_SNAPSHOT,
+            Self::ChangeVoteSnapshot => CHANGE_VOTE_SNAPSHOT,
+            Self::VoteSpend => VOTE_SPEND,
+            Self::ChangeVoteSpend => CHANGE_VOTE_SPEND,
+            Self::CloseVotePosition => CLOSE_VOTE_POSITION,
+            Self::Claim => CLAIM,
+            Self::ClaimRewards => CLAIM_REWARDS,
+            Self::ClaimFeeRebate => CLAIM_FEE_REBATE,
+            Self::Donate => DONATE,
+            Self::ClaimEscrowYield => CLAIM_ESCROW_YIELD,
+            Self::VaultDeposit => VAULT_DEPOSIT,
+            Self::VaultWithdraw => VAULT_WITHDRAW,
+        }
+    }
+
+    /// Phase requirements of this kind (mirrors the Phase 0 instructions)
+    ///
+    /// Arguments: inputs, nullifiers, outputs, needs_unshield, needs_execute.
+    pub fn requirements(self) -> PhaseRequirements {
+        const TRANSFER_INPUTS: u8 = MAX_BATCH_TRANSFERS as u8;
+        const ANY_OUTPUTS: u8 = MAX_PENDING_COMMITMENTS as u8;
+        type R = PhaseRequirements;
+        match self {
+            Self::Transfer => R::new(TRANSFER_INPUTS, TRANSFER_INPUTS, ANY_OUTPUTS, true, false),
+            Self::Swap => R::new(1, 1, 2, false, true),
+            Self::AddLiquidity => R::new(2, 2, 3, false, true),
+            Self::RemoveLiquidity => R::new(1, 1, 2, false, true),
+            Self::Consolidate => R::new(CONSOLIDATION_MAX_INPUTS, CONSOLIDATION_MAX_INPUTS, 1, false, false),
+            Self::AmmConvertLp => R::new(1, 1, 1, false, false),
+            Self::PerpsOpenPosition => R::new(1, 1, 2, false, true),
+            Self::PerpsClosePosition => R::new(1, 1, 1, false, true),
+            Self::PerpsLiquidate => R::new(1, 1, 2, false, true),
+            Self::PerpsAddLiquidity => R::new(1, 1, 1, false, true),
+            Self::PerpsRemoveLiquidity => R::new(1, 1, 2, false, true),
+            Self::PerpsTransferPosition => R::new(1, 1, 1, false, false),
+            Self::PerpsConvertLp => R::new(1, 1, 1, false, false),
+            Self::VoteSnapshot => R::new(0, 1, 1, false, true),
+            Self::ChangeVoteSnapshot => R::new(1, 1, 1, false, true),
+            Self::VoteSpend => R::new(1, 1, 1, false, true),
+            Self::ChangeVoteSpend => R::new(1, 1, 1, false, true),
+            Self::CloseVotePosition => R::new(1, 1, 1, false, true),
+            Self::Claim => R::new(CLAIM_MAX_POSITIONS, CLAIM_MAX_POSITIONS, 1, false, true),
+            Self::ClaimRewards => R::new(1, 1, 2, false, true),
+            Self::ClaimFeeRebate => R::new(0, 0, 1, false, true),
+            Self::Donate => R::new(1, 1, 2, false, true),
+            Self::ClaimEscrowYield => R::new(0, 0, 1, false, true),
+            Self::VaultDeposit => R::new(1, 1, 1, false, true),
+            Self::VaultWithdraw => R::new(1, 1, 1, false, true),
+        }
+    }
+}
+
+/// Default expiry for pending operations (5 minutes)
+///
+/// Used when ProtocolConfig has no expiry configured for the operation type.
 pub const PENDING_OPERATION_EXPIRY_SECONDS: i64 = 300;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ready_operation(kind: OperationKind) -> PendingOperation {
+        let requirements = kind.requirements();
+        let zeroed = vec![0u8; PendingOperation::SPACE - 8];
+        let mut op = PendingOperation::deserialize(&mut zeroed.as_slice()).unwrap();
+        op.operation_type = kind.operation_type();
+        op.expires_at = 100;
+        op.proof_verified = true;
+        op.num_inputs = requirements.nullifiers;
+        op.inputs_verified_mask = if requirements.inputs > 0 { PendingOperation::full_mask(op.num_inputs) } else { 0 };
+        op.nullifier_completed_mask = PendingOperation::full_mask(op.num_inputs);
+        op.num_commitments = requirements.outputs;
+        op
+    }
+
+    #[test]
+    fn test_operation_kind_round_trip() {
+        for operation_type in 0..=u8::MAX {
+            if let Some(kind) = OperationKind::from_type(operation_type) {
+                assert_eq!(kind.operation_type(), operation_type);
+                let requirements = kind.requirements();
+                assert!(requirements.inputs <= requirements.nullifiers);
+                assert!(requirements.nullifiers as usize <= MAX_INPUTS);
+                assert!(requirements.outputs as usize <= MAX_PENDING_COMMITMENTS);
+            }
+        }
+        assert_eq!(OperationKind::from_type(operation_types::CLAIM), Some(OperationKind::Claim));
+        assert_eq!(OperationKind::from_type(9), None);
+    }
+
+    #[test]
+    fn test_pool_value_bound() {
+        let mut op = ready_operation(OperationKind::Transfer);
+        let (pool, other) = ([1u8; 32], [2u8; 32]);
+        op.num_commitments = 2;
+        op.pools[0] = pool;
+        op.output_amounts[0] = 600;
+        op.pools[1] = other;
+        op.output_amounts[1] = 300;
+        op.unshield_amount = 80;
+        op.fee_amount = 20;
+        assert_eq!(op.pool_value_bound(&pool), 700);
+        assert_eq!(op.pool_value_bound(&other), 400);
+    }
+
+    #[test]
+    fn test_check_phase3() {
+        let op = ready_operation(OperationKind::Swap);
+        assert!(op.check_phase3(OperationKind::Swap).is_ok());
+        // Another kind's execute instruction
+        assert!(op.check_phase3(OperationKind::AddLiquidity).is_err());
+
+        let mut unverified = ready_operation(OperationKind::Swap);
+        unverified.inputs_verified_mask = 0;
+        assert!(unverified.check_phase3(OperationKind::Swap).is_err());
+        let mut unspent = ready_operation(OperationKind::Swap);
+        unspent.nullifier_completed_mask = 0;
+        assert!(unspent.check_phase3(OperationKind::Swap).is_err());
+
+        // Snapshot votes skip Phase 1, note-less claims have no inputs at all
+        assert!(ready_operation(OperationKind::VoteSnapshot).check_phase3(OperationKind::VoteSnapshot).is_ok());
+        assert!(ready_operation(OperationKind::ClaimFeeRebate).check_phase3(OperationKind::ClaimFeeRebate).is_ok());
+
+        // Operations without a Phase 3 never pass
+        let consolidation = ready_operation(OperationKind::Consolidate);
+        assert!(consolidation.check_phase3(OperationKind::Consolidate).is_err());
+    }
+}
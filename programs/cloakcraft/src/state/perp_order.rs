@@ -0,0 +1,105 @@
+//! Perps limit order
+//!
+//! A resting order to open a position once the oracle price crosses a limit.
+//! The trader's open-position proof is verified when the order is placed and
+//! its binding fields are stored here; a keeper fill turns them into the
+//! PendingOperation the regular open-position phases consume.
+//!
+//! Orders are not cancelled on-chain: spending the margin note makes the
+//! order unfillable (its nullifier already exists), and expired orders can be
+//! closed by anyone to refund the rent.
+//!
+//! The encrypted output notes are stored on the order so any keeper can
+//! complete the commitment phases after filling.
+
+use anchor_lang::prelude::*;
+
+/// Maximum size of each encrypted note stored on an order
+pub const MAX_PERP_ORDER_NOTE_SIZE: usize = 250;
+
+/// Perps limit order (binding fields of a verified open-position proof)
+#[account]
+#[derive(InitSpace)]
+pub struct PerpOrder {
+    /// Unique order ID (PDA seed)
+    pub order_id: [u8; 32],
+
+    /// Perps pool
+    pub perps_pool: Pubkey,
+
+    /// Market the position opens in
+    pub perps_market: Pubkey,
+
+    /// Margin token pool (margin note is spent from here)
+    pub margin_pool: Pubkey,
+
+    /// Position pool (position commitment is stored here)
+    pub position_pool: Pubkey,
+
+    /// Margin note commitment
+    pub input_commitment: [u8; 32],
+
+    /// Margin note nullifier
+    pub nullifier: [u8; 32],
+
+    /// Position commitment (committed entry price = limit price)
+    pub position_commitment: [u8; 32],
+
+    /// Change commitment (unused if change_amount is 0)
+    pub change_commitment: [u8; 32],
+
+    /// Position direction
+    pub is_long: bool,
+
+    /// Margin amount
+    pub margin_amount: u64,
+
+    /// Leverage
+    pub leverage: u8,
+
+    /// Position fee (at least the pool's maker rate)
+    pub position_fee: u64,
+
+    /// Change returned to the margin pool
+    pub change_amount: u64,
+
+    /// Limit price (same scale as oracle prices)
+    pub limit_price: u64,
+
+    /// Expiry timestamp
+    pub expiry: i64,
+
+    /// Creation timestamp
+    pub created_at: i64,
+
+    /// Stealth ephemeral pubkeys of the position and change outputs
+    pub stealth_ephemeral_pubkeys: [[u8; 64]; 2],
+
+    /// Encrypted position and change notes, for the filling keeper's
+    /// create_commitment calls (read them before filling: the order closes)
+    #[max_len(2, 250)]
+    pub encrypted_notes: Vec<Vec<u8>>,
+
+    /// Account that paid the rent (refunded on fill or close)
+    pub payer: Pubkey,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl PerpOrder {
+    /// Check if order is expired
+    pub fn is_expired(&self, current_time: i64) -> bool {
+        current_time >= self.expiry
+    }
+
+    /// Whether the oracle price crosses the limit
+    /// LONG fills at or below the limit, SHORT at or above
+    pub fn is_triggered(&self, oracle_price: u64) -> bool {
+        if self.is_long {
+            oracle_price <= self.limit_price
+        } else {
+            oracle_price >= self.limit_price
+        }
+    }
+}
@@ -5,6 +5,8 @@
 
 use anchor_lang::prelude::*;
 
+use crate::helpers::fixed::{apply_bps, mul_div, ratio_bps, saturating_u64, BPS_SCALE};
+
 /// Perpetual futures market for a trading pair
 #[account]
 #[derive(Default, InitSpace)]
@@ -36,14 +38,55 @@ pub struct PerpsMarket {
     /// PDA bump seed
     pub bump: u8,
 
+    /// Listing status (delisted markets only settle)
+    pub status: MarketStatus,
+
+    /// When the market was delisted (0 = listed)
+    pub delisted_at: i64,
+
+    /// Earliest time the market can be force-settled
+    pub settle_after: i64,
+
+    /// Final oracle price once Settled (scaled by 1e6)
+    pub settlement_price: u64,
+
     /// Reserved for future use
-    pub _reserved: [u8; 32],
+    pub _reserved: [u8; 7],
+}
+
+/// Market listing status
+///
+/// Active markets follow `is_active`. A delisted (SettleOnly) market stops
+/// new opens but still closes and liquidates at the oracle price; after its
+/// grace period it can be force-settled, fixing the price every remaining
+/// position closes or liquidates at.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, Default, InitSpace)]
+pub enum MarketStatus {
+    #[default]
+    Active,
+    SettleOnly,
+    Settled,
 }
 
 impl PerpsMarket {
     /// PDA seeds prefix
     pub const SEEDS_PREFIX: &'static [u8] = b"perps_market";
 
+    /// Whether new positions may be opened
+    pub fn accepts_opens(&self) -> bool {
+        self.is_active && self.status == MarketStatus::Active
+    }
+
+    /// Whether the market can be force-settled at `now`
+    pub fn can_force_settle(&self, now: i64) -> bool {
+        self.status == MarketStatus::SettleOnly && now >= self.settle_after
+    }
+
+    /// Final price positions settle at, once Settled
+    pub fn settlement_price(&self) -> Option<u64> {
+        (self.status == MarketStatus::Settled).then_some(self.settlement_price)
+    }
+
     /// Calculate total open interest
     pub fn total_open_interest(&self) -> u64 {
         self.long_open_interest.saturating_add(self.short_open_interest)
@@ -65,13 +108,7 @@ impl PerpsMarket {
             self.short_open_interest.saturating_sub(self.long_open_interest)
         };
 
-        let imbalance_ratio = (diff as u128)
-            .checked_mul(10000)
-            .unwrap_or(0)
-            .checked_div(total as u128)
-            .unwrap_or(0);
-
-        (imbalance_ratio.min(10000) as u16, is_long_dominant)
+        (ratio_bps(diff, total), is_long_dominant)
     }
 
     /// Calculate imbalance fee for opening a position
@@ -85,12 +122,7 @@ impl PerpsMarket {
         // If opening in minority direction, no fee (helps balance)
         if is_long == is_long_dominant {
             // Fee = max_fee * imbalance_ratio / 10000
-            let fee = (max_fee_bps as u32)
-                .checked_mul(imbalance_ratio as u32)
-                .unwrap_or(0)
-                .checked_div(10000)
-                .unwrap_or(0);
-            fee.min(max_fee_bps as u32) as u16
+            apply_bps(max_fee_bps as u64, imbalance_ratio).min(max_fee_bps as u64) as u16
         } else {
             0
         }
@@ -105,6 +137,16 @@ impl PerpsMarket {
         }
     }
 
+    /// Pool token a position borrows (and accrues borrow fees in)
+    /// LONG borrows quote, SHORT borrows base
+    pub fn borrow_token_index(&self, is_long: bool) -> u8 {
+        if is_long {
+            self.quote_token_index
+        } else {
+            self.base_token_index
+        }
+    }
+
     /// Update open interest when closing a position
     pub fn remove_open_interest(&mut self, size: u64, is_long: bool) {
         if is_long {
@@ -191,11 +233,8 @@ impl PositionData {
         };
 
         // PnL = price_diff * size / entry_price
-        let pnl = (price_diff as u128)
-            .checked_mul(self.size as u128)
-            .unwrap_or(0)
-            .checked_div(self.entry_price as u128)
-            .unwrap_or(0) as u64;
+        let pnl = mul_div(price_diff as u128, self.size as u128, self.entry_price as u128)
+            .map_or(0, saturating_u64);
 
         (pnl, is_profit)
     }
@@ -215,11 +254,7 @@ impl PositionData {
     /// liquidation_threshold_bps: margin percentage at which liquidation occurs
     pub fn is_liquidatable(&self, current_price: u64, liquidation_threshold_bps: u16) -> bool {
         let effective = self.effective_margin(current_price);
-        let threshold = (self.margin as u128)
-            .checked_mul(liquidation_threshold_bps as u128)
-            .unwrap_or(0)
-            .checked_div(10000)
-            .unwrap_or(0) as u64;
+        let threshold = apply_bps(self.margin, liquidation_threshold_bps);
 
         effective <= threshold
     }
@@ -230,41 +265,96 @@ impl PositionData {
         // For long: liq_price = entry_price * (1 - margin/size * (1 - threshold/10000))
         // For short: liq_price = entry_price * (1 + margin/size * (1 - threshold/10000))
 
-        let margin_ratio = (self.margin as u128)
-            .checked_mul(10000)
-            .unwrap_or(0)
-            .checked_div(self.size as u128)
-            .unwrap_or(0);
+        let margin_ratio = mul_div(self.margin as u128, BPS_SCALE, self.size as u128).unwrap_or(0);
 
-        let effective_ratio = margin_ratio
-            .checked_mul(10000 - liquidation_threshold_bps as u128)
-            .unwrap_or(0)
-            .checked_div(10000)
-            .unwrap_or(0);
+        let effective_ratio = mul_div(
+            margin_ratio,
+            BPS_SCALE.saturating_sub(liquidation_threshold_bps as u128),
+            BPS_SCALE,
+        )
+        .unwrap_or(0);
 
         match self.direction {
             PositionDirection::Long => {
                 // liq_price = entry_price * (10000 - effective_ratio) / 10000
-                (self.entry_price as u128)
-                    .checked_mul(10000u128.saturating_sub(effective_ratio))
-                    .unwrap_or(0)
-                    .checked_div(10000)
-                    .unwrap_or(0) as u64
+                mul_div(self.entry_price as u128, BPS_SCALE.saturating_sub(effective_ratio), BPS_SCALE)
+                    .map_or(0, saturating_u64)
             }
             PositionDirection::Short => {
                 // liq_price = entry_price * (10000 + effective_ratio) / 10000
-                (self.entry_price as u128)
-                    .checked_mul(10000u128.saturating_add(effective_ratio))
-                    .unwrap_or(0)
-                    .checked_div(10000)
-                    .unwrap_or(0) as u64
+                mul_div(self.entry_price as u128, BPS_SCALE.saturating_add(effective_ratio), BPS_SCALE)
+                    .map_or(0, saturating_u64)
             }
         }
     }
 
+    /// Price distance to liquidation as a fraction of the current price (bps)
+    ///
+    /// 0 when the position is already liquidatable.
+    pub fn distance_to_liquidation_bps(&self, current_price: u64, liquidation_threshold_bps: u16) -> u16 {
+        if self.is_liquidatable(current_price, liquidation_threshold_bps) {
+            return 0;
+        }
+        let liquidation_price = self.liquidation_price(liquidation_threshold_bps);
+        ratio_bps(current_price.abs_diff(liquidation_price), current_price)
+    }
+
     /// Check if position has hit profit bound (profit = margin)
     pub fn is_at_profit_bound(&self, current_price: u64) -> bool {
         let (pnl, is_profit) = self.calculate_pnl(current_price);
         is_profit && pnl >= self.margin
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(direction: PositionDirection) -> PositionData {
+        PositionData {
+            direction,
+            margin: 1_000,
+            size: 10_000,
+            leverage: 10,
+            entry_price: 100_000_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_distance_to_liquidation() {
+        let long = position(PositionDirection::Long);
+        let liq = long.liquidation_price(50);
+        assert!(liq < long.entry_price);
+        // ~9.95% move to liquidation at entry
+        assert_eq!(long.distance_to_liquidation_bps(long.entry_price, 50), 995);
+        assert_eq!(long.distance_to_liquidation_bps(liq / 2, 50), 0);
+
+        let short = position(PositionDirection::Short);
+        assert!(short.liquidation_price(50) > short.entry_price);
+        assert_eq!(short.distance_to_liquidation_bps(short.entry_price * 2, 50), 0);
+    }
+
+    #[test]
+    fn test_market_delisting() {
+        let mut market = PerpsMarket {
+            is_active: true,
+            ..Default::default()
+        };
+        assert!(market.accepts_opens());
+        assert_eq!(market.settlement_price(), None);
+
+        market.status = MarketStatus::SettleOnly;
+        market.settle_after = 1_000;
+        assert!(!market.accepts_opens());
+        assert!(!market.can_force_settle(999));
+        assert!(market.can_force_settle(1_000));
+        assert_eq!(market.settlement_price(), None);
+
+        market.status = MarketStatus::Settled;
+        market.settlement_price = 42_000_000;
+        assert!(!market.can_force_settle(2_000));
+        assert_eq!(market.settlement_price(), Some(42_000_000));
+    }
+}
@@ -11,6 +11,10 @@
 
 use anchor_lang::prelude::*;
 
+use crate::helpers::fixed::{
+    apply_bps, apply_bps_ceil, apply_rate, mul_div, ratio_bps, saturating_u64, to_u64, token_amount_for_usd, usd_value, BPS_SCALE, USD_SCALE,
+};
+
 /// Maximum number of tokens supported in the pool
 pub const MAX_PERPS_TOKENS: usize = 8;
 
@@ -49,8 +53,11 @@ pub struct PerpsToken {
     /// Vault bump seed
     pub vault_bump: u8,
 
+    /// Target share of pool value in basis points (0 = no target)
+    pub target_weight_bps: u16,
+
     /// Reserved for future use
-    pub _reserved: [u8; 5],
+    pub _reserved: [u8; 3],
 }
 
 impl PerpsToken {
@@ -64,12 +71,14 @@ impl PerpsToken {
         if self.balance == 0 {
             return 0;
         }
-        let utilization = (self.locked as u128)
-            .checked_mul(10000)
-            .unwrap_or(0)
-            .checked_div(self.balance as u128)
-            .unwrap_or(0);
-        utilization.min(10000) as u16
+        ratio_bps(self.locked, self.balance)
+    }
+
+    /// Borrow fee accrued by a position of `size` since it snapshotted
+    /// `entry_cumulative_borrow_fee` (same units as `size`)
+    pub fn accrued_borrow_fee(&self, size: u64, entry_cumulative_borrow_fee: u128) -> u64 {
+        let delta = self.cumulative_borrow_fee.saturating_sub(entry_cumulative_borrow_fee);
+        apply_rate(size, delta).unwrap_or(u64::MAX)
     }
 
     /// Check if adding more locked amount would exceed utilization limit
@@ -78,12 +87,8 @@ impl PerpsToken {
         if self.balance == 0 {
             return false;
         }
-        let new_utilization = (new_locked as u128)
-            .checked_mul(10000)
-            .unwrap_or(u128::MAX)
-            .checked_div(self.balance as u128)
-            .unwrap_or(u128::MAX);
-        new_utilization <= max_utilization_bps as u128
+        mul_div(new_locked as u128, BPS_SCALE, self.balance as u128)
+            .is_some_and(|utilization| utilization <= max_utilization_bps as u128)
     }
 }
 
@@ -153,8 +158,29 @@ pub struct PerpsPool {
     /// Position mint bump seed
     pub position_mint_bump: u8,
 
-    /// Reserved for future use (reduced from 32 to accommodate position_mint + bump)
-    pub _reserved: [u8; 31],
+    /// Position fee for resting limit orders in basis points (<= position_fee_bps)
+    /// Carved from the reserved bytes so existing pools keep their layout
+    pub maker_fee_bps: u16,
+
+    /// Maximum urgency bonus on top of the liquidation penalty in basis points
+    /// (paid in full once a position's effective margin reaches zero)
+    pub liquidation_bonus_bps: u16,
+
+    /// Minimum position size (0 = no minimum)
+    pub min_position_size: u64,
+
+    /// Minimum position margin (0 = no minimum)
+    pub min_margin: u64,
+
+    /// Frozen by a pool migration (no liquidity changes, trading or rebalancing)
+    pub is_migrating: bool,
+
+    /// Fee on rebalance swaps in basis points, withheld from token_out (stays with LPs)
+    pub rebalance_fee_bps: u16,
+
+    /// Reserved for future use (reduced from 32 to accommodate position_mint + bump + maker_fee_bps
+    /// + liquidation_bonus_bps + position minimums + is_migrating + rebalance_fee_bps)
+    pub _reserved: [u8; 8],
 }
 
 impl PerpsPool {
@@ -178,7 +204,13 @@ impl PerpsPool {
         1 + // bump
         1 + // lp_mint_bump
         1 + // position_mint_bump
-        32; // _reserved
+        2 + // maker_fee_bps
+        2 + // liquidation_bonus_bps
+        8 + // min_position_size
+        8 + // min_margin
+        1 + // is_migrating
+        2 + // rebalance_fee_bps
+        8; // _reserved
 
     /// PDA seeds prefix
     pub const SEEDS_PREFIX: &'static [u8] = b"perps_pool";
@@ -196,6 +228,35 @@ impl PerpsPool {
         None
     }
 
+    /// Minimum position fee for a position of `size` (resting orders pay the maker rate)
+    pub fn min_position_fee(&self, size: u64, is_maker: bool) -> u64 {
+        let bps = if is_maker { self.maker_fee_bps } else { self.position_fee_bps };
+        apply_bps(size, bps)
+    }
+
+    /// Rebalance fee withheld from a rebalance swap paying `amount_out` (rounded up)
+    pub fn rebalance_fee(&self, amount_out: u64) -> u64 {
+        apply_bps_ceil(amount_out, self.rebalance_fee_bps)
+    }
+
+    /// Whether a position meets the pool's minimum size and margin
+    pub fn meets_position_minimums(&self, margin: u64, size: u64) -> bool {
+        margin >= self.min_margin && size >= self.min_position_size
+    }
+
+    /// Keeper reward for liquidating a position with `margin` collateral and
+    /// `effective_margin` left after PnL
+    ///
+    /// The liquidation penalty plus an urgency bonus that grows linearly from 0
+    /// at the liquidation threshold to `liquidation_bonus_bps` at zero effective
+    /// margin, so the positions closest to bad debt pay keepers the most.
+    pub fn liquidation_reward(&self, margin: u64, effective_margin: u64) -> u64 {
+        let threshold = apply_bps(margin, self.liquidation_threshold_bps).max(1);
+        let depth_bps = ratio_bps(threshold.saturating_sub(effective_margin), threshold);
+        let bonus_bps = apply_bps(self.liquidation_bonus_bps as u64, depth_bps) as u16;
+        apply_bps(margin, self.liquidation_penalty_bps.saturating_add(bonus_bps)).min(margin)
+    }
+
     /// Find token by index
     pub fn get_token(&self, index: u8) -> Option<&PerpsToken> {
         if index < self.num_tokens && self.tokens[index as usize].is_active {
@@ -224,23 +285,90 @@ impl PerpsPool {
             }
             // value = balance * price / 10^decimals
             // prices are assumed to be in USD with 6 decimals
-            let token_value = (self.tokens[i].balance as u128)
-                .checked_mul(prices[i] as u128)?
-                .checked_div(10u128.pow(self.tokens[i].decimals as u32))?;
+            let token_value = usd_value(self.tokens[i].balance, prices[i], self.tokens[i].decimals)?;
             total = total.checked_add(token_value)?;
         }
         Some(total)
     }
 
+    /// USD value (1e6) of a single token's balance
+    pub fn token_value(&self, index: u8, prices: &[u64; MAX_PERPS_TOKENS]) -> Option<u128> {
+        let token = self.get_token(index)?;
+        usd_value(token.balance, prices[index as usize], token.decimals)
+    }
+
+    /// Token's current share of pool value in basis points
+    pub fn current_weight_bps(&self, index: u8, prices: &[u64; MAX_PERPS_TOKENS]) -> Option<u16> {
+        let total = self.calculate_total_value(prices)?;
+        if total == 0 {
+            return Some(0);
+        }
+        let weight = mul_div(self.token_value(index, prices)?, BPS_SCALE, total)?;
+        Some(weight.min(BPS_SCALE) as u16)
+    }
+
+    /// Imbalance fee (bps) for adding or removing `value_delta` USD of a token
+    ///
+    /// Charged only when the operation moves the token further from its
+    /// target weight; scales linearly up to `max_imbalance_fee_bps`.
+    pub fn liquidity_imbalance_fee_bps(
+        &self,
+        index: u8,
+        value_delta: u128,
+        is_deposit: bool,
+        prices: &[u64; MAX_PERPS_TOKENS],
+    ) -> Option<u16> {
+        let target = self.get_token(index)?.target_weight_bps as u128;
+        if self.max_imbalance_fee_bps == 0 || target == 0 {
+            return Some(0);
+        }
+
+        let total = self.calculate_total_value(prices)?;
+        let value = self.token_value(index, prices)?;
+        let (post_total, post_value) = if is_deposit {
+            (total.checked_add(value_delta)?, value.checked_add(value_delta)?)
+        } else {
+            (total.saturating_sub(value_delta), value.saturating_sub(value_delta))
+        };
+
+        let deviation = |v: u128, t: u128| -> Option<u128> {
+            if t == 0 {
+                return Some(0);
+            }
+            Some(mul_div(v, BPS_SCALE, t)?.abs_diff(target))
+        };
+        let pre_dev = deviation(value, total)?;
+        let post_dev = deviation(post_value, post_total)?;
+        if post_dev <= pre_dev {
+            return Some(0);
+        }
+
+        let fee = mul_div(self.max_imbalance_fee_bps as u128, post_dev, BPS_SCALE)?;
+        Some(fee.min(self.max_imbalance_fee_bps as u128) as u16)
+    }
+
+    /// Sum of |current - target| weight deviations across targeted tokens
+    pub fn total_weight_deviation_bps(&self, prices: &[u64; MAX_PERPS_TOKENS]) -> Option<u32> {
+        let mut deviation: u32 = 0;
+        for i in 0..self.num_tokens {
+            let token = &self.tokens[i as usize];
+            if !token.is_active || token.target_weight_bps == 0 {
+                continue;
+            }
+            let weight = self.current_weight_bps(i, prices)?;
+            deviation = deviation.checked_add(weight.abs_diff(token.target_weight_bps) as u32)?;
+        }
+        Some(deviation)
+    }
+
     /// Calculate LP token value based on pool total value
     /// Returns value per LP token scaled by 1e6
     pub fn calculate_lp_value(&self, prices: &[u64; MAX_PERPS_TOKENS]) -> Option<u64> {
         if self.lp_supply == 0 {
-            return Some(1_000_000); // Initial LP value = 1 USD
+            return Some(USD_SCALE as u64); // Initial LP value = 1 USD
         }
         let total_value = self.calculate_total_value(prices)?;
-        let value_per_lp = total_value.checked_div(self.lp_supply as u128)?;
-        Some(value_per_lp as u64)
+        to_u64(total_value.checked_div(self.lp_supply as u128)?)
     }
 
     /// Calculate LP tokens to mint for a deposit
@@ -259,10 +387,7 @@ impl PerpsPool {
             return Some(deposit_value);
         }
         // lp_amount = deposit_value * lp_supply / total_value
-        let lp_amount = (deposit_value as u128)
-            .checked_mul(self.lp_supply as u128)?
-            .checked_div(total_value)?;
-        Some(lp_amount as u64)
+        to_u64(mul_div(deposit_value as u128, self.lp_supply as u128, total_value)?)
     }
 
     /// Calculate tokens to withdraw for LP burn
@@ -280,18 +405,18 @@ impl PerpsPool {
         let total_value = self.calculate_total_value(prices)?;
 
         // value_to_withdraw = lp_amount * total_value / lp_supply
-        let value_to_withdraw = (lp_amount as u128)
-            .checked_mul(total_value)?
-            .checked_div(self.lp_supply as u128)?;
+        let value_to_withdraw = mul_div(lp_amount as u128, total_value, self.lp_supply as u128)?;
 
         // token_amount = value_to_withdraw * 10^decimals / price
-        let token_amount = value_to_withdraw
-            .checked_mul(10u128.pow(token.decimals as u32))?
-            .checked_div(prices[token_index as usize] as u128)?;
+        let token_amount = token_amount_for_usd(
+            value_to_withdraw,
+            prices[token_index as usize],
+            token.decimals,
+        )?;
 
         // Cap at available balance
         let available = token.available();
-        Some((token_amount as u64).min(available))
+        Some(saturating_u64(token_amount).min(available))
     }
 
     /// Calculate borrow fee rate based on utilization
@@ -304,11 +429,13 @@ impl PerpsPool {
         // Linear scaling: rate = base_rate * (1 + utilization_ratio)
         // At 0% utilization: rate = base_rate
         // At 80% utilization: rate = base_rate * 1.8
-        let rate = (self.base_borrow_rate_bps as u32)
-            .checked_mul(10000 + utilization as u32)?
-            .checked_div(10000)?;
+        let rate = mul_div(
+            self.base_borrow_rate_bps as u128,
+            BPS_SCALE + utilization as u128,
+            BPS_SCALE,
+        )?;
 
-        Some(rate.min(u16::MAX as u32) as u16)
+        Some(rate.min(u16::MAX as u128) as u16)
     }
 
     /// Check if pool can handle a new position
@@ -332,3 +459,123 @@ impl PerpsPool {
             && quote_token.can_lock(quote_lock_amount, self.max_utilization_bps)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two-token pool: 1000 units of each at $1 (6 decimals), 50/50 targets
+    fn balanced_pool() -> (PerpsPool, [u64; MAX_PERPS_TOKENS]) {
+        let mut pool = PerpsPool {
+            num_tokens: 2,
+            max_imbalance_fee_bps: 100,
+            ..Default::default()
+        };
+        for token in pool.tokens.iter_mut().take(2) {
+            token.balance = 1_000_000_000;
+            token.decimals = 6;
+            token.is_active = true;
+            token.target_weight_bps = 5_000;
+        }
+        let mut prices = [0u64; MAX_PERPS_TOKENS];
+        prices[0] = 1_000_000;
+        prices[1] = 1_000_000;
+        (pool, prices)
+    }
+
+    #[test]
+    fn test_imbalance_fee_only_when_moving_away_from_target() {
+        let (mut pool, prices) = balanced_pool();
+        assert_eq!(pool.total_weight_deviation_bps(&prices), Some(0));
+
+        // Deposit of 1000 USD into token 0 moves it to 66.6% (+1666 bps)
+        let fee = pool.liquidity_imbalance_fee_bps(0, 1_000_000_000, true, &prices).unwrap();
+        assert_eq!(fee, 16);
+
+        // Once overweight, withdrawing that token pulls it back: no fee
+        pool.tokens[0].balance = 2_000_000_000;
+        assert_eq!(pool.liquidity_imbalance_fee_bps(0, 500_000_000, false, &prices), Some(0));
+        // Depositing the underweight token is also free
+        assert_eq!(pool.liquidity_imbalance_fee_bps(1, 500_000_000, true, &prices), Some(0));
+    }
+
+    #[test]
+    fn test_imbalance_fee_disabled_without_target() {
+        let (mut pool, prices) = balanced_pool();
+        pool.tokens[0].target_weight_bps = 0;
+        assert_eq!(pool.liquidity_imbalance_fee_bps(0, 1_000_000_000, true, &prices), Some(0));
+
+        let (mut pool, prices) = balanced_pool();
+        pool.max_imbalance_fee_bps = 0;
+        assert_eq!(pool.liquidity_imbalance_fee_bps(0, 1_000_000_000, true, &prices), Some(0));
+    }
+
+    #[test]
+    fn test_rebalance_reduces_weight_deviation() {
+        let (mut pool, prices) = balanced_pool();
+        pool.tokens[0].balance = 3_000_000_000;
+        let before = pool.total_weight_deviation_bps(&prices).unwrap();
+        assert_eq!(before, 5_000);
+
+        // Swap 500 of token 1 in for 500 of token 0 out
+        pool.tokens[1].balance += 500_000_000;
+        pool.tokens[0].balance -= 500_000_000;
+        let after = pool.total_weight_deviation_bps(&prices).unwrap();
+        assert!(after < before);
+        assert_eq!(pool.current_weight_bps(0, &prices), Some(6_250));
+    }
+
+    #[test]
+    fn test_rebalance_fee_rounds_up() {
+        let mut pool = PerpsPool {
+            rebalance_fee_bps: 10,
+            ..Default::default()
+        };
+        assert_eq!(pool.rebalance_fee(1_000_000), 1_000);
+        assert_eq!(pool.rebalance_fee(1_001), 2);
+
+        pool.rebalance_fee_bps = 0;
+        assert_eq!(pool.rebalance_fee(1_000_000), 0);
+    }
+
+    #[test]
+    fn test_min_position_fee_maker_discount() {
+        let pool = PerpsPool {
+            position_fee_bps: 6,
+            maker_fee_bps: 2,
+            ..Default::default()
+        };
+        assert_eq!(pool.min_position_fee(1_000_000, false), 600);
+        assert_eq!(pool.min_position_fee(1_000_000, true), 200);
+    }
+
+    #[test]
+    fn test_position_minimums() {
+        let mut pool = PerpsPool::default();
+        assert!(pool.meets_position_minimums(1, 1));
+
+        pool.min_margin = 1_000;
+        pool.min_position_size = 10_000;
+        assert!(pool.meets_position_minimums(1_000, 10_000));
+        assert!(!pool.meets_position_minimums(999, 10_000));
+        assert!(!pool.meets_position_minimums(1_000, 9_999));
+    }
+
+    #[test]
+    fn test_liquidation_reward_urgency_bonus() {
+        let mut pool = PerpsPool {
+            liquidation_threshold_bps: 50,
+            liquidation_penalty_bps: 50,
+            liquidation_bonus_bps: 100,
+            ..Default::default()
+        };
+        // Threshold = 5_000 of 1_000_000 margin
+        assert_eq!(pool.liquidation_reward(1_000_000, 5_000), 5_000);
+        assert_eq!(pool.liquidation_reward(1_000_000, 2_500), 10_000);
+        assert_eq!(pool.liquidation_reward(1_000_000, 0), 15_000);
+
+        // Without a bonus the reward is the flat penalty
+        pool.liquidation_bonus_bps = 0;
+        assert_eq!(pool.liquidation_reward(1_000_000, 0), 5_000);
+    }
+}
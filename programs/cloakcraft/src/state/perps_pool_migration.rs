@@ -0,0 +1,92 @@
+//! Perps pool migration (old pool -> v2 pool)
+//!
+//! Moves a perps pool's assets to a new `PerpsPool` when its configuration or
+//! layout can't be fixed in place. `begin_pool_migration` freezes both pools
+//! (the old one for good), `migrate_token_vault` moves each token's vault
+//! balance, and `finalize_pool_migration` reserves the old LP supply in the
+//! new pool at a fixed conversion rate and unfreezes it. Old LP notes are
+//! then converted one by one with `create_pending_with_proof_convert_lp_note`.
+//!
+//! Migration requires the old pool to have no open positions (nothing
+//! locked) and the new pool to hold no LP yet, so the reserved supply prices
+//! the migrated assets exactly.
+
+use anchor_lang::prelude::*;
+
+use crate::helpers::fixed::{mul_div, saturating_u64};
+use super::MAX_PERPS_TOKENS;
+
+/// Migration of one perps pool, keyed by the old pool
+#[account]
+#[derive(Default, InitSpace)]
+pub struct PerpsPoolMigration {
+    /// Pool being migrated away from (frozen)
+    pub old_pool: Pubkey,
+
+    /// Pool receiving the assets
+    pub new_pool: Pubkey,
+
+    /// When the migration began
+    pub started_at: i64,
+
+    /// Bit `i` set once token `i`'s vault has been moved
+    pub migrated_vaults: u8,
+
+    /// Whether the conversion rate is registered (LP notes convertible)
+    pub finalized: bool,
+
+    /// Old pool LP supply at finalization
+    pub old_lp_supply: u64,
+
+    /// New pool LP reserved for it (rate = new_lp_supply / old_lp_supply)
+    pub new_lp_supply: u64,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl PerpsPoolMigration {
+    /// Seeds prefix: ["perps_migration", old_pool]
+    pub const SEEDS_PREFIX: &'static [u8] = b"perps_migration";
+
+    /// Whether every one of `num_tokens` vaults has been moved
+    pub fn all_vaults_migrated(&self, num_tokens: u8) -> bool {
+        let num_tokens = (num_tokens as usize).min(MAX_PERPS_TOKENS);
+        let required = ((1u16 << num_tokens) - 1) as u8;
+        self.migrated_vaults & required == required
+    }
+
+    /// New pool LP an old LP note of `amount` converts to (rounded down)
+    pub fn convert_lp_amount(&self, amount: u64) -> Option<u64> {
+        if !self.finalized || self.old_lp_supply == 0 {
+            return None;
+        }
+        mul_div(amount as u128, self.new_lp_supply as u128, self.old_lp_supply as u128).map(saturating_u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lp_conversion() {
+        let mut migration = PerpsPoolMigration::default();
+        migration.migrated_vaults = 0b011;
+        assert!(migration.all_vaults_migrated(2));
+        assert!(!migration.all_vaults_migrated(3));
+        migration.migrated_vaults = 0xff;
+        assert!(migration.all_vaults_migrated(8));
+
+        // Not convertible before finalization
+        assert_eq!(migration.convert_lp_amount(100), None);
+
+        migration.finalized = true;
+        migration.old_lp_supply = 3_000;
+        migration.new_lp_supply = 1_000;
+        assert_eq!(migration.convert_lp_amount(3_000), Some(1_000));
+        // Rounds down, so conversions never exceed the reserved supply
+        assert_eq!(migration.convert_lp_amount(1_000), Some(333));
+        assert_eq!(migration.convert_lp_amount(2), Some(0));
+    }
+}
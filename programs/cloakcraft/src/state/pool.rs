@@ -5,6 +5,30 @@
 
 use anchor_lang::prelude::*;
 
+use crate::constants::ANONYMITY_EPOCH_SECONDS;
+use super::commitment::{ENCRYPTED_NOTE_HARD_CAP, MAX_ENCRYPTED_NOTE_SIZE};
+
+/// Maximum number of fixed denominations per pool
+pub const MAX_DENOMINATIONS: usize = 8;
+
+/// Pool holds a fungible token
+pub const NFT_STANDARD_NONE: u8 = 0;
+/// Pool holds a single SPL NFT (decimals 0, supply 1)
+pub const NFT_STANDARD_NFT: u8 = 1;
+/// Pool holds a single Metaplex programmable NFT (custody via token records)
+pub const NFT_STANDARD_PNFT: u8 = 2;
+/// Pool holds a single Bubblegum compressed NFT (token_mint = asset id, no vault)
+pub const NFT_STANDARD_CNFT: u8 = 3;
+
+/// Light Protocol trees a pool's compressed accounts must use
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolTrees {
+    /// Output state tree (the V2 output queue) new accounts are appended to
+    pub state_tree: Pubkey,
+    /// Address tree commitment and nullifier addresses are derived in
+    pub address_tree: Pubkey,
+}
+
 /// Shielded pool for a single token
 ///
 /// Note: Merkle tree state (commitments, roots) is now stored in Light Protocol
@@ -29,6 +53,77 @@ pub struct Pool {
 
     /// Vault bump seed
     pub vault_bump: u8,
+
+    /// Activity epoch the current counters belong to
+    pub activity_epoch: u64,
+
+    /// Shields in the current activity epoch
+    pub epoch_shields: u32,
+
+    /// Spends (nullifiers created) in the current activity epoch
+    pub epoch_spends: u32,
+
+    /// Shields in the previous activity epoch
+    pub prev_epoch_shields: u32,
+
+    /// Spends in the previous activity epoch
+    pub prev_epoch_spends: u32,
+
+    /// Lifetime shields
+    pub total_shields: u64,
+
+    /// Lifetime spends
+    pub total_spends: u64,
+
+    /// Minimum recent anonymity set before unshields are allowed (0 = disabled)
+    pub min_anonymity_guard: u32,
+
+    /// Guard is bypassed until this timestamp (authority override)
+    pub guard_override_until: i64,
+
+    /// Allowed note amounts, ascending (all zero = flexible amounts)
+    pub fixed_denominations: [u64; MAX_DENOMINATIONS],
+
+    /// NFT_STANDARD_* (set by the first shield_nft; NONE for fungible pools)
+    pub nft_standard: u8,
+
+    /// Hash of the NFT's metadata account at first shield (field element, bound by the NFT circuit)
+    pub nft_metadata_hash: [u8; 32],
+
+    /// Light output state tree (V2 output queue) for new compressed accounts
+    pub state_tree: Pubkey,
+
+    /// Light address tree for commitment / nullifier addresses (fixed for the pool's lifetime)
+    pub address_tree: Pubkey,
+
+    /// State tree that replaces `state_tree` at `tree_cutover_slot` (default = none scheduled)
+    pub next_state_tree: Pubkey,
+
+    /// Slot from which new accounts must go to `next_state_tree`
+    pub tree_cutover_slot: u64,
+
+    /// Dust threshold: change notes below this are rejected (0 = no threshold)
+    pub min_note_amount: u64,
+
+    /// Only relayers on the pool's RelayerAllowlist may submit operations
+    pub relayer_allowlist_enabled: bool,
+
+    /// Root registry roots older than this many slots are rejected (0 = no limit)
+    pub max_root_age_slots: u64,
+
+    /// Largest encrypted note `create_commitment` stores (0 = MAX_ENCRYPTED_NOTE_SIZE)
+    pub max_encrypted_note_size: u16,
+
+    /// Operations moving at least this much through the pool must verify
+    /// inputs against a pinned root with a validity proof (0 = never)
+    pub pinned_inclusion_threshold: u64,
+
+    /// Layout the account was created with or last migrated to (see `migrate_pool`)
+    pub layout_version: u8,
+
+    /// AMM pool `sync_reserves` folds this vault's surplus into
+    /// (default = none; takes the former reserved bytes)
+    pub surplus_amm_pool: Pubkey,
 }
 
 impl Pool {
@@ -39,5 +134,313 @@ impl Pool {
         + 8   // total_shielded
         + 32  // authority
         + 1   // bump
-        + 1;  // vault_bump
+        + 1   // vault_bump
+        + 8   // activity_epoch
+        + 4   // epoch_shields
+        + 4   // epoch_spends
+        + 4   // prev_epoch_shields
+        + 4   // prev_epoch_spends
+        + 8   // total_shields
+        + 8   // total_spends
+        + 4   // min_anonymity_guard
+        + 8   // guard_override_until
+        + 8 * MAX_DENOMINATIONS // fixed_denominations
+        + 1   // nft_standard
+        + 32  // nft_metadata_hash
+        + 32  // state_tree
+        + 32  // address_tree
+        + 32  // next_state_tree
+        + 8   // tree_cutover_slot
+        + 8   // min_note_amount
+        + 1   // relayer_allowlist_enabled
+        + 8   // max_root_age_slots
+        + 2   // max_encrypted_note_size
+        + 8   // pinned_inclusion_threshold
+        + 1   // layout_version
+        + 32; // surplus_amm_pool
+
+    /// Current account layout
+    ///
+    /// Version 0 is every layout from before `layout_version` existed; fields
+    /// added since are zero (disabled) after migration.
+    pub const LAYOUT_VERSION: u8 = 1;
+
+    /// Activity epoch containing `timestamp`
+    pub fn activity_epoch_at(timestamp: i64) -> u64 {
+        (timestamp.max(0) / ANONYMITY_EPOCH_SECONDS) as u64
+    }
+
+    /// Advance the epoch counters to the epoch containing `timestamp`
+    pub fn roll_activity_epoch(&mut self, timestamp: i64) {
+        let epoch = Self::activity_epoch_at(timestamp);
+        if epoch <= self.activity_epoch {
+            return;
+        }
+        if epoch == self.activity_epoch + 1 {
+            self.prev_epoch_shields = self.epoch_shields;
+            self.prev_epoch_spends = self.epoch_spends;
+        } else {
+            self.prev_epoch_shields = 0;
+            self.prev_epoch_spends = 0;
+        }
+        self.epoch_shields = 0;
+        self.epoch_spends = 0;
+        self.activity_epoch = epoch;
+    }
+
+    /// Count a shield into the pool
+    pub fn record_shield(&mut self, timestamp: i64) {
+        self.roll_activity_epoch(timestamp);
+        self.epoch_shields = self.epoch_shields.saturating_add(1);
+        self.total_shields = self.total_shields.saturating_add(1);
+    }
+
+    /// Count a spend (nullifier) from the pool
+    pub fn record_spend(&mut self, timestamp: i64) {
+        self.roll_activity_epoch(timestamp);
+        self.epoch_spends = self.epoch_spends.saturating_add(1);
+        self.total_spends = self.total_spends.saturating_add(1);
+    }
+
+    /// Shields + spends over the current and previous epoch as of `timestamp`
+    pub fn recent_anonymity_set(&self, timestamp: i64) -> u64 {
+        let epoch = Self::activity_epoch_at(timestamp);
+        let current = self.epoch_shields as u64 + self.epoch_spends as u64;
+        let previous = self.prev_epoch_shields as u64 + self.prev_epoch_spends as u64;
+        if epoch <= self.activity_epoch {
+            current + previous
+        } else if epoch == self.activity_epoch + 1 {
+            current
+        } else {
+            0
+        }
+    }
+
+    /// Whether the pool only accepts fixed-denomination notes
+    pub fn has_fixed_denominations(&self) -> bool {
+        self.fixed_denominations.iter().any(|&d| d > 0)
+    }
+
+    /// Whether `amount` is one of the pool's denominations
+    pub fn is_denomination(&self, amount: u64) -> bool {
+        amount > 0 && self.fixed_denominations.contains(&amount)
+    }
+
+    /// Denominations must be strictly ascending, with unused slots zeroed at the end
+    pub fn validate_denominations(denominations: &[u64; MAX_DENOMINATIONS]) -> bool {
+        let used = denominations.iter().take_while(|&&d| d > 0).count();
+        denominations[used..].iter().all(|&d| d == 0)
+            && denominations[..used].windows(2).all(|w| w[0] < w[1])
+    }
+
+    /// Whether a non-zero note `amount` is below the dust threshold
+    pub fn is_dust(&self, amount: u64) -> bool {
+        amount > 0 && amount < self.min_note_amount
+    }
+
+    /// Largest encrypted note the pool stores
+    ///
+    /// Pools created before the limit was configurable read 0 and keep the
+    /// inline capacity.
+    pub fn encrypted_note_limit(&self) -> usize {
+        match self.max_encrypted_note_size {
+            0 => MAX_ENCRYPTED_NOTE_SIZE,
+            size => size as usize,
+        }
+    }
+
+    /// A pool's note limit is at least the inline capacity and at most the hard cap
+    pub fn validate_encrypted_note_limit(size: u16) -> bool {
+        (MAX_ENCRYPTED_NOTE_SIZE..=ENCRYPTED_NOTE_HARD_CAP).contains(&(size as usize))
+    }
+
+    /// Whether an operation moving `value` must use pinned inclusion verification
+    pub fn requires_pinned_inclusion(&self, value: u64) -> bool {
+        self.pinned_inclusion_threshold > 0 && value >= self.pinned_inclusion_threshold
+    }
+
+    /// Whether the pool holds a single NFT (notes are amount = 1)
+    pub fn is_nft_pool(&self) -> bool {
+        self.nft_standard != NFT_STANDARD_NONE
+    }
+
+    /// Whether a state tree migration is scheduled
+    pub fn has_pending_tree_migration(&self) -> bool {
+        self.next_state_tree != Pubkey::default()
+    }
+
+    /// Trees new compressed accounts must use at `slot`
+    ///
+    /// Before the cutover the current state tree stays active; from the cutover
+    /// slot on only the scheduled tree is accepted. The address tree never
+    /// changes, so nullifier non-inclusion stays meaningful across migrations.
+    pub fn active_trees(&self, slot: u64) -> PoolTrees {
+        let state_tree = if self.has_pending_tree_migration() && slot >= self.tree_cutover_slot {
+            self.next_state_tree
+        } else {
+            self.state_tree
+        };
+        PoolTrees {
+            state_tree,
+            address_tree: self.address_tree,
+        }
+    }
+
+    /// Make a scheduled state tree current once its cutover slot is reached
+    pub fn promote_state_tree(&mut self, slot: u64) {
+        if self.has_pending_tree_migration() && slot >= self.tree_cutover_slot {
+            self.state_tree = self.next_state_tree;
+            self.next_state_tree = Pubkey::default();
+            self.tree_cutover_slot = 0;
+        }
+    }
+
+    /// Whether unshields are held back because recent activity is too low
+    pub fn anonymity_guard_active(&self, timestamp: i64) -> bool {
+        self.min_anonymity_guard > 0
+            && timestamp >= self.guard_override_until
+            && self.recent_anonymity_set(timestamp) < self.min_anonymity_guard as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = ANONYMITY_EPOCH_SECONDS;
+
+    #[test]
+    fn test_epoch_counters_roll() {
+        let mut pool = Pool::default();
+        pool.record_shield(10 * DAY);
+        pool.record_shield(10 * DAY + 5);
+        pool.record_spend(10 * DAY + 10);
+        assert_eq!(pool.recent_anonymity_set(10 * DAY + 20), 3);
+
+        // Next epoch: previous counters still count as recent
+        pool.record_shield(11 * DAY);
+        assert_eq!(pool.prev_epoch_shields, 2);
+        assert_eq!(pool.recent_anonymity_set(11 * DAY + 1), 4);
+
+        // Two epochs later without activity: set decays to zero
+        assert_eq!(pool.recent_anonymity_set(13 * DAY), 0);
+        pool.record_spend(13 * DAY);
+        assert_eq!(pool.prev_epoch_shields, 0);
+        assert_eq!(pool.total_shields, 3);
+        assert_eq!(pool.total_spends, 2);
+    }
+
+    #[test]
+    fn test_encrypted_note_limit() {
+        let mut pool = Pool::default();
+        assert_eq!(pool.encrypted_note_limit(), MAX_ENCRYPTED_NOTE_SIZE);
+
+        pool.max_encrypted_note_size = 400;
+        assert_eq!(pool.encrypted_note_limit(), 400);
+
+        assert!(Pool::validate_encrypted_note_limit(MAX_ENCRYPTED_NOTE_SIZE as u16));
+        assert!(Pool::validate_encrypted_note_limit(ENCRYPTED_NOTE_HARD_CAP as u16));
+        assert!(!Pool::validate_encrypted_note_limit(MAX_ENCRYPTED_NOTE_SIZE as u16 - 1));
+        assert!(!Pool::validate_encrypted_note_limit(ENCRYPTED_NOTE_HARD_CAP as u16 + 1));
+    }
+
+    #[test]
+    fn test_fixed_denominations() {
+        let mut pool = Pool::default();
+        assert!(!pool.has_fixed_denominations());
+        assert!(!pool.is_denomination(0));
+
+        let mut denoms = [0u64; MAX_DENOMINATIONS];
+        denoms[..3].copy_from_slice(&[100, 1_000, 10_000]);
+        assert!(Pool::validate_denominations(&denoms));
+        assert!(Pool::validate_denominations(&[0; MAX_DENOMINATIONS]));
+
+        pool.fixed_denominations = denoms;
+        assert!(pool.has_fixed_denominations());
+        assert!(pool.is_denomination(1_000));
+        assert!(!pool.is_denomination(999));
+        assert!(!pool.is_denomination(0));
+
+        // Unsorted, duplicated, or gapped configs are rejected
+        denoms[1] = 100;
+        assert!(!Pool::validate_denominations(&denoms));
+        let mut gapped = [0u64; MAX_DENOMINATIONS];
+        gapped[0] = 100;
+        gapped[2] = 1_000;
+        assert!(!Pool::validate_denominations(&gapped));
+    }
+
+    #[test]
+    fn test_dust_threshold() {
+        let mut pool = Pool::default();
+        assert!(!pool.is_dust(1));
+
+        pool.min_note_amount = 1_000;
+        assert!(pool.is_dust(999));
+        assert!(!pool.is_dust(1_000));
+        // No note at all is not dust
+        assert!(!pool.is_dust(0));
+    }
+
+    #[test]
+    fn test_pinned_inclusion_threshold() {
+        let mut pool = Pool::default();
+        assert!(!pool.requires_pinned_inclusion(u64::MAX));
+
+        pool.pinned_inclusion_threshold = 1_000;
+        assert!(!pool.requires_pinned_inclusion(999));
+        assert!(pool.requires_pinned_inclusion(1_000));
+    }
+
+    #[test]
+    fn test_nft_pool() {
+        let mut pool = Pool::default();
+        assert!(!pool.is_nft_pool());
+        pool.nft_standard = NFT_STANDARD_PNFT;
+        assert!(pool.is_nft_pool());
+    }
+
+    #[test]
+    fn test_tree_migration() {
+        let old_tree = Pubkey::new_unique();
+        let new_tree = Pubkey::new_unique();
+        let mut pool = Pool {
+            state_tree: old_tree,
+            address_tree: Pubkey::new_unique(),
+            ..Default::default()
+        };
+        assert_eq!(pool.active_trees(100).state_tree, old_tree);
+
+        pool.next_state_tree = new_tree;
+        pool.tree_cutover_slot = 200;
+        assert!(pool.has_pending_tree_migration());
+        assert_eq!(pool.active_trees(199).state_tree, old_tree);
+        assert_eq!(pool.active_trees(200).state_tree, new_tree);
+        assert_eq!(pool.active_trees(200).address_tree, pool.address_tree);
+
+        pool.promote_state_tree(199);
+        assert_eq!(pool.state_tree, old_tree);
+        pool.promote_state_tree(200);
+        assert_eq!(pool.state_tree, new_tree);
+        assert!(!pool.has_pending_tree_migration());
+    }
+
+    #[test]
+    fn test_anonymity_guard() {
+        let mut pool = Pool::default();
+        assert!(!pool.anonymity_guard_active(DAY));
+
+        pool.min_anonymity_guard = 2;
+        pool.record_shield(DAY);
+        assert!(pool.anonymity_guard_active(DAY));
+
+        pool.record_shield(DAY + 1);
+        assert!(!pool.anonymity_guard_active(DAY + 2));
+
+        // Activity ages out; authority override bypasses until expiry
+        assert!(pool.anonymity_guard_active(3 * DAY));
+        pool.guard_override_until = 3 * DAY + 100;
+        assert!(!pool.anonymity_guard_active(3 * DAY));
+        assert!(pool.anonymity_guard_active(3 * DAY + 100));
+    }
 }
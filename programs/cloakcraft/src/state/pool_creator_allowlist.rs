@@ -0,0 +1,80 @@
+//! AMM pool creator allowlist
+//!
+//! Singleton PDA listing the accounts allowed to create AMM pools while
+//! ProtocolConfig.amm_creation_mode is AMM_CREATION_ALLOWLISTED.
+
+use anchor_lang::prelude::*;
+
+/// Maximum number of allowlisted pool creators
+pub const MAX_POOL_CREATORS: usize = 16;
+
+/// Allowlisted AMM pool creators
+#[account]
+#[derive(Default, InitSpace)]
+pub struct PoolCreatorAllowlist {
+    /// Allowed creators (`Pubkey::default()` = free slot)
+    pub creators: [Pubkey; MAX_POOL_CREATORS],
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl PoolCreatorAllowlist {
+    /// Whether `creator` may create AMM pools
+    pub fn contains(&self, creator: &Pubkey) -> bool {
+        *creator != Pubkey::default() && self.creators.contains(creator)
+    }
+
+    /// Allow or disallow a creator
+    ///
+    /// Returns false if all slots are in use.
+    pub fn set(&mut self, creator: Pubkey, allowed: bool) -> bool {
+        if creator == Pubkey::default() {
+            return !allowed;
+        }
+        if let Some(slot) = self.creators.iter_mut().find(|c| **c == creator) {
+            if !allowed {
+                *slot = Pubkey::default();
+            }
+            return true;
+        }
+        if !allowed {
+            return true;
+        }
+        match self.creators.iter_mut().find(|c| **c == Pubkey::default()) {
+            Some(slot) => {
+                *slot = creator;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowlist_set() {
+        let mut list = PoolCreatorAllowlist::default();
+        let creator = Pubkey::new_unique();
+        assert!(!list.contains(&creator));
+        assert!(!list.contains(&Pubkey::default()));
+
+        assert!(list.set(creator, true));
+        assert!(list.contains(&creator));
+        // Allowing twice reuses the slot
+        assert!(list.set(creator, true));
+        assert_eq!(list.creators.iter().filter(|c| **c == creator).count(), 1);
+
+        assert!(list.set(creator, false));
+        assert!(!list.contains(&creator));
+
+        for _ in 0..MAX_POOL_CREATORS {
+            assert!(list.set(Pubkey::new_unique(), true));
+        }
+        assert!(!list.set(Pubkey::new_unique(), true));
+        assert!(!list.set(Pubkey::default(), true));
+    }
+}
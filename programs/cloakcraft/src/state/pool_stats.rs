@@ -0,0 +1,185 @@
+//! Pool statistics
+//!
+//! Optional per-pool PDA (`["pool_stats", pool]`) tracking shielded value,
+//! lifetime deposits/withdrawals and live note count, updated as operations
+//! execute rather than reconstructed from vault balances. Counters start when
+//! the account is created; instructions update it only when it is passed.
+
+use anchor_lang::prelude::*;
+
+use super::pool::Pool;
+
+/// Emitted when the stats roll into a new epoch, with the closed epoch's totals
+#[event]
+pub struct PoolStatsEpochRolled {
+    pub pool: Pubkey,
+    /// Epoch that just closed
+    pub epoch: u64,
+    /// Deposits during the closed epoch
+    pub epoch_deposits: u64,
+    /// Withdrawals (unshields + fees) during the closed epoch
+    pub epoch_withdrawals: u64,
+    /// Shielded value at rollover
+    pub total_shielded: u64,
+    /// Live notes at rollover
+    pub note_count: u64,
+}
+
+/// Pool statistics account
+#[account]
+#[derive(InitSpace)]
+pub struct PoolStats {
+    /// Pool these stats belong to
+    pub pool: Pubkey,
+
+    /// Value currently shielded (deposits - withdrawals - fees)
+    pub total_shielded: u64,
+
+    /// Lifetime deposits (shields)
+    pub lifetime_deposits: u64,
+
+    /// Lifetime withdrawals (unshields)
+    pub lifetime_withdrawals: u64,
+
+    /// Lifetime protocol fees paid out of the pool
+    pub lifetime_fees: u64,
+
+    /// Live notes (commitments created - nullifiers created)
+    pub note_count: u64,
+
+    /// Lifetime commitments created
+    pub total_notes_created: u64,
+
+    /// Epoch the epoch counters belong to (same length as activity epochs)
+    pub epoch: u64,
+
+    /// Deposits in the current epoch
+    pub epoch_deposits: u64,
+
+    /// Withdrawals (unshields + fees) in the current epoch
+    pub epoch_withdrawals: u64,
+
+    /// Last update timestamp
+    pub updated_at: i64,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl PoolStats {
+    /// Advance to the epoch containing `timestamp`, returning the closed epoch's totals
+    pub fn roll_epoch(&mut self, timestamp: i64) -> Option<PoolStatsEpochRolled> {
+        let epoch = Pool::activity_epoch_at(timestamp);
+        self.updated_at = timestamp;
+        if epoch <= self.epoch {
+            return None;
+        }
+
+        let rolled = PoolStatsEpochRolled {
+            pool: self.pool,
+            epoch: self.epoch,
+            epoch_deposits: self.epoch_deposits,
+            epoch_withdrawals: self.epoch_withdrawals,
+            total_shielded: self.total_shielded,
+            note_count: self.note_count,
+        };
+        self.epoch = epoch;
+        self.epoch_deposits = 0;
+        self.epoch_withdrawals = 0;
+        Some(rolled)
+    }
+
+    /// Count a deposit that created one note
+    pub fn record_deposit(&mut self, amount: u64, timestamp: i64) -> Option<PoolStatsEpochRolled> {
+        let rolled = self.roll_epoch(timestamp);
+        self.total_shielded = self.total_shielded.saturating_add(amount);
+        self.lifetime_deposits = self.lifetime_deposits.saturating_add(amount);
+        self.epoch_deposits = self.epoch_deposits.saturating_add(amount);
+        self.note_created();
+        rolled
+    }
+
+    /// Count value leaving the pool (unshield and protocol fee)
+    pub fn record_withdrawal(&mut self, amount: u64, fee: u64, timestamp: i64) -> Option<PoolStatsEpochRolled> {
+        let rolled = self.roll_epoch(timestamp);
+        let total = amount.saturating_add(fee);
+        self.total_shielded = self.total_shielded.saturating_sub(total);
+        self.lifetime_withdrawals = self.lifetime_withdrawals.saturating_add(amount);
+        self.lifetime_fees = self.lifetime_fees.saturating_add(fee);
+        self.epoch_withdrawals = self.epoch_withdrawals.saturating_add(total);
+        rolled
+    }
+
+    /// Count a commitment created in the pool
+    pub fn record_note_created(&mut self, timestamp: i64) -> Option<PoolStatsEpochRolled> {
+        let rolled = self.roll_epoch(timestamp);
+        self.note_created();
+        rolled
+    }
+
+    /// Count a nullifier created in the pool (notes from before the stats existed floor at 0)
+    pub fn record_note_spent(&mut self, timestamp: i64) -> Option<PoolStatsEpochRolled> {
+        let rolled = self.roll_epoch(timestamp);
+        self.note_count = self.note_count.saturating_sub(1);
+        rolled
+    }
+
+    fn note_created(&mut self) {
+        self.note_count = self.note_count.saturating_add(1);
+        self.total_notes_created = self.total_notes_created.saturating_add(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ANONYMITY_EPOCH_SECONDS;
+
+    fn stats() -> PoolStats {
+        PoolStats {
+            pool: Pubkey::new_unique(),
+            total_shielded: 0,
+            lifetime_deposits: 0,
+            lifetime_withdrawals: 0,
+            lifetime_fees: 0,
+            note_count: 0,
+            total_notes_created: 0,
+            epoch: 0,
+            epoch_deposits: 0,
+            epoch_withdrawals: 0,
+            updated_at: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_pool_stats_accounting() {
+        let mut s = stats();
+        assert!(s.record_deposit(1_000, 10).is_none());
+        assert!(s.record_note_spent(20).is_none());
+        assert!(s.record_note_created(20).is_none());
+        assert!(s.record_withdrawal(400, 10, 30).is_none());
+
+        assert_eq!(s.total_shielded, 590);
+        assert_eq!(s.lifetime_deposits, 1_000);
+        assert_eq!(s.lifetime_withdrawals, 400);
+        assert_eq!(s.lifetime_fees, 10);
+        assert_eq!(s.note_count, 1);
+        assert_eq!(s.total_notes_created, 2);
+
+        // Spends of notes from before the account existed floor at zero
+        s.record_note_spent(40);
+        s.record_note_spent(40);
+        assert_eq!(s.note_count, 0);
+
+        // Rollover reports the closed epoch and resets epoch counters
+        let rolled = s.record_deposit(5, ANONYMITY_EPOCH_SECONDS * 2).unwrap();
+        assert_eq!(rolled.epoch, 0);
+        assert_eq!(rolled.epoch_deposits, 1_000);
+        assert_eq!(rolled.epoch_withdrawals, 410);
+        assert_eq!(rolled.total_shielded, 590);
+        assert_eq!(s.epoch, 2);
+        assert_eq!(s.epoch_deposits, 5);
+        assert_eq!(s.epoch_withdrawals, 0);
+    }
+}
@@ -26,6 +26,8 @@ pub enum PositionStatus {
     Liquidated = 1,
     /// Position was closed normally by owner
     Closed = 2,
+    /// Position was transferred to a new stealth owner - superseded by a new PositionMeta
+    Transferred = 3,
 }
 
 impl From<u8> for PositionStatus {
@@ -34,6 +36,7 @@ impl From<u8> for PositionStatus {
             0 => PositionStatus::Active,
             1 => PositionStatus::Liquidated,
             2 => PositionStatus::Closed,
+            3 => PositionStatus::Transferred,
             _ => PositionStatus::Active,
         }
     }
@@ -109,6 +112,19 @@ pub struct PositionMeta {
     /// Owner's stealth address (for notifications, optional)
     /// Not used for auth - just for off-chain indexing
     pub owner_stealth_pubkey: [u8; 32],
+
+    // =========================================================================
+    // Fee Accounting
+    // =========================================================================
+
+    /// Borrow token's `cumulative_borrow_fee` at open (1e18 scale)
+    /// Accrued fee on close/liquidation = size * (current - entry) / 1e18
+    pub entry_borrow_fee: u128,
+
+    /// Position commitment this metadata describes (from the open/transfer
+    /// proof). Close and liquidation proofs expose the commitment they spend,
+    /// which must match before `entry_borrow_fee` is charged.
+    pub position_commitment: [u8; 32],
 }
 
 impl Default for PositionMeta {
@@ -127,6 +143,8 @@ impl Default for PositionMeta {
             created_at: 0,
             updated_at: 0,
             owner_stealth_pubkey: [0u8; 32],
+            entry_borrow_fee: 0,
+            position_commitment: [0u8; 32],
         }
     }
 }
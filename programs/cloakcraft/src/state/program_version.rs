@@ -0,0 +1,85 @@
+//! Program version guard
+//!
+//! Singleton PDA (`["program_version"]`) bumped by the protocol authority on
+//! each deploy. Phase 0 instructions take the client's version tag and reject
+//! clients below `min_client_version`, so a client built for an older note
+//! format or circuit set fails up front instead of creating notes the new
+//! program (or the client itself) can no longer spend.
+
+use anchor_lang::prelude::*;
+
+use crate::errors::CloakCraftError;
+
+/// Program version account
+#[account]
+#[derive(InitSpace)]
+pub struct ProgramVersion {
+    /// Deployed program version (monotonic, bumped on each deploy)
+    pub version: u32,
+
+    /// Oldest client version compatible with the deployed program
+    pub min_client_version: u32,
+
+    /// Slot of the last bump
+    pub updated_slot: u64,
+
+    /// Timestamp of the last bump
+    pub updated_at: i64,
+
+    /// PDA bump seed
+    pub bump: u8,
+
+    /// Reserved for future use
+    pub _reserved: [u8; 32],
+}
+
+impl ProgramVersion {
+    /// Whether a version bump is valid: never backwards, minimum within range
+    pub fn is_valid_update(&self, version: u32, min_client_version: u32) -> bool {
+        version >= self.version
+            && min_client_version >= self.min_client_version
+            && min_client_version <= version
+    }
+
+    /// Reject clients older than `min_client_version`
+    pub fn check_client(&self, client_version: u32) -> Result<()> {
+        if client_version < self.min_client_version {
+            msg!(
+                "Client version {} < minimum {} (program version {})",
+                client_version, self.min_client_version, self.version
+            );
+            return err!(CloakCraftError::StaleClientVersion);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(version: u32, min_client_version: u32) -> ProgramVersion {
+        ProgramVersion {
+            version,
+            min_client_version,
+            updated_slot: 0,
+            updated_at: 0,
+            bump: 0,
+            _reserved: [0u8; 32],
+        }
+    }
+
+    #[test]
+    fn test_program_version_guard() {
+        let v = version(3, 2);
+        assert!(v.check_client(2).is_ok());
+        assert!(v.check_client(5).is_ok());
+        assert!(v.check_client(1).is_err());
+
+        assert!(v.is_valid_update(4, 4));
+        assert!(v.is_valid_update(3, 2));
+        assert!(!v.is_valid_update(2, 2)); // downgrade
+        assert!(!v.is_valid_update(4, 1)); // minimum lowered
+        assert!(!v.is_valid_update(4, 5)); // minimum above program
+    }
+}
@@ -3,9 +3,71 @@
 //! Stores fee rates and treasury address for protocol fee collection.
 //! Fee operations: transfer, unshield, swap, remove_liquidity
 //! Free operations: shield, add_liquidity, consolidate (add value to protocol)
+//!
+//! Fee rates can be overridden per token mint (e.g. stablecoins cheaper than
+//! volatile assets), and every rate the authority sets must lie within the
+//! admin-set fee floor / ceiling.
+//!
+//! Fees accrue in the treasury's token account for each mint; the treasury
+//! can have `convert_treasury_fees` swap them into one canonical asset through
+//! the internal AMM, within a configured price impact bound.
+//!
+//! Also stores the PendingOperation expiry, with per-operation-type overrides,
+//! and the operation epoch that every operation ID must carry.
 
 use anchor_lang::prelude::*;
 
+use crate::errors::CloakCraftError;
+use crate::helpers::fixed::apply_bps;
+use super::pending_operation::PENDING_OPERATION_EXPIRY_SECONDS;
+
+/// Maximum number of per-operation-type expiry overrides
+pub const MAX_PENDING_EXPIRY_OVERRIDES: usize = 8;
+
+/// Shortest allowed PendingOperation expiry (1 minute)
+pub const MIN_PENDING_EXPIRY_SECONDS: u32 = 60;
+
+/// Longest allowed PendingOperation expiry (1 hour)
+pub const MAX_PENDING_EXPIRY_SECONDS: u32 = 3600;
+
+/// Fold vault surplus into AMM reserves (donation to LPs)
+pub const SURPLUS_POLICY_RESERVES: u8 = 0;
+/// Sweep vault surplus to the treasury
+pub const SURPLUS_POLICY_TREASURY: u8 = 1;
+
+/// Anyone may create AMM pools
+pub const AMM_CREATION_PERMISSIONLESS: u8 = 0;
+/// Only PoolCreatorAllowlist members may create AMM pools
+pub const AMM_CREATION_ALLOWLISTED: u8 = 1;
+
+/// Highest price impact a treasury fee conversion may be allowed (10%)
+pub const MAX_CONVERSION_PRICE_IMPACT_BPS: u16 = 1000;
+
+/// Maximum number of per-mint fee overrides
+pub const MAX_FEE_OVERRIDES: usize = 8;
+
+/// Fee rates for one token mint (`mint == Pubkey::default()` = free slot)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct FeeOverride {
+    /// Token mint the rates apply to
+    pub mint: Pubkey,
+    /// Transfer fee in basis points
+    pub transfer_fee_bps: u16,
+    /// Unshield fee in basis points
+    pub unshield_fee_bps: u16,
+    /// Remove liquidity fee in basis points
+    pub remove_liquidity_fee_bps: u16,
+}
+
+/// Expiry override for one operation type (`expiry_seconds == 0` = free slot)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct PendingExpiryOverride {
+    /// Operation type (see `constants::operation_types`)
+    pub operation_type: u8,
+    /// Expiry in seconds
+    pub expiry_seconds: u32,
+}
+
 /// Protocol configuration account
 ///
 /// Stores fee rates in basis points (10000 = 100%) and treasury address.
@@ -42,8 +104,53 @@ pub struct ProtocolConfig {
     /// PDA bump seed
     pub bump: u8,
 
+    /// Default PendingOperation expiry in seconds (0 = PENDING_OPERATION_EXPIRY_SECONDS)
+    pub pending_expiry_seconds: u32,
+
+    /// Per-operation-type expiry overrides
+    pub pending_expiry_overrides: [PendingExpiryOverride; MAX_PENDING_EXPIRY_OVERRIDES],
+
+    /// Share of a closed PendingOperation's rent refunded to the user's proven
+    /// unshield recipient, in basis points (rest goes to the relayer)
+    pub rent_refund_bps: u16,
+
+    /// What `sync_reserves` does with surplus vault balance
+    /// (SURPLUS_POLICY_RESERVES or SURPLUS_POLICY_TREASURY)
+    pub surplus_policy: u8,
+
+    /// Who may create AMM pools
+    /// (AMM_CREATION_PERMISSIONLESS or AMM_CREATION_ALLOWLISTED)
+    pub amm_creation_mode: u8,
+
+    /// SOL fee (lamports) paid to the treasury on AMM pool creation
+    pub amm_creation_fee_lamports: u64,
+
+    /// Operation epoch, bumped by the authority on incident response
+    /// Operation IDs carry it in their first 4 bytes (little-endian), so
+    /// bumping it invalidates every in-flight operation that has not yet
+    /// spent its inputs.
+    pub operation_epoch: u32,
+
+    /// Every Phase 0 requires an operation credit (see `state::operation_credit`)
+    pub require_operation_credits: bool,
+
     /// Reserved for future use
-    pub _reserved: [u8; 62],
+    pub _reserved: [u8; 1],
+
+    /// Lowest fee rate the authority may set, in basis points
+    pub min_fee_bps: u16,
+
+    /// Highest fee rate the authority may set, in basis points (0 = MAX_FEE_BPS)
+    pub max_fee_bps: u16,
+
+    /// Per-mint fee rates, used instead of the global rates for that mint
+    pub fee_overrides: [FeeOverride; MAX_FEE_OVERRIDES],
+
+    /// Canonical asset treasury fees convert into (default = conversion disabled)
+    pub treasury_conversion_mint: Pubkey,
+
+    /// Highest price impact a treasury fee conversion may have, in basis points
+    pub max_conversion_price_impact_bps: u16,
 }
 
 impl Default for ProtocolConfig {
@@ -57,7 +164,20 @@ impl Default for ProtocolConfig {
             remove_liquidity_fee_bps: 0,
             fees_enabled: false,
             bump: 0,
-            _reserved: [0u8; 62],
+            pending_expiry_seconds: 0,
+            pending_expiry_overrides: [PendingExpiryOverride::default(); MAX_PENDING_EXPIRY_OVERRIDES],
+            rent_refund_bps: 0,
+            surplus_policy: SURPLUS_POLICY_RESERVES,
+            amm_creation_mode: AMM_CREATION_PERMISSIONLESS,
+            amm_creation_fee_lamports: 0,
+            operation_epoch: 0,
+            require_operation_credits: false,
+            _reserved: [0u8; 1],
+            min_fee_bps: 0,
+            max_fee_bps: 0,
+            fee_overrides: [FeeOverride::default(); MAX_FEE_OVERRIDES],
+            treasury_conversion_mint: Pubkey::default(),
+            max_conversion_price_impact_bps: 0,
         }
     }
 }
@@ -73,28 +193,68 @@ impl ProtocolConfig {
         + 2   // remove_liquidity_fee_bps
         + 1   // fees_enabled
         + 1   // bump
-        + 62; // reserved
+        + 4   // pending_expiry_seconds
+        + 5 * MAX_PENDING_EXPIRY_OVERRIDES // pending_expiry_overrides
+        + 2   // rent_refund_bps
+        + 1   // surplus_policy
+        + 1   // amm_creation_mode
+        + 8   // amm_creation_fee_lamports
+        + 4   // operation_epoch
+        + 2   // reserved
+        + 2   // min_fee_bps
+        + 2   // max_fee_bps
+        + 38 * MAX_FEE_OVERRIDES // fee_overrides
+        + 32  // treasury_conversion_mint
+        + 2;  // max_conversion_price_impact_bps
 
     /// Maximum fee in basis points (10% = 1000 bps)
     pub const MAX_FEE_BPS: u16 = 1000;
 
+    /// Maximum rent refund share (100%)
+    pub const MAX_RENT_REFUND_BPS: u16 = 10000;
+
+    /// Fee override for a token mint, if one is set
+    pub fn fee_override(&self, mint: &Pubkey) -> Option<&FeeOverride> {
+        if *mint == Pubkey::default() {
+            return None;
+        }
+        self.fee_overrides.iter().find(|o| o.mint == *mint)
+    }
+
+    /// Transfer fee rate for a token mint
+    pub fn transfer_fee_bps_for(&self, mint: &Pubkey) -> u16 {
+        self.fee_override(mint).map_or(self.transfer_fee_bps, |o| o.transfer_fee_bps)
+    }
+
+    /// Unshield fee rate for a token mint
+    pub fn unshield_fee_bps_for(&self, mint: &Pubkey) -> u16 {
+        self.fee_override(mint).map_or(self.unshield_fee_bps, |o| o.unshield_fee_bps)
+    }
+
+    /// Remove liquidity fee rate for a token mint
+    pub fn remove_liquidity_fee_bps_for(&self, mint: &Pubkey) -> u16 {
+        self.fee_override(mint).map_or(self.remove_liquidity_fee_bps, |o| o.remove_liquidity_fee_bps)
+    }
+
     /// Calculate fee amount from transfer amount
     /// Returns (fee_amount, amount_after_fee)
-    pub fn calculate_transfer_fee(&self, amount: u64) -> (u64, u64) {
-        if !self.fees_enabled || self.transfer_fee_bps == 0 {
+    pub fn calculate_transfer_fee(&self, mint: &Pubkey, amount: u64) -> (u64, u64) {
+        let fee_bps = self.transfer_fee_bps_for(mint);
+        if !self.fees_enabled || fee_bps == 0 {
             return (0, amount);
         }
-        let fee = self.calculate_fee(amount, self.transfer_fee_bps);
+        let fee = self.calculate_fee(amount, fee_bps);
         (fee, amount.saturating_sub(fee))
     }
 
     /// Calculate fee amount from unshield amount
     /// Returns (fee_amount, amount_after_fee)
-    pub fn calculate_unshield_fee(&self, amount: u64) -> (u64, u64) {
-        if !self.fees_enabled || self.unshield_fee_bps == 0 {
+    pub fn calculate_unshield_fee(&self, mint: &Pubkey, amount: u64) -> (u64, u64) {
+        let fee_bps = self.unshield_fee_bps_for(mint);
+        if !self.fees_enabled || fee_bps == 0 {
             return (0, amount);
         }
-        let fee = self.calculate_fee(amount, self.unshield_fee_bps);
+        let fee = self.calculate_fee(amount, fee_bps);
         (fee, amount.saturating_sub(fee))
     }
 
@@ -118,23 +278,148 @@ impl ProtocolConfig {
 
     /// Calculate fee amount from remove liquidity amount
     /// Returns (fee_amount, amount_after_fee)
-    pub fn calculate_remove_liquidity_fee(&self, amount: u64) -> (u64, u64) {
-        if !self.fees_enabled || self.remove_liquidity_fee_bps == 0 {
+    pub fn calculate_remove_liquidity_fee(&self, mint: &Pubkey, amount: u64) -> (u64, u64) {
+        let fee_bps = self.remove_liquidity_fee_bps_for(mint);
+        if !self.fees_enabled || fee_bps == 0 {
             return (0, amount);
         }
-        let fee = self.calculate_fee(amount, self.remove_liquidity_fee_bps);
+        let fee = self.calculate_fee(amount, fee_bps);
         (fee, amount.saturating_sub(fee))
     }
 
     /// Fee calculation: (amount * fee_bps) / 10000
     pub fn calculate_fee(&self, amount: u64, fee_bps: u16) -> u64 {
-        // Use u128 to avoid overflow during multiplication
-        let fee = (amount as u128)
-            .checked_mul(fee_bps as u128)
-            .unwrap_or(0)
-            .checked_div(10000)
-            .unwrap_or(0);
-        fee as u64
+        apply_bps(amount, fee_bps)
+    }
+
+    /// Minimum fee for a transfer of `mint` (0 while fees are disabled)
+    ///
+    /// Charged on the total value leaving the sender's control.
+    pub fn expected_transfer_fee(&self, mint: &Pubkey, transfer_amount: u64, unshield_amount: u64) -> Result<u64> {
+        if !self.fees_enabled {
+            return Ok(0);
+        }
+        let total_taxable = transfer_amount
+            .checked_add(unshield_amount)
+            .ok_or(CloakCraftError::AmountOverflow)?;
+        Ok(self.calculate_fee(total_taxable, self.transfer_fee_bps_for(mint)))
+    }
+
+    /// Effective fee ceiling (accounts created before bounds read 0)
+    pub fn fee_ceiling_bps(&self) -> u16 {
+        match self.max_fee_bps {
+            0 => Self::MAX_FEE_BPS,
+            max => max,
+        }
+    }
+
+    /// Whether a fee rate lies within the fee floor and ceiling
+    pub fn fee_within_bounds(&self, fee_bps: u16) -> bool {
+        (self.min_fee_bps..=self.fee_ceiling_bps()).contains(&fee_bps)
+    }
+
+    /// Whether every global and per-mint fee rate lies within the bounds
+    ///
+    /// Checked after every fee, override or bounds change, so the rates in
+    /// force always respect the bounds.
+    pub fn fees_within_bounds(&self) -> bool {
+        let global = [self.transfer_fee_bps, self.unshield_fee_bps, self.remove_liquidity_fee_bps];
+        global.iter().all(|&fee| self.fee_within_bounds(fee))
+            && self
+                .fee_overrides
+                .iter()
+                .filter(|o| o.mint != Pubkey::default())
+                .all(|o| {
+                    self.fee_within_bounds(o.transfer_fee_bps)
+                        && self.fee_within_bounds(o.unshield_fee_bps)
+                        && self.fee_within_bounds(o.remove_liquidity_fee_bps)
+                })
+    }
+
+    /// Set or clear (`fee_override == None`) a mint's fee rates
+    ///
+    /// Returns false if all override slots are in use.
+    pub fn set_fee_override(&mut self, mint: Pubkey, fee_override: Option<FeeOverride>) -> bool {
+        let existing = self.fee_overrides.iter_mut().find(|o| o.mint == mint);
+        match (existing, fee_override) {
+            (Some(slot), Some(rates)) => {
+                *slot = FeeOverride { mint, ..rates };
+                true
+            }
+            (Some(slot), None) => {
+                *slot = FeeOverride::default();
+                true
+            }
+            (None, None) => true,
+            (None, Some(rates)) => {
+                match self.fee_overrides.iter_mut().find(|o| o.mint == Pubkey::default()) {
+                    Some(slot) => {
+                        *slot = FeeOverride { mint, ..rates };
+                        true
+                    }
+                    None => false,
+                }
+            }
+        }
+    }
+
+    /// Whether treasury fees in `fee_mint` may be converted
+    ///
+    /// Conversion must be enabled, and the canonical asset itself is never
+    /// converted.
+    pub fn can_convert_treasury_fees(&self, fee_mint: &Pubkey) -> bool {
+        self.treasury_conversion_mint != Pubkey::default()
+            && *fee_mint != self.treasury_conversion_mint
+    }
+
+    /// Resolve the PendingOperation expiry for an operation type
+    ///
+    /// Falls back to the default, then to PENDING_OPERATION_EXPIRY_SECONDS
+    /// (accounts created before expiry was configurable read as zero).
+    pub fn pending_expiry_seconds(&self, operation_type: u8) -> i64 {
+        let seconds = self.pending_expiry_overrides
+            .iter()
+            .find(|o| o.expiry_seconds != 0 && o.operation_type == operation_type)
+            .map(|o| o.expiry_seconds)
+            .unwrap_or(self.pending_expiry_seconds);
+        if seconds == 0 {
+            return PENDING_OPERATION_EXPIRY_SECONDS;
+        }
+        // Bounds are enforced on update; clamp in case they are ever tightened
+        seconds.clamp(MIN_PENDING_EXPIRY_SECONDS, MAX_PENDING_EXPIRY_SECONDS) as i64
+    }
+
+    /// Set or clear (`expiry_seconds == 0`) an operation type's expiry override
+    ///
+    /// Returns false if all override slots are in use.
+    pub fn set_pending_expiry_override(&mut self, operation_type: u8, expiry_seconds: u32) -> bool {
+        let existing = self.pending_expiry_overrides
+            .iter_mut()
+            .find(|o| o.expiry_seconds != 0 && o.operation_type == operation_type);
+        if let Some(slot) = existing {
+            slot.expiry_seconds = expiry_seconds;
+            return true;
+        }
+        if expiry_seconds == 0 {
+            return true;
+        }
+        match self.pending_expiry_overrides.iter_mut().find(|o| o.expiry_seconds == 0) {
+            Some(slot) => {
+                *slot = PendingExpiryOverride { operation_type, expiry_seconds };
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Epoch an operation ID was derived for (first 4 bytes, little-endian)
+    pub fn operation_id_epoch(operation_id: &[u8; 32]) -> u32 {
+        u32::from_le_bytes([operation_id[0], operation_id[1], operation_id[2], operation_id[3]])
+    }
+
+    /// Whether an operation ID was derived for the current operation epoch
+    pub fn is_current_operation(&self, operation_id: &[u8; 32]) -> bool {
+        Self::operation_id_epoch(operation_id) == self.operation_epoch
     }
 
     /// Verify that a fee amount meets minimum requirements
@@ -169,3 +454,153 @@ impl FeeOperation {
         matches!(self, FeeOperation::Free)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_expiry_resolution() {
+        let mut config = ProtocolConfig::default();
+        assert_eq!(config.pending_expiry_seconds(0), PENDING_OPERATION_EXPIRY_SECONDS);
+
+        config.pending_expiry_seconds = 600;
+        assert!(config.set_pending_expiry_override(20, 120));
+        assert_eq!(config.pending_expiry_seconds(0), 600);
+        assert_eq!(config.pending_expiry_seconds(20), 120);
+
+        // Updating reuses the slot, clearing falls back to the default
+        assert!(config.set_pending_expiry_override(20, 90));
+        assert_eq!(config.pending_expiry_seconds(20), 90);
+        assert!(config.set_pending_expiry_override(20, 0));
+        assert_eq!(config.pending_expiry_seconds(20), 600);
+
+        for op in 0..MAX_PENDING_EXPIRY_OVERRIDES as u8 {
+            assert!(config.set_pending_expiry_override(op, 300));
+        }
+        assert!(!config.set_pending_expiry_override(99, 300));
+    }
+
+    #[test]
+    fn test_operation_epoch() {
+        let mut config = ProtocolConfig::default();
+        let mut operation_id = [7u8; 32];
+        operation_id[..4].copy_from_slice(&0u32.to_le_bytes());
+        assert!(config.is_current_operation(&operation_id));
+
+        // Bumping the epoch invalidates IDs derived for the previous one
+        config.operation_epoch = 1;
+        assert!(!config.is_current_operation(&operation_id));
+        operation_id[..4].copy_from_slice(&1u32.to_le_bytes());
+        assert_eq!(ProtocolConfig::operation_id_epoch(&operation_id), 1);
+        assert!(config.is_current_operation(&operation_id));
+    }
+
+    #[test]
+    fn test_expected_transfer_fee() {
+        let mut config = ProtocolConfig {
+            transfer_fee_bps: 10,
+            ..Default::default()
+        };
+        let mint = Pubkey::new_unique();
+        assert_eq!(config.expected_transfer_fee(&mint, 10_000, 5_000).unwrap(), 0);
+
+        // Charged on transfer + unshield
+        config.fees_enabled = true;
+        assert_eq!(config.expected_transfer_fee(&mint, 10_000, 5_000).unwrap(), 15);
+        assert_eq!(
+            config.expected_transfer_fee(&mint, u64::MAX, 1).unwrap_err(),
+            CloakCraftError::AmountOverflow.into()
+        );
+    }
+
+    #[test]
+    fn test_fee_overrides() {
+        let stable = Pubkey::new_unique();
+        let mut config = ProtocolConfig {
+            transfer_fee_bps: 10,
+            unshield_fee_bps: 25,
+            remove_liquidity_fee_bps: 25,
+            fees_enabled: true,
+            ..Default::default()
+        };
+        assert_eq!(config.transfer_fee_bps_for(&stable), 10);
+
+        let rates = FeeOverride {
+            transfer_fee_bps: 2,
+            unshield_fee_bps: 5,
+            remove_liquidity_fee_bps: 5,
+            ..Default::default()
+        };
+        assert!(config.set_fee_override(stable, Some(rates)));
+        assert_eq!(config.fee_override(&stable).unwrap().mint, stable);
+        assert_eq!(config.transfer_fee_bps_for(&stable), 2);
+        assert_eq!(config.unshield_fee_bps_for(&stable), 5);
+        assert_eq!(config.expected_transfer_fee(&stable, 10_000, 0).unwrap(), 2);
+        assert_eq!(config.calculate_remove_liquidity_fee(&stable, 10_000), (5, 9_995));
+        // Other mints keep the global rates
+        let volatile = Pubkey::new_unique();
+        assert_eq!(config.expected_transfer_fee(&volatile, 10_000, 0).unwrap(), 10);
+        // The default key never matches a (free) slot
+        assert!(config.fee_override(&Pubkey::default()).is_none());
+
+        // Updating reuses the slot, clearing falls back to the global rates
+        assert!(config.set_fee_override(stable, Some(FeeOverride { transfer_fee_bps: 3, ..rates })));
+        assert_eq!(config.transfer_fee_bps_for(&stable), 3);
+        assert_eq!(config.fee_overrides.iter().filter(|o| o.mint == stable).count(), 1);
+        assert!(config.set_fee_override(stable, None));
+        assert_eq!(config.transfer_fee_bps_for(&stable), 10);
+
+        for _ in 0..MAX_FEE_OVERRIDES {
+            assert!(config.set_fee_override(Pubkey::new_unique(), Some(rates)));
+        }
+        assert!(!config.set_fee_override(stable, Some(rates)));
+    }
+
+    #[test]
+    fn test_fee_bounds() {
+        let mut config = ProtocolConfig {
+            transfer_fee_bps: 10,
+            unshield_fee_bps: 25,
+            remove_liquidity_fee_bps: 25,
+            ..Default::default()
+        };
+        // No bounds set: anything up to MAX_FEE_BPS
+        assert_eq!(config.fee_ceiling_bps(), ProtocolConfig::MAX_FEE_BPS);
+        assert!(config.fees_within_bounds());
+
+        config.min_fee_bps = 5;
+        config.max_fee_bps = 30;
+        assert!(config.fees_within_bounds());
+        assert!(!config.fee_within_bounds(4));
+        assert!(!config.fee_within_bounds(31));
+
+        // Overrides are bounded too
+        let mint = Pubkey::new_unique();
+        config.set_fee_override(mint, Some(FeeOverride {
+            transfer_fee_bps: 2,
+            unshield_fee_bps: 5,
+            remove_liquidity_fee_bps: 5,
+            ..Default::default()
+        }));
+        assert!(!config.fees_within_bounds());
+        config.min_fee_bps = 2;
+        assert!(config.fees_within_bounds());
+
+        config.max_fee_bps = 20;
+        assert!(!config.fees_within_bounds());
+    }
+
+    #[test]
+    fn test_treasury_conversion() {
+        let mut config = ProtocolConfig::default();
+        let usdc = Pubkey::new_unique();
+        let sol = Pubkey::new_unique();
+        // Disabled until a canonical asset is set
+        assert!(!config.can_convert_treasury_fees(&sol));
+
+        config.treasury_conversion_mint = usdc;
+        assert!(config.can_convert_treasury_fees(&sol));
+        assert!(!config.can_convert_treasury_fees(&usdc));
+    }
+}
@@ -0,0 +1,106 @@
+//! Per-pool relayer allowlist
+//!
+//! PDA listing the relayers allowed to submit Phase 0 of operations that
+//! spend from a pool while Pool.relayer_allowlist_enabled is set. Pools
+//! without the flag stay permissionless.
+
+use anchor_lang::prelude::*;
+
+use crate::errors::CloakCraftError;
+use super::Pool;
+
+/// Maximum number of allowlisted relayers per pool
+pub const MAX_ALLOWED_RELAYERS: usize = 16;
+
+/// Relayers allowed to submit operations for a permissioned pool
+#[account]
+#[derive(Default, InitSpace)]
+pub struct RelayerAllowlist {
+    /// Pool this allowlist belongs to
+    pub pool: Pubkey,
+
+    /// Allowed relayers (`Pubkey::default()` = free slot)
+    pub relayers: [Pubkey; MAX_ALLOWED_RELAYERS],
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl RelayerAllowlist {
+    /// Whether `relayer` may submit operations
+    pub fn contains(&self, relayer: &Pubkey) -> bool {
+        *relayer != Pubkey::default() && self.relayers.contains(relayer)
+    }
+
+    /// Allow or disallow a relayer
+    ///
+    /// Returns false if all slots are in use.
+    pub fn set(&mut self, relayer: Pubkey, allowed: bool) -> bool {
+        if relayer == Pubkey::default() {
+            return !allowed;
+        }
+        if let Some(slot) = self.relayers.iter_mut().find(|r| **r == relayer) {
+            if !allowed {
+                *slot = Pubkey::default();
+            }
+            return true;
+        }
+        if !allowed {
+            return true;
+        }
+        match self.relayers.iter_mut().find(|r| **r == Pubkey::default()) {
+            Some(slot) => {
+                *slot = relayer;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Pool {
+    /// Check that `relayer` may submit an operation spending from this pool
+    ///
+    /// Permissionless pools accept anyone. Permissioned pools require their
+    /// allowlist (the caller constrains it to this pool's PDA).
+    pub fn check_relayer(&self, allowlist: Option<&RelayerAllowlist>, relayer: &Pubkey) -> Result<()> {
+        if !self.relayer_allowlist_enabled {
+            return Ok(());
+        }
+        let allowed = allowlist.is_some_and(|list| list.contains(relayer));
+        require!(allowed, CloakCraftError::RelayerNotAllowed);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relayer_allowlist() {
+        let mut list = RelayerAllowlist::default();
+        let relayer = Pubkey::new_unique();
+        assert!(list.set(relayer, true));
+        assert!(list.contains(&relayer));
+        assert!(!list.contains(&Pubkey::default()));
+
+        let mut pool = Pool::default();
+        let other = Pubkey::new_unique();
+        // Permissionless: no allowlist needed
+        assert!(pool.check_relayer(None, &other).is_ok());
+
+        pool.relayer_allowlist_enabled = true;
+        assert!(pool.check_relayer(Some(&list), &relayer).is_ok());
+        assert!(pool.check_relayer(Some(&list), &other).is_err());
+        assert!(pool.check_relayer(None, &relayer).is_err());
+
+        assert!(list.set(relayer, false));
+        assert!(pool.check_relayer(Some(&list), &relayer).is_err());
+
+        for _ in 0..MAX_ALLOWED_RELAYERS {
+            assert!(list.set(Pubkey::new_unique(), true));
+        }
+        assert!(!list.set(Pubkey::new_unique(), true));
+    }
+}
@@ -0,0 +1,213 @@
+//! Relayer stake
+//!
+//! SOL a relayer bonds as a liveness guarantee. If a transfer or
+//! consolidation it started is stranded (nullifiers created, commitments not,
+//! expiry passed; see `PendingOperation::is_rescuable`), anyone may complete
+//! it, and creating the last commitment slashes `RELAYER_SLASH_LAMPORTS` from
+//! the stake. The rescuer gets `RELAYER_RESCUE_BOUNTY_BPS` of the slash and
+//! the treasury the rest, so a relayer rescuing its own operation from
+//! another key still loses most of the slash.
+//!
+//! Unbonded stake stays slashable until it is withdrawn, and unbonding
+//! outlasts the longest pending expiry, so a relayer can't pull its stake
+//! ahead of an operation it is about to abandon.
+//!
+//! The stake also meters dust sweep bounties: only bonded relayers are paid,
+//! at most `MAX_DUST_SWEEPS_PER_EPOCH` times per epoch (see
+//! `state::dust_sweep_ledger`).
+
+use anchor_lang::prelude::*;
+
+use super::dust_sweep_ledger::{DUST_SWEEP_EPOCH_SECONDS, MAX_DUST_SWEEPS_PER_EPOCH};
+
+/// Lamports slashed when a stranded operation is rescued (0.05 SOL)
+pub const RELAYER_SLASH_LAMPORTS: u64 = 50_000_000;
+
+/// Share of a slash paid to the rescuer (20%); the treasury gets the rest
+pub const RELAYER_RESCUE_BOUNTY_BPS: u64 = 2_000;
+
+/// Delay between unbonding stake and withdrawing it (7 days)
+pub const RELAYER_UNBONDING_SECONDS: i64 = 7 * 86_400;
+
+/// Emitted when a stranded operation's rescue slashes the relayer's stake
+#[event]
+pub struct RelayerSlashed {
+    pub operation_id: [u8; 32],
+    pub relayer: Pubkey,
+    pub rescuer: Pubkey,
+    /// Lamports taken from the stake
+    pub amount: u64,
+    /// Part of `amount` paid to the rescuer (the rest went to the treasury)
+    pub rescuer_bounty: u64,
+}
+
+/// Rescuer's share of `slashed` lamports (rounded down)
+pub fn rescue_bounty(slashed: u64) -> u64 {
+    (slashed as u128 * RELAYER_RESCUE_BOUNTY_BPS as u128 / 10_000) as u64
+}
+
+/// A relayer's bonded SOL (one per relayer)
+#[account]
+#[derive(Default, InitSpace)]
+pub struct RelayerStake {
+    /// Relayer the stake backs
+    pub relayer: Pubkey,
+
+    /// Bonded lamports (held by this account on top of its rent)
+    pub bonded: u64,
+
+    /// Lamports waiting out the unbonding delay (still slashable)
+    pub unbonding: u64,
+
+    /// When `unbonding` becomes withdrawable
+    pub unbond_at: i64,
+
+    /// Lamports slashed
+    pub total_slashed: u64,
+
+    /// Epoch `dust_sweeps` counts in (unix time / DUST_SWEEP_EPOCH_SECONDS)
+    pub dust_sweep_epoch: u64,
+
+    /// Dust sweep bounties paid in `dust_sweep_epoch`
+    pub dust_sweeps: u32,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl RelayerStake {
+    /// Start unbonding `amount` (restarts the delay for everything unbonding)
+    ///
+    /// Returns false if less than `amount` is bonded.
+    pub fn unbond(&mut self, amount: u64, now: i64) -> bool {
+        if amount > self.bonded {
+            return false;
+        }
+        self.bonded -= amount;
+        self.unbonding += amount;
+        self.unbond_at = now.saturating_add(RELAYER_UNBONDING_SECONDS);
+        true
+    }
+
+    /// Lamports that can be withdrawn at `now`
+    pub fn withdrawable(&self, now: i64) -> u64 {
+        if now < self.unbond_at {
+            return 0;
+        }
+        self.unbonding
+    }
+
+    /// Take up to `amount` from the stake (bonded first, then unbonding)
+    ///
+    /// Returns the lamports taken.
+    pub fn slash(&mut self, amount: u64) -> u64 {
+        let from_bonded = amount.min(self.bonded);
+        let from_unbonding = (amount - from_bonded).min(self.unbonding);
+        self.bonded -= from_bonded;
+        self.unbonding -= from_unbonding;
+
+        let taken = from_bonded + from_unbonding;
+        self.total_slashed = self.total_slashed.saturating_add(taken);
+        taken
+    }
+
+    /// Count a dust sweep bounty against the per-epoch cap
+    ///
+    /// Returns false (nothing counted) if less than `RELAYER_SLASH_LAMPORTS`
+    /// is bonded or the relayer was already paid `MAX_DUST_SWEEPS_PER_EPOCH`
+    /// bounties this epoch.
+    pub fn claim_dust_sweep(&mut self, now: i64) -> bool {
+        if self.bonded < RELAYER_SLASH_LAMPORTS {
+            return false;
+        }
+        let epoch = (now.max(0) / DUST_SWEEP_EPOCH_SECONDS) as u64;
+        if epoch != self.dust_sweep_epoch {
+            self.dust_sweep_epoch = epoch;
+            self.dust_sweeps = 0;
+        }
+        if self.dust_sweeps >= MAX_DUST_SWEEPS_PER_EPOCH {
+            return false;
+        }
+        self.dust_sweeps += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::operation_types;
+    use crate::state::{PendingOperation, MAX_PENDING_EXPIRY_SECONDS};
+
+    #[test]
+    fn test_relayer_stake() {
+        assert!(RELAYER_UNBONDING_SECONDS > MAX_PENDING_EXPIRY_SECONDS as i64);
+
+        let mut stake = RelayerStake {
+            bonded: 100,
+            ..Default::default()
+        };
+        assert!(!stake.unbond(101, 0));
+        assert!(stake.unbond(40, 1_000));
+        assert_eq!((stake.bonded, stake.unbonding), (60, 40));
+        assert_eq!(stake.withdrawable(1_000 + RELAYER_UNBONDING_SECONDS - 1), 0);
+        assert_eq!(stake.withdrawable(1_000 + RELAYER_UNBONDING_SECONDS), 40);
+
+        // Bonded first, then unbonding
+        assert_eq!(stake.slash(80), 80);
+        assert_eq!((stake.bonded, stake.unbonding), (0, 20));
+        assert_eq!(stake.slash(80), 20);
+        assert_eq!(stake.slash(80), 0);
+        assert_eq!(stake.total_slashed, 100);
+
+        // The rescuer's cut stays well below the slash
+        assert_eq!(rescue_bounty(RELAYER_SLASH_LAMPORTS), 10_000_000);
+        assert_eq!(rescue_bounty(9), 1);
+        assert_eq!(rescue_bounty(0), 0);
+    }
+
+    #[test]
+    fn test_dust_sweep_cap() {
+        let mut stake = RelayerStake::default();
+        // Unbonded relayers are never paid
+        assert!(!stake.claim_dust_sweep(0));
+
+        stake.bonded = RELAYER_SLASH_LAMPORTS;
+        for _ in 0..MAX_DUST_SWEEPS_PER_EPOCH {
+            assert!(stake.claim_dust_sweep(10));
+        }
+        assert!(!stake.claim_dust_sweep(DUST_SWEEP_EPOCH_SECONDS - 1));
+        assert_eq!(stake.dust_sweeps, MAX_DUST_SWEEPS_PER_EPOCH);
+
+        // The next epoch starts a fresh count
+        assert!(stake.claim_dust_sweep(DUST_SWEEP_EPOCH_SECONDS));
+        assert_eq!((stake.dust_sweep_epoch, stake.dust_sweeps), (1, 1));
+    }
+
+    #[test]
+    fn test_rescuable_operation() {
+        let zeroed = vec![0u8; PendingOperation::SPACE];
+        let mut op = PendingOperation::deserialize(&mut &zeroed[..]).unwrap();
+        op.operation_type = operation_types::TRANSFER;
+        op.proof_verified = true;
+        op.num_inputs = 1;
+        op.num_commitments = 2;
+        op.expires_at = 100;
+        // Nullifier not created yet: nothing spent
+        assert!(!op.is_rescuable(101));
+
+        op.mark_nullifier_created(0);
+        assert!(!op.is_rescuable(100));
+        assert!(op.is_rescuable(101));
+
+        op.mark_completed(0);
+        assert!(op.is_rescuable(101));
+        op.mark_completed(1);
+        assert!(!op.is_rescuable(101));
+
+        // Swaps depend on execute_swap
+        op.operation_type = operation_types::SWAP;
+        op.completed_mask = 0;
+        assert!(!op.is_rescuable(101));
+    }
+}
@@ -0,0 +1,28 @@
+//! LP reward checkpoint
+//!
+//! Records the emissions accumulator an LP note starts earning from, keyed by
+//! the LP commitment. Registered with `register_reward_checkpoint` once the
+//! note exists, and rolled forward to the re-created LP note by every claim,
+//! so the claimed `entry_reward_per_lp` is never chosen by the claimant.
+
+use anchor_lang::prelude::*;
+
+/// Reward checkpoint of one LP commitment
+#[account]
+#[derive(Default, InitSpace)]
+pub struct RewardCheckpoint {
+    /// Emissions schedule the checkpoint belongs to
+    pub emissions_schedule: Pubkey,
+
+    /// LP commitment earning from this checkpoint
+    pub lp_commitment: [u8; 32],
+
+    /// Schedule `reward_per_lp` when the checkpoint was recorded
+    pub reward_per_lp: u128,
+
+    /// Paid the rent; refunded when the claim closes the checkpoint
+    pub payer: Pubkey,
+
+    /// PDA bump
+    pub bump: u8,
+}
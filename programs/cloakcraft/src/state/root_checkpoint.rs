@@ -0,0 +1,209 @@
+//! State tree root checkpoints
+//!
+//! Commitments live in Light Protocol compressed accounts, which grow forever.
+//! A checkpoint authority periodically anchors the state tree root together
+//! with the pool's commitment count. Checkpoints are hash-chained
+//! (`chain_hash = keccak(prev_chain_hash || pool || state_tree || root || count || slot || timestamp)`),
+//! so the history forms a tamper-evident log even after old entries rotate
+//! out of the ring buffer.
+//!
+//! Indexers may move raw encrypted notes below a checkpoint's commitment count
+//! to cold storage: the commitment and leaf index stay indexed (so merkle
+//! proofs still work against the anchored root), and an archived note is
+//! verified by decrypting it and recomputing its commitment.
+
+use anchor_lang::prelude::*;
+
+/// Number of checkpoints kept on-chain (ring buffer)
+pub const MAX_ROOT_CHECKPOINTS: usize = 32;
+
+/// Single anchored root
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct RootCheckpoint {
+    /// Light Protocol state tree the root belongs to
+    pub state_tree: Pubkey,
+
+    /// State tree root at checkpoint time
+    pub root: [u8; 32],
+
+    /// Pool commitment count (next leaf index) at checkpoint time
+    pub commitment_count: u64,
+
+    /// Slot the checkpoint was anchored in
+    pub slot: u64,
+
+    /// Timestamp the checkpoint was anchored at
+    pub timestamp: i64,
+
+    /// Hash chain value including this checkpoint
+    pub chain_hash: [u8; 32],
+}
+
+/// Root checkpoint history for one pool
+#[account]
+#[derive(InitSpace)]
+pub struct RootCheckpointHistory {
+    /// Pool (PDA seed)
+    pub pool: Pubkey,
+
+    /// Key allowed to anchor checkpoints
+    pub checkpoint_authority: Pubkey,
+
+    /// Minimum seconds between checkpoints
+    pub min_interval: i64,
+
+    /// Total checkpoints anchored (next ring buffer slot = total % MAX)
+    pub total_checkpoints: u64,
+
+    /// Chain hash of the most recent checkpoint (zero before the first)
+    pub latest_chain_hash: [u8; 32],
+
+    /// Recent checkpoints (ring buffer)
+    pub checkpoints: [RootCheckpoint; MAX_ROOT_CHECKPOINTS],
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Default for RootCheckpointHistory {
+    fn default() -> Self {
+        Self {
+            pool: Pubkey::default(),
+            checkpoint_authority: Pubkey::default(),
+            min_interval: 0,
+            total_checkpoints: 0,
+            latest_chain_hash: [0u8; 32],
+            checkpoints: [RootCheckpoint::default(); MAX_ROOT_CHECKPOINTS],
+            bump: 0,
+        }
+    }
+}
+
+impl RootCheckpointHistory {
+    /// Most recent checkpoint, if any
+    pub fn latest(&self) -> Option<&RootCheckpoint> {
+        if self.total_checkpoints == 0 {
+            return None;
+        }
+        let index = ((self.total_checkpoints - 1) % MAX_ROOT_CHECKPOINTS as u64) as usize;
+        Some(&self.checkpoints[index])
+    }
+
+    /// Whether a new checkpoint may be anchored at `timestamp`
+    pub fn can_checkpoint(&self, timestamp: i64) -> bool {
+        match self.latest() {
+            Some(latest) => timestamp >= latest.timestamp.saturating_add(self.min_interval),
+            None => true,
+        }
+    }
+
+    /// Find a retained checkpoint by root
+    pub fn find_by_root(&self, root: &[u8; 32]) -> Option<&RootCheckpoint> {
+        let retained = self.total_checkpoints.min(MAX_ROOT_CHECKPOINTS as u64) as usize;
+        self.checkpoints[..retained].iter().find(|c| &c.root == root)
+    }
+
+    /// Compute the chain hash for a new checkpoint
+    pub fn compute_chain_hash(
+        prev_chain_hash: &[u8; 32],
+        pool: &Pubkey,
+        state_tree: &Pubkey,
+        root: &[u8; 32],
+        commitment_count: u64,
+        slot: u64,
+        timestamp: i64,
+    ) -> [u8; 32] {
+        solana_keccak_hasher::hashv(&[
+            prev_chain_hash,
+            pool.as_ref(),
+            state_tree.as_ref(),
+            root,
+            &commitment_count.to_le_bytes(),
+            &slot.to_le_bytes(),
+            &timestamp.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+
+    /// Append a checkpoint, chaining it to the previous one
+    pub fn push(
+        &mut self,
+        state_tree: Pubkey,
+        root: [u8; 32],
+        commitment_count: u64,
+        slot: u64,
+        timestamp: i64,
+    ) -> RootCheckpoint {
+        let chain_hash = Self::compute_chain_hash(
+            &self.latest_chain_hash,
+            &self.pool,
+            &state_tree,
+            &root,
+            commitment_count,
+            slot,
+            timestamp,
+        );
+        let checkpoint = RootCheckpoint {
+            state_tree,
+            root,
+            commitment_count,
+            slot,
+            timestamp,
+            chain_hash,
+        };
+
+        let index = (self.total_checkpoints % MAX_ROOT_CHECKPOINTS as u64) as usize;
+        self.checkpoints[index] = checkpoint;
+        self.total_checkpoints += 1;
+        self.latest_chain_hash = chain_hash;
+
+        checkpoint
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_chain() {
+        let mut history = RootCheckpointHistory {
+            min_interval: 60,
+            ..Default::default()
+        };
+        let tree = Pubkey::new_unique();
+
+        assert!(history.latest().is_none());
+        assert!(history.can_checkpoint(0));
+
+        let first = history.push(tree, [1u8; 32], 10, 100, 1_000);
+        assert_eq!(first.chain_hash, RootCheckpointHistory::compute_chain_hash(
+            &[0u8; 32], &history.pool, &tree, &[1u8; 32], 10, 100, 1_000,
+        ));
+        assert!(!history.can_checkpoint(1_059));
+        assert!(history.can_checkpoint(1_060));
+
+        let second = history.push(tree, [2u8; 32], 20, 200, 1_060);
+        assert_ne!(second.chain_hash, first.chain_hash);
+        assert_eq!(history.latest_chain_hash, second.chain_hash);
+        assert_eq!(history.latest().unwrap().root, [2u8; 32]);
+        assert_eq!(history.find_by_root(&[1u8; 32]).unwrap().commitment_count, 10);
+    }
+
+    #[test]
+    fn test_checkpoint_ring_buffer() {
+        let mut history = RootCheckpointHistory::default();
+        let tree = Pubkey::new_unique();
+
+        for i in 0..(MAX_ROOT_CHECKPOINTS as u64 + 2) {
+            history.push(tree, [i as u8 + 1; 32], i, i, i as i64);
+        }
+
+        assert_eq!(history.total_checkpoints, MAX_ROOT_CHECKPOINTS as u64 + 2);
+        assert_eq!(history.latest().unwrap().commitment_count, MAX_ROOT_CHECKPOINTS as u64 + 1);
+        // Oldest two rotated out
+        assert!(history.find_by_root(&[1u8; 32]).is_none());
+        assert!(history.find_by_root(&[2u8; 32]).is_none());
+        assert!(history.find_by_root(&[3u8; 32]).is_some());
+    }
+}
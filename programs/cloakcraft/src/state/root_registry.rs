@@ -0,0 +1,272 @@
+//! Root registry (on-chain light client of committed roots)
+//!
+//! Commitments live in Light Protocol state trees, whose roots other programs
+//! can only check through a Light CPI with a validity proof. The registry
+//! mirrors a pool's commitments into a Poseidon merkle tree kept on-chain
+//! (frontier insertion, see `crate::merkle`) and retains the latest roots
+//! with the slot they were produced in, so `verify_external_inclusion` can
+//! check a plain merkle path against them.
+//!
+//! The registry has its own leaf numbering: leaves are appended in the order
+//! commitments are created, starting from zero when it is initialized. Every
+//! commitment creator takes the registry PDA, so none can skip it. Each append emits `CommitmentRegistered`, from
+//! which clients rebuild the tree to produce merkle paths.
+//!
+//! The pool authority can bound how old a retained root may be
+//! (`Pool::max_root_age_slots`), so a proof against a root that has since
+//! been superseded for long stops verifying before it rotates out.
+//!
+//! Inclusion says a commitment was created; it says nothing about whether the
+//! note has since been spent (nullifiers are unlinkable by design).
+
+use anchor_lang::prelude::*;
+
+use crate::constants::MERKLE_TREE_DEPTH;
+use crate::errors::CloakCraftError;
+use crate::merkle;
+
+/// Number of roots kept on-chain (ring buffer)
+pub const MAX_REGISTRY_ROOTS: usize = 64;
+
+/// Emitted for every commitment appended to a root registry
+#[event]
+pub struct CommitmentRegistered {
+    pub pool: Pubkey,
+    pub commitment: [u8; 32],
+    /// Leaf index in the registry tree
+    pub leaf_index: u32,
+    /// Registry root after the append
+    pub root: [u8; 32],
+    pub slot: u64,
+}
+
+/// Single retained root
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct RegisteredRoot {
+    /// Registry tree root
+    pub root: [u8; 32],
+
+    /// Leaves in the tree at this root
+    pub leaf_count: u32,
+
+    /// Slot the root was produced in
+    pub slot: u64,
+}
+
+impl RegisteredRoot {
+    /// Whether the root is at most `max_age_slots` old at `current_slot` (0 = no limit)
+    pub fn is_fresh(&self, current_slot: u64, max_age_slots: u64) -> bool {
+        max_age_slots == 0 || current_slot.saturating_sub(self.slot) <= max_age_slots
+    }
+}
+
+/// Root registry for one pool
+#[account]
+#[derive(InitSpace)]
+pub struct RootRegistry {
+    /// Pool (PDA seed)
+    pub pool: Pubkey,
+
+    /// Leaves appended (next leaf index)
+    pub leaf_count: u32,
+
+    /// Merkle frontier (left siblings on the insertion path)
+    pub frontier: [[u8; 32]; MERKLE_TREE_DEPTH],
+
+    /// Total roots produced (next ring buffer slot = total % MAX)
+    pub total_roots: u64,
+
+    /// Recent roots (ring buffer)
+    pub roots: [RegisteredRoot; MAX_REGISTRY_ROOTS],
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Default for RootRegistry {
+    fn default() -> Self {
+        Self {
+            pool: Pubkey::default(),
+            leaf_count: 0,
+            frontier: [[0u8; 32]; MERKLE_TREE_DEPTH],
+            total_roots: 0,
+            roots: [RegisteredRoot::default(); MAX_REGISTRY_ROOTS],
+            bump: 0,
+        }
+    }
+}
+
+impl RootRegistry {
+    /// Maximum leaves the registry tree can hold
+    pub const CAPACITY: u64 = 1u64 << MERKLE_TREE_DEPTH;
+
+    /// Most recent root, if any
+    pub fn latest(&self) -> Option<&RegisteredRoot> {
+        if self.total_roots == 0 {
+            return None;
+        }
+        let index = ((self.total_roots - 1) % MAX_REGISTRY_ROOTS as u64) as usize;
+        Some(&self.roots[index])
+    }
+
+    /// Find a retained root
+    pub fn find_root(&self, root: &[u8; 32]) -> Option<&RegisteredRoot> {
+        let retained = self.total_roots.min(MAX_REGISTRY_ROOTS as u64) as usize;
+        self.roots[..retained].iter().find(|r| &r.root == root)
+    }
+
+    /// Append a commitment to a pool's registry account, if initialized
+    ///
+    /// Every commitment creator takes the pool's registry PDA (address checked
+    /// by its seeds), so once the registry is initialized no commitment can
+    /// skip it. Before that the account is empty and nothing is recorded.
+    pub fn append_to(registry_info: &AccountInfo, commitment: [u8; 32], slot: u64) -> Result<()> {
+        if registry_info.data_is_empty() {
+            return Ok(());
+        }
+        require!(registry_info.owner == &crate::ID, CloakCraftError::InvalidRootRegistry);
+
+        let mut registry = {
+            let data = registry_info.try_borrow_data()?;
+            Box::new(RootRegistry::try_deserialize(&mut &data[..])?)
+        };
+        emit!(registry.append(commitment, slot)?);
+        registry.try_serialize(&mut &mut registry_info.try_borrow_mut_data()?[..])?;
+        Ok(())
+    }
+
+    /// Append a commitment, returning the event to emit
+    pub fn append(&mut self, commitment: [u8; 32], slot: u64) -> Result<CommitmentRegistered> {
+        require!(
+            (self.leaf_count as u64) < Self::CAPACITY,
+            CloakCraftError::RootRegistryFull
+        );
+
+        let leaf_index = self.leaf_count;
+        let root = merkle::insert_leaf(&mut self.frontier, leaf_index, commitment)?;
+        self.leaf_count += 1;
+
+        let index = (self.total_roots % MAX_REGISTRY_ROOTS as u64) as usize;
+        self.roots[index] = RegisteredRoot {
+            root,
+            leaf_count: self.leaf_count,
+            slot,
+        };
+        self.total_roots += 1;
+
+        Ok(CommitmentRegistered {
+            pool: self.pool,
+            commitment,
+            leaf_index,
+            root,
+            slot,
+        })
+    }
+
+    /// Check a merkle path from `commitment` to a retained root no older
+    /// than `max_root_age_slots` (0 = no limit)
+    ///
+    /// Returns the matched root entry.
+    pub fn verify_inclusion(
+        &self,
+        commitment: &[u8; 32],
+        leaf_index: u32,
+        root: &[u8; 32],
+        path: &[[u8; 32]; MERKLE_TREE_DEPTH],
+        current_slot: u64,
+        max_root_age_slots: u64,
+    ) -> Result<RegisteredRoot> {
+        let entry = *self
+            .find_root(root)
+            .ok_or(CloakCraftError::UnknownRegistryRoot)?;
+        require!(
+            entry.is_fresh(current_slot, max_root_age_slots),
+            CloakCraftError::StaleRegistryRoot
+        );
+        require!(
+            leaf_index < entry.leaf_count,
+            CloakCraftError::InvalidInclusionPath
+        );
+        require!(
+            merkle::verify_merkle_proof(root, commitment, leaf_index, path)?,
+            CloakCraftError::InvalidInclusionPath
+        );
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a full tree level by level and return the path for `index`
+    fn merkle_path(leaves: &[[u8; 32]], index: usize) -> [[u8; 32]; MERKLE_TREE_DEPTH] {
+        let mut path = [[0u8; 32]; MERKLE_TREE_DEPTH];
+        let mut level: Vec<[u8; 32]> = leaves.to_vec();
+        let mut index = index;
+        for (depth, sibling) in path.iter_mut().enumerate() {
+            let empty = merkle::compute_empty_hash_at_level(depth);
+            if level.len() % 2 == 1 {
+                level.push(empty);
+            }
+            *sibling = level[index ^ 1];
+            level = level
+                .chunks(2)
+                .map(|pair| merkle::hash_pair(&pair[0], &pair[1]))
+                .collect();
+            index /= 2;
+        }
+        path
+    }
+
+    #[test]
+    fn test_append_and_verify() {
+        let mut registry = RootRegistry::default();
+        let leaves: Vec<[u8; 32]> = (1..=3u8).map(|i| [i; 32]).collect();
+
+        for (i, leaf) in leaves.iter().enumerate() {
+            let event = registry.append(*leaf, 100 + i as u64).unwrap();
+            assert_eq!(event.leaf_index, i as u32);
+        }
+        let latest = *registry.latest().unwrap();
+        assert_eq!(latest.leaf_count, 3);
+        assert_eq!(latest.slot, 102);
+
+        let path = merkle_path(&leaves, 1);
+        let entry = registry.verify_inclusion(&leaves[1], 1, &latest.root, &path, 102, 0).unwrap();
+        assert_eq!(entry, latest);
+
+        // Wrong leaf, wrong index
+        assert!(registry.verify_inclusion(&leaves[0], 1, &latest.root, &path, 102, 0).is_err());
+        assert!(registry.verify_inclusion(&leaves[1], 0, &latest.root, &path, 102, 0).is_err());
+        // Leaf not yet appended at an older root
+        let first_root = registry.roots[0].root;
+        assert!(registry.verify_inclusion(&leaves[1], 1, &first_root, &path, 102, 0).is_err());
+        // Unknown root
+        assert!(registry.verify_inclusion(&leaves[1], 1, &[9u8; 32], &path, 102, 0).is_err());
+
+        // Age window: the latest root (slot 102) is fresh for 10 slots
+        assert!(registry.verify_inclusion(&leaves[1], 1, &latest.root, &path, 112, 10).is_ok());
+        assert_eq!(
+            registry.verify_inclusion(&leaves[1], 1, &latest.root, &path, 113, 10).unwrap_err(),
+            CloakCraftError::StaleRegistryRoot.into()
+        );
+        // No window: any retained root is accepted
+        assert!(registry.verify_inclusion(&leaves[1], 1, &latest.root, &path, u64::MAX, 0).is_ok());
+    }
+
+    #[test]
+    fn test_root_ring_buffer() {
+        let mut registry = RootRegistry::default();
+        for i in 0..(MAX_REGISTRY_ROOTS as u64 + 2) {
+            registry.append([i as u8 + 1; 32], i).unwrap();
+        }
+
+        assert_eq!(registry.total_roots, MAX_REGISTRY_ROOTS as u64 + 2);
+        assert_eq!(registry.latest().unwrap().leaf_count, MAX_REGISTRY_ROOTS as u32 + 2);
+        // Oldest two rotated out
+        assert!(registry.roots.iter().all(|r| r.leaf_count > 2));
+        let latest_root = registry.latest().unwrap().root;
+        assert!(registry.find_root(&latest_root).is_some());
+    }
+}
@@ -0,0 +1,209 @@
+//! Savings vault (auto-compounding LP shares)
+//!
+//! A vault holds the LP token of one AMM or perps pool (its strategy) on
+//! behalf of vault-share note holders. Users convert LP notes into share
+//! notes and back at the current share price (`total_lp / total_shares`);
+//! the LP itself stays accounted in the source pool, so no tokens move.
+//!
+//! Yield compounds through the source pool's emissions schedule: the vault
+//! authority harvests the rewards owed to the vault's LP, swaps them for more LP
+//! through an AMM pool pairing the reward token with the LP token, and adds
+//! the LP to the vault. Each harvest raises the share price, which is
+//! recorded on the vault.
+
+use anchor_lang::prelude::*;
+
+use crate::helpers::fixed::{mul_div, to_u64, RATE_SCALE};
+use super::emissions_schedule::{EmissionsPoolKind, EmissionsSchedule};
+use super::protocol_config::MAX_CONVERSION_PRICE_IMPACT_BPS;
+
+/// Savings vault over one LP pool
+#[account]
+#[derive(Default, InitSpace)]
+pub struct SavingsVault {
+    /// AMM or perps pool whose LP the vault holds (PDA seed)
+    pub source_pool: Pubkey,
+
+    /// Kind of `source_pool`
+    pub pool_kind: EmissionsPoolKind,
+
+    /// LP token mint of the source pool
+    pub lp_mint: Pubkey,
+
+    /// Vault share mint (PDA, accounting only)
+    pub share_mint: Pubkey,
+
+    /// Emissions schedule harvested by the vault (`Pubkey::default()` = none)
+    pub emissions_schedule: Pubkey,
+
+    /// Vault authority (the source pool authority)
+    pub authority: Pubkey,
+
+    /// LP held for share holders
+    pub total_lp: u64,
+
+    /// Outstanding vault shares
+    pub total_shares: u64,
+
+    /// LP per share at the last update (RATE_SCALE)
+    pub share_price: u128,
+
+    /// When `share_price` was last recorded
+    pub share_price_updated_at: i64,
+
+    /// Schedule `reward_per_lp` at the last settlement
+    pub reward_checkpoint: u128,
+
+    /// Settled rewards not yet compounded
+    pub pending_rewards: u64,
+
+    /// Lifetime LP added by harvests
+    pub total_compounded: u64,
+
+    /// Highest price impact a harvest swap may have, in basis points
+    pub max_price_impact_bps: u16,
+
+    /// Whether deposits are accepted (withdrawals always are)
+    pub is_active: bool,
+
+    /// PDA bump
+    pub bump: u8,
+
+    /// Share mint bump
+    pub share_mint_bump: u8,
+}
+
+impl SavingsVault {
+    /// Whether a harvest price impact bound is in range
+    pub fn validate_policy(max_price_impact_bps: u16) -> bool {
+        max_price_impact_bps <= MAX_CONVERSION_PRICE_IMPACT_BPS
+    }
+
+    /// Whether the vault harvests an emissions schedule
+    pub fn has_emissions(&self) -> bool {
+        self.emissions_schedule != Pubkey::default()
+    }
+
+    /// LP per share (RATE_SCALE); 1:1 while the vault is empty
+    pub fn current_share_price(&self) -> Option<u128> {
+        if self.total_shares == 0 {
+            return Some(RATE_SCALE);
+        }
+        mul_div(self.total_lp as u128, RATE_SCALE, self.total_shares as u128)
+    }
+
+    /// Shares minted for depositing `lp_amount` (rounded down)
+    pub fn shares_for_deposit(&self, lp_amount: u64) -> Option<u64> {
+        if self.total_shares == 0 || self.total_lp == 0 {
+            return Some(lp_amount);
+        }
+        to_u64(mul_div(lp_amount as u128, self.total_shares as u128, self.total_lp as u128)?)
+    }
+
+    /// LP paid for redeeming `shares` (rounded down)
+    pub fn lp_for_withdraw(&self, shares: u64) -> Option<u64> {
+        if self.total_shares == 0 {
+            return None;
+        }
+        to_u64(mul_div(shares as u128, self.total_lp as u128, self.total_shares as u128)?)
+    }
+
+    /// Settle the rewards owed to the vault's LP up to the schedule's accumulator
+    ///
+    /// Must run before `total_lp` changes.
+    pub fn settle_rewards(&mut self, schedule: &EmissionsSchedule) -> Option<()> {
+        let owed = schedule.pending_reward(self.total_lp, self.reward_checkpoint)?;
+        self.pending_rewards = self.pending_rewards.checked_add(owed)?;
+        self.reward_checkpoint = schedule.reward_per_lp;
+        Some(())
+    }
+
+    /// Record a deposit of `lp_amount` for `shares`
+    pub fn deposit(&mut self, lp_amount: u64, shares: u64) -> Option<()> {
+        self.total_lp = self.total_lp.checked_add(lp_amount)?;
+        self.total_shares = self.total_shares.checked_add(shares)?;
+        Some(())
+    }
+
+    /// Record a redemption of `shares` for `lp_amount`
+    pub fn withdraw(&mut self, shares: u64, lp_amount: u64) -> Option<()> {
+        self.total_shares = self.total_shares.checked_sub(shares)?;
+        self.total_lp = self.total_lp.checked_sub(lp_amount)?;
+        Some(())
+    }
+
+    /// Add harvested LP to the vault (settled rewards are spent)
+    pub fn compound(&mut self, lp_amount: u64) -> Option<()> {
+        self.total_lp = self.total_lp.checked_add(lp_amount)?;
+        self.total_compounded = self.total_compounded.checked_add(lp_amount)?;
+        self.pending_rewards = 0;
+        Some(())
+    }
+
+    /// Record the current share price at `now`
+    pub fn record_share_price(&mut self, now: i64) -> Option<u128> {
+        self.share_price = self.current_share_price()?;
+        self.share_price_updated_at = now;
+        Some(self.share_price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_conversion() {
+        let mut vault = SavingsVault::default();
+        // The first deposit sets shares 1:1
+        assert_eq!(vault.shares_for_deposit(1_000), Some(1_000));
+        assert_eq!(vault.lp_for_withdraw(1), None);
+        vault.deposit(1_000, 1_000).unwrap();
+        assert_eq!(vault.record_share_price(10), Some(RATE_SCALE));
+
+        // Compounding raises the price for existing shares only
+        vault.compound(500).unwrap();
+        assert_eq!(vault.record_share_price(20), Some(RATE_SCALE * 3 / 2));
+        assert_eq!(vault.shares_for_deposit(300), Some(200));
+        vault.deposit(300, 200).unwrap();
+        assert_eq!(vault.lp_for_withdraw(200), Some(300));
+
+        // Rounding favors the vault
+        assert_eq!(vault.shares_for_deposit(2), Some(1));
+        assert_eq!(vault.lp_for_withdraw(1), Some(1));
+
+        vault.withdraw(1_200, 1_800).unwrap();
+        assert_eq!((vault.total_lp, vault.total_shares), (0, 0));
+        assert!(vault.withdraw(1, 0).is_none());
+    }
+
+    #[test]
+    fn test_reward_settlement() {
+        let mut vault = SavingsVault { total_lp: 400, ..Default::default() };
+        let mut schedule = EmissionsSchedule {
+            reward_rate: 10,
+            start_time: 0,
+            end_time: 1_000,
+            ..Default::default()
+        };
+
+        // 100 seconds * 10/s over 1000 LP supply: the vault's 400 LP earn 400
+        schedule.accrue(100, 1_000).unwrap();
+        vault.settle_rewards(&schedule).unwrap();
+        assert_eq!(vault.pending_rewards, 400);
+
+        // Settling again at the same accumulator adds nothing
+        vault.settle_rewards(&schedule).unwrap();
+        assert_eq!(vault.pending_rewards, 400);
+
+        vault.compound(50).unwrap();
+        assert_eq!((vault.pending_rewards, vault.total_lp, vault.total_compounded), (0, 450, 50));
+    }
+
+    #[test]
+    fn test_vault_policy_bounds() {
+        assert!(SavingsVault::validate_policy(0));
+        assert!(SavingsVault::validate_policy(MAX_CONVERSION_PRICE_IMPACT_BPS));
+        assert!(!SavingsVault::validate_policy(MAX_CONVERSION_PRICE_IMPACT_BPS + 1));
+    }
+}
@@ -0,0 +1,149 @@
+//! Snapshot tree (per-ballot commitment tree for snapshot voting)
+//!
+//! A Snapshot-mode ballot normally pins a single root with
+//! `register_snapshot_root`. A snapshot tree instead lets the ballot's
+//! indexer or authority rebuild the snapshot commitment set on-chain
+//! (frontier insertion, see `crate::merkle`) in as many appends as it takes,
+//! retaining the latest roots. Votes that pass the tree are checked against
+//! any retained root, so a vote proven against a root from a few appends ago
+//! still lands.
+//!
+//! Each append emits `SnapshotLeavesAppended`; clients rebuild the tree from
+//! those events, in order, to compute paths.
+
+use anchor_lang::prelude::*;
+
+use crate::constants::MERKLE_TREE_DEPTH;
+use crate::errors::CloakCraftError;
+use crate::merkle;
+
+/// Number of roots kept on-chain (ring buffer)
+pub const MAX_SNAPSHOT_ROOTS: usize = 32;
+
+/// Emitted for every batch of leaves appended to a snapshot tree
+#[event]
+pub struct SnapshotLeavesAppended {
+    pub ballot_id: [u8; 32],
+    /// Leaf index of the first appended leaf
+    pub first_leaf_index: u32,
+    pub leaves: Vec<[u8; 32]>,
+    /// Tree root after the append
+    pub root: [u8; 32],
+}
+
+/// Snapshot tree for one ballot
+#[account]
+#[derive(InitSpace)]
+pub struct SnapshotTree {
+    /// Ballot (PDA seed)
+    pub ballot_id: [u8; 32],
+
+    /// Leaves appended (next leaf index)
+    pub leaf_count: u32,
+
+    /// Merkle frontier (left siblings on the insertion path)
+    pub frontier: [[u8; 32]; MERKLE_TREE_DEPTH],
+
+    /// Total roots produced (next ring buffer slot = total % MAX)
+    pub total_roots: u64,
+
+    /// Recent roots (ring buffer)
+    pub roots: [[u8; 32]; MAX_SNAPSHOT_ROOTS],
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl Default for SnapshotTree {
+    fn default() -> Self {
+        Self {
+            ballot_id: [0u8; 32],
+            leaf_count: 0,
+            frontier: [[0u8; 32]; MERKLE_TREE_DEPTH],
+            total_roots: 0,
+            roots: [[0u8; 32]; MAX_SNAPSHOT_ROOTS],
+            bump: 0,
+        }
+    }
+}
+
+impl SnapshotTree {
+    /// Maximum leaves the snapshot tree can hold
+    pub const CAPACITY: u64 = 1u64 << MERKLE_TREE_DEPTH;
+
+    /// Most recent root, if any
+    pub fn latest_root(&self) -> Option<[u8; 32]> {
+        if self.total_roots == 0 {
+            return None;
+        }
+        let index = ((self.total_roots - 1) % MAX_SNAPSHOT_ROOTS as u64) as usize;
+        Some(self.roots[index])
+    }
+
+    /// Append leaves, recording one root for the batch
+    pub fn append(&mut self, leaves: &[[u8; 32]]) -> Result<[u8; 32]> {
+        require!(!leaves.is_empty(), CloakCraftError::InvalidAmount);
+        require!(
+            self.leaf_count as u64 + leaves.len() as u64 <= Self::CAPACITY,
+            CloakCraftError::SnapshotTreeFull
+        );
+
+        let mut root = [0u8; 32];
+        for leaf in leaves {
+            root = merkle::insert_leaf(&mut self.frontier, self.leaf_count, *leaf)?;
+            self.leaf_count += 1;
+        }
+
+        let index = (self.total_roots % MAX_SNAPSHOT_ROOTS as u64) as usize;
+        self.roots[index] = root;
+        self.total_roots += 1;
+
+        Ok(root)
+    }
+
+    /// Check a vote's merkle root is a retained snapshot tree root
+    pub fn check_root(&self, root: &[u8; 32]) -> Result<()> {
+        let retained = self.total_roots.min(MAX_SNAPSHOT_ROOTS as u64) as usize;
+        require!(
+            merkle::is_known_root(&self.roots[..retained], root),
+            CloakCraftError::UnknownSnapshotTreeRoot
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_tree_roots() {
+        let mut tree = SnapshotTree::default();
+        assert!(tree.latest_root().is_none());
+        // Nothing appended: even the zero root is unknown
+        assert_eq!(
+            tree.check_root(&[0u8; 32]).unwrap_err(),
+            CloakCraftError::UnknownSnapshotTreeRoot.into()
+        );
+
+        let first = tree.append(&[[1u8; 32], [2u8; 32]]).unwrap();
+        assert_eq!(tree.leaf_count, 2);
+        assert_eq!(tree.latest_root(), Some(first));
+
+        // Same leaves one at a time give the same root
+        let mut single = SnapshotTree::default();
+        single.append(&[[1u8; 32]]).unwrap();
+        assert_eq!(single.append(&[[2u8; 32]]).unwrap(), first);
+
+        // Older roots stay valid until rotated out
+        for i in 0..MAX_SNAPSHOT_ROOTS as u8 - 1 {
+            tree.append(&[[i + 3; 32]]).unwrap();
+        }
+        assert!(tree.check_root(&first).is_ok());
+        tree.append(&[[0xAA; 32]]).unwrap();
+        assert!(tree.check_root(&first).is_err());
+        assert!(tree.check_root(&tree.latest_root().unwrap()).is_ok());
+
+        assert!(tree.append(&[]).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+//! Approved Light output state trees
+//!
+//! Pools write their compressed accounts to one state tree at a time (see
+//! `Pool::active_trees`), chosen at pool creation and rolled over with
+//! migrate_pool_trees. The tree registry lists the state trees the protocol
+//! authority approved, with a capacity hint for each: rollovers may only
+//! target a registered, unretired tree with headroom. Every instruction that
+//! creates commitments takes the registry PDA; once it is initialized they
+//! reject unregistered output trees and count their writes
+//! (`TreeRegistry::record_writes_to`). Once a tree's writes cross `near_capacity_bps` of its capacity,
+//! `TreeNearCapacity` is emitted (once) so operators can register a fresh
+//! tree and migrate pools before writes start failing.
+
+use anchor_lang::prelude::*;
+
+use crate::errors::CloakCraftError;
+
+/// Maximum state trees in the registry
+pub const MAX_REGISTERED_TREES: usize = 16;
+
+/// Default near-capacity threshold (90%)
+pub const DEFAULT_NEAR_CAPACITY_BPS: u16 = 9_000;
+
+/// Emitted when a state tree is registered or its capacity hint updated
+#[event]
+pub struct StateTreeRegistered {
+    pub state_tree: Pubkey,
+    pub capacity: u64,
+}
+
+/// Emitted once when a tree's counted writes cross the near-capacity threshold
+#[event]
+pub struct TreeNearCapacity {
+    pub state_tree: Pubkey,
+    pub written: u64,
+    pub capacity: u64,
+}
+
+/// One approved output state tree
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct RegisteredTree {
+    /// Output state tree (the V2 output queue)
+    pub state_tree: Pubkey,
+
+    /// Operator's capacity hint (compressed accounts)
+    pub capacity: u64,
+
+    /// Compressed accounts counted by phases that passed the registry
+    pub written: u64,
+
+    /// No longer accepted for new writes or rollovers
+    pub retired: bool,
+
+    /// TreeNearCapacity already emitted for the current capacity
+    pub near_capacity_emitted: bool,
+}
+
+impl RegisteredTree {
+    /// Whether `written` has reached `bps` of the capacity hint
+    fn is_near_capacity(&self, bps: u16) -> bool {
+        self.written as u128 * 10_000 >= self.capacity as u128 * bps as u128
+    }
+}
+
+/// Approved output state trees (singleton)
+#[account]
+#[derive(Default, InitSpace)]
+pub struct TreeRegistry {
+    /// Registered trees (first `tree_count` used)
+    pub trees: [RegisteredTree; MAX_REGISTERED_TREES],
+
+    /// Number of registered trees
+    pub tree_count: u8,
+
+    /// Share of a tree's capacity at which TreeNearCapacity is emitted
+    pub near_capacity_bps: u16,
+
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl TreeRegistry {
+    /// Threshold must leave room to roll over (1% - 100%)
+    pub fn validate_threshold(bps: u16) -> bool {
+        (100..=10_000).contains(&bps)
+    }
+
+    fn registered(&self) -> &[RegisteredTree] {
+        &self.trees[..self.tree_count as usize]
+    }
+
+    fn find_mut(&mut self, state_tree: &Pubkey) -> Option<&mut RegisteredTree> {
+        self.trees[..self.tree_count as usize]
+            .iter_mut()
+            .find(|tree| tree.state_tree == *state_tree)
+    }
+
+    /// Registered tree entry, if any
+    pub fn get(&self, state_tree: &Pubkey) -> Option<&RegisteredTree> {
+        self.registered().iter().find(|tree| tree.state_tree == *state_tree)
+    }
+
+    /// Register a tree, or update a registered tree's capacity hint
+    ///
+    /// Re-registering a retired tree makes it active again.
+    pub fn register(&mut self, state_tree: Pubkey, capacity: u64) -> Result<()> {
+        require!(capacity > 0, CloakCraftError::InvalidTreeCapacity);
+        let bps = self.near_capacity_bps;
+        if let Some(tree) = self.find_mut(&state_tree) {
+            tree.capacity = capacity;
+            tree.retired = false;
+            tree.near_capacity_emitted = tree.is_near_capacity(bps);
+            return Ok(());
+        }
+
+        let index = self.tree_count as usize;
+        require!(index < MAX_REGISTERED_TREES, CloakCraftError::TreeRegistryFull);
+        self.trees[index] = RegisteredTree {
+            state_tree,
+            capacity,
+            ..Default::default()
+        };
+        self.tree_count += 1;
+        Ok(())
+    }
+
+    /// Stop accepting a tree for new writes and rollovers
+    pub fn retire(&mut self, state_tree: &Pubkey) -> Result<()> {
+        let tree = self.find_mut(state_tree).ok_or(CloakCraftError::UnregisteredStateTree)?;
+        tree.retired = true;
+        Ok(())
+    }
+
+    /// Check `state_tree` may receive new compressed accounts
+    pub fn check_output_tree(&self, state_tree: &Pubkey) -> Result<()> {
+        let tree = self.get(state_tree).ok_or(CloakCraftError::UnregisteredStateTree)?;
+        require!(!tree.retired, CloakCraftError::StateTreeRetired);
+        Ok(())
+    }
+
+    /// Check `state_tree` may become a pool's state tree (registered, active, with headroom)
+    pub fn check_rollover_target(&self, state_tree: &Pubkey) -> Result<()> {
+        self.check_output_tree(state_tree)?;
+        let tree = self.get(state_tree).ok_or(CloakCraftError::UnregisteredStateTree)?;
+        require!(
+            !tree.is_near_capacity(self.near_capacity_bps),
+            CloakCraftError::StateTreeNearCapacity
+        );
+        Ok(())
+    }
+
+    /// Count `count` writes to `state_tree`
+    ///
+    /// Returns the event to emit when the tree first crosses the threshold.
+    pub fn record_writes(&mut self, state_tree: &Pubkey, count: u64) -> Result<Option<TreeNearCapacity>> {
+        self.check_output_tree(state_tree)?;
+        let bps = self.near_capacity_bps;
+        let tree = self.find_mut(state_tree).ok_or(CloakCraftError::UnregisteredStateTree)?;
+        tree.written = tree.written.saturating_add(count);
+        if tree.near_capacity_emitted || !tree.is_near_capacity(bps) {
+            return Ok(None);
+        }
+        tree.near_capacity_emitted = true;
+        Ok(Some(TreeNearCapacity {
+            state_tree: tree.state_tree,
+            written: tree.written,
+            capacity: tree.capacity,
+        }))
+    }
+
+    /// Count writes in the registry account, if initialized
+    ///
+    /// Commitment creators take the registry PDA (address checked by its
+    /// seeds), so once the registry is initialized no write can skip it.
+    /// Before that the account is empty and nothing is checked or counted.
+    pub fn record_writes_to(registry_info: &AccountInfo, state_tree: &Pubkey, count: u64) -> Result<()> {
+        if registry_info.data_is_empty() {
+            return Ok(());
+        }
+        require!(registry_info.owner == &crate::ID, CloakCraftError::InvalidTreeRegistry);
+
+        let mut registry = {
+            let data = registry_info.try_borrow_data()?;
+            Box::new(TreeRegistry::try_deserialize(&mut &data[..])?)
+        };
+        if let Some(event) = registry.record_writes(state_tree, count)? {
+            emit!(event);
+        }
+        registry.try_serialize(&mut &mut registry_info.try_borrow_mut_data()?[..])?;
+        Ok(())
+    }
+
+    /// Active tree with the most remaining capacity (the suggested rollover target)
+    pub fn roomiest_tree(&self) -> Option<&RegisteredTree> {
+        self.registered()
+            .iter()
+            .filter(|tree| !tree.retired)
+            .max_by_key(|tree| tree.capacity.saturating_sub(tree.written))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> TreeRegistry {
+        TreeRegistry {
+            near_capacity_bps: DEFAULT_NEAR_CAPACITY_BPS,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_near_capacity_emitted_once() {
+        let mut registry = registry();
+        let tree = Pubkey::new_unique();
+        registry.register(tree, 100).unwrap();
+
+        assert!(registry.record_writes(&tree, 89).unwrap().is_none());
+        let event = registry.record_writes(&tree, 1).unwrap().unwrap();
+        assert_eq!((event.written, event.capacity), (90, 100));
+        assert!(registry.record_writes(&tree, 5).unwrap().is_none());
+        assert!(registry.check_rollover_target(&tree).is_err());
+
+        // Raising the capacity hint re-arms the event
+        registry.register(tree, 1_000).unwrap();
+        assert!(registry.check_rollover_target(&tree).is_ok());
+        assert!(registry.record_writes(&tree, 805).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_unregistered_and_retired_trees_rejected() {
+        let mut registry = registry();
+        let tree = Pubkey::new_unique();
+        assert!(registry.check_output_tree(&tree).is_err());
+        assert!(registry.record_writes(&tree, 1).is_err());
+
+        registry.register(tree, 100).unwrap();
+        registry.retire(&tree).unwrap();
+        assert!(registry.check_output_tree(&tree).is_err());
+        assert!(registry.roomiest_tree().is_none());
+
+        registry.register(tree, 100).unwrap();
+        assert!(registry.check_output_tree(&tree).is_ok());
+    }
+
+    #[test]
+    fn test_roomiest_tree() {
+        let mut registry = registry();
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        registry.register(a, 100).unwrap();
+        registry.register(b, 50).unwrap();
+        registry.record_writes(&a, 80).unwrap();
+        assert_eq!(registry.roomiest_tree().unwrap().state_tree, b);
+    }
+}
@@ -1,6 +1,12 @@
 //! Verification key storage
 //!
 //! Stores Groth16 verification keys for each circuit type.
+//!
+//! Keys larger than a transaction are uploaded in chunks
+//! (`set_verification_key_data` / `append_verification_key_data`), so a key
+//! is only usable once `finalize_verification_key` has checked the uploaded
+//! bytes against the hash the authority expects and frozen the account.
+//! Proof-verifying instructions reject non-finalized keys.
 
 use anchor_lang::prelude::*;
 
@@ -28,6 +34,12 @@ pub struct VerificationKey {
 
     /// PDA bump
     pub bump: u8,
+
+    /// Set by `finalize_verification_key`; the key can no longer change
+    pub is_finalized: bool,
+
+    /// keccak256(vk_data), recorded at finalization
+    pub vk_hash: [u8; 32],
 }
 
 impl VerificationKey {
@@ -37,7 +49,9 @@ impl VerificationKey {
         + 4   // vk_data vec len
         + 32  // authority
         + 1   // is_active
-        + 1;  // bump
+        + 1   // bump
+        + 1   // is_finalized
+        + 32; // vk_hash
 
     /// Calculate space for given VK size
     pub fn space(vk_data_len: usize) -> usize {
@@ -47,4 +61,9 @@ impl VerificationKey {
     /// Typical VK size for circuits with ~10 public inputs
     /// alpha_g1 (64) + beta_g2 (128) + gamma_g2 (128) + delta_g2 (128) + ic (64 * 12)
     pub const TYPICAL_VK_SIZE: usize = 64 + 128 + 128 + 128 + (64 * 12);
+
+    /// Hash pinned at finalization
+    pub fn compute_hash(vk_data: &[u8]) -> [u8; 32] {
+        solana_keccak_hasher::hash(vk_data).to_bytes()
+    }
 }
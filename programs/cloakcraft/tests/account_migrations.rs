@@ -0,0 +1,294 @@
+//! Layout migration tests (solana-program-test)
+//!
+//! Accounts created before a struct grew are injected at their old size and
+//! reallocated by the program's migrate instructions. The old accounts are
+//! pre-funded for the new size, since the rent top-up is a system program CPI
+//! that only runs on-chain.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{entrypoint::ProgramResult, instruction::Instruction};
+use anchor_lang::{AccountDeserialize, Discriminator, InstructionData, ToAccountMetas};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account as SolanaAccount,
+    instruction::InstructionError,
+    signature::{Keypair, Signer as _},
+    transaction::{Transaction, TransactionError},
+};
+
+use cloakcraft::constants::seeds;
+use cloakcraft::errors::CloakCraftError;
+use cloakcraft::state::{
+    AmmPool, Ballot, BallotStatus, LpVoteSource, Pool, PoolType, TwabRoot, VoteBindingMode,
+    ELGAMAL_CIPHERTEXT_SIZE, MAX_LP_VOTE_SOURCES, MAX_TWAB_ROOTS,
+};
+
+/// AmmPool size before the LP lock, JIT penalty and version fields
+const AMM_POOL_ORIGINAL_LEN: usize = AmmPool::LEN - 8 - 2 - 1;
+
+/// Ballot size before the fields that follow `bump`
+const BALLOT_ORIGINAL_SPACE: usize = Ballot::SPACE
+    - (32 + 1 + LpVoteSource::INIT_SPACE * MAX_LP_VOTE_SOURCES + 1) // snapshot root, LP sources
+    - (1 + 1 + 8 + TwabRoot::INIT_SPACE * MAX_TWAB_ROOTS + 1) // TWAB roots
+    - (8 + 1 + 1) // proposal bond, option pages
+    - (1 + 32 + 1) // option registration
+    - (1 + ELGAMAL_CIPHERTEXT_SIZE) // turnout privacy
+    - 2 // loser refunds
+    - (8 + 8 + 8 + 32) // oracle disputes
+    - 128; // _reserved
+
+/// Native entry: Anchor's entry ties the account slice to its lifetime
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts: &[AccountInfo] = unsafe { std::mem::transmute(accounts) };
+    cloakcraft::entry(program_id, accounts, data)
+}
+
+/// Account holding the first `len` bytes of `value`, funded for `funded_len`
+fn legacy_account<T: AccountSerialize>(value: &T, len: usize, funded_len: usize) -> SolanaAccount {
+    let mut data = Vec::new();
+    value.try_serialize(&mut data).unwrap();
+    data.resize(len, 0);
+    SolanaAccount {
+        lamports: Rent::default().minimum_balance(funded_len),
+        data,
+        owner: cloakcraft::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Zero-initialized account of type `T`, as `init` would leave it
+fn zeroed<T: AccountDeserialize + Discriminator>(space: usize) -> T {
+    let mut data = vec![0u8; space];
+    data[..8].copy_from_slice(T::DISCRIMINATOR);
+    T::try_deserialize(&mut data.as_slice()).unwrap()
+}
+
+fn program_error(error: CloakCraftError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error.into()))
+}
+
+async fn send(context: &mut ProgramTestContext, ix: Instruction) -> std::result::Result<(), TransactionError> {
+    // A fresh blockhash keeps repeated instructions from deduplicating
+    let blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&context.payer.pubkey()), &[&context.payer], blockhash);
+    context.banks_client.process_transaction(tx).await.map_err(|e| e.unwrap())
+}
+
+fn migrate_amm_pool_layout_ix(payer: Pubkey, amm_pool: Pubkey, token_a_mint: Pubkey, token_b_mint: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cloakcraft::ID,
+        accounts: cloakcraft::accounts::MigrateAmmPoolLayout {
+            amm_pool,
+            payer,
+            system_program: anchor_lang::system_program::ID,
+        }
+        .to_account_metas(None),
+        data: cloakcraft::instruction::MigrateAmmPoolLayout { token_a_mint, token_b_mint }.data(),
+    }
+}
+
+#[tokio::test]
+async fn test_original_amm_pool_migrates_to_current_layout() {
+    let mut program_test = ProgramTest::new("cloakcraft", cloakcraft::ID, processor!(process_instruction));
+
+    let token_a_mint = Pubkey::new_unique();
+    let token_b_mint = Pubkey::new_unique();
+    let (amm_pool, bump) = Pubkey::find_program_address(
+        &[seeds::AMM_POOL, token_a_mint.as_ref(), token_b_mint.as_ref()],
+        &cloakcraft::ID,
+    );
+    let original = AmmPool {
+        pool_id: amm_pool,
+        token_a_mint,
+        token_b_mint,
+        reserve_a: 1_000_000,
+        reserve_b: 2_000_000,
+        lp_supply: 1_414_213,
+        fee_bps: 30,
+        is_active: true,
+        bump,
+        pool_type: PoolType::StableSwap,
+        amplification: 200,
+        ..Default::default()
+    };
+    program_test.add_account(amm_pool, legacy_account(&original, AMM_POOL_ORIGINAL_LEN, AmmPool::LEN));
+
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+
+    send(&mut context, migrate_amm_pool_layout_ix(payer, amm_pool, token_a_mint, token_b_mint))
+        .await
+        .unwrap();
+
+    let account = context.banks_client.get_account(amm_pool).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), AmmPool::LEN);
+    let migrated = AmmPool::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(migrated.reserve_a, original.reserve_a);
+    assert_eq!(migrated.reserve_b, original.reserve_b);
+    assert_eq!(migrated.lp_supply, original.lp_supply);
+    assert_eq!(migrated.pool_type, PoolType::StableSwap);
+    assert_eq!(migrated.amplification, 200);
+    assert_eq!(migrated.min_lp_lock_slots, 0);
+    assert_eq!(migrated.jit_penalty_bps, 0);
+
+    // A second run has nothing to do
+    let ix = migrate_amm_pool_layout_ix(payer, amm_pool, token_a_mint, token_b_mint);
+    let err = send(&mut context, ix).await.unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::AccountAlreadyMigrated));
+}
+
+#[tokio::test]
+async fn test_migrated_original_amm_pool_keeps_its_address() {
+    let mut program_test = ProgramTest::new("cloakcraft", cloakcraft::ID, processor!(process_instruction));
+
+    let token_a_mint = Pubkey::new_unique();
+    let token_b_mint = Pubkey::new_unique();
+    let (amm_pool, bump) = Pubkey::find_program_address(
+        &[seeds::AMM_POOL, token_a_mint.as_ref(), token_b_mint.as_ref()],
+        &cloakcraft::ID,
+    );
+    let original = AmmPool { token_a_mint, token_b_mint, is_active: true, bump, ..Default::default() };
+    program_test.add_account(amm_pool, legacy_account(&original, AMM_POOL_ORIGINAL_LEN, AmmPool::LEN));
+
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+
+    // Not loadable by migrate_amm_pool until reallocated
+    let account = context.banks_client.get_account(amm_pool).await.unwrap().unwrap();
+    assert!(AmmPool::try_deserialize(&mut account.data.as_slice()).is_err());
+
+    send(&mut context, migrate_amm_pool_layout_ix(payer, amm_pool, token_a_mint, token_b_mint))
+        .await
+        .unwrap();
+
+    // Version 0, so the successor migration's seed check re-derives the same PDA
+    let account = context.banks_client.get_account(amm_pool).await.unwrap().unwrap();
+    let migrated = AmmPool::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(migrated.version, 0);
+    assert!(migrated.version_seed().is_empty());
+    let pda = Pubkey::create_program_address(
+        &[
+            seeds::AMM_POOL,
+            migrated.token_a_mint.as_ref(),
+            migrated.token_b_mint.as_ref(),
+            migrated.version_seed(),
+            &[migrated.bump],
+        ],
+        &cloakcraft::ID,
+    )
+    .unwrap();
+    assert_eq!(pda, amm_pool);
+}
+
+fn migrate_ballot_ix(payer: Pubkey, ballot: Pubkey, ballot_id: [u8; 32]) -> Instruction {
+    Instruction {
+        program_id: cloakcraft::ID,
+        accounts: cloakcraft::accounts::MigrateBallot {
+            ballot,
+            payer,
+            system_program: anchor_lang::system_program::ID,
+        }
+        .to_account_metas(None),
+        data: cloakcraft::instruction::MigrateBallot { ballot_id }.data(),
+    }
+}
+
+#[tokio::test]
+async fn test_live_spend_to_vote_ballot_migrates_to_current_layout() {
+    let mut program_test = ProgramTest::new("cloakcraft", cloakcraft::ID, processor!(process_instruction));
+
+    let ballot_id = [9u8; 32];
+    let (ballot, bump) = Pubkey::find_program_address(&[seeds::BALLOT, &ballot_id], &cloakcraft::ID);
+    let mut original: Ballot = zeroed(Ballot::SPACE);
+    original.ballot_id = ballot_id;
+    original.authority = Pubkey::new_unique();
+    original.token_mint = Pubkey::new_unique();
+    original.binding_mode = VoteBindingMode::SpendToVote;
+    original.status = BallotStatus::Active;
+    original.num_options = 3;
+    original.option_amounts[1] = 500_000;
+    original.total_amount = 500_000;
+    original.vote_count = 4;
+    original.pool_balance = 500_000;
+    original.claim_deadline = 1_900_000_000;
+    original.bump = bump;
+    program_test.add_account(ballot, legacy_account(&original, BALLOT_ORIGINAL_SPACE, Ballot::SPACE));
+
+    let mut context = program_test.start_with_context().await;
+    let payer = context.payer.pubkey();
+
+    let account = context.banks_client.get_account(ballot).await.unwrap().unwrap();
+    assert!(Ballot::try_deserialize(&mut account.data.as_slice()).is_err());
+
+    send(&mut context, migrate_ballot_ix(payer, ballot, ballot_id)).await.unwrap();
+
+    let account = context.banks_client.get_account(ballot).await.unwrap().unwrap();
+    assert_eq!(account.data.len(), Ballot::SPACE);
+    let migrated = Ballot::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(migrated.binding_mode, VoteBindingMode::SpendToVote);
+    assert_eq!(migrated.status, BallotStatus::Active);
+    assert_eq!(migrated.option_amounts, original.option_amounts);
+    assert_eq!(migrated.pool_balance, original.pool_balance);
+    assert_eq!(migrated.claim_deadline, original.claim_deadline);
+    assert_eq!(migrated.bump, bump);
+    assert!(!migrated.has_snapshot_root);
+    assert_eq!(migrated.proposal_bond, 0);
+    assert_eq!(migrated.loser_refund_bps, 0);
+    assert_eq!(migrated.dispute_window_seconds, 0);
+
+    let err = send(&mut context, migrate_ballot_ix(payer, ballot, ballot_id)).await.unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::AccountAlreadyMigrated));
+}
+
+fn set_pool_trees_ix(authority: Pubkey, pool: Pubkey, state_tree: Pubkey, address_tree: Pubkey) -> Instruction {
+    Instruction {
+        program_id: cloakcraft::ID,
+        accounts: cloakcraft::accounts::SetPoolTrees { pool, authority }.to_account_metas(None),
+        data: cloakcraft::instruction::SetPoolTrees { state_tree, address_tree }.data(),
+    }
+}
+
+#[tokio::test]
+async fn test_migrated_pool_trees_are_set_once_by_authority() {
+    let mut program_test = ProgramTest::new("cloakcraft", cloakcraft::ID, processor!(process_instruction));
+
+    let authority = Keypair::new();
+    let token_mint = Pubkey::new_unique();
+    let (pool, bump) = Pubkey::find_program_address(&[seeds::POOL, token_mint.as_ref()], &cloakcraft::ID);
+    // As migrate_pool leaves it: current size, trees zeroed
+    let mut migrated: Pool = zeroed(Pool::LEN);
+    migrated.token_mint = token_mint;
+    migrated.authority = authority.pubkey();
+    migrated.bump = bump;
+    program_test.add_account(pool, legacy_account(&migrated, Pool::LEN, Pool::LEN));
+
+    let mut context = program_test.start_with_context().await;
+    let (state_tree, address_tree) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let mut send_as_authority = async |ix: Instruction| {
+        let blockhash = context.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &authority],
+            blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.map_err(|e| e.unwrap())
+    };
+
+    send_as_authority(set_pool_trees_ix(authority.pubkey(), pool, state_tree, address_tree))
+        .await
+        .unwrap();
+
+    // Trees are fixed once set; rollovers go through migrate_pool_trees
+    let err = send_as_authority(set_pool_trees_ix(authority.pubkey(), pool, Pubkey::new_unique(), Pubkey::new_unique()))
+        .await
+        .unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::PoolTreesAlreadySet));
+
+    let account = context.banks_client.get_account(pool).await.unwrap().unwrap();
+    let pool = Pool::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(pool.state_tree, state_tree);
+    assert_eq!(pool.address_tree, address_tree);
+}
@@ -0,0 +1,294 @@
+//! Append-pattern state machine tests
+//!
+//! Drives `PendingOperation` through the phase sequence used by every
+//! multi-phase flow (Phase 0 → verify inputs → nullifiers → execute →
+//! commitments → close) with the input/output shapes each Phase 0
+//! instruction configures, including out-of-order and expiry paths. The
+//! same sequences run through the program entrypoint in `phase_flows.rs`.
+
+use anchor_lang::prelude::*;
+use anchor_lang::AccountDeserialize;
+use anchor_lang::Discriminator;
+
+use cloakcraft::constants::operation_types;
+use cloakcraft::state::{
+    PendingOperation, ProtocolConfig, MAX_INPUTS, MAX_PENDING_COMMITMENTS,
+    PENDING_OPERATION_EXPIRY_SECONDS,
+};
+
+const NOW: i64 = 1_700_000_000;
+
+/// Mirror of what each Phase 0 instruction writes into the PDA
+fn phase0(operation_type: u8, num_inputs: u8, num_commitments: u8) -> PendingOperation {
+    let mut data = vec![0u8; PendingOperation::SPACE];
+    data[..8].copy_from_slice(PendingOperation::DISCRIMINATOR);
+    let mut op = PendingOperation::try_deserialize(&mut data.as_slice()).unwrap();
+
+    op.operation_id = [7u8; 32];
+    op.relayer = Pubkey::new_unique();
+    op.operation_type = operation_type;
+    op.proof_verified = true;
+    op.num_inputs = num_inputs;
+    op.num_commitments = num_commitments;
+    for i in 0..num_inputs as usize {
+        op.input_commitments[i] = [i as u8 + 1; 32];
+        op.expected_nullifiers[i] = [i as u8 + 0x10; 32];
+    }
+    for i in 0..num_commitments as usize {
+        op.commitments[i] = [i as u8 + 0x20; 32];
+    }
+    op.created_at = NOW;
+    op.expires_at = NOW + PENDING_OPERATION_EXPIRY_SECONDS;
+    op
+}
+
+/// Run Phases 1-4 in order, asserting the state machine never reports
+/// completion early
+fn run_to_completion(op: &mut PendingOperation) {
+    // Phase 1: verify_commitment_exists for each input
+    for i in 0..op.num_inputs {
+        assert!(!op.all_inputs_verified());
+        op.inputs_verified_mask |= 1u8 << i;
+    }
+    assert!(op.all_inputs_verified());
+
+    // Phase 2: create_nullifier_and_pending for each input
+    while let Some(i) = op.next_uncreated_nullifier() {
+        assert!(!op.is_complete());
+        op.mark_nullifier_created(i);
+    }
+    assert!(op.all_nullifiers_created());
+
+    // Phase 4: create_commitment for each output
+    while let Some(i) = op.next_uncompleted() {
+        assert!(!op.is_complete());
+        op.mark_completed(i);
+    }
+    assert!(op.all_commitments_created());
+    assert!(op.is_complete());
+}
+
+#[test]
+fn test_space_matches_serialized_len() {
+    let op = phase0(operation_types::TRANSFER, 1, 2);
+    let mut buf = Vec::new();
+    op.try_serialize(&mut buf).unwrap();
+    assert_eq!(buf.len(), PendingOperation::SPACE);
+}
+
+#[test]
+fn test_pending_pda_is_unique_per_operation() {
+    let (a, _) = Pubkey::find_program_address(
+        &[PendingOperation::SEEDS_PREFIX, &[1u8; 32]],
+        &cloakcraft::ID,
+    );
+    let (b, _) = Pubkey::find_program_address(
+        &[PendingOperation::SEEDS_PREFIX, &[2u8; 32]],
+        &cloakcraft::ID,
+    );
+    assert_ne!(a, b);
+}
+
+#[test]
+fn test_transfer_flow() {
+    let mut op = phase0(operation_types::TRANSFER, 1, 2);
+    run_to_completion(&mut op);
+}
+
+#[test]
+fn test_consolidation_flow() {
+    let mut op = phase0(operation_types::CONSOLIDATE, MAX_INPUTS as u8, 1);
+    run_to_completion(&mut op);
+}
+
+#[test]
+fn test_swap_flow() {
+    let mut op = phase0(operation_types::SWAP, 1, 2);
+    op.swap_amount = 1_000;
+    op.min_output = 990;
+    op.swap_a_to_b = true;
+    run_to_completion(&mut op);
+}
+
+#[test]
+fn test_add_liquidity_flow() {
+    let mut op = phase0(operation_types::ADD_LIQUIDITY, 2, 3);
+    run_to_completion(&mut op);
+}
+
+#[test]
+fn test_remove_liquidity_flow() {
+    let mut op = phase0(operation_types::REMOVE_LIQUIDITY, 1, 2);
+    run_to_completion(&mut op);
+}
+
+#[test]
+fn test_perps_flows() {
+    for (op_type, outputs) in [
+        (operation_types::PERPS_OPEN_POSITION, 2),
+        (operation_types::PERPS_CLOSE_POSITION, 1),
+        (operation_types::PERPS_LIQUIDATE, 2),
+        (operation_types::PERPS_ADD_LIQUIDITY, 1),
+        (operation_types::PERPS_REMOVE_LIQUIDITY, 2),
+    ] {
+        let mut op = phase0(op_type, 1, outputs);
+        run_to_completion(&mut op);
+    }
+}
+
+#[test]
+fn test_voting_flows() {
+    for op_type in [
+        operation_types::VOTE_SNAPSHOT,
+        operation_types::CHANGE_VOTE_SNAPSHOT,
+        operation_types::VOTE_SPEND,
+        operation_types::CHANGE_VOTE_SPEND,
+        operation_types::CLOSE_VOTE_POSITION,
+        operation_types::CLAIM,
+    ] {
+        let mut op = phase0(op_type, 1, 1);
+        run_to_completion(&mut op);
+    }
+}
+
+#[test]
+fn test_claim_rewards_flow() {
+    // LP note in, re-created LP note + rewards note out
+    let mut op = phase0(operation_types::CLAIM_REWARDS, 1, 2);
+    op.swap_amount = 1_000;
+    op.output_amount = 50;
+    run_to_completion(&mut op);
+}
+
+#[test]
+fn test_claim_fee_rebate_flow() {
+    // No input notes: Phase 0 -> execute -> single rebate commitment
+    let mut op = phase0(operation_types::CLAIM_FEE_REBATE, 0, 1);
+    op.input_pools[0] = [9u8; 32];
+    assert!(op.all_inputs_verified());
+    run_to_completion(&mut op);
+}
+
+#[test]
+fn test_donate_flow() {
+    // Input note in, donation note + change note out; round bound in spare input slot
+    let mut op = phase0(operation_types::DONATE, 1, 2);
+    op.input_pools[1] = [7u8; 32];
+    run_to_completion(&mut op);
+}
+
+#[test]
+fn test_max_outputs_flow() {
+    let mut op = phase0(operation_types::TRANSFER, 1, MAX_PENDING_COMMITMENTS as u8);
+    run_to_completion(&mut op);
+}
+
+#[test]
+fn test_out_of_order_commitments() {
+    let mut op = phase0(operation_types::ADD_LIQUIDITY, 2, 3);
+    op.mark_nullifier_created(1);
+    assert!(!op.all_nullifiers_created());
+    assert_eq!(op.next_uncreated_nullifier(), Some(0));
+    op.mark_nullifier_created(0);
+
+    op.mark_completed(2);
+    op.mark_completed(0);
+    assert_eq!(op.next_uncompleted(), Some(1));
+    assert!(!op.is_complete());
+
+    // Re-marking an index is idempotent
+    op.mark_completed(0);
+    assert_eq!(op.next_uncompleted(), Some(1));
+    op.mark_completed(1);
+    assert!(op.is_complete());
+}
+
+#[test]
+fn test_incomplete_operation_not_closable_before_expiry() {
+    let mut op = phase0(operation_types::TRANSFER, 1, 2);
+    op.mark_nullifier_created(0);
+    op.mark_completed(0);
+
+    // close_pending_operation requires complete || expired
+    let closable = |op: &PendingOperation, t: i64| op.is_complete() || op.is_expired(t);
+    assert!(!closable(&op, NOW));
+    assert!(!closable(&op, op.expires_at));
+    assert!(closable(&op, op.expires_at + 1));
+}
+
+#[test]
+fn test_expired_operation_rejects_further_phases() {
+    let op = phase0(operation_types::SWAP, 1, 2);
+    // Phase 1-4 constraints are `!is_expired(now)`
+    assert!(!op.is_expired(NOW + PENDING_OPERATION_EXPIRY_SECONDS));
+    assert!(op.is_expired(NOW + PENDING_OPERATION_EXPIRY_SECONDS + 1));
+}
+
+#[test]
+fn test_zero_input_operation_skips_nullifiers() {
+    let mut op = phase0(operation_types::TRANSFER, 0, 1);
+    assert!(op.all_nullifiers_created());
+    assert_eq!(op.next_uncreated_nullifier(), None);
+    op.mark_completed(0);
+    assert!(op.is_complete());
+}
+
+#[test]
+fn test_unshield_and_invoke_call_hash() {
+    use anchor_lang::solana_program::instruction::AccountMeta;
+    use cloakcraft::instructions::compute_call_hash;
+
+    let target = Pubkey::new_unique();
+    let recipient = Pubkey::new_unique();
+    let accounts = vec![AccountMeta::new(Pubkey::new_unique(), false)];
+    let hash = compute_call_hash(&target, &recipient, &accounts, b"deposit");
+
+    // Canonical field element, so Phase 0 accepts it as a public input
+    assert!(hash[0] < 0x20);
+    assert_ne!(hash, compute_call_hash(&target, &recipient, &accounts, b"withdraw"));
+    assert_ne!(hash, compute_call_hash(&target, &Pubkey::new_unique(), &accounts, b"deposit"));
+    // Forwarded accounts and their flags are bound too
+    assert_ne!(hash, compute_call_hash(&target, &recipient, &[], b"deposit"));
+    let readonly = vec![AccountMeta::new_readonly(accounts[0].pubkey, false)];
+    assert_ne!(hash, compute_call_hash(&target, &recipient, &readonly, b"deposit"));
+
+    let mut op = phase0(operation_types::TRANSFER, 1, 2);
+    assert_eq!(op.call_hash, [0u8; 32]);
+    op.unshield_amount = 100;
+    op.call_hash = hash;
+    run_to_completion(&mut op);
+}
+
+#[test]
+fn test_close_rent_refund_split() {
+    let config = ProtocolConfig { rent_refund_bps: 2_500, ..Default::default() };
+    let rent = 20_000_000;
+
+    // No recipient bound: everything goes to the relayer
+    let mut op = phase0(operation_types::TRANSFER, 1, 2);
+    op.set_rent_refund_bps(&config);
+    assert_eq!(op.rent_refund_bps, 2_500);
+    assert_eq!(op.rent_refund_amount(rent), 0);
+
+    // Split is resolved at Phase 0 and paid once the proven recipient is bound
+    let user = Pubkey::new_unique();
+    op.bind_rent_refund_recipient(user);
+    assert_eq!(op.rent_refund_recipient, user);
+    assert_eq!(op.rent_refund_amount(rent), 5_000_000);
+    run_to_completion(&mut op);
+}
+
+#[test]
+fn test_pending_outflow_for_solvency() {
+    let mut op = phase0(operation_types::TRANSFER, 1, 2);
+    assert_eq!(op.pending_outflow(), 0);
+
+    // Unshield and fee both still owed by the vault until Phase 3
+    op.unshield_amount = 1_000;
+    op.fee_amount = 10;
+    assert_eq!(op.pending_outflow(), 1_010);
+
+    // Fee already paid (retried Phase 3)
+    op.fee_processed = true;
+    assert_eq!(op.pending_outflow(), 1_000);
+}
@@ -0,0 +1,921 @@
+//! Full operation flows through the program entrypoint (solana-program-test)
+//!
+//! Each test drives one operation family from its Phase 0 state to
+//! close_pending_operation with real instructions: Phase 1
+//! (verify_commitment_exists), Phase 2 (create_nullifier_and_pending), the
+//! family's Phase 3 instruction, Phase 4 (create_commitment) and close.
+//!
+//! Phase 0 is the one step that cannot run natively: `init` creates the
+//! PendingOperation PDA through an Anchor CPI, which only runs on-chain. The
+//! tests inject the account exactly as the family's Phase 0 handler writes it
+//! and run everything after it through `cloakcraft::entry`. Light system
+//! program CPIs return without effect off-chain, so Phases 1, 2 and 4 cover
+//! the program's own checks (account hash, pool trees, masks, domains,
+//! counters) but not Light's proof verification.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{entrypoint::ProgramResult, instruction::Instruction, program_pack::Pack};
+use anchor_lang::{AccountDeserialize, Discriminator, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account as SolanaAccount,
+    instruction::{AccountMeta, InstructionError},
+    signature::{Keypair, Signer as _},
+    transaction::{Transaction, TransactionError},
+};
+
+use cloakcraft::constants::{circuits, operation_types, seeds};
+use cloakcraft::errors::CloakCraftError;
+use cloakcraft::instructions::generic::verify_commitment_exists::{CommitmentMerkleContext, LightVerifyCommitmentParams};
+use cloakcraft::instructions::{
+    LightCreateCommitmentParams, LightCreateNullifierAndPendingParams, LightCreateVoteCommitmentParams,
+    LightCreateVoteNullifierParams,
+};
+use cloakcraft::light_cpi::{commitment_account_hash_at, derive_commitment_address};
+use cloakcraft::pyth::feed_ids;
+use cloakcraft::state::{
+    AmmPool, Ballot, BallotStatus, LightAddressTreeInfo, LightValidityProof, MarketStatus, PendingOperation,
+    PerpsMarket, PerpsPool, Pool, PoolCommitmentCounter, PoolType, ProtocolConfig, RevealMode, VoteBindingMode,
+    PENDING_OPERATION_EXPIRY_SECONDS,
+};
+use pyth_solana_receiver_sdk::price_update::{PriceFeedMessage, PriceUpdateV2, VerificationLevel};
+
+/// Operation IDs carry the operation epoch (0) in their first 4 bytes
+const OPERATION_ID: [u8; 32] = {
+    let mut id = [9u8; 32];
+    id[0] = 0;
+    id[1] = 0;
+    id[2] = 0;
+    id[3] = 0;
+    id
+};
+const SHIELDED: u64 = 10_000_000;
+const RESERVE: u64 = 1_000_000;
+
+/// Light system accounts ahead of the tree accounts in `remaining_accounts`
+const LIGHT_SYSTEM_ACCOUNTS: usize = 6;
+
+/// Native entry: Anchor's entry ties the account slice to its lifetime
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts: &[AccountInfo] = unsafe { std::mem::transmute(accounts) };
+    cloakcraft::entry(program_id, accounts, data)
+}
+
+fn anchor_account<T: AccountSerialize>(value: &T, space: usize) -> SolanaAccount {
+    let mut data = Vec::with_capacity(space);
+    value.try_serialize(&mut data).unwrap();
+    data.resize(space, 0);
+    SolanaAccount {
+        lamports: Rent::default().minimum_balance(space),
+        data,
+        owner: cloakcraft::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn packed_account<T: Pack>(value: T) -> SolanaAccount {
+    let mut data = vec![0u8; T::LEN];
+    T::pack(value, &mut data).unwrap();
+    SolanaAccount {
+        lamports: Rent::default().minimum_balance(T::LEN),
+        data,
+        owner: spl_token::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Zero-initialized account of type `T`, as `init` would leave it
+fn zeroed<T: AccountDeserialize + Discriminator>(space: usize) -> T {
+    let mut data = vec![0u8; space];
+    data[..8].copy_from_slice(T::DISCRIMINATOR);
+    T::try_deserialize(&mut data.as_slice()).unwrap()
+}
+
+fn program_error(error: CloakCraftError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error.into()))
+}
+
+/// A v1 note for the 104-byte plaintext (version, ephemeral, nonce, body, tag)
+fn encrypted_note() -> Vec<u8> {
+    let mut note = vec![0u8; 1 + 64 + 12 + 104 + 16];
+    note[0] = 1;
+    note
+}
+
+/// A shielded pool with its vault and commitment counter
+struct TestPool {
+    address: Pubkey,
+    mint: Pubkey,
+    vault: Pubkey,
+    counter: Pubkey,
+}
+
+/// Bank, relayer and the trees every pool writes to
+struct Flow {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    state_tree: Pubkey,
+    address_tree: Pubkey,
+    light_system_accounts: Vec<Pubkey>,
+}
+
+async fn setup() -> Flow {
+    let mut program_test = ProgramTest::new("cloakcraft", cloakcraft::ID, processor!(process_instruction));
+
+    let relayer = Keypair::new();
+    program_test.add_account(
+        relayer.pubkey(),
+        SolanaAccount::new(10_000_000_000, 0, &anchor_lang::system_program::ID),
+    );
+
+    let (config, config_bump) = Pubkey::find_program_address(&[seeds::PROTOCOL_CONFIG], &cloakcraft::ID);
+    let mut config_state: ProtocolConfig = zeroed(8 + ProtocolConfig::LEN);
+    config_state.authority = Pubkey::new_unique();
+    config_state.treasury = Pubkey::new_unique();
+    config_state.fees_enabled = false;
+    config_state.bump = config_bump;
+    program_test.add_account(config, anchor_account(&config_state, 8 + ProtocolConfig::LEN));
+
+    Flow {
+        context: program_test.start_with_context().await,
+        relayer,
+        state_tree: Pubkey::new_unique(),
+        address_tree: Pubkey::new_unique(),
+        light_system_accounts: (0..LIGHT_SYSTEM_ACCOUNTS).map(|_| Pubkey::new_unique()).collect(),
+    }
+}
+
+/// Phase 0 state shared by every family: the proof verified for `relayer`
+fn phase0(relayer: Pubkey, operation_type: u8, circuit_id: &[u8; 32], now: i64) -> PendingOperation {
+    let (_, bump) = Pubkey::find_program_address(&[PendingOperation::SEEDS_PREFIX, &OPERATION_ID], &cloakcraft::ID);
+    let mut op: PendingOperation = zeroed(PendingOperation::SPACE);
+    op.operation_id = OPERATION_ID;
+    op.relayer = relayer;
+    op.bump = bump;
+    op.operation_type = operation_type;
+    op.proof_verified = true;
+    op.bind_circuit(circuit_id);
+    op.created_at = now;
+    op.expires_at = now + PENDING_OPERATION_EXPIRY_SECONDS;
+    op
+}
+
+/// Record a spent input as Phase 0 does
+fn add_input(op: &mut PendingOperation, pool: &TestPool) {
+    let index = op.num_inputs as usize;
+    op.input_commitments[index] = [0x10 + index as u8; 32];
+    op.expected_nullifiers[index] = [0x20 + index as u8; 32];
+    op.input_pools[index] = pool.address.to_bytes();
+    op.num_inputs += 1;
+}
+
+/// Record an output commitment as Phase 0 does
+fn add_output(op: &mut PendingOperation, pool: &TestPool, amount: u64) {
+    let index = op.num_commitments as usize;
+    op.pools[index] = pool.address.to_bytes();
+    op.commitments[index] = [0x30 + index as u8; 32];
+    op.output_amounts[index] = amount;
+    op.output_recipients[index] = [0x40 + index as u8; 32];
+    op.output_randomness[index] = [0x50 + index as u8; 32];
+    op.num_commitments += 1;
+}
+
+fn pending_operation_address() -> Pubkey {
+    Pubkey::find_program_address(&[PendingOperation::SEEDS_PREFIX, &OPERATION_ID], &cloakcraft::ID).0
+}
+
+fn protocol_config_address() -> Pubkey {
+    Pubkey::find_program_address(&[seeds::PROTOCOL_CONFIG], &cloakcraft::ID).0
+}
+
+fn tree_registry_address() -> Pubkey {
+    Pubkey::find_program_address(&[seeds::TREE_REGISTRY], &cloakcraft::ID).0
+}
+
+/// Trees after the Light system accounts: the state tree, then the address tree
+const STATE_TREE_INDEX: u8 = 0;
+const ADDRESS_TREE_INDEX: u8 = 1;
+
+fn address_tree_info() -> LightAddressTreeInfo {
+    LightAddressTreeInfo {
+        address_merkle_tree_pubkey_index: ADDRESS_TREE_INDEX,
+        address_queue_pubkey_index: ADDRESS_TREE_INDEX,
+        root_index: 0,
+    }
+}
+
+impl Flow {
+    async fn now(&mut self) -> i64 {
+        self.context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp
+    }
+
+    fn set(&mut self, address: Pubkey, account: SolanaAccount) {
+        self.context.set_account(&address, &account.into());
+    }
+
+    /// Store the PendingOperation a Phase 0 instruction would have created
+    fn inject(&mut self, op: &PendingOperation) {
+        self.set(pending_operation_address(), anchor_account(op, PendingOperation::SPACE));
+    }
+
+    /// A shielded pool in the flow's trees, with its vault and commitment counter
+    fn pool(&mut self) -> TestPool {
+        let mint = Pubkey::new_unique();
+        self.set(
+            mint,
+            packed_account(spl_token::state::Mint {
+                decimals: 6,
+                is_initialized: true,
+                supply: SHIELDED,
+                ..Default::default()
+            }),
+        );
+
+        let (address, pool_bump) = Pubkey::find_program_address(&[seeds::POOL, mint.as_ref()], &cloakcraft::ID);
+        let (vault, vault_bump) = Pubkey::find_program_address(&[seeds::VAULT, mint.as_ref()], &cloakcraft::ID);
+        let mut pool: Pool = zeroed(Pool::LEN);
+        pool.token_mint = mint;
+        pool.token_vault = vault;
+        pool.authority = Pubkey::new_unique();
+        pool.bump = pool_bump;
+        pool.vault_bump = vault_bump;
+        pool.total_shielded = SHIELDED;
+        pool.state_tree = self.state_tree;
+        pool.address_tree = self.address_tree;
+        pool.layout_version = Pool::LAYOUT_VERSION;
+        self.set(address, anchor_account(&pool, Pool::LEN));
+        self.set(
+            vault,
+            packed_account(spl_token::state::Account {
+                mint,
+                owner: address,
+                amount: SHIELDED,
+                state: spl_token::state::AccountState::Initialized,
+                ..Default::default()
+            }),
+        );
+
+        let (counter, counter_bump) =
+            Pubkey::find_program_address(&[PoolCommitmentCounter::SEEDS_PREFIX, address.as_ref()], &cloakcraft::ID);
+        let counter_state = PoolCommitmentCounter { pool: address, bump: counter_bump, ..Default::default() };
+        self.set(counter, anchor_account(&counter_state, 8 + PoolCommitmentCounter::INIT_SPACE));
+
+        TestPool { address, mint, vault, counter }
+    }
+
+    /// Two pools ordered as an AMM pair (token A has the lower mint)
+    fn pool_pair(&mut self) -> (TestPool, TestPool) {
+        let (first, second) = (self.pool(), self.pool());
+        if first.mint.as_ref() < second.mint.as_ref() {
+            (first, second)
+        } else {
+            (second, first)
+        }
+    }
+
+    /// An active constant-product AMM pool for `a`/`b` with equal reserves
+    fn amm_pool(&mut self, a: &TestPool, b: &TestPool, lp: &TestPool) -> (Pubkey, AmmPool) {
+        let (address, bump) =
+            Pubkey::find_program_address(&[seeds::AMM_POOL, a.mint.as_ref(), b.mint.as_ref()], &cloakcraft::ID);
+        let mut amm: AmmPool = zeroed(AmmPool::LEN);
+        amm.pool_id = address;
+        amm.token_a_mint = a.mint;
+        amm.token_b_mint = b.mint;
+        amm.lp_mint = lp.mint;
+        amm.reserve_a = RESERVE;
+        amm.reserve_b = RESERVE;
+        amm.lp_supply = RESERVE;
+        amm.fee_bps = 30;
+        amm.authority = Pubkey::new_unique();
+        amm.is_active = true;
+        amm.bump = bump;
+        amm.pool_type = PoolType::ConstantProduct;
+        amm.state_hash = amm.compute_state_hash();
+        self.set(address, anchor_account(&amm, AmmPool::LEN));
+        (address, amm)
+    }
+
+    async fn send(&mut self, ix: Instruction) -> std::result::Result<(), TransactionError> {
+        let blockhash = self.context.banks_client.get_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(&[ix], Some(&self.relayer.pubkey()), &[&self.relayer], blockhash);
+        self.context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .map_err(|e| e.unwrap())
+    }
+
+    /// Light system accounts followed by the pool trees
+    fn light_accounts(&self) -> Vec<AccountMeta> {
+        self.light_system_accounts
+            .iter()
+            .map(|&key| AccountMeta::new_readonly(key, false))
+            .chain([AccountMeta::new(self.state_tree, false), AccountMeta::new(self.address_tree, false)])
+            .collect()
+    }
+
+    fn instruction(&self, accounts: impl ToAccountMetas, data: impl InstructionData, light: bool) -> Instruction {
+        let mut metas = accounts.to_account_metas(None);
+        if light {
+            metas.extend(self.light_accounts());
+        }
+        Instruction { program_id: cloakcraft::ID, accounts: metas, data: data.data() }
+    }
+
+    /// Phase 1: prove `op.input_commitments[index]` exists in `pool`
+    fn verify_ix(&self, op: &PendingOperation, pool: &TestPool, index: u8) -> Instruction {
+        let commitment = op.input_commitments[index as usize];
+        let leaf_index = 40 + index as u32;
+        // Light data hashes are BN254 field elements
+        let mut data_hash = [0x60 + index; 32];
+        data_hash[0] = 0;
+        let account_hash = commitment_account_hash_at(
+            &derive_commitment_address(&pool.address, &commitment, &self.address_tree),
+            &self.state_tree,
+            leaf_index,
+            &data_hash,
+        )
+        .unwrap();
+        self.instruction(
+            cloakcraft::accounts::VerifyCommitmentExists {
+                pool: pool.address,
+                pending_operation: pending_operation_address(),
+                relayer: self.relayer.pubkey(),
+            },
+            cloakcraft::instruction::VerifyCommitmentExists {
+                operation_id: OPERATION_ID,
+                commitment_index: index,
+                light_params: LightVerifyCommitmentParams {
+                    commitment_account_hash: account_hash,
+                    commitment_data_hash: data_hash,
+                    commitment_merkle_context: CommitmentMerkleContext {
+                        merkle_tree_pubkey_index: STATE_TREE_INDEX,
+                        queue_pubkey_index: STATE_TREE_INDEX,
+                        leaf_index,
+                        root_index: 0,
+                        prove_by_index: true,
+                    },
+                    commitment_inclusion_proof: LightValidityProof::default(),
+                    commitment_address_tree_info: address_tree_info(),
+                },
+            },
+            true,
+        )
+    }
+
+    /// Phase 2: create the nullifier of input `index` in `pool`
+    fn nullifier_ix(&self, pool: &TestPool, index: u8) -> Instruction {
+        self.instruction(
+            cloakcraft::accounts::CreateNullifierAndPending {
+                pool: pool.address,
+                pending_operation: pending_operation_address(),
+                relayer: self.relayer.pubkey(),
+                protocol_config: protocol_config_address(),
+                pool_stats: None,
+                tree_registry: tree_registry_address(),
+            },
+            cloakcraft::instruction::CreateNullifierAndPending {
+                operation_id: OPERATION_ID,
+                nullifier_index: index,
+                light_params: LightCreateNullifierAndPendingParams {
+                    proof: LightValidityProof::default(),
+                    address_tree_info: address_tree_info(),
+                    output_tree_index: STATE_TREE_INDEX,
+                },
+            },
+            true,
+        )
+    }
+
+    /// Phase 4: create output commitment `index` in `pool`
+    fn commitment_ix(&self, pool: &TestPool, index: u8) -> Instruction {
+        self.instruction(
+            cloakcraft::accounts::CreateCommitment {
+                pool: pool.address,
+                commitment_counter: pool.counter,
+                pending_operation: pending_operation_address(),
+                relayer: self.relayer.pubkey(),
+                pool_stats: None,
+                root_registry: Pubkey::find_program_address(
+                    &[seeds::ROOT_REGISTRY, pool.address.as_ref()],
+                    &cloakcraft::ID,
+                )
+                .0,
+                relayer_stake: None,
+                protocol_config: None,
+                treasury: None,
+                tree_registry: tree_registry_address(),
+            },
+            cloakcraft::instruction::CreateCommitment {
+                operation_id: OPERATION_ID,
+                commitment_index: index,
+                stealth_ephemeral_pubkey: [0u8; 64],
+                encrypted_note: encrypted_note(),
+                light_params: LightCreateCommitmentParams {
+                    proof: LightValidityProof::default(),
+                    address_tree_info: address_tree_info(),
+                    output_tree_index: STATE_TREE_INDEX,
+                },
+                view_tag: None,
+                payment_receipt: None,
+            },
+            true,
+        )
+    }
+
+    fn close_ix(&self) -> Instruction {
+        self.instruction(
+            cloakcraft::accounts::ClosePendingOperation {
+                pending_operation: pending_operation_address(),
+                relayer: self.relayer.pubkey(),
+                rent_refund_recipient: None,
+                pool: None,
+                dust_sweep_ledger: None,
+                relayer_stake: None,
+            },
+            cloakcraft::instruction::ClosePendingOperation { operation_id: OPERATION_ID },
+            false,
+        )
+    }
+
+    /// Phases 1 and 2 for every input
+    async fn spend_inputs(&mut self, inputs: &[&TestPool]) {
+        let op = self.pending().await.unwrap();
+        for (index, pool) in inputs.iter().enumerate() {
+            self.send(self.verify_ix(&op, pool, index as u8)).await.unwrap();
+        }
+        for (index, pool) in inputs.iter().enumerate() {
+            self.send(self.nullifier_ix(pool, index as u8)).await.unwrap();
+        }
+        let op = self.pending().await.unwrap();
+        assert!(op.all_inputs_verified());
+        assert!(op.all_expected_nullifiers_created());
+    }
+
+    /// Phase 4 for every output, then close
+    async fn create_outputs_and_close(&mut self, outputs: &[&TestPool]) {
+        for (index, pool) in outputs.iter().enumerate() {
+            self.send(self.commitment_ix(pool, index as u8)).await.unwrap();
+        }
+        assert!(self.pending().await.unwrap().is_complete());
+
+        let relayer = self.relayer.pubkey();
+        let relayer_before = self.lamports(relayer).await;
+        let rent = self.lamports(pending_operation_address()).await;
+        self.send(self.close_ix()).await.unwrap();
+        assert!(self.pending().await.is_none());
+        // Rent back to the relayer, less the transaction fee
+        assert!(self.lamports(relayer).await + 10_000 > relayer_before + rent);
+    }
+
+    async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.context.banks_client.get_balance(address).await.unwrap()
+    }
+
+    async fn pending(&mut self) -> Option<PendingOperation> {
+        let account = self.context.banks_client.get_account(pending_operation_address()).await.unwrap()?;
+        Some(PendingOperation::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    async fn anchor<T: AccountDeserialize>(&mut self, address: Pubkey) -> T {
+        let account = self.context.banks_client.get_account(address).await.unwrap().unwrap();
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+}
+
+#[tokio::test]
+async fn test_transfer_flow() {
+    let mut flow = setup().await;
+    let pool = flow.pool();
+
+    let now = flow.now().await;
+    let mut op = phase0(flow.relayer.pubkey(), operation_types::TRANSFER, &circuits::TRANSFER_1X2, now);
+    add_input(&mut op, &pool);
+    add_output(&mut op, &pool, 600_000);
+    add_output(&mut op, &pool, 400_000);
+    flow.inject(&op);
+
+    flow.spend_inputs(&[&pool]).await;
+    flow.create_outputs_and_close(&[&pool, &pool]).await;
+
+    let counter: PoolCommitmentCounter = flow.anchor(pool.counter).await;
+    assert_eq!(counter.next_leaf_index, 2);
+}
+
+#[tokio::test]
+async fn test_consolidation_flow() {
+    let mut flow = setup().await;
+    let pool = flow.pool();
+
+    let now = flow.now().await;
+    let mut op = phase0(flow.relayer.pubkey(), operation_types::CONSOLIDATE, &circuits::CONSOLIDATE_3X1, now);
+    for _ in 0..3 {
+        add_input(&mut op, &pool);
+    }
+    add_output(&mut op, &pool, 900_000);
+    flow.inject(&op);
+
+    flow.spend_inputs(&[&pool, &pool, &pool]).await;
+    flow.create_outputs_and_close(&[&pool]).await;
+
+    let counter: PoolCommitmentCounter = flow.anchor(pool.counter).await;
+    assert_eq!(counter.next_leaf_index, 1);
+}
+
+#[tokio::test]
+async fn test_swap_flow() {
+    let mut flow = setup().await;
+    let (pool_a, pool_b) = flow.pool_pair();
+    let lp_pool = flow.pool();
+    let (amm_address, amm) = flow.amm_pool(&pool_a, &pool_b, &lp_pool);
+
+    // Swap A -> B at the Phase 0 quote
+    let swap_amount = 10_000;
+    let (output_amount, _) = amm.calculate_swap_output(swap_amount, true).unwrap();
+    let now = flow.now().await;
+    let mut op = phase0(flow.relayer.pubkey(), operation_types::SWAP, &circuits::SWAP_SWAP, now);
+    add_input(&mut op, &pool_a);
+    add_output(&mut op, &pool_b, output_amount);
+    add_output(&mut op, &pool_a, 1);
+    op.swap_amount = swap_amount;
+    op.output_amount = output_amount;
+    op.min_output = output_amount;
+    op.swap_a_to_b = true;
+    flow.inject(&op);
+
+    flow.spend_inputs(&[&pool_a]).await;
+    let execute = flow.instruction(
+        cloakcraft::accounts::ExecuteSwap {
+            input_pool: pool_a.address,
+            output_pool: pool_b.address,
+            amm_pool: amm_address,
+            input_vault: pool_a.vault,
+            pending_operation: pending_operation_address(),
+            relayer: flow.relayer.pubkey(),
+            protocol_config: protocol_config_address(),
+            treasury_ata: None,
+            fee_rebate_config: None,
+            swap_volume: None,
+            token_program: spl_token::ID,
+            input_pool_stats: None,
+        },
+        cloakcraft::instruction::ExecuteSwap { operation_id: OPERATION_ID },
+        false,
+    );
+    flow.send(execute).await.unwrap();
+    flow.create_outputs_and_close(&[&pool_b, &pool_a]).await;
+
+    let amm: AmmPool = flow.anchor(amm_address).await;
+    assert_eq!(amm.reserve_a, RESERVE + swap_amount);
+    assert_eq!(amm.reserve_b, RESERVE - output_amount);
+    assert!(amm.verify_state_hash(&amm.state_hash));
+}
+
+#[tokio::test]
+async fn test_add_liquidity_flow() {
+    let mut flow = setup().await;
+    let (pool_a, pool_b) = flow.pool_pair();
+    let lp_pool = flow.pool();
+    let (amm_address, _) = flow.amm_pool(&pool_a, &pool_b, &lp_pool);
+
+    // Proportional deposit into equal reserves: LP minted 1:1
+    let deposit = 10_000;
+    let now = flow.now().await;
+    let mut op = phase0(flow.relayer.pubkey(), operation_types::ADD_LIQUIDITY, &circuits::SWAP_ADD_LIQUIDITY, now);
+    add_input(&mut op, &pool_a);
+    add_input(&mut op, &pool_b);
+    add_output(&mut op, &lp_pool, deposit);
+    add_output(&mut op, &pool_a, 1);
+    add_output(&mut op, &pool_b, 1);
+    op.swap_amount = deposit;
+    op.output_amount = deposit;
+    op.extra_amount = deposit;
+    flow.inject(&op);
+
+    flow.spend_inputs(&[&pool_a, &pool_b]).await;
+    let execute = flow.instruction(
+        cloakcraft::accounts::ExecuteAddLiquidity {
+            pool_a: pool_a.address,
+            pool_b: pool_b.address,
+            lp_pool: lp_pool.address,
+            amm_pool: amm_address,
+            pending_operation: pending_operation_address(),
+            relayer: flow.relayer.pubkey(),
+        },
+        cloakcraft::instruction::ExecuteAddLiquidity { operation_id: OPERATION_ID, min_lp_amount: deposit },
+        false,
+    );
+    flow.send(execute).await.unwrap();
+    flow.create_outputs_and_close(&[&lp_pool, &pool_a, &pool_b]).await;
+
+    let amm: AmmPool = flow.anchor(amm_address).await;
+    assert_eq!((amm.reserve_a, amm.reserve_b, amm.lp_supply), (RESERVE + deposit, RESERVE + deposit, RESERVE + deposit));
+}
+
+#[tokio::test]
+async fn test_remove_liquidity_flow() {
+    let mut flow = setup().await;
+    let (pool_a, pool_b) = flow.pool_pair();
+    let lp_pool = flow.pool();
+    let (amm_address, mut expected) = flow.amm_pool(&pool_a, &pool_b, &lp_pool);
+
+    let burned = 10_000;
+    let now = flow.now().await;
+    let mut op = phase0(
+        flow.relayer.pubkey(),
+        operation_types::REMOVE_LIQUIDITY,
+        &circuits::SWAP_REMOVE_LIQUIDITY,
+        now,
+    );
+    add_input(&mut op, &lp_pool);
+    add_output(&mut op, &pool_a, burned);
+    add_output(&mut op, &pool_b, burned);
+    op.swap_amount = burned;
+    op.output_amount = burned;
+    op.extra_amount = burned;
+    flow.inject(&op);
+
+    // The proof commits to the reserves after the withdrawal
+    expected.reserve_a -= burned;
+    expected.reserve_b -= burned;
+    expected.lp_supply -= burned;
+
+    flow.spend_inputs(&[&lp_pool]).await;
+    let execute = flow.instruction(
+        cloakcraft::accounts::ExecuteRemoveLiquidity {
+            lp_pool: lp_pool.address,
+            pool_a: pool_a.address,
+            pool_b: pool_b.address,
+            amm_pool: amm_address,
+            vault_a: pool_a.vault,
+            vault_b: pool_b.vault,
+            pending_operation: pending_operation_address(),
+            relayer: flow.relayer.pubkey(),
+            protocol_config: protocol_config_address(),
+            treasury_ata_a: None,
+            treasury_ata_b: None,
+            token_program: spl_token::ID,
+            pool_a_stats: None,
+            pool_b_stats: None,
+        },
+        cloakcraft::instruction::ExecuteRemoveLiquidity {
+            operation_id: OPERATION_ID,
+            new_state_hash: expected.compute_state_hash(),
+        },
+        false,
+    );
+    flow.send(execute).await.unwrap();
+    flow.create_outputs_and_close(&[&pool_a, &pool_b]).await;
+
+    let amm: AmmPool = flow.anchor(amm_address).await;
+    assert_eq!((amm.reserve_a, amm.reserve_b, amm.lp_supply), (expected.reserve_a, expected.reserve_b, expected.lp_supply));
+    assert_eq!(amm.state_hash, expected.compute_state_hash());
+}
+
+#[tokio::test]
+async fn test_perps_open_position_flow() {
+    let mut flow = setup().await;
+    let margin_pool = flow.pool();
+    let position_pool = flow.pool();
+
+    // Two-token perps pool (base priced by the SOL/USD feed) with one market
+    let pool_id = Pubkey::new_unique();
+    let (perps_pool, perps_bump) = Pubkey::find_program_address(&[seeds::PERPS_POOL, pool_id.as_ref()], &cloakcraft::ID);
+    let mut perps_state: PerpsPool = zeroed(PerpsPool::LEN);
+    perps_state.pool_id = pool_id;
+    perps_state.authority = Pubkey::new_unique();
+    perps_state.num_tokens = 2;
+    perps_state.max_leverage = 10;
+    perps_state.max_utilization_bps = 8_000;
+    perps_state.is_active = true;
+    perps_state.bump = perps_bump;
+    for token in perps_state.tokens.iter_mut().take(2) {
+        token.mint = Pubkey::new_unique();
+        token.balance = 1_000_000_000;
+        token.decimals = 6;
+        token.is_active = true;
+    }
+    perps_state.tokens[0].pyth_feed_id = feed_ids::SOL_USD;
+    flow.set(perps_pool, anchor_account(&perps_state, PerpsPool::LEN));
+
+    let market_id = [3u8; 32];
+    let (perps_market, market_bump) = Pubkey::find_program_address(
+        &[seeds::PERPS_MARKET, perps_pool.as_ref(), market_id.as_ref()],
+        &cloakcraft::ID,
+    );
+    let market_state = PerpsMarket {
+        market_id,
+        pool: perps_pool,
+        base_token_index: 0,
+        quote_token_index: 1,
+        is_active: true,
+        bump: market_bump,
+        status: MarketStatus::Active,
+        ..Default::default()
+    };
+    flow.set(perps_market, anchor_account(&market_state, 8 + PerpsMarket::INIT_SPACE));
+
+    // Fresh, fully verified $150 price
+    let now = flow.now().await;
+    let price_update = Pubkey::new_unique();
+    let price = PriceUpdateV2 {
+        write_authority: Pubkey::new_unique(),
+        verification_level: VerificationLevel::Full,
+        price_message: PriceFeedMessage {
+            feed_id: feed_ids::SOL_USD,
+            price: 15_000_000_000,
+            conf: 1_000_000,
+            exponent: -8,
+            publish_time: now,
+            prev_publish_time: now - 1,
+            ema_price: 15_000_000_000,
+            ema_conf: 1_000_000,
+        },
+        posted_slot: 0,
+    };
+    let mut price_account = anchor_account(&price, PriceUpdateV2::LEN);
+    price_account.owner = pyth_solana_receiver_sdk::ID;
+    flow.set(price_update, price_account);
+
+    // 100k margin at 5x long, 50k change back to the margin pool
+    let margin = 100_000;
+    let mut op = phase0(
+        flow.relayer.pubkey(),
+        operation_types::PERPS_OPEN_POSITION,
+        &circuits::PERPS_OPEN_POSITION,
+        now,
+    );
+    add_input(&mut op, &margin_pool);
+    add_output(&mut op, &position_pool, 1);
+    add_output(&mut op, &margin_pool, 50_000);
+    op.swap_amount = margin;
+    op.output_amount = 5;
+    op.min_output = 100;
+    op.swap_a_to_b = true;
+    op.extra_amount = 50_000;
+    flow.inject(&op);
+
+    flow.spend_inputs(&[&margin_pool]).await;
+    let execute = flow.instruction(
+        cloakcraft::accounts::ExecuteOpenPosition {
+            margin_pool: margin_pool.address,
+            perps_pool,
+            perps_market,
+            pending_operation: pending_operation_address(),
+            relayer: flow.relayer.pubkey(),
+            price_update,
+        },
+        cloakcraft::instruction::ExecuteOpenPosition { operation_id: OPERATION_ID, entry_price: 0 },
+        false,
+    );
+    flow.send(execute).await.unwrap();
+
+    let op = flow.pending().await.unwrap();
+    assert_eq!((op.position_margin, op.position_size, op.position_entry_price), (margin, 5 * margin, 150_000_000));
+    flow.create_outputs_and_close(&[&position_pool, &margin_pool]).await;
+
+    let perps_state: PerpsPool = flow.anchor(perps_pool).await;
+    assert_eq!((perps_state.tokens[0].locked, perps_state.tokens[1].locked), (margin, margin));
+    let market_state: PerpsMarket = flow.anchor(perps_market).await;
+    assert_eq!(market_state.long_open_interest, 5 * margin);
+}
+
+#[tokio::test]
+async fn test_vote_snapshot_flow() {
+    let mut flow = setup().await;
+
+    let ballot_id = [0xBAu8; 32];
+    let (ballot, ballot_bump) = Pubkey::find_program_address(&[seeds::BALLOT, ballot_id.as_ref()], &cloakcraft::ID);
+    let now = flow.now().await;
+    let mut ballot_state: Ballot = zeroed(Ballot::SPACE);
+    ballot_state.ballot_id = ballot_id;
+    ballot_state.authority = Pubkey::new_unique();
+    ballot_state.token_mint = Pubkey::new_unique();
+    ballot_state.binding_mode = VoteBindingMode::Snapshot;
+    ballot_state.reveal_mode = RevealMode::Public;
+    ballot_state.status = BallotStatus::Active;
+    ballot_state.num_options = 2;
+    ballot_state.start_time = now - 1;
+    ballot_state.end_time = now + 3_600;
+    ballot_state.bump = ballot_bump;
+    flow.set(ballot, anchor_account(&ballot_state, Ballot::SPACE));
+
+    // Snapshot votes have no Phase 1: the note stays unspent
+    let weight = 250_000;
+    let mut op = phase0(flow.relayer.pubkey(), operation_types::VOTE_SNAPSHOT, &circuits::VOTE_SNAPSHOT, now);
+    op.num_inputs = 1;
+    op.input_commitments[0] = [0x10; 32];
+    op.expected_nullifiers[0] = [0x20; 32];
+    op.input_pools[0] = ballot_id;
+    op.num_commitments = 1;
+    op.commitments[0] = [0x30; 32];
+    op.pools[0] = ballot_id;
+    op.output_amounts[0] = weight;
+    op.swap_amount = 1;
+    op.output_amount = weight;
+    op.extra_amount = weight;
+    flow.inject(&op);
+
+    let nullifier = flow.instruction(
+        cloakcraft::accounts::CreateVoteNullifier {
+            ballot,
+            pending_operation: pending_operation_address(),
+            relayer: flow.relayer.pubkey(),
+            protocol_config: protocol_config_address(),
+        },
+        cloakcraft::instruction::CreateVoteNullifier {
+            operation_id: OPERATION_ID,
+            ballot_id,
+            nullifier_index: 0,
+            light_params: LightCreateVoteNullifierParams {
+                validity_proof: LightValidityProof::default(),
+                address_tree_info: address_tree_info(),
+                output_tree_index: STATE_TREE_INDEX,
+            },
+        },
+        true,
+    );
+    flow.send(nullifier).await.unwrap();
+
+    let execute = flow.instruction(
+        cloakcraft::accounts::ExecuteVoteSnapshot {
+            ballot,
+            pending_operation: pending_operation_address(),
+            relayer: flow.relayer.pubkey(),
+            options_page: None,
+        },
+        cloakcraft::instruction::ExecuteVoteSnapshot {
+            operation_id: OPERATION_ID,
+            ballot_id,
+            encrypted_contributions: None,
+            encrypted_turnout: None,
+        },
+        false,
+    );
+    flow.send(execute).await.unwrap();
+
+    let commitment = flow.instruction(
+        cloakcraft::accounts::CreateVoteCommitment {
+            ballot,
+            pending_operation: pending_operation_address(),
+            relayer: flow.relayer.pubkey(),
+        },
+        cloakcraft::instruction::CreateVoteCommitment {
+            operation_id: OPERATION_ID,
+            ballot_id,
+            commitment_index: 0,
+            encrypted_preimage: [0u8; 128],
+            encryption_type: 0,
+            light_params: LightCreateVoteCommitmentParams {
+                validity_proof: LightValidityProof::default(),
+                address_tree_info: address_tree_info(),
+                output_tree_index: STATE_TREE_INDEX,
+            },
+        },
+        true,
+    );
+    flow.send(commitment).await.unwrap();
+    flow.send(flow.close_ix()).await.unwrap();
+    assert!(flow.pending().await.is_none());
+
+    let ballot_state: Ballot = flow.anchor(ballot).await;
+    assert_eq!((ballot_state.option_weights[1], ballot_state.vote_count), (weight, 1));
+}
+
+#[tokio::test]
+async fn test_phases_run_in_order() {
+    let mut flow = setup().await;
+    let pool = flow.pool();
+    let other_pool = flow.pool();
+
+    let now = flow.now().await;
+    let mut op = phase0(flow.relayer.pubkey(), operation_types::TRANSFER, &circuits::TRANSFER_1X2, now);
+    add_input(&mut op, &pool);
+    add_output(&mut op, &pool, 600_000);
+    flow.inject(&op);
+
+    // Nullifier before the commitment is proven to exist
+    let err = flow.send(flow.nullifier_ix(&pool, 0)).await.unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::CommitmentNotVerified));
+
+    // Inclusion proven against another pool's commitment address
+    let err = flow.send(flow.verify_ix(&op, &other_pool, 0)).await.unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::PoolMismatch));
+
+    flow.send(flow.verify_ix(&op, &pool, 0)).await.unwrap();
+
+    // Outputs only after every nullifier exists
+    let err = flow.send(flow.commitment_ix(&pool, 0)).await.unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::NullifierNotCreated));
+    let err = flow.send(flow.close_ix()).await.unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::PendingOperationNotComplete));
+
+    flow.send(flow.nullifier_ix(&pool, 0)).await.unwrap();
+    let err = flow.send(flow.nullifier_ix(&pool, 0)).await.unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::NullifierAlreadyCreated));
+
+    flow.create_outputs_and_close(&[&pool]).await;
+}
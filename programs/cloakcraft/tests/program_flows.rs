@@ -0,0 +1,398 @@
+//! Program-level flow tests (solana-program-test)
+//!
+//! Runs the program natively against a bank and drives the phases that need
+//! neither a Groth16 proof nor the Light system program: Phase 3
+//! (process_unshield) and close_pending_operation. The state earlier phases
+//! would leave behind (pool, vault, protocol config, pending operation) is
+//! injected directly, so these cover account validation and lamport
+//! accounting on top of the state machine tests in `pending_operation_flows.rs`.
+//!
+//! Anchor CPIs only run on-chain, so Phase 3 is covered up to the vault
+//! transfer (its rejections) and close starts from the state a successful
+//! Phase 3 leaves. The full Phase 1 → close sequence per operation family
+//! runs through the entrypoint in `phase_flows.rs`.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{entrypoint::ProgramResult, instruction::Instruction, program_pack::Pack};
+use anchor_lang::{AccountDeserialize, Discriminator, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account as SolanaAccount,
+    instruction::InstructionError,
+    signature::{Keypair, Signer as _},
+    transaction::{Transaction, TransactionError},
+};
+
+use cloakcraft::constants::{operation_types, seeds};
+use cloakcraft::errors::CloakCraftError;
+use cloakcraft::instructions::compute_recipient_hash;
+use cloakcraft::state::{PendingOperation, Pool, ProtocolConfig, PENDING_OPERATION_EXPIRY_SECONDS};
+
+const OPERATION_ID: [u8; 32] = [7u8; 32];
+const SHIELDED: u64 = 1_000_000;
+const UNSHIELD: u64 = 250_000;
+const RENT_REFUND_BPS: u16 = 5000;
+
+/// Native entry: Anchor's entry ties the account slice to its lifetime
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts: &[AccountInfo] = unsafe { std::mem::transmute(accounts) };
+    cloakcraft::entry(program_id, accounts, data)
+}
+
+fn anchor_account<T: AccountSerialize>(value: &T, space: usize) -> SolanaAccount {
+    let mut data = Vec::with_capacity(space);
+    value.try_serialize(&mut data).unwrap();
+    data.resize(space, 0);
+    SolanaAccount {
+        lamports: Rent::default().minimum_balance(space),
+        data,
+        owner: cloakcraft::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn packed_account<T: Pack>(value: T) -> SolanaAccount {
+    let mut data = vec![0u8; T::LEN];
+    T::pack(value, &mut data).unwrap();
+    SolanaAccount {
+        lamports: Rent::default().minimum_balance(T::LEN),
+        data,
+        owner: spl_token::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Zero-initialized account of type `T`, as `init` would leave it
+fn zeroed<T: AccountDeserialize + Discriminator>(space: usize) -> T {
+    let mut data = vec![0u8; space];
+    data[..8].copy_from_slice(T::DISCRIMINATOR);
+    T::try_deserialize(&mut data.as_slice()).unwrap()
+}
+
+fn token_account(mint: Pubkey, owner: Pubkey, amount: u64) -> SolanaAccount {
+    packed_account(spl_token::state::Account {
+        mint,
+        owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    })
+}
+
+/// Accounts of one flow
+struct Flow {
+    context: ProgramTestContext,
+    relayer: Keypair,
+    pool: Pubkey,
+    vault: Pubkey,
+    pending_operation: Pubkey,
+    recipient_owner: Pubkey,
+    recipient: Pubkey,
+    other_recipient: Pubkey,
+}
+
+/// What Phases 0-2 leave for the operation
+struct PendingSetup {
+    /// Bind the unshield to `recipient` (destination-bound proof)
+    bound: bool,
+    /// Phase 3 paid `recipient` and bound its owner for the rent refund
+    refund_bound: bool,
+    nullifiers_created: bool,
+    commitments_created: bool,
+}
+
+impl PendingSetup {
+    const READY_FOR_PHASE3: Self = Self {
+        bound: true,
+        refund_bound: false,
+        nullifiers_created: true,
+        commitments_created: true,
+    };
+    const COMPLETE: Self = Self { refund_bound: true, ..Self::READY_FOR_PHASE3 };
+}
+
+async fn setup(pending: PendingSetup) -> Flow {
+    let mut program_test = ProgramTest::new("cloakcraft", cloakcraft::ID, processor!(process_instruction));
+
+    let relayer = Keypair::new();
+    program_test.add_account(
+        relayer.pubkey(),
+        SolanaAccount::new(10_000_000_000, 0, &anchor_lang::system_program::ID),
+    );
+
+    let mint = Pubkey::new_unique();
+    program_test.add_account(
+        mint,
+        packed_account(spl_token::state::Mint {
+            decimals: 6,
+            is_initialized: true,
+            supply: SHIELDED,
+            ..Default::default()
+        }),
+    );
+
+    let (pool, pool_bump) = Pubkey::find_program_address(&[seeds::POOL, mint.as_ref()], &cloakcraft::ID);
+    let (vault, vault_bump) = Pubkey::find_program_address(&[seeds::VAULT, mint.as_ref()], &cloakcraft::ID);
+    let mut pool_state: Pool = zeroed(Pool::LEN);
+    pool_state.token_mint = mint;
+    pool_state.token_vault = vault;
+    pool_state.authority = Pubkey::new_unique();
+    pool_state.bump = pool_bump;
+    pool_state.vault_bump = vault_bump;
+    pool_state.total_shielded = SHIELDED;
+    pool_state.state_tree = Pubkey::new_unique();
+    pool_state.address_tree = Pubkey::new_unique();
+    pool_state.layout_version = Pool::LAYOUT_VERSION;
+    program_test.add_account(pool, anchor_account(&pool_state, Pool::LEN));
+    program_test.add_account(vault, token_account(mint, pool, SHIELDED));
+
+    let (config, config_bump) = Pubkey::find_program_address(&[seeds::PROTOCOL_CONFIG], &cloakcraft::ID);
+    let mut config_state: ProtocolConfig = zeroed(8 + ProtocolConfig::LEN);
+    config_state.authority = Pubkey::new_unique();
+    config_state.treasury = Pubkey::new_unique();
+    config_state.fees_enabled = false;
+    config_state.rent_refund_bps = RENT_REFUND_BPS;
+    config_state.bump = config_bump;
+    program_test.add_account(config, anchor_account(&config_state, 8 + ProtocolConfig::LEN));
+
+    let recipient_owner = Pubkey::new_unique();
+    let recipient = Pubkey::new_unique();
+    let other_recipient = Pubkey::new_unique();
+    program_test.add_account(recipient, token_account(mint, recipient_owner, 0));
+    program_test.add_account(other_recipient, token_account(mint, Pubkey::new_unique(), 0));
+    program_test.add_account(recipient_owner, SolanaAccount::new(1_000_000, 0, &anchor_lang::system_program::ID));
+
+    let mut context = program_test.start_with_context().await;
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+
+    // Phase 0 (+ Phase 1/2 progress) of a 1-input unshield with one change output
+    let (pending_operation, pending_bump) =
+        Pubkey::find_program_address(&[PendingOperation::SEEDS_PREFIX, &OPERATION_ID], &cloakcraft::ID);
+    let mut op: PendingOperation = zeroed(PendingOperation::SPACE);
+    op.operation_id = OPERATION_ID;
+    op.relayer = relayer.pubkey();
+    op.bump = pending_bump;
+    op.operation_type = operation_types::TRANSFER;
+    op.proof_verified = true;
+    op.num_inputs = 1;
+    op.input_commitments[0] = [1u8; 32];
+    op.expected_nullifiers[0] = [2u8; 32];
+    op.inputs_verified_mask = 1;
+    op.num_commitments = 1;
+    op.commitments[0] = [3u8; 32];
+    op.pools[0] = pool.to_bytes();
+    op.unshield_amount = UNSHIELD;
+    op.set_rent_refund_bps(&config_state);
+    if pending.bound {
+        op.call_hash = compute_recipient_hash(&recipient, false);
+    }
+    if pending.refund_bound {
+        op.bind_rent_refund_recipient(recipient_owner);
+    }
+    if pending.nullifiers_created {
+        op.mark_nullifier_created(0);
+    }
+    if pending.commitments_created {
+        op.mark_completed(0);
+    }
+    op.created_at = now;
+    op.expires_at = now + PENDING_OPERATION_EXPIRY_SECONDS;
+    context.set_account(&pending_operation, &anchor_account(&op, PendingOperation::SPACE).into());
+
+    Flow {
+        context,
+        relayer,
+        pool,
+        vault,
+        pending_operation,
+        recipient_owner,
+        recipient,
+        other_recipient,
+    }
+}
+
+impl Flow {
+    async fn send(&mut self, ix: Instruction) -> std::result::Result<(), TransactionError> {
+        let blockhash = self.context.banks_client.get_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(&[ix], Some(&self.relayer.pubkey()), &[&self.relayer], blockhash);
+        self.context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .map_err(|e| e.unwrap())
+    }
+
+    fn process_unshield_ix(&self, unshield_recipient: Pubkey) -> Instruction {
+        Instruction {
+            program_id: cloakcraft::ID,
+            accounts: cloakcraft::accounts::ProcessUnshield {
+                pool: self.pool,
+                token_vault: self.vault,
+                pending_operation: self.pending_operation,
+                protocol_config: Pubkey::find_program_address(&[seeds::PROTOCOL_CONFIG], &cloakcraft::ID).0,
+                treasury_token_account: None,
+                unshield_recipient: Some(unshield_recipient),
+                recipient_owner: None,
+                recipient_ata: None,
+                token_mint: None,
+                relayer_token_account: None,
+                relayer: self.relayer.pubkey(),
+                token_program: spl_token::ID,
+                associated_token_program: None,
+                system_program: None,
+                memo_program: None,
+                pool_stats: None,
+            }
+            .to_account_metas(None),
+            data: cloakcraft::instruction::ProcessUnshield {
+                operation_id: OPERATION_ID,
+                unshield_amount: UNSHIELD,
+                ata_reimbursement: 0,
+                memo: None,
+            }
+            .data(),
+        }
+    }
+
+    fn close_ix(&self, rent_refund_recipient: Option<Pubkey>) -> Instruction {
+        Instruction {
+            program_id: cloakcraft::ID,
+            accounts: cloakcraft::accounts::ClosePendingOperation {
+                pending_operation: self.pending_operation,
+                relayer: self.relayer.pubkey(),
+                rent_refund_recipient,
+                pool: None,
+                dust_sweep_ledger: None,
+                relayer_stake: None,
+            }
+            .to_account_metas(None),
+            data: cloakcraft::instruction::ClosePendingOperation { operation_id: OPERATION_ID }.data(),
+        }
+    }
+
+    async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.context.banks_client.get_balance(address).await.unwrap()
+    }
+
+    async fn token_amount(&mut self, address: Pubkey) -> u64 {
+        let account = self.context.banks_client.get_account(address).await.unwrap().unwrap();
+        spl_token::state::Account::unpack(&account.data).unwrap().amount
+    }
+
+    async fn pending(&mut self) -> Option<PendingOperation> {
+        let account = self.context.banks_client.get_account(self.pending_operation).await.unwrap()?;
+        Some(PendingOperation::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    async fn warp_past_expiry(&mut self) {
+        let mut clock = self.context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        clock.unix_timestamp += PENDING_OPERATION_EXPIRY_SECONDS + 1;
+        self.context.set_sysvar(&clock);
+    }
+}
+
+fn program_error(error: CloakCraftError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(error.into()))
+}
+
+#[tokio::test]
+async fn test_bound_unshield_rejects_other_recipient() {
+    let mut flow = setup(PendingSetup::READY_FOR_PHASE3).await;
+
+    let err = flow.send(flow.process_unshield_ix(flow.other_recipient)).await.unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::UnshieldRecipientMismatch));
+    assert_eq!(flow.token_amount(flow.vault).await, SHIELDED);
+    assert_eq!(flow.pending().await.unwrap().rent_refund_recipient, Pubkey::default());
+}
+
+#[tokio::test]
+async fn test_close_refunds_rent_to_bound_recipient() {
+    let mut flow = setup(PendingSetup::COMPLETE).await;
+
+    let rent = flow.lamports(flow.pending_operation).await;
+    let owner_before = flow.lamports(flow.recipient_owner).await;
+    flow.send(flow.close_ix(Some(flow.recipient_owner))).await.unwrap();
+
+    assert!(flow.pending().await.is_none());
+    assert_eq!(
+        flow.lamports(flow.recipient_owner).await - owner_before,
+        rent * RENT_REFUND_BPS as u64 / 10_000
+    );
+}
+
+#[tokio::test]
+async fn test_close_requires_the_bound_refund_recipient() {
+    let mut flow = setup(PendingSetup::COMPLETE).await;
+
+    let err = flow.send(flow.close_ix(None)).await.unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::InvalidRentRefundRecipient));
+
+    let relayer = flow.relayer.pubkey();
+    let err = flow.send(flow.close_ix(Some(relayer))).await.unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::InvalidRentRefundRecipient));
+    assert!(flow.pending().await.is_some());
+}
+
+#[tokio::test]
+async fn test_unbound_close_returns_all_rent_to_relayer() {
+    let mut flow = setup(PendingSetup { bound: false, refund_bound: false, ..PendingSetup::COMPLETE }).await;
+
+    // A relayer-supplied account is ignored when nothing was bound
+    let owner_before = flow.lamports(flow.recipient_owner).await;
+    flow.send(flow.close_ix(Some(flow.recipient_owner))).await.unwrap();
+    assert!(flow.pending().await.is_none());
+    assert_eq!(flow.lamports(flow.recipient_owner).await, owner_before);
+}
+
+#[tokio::test]
+async fn test_process_unshield_requires_nullifiers_and_relayer() {
+    let mut flow = setup(PendingSetup { nullifiers_created: false, ..PendingSetup::READY_FOR_PHASE3 }).await;
+    let err = flow.send(flow.process_unshield_ix(flow.recipient)).await.unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::NullifierNotCreated));
+
+    let mut flow = setup(PendingSetup::READY_FOR_PHASE3).await;
+    flow.relayer = Keypair::new();
+    let payer = flow.context.payer.insecure_clone();
+    let blockhash = flow.context.banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[flow.process_unshield_ix(flow.recipient)],
+        Some(&payer.pubkey()),
+        &[&payer, &flow.relayer],
+        blockhash,
+    );
+    let err = flow.context.banks_client.process_transaction(tx).await.unwrap_err().unwrap();
+    assert_eq!(err, program_error(CloakCraftError::InvalidRelayer));
+}
+
+#[tokio::test]
+async fn test_incomplete_operation_closes_only_after_expiry() {
+    let mut flow = setup(PendingSetup {
+        bound: false,
+        refund_bound: false,
+        nullifiers_created: false,
+        commitments_created: false,
+    })
+    .await;
+
+    let err = flow.send(flow.close_ix(None)).await.unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::PendingOperationNotComplete));
+
+    flow.warp_past_expiry().await;
+    let err = flow.send(flow.process_unshield_ix(flow.recipient)).await.unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::PendingOperationExpired));
+
+    flow.send(flow.close_ix(None)).await.unwrap();
+    assert!(flow.pending().await.is_none());
+}
+
+#[tokio::test]
+async fn test_stranded_transfer_cannot_close() {
+    let mut flow = setup(PendingSetup { commitments_created: false, ..PendingSetup::READY_FOR_PHASE3 }).await;
+
+    flow.warp_past_expiry().await;
+    let err = flow.send(flow.close_ix(None)).await.unwrap_err();
+    assert_eq!(err, program_error(CloakCraftError::PendingOperationStranded));
+}
@@ -0,0 +1,35 @@
+#!/bin/bash
+# Fail if any program function overflows the 4KB SBF stack frame
+# Usage: ./scripts/check-stack-usage.sh [build-log]
+#
+# The SBF linker reports every function whose frame exceeds the limit
+# ("Stack offset of N exceeded max offset of 4096 by M bytes"). Pass the log
+# of an `anchor build` / `cargo build-sbf`, or omit it to build the program.
+
+set -e -o pipefail
+
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+ROOT_DIR="$(dirname "$SCRIPT_DIR")"
+
+if [ -n "$1" ]; then
+    LOG="$1"
+else
+    LOG="$(mktemp)"
+    (cd "$ROOT_DIR/programs/cloakcraft" && cargo build-sbf 2>&1) | tee "$LOG"
+fi
+
+OVERFLOWS=$(grep -E "Stack offset of [0-9]+ exceeded max offset" "$LOG" || true)
+
+echo "=========================================="
+echo "CloakCraft Stack Usage"
+echo "=========================================="
+
+if [ -n "$OVERFLOWS" ]; then
+    echo "$OVERFLOWS" | sed -E 's/.*Function ([^ ]+) Stack offset of ([0-9]+) exceeded max offset of ([0-9]+) by ([0-9]+) bytes.*/\1: +\4 bytes over \3/'
+    echo ""
+    echo "❌ $(echo "$OVERFLOWS" | wc -l) function(s) exceed the SBF stack frame"
+    echo "   Box large accounts or move big locals to the heap"
+    exit 1
+fi
+
+echo "✅ No function exceeds the SBF stack frame"
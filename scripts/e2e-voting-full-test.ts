@@ -1152,6 +1152,9 @@ async function testCreateBallot(state: TestState, options: CreateBallotOptions)
       snapshotSlot: new BN(currentSlot - 10),
       indexerPubkey: state.wallet.publicKey,
       eligibilityRoot: null,
+      twabMode: { disabled: {} },
+      twabNumRoots: 0,
+      twabIntervalSlots: new BN(0),
       weightFormula: Buffer.from([0]), // PushAmount
       weightParams: [],
       timeLockPubkey: options.timeLockPubkey ? Array.from(options.timeLockPubkey) : Array.from(new Uint8Array(32)),
@@ -1159,6 +1162,7 @@ async function testCreateBallot(state: TestState, options: CreateBallotOptions)
       resolver: options.resolver || null,
       oracle: options.oracle || null,
       claimDeadline: new BN(options.claimDeadline || 0),
+      proposalBond: new BN(0),
     };
 
     const accounts: any = {
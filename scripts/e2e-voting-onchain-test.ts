@@ -82,6 +82,7 @@ import { derivePublicKey } from "../packages/sdk/src/crypto/babyjubjub";
 import { deriveNullifierKey } from "../packages/sdk/src/crypto/nullifier";
 import { generateRandomness } from "../packages/sdk/src/crypto/commitment";
 import { loadCircomArtifacts, generateSnarkjsProof } from "../packages/sdk/src/snarkjs-prover";
+import { CLIENT_VERSION } from "../packages/sdk/src/instructions/constants";
 // @ts-ignore - no type declarations
 import { buildPoseidon } from "circomlibjs";
 
@@ -1207,7 +1208,8 @@ async function main() {
                 new BN(savedWeight.toString()),
                 null, // old_encrypted_contributions (public mode)
                 null, // new_encrypted_contributions
-                Array.from(newRandomness)
+                Array.from(newRandomness),
+                CLIENT_VERSION
               )
               .accounts({
                 ballot: ballotPda1,
@@ -1215,6 +1217,8 @@ async function main() {
                 pendingOperation: changeVotePendingOpPda,
                 relayer: wallet.publicKey,
                 payer: wallet.publicKey,
+                protocolConfig: PublicKey.findProgramAddressSync([Buffer.from("protocol_config")], PROGRAM_ID)[0],
+                programVersion: PublicKey.findProgramAddressSync([Buffer.from("program_version")], PROGRAM_ID)[0],
                 systemProgram: SystemProgram.programId,
               })
               .preInstructions([
@@ -1662,7 +1666,8 @@ async function main() {
               new BN(weight.toString()),
               null, // encrypted_contributions (public mode)
               null, // encrypted_preimage
-              Array.from(outputRandomnessSpend)
+              Array.from(outputRandomnessSpend),
+              CLIENT_VERSION
             )
             .accounts({
               ballot: ballotPdaSpend,
@@ -1671,6 +1676,8 @@ async function main() {
               pendingOperation: pendingOpPdaSpend,
               relayer: wallet.publicKey,
               payer: wallet.publicKey,
+              protocolConfig: PublicKey.findProgramAddressSync([Buffer.from("protocol_config")], PROGRAM_ID)[0],
+              programVersion: PublicKey.findProgramAddressSync([Buffer.from("program_version")], PROGRAM_ID)[0],
               systemProgram: SystemProgram.programId,
             })
             .preInstructions([
@@ -2204,7 +2211,8 @@ async function main() {
                 new BN(positionWeight.toString()),
                 null, // old_encrypted_contributions (public mode)
                 null, // new_encrypted_contributions
-                Array.from(outputRandomnessChangeSpend)
+                Array.from(outputRandomnessChangeSpend),
+                CLIENT_VERSION
             )
             .accounts({
               ballot: ballotPdaChangeSpend,
@@ -2213,6 +2221,8 @@ async function main() {
               pendingOperation: pendingOpPdaChangeSpend,
               relayer: wallet.publicKey,
               payer: wallet.publicKey,
+              protocolConfig: PublicKey.findProgramAddressSync([Buffer.from("protocol_config")], PROGRAM_ID)[0],
+              programVersion: PublicKey.findProgramAddressSync([Buffer.from("program_version")], PROGRAM_ID)[0],
               systemProgram: SystemProgram.programId,
             })
             .preInstructions([
@@ -2350,8 +2360,10 @@ async function main() {
           logInfo("Phase 2: Creating old position nullifier...");
 
           // Derive nullifier address for voting (uses "action_nullifier" seed)
+          // Position nullifiers are tagged with the vote-position domain (3)
           const changeSpendNullifierSeeds = [
             Buffer.from("action_nullifier"),
+            Buffer.from([3]),
             Buffer.from(ballotIdChangeSpend),
             oldPositionNullifierBytes,
           ];
@@ -2740,7 +2752,8 @@ async function main() {
               new BN(closeAmount.toString()),
               new BN(closeWeight.toString()),
               null, // encrypted_contributions (public mode)
-              Array.from(outputRandomnessClose)
+              Array.from(outputRandomnessClose),
+              CLIENT_VERSION
             )
             .accounts({
               ballot: ballotPdaClose,
@@ -2748,6 +2761,8 @@ async function main() {
               pendingOperation: pendingOpPdaClose,
               relayer: wallet.publicKey,
               payer: wallet.publicKey,
+              protocolConfig: PublicKey.findProgramAddressSync([Buffer.from("protocol_config")], PROGRAM_ID)[0],
+              programVersion: PublicKey.findProgramAddressSync([Buffer.from("program_version")], PROGRAM_ID)[0],
               systemProgram: SystemProgram.programId,
             })
             .preInstructions([
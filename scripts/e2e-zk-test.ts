@@ -101,6 +101,7 @@ import { VotingClient, VotingClientConfig } from "../packages/sdk/src/voting/cli
 
 // EdDSA signing for voting attestations
 import { buildEddsa, buildPoseidon } from "circomlibjs";
+import { buildAdminAuditRemainingAccounts } from "../packages/sdk/src/instructions/admin-audit";
 
 // Pyth Oracle is handled automatically by the SDK (Jupiter-style bundling)
 
@@ -2299,6 +2300,7 @@ async function main() {
           liquidationPenaltyBps: 50,    // 0.5% liquidation penalty
           baseBorrowRateBps: 1,         // 0.01% base borrow rate per hour
           maxImbalanceFeeBps: 3,        // 0.03% max imbalance fee
+          makerFeeBps: 2,               // 0.02% resting limit order fee
         };
 
         // Derive position mint PDA
@@ -3773,6 +3775,9 @@ async function main() {
             snapshotSlot: new anchor.BN(currentSlot - 10),
             indexerPubkey: payer.publicKey,
             eligibilityRoot: null,
+            twabMode: { disabled: {} },
+            twabNumRoots: 0,
+            twabIntervalSlots: new BN(0),
             weightFormula: Buffer.from([0]),
             weightParams: [],
             timeLockPubkey: Array.from(new Uint8Array(32)),
@@ -3780,6 +3785,7 @@ async function main() {
             resolver: null,
             oracle: null,
             claimDeadline: new anchor.BN(0),
+            proposalBond: new anchor.BN(0),
           }
         )
         .accounts({
@@ -3832,6 +3838,9 @@ async function main() {
             snapshotSlot: new anchor.BN(currentSlot - 10),
             indexerPubkey: payer.publicKey,
             eligibilityRoot: null,
+            twabMode: { disabled: {} },
+            twabNumRoots: 0,
+            twabIntervalSlots: new BN(0),
             weightFormula: Buffer.from([0]),
             weightParams: [],
             timeLockPubkey: Array.from(timeLockPubkey),
@@ -3839,6 +3848,7 @@ async function main() {
             resolver: null,
             oracle: null,
             claimDeadline: new anchor.BN(0),
+            proposalBond: new anchor.BN(0),
           }
         )
         .accounts({
@@ -3885,6 +3895,9 @@ async function main() {
             snapshotSlot: new anchor.BN(0),
             indexerPubkey: payer.publicKey,
             eligibilityRoot: null,
+            twabMode: { disabled: {} },
+            twabNumRoots: 0,
+            twabIntervalSlots: new BN(0),
             weightFormula: Buffer.from([0]),
             weightParams: [],
             timeLockPubkey: Array.from(new Uint8Array(32)),
@@ -3892,6 +3905,7 @@ async function main() {
             resolver: null,
             oracle: payer.publicKey, // Oracle for resolution
             claimDeadline: new anchor.BN(now + 172800), // 48 hours
+            proposalBond: new anchor.BN(0),
           }
         )
         .accounts({
@@ -5172,6 +5186,7 @@ async function main() {
         liquidationPenaltyBps: 50,    // 0.5% liquidation penalty
         baseBorrowRateBps: 1,         // 0.01% base borrow rate per hour
         maxImbalanceFeeBps: 3,        // 0.03% max imbalance fee
+        makerFeeBps: 2,               // 0.02% resting limit order fee
       };
 
       await program.methods
@@ -5369,6 +5384,7 @@ async function main() {
         liquidationPenaltyBps: null,   // None
         baseBorrowRateBps: null,       // None
         maxImbalanceFeeBps: null,      // None
+        makerFeeBps: null,             // None
         isActive: null,                // None - Keep unchanged
       };
 
@@ -5551,6 +5567,9 @@ async function main() {
         snapshotSlot: new anchor.BN(currentSlot - 100),
         indexerPubkey: payer.publicKey,
         eligibilityRoot: null,
+        twabMode: { disabled: {} },
+        twabNumRoots: 0,
+        twabIntervalSlots: new BN(0),
         weightFormula: Buffer.from([0]),  // PushAmount
         weightParams: [],
         timeLockPubkey: Array.from(new Uint8Array(32)),
@@ -5558,6 +5577,7 @@ async function main() {
         resolver: null,
         oracle: null,
         claimDeadline: new anchor.BN(0),
+        proposalBond: new anchor.BN(0),
       };
 
       await program.methods
@@ -5714,6 +5734,9 @@ async function main() {
         snapshotSlot: new anchor.BN(currentSlot - 100),
         indexerPubkey: payer.publicKey,
         eligibilityRoot: null,
+        twabMode: { disabled: {} },
+        twabNumRoots: 0,
+        twabIntervalSlots: new BN(0),
         weightFormula: Buffer.from([0]),
         weightParams: [],
         timeLockPubkey: Array.from(new Uint8Array(32)),  // Zero pubkey for test
@@ -5721,6 +5744,7 @@ async function main() {
         resolver: null,
         oracle: null,
         claimDeadline: new anchor.BN(0),
+        proposalBond: new anchor.BN(0),
       };
 
       await program.methods
@@ -5810,6 +5834,9 @@ async function main() {
         snapshotSlot: new anchor.BN(currentSlot - 100),
         indexerPubkey: payer.publicKey,
         eligibilityRoot: null,
+        twabMode: { disabled: {} },
+        twabNumRoots: 0,
+        twabIntervalSlots: new BN(0),
         weightFormula: Buffer.from([0]),
         weightParams: [],
         timeLockPubkey: Array.from(new Uint8Array(32)),
@@ -5817,6 +5844,7 @@ async function main() {
         resolver: payer.publicKey,  // Authority is the resolver
         oracle: null,
         claimDeadline: new anchor.BN(0),
+        proposalBond: new anchor.BN(0),
       };
 
       await program.methods
@@ -5896,6 +5924,7 @@ async function main() {
           payer: payer.publicKey,
           systemProgram: SystemProgram.programId,
         })
+        .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
         .rpc();
 
       const configData = await (program.account as any).protocolConfig.fetch(protocolConfigPda);
@@ -5938,6 +5967,7 @@ async function main() {
           protocolConfig: protocolConfigPda,
           authority: payer.publicKey,
         })
+        .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
         .rpc();
 
       const configData = await (program.account as any).protocolConfig.fetch(protocolConfigPda);
@@ -5987,6 +6017,7 @@ async function main() {
             payer: payer.publicKey,
             systemProgram: SystemProgram.programId,
           })
+          .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
           .rpc();
 
         const vkAccount = await connection.getAccountInfo(vkPda);
@@ -6030,6 +6061,7 @@ async function main() {
               payer: payer.publicKey,
               systemProgram: SystemProgram.programId,
             })
+            .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
             .rpc();
         }
       }
@@ -6044,6 +6076,7 @@ async function main() {
           verificationKey: vkPda,
           authority: payer.publicKey,
         })
+        .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
         .rpc();
 
       const vkAccount = await connection.getAccountInfo(vkPda);
@@ -6087,6 +6120,7 @@ async function main() {
               payer: payer.publicKey,
               systemProgram: SystemProgram.programId,
             })
+            .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
             .rpc();
         }
       }
@@ -6101,6 +6135,7 @@ async function main() {
           verificationKey: vkPda,
           authority: payer.publicKey,
         })
+        .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
         .rpc();
 
       // Append chunk 2
@@ -6113,6 +6148,7 @@ async function main() {
           verificationKey: vkPda,
           authority: payer.publicKey,
         })
+        .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
         .rpc();
 
       const vkAccount = await connection.getAccountInfo(vkPda);
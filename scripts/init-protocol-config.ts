@@ -18,6 +18,7 @@ import { PublicKey, Keypair, Connection } from "@solana/web3.js";
 import * as fs from "fs";
 import * as path from "path";
 import * as os from "os";
+import { buildAdminAuditRemainingAccounts } from "../packages/sdk/src/instructions/admin-audit";
 
 // Program ID (update to match your deployment)
 const PROGRAM_ID = new PublicKey("2VWF9TxMFgzHwbd5WPpYKoqHvtzk3fN66Ka3tVV82nZG");
@@ -124,6 +125,7 @@ async function main() {
         payer: wallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
+      .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
       .rpc();
 
     console.log("\n[OK] Protocol config initialized!");
@@ -4,6 +4,7 @@
  * This script:
  * 1. Converts Circom VK JSON to groth16-solana binary format
  * 2. Registers the verification keys on-chain
+ * 3. Finalizes them (hash check + freeze); proofs are rejected until then
  *
  * Usage:
  *   npx tsx scripts/register-circom-vkeys.ts [--circuit <name>] [--force]
@@ -11,7 +12,7 @@
  * Examples:
  *   npx tsx scripts/register-circom-vkeys.ts                    # All circuits
  *   npx tsx scripts/register-circom-vkeys.ts --circuit transfer_1x2
- *   npx tsx scripts/register-circom-vkeys.ts --force            # Overwrite existing
+ *   npx tsx scripts/register-circom-vkeys.ts --force            # Overwrite existing (until finalized)
  */
 
 import * as anchor from "@coral-xyz/anchor";
@@ -20,6 +21,8 @@ import { PublicKey, Keypair, Connection } from "@solana/web3.js";
 import * as fs from "fs";
 import * as path from "path";
 import * as os from "os";
+import { keccak_256 } from "@noble/hashes/sha3";
+import { buildAdminAuditRemainingAccounts } from "../packages/sdk/src/instructions/admin-audit";
 
 // Program ID
 const PROGRAM_ID = new PublicKey("2VWF9TxMFgzHwbd5WPpYKoqHvtzk3fN66Ka3tVV82nZG");
@@ -198,6 +201,7 @@ async function registerVk(
         payer: wallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
+      .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
       .rpc();
     console.log("Account created");
   }
@@ -213,6 +217,7 @@ async function registerVk(
         verificationKey: vkPda,
         authority: wallet.publicKey,
       })
+      .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
       .rpc();
   } else {
     console.log("VK too large, using chunked upload...");
@@ -224,6 +229,7 @@ async function registerVk(
         verificationKey: vkPda,
         authority: wallet.publicKey,
       })
+      .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
       .rpc();
 
     // Upload in chunks
@@ -243,6 +249,7 @@ async function registerVk(
           verificationKey: vkPda,
           authority: wallet.publicKey,
         })
+        .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
         .rpc();
       console.log(`  Chunk ${i + 1}/${chunks.length} uploaded`);
       await new Promise((r) => setTimeout(r, 500));
@@ -257,8 +264,23 @@ async function registerVk(
       console.log("[OK] VK registered successfully!");
     } else {
       console.log(`[WARN] VK length mismatch: got ${finalVecLen}, expected ${vkData.length}`);
+      return;
     }
   }
+
+  // Finalize: the program recomputes keccak256(vk_data) and rejects a mismatch
+  const vkHash = keccak_256(vkData);
+  await program.methods
+    .finalizeVerificationKey(Array.from(circuitIdBuf) as any, Array.from(vkHash) as any)
+    .accounts({
+      verificationKey: vkPda,
+      authority: wallet.publicKey,
+      payer: wallet.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
+    .rpc();
+  console.log("[OK] VK finalized, hash:", Buffer.from(vkHash).toString("hex"));
 }
 
 async function main() {
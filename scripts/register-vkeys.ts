@@ -10,6 +10,7 @@ import { PublicKey, Keypair, Connection } from "@solana/web3.js";
 import * as fs from "fs";
 import * as path from "path";
 import * as os from "os";
+import { buildAdminAuditRemainingAccounts } from "../packages/sdk/src/instructions/admin-audit";
 
 // Program ID
 const PROGRAM_ID = new PublicKey("2VWF9TxMFgzHwbd5WPpYKoqHvtzk3fN66Ka3tVV82nZG");
@@ -138,6 +139,7 @@ async function main() {
             payer: wallet.publicKey,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
+          .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
           .rpc();
         console.log("  VK data cleared ✓");
 
@@ -164,6 +166,7 @@ async function main() {
               payer: wallet.publicKey,
               systemProgram: anchor.web3.SystemProgram.programId,
             })
+            .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
             .rpc();
           console.log(`  Chunk ${i + 1}/${chunks.length}: ✓`);
 
@@ -188,6 +191,7 @@ async function main() {
             payer: wallet.publicKey,
             systemProgram: anchor.web3.SystemProgram.programId,
           })
+          .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
           .rpc();
 
         console.log("  Status: Registered ✓");
@@ -209,6 +213,7 @@ async function main() {
               payer: wallet.publicKey,
               systemProgram: anchor.web3.SystemProgram.programId,
             })
+            .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
             .rpc();
           console.log("  Account created ✓");
           console.log("  Tx:", tx1);
@@ -237,6 +242,7 @@ async function main() {
               payer: wallet.publicKey,
               systemProgram: anchor.web3.SystemProgram.programId,
             })
+            .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
             .rpc();
           console.log(`  Chunk ${i + 1}/${chunks.length}: ✓`);
 
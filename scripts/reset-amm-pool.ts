@@ -8,6 +8,7 @@ import { PublicKey, Keypair, Connection } from "@solana/web3.js";
 import * as fs from "fs";
 import * as path from "path";
 import * as os from "os";
+import { buildAdminAuditRemainingAccounts } from "../packages/sdk/src/instructions/admin-audit";
 
 const PROGRAM_ID = new PublicKey("fBh7FvBZpex64Qp7i45yuyxh7sH8YstYyxGLmToLRTP");
 
@@ -58,6 +59,7 @@ async function main() {
       ammPool: ammPoolPda,
       authority: wallet.publicKey,
     })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
     .rpc();
 
   console.log("Reset TX:", tx);
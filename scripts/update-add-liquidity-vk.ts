@@ -8,6 +8,7 @@ import { PublicKey, Keypair, Connection } from "@solana/web3.js";
 import * as fs from "fs";
 import * as path from "path";
 import * as os from "os";
+import { buildAdminAuditRemainingAccounts } from "../packages/sdk/src/instructions/admin-audit";
 
 const PROGRAM_ID = new PublicKey("fBh7FvBZpex64Qp7i45yuyxh7sH8YstYyxGLmToLRTP");
 const VK_SEED = Buffer.from("vk");
@@ -77,6 +78,7 @@ async function main() {
       payer: wallet.publicKey,
       systemProgram: anchor.web3.SystemProgram.programId,
     })
+    .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
     .rpc();
 
   console.log(`TX: ${tx}`);
@@ -26,6 +26,7 @@ import { PublicKey, Keypair, Connection } from "@solana/web3.js";
 import * as fs from "fs";
 import * as path from "path";
 import * as os from "os";
+import { buildAdminAuditRemainingAccounts } from "../packages/sdk/src/instructions/admin-audit";
 
 const PROGRAM_ID = new PublicKey("2VWF9TxMFgzHwbd5WPpYKoqHvtzk3fN66Ka3tVV82nZG");
 const PROTOCOL_CONFIG_SEED = Buffer.from("protocol_config");
@@ -214,6 +215,7 @@ async function main() {
         protocolConfig: protocolConfigPda,
         authority: wallet.publicKey,
       })
+      .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
       .rpc();
 
     console.log("\n[OK] Protocol fees updated!");
@@ -10,6 +10,7 @@ import { PublicKey, Keypair, Connection } from "@solana/web3.js";
 import * as fs from "fs";
 import * as path from "path";
 import * as os from "os";
+import { buildAdminAuditRemainingAccounts } from "../packages/sdk/src/instructions/admin-audit";
 
 const PROGRAM_ID = new PublicKey("fBh7FvBZpex64Qp7i45yuyxh7sH8YstYyxGLmToLRTP");
 const VK_SEED = Buffer.from("vk");
@@ -73,6 +74,7 @@ async function main() {
         payer: wallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
+      .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
       .rpc();
     console.log("Account created");
   } else {
@@ -96,6 +98,7 @@ async function main() {
         verificationKey: vkPda,
         authority: wallet.publicKey,
       })
+      .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
       .rpc();
     console.log("Tx:", tx);
   } else {
@@ -112,6 +115,7 @@ async function main() {
         verificationKey: vkPda,
         authority: wallet.publicKey,
       })
+      .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
       .rpc();
     console.log("VK data cleared");
 
@@ -134,6 +138,7 @@ async function main() {
           verificationKey: vkPda,
           authority: wallet.publicKey,
         })
+        .remainingAccounts(buildAdminAuditRemainingAccounts(program.programId))
         .rpc();
       console.log(`Chunk ${i + 1}/${chunks.length}: done`);
       await new Promise((resolve) => setTimeout(resolve, 500));